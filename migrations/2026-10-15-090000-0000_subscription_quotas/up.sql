@@ -0,0 +1 @@
+ALTER TABLE subscribers ADD COLUMN IF NOT EXISTS max_subscriptions INTEGER;
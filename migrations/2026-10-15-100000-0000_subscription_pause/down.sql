@@ -0,0 +1 @@
+ALTER TABLE feed_subscriptions DROP COLUMN IF EXISTS paused;
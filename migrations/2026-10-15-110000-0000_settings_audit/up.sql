@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS settings_audit (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    changed_by BIGINT,
+    key TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_settings_audit_guild
+ON settings_audit (guild_id, changed_at DESC);
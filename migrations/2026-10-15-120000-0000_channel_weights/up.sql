@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS channel_weights (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    weight_percent INTEGER NOT NULL DEFAULT 100,
+    UNIQUE (guild_id, channel_id)
+);
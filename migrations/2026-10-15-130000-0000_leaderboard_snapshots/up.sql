@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS leaderboard_snapshots (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    rank INTEGER NOT NULL,
+    total_duration BIGINT NOT NULL,
+    taken_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_leaderboard_snapshots_guild
+ON leaderboard_snapshots (guild_id, taken_at DESC);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS dashboard_tokens (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL UNIQUE,
+    token TEXT NOT NULL UNIQUE,
+    created_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
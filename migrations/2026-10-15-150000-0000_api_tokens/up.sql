@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id SERIAL PRIMARY KEY,
+    -- NULL for the bot owner's token, which can access every guild
+    guild_id BIGINT,
+    token TEXT NOT NULL UNIQUE,
+    created_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One token per guild, and a single owner token
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_tokens_scope
+ON api_tokens (COALESCE(guild_id, 0));
@@ -0,0 +1,2 @@
+ALTER TABLE feed_subscriptions DROP COLUMN IF EXISTS notify_edits;
+ALTER TABLE feed_items DROP COLUMN IF EXISTS content_hash;
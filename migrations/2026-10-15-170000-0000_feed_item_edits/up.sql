@@ -0,0 +1,3 @@
+-- Hash of the item's title and content, used to detect edits
+ALTER TABLE feed_items ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE feed_subscriptions ADD COLUMN IF NOT EXISTS notify_edits BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+ALTER TABLE feed_items DROP COLUMN IF EXISTS duration_secs;
+ALTER TABLE feed_items DROP COLUMN IF EXISTS media_url;
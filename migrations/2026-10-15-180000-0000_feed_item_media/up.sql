@@ -0,0 +1,3 @@
+-- Direct media link and length of an item, e.g., a podcast episode's audio file
+ALTER TABLE feed_items ADD COLUMN IF NOT EXISTS media_url TEXT;
+ALTER TABLE feed_items ADD COLUMN IF NOT EXISTS duration_secs INTEGER;
@@ -0,0 +1,3 @@
+ALTER TABLE feed_items DROP COLUMN IF EXISTS image_url;
+ALTER TABLE feed_items DROP COLUMN IF EXISTS item_url;
+ALTER TABLE feed_items DROP COLUMN IF EXISTS source_item_id;
@@ -0,0 +1,5 @@
+-- The platform's own ID of the item, e.g., a post URI
+ALTER TABLE feed_items ADD COLUMN IF NOT EXISTS source_item_id TEXT;
+-- Link to the item itself and a preview image, e.g., a social media post and its first image
+ALTER TABLE feed_items ADD COLUMN IF NOT EXISTS item_url TEXT;
+ALTER TABLE feed_items ADD COLUMN IF NOT EXISTS image_url TEXT;
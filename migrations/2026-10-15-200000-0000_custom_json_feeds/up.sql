@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS custom_json_feeds (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    -- Endpoint polled for items. Users subscribe with this url
+    url TEXT NOT NULL UNIQUE,
+    -- JSONPath selecting the items in the response
+    items_path TEXT NOT NULL,
+    -- JSONPaths evaluated against each item
+    id_path TEXT NOT NULL,
+    title_path TEXT NOT NULL,
+    url_path TEXT,
+    published_path TEXT,
+    created_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS voice_session_flags (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    session_id INTEGER NOT NULL REFERENCES voice_sessions(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    related_count INTEGER NOT NULL DEFAULT 0,
+    dismissed BOOLEAN NOT NULL DEFAULT FALSE,
+    flagged_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (session_id, reason)
+);
+
+CREATE INDEX IF NOT EXISTS idx_voice_session_flags_guild
+ON voice_session_flags (guild_id, flagged_at DESC);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS voice_adjustments (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    seconds BIGINT NOT NULL,
+    reason TEXT,
+    adjusted_by BIGINT NOT NULL,
+    applies_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_voice_adjustments_guild_user
+ON voice_adjustments (guild_id, user_id, applies_at);
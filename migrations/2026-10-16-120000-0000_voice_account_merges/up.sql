@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS voice_account_merges (
+    id SERIAL PRIMARY KEY,
+    from_user_id BIGINT NOT NULL,
+    to_user_id BIGINT NOT NULL,
+    merged_by BIGINT NOT NULL,
+    sessions_moved INTEGER NOT NULL DEFAULT 0,
+    adjustments_moved INTEGER NOT NULL DEFAULT 0,
+    flags_moved INTEGER NOT NULL DEFAULT 0,
+    snapshots_moved INTEGER NOT NULL DEFAULT 0,
+    merged_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
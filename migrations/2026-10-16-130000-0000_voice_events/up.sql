@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS voice_events (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    kind TEXT NOT NULL,
+    channel_id BIGINT,
+    previous_channel_id BIGINT,
+    muted BOOLEAN NOT NULL DEFAULT FALSE,
+    deafened BOOLEAN NOT NULL DEFAULT FALSE,
+    is_bot BOOLEAN NOT NULL DEFAULT FALSE,
+    is_stage BOOLEAN NOT NULL DEFAULT FALSE,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_voice_events_guild
+ON voice_events (guild_id, id);
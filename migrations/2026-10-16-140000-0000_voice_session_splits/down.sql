@@ -0,0 +1 @@
+ALTER TABLE voice_sessions DROP COLUMN IF EXISTS parent_session_id;
@@ -0,0 +1,6 @@
+ALTER TABLE voice_sessions
+ADD COLUMN IF NOT EXISTS parent_session_id INTEGER REFERENCES voice_sessions(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_voice_sessions_parent
+ON voice_sessions (parent_session_id)
+WHERE parent_session_id IS NOT NULL;
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS anilist_links (
+    user_id BIGINT PRIMARY KEY,
+    anilist_user_id INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    -- Whether the periodic sync keeps the user's subscriptions up to date
+    auto_sync BOOLEAN NOT NULL DEFAULT TRUE,
+    -- AniList media IDs the sync subscribed the user to
+    synced_media_ids JSONB NOT NULL DEFAULT '[]',
+    -- AniList media IDs the user unsubscribed from after a sync
+    ignored_media_ids JSONB NOT NULL DEFAULT '[]',
+    last_synced_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
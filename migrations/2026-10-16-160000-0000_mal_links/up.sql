@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS mal_links (
+    user_id BIGINT PRIMARY KEY,
+    mal_user_id INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    -- OAuth tokens for reading the user's lists
+    access_token TEXT NOT NULL,
+    refresh_token TEXT NOT NULL,
+    token_expires_at TIMESTAMPTZ NOT NULL,
+    last_imported_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1 @@
+ALTER TABLE subscribers ADD COLUMN IF NOT EXISTS preferences JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS emoji_stats (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    -- ID of the guild's custom emoji or sticker
+    emoji_id BIGINT NOT NULL,
+    -- 'emoji' or 'sticker'
+    kind TEXT NOT NULL DEFAULT 'emoji',
+    name TEXT NOT NULL,
+    animated BOOLEAN NOT NULL DEFAULT FALSE,
+    message_uses INTEGER NOT NULL DEFAULT 0,
+    reaction_uses INTEGER NOT NULL DEFAULT 0,
+    last_used_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (guild_id, emoji_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_emoji_stats_guild_kind
+ON emoji_stats (guild_id, kind);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS invite_uses (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    member_id BIGINT NOT NULL,
+    -- Creator of the invite used; NULL when it could not be told, e.g. vanity URL joins
+    inviter_id BIGINT,
+    invite_code TEXT,
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_invite_uses_guild_inviter
+ON invite_uses (guild_id, inviter_id);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS temp_voice_channels (
+    channel_id BIGINT PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    owner_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_temp_voice_channels_guild
+ON temp_voice_channels (guild_id);
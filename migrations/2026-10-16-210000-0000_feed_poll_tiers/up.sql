@@ -0,0 +1 @@
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS poll_tier TEXT NOT NULL DEFAULT 'active';
@@ -0,0 +1 @@
+ALTER TABLE feed_subscriptions ADD COLUMN IF NOT EXISTS added_by TEXT;
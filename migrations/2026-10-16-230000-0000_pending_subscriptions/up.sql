@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS pending_subscriptions (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    source_url TEXT NOT NULL,
+    requested_by BIGINT NOT NULL,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (guild_id, source_url)
+);
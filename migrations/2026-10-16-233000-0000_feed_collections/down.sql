@@ -0,0 +1,2 @@
+ALTER TABLE feed_subscriptions DROP COLUMN IF EXISTS collection_id;
+DROP TABLE IF EXISTS feed_collections;
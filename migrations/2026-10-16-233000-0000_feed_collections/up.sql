@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS feed_collections (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    mention_role_id BIGINT,
+    UNIQUE (guild_id, name)
+);
+
+ALTER TABLE feed_subscriptions
+ADD COLUMN IF NOT EXISTS collection_id INTEGER REFERENCES feed_collections (id) ON DELETE SET NULL;
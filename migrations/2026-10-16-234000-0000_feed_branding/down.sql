@@ -0,0 +1,2 @@
+ALTER TABLE feeds DROP COLUMN IF EXISTS emoji;
+ALTER TABLE feeds DROP COLUMN IF EXISTS accent_color;
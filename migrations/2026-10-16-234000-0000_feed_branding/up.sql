@@ -0,0 +1,2 @@
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS accent_color INTEGER;
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS emoji TEXT;
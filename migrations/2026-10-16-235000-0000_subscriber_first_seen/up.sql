@@ -0,0 +1,2 @@
+-- Existing subscribers already use the bot, so only new ones get the onboarding message
+ALTER TABLE subscribers ADD COLUMN IF NOT EXISTS first_seen BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_notification_log_sent_at;
+ALTER TABLE notification_log DROP COLUMN IF EXISTS delay_secs;
+ALTER TABLE notification_log DROP COLUMN IF EXISTS delivered;
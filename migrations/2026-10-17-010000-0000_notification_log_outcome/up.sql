@@ -0,0 +1,6 @@
+-- Rows logged so far were all successful deliveries
+ALTER TABLE notification_log ADD COLUMN IF NOT EXISTS delivered BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE notification_log ADD COLUMN IF NOT EXISTS delay_secs INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_notification_log_sent_at
+ON notification_log (sent_at);
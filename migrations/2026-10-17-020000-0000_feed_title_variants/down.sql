@@ -0,0 +1,3 @@
+ALTER TABLE feeds DROP COLUMN IF EXISTS title_native;
+ALTER TABLE feeds DROP COLUMN IF EXISTS title_romanized;
+ALTER TABLE feeds DROP COLUMN IF EXISTS title_english;
@@ -0,0 +1,4 @@
+-- Filled in when a feed is first tracked, on platforms that list them
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS title_english TEXT;
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS title_romanized TEXT;
+ALTER TABLE feeds ADD COLUMN IF NOT EXISTS title_native TEXT;
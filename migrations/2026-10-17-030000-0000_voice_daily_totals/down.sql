@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS voice_daily_totals_sync ON voice_sessions;
+DROP FUNCTION IF EXISTS voice_daily_totals_sync();
+DROP FUNCTION IF EXISTS voice_daily_totals_apply(voice_sessions, BIGINT);
+DROP TABLE IF EXISTS voice_daily_totals;
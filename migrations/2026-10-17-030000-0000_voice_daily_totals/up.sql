@@ -0,0 +1,56 @@
+-- Voice time of ended sessions per UTC day, so leaderboards over long ranges add up
+-- days instead of scanning every session. Seconds are not weighted, so channel weight
+-- changes apply to past days too.
+CREATE TABLE IF NOT EXISTS voice_daily_totals (
+    guild_id BIGINT NOT NULL,
+    day DATE NOT NULL,
+    user_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    seconds BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (guild_id, day, user_id, channel_id)
+);
+
+-- Adds the time of an ended session to each UTC day it spans, or takes it away with a
+-- `sign` of -1
+CREATE OR REPLACE FUNCTION voice_daily_totals_apply(session voice_sessions, sign BIGINT)
+RETURNS void AS $$
+    INSERT INTO voice_daily_totals (guild_id, day, user_id, channel_id, seconds)
+    SELECT
+        session.guild_id,
+        day::date,
+        session.user_id,
+        session.channel_id,
+        sign * (
+            EXTRACT(EPOCH FROM LEAST(session.leave_time AT TIME ZONE 'UTC', day + INTERVAL '1 day'))::bigint -
+            EXTRACT(EPOCH FROM GREATEST(session.join_time AT TIME ZONE 'UTC', day))::bigint
+        )
+    FROM generate_series(
+        date_trunc('day', session.join_time AT TIME ZONE 'UTC'),
+        session.leave_time AT TIME ZONE 'UTC',
+        INTERVAL '1 day'
+    ) AS day
+    WHERE session.leave_time > session.join_time
+    ON CONFLICT (guild_id, day, user_id, channel_id)
+    DO UPDATE SET seconds = voice_daily_totals.seconds + EXCLUDED.seconds;
+$$ LANGUAGE sql;
+
+-- Keeps the totals in step with every write to voice_sessions, including deletes
+-- cascading to later splits of a session
+CREATE OR REPLACE FUNCTION voice_daily_totals_sync() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') AND NOT OLD.is_active THEN
+        PERFORM voice_daily_totals_apply(OLD, -1);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') AND NOT NEW.is_active THEN
+        PERFORM voice_daily_totals_apply(NEW, 1);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER voice_daily_totals_sync
+AFTER INSERT OR UPDATE OR DELETE ON voice_sessions
+FOR EACH ROW EXECUTE FUNCTION voice_daily_totals_sync();
+
+-- Sessions ended before this migration
+SELECT voice_daily_totals_apply(vs, 1) FROM voice_sessions vs WHERE NOT vs.is_active;
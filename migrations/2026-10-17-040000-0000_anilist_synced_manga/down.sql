@@ -0,0 +1 @@
+ALTER TABLE anilist_links DROP COLUMN IF EXISTS synced_manga_urls;
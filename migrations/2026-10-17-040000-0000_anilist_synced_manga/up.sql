@@ -0,0 +1,2 @@
+-- MangaDex feed URLs of the manga the AniList sync subscribed to, by AniList media ID
+ALTER TABLE anilist_links ADD COLUMN IF NOT EXISTS synced_manga_urls JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,9 @@
+-- Feed notifications about to be sent, removed once sent or failed. Rows a run left
+-- behind by stopping mid-delivery are delivered again on the next start.
+CREATE TABLE IF NOT EXISTS delivery_outbox (
+    id SERIAL PRIMARY KEY,
+    subscriber_id INTEGER NOT NULL REFERENCES subscribers(id) ON DELETE CASCADE,
+    -- The feed update, serialized
+    data JSONB NOT NULL,
+    queued_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
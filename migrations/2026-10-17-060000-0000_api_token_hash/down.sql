@@ -0,0 +1,4 @@
+-- The hashes can't be turned back into tokens, so existing tokens have to be generated again
+DELETE FROM api_tokens;
+
+ALTER TABLE api_tokens RENAME COLUMN token_hash TO token;
@@ -0,0 +1,4 @@
+-- Store a SHA-256 hash instead of the token; tokens in use keep working
+UPDATE api_tokens SET token = encode(sha256(convert_to(token, 'UTF8')), 'hex');
+
+ALTER TABLE api_tokens RENAME COLUMN token TO token_hash;
@@ -4,12 +4,14 @@ use anyhow::Result;
 use image::RgbaImage;
 use pwr_bot::bot::command::welcome::image_generator::WelcomeCardData;
 use pwr_bot::bot::command::welcome::image_generator::WelcomeImageGenerator;
+use pwr_bot::bot::render::ImageRenderService;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     println!("Generating preview images for welcome templates...");
 
     let generator = WelcomeImageGenerator::new();
+    let renderer = ImageRenderService::default();
     let mut images = Vec::new();
 
     let template_width = 800;
@@ -42,7 +44,7 @@ async fn main() -> Result<()> {
             welcome_message: format!("Preview for Template {i}"),
         };
 
-        let png_bytes = generator.generate_card(data).await?;
+        let png_bytes = generator.generate_card(data, &renderer).await?;
         let img = image::load_from_memory(&png_bytes)?;
 
         images.push(img);
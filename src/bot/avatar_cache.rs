@@ -0,0 +1,221 @@
+//! Disk cache of decoded user avatars.
+//!
+//! Avatars are stored under `<DATA_PATH>/cache/avatars` as small PNGs named after the
+//! user ID and avatar hash, so a new avatar gets a new file and repeated renders skip
+//! Discord's CDN. The least recently used files are evicted once the cache grows past
+//! its size limit.
+
+use std::fs;
+use std::fs::File;
+use std::io::Cursor;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::Result;
+use image::DynamicImage;
+use image::imageops::FilterType;
+use log::debug;
+use log::warn;
+
+/// Default total size of cached avatars.
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Largest avatar download accepted.
+const MAX_DOWNLOAD_BYTES: usize = 8 * 1024 * 1024;
+
+/// Avatars are stored at this size, enough for every image generator.
+const STORED_SIZE: u32 = 128;
+
+/// Downloads, decodes and caches avatars on disk.
+pub struct AvatarCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    client: wreq::Client,
+    /// Total size of the cached files.
+    size: Mutex<u64>,
+}
+
+impl AvatarCache {
+    /// Opens the cache in `dir`, creating the directory if needed.
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let size = Self::files(&dir)?.iter().map(|(_, len, _)| len).sum();
+        let client = wreq::Client::builder()
+            .emulation(wreq_util::Emulation::Chrome137)
+            .build()?;
+
+        Ok(Self {
+            dir,
+            max_bytes,
+            client,
+            size: Mutex::new(size),
+        })
+    }
+
+    /// Returns the avatar at `url` for a user, from disk when cached.
+    pub async fn get(&self, user_id: u64, url: &str) -> Option<DynamicImage> {
+        let path = self.dir.join(Self::file_name(user_id, url));
+
+        if let Some(img) = Self::read(&path) {
+            return Some(img);
+        }
+
+        match self.download(url).await {
+            Ok(img) => {
+                self.store(&path, &img);
+                Some(img)
+            }
+            Err(e) => {
+                debug!("Failed to download avatar {url}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Names the file after the user and the avatar's hash, the last segment of its URL.
+    fn file_name(user_id: u64, url: &str) -> String {
+        let hash = url
+            .split('?')
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .and_then(|file| file.split('.').next())
+            .filter(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("default");
+        format!("{user_id}_{hash}.png")
+    }
+
+    /// Reads a cached avatar and marks it as recently used.
+    fn read(path: &Path) -> Option<DynamicImage> {
+        let bytes = fs::read(path).ok()?;
+        let img = image::load_from_memory(&bytes).ok()?;
+        if let Ok(file) = File::options().append(true).open(path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(img)
+    }
+
+    async fn download(&self, url: &str) -> Result<DynamicImage> {
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {}", response.status().as_u16());
+        }
+        let bytes = response.bytes().await?;
+        if bytes.len() > MAX_DOWNLOAD_BYTES {
+            anyhow::bail!("avatar is {} bytes", bytes.len());
+        }
+
+        let img = image::load_from_memory(&bytes)?;
+        Ok(img.resize_exact(STORED_SIZE, STORED_SIZE, FilterType::Lanczos3))
+    }
+
+    fn store(&self, path: &Path, img: &DynamicImage) {
+        let mut cursor = Cursor::new(Vec::new());
+        if let Err(e) = img.write_to(&mut cursor, image::ImageFormat::Png) {
+            warn!("Failed to encode avatar: {e}");
+            return;
+        }
+        let bytes = cursor.into_inner();
+        if let Err(e) = fs::write(path, &bytes) {
+            warn!("Failed to cache avatar at {}: {e}", path.display());
+            return;
+        }
+
+        let mut size = self.size.lock().unwrap();
+        *size += bytes.len() as u64;
+        if *size > self.max_bytes {
+            *size = self.evict(*size);
+        }
+    }
+
+    /// Deletes the least recently used files until the cache fits in 90% of its limit.
+    /// Returns the new total size.
+    fn evict(&self, mut size: u64) -> u64 {
+        let mut files = match Self::files(&self.dir) {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to list avatar cache: {e}");
+                return size;
+            }
+        };
+        files.sort_by_key(|(_, _, modified)| *modified);
+
+        let target = self.max_bytes / 10 * 9;
+        for (path, len, _) in files {
+            if size <= target {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                size = size.saturating_sub(len);
+            }
+        }
+        debug!("Evicted avatars, cache is now {size} bytes");
+        size
+    }
+
+    /// Lists cached files with their size and last use.
+    fn files(dir: &Path) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.push((entry.path(), metadata.len(), modified));
+            }
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use image::RgbaImage;
+
+    use super::*;
+
+    #[test]
+    fn file_name_uses_avatar_hash() {
+        assert_eq!(
+            AvatarCache::file_name(
+                42,
+                "https://cdn.discordapp.com/avatars/42/a1b2c3.png?size=1024"
+            ),
+            "42_a1b2c3.png"
+        );
+        assert_eq!(
+            AvatarCache::file_name(42, "https://cdn.discordapp.com/embed/avatars/3.png"),
+            "42_3.png"
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let dir = std::env::temp_dir().join(format!("pwr-bot-avatars-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let img = DynamicImage::ImageRgba8(RgbaImage::new(STORED_SIZE, STORED_SIZE));
+        let cache = AvatarCache::new(dir.clone(), u64::MAX).unwrap();
+        cache.store(&dir.join("1_old.png"), &img);
+        let old = File::options()
+            .append(true)
+            .open(dir.join("1_old.png"))
+            .unwrap();
+        old.set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        cache.store(&dir.join("2_new.png"), &img);
+        let size = *cache.size.lock().unwrap();
+
+        let cache = AvatarCache {
+            max_bytes: size - 1,
+            ..cache
+        };
+        cache.evict(size);
+
+        assert!(!dir.join("1_old.png").exists());
+        assert!(dir.join("2_new.png").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,211 @@
+//! "Report bug" buttons on internal error responses.
+//!
+//! Internal errors are answered with a reference ID and a button that sends the error's
+//! details to the bot owner by DM. The details stay in memory until reported, so only
+//! recent errors can be reported, each once.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use log::error;
+use log::info;
+use poise::serenity_prelude::*;
+use uuid::Uuid;
+
+use crate::bot::Data;
+use crate::bot::send_queue::SendTarget;
+
+/// Prefix of the custom IDs of report buttons.
+pub const BUG_REPORT_PREFIX: &str = "report_bug:";
+
+/// Errors kept for reporting. Older ones can no longer be reported.
+const MAX_PENDING_REPORTS: usize = 100;
+
+/// Longest error text sent to the owner, so the DM stays within Discord's limits.
+const MAX_DETAILS_LEN: usize = 1500;
+
+/// Custom ID of a report button, naming the error by its reference ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BugReportId(pub Uuid);
+
+impl BugReportId {
+    /// Encodes the reference ID as a component custom ID.
+    pub fn custom_id(&self) -> String {
+        format!("{BUG_REPORT_PREFIX}{}", self.0)
+    }
+
+    /// Decodes a custom ID. `None` if it is not a report button's.
+    pub fn parse(custom_id: &str) -> Option<Self> {
+        let ref_id = custom_id.strip_prefix(BUG_REPORT_PREFIX)?;
+        Uuid::parse_str(ref_id).ok().map(Self)
+    }
+}
+
+/// What went wrong, as sent to the owner.
+#[derive(Clone, Debug)]
+pub struct BugReport {
+    pub command: String,
+    pub user_id: UserId,
+    pub guild_id: Option<GuildId>,
+    /// The error's debug output.
+    pub details: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Internal errors waiting to be reported, by reference ID.
+#[derive(Default)]
+pub struct BugReports {
+    pending: Mutex<VecDeque<(Uuid, BugReport)>>,
+}
+
+impl BugReports {
+    /// Keeps an error for reporting, forgetting the oldest one if full.
+    pub fn insert(&self, ref_id: Uuid, report: BugReport) {
+        if let Ok(mut pending) = self.pending.lock() {
+            if pending.len() >= MAX_PENDING_REPORTS {
+                pending.pop_front();
+            }
+            pending.push_back((ref_id, report));
+        }
+    }
+
+    /// Returns a pending error.
+    pub fn get(&self, ref_id: Uuid) -> Option<BugReport> {
+        let pending = self.pending.lock().ok()?;
+        pending
+            .iter()
+            .find(|(id, _)| *id == ref_id)
+            .map(|(_, report)| report.clone())
+    }
+
+    /// Forgets an error, returning whether it was pending. Only one click reports it.
+    pub fn remove(&self, ref_id: Uuid) -> bool {
+        let Ok(mut pending) = self.pending.lock() else {
+            return false;
+        };
+        let before = pending.len();
+        pending.retain(|(id, _)| *id != ref_id);
+        pending.len() < before
+    }
+}
+
+/// Sends the error behind a clicked report button to the bot owner.
+pub async fn handle(
+    http: &Http,
+    data: &Data,
+    interaction: &ComponentInteraction,
+    id: BugReportId,
+) -> Result<()> {
+    let text = match report(data, interaction, id).await {
+        Ok(text) => text,
+        Err(e) => {
+            error!("Failed to report error {}: {e:?}", id.0);
+            "❌ The report could not be sent. Please try again later.".to_string()
+        }
+    };
+
+    let response = CreateInteractionResponseMessage::new()
+        .content(text)
+        .ephemeral(true);
+    interaction
+        .create_response(http, CreateInteractionResponse::Message(response))
+        .await?;
+    Ok(())
+}
+
+/// DMs the error to the owner. Returns the message for the member.
+async fn report(
+    data: &Data,
+    interaction: &ComponentInteraction,
+    id: BugReportId,
+) -> Result<String> {
+    let Some(report) = data.bug_reports.get(id.0) else {
+        return Ok("🛈 This error was already reported, or is too old to report.".to_string());
+    };
+    if report.user_id != interaction.user.id {
+        return Ok("❌ Only the member who ran into this error can report it.".to_string());
+    }
+    // Another click may have won the race
+    if !data.bug_reports.remove(id.0) {
+        return Ok("🛈 This error was already reported.".to_string());
+    }
+
+    let owner_id: UserId = data.config.admin_id.parse()?;
+    let message = CreateMessage::new()
+        .flags(MessageFlags::IS_COMPONENTS_V2)
+        .components(vec![CreateComponent::Container(CreateContainer::new(
+            vec![CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(render_report(id.0, &report)),
+            )],
+        ))]);
+    data.send_queue
+        .send(SendTarget::Dm(owner_id.get()), move |http| async move {
+            owner_id.dm(&http, message).await?;
+            Ok(())
+        })
+        .await?;
+
+    info!(
+        "Reported error {} from `/{}` to the bot owner.",
+        id.0, report.command
+    );
+    Ok("✅ Thanks! The bot owner received the details of this error.".to_string())
+}
+
+/// Formats a report for the owner's DM.
+fn render_report(ref_id: Uuid, report: &BugReport) -> String {
+    let guild = report
+        .guild_id
+        .map_or_else(|| "DM".to_string(), |guild_id| format!("`{guild_id}`"));
+    let details: String = report.details.chars().take(MAX_DETAILS_LEN).collect();
+    format!(
+        "## 🐞 Bug Report\n**Reference:** `{ref_id}`\n**Command:** `/{}`\n**Reported by:** <@{}>\n**Server:** {guild}\n**Occurred:** <t:{}:f>\n```\n{}\n```",
+        report.command,
+        report.user_id,
+        report.occurred_at.timestamp(),
+        details.replace("```", "'''"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> BugReport {
+        BugReport {
+            command: "vc stats".to_string(),
+            user_id: UserId::new(1),
+            guild_id: None,
+            details: "boom".to_string(),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn bug_report_id_round_trips() {
+        let id = BugReportId(Uuid::new_v4());
+
+        assert_eq!(BugReportId::parse(&id.custom_id()), Some(id));
+        assert_eq!(BugReportId::parse("report_bug:nope"), None);
+        assert_eq!(BugReportId::parse("temp_voice:lock"), None);
+    }
+
+    #[test]
+    fn bug_reports_are_reported_once_and_capped() {
+        let reports = BugReports::default();
+        let first = Uuid::new_v4();
+        reports.insert(first, report());
+        for _ in 0..MAX_PENDING_REPORTS {
+            reports.insert(Uuid::new_v4(), report());
+        }
+        assert!(reports.get(first).is_none());
+
+        let last = Uuid::new_v4();
+        reports.insert(last, report());
+        assert!(reports.remove(last));
+        assert!(!reports.remove(last));
+    }
+}
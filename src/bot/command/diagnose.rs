@@ -0,0 +1,289 @@
+//! Diagnose command reporting missing bot permissions per feature.
+
+use crate::bot::command::prelude::*;
+use crate::entity::ServerSettings;
+
+/// Permissions needed to post feed notifications.
+const FEED_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::SEND_MESSAGES)
+    .union(Permissions::EMBED_LINKS);
+
+/// Permissions needed to post welcome cards.
+const WELCOME_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::SEND_MESSAGES)
+    .union(Permissions::ATTACH_FILES);
+
+/// Permissions needed to see who is in a voice channel.
+const VOICE_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL;
+
+/// Display names of the permissions checked by this command.
+const PERMISSION_NAMES: [(Permissions, &str); 5] = [
+    (Permissions::VIEW_CHANNEL, "View Channel"),
+    (Permissions::SEND_MESSAGES, "Send Messages"),
+    (
+        Permissions::SEND_MESSAGES_IN_THREADS,
+        "Send Messages in Threads",
+    ),
+    (Permissions::EMBED_LINKS, "Embed Links"),
+    (Permissions::ATTACH_FILES, "Attach Files"),
+];
+
+/// Check the bot's permissions for each feature
+///
+/// Inspects the bot's effective permissions in the feed channel, welcome
+/// channel and voice channels, and reports which features will fail.
+/// Requires server administrator permissions.
+#[poise::command(slash_command, ephemeral)]
+pub async fn diagnose(ctx: Context<'_>) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+    ctx.defer_ephemeral().await?;
+
+    let settings = ctx
+        .data()
+        .service
+        .settings
+        .get_server_settings(guild_id.get())
+        .await?;
+    let bot_id = ctx.cache().current_user().id;
+    let bot_member = ctx.http().get_member(guild_id, bot_id).await?;
+
+    let reports = {
+        let guild = ctx.guild().ok_or(BotError::GuildOnlyCommand)?;
+        FeatureReport::all(&guild, &bot_member, &settings)
+    };
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(render_reports(&reports))),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(CreateReply::from(response).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Outcome of checking one feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnosis {
+    /// Everything the feature needs is granted.
+    Ready(String),
+    /// The feature is disabled or unconfigured, so nothing was checked.
+    Skipped(String),
+    /// The feature will fail. Each entry describes one problem.
+    Failing(Vec<String>),
+}
+
+/// Diagnosis of a single feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureReport {
+    pub feature: &'static str,
+    pub diagnosis: Diagnosis,
+}
+
+impl FeatureReport {
+    /// Checks every feature against the bot's permissions in the guild.
+    pub fn all(guild: &Guild, bot: &Member, settings: &ServerSettings) -> Vec<Self> {
+        vec![
+            Self::feeds(guild, bot, settings),
+            Self::welcome(guild, bot, settings),
+            Self::voice(guild, bot, settings),
+        ]
+    }
+
+    fn feeds(guild: &Guild, bot: &Member, settings: &ServerSettings) -> Self {
+        let feeds = &settings.feeds;
+        let diagnosis = if !feeds.enabled.unwrap_or(false) {
+            Diagnosis::Skipped("Feeds are disabled.".to_string())
+        } else {
+            check_channel(guild, bot, feeds.channel_id.as_deref(), FEED_PERMISSIONS)
+        };
+        Self {
+            feature: "Feeds",
+            diagnosis,
+        }
+    }
+
+    fn welcome(guild: &Guild, bot: &Member, settings: &ServerSettings) -> Self {
+        let welcome = &settings.welcome;
+        let diagnosis = if !welcome.enabled.unwrap_or(false) {
+            Diagnosis::Skipped("Welcome cards are disabled.".to_string())
+        } else {
+            check_channel(
+                guild,
+                bot,
+                welcome.channel_id.as_deref(),
+                WELCOME_PERMISSIONS,
+            )
+        };
+        Self {
+            feature: "Welcome",
+            diagnosis,
+        }
+    }
+
+    fn voice(guild: &Guild, bot: &Member, settings: &ServerSettings) -> Self {
+        let voice = &settings.voice;
+        if !voice.is_enabled() {
+            return Self {
+                feature: "Voice",
+                diagnosis: Diagnosis::Skipped("Voice tracking is disabled.".to_string()),
+            };
+        }
+
+        let mut tracked = 0;
+        let problems: Vec<_> = guild
+            .channels
+            .iter()
+            .filter(|channel| {
+                channel.kind == ChannelType::Voice
+                    || (channel.kind == ChannelType::Stage && voice.tracks_stage_channels())
+            })
+            .filter_map(|channel| {
+                tracked += 1;
+                let granted = guild.user_permissions_in(channel, bot);
+                let missing = missing_permissions(VOICE_PERMISSIONS, granted);
+                (!missing.is_empty()).then(|| {
+                    format!(
+                        "<#{}> is not tracked: missing {}",
+                        channel.id,
+                        permission_names(missing).join(", ")
+                    )
+                })
+            })
+            .collect();
+
+        let diagnosis = if problems.is_empty() {
+            Diagnosis::Ready(format!("All {tracked} voice channels are visible."))
+        } else {
+            Diagnosis::Failing(problems)
+        };
+        Self {
+            feature: "Voice",
+            diagnosis,
+        }
+    }
+}
+
+/// Checks the bot's permissions in a configured channel.
+fn check_channel(
+    guild: &Guild,
+    bot: &Member,
+    channel_id: Option<&str>,
+    required: Permissions,
+) -> Diagnosis {
+    let Some(channel_id) = channel_id.and_then(|id| id.parse::<u64>().ok()) else {
+        return Diagnosis::Failing(vec!["No channel is configured.".to_string()]);
+    };
+    let Some(channel) = guild.channels.get(&ChannelId::new(channel_id)) else {
+        return Diagnosis::Failing(vec![format!(
+            "The configured channel `{channel_id}` was not found. It may have been deleted."
+        )]);
+    };
+
+    let required = required_in_channel(required, channel.kind);
+    let granted = guild.user_permissions_in(channel, bot);
+    let missing = missing_permissions(required, granted);
+    if missing.is_empty() {
+        Diagnosis::Ready(format!("All permissions granted in <#{channel_id}>."))
+    } else {
+        Diagnosis::Failing(vec![format!(
+            "Missing in <#{channel_id}>: {}",
+            permission_names(missing).join(", ")
+        )])
+    }
+}
+
+/// Adjusts required permissions for the kind of channel posted into.
+///
+/// Posting in a thread needs `Send Messages in Threads` instead of `Send Messages`.
+pub fn required_in_channel(required: Permissions, kind: ChannelType) -> Permissions {
+    let is_thread = matches!(
+        kind,
+        ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+    );
+    if is_thread && required.contains(Permissions::SEND_MESSAGES) {
+        required.difference(Permissions::SEND_MESSAGES) | Permissions::SEND_MESSAGES_IN_THREADS
+    } else {
+        required
+    }
+}
+
+/// Returns the required permissions that are not granted.
+pub fn missing_permissions(required: Permissions, granted: Permissions) -> Permissions {
+    if granted.contains(Permissions::ADMINISTRATOR) {
+        return Permissions::empty();
+    }
+    required.difference(granted)
+}
+
+/// Lists the display names of the given permissions.
+pub fn permission_names(permissions: Permissions) -> Vec<&'static str> {
+    PERMISSION_NAMES
+        .iter()
+        .filter(|(permission, _)| permissions.contains(*permission))
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Formats the reports as a single message.
+fn render_reports(reports: &[FeatureReport]) -> String {
+    let mut text = "## Permission Check".to_string();
+    for report in reports {
+        text.push_str(&format!("\n### {}\n", report.feature));
+        match &report.diagnosis {
+            Diagnosis::Ready(summary) => text.push_str(&format!("✅ {summary}")),
+            Diagnosis::Skipped(reason) => text.push_str(&format!("-# {reason}")),
+            Diagnosis::Failing(problems) => {
+                text.push_str("⚠️ This feature will fail:");
+                for problem in problems {
+                    text.push_str(&format!("\n- {problem}"));
+                }
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_permissions_ignores_granted_and_admin() {
+        let granted = Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES;
+        assert_eq!(
+            missing_permissions(FEED_PERMISSIONS, granted),
+            Permissions::EMBED_LINKS
+        );
+        assert!(missing_permissions(FEED_PERMISSIONS, Permissions::ADMINISTRATOR).is_empty());
+    }
+
+    #[test]
+    fn required_in_channel_swaps_send_messages_for_threads() {
+        let required = required_in_channel(WELCOME_PERMISSIONS, ChannelType::PublicThread);
+        assert!(required.contains(Permissions::SEND_MESSAGES_IN_THREADS));
+        assert!(!required.contains(Permissions::SEND_MESSAGES));
+        assert_eq!(
+            required_in_channel(WELCOME_PERMISSIONS, ChannelType::Text),
+            WELCOME_PERMISSIONS
+        );
+    }
+
+    #[test]
+    fn permission_names_lists_known_permissions() {
+        assert_eq!(
+            permission_names(Permissions::EMBED_LINKS | Permissions::ATTACH_FILES),
+            vec!["Embed Links", "Attach Files"]
+        );
+    }
+
+    #[test]
+    fn render_reports_lists_problems() {
+        let text = render_reports(&[FeatureReport {
+            feature: "Feeds",
+            diagnosis: Diagnosis::Failing(vec!["Missing in <#1>: Embed Links".to_string()]),
+        }]);
+        assert!(text.contains("### Feeds"));
+        assert!(text.contains("- Missing in <#1>: Embed Links"));
+    }
+}
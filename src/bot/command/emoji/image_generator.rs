@@ -0,0 +1,230 @@
+//! Image generation for emoji stats.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::time::Instant;
+
+use anyhow::Result;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use image::imageops::FilterType;
+use log::trace;
+use log::warn;
+use minijinja::Environment;
+use minijinja::context;
+use serde::Serialize;
+
+use crate::bot::render::ImageRenderService;
+use crate::entity::EmojiStatEntity;
+
+const IMAGE_WIDTH: u32 = 500;
+const IMAGE_HEIGHT_PER_ENTRY: u32 = 64;
+const PADDING: u32 = 12;
+const EMOJI_SIZE: u32 = 40;
+
+const GOLD_COLOR: &str = "#FACC15";
+const SILVER_COLOR: &str = "#B7B8BD";
+const BRONZE_COLOR: &str = "#EB459E";
+const TEXT_COLOR: &str = "#F2F3F5";
+const PROGRESS_COLOR: &str = "rgba(88, 101, 242, 0.235)";
+const PROGRESS_TOP_COLOR: &str = "rgba(88, 101, 242, 0.392)";
+
+/// Defines the exact data structure expected by the Minijinja SVG template.
+#[derive(Serialize)]
+struct TemplateEntry {
+    rank: u32,
+    rank_color: &'static str,
+    name: String,
+    uses: i64,
+    /// Uses split into messages and reactions.
+    breakdown: String,
+
+    // Layout metrics calculated by Rust
+    card_y: u32,
+    progress_width: u32,
+    progress_color: &'static str,
+    text_baseline: f32,
+    name_baseline: f32,
+    breakdown_baseline: f32,
+    image_y: u32,
+
+    // Processed data
+    image_b64: Option<String>,
+}
+
+pub struct EmojiStatsImageGenerator {
+    http_client: wreq::Client,
+    /// Base64 PNGs by CDN url.
+    image_cache: HashMap<String, String>,
+    jinja_env: Environment<'static>,
+}
+
+impl EmojiStatsImageGenerator {
+    pub fn new() -> Self {
+        let http_client = wreq::Client::builder()
+            .emulation(wreq_util::Emulation::Chrome137)
+            .build()
+            .expect("Failed to build HTTP client");
+
+        // Initialize template engine and load the template
+        let mut jinja_env = Environment::new();
+        let template_str = include_str!("../../../../assets/emoji_stats.svg");
+        jinja_env.add_template("emoji_stats", template_str).unwrap();
+
+        Self {
+            http_client,
+            image_cache: HashMap::new(),
+            jinja_env,
+        }
+    }
+
+    /// Downloads an emoji or sticker image and encodes it for the template.
+    async fn download_image(&self, url: &str) -> Result<String> {
+        let response = self.http_client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to download emoji: {}",
+                response.status()
+            ));
+        }
+        let bytes = response.bytes().await?;
+        let img = image::load_from_memory(&bytes)?;
+        let resized = img.resize_exact(EMOJI_SIZE, EMOJI_SIZE, FilterType::Lanczos3);
+        let mut cursor = Cursor::new(Vec::new());
+        resized.write_to(&mut cursor, image::ImageFormat::Png)?;
+        Ok(BASE64.encode(cursor.into_inner()))
+    }
+
+    /// Downloads the images of `stats` not yet cached.
+    ///
+    /// Images that fail to download, such as animated stickers, render as a placeholder.
+    async fn fetch_missing_images(&mut self, stats: &[EmojiStatEntity]) {
+        let urls: Vec<String> = stats
+            .iter()
+            .map(EmojiStatEntity::image_url)
+            .filter(|url| !self.image_cache.contains_key(url))
+            .collect();
+        let downloads = urls.iter().map(|url| self.download_image(url));
+        let images = futures::future::join_all(downloads).await;
+
+        for (url, image) in urls.into_iter().zip(images) {
+            match image {
+                Ok(b64) => {
+                    self.image_cache.insert(url, b64);
+                }
+                Err(e) => warn!("Failed to load emoji image {url}: {e}"),
+            }
+        }
+    }
+
+    /// Renders a page of emoji stats ranked from `rank_offset + 1`. Bars are relative to
+    /// `max_uses`, the most used emoji of the guild. Rasterizing runs on `renderer`, off
+    /// the async runtime.
+    pub async fn generate_stats(
+        &mut self,
+        stats: &[EmojiStatEntity],
+        rank_offset: u32,
+        max_uses: i64,
+        renderer: &ImageRenderService,
+    ) -> Result<Vec<u8>> {
+        let total_start = Instant::now();
+
+        // 1. Ensure all images are cached
+        self.fetch_missing_images(stats).await;
+
+        // 2. Pre-calculate metrics to keep the template logic-less
+        let total_height = (stats.len() as u32 * IMAGE_HEIGHT_PER_ENTRY) + PADDING * 2;
+        let max_uses = max_uses.max(1);
+        let card_w = IMAGE_WIDTH - (PADDING * 2);
+        let uses_x = PADDING + card_w - 15;
+
+        // 3. Map entries to the template structure
+        let template_entries: Vec<TemplateEntry> = stats
+            .iter()
+            .enumerate()
+            .map(|(idx, stat)| {
+                let rank = rank_offset + idx as u32 + 1;
+                let y = PADDING + (idx as u32 * IMAGE_HEIGHT_PER_ENTRY);
+                let row_center_y = y + (IMAGE_HEIGHT_PER_ENTRY / 2);
+
+                let rank_color = match rank {
+                    1 => GOLD_COLOR,
+                    2 => SILVER_COLOR,
+                    3 => BRONZE_COLOR,
+                    _ => TEXT_COLOR,
+                };
+
+                let progress_width =
+                    ((stat.total_uses() as f32 / max_uses as f32) * card_w as f32) as u32;
+                let progress_color = if rank <= 3 {
+                    PROGRESS_TOP_COLOR
+                } else {
+                    PROGRESS_COLOR
+                };
+
+                TemplateEntry {
+                    rank,
+                    rank_color,
+                    name: format!(":{}:", stat.name), // Minijinja auto-escapes HTML/XML by default
+                    uses: stat.total_uses(),
+                    breakdown: format!(
+                        "{} in messages · {} as reactions",
+                        stat.message_uses, stat.reaction_uses
+                    ),
+                    card_y: y + 2,
+                    progress_width: progress_width.min(card_w),
+                    progress_color,
+                    text_baseline: row_center_y as f32 + 6.0,
+                    name_baseline: row_center_y as f32 - 1.0,
+                    breakdown_baseline: row_center_y as f32 + 16.0,
+                    image_y: row_center_y.saturating_sub(EMOJI_SIZE / 2),
+                    image_b64: self.image_cache.get(&stat.image_url()).cloned(),
+                }
+            })
+            .collect();
+
+        // 4. Render the template
+        let template = self.jinja_env.get_template("emoji_stats")?;
+        let svg = template.render(context! {
+            image_width => IMAGE_WIDTH,
+            total_height => total_height,
+            card_w => card_w,
+            card_h => IMAGE_HEIGHT_PER_ENTRY - 4,
+            uses_x => uses_x,
+            entries => template_entries,
+        })?;
+
+        let png = renderer
+            .render("emoji_stats", move || {
+                Self::svg_to_png(&svg, IMAGE_WIDTH, total_height)
+            })
+            .await?;
+
+        trace!(
+            "generate_stats total {} ms",
+            total_start.elapsed().as_millis()
+        );
+        Ok(png)
+    }
+
+    fn svg_to_png(svg: &str, width: u32, height: u32) -> Result<Vec<u8>> {
+        let mut fontdb = resvg::usvg::fontdb::Database::new();
+        fontdb
+            .load_font_data(include_bytes!("../../../../assets/fonts/Roboto-Regular.ttf").to_vec());
+
+        let options = resvg::usvg::Options {
+            fontdb: std::sync::Arc::new(fontdb),
+            ..Default::default()
+        };
+
+        let tree = resvg::usvg::Tree::from_str(svg, &options)?;
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+            .ok_or_else(|| anyhow::anyhow!("Failed to create pixmap"))?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+        Ok(pixmap.encode_png()?)
+    }
+}
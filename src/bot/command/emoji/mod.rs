@@ -0,0 +1,14 @@
+//! Custom emoji and sticker commands.
+
+use crate::bot::command::prelude::*;
+
+pub mod image_generator;
+pub mod stats;
+
+/// Custom emoji and sticker commands
+///
+/// See how this server's own emojis and stickers are used.
+#[poise::command(slash_command, subcommands("stats::stats"))]
+pub async fn emoji(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
@@ -0,0 +1,230 @@
+//! Emoji stats subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::bot::command::emoji::image_generator::EmojiStatsImageGenerator;
+use crate::bot::command::prelude::*;
+use crate::bot::render::ImageRenderService;
+use crate::bot::view::pagination::PaginationModel;
+use crate::entity::EmojiKind;
+use crate::entity::EmojiStatEntity;
+use crate::service::traits::EmojiStatsProvider;
+
+/// Filename for the emoji stats image attachment.
+pub const IMAGE_FILENAME: &str = "emoji_stats.png";
+
+/// Number of emojis or stickers per page.
+const STATS_PER_PAGE: u32 = 10;
+
+/// Show the most used emojis and stickers of this server
+///
+/// Ranks the server's own custom emojis by how often they are sent in messages
+/// and added as reactions. Switch to stickers with the buttons.
+#[poise::command(slash_command)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::EmojiStats).await?;
+    Ok(())
+}
+
+handler! { pub struct EmojiStatsHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for EmojiStatsHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+        ctx.defer().await?;
+
+        let mut view = EmojiStatsView {
+            kind: EmojiKind::Emoji,
+            stats: Vec::new(),
+            max_uses: 0,
+            pagination: PaginationView::new(0u32, STATS_PER_PAGE),
+            image: None,
+            image_gen: EmojiStatsImageGenerator::new(),
+            renderer: ctx.data().renderer.clone(),
+            service: ctx.data().service.emoji_stats.clone(),
+            guild_id,
+        };
+        view.show_kind(EmojiKind::Emoji).await?;
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct EmojiStatsView {
+    pub kind: EmojiKind,
+    pub stats: Vec<EmojiStatEntity>,
+    /// Uses of the most used emoji or sticker, which fills a whole bar.
+    pub max_uses: i64,
+    pub pagination: PaginationView,
+    /// Rendered image of the current page.
+    pub image: Option<Vec<u8>>,
+    pub image_gen: EmojiStatsImageGenerator,
+    pub renderer: Arc<ImageRenderService>,
+    pub service: Arc<dyn EmojiStatsProvider>,
+    pub guild_id: u64,
+}
+
+impl EmojiStatsView {
+    /// Switches to the first page of `kind`.
+    async fn show_kind(&mut self, kind: EmojiKind) -> Result<(), Error> {
+        let total = self.service.count_emoji_stats(self.guild_id, kind).await?;
+        self.kind = kind;
+        self.pagination.state =
+            PaginationModel::new(total.div_ceil(STATS_PER_PAGE), STATS_PER_PAGE, 1);
+        self.update_page().await?;
+        self.max_uses = self
+            .stats
+            .first()
+            .map(EmojiStatEntity::total_uses)
+            .unwrap_or(0);
+        self.generate_img().await;
+        Ok(())
+    }
+
+    /// Loads the current page.
+    async fn update_page(&mut self) -> Result<(), Error> {
+        self.stats = self
+            .service
+            .list_emoji_stats(
+                self.guild_id,
+                self.kind,
+                self.pagination.current_page(),
+                STATS_PER_PAGE,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Renders the image of the current page. The page is shown without one if it fails.
+    async fn generate_img(&mut self) {
+        self.image = None;
+        if self.stats.is_empty() {
+            return;
+        }
+        let rank_offset = (self.pagination.current_page() - 1) * STATS_PER_PAGE;
+        match self
+            .image_gen
+            .generate_stats(&self.stats, rank_offset, self.max_uses, &self.renderer)
+            .await
+        {
+            Ok(image) => self.image = Some(image),
+            Err(e) => log::error!("Failed to generate emoji stats image: {e}"),
+        }
+    }
+
+    /// Returns the user-facing name of the shown kind.
+    fn kind_name(&self) -> &'static str {
+        match self.kind {
+            EmojiKind::Emoji => "Emojis",
+            EmojiKind::Sticker => "Stickers",
+        }
+    }
+}
+
+action_extends! { EmojiStatsAction extends PaginationAction {
+    #[label = "Emojis"]
+    ShowEmojis,
+    #[label = "Stickers"]
+    ShowStickers,
+}}
+
+#[async_trait::async_trait]
+impl ViewHandler for EmojiStatsView {
+    type Action = EmojiStatsAction;
+    async fn handle(&mut self, ctx: ViewContext<'_, EmojiStatsAction>) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            EmojiStatsAction::Base(inner) => {
+                match inner {
+                    PaginationAction::First => self.pagination.state.first_page(),
+                    PaginationAction::Prev => self.pagination.state.prev_page(),
+                    PaginationAction::Next => self.pagination.state.next_page(),
+                    PaginationAction::Last => self.pagination.state.last_page(),
+                    PaginationAction::Page => return Ok(ViewCmd::Continue),
+                }
+                self.update_page().await?;
+                self.generate_img().await;
+            }
+            EmojiStatsAction::ShowEmojis => self.show_kind(EmojiKind::Emoji).await?,
+            EmojiStatsAction::ShowStickers => self.show_kind(EmojiKind::Sticker).await?,
+        }
+        Ok(ViewCmd::Render)
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.pagination.disabled = true;
+        Ok(ViewCmd::RenderOnce)
+    }
+}
+
+impl ViewRender for EmojiStatsView {
+    type Action = EmojiStatsAction;
+    fn render(&self, registry: &mut ActionRegistry<EmojiStatsAction>) -> ResponseKind<'_> {
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!("## Most Used {}", self.kind_name())),
+        )];
+
+        if self.stats.is_empty() {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(format!(
+                    "> 🛈  No {} of this server have been used yet.",
+                    self.kind_name().to_lowercase()
+                )),
+            ));
+        } else if self.image.is_some() {
+            sections.push(CreateContainerComponent::MediaGallery(
+                CreateMediaGallery::new(vec![CreateMediaGalleryItem::new(
+                    CreateUnfurledMediaItem::new(format!("attachment://{IMAGE_FILENAME}")),
+                )]),
+            ));
+        } else {
+            let text = self
+                .stats
+                .iter()
+                .map(|stat| format!("- `:{}:` — {} uses", stat.name, stat.total_uses()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(text),
+            ));
+        }
+
+        if !self.pagination.disabled {
+            let emojis = registry
+                .register(EmojiStatsAction::ShowEmojis)
+                .as_button()
+                .style(ButtonStyle::Secondary)
+                .disabled(self.kind == EmojiKind::Emoji);
+            let stickers = registry
+                .register(EmojiStatsAction::ShowStickers)
+                .as_button()
+                .style(ButtonStyle::Secondary)
+                .disabled(self.kind == EmojiKind::Sticker);
+            sections.push(CreateContainerComponent::ActionRow(
+                CreateActionRow::Buttons(vec![emojis, stickers].into()),
+            ));
+        }
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+        self.pagination
+            .attach_if_multipage(registry, &mut components, EmojiStatsAction::Base);
+
+        components.into()
+    }
+
+    fn create_reply(&self, registry: &mut ActionRegistry<EmojiStatsAction>) -> CreateReply<'_> {
+        let mut reply: CreateReply<'_> = self.render(registry).into();
+
+        if let Some(bytes) = &self.image {
+            reply = reply.attachment(CreateAttachment::bytes(bytes.clone(), IMAGE_FILENAME));
+        }
+
+        reply
+    }
+}
@@ -0,0 +1,147 @@
+//! AniList account linking and list sync subcommands.
+
+use crate::bot::command::prelude::*;
+use crate::service::anilist_sync::AniListSyncResult;
+
+/// Titles listed after a sync before the rest are summarized.
+const TITLES_SHOWN: usize = 10;
+
+/// Link your AniList account
+///
+/// Links an AniList username so `/feed sync-anilist` can subscribe you to the
+/// anime you watch and the manga you read. With auto sync on, your lists are
+/// synced again every 12 hours. Notifications are sent to your DMs.
+#[poise::command(slash_command, rename = "link-anilist", ephemeral)]
+pub async fn link_anilist(
+    ctx: Context<'_>,
+    #[description = "Your AniList username"]
+    #[max_length = 20]
+    username: String,
+    #[description = "Sync your list again every 12 hours. Defaults to on"] auto_sync: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let link = ctx
+        .data()
+        .service
+        .anilist_sync
+        .link(ctx.author().id.get(), &username, auto_sync.unwrap_or(true))
+        .await?;
+
+    let text = format!(
+        "### AniList Linked\nLinked [{}](<https://anilist.co/user/{}>). Run `/feed sync-anilist` to subscribe to your watching and reading lists.\n-# Auto sync is {}",
+        link.username,
+        link.username,
+        if link.auto_sync { "on" } else { "off" }
+    );
+    send_text(ctx, text).await
+}
+
+/// Subscribe to the anime and manga on your AniList lists
+///
+/// Subscribes you in DMs to every airing anime you are watching and every
+/// releasing manga you are reading on your linked AniList account. Manga are
+/// found on MangaDex by title. Entries an earlier sync added that left your
+/// lists are unsubscribed. Subscriptions you made yourself are never removed,
+/// and entries you unsubscribed from after a sync are not added again.
+#[poise::command(slash_command, rename = "sync-anilist", ephemeral)]
+pub async fn sync_anilist(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let result = ctx
+        .data()
+        .service
+        .anilist_sync
+        .sync(ctx.author().id.get())
+        .await?;
+
+    send_text(ctx, format_sync_result(&result)).await
+}
+
+/// Unlink your AniList account
+///
+/// Stops syncing your AniList lists. Your subscriptions are kept.
+#[poise::command(slash_command, rename = "unlink-anilist", ephemeral)]
+pub async fn unlink_anilist(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let unlinked = ctx
+        .data()
+        .service
+        .anilist_sync
+        .unlink(ctx.author().id.get())
+        .await?;
+
+    let text = if unlinked {
+        "### AniList Unlinked\nYour lists are no longer synced. Your subscriptions were kept."
+    } else {
+        "### AniList Unlinked\nNo AniList account was linked."
+    };
+    send_text(ctx, text.to_string()).await
+}
+
+async fn send_text(ctx: Context<'_>, text: String) -> Result<(), Error> {
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+    Ok(())
+}
+
+fn format_sync_result(result: &AniListSyncResult) -> String {
+    let mut text = String::from("### AniList Synced");
+    if result.subscribed.is_empty() {
+        text.push_str("\nNo new anime or manga to subscribe to.");
+    } else {
+        text.push_str(&format!(
+            "\nSubscribed to **{}** titles:",
+            result.subscribed.len()
+        ));
+        for title in result.subscribed.iter().take(TITLES_SHOWN) {
+            text.push_str(&format!("\n- {title}"));
+        }
+        if result.subscribed.len() > TITLES_SHOWN {
+            text.push_str(&format!(
+                "\n-# …and {} more",
+                result.subscribed.len() - TITLES_SHOWN
+            ));
+        }
+    }
+
+    let notes = [
+        (result.unsubscribed, "unsubscribed, no longer on your list"),
+        (result.already_subscribed, "already subscribed"),
+        (result.finished, "skipped, no longer releasing"),
+        (result.ignored, "skipped, you unsubscribed after a sync"),
+        (result.over_limit, "skipped, subscription limit reached"),
+        (result.failed, "failed, try again later"),
+        (result.unmatched, "skipped, manga not found on MangaDex"),
+    ];
+    for (count, note) in notes {
+        if count > 0 {
+            text.push_str(&format!("\n-# {count} {note}"));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_sync_result_lists_titles_and_nonzero_counts() {
+        let result = AniListSyncResult {
+            subscribed: (0..TITLES_SHOWN + 2)
+                .map(|i| format!("Anime {i}"))
+                .collect(),
+            finished: 3,
+            ..Default::default()
+        };
+
+        let text = format_sync_result(&result);
+
+        assert_eq!(text.matches("\n- ").count(), TITLES_SHOWN);
+        assert!(text.contains("…and 2 more"));
+        assert!(text.contains("3 skipped, no longer releasing"));
+        assert!(!text.contains("already subscribed"));
+    }
+}
@@ -0,0 +1,159 @@
+//! Feed collection subcommands.
+
+use crate::bot::command::prelude::*;
+use crate::service::error::ServiceError;
+
+/// Group the server's subscriptions into collections
+///
+/// Each collection posts its feeds in its own channel and can mention a role.
+/// Pick a collection with the `collection` option of `/feed subscribe`.
+/// Requires server administrator permissions.
+#[poise::command(
+    slash_command,
+    subcommands("set", "remove", "list"),
+    default_member_permissions = "ADMINISTRATOR | MANAGE_GUILD"
+)]
+pub async fn collection(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Create a collection, or change its channel and role
+#[poise::command(slash_command, ephemeral)]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "Name of the collection, e.g. Manga"]
+    #[autocomplete = "autocomplete_collections"]
+    name: String,
+    #[description = "Channel the collection's feeds are posted in"]
+    #[channel_types("Text", "News")]
+    channel: GuildChannel,
+    #[description = "Role mentioned in each notification"] mention_role: Option<Role>,
+) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    let collection = ctx
+        .data()
+        .service
+        .feed_collections
+        .set_collection(
+            guild_id,
+            &name,
+            channel.id.get(),
+            mention_role.as_ref().map(|role| role.id.get()),
+        )
+        .await?;
+
+    let mention = match collection.mention_role_id {
+        Some(role_id) => format!(", mentioning <@&{role_id}>"),
+        None => String::new(),
+    };
+    send_status(
+        ctx,
+        format!(
+            "### Collection Saved\nFeeds in **{}** are posted in <#{}>{mention}.",
+            collection.name, collection.channel_id
+        ),
+    )
+    .await
+}
+
+/// Delete a collection
+///
+/// Its subscriptions are kept and posted in the server's feed channel again.
+#[poise::command(slash_command, ephemeral)]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "Name of the collection"]
+    #[autocomplete = "autocomplete_collections"]
+    name: String,
+) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    let name = name.trim().to_string();
+    if !ctx
+        .data()
+        .service
+        .feed_collections
+        .delete_collection(guild_id, &name)
+        .await?
+    {
+        return Err(ServiceError::CollectionNotFound { name }.into());
+    }
+
+    send_status(
+        ctx,
+        format!(
+            "### Collection Removed\n**{name}** has been deleted. Its feeds are posted in the server's feed channel again."
+        ),
+    )
+    .await
+}
+
+/// List the server's collections
+#[poise::command(slash_command, ephemeral)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    let collections = ctx
+        .data()
+        .service
+        .feed_collections
+        .list_collections(guild_id)
+        .await?;
+
+    let mut status_text = "### Feed Collections".to_string();
+    if collections.is_empty() {
+        status_text.push_str("\n> 🛈  No collections yet. Create one with `/feed collection set`.");
+    }
+    for collection in collections {
+        status_text.push_str(&format!(
+            "\n- **{}**: <#{}>",
+            collection.name, collection.channel_id
+        ));
+        if let Some(role_id) = collection.mention_role_id {
+            status_text.push_str(&format!(", mentions <@&{role_id}>"));
+        }
+    }
+    send_status(ctx, status_text).await
+}
+
+/// Autocompletes collection names of the current server.
+pub async fn autocomplete_collections<'a>(
+    ctx: Context<'_>,
+    partial: &str,
+) -> CreateAutocompleteResponse<'a> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return CreateAutocompleteResponse::new();
+    };
+
+    let collections = ctx
+        .data()
+        .service
+        .feed_collections
+        .list_collections(guild_id.get())
+        .await
+        .unwrap_or_default();
+
+    let partial = partial.to_lowercase();
+    let choices: Vec<AutocompleteChoice> = collections
+        .into_iter()
+        .filter(|collection| collection.name.to_lowercase().contains(&partial))
+        .map(|collection| AutocompleteChoice::new(collection.name.clone(), collection.name))
+        .collect();
+
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
+
+/// Sends an ephemeral status message to the admin managing collections.
+async fn send_status(ctx: Context<'_>, status_text: String) -> Result<(), Error> {
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(CreateReply::from(response).ephemeral(true))
+        .await?;
+    Ok(())
+}
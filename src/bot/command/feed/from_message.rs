@@ -0,0 +1,154 @@
+//! Subscribe from a message context-menu command.
+
+use std::time::Duration;
+
+use crate::bot::command::feed::SendInto;
+use crate::bot::command::prelude::*;
+
+/// Subscribe to the feed links in a message
+///
+/// Finds the links of supported feeds in the message and asks where to send their
+/// notifications before subscribing, as `/feed subscribe` would.
+#[poise::command(context_menu_command = "Subscribe to Feeds")]
+pub async fn subscribe_from_message(ctx: Context<'_>, message: Message) -> Result<(), Error> {
+    let urls = ctx.data().platforms.find_source_urls(&message.content);
+    if urls.is_empty() {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "message".to_string(),
+            reason: "This message has no links of supported feeds".to_string(),
+        }
+        .into());
+    }
+
+    Router::new(ctx)
+        .run(Navigation::FeedSubscribeFromMessage { urls })
+        .await?;
+    Ok(())
+}
+
+handler! { pub struct FeedSubscribeFromMessageHandler<'a> {
+    urls: Vec<String>,
+} }
+
+#[async_trait::async_trait]
+impl CommandHandler for FeedSubscribeFromMessageHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        let view = FeedSubscribeConfirmView {
+            urls: std::mem::take(&mut self.urls),
+            in_guild: ctx.guild_id().is_some(),
+            cancelled: false,
+        };
+        ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone())
+            .run()
+            .await
+    }
+}
+
+action_enum! { FeedSubscribeConfirmAction {
+    #[label = "Subscribe in DM"]
+    Dm,
+    #[label = "Subscribe in Server"]
+    Server,
+    #[label = "Cancel"]
+    Cancel,
+} }
+
+/// Lists the feeds found in a message with where to subscribe to them.
+pub struct FeedSubscribeConfirmView {
+    urls: Vec<String>,
+    /// Whether the command was used in a server, offering server subscriptions.
+    in_guild: bool,
+    cancelled: bool,
+}
+
+impl FeedSubscribeConfirmView {
+    /// Feeds subscribed to, at most [`MAX_URLS_PER_REQUEST`].
+    fn subscribed_urls(&self) -> &[String] {
+        &self.urls[..self.urls.len().min(MAX_URLS_PER_REQUEST)]
+    }
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for FeedSubscribeConfirmView {
+    type Action = FeedSubscribeConfirmAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, FeedSubscribeConfirmAction>,
+    ) -> Result<ViewCmd, Error> {
+        use FeedSubscribeConfirmAction as Action;
+        let send_into = match ctx.action() {
+            Action::Dm => SendInto::DM,
+            Action::Server => SendInto::Server,
+            Action::Cancel => {
+                self.cancelled = true;
+                return Ok(ViewCmd::RenderOnce);
+            }
+        };
+        ctx.coordinator
+            .navigate(Navigation::FeedSubscribe {
+                links: self.subscribed_urls().join(", "),
+                send_into: Some(send_into),
+                filter: None,
+                collection: None,
+                platform: None,
+            })
+            .await;
+        Ok(ViewCmd::Exit)
+    }
+}
+
+impl ViewRender for FeedSubscribeConfirmView {
+    type Action = FeedSubscribeConfirmAction;
+    fn render(
+        &self,
+        registry: &mut ActionRegistry<FeedSubscribeConfirmAction>,
+    ) -> ResponseKind<'_> {
+        if self.cancelled {
+            return vec![CreateComponent::TextDisplay(CreateTextDisplay::new(
+                "Cancelled, no feeds were subscribed to.",
+            ))]
+            .into();
+        }
+
+        let mut text = "### Subscribe to Feeds\n".to_string();
+        for url in self.subscribed_urls() {
+            text.push_str(&format!("- <{url}>\n"));
+        }
+        if self.urls.len() > MAX_URLS_PER_REQUEST {
+            text.push_str(&format!(
+                "-# Only the first {MAX_URLS_PER_REQUEST} of {} links are subscribed to",
+                self.urls.len()
+            ));
+        }
+
+        let mut buttons = vec![
+            registry
+                .register(FeedSubscribeConfirmAction::Dm)
+                .as_button()
+                .style(ButtonStyle::Primary),
+        ];
+        if self.in_guild {
+            buttons.push(
+                registry
+                    .register(FeedSubscribeConfirmAction::Server)
+                    .as_button()
+                    .style(ButtonStyle::Primary),
+            );
+        }
+        buttons.push(
+            registry
+                .register(FeedSubscribeConfirmAction::Cancel)
+                .as_button()
+                .style(ButtonStyle::Secondary),
+        );
+
+        vec![
+            CreateComponent::Container(CreateContainer::new(vec![
+                CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+            ])),
+            CreateComponent::ActionRow(CreateActionRow::Buttons(buttons.into())),
+        ]
+        .into()
+    }
+}
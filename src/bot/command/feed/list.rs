@@ -2,9 +2,14 @@
 use std::time::Duration;
 
 use crate::bot::command::feed::SendInto;
+use crate::bot::command::feed::check_added_by;
 use crate::bot::command::feed::get_or_create_subscriber;
+use crate::bot::command::feed::unsubscribe_restriction;
+use crate::bot::command::feed::verify_server_config;
 use crate::bot::command::prelude::*;
+use crate::entity::FeedCollectionEntity;
 use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberType;
 use crate::service::feed_subscription::Subscription;
 use crate::service::traits::FeedSubscriptionProvider;
 use crate::update::Update;
@@ -17,9 +22,18 @@ use crate::update::feed_list::FeedListViewState;
 /// Number of items per page for subscriptions list.
 pub(crate) const SUBSCRIPTIONS_PER_PAGE: u32 = 10;
 
-/// List your current feed subscriptions
+/// Most collections offered in the collection select, after the feed channel option.
+const MAX_COLLECTION_OPTIONS: usize = 24;
+
+/// Value of the collection select option for the server's feed channel.
+const FEED_CHANNEL_OPTION: &str = "none";
+
+/// List and manage your current feed subscriptions
 ///
-/// View all feeds you are subscribed to, with pagination support.
+/// View all feeds you are subscribed to, with pagination support. Edit mode
+/// lets you select feeds to unsubscribe, pause, resume, or move between your
+/// DM and the server, and change their settings, e.g. the server collection they
+/// are posted in.
 #[poise::command(slash_command)]
 pub async fn list(
     ctx: Context<'_>,
@@ -47,6 +61,16 @@ impl CommandHandler for FeedListHandler<'_> {
         let subscriber = get_or_create_subscriber(ctx, &self.send_into).await?;
 
         let service = ctx.data().service.feed_subscription.clone();
+        let collections = match (ctx.guild_id(), &subscriber.r#type) {
+            (Some(guild_id), SubscriberType::Guild) => {
+                ctx.data()
+                    .service
+                    .feed_collections
+                    .list_collections(guild_id.get())
+                    .await?
+            }
+            _ => Vec::new(),
+        };
 
         let subscriptions = service
             .list_paginated_subscriptions(&subscriber, 1u32, SUBSCRIPTIONS_PER_PAGE)
@@ -57,6 +81,7 @@ impl CommandHandler for FeedListHandler<'_> {
             model: FeedListModel::new(SUBSCRIPTIONS_PER_PAGE),
             service: service.clone(),
             subscriber: subscriber.clone(),
+            collections,
         };
 
         let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
@@ -72,6 +97,8 @@ pub struct FeedListView {
     pub model: FeedListModel,
     pub service: std::sync::Arc<dyn FeedSubscriptionProvider>,
     pub subscriber: SubscriberEntity,
+    /// The server's collections, offered for server subscriptions. Empty for DMs.
+    pub collections: Vec<FeedCollectionEntity>,
 }
 
 impl FeedListView {
@@ -84,6 +111,22 @@ impl FeedListView {
         ]))]
     }
 
+    /// Returns where the subscriptions of this list are currently sent.
+    fn send_into(&self) -> SendInto {
+        match self.subscriber.r#type {
+            SubscriberType::Guild => SendInto::Server,
+            SubscriberType::Dm => SendInto::DM,
+        }
+    }
+
+    /// Returns the send target that "Move" would move subscriptions into.
+    fn move_target(&self) -> SendInto {
+        match self.send_into() {
+            SendInto::Server => SendInto::DM,
+            SendInto::DM => SendInto::Server,
+        }
+    }
+
     /// Creates a section component for a single subscription.
     fn create_subscription_section<'b>(
         &self,
@@ -91,18 +134,42 @@ impl FeedListView {
         sub: Subscription,
     ) -> CreateContainerComponent<'b> {
         use FeedListAction::*;
+        let paused = if sub.paused {
+            "\n- ⏸ **Paused**: no notifications are sent"
+        } else {
+            ""
+        };
+        let paused = if sub.notify_edits {
+            format!("{paused}\n- ✏️ **Edits**: notified when a version is edited")
+        } else {
+            paused.to_string()
+        };
+        let paused = match sub
+            .collection_id
+            .and_then(|id| self.collections.iter().find(|c| c.id == id))
+        {
+            Some(collection) => format!("{paused}\n- 📁 **Collection**: {}", collection.name),
+            None => paused,
+        };
+        let paused = match (&sub.added_by, self.send_into()) {
+            (Some(added_by), SendInto::Server) => {
+                format!("{paused}\n- 👤 **Added by**: <@{added_by}>")
+            }
+            _ => paused,
+        };
         let text = if let Some(latest) = sub.feed_latest {
             format!(
-                "### {}\n\n- **Last version**: {}\n- **Last updated**: <t:{}>\n- [**Source** 🗗](<{}>)",
+                "### {}\n\n- **Last version**: {}\n- **Last updated**: <t:{}>\n- [**Source** 🗗](<{}>){}",
                 sub.feed.name,
                 latest.description,
                 latest.published.timestamp(),
-                sub.feed.source_url
+                sub.feed.source_url,
+                paused
             )
         } else {
             format!(
-                "### {}\n\n> No latest version found.\n- [**Source** 🗗](<{}>)",
-                sub.feed.name, sub.feed.source_url
+                "### {}\n\n> No latest version found.\n- [**Source** 🗗](<{}>){}",
+                sub.feed.name, sub.feed.source_url, paused
             )
         };
 
@@ -114,16 +181,16 @@ impl FeedListView {
             )),
             FeedListViewState::Edit => {
                 let source_url = sub.feed.source_url;
-                let button = if self.model.marked_unsub.contains(&source_url) {
+                let button = if self.model.selected.contains(&source_url) {
                     registry
-                        .register(UndoUnsub { source_url })
+                        .register(Deselect { source_url })
                         .as_button()
-                        .style(ButtonStyle::Secondary)
+                        .style(ButtonStyle::Primary)
                 } else {
                     registry
-                        .register(Unsubscribe { source_url })
+                        .register(Select { source_url })
                         .as_button()
-                        .style(ButtonStyle::Danger)
+                        .style(ButtonStyle::Secondary)
                 };
                 CreateSectionAccessory::Button(button)
             }
@@ -133,31 +200,111 @@ impl FeedListView {
     }
 
     /// Create button section of the view at the bottom.
-    fn create_toggle_button<'b>(
+    fn create_action_buttons<'b>(
         &self,
         registry: &mut ActionRegistry<FeedListAction>,
-    ) -> CreateComponent<'b> {
-        let action = match self.model.state {
-            FeedListViewState::Edit => FeedListAction::View,
-            FeedListViewState::View => FeedListAction::Edit,
-        };
+    ) -> Vec<CreateComponent<'b>> {
+        if self.model.state == FeedListViewState::View {
+            let edit_button = registry
+                .register(FeedListAction::Edit)
+                .as_button()
+                .style(ButtonStyle::Primary);
+            return vec![CreateComponent::ActionRow(CreateActionRow::Buttons(
+                vec![edit_button].into(),
+            ))];
+        }
 
-        let state_button = registry
-            .register(action.clone())
+        let no_selection = self.model.selected.is_empty();
+        let view_button = registry
+            .register(FeedListAction::View)
             .as_button()
             .style(ButtonStyle::Primary);
-        let mut save_button = registry
-            .register(FeedListAction::Save)
+        let unsub_button = registry
+            .register(FeedListAction::Unsubscribe)
+            .as_button()
+            .style(ButtonStyle::Danger)
+            .disabled(no_selection);
+        let pause_button = registry
+            .register(FeedListAction::Pause)
             .as_button()
-            .style(ButtonStyle::Success);
+            .style(ButtonStyle::Secondary)
+            .disabled(no_selection);
+        let resume_button = registry
+            .register(FeedListAction::Resume)
+            .as_button()
+            .style(ButtonStyle::Secondary)
+            .disabled(no_selection);
+        let move_button = registry
+            .register(FeedListAction::Move)
+            .as_button()
+            .label(format!("⇄ Move to {}", self.move_target().name()))
+            .style(ButtonStyle::Secondary)
+            .disabled(no_selection);
 
-        if self.model.marked_unsub.is_empty() {
-            save_button = save_button.disabled(true)
+        let notify_edits_button = registry
+            .register(FeedListAction::NotifyEdits)
+            .as_button()
+            .style(ButtonStyle::Secondary)
+            .disabled(no_selection);
+        let mute_edits_button = registry
+            .register(FeedListAction::MuteEdits)
+            .as_button()
+            .style(ButtonStyle::Secondary)
+            .disabled(no_selection);
+
+        let mut second_row = vec![notify_edits_button, mute_edits_button];
+        // Only a single feed can be opened at a time
+        if self.model.selected.len() == 1
+            && let Some(url) = self.model.selected.iter().next()
+        {
+            second_row.push(CreateButton::new_link(url.clone()).label("🗗 Open Source"));
         }
 
-        let buttons = vec![state_button, save_button];
+        let mut rows = vec![
+            CreateComponent::ActionRow(CreateActionRow::Buttons(
+                vec![
+                    view_button,
+                    unsub_button,
+                    pause_button,
+                    resume_button,
+                    move_button,
+                ]
+                .into(),
+            )),
+            CreateComponent::ActionRow(CreateActionRow::Buttons(second_row.into())),
+        ];
+        if !self.collections.is_empty() {
+            rows.push(self.create_collection_select(registry, no_selection));
+        }
+        rows
+    }
 
-        CreateComponent::ActionRow(CreateActionRow::Buttons(buttons.into()))
+    /// Creates the select that puts the selected server subscriptions in a collection.
+    fn create_collection_select<'b>(
+        &self,
+        registry: &mut ActionRegistry<FeedListAction>,
+        no_selection: bool,
+    ) -> CreateComponent<'b> {
+        let mut options = vec![CreateSelectMenuOption::new(
+            "Server feed channel",
+            FEED_CHANNEL_OPTION,
+        )];
+        options.extend(
+            self.collections
+                .iter()
+                .take(MAX_COLLECTION_OPTIONS)
+                .map(|collection| {
+                    CreateSelectMenuOption::new(collection.name.clone(), collection.id.to_string())
+                }),
+        );
+        let select = registry
+            .register(FeedListAction::Collection)
+            .as_select(CreateSelectMenuKind::String {
+                options: options.into(),
+            })
+            .placeholder("📁 Post selected feeds in...")
+            .disabled(no_selection);
+        CreateComponent::ActionRow(CreateActionRow::SelectMenu(select))
     }
 
     async fn update_subs(&mut self) -> Result<(), Error> {
@@ -172,6 +319,41 @@ impl FeedListView {
         self.subscriptions = subs;
         Ok(())
     }
+
+    /// Fails unless the author may remove every one of these subscriptions.
+    async fn check_unsubscribe(
+        &self,
+        ctx: Context<'_>,
+        source_urls: &[String],
+    ) -> Result<(), Error> {
+        if let Some(member) = unsubscribe_restriction(ctx, &self.subscriber).await? {
+            for source_url in source_urls {
+                check_added_by(ctx, &self.subscriber, source_url, member).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves subscriptions to the other send target after checking permissions.
+    async fn move_subscriptions(
+        &self,
+        ctx: Context<'_>,
+        source_urls: impl IntoIterator<Item = String>,
+    ) -> Result<(), Error> {
+        let target = self.move_target();
+        // Moving into the server subscribes it there; moving out unsubscribes it
+        verify_server_config(ctx, &SendInto::Server, target == SendInto::Server).await?;
+        let source_urls: Vec<String> = source_urls.into_iter().collect();
+        self.check_unsubscribe(ctx, &source_urls).await?;
+        let target_subscriber = get_or_create_subscriber(ctx, &target).await?;
+
+        for source_url in source_urls {
+            self.service
+                .move_subscription(&source_url, &self.subscriber, &target_subscriber)
+                .await?;
+        }
+        Ok(())
+    }
 }
 
 impl ViewRender for FeedListView {
@@ -196,7 +378,7 @@ impl ViewRender for FeedListView {
         pagination.state.current_page = self.model.current_page;
         pagination.disabled = self.model.pagination_disabled;
         pagination.attach_if_multipage(registry, &mut components, FeedListAction::Base);
-        components.push(self.create_toggle_button(registry));
+        components.extend(self.create_action_buttons(registry));
 
         components.into()
     }
@@ -207,11 +389,23 @@ action_extends! { FeedListAction extends PaginationAction {
     Edit,
     #[label = "👁 View Mode"]
     View,
+    #[label = "☐ Select"]
+    Select { source_url: String },
+    #[label = "☑ Selected"]
+    Deselect { source_url: String },
     #[label = "🗑 Unsubscribe"]
-    Unsubscribe { source_url: String },
-    #[label = "↶ Undo"]
-    UndoUnsub { source_url: String },
-    Save,
+    Unsubscribe,
+    #[label = "⏸ Pause"]
+    Pause,
+    #[label = "▶ Resume"]
+    Resume,
+    #[label = "⇄ Move"]
+    Move,
+    #[label = "✏️ Notify Edits"]
+    NotifyEdits,
+    #[label = "✏️ Mute Edits"]
+    MuteEdits,
+    Collection,
     Exit,
 }}
 
@@ -220,51 +414,80 @@ impl ViewHandler for FeedListView {
     type Action = FeedListAction;
     async fn handle(&mut self, ctx: ViewContext<'_, FeedListAction>) -> Result<ViewCmd, Error> {
         use FeedListAction::*;
-        match ctx.action() {
-            Base(inner) => {
-                FeedListUpdate::update(FeedListMsg::Pagination(*inner), &mut self.model);
+        let msg = match ctx.action() {
+            Base(inner) => FeedListMsg::Pagination(*inner),
+            Edit => FeedListMsg::Edit,
+            View => FeedListMsg::View,
+            Select { source_url } | Deselect { source_url } => FeedListMsg::ToggleSelect {
+                source_url: source_url.clone(),
+            },
+            Unsubscribe => FeedListMsg::Unsubscribe,
+            Pause => FeedListMsg::Pause,
+            Resume => FeedListMsg::Resume,
+            Move => FeedListMsg::Move,
+            NotifyEdits => FeedListMsg::NotifyEdits,
+            MuteEdits => FeedListMsg::MuteEdits,
+            Collection => FeedListMsg::SetCollection(
+                ctx.string_select_values()
+                    .and_then(|v| v.first().and_then(|id| id.parse::<i32>().ok())),
+            ),
+            Exit => return Ok(ViewCmd::Continue),
+        };
+
+        match FeedListUpdate::update(msg, &mut self.model) {
+            FeedListCmd::None => {}
+            FeedListCmd::Unsubscribe(urls) => {
+                let urls: Vec<String> = urls.into_iter().collect();
+                self.check_unsubscribe(ctx.poise, &urls).await?;
+                for url in urls {
+                    self.service.unsubscribe(&url, &self.subscriber).await?;
+                }
                 self.update_subs().await?;
-                return Ok(ViewCmd::Render);
             }
-            Edit => {
-                FeedListUpdate::update(FeedListMsg::Edit, &mut self.model);
+            FeedListCmd::SetPaused {
+                source_urls,
+                paused,
+            } => {
+                for url in source_urls {
+                    self.service
+                        .set_subscription_paused(&url, &self.subscriber, paused)
+                        .await?;
+                }
+                self.update_subs().await?;
             }
-            View => {
-                FeedListUpdate::update(FeedListMsg::View, &mut self.model);
+            FeedListCmd::SetNotifyEdits {
+                source_urls,
+                notify_edits,
+            } => {
+                for url in source_urls {
+                    self.service
+                        .set_subscription_notify_edits(&url, &self.subscriber, notify_edits)
+                        .await?;
+                }
+                self.update_subs().await?;
             }
-            Unsubscribe { source_url } => {
-                FeedListUpdate::update(
-                    FeedListMsg::ToggleUnsub {
-                        source_url: source_url.clone(),
-                    },
-                    &mut self.model,
-                );
+            FeedListCmd::SetCollection {
+                source_urls,
+                collection_id,
+            } => {
+                let urls: Vec<String> = source_urls.into_iter().collect();
+                // Changes where the feeds are posted, so it takes the same permission
+                self.check_unsubscribe(ctx.poise, &urls).await?;
+                for url in urls {
+                    self.service
+                        .set_subscription_collection(&url, &self.subscriber, collection_id)
+                        .await?;
+                }
+                self.update_subs().await?;
             }
-            UndoUnsub { source_url } => {
-                FeedListUpdate::update(
-                    FeedListMsg::ToggleUnsub {
-                        source_url: source_url.clone(),
-                    },
-                    &mut self.model,
-                );
+            FeedListCmd::Move(urls) => {
+                self.move_subscriptions(ctx.poise, urls).await?;
+                self.update_subs().await?;
             }
-            Exit => return Ok(ViewCmd::Continue),
-            Save => {
-                let cmd = FeedListUpdate::update(FeedListMsg::Save, &mut self.model);
-                match cmd {
-                    FeedListCmd::SaveUnsubscribes(urls) => {
-                        for sub in urls {
-                            self.service.unsubscribe(&sub, &self.subscriber).await?;
-                        }
-                        self.update_subs().await?;
-                    }
-                    FeedListCmd::RefetchSubscriptions => {
-                        self.update_subs().await?;
-                    }
-                    FeedListCmd::None => {}
-                }
+            FeedListCmd::RefetchSubscriptions => {
+                self.update_subs().await?;
             }
-        };
+        }
 
         Ok(ViewCmd::Render)
     }
@@ -0,0 +1,186 @@
+//! MyAnimeList account linking and list import subcommands.
+
+use crate::bot::command::prelude::*;
+use crate::feed::mal::MalListKind;
+use crate::service::mal_import::MalImportResult;
+
+/// Titles listed after an import before the rest are summarized.
+const TITLES_SHOWN: usize = 10;
+
+/// MyAnimeList lists an import reads.
+#[derive(ChoiceParameter, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MalLists {
+    #[default]
+    #[name = "Watching and reading"]
+    Both,
+    #[name = "Watching (anime)"]
+    Anime,
+    #[name = "Reading (manga)"]
+    Manga,
+}
+
+impl MalLists {
+    fn kinds(&self) -> &'static [MalListKind] {
+        match self {
+            Self::Both => &[MalListKind::Anime, MalListKind::Manga],
+            Self::Anime => &[MalListKind::Anime],
+            Self::Manga => &[MalListKind::Manga],
+        }
+    }
+}
+
+/// Link your MyAnimeList account
+///
+/// Sends a link where you authorize the bot to read your MyAnimeList lists. The link
+/// expires after 10 minutes. Once linked, import your lists with `/feed import-mal`.
+#[poise::command(slash_command, rename = "link-mal", ephemeral)]
+pub async fn link_mal(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let url = ctx
+        .data()
+        .service
+        .mal_import
+        .start_link(ctx.author().id.get())?;
+
+    let text = format!(
+        "### Link MyAnimeList\n[Authorize pwr-bot on MyAnimeList](<{url}>), then run `/feed import-mal`.\n-# The link expires in 10 minutes"
+    );
+    send_text(ctx, text).await
+}
+
+/// Subscribe to the titles on your MyAnimeList lists
+///
+/// Subscribes you in DMs to the anime you are watching and the manga you are reading on
+/// your linked MyAnimeList account. Anime are followed on AniList and manga on MangaDex,
+/// matched by title. Finished titles are skipped. Run with `preview` to see the matches
+/// first.
+#[poise::command(slash_command, rename = "import-mal", ephemeral)]
+pub async fn import_mal(
+    ctx: Context<'_>,
+    #[description = "Lists to import. Defaults to both"] lists: Option<MalLists>,
+    #[description = "Only show what would be subscribed to"] preview: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let preview = preview.unwrap_or(false);
+    let result = ctx
+        .data()
+        .service
+        .mal_import
+        .import(
+            ctx.author().id.get(),
+            lists.unwrap_or_default().kinds(),
+            preview,
+        )
+        .await?;
+
+    send_text(ctx, format_import_result(&result, preview)).await
+}
+
+/// Unlink your MyAnimeList account
+///
+/// Removes the bot's access to your MyAnimeList lists. Your subscriptions are kept.
+#[poise::command(slash_command, rename = "unlink-mal", ephemeral)]
+pub async fn unlink_mal(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let unlinked = ctx
+        .data()
+        .service
+        .mal_import
+        .unlink(ctx.author().id.get())
+        .await?;
+
+    let text = if unlinked {
+        "### MyAnimeList Unlinked\nThe bot can no longer read your lists. Your subscriptions were kept."
+    } else {
+        "### MyAnimeList Unlinked\nNo MyAnimeList account was linked."
+    };
+    send_text(ctx, text.to_string()).await
+}
+
+async fn send_text(ctx: Context<'_>, text: String) -> Result<(), Error> {
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+    Ok(())
+}
+
+fn format_import_result(result: &MalImportResult, preview: bool) -> String {
+    let mut text = String::from(if preview {
+        "### MyAnimeList Import Preview"
+    } else {
+        "### MyAnimeList Imported"
+    });
+    if result.subscribed.is_empty() {
+        text.push_str("\nNo new titles to subscribe to.");
+    } else {
+        text.push_str(&format!(
+            "\n{} **{}** titles:",
+            if preview {
+                "Would subscribe to"
+            } else {
+                "Subscribed to"
+            },
+            result.subscribed.len()
+        ));
+        push_titles(&mut text, &result.subscribed);
+    }
+    if !result.unmatched.is_empty() {
+        text.push_str(&format!(
+            "\nNo feed found for **{}** titles:",
+            result.unmatched.len()
+        ));
+        push_titles(&mut text, &result.unmatched);
+    }
+
+    let notes = [
+        (result.already_subscribed, "already subscribed"),
+        (result.finished, "skipped, finished"),
+        (result.over_limit, "skipped, subscription limit reached"),
+        (result.failed, "failed, try again later"),
+    ];
+    for (count, note) in notes {
+        if count > 0 {
+            text.push_str(&format!("\n-# {count} {note}"));
+        }
+    }
+    if preview {
+        text.push_str("\n-# Nothing was subscribed to. Run again without `preview` to import");
+    }
+    text
+}
+
+fn push_titles(text: &mut String, titles: &[String]) {
+    for title in titles.iter().take(TITLES_SHOWN) {
+        text.push_str(&format!("\n- {title}"));
+    }
+    if titles.len() > TITLES_SHOWN {
+        text.push_str(&format!("\n-# …and {} more", titles.len() - TITLES_SHOWN));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_import_result_lists_unmatched_titles_in_preview() {
+        let result = MalImportResult {
+            subscribed: vec!["Frieren".to_string()],
+            unmatched: (0..TITLES_SHOWN + 1)
+                .map(|i| format!("Manga {i}"))
+                .collect(),
+            finished: 2,
+            ..Default::default()
+        };
+
+        let text = format_import_result(&result, true);
+
+        assert!(text.contains("Would subscribe to **1** titles"));
+        assert!(text.contains("No feed found for **11** titles"));
+        assert!(text.contains("…and 1 more"));
+        assert!(text.contains("2 skipped, finished"));
+        assert!(text.contains("Run again without `preview`"));
+    }
+}
@@ -4,16 +4,27 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
+use log::warn;
+
 use crate::bot::checks::check_author_roles;
 use crate::bot::command::prelude::*;
+use crate::bot::send_queue::SendTarget;
 use crate::entity::SubscriberEntity;
 use crate::entity::SubscriberType;
 use crate::service::feed_subscription::SubscribeResult;
 use crate::service::feed_subscription::SubscriberTarget;
 use crate::service::feed_subscription::UnsubscribeResult;
 
+pub mod anilist;
+pub mod collection;
+pub mod from_message;
 pub mod list;
+pub mod mal;
+pub mod preferences;
+pub mod replay;
+pub mod requests;
 pub mod settings;
+pub mod stats;
 pub mod subscribe;
 pub mod unsubscribe;
 
@@ -23,14 +34,30 @@ pub mod unsubscribe;
 /// - Subscribe to feeds
 /// - Unsubscribe from feeds
 /// - View your subscriptions
-/// - Configure server feed settings (admin only)
+/// - Replay recent notifications you missed
+/// - View stats of your notifications
+/// - Review feeds members requested for the server (admin only)
+/// - Group the server's feeds into collections with their own channel (admin only)
+/// - Sync your AniList watching list
+/// - Import your MyAnimeList lists
+/// - Configure server feed settings (admin only) or your DM preferences
 #[poise::command(
     slash_command,
     subcommands(
         "settings::settings",
         "subscribe::subscribe",
         "unsubscribe::unsubscribe",
-        "list::list"
+        "list::list",
+        "replay::replay",
+        "stats::stats",
+        "requests::requests",
+        "collection::collection",
+        "anilist::link_anilist",
+        "anilist::sync_anilist",
+        "anilist::unlink_anilist",
+        "mal::link_mal",
+        "mal::import_mal",
+        "mal::unlink_mal"
     )
 )]
 pub async fn feed(_ctx: Context<'_>) -> Result<(), Error> {
@@ -117,29 +144,64 @@ impl From<UnsubscribeResult> for String {
 }
 
 /// Processes a batch of subscription/unsubscription operations.
+///
+/// New and existing subscriptions are put in `collection_id`, if any. A user's first
+/// DM subscription also sends them the onboarding message.
 async fn process_subscription_batch(
     coordinator: Arc<Router<'_>>,
     urls: &[&str],
     subscriber: &SubscriberEntity,
     is_subscribe: bool,
+    collection_id: Option<i32>,
 ) -> Result<(), Error> {
     let mut states: Vec<String> = vec!["⏳ Processing...".to_string(); urls.len()];
     let mut last_send = Instant::now();
     let mut handler: Option<FeedSubscriptionBatchHandler> = None;
     let ctx = coordinator.context();
     let service = ctx.data().service.feed_subscription.clone();
+    let added_by = (subscriber.r#type == SubscriberType::Guild).then(|| ctx.author().id.get());
+    let restriction = if is_subscribe {
+        None
+    } else {
+        unsubscribe_restriction(*ctx, subscriber).await?
+    };
+    let mut subscribed_any = false;
 
     for (i, url) in urls.iter().enumerate() {
-        let result_str = if is_subscribe {
-            service
-                .subscribe(url, subscriber)
-                .await
-                .map(|res| res.into())
+        let result_str: Result<String, Error> = if is_subscribe {
+            match service.subscribe_added_by(url, subscriber, added_by).await {
+                Ok(res) => {
+                    subscribed_any |= matches!(res, SubscribeResult::Success { .. });
+                    let (SubscribeResult::Success { feed }
+                    | SubscribeResult::AlreadySubscribed { feed }) = &res;
+                    match collection_id {
+                        Some(collection_id) => service
+                            .set_subscription_collection(
+                                &feed.source_url,
+                                subscriber,
+                                Some(collection_id),
+                            )
+                            .await
+                            .map(|_| res.into())
+                            .map_err(Into::into),
+                        None => Ok(res.into()),
+                    }
+                }
+                Err(e) => Err(e.into()),
+            }
         } else {
-            service
-                .unsubscribe(url, subscriber)
-                .await
-                .map(|res| res.into())
+            let allowed = match restriction {
+                Some(member) => check_added_by(*ctx, subscriber, url, member).await,
+                None => Ok(()),
+            };
+            match allowed {
+                Ok(()) => service
+                    .unsubscribe(url, subscriber)
+                    .await
+                    .map(|res| res.into())
+                    .map_err(Into::into),
+                Err(e) => Err(e),
+            }
         };
 
         states[i] = result_str.unwrap_or_else(|e| format!("❌ {e}"));
@@ -170,6 +232,13 @@ async fn process_subscription_batch(
         }
     }
 
+    if subscribed_any
+        && subscriber.r#type == SubscriberType::Dm
+        && service.take_first_seen(subscriber).await?
+    {
+        send_onboarding(*ctx).await;
+    }
+
     // Listen for "View Subscriptions" button click after final message
     if let Some(handler) = handler {
         let mut engine =
@@ -180,6 +249,35 @@ async fn process_subscription_batch(
     Ok(())
 }
 
+/// DMs a user what to expect after their first DM subscription. Failures are only
+/// logged, e.g. when the user does not accept DMs.
+async fn send_onboarding(ctx: Context<'_>) {
+    let interval = format_duration(ctx.data().config.poll_interval.as_secs() as i64);
+    let content = format!(
+        "### Welcome to feed notifications!
+You just subscribed to your first feed. A few things worth knowing:
+- **Timing**: active feeds are checked about every {interval}, quiet ones less often. Each new chapter, episode or release is sent here as its own message once it is found.
+- **Preferences**: `/feed preferences` picks what each platform notifies you of, and can skip updates a server you are in already receives.
+- **Pausing**: `/feed list` pauses a feed without unsubscribing, and turns notifications for edited items on or off.
+- **Unsubscribing**: `/feed unsubscribe` removes a feed.
+- **Missed something?** `/feed replay` sends a feed's recent updates again.
+-# This message is only sent once."
+    );
+    let user_id = ctx.author().id;
+    let message = CreateMessage::new().content(content);
+    let result = ctx
+        .data()
+        .send_queue
+        .send(SendTarget::Dm(user_id.get()), move |http| async move {
+            user_id.dm(&http, message).await?;
+            Ok(())
+        })
+        .await;
+    if let Err(e) = result {
+        warn!("Failed to send the onboarding message to {user_id}: {e:?}");
+    }
+}
+
 /// Verifies server configuration is valid for the operation.
 async fn verify_server_config(
     ctx: Context<'_>,
@@ -197,7 +295,7 @@ async fn verify_server_config(
 
         if settings.feeds.channel_id.is_none() {
             return Err(BotError::ConfigurationError(
-                "Server feed settings are not configured. A server admin must run `/settings` to configure a notification channel first.".to_string(),
+                "Server feed settings are not configured. A server admin must run `/settings open` to configure a notification channel first.".to_string(),
             ).into());
         }
 
@@ -236,6 +334,58 @@ fn get_target_id(
     }
 }
 
+/// Returns the author if the server only lets them remove subscriptions they added,
+/// or `None` if they can remove any of `subscriber`'s subscriptions.
+async fn unsubscribe_restriction(
+    ctx: Context<'_>,
+    subscriber: &SubscriberEntity,
+) -> Result<Option<UserId>, Error> {
+    if subscriber.r#type != SubscriberType::Guild {
+        return Ok(None);
+    }
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+    let settings = ctx
+        .data()
+        .service
+        .feed_subscription
+        .get_server_settings(guild_id.get())
+        .await?;
+    if !settings.feeds.is_unsubscribe_own_only() || is_author_guild_admin(ctx).await.is_ok() {
+        return Ok(None);
+    }
+    Ok(Some(ctx.author().id))
+}
+
+/// Fails unless `member` added the subscription to `source_url`. Passes if there is no
+/// such subscription, so unsubscribing reports it as not subscribed.
+async fn check_added_by(
+    ctx: Context<'_>,
+    subscriber: &SubscriberEntity,
+    source_url: &str,
+    member: UserId,
+) -> Result<(), Error> {
+    let Some(subscription) = ctx
+        .data()
+        .service
+        .feed_subscription
+        .get_subscription(source_url, subscriber)
+        .await?
+    else {
+        return Ok(());
+    };
+    match subscription.added_by {
+        Some(added_by) if added_by == member.to_string() => Ok(()),
+        Some(added_by) => Err(BotError::PermissionDenied(format!(
+            "<@{added_by}> added <{source_url}>. Only they or members with \"Manage Server\" can remove it."
+        ))
+        .into()),
+        None => Err(BotError::PermissionDenied(format!(
+            "Only members with \"Manage Server\" can remove <{source_url}>, since it is not known who added it."
+        ))
+        .into()),
+    }
+}
+
 /// Gets or creates a subscriber for the current context.
 async fn get_or_create_subscriber(
     ctx: Context<'_>,
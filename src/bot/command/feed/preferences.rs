@@ -0,0 +1,325 @@
+//! Notification preferences view for DM subscribers.
+
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::entity::PlatformPreferences;
+use crate::entity::SubscriberPreferences;
+use crate::entity::SubscriberType;
+use crate::entity::TitleLanguage;
+use crate::service::feed_subscription::SubscriberTarget;
+use crate::update::Staged;
+
+/// Most options a Discord select menu holds.
+const MAX_SELECT_OPTIONS: usize = 25;
+
+#[derive(Debug, Modal, Clone, PartialEq, Eq)]
+#[name = "Excluded Words"]
+pub struct ExcludedWordsModal {
+    #[name = "Words, separated by commas"]
+    #[placeholder = "trailer, PV, recap"]
+    #[max_length = 200]
+    words: Option<String>,
+}
+
+handler! { pub struct FeedPreferencesHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for FeedPreferencesHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        ctx.defer().await?;
+
+        let target = SubscriberTarget {
+            subscriber_type: SubscriberType::Dm,
+            target_id: ctx.author().id.to_string(),
+        };
+        let subscriber = ctx
+            .data()
+            .service
+            .feed_subscription
+            .get_or_create_subscriber(&target)
+            .await?;
+
+        let platforms: Vec<String> = ctx
+            .data()
+            .platforms
+            .get_all_platforms()
+            .iter()
+            .take(MAX_SELECT_OPTIONS)
+            .map(|platform| platform.get_id().to_string())
+            .collect();
+
+        let view = FeedPreferencesView {
+            selected: platforms.first().cloned(),
+            platforms,
+            preferences: Staged::new(subscriber.preferences.0),
+            target,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        // Unsaved changes are discarded when the view exits
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+action_enum! {
+    FeedPreferencesAction {
+        Platform,
+        ToggleMuted,
+        ToggleServerDuplicates,
+        Titles,
+        #[label = "Exclude Words"]
+        SetExcludedWords(Option<ExcludedWordsModal>),
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
+    }
+}
+
+pub struct FeedPreferencesView {
+    /// IDs of the platforms that can be configured.
+    pub platforms: Vec<String>,
+    /// The platform being edited.
+    pub selected: Option<String>,
+    pub preferences: Staged<SubscriberPreferences>,
+    pub target: SubscriberTarget,
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for FeedPreferencesView {
+    type Action = FeedPreferencesAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, FeedPreferencesAction>,
+    ) -> Result<ViewCmd, Error> {
+        use FeedPreferencesAction::*;
+
+        match ctx.action() {
+            Platform => {
+                let platform = ctx.string_select_values().and_then(|v| v.first().cloned());
+                if platform.is_some() {
+                    self.selected = platform;
+                }
+            }
+            ToggleMuted => {
+                self.edit_selected(|prefs| prefs.muted = !prefs.muted);
+            }
+            ToggleServerDuplicates => {
+                self.preferences.skip_server_duplicates = !self.preferences.skip_server_duplicates;
+            }
+            Titles => {
+                let language = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|name| TitleLanguage::from_name(name)));
+                if let Some(language) = language {
+                    self.preferences.title_language = language;
+                }
+            }
+            SetExcludedWords(None) => {
+                ctx.spawn_modal_component(|m| SetExcludedWords(Some(m)))
+                    .await;
+                return Ok(ViewCmd::AlreadyResponded);
+            }
+            SetExcludedWords(Some(modal)) => {
+                let input = modal.words.as_deref().unwrap_or_default();
+                let words = PlatformPreferences::parse_words(input);
+                self.edit_selected(|prefs| prefs.excluded_words = words);
+            }
+            Save => {
+                let service = &ctx.poise.data().service.feed_subscription;
+                // Keep preferences saved from elsewhere since this view opened
+                let latest = service.get_or_create_subscriber(&self.target).await?;
+                self.preferences.rebase(latest.preferences.0)?;
+                service
+                    .set_subscriber_preferences(&self.target, self.preferences.current().clone())
+                    .await?;
+                self.preferences.save();
+            }
+            Revert => {
+                self.preferences.revert();
+            }
+        }
+
+        Ok(ViewCmd::Render)
+    }
+}
+
+impl FeedPreferencesView {
+    /// Applies `edit` to the preferences of the selected platform without persisting.
+    fn edit_selected(&mut self, edit: impl FnOnce(&mut PlatformPreferences)) {
+        let Some(platform) = &self.selected else {
+            return;
+        };
+        let mut prefs = self.preferences.platform(platform);
+        edit(&mut prefs);
+        self.preferences.set_platform(platform, prefs);
+    }
+}
+
+impl ViewRender for FeedPreferencesView {
+    type Action = FeedPreferencesAction;
+    fn render(&self, registry: &mut ActionRegistry<FeedPreferencesAction>) -> ResponseKind<'_> {
+        let is_dirty = self.preferences.is_dirty();
+
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!(
+                "-# **Feeds > Preferences**{}\n## Notification Preferences\n\n> 🛈  Choose what you are notified of in your DMs, per platform.",
+                unsaved_marker(is_dirty)
+            )),
+        )];
+
+        let skip_duplicates = self.preferences.skip_server_duplicates;
+        sections.push(CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+            format!(
+                "### Server Duplicates\n\n> 🛈  {}",
+                if skip_duplicates {
+                    "Updates a server you are in already receives are **not** sent to your DMs."
+                } else {
+                    "Updates are sent to your DMs even if a server you are in already receives them."
+                }
+            ),
+        )));
+        sections.push(CreateContainerComponent::ActionRow(
+            CreateActionRow::Buttons(
+                vec![
+                    registry
+                        .register(FeedPreferencesAction::ToggleServerDuplicates)
+                        .as_button()
+                        .label(if skip_duplicates {
+                            "Send Duplicates"
+                        } else {
+                            "Skip Duplicates"
+                        })
+                        .style(ButtonStyle::Secondary),
+                ]
+                .into(),
+            ),
+        ));
+
+        let title_language = self.preferences.title_language;
+        let titles_text = match title_language {
+            TitleLanguage::Default => {
+                "Feeds are shown by the title their platform lists first.".to_string()
+            }
+            language => format!(
+                "Feeds are shown by their **{}** title where the platform lists one, e.g. on MangaDex and AniList.",
+                language.name().to_lowercase()
+            ),
+        };
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!("### Titles\n\n> 🛈  {titles_text}")),
+        ));
+        let title_options: Vec<_> = TitleLanguage::ALL
+            .iter()
+            .map(|language| {
+                CreateSelectMenuOption::new(language.name(), language.name())
+                    .default_selection(*language == title_language)
+            })
+            .collect();
+        sections.push(CreateContainerComponent::ActionRow(
+            CreateActionRow::SelectMenu(
+                registry
+                    .register(FeedPreferencesAction::Titles)
+                    .as_select(CreateSelectMenuKind::String {
+                        options: title_options.into(),
+                    })
+                    .placeholder("Select a title language"),
+            ),
+        ));
+
+        let platform_options: Vec<_> = self
+            .platforms
+            .iter()
+            .map(|id| {
+                let label = if self.preferences.platforms.contains_key(id) {
+                    format!("{id} ✎")
+                } else {
+                    id.clone()
+                };
+                CreateSelectMenuOption::new(label, id.as_str())
+                    .default_selection(self.selected.as_ref() == Some(id))
+            })
+            .collect();
+        sections.push(CreateContainerComponent::ActionRow(
+            CreateActionRow::SelectMenu(
+                registry
+                    .register(FeedPreferencesAction::Platform)
+                    .as_select(CreateSelectMenuKind::String {
+                        options: platform_options.into(),
+                    })
+                    .placeholder("Select a platform"),
+            ),
+        ));
+
+        if let Some(platform) = &self.selected {
+            let prefs = self.preferences.platform(platform);
+            let words = if prefs.excluded_words.is_empty() {
+                "None".to_string()
+            } else {
+                prefs
+                    .excluded_words
+                    .iter()
+                    .map(|word| format!("`{word}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let platform_text = format!(
+                "### {platform}\n\n> 🛈  {}\n-# Excluded words: {words}",
+                if prefs.muted {
+                    "Notifications from this platform are **muted**."
+                } else {
+                    "You are notified of new items, except those whose title contains an excluded word."
+                }
+            );
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(platform_text),
+            ));
+            sections.push(CreateContainerComponent::ActionRow(
+                CreateActionRow::Buttons(
+                    vec![
+                        registry
+                            .register(FeedPreferencesAction::ToggleMuted)
+                            .as_button()
+                            .label(if prefs.muted { "Unmute" } else { "Mute" })
+                            .style(if prefs.muted {
+                                ButtonStyle::Success
+                            } else {
+                                ButtonStyle::Danger
+                            }),
+                        registry
+                            .register(FeedPreferencesAction::SetExcludedWords(None))
+                            .as_button()
+                            .style(ButtonStyle::Secondary)
+                            .disabled(prefs.muted),
+                    ]
+                    .into(),
+                ),
+            ));
+        }
+
+        let container = CreateComponent::Container(CreateContainer::new(sections));
+
+        let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
+            vec![
+                registry
+                    .register(FeedPreferencesAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(FeedPreferencesAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
+            ]
+            .into(),
+        ));
+
+        vec![container, nav_buttons].into()
+    }
+}
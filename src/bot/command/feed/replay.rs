@@ -0,0 +1,224 @@
+//! Feed replay subcommand.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::TimeDelta;
+use chrono::Utc;
+use log::warn;
+
+use crate::bot::command::feed::SendInto;
+use crate::bot::command::feed::get_or_create_subscriber;
+use crate::bot::command::feed::unsubscribe::autocomplete_subscriptions;
+use crate::bot::command::feed::verify_server_config;
+use crate::bot::command::prelude::*;
+use crate::bot::send_queue::SendTarget;
+use crate::entity::SubscriberEntity;
+use crate::event::FeedUpdateData;
+use crate::event::FeedUpdateKind;
+use crate::event::MessageOptions;
+
+/// Items sent again per replay, oldest first.
+const MAX_REPLAYED: usize = 10;
+/// How far back items are replayed without `since`.
+const DEFAULT_SINCE_DAYS: i64 = 7;
+
+/// Send a feed's recent notifications again
+///
+/// Sends the stored updates of a feed you are subscribed to that were published
+/// after `since`, only to you or your server's feed channel. Useful after you
+/// allowed DMs again or fixed the channel's permissions.
+#[poise::command(slash_command, ephemeral)]
+pub async fn replay(
+    ctx: Context<'_>,
+    #[description = "Link of the feed"]
+    #[autocomplete = "autocomplete_subscriptions"]
+    link: String,
+    #[description = "Date (YYYY-MM-DD), RFC 3339 time, or age like 12h or 3d. Defaults to 7d"]
+    since: Option<String>,
+    #[description = "Where notifications are sent. Default to DM"] send_into: Option<SendInto>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let data = ctx.data();
+    let send_into = send_into.unwrap_or(SendInto::DM);
+
+    let now = Utc::now();
+    let since = match since.as_deref() {
+        Some(since) => parse_since(since, now).ok_or_else(|| BotError::InvalidCommandArgument {
+            parameter: "since".to_string(),
+            reason: "Use a date like 2026-01-31, an RFC 3339 time, or an age like 12h or 3d"
+                .to_string(),
+        })?,
+        None => now - TimeDelta::days(DEFAULT_SINCE_DAYS),
+    };
+
+    verify_server_config(ctx, &send_into, true).await?;
+    let subscriber = get_or_create_subscriber(ctx, &send_into).await?;
+
+    let Some(replay) = data
+        .service
+        .feed_subscription
+        .get_replay_items(link.trim(), &subscriber, since, MAX_REPLAYED)
+        .await?
+    else {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "link".to_string(),
+            reason: format!(
+                "You are not subscribed to this feed in {}",
+                send_into.name()
+            ),
+        }
+        .into());
+    };
+
+    let feed = Arc::new(replay.feed);
+    let feed_info = Arc::new(replay.feed_info);
+    let total = replay.items.len();
+    let mut sent = 0;
+    for item in replay.items {
+        let update = FeedUpdateData {
+            feed: feed.clone(),
+            feed_info: feed_info.clone(),
+            old_feed_item: None,
+            new_feed_item: Arc::new(item),
+            kind: FeedUpdateKind::New,
+        };
+        if let Err(e) = send_update(ctx, &send_into, &subscriber, &update).await {
+            warn!(
+                "Failed to replay an item of feed `{}` to {}: {e:?}",
+                feed.id, subscriber.target_id
+            );
+            break;
+        }
+        sent += 1;
+    }
+
+    let text = if total == 0 {
+        format!(
+            "### Nothing to Replay\n[{}](<{}>) has no stored updates since <t:{}:f>.",
+            feed.name,
+            feed.source_url,
+            since.timestamp()
+        )
+    } else if sent == total {
+        format!(
+            "### Replayed\nSent **{sent}** update(s) of [{}](<{}>) since <t:{}:f> to {}.",
+            feed.name,
+            feed.source_url,
+            since.timestamp(),
+            send_into.name()
+        )
+    } else {
+        format!(
+            "### Replay Failed\nSent **{sent}** of {total} update(s) of [{}](<{}>) to {}. Check that the bot can message you there, then try again.",
+            feed.name,
+            feed.source_url,
+            send_into.name()
+        )
+    };
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+    Ok(())
+}
+
+/// Sends one update to the invoking user's DMs or the server's feed channel.
+async fn send_update(
+    ctx: Context<'_>,
+    send_into: &SendInto,
+    subscriber: &SubscriberEntity,
+    update: &FeedUpdateData,
+) -> Result<(), Error> {
+    let data = ctx.data();
+    match send_into {
+        SendInto::DM => {
+            let user_id = ctx.author().id;
+            let message = update.create_message_with(MessageOptions {
+                title_language: subscriber.preferences.0.title_language,
+                ..Default::default()
+            });
+            data.send_queue
+                .send(SendTarget::Dm(user_id.get()), move |http| async move {
+                    user_id.dm(&http, message).await?;
+                    Ok(())
+                })
+                .await?;
+        }
+        SendInto::Server => {
+            let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+            let settings = data
+                .service
+                .feed_subscription
+                .get_server_settings(guild_id.get())
+                .await?;
+            let Some(channel_id) = settings.feeds.channel_id.as_deref() else {
+                return Err(BotError::ConfigurationError(
+                    "Server feed settings are not configured.".to_string(),
+                )
+                .into());
+            };
+            let channel_id = ChannelId::from_str(channel_id)?;
+            let message = update.create_message_with(MessageOptions::from(&settings.feeds));
+            data.send_queue
+                .send(
+                    SendTarget::Channel(channel_id.get()),
+                    move |http| async move {
+                        let channel = channel_id.to_guild_channel(&http, Some(guild_id)).await?;
+                        channel.send_message(&http, message).await?;
+                        Ok(())
+                    },
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Parses a `YYYY-MM-DD` date (midnight UTC), an RFC 3339 time, or an age like `12h`
+/// or `3d` before `now`.
+fn parse_since(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+    let (amount, unit) = input.split_at(input.char_indices().last()?.0);
+    let amount: i64 = amount.trim().parse().ok().filter(|n| *n >= 0)?;
+    let age = match unit.to_ascii_lowercase().as_str() {
+        "h" => TimeDelta::try_hours(amount)?,
+        "d" => TimeDelta::try_days(amount)?,
+        "w" => TimeDelta::try_weeks(amount)?,
+        _ => return None,
+    };
+    now.checked_sub_signed(age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_since_accepts_dates_times_and_ages() {
+        let now = Utc::now();
+
+        assert_eq!(
+            parse_since("2026-01-31", now),
+            Some("2026-01-31T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            parse_since("2026-01-31T12:00:00+02:00", now),
+            Some("2026-01-31T10:00:00Z".parse().unwrap())
+        );
+        assert_eq!(parse_since("12h", now), Some(now - TimeDelta::hours(12)));
+        assert_eq!(parse_since(" 3D ", now), Some(now - TimeDelta::days(3)));
+        assert_eq!(parse_since("yesterday", now), None);
+        assert_eq!(parse_since("-1d", now), None);
+        assert_eq!(parse_since("", now), None);
+    }
+}
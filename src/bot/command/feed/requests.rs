@@ -0,0 +1,298 @@
+//! Feed subscription requests subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::warn;
+
+use crate::bot::Data;
+use crate::bot::command::feed::SendInto;
+use crate::bot::command::prelude::*;
+use crate::bot::send_queue::SendTarget;
+use crate::bot::view::pagination::PaginationModel;
+use crate::entity::PendingSubscriptionEntity;
+use crate::service::feed_subscription::SubscribeResult;
+use crate::service::subscription_request::RequestResult;
+use crate::service::traits::SubscriptionRequestProvider;
+
+const REQUESTS_PER_PAGE: u32 = 5;
+
+/// Review feeds members asked this server to subscribe to
+///
+/// When the server allows requests, members without the subscribe role request
+/// feeds with `/feed subscribe` instead of adding them. Approve a request to
+/// subscribe the server, or deny it. The member is told in their DM either way.
+#[poise::command(slash_command)]
+pub async fn requests(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::FeedRequests).await?;
+    Ok(())
+}
+
+/// Whether the author's server subscriptions wait for an admin: the server allows
+/// requests and the author has neither "Manage Server" nor the subscribe role, if set.
+pub(super) async fn needs_approval(ctx: Context<'_>, send_into: &SendInto) -> Result<bool, Error> {
+    if *send_into != SendInto::Server {
+        return Ok(false);
+    }
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+    let settings = ctx
+        .data()
+        .service
+        .feed_subscription
+        .get_server_settings(guild_id.get())
+        .await?;
+    if !settings.feeds.is_approval_required() || is_author_guild_admin(ctx).await.is_ok() {
+        return Ok(false);
+    }
+    Ok(match settings.feeds.subscribe_role_id.as_deref() {
+        Some(id) => check_author_roles(ctx, vec![RoleId::new(id.parse()?)])
+            .await
+            .is_err(),
+        None => true,
+    })
+}
+
+/// Requests server subscriptions to `urls` for an admin to review.
+pub(super) async fn request_subscriptions(ctx: Context<'_>, urls: &[&str]) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+    let service = ctx.data().service.subscription_requests.clone();
+    let author_id = ctx.author().id.get();
+
+    let mut lines = vec![
+        "### Subscription Requests\n> 🛈  This server's admins review new feeds. You will get a DM once they decide.".to_string(),
+    ];
+    for url in urls {
+        let line = match service.request(guild_id, url, author_id).await {
+            Ok(RequestResult::Requested { .. }) => format!("⏳ **Requested** <{url}>"),
+            Ok(RequestResult::AlreadyRequested { request }) => format!(
+                "⏳ <@{}> **already requested** <{url}>",
+                request.requested_by
+            ),
+            Ok(RequestResult::AlreadySubscribed) => {
+                format!("❌ This server is **already subscribed** to <{url}>")
+            }
+            Err(e) => format!("❌ {e}"),
+        };
+        lines.push(line);
+    }
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(lines.join("\n"))),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+    Ok(())
+}
+
+handler! { pub struct FeedRequestsHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for FeedRequestsHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        is_author_guild_admin(ctx).await?;
+        ctx.defer().await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let service = ctx.data().service.subscription_requests.clone();
+        let requests = service.list_requests(guild_id).await?;
+
+        let view = FeedRequestsView {
+            pagination: PaginationView::new(requests.len() as u32, REQUESTS_PER_PAGE),
+            requests,
+            service,
+            guild_id,
+            notice: None,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct FeedRequestsView {
+    pub requests: Vec<PendingSubscriptionEntity>,
+    pub pagination: PaginationView,
+    pub service: Arc<dyn SubscriptionRequestProvider>,
+    pub guild_id: u64,
+    /// Outcome of the last review action, shown above the list.
+    pub notice: Option<String>,
+}
+
+impl FeedRequestsView {
+    fn format_request(request: &PendingSubscriptionEntity) -> String {
+        format!(
+            "<{}>\n-# Requested by <@{}> <t:{}:R>",
+            request.source_url,
+            request.requested_by,
+            request.requested_at.timestamp()
+        )
+    }
+
+    /// The requests on the current page.
+    fn page(&self) -> &[PendingSubscriptionEntity] {
+        let per_page = REQUESTS_PER_PAGE as usize;
+        let start = (self.pagination.current_page() as usize - 1) * per_page;
+        let start = start.min(self.requests.len());
+        let end = (start + per_page).min(self.requests.len());
+        &self.requests[start..end]
+    }
+
+    /// Reloads the requests, staying on the current page when it still exists.
+    async fn refresh(&mut self) -> Result<(), Error> {
+        self.requests = self.service.list_requests(self.guild_id).await?;
+        let pages = (self.requests.len() as u32).div_ceil(REQUESTS_PER_PAGE);
+        self.pagination.state =
+            PaginationModel::new(pages, REQUESTS_PER_PAGE, self.pagination.current_page());
+        Ok(())
+    }
+}
+
+action_extends! { FeedRequestsAction extends PaginationAction {
+    #[label = "✓ Approve"]
+    Approve { request_id: i32 },
+    #[label = "✕ Deny"]
+    Deny { request_id: i32 },
+}}
+
+#[async_trait::async_trait]
+impl ViewHandler for FeedRequestsView {
+    type Action = FeedRequestsAction;
+    async fn handle(&mut self, ctx: ViewContext<'_, FeedRequestsAction>) -> Result<ViewCmd, Error> {
+        let guild_name = ctx
+            .poise
+            .guild()
+            .map(|guild| guild.name.to_string())
+            .unwrap_or_else(|| "the server".to_string());
+        match ctx.action() {
+            FeedRequestsAction::Base(inner) => {
+                match inner {
+                    PaginationAction::First => self.pagination.state.first_page(),
+                    PaginationAction::Prev => self.pagination.state.prev_page(),
+                    PaginationAction::Next => self.pagination.state.next_page(),
+                    PaginationAction::Last => self.pagination.state.last_page(),
+                    PaginationAction::Page => return Ok(ViewCmd::Continue),
+                }
+                self.notice = None;
+            }
+            FeedRequestsAction::Approve { request_id } => {
+                self.notice = Some(
+                    match self.service.approve(self.guild_id, *request_id).await {
+                        Ok(Some((request, result))) => {
+                            let (SubscribeResult::Success { feed }
+                            | SubscribeResult::AlreadySubscribed { feed }) = result;
+                            let text = format!(
+                                "✅ Your request to subscribe **{guild_name}** to [{}](<{}>) was **approved**.",
+                                feed.name, feed.source_url
+                            );
+                            notify_requester(ctx.poise.data(), &request, text).await;
+                            format!(
+                                "✓ Subscribed to [{}](<{}>), requested by <@{}>.",
+                                feed.name, feed.source_url, request.requested_by
+                            )
+                        }
+                        Ok(None) => "This request was already reviewed.".to_string(),
+                        Err(e) => format!("❌ {e}"),
+                    },
+                );
+            }
+            FeedRequestsAction::Deny { request_id } => {
+                self.notice = Some(match self.service.deny(self.guild_id, *request_id).await? {
+                    Some(request) => {
+                        let text = format!(
+                            "❌ Your request to subscribe **{guild_name}** to <{}> was **denied**.",
+                            request.source_url
+                        );
+                        notify_requester(ctx.poise.data(), &request, text).await;
+                        format!(
+                            "✕ Denied <{}>, requested by <@{}>.",
+                            request.source_url, request.requested_by
+                        )
+                    }
+                    None => "This request was already reviewed.".to_string(),
+                });
+            }
+        }
+        self.refresh().await?;
+        Ok(ViewCmd::Render)
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.pagination.disabled = true;
+        Ok(ViewCmd::RenderOnce)
+    }
+}
+
+impl ViewRender for FeedRequestsView {
+    type Action = FeedRequestsAction;
+    fn render(&self, registry: &mut ActionRegistry<FeedRequestsAction>) -> ResponseKind<'_> {
+        let mut header = "## Subscription Requests".to_string();
+        if let Some(notice) = &self.notice {
+            header.push_str(&format!("\n> {notice}"));
+        }
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(header),
+        )];
+
+        if self.requests.is_empty() {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new("> 🛈  No feeds are waiting for review."),
+            ));
+        }
+        for request in self.page() {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(Self::format_request(request)),
+            ));
+            if !self.pagination.disabled {
+                sections.push(CreateContainerComponent::ActionRow(
+                    CreateActionRow::Buttons(
+                        vec![
+                            registry
+                                .register(FeedRequestsAction::Approve {
+                                    request_id: request.id,
+                                })
+                                .as_button()
+                                .style(ButtonStyle::Success),
+                            registry
+                                .register(FeedRequestsAction::Deny {
+                                    request_id: request.id,
+                                })
+                                .as_button()
+                                .style(ButtonStyle::Danger),
+                        ]
+                        .into(),
+                    ),
+                ));
+            }
+        }
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+        self.pagination
+            .attach_if_multipage(registry, &mut components, FeedRequestsAction::Base);
+
+        components.into()
+    }
+}
+
+/// Tells the member who made a request how it was decided. Failures are only logged,
+/// e.g. when the member does not accept DMs.
+async fn notify_requester(data: &Data, request: &PendingSubscriptionEntity, text: String) {
+    let user_id = UserId::new(request.requested_by.into());
+    let message = CreateMessage::new().content(text);
+    let result = data
+        .send_queue
+        .send(SendTarget::Dm(user_id.get()), move |http| async move {
+            user_id.dm(&http, message).await?;
+            Ok(())
+        })
+        .await;
+    if let Err(e) = result {
+        warn!(
+            "Failed to notify {} about subscription request {}: {e:?}",
+            user_id, request.id
+        );
+    }
+}
@@ -4,22 +4,46 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use crate::bot::command::prelude::*;
+use crate::bot::command::settings::open_staged_settings;
+use crate::bot::command::settings::save_staged_settings;
+use crate::entity::CalendarMode;
+use crate::entity::NotificationStyle;
 use crate::entity::ServerSettings;
+use crate::update::Staged;
 use crate::update::Update;
 use crate::update::feed_settings::FeedSettingsModel;
 use crate::update::feed_settings::FeedSettingsMsg;
 use crate::update::feed_settings::FeedSettingsUpdate;
 
+/// Watch party delays offered in the settings view, in minutes. `None` turns the button off.
+const WATCH_PARTY_OPTIONS: [(Option<u32>, &str); 6] = [
+    (None, "Off"),
+    (Some(5), "In 5 minutes"),
+    (Some(15), "In 15 minutes"),
+    (Some(30), "In 30 minutes"),
+    (Some(60), "In 1 hour"),
+    (Some(120), "In 2 hours"),
+];
+
 /// Configure feed settings for this server
 ///
-/// Set up notification channels and required roles for feed subscriptions.
-/// Only server administrators can use this command.
+/// Set up notification channels and required roles for feed subscriptions,
+/// keep a pinned calendar of upcoming releases, let members schedule watch parties from
+/// episode notifications, keep server events for airing anime, and choose how long feed
+/// items are kept.
+/// Only server administrators can use this command. In DMs, choose which
+/// platforms' updates you are notified of instead.
 #[poise::command(
     slash_command,
     default_member_permissions = "ADMINISTRATOR | MANAGE_GUILD"
 )]
 pub async fn settings(ctx: Context<'_>) -> Result<(), Error> {
-    Router::new(ctx).run(Navigation::SettingsFeeds).await?;
+    let initial = if ctx.guild_id().is_some() {
+        Navigation::SettingsFeeds
+    } else {
+        Navigation::FeedPreferences
+    };
+    Router::new(ctx).run(initial).await?;
     Ok(())
 }
 
@@ -30,33 +54,22 @@ impl CommandHandler for FeedSettingsHandler<'_> {
     async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
         let ctx = *coordinator.context();
         ctx.defer().await?;
-        let service = ctx.data().service.feed_subscription.clone();
-
         let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
 
-        let mut settings = service.get_server_settings(guild_id).await?;
+        let settings = open_staged_settings(&ctx.data().service, guild_id).await?;
 
-        let feeds_settings = settings.feeds.clone();
         let view = SettingsFeedHandler {
-            model: FeedSettingsModel {
-                enabled: feeds_settings.enabled,
-                channel_id: feeds_settings.channel_id,
-                subscribe_role_id: feeds_settings.subscribe_role_id,
-                unsubscribe_role_id: feeds_settings.unsubscribe_role_id,
-            },
-            settings: &mut settings,
+            model: FeedSettingsModel::from(&settings.feeds),
+            settings,
+            guild_id,
+            default_retention_days: ctx.data().config.retention.feed_item_days,
         };
 
         let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
 
+        // Unsaved changes are discarded when the view exits
         engine.run().await?;
 
-        // Save settings after the view loop completes
-        service
-            .update_server_settings(guild_id, settings.clone())
-            .await
-            .ok();
-
         Ok(())
     }
 }
@@ -65,26 +78,42 @@ action_enum! { SettingsFeedAction {
     Enabled,
     Channel,
     SubRole,
+    RequireApproval,
     UnsubRole,
+    UnsubOwnOnly,
+    Style,
+    HideCover,
+    SuppressEmbeds,
+    Calendar,
+    WatchParty,
+    AiringEvents,
+    Retention,
+    #[label = "✓ Save"]
+    Save,
+    #[label = "↺ Revert"]
+    Revert,
     #[label = "❮ Back"]
     Back,
     #[label = "🛈 About"]
     About,
 } }
 
-pub struct SettingsFeedHandler<'a> {
+pub struct SettingsFeedHandler {
     pub model: FeedSettingsModel,
-    pub settings: &'a mut ServerSettings,
+    pub settings: Staged<ServerSettings>,
+    pub guild_id: u64,
+    /// The bot's feed item retention in days, which the server can only shorten.
+    pub default_retention_days: u32,
 }
 
 #[async_trait::async_trait]
-impl<'a> ViewHandler for SettingsFeedHandler<'a> {
+impl ViewHandler for SettingsFeedHandler {
     type Action = SettingsFeedAction;
     async fn handle(&mut self, ctx: ViewContext<'_, SettingsFeedAction>) -> Result<ViewCmd, Error> {
         match ctx.action() {
             SettingsFeedAction::Enabled => {
                 FeedSettingsUpdate::update(FeedSettingsMsg::ToggleEnabled, &mut self.model);
-                self.settings.feeds.enabled = self.model.enabled;
+                self.stage();
                 Ok(ViewCmd::Render)
             }
             SettingsFeedAction::Channel => {
@@ -95,7 +124,7 @@ impl<'a> ViewHandler for SettingsFeedHandler<'a> {
                     FeedSettingsMsg::SetChannel(channel_id),
                     &mut self.model,
                 );
-                self.settings.feeds.channel_id = self.model.channel_id.clone();
+                self.stage();
                 Ok(ViewCmd::Render)
             }
             SettingsFeedAction::SubRole => {
@@ -103,7 +132,7 @@ impl<'a> ViewHandler for SettingsFeedHandler<'a> {
                     .role_select_values()
                     .and_then(|v| v.first().map(|id| id.to_string()));
                 FeedSettingsUpdate::update(FeedSettingsMsg::SetSubRole(role_id), &mut self.model);
-                self.settings.feeds.subscribe_role_id = self.model.subscribe_role_id.clone();
+                self.stage();
                 Ok(ViewCmd::Render)
             }
             SettingsFeedAction::UnsubRole => {
@@ -111,7 +140,100 @@ impl<'a> ViewHandler for SettingsFeedHandler<'a> {
                     .role_select_values()
                     .and_then(|v| v.first().map(|id| id.to_string()));
                 FeedSettingsUpdate::update(FeedSettingsMsg::SetUnsubRole(role_id), &mut self.model);
-                self.settings.feeds.unsubscribe_role_id = self.model.unsubscribe_role_id.clone();
+                self.stage();
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::Style => {
+                let style = ctx.string_select_values().and_then(|v| {
+                    v.first()
+                        .and_then(|name| NotificationStyle::from_name(name))
+                });
+                if let Some(style) = style {
+                    FeedSettingsUpdate::update(FeedSettingsMsg::SetStyle(style), &mut self.model);
+                    self.stage();
+                }
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::HideCover => {
+                FeedSettingsUpdate::update(FeedSettingsMsg::ToggleHideCover, &mut self.model);
+                self.stage();
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::SuppressEmbeds => {
+                FeedSettingsUpdate::update(FeedSettingsMsg::ToggleSuppressEmbeds, &mut self.model);
+                self.stage();
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::Calendar => {
+                let mode = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|name| CalendarMode::from_name(name)));
+                if let Some(mode) = mode {
+                    FeedSettingsUpdate::update(
+                        FeedSettingsMsg::SetCalendarMode(mode),
+                        &mut self.model,
+                    );
+                    self.stage();
+                }
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::WatchParty => {
+                let delay = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().map(|mins| mins.parse::<u32>().ok()));
+                if let Some(delay) = delay {
+                    FeedSettingsUpdate::update(
+                        FeedSettingsMsg::SetWatchPartyDelay(delay),
+                        &mut self.model,
+                    );
+                    self.stage();
+                }
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::RequireApproval => {
+                FeedSettingsUpdate::update(FeedSettingsMsg::ToggleRequireApproval, &mut self.model);
+                self.stage();
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::UnsubOwnOnly => {
+                FeedSettingsUpdate::update(
+                    FeedSettingsMsg::ToggleUnsubscribeOwnOnly,
+                    &mut self.model,
+                );
+                self.stage();
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::AiringEvents => {
+                FeedSettingsUpdate::update(FeedSettingsMsg::ToggleAiringEvents, &mut self.model);
+                self.stage();
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::Retention => {
+                let days = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|days| days.parse::<u32>().ok()));
+                if let Some(days) = days {
+                    FeedSettingsUpdate::update(
+                        FeedSettingsMsg::SetItemRetention(Some(days)),
+                        &mut self.model,
+                    );
+                    self.stage();
+                }
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::Save => {
+                save_staged_settings(
+                    &ctx.poise.data().service,
+                    self.guild_id,
+                    ctx.poise.author().id.get(),
+                    &mut self.settings,
+                )
+                .await?;
+                Ok(ViewCmd::Render)
+            }
+            SettingsFeedAction::Revert => {
+                self.settings.revert();
+                self.model = FeedSettingsModel::from(&self.settings.feeds);
                 Ok(ViewCmd::Render)
             }
             SettingsFeedAction::Back => {
@@ -126,7 +248,30 @@ impl<'a> ViewHandler for SettingsFeedHandler<'a> {
     }
 }
 
-impl<'a> SettingsFeedHandler<'a> {
+impl SettingsFeedHandler {
+    /// Copies the model into the staged settings without persisting.
+    fn stage(&mut self) {
+        let channel_changed = self.model.channel_id != self.settings.saved().feeds.channel_id;
+        let feeds = &mut self.settings.feeds;
+        // A new channel gets a fresh chance; the next failure is reported again
+        if channel_changed {
+            feeds.delivery_failure = None;
+        }
+        feeds.enabled = self.model.enabled;
+        feeds.channel_id = self.model.channel_id.clone();
+        feeds.subscribe_role_id = self.model.subscribe_role_id.clone();
+        feeds.unsubscribe_role_id = self.model.unsubscribe_role_id.clone();
+        feeds.unsubscribe_own_only = self.model.unsubscribe_own_only;
+        feeds.require_approval = self.model.require_approval;
+        feeds.notification_style = self.model.notification_style;
+        feeds.hide_cover = self.model.hide_cover;
+        feeds.suppress_embeds = self.model.suppress_embeds;
+        feeds.item_retention_days = self.model.item_retention_days;
+        feeds.release_calendar = self.model.release_calendar;
+        feeds.watch_party_delay_mins = self.model.watch_party_delay_mins;
+        feeds.airing_events = self.model.airing_events;
+    }
+
     /// Parses a role ID string into a RoleId vector.
     fn parse_role_id(id: Option<&String>) -> Vec<RoleId> {
         id.and_then(|id| RoleId::from_str(id).ok())
@@ -142,13 +287,14 @@ impl<'a> SettingsFeedHandler<'a> {
     }
 }
 
-impl<'a> ViewRender for SettingsFeedHandler<'a> {
+impl ViewRender for SettingsFeedHandler {
     type Action = SettingsFeedAction;
     fn render(&self, registry: &mut ActionRegistry<SettingsFeedAction>) -> ResponseKind<'_> {
         let is_enabled = self.model.is_enabled();
 
         let status_text = format!(
-            "-# **Settings > Feeds**\n## Feed Subscription Settings\n\n> 🛈  {}",
+            "-# **Settings > Feeds**{}\n## Feed Subscription Settings\n\n> 🛈  {}{}",
+            unsaved_marker(self.settings.is_dirty()),
             if is_enabled {
                 match &self.model.channel_id {
                     Some(id) => format!("Feed notifications are currently **active**. Notifications will be sent to <#{id}>"),
@@ -156,6 +302,14 @@ impl<'a> ViewRender for SettingsFeedHandler<'a> {
                 }
             } else {
                 "Feed notifications are currently **paused**. No notifications will be sent until it is re-enabled.".to_string()
+            },
+            match &self.settings.feeds.delivery_failure {
+                Some(failure) => format!(
+                    "\n-# ⚠️ The last update could not be delivered <t:{}:R>. {}",
+                    failure.failed_at.timestamp(),
+                    failure.reason.advice()
+                ),
+                None => String::new(),
             }
         );
 
@@ -186,7 +340,14 @@ impl<'a> ViewRender for SettingsFeedHandler<'a> {
                 "⚠️ Required: Select a notification channel"
             });
 
-        let sub_role_text = "### Subscribe Permission\n\n> 🛈  Who can add new feeds to this server. Leave empty to allow users with \"Manage Server\" permission.";
+        let sub_role_text = format!(
+            "### Subscribe Permission\n\n> 🛈  Who can add new feeds to this server. Leave empty to allow users with \"Manage Server\" permission.\n-# {}",
+            if self.model.is_approval_required() {
+                "Other members can request feeds, which admins approve with `/feed requests`."
+            } else {
+                "Other members cannot add feeds."
+            }
+        );
         let sub_role_select = registry
             .register(SettingsFeedAction::SubRole)
             .as_select(CreateSelectMenuKind::Role {
@@ -201,7 +362,24 @@ impl<'a> ViewRender for SettingsFeedHandler<'a> {
                 "Optional: Select role for subscribe permission"
             });
 
-        let unsub_role_text = "### Unsubscribe Permission\n\n> 🛈  Who can remove feeds from this server. Leave empty to allow users with \"Manage Server\" permission.";
+        let require_approval_button = registry
+            .register(SettingsFeedAction::RequireApproval)
+            .as_button()
+            .label(if self.model.is_approval_required() {
+                "Disable Requests"
+            } else {
+                "Allow Requests"
+            })
+            .style(ButtonStyle::Secondary);
+
+        let unsub_role_text = format!(
+            "### Unsubscribe Permission\n\n> 🛈  Who can remove feeds from this server. Leave empty to allow users with \"Manage Server\" permission.\n-# {}",
+            if self.model.is_unsubscribe_own_only() {
+                "Members can only remove feeds they added. Members with \"Manage Server\" can remove any."
+            } else {
+                "Members can remove feeds anyone added."
+            }
+        );
         let unsub_role_select = registry
             .register(SettingsFeedAction::UnsubRole)
             .as_select(CreateSelectMenuKind::Role {
@@ -216,6 +394,134 @@ impl<'a> ViewRender for SettingsFeedHandler<'a> {
                 "Optional: Select role for unsubscribe permission"
             });
 
+        let unsub_own_only_button = registry
+            .register(SettingsFeedAction::UnsubOwnOnly)
+            .as_button()
+            .label(if self.model.is_unsubscribe_own_only() {
+                "Allow Removing Any Feed"
+            } else {
+                "Only Own Feeds"
+            })
+            .style(ButtonStyle::Secondary);
+
+        let style_text = "### Notification Style\n\n> 🛈  **Rich** posts a full card with cover art. **Compact** posts a single line, whose link previews can be suppressed.";
+        let style_options: Vec<_> = NotificationStyle::ALL
+            .iter()
+            .map(|style| {
+                CreateSelectMenuOption::new(style.name(), style.name())
+                    .default_selection(*style == self.model.style())
+            })
+            .collect();
+        let style_select = registry
+            .register(SettingsFeedAction::Style)
+            .as_select(CreateSelectMenuKind::String {
+                options: style_options.into(),
+            })
+            .placeholder("Select notification style");
+
+        let hide_cover_button = registry
+            .register(SettingsFeedAction::HideCover)
+            .as_button()
+            .label(if self.model.is_cover_hidden() {
+                "Show Covers"
+            } else {
+                "Hide Covers"
+            })
+            .style(ButtonStyle::Secondary);
+        let suppress_embeds_button = registry
+            .register(SettingsFeedAction::SuppressEmbeds)
+            .as_button()
+            .label(if self.model.is_embeds_suppressed() {
+                "Allow Embeds"
+            } else {
+                "Suppress Embeds"
+            })
+            .style(ButtonStyle::Secondary)
+            // Rich cards have no link previews
+            .disabled(self.model.style() == NotificationStyle::Rich);
+
+        let calendar_text = "### Release Calendar\n\n> 🛈  Keep a pinned message in the notification channel listing the releases expected this week, updated daily. **Calendar only** posts no notification for each new item.";
+        let calendar_options: Vec<_> = CalendarMode::ALL
+            .iter()
+            .map(|mode| {
+                CreateSelectMenuOption::new(mode.name(), mode.name())
+                    .default_selection(*mode == self.model.calendar_mode())
+            })
+            .collect();
+        let calendar_select = registry
+            .register(SettingsFeedAction::Calendar)
+            .as_select(CreateSelectMenuKind::String {
+                options: calendar_options.into(),
+            })
+            .placeholder("Select release calendar mode");
+
+        let watch_party_text = "### Watch Parties\n\n> 🛈  Add a **Schedule watch party** button to episode notifications. It creates a server event starting after the chosen delay. Members need the **Create Events** permission to use it.";
+        let watch_party_options: Vec<_> = WATCH_PARTY_OPTIONS
+            .iter()
+            .map(|(mins, name)| {
+                let value = mins.map_or("off".to_string(), |mins| mins.to_string());
+                CreateSelectMenuOption::new(*name, value)
+                    .default_selection(*mins == self.model.watch_party_delay_mins)
+            })
+            .collect();
+        let watch_party_select = registry
+            .register(SettingsFeedAction::WatchParty)
+            .as_select(CreateSelectMenuKind::String {
+                options: watch_party_options.into(),
+            })
+            .placeholder("Select when watch parties start");
+
+        let airing_events_text = "### Airing Events
+
+> 🛈  Keep a server event for the next episode of each AniList anime this server follows, created at its airing time and updated hourly. The bot needs the **Create Events** permission.";
+        let airing_events_button = registry
+            .register(SettingsFeedAction::AiringEvents)
+            .as_button()
+            .label(if self.model.has_airing_events() {
+                "Disable Airing Events"
+            } else {
+                "Enable Airing Events"
+            })
+            .style(if self.model.has_airing_events() {
+                ButtonStyle::Danger
+            } else {
+                ButtonStyle::Success
+            });
+
+        let retention_days = self
+            .settings
+            .feeds
+            .item_retention_days(self.default_retention_days);
+        let retention_text = format!(
+            "### Data Retention\n\n> 🛈  {}",
+            if retention_days == 0 {
+                "Feed items are kept **forever**.".to_string()
+            } else {
+                format!(
+                    "Feed items are deleted after **{}**, unless another subscriber of the feed keeps them longer. Each feed keeps its latest item.",
+                    format_retention_days(retention_days)
+                )
+            }
+        );
+        let retention_options: Vec<_> = RETENTION_OPTIONS
+            .iter()
+            .filter(|(days, _)| {
+                *days == 0
+                    || self.default_retention_days == 0
+                    || *days < self.default_retention_days
+            })
+            .map(|(days, name)| {
+                CreateSelectMenuOption::new(*name, days.to_string())
+                    .default_selection(*days == self.model.item_retention_days.unwrap_or(0))
+            })
+            .collect();
+        let retention_select = registry
+            .register(SettingsFeedAction::Retention)
+            .as_select(CreateSelectMenuKind::String {
+                options: retention_options.into(),
+            })
+            .placeholder("Select how long feed items are kept");
+
         let container = CreateComponent::Container(CreateContainer::new(vec![
             CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
             CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
@@ -225,10 +531,42 @@ impl<'a> ViewRender for SettingsFeedHandler<'a> {
             CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(channel_select)),
             CreateContainerComponent::TextDisplay(CreateTextDisplay::new(sub_role_text)),
             CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(sub_role_select)),
+            CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
+                vec![require_approval_button].into(),
+            )),
             CreateContainerComponent::TextDisplay(CreateTextDisplay::new(unsub_role_text)),
             CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(unsub_role_select)),
+            CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
+                vec![unsub_own_only_button].into(),
+            )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(style_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(style_select)),
+            CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
+                vec![hide_cover_button, suppress_embeds_button].into(),
+            )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(calendar_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(calendar_select)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(watch_party_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(watch_party_select)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(airing_events_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
+                vec![airing_events_button].into(),
+            )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(retention_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(retention_select)),
         ]));
 
+        let is_dirty = self.settings.is_dirty();
+        let save_button = registry
+            .register(SettingsFeedAction::Save)
+            .as_button()
+            .style(ButtonStyle::Success)
+            .disabled(!is_dirty);
+        let revert_button = registry
+            .register(SettingsFeedAction::Revert)
+            .as_button()
+            .style(ButtonStyle::Secondary)
+            .disabled(!is_dirty);
         let back_button = registry
             .register(SettingsFeedAction::Back)
             .as_button()
@@ -239,7 +577,7 @@ impl<'a> ViewRender for SettingsFeedHandler<'a> {
             .style(ButtonStyle::Secondary);
 
         let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
-            vec![back_button, about_button].into(),
+            vec![save_button, revert_button, back_button, about_button].into(),
         ));
 
         vec![container, nav_buttons].into()
@@ -0,0 +1,98 @@
+//! Feed stats subcommand.
+
+use crate::bot::command::feed::SendInto;
+use crate::bot::command::feed::get_or_create_subscriber;
+use crate::bot::command::prelude::*;
+use crate::service::feed_stats::SubscriberStats;
+
+/// Show stats of your feed notifications
+///
+/// Shows how many feeds you follow, how many notifications you received this
+/// month, your most active feeds and how many updates you get per week. Use
+/// `send_into: server` for the server's stats instead.
+#[poise::command(slash_command, ephemeral)]
+pub async fn stats(
+    ctx: Context<'_>,
+    #[description = "Whose stats to show. Default to DM"] send_into: Option<SendInto>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let send_into = send_into.unwrap_or(SendInto::DM);
+
+    let subscriber = get_or_create_subscriber(ctx, &send_into).await?;
+    let stats = ctx
+        .data()
+        .service
+        .feed_stats
+        .subscriber_stats(&subscriber)
+        .await?;
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format_stats(
+            &stats,
+            send_into.name(),
+        ))),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+    Ok(())
+}
+
+fn format_stats(stats: &SubscriberStats, target: &str) -> String {
+    let mut text = format!(
+        "### Feed Stats ({target})\n- **Subscriptions**: {}\n- **Notifications this month**: {}\n- **Per week**: {:.1} on average over the last 4 weeks",
+        stats.subscriptions, stats.this_month, stats.weekly_average
+    );
+
+    text.push_str("\n### Most Active Feeds\n-# Last 30 days");
+    if stats.top_feeds.is_empty() {
+        text.push_str("\n> 🛈  No notifications in the last 30 days.");
+    }
+    for (rank, feed) in stats.top_feeds.iter().enumerate() {
+        text.push_str(&format!(
+            "\n{}. [{}](<{}>): {} update(s)",
+            rank + 1,
+            feed.name,
+            feed.source_url,
+            feed.notifications
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::FeedNotificationCountRow;
+
+    #[test]
+    fn format_stats_lists_top_feeds_in_order() {
+        let stats = SubscriberStats {
+            subscriptions: 4,
+            this_month: 12,
+            top_feeds: vec![
+                FeedNotificationCountRow {
+                    feed_id: 1,
+                    name: "One Piece".to_string(),
+                    source_url: "https://example.com/one-piece".to_string(),
+                    notifications: 5,
+                },
+                FeedNotificationCountRow {
+                    feed_id: 2,
+                    name: "Frieren".to_string(),
+                    source_url: "https://example.com/frieren".to_string(),
+                    notifications: 2,
+                },
+            ],
+            weekly_average: 1.75,
+        };
+
+        let text = format_stats(&stats, "DM");
+        assert!(text.starts_with("### Feed Stats (DM)"));
+        assert!(text.contains("**Subscriptions**: 4"));
+        assert!(text.contains("**Notifications this month**: 12"));
+        assert!(text.contains("**Per week**: 1.8"));
+        assert!(text.contains("1. [One Piece](<https://example.com/one-piece>): 5 update(s)"));
+        assert!(text.contains("2. [Frieren](<https://example.com/frieren>): 2 update(s)"));
+        assert!(!text.contains("No notifications"));
+    }
+}
@@ -1,15 +1,24 @@
 //! Feed subscribe subcommand.
 
 use crate::bot::command::feed::SendInto;
+use crate::bot::command::feed::collection::autocomplete_collections;
 use crate::bot::command::feed::get_or_create_subscriber;
 use crate::bot::command::feed::process_subscription_batch;
+use crate::bot::command::feed::requests::needs_approval;
+use crate::bot::command::feed::requests::request_subscriptions;
 use crate::bot::command::feed::verify_server_config;
 use crate::bot::command::prelude::*;
+use crate::feed::PlatformChoice;
 
 /// Subscribe to one or more feeds
 ///
 /// Add feeds to receive notifications. You can subscribe in your DM or
-/// in the server (if server feed settings are configured).
+/// in the server (if server feed settings are configured). Searches such
+/// as Nyaa can be narrowed with a filter, e.g. `1080p SubsPlease`. If the server
+/// reviews new feeds, your server subscriptions are sent to its admins instead.
+/// Server subscriptions can be put in a collection to post them in its channel.
+/// Links can also be bare IDs, e.g. a MangaDex UUID or an AniList ID with `platform`,
+/// or links to a message in this server whose feed links should be subscribed to.
 #[poise::command(slash_command)]
 pub async fn subscribe(
     ctx: Context<'_>,
@@ -19,9 +28,23 @@ pub async fn subscribe(
     #[description = "Where to send the notifications. Default to your DM"] send_into: Option<
         SendInto,
     >,
+    #[description = "Only notify for items containing these words, e.g. 1080p SubsPlease. Nyaa only"]
+    filter: Option<String>,
+    #[description = "Server collection to post the feeds in, instead of the server's feed channel"]
+    #[autocomplete = "autocomplete_collections"]
+    collection: Option<String>,
+    #[description = "Platform of bare IDs in links, e.g. AniList for 154587"]
+    #[autocomplete = "autocomplete_platforms"]
+    platform: Option<String>,
 ) -> Result<(), Error> {
     Router::new(ctx)
-        .run(Navigation::FeedSubscribe { links, send_into })
+        .run(Navigation::FeedSubscribe {
+            links,
+            send_into,
+            filter,
+            collection,
+            platform,
+        })
         .await?;
     Ok(())
 }
@@ -29,6 +52,9 @@ pub async fn subscribe(
 handler! { pub struct FeedSubscribeHandler<'a> {
     links: String,
     send_into: Option<SendInto>,
+    filter: Option<String>,
+    collection: Option<String>,
+    platform: Option<String>,
 } }
 
 #[async_trait::async_trait]
@@ -38,33 +64,160 @@ impl CommandHandler for FeedSubscribeHandler<'_> {
         ctx.defer().await?;
 
         let send_into = self.send_into.unwrap_or(SendInto::DM);
-        let urls = parse_and_validate_urls(&self.links)?;
+        let links = resolve_links(ctx, &self.links, self.platform.as_deref()).await?;
+        let urls: Vec<&str> = links.iter().map(String::as_str).collect();
+        validate_url_count(&urls)?;
 
+        // Filtered searches are separate feeds, so subscribe to their urls instead
+        let filtered: Vec<String>;
+        let urls = match self.filter.as_deref().map(str::trim) {
+            Some(filter) if !filter.is_empty() => {
+                let platforms = &ctx.data().platforms;
+                filtered = urls
+                    .iter()
+                    .map(|url| platforms.get_filtered_source_url(url, filter))
+                    .collect::<Result<_, _>>()?;
+                filtered.iter().map(String::as_str).collect()
+            }
+            _ => urls,
+        };
+
+        if needs_approval(ctx, &send_into).await? {
+            return request_subscriptions(ctx, &urls).await;
+        }
         verify_server_config(ctx, &send_into, true).await?;
+        let collection_id = match self.collection.as_deref() {
+            Some(name) => {
+                if send_into != SendInto::Server {
+                    return Err(BotError::InvalidCommandArgument {
+                        parameter: "collection".to_string(),
+                        reason: "Collections are only available for server subscriptions"
+                            .to_string(),
+                    }
+                    .into());
+                }
+                let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+                let collections = &ctx.data().service.feed_collections;
+                Some(collections.get_collection(guild_id, name).await?.id)
+            }
+            None => None,
+        };
 
         let subscriber = get_or_create_subscriber(ctx, &send_into).await?;
-        Ok(process_subscription_batch(coordinator, &urls, &subscriber, true).await?)
+        Ok(
+            process_subscription_batch(coordinator, &urls, &subscriber, true, collection_id)
+                .await?,
+        )
+    }
+}
+
+/// Resolves comma-separated links to feed urls. Bare IDs are looked up on `platform`,
+/// and message links are replaced by the supported links in the message.
+///
+/// Only messages in the current server, or the current DM, are read.
+async fn resolve_links(
+    ctx: Context<'_>,
+    links: &str,
+    platform: Option<&str>,
+) -> Result<Vec<String>, Error> {
+    let platforms = &ctx.data().platforms;
+    let mut urls = Vec::new();
+    for link in parse_and_validate_urls(links)? {
+        if let Some(message) = parse_message_link(link) {
+            let readable = match message.guild_id {
+                Some(guild_id) => ctx.guild_id().is_some_and(|id| id.get() == guild_id),
+                None => ctx.guild_id().is_none() && ctx.channel_id().get() == message.channel_id,
+            };
+            if !readable {
+                return Err(BotError::InvalidCommandArgument {
+                    parameter: "links".to_string(),
+                    reason: "Only messages from this server can be subscribed from".to_string(),
+                }
+                .into());
+            }
+            let message = ctx
+                .http()
+                .get_message(
+                    GenericChannelId::new(message.channel_id),
+                    MessageId::new(message.message_id),
+                )
+                .await?;
+            let found = platforms.find_source_urls(&message.content);
+            if found.is_empty() {
+                return Err(BotError::InvalidCommandArgument {
+                    parameter: "links".to_string(),
+                    reason: "The linked message has no links of supported feeds".to_string(),
+                }
+                .into());
+            }
+            urls.extend(found);
+        } else if link.contains('/') {
+            urls.push(link.to_string());
+        } else {
+            let url = platforms
+                .get_source_url_from_id(link, platform)
+                .ok_or_else(|| match platform {
+                    Some(platform) => BotError::InvalidCommandArgument {
+                        parameter: "platform".to_string(),
+                        reason: format!("`{platform}` is not a supported platform"),
+                    },
+                    None => BotError::InvalidCommandArgument {
+                        parameter: "links".to_string(),
+                        reason: format!(
+                            "`{link}` is not a link. Pick the platform of bare IDs with `platform`"
+                        ),
+                    },
+                })?;
+            urls.push(url);
+        }
     }
+    Ok(urls)
 }
 
+/// Autocompletes supported platforms after a header listing them.
 pub async fn autocomplete_supported_feeds<'a>(
     ctx: Context<'_>,
     partial: &str,
 ) -> CreateAutocompleteResponse<'a> {
     let mut choices = vec![AutocompleteChoice::new("Supported feeds are:", "foo")];
-    let feeds = ctx.data().platforms.get_all_platforms();
-
-    for feed in feeds {
-        let info = &feed.get_base().info;
-        let name = format!("{} ({})", info.name, info.api_domain);
-        if partial.is_empty()
-            || name.to_lowercase().contains(&partial.to_lowercase())
-            || info.api_domain.contains(&partial.to_lowercase())
-        {
-            choices.push(AutocompleteChoice::new(name, info.api_domain.clone()));
-        }
-    }
+    choices.extend(
+        matching_platforms(ctx, partial)
+            .into_iter()
+            .map(|choice| AutocompleteChoice::new(choice.label, choice.domain)),
+    );
 
     choices.truncate(25);
     CreateAutocompleteResponse::new().set_choices(choices)
 }
+
+/// Autocompletes the domain of a supported platform.
+pub async fn autocomplete_platforms<'a>(
+    ctx: Context<'_>,
+    partial: &str,
+) -> CreateAutocompleteResponse<'a> {
+    let choices: Vec<AutocompleteChoice> = matching_platforms(ctx, partial)
+        .into_iter()
+        // Platforms without a domain, like podcasts and RSS feeds, have no bare IDs
+        .filter(|choice| !choice.domain.is_empty())
+        .map(|choice| AutocompleteChoice::new(choice.label, choice.domain))
+        .take(25)
+        .collect();
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
+
+/// Supported platforms, best fuzzy matches first, e.g. "mdx" for MangaDex.
+fn matching_platforms(ctx: Context<'_>, partial: &str) -> Vec<PlatformChoice> {
+    let mut matches: Vec<(i32, &PlatformChoice)> = ctx
+        .data()
+        .platforms
+        .get_choices()
+        .iter()
+        .filter_map(|choice| Some((fuzzy_score(partial, &choice.label)?, choice)))
+        .collect();
+    // Stable, so equal scores keep the registry's order
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches
+        .into_iter()
+        .map(|(_, choice)| choice.clone())
+        .collect()
+}
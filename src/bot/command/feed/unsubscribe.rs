@@ -5,6 +5,8 @@ use crate::bot::command::feed::get_or_create_subscriber;
 use crate::bot::command::feed::process_subscription_batch;
 use crate::bot::command::feed::verify_server_config;
 use crate::bot::command::prelude::*;
+use crate::entity::SubscriberType;
+use crate::service::feed_subscription::SubscriberTarget;
 
 /// Unsubscribe from one or more feeds
 ///
@@ -43,7 +45,7 @@ impl CommandHandler for FeedUnsubscribeHandler<'_> {
         verify_server_config(ctx, &send_into, false).await?;
 
         let subscriber = get_or_create_subscriber(ctx, &send_into).await?;
-        Ok(process_subscription_batch(coordinator, &urls, &subscriber, false).await?)
+        Ok(process_subscription_batch(coordinator, &urls, &subscriber, false, None).await?)
     }
 }
 
@@ -58,21 +60,20 @@ pub async fn autocomplete_subscriptions<'a>(
         )]);
     }
 
-    let service = ctx.data().service.feed_subscription.clone();
+    let user_target = SubscriberTarget {
+        subscriber_type: SubscriberType::Dm,
+        target_id: ctx.author().id.to_string(),
+    };
+    let guild_target = ctx.guild_id().map(|guild_id| SubscriberTarget {
+        subscriber_type: SubscriberType::Guild,
+        target_id: guild_id.to_string(),
+    });
 
-    let (user_sub, guild_sub) = service
-        .get_both_subscribers(
-            ctx.author().id.to_string(),
-            ctx.guild_id().map(|v| v.to_string()),
-        )
-        .await;
-
-    if user_sub.is_none() && guild_sub.is_none() {
-        return CreateAutocompleteResponse::new();
-    }
-
-    let feeds = service
-        .search_and_combine_feeds(partial, user_sub, guild_sub)
+    let feeds = ctx
+        .data()
+        .service
+        .feed_subscription
+        .search_and_combine_feeds(partial, Some(user_target), guild_target)
         .await;
 
     if ctx.guild_id().is_none() && feeds.is_empty() {
@@ -39,7 +39,7 @@ const TEST_STEPS: &[TestStep] = &[
         steps::feed::feed_list_empty
     ),
     crate::test_step!(
-        "/vc leaderboard",
+        "/vc leaderboard show",
         "Voice leaderboard",
         steps::voice::voice_leaderboard
     ),
@@ -10,6 +10,7 @@ use crate::bot::test_framework::helpers::extract_actions;
 use crate::bot::test_framework::helpers::simulate_click;
 use crate::bot::view::ViewCmd;
 use crate::entity::FeedEntity;
+use crate::entity::PollTier;
 use crate::entity::SubscriberEntity;
 use crate::entity::SubscriberType;
 use crate::service::feed_subscription::Subscription;
@@ -20,6 +21,8 @@ pub async fn feed_list_empty(ctx: Context<'_>) -> Result<(), GuiTestError> {
         id: 0,
         r#type: SubscriberType::Dm,
         target_id: ctx.author().id.to_string(),
+        max_subscriptions: None,
+        ..Default::default()
     };
 
     let feed = FeedEntity {
@@ -32,11 +35,21 @@ pub async fn feed_list_empty(ctx: Context<'_>) -> Result<(), GuiTestError> {
         source_url: "https://example.com/test".to_string(),
         cover_url: "https://example.com/cover.png".to_string(),
         tags: "test".to_string(),
+        poll_tier: PollTier::Active,
+        accent_color: None,
+        emoji: None,
+        title_english: None,
+        title_romanized: None,
+        title_native: None,
     };
 
     let subscription = Subscription {
         feed,
         feed_latest: None,
+        paused: false,
+        notify_edits: false,
+        added_by: None,
+        collection_id: None,
     };
 
     let mut view = FeedListView {
@@ -44,6 +57,7 @@ pub async fn feed_list_empty(ctx: Context<'_>) -> Result<(), GuiTestError> {
         model: FeedListModel::new(SUBSCRIPTIONS_PER_PAGE),
         service: ctx.data().service.feed_subscription.clone(),
         subscriber,
+        collections: Vec::new(),
     };
 
     // Initial view mode should have Edit button
@@ -16,6 +16,7 @@ use crate::bot::view::SelectValues;
 use crate::bot::view::ViewCmd;
 use crate::entity::Json;
 use crate::entity::ServerSettingsEntity;
+use crate::update::Staged;
 use crate::update::feed_settings::FeedSettingsModel;
 use crate::update::settings_main::SettingsMainModel;
 
@@ -111,7 +112,7 @@ pub async fn feed_settings(ctx: Context<'_>) -> Result<(), GuiTestError> {
         "none",
     ))?;
 
-    let mut settings = ctx
+    let settings = ctx
         .data()
         .service
         .feed_subscription
@@ -119,15 +120,11 @@ pub async fn feed_settings(ctx: Context<'_>) -> Result<(), GuiTestError> {
         .await
         .map_err(|e| GuiTestError::setup_failed("feed_settings", e))?;
 
-    let feeds_settings = settings.feeds.clone();
     let mut handler = SettingsFeedHandler {
-        model: FeedSettingsModel {
-            enabled: feeds_settings.enabled,
-            channel_id: feeds_settings.channel_id,
-            subscribe_role_id: feeds_settings.subscribe_role_id,
-            unsubscribe_role_id: feeds_settings.unsubscribe_role_id,
-        },
-        settings: &mut settings,
+        model: FeedSettingsModel::from(&settings.feeds),
+        settings: Staged::new(settings),
+        guild_id: guild_id.into(),
+        default_retention_days: ctx.data().config.retention.feed_item_days,
     };
 
     let registry = extract_actions(&handler);
@@ -181,7 +178,13 @@ pub async fn voice_settings(ctx: Context<'_>) -> Result<(), GuiTestError> {
         .await
         .map_err(|e| GuiTestError::setup_failed("voice_settings", e))?;
 
-    let mut handler = SettingsVoiceHandler { settings };
+    let mut handler = SettingsVoiceHandler {
+        settings: Staged::new(settings),
+        guild_id: guild_id.into(),
+        weight_channel: None,
+        open_flags: 0,
+        default_retention_days: ctx.data().config.retention.voice_session_days,
+    };
 
     let registry = extract_actions(&handler);
     let toggle_action = assert_has_action(&registry, "ToggleEnabled")
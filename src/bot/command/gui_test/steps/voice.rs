@@ -54,6 +54,7 @@ pub async fn voice_stats(ctx: Context<'_>) -> Result<(), GuiTestError> {
         stat_type: GuildStatType::AverageTime,
         time_range: VoiceStatsTimeRange::Monthly,
         raw_sessions: vec![],
+        goal: None,
     };
 
     let mut view = VoiceStatsView::new(
@@ -61,6 +62,8 @@ pub async fn voice_stats(ctx: Context<'_>) -> Result<(), GuiTestError> {
         ctx.data().service.voice_tracking.clone(),
         guild_id.get(),
         ctx.author().clone(),
+        ctx.data().renderer.clone(),
+        ctx.data().config.chart_backend,
     );
 
     let registry = extract_actions(&view);
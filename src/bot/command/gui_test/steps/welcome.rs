@@ -11,6 +11,7 @@ use crate::bot::test_framework::assert::assert_has_action;
 use crate::bot::test_framework::helpers::extract_actions;
 use crate::bot::test_framework::helpers::simulate_click;
 use crate::bot::view::ViewCmd;
+use crate::update::Staged;
 use crate::update::welcome_settings::WelcomeSettingsModel;
 
 pub async fn welcome_settings(ctx: Context<'_>) -> Result<(), GuiTestError> {
@@ -20,8 +21,10 @@ pub async fn welcome_settings(ctx: Context<'_>) -> Result<(), GuiTestError> {
         "none",
     ))?;
 
-    let service = ctx.data().service.feed_subscription.clone();
-    let settings = service
+    let settings = ctx
+        .data()
+        .service
+        .settings
         .get_server_settings(guild_id.into())
         .await
         .map_err(|e| GuiTestError::setup_failed("welcome_settings", e))?;
@@ -30,10 +33,10 @@ pub async fn welcome_settings(ctx: Context<'_>) -> Result<(), GuiTestError> {
 
     let mut handler = SettingsWelcomeHandler {
         model: WelcomeSettingsModel::new(settings.welcome.clone()),
-        settings: settings.clone(),
+        settings: Staged::new(settings),
         current_image_bytes: None,
-        service,
         generator,
+        renderer: ctx.data().renderer.clone(),
         guild_id: guild_id.into(),
         ctx_serenity: ctx.serenity_context().clone(),
     };
@@ -0,0 +1,137 @@
+//! Invite leaderboard subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::entity::InviteLeaderboardEntry;
+use crate::service::traits::InviteTrackingProvider;
+
+const INVITERS_PER_PAGE: u32 = 20;
+
+/// Show who invited the most members to this server
+///
+/// Ranks members by how many members joined through their invites. Members who
+/// leave and join again are counted once.
+#[poise::command(slash_command)]
+pub async fn leaderboard(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::InviteLeaderboard).await?;
+    Ok(())
+}
+
+handler! { pub struct InviteLeaderboardHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for InviteLeaderboardHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+        ctx.defer().await?;
+
+        let service = ctx.data().service.invite_tracking.clone();
+        let total = service.count_inviters(guild_id).await?;
+        let entries = service
+            .list_leaderboard(guild_id, 1, INVITERS_PER_PAGE)
+            .await?;
+        let author_invites = service
+            .count_invites_by(guild_id, ctx.author().id.get())
+            .await?;
+
+        let view = InviteLeaderboardView {
+            entries,
+            author_invites,
+            pagination: PaginationView::new(total, INVITERS_PER_PAGE),
+            service,
+            guild_id,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct InviteLeaderboardView {
+    pub entries: Vec<InviteLeaderboardEntry>,
+    /// Members invited by the user who ran the command.
+    pub author_invites: u32,
+    pub pagination: PaginationView,
+    pub service: Arc<dyn InviteTrackingProvider>,
+    pub guild_id: u64,
+}
+
+impl InviteLeaderboardView {
+    async fn update_entries(&mut self) -> Result<(), Error> {
+        self.entries = self
+            .service
+            .list_leaderboard(
+                self.guild_id,
+                self.pagination.current_page(),
+                INVITERS_PER_PAGE,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for InviteLeaderboardView {
+    type Action = PaginationAction;
+    async fn handle(&mut self, ctx: ViewContext<'_, PaginationAction>) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            PaginationAction::First => self.pagination.state.first_page(),
+            PaginationAction::Prev => self.pagination.state.prev_page(),
+            PaginationAction::Next => self.pagination.state.next_page(),
+            PaginationAction::Last => self.pagination.state.last_page(),
+            PaginationAction::Page => return Ok(ViewCmd::Continue),
+        }
+        self.update_entries().await?;
+        Ok(ViewCmd::Render)
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.pagination.on_timeout().await
+    }
+}
+
+impl ViewRender for InviteLeaderboardView {
+    type Action = PaginationAction;
+    fn render(&self, registry: &mut ActionRegistry<PaginationAction>) -> ResponseKind<'_> {
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("## Invite Leaderboard"),
+        )];
+
+        let text = if self.entries.is_empty() {
+            "> 🛈  No members have joined through a tracked invite yet.".to_string()
+        } else {
+            let rank_offset = (self.pagination.current_page() - 1) * INVITERS_PER_PAGE;
+            self.entries
+                .iter()
+                .enumerate()
+                .map(|(idx, entry)| {
+                    format!(
+                        "{}. <@{}> — {} invites",
+                        rank_offset + idx as u32 + 1,
+                        entry.inviter_id,
+                        entry.invites
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(text),
+        ));
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!("-# You invited {} members", self.author_invites)),
+        ));
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+        self.pagination
+            .attach_if_multipage(registry, &mut components, std::convert::identity);
+
+        components.into()
+    }
+}
@@ -0,0 +1,13 @@
+//! Invite tracking commands.
+
+use crate::bot::command::prelude::*;
+
+pub mod leaderboard;
+
+/// Invite tracking commands
+///
+/// See who brought the most members to this server.
+#[poise::command(slash_command, subcommands("leaderboard::leaderboard"))]
+pub async fn invites(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
@@ -5,13 +5,18 @@
 //! different files and domains.
 
 pub mod about;
+pub mod diagnose;
 pub mod dump_db;
+pub mod emoji;
 pub mod feed;
 pub mod gui_test;
+pub mod invites;
+pub mod owner;
 pub mod prelude;
 pub mod register;
 pub mod register_owner;
 pub mod settings;
+pub mod tag;
 pub mod unregister;
 pub mod voice;
 pub mod welcome;
@@ -32,14 +37,29 @@ use poise::ReplyHandle;
 
 use crate::bot::Data;
 use crate::bot::command::about::AboutHandler;
+use crate::bot::command::emoji::stats::EmojiStatsHandler;
+use crate::bot::command::feed::from_message::FeedSubscribeFromMessageHandler;
 use crate::bot::command::feed::list::FeedListHandler;
+use crate::bot::command::feed::preferences::FeedPreferencesHandler;
+use crate::bot::command::feed::requests::FeedRequestsHandler;
 use crate::bot::command::feed::settings::FeedSettingsHandler;
 use crate::bot::command::feed::subscribe::FeedSubscribeHandler;
 use crate::bot::command::feed::unsubscribe::FeedUnsubscribeHandler;
+use crate::bot::command::invites::leaderboard::InviteLeaderboardHandler;
 use crate::bot::command::settings::SettingsMainHandler;
+use crate::bot::command::settings::api::SettingsApiHandler;
+use crate::bot::command::settings::commands::SettingsCommandsHandler;
+use crate::bot::command::settings::dashboard::SettingsDashboardHandler;
+use crate::bot::command::settings::general::SettingsGeneralHandler;
+use crate::bot::command::settings::history::SettingsHistoryHandler;
+use crate::bot::command::tag::list::TagListHandler;
+use crate::bot::command::voice::admin::review::VoiceAdminReviewHandler;
+use crate::bot::command::voice::afk::VoiceAfkHandler;
 use crate::bot::command::voice::leaderboard::VoiceLeaderboardHandler;
+use crate::bot::command::voice::now::VoiceNowHandler;
 use crate::bot::command::voice::settings::VoiceSettingsHandler;
 use crate::bot::command::voice::stats::VoiceStatsHandler;
+use crate::bot::command::voice::temp_channels::VoiceTempChannelsHandler;
 use crate::bot::command::welcome::WelcomeSettingsHandler;
 use crate::bot::navigation::Navigation;
 
@@ -61,14 +81,21 @@ impl Cog for Cogs {
     fn commands(&self) -> Vec<Command<Data, Error>> {
         vec![
             about::about(),
+            diagnose::diagnose(),
             dump_db::dump_db(),
+            emoji::emoji(),
             feed::feed(),
+            feed::from_message::subscribe_from_message(),
             gui_test::gui_test(),
+            invites::invites(),
+            owner::owner(),
             register::register(),
             register_owner::register_owner(),
             settings::settings(),
+            tag::tag(),
             unregister::unregister(),
             voice::voice(),
+            voice::stats::stats_of_user(),
             welcome::welcome(),
         ]
     }
@@ -158,18 +185,34 @@ impl<'a> Router<'a> {
             let nav = self.pop_next().await?;
             let res: Box<dyn CommandHandler> = match nav {
                 SettingsMain => Box::new(SettingsMainHandler::new(ctx)),
+                SettingsGeneral => Box::new(SettingsGeneralHandler::new(ctx)),
+                SettingsCommands => Box::new(SettingsCommandsHandler::new(ctx)),
                 SettingsFeeds => Box::new(FeedSettingsHandler::new(ctx)),
                 SettingsVoice => Box::new(VoiceSettingsHandler::new(ctx)),
                 SettingsWelcome => Box::new(WelcomeSettingsHandler::new(ctx)),
                 SettingsAbout => Box::new(AboutHandler::new(ctx)),
+                SettingsHistory => Box::new(SettingsHistoryHandler::new(ctx)),
+                SettingsDashboard => Box::new(SettingsDashboardHandler::new(ctx)),
+                SettingsApi => Box::new(SettingsApiHandler::new(ctx)),
                 FeedSubscriptions { send_into } => Box::new(FeedListHandler::new(ctx, send_into?)),
-                FeedSubscribe { links, send_into } => {
-                    Box::new(FeedSubscribeHandler::new(ctx, links, send_into))
+                FeedSubscribe {
+                    links,
+                    send_into,
+                    filter,
+                    collection,
+                    platform,
+                } => Box::new(FeedSubscribeHandler::new(
+                    ctx, links, send_into, filter, collection, platform,
+                )),
+                FeedSubscribeFromMessage { urls } => {
+                    Box::new(FeedSubscribeFromMessageHandler::new(ctx, urls))
                 }
                 FeedUnsubscribe { links, send_into } => {
                     Box::new(FeedUnsubscribeHandler::new(ctx, links, send_into))
                 }
                 FeedList(send_into) => Box::new(FeedListHandler::new(ctx, send_into?)),
+                FeedPreferences => Box::new(FeedPreferencesHandler::new(ctx)),
+                FeedRequests => Box::new(FeedRequestsHandler::new(ctx)),
                 VoiceLeaderboard { time_range } => {
                     Box::new(VoiceLeaderboardHandler::new(ctx, time_range))
                 }
@@ -183,6 +226,13 @@ impl<'a> Router<'a> {
                     *target_user,
                     stat_type,
                 )),
+                VoiceNow => Box::new(VoiceNowHandler::new(ctx)),
+                VoiceAdminReview => Box::new(VoiceAdminReviewHandler::new(ctx)),
+                VoiceAfk => Box::new(VoiceAfkHandler::new(ctx)),
+                VoiceTempChannels => Box::new(VoiceTempChannelsHandler::new(ctx)),
+                TagList => Box::new(TagListHandler::new(ctx)),
+                EmojiStats => Box::new(EmojiStatsHandler::new(ctx)),
+                InviteLeaderboard => Box::new(InviteLeaderboardHandler::new(ctx)),
                 Back => continue,
                 Exit => return None,
             };
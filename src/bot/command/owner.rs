@@ -0,0 +1,47 @@
+//! Bot owner maintenance and debugging commands.
+
+use crate::bot::command::prelude::*;
+
+pub mod api_status;
+pub mod api_token;
+pub mod config_check;
+pub mod custom_feed;
+pub mod delivery_stats;
+pub mod feed_branding;
+pub mod quota;
+pub mod register;
+pub mod simulate_update;
+pub mod status;
+pub mod voice_import;
+pub mod voice_leaderboard;
+pub mod voice_merge;
+pub mod voice_rebuild;
+
+/// Bot owner commands
+///
+/// Maintenance and debugging tools restricted to the bot owner.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    hide_in_help,
+    subcommands(
+        "api_status::api_status",
+        "api_token::api_token",
+        "config_check::config_check",
+        "custom_feed::custom_feed",
+        "delivery_stats::delivery_stats",
+        "feed_branding::feed_branding",
+        "quota::quota",
+        "register::register",
+        "simulate_update::simulate_update",
+        "status::status",
+        "voice_import::voice_import",
+        "voice_leaderboard::voice_leaderboard",
+        "voice_merge::voice_merge",
+        "voice_rebuild::voice_rebuild"
+    )
+)]
+pub async fn owner(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Owner API status subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::feed::request_stats::RequestSummary;
+use crate::feed::request_stats::WINDOW_HOURS;
+
+/// Show feed platform requests of the last day against their budgets
+///
+/// Lists each platform's requests, error rate and rate limiter waits, with the
+/// share of the budget its rate limiter allows and the rate limit the platform
+/// last reported, to help tune the poll interval.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    hide_in_help,
+    rename = "apistatus"
+)]
+pub async fn api_status(ctx: Context<'_>) -> Result<(), Error> {
+    command(ctx).await
+}
+
+pub async fn command(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.data();
+
+    let mut status_text = format!(
+        "### API Status (last {WINDOW_HOURS}h)\n- **Poll interval**: {}",
+        format_duration(data.config.poll_interval.as_secs() as i64)
+    );
+    let mut idle = Vec::new();
+    for platform in data.platforms.get_all_platforms() {
+        let summary = platform.get_base().requests.summary();
+        if summary.counts.requests == 0 && summary.counts.throttled == 0 {
+            idle.push(platform.get_id().to_string());
+            continue;
+        }
+        status_text.push_str(&format!(
+            "\n### {}\n{}",
+            platform.get_id(),
+            render_summary(&summary)
+        ));
+    }
+    if !idle.is_empty() {
+        status_text.push_str(&format!("\n-# No requests: {}", idle.join(", ")));
+    }
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
+
+fn render_summary(summary: &RequestSummary) -> String {
+    let counts = &summary.counts;
+    let mut lines = vec![match (summary.budget, summary.budget_used()) {
+        (Some(budget), Some(used)) => format!(
+            "- **Requests**: {} of {budget} budget ({:.1}%)",
+            counts.requests,
+            used * 100.0
+        ),
+        _ => format!("- **Requests**: {}", counts.requests),
+    }];
+    lines.push(format!(
+        "- **Errors**: {} ({:.1}%)",
+        counts.errors,
+        counts.error_rate() * 100.0
+    ));
+    if counts.throttled > 0 {
+        lines.push(format!(
+            "- **Rate limited**: waited {} times",
+            counts.throttled
+        ));
+    }
+    if let Some(rate_limit) = summary.rate_limit {
+        let limit = rate_limit
+            .limit
+            .map(|limit| format!(" of {limit}"))
+            .unwrap_or_default();
+        lines.push(format!(
+            "- **Reported limit**: {}{limit} remaining <t:{}:R>",
+            rate_limit.remaining,
+            rate_limit.reported_at.timestamp()
+        ));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,49 @@
+//! Owner REST API token subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::service::api::ApiScope;
+
+/// Generate or revoke the owner REST API token
+///
+/// The owner token can access every server through the REST API.
+/// Generating a new token invalidates the previous one.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help, ephemeral)]
+pub async fn api_token(
+    ctx: Context<'_>,
+    #[description = "Revoke the current token instead of generating a new one"] revoke: Option<
+        bool,
+    >,
+) -> Result<(), Error> {
+    command(ctx, revoke.unwrap_or(false)).await
+}
+
+pub async fn command(ctx: Context<'_>, revoke: bool) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let service = ctx.data().service.api_tokens.clone();
+    let status_text = if revoke {
+        if service.revoke_api_token(ApiScope::Owner).await? {
+            "### Owner API Token Revoked".to_string()
+        } else {
+            "### No Owner API Token\nThere was no owner token to revoke.".to_string()
+        }
+    } else {
+        let (_, token) = service
+            .generate_api_token(ApiScope::Owner, ctx.author().id.get())
+            .await?;
+        format!(
+            "### Owner API Token Generated\n- **Base URL**: {}/api/v1\n- **Token**: ||`{}`||\n-# It is only shown now. The previous owner token no longer works.",
+            ctx.data().config.web.public_url,
+            token
+        )
+    };
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(CreateReply::from(response).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
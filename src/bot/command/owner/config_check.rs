@@ -0,0 +1,101 @@
+//! Owner config check subcommand.
+
+use crate::bot::command::diagnose::Diagnosis;
+use crate::bot::command::diagnose::FeatureReport;
+use crate::bot::command::prelude::*;
+use crate::check;
+use crate::check::CheckReport;
+use crate::check::CheckStatus;
+
+/// Servers listed by name when missing permissions. The rest are only counted.
+const MAX_LISTED_GUILDS: usize = 10;
+
+/// Validate the configuration, database and Discord setup
+///
+/// Runs the checks of `pwr-bot --check`, then checks the bot's permissions
+/// for the configured features of every server.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn config_check(ctx: Context<'_>) -> Result<(), Error> {
+    command(ctx).await
+}
+
+pub async fn command(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+    let data = ctx.data();
+
+    let mut report = check::run(&data.config).await;
+    check_permissions(ctx, &mut report).await?;
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(report.render_markdown())),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
+
+/// Runs `/diagnose` for every cached server and reports those with failing features.
+async fn check_permissions(ctx: Context<'_>, report: &mut CheckReport) -> Result<(), Error> {
+    let bot_id = ctx.cache().current_user().id;
+    let guild_ids = ctx.cache().guilds();
+
+    let mut failing = Vec::new();
+    for guild_id in &guild_ids {
+        let settings = ctx
+            .data()
+            .service
+            .settings
+            .get_server_settings(guild_id.get())
+            .await?;
+        let (guild_name, reports) = {
+            let Some(guild) = ctx.cache().guild(*guild_id) else {
+                continue;
+            };
+            let Some(bot) = guild.members.get(&bot_id) else {
+                continue;
+            };
+            (
+                guild.name.to_string(),
+                FeatureReport::all(&guild, bot, &settings),
+            )
+        };
+
+        let features: Vec<_> = reports
+            .iter()
+            .filter(|report| matches!(report.diagnosis, Diagnosis::Failing(_)))
+            .map(|report| report.feature)
+            .collect();
+        if !features.is_empty() {
+            failing.push(format!("{guild_name} ({})", features.join(", ")));
+        }
+    }
+
+    if failing.is_empty() {
+        report.push(
+            "permissions",
+            CheckStatus::Pass,
+            format!(
+                "The bot has what its features need in all {} servers",
+                guild_ids.len()
+            ),
+        );
+    } else {
+        let mut detail = format!(
+            "{} of {} servers are missing permissions, run `/diagnose` there: {}",
+            failing.len(),
+            guild_ids.len(),
+            failing
+                .iter()
+                .take(MAX_LISTED_GUILDS)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+        if failing.len() > MAX_LISTED_GUILDS {
+            detail.push_str(&format!(" and {} more", failing.len() - MAX_LISTED_GUILDS));
+        }
+        report.push("permissions", CheckStatus::Warn, detail);
+    }
+    Ok(())
+}
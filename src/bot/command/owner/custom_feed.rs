@@ -0,0 +1,130 @@
+//! Owner custom JSON feed subcommand.
+
+use chrono::Utc;
+
+use crate::bot::command::prelude::*;
+use crate::entity::CustomJsonFeedEntity;
+use crate::entity::DbU64;
+use crate::feed::Platform;
+
+/// Add, replace, remove or list custom JSON feeds
+///
+/// A custom feed polls a JSON API and reads its items with JSONPath
+/// expressions. Item paths are relative to each item, e.g. `$.id`. Users
+/// subscribe to it with its url. Leave `url` empty to list the feeds.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help, ephemeral)]
+#[allow(clippy::too_many_arguments)]
+pub async fn custom_feed(
+    ctx: Context<'_>,
+    #[description = "API endpoint returning the items. Leave empty to list custom feeds"]
+    url: Option<String>,
+    #[description = "Remove the custom feed with this url"] remove: Option<bool>,
+    #[description = "Name shown as the feed's title"] name: Option<String>,
+    #[description = "JSONPath selecting the items, e.g. $.data[*]"] items_path: Option<String>,
+    #[description = "JSONPath of an item's unique ID, e.g. $.id"] id_path: Option<String>,
+    #[description = "JSONPath of an item's title, e.g. $.title"] title_path: Option<String>,
+    #[description = "JSONPath of an item's link, e.g. $.url"] url_path: Option<String>,
+    #[description = "JSONPath of an item's publish time. Items are taken in response order when empty"]
+    published_path: Option<String>,
+) -> Result<(), Error> {
+    let Some(url) = url else {
+        return list(ctx).await;
+    };
+    if remove.unwrap_or(false) {
+        return command_remove(ctx, url).await;
+    }
+
+    let required = |value: Option<String>, parameter: &str| {
+        value
+            .filter(|v| !v.trim().is_empty())
+            .ok_or_else(|| BotError::InvalidCommandArgument {
+                parameter: parameter.to_string(),
+                reason: "Required when adding a custom feed".to_string(),
+            })
+    };
+    let feed = CustomJsonFeedEntity {
+        name: required(name, "name")?.trim().to_string(),
+        url,
+        items_path: required(items_path, "items_path")?,
+        id_path: required(id_path, "id_path")?,
+        title_path: required(title_path, "title_path")?,
+        url_path: url_path.filter(|v| !v.trim().is_empty()),
+        published_path: published_path.filter(|v| !v.trim().is_empty()),
+        created_by: Some(DbU64::from(ctx.author().id.get())),
+        created_at: Utc::now(),
+        ..Default::default()
+    };
+    command_set(ctx, feed).await
+}
+
+pub async fn command_set(ctx: Context<'_>, feed: CustomJsonFeedEntity) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let feed = ctx
+        .data()
+        .service
+        .custom_feeds
+        .set_custom_feed(feed)
+        .await?;
+    // Fetching the newest item checks the mapping against the live API
+    let latest = ctx
+        .data()
+        .platforms
+        .custom_json
+        .fetch_latest(&feed.url)
+        .await;
+    let check = match latest {
+        Ok(item) => format!("✅ Newest item: **{}** (`{}`)", item.title, item.id),
+        Err(e) => format!("⚠️ The API could not be read yet: {e}"),
+    };
+
+    let status_text = format!(
+        "### Custom Feed Saved\n- **Name**: {}\n- **Url**: {}\n{check}\n-# Users can subscribe with `/feed subscribe` and this url.",
+        feed.name, feed.url
+    );
+    reply(ctx, status_text).await
+}
+
+pub async fn command_remove(ctx: Context<'_>, url: String) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let removed = ctx
+        .data()
+        .service
+        .custom_feeds
+        .remove_custom_feed(&url)
+        .await?;
+    let status_text = if removed {
+        format!("### Custom Feed Removed\n{url}\n-# Existing subscriptions stop receiving updates.")
+    } else {
+        format!("### No Custom Feed\nNo custom feed polls {url}.")
+    };
+    reply(ctx, status_text).await
+}
+
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let feeds = ctx.data().service.custom_feeds.list_custom_feeds().await?;
+    let status_text = if feeds.is_empty() {
+        "### Custom Feeds\nNo custom feeds are configured.".to_string()
+    } else {
+        let lines = feeds
+            .iter()
+            .map(|feed| format!("- **{}**: {} (`{}`)", feed.name, feed.url, feed.items_path))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("### Custom Feeds\n{lines}")
+    };
+    reply(ctx, status_text).await
+}
+
+async fn reply(ctx: Context<'_>, status_text: String) -> Result<(), Error> {
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(CreateReply::from(response).ephemeral(true))
+        .await?;
+    Ok(())
+}
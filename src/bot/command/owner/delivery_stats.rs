@@ -0,0 +1,64 @@
+//! Owner delivery analytics subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::bot::delivery_report::DELIVERY_REPORT_FILENAME;
+use crate::bot::delivery_report::DeliveryReport;
+
+/// Days covered without `days`.
+const DEFAULT_DAYS: u32 = 30;
+/// Most days one report covers.
+const MAX_DAYS: u32 = 90;
+
+/// Show delivery analytics of feed notifications
+///
+/// Renders the notifications sent per day, the failure rate per subscriber type
+/// and the platforms whose updates take the longest to arrive after publication.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn delivery_stats(
+    ctx: Context<'_>,
+    #[description = "Days to cover, up to 90. Defaults to 30"]
+    #[min = 1]
+    #[max = 90]
+    days: Option<u32>,
+) -> Result<(), Error> {
+    command(ctx, days).await
+}
+
+pub async fn command(ctx: Context<'_>, days: Option<u32>) -> Result<(), Error> {
+    ctx.defer().await?;
+    let days = days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+    let analytics = ctx
+        .data()
+        .service
+        .feed_stats
+        .delivery_analytics(days)
+        .await?;
+    let delivered = analytics.delivered();
+    let failed = analytics.failed();
+
+    let report = DeliveryReport::new(analytics);
+    let image = ctx
+        .data()
+        .renderer
+        .render("delivery_report", move || report.render())
+        .await
+        .map_err(AppError::internal_with_ref)?;
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+            "### Delivery Analytics\n-# Last **{days}** day(s): **{delivered}** delivered, **{failed}** failed"
+        ))),
+        CreateContainerComponent::MediaGallery(CreateMediaGallery::new(vec![
+            CreateMediaGalleryItem::new(CreateUnfurledMediaItem::new(format!(
+                "attachment://{DELIVERY_REPORT_FILENAME}"
+            ))),
+        ])),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    let reply: poise::CreateReply<'_> = response.into();
+    ctx.send(reply.attachment(CreateAttachment::bytes(image, DELIVERY_REPORT_FILENAME)))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Owner feed branding subcommand.
+
+use crate::bot::command::prelude::*;
+
+/// Longest accepted emoji, in characters. Fits a custom emoji like `<:name:id>`.
+const MAX_EMOJI_LEN: usize = 64;
+
+/// Set the accent color and emoji of a feed's notifications
+///
+/// Overrides the defaults of the feed's platform for every subscriber. Options
+/// left out fall back to the platform's, so running it with only a link resets
+/// the feed. Use `none` as the emoji to show none.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    hide_in_help,
+    rename = "feed-branding"
+)]
+pub async fn feed_branding(
+    ctx: Context<'_>,
+    #[description = "Link of a feed that is already tracked"] link: String,
+    #[description = "Accent color as hex, e.g. #FF6740"] color: Option<String>,
+    #[description = "Emoji shown before the feed's name, or none"] emoji: Option<String>,
+) -> Result<(), Error> {
+    command(ctx, link, color, emoji).await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    link: String,
+    color: Option<String>,
+    emoji: Option<String>,
+) -> Result<(), Error> {
+    let accent_color = color
+        .as_deref()
+        .map(|color| {
+            parse_hex_color(color).ok_or_else(|| BotError::InvalidCommandArgument {
+                parameter: "color".to_string(),
+                reason: "Use a hex color like #FF6740".to_string(),
+            })
+        })
+        .transpose()?;
+    let emoji = match emoji.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(emoji) if emoji.eq_ignore_ascii_case("none") => Some(String::new()),
+        Some(emoji) if emoji.chars().count() > MAX_EMOJI_LEN => {
+            return Err(BotError::InvalidCommandArgument {
+                parameter: "emoji".to_string(),
+                reason: format!("The emoji cannot be longer than {MAX_EMOJI_LEN} characters"),
+            }
+            .into());
+        }
+        Some(emoji) => Some(emoji.to_string()),
+    };
+
+    let Some(feed) = ctx
+        .data()
+        .service
+        .feed_subscription
+        .set_feed_branding(link.trim(), accent_color, emoji)
+        .await?
+    else {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "link".to_string(),
+            reason: "No one is subscribed to this feed yet".to_string(),
+        }
+        .into());
+    };
+
+    let color = feed
+        .accent_color
+        .map(|color| format!("#{color:06X}"))
+        .unwrap_or_else(|| "platform default".to_string());
+    let emoji = match feed.emoji.as_deref() {
+        Some("") => "none".to_string(),
+        Some(emoji) => emoji.to_string(),
+        None => "platform default".to_string(),
+    };
+    let status_text = format!(
+        "### Feed Branding Saved\n- **Feed**: [{}](<{}>)\n- **Color**: {color}\n- **Emoji**: {emoji}",
+        feed.name, feed.source_url
+    );
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
+
+/// Parses a `#RRGGBB`, `0xRRGGBB` or `RRGGBB` color.
+fn parse_hex_color(input: &str) -> Option<i32> {
+    let input = input.trim();
+    let hex = input
+        .strip_prefix('#')
+        .or_else(|| input.strip_prefix("0x"))
+        .unwrap_or(input);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    i32::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_color_accepts_common_forms() {
+        assert_eq!(parse_hex_color("#FF6740"), Some(0xFF6740));
+        assert_eq!(parse_hex_color(" 0x02a9ff "), Some(0x02A9FF));
+        assert_eq!(parse_hex_color("1185FE"), Some(0x1185FE));
+        assert_eq!(parse_hex_color("#FFF"), None);
+        assert_eq!(parse_hex_color("#GGGGGG"), None);
+        assert_eq!(parse_hex_color("#+12345"), None);
+    }
+}
@@ -0,0 +1,64 @@
+//! Owner quota subcommand.
+
+use crate::bot::command::feed::SendInto;
+use crate::bot::command::prelude::*;
+use crate::entity::SubscriberType;
+use crate::service::feed_subscription::SubscriberTarget;
+
+/// Set the subscription quota of a user or server
+///
+/// Overrides the configured default subscription cap for a single target.
+/// Leave `limit` empty to restore the default.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn quota(
+    ctx: Context<'_>,
+    #[description = "Whether the target is a user (DM) or a server"] target_type: SendInto,
+    #[description = "User ID or server ID"] target_id: String,
+    #[description = "Maximum number of subscriptions. Leave empty to restore the default"]
+    limit: Option<u32>,
+) -> Result<(), Error> {
+    command(ctx, target_type, target_id, limit).await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    target_type: SendInto,
+    target_id: String,
+    limit: Option<u32>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let target_id = target_id.trim();
+    if target_id.parse::<u64>().is_err() {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "target_id".to_string(),
+            reason: format!("`{target_id}` is not a valid Discord ID"),
+        }
+        .into());
+    }
+
+    let target = SubscriberTarget {
+        subscriber_type: SubscriberType::from(&target_type),
+        target_id: target_id.to_string(),
+    };
+
+    let service = ctx.data().service.feed_subscription.clone();
+    let subscriber = service.set_subscription_quota(&target, limit).await?;
+    let effective = service.get_subscription_limit(&subscriber);
+
+    let status_text = format!(
+        "### Subscription Quota Updated\n- **Target**: {} `{}`\n- **Limit**: {} ({})",
+        target_type.name(),
+        target_id,
+        effective,
+        if limit.is_some() { "custom" } else { "default" }
+    );
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! Owner command registration subcommand.
+
+use poise::samples::create_application_commands;
+
+use crate::bot::command::prelude::*;
+
+/// Where slash commands are registered.
+#[derive(ChoiceParameter, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegisterScope {
+    /// Every server and DM. Changes can take up to an hour to show up.
+    #[name = "global"]
+    Global,
+    /// The current server only. Changes show up instantly.
+    #[name = "guild"]
+    Guild,
+    /// The servers set in `DEV_GUILD_IDS`. Changes show up instantly.
+    #[name = "dev"]
+    Dev,
+}
+
+/// Register slash commands globally or in servers
+///
+/// Server commands update instantly, global ones can take up to an hour. Without
+/// `scope`, registers in the dev servers set in `DEV_GUILD_IDS`, or in this server if
+/// none are set. `clear` removes the commands from the scope instead.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn register(
+    ctx: Context<'_>,
+    #[description = "Where to register the commands"] scope: Option<RegisterScope>,
+    #[description = "Remove the commands from the scope instead"] clear: Option<bool>,
+) -> Result<(), Error> {
+    command(ctx, scope, clear.unwrap_or(false)).await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    scope: Option<RegisterScope>,
+    clear: bool,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let dev_guild_ids = &ctx.data().config.dev_guild_ids;
+    let scope = scope.unwrap_or(if dev_guild_ids.is_empty() {
+        RegisterScope::Guild
+    } else {
+        RegisterScope::Dev
+    });
+
+    let commands = if clear {
+        Vec::new()
+    } else {
+        create_application_commands(&ctx.framework().options().commands)
+    };
+
+    let target = match scope {
+        RegisterScope::Global => {
+            poise::serenity_prelude::Command::set_global_commands(ctx.http(), &commands).await?;
+            "globally".to_string()
+        }
+        RegisterScope::Guild => {
+            let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+            guild_id.set_commands(ctx.http(), &commands).await?;
+            "in this server".to_string()
+        }
+        RegisterScope::Dev => {
+            if dev_guild_ids.is_empty() {
+                return Err(BotError::ConfigurationError(
+                    "No dev servers are set. List their IDs in `DEV_GUILD_IDS`.".to_string(),
+                )
+                .into());
+            }
+            for guild_id in dev_guild_ids {
+                GuildId::new(*guild_id)
+                    .set_commands(ctx.http(), &commands)
+                    .await?;
+            }
+            format!("in {} dev server(s)", dev_guild_ids.len())
+        }
+    };
+
+    let message = if clear {
+        format!("Removed the commands {target}.")
+    } else {
+        format!("Registered **{}** commands {target}.", commands.len())
+    };
+    ctx.say(message).await?;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! Owner simulate-update subcommand.
+
+use std::sync::Arc;
+
+use crate::bot::command::prelude::*;
+use crate::entity::SubscriberType;
+use crate::event::FeedUpdateData;
+use crate::event::FeedUpdateEvent;
+use crate::event::FeedUpdateKind;
+use crate::service::feed_subscription::FeedUpdateResult;
+use crate::service::feed_subscription::SubscriberTarget;
+
+/// Publish a fake update for a tracked feed
+///
+/// Sends a synthetic feed update through the event bus so that channel settings,
+/// message templates, and role mentions can be verified end to end. It only goes to
+/// this server, or to your DMs outside a server. Nothing is written to the database,
+/// logged for stats or streamed.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    hide_in_help,
+    rename = "simulate-update"
+)]
+pub async fn simulate_update(
+    ctx: Context<'_>,
+    #[description = "Link of a feed that is already tracked"] link: String,
+) -> Result<(), Error> {
+    command(ctx, link).await
+}
+
+pub async fn command(ctx: Context<'_>, link: String) -> Result<(), Error> {
+    ctx.defer().await?;
+    let data = ctx.data();
+
+    let target = match ctx.guild_id() {
+        Some(guild_id) => SubscriberTarget {
+            subscriber_type: SubscriberType::Guild,
+            target_id: guild_id.to_string(),
+        },
+        None => SubscriberTarget {
+            subscriber_type: SubscriberType::Dm,
+            target_id: ctx.author().id.to_string(),
+        },
+    };
+    let subscriber = data
+        .service
+        .feed_subscription
+        .get_subscriber(&target)
+        .await?;
+    let subscribed = match &subscriber {
+        Some(subscriber) => data
+            .service
+            .feed_subscription
+            .get_subscription(link.trim(), subscriber)
+            .await?
+            .is_some(),
+        None => false,
+    };
+    let Some(subscriber) = subscriber.filter(|_| subscribed) else {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "link".to_string(),
+            reason: "Subscribe here to the feed first; the update is only sent here".to_string(),
+        }
+        .into());
+    };
+
+    let result = data
+        .service
+        .feed_subscription
+        .simulate_feed_update(link.trim())
+        .await?;
+
+    let FeedUpdateResult::Updated {
+        feed,
+        old_item,
+        new_item,
+        feed_info,
+    } = result
+    else {
+        return Err(AppError::internal_with_ref("simulate_feed_update returned no update").into());
+    };
+
+    let status_text = format!(
+        "### Simulated Update Published\n- **Feed**: [{}](<{}>)\n- **Item**: {}",
+        feed.name, feed.source_url, new_item.description
+    );
+
+    let event = FeedUpdateEvent::simulated(
+        FeedUpdateData {
+            feed: Arc::new(feed),
+            feed_info: Arc::new(feed_info),
+            old_feed_item: old_item.map(Arc::new),
+            new_feed_item: Arc::new(new_item),
+            kind: FeedUpdateKind::New,
+        },
+        subscriber.id,
+    );
+    data.event_bus.publish(event);
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+//! Owner status subcommand.
+
+use crate::bot::command::prelude::*;
+
+/// Show the connection health of every bot client
+///
+/// Lists each bot's lifecycle state and ready shards, how often its client
+/// was rebuilt since startup, and why it last disconnected.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    command(ctx).await
+}
+
+pub async fn command(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.data();
+
+    let mut status_text = format!(
+        "### Bot Status\n- **Uptime**: {}",
+        format_duration(data.start_time.elapsed().as_secs() as i64)
+    );
+    for client in data.clients.clients() {
+        let lifecycle = client.lifecycle();
+        let icon = if client.is_connected() {
+            "🟢"
+        } else {
+            "🔴"
+        };
+        let shards = lifecycle
+            .ready_shards()
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>();
+        status_text.push_str(&format!(
+            "\n### `{}`\n- **State**: {icon} {}\n- **Ready shards**: {}\n- **Reconnects**: {}",
+            client.name(),
+            lifecycle.state(),
+            if shards.is_empty() {
+                "None".to_string()
+            } else {
+                shards.join(", ")
+            },
+            client.reconnects()
+        ));
+        if let Some((at, reason)) = client.last_disconnect() {
+            status_text.push_str(&format!(
+                "\n- **Last disconnect**: <t:{}:R> — {reason}",
+                at.timestamp()
+            ));
+        }
+    }
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Owner voice time import subcommand.
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::bot::command::prelude::*;
+use crate::service::voice_import::VoiceImportSource;
+use crate::service::voice_import::parse_voice_export;
+
+/// Largest export accepted, in bytes.
+const MAX_EXPORT_BYTES: u32 = 8 * 1024 * 1024;
+
+/// Milliseconds between the Unix epoch and the first Discord snowflake.
+const DISCORD_EPOCH_MS: u64 = 1_420_070_400_000;
+
+/// Stat bot an export comes from.
+#[derive(ChoiceParameter, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportSource {
+    Statbot,
+    #[name = "MEE6"]
+    Mee6,
+}
+
+impl From<ImportSource> for VoiceImportSource {
+    fn from(source: ImportSource) -> Self {
+        match source {
+            ImportSource::Statbot => Self::Statbot,
+            ImportSource::Mee6 => Self::Mee6,
+        }
+    }
+}
+
+/// Import voice time from another stat bot's CSV export
+///
+/// Adds each member's exported voice time to a server as an adjustment, so
+/// history from the previous bot counts towards all-time stats. The time is
+/// dated at the server's creation and stays out of ranged stats. Run with
+/// `preview` first: importing the same export twice adds the time twice.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn voice_import(
+    ctx: Context<'_>,
+    #[description = "CSV export with a user ID and a voice time column"] file: Attachment,
+    #[description = "Bot the export comes from"] source: ImportSource,
+    #[description = "Server ID to import into. Defaults to this server"] guild_id: Option<String>,
+    #[description = "Only show what would be imported"] preview: Option<bool>,
+) -> Result<(), Error> {
+    command(ctx, file, source, guild_id, preview.unwrap_or(false)).await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    file: Attachment,
+    source: ImportSource,
+    guild_id: Option<String>,
+    preview: bool,
+) -> Result<(), Error> {
+    let guild_id = match guild_id {
+        Some(value) => {
+            let value = value.trim();
+            value
+                .parse::<u64>()
+                .map_err(|_| BotError::InvalidCommandArgument {
+                    parameter: "guild_id".to_string(),
+                    reason: format!("`{value}` is not a valid Discord ID"),
+                })?
+        }
+        None => ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get(),
+    };
+    if file.size > MAX_EXPORT_BYTES {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "file".to_string(),
+            reason: format!(
+                "The export is {} bytes, over the {MAX_EXPORT_BYTES} byte limit",
+                file.size
+            ),
+        }
+        .into());
+    }
+
+    ctx.defer().await?;
+    let bytes = file.download().await?;
+    let csv = String::from_utf8(bytes).map_err(|_| BotError::InvalidCommandArgument {
+        parameter: "file".to_string(),
+        reason: "The export is not a UTF-8 CSV file".to_string(),
+    })?;
+    let source = VoiceImportSource::from(source);
+    let import = parse_voice_export(source, &csv)?;
+
+    let imported = if preview || import.members.is_empty() {
+        0
+    } else {
+        ctx.data()
+            .service
+            .voice_tracking
+            .import_voice_time(
+                guild_id,
+                &import.members,
+                format!("Imported from {}", source.name()),
+                ctx.author().id.get(),
+                guild_created_at(guild_id),
+            )
+            .await?
+    };
+
+    let mut status_text = format!(
+        "### {}\n- **Server**: `{}`\n- **Source**: {} · `{}`\n- **Members**: {}\n- **Voice time**: {}",
+        if preview {
+            "Voice Import Preview"
+        } else {
+            "Voice Time Imported"
+        },
+        guild_id,
+        source.name(),
+        file.filename,
+        import.members.len(),
+        format_duration(import.total_secs()),
+    );
+    if !preview {
+        status_text.push_str(&format!("\n- **Adjustments added**: {imported}"));
+    }
+    if import.skipped > 0 {
+        let lines: Vec<String> = import
+            .skipped_lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        status_text.push_str(&format!(
+            "\n-# Skipped {} unreadable line{}: {}{}",
+            import.skipped,
+            if import.skipped == 1 { "" } else { "s" },
+            lines.join(", "),
+            if import.skipped > lines.len() {
+                ", …"
+            } else {
+                ""
+            }
+        ));
+    }
+    if preview {
+        status_text.push_str("\n-# Nothing was imported. Run again without `preview` to import");
+    }
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
+
+/// When a guild was created, read from its ID.
+fn guild_created_at(guild_id: u64) -> DateTime<Utc> {
+    let millis = (guild_id >> 22) + DISCORD_EPOCH_MS;
+    DateTime::from_timestamp_millis(millis as i64).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guild_created_at_reads_the_snowflake_timestamp() {
+        // Discord's API documentation example snowflake
+        let created_at = guild_created_at(175_928_847_299_117_063);
+
+        assert_eq!(created_at.timestamp_millis(), 1_462_015_105_796);
+    }
+}
@@ -0,0 +1,119 @@
+//! Owner global voice leaderboard subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::voice::TimeRange;
+use crate::bot::command::voice::VoiceLeaderboardTimeRange;
+use crate::entity::GlobalVoiceLeaderboardEntry;
+use crate::service::traits::VoiceTracker;
+
+/// Number of members listed by default.
+const DEFAULT_LIMIT: u32 = 20;
+
+/// Show the voice leaderboard across all servers
+///
+/// Ranks members by voice time summed over every server that opted in with
+/// `/settings voice`. Servers that chose to be anonymous, and members who
+/// spend time in them, are listed without names.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn voice_leaderboard(
+    ctx: Context<'_>,
+    #[description = "Time period to rank. Defaults to \"This month\""] time_range: Option<
+        VoiceLeaderboardTimeRange,
+    >,
+    #[description = "Hide every member and server name, e.g. to share the result"]
+    anonymize: Option<bool>,
+    #[description = "Number of members to list (default: 20)"]
+    #[min = 1]
+    #[max = 50]
+    limit: Option<u32>,
+) -> Result<(), Error> {
+    command(
+        ctx,
+        time_range.unwrap_or_default(),
+        anonymize.unwrap_or(false),
+        limit.unwrap_or(DEFAULT_LIMIT).clamp(1, 50),
+    )
+    .await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    time_range: VoiceLeaderboardTimeRange,
+    anonymize: bool,
+    limit: u32,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let (since, until) = time_range.to_range();
+    let entries = ctx
+        .data()
+        .service
+        .voice_tracking
+        .get_global_leaderboard(&since, &until, limit)
+        .await?;
+
+    let mut status_text = format!(
+        "### Global Voice Leaderboard\n-# Time Range: **{}** — <t:{}:f> to <t:{}:R>\n",
+        time_range.name(),
+        since.timestamp(),
+        until.timestamp(),
+    );
+    if entries.is_empty() {
+        status_text.push_str(
+            "\nNo voice activity in this time range, or no server has opted in to the global leaderboard.",
+        );
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        status_text.push_str(&format!(
+            "\n**#{}** {} — **{}**\n-# {}",
+            i + 1,
+            member_name(&ctx, entry, anonymize),
+            format_duration(entry.total_duration),
+            guild_summary(&ctx, entry, anonymize),
+        ));
+    }
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
+
+/// Names a member from the cache of their top server, unless they must stay anonymous.
+fn member_name(ctx: &Context<'_>, entry: &GlobalVoiceLeaderboardEntry, anonymize: bool) -> String {
+    if anonymize || !entry.named {
+        return "Anonymous member".to_string();
+    }
+    ctx.cache()
+        .guild(GuildId::new(entry.top_guild_id))
+        .and_then(|guild| {
+            let member = guild.members.get(&UserId::new(entry.user_id))?;
+            Some(member.display_name().to_string())
+        })
+        .unwrap_or_else(|| format!("User {}", entry.user_id))
+}
+
+/// Describes where a member's time comes from, naming the top server when allowed.
+fn guild_summary(
+    ctx: &Context<'_>,
+    entry: &GlobalVoiceLeaderboardEntry,
+    anonymize: bool,
+) -> String {
+    let servers = format!(
+        "{} server{}",
+        entry.guild_count,
+        if entry.guild_count == 1 { "" } else { "s" }
+    );
+    if anonymize || !entry.named {
+        return servers;
+    }
+    let top = ctx
+        .cache()
+        .guild(GuildId::new(entry.top_guild_id))
+        .map(|guild| guild.name.to_string())
+        .unwrap_or_else(|| format!("Server {}", entry.top_guild_id));
+    format!("{servers}, mostly {top}")
+}
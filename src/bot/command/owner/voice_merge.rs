@@ -0,0 +1,63 @@
+//! Owner voice account merge subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::service::traits::VoiceTracker;
+
+/// Move voice data from an old account to a new one
+///
+/// Reassigns all voice sessions, adjustments, flags and leaderboard snapshots
+/// of a user in every server to another user, e.g. after they changed accounts.
+/// The merge is recorded for auditing and cannot be undone.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn voice_merge(
+    ctx: Context<'_>,
+    #[description = "User ID of the old account"] from_id: String,
+    #[description = "User ID of the new account"] to_id: String,
+) -> Result<(), Error> {
+    command(ctx, from_id, to_id).await
+}
+
+pub async fn command(ctx: Context<'_>, from_id: String, to_id: String) -> Result<(), Error> {
+    let from_user_id = parse_user_id("from_id", &from_id)?;
+    let to_user_id = parse_user_id("to_id", &to_id)?;
+
+    ctx.defer().await?;
+    let merge = ctx
+        .data()
+        .service
+        .voice_tracking
+        .merge_users(from_user_id, to_user_id, ctx.author().id.get())
+        .await?;
+
+    let status_text = format!(
+        "### Voice Data Merged\n- **From**: <@{}> `{}`\n- **To**: <@{}> `{}`\n- **Sessions**: {}\n- **Adjustments**: {}\n- **Flags**: {}\n- **Snapshots**: {}\n-# Recorded as merge #{}",
+        from_user_id,
+        from_user_id,
+        to_user_id,
+        to_user_id,
+        merge.sessions_moved,
+        merge.adjustments_moved,
+        merge.flags_moved,
+        merge.snapshots_moved,
+        merge.id
+    );
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
+
+fn parse_user_id(parameter: &str, value: &str) -> Result<u64, Error> {
+    let value = value.trim();
+    value.parse::<u64>().map_err(|_| {
+        BotError::InvalidCommandArgument {
+            parameter: parameter.to_string(),
+            reason: format!("`{value}` is not a valid Discord ID"),
+        }
+        .into()
+    })
+}
@@ -0,0 +1,67 @@
+//! Owner voice session rebuild subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::service::traits::VoiceTracker;
+
+/// Recompute a server's voice sessions from recorded voice events
+///
+/// Deletes the sessions recorded since the server's first voice event and
+/// derives them again from the event log under the current settings, e.g.
+/// after changing tracking rules or fixing a tracking bug. Requires
+/// `ENABLE_VOICE_EVENT_SOURCING`.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn voice_rebuild(
+    ctx: Context<'_>,
+    #[description = "Server ID to rebuild. Defaults to this server"] guild_id: Option<String>,
+) -> Result<(), Error> {
+    command(ctx, guild_id).await
+}
+
+pub async fn command(ctx: Context<'_>, guild_id: Option<String>) -> Result<(), Error> {
+    let guild_id = match guild_id {
+        Some(value) => {
+            let value = value.trim();
+            value
+                .parse::<u64>()
+                .map_err(|_| BotError::InvalidCommandArgument {
+                    parameter: "guild_id".to_string(),
+                    reason: format!("`{value}` is not a valid Discord ID"),
+                })?
+        }
+        None => ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get(),
+    };
+    if !ctx.data().service.voice_tracking.records_voice_events() {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "guild_id".to_string(),
+            reason: "Voice event sourcing is disabled. Set `ENABLE_VOICE_EVENT_SOURCING=true` to record voice events".to_string(),
+        }
+        .into());
+    }
+
+    ctx.defer().await?;
+    let replayed = ctx
+        .data()
+        .service
+        .voice_tracking
+        .rebuild_voice_sessions(guild_id)
+        .await?;
+
+    let status_text = if replayed == 0 {
+        format!(
+            "### Voice Sessions Rebuilt\nNo voice events are recorded for server `{guild_id}` yet."
+        )
+    } else {
+        format!(
+            "### Voice Sessions Rebuilt\nReplayed **{replayed}** voice event{} for server `{guild_id}`.",
+            if replayed == 1 { "" } else { "s" }
+        )
+    };
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
@@ -6,11 +6,19 @@ use crate::bot::command::prelude::*;
 use crate::entity::Json;
 use crate::entity::ServerSettings;
 use crate::entity::ServerSettingsEntity;
+use crate::service::Services;
+use crate::update::Staged;
 use crate::update::Update;
 use crate::update::settings_main::SettingsMainModel;
 use crate::update::settings_main::SettingsMainMsg;
 use crate::update::settings_main::SettingsMainUpdate;
 
+pub mod api;
+pub mod commands;
+pub mod dashboard;
+pub mod general;
+pub mod history;
+
 /// Model representing a configurable feature in the bot.
 ///
 /// Encapsulates feature identity, state access, and configuration logic
@@ -84,11 +92,78 @@ impl FeatureRegistry {
     }
 }
 
+/// Loads a guild's settings for a page that stages edits until Save.
+pub async fn open_staged_settings(
+    services: &Services,
+    guild_id: u64,
+) -> Result<Staged<ServerSettings>, Error> {
+    let settings = services.settings.get_server_settings(guild_id).await?;
+    Ok(Staged::new(settings))
+}
+
+/// Saves the edits staged on a settings page.
+///
+/// The edits are moved on top of the guild's latest settings first, so fields
+/// the page did not touch keep what other admins or background writers saved
+/// while the page was open. Voice changes go through voice tracking to keep
+/// its cache and channel weights in step.
+pub async fn save_staged_settings(
+    services: &Services,
+    guild_id: u64,
+    changed_by: u64,
+    settings: &mut Staged<ServerSettings>,
+) -> Result<(), Error> {
+    let voice_changed = settings.voice != settings.saved().voice;
+
+    let latest = services.settings.get_server_settings(guild_id).await?;
+    settings.rebase(latest)?;
+
+    let edited = settings.current().clone();
+    if voice_changed {
+        services
+            .voice_tracking
+            .update_server_settings_by(guild_id, edited, changed_by)
+            .await
+            .map_err(Error::from)?;
+    } else {
+        services
+            .settings
+            .update_server_settings_by(guild_id, edited, changed_by)
+            .await?;
+    }
+    settings.save();
+    Ok(())
+}
+
+/// Manage server settings
+///
+/// Base command for server settings. Use subcommands to:
+/// - Open the settings hub
+/// - Configure timezone, locale, prefix and dashboard access
+/// - Turn commands on or off
+/// - View the settings change history
+/// - Manage the web dashboard link
+/// - Manage the REST API token
+#[poise::command(
+    slash_command,
+    subcommands(
+        "open",
+        "general::general",
+        "commands::commands",
+        "history::history",
+        "dashboard::dashboard",
+        "api::api"
+    )
+)]
+pub async fn settings(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
 /// Opens main server settings
 ///
 /// Requires server administrator permissions.
 #[poise::command(slash_command)]
-pub async fn settings(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn open(ctx: Context<'_>) -> Result<(), Error> {
     Router::new(ctx).run(Navigation::SettingsMain).await?;
     Ok(())
 }
@@ -114,30 +189,14 @@ impl CommandHandler for SettingsMainHandler<'_> {
             settings: Json(settings),
         };
 
-        let model = SettingsMainModel::new(
-            settings.settings.0.feeds.enabled.unwrap_or(false),
-            settings.settings.0.voice.enabled.unwrap_or(false),
-            settings.settings.0.welcome.enabled.unwrap_or(false),
-        );
+        let model = SettingsMainModel::from_settings(&settings.settings.0);
         let view = SettingsMainView { settings, model };
 
         let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
 
+        // Unsaved changes are discarded when the view exits
         engine.run().await?;
 
-        // Save settings if modified
-        if engine.handler.model.is_modified {
-            engine.handler.sync_model_to_settings();
-            let guild_id = engine.handler.settings.guild_id;
-            let settings_data = engine.handler.settings.settings.0.clone();
-            ctx.data()
-                .service
-                .feed_subscription
-                .update_server_settings(*guild_id, settings_data)
-                .await?;
-            engine.handler.done_update_settings()?;
-        }
-
         Ok(())
     }
 }
@@ -167,17 +226,19 @@ impl SettingsMainView {
         Ok(())
     }
 
-    fn sync_model_to_settings(&mut self) {
-        self.settings.settings.0.feeds.enabled = Some(self.model.feeds_enabled);
-        self.settings.settings.0.voice.enabled = Some(self.model.voice_enabled);
-        self.settings.settings.0.welcome.enabled = Some(self.model.welcome_enabled);
+    fn sync_model_to_settings(&self, settings: &mut ServerSettings) {
+        settings.feeds.enabled = Some(self.model.feeds_enabled);
+        settings.voice.enabled = Some(self.model.voice_enabled);
+        settings.welcome.enabled = Some(self.model.welcome_enabled);
     }
 }
 
 impl ViewRender for SettingsMainView {
     type Action = SettingsMainAction;
     fn render(&self, registry: &mut ActionRegistry<SettingsMainAction>) -> ResponseKind<'_> {
-        let text_settings = CreateTextDisplay::new("-# **Settings**");
+        let is_dirty = self.model.is_modified;
+        let text_settings =
+            CreateTextDisplay::new(format!("-# **Settings**{}", unsaved_marker(is_dirty)));
         let mut components = vec![CreateContainerComponent::TextDisplay(text_settings)];
 
         // Navigation section
@@ -189,21 +250,31 @@ impl ViewRender for SettingsMainView {
 
         // Build navigation buttons for all features
         let navigation_buttons = CreateActionRow::Buttons(
-            FeatureRegistry::all()
-                .iter()
-                .map(|feature| {
-                    registry
-                        .register(match feature.label {
-                            "Feeds" => SettingsMainAction::FeedsFeature,
-                            "Voice" => SettingsMainAction::VoiceFeature,
-                            "Welcome" => SettingsMainAction::WelcomeFeature,
-                            _ => SettingsMainAction::About, // Should never happen
-                        })
-                        .as_button()
-                        .label(feature.label)
-                        .style(ButtonStyle::Secondary)
-                })
-                .collect(),
+            std::iter::once(
+                registry
+                    .register(SettingsMainAction::General)
+                    .as_button()
+                    .style(ButtonStyle::Secondary),
+            )
+            .chain(std::iter::once(
+                registry
+                    .register(SettingsMainAction::Commands)
+                    .as_button()
+                    .style(ButtonStyle::Secondary),
+            ))
+            .chain(FeatureRegistry::all().iter().map(|feature| {
+                registry
+                    .register(match feature.label {
+                        "Feeds" => SettingsMainAction::FeedsFeature,
+                        "Voice" => SettingsMainAction::VoiceFeature,
+                        "Welcome" => SettingsMainAction::WelcomeFeature,
+                        _ => SettingsMainAction::About, // Should never happen
+                    })
+                    .as_button()
+                    .label(feature.label)
+                    .style(ButtonStyle::Secondary)
+            }))
+            .collect(),
         );
         components.push(CreateContainerComponent::ActionRow(navigation_buttons));
 
@@ -259,6 +330,20 @@ impl ViewRender for SettingsMainView {
 
         let bottom_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
             vec![
+                registry
+                    .register(SettingsMainAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsMainAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsMainAction::History)
+                    .as_button()
+                    .style(ButtonStyle::Secondary),
                 registry
                     .register(SettingsMainAction::About)
                     .as_button()
@@ -273,6 +358,10 @@ impl ViewRender for SettingsMainView {
 
 action_enum! {
     SettingsMainAction {
+        #[label = "General"]
+        General,
+        #[label = "Commands"]
+        Commands,
         #[label = "Feeds"]
         FeedsFeature,
         #[label = "Voice"]
@@ -280,6 +369,12 @@ action_enum! {
         #[label = "Welcome"]
         WelcomeFeature,
         ToggleFeature,
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
+        #[label = "🕘 History"]
+        History,
         #[label = "🛈 About"]
         About,
     }
@@ -294,6 +389,14 @@ impl ViewHandler for SettingsMainView {
         let cor = ctx.coordinator.clone();
         let action = ctx.action();
         match action {
+            General => {
+                cor.navigate(Navigation::SettingsGeneral).await;
+                Ok(ViewCmd::Exit)
+            }
+            Commands => {
+                cor.navigate(Navigation::SettingsCommands).await;
+                Ok(ViewCmd::Exit)
+            }
             FeedsFeature => {
                 if let Some(feature) = FeatureRegistry::find_by_label("Feeds") {
                     cor.navigate(feature.navigate.clone()).await;
@@ -340,6 +443,30 @@ impl ViewHandler for SettingsMainView {
                 }
                 Ok(ViewCmd::Render)
             }
+            Save => {
+                if self.model.is_modified {
+                    let mut settings = Staged::new(self.settings().clone());
+                    self.sync_model_to_settings(&mut settings);
+                    save_staged_settings(
+                        &ctx.poise.data().service,
+                        *self.settings.guild_id,
+                        ctx.poise.author().id.get(),
+                        &mut settings,
+                    )
+                    .await?;
+                    *self.settings_mut() = settings.current().clone();
+                    self.done_update_settings()?;
+                }
+                Ok(ViewCmd::Render)
+            }
+            Revert => {
+                self.model = SettingsMainModel::from_settings(self.settings());
+                Ok(ViewCmd::Render)
+            }
+            History => {
+                cor.navigate(Navigation::SettingsHistory).await;
+                Ok(ViewCmd::Exit)
+            }
             About => {
                 cor.navigate(Navigation::SettingsAbout).await;
                 Ok(ViewCmd::Exit)
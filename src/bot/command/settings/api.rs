@@ -0,0 +1,182 @@
+//! REST API token subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::entity::ApiTokenEntity;
+use crate::service::api::ApiScope;
+use crate::service::traits::ApiTokenProvider;
+
+/// Manage the REST API token for this server
+///
+/// Generates or revokes the bearer token external tools use to manage this
+/// server's subscriptions and read its voice stats.
+/// Requires server administrator permissions.
+#[poise::command(slash_command, ephemeral)]
+pub async fn api(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::SettingsApi).await?;
+    Ok(())
+}
+
+handler! { pub struct SettingsApiHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for SettingsApiHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        is_author_guild_admin(ctx).await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let config = &ctx.data().config;
+        let service = ctx.data().service.api_tokens.clone();
+        let token = service.get_api_token(ApiScope::Guild(guild_id)).await?;
+
+        let view = SettingsApiView {
+            token,
+            generated: None,
+            service,
+            guild_id,
+            public_url: config.web.public_url.clone(),
+            api_enabled: config.features.web_api,
+            disabled: false,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct SettingsApiView {
+    pub token: Option<ApiTokenEntity>,
+    /// The token generated in this view, the only time it can be shown.
+    pub generated: Option<String>,
+    pub service: Arc<dyn ApiTokenProvider>,
+    pub guild_id: u64,
+    pub public_url: String,
+    pub api_enabled: bool,
+    pub disabled: bool,
+}
+
+impl SettingsApiView {
+    /// Builds the API base URL for this server.
+    fn api_url(&self) -> String {
+        format!("{}/api/v1/guilds/{}", self.public_url, self.guild_id)
+    }
+}
+
+action_enum! { SettingsApiAction {
+    #[label = "Generate Token"]
+    Generate,
+    #[label = "Revoke Token"]
+    Revoke,
+    #[label = "❮ Back"]
+    Back,
+} }
+
+#[async_trait::async_trait]
+impl ViewHandler for SettingsApiView {
+    type Action = SettingsApiAction;
+    async fn handle(&mut self, ctx: ViewContext<'_, SettingsApiAction>) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            SettingsApiAction::Generate => {
+                let (entity, token) = self
+                    .service
+                    .generate_api_token(ApiScope::Guild(self.guild_id), ctx.poise.author().id.get())
+                    .await?;
+                self.token = Some(entity);
+                self.generated = Some(token);
+                Ok(ViewCmd::Render)
+            }
+            SettingsApiAction::Revoke => {
+                self.service
+                    .revoke_api_token(ApiScope::Guild(self.guild_id))
+                    .await?;
+                self.token = None;
+                self.generated = None;
+                Ok(ViewCmd::Render)
+            }
+            SettingsApiAction::Back => {
+                ctx.coordinator.navigate(Navigation::SettingsMain).await;
+                Ok(ViewCmd::Exit)
+            }
+        }
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.disabled = true;
+        Ok(ViewCmd::RenderOnce)
+    }
+}
+
+impl ViewRender for SettingsApiView {
+    type Action = SettingsApiAction;
+    fn render(&self, registry: &mut ActionRegistry<SettingsApiAction>) -> ResponseKind<'_> {
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("-# **Settings > API**\n## REST API"),
+        )];
+
+        // Only the token's hash is stored, so it is shown once, right after generating it
+        let shown_token = match &self.generated {
+            Some(generated) => format!("||`{generated}`|| (copy it now, it won't be shown again)"),
+            None => "hidden, generate a new one if it was lost".to_string(),
+        };
+        let status = match &self.token {
+            Some(token) => format!(
+                "Base URL: {}\nToken: {}\n-# Generated by {} <t:{}:R>. Send it as `Authorization: Bearer <token>`. Anyone with this token can change this server's subscriptions; generate a new one to invalidate it.",
+                self.api_url(),
+                shown_token,
+                token
+                    .created_by
+                    .map(|user_id| format!("<@{}>", *user_id))
+                    .unwrap_or_else(|| "an admin".to_string()),
+                token.created_at.timestamp()
+            ),
+            None => "> 🛈  No API token has been generated for this server yet.".to_string(),
+        };
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(status),
+        ));
+
+        if !self.api_enabled {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(
+                    "-# ⚠️ The REST API is disabled on this bot instance, so tokens won't work until the bot owner enables it.",
+                ),
+            ));
+        }
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+
+        if !self.disabled {
+            components.push(CreateComponent::ActionRow(CreateActionRow::Buttons(
+                vec![
+                    registry
+                        .register(SettingsApiAction::Generate)
+                        .as_button()
+                        .style(ButtonStyle::Primary),
+                    registry
+                        .register(SettingsApiAction::Revoke)
+                        .as_button()
+                        .style(ButtonStyle::Danger)
+                        .disabled(self.token.is_none()),
+                    registry
+                        .register(SettingsApiAction::Back)
+                        .as_button()
+                        .style(ButtonStyle::Secondary),
+                ]
+                .into(),
+            )));
+        }
+
+        components.into()
+    }
+
+    // The token grants write access, so only the invoking admin sees it
+    fn create_reply(&self, registry: &mut ActionRegistry<SettingsApiAction>) -> CreateReply<'_> {
+        CreateReply::from(self.render(registry)).ephemeral(true)
+    }
+}
@@ -0,0 +1,208 @@
+//! Command settings subcommand.
+
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::settings::open_staged_settings;
+use crate::bot::command::settings::save_staged_settings;
+use crate::bot::disabled_commands::display_name;
+use crate::bot::disabled_commands::is_toggleable;
+use crate::entity::ServerSettings;
+use crate::update::Staged;
+
+/// Most options a select menu holds.
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// Turn commands on or off for this server
+///
+/// Pick the commands members can't use in this server, e.g. modules you don't use.
+/// Turning off a command turns off its subcommands. `/settings` can't be turned off.
+/// Requires server administrator permissions.
+#[poise::command(slash_command)]
+pub async fn commands(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::SettingsCommands).await?;
+    Ok(())
+}
+
+handler! { pub struct SettingsCommandsHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for SettingsCommandsHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        is_author_guild_admin(ctx).await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let commands = ctx
+            .framework()
+            .options()
+            .commands
+            .iter()
+            .filter(|command| is_toggleable(command))
+            .map(|command| (command.name.to_string(), display_name(command).to_string()))
+            .take(MAX_SELECT_OPTIONS)
+            .collect();
+
+        let view = SettingsCommandsView {
+            settings: open_staged_settings(&ctx.data().service, guild_id).await?,
+            guild_id,
+            commands,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        // Unsaved changes are discarded when the view exits
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+action_enum! {
+    SettingsCommandsAction {
+        Disabled,
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
+        #[label = "❮ Back"]
+        Back,
+        #[label = "🛈 About"]
+        About,
+    }
+}
+
+pub struct SettingsCommandsView {
+    pub settings: Staged<ServerSettings>,
+    pub guild_id: u64,
+    /// Name and display name of each command the server can turn off.
+    pub commands: Vec<(String, String)>,
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for SettingsCommandsView {
+    type Action = SettingsCommandsAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, SettingsCommandsAction>,
+    ) -> Result<ViewCmd, Error> {
+        use SettingsCommandsAction::*;
+
+        match ctx.action() {
+            Disabled => {
+                let disabled = ctx.string_select_values().unwrap_or_default();
+                self.settings.general.disabled_commands =
+                    (!disabled.is_empty()).then_some(disabled);
+            }
+            Save => {
+                let data = ctx.poise.data();
+                save_staged_settings(
+                    &data.service,
+                    self.guild_id,
+                    ctx.poise.author().id.get(),
+                    &mut self.settings,
+                )
+                .await?;
+            }
+            Revert => {
+                self.settings.revert();
+            }
+            Back => {
+                ctx.coordinator.navigate(Navigation::SettingsMain).await;
+                return Ok(ViewCmd::Exit);
+            }
+            About => {
+                ctx.coordinator.navigate(Navigation::SettingsAbout).await;
+                return Ok(ViewCmd::Exit);
+            }
+        }
+
+        Ok(ViewCmd::Render)
+    }
+}
+
+impl ViewRender for SettingsCommandsView {
+    type Action = SettingsCommandsAction;
+    fn render(&self, registry: &mut ActionRegistry<SettingsCommandsAction>) -> ResponseKind<'_> {
+        let general = &self.settings.general;
+        let is_dirty = self.settings.is_dirty();
+
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!(
+                "-# **Settings > Commands**{}\n## Command Settings",
+                unsaved_marker(is_dirty)
+            )),
+        )];
+
+        let status = self
+            .commands
+            .iter()
+            .map(|(name, label)| {
+                let emoji = if general.is_command_enabled(name) {
+                    "✅"
+                } else {
+                    "⬜"
+                };
+                format!("{emoji} `{label}`")
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!(
+                "### Enabled Commands\n\n> 🛈  Members can't use commands turned off here, including their subcommands. `/settings` always stays on.\n\n{status}"
+            )),
+        ));
+
+        let options: Vec<_> = self
+            .commands
+            .iter()
+            .map(|(name, label)| {
+                CreateSelectMenuOption::new(label.clone(), name.clone())
+                    .default_selection(!general.is_command_enabled(name))
+            })
+            .collect();
+        if !options.is_empty() {
+            let max_values = options.len() as u8;
+            sections.push(CreateContainerComponent::ActionRow(
+                CreateActionRow::SelectMenu(
+                    registry
+                        .register(SettingsCommandsAction::Disabled)
+                        .as_select(CreateSelectMenuKind::String {
+                            options: options.into(),
+                        })
+                        .placeholder("Select commands to turn off")
+                        .min_values(0)
+                        .max_values(max_values),
+                ),
+            ));
+        }
+
+        let container = CreateComponent::Container(CreateContainer::new(sections));
+
+        let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
+            vec![
+                registry
+                    .register(SettingsCommandsAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsCommandsAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsCommandsAction::Back)
+                    .as_button()
+                    .style(ButtonStyle::Secondary),
+                registry
+                    .register(SettingsCommandsAction::About)
+                    .as_button()
+                    .style(ButtonStyle::Secondary),
+            ]
+            .into(),
+        ));
+
+        vec![container, nav_buttons].into()
+    }
+}
@@ -0,0 +1,191 @@
+//! Web dashboard link subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::entity::DashboardTokenEntity;
+use crate::service::traits::DashboardProvider;
+
+/// Manage the web dashboard link for this server
+///
+/// Generates or revokes the private link to this server's read-only dashboard.
+/// Anyone with the link can view the feed list, voice leaderboard and activity charts.
+/// Requires server administrator permissions.
+#[poise::command(slash_command, ephemeral)]
+pub async fn dashboard(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::SettingsDashboard).await?;
+    Ok(())
+}
+
+handler! { pub struct SettingsDashboardHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for SettingsDashboardHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        is_author_guild_admin(ctx).await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let config = &ctx.data().config;
+        let service = ctx.data().service.dashboard.clone();
+        let token = service.get_token(guild_id).await?;
+        let settings = ctx
+            .data()
+            .service
+            .settings
+            .get_server_settings(guild_id)
+            .await?;
+
+        let view = SettingsDashboardView {
+            token,
+            service,
+            guild_id,
+            public_url: config.web.public_url.clone(),
+            web_enabled: config.features.web_dashboard,
+            guild_enabled: settings.general.is_dashboard_enabled(),
+            disabled: false,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct SettingsDashboardView {
+    pub token: Option<DashboardTokenEntity>,
+    pub service: Arc<dyn DashboardProvider>,
+    pub guild_id: u64,
+    pub public_url: String,
+    pub web_enabled: bool,
+    /// Whether the dashboard is allowed in the server's general settings.
+    pub guild_enabled: bool,
+    pub disabled: bool,
+}
+
+impl SettingsDashboardView {
+    /// Builds the dashboard URL for a token.
+    fn dashboard_url(&self, token: &DashboardTokenEntity) -> String {
+        format!("{}/g/{}", self.public_url, token.token)
+    }
+}
+
+action_enum! { SettingsDashboardAction {
+    #[label = "Generate Link"]
+    Generate,
+    #[label = "Revoke Link"]
+    Revoke,
+    #[label = "❮ Back"]
+    Back,
+} }
+
+#[async_trait::async_trait]
+impl ViewHandler for SettingsDashboardView {
+    type Action = SettingsDashboardAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, SettingsDashboardAction>,
+    ) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            SettingsDashboardAction::Generate => {
+                let token = self
+                    .service
+                    .generate_token(self.guild_id, ctx.poise.author().id.get())
+                    .await?;
+                self.token = Some(token);
+                Ok(ViewCmd::Render)
+            }
+            SettingsDashboardAction::Revoke => {
+                self.service.revoke_token(self.guild_id).await?;
+                self.token = None;
+                Ok(ViewCmd::Render)
+            }
+            SettingsDashboardAction::Back => {
+                ctx.coordinator.navigate(Navigation::SettingsMain).await;
+                Ok(ViewCmd::Exit)
+            }
+        }
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.disabled = true;
+        Ok(ViewCmd::RenderOnce)
+    }
+}
+
+impl ViewRender for SettingsDashboardView {
+    type Action = SettingsDashboardAction;
+    fn render(&self, registry: &mut ActionRegistry<SettingsDashboardAction>) -> ResponseKind<'_> {
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("-# **Settings > Dashboard**\n## Web Dashboard"),
+        )];
+
+        let status = match &self.token {
+            Some(token) => format!(
+                "Your server's dashboard is available at:\n{}\n-# Generated by {} <t:{}:R>. Anyone with this link can view it; generate a new one to invalidate it.",
+                self.dashboard_url(token),
+                token
+                    .created_by
+                    .map(|user_id| format!("<@{}>", *user_id))
+                    .unwrap_or_else(|| "an admin".to_string()),
+                token.created_at.timestamp()
+            ),
+            None => "> 🛈  No dashboard link has been generated for this server yet.".to_string(),
+        };
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(status),
+        ));
+
+        if !self.web_enabled {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(
+                    "-# ⚠️ The web dashboard is disabled on this bot instance, so links won't open until the bot owner enables it.",
+                ),
+            ));
+        }
+
+        if !self.guild_enabled {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(
+                    "-# ⚠️ The dashboard is blocked in `/settings general`, so links won't open until it is allowed again.",
+                ),
+            ));
+        }
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+
+        if !self.disabled {
+            components.push(CreateComponent::ActionRow(CreateActionRow::Buttons(
+                vec![
+                    registry
+                        .register(SettingsDashboardAction::Generate)
+                        .as_button()
+                        .style(ButtonStyle::Primary),
+                    registry
+                        .register(SettingsDashboardAction::Revoke)
+                        .as_button()
+                        .style(ButtonStyle::Danger)
+                        .disabled(self.token.is_none()),
+                    registry
+                        .register(SettingsDashboardAction::Back)
+                        .as_button()
+                        .style(ButtonStyle::Secondary),
+                ]
+                .into(),
+            )));
+        }
+
+        components.into()
+    }
+
+    // The link grants access to the dashboard, so only the invoking admin sees it
+    fn create_reply(
+        &self,
+        registry: &mut ActionRegistry<SettingsDashboardAction>,
+    ) -> CreateReply<'_> {
+        CreateReply::from(self.render(registry)).ephemeral(true)
+    }
+}
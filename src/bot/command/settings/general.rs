@@ -0,0 +1,315 @@
+//! General settings subcommand.
+
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::settings::open_staged_settings;
+use crate::bot::command::settings::save_staged_settings;
+use crate::entity::GeneralSettings;
+use crate::entity::MAX_PREFIX_LEN;
+use crate::entity::ServerSettings;
+use crate::update::Staged;
+
+/// Locales offered in the settings view.
+const LOCALE_OPTIONS: [(&str, &str); 8] = [
+    ("en-US", "English (US)"),
+    ("en-GB", "English (UK)"),
+    ("de-DE", "Deutsch"),
+    ("fr-FR", "Français"),
+    ("es-ES", "Español"),
+    ("pt-BR", "Português (Brasil)"),
+    ("ja-JP", "日本語"),
+    ("id-ID", "Bahasa Indonesia"),
+];
+
+/// Configure general settings for this server
+///
+/// Set the server's timezone, locale and text command prefix, and choose
+/// whether the web dashboard link can be opened.
+/// Requires server administrator permissions.
+#[poise::command(slash_command)]
+pub async fn general(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::SettingsGeneral).await?;
+    Ok(())
+}
+
+#[derive(Debug, Modal, Clone, PartialEq, Eq)]
+#[name = "Set Timezone"]
+pub struct TimezoneModal {
+    #[name = "Timezone (IANA name)"]
+    #[placeholder = "Europe/Berlin"]
+    #[min_length = 1]
+    #[max_length = 64]
+    timezone: String,
+}
+
+#[derive(Debug, Modal, Clone, PartialEq, Eq)]
+#[name = "Set Command Prefix"]
+pub struct PrefixModal {
+    #[name = "Prefix"]
+    #[placeholder = "!"]
+    #[min_length = 1]
+    #[max_length = 5]
+    prefix: String,
+}
+
+handler! { pub struct SettingsGeneralHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for SettingsGeneralHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        is_author_guild_admin(ctx).await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let view = SettingsGeneralView {
+            settings: open_staged_settings(&ctx.data().service, guild_id).await?,
+            guild_id,
+            error: None,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        // Unsaved changes are discarded when the view exits
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+action_enum! {
+    SettingsGeneralAction {
+        #[label = "Set Timezone"]
+        SetTimezone(Option<TimezoneModal>),
+        #[label = "Set Prefix"]
+        SetPrefix(Option<PrefixModal>),
+        Locale,
+        ToggleDashboard,
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
+        #[label = "❮ Back"]
+        Back,
+        #[label = "🛈 About"]
+        About,
+    }
+}
+
+pub struct SettingsGeneralView {
+    pub settings: Staged<ServerSettings>,
+    pub guild_id: u64,
+    /// Why the last entered value was rejected, shown until the next action.
+    pub error: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for SettingsGeneralView {
+    type Action = SettingsGeneralAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, SettingsGeneralAction>,
+    ) -> Result<ViewCmd, Error> {
+        use SettingsGeneralAction::*;
+
+        self.error = None;
+        match ctx.action() {
+            SetTimezone(None) => {
+                ctx.spawn_modal_component(|m| SetTimezone(Some(m))).await;
+                return Ok(ViewCmd::AlreadyResponded);
+            }
+            SetPrefix(None) => {
+                ctx.spawn_modal_component(|m| SetPrefix(Some(m))).await;
+                return Ok(ViewCmd::AlreadyResponded);
+            }
+            SetTimezone(Some(modal)) => match GeneralSettings::parse_timezone(&modal.timezone) {
+                Some(tz) => self.settings.general.timezone = Some(tz.name().to_string()),
+                None => {
+                    self.error = Some(format!(
+                        "`{}` is not a known timezone. Use an IANA name such as `Europe/Berlin`.",
+                        modal.timezone.trim()
+                    ))
+                }
+            },
+            SetPrefix(Some(modal)) => {
+                let prefix = modal.prefix.trim();
+                if GeneralSettings::is_valid_prefix(prefix) {
+                    self.settings.general.prefix = Some(prefix.to_string());
+                } else {
+                    self.error = Some(format!(
+                        "The prefix must be 1 to {MAX_PREFIX_LEN} characters without spaces."
+                    ));
+                }
+            }
+            Locale => {
+                if let Some(locale) = ctx.string_select_values().and_then(|v| v.first().cloned()) {
+                    self.settings.general.locale = Some(locale);
+                }
+            }
+            ToggleDashboard => {
+                let current = self.settings.general.is_dashboard_enabled();
+                self.settings.general.dashboard_enabled = Some(!current);
+            }
+            Save => {
+                let data = ctx.poise.data();
+                save_staged_settings(
+                    &data.service,
+                    self.guild_id,
+                    ctx.poise.author().id.get(),
+                    &mut self.settings,
+                )
+                .await?;
+                data.prefixes
+                    .set(self.guild_id, self.settings.general.prefix());
+            }
+            Revert => {
+                self.settings.revert();
+            }
+            Back => {
+                ctx.coordinator.navigate(Navigation::SettingsMain).await;
+                return Ok(ViewCmd::Exit);
+            }
+            About => {
+                ctx.coordinator.navigate(Navigation::SettingsAbout).await;
+                return Ok(ViewCmd::Exit);
+            }
+        }
+
+        Ok(ViewCmd::Render)
+    }
+}
+
+impl ViewRender for SettingsGeneralView {
+    type Action = SettingsGeneralAction;
+    fn render(&self, registry: &mut ActionRegistry<SettingsGeneralAction>) -> ResponseKind<'_> {
+        let general = &self.settings.general;
+        let is_dirty = self.settings.is_dirty();
+
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!(
+                "-# **Settings > General**{}\n## General Settings",
+                unsaved_marker(is_dirty)
+            )),
+        )];
+
+        if let Some(error) = &self.error {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(format!("-# ⚠️ {error}")),
+            ));
+        }
+
+        let timezone_text = format!(
+            "### Timezone\n\n> 🛈  Daily voice stats start at midnight in **{}**.",
+            general.timezone().name()
+        );
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(timezone_text),
+        ));
+
+        let locale_text = "### Locale\n\n> 🛈  Language and region used for dates and numbers.";
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(locale_text),
+        ));
+        let locale = general.locale();
+        let locale_options: Vec<_> = LOCALE_OPTIONS
+            .iter()
+            .map(|(tag, name)| {
+                CreateSelectMenuOption::new(*name, *tag).default_selection(*tag == locale)
+            })
+            .collect();
+        sections.push(CreateContainerComponent::ActionRow(
+            CreateActionRow::SelectMenu(
+                registry
+                    .register(SettingsGeneralAction::Locale)
+                    .as_select(CreateSelectMenuKind::String {
+                        options: locale_options.into(),
+                    })
+                    .placeholder("Select locale"),
+            ),
+        ));
+
+        let prefix_text = format!(
+            "### Command Prefix\n\n> 🛈  Text commands start with `{}`.",
+            general.prefix()
+        );
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(prefix_text),
+        ));
+        sections.push(CreateContainerComponent::ActionRow(
+            CreateActionRow::Buttons(
+                vec![
+                    registry
+                        .register(SettingsGeneralAction::SetTimezone(None))
+                        .as_button()
+                        .style(ButtonStyle::Secondary),
+                    registry
+                        .register(SettingsGeneralAction::SetPrefix(None))
+                        .as_button()
+                        .style(ButtonStyle::Secondary),
+                ]
+                .into(),
+            ),
+        ));
+
+        let dashboard_enabled = general.is_dashboard_enabled();
+        let dashboard_text = format!(
+            "### Web Dashboard\n\n> 🛈  The dashboard link is **{}**. Manage the link with `/settings dashboard`.",
+            if dashboard_enabled {
+                "accessible"
+            } else {
+                "blocked"
+            }
+        );
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(dashboard_text),
+        ));
+        sections.push(CreateContainerComponent::ActionRow(
+            CreateActionRow::Buttons(
+                vec![
+                    registry
+                        .register(SettingsGeneralAction::ToggleDashboard)
+                        .as_button()
+                        .label(if dashboard_enabled {
+                            "Block Dashboard"
+                        } else {
+                            "Allow Dashboard"
+                        })
+                        .style(if dashboard_enabled {
+                            ButtonStyle::Danger
+                        } else {
+                            ButtonStyle::Success
+                        }),
+                ]
+                .into(),
+            ),
+        ));
+
+        let container = CreateComponent::Container(CreateContainer::new(sections));
+
+        let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
+            vec![
+                registry
+                    .register(SettingsGeneralAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsGeneralAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsGeneralAction::Back)
+                    .as_button()
+                    .style(ButtonStyle::Secondary),
+                registry
+                    .register(SettingsGeneralAction::About)
+                    .as_button()
+                    .style(ButtonStyle::Secondary),
+            ]
+            .into(),
+        ));
+
+        vec![container, nav_buttons].into()
+    }
+}
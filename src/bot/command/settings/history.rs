@@ -0,0 +1,172 @@
+//! Settings change history subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::entity::SettingsAuditEntity;
+use crate::service::traits::SettingsProvider;
+
+const CHANGES_PER_PAGE: u32 = 10;
+
+/// Show the settings change history for this server
+///
+/// Lists who changed which setting, newest first.
+/// Requires server administrator permissions.
+#[poise::command(slash_command)]
+pub async fn history(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::SettingsHistory).await?;
+    Ok(())
+}
+
+handler! { pub struct SettingsHistoryHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for SettingsHistoryHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        is_author_guild_admin(ctx).await?;
+        ctx.defer().await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let service = ctx.data().service.settings.clone();
+        let total = service.count_settings_history(guild_id).await?;
+        let changes = service
+            .get_settings_history(guild_id, 1, CHANGES_PER_PAGE)
+            .await?;
+
+        let view = SettingsHistoryView {
+            changes,
+            pagination: PaginationView::new(total, CHANGES_PER_PAGE),
+            service,
+            guild_id,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct SettingsHistoryView {
+    pub changes: Vec<SettingsAuditEntity>,
+    pub pagination: PaginationView,
+    pub service: Arc<dyn SettingsProvider>,
+    pub guild_id: u64,
+}
+
+impl SettingsHistoryView {
+    /// Formats a single change as `key: old → new` with its author and time.
+    fn format_change(change: &SettingsAuditEntity) -> String {
+        let value = |v: &Option<String>| match v {
+            Some(v) => format!("`{v}`"),
+            None => "*unset*".to_string(),
+        };
+        let author = match change.changed_by {
+            Some(user_id) => format!("<@{}>", *user_id),
+            None => "the bot".to_string(),
+        };
+        format!(
+            "**{}**: {} → {}\n-# by {} <t:{}:R>",
+            change.key,
+            value(&change.old_value),
+            value(&change.new_value),
+            author,
+            change.changed_at.timestamp()
+        )
+    }
+
+    async fn update_changes(&mut self) -> Result<(), Error> {
+        self.changes = self
+            .service
+            .get_settings_history(
+                self.guild_id,
+                self.pagination.current_page(),
+                CHANGES_PER_PAGE,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+action_extends! { SettingsHistoryAction extends PaginationAction {
+    #[label = "❮ Back"]
+    Back,
+}}
+
+#[async_trait::async_trait]
+impl ViewHandler for SettingsHistoryView {
+    type Action = SettingsHistoryAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, SettingsHistoryAction>,
+    ) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            SettingsHistoryAction::Base(inner) => {
+                match inner {
+                    PaginationAction::First => self.pagination.state.first_page(),
+                    PaginationAction::Prev => self.pagination.state.prev_page(),
+                    PaginationAction::Next => self.pagination.state.next_page(),
+                    PaginationAction::Last => self.pagination.state.last_page(),
+                    PaginationAction::Page => return Ok(ViewCmd::Continue),
+                }
+                self.update_changes().await?;
+                Ok(ViewCmd::Render)
+            }
+            SettingsHistoryAction::Back => {
+                ctx.coordinator.navigate(Navigation::SettingsMain).await;
+                Ok(ViewCmd::Exit)
+            }
+        }
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.pagination.disabled = true;
+        Ok(ViewCmd::RenderOnce)
+    }
+}
+
+impl ViewRender for SettingsHistoryView {
+    type Action = SettingsHistoryAction;
+    fn render(&self, registry: &mut ActionRegistry<SettingsHistoryAction>) -> ResponseKind<'_> {
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("-# **Settings > History**\n## Settings History"),
+        )];
+
+        if self.changes.is_empty() {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new("> 🛈  No settings changes have been recorded yet."),
+            ));
+        } else {
+            let text = self
+                .changes
+                .iter()
+                .map(Self::format_change)
+                .collect::<Vec<_>>()
+                .join("\n");
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(text),
+            ));
+        }
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+        self.pagination
+            .attach_if_multipage(registry, &mut components, SettingsHistoryAction::Base);
+
+        if !self.pagination.disabled {
+            components.push(CreateComponent::ActionRow(CreateActionRow::Buttons(
+                vec![
+                    registry
+                        .register(SettingsHistoryAction::Back)
+                        .as_button()
+                        .style(ButtonStyle::Secondary),
+                ]
+                .into(),
+            )));
+        }
+
+        components.into()
+    }
+}
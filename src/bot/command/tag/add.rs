@@ -0,0 +1,56 @@
+//! Tag add subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::tag::TAG_VARIABLES_HELP;
+use crate::bot::command::tag::send_status;
+use crate::bot::command::tag::unescape_newlines;
+
+/// Create a tag for this server
+///
+/// Content supports markdown, `\n` for line breaks and `{{ variable }}` placeholders.
+/// Requires server administrator permissions.
+#[poise::command(slash_command, ephemeral)]
+pub async fn add(
+    ctx: Context<'_>,
+    #[description = "Name of the tag, one word"]
+    #[max_length = 32]
+    name: String,
+    #[description = "Response text. Use \\n for line breaks and {{ user }} style variables"]
+    #[max_length = 2000]
+    content: String,
+    #[description = "Send the response inside an embed"] embed: Option<bool>,
+) -> Result<(), Error> {
+    command(ctx, name, content, embed.unwrap_or(false)).await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    name: String,
+    content: String,
+    embed: bool,
+) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    let tag = ctx
+        .data()
+        .service
+        .tags
+        .create_tag(
+            guild_id,
+            &name,
+            &unescape_newlines(&content),
+            embed,
+            ctx.author().id.get(),
+        )
+        .await?;
+
+    send_status(
+        ctx,
+        format!(
+            "### Tag Created\nPost it with `/tag show {}`.\n### Variables\n{TAG_VARIABLES_HELP}",
+            tag.name
+        ),
+    )
+    .await
+}
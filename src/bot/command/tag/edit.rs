@@ -0,0 +1,56 @@
+//! Tag edit subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::tag::autocomplete_tags;
+use crate::bot::command::tag::send_status;
+use crate::bot::command::tag::unescape_newlines;
+
+/// Edit a tag of this server
+///
+/// Leave an option empty to keep its current value.
+/// Requires server administrator permissions.
+#[poise::command(slash_command, ephemeral)]
+pub async fn edit(
+    ctx: Context<'_>,
+    #[description = "Name of the tag"]
+    #[autocomplete = "autocomplete_tags"]
+    name: String,
+    #[description = "New response text. Use \\n for line breaks"]
+    #[max_length = 2000]
+    content: Option<String>,
+    #[description = "Send the response inside an embed"] embed: Option<bool>,
+) -> Result<(), Error> {
+    command(ctx, name, content, embed).await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    name: String,
+    content: Option<String>,
+    embed: Option<bool>,
+) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    if content.is_none() && embed.is_none() {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "content".to_string(),
+            reason: "provide new content or an embed setting".to_string(),
+        }
+        .into());
+    }
+
+    let content = content.as_deref().map(unescape_newlines);
+    let tag = ctx
+        .data()
+        .service
+        .tags
+        .edit_tag(guild_id, &name, content.as_deref(), embed)
+        .await?;
+
+    send_status(
+        ctx,
+        format!("### Tag Updated\n`{}` has been updated.", tag.name),
+    )
+    .await
+}
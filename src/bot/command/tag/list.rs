@@ -0,0 +1,112 @@
+//! Tag list subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::entity::TagEntity;
+use crate::service::traits::TagProvider;
+
+const TAGS_PER_PAGE: u32 = 20;
+
+/// List the tags of this server
+///
+/// Shows every tag with how often it was posted, most used first.
+#[poise::command(slash_command)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::TagList).await?;
+    Ok(())
+}
+
+handler! { pub struct TagListHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for TagListHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+        ctx.defer().await?;
+
+        let service = ctx.data().service.tags.clone();
+        let total = service.count_tags(guild_id).await?;
+        let tags = service.list_tags(guild_id, 1, TAGS_PER_PAGE).await?;
+
+        let view = TagListView {
+            tags,
+            pagination: PaginationView::new(total, TAGS_PER_PAGE),
+            service,
+            guild_id,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct TagListView {
+    pub tags: Vec<TagEntity>,
+    pub pagination: PaginationView,
+    pub service: Arc<dyn TagProvider>,
+    pub guild_id: u64,
+}
+
+impl TagListView {
+    async fn update_tags(&mut self) -> Result<(), Error> {
+        self.tags = self
+            .service
+            .list_tags(self.guild_id, self.pagination.current_page(), TAGS_PER_PAGE)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for TagListView {
+    type Action = PaginationAction;
+    async fn handle(&mut self, ctx: ViewContext<'_, PaginationAction>) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            PaginationAction::First => self.pagination.state.first_page(),
+            PaginationAction::Prev => self.pagination.state.prev_page(),
+            PaginationAction::Next => self.pagination.state.next_page(),
+            PaginationAction::Last => self.pagination.state.last_page(),
+            PaginationAction::Page => return Ok(ViewCmd::Continue),
+        }
+        self.update_tags().await?;
+        Ok(ViewCmd::Render)
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.pagination.on_timeout().await
+    }
+}
+
+impl ViewRender for TagListView {
+    type Action = PaginationAction;
+    fn render(&self, registry: &mut ActionRegistry<PaginationAction>) -> ResponseKind<'_> {
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("## Tags"),
+        )];
+
+        let text = if self.tags.is_empty() {
+            "> 🛈  This server has no tags yet. Admins can create one with `/tag add`.".to_string()
+        } else {
+            self.tags
+                .iter()
+                .map(|tag| format!("- `{}` — {} uses", tag.name, tag.uses))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        sections.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(text),
+        ));
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+        self.pagination
+            .attach_if_multipage(registry, &mut components, std::convert::identity);
+
+        components.into()
+    }
+}
@@ -0,0 +1,138 @@
+//! Tag commands: admin-defined text responses.
+
+use minijinja::Environment;
+use serde::Serialize;
+
+use crate::bot::command::prelude::*;
+use crate::entity::TagEntity;
+
+pub mod add;
+pub mod edit;
+pub mod list;
+pub mod remove;
+pub mod show;
+
+/// Custom text responses for this server
+///
+/// Admins define named responses with `/tag add`; anyone can post one with `/tag show`.
+#[poise::command(
+    slash_command,
+    subcommands("show::show", "add::add", "edit::edit", "remove::remove", "list::list")
+)]
+pub async fn tag(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Help text listing the placeholders available in tag content.
+pub const TAG_VARIABLES_HELP: &str = "> `{{ user }}` - Mention of the user posting the tag\n> `{{ username }}` - Their display name\n> `{{ server_name }}` - Server name\n> `{{ channel }}` - Mention of the current channel\n> `{{ uses }}` - How many times the tag was posted\n> `{{ args }}` - Text passed to `/tag show`";
+
+/// Values substituted into `{{ variable }}` placeholders of a tag's content.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TagVariables {
+    pub user: String,
+    pub username: String,
+    pub server_name: String,
+    pub channel: String,
+    pub uses: i32,
+    pub args: String,
+}
+
+impl TagVariables {
+    /// Collects the variables for a tag posted in the given context.
+    pub fn from_context(ctx: Context<'_>, tag: &TagEntity, args: Option<String>) -> Self {
+        Self {
+            user: format!("<@{}>", ctx.author().id),
+            username: ctx.author().display_name().to_string(),
+            server_name: ctx
+                .guild()
+                .map(|guild| guild.name.to_string())
+                .unwrap_or_default(),
+            channel: format!("<#{}>", ctx.channel_id()),
+            uses: tag.uses,
+            args: args.unwrap_or_default(),
+        }
+    }
+}
+
+/// Renders tag content with its placeholders filled in.
+///
+/// Content that is not a valid template is sent as written.
+pub fn render_tag(content: &str, vars: &TagVariables) -> String {
+    Environment::new()
+        .render_str(content, vars)
+        .unwrap_or_else(|_| content.to_string())
+}
+
+/// Turns `\n` typed in a slash command option into line breaks.
+pub fn unescape_newlines(content: &str) -> String {
+    content.replace("\\n", "\n")
+}
+
+/// Autocompletes tag names of the current server.
+pub async fn autocomplete_tags<'a>(
+    ctx: Context<'_>,
+    partial: &str,
+) -> CreateAutocompleteResponse<'a> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return CreateAutocompleteResponse::new();
+    };
+
+    let tags = ctx
+        .data()
+        .service
+        .tags
+        .search_tags(guild_id.get(), partial)
+        .await
+        .unwrap_or_default();
+
+    let choices: Vec<AutocompleteChoice> = tags
+        .into_iter()
+        .map(|tag| AutocompleteChoice::new(tag.name.clone(), tag.name))
+        .collect();
+
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
+
+/// Sends an ephemeral status message to the admin managing tags.
+async fn send_status(ctx: Context<'_>, status_text: String) -> Result<(), Error> {
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(CreateReply::from(response).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_tag_fills_placeholders() {
+        let vars = TagVariables {
+            user: "<@1>".to_string(),
+            server_name: "pwr".to_string(),
+            uses: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_tag(
+                "Hi {{ user }}, welcome to {{ server_name }} ({{ uses }})",
+                &vars
+            ),
+            "Hi <@1>, welcome to pwr (3)"
+        );
+    }
+
+    #[test]
+    fn render_tag_keeps_invalid_templates_as_written() {
+        let content = "Use {{ to open a placeholder";
+        assert_eq!(render_tag(content, &TagVariables::default()), content);
+    }
+
+    #[test]
+    fn unescape_newlines_converts_escaped_line_breaks() {
+        assert_eq!(unescape_newlines("a\\nb"), "a\nb");
+    }
+}
@@ -0,0 +1,32 @@
+//! Tag remove subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::tag::autocomplete_tags;
+use crate::bot::command::tag::send_status;
+use crate::service::error::ServiceError;
+use crate::service::tag::normalize_tag_name;
+
+/// Delete a tag of this server
+///
+/// Requires server administrator permissions.
+#[poise::command(slash_command, ephemeral)]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "Name of the tag"]
+    #[autocomplete = "autocomplete_tags"]
+    name: String,
+) -> Result<(), Error> {
+    command(ctx, name).await
+}
+
+pub async fn command(ctx: Context<'_>, name: String) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    let name = normalize_tag_name(&name)?;
+    if !ctx.data().service.tags.delete_tag(guild_id, &name).await? {
+        return Err(ServiceError::TagNotFound { name }.into());
+    }
+
+    send_status(ctx, format!("### Tag Removed\n`{name}` has been deleted.")).await
+}
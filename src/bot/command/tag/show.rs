@@ -0,0 +1,39 @@
+//! Tag show subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::tag::TagVariables;
+use crate::bot::command::tag::autocomplete_tags;
+use crate::bot::command::tag::render_tag;
+
+/// Post a tag in this channel
+///
+/// Sends the tag's response, filling in its variables.
+#[poise::command(slash_command)]
+pub async fn show(
+    ctx: Context<'_>,
+    #[description = "Name of the tag"]
+    #[autocomplete = "autocomplete_tags"]
+    name: String,
+    #[description = "Text to fill into the tag's {{ args }} placeholder"] args: Option<String>,
+) -> Result<(), Error> {
+    command(ctx, name, args).await
+}
+
+pub async fn command(ctx: Context<'_>, name: String, args: Option<String>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    let tag = ctx.data().service.tags.use_tag(guild_id, &name).await?;
+    let content = render_tag(&tag.content, &TagVariables::from_context(ctx, &tag, args));
+
+    let reply = if tag.embed {
+        CreateReply::from(ResponseKind::from(CreateEmbed::new().description(content)))
+    } else {
+        CreateReply::new().content(content)
+    };
+
+    // Arguments come from whoever posts the tag, so never let them ping roles or everyone
+    ctx.send(reply.allowed_mentions(CreateAllowedMentions::new().all_users(true)))
+        .await?;
+
+    Ok(())
+}
@@ -7,9 +7,15 @@ use chrono::Utc;
 
 use crate::bot::command::prelude::*;
 
+pub mod admin;
+pub mod afk;
+pub mod channels;
 pub mod leaderboard;
+pub mod now;
+pub mod report;
 pub mod settings;
 pub mod stats;
+pub mod temp_channels;
 
 /// Voice channel tracking and leaderboard commands
 ///
@@ -18,7 +24,17 @@ pub mod stats;
 #[poise::command(
     slash_command,
     rename = "vc",
-    subcommands("settings::settings", "leaderboard::leaderboard", "stats::stats")
+    subcommands(
+        "settings::settings",
+        "leaderboard::leaderboard",
+        "stats::stats",
+        "now::now",
+        "channels::channels",
+        "report::report",
+        "temp_channels::temp_channels",
+        "afk::afk",
+        "admin::admin"
+    )
 )]
 pub async fn voice(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
@@ -0,0 +1,118 @@
+//! Voice time adjustment subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::service::traits::VoiceTracker;
+
+/// Longest adjustment accepted in a single command.
+const MAX_ADJUST_HOURS: u32 = 1000;
+
+/// Whether an adjustment adds or removes voice time.
+#[derive(ChoiceParameter, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdjustDirection {
+    Add,
+    Remove,
+}
+
+/// Add or remove voice time for a member
+///
+/// Corrects a member's voice time, e.g. after a tracking outage. Adjustments
+/// count towards the leaderboard and stats but leave recorded sessions untouched.
+#[poise::command(slash_command)]
+pub async fn adjust(
+    ctx: Context<'_>,
+    #[description = "Member whose voice time to adjust"] user: User,
+    #[description = "Whether to add or remove time"] direction: AdjustDirection,
+    #[description = "Hours to add or remove"]
+    #[max = 1000]
+    hours: Option<u32>,
+    #[description = "Minutes to add or remove"]
+    #[max = 59]
+    minutes: Option<u32>,
+    #[description = "Why the time is adjusted"]
+    #[max_length = 200]
+    reason: Option<String>,
+) -> Result<(), Error> {
+    command(ctx, user, direction, hours, minutes, reason).await
+}
+
+pub async fn command(
+    ctx: Context<'_>,
+    user: User,
+    direction: AdjustDirection,
+    hours: Option<u32>,
+    minutes: Option<u32>,
+    reason: Option<String>,
+) -> Result<(), Error> {
+    is_author_guild_admin(ctx).await?;
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+    let hours = hours.unwrap_or(0).min(MAX_ADJUST_HOURS);
+    let minutes = minutes.unwrap_or(0).min(59);
+    let amount = i64::from(hours) * 3600 + i64::from(minutes) * 60;
+    if amount == 0 {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "hours".to_string(),
+            reason: "Set `hours` or `minutes` to the time to adjust".to_string(),
+        }
+        .into());
+    }
+    if user.bot() {
+        return Err(BotError::InvalidCommandArgument {
+            parameter: "user".to_string(),
+            reason: "Voice time is not tracked for bots".to_string(),
+        }
+        .into());
+    }
+
+    ctx.defer().await?;
+    let seconds = match direction {
+        AdjustDirection::Add => amount,
+        AdjustDirection::Remove => -amount,
+    };
+    let reason = reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+
+    let net = ctx
+        .data()
+        .service
+        .voice_tracking
+        .adjust_voice_time(
+            guild_id,
+            user.id.get(),
+            seconds,
+            reason.clone(),
+            ctx.author().id.get(),
+        )
+        .await?;
+
+    let mut text = format!(
+        "### Voice Time Adjusted\n{} **{}** {} <@{}>.",
+        match direction {
+            AdjustDirection::Add => "Added",
+            AdjustDirection::Remove => "Removed",
+        },
+        format_duration(amount),
+        match direction {
+            AdjustDirection::Add => "to",
+            AdjustDirection::Remove => "from",
+        },
+        user.id
+    );
+    if let Some(reason) = &reason {
+        text.push_str(&format!("\n**Reason:** {reason}"));
+    }
+    text.push_str(&format!(
+        "\n-# Net adjustment for this member: {}{}",
+        if net < 0 { "-" } else { "+" },
+        format_duration(net.abs())
+    ));
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
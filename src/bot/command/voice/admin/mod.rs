@@ -0,0 +1,19 @@
+//! Voice admin subcommands.
+
+use crate::bot::command::prelude::*;
+
+pub mod adjust;
+pub mod review;
+
+/// Voice tracking moderation tools
+///
+/// Review flagged sessions and correct recorded voice time.
+/// Only server administrators can use these commands.
+#[poise::command(
+    slash_command,
+    subcommands("review::review", "adjust::adjust"),
+    default_member_permissions = "ADMINISTRATOR | MANAGE_GUILD"
+)]
+pub async fn admin(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
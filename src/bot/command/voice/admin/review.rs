@@ -0,0 +1,229 @@
+//! Flagged voice session review subcommand.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::bot::view::pagination::PaginationModel;
+use crate::entity::VoiceFlagReason;
+use crate::entity::VoiceSessionFlag;
+use crate::service::traits::VoiceTracker;
+
+const FLAGS_PER_PAGE: u32 = 5;
+
+/// Review voice sessions flagged as suspicious
+///
+/// Lists sessions flagged by the periodic activity check, such as day-long
+/// sessions or accounts joining and leaving together. Invalidate a session to
+/// remove it from all stats, or keep it to clear the flag.
+#[poise::command(slash_command)]
+pub async fn review(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::VoiceAdminReview).await?;
+    Ok(())
+}
+
+handler! { pub struct VoiceAdminReviewHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for VoiceAdminReviewHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        is_author_guild_admin(ctx).await?;
+        ctx.defer().await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let service: Arc<dyn VoiceTracker> = ctx.data().service.voice_tracking.clone();
+        let total = service.count_open_flags(guild_id).await?;
+        let flags = service.get_open_flags(guild_id, 1, FLAGS_PER_PAGE).await?;
+
+        let view = VoiceAdminReviewView {
+            flags,
+            pagination: PaginationView::new(total, FLAGS_PER_PAGE),
+            service,
+            guild_id,
+            notice: None,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+pub struct VoiceAdminReviewView {
+    pub flags: Vec<VoiceSessionFlag>,
+    pub pagination: PaginationView,
+    pub service: Arc<dyn VoiceTracker>,
+    pub guild_id: u64,
+    /// Outcome of the last review action, shown above the list.
+    pub notice: Option<String>,
+}
+
+impl VoiceAdminReviewView {
+    /// Formats a flag with the session it points at.
+    fn format_flag(flag: &VoiceSessionFlag) -> String {
+        let (reason, summary) = match flag.reason {
+            VoiceFlagReason::LongSession => (
+                "Long session",
+                format!(
+                    "**{}** continuously{}",
+                    format_duration(flag.duration_secs()),
+                    if flag.is_active {
+                        ", still in voice"
+                    } else {
+                        ""
+                    }
+                ),
+            ),
+            VoiceFlagReason::SyncedSessions => (
+                "Synced sessions",
+                format!(
+                    "**{}**, joined and left together with **{}** other account{}",
+                    format_duration(flag.duration_secs()),
+                    flag.related_count,
+                    if flag.related_count == 1 { "" } else { "s" }
+                ),
+            ),
+        };
+        format!(
+            "<@{}> in <#{}> — {}\n-# {} · joined <t:{}:f> · flagged <t:{}:R>",
+            flag.user_id,
+            flag.channel_id,
+            summary,
+            reason,
+            flag.join_time.timestamp(),
+            flag.flagged_at.timestamp()
+        )
+    }
+
+    /// Reloads the flag count and the current page, staying on it when it still exists.
+    async fn refresh(&mut self) -> Result<(), Error> {
+        let total = self.service.count_open_flags(self.guild_id).await?;
+        let pages = total.div_ceil(FLAGS_PER_PAGE);
+        self.pagination.state =
+            PaginationModel::new(pages, FLAGS_PER_PAGE, self.pagination.current_page());
+        self.flags = self
+            .service
+            .get_open_flags(
+                self.guild_id,
+                self.pagination.current_page(),
+                FLAGS_PER_PAGE,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the flag with `flag_id` on the current page.
+    fn flag(&self, flag_id: i32) -> Option<&VoiceSessionFlag> {
+        self.flags.iter().find(|flag| flag.id == flag_id)
+    }
+}
+
+action_extends! { VoiceAdminReviewAction extends PaginationAction {
+    #[label = "🗑 Invalidate"]
+    Invalidate { flag_id: i32 },
+    #[label = "✓ Keep"]
+    Keep { flag_id: i32 },
+}}
+
+#[async_trait::async_trait]
+impl ViewHandler for VoiceAdminReviewView {
+    type Action = VoiceAdminReviewAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, VoiceAdminReviewAction>,
+    ) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            VoiceAdminReviewAction::Base(inner) => {
+                match inner {
+                    PaginationAction::First => self.pagination.state.first_page(),
+                    PaginationAction::Prev => self.pagination.state.prev_page(),
+                    PaginationAction::Next => self.pagination.state.next_page(),
+                    PaginationAction::Last => self.pagination.state.last_page(),
+                    PaginationAction::Page => return Ok(ViewCmd::Continue),
+                }
+                self.notice = None;
+            }
+            VoiceAdminReviewAction::Invalidate { flag_id } => {
+                let user_id = self.flag(*flag_id).map(|flag| flag.user_id);
+                let removed = self
+                    .service
+                    .invalidate_flagged_session(self.guild_id, *flag_id)
+                    .await?;
+                self.notice = Some(match (removed, user_id) {
+                    (true, Some(user_id)) => {
+                        format!("🗑 Invalidated the session of <@{user_id}>.")
+                    }
+                    _ => "This session was already reviewed.".to_string(),
+                });
+            }
+            VoiceAdminReviewAction::Keep { flag_id } => {
+                let user_id = self.flag(*flag_id).map(|flag| flag.user_id);
+                let dismissed = self.service.dismiss_flag(self.guild_id, *flag_id).await?;
+                self.notice = Some(match (dismissed, user_id) {
+                    (true, Some(user_id)) => format!("✓ Kept the session of <@{user_id}>."),
+                    _ => "This session was already reviewed.".to_string(),
+                });
+            }
+        }
+        self.refresh().await?;
+        Ok(ViewCmd::Render)
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.pagination.disabled = true;
+        Ok(ViewCmd::RenderOnce)
+    }
+}
+
+impl ViewRender for VoiceAdminReviewView {
+    type Action = VoiceAdminReviewAction;
+    fn render(&self, registry: &mut ActionRegistry<VoiceAdminReviewAction>) -> ResponseKind<'_> {
+        let mut header = "## Flagged Voice Sessions".to_string();
+        if let Some(notice) = &self.notice {
+            header.push_str(&format!("\n> {notice}"));
+        }
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(header),
+        )];
+
+        if self.flags.is_empty() {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new("> 🛈  No sessions are waiting for review."),
+            ));
+        }
+        for flag in &self.flags {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(Self::format_flag(flag)),
+            ));
+            if !self.pagination.disabled {
+                sections.push(CreateContainerComponent::ActionRow(
+                    CreateActionRow::Buttons(
+                        vec![
+                            registry
+                                .register(VoiceAdminReviewAction::Invalidate { flag_id: flag.id })
+                                .as_button()
+                                .style(ButtonStyle::Danger),
+                            registry
+                                .register(VoiceAdminReviewAction::Keep { flag_id: flag.id })
+                                .as_button()
+                                .style(ButtonStyle::Secondary),
+                        ]
+                        .into(),
+                    ),
+                ));
+            }
+        }
+
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(sections))];
+        self.pagination.attach_if_multipage(
+            registry,
+            &mut components,
+            VoiceAdminReviewAction::Base,
+        );
+
+        components.into()
+    }
+}
@@ -0,0 +1,185 @@
+//! AFK mover settings subcommand.
+
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::settings::open_staged_settings;
+use crate::bot::command::settings::save_staged_settings;
+use crate::entity::ServerSettings;
+use crate::update::Staged;
+
+/// Times a member may idle before being moved, in minutes.
+const AFK_MOVE_OPTIONS: [(u32, &str); 5] = [
+    (0, "Off"),
+    (10, "10 minutes"),
+    (15, "15 minutes"),
+    (30, "30 minutes"),
+    (60, "1 hour"),
+];
+
+/// Configure the AFK mover
+///
+/// Move members who stay self-deafened and alone in a voice channel to the
+/// server's AFK channel.
+/// Only server administrators can use this command.
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR | MANAGE_GUILD"
+)]
+pub async fn afk(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::VoiceAfk).await?;
+    Ok(())
+}
+
+handler! { pub struct VoiceAfkHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for VoiceAfkHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        ctx.defer().await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let afk_channel_id = ctx.guild().and_then(|guild| {
+            guild
+                .afk_metadata
+                .as_ref()
+                .map(|afk| afk.afk_channel_id.get())
+        });
+
+        let view = SettingsAfkHandler {
+            settings: open_staged_settings(&ctx.data().service, guild_id).await?,
+            guild_id,
+            afk_channel_id,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        // Unsaved changes are discarded when the view exits
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+action_enum! {
+    SettingsAfkAction {
+        AfterMins,
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
+    }
+}
+
+pub struct SettingsAfkHandler {
+    pub settings: Staged<ServerSettings>,
+    pub guild_id: u64,
+    /// The AFK channel set in the server's settings on Discord.
+    pub afk_channel_id: Option<u64>,
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for SettingsAfkHandler {
+    type Action = SettingsAfkAction;
+    async fn handle(&mut self, ctx: ViewContext<'_, SettingsAfkAction>) -> Result<ViewCmd, Error> {
+        let ret = match ctx.action() {
+            SettingsAfkAction::AfterMins => {
+                let mins = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|mins| mins.parse::<u32>().ok()));
+                if let Some(mins) = mins {
+                    self.settings.voice.afk_move_mins = (mins > 0).then_some(mins);
+                }
+                ViewCmd::Render
+            }
+            SettingsAfkAction::Save => {
+                save_staged_settings(
+                    &ctx.poise.data().service,
+                    self.guild_id,
+                    ctx.poise.author().id.get(),
+                    &mut self.settings,
+                )
+                .await?;
+                ViewCmd::Render
+            }
+            SettingsAfkAction::Revert => {
+                self.settings.revert();
+                ViewCmd::Render
+            }
+        };
+        Ok(ret)
+    }
+}
+
+impl ViewRender for SettingsAfkHandler {
+    type Action = SettingsAfkAction;
+    fn render(&self, registry: &mut ActionRegistry<SettingsAfkAction>) -> ResponseKind<'_> {
+        let voice = &self.settings.voice;
+        let after_mins = voice.afk_move_secs().map(|secs| secs / 60);
+        let is_dirty = self.settings.is_dirty();
+
+        let mut status_text = format!(
+            "-# **Voice > AFK Mover**{}\n## AFK Mover\n\n> 🛈  {}",
+            unsaved_marker(is_dirty),
+            match (after_mins, self.afk_channel_id) {
+                (Some(mins), Some(channel_id)) => format!(
+                    "Members self-deafened and alone in a channel for **{mins} minutes** are moved to <#{channel_id}>."
+                ),
+                (Some(mins), None) => format!(
+                    "Members self-deafened and alone in a channel for **{mins} minutes** are moved to the AFK channel."
+                ),
+                (None, _) => "The AFK mover is **off**.".to_string(),
+            }
+        );
+        if self.afk_channel_id.is_none() {
+            status_text.push_str(
+                "\n> ⚠  This server has no AFK channel. Set one in the server settings on Discord.",
+            );
+        }
+        if !voice.is_enabled() {
+            status_text.push_str(
+                "\n> ⚠  Voice tracking is paused, so nobody is moved until it is enabled again.",
+            );
+        }
+
+        let after_text = "### Idle Time\n\n> 🛈  Only tracked members are moved. The bot needs the **Move Members** permission, and **Connect** in the AFK channel.";
+        let after_options: Vec<_> = AFK_MOVE_OPTIONS
+            .iter()
+            .map(|(mins, name)| {
+                CreateSelectMenuOption::new(*name, mins.to_string())
+                    .default_selection(*mins == after_mins.unwrap_or(0))
+            })
+            .collect();
+        let after_select = registry
+            .register(SettingsAfkAction::AfterMins)
+            .as_select(CreateSelectMenuKind::String {
+                options: after_options.into(),
+            })
+            .placeholder("Select idle time");
+
+        let container = CreateComponent::Container(CreateContainer::new(vec![
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(after_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(after_select)),
+        ]));
+
+        let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
+            vec![
+                registry
+                    .register(SettingsAfkAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsAfkAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
+            ]
+            .into(),
+        ));
+
+        vec![container, nav_buttons].into()
+    }
+}
@@ -0,0 +1,160 @@
+//! Voice channels subcommand.
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::voice::TimeRange;
+use crate::bot::command::voice::VoiceLeaderboardTimeRange;
+use crate::entity::VoiceChannelUsage;
+
+/// Channels listed by `/vc channels`.
+const TOP_CHANNELS: usize = 10;
+/// Width of the usage bars, in characters.
+const BAR_WIDTH: usize = 16;
+
+/// Kind of voice channel to include.
+#[derive(ChoiceParameter, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ChannelTypeFilter {
+    /// Voice and stage channels
+    #[default]
+    #[name = "All"]
+    All,
+    #[name = "Voice channels"]
+    Voice,
+    #[name = "Stage channels"]
+    Stage,
+}
+
+impl ChannelTypeFilter {
+    /// Whether a channel of `kind` passes the filter. Channels no longer in the cache
+    /// have no kind and only show under [`ChannelTypeFilter::All`].
+    fn matches(self, kind: Option<ChannelType>) -> bool {
+        match self {
+            ChannelTypeFilter::All => true,
+            ChannelTypeFilter::Voice => kind == Some(ChannelType::Voice),
+            ChannelTypeFilter::Stage => kind == Some(ChannelType::Stage),
+        }
+    }
+}
+
+/// Show the most used voice channels
+///
+/// Lists voice channels by time spent in them, with each channel's share of
+/// the total.
+#[poise::command(slash_command)]
+pub async fn channels(
+    ctx: Context<'_>,
+    #[description = "Time period to filter voice activity. Defaults to \"This month\""]
+    time_range: Option<VoiceLeaderboardTimeRange>,
+    #[description = "Kind of channel to include. Defaults to all"] channel_type: Option<
+        ChannelTypeFilter,
+    >,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+    let time_range = time_range.unwrap_or_default();
+    let channel_type = channel_type.unwrap_or_default();
+    let (since, until) = time_range.to_range();
+
+    let usage = ctx
+        .data()
+        .service
+        .voice_tracking
+        .get_channel_usage(guild_id.get(), &since, &until)
+        .await
+        .map_err(Error::from)?;
+    let usage: Vec<VoiceChannelUsage> = match ctx.guild() {
+        Some(guild) => usage
+            .into_iter()
+            .filter(|entry| {
+                let kind = guild
+                    .channels
+                    .get(&ChannelId::new(entry.channel_id))
+                    .map(|channel| channel.kind);
+                channel_type.matches(kind)
+            })
+            .collect(),
+        None => usage,
+    };
+
+    let mut text = format!(
+        "### Top Voice Channels\n-# Time Range: **{}** — <t:{}:f> to <t:{}:R>",
+        time_range.name(),
+        since.timestamp(),
+        until.timestamp(),
+    );
+    if channel_type != ChannelTypeFilter::All {
+        text.push_str(&format!(" · {}", channel_type.name()));
+    }
+    text.push_str("\n\n");
+    if usage.is_empty() {
+        text.push_str("No voice activity recorded in these channels at this time range.");
+    } else {
+        text.push_str(&format_usage(&usage));
+    }
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    ctx.send(response.into()).await?;
+
+    Ok(())
+}
+
+/// Formats the top channels with a bar and their share of the time in every listed
+/// channel.
+fn format_usage(usage: &[VoiceChannelUsage]) -> String {
+    let total = usage
+        .iter()
+        .map(|entry| entry.total_duration)
+        .sum::<i64>()
+        .max(1) as f64;
+    usage
+        .iter()
+        .take(TOP_CHANNELS)
+        .enumerate()
+        .map(|(i, entry)| {
+            let share = entry.total_duration as f64 / total;
+            format!(
+                "**{}.** <#{}> — **{}** ({:.0}%) · {} member{}\n`{}`",
+                i + 1,
+                entry.channel_id,
+                format_duration(entry.total_duration),
+                share * 100.0,
+                entry.user_count,
+                if entry.user_count == 1 { "" } else { "s" },
+                usage_bar(share),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Draws `share`, from 0 to 1, as a bar of block characters.
+fn usage_bar(share: f64) -> String {
+    let filled = ((share * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_bar_scales_with_share() {
+        assert_eq!(usage_bar(0.0), "░".repeat(BAR_WIDTH));
+        assert_eq!(
+            usage_bar(0.5),
+            format!("{}{}", "█".repeat(8), "░".repeat(8))
+        );
+        assert_eq!(usage_bar(1.0), "█".repeat(BAR_WIDTH));
+    }
+
+    #[test]
+    fn filter_hides_uncached_channels_unless_all() {
+        assert!(ChannelTypeFilter::All.matches(None));
+        assert!(!ChannelTypeFilter::Voice.matches(None));
+        assert!(ChannelTypeFilter::Stage.matches(Some(ChannelType::Stage)));
+        assert!(!ChannelTypeFilter::Stage.matches(Some(ChannelType::Voice)));
+    }
+}
@@ -0,0 +1,99 @@
+//! Voice leaderboard animate subcommand.
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude::UserId;
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::voice::TimeRange;
+use crate::bot::command::voice::VoiceLeaderboardTimeRange;
+use crate::bot::command::voice::leaderboard::animation::ANIMATION_FILENAME;
+use crate::bot::command::voice::leaderboard::animation::LeaderboardAnimation;
+use crate::entity::VoiceLeaderboardOptBuilder;
+
+/// Animate how the leaderboard changed
+///
+/// Renders an animated GIF of the all-time standings, from each weekly snapshot
+/// in the selected period up to now.
+#[poise::command(slash_command)]
+pub async fn animate(
+    ctx: Context<'_>,
+    #[description = "Period to animate. Defaults to \"This year\""] time_range: Option<
+        VoiceLeaderboardTimeRange,
+    >,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+    let (since, now) = time_range
+        .unwrap_or(VoiceLeaderboardTimeRange::ThisYear)
+        .to_range();
+    let voice_tracking = &ctx.data().service.voice_tracking;
+
+    let snapshots = voice_tracking
+        .get_leaderboard_snapshots_since(guild_id.get(), &since)
+        .await
+        .map_err(Error::from)?;
+    if snapshots.is_empty() {
+        let container = CreateComponent::Container(CreateContainer::new(vec![
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                "No leaderboard snapshots were taken in this period yet. Snapshots are taken once a week.",
+            )),
+        ]));
+        let response: ResponseKind<'_> = vec![container].into();
+        ctx.send(response.into()).await?;
+        return Ok(());
+    }
+
+    // Snapshots hold all-time standings, so the last frame does too
+    let (all_time, _) = VoiceLeaderboardTimeRange::AllTime.to_range();
+    let opts = VoiceLeaderboardOptBuilder::default()
+        .guild_id(guild_id.get())
+        .limit(Some(u32::MAX))
+        .since(Some(all_time))
+        .until(Some(now))
+        .build()
+        .map_err(AppError::from)?;
+    let current = voice_tracking
+        .get_leaderboard_withopt(&opts)
+        .await
+        .map_err(Error::from)?;
+
+    let animation = LeaderboardAnimation::new(&snapshots, &current, now);
+    let user_ids: Vec<UserId> = animation.user_ids().into_iter().map(UserId::new).collect();
+    let names: HashMap<u64, String> = ctx
+        .data()
+        .users
+        .resolve_many(ctx.http(), ctx.cache(), Some(guild_id), &user_ids)
+        .await
+        .into_iter()
+        .map(|(user_id, user)| (user_id.get(), user.display_name))
+        .collect();
+    let animation = animation.with_names(names);
+
+    let gif = ctx
+        .data()
+        .renderer
+        .render("leaderboard_animation", move || animation.render())
+        .await
+        .map_err(AppError::internal_with_ref)?;
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+            "### Voice Leaderboard\n-# All-time standings from <t:{}:D> to <t:{}:R>",
+            snapshots[0].taken_at.timestamp(),
+            now.timestamp(),
+        ))),
+        CreateContainerComponent::MediaGallery(CreateMediaGallery::new(vec![
+            CreateMediaGalleryItem::new(CreateUnfurledMediaItem::new(format!(
+                "attachment://{ANIMATION_FILENAME}"
+            ))),
+        ])),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    let reply: poise::CreateReply<'_> = response.into();
+    ctx.send(reply.attachment(CreateAttachment::bytes(gif, ANIMATION_FILENAME)))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,350 @@
+//! Animated "race chart" of leaderboard standings over time.
+//!
+//! Each weekly snapshot in `leaderboard_snapshots` becomes a keyframe, with the current
+//! standings as the last one. Frames in between interpolate durations and positions, so
+//! users slide past each other instead of jumping.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use image::Delay;
+use image::Frame;
+use image::RgbaImage;
+use image::codecs::gif::GifEncoder;
+use image::codecs::gif::Repeat;
+use minijinja::AutoEscape;
+use minijinja::Environment;
+use minijinja::context;
+use serde::Serialize;
+
+use crate::bot::utils::format_duration;
+use crate::entity::LeaderboardSnapshotEntity;
+use crate::entity::VoiceLeaderboardEntry;
+
+/// Filename for the animated leaderboard attachment.
+pub const ANIMATION_FILENAME: &str = "voice_leaderboard.gif";
+
+/// Most frames in one animation, in-between frames included.
+const MAX_FRAMES: usize = 60;
+/// Most keyframes in one animation. Longer periods skip snapshots evenly.
+const MAX_KEYFRAMES: usize = 20;
+/// In-between frames from one keyframe to the next, fewer when the frame cap is hit.
+const TWEEN_FRAMES: usize = 5;
+/// Largest GIF sent. Discord rejects bigger uploads.
+const MAX_GIF_BYTES: usize = 8 * 1024 * 1024;
+/// Users shown in each frame.
+const TOP_N: usize = 10;
+
+const WIDTH: u32 = 500;
+const HEADER_HEIGHT: u32 = 44;
+const ROW_PITCH: u32 = 34;
+const BAR_HEIGHT: u32 = 26;
+const PADDING: u32 = 12;
+/// Room kept right of the bars for the duration.
+const DURATION_WIDTH: u32 = 80;
+
+const TWEEN_DELAY_MS: u32 = 80;
+const KEYFRAME_DELAY_MS: u32 = 800;
+const LAST_FRAME_DELAY_MS: u32 = 3000;
+
+const BAR_COLORS: [&str; 8] = [
+    "#5865F2", "#57F287", "#FEE75C", "#EB459E", "#ED4245", "#3BA55C", "#FAA61A", "#00A8FC",
+];
+
+/// Standings at one point in time.
+#[derive(Clone, Debug, PartialEq)]
+struct Keyframe {
+    taken_at: DateTime<Utc>,
+    /// Total voice time per user, in seconds.
+    totals: HashMap<u64, i64>,
+}
+
+impl Keyframe {
+    /// Returns the top users, most voice time first.
+    fn top(&self) -> Vec<u64> {
+        let mut users: Vec<(&u64, &i64)> = self.totals.iter().collect();
+        users.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        users.into_iter().take(TOP_N).map(|(id, _)| *id).collect()
+    }
+}
+
+/// A bar as drawn by the template.
+#[derive(Serialize)]
+struct Bar {
+    y: f64,
+    width: f64,
+    color: &'static str,
+    opacity: f64,
+    name: String,
+    duration: String,
+}
+
+/// A frame ready to render.
+struct FrameData {
+    label: String,
+    bars: Vec<Bar>,
+    delay_ms: u32,
+}
+
+/// Leaderboard race built from snapshots.
+pub struct LeaderboardAnimation {
+    keyframes: Vec<Keyframe>,
+    names: HashMap<u64, String>,
+}
+
+impl LeaderboardAnimation {
+    /// Groups snapshot rows, ordered by time, into keyframes and appends the current
+    /// standings taken at `now`.
+    pub fn new(
+        snapshots: &[LeaderboardSnapshotEntity],
+        current: &[VoiceLeaderboardEntry],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut keyframes: Vec<Keyframe> = Vec::new();
+        for row in snapshots {
+            match keyframes.last_mut() {
+                Some(frame) if frame.taken_at == row.taken_at => {
+                    frame.totals.insert(*row.user_id, row.total_duration);
+                }
+                _ => keyframes.push(Keyframe {
+                    taken_at: row.taken_at,
+                    totals: HashMap::from([(*row.user_id, row.total_duration)]),
+                }),
+            }
+        }
+        keyframes.push(Keyframe {
+            taken_at: now,
+            totals: current
+                .iter()
+                .map(|e| (e.user_id, e.total_duration))
+                .collect(),
+        });
+
+        Self {
+            keyframes: sample(keyframes, MAX_KEYFRAMES),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Sets the names shown on the bars. Users without one are shown by ID.
+    pub fn with_names(mut self, names: HashMap<u64, String>) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Returns every user shown in some frame.
+    pub fn user_ids(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self.keyframes.iter().flat_map(Keyframe::top).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Renders the animation to a looping GIF.
+    ///
+    /// Fails if the GIF is larger than Discord accepts.
+    pub fn render(&self) -> Result<Vec<u8>> {
+        let mut env = Environment::new();
+        // Display names are user input
+        env.set_auto_escape_callback(|_| AutoEscape::Html);
+        env.add_template(
+            "leaderboard_race",
+            include_str!("../../../../../assets/leaderboard_race.svg"),
+        )?;
+        let template = env.get_template("leaderboard_race")?;
+
+        let mut fontdb = resvg::usvg::fontdb::Database::new();
+        fontdb.load_font_data(
+            include_bytes!("../../../../../assets/fonts/Roboto-Regular.ttf").to_vec(),
+        );
+        let options = resvg::usvg::Options {
+            fontdb: std::sync::Arc::new(fontdb),
+            ..Default::default()
+        };
+
+        let height = HEADER_HEIGHT + TOP_N as u32 * ROW_PITCH + PADDING;
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = GifEncoder::new_with_speed(&mut bytes, 10);
+            encoder.set_repeat(Repeat::Infinite)?;
+
+            for frame in self.frames() {
+                let svg = template.render(context! {
+                    width => WIDTH,
+                    height => height,
+                    bar_height => BAR_HEIGHT,
+                    label => frame.label,
+                    bars => frame.bars,
+                })?;
+                let tree = resvg::usvg::Tree::from_str(&svg, &options)?;
+                let mut pixmap = resvg::tiny_skia::Pixmap::new(WIDTH, height)
+                    .ok_or_else(|| anyhow::anyhow!("Failed to create pixmap"))?;
+                resvg::render(
+                    &tree,
+                    resvg::tiny_skia::Transform::default(),
+                    &mut pixmap.as_mut(),
+                );
+                // The background is opaque, so premultiplied pixels equal plain RGBA
+                let img = RgbaImage::from_raw(WIDTH, height, pixmap.take())
+                    .ok_or_else(|| anyhow::anyhow!("Failed to read pixmap"))?;
+                encoder.encode_frame(Frame::from_parts(
+                    img,
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(frame.delay_ms, 1),
+                ))?;
+            }
+        }
+
+        if bytes.len() > MAX_GIF_BYTES {
+            anyhow::bail!(
+                "Animation is {} bytes, over the {MAX_GIF_BYTES} byte limit",
+                bytes.len()
+            );
+        }
+        Ok(bytes)
+    }
+
+    /// Lays out every frame: each keyframe, then the in-between frames to the next.
+    fn frames(&self) -> Vec<FrameData> {
+        let keyframes = self.keyframes.len();
+        let tweens = match keyframes {
+            0 | 1 => 0,
+            n => TWEEN_FRAMES.min(MAX_FRAMES.saturating_sub(n) / (n - 1)),
+        };
+
+        let mut frames = Vec::new();
+        for (i, from) in self.keyframes.iter().enumerate() {
+            let Some(to) = self.keyframes.get(i + 1) else {
+                frames.push(self.frame(from, from, 0.0, LAST_FRAME_DELAY_MS));
+                break;
+            };
+            frames.push(self.frame(from, to, 0.0, KEYFRAME_DELAY_MS));
+            for step in 1..=tweens {
+                let t = step as f64 / (tweens + 1) as f64;
+                frames.push(self.frame(from, to, t, TWEEN_DELAY_MS));
+            }
+        }
+        frames
+    }
+
+    /// Lays out the standings `t` of the way from `from` to `to`.
+    fn frame(&self, from: &Keyframe, to: &Keyframe, t: f64, delay_ms: u32) -> FrameData {
+        let from_top = from.top();
+        let to_top = to.top();
+        let position =
+            |top: &[u64], id: u64| top.iter().position(|u| *u == id).unwrap_or(TOP_N) as f64;
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        let mut users: Vec<(u64, f64, f64)> = Vec::new();
+        for &id in from_top.iter().chain(&to_top) {
+            if users.iter().any(|(user, _, _)| *user == id) {
+                continue;
+            }
+            let value = lerp(
+                from.totals.get(&id).copied().unwrap_or(0) as f64,
+                to.totals.get(&id).copied().unwrap_or(0) as f64,
+            );
+            let pos = lerp(position(&from_top, id), position(&to_top, id));
+            if pos < TOP_N as f64 {
+                users.push((id, value, pos));
+            }
+        }
+
+        let max_value = users.iter().map(|(_, value, _)| *value).fold(1.0, f64::max);
+        let max_width = f64::from(WIDTH - 2 * PADDING - DURATION_WIDTH);
+        let bars = users
+            .into_iter()
+            .map(|(id, value, pos)| Bar {
+                y: f64::from(HEADER_HEIGHT) + pos * f64::from(ROW_PITCH),
+                width: (value / max_value * max_width).max(2.0),
+                color: BAR_COLORS[(id % BAR_COLORS.len() as u64) as usize],
+                opacity: (TOP_N as f64 - pos).clamp(0.0, 1.0),
+                name: self
+                    .names
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("User {id}")),
+                duration: format_duration(value as i64),
+            })
+            .collect();
+
+        let span = (to.taken_at - from.taken_at).num_milliseconds() as f64;
+        let at = from.taken_at + chrono::Duration::milliseconds((span * t) as i64);
+        FrameData {
+            label: at.format("%Y-%m-%d").to_string(),
+            bars,
+            delay_ms,
+        }
+    }
+}
+
+/// Keeps at most `max` items, spread evenly and always including the first and last.
+fn sample<T>(items: Vec<T>, max: usize) -> Vec<T> {
+    let len = items.len();
+    if len <= max || max < 2 {
+        return items;
+    }
+    let keep: Vec<usize> = (0..max).map(|i| i * (len - 1) / (max - 1)).collect();
+    items
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| keep.contains(i))
+        .map(|(_, item)| item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn snapshot(
+        user_id: u64,
+        total_duration: i64,
+        taken_at: DateTime<Utc>,
+    ) -> LeaderboardSnapshotEntity {
+        LeaderboardSnapshotEntity {
+            user_id: user_id.into(),
+            total_duration,
+            taken_at,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn snapshots_become_keyframes_before_current_standings() {
+        let now = Utc::now();
+        let week_ago = now - Duration::weeks(1);
+        let snapshots = vec![snapshot(1, 100, week_ago), snapshot(2, 50, week_ago)];
+        let current = vec![VoiceLeaderboardEntry {
+            user_id: 2,
+            total_duration: 300,
+        }];
+
+        let animation = LeaderboardAnimation::new(&snapshots, &current, now);
+
+        assert_eq!(animation.keyframes.len(), 2);
+        assert_eq!(animation.keyframes[0].top(), vec![1, 2]);
+        assert_eq!(animation.keyframes[1].top(), vec![2]);
+        assert_eq!(animation.user_ids(), vec![1, 2]);
+    }
+
+    #[test]
+    fn frames_stay_under_cap() {
+        let now = Utc::now();
+        let snapshots: Vec<_> = (0..52)
+            .map(|week| snapshot(1, week * 3600, now - Duration::weeks(52 - week)))
+            .collect();
+
+        let animation = LeaderboardAnimation::new(&snapshots, &[], now);
+
+        assert_eq!(animation.keyframes.len(), MAX_KEYFRAMES);
+        assert_eq!(animation.keyframes[0].taken_at, snapshots[0].taken_at);
+        assert_eq!(animation.keyframes.last().unwrap().taken_at, now);
+        assert!(animation.frames().len() <= MAX_FRAMES);
+    }
+}
@@ -7,8 +7,10 @@ use poise::serenity_prelude::UserId;
 use crate::bot::command::Context;
 use crate::bot::command::Error;
 use crate::bot::command::voice::leaderboard::image_generator::LeaderboardImageGenerator;
+use crate::bot::user_resolver::ResolvedUser;
 use crate::entity::VoiceLeaderboardEntry;
 use crate::error::AppError;
+use crate::update::voice_leaderboard::RankChange;
 
 /// A single entry in the voice leaderboard.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -20,6 +22,12 @@ pub struct LeaderboardEntry {
     pub duration_seconds: i64,
     #[serde(skip)]
     pub avatar_image: Option<image::DynamicImage>,
+    /// Movement since last week's snapshot, when shown.
+    #[serde(skip)]
+    pub rank_change: Option<RankChange>,
+    /// Whether the member is in a tracked voice channel right now.
+    #[serde(default)]
+    pub is_live: bool,
 }
 
 /// Result of generating a leaderboard page.
@@ -32,7 +40,7 @@ pub struct ImageGenerationResult {
 pub struct LeaderboardImageBuilder<'a> {
     ctx: &'a Context<'a>,
     image_gen: LeaderboardImageGenerator,
-    user_cache: HashMap<u64, poise::serenity_prelude::User>,
+    users: HashMap<u64, ResolvedUser>,
 }
 
 impl<'a> LeaderboardImageBuilder<'a> {
@@ -42,24 +50,26 @@ impl<'a> LeaderboardImageBuilder<'a> {
         Self {
             ctx,
             image_gen,
-            user_cache: HashMap::new(),
+            users: HashMap::new(),
         }
     }
 
     /// Generates a page for the given entries with the specified rank offset.
+    ///
+    /// `rank_changes` is aligned with `entries`; missing items render without movement.
     pub async fn build(
         &mut self,
         entries: &[VoiceLeaderboardEntry],
         rank_offset: u32,
+        rank_changes: &[Option<RankChange>],
     ) -> Result<ImageGenerationResult, Error> {
         let fetch_start = Instant::now();
-        let http_client = self.image_gen.http_client.clone();
 
-        // Fetch missing users
-        self.fetch_missing_users(entries).await;
+        // Resolve names and avatars
+        self.resolve_users(entries).await;
 
         // Fetch missing avatars
-        let new_avatars = self.fetch_missing_avatars(entries, &http_client).await;
+        let new_avatars = self.fetch_missing_avatars(entries).await;
 
         trace!(
             "fetch_users_and_avatars_parallel {} ms",
@@ -68,13 +78,13 @@ impl<'a> LeaderboardImageBuilder<'a> {
 
         // Prepare entries for rendering
         let (entries_with_names, entries_for_image) =
-            self.prepare_render_data(entries, rank_offset, &new_avatars);
+            self.prepare_render_data(entries, rank_offset, rank_changes, &new_avatars);
 
         // Generate the image
         let init_start = Instant::now();
         let image_bytes = self
             .image_gen
-            .generate_leaderboard(&entries_for_image)
+            .generate_leaderboard(&entries_for_image, &self.ctx.data().renderer)
             .await
             .map_err(|e| {
                 AppError::internal_with_ref(format!("Failed to generate leaderboard image: {e}"))
@@ -87,69 +97,44 @@ impl<'a> LeaderboardImageBuilder<'a> {
         })
     }
 
-    /// Fetches user data for entries not in the cache.
-    async fn fetch_missing_users(&mut self, entries: &[VoiceLeaderboardEntry]) {
-        let missing_users: Vec<_> = entries
-            .iter()
-            .filter(|e| !self.user_cache.contains_key(&e.user_id))
-            .collect();
-
-        if missing_users.is_empty() {
-            return;
-        }
-
-        let user_futures: Vec<_> = missing_users
-            .iter()
-            .map(|entry| {
-                let user_id = UserId::new(entry.user_id);
-                let http = self.ctx.http();
-                async move {
-                    user_id
-                        .to_user(&http)
-                        .await
-                        .ok()
-                        .map(|u| (entry.user_id, u))
-                }
-            })
+    /// Resolves the users of the given entries through the shared resolver.
+    async fn resolve_users(&mut self, entries: &[VoiceLeaderboardEntry]) {
+        let user_ids: Vec<UserId> = entries.iter().map(|e| UserId::new(e.user_id)).collect();
+        let resolved = self
+            .ctx
+            .data()
+            .users
+            .resolve_many(
+                self.ctx.http(),
+                self.ctx.cache(),
+                self.ctx.guild_id(),
+                &user_ids,
+            )
+            .await;
+        self.users = resolved
+            .into_iter()
+            .map(|(user_id, user)| (user_id.get(), user))
             .collect();
-
-        let fetched_users: Vec<_> = futures::future::join_all(user_futures).await;
-        for (uid, user) in fetched_users.into_iter().flatten() {
-            self.user_cache.insert(uid, user);
-        }
     }
 
-    /// Fetches avatar images for users not in the cache.
+    /// Loads avatar images not yet rendered, from disk or Discord's CDN.
     async fn fetch_missing_avatars(
         &self,
         entries: &[VoiceLeaderboardEntry],
-        http_client: &wreq::Client,
     ) -> HashMap<u64, image::DynamicImage> {
+        let avatars = &self.ctx.data().avatars;
         let avatar_futures: Vec<_> = entries
             .iter()
             .filter_map(|entry| {
-                let user = self.user_cache.get(&entry.user_id)?;
-                let avatar_url = user.static_face();
+                let user = self.users.get(&entry.user_id)?;
+                let avatar_url = user.avatar_url();
 
                 if self.image_gen.has_avatar(&avatar_url) {
                     return None;
                 }
 
-                let client = http_client.clone();
                 let uid = entry.user_id;
-
-                Some(async move {
-                    let img = if let Ok(resp) = client.get(&avatar_url).send().await {
-                        if let Ok(bytes) = resp.bytes().await {
-                            image::load_from_memory(&bytes).ok()
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    };
-                    (uid, img)
-                })
+                Some(async move { (uid, avatars.get(uid, &avatar_url).await) })
             })
             .collect();
 
@@ -165,19 +150,21 @@ impl<'a> LeaderboardImageBuilder<'a> {
         &self,
         entries: &[VoiceLeaderboardEntry],
         rank_offset: u32,
+        rank_changes: &[Option<RankChange>],
         new_avatars: &HashMap<u64, image::DynamicImage>,
     ) -> (Vec<(VoiceLeaderboardEntry, String)>, Vec<LeaderboardEntry>) {
         let mut entries_with_names: Vec<(VoiceLeaderboardEntry, String)> = Vec::new();
         let mut entries_for_image: Vec<LeaderboardEntry> = Vec::new();
+        let open_sessions = self.ctx.data().service.voice_tracking.open_sessions();
+        let guild_id = self.ctx.guild_id().map(|id| id.get());
 
         for (idx, entry) in entries.iter().enumerate() {
             let rank = rank_offset + idx as u32 + 1;
 
             let (display_name, avatar_url, avatar_image) =
-                if let Some(user) = self.user_cache.get(&entry.user_id) {
-                    let url = user.static_face();
+                if let Some(user) = self.users.get(&entry.user_id) {
                     let img = new_avatars.get(&entry.user_id).cloned();
-                    (user.name.to_string(), url, img)
+                    (user.display_name.clone(), user.avatar_url(), img)
                 } else {
                     (format!("User {}", entry.user_id), String::new(), None)
                 };
@@ -190,6 +177,9 @@ impl<'a> LeaderboardImageBuilder<'a> {
                 avatar_url,
                 duration_seconds: entry.total_duration,
                 avatar_image,
+                rank_change: rank_changes.get(idx).copied().flatten(),
+                is_live: guild_id
+                    .is_some_and(|guild_id| open_sessions.is_live(guild_id, entry.user_id)),
             });
         }
 
@@ -15,7 +15,9 @@ use minijinja::context;
 use serde::Serialize;
 
 use crate::bot::command::voice::leaderboard::image_builder::LeaderboardEntry;
+use crate::bot::render::ImageRenderService;
 use crate::bot::utils::format_duration;
+use crate::update::voice_leaderboard::RankChange;
 
 const IMAGE_WIDTH: u32 = 500;
 const IMAGE_HEIGHT_PER_ENTRY: u32 = 64;
@@ -28,6 +30,11 @@ const BRONZE_COLOR: &str = "#EB459E";
 const TEXT_COLOR: &str = "#F2F3F5";
 const PROGRESS_COLOR: &str = "rgba(88, 101, 242, 0.235)";
 const PROGRESS_TOP_COLOR: &str = "rgba(88, 101, 242, 0.392)";
+const RANK_UP_COLOR: &str = "#57F287";
+const RANK_DOWN_COLOR: &str = "#ED4245";
+const RANK_SAME_COLOR: &str = "#949BA4";
+const RANK_NEW_COLOR: &str = "#5865F2";
+const LIVE_COLOR: &str = "#ED4245";
 
 /// Defines the exact data structure expected by the Minijinja SVG template.
 #[derive(Serialize)]
@@ -36,15 +43,22 @@ struct TemplateEntry {
     rank_color: &'static str,
     name: String,
     duration: String,
+    change: Option<String>,
+    change_color: &'static str,
+    /// In voice right now; drawn as a red dot on the avatar.
+    live: bool,
 
     // Layout metrics calculated by Rust
     card_y: u32,
     progress_width: u32,
     progress_color: &'static str,
     text_baseline: f32,
+    change_baseline: f32,
     avatar_y: u32,
     avatar_cx: u32,
     avatar_cy: u32,
+    live_cx: u32,
+    live_cy: u32,
 
     // Processed data
     avatar_b64: Option<String>,
@@ -101,7 +115,12 @@ impl LeaderboardImageGenerator {
         BASE64.encode(cursor.into_inner())
     }
 
-    pub async fn generate_leaderboard(&mut self, entries: &[LeaderboardEntry]) -> Result<Vec<u8>> {
+    /// Renders the leaderboard. Rasterizing runs on `renderer`, off the async runtime.
+    pub async fn generate_leaderboard(
+        &mut self,
+        entries: &[LeaderboardEntry],
+        renderer: &ImageRenderService,
+    ) -> Result<Vec<u8>> {
         let total_start = Instant::now();
 
         // 1. Ensure all avatars are cached
@@ -148,18 +167,36 @@ impl LeaderboardImageGenerator {
                     PROGRESS_COLOR
                 };
 
+                let (change, change_color) = match entry.rank_change {
+                    Some(RankChange::Up(places)) => (Some(format!("↑{places}")), RANK_UP_COLOR),
+                    Some(RankChange::Down(places)) => (Some(format!("↓{places}")), RANK_DOWN_COLOR),
+                    Some(RankChange::Same) => (Some("–".to_string()), RANK_SAME_COLOR),
+                    Some(RankChange::New) => (Some("NEW".to_string()), RANK_NEW_COLOR),
+                    None => (None, TEXT_COLOR),
+                };
+
+                let avatar_cx = 72 + (AVATAR_SIZE / 2);
+                let avatar_cy = avatar_y + (AVATAR_SIZE / 2);
+
                 TemplateEntry {
                     rank: entry.rank,
                     rank_color,
                     name: entry.display_name.clone(), // Minijinja auto-escapes HTML/XML by default
                     duration: format_duration(entry.duration_seconds),
+                    change,
+                    change_color,
+                    live: entry.is_live,
                     card_y: y + 2,
                     progress_width,
                     progress_color,
                     text_baseline: row_center_y as f32 + 6.0,
+                    change_baseline: row_center_y as f32 + 22.0,
                     avatar_y,
-                    avatar_cx: 72 + (AVATAR_SIZE / 2),
-                    avatar_cy: avatar_y + (AVATAR_SIZE / 2),
+                    avatar_cx,
+                    avatar_cy,
+                    // Bottom right of the avatar, like a Discord status
+                    live_cx: avatar_cx + 14,
+                    live_cy: avatar_cy + 14,
                     avatar_b64: self.avatar_cache.get(&entry.avatar_url).cloned(),
                 }
             })
@@ -173,15 +210,20 @@ impl LeaderboardImageGenerator {
             card_w => card_w,
             card_h => IMAGE_HEIGHT_PER_ENTRY - 4,
             time_x => time_x,
+            live_color => LIVE_COLOR,
             entries => template_entries,
         })?;
 
+        let png = renderer
+            .render("leaderboard", move || {
+                Self::svg_to_png(&svg, IMAGE_WIDTH, total_height)
+            })
+            .await?;
+
         trace!(
             "generate_leaderboard total {} ms",
             total_start.elapsed().as_millis()
         );
-
-        let png = Self::svg_to_png(&svg, IMAGE_WIDTH, total_height)?;
         Ok(png)
     }
 
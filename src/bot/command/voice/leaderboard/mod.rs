@@ -4,22 +4,28 @@ use std::time::Duration;
 use std::time::Instant;
 
 use log::trace;
+use serde_json::Value;
 
 use crate::bot::command::prelude::*;
 use crate::bot::command::voice::TimeRange;
 use crate::bot::command::voice::VoiceLeaderboardTimeRange;
 use crate::bot::command::voice::leaderboard::image_builder::LeaderboardImageBuilder;
+use crate::bot::view::export::ExportAction;
+use crate::bot::view::export::TabularExport;
 use crate::bot::view::pagination::PaginationAction;
 use crate::bot::view::pagination::PaginationView;
 use crate::entity::VoiceLeaderboardEntry;
 use crate::entity::VoiceLeaderboardOptBuilder;
 use crate::service::traits::VoiceTracker;
 use crate::update::Update;
+use crate::update::voice_leaderboard::RankChange;
 use crate::update::voice_leaderboard::VoiceLeaderboardCmd;
 use crate::update::voice_leaderboard::VoiceLeaderboardModel;
 use crate::update::voice_leaderboard::VoiceLeaderboardMsg;
 use crate::update::voice_leaderboard::VoiceLeaderboardUpdate;
 
+pub mod animate;
+pub mod animation;
 pub mod image_builder;
 pub mod image_generator;
 
@@ -29,12 +35,21 @@ pub const IMAGE_FILENAME: &str = "voice_leaderboard.jpg";
 /// Number of leaderboard entries per page.
 pub const LEADERBOARD_PER_PAGE: u32 = 10;
 
+/// Voice activity leaderboard
+///
+/// Show the current rankings or animate how they changed over time.
+#[poise::command(slash_command, subcommands("show", "animate::animate"))]
+pub async fn leaderboard(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
 /// Display the voice activity leaderboard
 ///
 /// Shows a ranked list of users by total time spent in voice channels.
-/// Includes your current rank position.
+/// Includes your current rank position and, for all-time standings, movement
+/// since last week.
 #[poise::command(slash_command)]
-pub async fn leaderboard(
+pub async fn show(
     ctx: Context<'_>,
     #[description = "Time period to filter voice activity. Defaults to \"This month\""]
     time_range: Option<VoiceLeaderboardTimeRange>,
@@ -147,7 +162,18 @@ impl CommandHandler for VoiceLeaderboardHandler<'_> {
         let entries = Self::fetch_entries(&ctx, self.time_range, false, None).await?;
         let guild_id = ctx.guild_id().map(|id| id.get()).unwrap_or(0);
         let author_id = ctx.author().id.get();
-        let model = VoiceLeaderboardModel::from_entries(entries, author_id, LEADERBOARD_PER_PAGE);
+        let mut model =
+            VoiceLeaderboardModel::from_entries(entries, author_id, LEADERBOARD_PER_PAGE);
+        model.time_range = self.time_range;
+
+        let snapshot = ctx
+            .data()
+            .service
+            .voice_tracking
+            .get_latest_leaderboard_snapshot(guild_id)
+            .await
+            .map_err(Error::from)?;
+        VoiceLeaderboardUpdate::update(VoiceLeaderboardMsg::SetSnapshot(snapshot), &mut model);
 
         let mut view = VoiceLeaderboardView::new(model, &ctx, guild_id, author_id);
         view.generate_img().await?;
@@ -168,7 +194,6 @@ pub struct VoiceLeaderboardView<'a> {
     pub service: std::sync::Arc<dyn VoiceTracker>,
     pub guild_id: u64,
     pub author_id: u64,
-    pub http: std::sync::Arc<poise::serenity_prelude::Http>,
     pub pagination: bool,
 }
 
@@ -187,7 +212,6 @@ impl<'a> VoiceLeaderboardView<'a> {
             service: ctx.data().service.voice_tracking.clone(),
             guild_id,
             author_id,
-            http: ctx.serenity_context().http.clone(),
             img_builder: LeaderboardImageBuilder::new(ctx),
         }
     }
@@ -197,7 +221,11 @@ impl<'a> VoiceLeaderboardView<'a> {
         if !self.model.is_empty() {
             let entries = self.model.current_page_entries();
             let rank_offset = self.model.current_page_rank_offset();
-            let res = self.img_builder.build(entries, rank_offset).await;
+            let rank_changes = self.model.current_page_rank_changes();
+            let res = self
+                .img_builder
+                .build(entries, rank_offset, &rank_changes)
+                .await;
             if let Ok(img) = res {
                 self.lb_img = Some(img.image_bytes);
             }
@@ -238,6 +266,44 @@ impl<'a> VoiceLeaderboardView<'a> {
         self.generate_img().await?;
         Ok(())
     }
+
+    /// Builds an export of every entry for the current time range and mode.
+    ///
+    /// Names come from the caches only, since the interaction must be answered quickly.
+    fn export(&self, ctx: &Context<'_>) -> TabularExport {
+        let name = if self.model.is_partner_mode {
+            "voice_partners"
+        } else {
+            "voice_leaderboard"
+        };
+        let user_ids: Vec<UserId> = self
+            .model
+            .entries
+            .iter()
+            .map(|e| UserId::new(e.user_id))
+            .collect();
+        let names = ctx
+            .data()
+            .users
+            .resolve_cached(ctx.cache(), ctx.guild_id(), &user_ids);
+
+        let mut export =
+            TabularExport::new(name, &["rank", "user_id", "display_name", "total_seconds"]);
+        for (i, entry) in self.model.entries.iter().enumerate() {
+            let display_name = names
+                .get(&UserId::new(entry.user_id))
+                .map(|user| Value::from(user.display_name.clone()))
+                .unwrap_or_default();
+            export.push_row([
+                Value::from(i + 1),
+                // IDs as text, since spreadsheets round large numbers
+                Value::from(entry.user_id.to_string()),
+                display_name,
+                Value::from(entry.total_duration),
+            ]);
+        }
+        export
+    }
 }
 
 #[async_trait::async_trait]
@@ -283,8 +349,19 @@ impl ViewHandler for VoiceLeaderboardView<'_> {
             }
             SelectUser => {
                 if let Some(user_id) = ctx.user_select_values().and_then(|v| v.first().copied())
-                    && let Ok(user) = user_id.to_user(&self.http).await
+                    && let Some(resolved) = ctx
+                        .poise
+                        .data()
+                        .users
+                        .resolve(
+                            ctx.poise.http(),
+                            ctx.poise.cache(),
+                            ctx.poise.guild_id(),
+                            user_id,
+                        )
+                        .await
                 {
+                    let user = resolved.user;
                     self.target_user = Some(user.clone());
                     let cmd = VoiceLeaderboardUpdate::update(
                         VoiceLeaderboardMsg::SetTargetUser(Some(user.id.get())),
@@ -293,6 +370,10 @@ impl ViewHandler for VoiceLeaderboardView<'_> {
                     fetch_new = matches!(cmd, VoiceLeaderboardCmd::RefetchData);
                 }
             }
+            Export(action) => {
+                let export = self.export(&ctx.poise);
+                return ctx.send_file(export.to_attachment((*action).into())).await;
+            }
         }
 
         if fetch_new {
@@ -361,6 +442,30 @@ impl ViewRender for VoiceLeaderboardView<'_> {
             )),
         ));
 
+        if self.model.shows_movement()
+            && let Some(taken_at) = self.model.snapshot_taken_at
+        {
+            let mut text = format!(
+                "-# Rank changes since last week's snapshot (<t:{}:D>)",
+                taken_at.timestamp()
+            );
+            if let Some(gainer) = self.model.biggest_gainer() {
+                let movement = match gainer.change {
+                    RankChange::Up(places) => format!(", up {places}"),
+                    RankChange::New => ", new on the board".to_string(),
+                    RankChange::Down(_) | RankChange::Same => String::new(),
+                };
+                text.push_str(&format!(
+                    "\n🚀 Biggest gainer: <@{}> with **+{}**{movement}",
+                    gainer.user_id,
+                    format_duration(gainer.gained),
+                ));
+            }
+            container.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(text),
+            ));
+        }
+
         container.push(CreateContainerComponent::Separator(CreateSeparator::new(
             true,
         )));
@@ -390,8 +495,12 @@ impl ViewRender for VoiceLeaderboardView<'_> {
             .label(toggle_label)
             .style(poise::serenity_prelude::ButtonStyle::Primary);
 
+        let mut buttons = vec![toggle_button];
+        if !self.model.is_empty() {
+            buttons.extend(ExportAction::create_buttons(registry, Export));
+        }
         container.push(CreateContainerComponent::ActionRow(
-            CreateActionRow::Buttons(vec![toggle_button].into()),
+            CreateActionRow::Buttons(buttons.into()),
         ));
 
         let time_range_menu = registry
@@ -469,6 +578,7 @@ action_extends! {
         TimeRange,
         ToggleMode,
         SelectUser,
+        Export(ExportAction),
     }
 }
 
@@ -683,6 +793,8 @@ mod tests {
             avatar_url: "https://cdn.discordapp.com/avatars/123/abc.png".to_string(),
             duration_seconds: 3600,
             avatar_image: None,
+            rank_change: None,
+            is_live: false,
         };
 
         let cloned = entry.clone();
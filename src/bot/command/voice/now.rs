@@ -0,0 +1,272 @@
+//! Voice now subcommand.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::voice::TimeRange;
+use crate::bot::command::voice::VoiceLeaderboardTimeRange;
+use crate::entity::VoiceLeaderboardOptBuilder;
+use crate::service::traits::VoiceTracker;
+
+/// Members listed per channel before the rest are summarized.
+const MEMBERS_PER_CHANNEL: usize = 20;
+
+/// Show who is in voice right now
+///
+/// Lists members in tracked voice channels by channel, with the length of
+/// their current session and their voice time today.
+#[poise::command(slash_command)]
+pub async fn now(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::VoiceNow).await?;
+    Ok(())
+}
+
+handler! { pub struct VoiceNowHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for VoiceNowHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        ctx.defer().await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let mut view = VoiceNowView {
+            service: ctx.data().service.voice_tracking.clone(),
+            guild_id,
+            channels: Vec::new(),
+            updated_at: Utc::now().timestamp(),
+            disabled: false,
+        };
+        view.refresh().await?;
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+/// A member in a tracked voice channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VoiceNowMember {
+    pub user_id: u64,
+    pub channel_id: u64,
+    /// Length of the current session, across channel moves.
+    pub session_secs: i64,
+    /// Voice time today, including the current session.
+    pub today_secs: i64,
+}
+
+pub struct VoiceNowView {
+    pub service: Arc<dyn VoiceTracker>,
+    pub guild_id: u64,
+    /// Members by channel, busiest channel first.
+    pub channels: Vec<(u64, Vec<VoiceNowMember>)>,
+    /// Unix timestamp of the last refresh.
+    pub updated_at: i64,
+    /// Whether the view timed out and stopped taking refreshes.
+    pub disabled: bool,
+}
+
+impl VoiceNowView {
+    /// Reloads the open sessions of the guild and the members' totals for today.
+    async fn refresh(&mut self) -> Result<(), Error> {
+        let now = Utc::now();
+        let open = self.service.open_sessions().by_guild(self.guild_id);
+
+        let today: HashMap<u64, i64> = if open.is_empty() {
+            HashMap::new()
+        } else {
+            let (since, until) = VoiceLeaderboardTimeRange::Today.to_range();
+            let options = VoiceLeaderboardOptBuilder::default()
+                .guild_id(self.guild_id)
+                .limit(Some(u32::MAX))
+                .since(Some(since))
+                .until(Some(until))
+                .build()
+                .map_err(AppError::from)?;
+            self.service
+                .get_leaderboard_withopt(&options)
+                .await?
+                .into_iter()
+                .map(|entry| (entry.user_id, entry.total_duration))
+                .collect()
+        };
+
+        let members = open
+            .into_iter()
+            .map(|(user_id, session)| VoiceNowMember {
+                user_id,
+                channel_id: session.channel_id,
+                session_secs: session.duration_secs(&now),
+                today_secs: today.get(&user_id).copied().unwrap_or(0),
+            })
+            .collect();
+        self.channels = group_by_channel(members);
+        self.updated_at = now.timestamp();
+        Ok(())
+    }
+
+    /// Formats a channel's members, longest session first.
+    fn format_channel(channel_id: u64, members: &[VoiceNowMember]) -> String {
+        let mut text = format!(
+            "**<#{}>** · {} member{}",
+            channel_id,
+            members.len(),
+            if members.len() == 1 { "" } else { "s" }
+        );
+        for member in members.iter().take(MEMBERS_PER_CHANNEL) {
+            text.push_str(&format!(
+                "\n🔴 <@{}> — **{}** · today **{}**",
+                member.user_id,
+                format_duration(member.session_secs),
+                format_duration(member.today_secs),
+            ));
+        }
+        if members.len() > MEMBERS_PER_CHANNEL {
+            text.push_str(&format!(
+                "\n-# …and {} more",
+                members.len() - MEMBERS_PER_CHANNEL
+            ));
+        }
+        text
+    }
+}
+
+/// Groups members by channel. Channels with more members come first, and members
+/// with longer sessions come first within a channel.
+fn group_by_channel(members: Vec<VoiceNowMember>) -> Vec<(u64, Vec<VoiceNowMember>)> {
+    let mut by_channel: HashMap<u64, Vec<VoiceNowMember>> = HashMap::new();
+    for member in members {
+        by_channel
+            .entry(member.channel_id)
+            .or_default()
+            .push(member);
+    }
+    let mut channels: Vec<(u64, Vec<VoiceNowMember>)> = by_channel.into_iter().collect();
+    for (_, members) in &mut channels {
+        members.sort_by(|a, b| {
+            b.session_secs
+                .cmp(&a.session_secs)
+                .then(a.user_id.cmp(&b.user_id))
+        });
+    }
+    channels.sort_by(|(a_id, a), (b_id, b)| b.len().cmp(&a.len()).then(a_id.cmp(b_id)));
+    channels
+}
+
+action_enum! {
+    VoiceNowAction {
+        #[label = "↻ Refresh"]
+        Refresh,
+    }
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for VoiceNowView {
+    type Action = VoiceNowAction;
+    async fn handle(&mut self, ctx: ViewContext<'_, VoiceNowAction>) -> Result<ViewCmd, Error> {
+        match ctx.action() {
+            VoiceNowAction::Refresh => self.refresh().await?,
+        }
+        Ok(ViewCmd::Render)
+    }
+
+    async fn on_timeout(&mut self) -> Result<ViewCmd, Error> {
+        self.disabled = true;
+        Ok(ViewCmd::RenderOnce)
+    }
+}
+
+impl ViewRender for VoiceNowView {
+    type Action = VoiceNowAction;
+    fn render(&self, registry: &mut ActionRegistry<VoiceNowAction>) -> ResponseKind<'_> {
+        let members: usize = self.channels.iter().map(|(_, members)| members.len()).sum();
+        let header = format!(
+            "### In Voice Now\n-# {} member{} in {} channel{} · updated <t:{}:R>",
+            members,
+            if members == 1 { "" } else { "s" },
+            self.channels.len(),
+            if self.channels.len() == 1 { "" } else { "s" },
+            self.updated_at,
+        );
+        let mut sections = vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(header),
+        )];
+
+        if self.channels.is_empty() {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new("> 🛈  Nobody is in a tracked voice channel."),
+            ));
+        }
+        for (channel_id, members) in &self.channels {
+            sections.push(CreateContainerComponent::TextDisplay(
+                CreateTextDisplay::new(Self::format_channel(*channel_id, members)),
+            ));
+        }
+
+        if !self.disabled {
+            sections.push(CreateContainerComponent::ActionRow(
+                CreateActionRow::Buttons(
+                    vec![
+                        registry
+                            .register(VoiceNowAction::Refresh)
+                            .as_button()
+                            .style(ButtonStyle::Secondary),
+                    ]
+                    .into(),
+                ),
+            ));
+        }
+
+        vec![CreateComponent::Container(CreateContainer::new(sections))].into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(user_id: u64, channel_id: u64, session_secs: i64) -> VoiceNowMember {
+        VoiceNowMember {
+            user_id,
+            channel_id,
+            session_secs,
+            today_secs: session_secs,
+        }
+    }
+
+    #[test]
+    fn group_by_channel_orders_busiest_channel_and_longest_session_first() {
+        let channels = group_by_channel(vec![
+            member(1, 10, 60),
+            member(2, 20, 30),
+            member(3, 20, 90),
+        ]);
+
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].0, 20);
+        assert_eq!(
+            channels[0].1.iter().map(|m| m.user_id).collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+        assert_eq!(channels[1].0, 10);
+    }
+
+    #[test]
+    fn format_channel_summarizes_members_past_the_cap() {
+        let members: Vec<VoiceNowMember> = (0..MEMBERS_PER_CHANNEL as u64 + 3)
+            .map(|user_id| member(user_id, 10, 60))
+            .collect();
+
+        let text = VoiceNowView::format_channel(10, &members);
+
+        assert_eq!(text.matches("🔴").count(), MEMBERS_PER_CHANNEL);
+        assert!(text.ends_with("…and 3 more"));
+    }
+}
@@ -0,0 +1,92 @@
+//! Voice report subcommand.
+
+use std::collections::HashMap;
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::voice::TimeRange;
+use crate::bot::command::voice::VoiceLeaderboardTimeRange;
+use crate::bot::report::REPORT_FILENAME;
+use crate::bot::report::VoiceReportService;
+
+/// Show a voice activity report
+///
+/// Renders a summary of the server's voice activity: top members, activity by
+/// hour and the most used channels.
+#[poise::command(slash_command)]
+pub async fn report(
+    ctx: Context<'_>,
+    #[description = "Period to report on. Defaults to \"Past 7 days\""] time_range: Option<
+        VoiceLeaderboardTimeRange,
+    >,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?;
+    let time_range = time_range.unwrap_or(VoiceLeaderboardTimeRange::Past7Days);
+    let (since, until) = time_range.to_range();
+
+    let reports = VoiceReportService::new(
+        ctx.data().service.voice_tracking.clone(),
+        ctx.data().renderer.clone(),
+    );
+    let report = reports
+        .build(guild_id.get(), since, until)
+        .await
+        .map_err(AppError::internal_with_ref)?;
+
+    let user_ids: Vec<UserId> = report
+        .members
+        .iter()
+        .map(|(user_id, _)| UserId::new(*user_id))
+        .collect();
+    let user_names: HashMap<u64, String> = ctx
+        .data()
+        .users
+        .resolve_many(ctx.http(), ctx.cache(), Some(guild_id), &user_ids)
+        .await
+        .into_iter()
+        .map(|(user_id, user)| (user_id.get(), user.display_name))
+        .collect();
+    let (guild_name, channel_names) = match ctx.guild() {
+        Some(guild) => (
+            guild.name.to_string(),
+            report
+                .channels
+                .iter()
+                .filter_map(|(channel_id, _)| {
+                    let channel = guild.channels.get(&ChannelId::new(*channel_id))?;
+                    Some((*channel_id, channel.name.to_string()))
+                })
+                .collect(),
+        ),
+        None => ("Voice Report".to_string(), HashMap::new()),
+    };
+    let report = report
+        .with_title(guild_name)
+        .with_names(user_names, channel_names);
+
+    let image = reports
+        .render(report)
+        .await
+        .map_err(AppError::internal_with_ref)?;
+
+    let container = CreateComponent::Container(CreateContainer::new(vec![
+        CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+            "### Voice Report\n-# Time Range: **{}** — <t:{}:f> to <t:{}:R>",
+            time_range.name(),
+            since.timestamp(),
+            until.timestamp(),
+        ))),
+        CreateContainerComponent::MediaGallery(CreateMediaGallery::new(vec![
+            CreateMediaGalleryItem::new(CreateUnfurledMediaItem::new(format!(
+                "attachment://{REPORT_FILENAME}"
+            ))),
+        ])),
+    ]));
+    let response: ResponseKind<'_> = vec![container].into();
+    let reply: poise::CreateReply<'_> = response.into();
+    ctx.send(reply.attachment(CreateAttachment::bytes(image, REPORT_FILENAME)))
+        .await?;
+
+    Ok(())
+}
@@ -3,11 +3,50 @@
 use std::time::Duration;
 
 use crate::bot::command::prelude::*;
+use crate::bot::command::settings::open_staged_settings;
+use crate::bot::command::settings::save_staged_settings;
 use crate::entity::ServerSettings;
+use crate::update::Staged;
+
+/// Minimum session lengths offered in the settings view, in seconds.
+const MIN_SESSION_OPTIONS: [(u32, &str); 4] = [
+    (0, "Off"),
+    (30, "30 seconds"),
+    (60, "1 minute"),
+    (300, "5 minutes"),
+];
+
+/// Channel multipliers offered in the settings view, in percent.
+const CHANNEL_WEIGHT_OPTIONS: [u32; 6] = [0, 50, 100, 150, 200, 300];
+
+/// Idle-alone thresholds offered in the settings view, in minutes.
+const IDLE_ALONE_OPTIONS: [(u32, &str); 5] = [
+    (0, "Off"),
+    (15, "15 minutes"),
+    (30, "30 minutes"),
+    (60, "1 hour"),
+    (120, "2 hours"),
+];
+
+/// Shares of idle-alone time that still count, in percent.
+const IDLE_ALONE_PERCENT_OPTIONS: [(u32, &str); 3] =
+    [(0, "Excluded"), (25, "Counts 25%"), (50, "Counts 50%")];
+
+/// Monthly server goals offered in the settings view, in hours.
+const GOAL_OPTIONS: [(u32, &str); 6] = [
+    (0, "Off"),
+    (100, "100 hours"),
+    (250, "250 hours"),
+    (500, "500 hours"),
+    (1000, "1,000 hours"),
+    (2500, "2,500 hours"),
+];
 
 /// Configure voice tracking settings for this server
 ///
-/// Enable or disable voice channel activity tracking.
+/// Enable or disable voice channel activity tracking, choose which
+/// sessions are recorded and how long they are kept, and weight time spent
+/// in specific channels.
 /// Only server administrators can use this command.
 #[poise::command(
     slash_command,
@@ -29,23 +68,24 @@ impl CommandHandler for VoiceSettingsHandler<'_> {
 
         let service = ctx.data().service.voice_tracking.clone();
 
-        let settings = service
-            .get_server_settings(guild_id)
+        let open_flags = service
+            .count_open_flags(guild_id)
             .await
             .map_err(Error::from)?;
 
-        let view = SettingsVoiceHandler { settings };
+        let view = SettingsVoiceHandler {
+            settings: open_staged_settings(&ctx.data().service, guild_id).await?,
+            guild_id,
+            weight_channel: None,
+            open_flags,
+            default_retention_days: ctx.data().config.retention.voice_session_days,
+        };
 
         let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
 
+        // Unsaved changes are discarded when the view exits
         engine.run().await?;
 
-        // Save the settings once the run exits
-        service
-            .update_server_settings(guild_id, engine.handler.settings.clone())
-            .await
-            .map_err(Error::from)?;
-
         Ok(())
     }
 }
@@ -53,6 +93,22 @@ impl CommandHandler for VoiceSettingsHandler<'_> {
 action_enum! {
     SettingsVoiceAction {
         ToggleEnabled,
+        MinSession,
+        ToggleBots,
+        ToggleStage,
+        WeightChannel,
+        Weight,
+        IdleAlone,
+        IdleAlonePercent,
+        Goal,
+        GoalChannel,
+        ToggleGlobal,
+        ToggleGlobalAnonymous,
+        Retention,
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
         #[label = "❮ Back"]
         Back,
         #[label = "🛈 About"]
@@ -61,7 +117,21 @@ action_enum! {
 }
 
 pub struct SettingsVoiceHandler {
-    pub settings: ServerSettings,
+    pub settings: Staged<ServerSettings>,
+    pub guild_id: u64,
+    /// Channel whose multiplier the weight select edits.
+    pub weight_channel: Option<u64>,
+    /// Flagged sessions awaiting review in `/vc admin review`.
+    pub open_flags: u32,
+    /// The bot's session retention in days, which the server can only shorten.
+    pub default_retention_days: u32,
+}
+
+impl SettingsVoiceHandler {
+    /// Formats a percent weight as a multiplier, e.g. `150` as `1.5×`.
+    fn format_weight(weight_percent: u32) -> String {
+        format!("{}×", f64::from(weight_percent) / 100.0)
+    }
 }
 
 #[async_trait::async_trait]
@@ -77,6 +147,106 @@ impl ViewHandler for SettingsVoiceHandler {
                 self.settings.voice.enabled = Some(!current);
                 ViewCmd::Render
             }
+            SettingsVoiceAction::MinSession => {
+                let secs = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|secs| secs.parse::<u32>().ok()));
+                if let Some(secs) = secs {
+                    self.settings.voice.min_session_secs = (secs > 0).then_some(secs);
+                }
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::ToggleBots => {
+                let current = self.settings.voice.tracks_bots();
+                self.settings.voice.track_bots = Some(!current);
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::ToggleStage => {
+                let current = self.settings.voice.tracks_stage_channels();
+                self.settings.voice.track_stage_channels = Some(!current);
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::WeightChannel => {
+                self.weight_channel = ctx
+                    .channel_select_values()
+                    .and_then(|v| v.first().map(|id| id.get()));
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::Weight => {
+                let weight = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|weight| weight.parse::<u32>().ok()));
+                if let (Some(channel_id), Some(weight)) = (self.weight_channel, weight) {
+                    self.settings.voice.set_channel_weight(channel_id, weight);
+                }
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::IdleAlone => {
+                let mins = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|mins| mins.parse::<u32>().ok()));
+                if let Some(mins) = mins {
+                    self.settings.voice.idle_alone_mins = (mins > 0).then_some(mins);
+                }
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::IdleAlonePercent => {
+                let percent = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|percent| percent.parse::<u32>().ok()));
+                if let Some(percent) = percent {
+                    self.settings.voice.idle_alone_percent = (percent > 0).then_some(percent);
+                }
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::Goal => {
+                let hours = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|hours| hours.parse::<u32>().ok()));
+                if let Some(hours) = hours {
+                    self.settings.voice.monthly_goal_hours = (hours > 0).then_some(hours);
+                }
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::GoalChannel => {
+                self.settings.voice.goal_channel_id = ctx
+                    .channel_select_values()
+                    .and_then(|v| v.first().map(|id| id.to_string()));
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::ToggleGlobal => {
+                let current = self.settings.voice.joins_global_leaderboard();
+                self.settings.voice.global_leaderboard = Some(!current);
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::ToggleGlobalAnonymous => {
+                let current = self.settings.voice.is_global_leaderboard_anonymous();
+                self.settings.voice.global_leaderboard_anonymous = Some(!current);
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::Retention => {
+                let days = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|days| days.parse::<u32>().ok()));
+                if let Some(days) = days {
+                    self.settings.voice.session_retention_days = (days > 0).then_some(days);
+                }
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::Save => {
+                save_staged_settings(
+                    &ctx.poise.data().service,
+                    self.guild_id,
+                    ctx.poise.author().id.get(),
+                    &mut self.settings,
+                )
+                .await?;
+                ViewCmd::Render
+            }
+            SettingsVoiceAction::Revert => {
+                self.settings.revert();
+                ViewCmd::Render
+            }
             SettingsVoiceAction::Back => {
                 ctx.coordinator.navigate(Navigation::SettingsMain).await;
                 ViewCmd::Exit
@@ -93,16 +263,26 @@ impl ViewHandler for SettingsVoiceHandler {
 impl ViewRender for SettingsVoiceHandler {
     type Action = SettingsVoiceAction;
     fn render(&self, registry: &mut ActionRegistry<SettingsVoiceAction>) -> ResponseKind<'_> {
-        let is_enabled = self.settings.voice.enabled.unwrap_or(true);
+        let voice = &self.settings.voice;
+        let is_enabled = voice.is_enabled();
+        let is_dirty = self.settings.is_dirty();
 
-        let status_text = format!(
-            "-# **Settings > Voice**\n## Voice Tracking Settings\n\n> 🛈  {}",
+        let mut status_text = format!(
+            "-# **Settings > Voice**{}\n## Voice Tracking Settings\n\n> 🛈  {}",
+            unsaved_marker(is_dirty),
             if is_enabled {
                 "Voice tracking is **active**."
             } else {
                 "Voice tracking is **paused**."
             }
         );
+        if self.open_flags > 0 {
+            status_text.push_str(&format!(
+                "\n> ⚠  **{}** flagged session{} awaiting review in `/vc admin review`.",
+                self.open_flags,
+                if self.open_flags == 1 { "" } else { "s" }
+            ));
+        }
 
         let enabled_button = registry
             .register(SettingsVoiceAction::ToggleEnabled)
@@ -114,15 +294,293 @@ impl ViewRender for SettingsVoiceHandler {
                 ButtonStyle::Success
             });
 
+        let min_session_text = "### Minimum Session Length\n\n> 🛈  Sessions shorter than this are discarded, so quick channel hops don't count.";
+        let min_session_secs = voice.min_session_secs();
+        let min_session_options: Vec<_> = MIN_SESSION_OPTIONS
+            .iter()
+            .map(|(secs, name)| {
+                CreateSelectMenuOption::new(*name, secs.to_string())
+                    .default_selection(*secs == min_session_secs)
+            })
+            .collect();
+        let min_session_select = registry
+            .register(SettingsVoiceAction::MinSession)
+            .as_select(CreateSelectMenuKind::String {
+                options: min_session_options.into(),
+            })
+            .placeholder("Select minimum session length");
+
+        let tracking_text = format!(
+            "### Tracked Sessions\n\n> 🛈  Bots are **{}**. Stage channels are **{}**.",
+            if voice.tracks_bots() {
+                "tracked"
+            } else {
+                "ignored"
+            },
+            if voice.tracks_stage_channels() {
+                "tracked"
+            } else {
+                "ignored"
+            }
+        );
+        let bots_button = registry
+            .register(SettingsVoiceAction::ToggleBots)
+            .as_button()
+            .label(if voice.tracks_bots() {
+                "Ignore Bots"
+            } else {
+                "Track Bots"
+            })
+            .style(ButtonStyle::Secondary);
+        let stage_button = registry
+            .register(SettingsVoiceAction::ToggleStage)
+            .as_button()
+            .label(if voice.tracks_stage_channels() {
+                "Ignore Stage Channels"
+            } else {
+                "Track Stage Channels"
+            })
+            .style(ButtonStyle::Secondary);
+
+        let weights_list = voice
+            .channel_weights
+            .iter()
+            .flatten()
+            .map(|(channel_id, weight)| {
+                format!(
+                    "- <#{channel_id}> counts **{}**",
+                    Self::format_weight(*weight)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let weights_text = format!(
+            "### Channel Multipliers\n\n> 🛈  Time in a weighted channel is scaled on the leaderboard. Other channels count at 1×.\n{weights_list}"
+        );
+        let weight_channel_select = registry
+            .register(SettingsVoiceAction::WeightChannel)
+            .as_select(CreateSelectMenuKind::Channel {
+                channel_types: Some(vec![ChannelType::Voice, ChannelType::Stage].into()),
+                default_channels: Some(
+                    self.weight_channel
+                        .map(GenericChannelId::new)
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+            })
+            .placeholder("Select a channel to weight");
+        let current_weight = self.weight_channel.map(|id| voice.channel_weight(id));
+        let weight_options: Vec<_> = CHANNEL_WEIGHT_OPTIONS
+            .iter()
+            .map(|weight| {
+                CreateSelectMenuOption::new(Self::format_weight(*weight), weight.to_string())
+                    .default_selection(Some(*weight) == current_weight)
+            })
+            .collect();
+        let weight_select = registry
+            .register(SettingsVoiceAction::Weight)
+            .as_select(CreateSelectMenuKind::String {
+                options: weight_options.into(),
+            })
+            .placeholder("Select multiplier")
+            .disabled(self.weight_channel.is_none());
+
+        let idle_discount = voice.idle_discount();
+        let idle_text = format!(
+            "### Idle Alone\n\n> 🛈  {}",
+            match idle_discount {
+                Some(discount) if discount.weight_percent == 0 => format!(
+                    "Time alone in a channel past **{} minutes** is excluded from the leaderboard.",
+                    discount.after_secs / 60
+                ),
+                Some(discount) => format!(
+                    "Time alone in a channel past **{} minutes** counts **{}%** on the leaderboard.",
+                    discount.after_secs / 60,
+                    discount.weight_percent
+                ),
+                None => "Time alone in a channel counts in full on the leaderboard.".to_string(),
+            }
+        );
+        let idle_mins = idle_discount.map_or(0, |discount| discount.after_secs / 60);
+        let idle_options: Vec<_> = IDLE_ALONE_OPTIONS
+            .iter()
+            .map(|(mins, name)| {
+                CreateSelectMenuOption::new(*name, mins.to_string())
+                    .default_selection(*mins == idle_mins)
+            })
+            .collect();
+        let idle_select = registry
+            .register(SettingsVoiceAction::IdleAlone)
+            .as_select(CreateSelectMenuKind::String {
+                options: idle_options.into(),
+            })
+            .placeholder("Select idle-alone threshold");
+        let idle_percent = idle_discount.map_or(0, |discount| discount.weight_percent);
+        let idle_percent_options: Vec<_> = IDLE_ALONE_PERCENT_OPTIONS
+            .iter()
+            .map(|(percent, name)| {
+                CreateSelectMenuOption::new(*name, percent.to_string())
+                    .default_selection(*percent == idle_percent)
+            })
+            .collect();
+        let idle_percent_select = registry
+            .register(SettingsVoiceAction::IdleAlonePercent)
+            .as_select(CreateSelectMenuKind::String {
+                options: idle_percent_options.into(),
+            })
+            .placeholder("Select how much idle time counts")
+            .disabled(idle_discount.is_none());
+
+        let goal_hours = voice.monthly_goal_hours();
+        let goal_text = format!(
+            "### Monthly Goal\n\n> 🛈  {}",
+            match (goal_hours, voice.goal_channel_id.as_deref()) {
+                (Some(hours), Some(channel_id)) => format!(
+                    "The server aims for **{hours} hours** in voice each month. Reaching it is announced in <#{channel_id}>."
+                ),
+                (Some(hours), None) => format!(
+                    "The server aims for **{hours} hours** in voice each month. Select a channel to announce it when reached."
+                ),
+                (None, _) =>
+                    "No goal is set. Progress is shown in `/vc stats` once one is.".to_string(),
+            }
+        );
+        let goal_options: Vec<_> = GOAL_OPTIONS
+            .iter()
+            .map(|(hours, name)| {
+                CreateSelectMenuOption::new(*name, hours.to_string())
+                    .default_selection(*hours == goal_hours.unwrap_or(0))
+            })
+            .collect();
+        let goal_select = registry
+            .register(SettingsVoiceAction::Goal)
+            .as_select(CreateSelectMenuKind::String {
+                options: goal_options.into(),
+            })
+            .placeholder("Select monthly goal");
+        let goal_channel_select = registry
+            .register(SettingsVoiceAction::GoalChannel)
+            .as_select(CreateSelectMenuKind::Channel {
+                channel_types: Some(vec![ChannelType::Text, ChannelType::News].into()),
+                default_channels: Some(
+                    voice
+                        .goal_channel_id
+                        .as_deref()
+                        .and_then(|id| id.parse::<u64>().ok())
+                        .map(GenericChannelId::new)
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+            })
+            .placeholder("Select announcement channel")
+            .disabled(goal_hours.is_none());
+
+        let global_text = format!(
+            "### Global Leaderboard\n\n> 🛈  {}",
+            match (
+                voice.joins_global_leaderboard(),
+                voice.is_global_leaderboard_anonymous()
+            ) {
+                (true, false) =>
+                    "Voice time here counts on the bot owner's cross-server leaderboard, with this server's name.",
+                (true, true) =>
+                    "Voice time here counts on the bot owner's cross-server leaderboard, without this server's or its members' names.",
+                (false, _) => "Voice time here is not shared outside this server.",
+            }
+        );
+        let global_button = registry
+            .register(SettingsVoiceAction::ToggleGlobal)
+            .as_button()
+            .label(if voice.joins_global_leaderboard() {
+                "Leave Global Leaderboard"
+            } else {
+                "Join Global Leaderboard"
+            })
+            .style(ButtonStyle::Secondary);
+        let global_anonymous_button = registry
+            .register(SettingsVoiceAction::ToggleGlobalAnonymous)
+            .as_button()
+            .label(if voice.is_global_leaderboard_anonymous() {
+                "Show Names"
+            } else {
+                "Hide Names"
+            })
+            .style(ButtonStyle::Secondary)
+            .disabled(!voice.joins_global_leaderboard());
+
+        let retention_days = voice.session_retention_days(self.default_retention_days);
+        let retention_text = format!(
+            "### Data Retention\n\n> 🛈  {}",
+            if retention_days == 0 {
+                "Ended voice sessions are kept **forever**.".to_string()
+            } else {
+                format!(
+                    "Ended voice sessions are deleted after **{}**, and stop counting on leaderboards and stats.",
+                    format_retention_days(retention_days)
+                )
+            }
+        );
+        let retention_options: Vec<_> = RETENTION_OPTIONS
+            .iter()
+            .filter(|(days, _)| {
+                *days == 0
+                    || self.default_retention_days == 0
+                    || *days < self.default_retention_days
+            })
+            .map(|(days, name)| {
+                CreateSelectMenuOption::new(*name, days.to_string())
+                    .default_selection(*days == voice.session_retention_days.unwrap_or(0))
+            })
+            .collect();
+        let retention_select = registry
+            .register(SettingsVoiceAction::Retention)
+            .as_select(CreateSelectMenuKind::String {
+                options: retention_options.into(),
+            })
+            .placeholder("Select how long sessions are kept");
+
         let container = CreateComponent::Container(CreateContainer::new(vec![
             CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
             CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
                 vec![enabled_button].into(),
             )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(min_session_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(min_session_select)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(tracking_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
+                vec![bots_button, stage_button].into(),
+            )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(weights_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(weight_channel_select)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(weight_select)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(idle_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(idle_select)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(idle_percent_select)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(goal_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(goal_select)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(goal_channel_select)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(global_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::Buttons(
+                vec![global_button, global_anonymous_button].into(),
+            )),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(retention_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(retention_select)),
         ]));
 
         let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
             vec![
+                registry
+                    .register(SettingsVoiceAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsVoiceAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
                 registry
                     .register(SettingsVoiceAction::Back)
                     .as_button()
@@ -5,18 +5,38 @@ use std::collections::HashSet;
 
 use chrono::DateTime;
 use chrono::Datelike;
+use chrono::NaiveDate;
 use chrono::Timelike;
 use chrono::Utc;
+use contribution_grid::ContributionGraph;
+use contribution_grid::builtins::Strategy;
+use contribution_grid::builtins::Theme;
 use image::ImageEncoder;
 use plotters::prelude::*;
 
 use crate::bot::command::voice::GuildStatType;
 use crate::bot::command::voice::VoiceStatsTimeRange;
+use crate::bot::command::voice::stats::svg_chart;
+use crate::bot::command::voice::stats::svg_chart::ChartTheme;
+use crate::config::ChartBackend;
 use crate::entity::VoiceSessionsEntity;
 
-/// Compute duration from join to leave
+/// Series and axes of a line chart, independent of how it is drawn.
+pub(crate) struct LineChart {
+    pub x_min: u32,
+    pub x_max: u32,
+    pub x_labels: Vec<String>,
+    pub x_desc: &'static str,
+    pub y_desc: &'static str,
+    pub max_y: f64,
+    /// Current period, 1 to 3 periods ago, then the mean.
+    pub series: Vec<Vec<(u32, f64)>>,
+    pub labels: Vec<&'static str>,
+}
+
+/// Compute duration from join to leave, or to now while the session is open
 fn duration_secs(session: &VoiceSessionsEntity, now: DateTime<Utc>) -> i64 {
-    let leave = if session.leave_time == session.join_time {
+    let leave = if session.is_active {
         now
     } else {
         session.leave_time
@@ -30,7 +50,48 @@ pub fn generate_line_chart(
     time_range: VoiceStatsTimeRange,
     stat_type: GuildStatType,
     is_user: bool,
+    backend: ChartBackend,
 ) -> anyhow::Result<Vec<u8>> {
+    let chart = line_chart_data(sessions, time_range, stat_type, is_user);
+    match backend {
+        ChartBackend::Bitmap => draw_line_chart(&chart),
+        ChartBackend::Svg => svg_chart::line_chart(&chart, &ChartTheme::default()),
+    }
+}
+
+/// Generate a contribution heatmap of daily values from `start` to `end`
+pub fn generate_heatmap(
+    data: HashMap<NaiveDate, u32>,
+    start: NaiveDate,
+    end: NaiveDate,
+    backend: ChartBackend,
+) -> anyhow::Result<Vec<u8>> {
+    if backend == ChartBackend::Svg {
+        return svg_chart::heatmap(&data, start, end, &ChartTheme::default());
+    }
+
+    let img = ContributionGraph::new()
+        .with_data(data)
+        .start_date(start)
+        .end_date(end)
+        .theme(Theme::github(Strategy::linear()))
+        .generate();
+
+    let mut bytes: Vec<u8> = Vec::new();
+    img.write_to(
+        &mut std::io::Cursor::new(&mut bytes),
+        image::ImageFormat::Png,
+    )?;
+    Ok(bytes)
+}
+
+/// Group sessions into one series per period plus their mean
+fn line_chart_data(
+    sessions: &[VoiceSessionsEntity],
+    time_range: VoiceStatsTimeRange,
+    stat_type: GuildStatType,
+    is_user: bool,
+) -> LineChart {
     let now = Utc::now();
 
     // Groupings: map of (line_idx, x_val) -> stat
@@ -162,6 +223,54 @@ pub fn generate_line_chart(
     // Pad max_y
     max_y = (max_y * 1.1).max(1.0);
 
+    LineChart {
+        x_min,
+        x_max,
+        x_labels,
+        x_desc: match time_range {
+            VoiceStatsTimeRange::Hourly => "Hour of Day",
+            VoiceStatsTimeRange::Weekly => "Day of Week",
+            VoiceStatsTimeRange::Monthly => "Day of Month",
+            _ => "",
+        },
+        y_desc: if stat_type == GuildStatType::ActiveUserCount && !is_user {
+            "Users"
+        } else {
+            "Hours"
+        },
+        max_y,
+        series: series_data,
+        labels: match time_range {
+            VoiceStatsTimeRange::Hourly => vec![
+                "Current Day",
+                "1 Day Ago",
+                "2 Days Ago",
+                "3 Days Ago",
+                "Mean",
+            ],
+            VoiceStatsTimeRange::Weekly => vec![
+                "Current Week",
+                "1 Week Ago",
+                "2 Weeks Ago",
+                "3 Weeks Ago",
+                "Mean",
+            ],
+            VoiceStatsTimeRange::Monthly => vec![
+                "Current Month",
+                "1 Month Ago",
+                "2 Months Ago",
+                "3 Months Ago",
+                "Mean",
+            ],
+            _ => vec!["", "", "", "", "Mean"],
+        },
+    }
+}
+
+/// Draw a line chart into an 800x400 bitmap with plotters
+fn draw_line_chart(data: &LineChart) -> anyhow::Result<Vec<u8>> {
+    let (x_min, x_max) = (data.x_min, data.x_max);
+
     let mut buffer = vec![0; 800 * 400 * 3];
     {
         let root = BitMapBackend::with_buffer(&mut buffer, (800, 400)).into_drawing_area();
@@ -171,27 +280,18 @@ pub fn generate_line_chart(
             .margin(20)
             .x_label_area_size(40)
             .y_label_area_size(50)
-            .build_cartesian_2d(x_min..x_max, 0.0..max_y)?;
+            .build_cartesian_2d(x_min..x_max, 0.0..data.max_y)?;
 
         chart
             .configure_mesh()
             .disable_x_mesh()
             .disable_y_mesh()
-            .x_desc(match time_range {
-                VoiceStatsTimeRange::Hourly => "Hour of Day",
-                VoiceStatsTimeRange::Weekly => "Day of Week",
-                VoiceStatsTimeRange::Monthly => "Day of Month",
-                _ => "",
-            })
-            .y_desc(if stat_type == GuildStatType::ActiveUserCount && !is_user {
-                "Users"
-            } else {
-                "Hours"
-            })
+            .x_desc(data.x_desc)
+            .y_desc(data.y_desc)
             .label_style(("sans-serif", 15).into_font().color(&WHITE))
             .x_label_formatter(&|x| {
                 if *x >= x_min && *x <= x_max {
-                    x_labels[(*x - x_min) as usize].clone()
+                    data.x_labels[(*x - x_min) as usize].clone()
                 } else {
                     "".to_string()
                 }
@@ -207,32 +307,7 @@ pub fn generate_line_chart(
             &RGBColor(229, 192, 123), // mean line (yellow/orange)
         ];
 
-        let labels = match time_range {
-            VoiceStatsTimeRange::Hourly => vec![
-                "Current Day",
-                "1 Day Ago",
-                "2 Days Ago",
-                "3 Days Ago",
-                "Mean",
-            ],
-            VoiceStatsTimeRange::Weekly => vec![
-                "Current Week",
-                "1 Week Ago",
-                "2 Weeks Ago",
-                "3 Weeks Ago",
-                "Mean",
-            ],
-            VoiceStatsTimeRange::Monthly => vec![
-                "Current Month",
-                "1 Month Ago",
-                "2 Months Ago",
-                "3 Months Ago",
-                "Mean",
-            ],
-            _ => vec!["", "", "", "", "Mean"],
-        };
-
-        for (i, series) in series_data.iter().enumerate() {
+        for (i, series) in data.series.iter().enumerate() {
             // Lines are in reverse order of age: 0 = current, 1 = 1 ago, 2 = 2 ago, 3 = 3 ago
             // So we map line 3 -> colors[0], line 2 -> colors[1], line 1 -> colors[2], line 0 -> colors[3]
             // and line 4 (mean) -> colors[4]
@@ -246,7 +321,7 @@ pub fn generate_line_chart(
                     series.clone(),
                     c.stroke_width(stroke_width),
                 ))?
-                .label(labels[i])
+                .label(data.labels[i])
                 .legend(move |(x, y)| {
                     PathElement::new(vec![(x, y), (x + 20, y)], c.stroke_width(stroke_width))
                 });
@@ -295,6 +370,7 @@ mod tests {
             join_time: now - Duration::days(30),
             leave_time: now - Duration::days(30), // active ghost
             is_active: true,
+            parent_session_id: None,
         };
         // It shouldn't sum 30 days of seconds, it should cap at 86400 (24h)
         assert_eq!(duration_secs(&session, now), 86400);
@@ -308,6 +384,7 @@ mod tests {
             join_time: now - Duration::hours(3),
             leave_time: now - Duration::hours(1),
             is_active: false,
+            parent_session_id: None,
         };
         assert_eq!(duration_secs(&session2, now), 7200);
     }
@@ -4,18 +4,22 @@ use std::time::Duration;
 use std::time::Instant;
 
 use chrono::NaiveDate;
-use contribution_grid::ContributionGraph;
-use contribution_grid::builtins::Strategy;
-use contribution_grid::builtins::Theme;
 use log::trace;
+use serde_json::Value;
 
 use crate::bot::command::prelude::*;
 use crate::bot::command::voice::GuildStatType;
 use crate::bot::command::voice::TimeRange;
 use crate::bot::command::voice::VoiceStatsTimeRange;
+use crate::bot::command::voice::stats::chart::generate_heatmap;
 use crate::bot::command::voice::stats::chart::generate_line_chart;
+use crate::bot::render::ImageRenderService;
+use crate::bot::view::export::ExportAction;
+use crate::bot::view::export::TabularExport;
+use crate::config::ChartBackend;
 use crate::entity::GuildDailyStats;
 use crate::entity::VoiceDailyActivity;
+use crate::entity::VoiceGoalProgress;
 use crate::entity::VoiceSessionsEntity;
 use crate::service::traits::VoiceTracker;
 use crate::update::Update;
@@ -25,6 +29,7 @@ use crate::update::voice_stats::VoiceStatsMsg;
 use crate::update::voice_stats::VoiceStatsUpdate;
 
 pub mod chart;
+pub mod svg_chart;
 
 /// Show voice activity statistics
 ///
@@ -42,6 +47,14 @@ pub async fn stats(
     command(ctx, time_range, user, statistic).await
 }
 
+/// Show voice activity statistics of a member
+///
+/// Opens the voice stats of the selected member, as `/vc stats user` would.
+#[poise::command(context_menu_command = "Voice stats")]
+pub async fn stats_of_user(ctx: Context<'_>, user: User) -> Result<(), Error> {
+    command(ctx, None, Some(user), None).await
+}
+
 /// Entry point for the stats command.
 pub async fn command(
     ctx: Context<'_>,
@@ -116,6 +129,7 @@ action_enum! {
 
         ToggleDataMode,
         SelectUser,
+        Export(ExportAction),
     }
 }
 
@@ -136,6 +150,9 @@ pub struct VoiceStatsData {
     pub time_range: VoiceStatsTimeRange,
     /// Raw sessions for line chart generation
     pub raw_sessions: Vec<VoiceSessionsEntity>,
+    /// This month's progress toward the server goal (guild stats only)
+    #[serde(default)]
+    pub goal: Option<VoiceGoalProgress>,
 }
 
 impl VoiceStatsData {
@@ -227,6 +244,8 @@ pub struct VoiceStatsView {
     pub service: std::sync::Arc<dyn VoiceTracker>,
     pub guild_id: u64,
     pub user: User,
+    pub renderer: std::sync::Arc<ImageRenderService>,
+    pub chart_backend: ChartBackend,
 }
 
 impl VoiceStatsView {
@@ -235,6 +254,8 @@ impl VoiceStatsView {
         service: std::sync::Arc<dyn VoiceTracker>,
         guild_id: u64,
         user: User,
+        renderer: std::sync::Arc<ImageRenderService>,
+        chart_backend: ChartBackend,
     ) -> Self {
         let model = VoiceStatsModel {
             time_range: data.time_range,
@@ -249,6 +270,8 @@ impl VoiceStatsView {
             service,
             guild_id,
             user,
+            renderer,
+            chart_backend,
         }
     }
 
@@ -279,6 +302,7 @@ impl VoiceStatsView {
                 stat_type: self.model.stat_type,
                 time_range: self.model.time_range,
                 raw_sessions,
+                goal: None,
             };
         } else {
             let guild_stats = self
@@ -286,6 +310,11 @@ impl VoiceStatsView {
                 .get_guild_daily_stats(self.guild_id, &since, &until, self.model.stat_type)
                 .await
                 .map_err(Error::from)?;
+            let goal = self
+                .service
+                .get_goal_progress(self.guild_id, &chrono::Utc::now())
+                .await
+                .map_err(Error::from)?;
 
             self.data = VoiceStatsData {
                 user: None,
@@ -295,10 +324,11 @@ impl VoiceStatsView {
                 stat_type: self.model.stat_type,
                 time_range: self.model.time_range,
                 raw_sessions,
+                goal,
             };
         }
 
-        if let Ok(bytes) = self.generate_image() {
+        if let Ok(bytes) = self.generate_image().await {
             self.image_bytes = Some(bytes);
         } else {
             self.image_bytes = None;
@@ -308,14 +338,19 @@ impl VoiceStatsView {
     }
 
     /// Generates the contribution grid image.
-    fn generate_image(&self) -> anyhow::Result<Vec<u8>> {
+    async fn generate_image(&self) -> anyhow::Result<Vec<u8>> {
         if self.model.time_range != VoiceStatsTimeRange::Yearly {
-            return generate_line_chart(
-                &self.data.raw_sessions,
-                self.model.time_range,
-                self.model.stat_type,
-                self.model.is_user_stats(),
-            );
+            let sessions = self.data.raw_sessions.clone();
+            let time_range = self.model.time_range;
+            let stat_type = self.model.stat_type;
+            let is_user_stats = self.model.is_user_stats();
+            let backend = self.chart_backend;
+            return self
+                .renderer
+                .render("stats_line_chart", move || {
+                    generate_line_chart(&sessions, time_range, stat_type, is_user_stats, backend)
+                })
+                .await;
         }
 
         let (since, _until) = self.model.time_range.to_range();
@@ -344,22 +379,37 @@ impl VoiceStatsView {
             }
         }
 
-        // Generate the graph with appropriate date range
-        let img = ContributionGraph::new()
-            .with_data(data_map)
-            .start_date(since.date_naive())
-            .end_date(today)
-            .theme(Theme::github(Strategy::linear()))
-            .generate();
-
-        // Convert to PNG bytes
-        let mut bytes: Vec<u8> = Vec::new();
-        img.write_to(
-            &mut std::io::Cursor::new(&mut bytes),
-            image::ImageFormat::Png,
-        )?;
-
-        Ok(bytes)
+        let backend = self.chart_backend;
+        self.renderer
+            .render("stats_contribution_graph", move || {
+                generate_heatmap(data_map, since.date_naive(), today, backend)
+            })
+            .await
+    }
+
+    /// Builds an export of the daily values behind the current chart.
+    fn export(&self) -> TabularExport {
+        if self.model.is_user_stats() {
+            let mut export = TabularExport::new("voice_stats", &["day", "total_seconds"]);
+            for activity in &self.data.user_activity {
+                export.push_row([
+                    Value::from(activity.day.to_string()),
+                    Value::from(activity.total_seconds),
+                ]);
+            }
+            return export;
+        }
+
+        let value_column = match self.model.stat_type {
+            GuildStatType::AverageTime => "average_seconds",
+            GuildStatType::ActiveUserCount => "active_users",
+            GuildStatType::TotalTime => "total_seconds",
+        };
+        let mut export = TabularExport::new("voice_server_stats", &["day", value_column]);
+        for stat in &self.data.guild_stats {
+            export.push_row([Value::from(stat.day.to_string()), Value::from(stat.value)]);
+        }
+        export
     }
 
     /// Formats the stats summary text.
@@ -376,14 +426,26 @@ impl VoiceStatsView {
             let total = format_duration(self.data.total_time());
             let avg = format_duration(self.data.average_daily_time());
             let streak = self.data.current_streak();
+            let live = self
+                .model
+                .user_id
+                .and_then(|user_id| self.service.open_sessions().get(self.guild_id, user_id))
+                .map(|open| {
+                    format!(
+                        "\n**In Voice:** 🔴 {}",
+                        format_duration(open.duration_secs(&chrono::Utc::now()))
+                    )
+                })
+                .unwrap_or_default();
 
             format!(
-                "### Voice Stats\n{}\n\n**User:** {}\n**Total Time:** {}\n**Average Daily:** {}\n**Current Streak:** {} day(s)",
+                "### Voice Stats\n{}\n\n**User:** {}\n**Total Time:** {}\n**Average Daily:** {}\n**Current Streak:** {} day(s){}",
                 time_range_text,
                 self.data.display_name(),
                 total,
                 avg,
-                streak
+                streak,
+                live
             )
         } else {
             // For guild stats, show different metrics based on stat_type
@@ -455,7 +517,7 @@ impl VoiceStatsView {
                 }
             };
 
-            format!(
+            let mut summary = format!(
                 "### Voice Stats\n{}\n\n**Server:** {}\n**{}:** {}\n**{}:**{}",
                 time_range_text,
                 self.data.guild_name,
@@ -463,11 +525,28 @@ impl VoiceStatsView {
                 first_value,
                 second_label,
                 second_value
-            )
+            );
+            if let Some(goal) = &self.data.goal {
+                summary.push_str(&format!(
+                    "\n**Monthly Goal:** `{}` {:.0}% ({} of {})",
+                    goal_bar(goal.fraction()),
+                    goal.fraction() * 100.0,
+                    format_duration(goal.total_seconds),
+                    format_duration(goal.goal_seconds),
+                ));
+            }
+            summary
         }
     }
 }
 
+/// Draws goal progress, from 0 to 1, as a bar of block characters.
+fn goal_bar(fraction: f64) -> String {
+    const WIDTH: usize = 10;
+    let filled = ((fraction * WIDTH as f64).floor() as usize).min(WIDTH);
+    format!("{}{}", "█".repeat(filled), "░".repeat(WIDTH - filled))
+}
+
 #[async_trait::async_trait]
 impl ViewHandler for VoiceStatsView {
     type Action = VoiceStatsAction;
@@ -534,8 +613,19 @@ impl ViewHandler for VoiceStatsView {
             }
             SelectUser => {
                 if let Some(user_id) = ctx.user_select_values().and_then(|v| v.first().copied())
-                    && let Ok(user) = user_id.to_user(ctx.poise.http()).await
+                    && let Some(resolved) = ctx
+                        .poise
+                        .data()
+                        .users
+                        .resolve(
+                            ctx.poise.http(),
+                            ctx.poise.cache(),
+                            ctx.poise.guild_id(),
+                            user_id,
+                        )
+                        .await
                 {
+                    let user = resolved.user;
                     self.user = user.clone();
                     let cmd = VoiceStatsUpdate::update(
                         VoiceStatsMsg::SetUser(Some(user.id.get())),
@@ -544,6 +634,10 @@ impl ViewHandler for VoiceStatsView {
                     changed = matches!(cmd, VoiceStatsCmd::RefetchData);
                 }
             }
+            Export(action) => {
+                let export = self.export();
+                return ctx.send_file(export.to_attachment((*action).into())).await;
+            }
         }
 
         if changed {
@@ -596,8 +690,12 @@ impl ViewRender for VoiceStatsView {
             .label(toggle_label)
             .style(ButtonStyle::Primary);
 
+        let mut buttons = vec![toggle_button];
+        if !self.data.user_activity.is_empty() || !self.data.guild_stats.is_empty() {
+            buttons.extend(ExportAction::create_buttons(registry, Export));
+        }
         container_components.push(CreateContainerComponent::ActionRow(
-            CreateActionRow::Buttons(vec![toggle_button].into()),
+            CreateActionRow::Buttons(buttons.into()),
         ));
 
         let mut components = vec![CreateComponent::Container(CreateContainer::new(
@@ -766,6 +864,7 @@ impl<'a> VoiceStatsHandler<'a> {
                 stat_type: self.stat_type,
                 time_range: self.time_range,
                 raw_sessions,
+                goal: None,
             })
         } else {
             // Fetch guild-wide stats
@@ -773,6 +872,10 @@ impl<'a> VoiceStatsHandler<'a> {
                 .get_guild_daily_stats(guild_id, &since, &until, self.stat_type)
                 .await
                 .map_err(Error::from)?;
+            let goal = service
+                .get_goal_progress(guild_id, &chrono::Utc::now())
+                .await
+                .map_err(Error::from)?;
 
             Ok(VoiceStatsData {
                 user: None,
@@ -782,6 +885,7 @@ impl<'a> VoiceStatsHandler<'a> {
                 stat_type: self.stat_type,
                 time_range: self.time_range,
                 raw_sessions,
+                goal,
             })
         }
     }
@@ -812,6 +916,8 @@ impl CommandHandler for VoiceStatsHandler<'_> {
             ctx.data().service.voice_tracking.clone(),
             guild_id,
             user,
+            ctx.data().renderer.clone(),
+            ctx.data().config.chart_backend,
         );
 
         // Generate and send the image
@@ -819,7 +925,10 @@ impl CommandHandler for VoiceStatsHandler<'_> {
             || !view.data.guild_stats.is_empty()
             || !view.data.raw_sessions.is_empty()
         {
-            let bytes = view.generate_image().map_err(AppError::internal_with_ref)?;
+            let bytes = view
+                .generate_image()
+                .await
+                .map_err(AppError::internal_with_ref)?;
             view.image_bytes = Some(bytes);
         }
 
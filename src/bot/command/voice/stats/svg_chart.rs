@@ -0,0 +1,323 @@
+//! SVG backend for voice stats charts.
+//!
+//! Charts are laid out here, drawn by the Minijinja templates in `assets/charts` and
+//! rasterized with resvg at twice their size, so text stays sharp when Discord scales
+//! the image.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::Datelike;
+use chrono::Days;
+use chrono::NaiveDate;
+use minijinja::Environment;
+use minijinja::context;
+use serde::Serialize;
+
+use crate::bot::command::voice::stats::chart::LineChart;
+
+const LINE_CHART_WIDTH: f64 = 800.0;
+const LINE_CHART_HEIGHT: f64 = 400.0;
+const PLOT_LEFT: f64 = 70.0;
+const PLOT_RIGHT: f64 = 780.0;
+const PLOT_TOP: f64 = 20.0;
+const PLOT_BOTTOM: f64 = 330.0;
+/// Most x axis labels drawn, so hourly and monthly charts stay readable.
+const MAX_X_LABELS: usize = 12;
+const Y_TICKS: u32 = 5;
+
+const CELL_SIZE: u32 = 11;
+const CELL_GAP: u32 = 3;
+const HEATMAP_LEFT: u32 = 36;
+const HEATMAP_TOP: u32 = 24;
+
+/// Charts are rasterized at this multiple of their SVG size.
+const SCALE: f32 = 2.0;
+
+/// Colors of SVG charts.
+#[derive(Clone, Debug, Serialize)]
+pub struct ChartTheme {
+    pub background: &'static str,
+    pub text: &'static str,
+    pub grid: &'static str,
+    pub legend_background: &'static str,
+    /// Line colors of the current period, 1 to 3 periods ago, then the mean.
+    pub series: [&'static str; 5],
+    /// Heatmap cell colors from no activity to the busiest day.
+    pub heatmap: [&'static str; 5],
+}
+
+impl Default for ChartTheme {
+    /// Discord's dark theme, with the same line colors as the bitmap charts.
+    fn default() -> Self {
+        Self {
+            background: "#2B2D31",
+            text: "#F2F3F5",
+            grid: "#3F4147",
+            legend_background: "#1E1F22",
+            series: ["#61AFEF", "#98C379", "#B4B4B4", "#808080", "#E5C07B"],
+            heatmap: ["#161B22", "#0E4429", "#006D32", "#26A641", "#39D353"],
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Tick {
+    pos: f64,
+    label: String,
+}
+
+#[derive(Serialize)]
+struct Line {
+    points: String,
+    color: &'static str,
+    width: u32,
+}
+
+#[derive(Serialize)]
+struct LegendEntry {
+    label: &'static str,
+    color: &'static str,
+    width: u32,
+    y: f64,
+}
+
+#[derive(Serialize)]
+struct Cell {
+    x: u32,
+    y: u32,
+    level: usize,
+}
+
+/// Renders a line chart to PNG.
+pub(crate) fn line_chart(chart: &LineChart, theme: &ChartTheme) -> Result<Vec<u8>> {
+    let span = chart.x_max.saturating_sub(chart.x_min).max(1) as f64;
+    let x_pos = |x: u32| PLOT_LEFT + (x - chart.x_min) as f64 / span * (PLOT_RIGHT - PLOT_LEFT);
+    let y_pos = |y: f64| PLOT_BOTTOM - y / chart.max_y * (PLOT_BOTTOM - PLOT_TOP);
+
+    let step = chart.x_labels.len().div_ceil(MAX_X_LABELS).max(1);
+    let x_ticks: Vec<Tick> = chart
+        .x_labels
+        .iter()
+        .enumerate()
+        .step_by(step)
+        .map(|(i, label)| Tick {
+            pos: x_pos(chart.x_min + i as u32),
+            label: label.clone(),
+        })
+        .collect();
+    let y_ticks: Vec<Tick> = (0..=Y_TICKS)
+        .map(|i| {
+            let value = chart.max_y * f64::from(i) / f64::from(Y_TICKS);
+            Tick {
+                pos: y_pos(value),
+                label: if chart.max_y < 10.0 {
+                    format!("{value:.1}")
+                } else {
+                    format!("{value:.0}")
+                },
+            }
+        })
+        .collect();
+
+    let width = |i: usize| if i == 0 || i == 4 { 3 } else { 2 };
+    // Oldest period first so the current period and the mean are drawn on top
+    let lines: Vec<Line> = [3, 2, 1, 0, 4]
+        .into_iter()
+        .filter_map(|i| {
+            let points = chart
+                .series
+                .get(i)?
+                .iter()
+                .map(|&(x, y)| format!("{:.1},{:.1}", x_pos(x), y_pos(y)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Some(Line {
+                points,
+                color: theme.series[i],
+                width: width(i),
+            })
+        })
+        .collect();
+    let legend: Vec<LegendEntry> = chart
+        .labels
+        .iter()
+        .enumerate()
+        .take(chart.series.len())
+        .map(|(i, &label)| LegendEntry {
+            label,
+            color: theme.series[i],
+            width: width(i),
+            y: PLOT_TOP + 18.0 + i as f64 * 20.0,
+        })
+        .collect();
+
+    let mut env = Environment::new();
+    env.add_template(
+        "line_chart",
+        include_str!("../../../../../assets/charts/line_chart.svg"),
+    )?;
+    let svg = env.get_template("line_chart")?.render(context! {
+        width => LINE_CHART_WIDTH,
+        height => LINE_CHART_HEIGHT,
+        plot_left => PLOT_LEFT,
+        plot_right => PLOT_RIGHT,
+        plot_top => PLOT_TOP,
+        plot_bottom => PLOT_BOTTOM,
+        x_desc => chart.x_desc,
+        y_desc => chart.y_desc,
+        x_ticks,
+        y_ticks,
+        lines,
+        legend,
+        theme,
+    })?;
+
+    rasterize(&svg)
+}
+
+/// Renders a contribution heatmap of daily values from `start` to `end` to PNG.
+///
+/// Columns are weeks starting on Monday. Days are shaded in four steps relative to the
+/// busiest day.
+pub(crate) fn heatmap(
+    data: &HashMap<NaiveDate, u32>,
+    start: NaiveDate,
+    end: NaiveDate,
+    theme: &ChartTheme,
+) -> Result<Vec<u8>> {
+    let pitch = CELL_SIZE + CELL_GAP;
+    let grid_start = start - Days::new(u64::from(start.weekday().num_days_from_monday()));
+    let weeks = ((end - grid_start).num_days().max(0) / 7 + 1) as u32;
+    let max = data.values().copied().max().unwrap_or(0);
+
+    let mut cells = Vec::new();
+    let mut months = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let week = ((date - grid_start).num_days() / 7) as u32;
+        let x = HEATMAP_LEFT + week * pitch;
+        cells.push(Cell {
+            x,
+            y: HEATMAP_TOP + date.weekday().num_days_from_monday() * pitch,
+            level: heatmap_level(data.get(&date).copied().unwrap_or(0), max),
+        });
+        if date.day() == 1 || date == start {
+            months.push(Tick {
+                pos: f64::from(x),
+                label: date.format("%b").to_string(),
+            });
+        }
+        let Some(next) = date.succ_opt() else {
+            break;
+        };
+        date = next;
+    }
+    // A partial first month would crowd the next label
+    if months.len() > 1 && months[1].pos - months[0].pos < f64::from(3 * pitch) {
+        months.remove(0);
+    }
+
+    let weekdays: Vec<Tick> = [(0, "Mon"), (2, "Wed"), (4, "Fri")]
+        .into_iter()
+        .map(|(row, label)| Tick {
+            pos: f64::from(HEATMAP_TOP + row * pitch),
+            label: label.to_string(),
+        })
+        .collect();
+
+    let width = HEATMAP_LEFT + weeks * pitch + 16;
+    let height = HEATMAP_TOP + 7 * pitch + 28;
+    let mut env = Environment::new();
+    env.add_template(
+        "heatmap",
+        include_str!("../../../../../assets/charts/heatmap.svg"),
+    )?;
+    let svg = env.get_template("heatmap")?.render(context! {
+        width,
+        height,
+        left => HEATMAP_LEFT,
+        top => HEATMAP_TOP,
+        cell_size => CELL_SIZE,
+        cell_gap => CELL_GAP,
+        legend_x => width.saturating_sub(16 + 5 * pitch + 28),
+        legend_y => HEATMAP_TOP + 7 * pitch + 8,
+        months,
+        weekdays,
+        cells,
+        theme,
+    })?;
+
+    rasterize(&svg)
+}
+
+/// Returns the shade of a day, 0 for no activity up to 4 for the busiest.
+fn heatmap_level(value: u32, max: u32) -> usize {
+    if value == 0 || max == 0 {
+        return 0;
+    }
+    ((f64::from(value) / f64::from(max) * 4.0).ceil() as usize).clamp(1, 4)
+}
+
+fn rasterize(svg: &str) -> Result<Vec<u8>> {
+    let mut fontdb = resvg::usvg::fontdb::Database::new();
+    fontdb
+        .load_font_data(include_bytes!("../../../../../assets/fonts/Roboto-Regular.ttf").to_vec());
+
+    let options = resvg::usvg::Options {
+        fontdb: std::sync::Arc::new(fontdb),
+        ..Default::default()
+    };
+
+    let tree = resvg::usvg::Tree::from_str(svg, &options)?;
+    let width = (tree.size().width() * SCALE).ceil() as u32;
+    let height = (tree.size().height() * SCALE).ceil() as u32;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| anyhow::anyhow!("Failed to create pixmap"))?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::from_scale(SCALE, SCALE),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap.encode_png()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heatmap_levels_scale_with_busiest_day() {
+        assert_eq!(heatmap_level(0, 100), 0);
+        assert_eq!(heatmap_level(1, 100), 1);
+        assert_eq!(heatmap_level(50, 100), 2);
+        assert_eq!(heatmap_level(100, 100), 4);
+    }
+
+    #[test]
+    fn line_chart_renders_at_double_size() {
+        let chart = LineChart {
+            x_min: 0,
+            x_max: 6,
+            x_labels: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+                .map(String::from)
+                .to_vec(),
+            x_desc: "Day of Week",
+            y_desc: "Hours",
+            max_y: 2.0,
+            series: vec![(0..=6).map(|x| (x, f64::from(x) / 4.0)).collect(); 5],
+            labels: vec![
+                "Current Week",
+                "1 Week Ago",
+                "2 Weeks Ago",
+                "3 Weeks Ago",
+                "Mean",
+            ],
+        };
+
+        let png = line_chart(&chart, &ChartTheme::default()).unwrap();
+        let img = image::load_from_memory(&png).unwrap();
+
+        assert_eq!((img.width(), img.height()), (1600, 800));
+    }
+}
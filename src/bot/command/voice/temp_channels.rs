@@ -0,0 +1,201 @@
+//! Temporary voice channel settings subcommand.
+
+use std::time::Duration;
+
+use crate::bot::command::prelude::*;
+use crate::bot::command::settings::open_staged_settings;
+use crate::bot::command::settings::save_staged_settings;
+use crate::entity::ServerSettings;
+use crate::update::Staged;
+
+/// Member limits of new temporary channels offered in the view.
+const USER_LIMIT_OPTIONS: [(u32, &str); 5] = [
+    (0, "No limit"),
+    (2, "2 members"),
+    (4, "4 members"),
+    (5, "5 members"),
+    (10, "10 members"),
+];
+
+/// Configure join-to-create temporary voice channels
+///
+/// Pick a hub channel. Members joining it get their own voice channel, which they
+/// can lock, rename, and limit, and which is deleted once empty.
+/// Only server administrators can use this command.
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR | MANAGE_GUILD"
+)]
+pub async fn temp_channels(ctx: Context<'_>) -> Result<(), Error> {
+    Router::new(ctx).run(Navigation::VoiceTempChannels).await?;
+    Ok(())
+}
+
+handler! { pub struct VoiceTempChannelsHandler<'a> {} }
+
+#[async_trait::async_trait]
+impl CommandHandler for VoiceTempChannelsHandler<'_> {
+    async fn run(&mut self, coordinator: std::sync::Arc<Router<'_>>) -> Result<(), Error> {
+        let ctx = *coordinator.context();
+        ctx.defer().await?;
+        let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
+
+        let view = SettingsTempVoiceHandler {
+            settings: open_staged_settings(&ctx.data().service, guild_id).await?,
+            guild_id,
+        };
+
+        let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
+
+        // Unsaved changes are discarded when the view exits
+        engine.run().await?;
+
+        Ok(())
+    }
+}
+
+action_enum! {
+    SettingsTempVoiceAction {
+        Hub,
+        #[label = "Turn Off"]
+        TurnOff,
+        UserLimit,
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
+    }
+}
+
+pub struct SettingsTempVoiceHandler {
+    pub settings: Staged<ServerSettings>,
+    pub guild_id: u64,
+}
+
+#[async_trait::async_trait]
+impl ViewHandler for SettingsTempVoiceHandler {
+    type Action = SettingsTempVoiceAction;
+    async fn handle(
+        &mut self,
+        ctx: ViewContext<'_, SettingsTempVoiceAction>,
+    ) -> Result<ViewCmd, Error> {
+        let ret = match ctx.action() {
+            SettingsTempVoiceAction::Hub => {
+                self.settings.voice.temp_hub_channel_id = ctx
+                    .channel_select_values()
+                    .and_then(|v| v.first().map(|id| id.to_string()));
+                ViewCmd::Render
+            }
+            SettingsTempVoiceAction::TurnOff => {
+                self.settings.voice.temp_hub_channel_id = None;
+                ViewCmd::Render
+            }
+            SettingsTempVoiceAction::UserLimit => {
+                let limit = ctx
+                    .string_select_values()
+                    .and_then(|v| v.first().and_then(|limit| limit.parse::<u32>().ok()));
+                if let Some(limit) = limit {
+                    self.settings.voice.temp_user_limit = (limit > 0).then_some(limit);
+                }
+                ViewCmd::Render
+            }
+            SettingsTempVoiceAction::Save => {
+                save_staged_settings(
+                    &ctx.poise.data().service,
+                    self.guild_id,
+                    ctx.poise.author().id.get(),
+                    &mut self.settings,
+                )
+                .await?;
+                ViewCmd::Render
+            }
+            SettingsTempVoiceAction::Revert => {
+                self.settings.revert();
+                ViewCmd::Render
+            }
+        };
+        Ok(ret)
+    }
+}
+
+impl ViewRender for SettingsTempVoiceHandler {
+    type Action = SettingsTempVoiceAction;
+    fn render(&self, registry: &mut ActionRegistry<SettingsTempVoiceAction>) -> ResponseKind<'_> {
+        let voice = &self.settings.voice;
+        let hub = voice.temp_hub_channel_id();
+        let is_dirty = self.settings.is_dirty();
+
+        let status_text = format!(
+            "-# **Voice > Temporary Channels**{}\n## Temporary Channels\n\n> 🛈  {}",
+            unsaved_marker(is_dirty),
+            match hub {
+                Some(channel_id) => format!(
+                    "Joining <#{channel_id}> creates a channel for the member, deleted once everyone leaves. Time in it counts like any other channel."
+                ),
+                None => "Temporary channels are **off**. Select a hub channel to turn them on."
+                    .to_string(),
+            }
+        );
+        let hub_text = "### Hub Channel\n\n> 🛈  The bot needs the **Manage Channels** and **Move Members** permissions in the hub's category.";
+        let hub_select = registry
+            .register(SettingsTempVoiceAction::Hub)
+            .as_select(CreateSelectMenuKind::Channel {
+                channel_types: Some(vec![ChannelType::Voice].into()),
+                default_channels: Some(
+                    hub.map(GenericChannelId::new)
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+            })
+            .placeholder("Select hub channel");
+        let off_button = registry
+            .register(SettingsTempVoiceAction::TurnOff)
+            .as_button()
+            .style(ButtonStyle::Danger)
+            .disabled(hub.is_none());
+
+        let limit_text = "### Member Limit\n\n> 🛈  How many members new channels allow. Owners can change it from the channel's panel.";
+        let user_limit = voice.temp_user_limit().unwrap_or(0);
+        let limit_options: Vec<_> = USER_LIMIT_OPTIONS
+            .iter()
+            .map(|(limit, name)| {
+                CreateSelectMenuOption::new(*name, limit.to_string())
+                    .default_selection(*limit == user_limit)
+            })
+            .collect();
+        let limit_select = registry
+            .register(SettingsTempVoiceAction::UserLimit)
+            .as_select(CreateSelectMenuKind::String {
+                options: limit_options.into(),
+            })
+            .placeholder("Select member limit");
+
+        let container = CreateComponent::Container(CreateContainer::new(vec![
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status_text)),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(hub_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(hub_select)),
+            CreateContainerComponent::ActionRow(CreateActionRow::Buttons(vec![off_button].into())),
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(limit_text)),
+            CreateContainerComponent::ActionRow(CreateActionRow::SelectMenu(limit_select)),
+        ]));
+
+        let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
+            vec![
+                registry
+                    .register(SettingsTempVoiceAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsTempVoiceAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
+            ]
+            .into(),
+        ));
+
+        vec![container, nav_buttons].into()
+    }
+}
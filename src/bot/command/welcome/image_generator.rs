@@ -11,6 +11,8 @@ use minijinja::Environment;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::bot::render::ImageRenderService;
+
 const AVATAR_SIZE: u32 = 128; // Adjust based on templates, using a larger one is safe
 
 /// Defines the exact data structure expected by the Minijinja SVG template.
@@ -108,7 +110,11 @@ impl WelcomeImageGenerator {
         BASE64.encode(cursor.into_inner())
     }
 
-    pub async fn generate_card(&self, mut data: WelcomeCardData) -> Result<Vec<u8>> {
+    pub async fn generate_card(
+        &self,
+        mut data: WelcomeCardData,
+        renderer: &ImageRenderService,
+    ) -> Result<Vec<u8>> {
         // Placeholder: 1x1 gray pixel as fallback avatar
         const PLACEHOLDER_AVATAR: &str = "iVBORw0KGgoAAAANSUhEUgAAABAAAAAQCAYAAAAf8/9hAAAABHNCSVQICAgIfAhkiAAAAAlwSFlzAAAAdgAAAHYBTnsmCAAAABl0RVh0U29mdHdhcmUAd3d3Lmlua3NjYXBlLm9yZ5vuPBoAAADASURBVDiNY2AYBYMBMDIw/P/PyMDwn5GBgYGRgeE/I8N/BkYGBgZGRgYGRob/DIz//zMw/mdk+P+fkfE/AyPDfwbG//8ZGP//Z2D8z8D4n4Hh/38Ghv8MDAwMjIwMDAz//2dg/G9k+P+/gRHJAMD//z8DI7YBZ3h4FIiBgfH/PyMD4z8jw38GBoamHBgYGBgYGBgYGBj+/2NgYGBgYGBgYGBgYPjPYGBgYGBgYGBgYGBg+A8jI8N/BkYGBkaGBgZGhgYGhlIMDAwMjIwMDIwMDIwMDIyMjIyMjAwMjIwMDIwMDIyM/wwMjAwMDIzMDAzM//9nYGBgYICBgfF/BgYGBkZGBgZGRgYGRob/DIwMDIyMDAwMjAwMjIz8GRgYGBgZGRkYGRkZGRkZGf4zMDAwMDIyMjAyMjIyMjIyMv9nYGBgYICBgYGBgYHhPwPDfwbG/wwM//9nYPjPoAMPABw7JKxFaM0lAAAAAElFTkSuQmCC";
 
@@ -131,8 +137,11 @@ impl WelcomeImageGenerator {
         let width = 800; // Will be determined by svg
         let height = 300;
 
-        let png = Self::svg_to_png(&svg, width, height)?;
-        Ok(png)
+        renderer
+            .render("welcome_card", move || {
+                Self::svg_to_png(&svg, width, height)
+            })
+            .await
     }
 
     pub fn svg_to_png(svg: &str, _width: u32, _height: u32) -> Result<Vec<u8>> {
@@ -6,10 +6,13 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::bot::command::prelude::*;
+use crate::bot::command::settings::open_staged_settings;
+use crate::bot::command::settings::save_staged_settings;
 use crate::bot::command::welcome::image_generator::WelcomeCardData;
 use crate::bot::command::welcome::image_generator::WelcomeImageGenerator;
+use crate::bot::render::ImageRenderService;
 use crate::entity::ServerSettings;
-use crate::service::traits::FeedSubscriptionProvider;
+use crate::update::Staged;
 use crate::update::Update;
 use crate::update::welcome_settings::WelcomeSettingsCmd;
 use crate::update::welcome_settings::WelcomeSettingsModel;
@@ -52,23 +55,28 @@ pub struct SetPrimaryColorModal {
 
 pub struct SettingsWelcomeHandler {
     pub model: WelcomeSettingsModel,
-    pub settings: ServerSettings,
+    pub settings: Staged<ServerSettings>,
     pub current_image_bytes: Option<Vec<u8>>,
-    pub service: Arc<dyn FeedSubscriptionProvider>,
     pub generator: Arc<WelcomeImageGenerator>,
+    pub renderer: Arc<ImageRenderService>,
     pub guild_id: u64,
     pub ctx_serenity: poise::serenity_prelude::Context,
 }
 
 impl SettingsWelcomeHandler {
-    async fn persist_and_regenerate(&mut self) -> Result<(), Error> {
+    /// Stages the model into the working settings and refreshes the preview.
+    async fn stage_and_regenerate(&mut self) {
         self.settings.welcome = self.model.settings.clone();
-        self.service
-            .update_server_settings(self.guild_id, self.settings.clone())
-            .await?;
-        self.current_image_bytes =
-            WelcomeSettingsHandler::generate_preview_from(&self.settings, &self.generator).await;
-        Ok(())
+        self.regenerate().await;
+    }
+
+    async fn regenerate(&mut self) {
+        self.current_image_bytes = WelcomeSettingsHandler::generate_preview_from(
+            &self.settings,
+            &self.generator,
+            &self.renderer,
+        )
+        .await;
     }
 
     fn update(&mut self, msg: WelcomeSettingsMsg) -> WelcomeSettingsCmd {
@@ -97,8 +105,8 @@ impl ViewHandler for SettingsWelcomeHandler {
             }
             ToggleEnabled => {
                 let cmd = self.update(WelcomeSettingsMsg::ToggleEnabled);
-                if matches!(cmd, WelcomeSettingsCmd::PersistSettings) {
-                    self.persist_and_regenerate().await?;
+                if matches!(cmd, WelcomeSettingsCmd::RegeneratePreview) {
+                    self.stage_and_regenerate().await;
                 }
             }
             ChannelSelect => {
@@ -106,8 +114,8 @@ impl ViewHandler for SettingsWelcomeHandler {
                 {
                     let cmd =
                         self.update(WelcomeSettingsMsg::SetChannel(Some(channel.to_string())));
-                    if matches!(cmd, WelcomeSettingsCmd::PersistSettings) {
-                        self.persist_and_regenerate().await?;
+                    if matches!(cmd, WelcomeSettingsCmd::RegeneratePreview) {
+                        self.stage_and_regenerate().await;
                     }
                 }
             }
@@ -115,8 +123,8 @@ impl ViewHandler for SettingsWelcomeHandler {
                 if let Some(template) = ctx.string_select_values().and_then(|v| v.first().cloned())
                 {
                     let cmd = self.update(WelcomeSettingsMsg::SetTemplate(Some(template)));
-                    if matches!(cmd, WelcomeSettingsCmd::PersistSettings) {
-                        self.persist_and_regenerate().await?;
+                    if matches!(cmd, WelcomeSettingsCmd::RegeneratePreview) {
+                        self.stage_and_regenerate().await;
                     }
                 }
             }
@@ -133,25 +141,39 @@ impl ViewHandler for SettingsWelcomeHandler {
             }
             AddMessage(Some(modal)) => {
                 let cmd = self.update(WelcomeSettingsMsg::AddMessage(modal.message.clone()));
-                if matches!(cmd, WelcomeSettingsCmd::PersistSettings) {
-                    self.persist_and_regenerate().await?;
+                if matches!(cmd, WelcomeSettingsCmd::RegeneratePreview) {
+                    self.stage_and_regenerate().await;
                 }
             }
             SetColor(Some(modal)) => {
                 let cmd = self.update(WelcomeSettingsMsg::SetColor(modal.color.clone()));
-                if matches!(cmd, WelcomeSettingsCmd::PersistSettings) {
-                    self.persist_and_regenerate().await?;
+                if matches!(cmd, WelcomeSettingsCmd::RegeneratePreview) {
+                    self.stage_and_regenerate().await;
                 }
             }
             SaveRemoval => {
                 let cmd = self.update(WelcomeSettingsMsg::SaveRemoval);
-                if matches!(cmd, WelcomeSettingsCmd::PersistSettings) {
-                    self.persist_and_regenerate().await?;
+                if matches!(cmd, WelcomeSettingsCmd::RegeneratePreview) {
+                    self.stage_and_regenerate().await;
                 }
             }
             CancelRemoval => {
                 self.update(WelcomeSettingsMsg::CancelRemoval);
             }
+            Save => {
+                save_staged_settings(
+                    &ctx.poise.data().service,
+                    self.guild_id,
+                    ctx.poise.author().id.get(),
+                    &mut self.settings,
+                )
+                .await?;
+            }
+            Revert => {
+                self.settings.revert();
+                self.model = WelcomeSettingsModel::new(self.settings.welcome.clone());
+                self.regenerate().await;
+            }
             About => {
                 ctx.coordinator.navigate(Navigation::SettingsAbout).await;
                 return Ok(ViewCmd::Exit);
@@ -172,10 +194,12 @@ impl ViewRender for SettingsWelcomeHandler {
     type Action = SettingsWelcomeAction;
     fn render(&self, registry: &mut ActionRegistry<SettingsWelcomeAction>) -> ResponseKind<'_> {
         let is_enabled = self.model.is_enabled();
+        let is_dirty = self.settings.is_dirty();
         let msgs = self.model.message_count();
 
         let status_text = format!(
-            "-# **Settings > Welcome**\n## Welcome Settings\n\n> 🛈  {}",
+            "-# **Settings > Welcome**{}\n## Welcome Settings\n\n> 🛈  {}",
+            unsaved_marker(is_dirty),
             if is_enabled {
                 "Welcome cards are **active**."
             } else {
@@ -268,7 +292,7 @@ impl ViewRender for SettingsWelcomeHandler {
             CreateActionRow::Buttons(button_row.into()),
         ));
 
-        let variables_text = "### Template Variables\n> `{{ username }}` - User's display name\n> `{{ user_tag }}` - User's handle (@username)\n> `{{ server_name }}` - Server name\n> `{{ member_count }}` - Total member count\n> `{{ member_number }}` - Member join number\n> `{{ primary_color }}` - Accent color\n> `{{ welcome_message }}` - Your greetings";
+        let variables_text = "### Template Variables\n> `{{ username }}` - User's display name\n> `{{ user_tag }}` - User's handle (@username)\n> `{{ server_name }}` - Server name\n> `{{ member_count }}` - Total member count\n> `{{ member_number }}` - Member join number\n> `{{ inviter }}` - Who invited the member, in messages\n> `{{ primary_color }}` - Accent color\n> `{{ welcome_message }}` - Your greetings";
         components.push(CreateContainerComponent::TextDisplay(
             CreateTextDisplay::new(variables_text),
         ));
@@ -337,6 +361,16 @@ impl ViewRender for SettingsWelcomeHandler {
         let container = CreateComponent::Container(CreateContainer::new(components));
         let nav_buttons = CreateComponent::ActionRow(CreateActionRow::Buttons(
             vec![
+                registry
+                    .register(SettingsWelcomeAction::Save)
+                    .as_button()
+                    .style(ButtonStyle::Success)
+                    .disabled(!is_dirty),
+                registry
+                    .register(SettingsWelcomeAction::Revert)
+                    .as_button()
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!is_dirty),
                 registry
                     .register(SettingsWelcomeAction::Back)
                     .as_button()
@@ -377,6 +411,7 @@ impl<'a> WelcomeSettingsHandler<'a> {
     pub async fn generate_preview_from(
         settings: &ServerSettings,
         generator: &WelcomeImageGenerator,
+        renderer: &ImageRenderService,
     ) -> Option<Vec<u8>> {
         if !settings.welcome.enabled.unwrap_or(false) {
             return None;
@@ -408,7 +443,7 @@ impl<'a> WelcomeSettingsHandler<'a> {
                 .cloned()
                 .unwrap_or_else(|| "Welcome to the server!".to_string()),
         };
-        generator.generate_card(data).await.ok()
+        generator.generate_card(data, renderer).await.ok()
     }
 }
 
@@ -419,28 +454,26 @@ impl CommandHandler for WelcomeSettingsHandler<'_> {
         ctx.defer().await?;
 
         let guild_id = ctx.guild_id().ok_or(BotError::GuildOnlyCommand)?.get();
-        let service = ctx.data().service.feed_subscription.clone();
         let generator = Arc::new(WelcomeImageGenerator::new());
 
-        let settings = service
-            .get_server_settings(guild_id)
-            .await
-            .map_err(Error::from)?;
+        let settings = open_staged_settings(&ctx.data().service, guild_id).await?;
 
         let mut view = SettingsWelcomeHandler {
             model: WelcomeSettingsModel::new(settings.welcome.clone()),
             settings,
             current_image_bytes: None,
-            service,
             generator: generator.clone(),
+            renderer: ctx.data().renderer.clone(),
             guild_id,
             ctx_serenity: ctx.serenity_context().clone(),
         };
 
-        view.current_image_bytes = Self::generate_preview_from(&view.settings, &generator).await;
+        view.current_image_bytes =
+            Self::generate_preview_from(&view.settings, &generator, &view.renderer).await;
 
         let mut engine = ViewEngine::new(ctx, view, Duration::from_secs(120), coordinator.clone());
 
+        // Unsaved changes are discarded when the view exits
         engine.run().await?;
 
         Ok(())
@@ -457,10 +490,14 @@ action_enum! {
         MarkRemoval,
         #[label = "Add Welcome Message"]
         AddMessage(Option<AddWelcomeMessageModal>),
-        #[label = "Save Removals"]
+        #[label = "Remove Selected"]
         SaveRemoval,
         #[label = "Cancel"]
         CancelRemoval,
+        #[label = "✓ Save"]
+        Save,
+        #[label = "↺ Revert"]
+        Revert,
         #[label = "❮ Back"]
         Back,
         #[label = "🛈 About"]
@@ -0,0 +1,310 @@
+//! Delivery analytics of feed notifications rendered as one image for the bot owner.
+//!
+//! The report has a header with totals, the daily deliveries as stacked bars, the
+//! failure rate per subscriber type and the slowest platforms. It is laid out here,
+//! drawn by the Minijinja template in `assets/delivery_report.svg` and rendered on the
+//! [`ImageRenderService`](crate::bot::render::ImageRenderService).
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use minijinja::AutoEscape;
+use minijinja::Environment;
+use minijinja::context;
+use serde::Serialize;
+
+use crate::bot::utils::format_duration;
+use crate::entity::SubscriberType;
+use crate::service::feed_stats::DeliveryAnalytics;
+
+/// Filename for the report attachment.
+pub const DELIVERY_REPORT_FILENAME: &str = "delivery_report.png";
+
+const WIDTH: u32 = 800;
+const PADDING: u32 = 24;
+const HEADER_HEIGHT: u32 = 96;
+const SECTION_TITLE_HEIGHT: u32 = 36;
+const CHART_HEIGHT: u32 = 160;
+/// Room kept left of the daily bars for the scale.
+const AXIS_WIDTH: u32 = 48;
+/// Room kept below the daily bars for dates.
+const DATE_LABEL_HEIGHT: u32 = 28;
+/// Most dates labelled under the daily bars.
+const MAX_DATE_LABELS: usize = 8;
+const ROW_PITCH: u32 = 30;
+const BAR_HEIGHT: u32 = 22;
+/// Room kept left of the bars for names.
+const LABEL_WIDTH: u32 = 180;
+/// Room kept right of the bars for values.
+const VALUE_WIDTH: u32 = 160;
+
+/// Delivery analytics ready to be drawn.
+#[derive(Clone, Debug)]
+pub struct DeliveryReport {
+    analytics: DeliveryAnalytics,
+}
+
+impl DeliveryReport {
+    pub fn new(analytics: DeliveryAnalytics) -> Self {
+        Self { analytics }
+    }
+
+    /// Renders the report to PNG.
+    pub fn render(&self) -> Result<Vec<u8>> {
+        let analytics = &self.analytics;
+        let chart_top = HEADER_HEIGHT + SECTION_TITLE_HEIGHT;
+        let chart_bottom = chart_top + CHART_HEIGHT;
+        let types_title = chart_bottom + DATE_LABEL_HEIGHT;
+        let types_top = types_title + SECTION_TITLE_HEIGHT;
+        let platforms_title = types_top + section_height(analytics.by_subscriber_type.len());
+        let platforms_top = platforms_title + SECTION_TITLE_HEIGHT;
+        let height = platforms_top + section_height(analytics.slowest_platforms.len()) + PADDING;
+
+        let max_day = analytics
+            .daily
+            .iter()
+            .map(|day| day.delivered + day.failed)
+            .max()
+            .unwrap_or(0);
+        let columns = daily_columns(analytics, chart_top, max_day);
+        let dates = date_labels(analytics);
+
+        let max_rate = analytics
+            .by_subscriber_type
+            .iter()
+            .map(|row| row.failure_rate())
+            .fold(0.0, f64::max);
+        let subscriber_types: Vec<Bar> = analytics
+            .by_subscriber_type
+            .iter()
+            .enumerate()
+            .map(|(i, row)| Bar {
+                y: types_top + i as u32 * ROW_PITCH,
+                width: bar_width(row.failure_rate(), max_rate),
+                name: match row.subscriber_type {
+                    SubscriberType::Guild => "Servers".to_string(),
+                    SubscriberType::Dm => "DMs".to_string(),
+                },
+                value: format!(
+                    "{:.1}% of {}",
+                    row.failure_rate() * 100.0,
+                    row.delivered + row.failed
+                ),
+            })
+            .collect();
+
+        let max_delay = analytics
+            .slowest_platforms
+            .iter()
+            .map(|row| row.median_delay_secs)
+            .fold(0.0, f64::max);
+        let platforms: Vec<Bar> = analytics
+            .slowest_platforms
+            .iter()
+            .enumerate()
+            .map(|(i, row)| Bar {
+                y: platforms_top + i as u32 * ROW_PITCH,
+                width: bar_width(row.median_delay_secs, max_delay),
+                name: row.platform_id.clone(),
+                value: format!(
+                    "{} median",
+                    format_duration(row.median_delay_secs.round() as i64)
+                ),
+            })
+            .collect();
+
+        let delivered = analytics.delivered();
+        let failed = analytics.failed();
+        let total = (delivered + failed).max(1) as f64;
+        let until = analytics
+            .daily
+            .last()
+            .map(|day| day.day)
+            .unwrap_or(analytics.since);
+        let period = format!(
+            "{} to {} (UTC)",
+            analytics.since.format("%b %-d, %Y"),
+            until.format("%b %-d, %Y")
+        );
+
+        let mut env = Environment::new();
+        // Platform IDs come from the database
+        env.set_auto_escape_callback(|_| AutoEscape::Html);
+        env.add_template(
+            "delivery_report",
+            include_str!("../../assets/delivery_report.svg"),
+        )?;
+        let svg = env.get_template("delivery_report")?.render(context! {
+            width => WIDTH,
+            height,
+            padding => PADDING,
+            period,
+            delivered,
+            failure_rate => format!("{:.1}%", failed as f64 / total * 100.0),
+            daily_title => HEADER_HEIGHT,
+            chart_top,
+            chart_bottom,
+            axis_left => PADDING + AXIS_WIDTH,
+            max_day,
+            columns,
+            dates,
+            dates_y => chart_bottom + 18,
+            types_title,
+            subscriber_types,
+            platforms_title,
+            platforms,
+            bar_height => BAR_HEIGHT,
+            label_width => LABEL_WIDTH,
+        })?;
+
+        let mut fontdb = resvg::usvg::fontdb::Database::new();
+        fontdb.load_font_data(include_bytes!("../../assets/fonts/Roboto-Regular.ttf").to_vec());
+        let options = resvg::usvg::Options {
+            fontdb: Arc::new(fontdb),
+            ..Default::default()
+        };
+        let tree = resvg::usvg::Tree::from_str(&svg, &options)?;
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(WIDTH, height)
+            .ok_or_else(|| anyhow::anyhow!("Failed to create pixmap"))?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+        Ok(pixmap.encode_png()?)
+    }
+}
+
+/// A labelled bar as drawn by the template.
+#[derive(Serialize)]
+struct Bar {
+    y: u32,
+    width: f64,
+    name: String,
+    value: String,
+}
+
+/// One day of the daily chart: delivered at the bottom, failed stacked on top.
+#[derive(Serialize, Debug, PartialEq)]
+struct Column {
+    x: f64,
+    width: f64,
+    delivered_y: f64,
+    delivered_height: f64,
+    failed_y: f64,
+    failed_height: f64,
+}
+
+#[derive(Serialize)]
+struct Label {
+    pos: f64,
+    text: String,
+}
+
+/// Height of a bar section with `rows` rows, leaving room for an empty note.
+fn section_height(rows: usize) -> u32 {
+    rows.max(1) as u32 * ROW_PITCH + 8
+}
+
+/// Width of a horizontal bar for `value`, scaled to the largest.
+fn bar_width(value: f64, max: f64) -> f64 {
+    let max_width = f64::from(WIDTH - 2 * PADDING - LABEL_WIDTH - VALUE_WIDTH);
+    if max <= 0.0 {
+        return 2.0;
+    }
+    (value / max * max_width).max(2.0)
+}
+
+/// Width of the slot each day of the daily chart takes.
+fn column_pitch(days: usize) -> f64 {
+    f64::from(WIDTH - 2 * PADDING - AXIS_WIDTH) / days.max(1) as f64
+}
+
+/// Lays out one stacked column per day, scaled to the busiest day.
+fn daily_columns(analytics: &DeliveryAnalytics, top: u32, max_day: i64) -> Vec<Column> {
+    let pitch = column_pitch(analytics.daily.len());
+    let scale = f64::from(CHART_HEIGHT) / max_day.max(1) as f64;
+    let bottom = f64::from(top + CHART_HEIGHT);
+    analytics
+        .daily
+        .iter()
+        .enumerate()
+        .map(|(i, day)| {
+            let delivered_height = day.delivered as f64 * scale;
+            let failed_height = day.failed as f64 * scale;
+            Column {
+                x: f64::from(PADDING + AXIS_WIDTH) + i as f64 * pitch + pitch * 0.15,
+                width: pitch * 0.7,
+                delivered_y: bottom - delivered_height,
+                delivered_height,
+                failed_y: bottom - delivered_height - failed_height,
+                failed_height,
+            }
+        })
+        .collect()
+}
+
+/// Labels evenly spaced days under the daily chart.
+fn date_labels(analytics: &DeliveryAnalytics) -> Vec<Label> {
+    let pitch = column_pitch(analytics.daily.len());
+    let step = analytics.daily.len().div_ceil(MAX_DATE_LABELS).max(1);
+    analytics
+        .daily
+        .iter()
+        .enumerate()
+        .step_by(step)
+        .map(|(i, day)| Label {
+            pos: f64::from(PADDING + AXIS_WIDTH) + i as f64 * pitch + pitch / 2.0,
+            text: day.day.format("%b %-d").to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::entity::DailyDeliveryRow;
+
+    fn analytics(daily: Vec<(i64, i64)>) -> DeliveryAnalytics {
+        let since = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        DeliveryAnalytics {
+            since,
+            daily: daily
+                .into_iter()
+                .enumerate()
+                .map(|(i, (delivered, failed))| DailyDeliveryRow {
+                    day: since + chrono::Duration::days(i as i64),
+                    delivered,
+                    failed,
+                })
+                .collect(),
+            by_subscriber_type: vec![],
+            slowest_platforms: vec![],
+        }
+    }
+
+    #[test]
+    fn daily_columns_stack_failures_on_deliveries() {
+        let analytics = analytics(vec![(30, 10), (0, 0)]);
+
+        let columns = daily_columns(&analytics, 0, 40);
+
+        let bottom = f64::from(CHART_HEIGHT);
+        assert_eq!(columns[0].delivered_height, bottom * 0.75);
+        assert_eq!(columns[0].failed_y, 0.0);
+        assert_eq!(columns[1].delivered_height, 0.0);
+        assert_eq!(columns[1].delivered_y, bottom);
+    }
+
+    #[test]
+    fn date_labels_are_capped() {
+        let analytics = analytics(vec![(1, 0); 30]);
+
+        let labels = date_labels(&analytics);
+
+        assert!(labels.len() <= MAX_DATE_LABELS);
+        assert_eq!(labels[0].text, "Oct 1");
+    }
+}
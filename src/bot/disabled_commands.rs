@@ -0,0 +1,60 @@
+//! Per-guild switches turning off top-level commands.
+
+use poise::Command;
+
+use crate::bot::Data;
+use crate::bot::command::Context;
+use crate::bot::command::Error;
+use crate::bot::error::BotError;
+
+/// Commands that can't be turned off, so admins can always turn the others back on.
+pub const ALWAYS_ENABLED_COMMANDS: [&str; 3] = ["settings", "register", "unregister"];
+
+/// Whether a server can turn `command` off. Owner-only, hidden and
+/// [`ALWAYS_ENABLED_COMMANDS`] are not listed.
+pub fn is_toggleable(command: &Command<Data, Error>) -> bool {
+    !command.owners_only
+        && !command.hide_in_help
+        && !ALWAYS_ENABLED_COMMANDS.contains(&command.name.as_ref())
+}
+
+/// Name of `command` as members see it, e.g. "Voice stats" for a context-menu command.
+pub fn display_name(command: &Command<Data, Error>) -> &str {
+    command
+        .context_menu_name
+        .as_deref()
+        .unwrap_or(command.name.as_ref())
+}
+
+/// Command check failing commands the server turned off in `/settings commands`.
+///
+/// Subcommands are turned off with their top-level command. DMs are not affected.
+pub async fn check_command_enabled(ctx: Context<'_>) -> Result<bool, Error> {
+    let Some(guild_id) = ctx.guild_id().map(|id| id.get()) else {
+        return Ok(true);
+    };
+    let command = ctx
+        .parent_commands()
+        .first()
+        .copied()
+        .unwrap_or(ctx.command());
+    if !is_toggleable(command) {
+        return Ok(true);
+    }
+
+    let disabled = ctx
+        .data()
+        .service
+        .settings
+        .get_disabled_commands(guild_id)
+        .await?;
+
+    if disabled.iter().any(|name| name == command.name.as_ref()) {
+        return Err(BotError::PermissionDenied(format!(
+            "`{}` is turned off in this server.",
+            display_name(command)
+        ))
+        .into());
+    }
+    Ok(true)
+}
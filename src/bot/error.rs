@@ -1,5 +1,7 @@
 //! Bot-specific error types.
 
+use crate::error::ErrorCategory;
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum BotError {
@@ -17,4 +19,21 @@ pub enum BotError {
 
     #[error("User not in server")]
     UserNotInGuild(String),
+
+    #[error("{service} failed to respond: {reason}")]
+    UpstreamError { service: String, reason: String },
+}
+
+impl BotError {
+    /// What kind of failure this is.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::InvalidCommandArgument { .. }
+            | Self::GuildOnlyCommand
+            | Self::UserNotInGuild(_) => ErrorCategory::User,
+            Self::PermissionDenied(_) => ErrorCategory::Permission,
+            Self::ConfigurationError(_) => ErrorCategory::Configuration,
+            Self::UpstreamError { .. } => ErrorCategory::Upstream,
+        }
+    }
 }
@@ -1,16 +1,32 @@
 //! Error handling for Discord bot commands.
 
+use chrono::Utc;
 use log::error;
+use log::warn;
 use poise::CreateReply;
 use poise::FrameworkError;
 use poise::serenity_prelude::*;
+use uuid::Uuid;
 
 use crate::bot::Data;
 use crate::bot::Error;
+use crate::bot::bug_report::BugReport;
+use crate::bot::bug_report::BugReportId;
 use crate::bot::error::BotError;
 use crate::error::AppError;
+use crate::error::ErrorCategory;
+use crate::feed::error::FeedError;
+use crate::repo::error::DatabaseError;
 use crate::service::error::ServiceError;
 
+/// An error explained to the user.
+struct ErrorResponse {
+    category: ErrorCategory,
+    description: String,
+    /// Reference ID of an internal error, shown to the user and kept for reporting.
+    ref_id: Option<Uuid>,
+}
+
 /// Handles framework errors and sends appropriate responses to users.
 pub struct ErrorHandler;
 
@@ -19,23 +35,24 @@ impl ErrorHandler {
     pub async fn handle(error: FrameworkError<'_, Data, Error>) {
         match error {
             FrameworkError::Command { error, ctx, .. } => {
-                let (title, description) = Self::classify_error(&error, &ctx);
-                let message = format!(
-                    "### {}\n\n**Command:** `{}`\n**Error:** {}",
-                    title,
-                    ctx.command().qualified_name,
-                    description
-                );
-                Self::send_component(&ctx, &message).await;
+                let response = Self::classify_error(&error, &ctx);
+                Self::send_response(&ctx, &response).await;
             }
             FrameworkError::ArgumentParse { error, ctx, .. } => {
-                let message = format!(
-                    "### ⚠️ Invalid Arguments\n\n**Command:** `/{}`\n**Issue:** {}\n\n> Use `/help {}` for usage information.",
-                    ctx.command().name,
-                    error,
-                    ctx.command().name
-                );
-                Self::send_component(&ctx, &message).await;
+                let response = ErrorResponse {
+                    category: ErrorCategory::User,
+                    description: error.to_string(),
+                    ref_id: None,
+                };
+                Self::send_response(&ctx, &response).await;
+            }
+            FrameworkError::CommandCheckFailed {
+                error: Some(error),
+                ctx,
+                ..
+            } => {
+                let response = Self::classify_error(&error, &ctx);
+                Self::send_response(&ctx, &response).await;
             }
             error => {
                 if let Err(e) = poise::builtins::on_error(error).await {
@@ -45,36 +62,129 @@ impl ErrorHandler {
         }
     }
 
-    /// Classifies an error and returns user-friendly title and description.
-    fn classify_error(
-        error: &Error,
-        ctx: &poise::Context<'_, Data, Error>,
-    ) -> (&'static str, String) {
-        if let Some(bot_error) = error.downcast_ref::<BotError>() {
-            ("❌ Action Failed", bot_error.to_string())
-        } else if let Some(service_error) = error.downcast_ref::<ServiceError>() {
-            ("❌ Service Error", service_error.to_string())
+    /// Classifies an error and returns what to tell the user about it.
+    ///
+    /// Internal errors are logged with a reference ID and kept so the user can report
+    /// them to the bot owner.
+    fn classify_error(error: &Error, ctx: &poise::Context<'_, Data, Error>) -> ErrorResponse {
+        let known = if let Some(e) = error.downcast_ref::<BotError>() {
+            Some((e.category(), e.to_string()))
+        } else if let Some(e) = error.downcast_ref::<ServiceError>() {
+            Some((e.category(), e.to_string()))
+        } else if let Some(e) = error.downcast_ref::<FeedError>() {
+            Some((e.category(), e.to_string()))
+        } else if let Some(e) = error.downcast_ref::<DatabaseError>() {
+            Some((e.category(), e.to_string()))
+        } else if let Some(e) = error.downcast_ref::<AppError>() {
+            Some((e.category(), e.to_string()))
         } else {
-            let ref_id = AppError::log_with_ref(error);
-            error!(
-                "Unexpected error in command `{}`: {:?}",
-                ctx.command().name,
-                error
-            );
-            (
-                "❌ Internal Error",
-                format!(
-                    "An unexpected error occurred. Please contact the bot developer.\n-# Reference ID: {ref_id}"
-                ),
-            )
+            error
+                .downcast_ref::<SerenityError>()
+                .map(Self::classify_discord_error)
+        };
+
+        match known {
+            Some((category, description)) if category != ErrorCategory::Internal => {
+                if category == ErrorCategory::Upstream {
+                    warn!(
+                        "Upstream error in command `{}`: {error:?}",
+                        ctx.command().name
+                    );
+                }
+                ErrorResponse {
+                    category,
+                    description,
+                    ref_id: None,
+                }
+            }
+            _ => {
+                // `AppError::internal_with_ref` already logged the error under its ID
+                let ref_id = match error.downcast_ref::<AppError>() {
+                    Some(AppError::InternalWithRef { ref_id }) => *ref_id,
+                    _ => AppError::log_with_ref(error),
+                };
+                error!(
+                    "Unexpected error in command `{}`: {:?}",
+                    ctx.command().name,
+                    error
+                );
+                ctx.data().bug_reports.insert(
+                    ref_id,
+                    BugReport {
+                        command: ctx.command().qualified_name.to_string(),
+                        user_id: ctx.author().id,
+                        guild_id: ctx.guild_id(),
+                        details: format!("{error:?}"),
+                        occurred_at: Utc::now(),
+                    },
+                );
+                ErrorResponse {
+                    category: ErrorCategory::Internal,
+                    description: "An unexpected error occurred.".to_string(),
+                    ref_id: Some(ref_id),
+                }
+            }
         }
     }
 
-    /// Sends an error message as a Components V2 container.
-    async fn send_component(ctx: &poise::Context<'_, Data, Error>, message: &str) {
-        let components = vec![CreateComponent::Container(CreateContainer::new(vec![
+    /// Classifies a failed Discord request.
+    fn classify_discord_error(error: &SerenityError) -> (ErrorCategory, String) {
+        match error {
+            SerenityError::Http(HttpError::UnsuccessfulRequest(response))
+                if response.status_code.as_u16() == 403 =>
+            {
+                (
+                    ErrorCategory::Permission,
+                    "The bot is missing a permission it needs here.".to_string(),
+                )
+            }
+            SerenityError::Http(HttpError::UnsuccessfulRequest(response))
+                if response.status_code.as_u16() == 404 =>
+            {
+                (
+                    ErrorCategory::User,
+                    "The channel, message or member no longer exists.".to_string(),
+                )
+            }
+            SerenityError::Http(_) => (
+                ErrorCategory::Upstream,
+                "Discord did not accept the request.".to_string(),
+            ),
+            _ => (ErrorCategory::Internal, error.to_string()),
+        }
+    }
+
+    /// Formats the response for an error in the template of its category.
+    fn render_message(command: &str, response: &ErrorResponse) -> String {
+        let mut message = format!(
+            "### {}\n\n**Command:** `/{command}`\n**Error:** {}\n\n> {}",
+            response.category.title(),
+            response.description,
+            response.category.next_steps()
+        );
+        if let Some(ref_id) = response.ref_id {
+            message.push_str(&format!("\n-# Reference ID: {ref_id}"));
+        }
+        message
+    }
+
+    /// Sends an error response as a Components V2 container, with a report button for
+    /// internal errors.
+    async fn send_response(ctx: &poise::Context<'_, Data, Error>, response: &ErrorResponse) {
+        let message = Self::render_message(&ctx.command().qualified_name, response);
+        let mut components = vec![CreateComponent::Container(CreateContainer::new(vec![
             CreateContainerComponent::TextDisplay(CreateTextDisplay::new(message)),
         ]))];
+        if let Some(ref_id) = response.ref_id
+            && response.category.is_reportable()
+        {
+            let button = CreateButton::new(BugReportId(ref_id).custom_id())
+                .label("🐞 Report Bug")
+                .style(ButtonStyle::Secondary);
+            components.push(CreateComponent::ActionRow(CreateActionRow::Buttons(
+                vec![button].into(),
+            )));
+        }
 
         let _ = ctx
             .send(
@@ -85,3 +195,35 @@ impl ErrorHandler {
             .await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_message_adds_next_steps_and_reference() {
+        let ref_id = Uuid::new_v4();
+        let response = ErrorResponse {
+            category: ErrorCategory::Internal,
+            description: "An unexpected error occurred.".to_string(),
+            ref_id: Some(ref_id),
+        };
+
+        let message = ErrorHandler::render_message("vc stats", &response);
+
+        assert!(message.starts_with("### ❌ Internal Error"));
+        assert!(message.contains("**Command:** `/vc stats`"));
+        assert!(message.contains(ErrorCategory::Internal.next_steps()));
+        assert!(message.ends_with(&format!("Reference ID: {ref_id}")));
+    }
+
+    #[test]
+    fn bot_errors_map_to_categories() {
+        let denied = BotError::PermissionDenied("admins only".to_string());
+        let config = BotError::ConfigurationError("no channel".to_string());
+
+        assert_eq!(denied.category(), ErrorCategory::Permission);
+        assert_eq!(config.category(), ErrorCategory::Configuration);
+        assert_eq!(BotError::GuildOnlyCommand.category(), ErrorCategory::User);
+    }
+}
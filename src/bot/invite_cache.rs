@@ -0,0 +1,167 @@
+//! Invite use counts per guild, to tell which invite a new member joined through.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use anyhow::Result;
+use poise::serenity_prelude::*;
+
+use crate::entity::UsedInvite;
+
+/// An invite as last seen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CachedInvite {
+    uses: u64,
+    /// 0 for unlimited.
+    max_uses: u64,
+    inviter_id: Option<u64>,
+}
+
+type GuildInvites = HashMap<String, CachedInvite>;
+
+/// Use counts of each guild's invites, kept in memory.
+///
+/// Discord does not say which invite a member used, so the counts are fetched again on
+/// each join and compared with the ones cached here. Guilds are loaded when they become
+/// available and kept up to date by the invite create and delete events. Listing invites
+/// needs the **Manage Server** permission; guilds without it are never cached.
+#[derive(Default)]
+pub struct InviteCache {
+    guilds: RwLock<HashMap<u64, GuildInvites>>,
+}
+
+impl InviteCache {
+    /// Replaces the cached invites of a guild with the current ones.
+    pub async fn refresh(&self, http: &Http, guild_id: GuildId) -> Result<()> {
+        let invites = Self::fetch(http, guild_id).await?;
+        self.replace(guild_id.get(), invites);
+        Ok(())
+    }
+
+    /// Adds a newly created invite.
+    pub fn insert(&self, guild_id: u64, code: &str, max_uses: u64, inviter_id: Option<u64>) {
+        if let Ok(mut guilds) = self.guilds.write() {
+            guilds.entry(guild_id).or_default().insert(
+                code.to_string(),
+                CachedInvite {
+                    uses: 0,
+                    max_uses,
+                    inviter_id,
+                },
+            );
+        }
+    }
+
+    /// Forgets a deleted invite.
+    pub fn remove(&self, guild_id: u64, code: &str) {
+        if let Ok(mut guilds) = self.guilds.write()
+            && let Some(invites) = guilds.get_mut(&guild_id)
+        {
+            invites.remove(code);
+        }
+    }
+
+    /// Fetches the invites of a guild a member just joined and returns the one they used.
+    ///
+    /// Returns `None` when no single invite can be told apart, e.g. for vanity URL joins,
+    /// members joining at the same moment, or guilds not cached before the join.
+    pub async fn find_used(&self, http: &Http, guild_id: GuildId) -> Result<Option<UsedInvite>> {
+        let current = Self::fetch(http, guild_id).await?;
+        let previous = self.replace(guild_id.get(), current.clone());
+        Ok(previous.and_then(|previous| find_used_invite(&previous, &current)))
+    }
+
+    /// Stores the invites of a guild, returning the ones cached before.
+    fn replace(&self, guild_id: u64, invites: GuildInvites) -> Option<GuildInvites> {
+        self.guilds.write().ok()?.insert(guild_id, invites)
+    }
+
+    async fn fetch(http: &Http, guild_id: GuildId) -> Result<GuildInvites> {
+        let invites = guild_id.invites(http).await?;
+        Ok(invites
+            .into_iter()
+            .map(|invite| {
+                let cached = CachedInvite {
+                    uses: u64::from(invite.uses),
+                    max_uses: u64::from(invite.max_uses),
+                    inviter_id: invite.inviter.as_ref().map(|user| user.id.get()),
+                };
+                (invite.code.to_string(), cached)
+            })
+            .collect())
+    }
+}
+
+/// Returns the invite used between two snapshots of a guild's invites.
+///
+/// That is the invite whose uses went up. Discord deletes an invite once it reaches its
+/// max uses, so an invite that disappeared one use short of its limit counts too. With
+/// more than one candidate, none is picked.
+fn find_used_invite(previous: &GuildInvites, current: &GuildInvites) -> Option<UsedInvite> {
+    let grown = current.iter().filter(|(code, invite)| {
+        let before = previous.get(*code).map_or(0, |p| p.uses);
+        invite.uses > before
+    });
+    let used_up = previous.iter().filter(|(code, invite)| {
+        !current.contains_key(*code) && invite.max_uses > 0 && invite.uses + 1 == invite.max_uses
+    });
+
+    let mut candidates = grown.chain(used_up);
+    let (code, invite) = candidates.next()?;
+    if candidates.next().is_some() {
+        return None;
+    }
+    Some(UsedInvite {
+        code: code.clone(),
+        inviter_id: invite.inviter_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invites(entries: &[(&str, u64, u64, u64)]) -> GuildInvites {
+        entries
+            .iter()
+            .map(|&(code, uses, max_uses, inviter_id)| {
+                let invite = CachedInvite {
+                    uses,
+                    max_uses,
+                    inviter_id: Some(inviter_id),
+                };
+                (code.to_string(), invite)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn find_used_invite_picks_the_grown_invite() {
+        let previous = invites(&[("abc", 3, 0, 1), ("def", 5, 0, 2)]);
+        let current = invites(&[("abc", 3, 0, 1), ("def", 6, 0, 2)]);
+
+        let used = find_used_invite(&previous, &current).unwrap();
+
+        assert_eq!(used.code, "def");
+        assert_eq!(used.inviter_id, Some(2));
+    }
+
+    #[test]
+    fn find_used_invite_counts_invites_used_up() {
+        let previous = invites(&[("abc", 3, 0, 1), ("once", 0, 1, 2)]);
+        let current = invites(&[("abc", 3, 0, 1)]);
+
+        let used = find_used_invite(&previous, &current).unwrap();
+
+        assert_eq!(used.code, "once");
+    }
+
+    #[test]
+    fn find_used_invite_gives_up_when_ambiguous_or_unchanged() {
+        let previous = invites(&[("abc", 3, 0, 1), ("def", 5, 0, 2)]);
+        let both = invites(&[("abc", 4, 0, 1), ("def", 6, 0, 2)]);
+
+        assert_eq!(find_used_invite(&previous, &both), None);
+        assert_eq!(find_used_invite(&previous, &previous), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! Command latency tracking and the auto-defer safety net built on it.
+//!
+//! Discord drops an interaction that is not answered within 3 seconds, showing "The
+//! application did not respond". Commands record how long they took to answer, and
+//! commands that recently took longer than [`AUTO_DEFER_THRESHOLD`] are deferred before
+//! they run, so slow ones like image renders always answer in time. A command answers
+//! with its first message, or with its error if it fails first. The auto-defer itself
+//! doesn't count, or a deferred command would soon be predicted fast again. Commands marked
+//! `ephemeral` are deferred ephemerally, since a public defer would make their private
+//! answer public.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use log::debug;
+use log::warn;
+
+use crate::bot::command::Context;
+
+/// Predicted run time above which a command is deferred before it runs.
+pub const AUTO_DEFER_THRESHOLD: Duration = Duration::from_millis(2500);
+
+/// Runs kept per command.
+const SAMPLES_PER_COMMAND: usize = 20;
+
+/// Recent times to answer of each command, by qualified name.
+#[derive(Default)]
+pub struct CommandLatencies {
+    samples: RwLock<HashMap<String, VecDeque<Duration>>>,
+}
+
+impl CommandLatencies {
+    /// Records a run of `command`, dropping its oldest run past [`SAMPLES_PER_COMMAND`].
+    pub fn record(&self, command: &str, elapsed: Duration) {
+        if let Ok(mut samples) = self.samples.write() {
+            let runs = samples.entry(command.to_string()).or_default();
+            if runs.len() == SAMPLES_PER_COMMAND {
+                runs.pop_front();
+            }
+            runs.push_back(elapsed);
+        }
+    }
+
+    /// Predicts how long `command` will run: the median of its recent runs.
+    pub fn predict(&self, command: &str) -> Option<Duration> {
+        let samples = self.samples.read().ok()?;
+        let mut runs: Vec<Duration> = samples.get(command)?.iter().copied().collect();
+        runs.sort_unstable();
+        runs.get(runs.len() / 2).copied()
+    }
+}
+
+/// Timing of a command run, kept as invocation data.
+struct CommandTiming {
+    start: Instant,
+    /// Time until the command's first message, once sent.
+    answered_after: Option<Duration>,
+}
+
+/// Pre-command hook deferring slash and context-menu commands predicted to run past
+/// [`AUTO_DEFER_THRESHOLD`], ephemerally for commands marked `ephemeral`.
+pub async fn auto_defer(ctx: Context<'_>) {
+    ctx.set_invocation_data(CommandTiming {
+        start: Instant::now(),
+        answered_after: None,
+    })
+    .await;
+
+    let poise::Context::Application(_) = ctx else {
+        return;
+    };
+    let command = &ctx.command().qualified_name;
+    let Some(predicted) = ctx.data().latencies.predict(command) else {
+        return;
+    };
+    if predicted > AUTO_DEFER_THRESHOLD {
+        debug!("Deferring `{command}`, predicted to take {predicted:?}");
+        let deferred = if ctx.command().ephemeral {
+            ctx.defer_ephemeral().await
+        } else {
+            ctx.defer().await
+        };
+        if let Err(e) = deferred {
+            warn!("Failed to defer `{command}`: {e}");
+        }
+    }
+}
+
+/// Notes that the command sent its first message. Later calls are ignored, so views
+/// waiting on the user afterwards don't count.
+pub async fn mark_answered(ctx: Context<'_>) {
+    if let Some(mut timing) = ctx.invocation_data::<CommandTiming>().await
+        && timing.answered_after.is_none()
+    {
+        timing.answered_after = Some(timing.start.elapsed());
+    }
+}
+
+/// Post-command and error hook recording how long a command took to answer: until its
+/// first message if it sent one, otherwise until it finished or failed.
+pub async fn record_latency(ctx: Context<'_>) {
+    let Some(elapsed) = ctx.invocation_data::<CommandTiming>().await.map(|timing| {
+        timing
+            .answered_after
+            .unwrap_or_else(|| timing.start.elapsed())
+    }) else {
+        return;
+    };
+    ctx.data()
+        .latencies
+        .record(&ctx.command().qualified_name, elapsed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predict_is_median_of_recent_runs() {
+        let latencies = CommandLatencies::default();
+        assert_eq!(latencies.predict("vc leaderboard"), None);
+
+        for ms in [100, 4000, 3000] {
+            latencies.record("vc leaderboard", Duration::from_millis(ms));
+        }
+        assert_eq!(
+            latencies.predict("vc leaderboard"),
+            Some(Duration::from_millis(3000))
+        );
+    }
+
+    #[test]
+    fn record_keeps_latest_runs() {
+        let latencies = CommandLatencies::default();
+        latencies.record("vc report", Duration::from_secs(5));
+        for _ in 0..SAMPLES_PER_COMMAND {
+            latencies.record("vc report", Duration::from_millis(200));
+        }
+
+        assert_eq!(
+            latencies.predict("vc report"),
+            Some(Duration::from_millis(200))
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Lifecycle state of a bot's Discord client.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+
+/// Where a bot's client is in its lifecycle.
+///
+/// ```text
+/// Created → Connecting → Ready → Stopping → Stopped
+///               ↑          │
+///               └──────────┘ reconnect
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BotState {
+    /// Built but not started.
+    Created,
+    /// Building the client or waiting for Discord's `Ready`, including after a disconnect.
+    Connecting,
+    /// At least one shard received `Ready`.
+    Ready,
+    /// Shutdown was requested.
+    Stopping,
+    /// The client is gone and won't be rebuilt.
+    Stopped,
+}
+
+impl BotState {
+    /// Whether the client may move from this state to `next`.
+    pub fn can_transition_to(self, next: BotState) -> bool {
+        use BotState::*;
+        matches!(
+            (self, next),
+            (Created, Connecting)
+                | (Connecting, Ready)
+                | (Ready, Connecting)
+                | (Created | Connecting | Ready, Stopping)
+                | (Stopping, Stopped)
+        )
+    }
+}
+
+impl fmt::Display for BotState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BotState::Created => "Created",
+            BotState::Connecting => "Connecting",
+            BotState::Ready => "Ready",
+            BotState::Stopping => "Stopping",
+            BotState::Stopped => "Stopped",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A transition the lifecycle doesn't allow.
+#[derive(Debug, thiserror::Error)]
+#[error("Bot can't go from {from} to {to}")]
+pub struct InvalidTransition {
+    pub from: BotState,
+    pub to: BotState,
+}
+
+/// Observable lifecycle of one bot's client.
+pub struct Lifecycle {
+    state: watch::Sender<BotState>,
+    ready_shards: Mutex<BTreeSet<u32>>,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lifecycle {
+    pub fn new() -> Self {
+        Self {
+            state: watch::Sender::new(BotState::Created),
+            ready_shards: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> BotState {
+        *self.state.borrow()
+    }
+
+    /// Returns a receiver notified on every state change.
+    pub fn subscribe(&self) -> watch::Receiver<BotState> {
+        self.state.subscribe()
+    }
+
+    /// Moves to `next`, returning the previous state.
+    pub fn transition(&self, next: BotState) -> Result<BotState, InvalidTransition> {
+        let mut result = Ok(next);
+        self.state.send_if_modified(|state| {
+            if !state.can_transition_to(next) {
+                result = Err(InvalidTransition {
+                    from: *state,
+                    to: next,
+                });
+                return false;
+            }
+            result = Ok(*state);
+            *state = next;
+            true
+        });
+
+        if matches!(next, BotState::Connecting | BotState::Stopped) && result.is_ok() {
+            // Shards report `Ready` again after reconnecting
+            self.ready_shards.lock().unwrap().clear();
+        }
+        result
+    }
+
+    /// Whether shutdown was requested or finished.
+    pub fn is_stopping(&self) -> bool {
+        matches!(self.state(), BotState::Stopping | BotState::Stopped)
+    }
+
+    /// Waits until the client reaches `state`.
+    pub async fn wait_for(&self, state: BotState) {
+        let mut rx = self.subscribe();
+        // The sender lives as long as `self`, so the channel can't close while waiting
+        let _ = rx.wait_for(|current| *current == state).await;
+    }
+
+    /// Waits until shutdown is requested.
+    pub async fn stop_requested(&self) {
+        let mut rx = self.subscribe();
+        let _ = rx
+            .wait_for(|current| matches!(current, BotState::Stopping | BotState::Stopped))
+            .await;
+    }
+
+    /// Records that a shard received `Ready`.
+    pub fn shard_ready(&self, shard_id: u32) {
+        self.ready_shards.lock().unwrap().insert(shard_id);
+    }
+
+    /// Returns the IDs of shards that received `Ready` since the client last connected.
+    pub fn ready_shards(&self) -> Vec<u32> {
+        self.ready_shards.lock().unwrap().iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_is_only_allowed_once() {
+        let lifecycle = Lifecycle::new();
+
+        assert_eq!(
+            lifecycle.transition(BotState::Connecting).unwrap(),
+            BotState::Created
+        );
+        assert!(lifecycle.transition(BotState::Connecting).is_err());
+        assert_eq!(lifecycle.state(), BotState::Connecting);
+    }
+
+    #[test]
+    fn reconnect_clears_ready_shards() {
+        let lifecycle = Lifecycle::new();
+        lifecycle.transition(BotState::Connecting).unwrap();
+        lifecycle.transition(BotState::Ready).unwrap();
+        lifecycle.shard_ready(0);
+        lifecycle.shard_ready(1);
+        assert_eq!(lifecycle.ready_shards(), vec![0, 1]);
+
+        lifecycle.transition(BotState::Connecting).unwrap();
+
+        assert!(lifecycle.ready_shards().is_empty());
+    }
+
+    #[tokio::test]
+    async fn shutdown_waits_for_stopped() {
+        let lifecycle = std::sync::Arc::new(Lifecycle::new());
+        lifecycle.transition(BotState::Connecting).unwrap();
+        lifecycle.transition(BotState::Stopping).unwrap();
+
+        let waiter = tokio::spawn({
+            let lifecycle = lifecycle.clone();
+            async move { lifecycle.wait_for(BotState::Stopped).await }
+        });
+        lifecycle.transition(BotState::Stopped).unwrap();
+
+        waiter.await.unwrap();
+        assert!(lifecycle.transition(BotState::Connecting).is_err());
+    }
+}
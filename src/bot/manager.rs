@@ -0,0 +1,120 @@
+//! Runs every configured Discord bot in one process.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use log::error;
+use log::info;
+use log::warn;
+use tokio::task::JoinHandle;
+
+use crate::bot::Bot;
+use crate::bot::SharedState;
+use crate::bot::avatar_cache;
+use crate::bot::avatar_cache::AvatarCache;
+use crate::bot::render::ImageRenderService;
+use crate::bot::supervisor::ClientMonitor;
+use crate::config::Config;
+use crate::event::event_bus::EventBus;
+use crate::feed::Platforms;
+use crate::service::Services;
+use crate::subscriber::voice_state::VoiceStateSubscriber;
+
+/// Starts and supervises the main bot and every bot listed in `EXTRA_BOTS`.
+///
+/// All bots share the database, services, event bus and [`SharedState`]. Each registers its own commands.
+/// Feed notifications and voice tracking go through the main bot only.
+pub struct BotManager {
+    bots: Vec<Arc<Bot>>,
+    shared: SharedState,
+}
+
+impl BotManager {
+    /// Creates and starts a client for each configured bot, main bot first.
+    pub async fn start(
+        config: Arc<Config>,
+        event_bus: Arc<EventBus>,
+        platforms: Arc<Platforms>,
+        services: Arc<Services>,
+        voice_subscriber: Arc<VoiceStateSubscriber>,
+    ) -> Result<Self> {
+        let profiles = config.bot_profiles();
+        let shared = SharedState {
+            clients: Arc::new(ClientMonitor::new(
+                profiles.iter().map(|profile| profile.name.clone()),
+            )),
+            avatars: Arc::new(AvatarCache::new(
+                config.data_path.join("cache").join("avatars"),
+                avatar_cache::DEFAULT_MAX_BYTES,
+            )?),
+            renderer: Arc::new(ImageRenderService::default()),
+        };
+        let mut bots = Vec::new();
+
+        for profile in profiles {
+            let mut bot = Bot::new(
+                config.clone(),
+                profile,
+                event_bus.clone(),
+                platforms.clone(),
+                services.clone(),
+                voice_subscriber.clone(),
+                shared.clone(),
+            )
+            .await?;
+
+            let handle = bot.start()?;
+            Self::watch(bot.name.clone(), handle);
+            bots.push(Arc::new(bot));
+        }
+
+        info!("Started {} Discord bot(s).", bots.len());
+        Ok(Self { bots, shared })
+    }
+
+    /// Returns the bot configured by `DISCORD_TOKEN`.
+    pub fn main(&self) -> &Arc<Bot> {
+        // `Config::bot_profiles` always puts the main bot first
+        &self.bots[0]
+    }
+
+    /// Returns the bot with the given name.
+    pub fn get(&self, name: &str) -> Option<&Arc<Bot>> {
+        self.bots.iter().find(|bot| bot.name == name)
+    }
+
+    /// Returns every running bot, main bot first.
+    pub fn bots(&self) -> &[Arc<Bot>] {
+        &self.bots
+    }
+
+    /// Returns the connection health of every bot.
+    pub fn clients(&self) -> &Arc<ClientMonitor> {
+        &self.shared.clients
+    }
+
+    /// Returns the image renderer shared by every bot.
+    pub fn renderer(&self) -> &Arc<ImageRenderService> {
+        &self.shared.renderer
+    }
+
+    /// Stops every bot, waiting at most `timeout` for each client to shut down.
+    pub async fn shutdown(&self, timeout: Duration) {
+        for bot in &self.bots {
+            if tokio::time::timeout(timeout, bot.shutdown()).await.is_err() {
+                warn!("{} bot did not shut down in time.", bot.name);
+            }
+        }
+    }
+
+    /// Reports when a bot's supervisor crashes, so one dead bot doesn't go unnoticed while
+    /// the others keep running.
+    fn watch(name: String, handle: JoinHandle<()>) {
+        tokio::spawn(async move {
+            if let Err(e) = handle.await {
+                error!("{name} bot supervisor crashed: {e}");
+            }
+        });
+    }
+}
@@ -4,14 +4,32 @@
 //! and the [`BotEventHandler`] which processes gateway events. It acts as the
 //! bridge between the Discord gateway and the application's internal services.
 
+pub mod avatar_cache;
+pub mod bug_report;
 pub mod checks;
 pub mod command;
+pub mod delivery_report;
+pub mod disabled_commands;
 pub mod error;
 pub mod error_handler;
+pub mod invite_cache;
+pub mod latency;
+pub mod lifecycle;
+pub mod manager;
 pub mod navigation;
+pub mod prefix;
+pub mod render;
+pub mod report;
+pub mod scheduled_event;
+pub mod send_queue;
+pub mod supervisor;
+pub mod temp_voice;
 pub mod test_framework;
+pub mod user_resolver;
 pub mod utils;
 pub mod view;
+pub mod watch_party;
+pub mod welcome;
 
 use std::collections::HashSet;
 use std::str::FromStr;
@@ -22,65 +40,141 @@ use std::time::Instant;
 use anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
-use futures::lock::Mutex;
 use log::debug;
 use log::error;
 use log::info;
 use poise::Framework;
 use poise::FrameworkOptions;
 use poise::serenity_prelude::*;
+use tokio::task::JoinHandle;
 
 type Error = Box<dyn std::error::Error + Send + Sync>;
 
+use crate::bot::avatar_cache::AvatarCache;
+use crate::bot::bug_report::BugReportId;
+use crate::bot::bug_report::BugReports;
 use crate::bot::command::Cog;
 use crate::bot::command::Cogs;
+use crate::bot::command::welcome::image_generator::WelcomeImageGenerator;
 use crate::bot::error_handler::ErrorHandler;
+use crate::bot::invite_cache::InviteCache;
+use crate::bot::latency::CommandLatencies;
+use crate::bot::lifecycle::BotState;
+use crate::bot::prefix::PrefixCache;
+use crate::bot::render::ImageRenderService;
+use crate::bot::send_queue::SendQueue;
+use crate::bot::supervisor::ClientHealth;
+use crate::bot::supervisor::ClientMonitor;
+use crate::bot::temp_voice::TempVoiceAction;
+use crate::bot::user_resolver::UserResolver;
+use crate::config::BotProfile;
 use crate::config::Config;
 use crate::entity::BotMetaKey;
+use crate::entity::EmojiKind;
+use crate::entity::EmojiUse;
+use crate::entity::VoiceSettings;
 use crate::event::VoiceStateEvent;
+use crate::event::WatchPartyId;
 use crate::event::event_bus::EventBus;
 use crate::feed::Platforms;
+use crate::readiness;
 use crate::service::Services;
+use crate::service::emoji_stats::parse_custom_emojis;
 use crate::subscriber::voice_state::VoiceStateSubscriber;
 
 /// Data shared across bot commands and contexts.
 pub struct Data {
     pub config: Arc<Config>,
+    /// The bot these commands run on.
+    pub profile: BotProfile,
+    pub event_bus: Arc<EventBus>,
     pub platforms: Arc<Platforms>,
     pub service: Arc<Services>,
+    pub prefixes: PrefixCache,
+    /// Invite use counts, to tell which invite a new member used.
+    pub invites: InviteCache,
+    /// Internal errors users can still report to the bot owner.
+    pub bug_reports: BugReports,
+    /// Recent run times of each command, to defer slow ones in time.
+    pub latencies: CommandLatencies,
+    pub send_queue: Arc<SendQueue>,
+    /// Cached display names and avatars for rendering.
+    pub users: UserResolver,
+    /// Avatar images cached on disk for rendering.
+    pub avatars: Arc<AvatarCache>,
+    /// Runs image rendering off the async runtime.
+    pub renderer: Arc<ImageRenderService>,
+    /// Connection health of this bot.
+    pub health: Arc<ClientHealth>,
+    /// Connection health of every bot in the process.
+    pub clients: Arc<ClientMonitor>,
     pub start_time: Instant,
 }
 
+/// State shared by every bot in the process.
+#[derive(Clone)]
+pub struct SharedState {
+    pub clients: Arc<ClientMonitor>,
+    pub avatars: Arc<AvatarCache>,
+    pub renderer: Arc<ImageRenderService>,
+}
+
 /// Discord bot client and framework.
 pub struct Bot {
+    pub name: String,
     pub cache: Arc<Cache>,
     pub http: Arc<Http>,
+    pub send_queue: Arc<SendQueue>,
+    pub health: Arc<ClientHealth>,
     client_builder: Option<ClientBuilder>,
-    client: Arc<Mutex<Option<Client>>>,
+    client_factory: ClientFactory,
 }
 
 impl Bot {
     /// Creates a new bot instance with all required components.
     pub async fn new(
         config: Arc<Config>,
+        profile: BotProfile,
         event_bus: Arc<EventBus>,
         platforms: Arc<Platforms>,
         service: Arc<Services>,
         voice_subscriber: Arc<VoiceStateSubscriber>,
+        shared: SharedState,
     ) -> Result<Self> {
-        info!("Initializing bot...");
-
-        let (token, intents) = Self::create_client_config(&config)?;
-        let framework = Self::create_framework(&config)?;
+        info!("Initializing {} bot...", profile.name);
+
+        let (token, intents) = Self::create_client_config(&config, &profile)?;
+        let SharedState {
+            clients,
+            avatars,
+            renderer,
+        } = shared;
+        let health = clients
+            .get(&profile.name)
+            .ok_or_else(|| anyhow::anyhow!("Bot {} is not monitored", profile.name))?;
         let http = Http::new(token.clone());
-        if let Some(application_id) = config.discord_application_id {
+        if let Some(application_id) = profile.application_id {
             http.set_application_id(ApplicationId::new(application_id));
         }
-        let http = Arc::new(http);
+        let send_queue = Arc::new(SendQueue::new(http));
+        let http = send_queue.http().clone();
+        let name = profile.name.clone();
         let data = Arc::new(Data {
             config: config.clone(),
+            profile,
+            event_bus: event_bus.clone(),
             platforms,
             service,
+            prefixes: PrefixCache::default(),
+            invites: InviteCache::default(),
+            bug_reports: BugReports::default(),
+            latencies: CommandLatencies::default(),
+            send_queue: send_queue.clone(),
+            users: UserResolver::default(),
+            avatars,
+            renderer,
+            health: health.clone(),
+            clients,
             start_time: Instant::now(),
         });
 
@@ -91,50 +185,62 @@ impl Bot {
             http.clone(),
         ));
 
-        let client_builder = ClientBuilder::new(token.clone(), intents)
-            .event_handler(event_handler)
-            .framework(framework)
-            .data(data)
-            .activity(ActivityData::playing(format!(
-                "v{}",
-                config.version.clone()
-            )));
+        let client_factory = ClientFactory {
+            config,
+            token,
+            intents,
+            event_handler,
+            data,
+        };
+        let client_builder = client_factory.build()?;
 
         Ok(Self {
+            name,
             cache: Arc::new(Cache::default()),
             http,
+            send_queue,
+            health,
             client_builder: Some(client_builder),
-            client: Arc::new(Mutex::new(None)),
+            client_factory,
         })
     }
 
     /// Starts the bot client in a background task.
-    pub fn start(&mut self) {
-        info!("Starting bot client...");
-        let client_builder = self.client_builder.take().expect("start() called twice");
-        let client = self.client.clone();
-
-        tokio::spawn(async move {
-            info!("Connecting bot to Discord...");
-
-            let built_client = client_builder
-                .await
-                .expect("Failed to build Discord client");
-
-            *client.lock().await = Some(built_client);
-            info!("Bot connected to Discord.");
-
-            client
-                .lock()
-                .await
-                .as_mut()
-                .unwrap()
-                .start()
-                .await
-                .expect("Bot client crashed");
+    ///
+    /// The client is rebuilt whenever it stops, so the returned handle finishes once
+    /// [`Bot::shutdown`] is called. Fails if the bot was already started.
+    pub fn start(&mut self) -> Result<JoinHandle<()>> {
+        self.health.lifecycle().transition(BotState::Connecting)?;
+        info!("Starting {} bot client...", self.name);
+        let client_builder = self
+            .client_builder
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Bot {} has no client to start", self.name))?;
+        let client_factory = self.client_factory.clone();
+        let health = self.health.clone();
+
+        let handle = tokio::spawn(async move {
+            info!("Connecting {} bot to Discord...", health.name());
+            supervisor::supervise(health, client_builder, || client_factory.build()).await;
         });
 
-        info!("Bot client start initiated.");
+        info!("{} bot client start initiated.", self.name);
+        Ok(handle)
+    }
+
+    /// Stops the bot client and waits until it is gone.
+    pub async fn shutdown(&self) {
+        let lifecycle = self.health.lifecycle();
+        match lifecycle.transition(BotState::Stopping) {
+            // Never started, so there is no supervisor to finish the shutdown
+            Ok(BotState::Created) => {
+                let _ = lifecycle.transition(BotState::Stopped);
+            }
+            Ok(_) => info!("Stopping {} bot client...", self.name),
+            // Already stopping
+            Err(_) => {}
+        }
+        lifecycle.wait_for(BotState::Stopped).await;
     }
 
     /// Creates the Poise framework with commands and configuration.
@@ -142,8 +248,17 @@ impl Bot {
         let options = FrameworkOptions::<Data, Error> {
             commands: Cogs.commands(),
             on_error: |error| Box::pin(Self::on_error(error)),
+            command_check: Some(|ctx| Box::pin(disabled_commands::check_command_enabled(ctx))),
+            // Text commands don't arrive as interactions, so mark them here
+            pre_command: |ctx| {
+                Box::pin(async move {
+                    ctx.data().send_queue.mark_interaction();
+                    latency::auto_defer(ctx).await;
+                })
+            },
+            post_command: |ctx| Box::pin(latency::record_latency(ctx)),
             prefix_options: poise::PrefixFrameworkOptions {
-                prefix: Some("!".into()),
+                dynamic_prefix: Some(|ctx| Box::pin(prefix::dynamic_prefix(ctx))),
                 edit_tracker: Some(Arc::new(poise::EditTracker::for_timespan(
                     Duration::from_secs(3600),
                 ))),
@@ -160,23 +275,65 @@ impl Bot {
     }
 
     /// Creates Discord client configuration (token and intents).
-    fn create_client_config(config: &Config) -> Result<(Token, GatewayIntents)> {
-        let token = Token::from_str(&config.discord_token)?;
-        let intents = GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT;
-        Ok((token, intents))
+    fn create_client_config(
+        config: &Config,
+        profile: &BotProfile,
+    ) -> Result<(Token, GatewayIntents)> {
+        let token = Token::from_str(&profile.token)?;
+        Ok((token, Self::gateway_intents(config, profile)))
+    }
+
+    /// Returns the gateway intents a bot requests.
+    pub fn gateway_intents(config: &Config, profile: &BotProfile) -> GatewayIntents {
+        let mut intents = GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT;
+        // Privileged, so only requested from the bot that handles member joins
+        if config.features.member_events && profile.is_main() {
+            intents |= GatewayIntents::GUILD_MEMBERS;
+        }
+        intents
     }
 
     async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+        // Failed runs answer with their error, so they count towards the prediction too
+        if let Some(ctx) = error.ctx() {
+            latency::record_latency(ctx).await;
+        }
         ErrorHandler::handle(error).await;
     }
 }
 
+/// Builds a fresh client for a bot, first at startup and again after every disconnect.
+#[derive(Clone)]
+struct ClientFactory {
+    config: Arc<Config>,
+    token: Token,
+    intents: GatewayIntents,
+    event_handler: Arc<BotEventHandler>,
+    data: Arc<Data>,
+}
+
+impl ClientFactory {
+    fn build(&self) -> Result<ClientBuilder> {
+        let framework = Bot::create_framework(&self.config)?;
+
+        Ok(ClientBuilder::new(self.token.clone(), self.intents)
+            .event_handler(self.event_handler.clone())
+            .framework(framework)
+            .data(self.data.clone())
+            .activity(ActivityData::playing(format!(
+                "v{}",
+                self.config.version.clone()
+            ))))
+    }
+}
+
 /// Event handler for Discord gateway events.
 pub struct BotEventHandler {
     event_bus: Arc<EventBus>,
     data: Arc<Data>,
     voice_subscriber: Arc<VoiceStateSubscriber>,
     http: Arc<poise::serenity_prelude::Http>,
+    welcome_gen: WelcomeImageGenerator,
 }
 
 impl BotEventHandler {
@@ -191,6 +348,105 @@ impl BotEventHandler {
             data,
             voice_subscriber,
             http,
+            welcome_gen: WelcomeImageGenerator::new(),
+        }
+    }
+
+    /// Whether this bot reports voice activity.
+    ///
+    /// Only the main bot does. Bots sharing a guild would otherwise track every session twice.
+    fn tracks_voice(&self) -> bool {
+        self.data.profile.is_main()
+    }
+
+    /// Whether this bot counts emoji and sticker uses.
+    ///
+    /// Only the main bot does, for the same reason as [`Self::tracks_voice`].
+    fn tracks_emojis(&self) -> bool {
+        self.data.profile.is_main()
+    }
+
+    /// Whether this bot handles member joins, sending welcome cards and tracking invites.
+    ///
+    /// Only the main bot does, so members are welcomed once.
+    fn tracks_members(&self) -> bool {
+        self.data.profile.is_main() && self.data.config.features.member_events
+    }
+
+    /// Records the invite a new member used and sends the guild's welcome card.
+    async fn welcome_member(&self, ctx: &poise::serenity_prelude::Context, member: &Member) {
+        let guild_id = member.guild_id;
+        let used = match self.data.invites.find_used(&ctx.http, guild_id).await {
+            Ok(used) => used,
+            Err(e) => {
+                debug!("Failed to list invites of guild {guild_id}: {e}");
+                None
+            }
+        };
+        let recorded = self
+            .data
+            .service
+            .invite_tracking
+            .record_join(guild_id.get(), member.user.id.get(), used.as_ref())
+            .await;
+        if let Err(e) = recorded {
+            error!(
+                "Failed to record join of {} in guild {guild_id}: {e}",
+                member.user.id
+            );
+        }
+
+        let inviter_id = used.and_then(|used| used.inviter_id).map(UserId::new);
+        let sent = welcome::send_welcome(ctx, &self.data, &self.welcome_gen, member, inviter_id);
+        if let Err(e) = sent.await {
+            error!("Failed to send welcome card in guild {guild_id}: {e}");
+        }
+    }
+
+    /// Counts the uses of the guild's own emojis and stickers among `uses`.
+    ///
+    /// Emojis from other servers are left out. Names are taken from the cache, since
+    /// reactions may not carry one.
+    async fn record_emoji_uses(
+        &self,
+        ctx: &poise::serenity_prelude::Context,
+        guild_id: GuildId,
+        uses: Vec<EmojiUse>,
+    ) {
+        let uses: Vec<EmojiUse> = {
+            let Some(guild) = ctx.cache.guild(guild_id) else {
+                return;
+            };
+            uses.into_iter()
+                .filter_map(|mut emoji| {
+                    emoji.name = match emoji.kind {
+                        EmojiKind::Emoji => guild
+                            .emojis
+                            .get(&EmojiId::new(emoji.emoji_id))?
+                            .name
+                            .to_string(),
+                        EmojiKind::Sticker => guild
+                            .stickers
+                            .get(&StickerId::new(emoji.emoji_id))?
+                            .name
+                            .to_string(),
+                    };
+                    Some(emoji)
+                })
+                .collect()
+        };
+        if uses.is_empty() {
+            return;
+        }
+
+        let recorded = self
+            .data
+            .service
+            .emoji_stats
+            .record_uses(guild_id.get(), &uses)
+            .await;
+        if let Err(e) = recorded {
+            error!("Failed to record emoji uses in guild {guild_id}: {e}");
         }
     }
 
@@ -211,11 +467,17 @@ impl BotEventHandler {
                 continue;
             }
 
+            let voice_settings = self
+                .data
+                .service
+                .voice_tracking
+                .voice_settings(guild_id.get())
+                .await;
             let voice_states = {
                 let Some(guild) = ctx.cache.guild(guild_id) else {
                     continue;
                 };
-                self.collect_voice_states_from_guild(&guild)
+                self.collect_voice_states_from_guild(&guild, &voice_settings)
             };
 
             for (user_id, guild_id, channel_id, session_id) in voice_states {
@@ -237,12 +499,14 @@ impl BotEventHandler {
         }
     }
 
-    /// Collects voice state data from a guild reference.
+    /// Collects voice state data from a guild reference, keeping only states the guild's
+    /// voice settings track.
     /// For large guilds the member list may be incomplete on `GuildCreate`; in that case
     /// we default to treating unknown users as non-bots (better to over-track than under-track).
     fn collect_voice_states_from_guild(
         &self,
         guild: &Guild,
+        voice_settings: &VoiceSettings,
     ) -> Vec<(u64, u64, u64, small_fixed_array::FixedString)> {
         guild
             .voice_states
@@ -257,7 +521,9 @@ impl BotEventHandler {
                     .map(|m| m.user.bot())
                     .unwrap_or(false);
 
-                if is_bot {
+                let is_stage = Self::is_stage_channel(guild, channel_id);
+
+                if !voice_settings.tracks(is_bot, is_stage) {
                     return None;
                 }
 
@@ -271,48 +537,82 @@ impl BotEventHandler {
             .collect()
     }
 
-    /// Registers commands globally if the bot version has changed.
+    /// Whether a cached guild channel is a stage channel.
+    fn is_stage_channel(guild: &Guild, channel_id: ChannelId) -> bool {
+        guild
+            .channels
+            .get(&channel_id)
+            .is_some_and(|channel| channel.kind == ChannelType::Stage)
+    }
+
+    /// Registers commands if the bot version has changed: in the dev guilds if any are
+    /// configured, globally otherwise.
     async fn register_commands_if_needed(&self) {
         if !self.data.config.features.autoregister_cmds {
-            info!(
-                "Autoregister command feature is disabled. Commands will not be registered globally."
-            );
+            info!("Autoregister command feature is disabled. Commands will not be registered.");
             return;
         }
 
         let current_version = self.data.config.version.clone();
         let service = self.data.service.internal.clone();
+        let profile = &self.data.profile;
+        // Each bot registers its own commands, so each tracks its own version
+        let version_key = || match profile.is_main() {
+            true => BotMetaKey::BotVersion,
+            false => BotMetaKey::ExtraBotVersion(profile.name.clone()),
+        };
 
         // Get stored version from database
-        let stored_version = service.get_meta(BotMetaKey::BotVersion).await;
+        let stored_version = service.get_meta(version_key()).await;
 
         match stored_version {
             Ok(Some(version)) if version == current_version => {
                 debug!("Bot version unchanged ({current_version})");
             }
             _ => {
-                // Version mismatch or not found - register commands globally
+                // Version mismatch or not found - register commands
+                let dev_guild_ids = &self.data.config.dev_guild_ids;
+                let scope = if dev_guild_ids.is_empty() {
+                    "globally".to_string()
+                } else {
+                    format!("in {} dev guild(s)", dev_guild_ids.len())
+                };
                 info!(
-                    "Bot version changed or not found. Registering commands globally (current: {}, stored: {:?})",
+                    "Bot version changed or not found. Registering commands {} (current: {}, stored: {:?})",
+                    scope,
                     current_version,
                     stored_version.ok().flatten()
                 );
 
                 let commands = Cogs.commands();
-                match poise::builtins::register_globally(&self.http, &commands).await {
+                let result = if dev_guild_ids.is_empty() {
+                    poise::builtins::register_globally(&self.http, &commands).await
+                } else {
+                    let mut result = Ok(());
+                    for guild_id in dev_guild_ids {
+                        result = poise::builtins::register_in_guild(
+                            &self.http,
+                            &commands,
+                            GuildId::new(*guild_id),
+                        )
+                        .await;
+                        if result.is_err() {
+                            break;
+                        }
+                    }
+                    result
+                };
+                match result {
                     Ok(_) => {
-                        info!("Commands registered globally successfully");
+                        info!("Commands registered {scope} successfully");
 
                         // Update stored version
-                        if let Err(e) = service
-                            .set_meta(BotMetaKey::BotVersion, current_version)
-                            .await
-                        {
+                        if let Err(e) = service.set_meta(version_key(), current_version).await {
                             error!("Failed to update bot version in database: {e}");
                         }
                     }
                     Err(e) => {
-                        error!("Failed to register commands globally: {e}");
+                        error!("Failed to register commands {scope}: {e}");
                     }
                 }
             }
@@ -325,13 +625,42 @@ impl poise::serenity_prelude::EventHandler for BotEventHandler {
     async fn dispatch(&self, ctx: &poise::serenity_prelude::Context, event: &FullEvent) {
         match event {
             FullEvent::Ready { .. } => {
-                info!("Bot is ready, scanning voice channels...");
-                self.scan_voice_channels(ctx).await;
+                let lifecycle = self.data.health.lifecycle();
+                // Only the first shard moves the bot to `Ready`. The rest are already there
+                let _ = lifecycle.transition(BotState::Ready);
+                lifecycle.shard_ready(u32::from(ctx.shard_id.get()));
+
+                if self.tracks_voice() {
+                    info!("Bot is ready, scanning voice channels...");
+                    self.scan_voice_channels(ctx).await;
+                }
 
                 // Check if commands need to be re-registered
                 self.register_commands_if_needed().await;
+
+                if self.data.profile.is_main()
+                    && let Some(ready_file) = &self.data.config.ready_file
+                    && let Err(e) = readiness::mark_ready(ready_file)
+                {
+                    error!("Failed to write ready file {}: {e}", ready_file.display());
+                }
             }
             FullEvent::GuildCreate { guild, .. } => {
+                // Listing invites needs Manage Server, which many guilds don't grant
+                if self.tracks_members()
+                    && let Err(e) = self.data.invites.refresh(&ctx.http, guild.id).await
+                {
+                    debug!("Failed to list invites of guild {}: {e}", guild.id);
+                }
+                if !self.tracks_voice() {
+                    return;
+                }
+                if let Err(e) = temp_voice::clean_up_guild(ctx, &self.data, guild).await {
+                    error!(
+                        "Failed to clean up temporary channels of guild {}: {e}",
+                        guild.id
+                    );
+                }
                 let is_enabled = self
                     .data
                     .service
@@ -343,7 +672,13 @@ impl poise::serenity_prelude::EventHandler for BotEventHandler {
                     return;
                 }
 
-                let voice_states = self.collect_voice_states_from_guild(guild);
+                let voice_settings = self
+                    .data
+                    .service
+                    .voice_tracking
+                    .voice_settings(guild.id.get())
+                    .await;
+                let voice_states = self.collect_voice_states_from_guild(guild, &voice_settings);
                 let mut tracked = 0u32;
 
                 for (user_id, guild_id, channel_id, session_id) in voice_states {
@@ -367,11 +702,111 @@ impl poise::serenity_prelude::EventHandler for BotEventHandler {
                     );
                 }
             }
-            FullEvent::VoiceStateUpdate { old, new, .. } => {
+            FullEvent::InteractionCreate { interaction, .. } => {
+                // Let command and component responses go ahead of bulk notifications
+                self.data.send_queue.mark_interaction();
+
+                // Notification buttons outlive the views that collect other components
+                let Interaction::Component(component) = interaction else {
+                    return;
+                };
+                if let Some(id) = WatchPartyId::parse(&component.data.custom_id) {
+                    let handled = watch_party::handle(&ctx.http, &self.data, component, id);
+                    if let Err(e) = handled.await {
+                        error!("Failed to answer watch party button: {e:?}");
+                    }
+                } else if let Some(id) = BugReportId::parse(&component.data.custom_id) {
+                    let handled = bug_report::handle(&ctx.http, &self.data, component, id);
+                    if let Err(e) = handled.await {
+                        error!("Failed to answer bug report button: {e:?}");
+                    }
+                } else if let Some(action) = TempVoiceAction::parse(&component.data.custom_id) {
+                    let handled = temp_voice::handle(ctx, &self.data, component, action);
+                    if let Err(e) = handled.await {
+                        error!("Failed to answer temporary channel button: {e:?}");
+                    }
+                }
+            }
+            FullEvent::Message { new_message, .. } if self.tracks_emojis() => {
+                let Some(guild_id) = new_message.guild_id else {
+                    return;
+                };
+                if new_message.author.bot() {
+                    return;
+                }
+                let mut uses = parse_custom_emojis(&new_message.content);
+                uses.extend(new_message.sticker_items.iter().map(|sticker| EmojiUse {
+                    emoji_id: sticker.id.get(),
+                    kind: EmojiKind::Sticker,
+                    name: sticker.name.to_string(),
+                    animated: false,
+                    reaction: false,
+                }));
+                self.record_emoji_uses(ctx, guild_id, uses).await;
+            }
+            FullEvent::ReactionAdd { add_reaction, .. } if self.tracks_emojis() => {
+                let Some(guild_id) = add_reaction.guild_id else {
+                    return;
+                };
+                if add_reaction.member.as_ref().is_some_and(|m| m.user.bot()) {
+                    return;
+                }
+                let ReactionType::Custom { animated, id, .. } = &add_reaction.emoji else {
+                    return;
+                };
+                let emoji = EmojiUse {
+                    emoji_id: id.get(),
+                    kind: EmojiKind::Emoji,
+                    name: String::new(),
+                    animated: *animated,
+                    reaction: true,
+                };
+                self.record_emoji_uses(ctx, guild_id, vec![emoji]).await;
+            }
+            FullEvent::InviteCreate { data, .. } if self.tracks_members() => {
+                if let Some(guild_id) = data.guild_id {
+                    let inviter_id = data.inviter.as_ref().map(|user| user.id.get());
+                    let max_uses = u64::from(data.max_uses);
+                    self.data
+                        .invites
+                        .insert(guild_id.get(), &data.code, max_uses, inviter_id);
+                }
+            }
+            FullEvent::InviteDelete { data, .. } if self.tracks_members() => {
+                if let Some(guild_id) = data.guild_id {
+                    self.data.invites.remove(guild_id.get(), &data.code);
+                }
+            }
+            FullEvent::GuildMemberAddition { new_member, .. } if self.tracks_members() => {
+                if !new_member.user.bot() {
+                    self.welcome_member(ctx, new_member).await;
+                }
+            }
+            FullEvent::VoiceStateUpdate { old, new, .. } if self.tracks_voice() => {
+                let is_bot = new.member.as_ref().is_some_and(|m| m.user.bot());
+                let is_stage = match (new.guild_id, new.channel_id) {
+                    (Some(guild_id), Some(channel_id)) => ctx
+                        .cache
+                        .guild(guild_id)
+                        .is_some_and(|guild| Self::is_stage_channel(&guild, channel_id)),
+                    _ => false,
+                };
                 self.event_bus.publish(VoiceStateEvent {
                     old: old.clone(),
                     new: new.clone(),
+                    is_bot,
+                    is_stage,
                 });
+                temp_voice::on_voice_state_update(ctx, &self.data, old.as_ref(), new).await;
+            }
+            FullEvent::ChannelDelete { channel, .. } if self.tracks_voice() => {
+                temp_voice::on_channel_delete(&self.data, channel.id).await;
+            }
+            // An unavailable guild comes back after the outage, so only a removal counts
+            FullEvent::GuildDelete { incomplete, .. }
+                if self.tracks_voice() && !incomplete.unavailable =>
+            {
+                self.voice_subscriber.remove_guild(incomplete.id.get());
             }
             _ => {}
         }
@@ -18,6 +18,10 @@ pub enum Navigation {
     // -- Settings section --
     /// Navigate to main settings page
     SettingsMain,
+    /// Navigate to general settings page
+    SettingsGeneral,
+    /// Navigate to command settings page
+    SettingsCommands,
     /// Navigate to feed settings page
     SettingsFeeds,
     /// Navigate to voice settings page
@@ -26,6 +30,12 @@ pub enum Navigation {
     SettingsWelcome,
     /// Navigate to about page (within settings context)
     SettingsAbout,
+    /// Navigate to settings change history
+    SettingsHistory,
+    /// Navigate to web dashboard link management
+    SettingsDashboard,
+    /// Navigate to REST API token management
+    SettingsApi,
 
     // -- Feed commands section --
     /// Show subscriptions list
@@ -34,7 +44,12 @@ pub enum Navigation {
     FeedSubscribe {
         links: String,
         send_into: Option<SendInto>,
+        filter: Option<String>,
+        collection: Option<String>,
+        platform: Option<String>,
     },
+    /// Confirm subscribing to the feed links found in a message
+    FeedSubscribeFromMessage { urls: Vec<String> },
     /// Start unsubscribe flow
     FeedUnsubscribe {
         links: String,
@@ -42,6 +57,10 @@ pub enum Navigation {
     },
     /// Start subscription list flow
     FeedList(Option<SendInto>),
+    /// Show the DM notification preferences
+    FeedPreferences,
+    /// Review the server's subscription requests
+    FeedRequests,
 
     // Voice commands section
     VoiceLeaderboard {
@@ -55,6 +74,34 @@ pub enum Navigation {
         stat_type: GuildStatType,
     },
 
+    // -- /vc now --
+    /// Show members currently in voice
+    VoiceNow,
+
+    // -- /vc admin --
+    /// Show flagged voice sessions awaiting review
+    VoiceAdminReview,
+
+    // -- /vc afk --
+    /// Show the AFK mover settings
+    VoiceAfk,
+
+    // -- /vc temp_channels --
+    /// Show the temporary voice channel settings
+    VoiceTempChannels,
+
+    // -- Tag commands section --
+    /// Show the server's tag list
+    TagList,
+
+    // -- Emoji commands section --
+    /// Show the server's most used emojis and stickers
+    EmojiStats,
+
+    // -- Invite commands section --
+    /// Show the server's invite leaderboard
+    InviteLeaderboard,
+
     // -- Universal navigation --
     /// Go back to previous handler
     Back,
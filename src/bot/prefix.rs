@@ -0,0 +1,71 @@
+//! Per-guild text command prefixes.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use poise::PartialContext;
+
+use crate::bot::Data;
+use crate::bot::command::Error;
+use crate::entity::DEFAULT_PREFIX;
+
+/// Guild prefixes cached in memory, so messages don't each cost a settings lookup.
+///
+/// Entries are filled on first use and refreshed when the prefix is changed in
+/// the settings.
+#[derive(Default)]
+pub struct PrefixCache {
+    prefixes: RwLock<HashMap<u64, String>>,
+}
+
+impl PrefixCache {
+    /// Returns the cached prefix of a guild, if it has been looked up.
+    pub fn get(&self, guild_id: u64) -> Option<String> {
+        self.prefixes.read().ok()?.get(&guild_id).cloned()
+    }
+
+    /// Stores the current prefix of a guild.
+    pub fn set(&self, guild_id: u64, prefix: impl Into<String>) {
+        if let Ok(mut prefixes) = self.prefixes.write() {
+            prefixes.insert(guild_id, prefix.into());
+        }
+    }
+}
+
+/// Resolves the text command prefix for a message: the guild's configured
+/// prefix, or [`DEFAULT_PREFIX`] in DMs.
+pub async fn dynamic_prefix(
+    ctx: PartialContext<'_, Data, Error>,
+) -> Result<Option<Cow<'static, str>>, Error> {
+    let Some(guild_id) = ctx.guild_id.map(|id| id.get()) else {
+        return Ok(Some(Cow::Borrowed(DEFAULT_PREFIX)));
+    };
+
+    let data = ctx.framework.user_data();
+    if let Some(prefix) = data.prefixes.get(guild_id) {
+        return Ok(Some(Cow::Owned(prefix)));
+    }
+
+    let settings = data.service.settings.get_server_settings(guild_id).await?;
+    let prefix = settings.general.prefix().to_string();
+    data.prefixes.set(guild_id, prefix.clone());
+    Ok(Some(Cow::Owned(prefix)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_cache_overwrites_entries() {
+        let cache = PrefixCache::default();
+        assert_eq!(cache.get(1), None);
+
+        cache.set(1, "?");
+        cache.set(1, "pwr!");
+
+        assert_eq!(cache.get(1).as_deref(), Some("pwr!"));
+        assert_eq!(cache.get(2), None);
+    }
+}
@@ -0,0 +1,145 @@
+//! Runs CPU-bound image rendering off the async runtime.
+
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread::available_parallelism;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Result;
+use log::trace;
+use log::warn;
+use tokio::sync::Semaphore;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout;
+
+/// Longest a render may take before the caller gives up on it.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Renders images on tokio's blocking pool, so SVG rasterizing and chart drawing don't
+/// stall the workers serving gateway events.
+///
+/// At most one render per CPU runs at a time. The rest wait in a queue whose depth is
+/// exported under `/metrics`.
+pub struct ImageRenderService {
+    permits: Arc<Semaphore>,
+    timeout: Duration,
+    /// Renders queued or running.
+    depth: AtomicUsize,
+    timeouts: AtomicU64,
+}
+
+impl Default for ImageRenderService {
+    fn default() -> Self {
+        let workers = available_parallelism().map(usize::from).unwrap_or(2);
+        Self::new(workers, DEFAULT_TIMEOUT)
+    }
+}
+
+impl ImageRenderService {
+    pub fn new(workers: usize, timeout: Duration) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(workers.max(1))),
+            timeout,
+            depth: AtomicUsize::new(0),
+            timeouts: AtomicU64::new(0),
+        }
+    }
+
+    /// Runs `render` on the blocking pool and returns its result.
+    ///
+    /// Fails if the render panics or takes longer than the timeout, queueing included.
+    pub async fn render<T, F>(&self, name: &'static str, render: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        let start = Instant::now();
+        self.depth.fetch_add(1, Ordering::Relaxed);
+        let result = timeout(self.timeout, async {
+            let permit = self.permits.clone().acquire_owned().await?;
+            // The permit moves into the render, so a timed-out render keeps its slot until
+            // it actually finishes
+            spawn_blocking(move || {
+                let _permit = permit;
+                render()
+            })
+            .await?
+        })
+        .await;
+        self.depth.fetch_sub(1, Ordering::Relaxed);
+
+        match result {
+            Ok(result) => {
+                trace!("render {name} {} ms", start.elapsed().as_millis());
+                result
+            }
+            Err(_) => {
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "Rendering {name} timed out after {}s",
+                    self.timeout.as_secs()
+                );
+                anyhow::bail!("Rendering {name} timed out")
+            }
+        }
+    }
+
+    /// Returns the number of renders queued or running.
+    pub fn queue_depth(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    /// Renders the queue metrics in the Prometheus text format.
+    pub fn render_metrics(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP pwr_bot_render_queue_depth Image renders queued or running.\n");
+        out.push_str("# TYPE pwr_bot_render_queue_depth gauge\n");
+        let _ = writeln!(out, "pwr_bot_render_queue_depth {}", self.queue_depth());
+        out.push_str("# HELP pwr_bot_render_timeouts_total Image renders that timed out.\n");
+        out.push_str("# TYPE pwr_bot_render_timeouts_total counter\n");
+        let _ = writeln!(
+            out,
+            "pwr_bot_render_timeouts_total {}",
+            self.timeouts.load(Ordering::Relaxed)
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn render_returns_result() {
+        let renderer = ImageRenderService::default();
+
+        let bytes = renderer.render("test", || Ok(vec![1, 2, 3])).await.unwrap();
+
+        assert_eq!(bytes, vec![1, 2, 3]);
+        assert_eq!(renderer.queue_depth(), 0);
+    }
+
+    #[tokio::test]
+    async fn slow_render_times_out() {
+        let renderer = ImageRenderService::new(1, Duration::from_millis(10));
+
+        let result = renderer
+            .render("test", || {
+                std::thread::sleep(Duration::from_millis(200));
+                Ok(())
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert!(
+            renderer
+                .render_metrics()
+                .contains("pwr_bot_render_timeouts_total 1\n")
+        );
+    }
+}
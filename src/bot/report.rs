@@ -0,0 +1,475 @@
+//! Voice activity reports summarizing a period in one image.
+//!
+//! A report has a header with totals, the top members, a weekday by hour heatmap and
+//! the most used channels. It is laid out here, drawn by the Minijinja template in
+//! `assets/voice_report.svg` and rendered on the [`ImageRenderService`].
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Timelike;
+use chrono::Utc;
+use chrono_tz::Tz;
+use minijinja::AutoEscape;
+use minijinja::Environment;
+use minijinja::context;
+use serde::Serialize;
+
+use crate::bot::render::ImageRenderService;
+use crate::bot::utils::format_duration;
+use crate::entity::VoiceLeaderboardOptBuilder;
+use crate::entity::VoiceSessionsEntity;
+use crate::service::traits::VoiceTracker;
+
+/// Filename for the report attachment.
+pub const REPORT_FILENAME: &str = "voice_report.png";
+
+/// Members and channels listed in a report.
+const TOP_N: usize = 5;
+
+const WIDTH: u32 = 800;
+const PADDING: u32 = 24;
+const HEADER_HEIGHT: u32 = 96;
+const SECTION_TITLE_HEIGHT: u32 = 36;
+const ROW_PITCH: u32 = 30;
+const BAR_HEIGHT: u32 = 22;
+/// Room kept left of the bars for names.
+const LABEL_WIDTH: u32 = 180;
+/// Room kept right of the bars for durations.
+const VALUE_WIDTH: u32 = 120;
+/// Longest name that fits left of the bars.
+const MAX_NAME_CHARS: usize = 20;
+const CELL_SIZE: u32 = 24;
+const CELL_GAP: u32 = 4;
+const HEATMAP_LEFT: u32 = PADDING + 40;
+
+const HEATMAP_COLORS: [&str; 5] = ["#1E1F22", "#0E4429", "#006D32", "#26A641", "#39D353"];
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Voice activity of a guild over one period.
+#[derive(Clone, Debug)]
+pub struct VoiceReport {
+    pub title: String,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// Timezone the heatmap and dates are shown in.
+    pub timezone: Tz,
+    pub total_seconds: i64,
+    pub active_users: usize,
+    /// Top members by voice time, most first.
+    pub members: Vec<(u64, i64)>,
+    /// Seconds in voice per weekday, starting Monday, and hour.
+    pub heatmap: [[i64; 24]; 7],
+    /// Top channels by voice time, most first.
+    pub channels: Vec<(u64, i64)>,
+    user_names: HashMap<u64, String>,
+    channel_names: HashMap<u64, String>,
+}
+
+impl VoiceReport {
+    /// Aggregates `sessions`, clipped to the period from `since` to `until`.
+    ///
+    /// `members` are the top of the leaderboard, which applies channel weights, so they
+    /// are taken as given rather than summed from the sessions.
+    pub fn new(
+        sessions: &[VoiceSessionsEntity],
+        members: Vec<(u64, i64)>,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        timezone: Tz,
+    ) -> Self {
+        let mut heatmap = [[0; 24]; 7];
+        let mut channels: HashMap<u64, i64> = HashMap::new();
+        let mut users = HashSet::new();
+        let mut total_seconds = 0;
+
+        for session in sessions {
+            let start = session.join_time.max(since);
+            let end = session.leave_time.min(until);
+            if start >= end {
+                continue;
+            }
+            let seconds = (end - start).num_seconds();
+            total_seconds += seconds;
+            *channels.entry(session.channel_id).or_default() += seconds;
+            users.insert(session.user_id);
+            add_to_heatmap(&mut heatmap, start, end, timezone);
+        }
+
+        let mut channels: Vec<(u64, i64)> = channels.into_iter().collect();
+        channels.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        channels.truncate(TOP_N);
+
+        Self {
+            title: "Voice Report".to_string(),
+            since,
+            until,
+            timezone,
+            total_seconds,
+            active_users: users.len(),
+            members: members.into_iter().take(TOP_N).collect(),
+            heatmap,
+            channels,
+            user_names: HashMap::new(),
+            channel_names: HashMap::new(),
+        }
+    }
+
+    /// Sets the title shown in the header.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the names shown for members and channels. Those without one are shown by ID.
+    pub fn with_names(
+        mut self,
+        user_names: HashMap<u64, String>,
+        channel_names: HashMap<u64, String>,
+    ) -> Self {
+        self.user_names = user_names;
+        self.channel_names = channel_names;
+        self
+    }
+
+    /// Renders the report to PNG.
+    pub fn render(&self) -> Result<Vec<u8>> {
+        let members_top = HEADER_HEIGHT + SECTION_TITLE_HEIGHT;
+        let heatmap_title = members_top + section_height(self.members.len());
+        let heatmap_top = heatmap_title + SECTION_TITLE_HEIGHT;
+        let channels_title = heatmap_top + 7 * (CELL_SIZE + CELL_GAP) + 28;
+        let channels_top = channels_title + SECTION_TITLE_HEIGHT;
+        let height = channels_top + section_height(self.channels.len()) + PADDING;
+
+        let members = bars(&self.members, members_top, false, |id| {
+            self.user_names
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| format!("User {id}"))
+        });
+        let channels = bars(&self.channels, channels_top, true, |id| {
+            self.channel_names
+                .get(&id)
+                .map(|name| format!("#{name}"))
+                .unwrap_or_else(|| format!("Channel {id}"))
+        });
+
+        let max = self.heatmap.iter().flatten().copied().max().unwrap_or(0);
+        let pitch = CELL_SIZE + CELL_GAP;
+        let mut cells = Vec::with_capacity(7 * 24);
+        for (day, hours) in self.heatmap.iter().enumerate() {
+            for (hour, &seconds) in hours.iter().enumerate() {
+                cells.push(Cell {
+                    x: HEATMAP_LEFT + hour as u32 * pitch,
+                    y: heatmap_top + day as u32 * pitch,
+                    color: HEATMAP_COLORS[heatmap_level(seconds, max)],
+                });
+            }
+        }
+        let hours: Vec<Label> = (0..24)
+            .step_by(3)
+            .map(|hour| Label {
+                pos: HEATMAP_LEFT + hour * pitch + CELL_SIZE / 2,
+                text: format!("{hour:02}:00"),
+            })
+            .collect();
+        let weekdays: Vec<Label> = WEEKDAYS
+            .iter()
+            .enumerate()
+            .map(|(day, name)| Label {
+                pos: heatmap_top + day as u32 * pitch + CELL_SIZE / 2 + 5,
+                text: name.to_string(),
+            })
+            .collect();
+
+        let period = format!(
+            "{} to {}",
+            self.since
+                .with_timezone(&self.timezone)
+                .format("%b %-d, %Y"),
+            self.until
+                .with_timezone(&self.timezone)
+                .format("%b %-d, %Y"),
+        );
+
+        let mut env = Environment::new();
+        // Member and channel names are user input
+        env.set_auto_escape_callback(|_| AutoEscape::Html);
+        env.add_template(
+            "voice_report",
+            include_str!("../../assets/voice_report.svg"),
+        )?;
+        let svg = env.get_template("voice_report")?.render(context! {
+            width => WIDTH,
+            height,
+            padding => PADDING,
+            title => self.title,
+            period,
+            timezone => self.timezone.name(),
+            total_time => format_duration(self.total_seconds),
+            active_users => self.active_users,
+            members_title => HEADER_HEIGHT,
+            members,
+            heatmap_title,
+            cells,
+            cell_size => CELL_SIZE,
+            hours,
+            hours_y => heatmap_top + 7 * pitch + 12,
+            weekdays,
+            channels_title,
+            channels,
+            bar_height => BAR_HEIGHT,
+            label_width => LABEL_WIDTH,
+        })?;
+
+        let mut fontdb = resvg::usvg::fontdb::Database::new();
+        fontdb.load_font_data(include_bytes!("../../assets/fonts/Roboto-Regular.ttf").to_vec());
+        let options = resvg::usvg::Options {
+            fontdb: Arc::new(fontdb),
+            ..Default::default()
+        };
+        let tree = resvg::usvg::Tree::from_str(&svg, &options)?;
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(WIDTH, height)
+            .ok_or_else(|| anyhow::anyhow!("Failed to create pixmap"))?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+        Ok(pixmap.encode_png()?)
+    }
+}
+
+/// Builds and renders voice reports.
+///
+/// Used by `/vc report`. It only needs the voice tracker and renderer, so scheduled
+/// reports can use it too.
+pub struct VoiceReportService {
+    voice_tracking: Arc<dyn VoiceTracker>,
+    renderer: Arc<ImageRenderService>,
+}
+
+impl VoiceReportService {
+    pub fn new(voice_tracking: Arc<dyn VoiceTracker>, renderer: Arc<ImageRenderService>) -> Self {
+        Self {
+            voice_tracking,
+            renderer,
+        }
+    }
+
+    /// Collects the report of a guild from `since` to `until`, without names.
+    ///
+    /// # Performance
+    ///
+    /// DB calls: 3
+    pub async fn build(
+        &self,
+        guild_id: u64,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<VoiceReport> {
+        let settings = self.voice_tracking.get_server_settings(guild_id).await?;
+        let sessions = self
+            .voice_tracking
+            .get_sessions_in_range(guild_id, None, &since, &until)
+            .await?;
+        let opts = VoiceLeaderboardOptBuilder::default()
+            .guild_id(guild_id)
+            .limit(Some(TOP_N as u32))
+            .since(Some(since))
+            .until(Some(until))
+            .build()?;
+        let members = self
+            .voice_tracking
+            .get_leaderboard_withopt(&opts)
+            .await?
+            .into_iter()
+            .map(|entry| (entry.user_id, entry.total_duration))
+            .collect();
+
+        Ok(VoiceReport::new(
+            &sessions,
+            members,
+            since,
+            until,
+            settings.general.timezone(),
+        ))
+    }
+
+    /// Renders a report to PNG on the blocking pool.
+    pub async fn render(&self, report: VoiceReport) -> Result<Vec<u8>> {
+        self.renderer
+            .render("voice_report", move || report.render())
+            .await
+    }
+}
+
+/// A labelled bar as drawn by the template.
+#[derive(Serialize)]
+struct Bar {
+    y: u32,
+    width: f64,
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct Cell {
+    x: u32,
+    y: u32,
+    color: &'static str,
+}
+
+#[derive(Serialize)]
+struct Label {
+    pos: u32,
+    text: String,
+}
+
+/// Height of a bar section with `rows` rows, leaving room for an empty note.
+fn section_height(rows: usize) -> u32 {
+    rows.max(1) as u32 * ROW_PITCH + 8
+}
+
+/// Lays out one bar per item, scaled to the largest. Shares of the total are appended
+/// to the value when `with_share` is set.
+fn bars(
+    items: &[(u64, i64)],
+    top: u32,
+    with_share: bool,
+    name: impl Fn(u64) -> String,
+) -> Vec<Bar> {
+    let max = items
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let total = items.iter().map(|(_, value)| *value).sum::<i64>().max(1) as f64;
+    let max_width = f64::from(WIDTH - 2 * PADDING - LABEL_WIDTH - VALUE_WIDTH);
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, &(id, value))| {
+            let mut text = format_duration(value);
+            if with_share {
+                text.push_str(&format!(" ({:.0}%)", value as f64 / total * 100.0));
+            }
+            Bar {
+                y: top + i as u32 * ROW_PITCH,
+                width: (value as f64 / max * max_width).max(2.0),
+                name: truncate(&name(id), MAX_NAME_CHARS),
+                value: text,
+            }
+        })
+        .collect()
+}
+
+/// Shortens `text` to `max` characters, ending in an ellipsis when cut.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(max - 1).collect();
+    short.push('…');
+    short
+}
+
+/// Adds the time from `start` to `end` to the weekday and hour cells it spans, in
+/// `timezone`.
+fn add_to_heatmap(
+    heatmap: &mut [[i64; 24]; 7],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    timezone: Tz,
+) {
+    let mut cursor = start;
+    while cursor < end {
+        let local = cursor.with_timezone(&timezone);
+        let into_hour = i64::from(local.minute() * 60 + local.second());
+        let next = (cursor + chrono::Duration::seconds(3600 - into_hour)).min(end);
+        let day = local.weekday().num_days_from_monday() as usize;
+        heatmap[day][local.hour() as usize] += (next - cursor).num_seconds();
+        cursor = next;
+    }
+}
+
+/// Returns the shade of a cell, 0 for no activity up to 4 for the busiest.
+fn heatmap_level(value: i64, max: i64) -> usize {
+    if value <= 0 || max <= 0 {
+        return 0;
+    }
+    ((value as f64 / max as f64 * 4.0).ceil() as usize).clamp(1, 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn session(
+        user_id: u64,
+        channel_id: u64,
+        join_time: DateTime<Utc>,
+        leave_time: DateTime<Utc>,
+    ) -> VoiceSessionsEntity {
+        VoiceSessionsEntity {
+            id: 0,
+            user_id,
+            guild_id: 1,
+            channel_id,
+            join_time,
+            leave_time,
+            is_active: false,
+            parent_session_id: None,
+        }
+    }
+
+    #[test]
+    fn sessions_are_clipped_and_split_by_hour() {
+        // Monday 2024-01-01
+        let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let until = Utc.with_ymd_and_hms(2024, 1, 8, 0, 0, 0).unwrap();
+        let sessions = vec![
+            // Starts before the period, so only 30 minutes count
+            session(
+                1,
+                10,
+                since - chrono::Duration::hours(1),
+                since + chrono::Duration::minutes(30),
+            ),
+            session(
+                2,
+                20,
+                Utc.with_ymd_and_hms(2024, 1, 2, 9, 30, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 2, 11, 0, 0).unwrap(),
+            ),
+        ];
+
+        let report = VoiceReport::new(&sessions, vec![], since, until, Tz::UTC);
+
+        assert_eq!(report.total_seconds, 1800 + 5400);
+        assert_eq!(report.active_users, 2);
+        assert_eq!(report.heatmap[0][0], 1800);
+        assert_eq!(report.heatmap[1][9], 1800);
+        assert_eq!(report.heatmap[1][10], 3600);
+        assert_eq!(report.channels, vec![(20, 5400), (10, 1800)]);
+    }
+
+    #[test]
+    fn heatmap_follows_timezone() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 23, 0, 0).unwrap();
+        let end = start + chrono::Duration::hours(1);
+        let mut heatmap = [[0; 24]; 7];
+
+        add_to_heatmap(&mut heatmap, start, end, chrono_tz::Asia::Jakarta);
+
+        // 23:00 UTC on Monday is 06:00 on Tuesday in Jakarta
+        assert_eq!(heatmap[1][6], 3600);
+    }
+}
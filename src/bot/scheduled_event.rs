@@ -0,0 +1,161 @@
+//! Discord Scheduled Events created by the bot.
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use poise::serenity_prelude::*;
+
+/// Longest event name Discord accepts.
+const MAX_NAME_LEN: usize = 100;
+/// Longest event location Discord accepts.
+const MAX_LOCATION_LEN: usize = 100;
+/// Longest event description Discord accepts.
+const MAX_DESCRIPTION_LEN: usize = 1000;
+
+/// A scheduled event held outside Discord, e.g. on a streaming site.
+///
+/// Text longer than Discord accepts is cut short when the event is sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalEvent {
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    pub start: DateTime<Utc>,
+    /// Discord requires an end for external events.
+    pub end: DateTime<Utc>,
+}
+
+impl ExternalEvent {
+    fn create_builder(&self) -> Result<CreateScheduledEvent<'static>> {
+        let start = timestamp(self.start)?;
+        let end = timestamp(self.end)?;
+        Ok(CreateScheduledEvent::new(
+            ScheduledEventType::External,
+            truncate(&self.name, MAX_NAME_LEN),
+            start,
+        )
+        .description(truncate(&self.description, MAX_DESCRIPTION_LEN))
+        .location(truncate(&self.location, MAX_LOCATION_LEN))
+        .end_time(end))
+    }
+
+    fn edit_builder(&self) -> Result<EditScheduledEvent<'static>> {
+        Ok(EditScheduledEvent::new()
+            .name(truncate(&self.name, MAX_NAME_LEN))
+            .description(truncate(&self.description, MAX_DESCRIPTION_LEN))
+            .location(truncate(&self.location, MAX_LOCATION_LEN))
+            .start_time(timestamp(self.start)?)
+            .end_time(timestamp(self.end)?))
+    }
+}
+
+/// An external event that has not started yet, as listed by Discord.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpcomingEvent {
+    pub id: ScheduledEventId,
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    pub start: DateTime<Utc>,
+}
+
+impl UpcomingEvent {
+    /// Whether the event already matches `event`, as far as Discord keeps it.
+    pub fn matches(&self, event: &ExternalEvent) -> bool {
+        self.name == truncate(&event.name, MAX_NAME_LEN)
+            && self.description == truncate(&event.description, MAX_DESCRIPTION_LEN)
+            && self.start.timestamp() == event.start.timestamp()
+    }
+}
+
+/// Creates an external event in a guild and returns its ID.
+///
+/// Needs the bot to have the **Create Events** permission in the guild.
+pub async fn create_external_event(
+    http: &Http,
+    guild_id: GuildId,
+    event: &ExternalEvent,
+) -> Result<ScheduledEventId> {
+    let created = guild_id
+        .create_scheduled_event(http, event.create_builder()?)
+        .await?;
+    Ok(created.id)
+}
+
+/// Replaces the details of an external event.
+pub async fn edit_external_event(
+    http: &Http,
+    guild_id: GuildId,
+    event_id: ScheduledEventId,
+    event: &ExternalEvent,
+) -> Result<()> {
+    guild_id
+        .edit_scheduled_event(http, event_id, event.edit_builder()?)
+        .await?;
+    Ok(())
+}
+
+/// Deletes a scheduled event.
+pub async fn delete_event(
+    http: &Http,
+    guild_id: GuildId,
+    event_id: ScheduledEventId,
+) -> Result<()> {
+    guild_id.delete_scheduled_event(http, event_id).await?;
+    Ok(())
+}
+
+/// Returns the external events in a guild created by `creator_id` that have not started.
+pub async fn upcoming_events_by(
+    http: &Http,
+    guild_id: GuildId,
+    creator_id: UserId,
+) -> Result<Vec<UpcomingEvent>> {
+    let events = guild_id.scheduled_events(http, false).await?;
+    Ok(events
+        .into_iter()
+        .filter(|event| {
+            event.kind == ScheduledEventType::External
+                && event.status == ScheduledEventStatus::Scheduled
+                && event.creator_id == Some(creator_id)
+        })
+        .filter_map(|event| {
+            Some(UpcomingEvent {
+                id: event.id,
+                name: event.name.to_string(),
+                description: event
+                    .description
+                    .map(|description| description.to_string())
+                    .unwrap_or_default(),
+                location: event.metadata?.location?.to_string(),
+                start: DateTime::from_timestamp(event.start_time.unix_timestamp(), 0)?,
+            })
+        })
+        .collect())
+}
+
+/// Returns the link that opens a scheduled event in Discord.
+pub fn event_url(guild_id: GuildId, event_id: ScheduledEventId) -> String {
+    format!("https://discord.com/events/{guild_id}/{event_id}")
+}
+
+fn timestamp(time: DateTime<Utc>) -> Result<Timestamp> {
+    Timestamp::from_unix_timestamp(time.timestamp())
+        .map_err(|_| anyhow::anyhow!("{time} is out of Discord's timestamp range"))
+}
+
+/// Cuts `text` to at most `max` characters.
+fn truncate(text: &str, max: usize) -> String {
+    text.chars().take(max).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_counts_characters() {
+        assert_eq!(truncate("ééé", 2), "éé");
+        assert_eq!(truncate("ab", 5), "ab");
+    }
+}
@@ -0,0 +1,325 @@
+//! Paced delivery of bulk notifications.
+//!
+//! Feed updates arrive in bursts when many series release at once. The
+//! [`SendQueue`] spreads those sends out so they stay inside Discord's rate
+//! limits and leave room for interactive command responses. Targets are served
+//! round-robin, so one busy channel cannot hold up the others.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use log::debug;
+use log::warn;
+use poise::serenity_prelude::Http;
+use poise::serenity_prelude::RatelimitInfo;
+use poise::serenity_prelude::SerenityError;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+use tokio::time::sleep;
+use tokio::time::sleep_until;
+
+/// Number of sends that can wait in the queue before callers are held back.
+const QUEUE_CAPACITY: usize = 256;
+
+/// Minimum gap between any two sends. Discord allows 50 requests per second
+/// globally; this keeps bulk sends at half of that.
+const GLOBAL_INTERVAL: Duration = Duration::from_millis(40);
+
+/// Minimum gap between two sends to the same channel or user.
+const TARGET_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long after an interaction bulk sends hold back.
+const PRIORITY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Longest a single send yields to interactions, so notifications aren't starved.
+const MAX_YIELD: Duration = Duration::from_secs(5);
+
+/// How often a yielding send checks whether interactions have calmed down.
+const YIELD_STEP: Duration = Duration::from_millis(250);
+
+/// Where a queued message goes. Each target is paced separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SendTarget {
+    /// A guild channel, by ID.
+    Channel(u64),
+    /// A user's DMs, by user ID.
+    Dm(u64),
+}
+
+type SendFuture = Pin<Box<dyn Future<Output = Result<(), SerenityError>> + Send>>;
+
+struct Job {
+    send: Box<dyn FnOnce(Arc<Http>) -> SendFuture + Send>,
+    done: oneshot::Sender<Result<(), SerenityError>>,
+}
+
+/// Signals from outside the queue that slow it down.
+#[derive(Default)]
+struct Signals {
+    last_interaction: Mutex<Option<Instant>>,
+    paused_until: Mutex<Option<Instant>>,
+}
+
+impl Signals {
+    fn interaction_is_recent(&self) -> bool {
+        self.last_interaction
+            .lock()
+            .ok()
+            .and_then(|last| *last)
+            .is_some_and(|last| last.elapsed() < PRIORITY_WINDOW)
+    }
+
+    fn paused_until(&self) -> Option<Instant> {
+        self.paused_until
+            .lock()
+            .ok()
+            .and_then(|until| *until)
+            .filter(|until| *until > Instant::now())
+    }
+
+    /// Waits out rate-limit pauses, then yields to recent interactions.
+    async fn wait_turn(&self) {
+        if let Some(until) = self.paused_until() {
+            sleep_until(until).await;
+        }
+
+        let started = Instant::now();
+        while self.interaction_is_recent() && started.elapsed() < MAX_YIELD {
+            sleep(YIELD_STEP).await;
+        }
+    }
+}
+
+/// Queue pacing bulk Discord sends, shared by the notification subscribers.
+pub struct SendQueue {
+    http: Arc<Http>,
+    tx: mpsc::Sender<(SendTarget, Job)>,
+    signals: Arc<Signals>,
+}
+
+impl SendQueue {
+    /// Creates the queue and starts its worker.
+    ///
+    /// The queue owns `http` so it can pause itself when Discord reports a rate limit.
+    pub fn new(mut http: Http) -> Self {
+        let signals = Arc::new(Signals::default());
+
+        if let Some(ratelimiter) = http.ratelimiter.as_mut() {
+            let signals = signals.clone();
+            ratelimiter.set_ratelimit_callback(Box::new(move |info: RatelimitInfo| {
+                warn!(
+                    "Rate limited on `{}` for {:?} (global: {}). Pausing notifications.",
+                    info.path, info.timeout, info.global
+                );
+                if let Ok(mut until) = signals.paused_until.lock() {
+                    *until = Some(Instant::now() + info.timeout);
+                }
+            }));
+        }
+
+        let http = Arc::new(http);
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(Self::run(http.clone(), rx, signals.clone()));
+
+        Self { http, tx, signals }
+    }
+
+    /// Returns the HTTP client sends are made with.
+    pub fn http(&self) -> &Arc<Http> {
+        &self.http
+    }
+
+    /// Queues a send and waits until it has been made.
+    ///
+    /// Waits for room first when the queue is full, so a burst of notifications
+    /// slows its producer down instead of piling up in memory.
+    pub async fn send<F, Fut>(&self, target: SendTarget, send: F) -> Result<()>
+    where
+        F: FnOnce(Arc<Http>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), SerenityError>> + Send + 'static,
+    {
+        let (done, result) = oneshot::channel();
+        let job = Job {
+            send: Box::new(move |http| Box::pin(send(http))),
+            done,
+        };
+        self.tx
+            .send((target, job))
+            .await
+            .map_err(|_| anyhow::anyhow!("Send queue is closed"))?;
+        result
+            .await
+            .map_err(|_| anyhow::anyhow!("Send queue dropped the message"))??;
+        Ok(())
+    }
+
+    /// Records an interaction, making bulk sends yield to its responses for a moment.
+    pub fn mark_interaction(&self) {
+        if let Ok(mut last) = self.signals.last_interaction.lock() {
+            *last = Some(Instant::now());
+        }
+    }
+
+    async fn run(
+        http: Arc<Http>,
+        mut rx: mpsc::Receiver<(SendTarget, Job)>,
+        signals: Arc<Signals>,
+    ) {
+        let mut pending = Pending::default();
+        let mut next_global = Instant::now();
+
+        loop {
+            if pending.is_empty() {
+                match rx.recv().await {
+                    Some((target, job)) => pending.push(target, job),
+                    None => return,
+                }
+            }
+            while let Ok((target, job)) = rx.try_recv() {
+                pending.push(target, job);
+            }
+
+            let (target, job) = match pending.pop_ready(Instant::now()) {
+                Ok(ready) => ready,
+                Err(until) => {
+                    // Every waiting target was sent to recently; new targets may be ready sooner
+                    tokio::select! {
+                        _ = sleep_until(until) => {}
+                        Some((target, job)) = rx.recv() => pending.push(target, job),
+                    }
+                    continue;
+                }
+            };
+
+            signals.wait_turn().await;
+            sleep_until(next_global).await;
+
+            let now = Instant::now();
+            next_global = now + GLOBAL_INTERVAL;
+            pending.mark_sent(target, now);
+            debug!("Sending queued message to {target:?}.");
+
+            let http = http.clone();
+            tokio::spawn(async move {
+                let result = (job.send)(http).await;
+                let _ = job.done.send(result);
+            });
+        }
+    }
+}
+
+/// Sends waiting in the queue, grouped by target and served round-robin.
+struct Pending<J> {
+    jobs: HashMap<SendTarget, VecDeque<J>>,
+    /// Targets with waiting jobs, in the order they are served.
+    order: VecDeque<SendTarget>,
+    /// Earliest time each target may be sent to again.
+    next_allowed: HashMap<SendTarget, Instant>,
+}
+
+impl<J> Default for Pending<J> {
+    fn default() -> Self {
+        Self {
+            jobs: HashMap::new(),
+            order: VecDeque::new(),
+            next_allowed: HashMap::new(),
+        }
+    }
+}
+
+impl<J> Pending<J> {
+    fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    fn push(&mut self, target: SendTarget, job: J) {
+        let jobs = self.jobs.entry(target).or_default();
+        if jobs.is_empty() {
+            self.order.push_back(target);
+        }
+        jobs.push_back(job);
+    }
+
+    /// Takes the next job whose target may be sent to at `now`.
+    ///
+    /// Returns when the earliest waiting target becomes ready if none is ready yet.
+    fn pop_ready(&mut self, now: Instant) -> Result<(SendTarget, J), Instant> {
+        let ready = self.order.iter().position(|target| {
+            self.next_allowed
+                .get(target)
+                .is_none_or(|allowed| *allowed <= now)
+        });
+        let Some(index) = ready else {
+            let earliest = self
+                .order
+                .iter()
+                .filter_map(|target| self.next_allowed.get(target))
+                .min()
+                .copied()
+                .unwrap_or(now);
+            return Err(earliest);
+        };
+
+        let target = self.order.remove(index).expect("index is in bounds");
+        let jobs = self
+            .jobs
+            .get_mut(&target)
+            .expect("ordered targets have jobs");
+        let job = jobs.pop_front().expect("ordered targets have jobs");
+        if jobs.is_empty() {
+            self.jobs.remove(&target);
+        } else {
+            self.order.push_back(target);
+        }
+        Ok((target, job))
+    }
+
+    /// Records a send to `target`, delaying its next one.
+    fn mark_sent(&mut self, target: SendTarget, at: Instant) {
+        self.next_allowed.retain(|_, allowed| *allowed > at);
+        self.next_allowed.insert(target, at + TARGET_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: SendTarget = SendTarget::Channel(1);
+    const B: SendTarget = SendTarget::Dm(2);
+
+    #[test]
+    fn pending_serves_targets_round_robin() {
+        let mut pending = Pending::default();
+        pending.push(A, "a1");
+        pending.push(A, "a2");
+        pending.push(B, "b1");
+
+        let now = Instant::now();
+        assert_eq!(pending.pop_ready(now), Ok((A, "a1")));
+        assert_eq!(pending.pop_ready(now), Ok((B, "b1")));
+        assert_eq!(pending.pop_ready(now), Ok((A, "a2")));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn pending_waits_for_recently_sent_targets() {
+        let mut pending = Pending::default();
+        let now = Instant::now();
+        pending.mark_sent(A, now);
+        pending.push(A, "a1");
+        pending.push(B, "b1");
+
+        // B is ready while A is still cooling down
+        assert_eq!(pending.pop_ready(now), Ok((B, "b1")));
+        assert_eq!(pending.pop_ready(now), Err(now + TARGET_INTERVAL));
+        assert_eq!(pending.pop_ready(now + TARGET_INTERVAL), Ok((A, "a1")));
+    }
+}
@@ -0,0 +1,293 @@
+//! Keeps each bot's Discord client running.
+//!
+//! The supervisor rebuilds a client whenever it stops, waiting longer after each failed
+//! attempt, until shutdown is requested through the bot's [`Lifecycle`]. Every reconnect
+//! is recorded in a [`ClientMonitor`] shown by `/owner status` and the web server's
+//! `/metrics` endpoint.
+
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use log::error;
+use log::info;
+use log::warn;
+use poise::serenity_prelude::ClientBuilder;
+use tokio::time::sleep;
+
+use crate::bot::lifecycle::BotState;
+use crate::bot::lifecycle::Lifecycle;
+
+/// Delay before the first reconnect attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest delay between reconnect attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// A client running this long is considered stable, so the next failure starts the
+/// backoff over.
+const STABLE_AFTER: Duration = Duration::from_secs(600);
+
+/// Connection health of one bot's client.
+pub struct ClientHealth {
+    name: String,
+    lifecycle: Lifecycle,
+    reconnects: AtomicU64,
+    last_disconnect: Mutex<Option<(DateTime<Utc>, String)>>,
+}
+
+impl ClientHealth {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            lifecycle: Lifecycle::new(),
+            reconnects: AtomicU64::new(0),
+            last_disconnect: Mutex::new(None),
+        }
+    }
+
+    /// Returns the name of the bot.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the lifecycle of the bot's client.
+    pub fn lifecycle(&self) -> &Lifecycle {
+        &self.lifecycle
+    }
+
+    /// Whether the client is connected and received `Ready`.
+    pub fn is_connected(&self) -> bool {
+        self.lifecycle.state() == BotState::Ready
+    }
+
+    /// Returns how many times the client was rebuilt since startup.
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Returns when and why the client last stopped.
+    pub fn last_disconnect(&self) -> Option<(DateTime<Utc>, String)> {
+        self.last_disconnect.lock().unwrap().clone()
+    }
+
+    fn set_disconnected(&self, reason: String) {
+        // Still `Connecting` if the client stopped before `Ready`
+        let _ = self.lifecycle.transition(BotState::Connecting);
+        *self.last_disconnect.lock().unwrap() = Some((Utc::now(), reason));
+    }
+
+    fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Connection health of every bot in the process.
+#[derive(Default)]
+pub struct ClientMonitor {
+    clients: Vec<Arc<ClientHealth>>,
+}
+
+impl ClientMonitor {
+    /// Creates a monitor tracking the bots with the given names.
+    pub fn new(names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            clients: names
+                .into_iter()
+                .map(|name| Arc::new(ClientHealth::new(name)))
+                .collect(),
+        }
+    }
+
+    /// Returns the health of the bot with the given name.
+    pub fn get(&self, name: &str) -> Option<Arc<ClientHealth>> {
+        self.clients
+            .iter()
+            .find(|client| client.name == name)
+            .cloned()
+    }
+
+    /// Returns the health of every bot.
+    pub fn clients(&self) -> &[Arc<ClientHealth>] {
+        &self.clients
+    }
+
+    /// Renders client health in the Prometheus text format.
+    pub fn render_metrics(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP pwr_bot_client_connected Whether the Discord client is running.\n");
+        out.push_str("# TYPE pwr_bot_client_connected gauge\n");
+        for client in &self.clients {
+            let _ = writeln!(
+                out,
+                "pwr_bot_client_connected{{bot=\"{}\"}} {}",
+                client.name,
+                u8::from(client.is_connected())
+            );
+        }
+        out.push_str(
+            "# HELP pwr_bot_client_reconnects_total Times the Discord client was rebuilt.\n",
+        );
+        out.push_str("# TYPE pwr_bot_client_reconnects_total counter\n");
+        for client in &self.clients {
+            let _ = writeln!(
+                out,
+                "pwr_bot_client_reconnects_total{{bot=\"{}\"}} {}",
+                client.name,
+                client.reconnects()
+            );
+        }
+        out
+    }
+}
+
+/// Exponential backoff between reconnect attempts.
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            next: initial,
+        }
+    }
+
+    /// Returns the delay before the next attempt and doubles the one after it.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(self.max);
+        delay
+    }
+
+    /// Starts over from the initial delay.
+    pub fn reset(&mut self) {
+        self.next = self.initial;
+    }
+}
+
+/// Runs a client built by `build` until it stops, then rebuilds it, until shutdown is
+/// requested.
+///
+/// `first` is used for the first run, so configuration errors surface before the
+/// supervisor starts.
+pub async fn supervise<F>(health: Arc<ClientHealth>, first: ClientBuilder, build: F)
+where
+    F: Fn() -> Result<ClientBuilder>,
+{
+    let name = health.name().to_string();
+    let mut backoff = Backoff::new(INITIAL_BACKOFF, MAX_BACKOFF);
+    let mut next = Some(first);
+
+    while !health.lifecycle().is_stopping() {
+        let builder = match next.take() {
+            Some(builder) => Ok(builder),
+            None => build(),
+        };
+        let builder = match builder {
+            Ok(builder) => builder,
+            Err(e) => {
+                error!("Failed to rebuild {name} bot client: {e}");
+                retry(&health, &mut backoff).await;
+                continue;
+            }
+        };
+
+        let started = Instant::now();
+        let Some(reason) = run(&health, builder).await else {
+            break;
+        };
+        health.set_disconnected(reason.clone());
+        warn!("{name} bot client stopped: {reason}");
+
+        if started.elapsed() >= STABLE_AFTER {
+            backoff.reset();
+        }
+        retry(&health, &mut backoff).await;
+    }
+
+    let _ = health.lifecycle().transition(BotState::Stopped);
+    info!("{name} bot client shut down.");
+}
+
+/// Waits out the backoff before the next attempt, or until shutdown is requested.
+async fn retry(health: &ClientHealth, backoff: &mut Backoff) {
+    let delay = backoff.next_delay();
+    info!(
+        "Reconnecting {} bot client in {}s...",
+        health.name(),
+        delay.as_secs()
+    );
+    tokio::select! {
+        _ = sleep(delay) => health.record_reconnect(),
+        _ = health.lifecycle().stop_requested() => {}
+    }
+}
+
+/// Connects a client and runs it until it stops. Returns why it stopped, or `None` if it
+/// was shut down.
+async fn run(health: &ClientHealth, builder: ClientBuilder) -> Option<String> {
+    let name = health.name().to_string();
+    // A separate task turns a panicking client into a reconnect instead of a dead bot
+    let task = tokio::spawn(async move {
+        let mut client = builder.await?;
+        info!("{name} bot client built. Connecting to Discord...");
+        client.start().await
+    });
+    let abort = task.abort_handle();
+
+    tokio::select! {
+        result = task => Some(match result {
+            Ok(Ok(())) => "client exited".to_string(),
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => format!("client panicked: {e}"),
+        }),
+        _ = health.lifecycle().stop_requested() => {
+            // Dropping the client closes its gateway connections
+            abort.abort();
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max_and_resets() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+
+        let delays: Vec<u64> = (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn metrics_list_every_bot() {
+        let monitor = ClientMonitor::new(["main".to_string(), "beta".to_string()]);
+        let beta = monitor.get("beta").unwrap();
+        beta.lifecycle().transition(BotState::Connecting).unwrap();
+        beta.lifecycle().transition(BotState::Ready).unwrap();
+        beta.record_reconnect();
+
+        let metrics = monitor.render_metrics();
+
+        assert!(metrics.contains("pwr_bot_client_connected{bot=\"main\"} 0\n"));
+        assert!(metrics.contains("pwr_bot_client_connected{bot=\"beta\"} 1\n"));
+        assert!(metrics.contains("pwr_bot_client_reconnects_total{bot=\"beta\"} 1\n"));
+    }
+}
@@ -0,0 +1,559 @@
+//! Temporary voice channels ("join to create").
+//!
+//! Joining a guild's hub channel creates a voice channel owned by the member, who is
+//! moved into it. The owner manages the channel from a control panel posted in its
+//! chat, and the channel is deleted once everyone left. Moving out of the hub continues
+//! the member's voice session, so time in temporary channels counts like any other.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use log::error;
+use log::info;
+use poise::Modal;
+use poise::serenity_prelude::*;
+
+use crate::bot::Data;
+use crate::bot::send_queue::SendTarget;
+
+/// Prefix of the custom IDs of the control panel buttons.
+pub const TEMP_VOICE_PREFIX: &str = "temp_voice:";
+
+/// Longest channel name Discord accepts.
+const MAX_NAME_LEN: usize = 100;
+/// Largest member limit Discord accepts for voice channels.
+const MAX_USER_LIMIT: u32 = 99;
+/// How long the owner has to fill in a rename or limit form.
+const MODAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A button of the control panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TempVoiceAction {
+    Lock,
+    Unlock,
+    Rename,
+    Limit,
+    /// Take over a channel whose owner left it.
+    Claim,
+}
+
+impl TempVoiceAction {
+    const ALL: [Self; 5] = [
+        Self::Lock,
+        Self::Unlock,
+        Self::Rename,
+        Self::Limit,
+        Self::Claim,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Lock => "lock",
+            Self::Unlock => "unlock",
+            Self::Rename => "rename",
+            Self::Limit => "limit",
+            Self::Claim => "claim",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Lock => "🔒 Lock",
+            Self::Unlock => "🔓 Unlock",
+            Self::Rename => "✏️ Rename",
+            Self::Limit => "👥 Limit",
+            Self::Claim => "👑 Claim",
+        }
+    }
+
+    /// Encodes the action as a component custom ID.
+    pub fn custom_id(self) -> String {
+        format!("{TEMP_VOICE_PREFIX}{}", self.name())
+    }
+
+    /// Decodes a custom ID. `None` if it is not a control panel button's.
+    pub fn parse(custom_id: &str) -> Option<Self> {
+        let name = custom_id.strip_prefix(TEMP_VOICE_PREFIX)?;
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+#[derive(Debug, Modal, Clone, PartialEq, Eq)]
+#[name = "Rename Channel"]
+struct RenameModal {
+    #[name = "Channel name"]
+    #[placeholder = "Movie night"]
+    #[min_length = 1]
+    #[max_length = 100]
+    name: String,
+}
+
+#[derive(Debug, Modal, Clone, PartialEq, Eq)]
+#[name = "Limit Members"]
+struct LimitModal {
+    #[name = "Member limit (0 for none)"]
+    #[placeholder = "5"]
+    #[min_length = 1]
+    #[max_length = 2]
+    limit: String,
+}
+
+/// Creates a temporary channel for a member who joined the hub, and deletes the
+/// temporary channel a member left if it is now empty.
+pub async fn on_voice_state_update(
+    ctx: &Context,
+    data: &Data,
+    old: Option<&VoiceState>,
+    new: &VoiceState,
+) {
+    let Some(guild_id) = new.guild_id else {
+        return;
+    };
+    let old_channel = old.and_then(|state| state.channel_id);
+    if old_channel == new.channel_id {
+        return;
+    }
+
+    if let Some(channel_id) = old_channel
+        && let Err(e) = delete_if_empty(ctx, data, guild_id, channel_id).await
+    {
+        error!("Failed to delete temporary channel {channel_id} in guild {guild_id}: {e}");
+    }
+    if let Some(channel_id) = new.channel_id
+        && let Err(e) = create_if_hub(ctx, data, guild_id, channel_id, new).await
+    {
+        error!("Failed to create temporary channel in guild {guild_id}: {e}");
+    }
+}
+
+/// Deletes the temporary channels of a guild that emptied while the bot was offline,
+/// and forgets the ones deleted by hand.
+pub async fn clean_up_guild(ctx: &Context, data: &Data, guild: &Guild) -> Result<()> {
+    let channels = data
+        .service
+        .temp_voice
+        .list_channels(guild.id.get())
+        .await?;
+    for channel in channels {
+        let channel_id = ChannelId::new(channel.channel_id.into());
+        if is_occupied(guild, channel_id) {
+            continue;
+        }
+        if guild.channels.contains_key(&channel_id) {
+            channel_id
+                .delete(&ctx.http, Some("Temporary channel is empty"))
+                .await?;
+        }
+        data.service
+            .temp_voice
+            .remove_channel(channel_id.get())
+            .await?;
+    }
+    Ok(())
+}
+
+/// Forgets a temporary channel deleted by someone else.
+pub async fn on_channel_delete(data: &Data, channel_id: ChannelId) {
+    if let Err(e) = data
+        .service
+        .temp_voice
+        .remove_channel(channel_id.get())
+        .await
+    {
+        error!("Failed to forget deleted temporary channel {channel_id}: {e}");
+    }
+}
+
+/// Runs a clicked control panel button and tells the member the outcome.
+pub async fn handle(
+    ctx: &Context,
+    data: &Data,
+    interaction: &ComponentInteraction,
+    action: TempVoiceAction,
+) -> Result<()> {
+    let text = match apply(ctx, data, interaction, action).await {
+        Ok(Some(text)) => text,
+        // Forms are answered when submitted
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            error!(
+                "Failed to {} temporary channel {}: {e:?}",
+                action.name(),
+                interaction.channel_id
+            );
+            "❌ The channel could not be changed. Make sure the bot has the **Manage Channels** permission.".to_string()
+        }
+    };
+
+    let response = CreateInteractionResponseMessage::new()
+        .content(text)
+        .ephemeral(true);
+    interaction
+        .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+        .await?;
+    Ok(())
+}
+
+/// Changes the channel. Returns the message for the member, or `None` once a form was
+/// shown instead.
+async fn apply(
+    ctx: &Context,
+    data: &Data,
+    interaction: &ComponentInteraction,
+    action: TempVoiceAction,
+) -> Result<Option<String>> {
+    let Some(guild_id) = interaction.guild_id else {
+        return Ok(Some("❌ This panel only works in servers.".to_string()));
+    };
+    let channel_id = ChannelId::new(interaction.channel_id.get());
+    let Some(channel) = data
+        .service
+        .temp_voice
+        .get_channel(channel_id.get())
+        .await?
+    else {
+        return Ok(Some(
+            "❌ This is no longer a temporary channel.".to_string(),
+        ));
+    };
+    let user_id = interaction.user.id;
+    let owner_id = UserId::new(channel.owner_id.into());
+
+    if action == TempVoiceAction::Claim {
+        return claim(ctx, data, guild_id, channel_id, owner_id, user_id).await;
+    }
+    if user_id != owner_id {
+        return Ok(Some(format!(
+            "❌ Only the owner, <@{owner_id}>, can change this channel."
+        )));
+    }
+
+    let text = match action {
+        TempVoiceAction::Lock => {
+            set_locked(ctx, guild_id, channel_id, true).await?;
+            "🔒 The channel is locked. Members already in it can stay."
+        }
+        TempVoiceAction::Unlock => {
+            set_locked(ctx, guild_id, channel_id, false).await?;
+            "🔓 The channel is open to everyone again."
+        }
+        TempVoiceAction::Rename => {
+            let modal = poise::execute_modal_on_component_interaction::<RenameModal>(
+                ctx,
+                interaction.clone(),
+                None,
+                Some(MODAL_TIMEOUT),
+            )
+            .await?;
+            if let Some(modal) = modal {
+                let name = truncate(modal.name.trim(), MAX_NAME_LEN);
+                channel_id
+                    .edit(&ctx.http, EditChannel::new().name(name))
+                    .await?;
+            }
+            return Ok(None);
+        }
+        TempVoiceAction::Limit => {
+            let modal = poise::execute_modal_on_component_interaction::<LimitModal>(
+                ctx,
+                interaction.clone(),
+                None,
+                Some(MODAL_TIMEOUT),
+            )
+            .await?;
+            if let Some(limit) = modal.and_then(|modal| parse_user_limit(&modal.limit)) {
+                channel_id
+                    .edit(&ctx.http, EditChannel::new().user_limit(limit))
+                    .await?;
+            }
+            return Ok(None);
+        }
+        TempVoiceAction::Claim => unreachable!("claims are handled above"),
+    };
+    Ok(Some(text.to_string()))
+}
+
+/// Hands the channel to `user_id` if its owner left it.
+async fn claim(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    owner_id: UserId,
+    user_id: UserId,
+) -> Result<Option<String>> {
+    if user_id == owner_id {
+        return Ok(Some("🛈 You already own this channel.".to_string()));
+    }
+    let (owner_present, user_present) = match ctx.cache.guild(guild_id) {
+        Some(guild) => (
+            is_in_channel(&guild, channel_id, owner_id),
+            is_in_channel(&guild, channel_id, user_id),
+        ),
+        None => (true, false),
+    };
+    if !user_present {
+        return Ok(Some("❌ Join the channel to claim it.".to_string()));
+    }
+    if owner_present {
+        return Ok(Some(format!(
+            "❌ <@{owner_id}> is still here and keeps the channel."
+        )));
+    }
+
+    data.service
+        .temp_voice
+        .set_owner(channel_id.get(), user_id.get())
+        .await?;
+    channel_id
+        .create_permission(&ctx.http, owner_overwrite(user_id), None)
+        .await?;
+    info!("Member {user_id} claimed temporary channel {channel_id} in guild {guild_id}.");
+    Ok(Some("👑 You own this channel now.".to_string()))
+}
+
+/// Creates a temporary channel for the member and moves them into it, if they joined
+/// the guild's hub channel.
+async fn create_if_hub(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    state: &VoiceState,
+) -> Result<()> {
+    let settings = data
+        .service
+        .settings
+        .get_server_settings(guild_id.get())
+        .await?;
+    if settings.voice.temp_hub_channel_id() != Some(channel_id.get()) {
+        return Ok(());
+    }
+    let Some(member) = state.member.as_ref().filter(|member| !member.user.bot()) else {
+        return Ok(());
+    };
+    let owner_id = member.user.id;
+
+    // Copy the hub's category permissions, so the new channel is as visible as the hub
+    let (category, mut overwrites) = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|guild| {
+            let hub = guild.channels.get(&channel_id)?;
+            let category = hub.parent_id;
+            let overwrites = category
+                .and_then(|id| guild.channels.get(&id))
+                .map(|category| category.permission_overwrites.to_vec())
+                .unwrap_or_default();
+            Some((category, overwrites))
+        })
+        .unwrap_or_default();
+    overwrites.push(owner_overwrite(owner_id));
+
+    let name = truncate(
+        &format!("{}'s channel", member.display_name()),
+        MAX_NAME_LEN,
+    );
+    let mut builder = CreateChannel::new(name)
+        .kind(ChannelType::Voice)
+        .permissions(overwrites);
+    if let Some(category) = category {
+        builder = builder.category(category);
+    }
+    if let Some(limit) = settings.voice.temp_user_limit() {
+        builder = builder.user_limit(limit.min(MAX_USER_LIMIT));
+    }
+    let channel = guild_id.create_channel(&ctx.http, builder).await?;
+    data.service
+        .temp_voice
+        .add_channel(guild_id.get(), channel.id.get(), owner_id.get())
+        .await?;
+
+    if let Err(e) = guild_id.move_member(&ctx.http, owner_id, channel.id).await {
+        // The member left the hub before they could be moved
+        channel
+            .id
+            .delete(&ctx.http, Some("Temporary channel is empty"))
+            .await?;
+        data.service
+            .temp_voice
+            .remove_channel(channel.id.get())
+            .await?;
+        return Err(e.into());
+    }
+    info!(
+        "Created temporary channel {} for {owner_id} in guild {guild_id}.",
+        channel.id
+    );
+
+    send_panel(data, guild_id, channel.id, owner_id).await
+}
+
+/// Deletes a temporary channel once nobody is left in it.
+async fn delete_if_empty(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<()> {
+    let occupied = ctx
+        .cache
+        .guild(guild_id)
+        .is_some_and(|guild| is_occupied(&guild, channel_id));
+    if occupied {
+        return Ok(());
+    }
+    if data
+        .service
+        .temp_voice
+        .get_channel(channel_id.get())
+        .await?
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    channel_id
+        .delete(&ctx.http, Some("Temporary channel is empty"))
+        .await?;
+    data.service
+        .temp_voice
+        .remove_channel(channel_id.get())
+        .await?;
+    info!("Deleted empty temporary channel {channel_id} in guild {guild_id}.");
+    Ok(())
+}
+
+/// Posts the control panel in the channel's chat.
+async fn send_panel(
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    owner_id: UserId,
+) -> Result<()> {
+    let text = format!(
+        "## 🔊 Your Channel\n<@{owner_id}> owns this channel. It is deleted once everyone leaves.\n-# If the owner leaves, anyone still here can claim it."
+    );
+    let buttons: Vec<_> = TempVoiceAction::ALL
+        .into_iter()
+        .map(|action| {
+            CreateButton::new(action.custom_id())
+                .label(action.label())
+                .style(ButtonStyle::Secondary)
+        })
+        .collect();
+    let message = CreateMessage::new()
+        .flags(MessageFlags::IS_COMPONENTS_V2)
+        .components(vec![CreateComponent::Container(CreateContainer::new(
+            vec![
+                CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+                CreateContainerComponent::ActionRow(CreateActionRow::Buttons(buttons.into())),
+            ],
+        ))])
+        .allowed_mentions(CreateAllowedMentions::new().users(vec![owner_id]));
+
+    data.send_queue
+        .send(
+            SendTarget::Channel(channel_id.get()),
+            move |http| async move {
+                let channel = channel_id.to_guild_channel(&http, Some(guild_id)).await?;
+                channel.send_message(&http, message).await?;
+                Ok(())
+            },
+        )
+        .await
+}
+
+/// Denies or restores @everyone's **Connect** permission, keeping the rest of their
+/// overwrite.
+async fn set_locked(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    locked: bool,
+) -> Result<()> {
+    let everyone = PermissionOverwriteType::Role(guild_id.everyone_role());
+    let mut overwrite = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|guild| {
+            guild
+                .channels
+                .get(&channel_id)?
+                .permission_overwrites
+                .iter()
+                .find(|overwrite| overwrite.kind == everyone)
+                .cloned()
+        })
+        .unwrap_or(PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::empty(),
+            kind: everyone,
+        });
+    overwrite.allow.remove(Permissions::CONNECT);
+    overwrite.deny.set(Permissions::CONNECT, locked);
+    channel_id
+        .create_permission(&ctx.http, overwrite, None)
+        .await?;
+    Ok(())
+}
+
+/// Lets the owner join their channel even while it is locked or full.
+fn owner_overwrite(owner_id: UserId) -> PermissionOverwrite {
+    PermissionOverwrite {
+        allow: Permissions::VIEW_CHANNEL | Permissions::CONNECT | Permissions::MOVE_MEMBERS,
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Member(owner_id),
+    }
+}
+
+/// Whether anyone is in a voice channel.
+fn is_occupied(guild: &Guild, channel_id: ChannelId) -> bool {
+    guild
+        .voice_states
+        .iter()
+        .any(|state| state.channel_id == Some(channel_id))
+}
+
+/// Whether a member is in a voice channel.
+fn is_in_channel(guild: &Guild, channel_id: ChannelId, user_id: UserId) -> bool {
+    guild
+        .voice_states
+        .get(&user_id)
+        .is_some_and(|state| state.channel_id == Some(channel_id))
+}
+
+/// Parses a member limit typed by the owner. 0 removes the limit.
+fn parse_user_limit(input: &str) -> Option<u32> {
+    input
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|limit| *limit <= MAX_USER_LIMIT)
+}
+
+fn truncate(text: &str, max_len: usize) -> String {
+    text.chars().take(max_len).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_voice_action_round_trips() {
+        for action in TempVoiceAction::ALL {
+            assert_eq!(TempVoiceAction::parse(&action.custom_id()), Some(action));
+        }
+        assert_eq!(TempVoiceAction::parse("temp_voice:explode"), None);
+        assert_eq!(TempVoiceAction::parse("watch_party:1:lock"), None);
+    }
+
+    #[test]
+    fn parse_user_limit_accepts_discord_range() {
+        assert_eq!(parse_user_limit(" 5 "), Some(5));
+        assert_eq!(parse_user_limit("0"), Some(0));
+        assert_eq!(parse_user_limit("100"), None);
+        assert_eq!(parse_user_limit("five"), None);
+    }
+}
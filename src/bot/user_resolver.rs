@@ -0,0 +1,208 @@
+//! Cached lookup of user display names and avatars.
+//!
+//! Leaderboard and stats renders show many users at once. Looking each one up over
+//! REST takes seconds, so [`UserResolver`] keeps resolved users for a while, reads
+//! the gateway cache first and fetches the rest in concurrent chunks.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use futures::future::join_all;
+use log::trace;
+use poise::serenity_prelude::Cache;
+use poise::serenity_prelude::GuildId;
+use poise::serenity_prelude::Http;
+use poise::serenity_prelude::User;
+use poise::serenity_prelude::UserId;
+
+/// How long a resolved user is reused before it is looked up again.
+const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Number of users fetched over REST at the same time.
+const FETCH_CHUNK_SIZE: usize = 10;
+
+/// Number of cached users above which expired entries are dropped.
+const MAX_ENTRIES: usize = 10_000;
+
+/// A user as shown in a guild.
+#[derive(Clone, Debug)]
+pub struct ResolvedUser {
+    pub user: User,
+    /// Server nickname, global name or username, in that order.
+    pub display_name: String,
+}
+
+impl ResolvedUser {
+    /// Returns the URL of the user's non-animated avatar.
+    pub fn avatar_url(&self) -> String {
+        self.user.static_face()
+    }
+}
+
+/// Cache key. Display names differ per guild, so users are cached per guild.
+type Key = (Option<GuildId>, UserId);
+
+/// Resolves users to display names and avatars, caching them with a TTL.
+pub struct UserResolver {
+    ttl: Duration,
+    entries: RwLock<HashMap<Key, (Instant, ResolvedUser)>>,
+}
+
+impl Default for UserResolver {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl UserResolver {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Resolves a single user.
+    pub async fn resolve(
+        &self,
+        http: &Http,
+        cache: &Cache,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+    ) -> Option<ResolvedUser> {
+        self.resolve_many(http, cache, guild_id, &[user_id])
+            .await
+            .remove(&user_id)
+    }
+
+    /// Resolves every user it can. Users that can't be fetched are left out.
+    pub async fn resolve_many(
+        &self,
+        http: &Http,
+        cache: &Cache,
+        guild_id: Option<GuildId>,
+        user_ids: &[UserId],
+    ) -> HashMap<UserId, ResolvedUser> {
+        let start = Instant::now();
+        let mut resolved = self.cached(guild_id, user_ids);
+
+        let mut fresh: Vec<ResolvedUser> = Vec::new();
+        let mut missing: Vec<UserId> = Vec::new();
+        for &user_id in user_ids {
+            if resolved.contains_key(&user_id) || missing.contains(&user_id) {
+                continue;
+            }
+            match Self::from_gateway_cache(cache, guild_id, user_id) {
+                Some(user) => fresh.push(user),
+                None => missing.push(user_id),
+            }
+        }
+
+        for chunk in missing.chunks(FETCH_CHUNK_SIZE) {
+            let users = join_all(
+                chunk
+                    .iter()
+                    .map(|&user_id| Self::fetch(http, guild_id, user_id)),
+            )
+            .await;
+            fresh.extend(users.into_iter().flatten());
+        }
+
+        self.store(guild_id, &fresh);
+        resolved.extend(fresh.into_iter().map(|user| (user.user.id, user)));
+
+        trace!(
+            "resolve_users {} of {} ({} over REST) in {} ms",
+            resolved.len(),
+            user_ids.len(),
+            missing.len(),
+            start.elapsed().as_millis()
+        );
+        resolved
+    }
+
+    /// Resolves users from the caches only, without REST calls. Users in neither
+    /// cache are left out.
+    pub fn resolve_cached(
+        &self,
+        cache: &Cache,
+        guild_id: Option<GuildId>,
+        user_ids: &[UserId],
+    ) -> HashMap<UserId, ResolvedUser> {
+        let mut resolved = self.cached(guild_id, user_ids);
+        for &user_id in user_ids {
+            if !resolved.contains_key(&user_id)
+                && let Some(user) = Self::from_gateway_cache(cache, guild_id, user_id)
+            {
+                resolved.insert(user_id, user);
+            }
+        }
+        resolved
+    }
+
+    /// Returns the users cached and not expired.
+    fn cached(
+        &self,
+        guild_id: Option<GuildId>,
+        user_ids: &[UserId],
+    ) -> HashMap<UserId, ResolvedUser> {
+        let entries = self.entries.read().unwrap();
+        user_ids
+            .iter()
+            .filter_map(|&user_id| {
+                let (at, user) = entries.get(&(guild_id, user_id))?;
+                (at.elapsed() < self.ttl).then(|| (user_id, user.clone()))
+            })
+            .collect()
+    }
+
+    fn store(&self, guild_id: Option<GuildId>, users: &[ResolvedUser]) {
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        for user in users {
+            entries.insert((guild_id, user.user.id), (now, user.clone()));
+        }
+        if entries.len() > MAX_ENTRIES {
+            entries.retain(|_, (at, _)| at.elapsed() < self.ttl);
+        }
+    }
+
+    /// Looks a member up in the gateway cache, which costs no request.
+    fn from_gateway_cache(
+        cache: &Cache,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+    ) -> Option<ResolvedUser> {
+        let guild = cache.guild(guild_id?)?;
+        let member = guild.members.get(&user_id)?;
+        Some(ResolvedUser {
+            user: member.user.clone(),
+            display_name: member.display_name().to_string(),
+        })
+    }
+
+    /// Fetches a user over REST, as a guild member when possible.
+    async fn fetch(
+        http: &Http,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+    ) -> Option<ResolvedUser> {
+        if let Some(guild_id) = guild_id
+            && let Ok(member) = guild_id.member(http, user_id).await
+        {
+            return Some(ResolvedUser {
+                display_name: member.display_name().to_string(),
+                user: member.user,
+            });
+        }
+
+        // Users who left the guild still have a global profile
+        let user = user_id.to_user(http).await.ok()?;
+        Some(ResolvedUser {
+            display_name: user.display_name().to_string(),
+            user,
+        })
+    }
+}
@@ -37,6 +37,34 @@ pub fn format_duration(seconds: i64) -> String {
     }
 }
 
+/// Retention windows offered in settings views, in days. 0 uses the bot's default.
+pub const RETENTION_OPTIONS: [(u32, &str); 6] = [
+    (0, "Bot default"),
+    (7, "7 days"),
+    (30, "30 days"),
+    (90, "90 days"),
+    (180, "180 days"),
+    (365, "1 year"),
+];
+
+/// Formats a retention window in days, where 0 is forever.
+pub fn format_retention_days(days: u32) -> String {
+    match days {
+        0 => "forever".to_string(),
+        1 => "1 day".to_string(),
+        days => format!("{days} days"),
+    }
+}
+
+/// Returns the breadcrumb suffix shown on settings pages with unsaved changes.
+pub fn unsaved_marker(is_dirty: bool) -> &'static str {
+    if is_dirty {
+        "  •  ✎ *Unsaved changes*"
+    } else {
+        ""
+    }
+}
+
 /// Parses a comma-separated string of URLs and validates the count.
 pub fn parse_and_validate_urls(links: &str) -> Result<Vec<&str>, BotError> {
     let urls: Vec<&str> = links.split(',').map(|s| s.trim()).collect();
@@ -57,10 +85,156 @@ pub fn validate_url_count(urls: &[&str]) -> Result<(), BotError> {
     Ok(())
 }
 
+/// A link to a Discord message, e.g.
+/// `https://discord.com/channels/<guild>/<channel>/<message>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageLink {
+    /// Server of the message, or `None` for a DM.
+    pub guild_id: Option<u64>,
+    pub channel_id: u64,
+    pub message_id: u64,
+}
+
+/// Parses a Discord message link, including ones from the PTB and Canary clients.
+pub fn parse_message_link(link: &str) -> Option<MessageLink> {
+    let link = link.trim().trim_start_matches('<').trim_end_matches('>');
+    let path = link
+        .strip_prefix("https://")
+        .or_else(|| link.strip_prefix("http://"))?;
+    let (host, path) = path.split_once('/')?;
+    let host = host
+        .strip_prefix("ptb.")
+        .or_else(|| host.strip_prefix("canary."))
+        .unwrap_or(host);
+    if host != "discord.com" && host != "discordapp.com" {
+        return None;
+    }
+
+    let mut parts = path.strip_prefix("channels/")?.split('/');
+    let guild_id = match parts.next()? {
+        "@me" => None,
+        id => Some(id.parse().ok()?),
+    };
+    let channel_id = parts.next()?.parse().ok()?;
+    let message_id = parts.next()?.parse().ok()?;
+    if parts.next().is_some_and(|rest| !rest.is_empty()) {
+        return None;
+    }
+    Some(MessageLink {
+        guild_id,
+        channel_id,
+        message_id,
+    })
+}
+
+/// Score of a matched character.
+const FUZZY_MATCH_BONUS: i32 = 10;
+/// Bonus for a character matched right after the previous match.
+const FUZZY_SEQUENTIAL_BONUS: i32 = 15;
+/// Bonus for a character matched at the start of a word, e.g. after a space or in
+/// camelCase.
+const FUZZY_WORD_START_BONUS: i32 = 30;
+/// Penalty per character skipped before the first match, up to three.
+const FUZZY_LEADING_PENALTY: i32 = -5;
+/// Penalty per character left unmatched.
+const FUZZY_UNMATCHED_PENALTY: i32 = -1;
+
+/// Scores how well `candidate` matches `pattern` typed in order with gaps, like "mdx"
+/// for "MangaDex", ignoring case and spaces in the pattern. Higher is better.
+///
+/// Returns `None` if some character of the pattern is not found in order, and 0 for an
+/// empty pattern.
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i32> {
+    let pattern: Vec<char> = pattern
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let mut next = 0;
+    let mut score = 0;
+    let mut first_match = None;
+    let mut prev_matched = false;
+    let mut prev: Option<char> = None;
+    let mut len = 0;
+
+    for (i, c) in candidate.chars().enumerate() {
+        len += 1;
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        if next < pattern.len() && lower == pattern[next] {
+            score += FUZZY_MATCH_BONUS;
+            if prev_matched {
+                score += FUZZY_SEQUENTIAL_BONUS;
+            }
+            let word_start = match prev {
+                None => true,
+                Some(prev) => !prev.is_alphanumeric() || (prev.is_lowercase() && c.is_uppercase()),
+            };
+            if word_start {
+                score += FUZZY_WORD_START_BONUS;
+            }
+            first_match.get_or_insert(i);
+            next += 1;
+            prev_matched = true;
+        } else {
+            prev_matched = false;
+        }
+        prev = Some(c);
+    }
+
+    if next < pattern.len() {
+        return None;
+    }
+    let leading = first_match.unwrap_or(0).min(3) as i32;
+    let unmatched = (len - pattern.len()) as i32;
+    Some(score + leading * FUZZY_LEADING_PENALTY + unmatched * FUZZY_UNMATCHED_PENALTY)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_message_link_reads_ids() {
+        assert_eq!(
+            parse_message_link("https://discord.com/channels/1/2/3"),
+            Some(MessageLink {
+                guild_id: Some(1),
+                channel_id: 2,
+                message_id: 3,
+            })
+        );
+        assert_eq!(
+            parse_message_link("<https://canary.discord.com/channels/@me/2/3>"),
+            Some(MessageLink {
+                guild_id: None,
+                channel_id: 2,
+                message_id: 3,
+            })
+        );
+        assert!(parse_message_link("https://discord.com/channels/1/2").is_none());
+        assert!(parse_message_link("https://example.com/channels/1/2/3").is_none());
+        assert!(parse_message_link("https://mangadex.org/title/abc").is_none());
+    }
+
+    #[test]
+    fn fuzzy_score_matches_characters_in_order() {
+        assert!(fuzzy_score("mdx", "MangaDex (mangadex.org)").is_some());
+        assert!(fuzzy_score("MANGA", "MangaDex (mangadex.org)").is_some());
+        assert!(fuzzy_score("xdm", "MangaDex (mangadex.org)").is_none());
+        assert!(fuzzy_score("mdx", "AniList Anime (anilist.co)").is_none());
+        assert_eq!(fuzzy_score(" ", "Nyaa (nyaa.si)"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts_and_runs() {
+        let score = |pattern| fuzzy_score(pattern, "MangaDex (mangadex.org)").unwrap();
+        assert!(score("md") > score("ng"));
+        assert!(score("man") > score("mng"));
+    }
+
     #[test]
     fn validate_urls_accepts_valid_count() {
         let urls = vec!["url1", "url2", "url3"];
@@ -124,6 +298,13 @@ mod tests {
         assert_eq!(format_duration(604800), "7d");
     }
 
+    #[test]
+    fn format_retention_days_treats_zero_as_forever() {
+        assert_eq!(format_retention_days(0), "forever");
+        assert_eq!(format_retention_days(1), "1 day");
+        assert_eq!(format_retention_days(90), "90 days");
+    }
+
     #[test]
     fn format_duration_large_values() {
         assert_eq!(format_duration(8640000), "100d"); // 100 days exactly
@@ -0,0 +1,189 @@
+//! Tabular data exports attached to views.
+use poise::serenity_prelude::ButtonStyle;
+use poise::serenity_prelude::CreateAttachment;
+use poise::serenity_prelude::CreateButton;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::action_enum;
+use crate::bot::view::Action;
+use crate::bot::view::ActionRegistry;
+
+action_enum! {
+    #[derive(Copy)]
+    ExportAction {
+        #[label = "Export CSV"]
+        Csv,
+        #[label = "Export JSON"]
+        Json,
+    }
+}
+
+/// File format of a [`TabularExport`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl From<ExportAction> for ExportFormat {
+    fn from(action: ExportAction) -> Self {
+        match action {
+            ExportAction::Csv => ExportFormat::Csv,
+            ExportAction::Json => ExportFormat::Json,
+        }
+    }
+}
+
+impl ExportAction {
+    /// Creates one button per export format.
+    pub fn create_buttons<T: Action>(
+        registry: &mut ActionRegistry<T>,
+        wrap: fn(ExportAction) -> T,
+    ) -> Vec<CreateButton<'static>> {
+        [ExportAction::Csv, ExportAction::Json]
+            .into_iter()
+            .map(|action| {
+                registry
+                    .register(wrap(action))
+                    .as_button()
+                    .label(action.label())
+                    .style(ButtonStyle::Secondary)
+            })
+            .collect()
+    }
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Rows of named columns a view can hand out as a CSV or JSON file.
+#[derive(Clone, Debug)]
+pub struct TabularExport {
+    name: String,
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl TabularExport {
+    /// Creates an empty export. `name` is the file name without extension.
+    pub fn new(name: impl Into<String>, columns: &[&'static str]) -> Self {
+        Self {
+            name: name.into(),
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row with one value per column.
+    pub fn push_row<I, V>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.rows.push(values.into_iter().map(Into::into).collect());
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the export has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Formats the rows as CSV with a header line.
+    ///
+    /// Text that a spreadsheet would run as a formula is prefixed with `'`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = self.columns.join(",");
+        csv.push('\n');
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(csv_field).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Formats the rows as a JSON array of objects keyed by column.
+    pub fn to_json(&self) -> String {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = self
+                    .columns
+                    .iter()
+                    .map(|column| column.to_string())
+                    .zip(row.iter().cloned())
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        serde_json::to_string_pretty(&rows).unwrap_or_default()
+    }
+
+    /// Returns the export as a file attachment.
+    pub fn to_attachment(&self, format: ExportFormat) -> CreateAttachment<'static> {
+        let content = match format {
+            ExportFormat::Csv => self.to_csv(),
+            ExportFormat::Json => self.to_json(),
+        };
+        CreateAttachment::bytes(
+            content.into_bytes(),
+            format!("{}.{}", self.name, format.extension()),
+        )
+    }
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(text) if text.starts_with(['=', '+', '-', '@']) => format!("'{text}"),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export() -> TabularExport {
+        let mut export = TabularExport::new("test", &["name", "seconds"]);
+        export.push_row([Value::from("Alice, \"Al\""), Value::from(60)]);
+        export.push_row([Value::from("=1+1"), Value::Null]);
+        export
+    }
+
+    #[test]
+    fn csv_quotes_and_defuses_fields() {
+        assert_eq!(
+            export().to_csv(),
+            "name,seconds\n\"Alice, \"\"Al\"\"\",60\n'=1+1,\n"
+        );
+    }
+
+    #[test]
+    fn json_keys_rows_by_column() {
+        let rows: Value = serde_json::from_str(&export().to_json()).unwrap();
+
+        assert_eq!(rows[0]["name"], "Alice, \"Al\"");
+        assert_eq!(rows[0]["seconds"], 60);
+        assert!(rows[1]["seconds"].is_null());
+    }
+}
@@ -26,11 +26,13 @@ use tokio::sync::mpsc;
 use crate::bot::command::Context;
 use crate::bot::command::Error;
 use crate::bot::command::prelude::Router;
+use crate::bot::latency;
 
 /// Type alias for a thread-safe, shared handle to a Discord message.
 type EventMessage<T> = (Option<T>, ViewEvent);
 type Registry<T> = Arc<RwLock<ActionRegistry<T>>>;
 
+pub mod export;
 pub mod pagination;
 
 // ── Response Content ───────────────────────────────────────────────────────────────
@@ -490,6 +492,33 @@ impl<'a, T: Action + 'static> ViewContext<'a, T> {
         });
     }
 
+    /// Sends `attachment` to the user who triggered this event, visible only to them.
+    ///
+    /// Answers the component interaction directly, so return the result from the handler.
+    pub async fn send_file(&self, attachment: CreateAttachment<'static>) -> Result<ViewCmd, Error> {
+        match &self.event {
+            ViewEvent::Component(interaction) => {
+                let response = CreateInteractionResponseMessage::new()
+                    .add_file(attachment)
+                    .ephemeral(true);
+                interaction
+                    .create_response(
+                        self.poise.http(),
+                        CreateInteractionResponse::Message(response),
+                    )
+                    .await?;
+                Ok(ViewCmd::AlreadyResponded)
+            }
+            _ => {
+                let reply = CreateReply::default()
+                    .attachment(attachment)
+                    .ephemeral(true);
+                self.poise.send(reply).await?;
+                Ok(ViewCmd::Continue)
+            }
+        }
+    }
+
     pub async fn spawn_modal_component<M: poise::Modal + Send>(
         &self,
         modal_consumer: impl FnOnce(M) -> T + Send + 'static,
@@ -599,6 +628,8 @@ where
     pub async fn run(&mut self) -> Result<(), Error> {
         let mut channel = ViewChannel::new(self.handler.channel_config(), self.registry.clone());
         self.render_view().await?;
+        // The command has answered; time spent waiting on the user isn't its latency
+        latency::mark_answered(self.ctx).await;
 
         let msg_id = {
             let lock = self.coordinator.reply_handle().await;
@@ -0,0 +1,115 @@
+//! Handles the "Schedule watch party" buttons on feed notifications.
+//!
+//! Notifications are sent outside any command, so no view collects their buttons.
+//! [`crate::bot::BotEventHandler`] routes clicks here by their custom ID instead.
+
+use anyhow::Result;
+use chrono::Duration;
+use chrono::Utc;
+use log::error;
+use log::info;
+use poise::serenity_prelude::*;
+
+use crate::bot::Data;
+use crate::bot::scheduled_event::ExternalEvent;
+use crate::bot::scheduled_event::create_external_event;
+use crate::bot::scheduled_event::event_url;
+use crate::event::WatchPartyId;
+
+/// How long a scheduled watch party lasts.
+const WATCH_PARTY_LENGTH_MINS: i64 = 60;
+
+/// Discord rejects events that start in the past, so a party starts at least this late.
+const MIN_DELAY_MINS: u32 = 1;
+
+/// Schedules a watch party for the episode of a clicked button and tells the member
+/// the outcome.
+pub async fn handle(
+    http: &Http,
+    data: &Data,
+    interaction: &ComponentInteraction,
+    id: WatchPartyId,
+) -> Result<()> {
+    let text = match schedule(http, data, interaction, &id).await {
+        Ok(text) => text,
+        Err(e) => {
+            error!(
+                "Failed to schedule a watch party for feed {}: {e:?}",
+                id.feed_id
+            );
+            "❌ The event could not be created. Make sure the bot has the **Create Events** permission.".to_string()
+        }
+    };
+
+    let response = CreateInteractionResponseMessage::new()
+        .content(text)
+        .ephemeral(true);
+    interaction
+        .create_response(http, CreateInteractionResponse::Message(response))
+        .await?;
+    Ok(())
+}
+
+/// Creates the event. Returns the message for the member, which explains why nothing
+/// was scheduled when the member or server cannot schedule one.
+async fn schedule(
+    http: &Http,
+    data: &Data,
+    interaction: &ComponentInteraction,
+    id: &WatchPartyId,
+) -> Result<String> {
+    let Some(guild_id) = interaction.guild_id else {
+        return Ok("❌ Watch parties can only be scheduled in servers.".to_string());
+    };
+
+    let can_create_events = interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| {
+            permissions.intersects(Permissions::CREATE_EVENTS | Permissions::MANAGE_EVENTS)
+        });
+    if !can_create_events {
+        return Ok(
+            "❌ You need the **Create Events** permission to schedule a watch party.".to_string(),
+        );
+    }
+
+    let settings = data
+        .service
+        .settings
+        .get_server_settings(guild_id.get())
+        .await?;
+    let Some(delay_mins) = settings.feeds.watch_party_delay_mins else {
+        return Ok("❌ Watch parties are turned off on this server.".to_string());
+    };
+
+    let Some(feed) = data.service.feed_subscription.get_feed(id.feed_id).await? else {
+        return Ok("❌ This feed is no longer tracked.".to_string());
+    };
+
+    let start = Utc::now() + Duration::minutes(delay_mins.max(MIN_DELAY_MINS).into());
+    let event = ExternalEvent {
+        name: format!("{} · Episode {}", feed.name, id.episode),
+        description: format!(
+            "Watch party for episode {} of {}, scheduled by {} from a feed notification.",
+            id.episode,
+            feed.name,
+            interaction.user.display_name()
+        ),
+        location: feed.source_url.clone(),
+        start,
+        end: start + Duration::minutes(WATCH_PARTY_LENGTH_MINS),
+    };
+    let event_id = create_external_event(http, guild_id, &event).await?;
+
+    info!(
+        "Scheduled watch party for feed {} in guild {guild_id}.",
+        feed.id
+    );
+    Ok(format!(
+        "🍿 Watch party scheduled <t:{}:R>: {}",
+        start.timestamp(),
+        event_url(guild_id, event_id)
+    ))
+}
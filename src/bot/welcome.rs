@@ -0,0 +1,171 @@
+//! Posts welcome cards for members joining a guild.
+
+use anyhow::Result;
+use log::info;
+use minijinja::Environment;
+use poise::serenity_prelude::*;
+
+use crate::bot::Data;
+use crate::bot::command::welcome::image_generator::WelcomeCardData;
+use crate::bot::command::welcome::image_generator::WelcomeImageGenerator;
+use crate::bot::send_queue::SendTarget;
+
+const WELCOME_FILE: &str = "welcome.png";
+
+/// Posts the guild's welcome card for a new member, if the guild turned welcome cards on.
+///
+/// `inviter_id` is the creator of the invite the member joined through, if known. They are
+/// named under the card without being pinged, and welcome messages can mention them with
+/// `{{ inviter }}`.
+pub async fn send_welcome(
+    ctx: &Context,
+    data: &Data,
+    generator: &WelcomeImageGenerator,
+    member: &Member,
+    inviter_id: Option<UserId>,
+) -> Result<()> {
+    let guild_id = member.guild_id;
+    let settings = data
+        .service
+        .settings
+        .get_server_settings(guild_id.get())
+        .await?;
+    let welcome = &settings.welcome;
+    if !welcome.enabled.unwrap_or(false) {
+        return Ok(());
+    }
+    let Some(channel_id) = welcome
+        .channel_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+
+    let (server_name, member_count) = ctx
+        .cache
+        .guild(guild_id)
+        .map(|guild| (guild.name.to_string(), guild.member_count))
+        .unwrap_or_default();
+    let inviter = match inviter_id {
+        Some(inviter_id) => data
+            .users
+            .resolve(&ctx.http, &ctx.cache, Some(guild_id), inviter_id)
+            .await
+            .map(|user| user.display_name),
+        None => None,
+    };
+
+    let mut card = WelcomeCardData {
+        template_id: welcome
+            .template_id
+            .clone()
+            .unwrap_or_else(|| "1".to_string()),
+        username: member.display_name().to_string(),
+        user_tag: format!("@{}", member.user.name),
+        avatar_url: member.face(),
+        avatar_b64: None,
+        server_name,
+        member_count: member_count.to_string(),
+        member_number: format!("#{member_count}"),
+        primary_color: welcome
+            .primary_color
+            .clone()
+            .unwrap_or_else(|| "#5865F2".to_string()),
+        welcome_message: String::new(),
+    };
+    // Rotate through the messages, so consecutive members get different ones
+    let template = welcome
+        .messages
+        .as_deref()
+        .filter(|messages| !messages.is_empty())
+        .map(|messages| messages[member_count as usize % messages.len()].as_str())
+        .unwrap_or("Welcome to the server!");
+    card.welcome_message = render_welcome_message(template, &card, inviter.as_deref());
+
+    let image = generator.generate_card(card, &data.renderer).await?;
+
+    let mut content = member.mention().to_string();
+    if let Some(inviter_id) = inviter_id {
+        content.push_str(&format!("\n-# Invited by <@{inviter_id}>"));
+    }
+    let message = CreateMessage::new()
+        .content(content)
+        .add_file(CreateAttachment::bytes(image, WELCOME_FILE))
+        .allowed_mentions(CreateAllowedMentions::new().users(vec![member.user.id]));
+    data.send_queue
+        .send(SendTarget::Channel(channel_id), move |http| async move {
+            ChannelId::new(channel_id)
+                .send_message(&http, message)
+                .await?;
+            Ok(())
+        })
+        .await?;
+
+    info!(
+        "Sent welcome card for {} in guild {guild_id}.",
+        member.user.id
+    );
+    Ok(())
+}
+
+/// Fills the `{{ variable }}` placeholders of a welcome message.
+///
+/// Messages that are not valid templates are sent as written.
+pub fn render_welcome_message(
+    template: &str,
+    card: &WelcomeCardData,
+    inviter: Option<&str>,
+) -> String {
+    let env = Environment::new();
+    env.render_str(
+        template,
+        minijinja::context! {
+            username => &card.username,
+            user_tag => &card.user_tag,
+            server_name => &card.server_name,
+            member_count => &card.member_count,
+            member_number => &card.member_number,
+            inviter => inviter.unwrap_or("someone"),
+        },
+    )
+    .unwrap_or_else(|_| template.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card() -> WelcomeCardData {
+        WelcomeCardData {
+            template_id: "1".to_string(),
+            username: "Frieren".to_string(),
+            user_tag: "@frieren".to_string(),
+            avatar_url: String::new(),
+            avatar_b64: None,
+            server_name: "Mages".to_string(),
+            member_count: "42".to_string(),
+            member_number: "#42".to_string(),
+            primary_color: "#5865F2".to_string(),
+            welcome_message: String::new(),
+        }
+    }
+
+    #[test]
+    fn render_welcome_message_fills_inviter() {
+        let text = render_welcome_message(
+            "Welcome to {{ server_name }}, {{ username }}! Invited by {{ inviter }}.",
+            &card(),
+            Some("Himmel"),
+        );
+
+        assert_eq!(text, "Welcome to Mages, Frieren! Invited by Himmel.");
+    }
+
+    #[test]
+    fn render_welcome_message_keeps_invalid_templates() {
+        let text = render_welcome_message("Hi {{ username", &card(), None);
+
+        assert_eq!(text, "Hi {{ username");
+    }
+}
@@ -0,0 +1,396 @@
+//! Deployment checks run by `pwr-bot --check` and `/owner config-check`.
+//!
+//! Validates the configuration, database and Discord credentials without starting the
+//! bot, and collects the results in a [`CheckReport`]. A report with any failure makes
+//! `--check` exit with a non-zero status, so deployment pipelines can stop early.
+
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::str::FromStr;
+
+use poise::serenity_prelude::*;
+
+use crate::bot::Bot;
+use crate::config::Config;
+use crate::repo::PgRepos;
+
+/// Outcome of one check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but may not do what the operator expects.
+    Warn,
+    Fail,
+    /// Not checked, e.g. because the feature is disabled.
+    Skip,
+}
+
+impl CheckStatus {
+    /// Fixed-width label for the text report.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        }
+    }
+
+    /// Icon for the Discord report.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Pass => "✅",
+            Self::Warn => "⚠️",
+            Self::Fail => "❌",
+            Self::Skip => "➖",
+        }
+    }
+}
+
+/// Result of one check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckItem {
+    /// Short name of what was checked, e.g. `database`.
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// Results of a deployment check, in the order they ran.
+#[derive(Clone, Debug, Default)]
+pub struct CheckReport {
+    pub items: Vec<CheckItem>,
+}
+
+impl CheckReport {
+    /// Adds the result of a check.
+    pub fn push(
+        &mut self,
+        name: impl Into<String>,
+        status: CheckStatus,
+        detail: impl Into<String>,
+    ) {
+        self.items.push(CheckItem {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    /// Whether no check failed. Warnings still pass.
+    pub fn passed(&self) -> bool {
+        self.items
+            .iter()
+            .all(|item| item.status != CheckStatus::Fail)
+    }
+
+    /// Counts the checks with a status.
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.status == status)
+            .count()
+    }
+
+    /// Formats the report as one line per check and a summary line, for `--check`.
+    ///
+    /// Each line is `STATUS<tab>name<tab>detail`, so pipelines can grep or split it.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        for item in &self.items {
+            let _ = writeln!(
+                out,
+                "{}\t{}\t{}",
+                item.status.label(),
+                item.name,
+                item.detail
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}\tsummary\t{} passed, {} warnings, {} failed, {} skipped",
+            if self.passed() { "PASS" } else { "FAIL" },
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail),
+            self.count(CheckStatus::Skip),
+        );
+        out
+    }
+
+    /// Formats the report as Markdown, for `/owner config-check`.
+    pub fn render_markdown(&self) -> String {
+        let mut out = String::from("### Config Check");
+        for item in &self.items {
+            let _ = write!(
+                out,
+                "\n- {} **{}**: {}",
+                item.status.icon(),
+                item.name,
+                item.detail
+            );
+        }
+        let _ = write!(
+            out,
+            "\n\n-# {} passed, {} warnings, {} failed, {} skipped",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail),
+            self.count(CheckStatus::Skip),
+        );
+        out
+    }
+}
+
+/// Runs every check that needs no gateway connection.
+pub async fn run(config: &Config) -> CheckReport {
+    let mut report = CheckReport::default();
+    check_config(&mut report, config);
+    check_database(&mut report, config).await;
+    check_discord(&mut report, config).await;
+    report
+}
+
+/// Checks settings the configuration loader accepts but that fail later.
+pub fn check_config(report: &mut CheckReport, config: &Config) {
+    let features = &config.features;
+    let mut enabled = Vec::new();
+    for (name, on) in [
+        ("discord_bot", features.discord_bot),
+        ("voice_tracking", features.voice_tracking),
+        ("voice_event_sourcing", features.voice_event_sourcing),
+        ("member_events", features.member_events),
+        ("feed_publisher", features.feed_publisher),
+        ("web_dashboard", features.web_dashboard),
+        ("web_api", features.web_api),
+    ] {
+        if on {
+            enabled.push(name);
+        }
+    }
+    report.push(
+        "config",
+        CheckStatus::Pass,
+        format!(
+            "Loaded v{}, features: {}",
+            config.version,
+            enabled.join(", ")
+        ),
+    );
+
+    if features.discord_bot {
+        match config.admin_id.parse::<u64>() {
+            Ok(_) => report.push("admin_id", CheckStatus::Pass, "Valid user ID"),
+            Err(_) => report.push(
+                "admin_id",
+                CheckStatus::Fail,
+                format!("ADMIN_ID '{}' is not a valid user ID", config.admin_id),
+            ),
+        }
+    }
+
+    for (name, path) in [
+        ("data_path", &config.data_path),
+        ("logs_path", &config.logs_path),
+    ] {
+        match check_writable(path) {
+            Ok(()) => report.push(
+                name,
+                CheckStatus::Pass,
+                format!("{} is writable", path.display()),
+            ),
+            Err(e) => report.push(
+                name,
+                CheckStatus::Fail,
+                format!("{} is not writable: {e}", path.display()),
+            ),
+        }
+    }
+
+    if features.web_dashboard || features.web_api || config.mal.is_some() {
+        match config.web.bind_addr.parse::<SocketAddr>() {
+            Ok(_) => report.push("web_bind_addr", CheckStatus::Pass, &config.web.bind_addr),
+            Err(_) => report.push(
+                "web_bind_addr",
+                CheckStatus::Fail,
+                format!(
+                    "WEB_BIND_ADDR '{}' is not an address and port",
+                    config.web.bind_addr
+                ),
+            ),
+        }
+        let public_url = &config.web.public_url;
+        if !public_url.starts_with("http://") && !public_url.starts_with("https://") {
+            report.push(
+                "web_public_url",
+                CheckStatus::Fail,
+                format!("WEB_PUBLIC_URL '{public_url}' must start with http:// or https://"),
+            );
+        } else if public_url.contains("localhost") {
+            report.push(
+                "web_public_url",
+                CheckStatus::Warn,
+                format!("{public_url} is only reachable from this machine"),
+            );
+        } else {
+            report.push("web_public_url", CheckStatus::Pass, public_url);
+        }
+    }
+}
+
+/// Checks that the database accepts connections and reports pending migrations.
+pub async fn check_database(report: &mut CheckReport, config: &Config) {
+    let pending = match PgRepos::new(&config.db_url).await {
+        Ok(repos) => repos.count_pending_migrations().await,
+        Err(e) => Err(e),
+    };
+    match pending {
+        Ok(0) => report.push(
+            "database",
+            CheckStatus::Pass,
+            "Connected, schema is up to date",
+        ),
+        Ok(pending) => report.push(
+            "database",
+            CheckStatus::Pass,
+            format!("Connected, {pending} migrations will run at startup"),
+        ),
+        Err(e) => report.push(
+            "database",
+            CheckStatus::Fail,
+            format!("Cannot connect: {e}"),
+        ),
+    }
+}
+
+/// Checks every bot token and the privileged intents its application allows.
+pub async fn check_discord(report: &mut CheckReport, config: &Config) {
+    if !config.features.discord_bot {
+        report.push("discord", CheckStatus::Skip, "Discord bot is disabled");
+        return;
+    }
+
+    for profile in config.bot_profiles() {
+        let name = format!("discord:{}", profile.name);
+        let token = match Token::from_str(&profile.token) {
+            Ok(token) => token,
+            Err(e) => {
+                report.push(name, CheckStatus::Fail, format!("Invalid token: {e}"));
+                continue;
+            }
+        };
+        let http = Http::new(token);
+        let info = match http.get_current_application_info().await {
+            Ok(info) => info,
+            Err(e) => {
+                report.push(name, CheckStatus::Fail, format!("Token was rejected: {e}"));
+                continue;
+            }
+        };
+
+        if let Some(application_id) = profile.application_id
+            && application_id != info.id.get()
+        {
+            report.push(
+                name,
+                CheckStatus::Fail,
+                format!(
+                    "Application ID {application_id} does not match the token's application {}",
+                    info.id
+                ),
+            );
+            continue;
+        }
+
+        let missing = missing_intents(
+            Bot::gateway_intents(config, &profile),
+            info.flags.unwrap_or_default(),
+        );
+        if missing.is_empty() {
+            report.push(
+                name,
+                CheckStatus::Pass,
+                format!("Authenticated as application {} ({})", info.name, info.id),
+            );
+        } else {
+            report.push(
+                name,
+                CheckStatus::Fail,
+                format!(
+                    "Enable the {} intent for {} in the Discord developer portal",
+                    missing.join(" and "),
+                    info.name
+                ),
+            );
+        }
+    }
+}
+
+/// Returns the privileged intents requested but not enabled for the application.
+fn missing_intents(intents: GatewayIntents, flags: ApplicationFlags) -> Vec<&'static str> {
+    let mut missing = Vec::new();
+    if intents.contains(GatewayIntents::MESSAGE_CONTENT)
+        && !flags.intersects(
+            ApplicationFlags::GATEWAY_MESSAGE_CONTENT
+                | ApplicationFlags::GATEWAY_MESSAGE_CONTENT_LIMITED,
+        )
+    {
+        missing.push("Message Content");
+    }
+    if intents.contains(GatewayIntents::GUILD_MEMBERS)
+        && !flags.intersects(
+            ApplicationFlags::GATEWAY_GUILD_MEMBERS
+                | ApplicationFlags::GATEWAY_GUILD_MEMBERS_LIMITED,
+        )
+    {
+        missing.push("Server Members");
+    }
+    missing
+}
+
+/// Writes and removes a file in `dir`.
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(".pwr-bot-check");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_fails_only_on_failures() {
+        let mut report = CheckReport::default();
+        report.push("config", CheckStatus::Pass, "Loaded");
+        report.push("web_public_url", CheckStatus::Warn, "localhost");
+        assert!(report.passed());
+        assert!(
+            report
+                .render_text()
+                .ends_with("PASS\tsummary\t1 passed, 1 warnings, 0 failed, 0 skipped\n")
+        );
+
+        report.push("database", CheckStatus::Fail, "Cannot connect");
+        assert!(!report.passed());
+        assert!(
+            report
+                .render_text()
+                .contains("FAIL\tdatabase\tCannot connect\n")
+        );
+    }
+
+    #[test]
+    fn missing_intents_accepts_limited_flags() {
+        let intents = GatewayIntents::MESSAGE_CONTENT | GatewayIntents::GUILD_MEMBERS;
+
+        assert_eq!(
+            missing_intents(intents, ApplicationFlags::GATEWAY_MESSAGE_CONTENT_LIMITED),
+            vec!["Server Members"]
+        );
+        assert!(
+            missing_intents(GatewayIntents::non_privileged(), ApplicationFlags::empty()).is_empty()
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Command line interface.
+//!
+//! `pwr-bot` with no subcommand runs the bot. The other subcommands are maintenance
+//! operations that work on the database through the services, without connecting to
+//! Discord, so they also work while the bot is stopped.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::Utc;
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
+use log::info;
+use log::warn;
+
+use crate::entity::SubscriberType;
+use crate::feed::opml;
+use crate::feed::opml::OpmlFeed;
+use crate::service::Services;
+use crate::service::feed_subscription::SubscribeResult;
+use crate::service::feed_subscription::SubscriberTarget;
+use crate::task::data_pruning::DataPruningTask;
+
+/// Subscriptions fetched per page when exporting.
+const EXPORT_PAGE_SIZE: u32 = 100;
+
+/// Discord bot with feed subscriptions and voice channel tracking.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// Validate the configuration, database and Discord credentials, then exit.
+    #[arg(long)]
+    pub check: bool,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    /// Run the bot. The default without a subcommand.
+    Run,
+    /// Apply pending database migrations, then exit.
+    Migrate,
+    /// Write feeds, feed items, subscribers and subscriptions to a JSON file.
+    Backup {
+        /// File to write. Defaults to `backup-<timestamp>.json` in `DATA_PATH`.
+        output: Option<PathBuf>,
+    },
+    /// Write the feeds a server or user subscribes to as an OPML file.
+    Export {
+        #[command(flatten)]
+        target: TargetArgs,
+        /// File to write.
+        output: PathBuf,
+    },
+    /// Subscribe a server or user to every feed of an OPML file.
+    ImportOpml {
+        #[command(flatten)]
+        target: TargetArgs,
+        /// OPML file to read.
+        file: PathBuf,
+    },
+    /// Delete feed items and voice sessions past their retention window.
+    Prune,
+}
+
+/// The subscriber a command works on: a server or a user's DMs.
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct TargetArgs {
+    /// Server ID.
+    #[arg(long)]
+    pub guild: Option<u64>,
+    /// User ID, for DM subscriptions.
+    #[arg(long)]
+    pub user: Option<u64>,
+}
+
+impl TargetArgs {
+    fn target(&self) -> SubscriberTarget {
+        match (self.guild, self.user) {
+            (Some(guild_id), _) => SubscriberTarget {
+                subscriber_type: SubscriberType::Guild,
+                target_id: guild_id.to_string(),
+            },
+            (None, user_id) => SubscriberTarget {
+                subscriber_type: SubscriberType::Dm,
+                target_id: user_id.unwrap_or_default().to_string(),
+            },
+        }
+    }
+}
+
+/// Writes a database dump as JSON. Returns the file written.
+pub async fn backup(services: &Services, output: &Path) -> Result<PathBuf> {
+    let output = if output.is_dir() {
+        output.join(format!(
+            "backup-{}.json",
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ))
+    } else {
+        output.to_path_buf()
+    };
+    let dump = services.internal.dump_database().await?;
+    std::fs::write(&output, serde_json::to_vec_pretty(&dump)?)?;
+    info!(
+        "Backed up {} feeds, {} feed items, {} subscribers and {} subscriptions to {}",
+        dump.feeds.len(),
+        dump.feed_items.len(),
+        dump.subscribers.len(),
+        dump.subscriptions.len(),
+        output.display()
+    );
+    Ok(output)
+}
+
+/// Writes a subscriber's feeds as OPML. Returns the number of feeds written.
+pub async fn export(services: &Services, target: &TargetArgs, output: &Path) -> Result<usize> {
+    let feeds = &services.feed_subscription;
+    let subscriber = feeds.get_or_create_subscriber(&target.target()).await?;
+
+    let mut outlines = Vec::new();
+    for page in 1.. {
+        let subscriptions = feeds
+            .list_paginated_subscriptions(&subscriber, page, EXPORT_PAGE_SIZE)
+            .await?;
+        let last = (subscriptions.len() as u32) < EXPORT_PAGE_SIZE;
+        outlines.extend(subscriptions.into_iter().map(|subscription| OpmlFeed {
+            title: subscription.feed.name,
+            url: subscription.feed.source_url,
+        }));
+        if last {
+            break;
+        }
+    }
+
+    std::fs::write(output, opml::write("pwr-bot subscriptions", &outlines))?;
+    info!(
+        "Exported {} feeds of {} to {}",
+        outlines.len(),
+        subscriber.target_id,
+        output.display()
+    );
+    Ok(outlines.len())
+}
+
+/// Subscribes a subscriber to every feed of an OPML file. A feed that fails is logged
+/// and skipped. Returns the number of new subscriptions.
+pub async fn import_opml(services: &Services, target: &TargetArgs, file: &Path) -> Result<u32> {
+    let feeds = &services.feed_subscription;
+    let urls = opml::parse_urls(&std::fs::read_to_string(file)?);
+    let subscriber = feeds.get_or_create_subscriber(&target.target()).await?;
+
+    let (mut subscribed, mut already, mut failed) = (0u32, 0u32, 0u32);
+    for url in &urls {
+        match feeds.subscribe(url, &subscriber).await {
+            Ok(SubscribeResult::Success { .. }) => subscribed += 1,
+            Ok(SubscribeResult::AlreadySubscribed { .. }) => already += 1,
+            Err(e) => {
+                warn!("Failed to subscribe to {url}: {e}");
+                failed += 1;
+            }
+        }
+    }
+
+    info!(
+        "Imported {} feeds for {}: {subscribed} subscribed, {already} already subscribed, {failed} failed",
+        urls.len(),
+        subscriber.target_id
+    );
+    Ok(subscribed)
+}
+
+/// Prunes data past its retention window once, like the daily pruning task.
+pub async fn prune(services: &Services) {
+    DataPruningTask::new(
+        services.feed_subscription.clone(),
+        services.voice_tracking.clone(),
+    )
+    .run(Utc::now())
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn cli_parses_subcommands() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::parse_from(["pwr-bot", "import-opml", "--guild", "42", "feeds.opml"]);
+        let Some(CliCommand::ImportOpml { target, file }) = cli.command else {
+            panic!("expected import-opml");
+        };
+        assert_eq!(target.target().target_id, "42");
+        assert_eq!(file, PathBuf::from("feeds.opml"));
+
+        assert!(Cli::try_parse_from(["pwr-bot", "export", "out.opml"]).is_err());
+        assert!(Cli::parse_from(["pwr-bot", "--check"]).check);
+    }
+}
@@ -1,4 +1,5 @@
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::io::Write;
 use std::ops::Deref;
@@ -8,6 +9,7 @@ use byteorder::WriteBytesExt;
 use chrono::DateTime;
 use chrono::SubsecRound;
 use chrono::Utc;
+use chrono_tz::Tz;
 use diesel::backend::Backend;
 use diesel::deserialize::FromSql;
 use diesel::deserialize::FromSqlRow;
@@ -17,14 +19,37 @@ use diesel::serialize::IsNull;
 use diesel::serialize::ToSql;
 use diesel::sql_types::*;
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
+use serde::Serializer;
+use serde_json::Value;
 
+use crate::repo::schema::anilist_links;
+use crate::repo::schema::api_tokens;
 use crate::repo::schema::bot_meta;
+use crate::repo::schema::channel_weights;
+use crate::repo::schema::custom_json_feeds;
+use crate::repo::schema::dashboard_tokens;
+use crate::repo::schema::delivery_outbox;
+use crate::repo::schema::emoji_stats;
+use crate::repo::schema::feed_collections;
 use crate::repo::schema::feed_items;
 use crate::repo::schema::feed_subscriptions;
 use crate::repo::schema::feeds;
+use crate::repo::schema::invite_uses;
+use crate::repo::schema::leaderboard_snapshots;
+use crate::repo::schema::mal_links;
+use crate::repo::schema::notification_log;
+use crate::repo::schema::pending_subscriptions;
 use crate::repo::schema::server_settings;
+use crate::repo::schema::settings_audit;
 use crate::repo::schema::subscribers;
+use crate::repo::schema::tags;
+use crate::repo::schema::temp_voice_channels;
+use crate::repo::schema::voice_account_merges;
+use crate::repo::schema::voice_adjustments;
+use crate::repo::schema::voice_events;
+use crate::repo::schema::voice_session_flags;
 use crate::repo::schema::voice_sessions;
 
 // =============================================================================
@@ -130,7 +155,17 @@ impl<T: for<'de> Deserialize<'de>> FromSql<Jsonb, diesel::pg::Pg> for Json<T> {
 
 /// Notification target type for feed updates.
 #[derive(
-    Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, AsExpression, FromSqlRow,
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    AsExpression,
+    FromSqlRow,
 )]
 #[diesel(sql_type = Text)]
 #[serde(rename_all = "lowercase")]
@@ -170,6 +205,216 @@ where
     }
 }
 
+/// Why a voice session was flagged for review.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, AsExpression, FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceFlagReason {
+    /// The session ran continuously for an improbably long time.
+    #[default]
+    LongSession,
+    /// Several accounts joined and left at the same moments.
+    SyncedSessions,
+}
+
+impl VoiceFlagReason {
+    /// Returns the stored name of this reason.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LongSession => "long_session",
+            Self::SyncedSessions => "synced_sessions",
+        }
+    }
+}
+
+impl<B> ToSql<Text, B> for VoiceFlagReason
+where
+    B: Backend,
+    str: ToSql<Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        <str as ToSql<Text, B>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl<B> FromSql<Text, B> for VoiceFlagReason
+where
+    B: Backend,
+    String: FromSql<Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match <String as FromSql<Text, B>>::from_sql(bytes)?.as_str() {
+            "long_session" => Ok(VoiceFlagReason::LongSession),
+            "synced_sessions" => Ok(VoiceFlagReason::SyncedSessions),
+            other => Err(format!("unknown voice flag reason: {other}").into()),
+        }
+    }
+}
+
+/// What happened in a recorded voice event.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, AsExpression, FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceEventKind {
+    /// The member joined a voice channel.
+    #[default]
+    Join,
+    /// The member left voice.
+    Leave,
+    /// The member moved from one voice channel to another.
+    Move,
+    /// The member's mute or deafen state changed without changing channel.
+    Mute,
+}
+
+impl VoiceEventKind {
+    /// Returns the stored name of this kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Join => "join",
+            Self::Leave => "leave",
+            Self::Move => "move",
+            Self::Mute => "mute",
+        }
+    }
+}
+
+impl<B> ToSql<Text, B> for VoiceEventKind
+where
+    B: Backend,
+    str: ToSql<Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        <str as ToSql<Text, B>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl<B> FromSql<Text, B> for VoiceEventKind
+where
+    B: Backend,
+    String: FromSql<Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match <String as FromSql<Text, B>>::from_sql(bytes)?.as_str() {
+            "join" => Ok(VoiceEventKind::Join),
+            "leave" => Ok(VoiceEventKind::Leave),
+            "move" => Ok(VoiceEventKind::Move),
+            "mute" => Ok(VoiceEventKind::Mute),
+            other => Err(format!("unknown voice event kind: {other}").into()),
+        }
+    }
+}
+
+/// Whether an emoji stat counts a custom emoji or a sticker.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, AsExpression, FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "snake_case")]
+pub enum EmojiKind {
+    #[default]
+    Emoji,
+    Sticker,
+}
+
+impl EmojiKind {
+    /// Returns the stored name of this kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Emoji => "emoji",
+            Self::Sticker => "sticker",
+        }
+    }
+}
+
+impl<B> ToSql<Text, B> for EmojiKind
+where
+    B: Backend,
+    str: ToSql<Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        <str as ToSql<Text, B>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl<B> FromSql<Text, B> for EmojiKind
+where
+    B: Backend,
+    String: FromSql<Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match <String as FromSql<Text, B>>::from_sql(bytes)?.as_str() {
+            "emoji" => Ok(EmojiKind::Emoji),
+            "sticker" => Ok(EmojiKind::Sticker),
+            other => Err(format!("unknown emoji kind: {other}").into()),
+        }
+    }
+}
+
+/// How often the feed publisher polls a feed.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, AsExpression, FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "snake_case")]
+pub enum PollTier {
+    /// Released recently. Polled at the normal interval.
+    #[default]
+    Active,
+    /// Released nothing recently. Polled at a multiple of the normal interval.
+    Dormant,
+}
+
+impl PollTier {
+    /// Returns the stored name of this tier.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Dormant => "dormant",
+        }
+    }
+}
+
+impl<B> ToSql<Text, B> for PollTier
+where
+    B: Backend,
+    str: ToSql<Text, B>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, B>,
+    ) -> diesel::serialize::Result {
+        <str as ToSql<Text, B>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl<B> FromSql<Text, B> for PollTier
+where
+    B: Backend,
+    String: FromSql<Text, B>,
+{
+    fn from_sql(bytes: B::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match <String as FromSql<Text, B>>::from_sql(bytes)?.as_str() {
+            "active" => Ok(PollTier::Active),
+            "dormant" => Ok(PollTier::Dormant),
+            other => Err(format!("unknown poll tier: {other}").into()),
+        }
+    }
+}
+
 // =============================================================================
 // Table models
 // =============================================================================
@@ -189,6 +434,40 @@ pub struct FeedEntity {
     pub source_url: String,
     pub cover_url: String,
     pub tags: String,
+    /// Kept up to date by the feed publisher from the feed's latest release.
+    #[serde(default)]
+    pub poll_tier: PollTier,
+    /// Overrides the platform's accent color in notifications, as `0xRRGGBB`.
+    #[serde(default)]
+    pub accent_color: Option<i32>,
+    /// Overrides the platform's emoji in notifications.
+    #[serde(default)]
+    pub emoji: Option<String>,
+    /// English title, on platforms that list one.
+    #[serde(default)]
+    pub title_english: Option<String>,
+    /// Original title in Latin script, on platforms that list one.
+    #[serde(default)]
+    pub title_romanized: Option<String>,
+    /// Original title in its own script, on platforms that list one.
+    #[serde(default)]
+    pub title_native: Option<String>,
+}
+
+impl FeedEntity {
+    /// Returns the feed's title in `language`, falling back to its name when the
+    /// platform does not list that title.
+    pub fn title(&self, language: TitleLanguage) -> &str {
+        let variant = match language {
+            TitleLanguage::Default => None,
+            TitleLanguage::English => self.title_english.as_deref(),
+            TitleLanguage::Romanized => self.title_romanized.as_deref(),
+            TitleLanguage::Native => self.title_native.as_deref(),
+        };
+        variant
+            .filter(|title| !title.is_empty())
+            .unwrap_or(&self.name)
+    }
 }
 
 /// A specific version or episode of a feed.
@@ -201,6 +480,18 @@ pub struct FeedItemEntity {
     pub feed_id: i32,
     pub description: String,
     pub published: DateTime<Utc>,
+    /// Hash of the item's title and content. `None` for items stored before edits were tracked.
+    pub content_hash: Option<String>,
+    /// Direct link to the item's media, e.g., a podcast episode's audio file.
+    pub media_url: Option<String>,
+    /// Length of the item's media in seconds.
+    pub duration_secs: Option<i32>,
+    /// The platform's own ID of the item, see [`crate::feed::FeedItem::id`].
+    pub source_item_id: Option<String>,
+    /// Link to the item itself. Notifications link to the feed source when `None`.
+    pub item_url: Option<String>,
+    /// Preview image of the item, shown instead of the feed cover.
+    pub image_url: Option<String>,
 }
 
 /// A notification target that can receive feed updates.
@@ -213,6 +504,88 @@ pub struct SubscriberEntity {
     #[diesel(column_name = type_)]
     pub r#type: SubscriberType,
     pub target_id: String,
+    /// Owner-granted subscription cap. `None` uses the configured default.
+    pub max_subscriptions: Option<i32>,
+    /// What the subscriber wants to be notified of.
+    pub preferences: Json<SubscriberPreferences>,
+    /// Whether the subscriber is yet to get the onboarding message of its first DM
+    /// subscription.
+    #[serde(default)]
+    pub first_seen: bool,
+}
+
+/// A subscriber's notification preferences per platform.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct SubscriberPreferences {
+    /// Preferences keyed by platform ID. Platforms without an entry notify everything.
+    #[serde(default)]
+    pub platforms: BTreeMap<String, PlatformPreferences>,
+    /// Whether DMs skip updates that a server the user is in already receives.
+    #[serde(default)]
+    pub skip_server_duplicates: bool,
+    /// Which title of a feed is shown in notifications, search and autocomplete.
+    #[serde(default)]
+    pub title_language: TitleLanguage,
+}
+
+impl SubscriberPreferences {
+    /// Returns the preferences for a platform.
+    pub fn platform(&self, platform_id: &str) -> PlatformPreferences {
+        self.platforms.get(platform_id).cloned().unwrap_or_default()
+    }
+
+    /// Sets the preferences for a platform, dropping the entry once it is back to the
+    /// defaults.
+    pub fn set_platform(&mut self, platform_id: &str, preferences: PlatformPreferences) {
+        if preferences == PlatformPreferences::default() {
+            self.platforms.remove(platform_id);
+        } else {
+            self.platforms.insert(platform_id.to_string(), preferences);
+        }
+    }
+
+    /// Whether an item of a platform should be notified.
+    pub fn allows(&self, platform_id: &str, item_title: &str) -> bool {
+        self.platforms
+            .get(platform_id)
+            .is_none_or(|preferences| preferences.allows(item_title))
+    }
+}
+
+/// What a subscriber wants to be notified of from one platform.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct PlatformPreferences {
+    /// Whether nothing from the platform is notified.
+    #[serde(default)]
+    pub muted: bool,
+    /// Items whose title contains one of these words, ignoring case, are not notified.
+    #[serde(default)]
+    pub excluded_words: Vec<String>,
+}
+
+impl PlatformPreferences {
+    /// Parses comma-separated words, dropping blanks and duplicates.
+    pub fn parse_words(input: &str) -> Vec<String> {
+        let mut words: Vec<String> = Vec::new();
+        for word in input.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+            if !words.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+                words.push(word.to_string());
+            }
+        }
+        words
+    }
+
+    /// Whether an item with this title should be notified.
+    pub fn allows(&self, item_title: &str) -> bool {
+        if self.muted {
+            return false;
+        }
+        let title = item_title.to_lowercase();
+        !self
+            .excluded_words
+            .iter()
+            .any(|word| title.contains(&word.to_lowercase()))
+    }
 }
 
 /// Links subscribers to the feeds they're monitoring.
@@ -224,6 +597,118 @@ pub struct FeedSubscriptionEntity {
     pub id: i32,
     pub feed_id: i32,
     pub subscriber_id: i32,
+    /// Paused subscriptions are kept but receive no notifications.
+    pub paused: bool,
+    /// Whether edits to already-announced items are also notified.
+    pub notify_edits: bool,
+    /// Discord user ID of the member who subscribed the server. `None` for DM
+    /// subscriptions and server subscriptions added before this was recorded.
+    #[serde(default)]
+    pub added_by: Option<String>,
+    /// Server collection the subscription is posted in, instead of the server's feed channel.
+    #[serde(default)]
+    pub collection_id: Option<i32>,
+}
+
+/// A named group of a server's subscriptions, posted in its own channel.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = feed_collections)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct FeedCollectionEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub name: String,
+    pub channel_id: DbU64,
+    /// Role mentioned in each notification of the collection.
+    pub mention_role_id: Option<DbU64>,
+}
+
+/// A feed notification sent to a subscriber, or attempted.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = notification_log)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct NotificationLogEntity {
+    pub id: i32,
+    pub subscriber_id: i32,
+    pub feed_id: i32,
+    pub sent_at: DateTime<Utc>,
+    /// Whether the notification reached the subscriber.
+    pub delivered: bool,
+    /// Seconds from the item's publication to the notification. `None` for edits.
+    pub delay_secs: Option<i32>,
+}
+
+/// A feed notification about to be sent to a subscriber. Removed once it was sent or
+/// failed, so the rows left after a run stopped are the notifications it never sent.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = delivery_outbox)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct DeliveryOutboxEntity {
+    pub id: i32,
+    pub subscriber_id: i32,
+    /// The feed update, serialized by the subscriber sending it.
+    pub data: Json<serde_json::Value>,
+    pub queued_at: DateTime<Utc>,
+}
+
+/// Number of notifications a subscriber received from one feed.
+#[derive(QueryableByName, Clone, Debug, PartialEq, Eq)]
+pub struct FeedNotificationCountRow {
+    #[diesel(sql_type = Integer)]
+    pub feed_id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Text)]
+    pub source_url: String,
+    #[diesel(sql_type = BigInt)]
+    pub notifications: i64,
+}
+
+/// Notifications sent on one day, in UTC.
+#[derive(QueryableByName, Clone, Debug, PartialEq, Eq)]
+pub struct DailyDeliveryRow {
+    #[diesel(sql_type = diesel::sql_types::Date)]
+    pub day: chrono::NaiveDate,
+    #[diesel(sql_type = BigInt)]
+    pub delivered: i64,
+    #[diesel(sql_type = BigInt)]
+    pub failed: i64,
+}
+
+/// Notifications sent to one type of subscriber.
+#[derive(QueryableByName, Clone, Debug, PartialEq, Eq)]
+pub struct SubscriberTypeDeliveryRow {
+    #[diesel(sql_type = Text)]
+    pub subscriber_type: SubscriberType,
+    #[diesel(sql_type = BigInt)]
+    pub delivered: i64,
+    #[diesel(sql_type = BigInt)]
+    pub failed: i64,
+}
+
+impl SubscriberTypeDeliveryRow {
+    /// Share of the notifications that failed, from 0 to 1.
+    pub fn failure_rate(&self) -> f64 {
+        let total = self.delivered + self.failed;
+        if total == 0 {
+            return 0.0;
+        }
+        self.failed as f64 / total as f64
+    }
+}
+
+/// How long one platform's notifications took to arrive after publication.
+#[derive(QueryableByName, Clone, Debug, PartialEq)]
+pub struct PlatformDelayRow {
+    #[diesel(sql_type = Text)]
+    pub platform_id: String,
+    #[diesel(sql_type = Double)]
+    pub median_delay_secs: f64,
+    #[diesel(sql_type = BigInt)]
+    pub deliveries: i64,
 }
 
 #[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
@@ -236,45 +721,831 @@ pub struct ServerSettingsEntity {
     pub settings: Json<ServerSettings>,
 }
 
+/// A single settings change, recorded whenever server settings are saved.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = settings_audit)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
-pub struct ServerSettings {
-    #[serde(default)]
-    pub feeds: FeedsSettings,
-    #[serde(default)]
-    pub voice: VoiceSettings,
-    #[serde(default)]
-    pub welcome: WelcomeSettings,
+pub struct SettingsAuditEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    /// User who made the change. `None` when the bot changed it itself.
+    pub changed_by: Option<DbU64>,
+    /// Dotted path of the changed setting, e.g. `feeds.channel_id`.
+    pub key: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Access token granting read-only web dashboard access to a single guild.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = dashboard_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct DashboardTokenEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub token: String,
+    /// Admin who generated the token.
+    pub created_by: Option<DbU64>,
+    pub created_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
-pub struct WelcomeSettings {
-    #[serde(default)]
-    pub enabled: Option<bool>,
-    #[serde(default)]
-    pub channel_id: Option<String>,
-    #[serde(default)]
-    pub primary_color: Option<String>,
-    #[serde(default)]
-    pub template_id: Option<String>,
-    #[serde(default)]
-    pub messages: Option<Vec<String>>,
-}
+/// Bearer token granting REST API access to one guild, or to all guilds for the owner.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = api_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ApiTokenEntity {
+    pub id: i32,
+    /// Guild the token is scoped to. `None` for the bot owner's token.
+    pub guild_id: Option<DbU64>,
+    /// Hex-encoded SHA-256 of the token. The token itself is only shown when generated.
+    pub token_hash: String,
+    pub created_by: Option<DbU64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Admin-defined text response invoked with `/tag show`.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = tags)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct TagEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    /// Lowercase name, unique per guild.
+    pub name: String,
+    /// Markdown content, may contain `{{ variable }}` placeholders.
+    pub content: String,
+    /// Whether the content is sent inside a card.
+    pub embed: bool,
+    pub uses: i32,
+    /// Admin who created the tag.
+    pub created_by: Option<DbU64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How often a guild's custom emoji or sticker was used.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = emoji_stats)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct EmojiStatEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    /// ID of the custom emoji or sticker, unique per guild.
+    pub emoji_id: DbU64,
+    pub kind: EmojiKind,
+    /// Name when last used.
+    pub name: String,
+    pub animated: bool,
+    /// Messages the emoji or sticker was sent in.
+    pub message_uses: i32,
+    /// Reactions added with the emoji.
+    pub reaction_uses: i32,
+    pub last_used_at: DateTime<Utc>,
+}
+
+impl EmojiStatEntity {
+    /// Uses in messages and reactions combined.
+    pub fn total_uses(&self) -> i64 {
+        i64::from(self.message_uses) + i64::from(self.reaction_uses)
+    }
+
+    /// Returns the CDN url of the emoji or sticker's image.
+    pub fn image_url(&self) -> String {
+        match self.kind {
+            EmojiKind::Emoji => format!("https://cdn.discordapp.com/emojis/{}.png", *self.emoji_id),
+            EmojiKind::Sticker => {
+                format!(
+                    "https://media.discordapp.net/stickers/{}.png",
+                    *self.emoji_id
+                )
+            }
+        }
+    }
+}
+
+/// A member joining a guild, with the invite they used when it could be told.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = invite_uses)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct InviteUseEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub member_id: DbU64,
+    /// Creator of the invite. `None` when no invite could be matched, e.g. for vanity URL
+    /// joins.
+    pub inviter_id: Option<DbU64>,
+    pub invite_code: Option<String>,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Voice channel the bot created for a member who joined a guild's hub channel.
+///
+/// Deleted along with the channel once it is empty.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = temp_voice_channels)]
+#[diesel(primary_key(channel_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct TempVoiceChannelEntity {
+    pub channel_id: DbU64,
+    pub guild_id: DbU64,
+    /// Member allowed to lock, rename and limit the channel.
+    pub owner_id: DbU64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A member's request to subscribe their server to a feed, waiting for an admin to
+/// approve or deny it.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = pending_subscriptions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct PendingSubscriptionEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub source_url: String,
+    /// Member notified once the request is approved or denied.
+    pub requested_by: DbU64,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// Owner-defined feed reading items from a JSON API through JSONPath expressions.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = custom_json_feeds)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct CustomJsonFeedEntity {
+    pub id: i32,
+    /// Name shown as the feed's title.
+    pub name: String,
+    /// Endpoint polled for items. Users subscribe with this url.
+    pub url: String,
+    /// JSONPath selecting the items in the response, e.g. `$.data[*]`.
+    pub items_path: String,
+    /// JSONPath of an item's unique ID, relative to the item.
+    pub id_path: String,
+    /// JSONPath of an item's title, relative to the item.
+    pub title_path: String,
+    /// JSONPath of an item's link, relative to the item.
+    pub url_path: Option<String>,
+    /// JSONPath of an item's publish time, relative to the item. Items are taken in
+    /// response order when `None`.
+    pub published_path: Option<String>,
+    pub created_by: Option<DbU64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// AniList account a Discord user linked to sync their lists into subscriptions.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = anilist_links)]
+#[diesel(primary_key(user_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct AniListLinkEntity {
+    pub user_id: DbU64,
+    pub anilist_user_id: i32,
+    /// AniList username as AniList spells it.
+    pub username: String,
+    /// Whether the periodic sync keeps the user's subscriptions up to date.
+    pub auto_sync: bool,
+    /// AniList media IDs the sync subscribed the user to. Only these are unsubscribed
+    /// when they leave the list.
+    pub synced_media_ids: Json<Vec<i32>>,
+    /// AniList media IDs the user unsubscribed from after a sync. They are not
+    /// subscribed again while they stay on the list.
+    pub ignored_media_ids: Json<Vec<i32>>,
+    /// MangaDex feed URLs of the synced and ignored manga, by AniList media ID. AniList
+    /// has no manga feeds, so reading list entries are matched to MangaDex.
+    pub synced_manga_urls: Json<BTreeMap<i32, String>>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// MyAnimeList account a Discord user linked through OAuth to import their lists.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = mal_links)]
+#[diesel(primary_key(user_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct MalLinkEntity {
+    pub user_id: DbU64,
+    pub mal_user_id: i32,
+    /// MyAnimeList username as MyAnimeList spells it.
+    pub username: String,
+    pub access_token: String,
+    /// Exchanged for a new access token once `token_expires_at` passes.
+    pub refresh_token: String,
+    pub token_expires_at: DateTime<Utc>,
+    pub last_imported_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Current version of the stored [`ServerSettings`] document.
+pub const SERVER_SETTINGS_VERSION: u32 = 1;
+
+/// Upgrades a stored settings document by one version. Index `n` upgrades version `n` to `n + 1`.
+///
+/// Append a step whenever a settings field is renamed, moved, or changes type. Adding or
+/// removing an optional field needs no step since missing fields default and unknown fields
+/// are ignored.
+const SERVER_SETTINGS_MIGRATIONS: [fn(&mut Value); SERVER_SETTINGS_VERSION as usize] = [
+    // 0 → 1: documents written before versioning; only the version stamp is added
+    |_| {},
+];
+
+/// Per-guild configuration stored as a JSON document.
+///
+/// Older documents are migrated to [`SERVER_SETTINGS_VERSION`] when deserialized, so every
+/// read sees the current shape. The upgraded document is written back on the next save.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(remote = "Self")]
+pub struct ServerSettings {
+    /// Version of the stored document.
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub general: GeneralSettings,
+    #[serde(default)]
+    pub feeds: FeedsSettings,
+    #[serde(default)]
+    pub voice: VoiceSettings,
+    #[serde(default)]
+    pub welcome: WelcomeSettings,
+}
+
+impl ServerSettings {
+    /// Applies every pending migration step to a raw settings document.
+    pub fn migrate(value: &mut Value) {
+        let version = value
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as usize;
+        if version >= SERVER_SETTINGS_VERSION as usize {
+            return;
+        }
+        for step in SERVER_SETTINGS_MIGRATIONS.iter().skip(version) {
+            step(value);
+        }
+        if let Value::Object(map) = value {
+            map.insert(
+                "schema_version".to_string(),
+                Value::from(SERVER_SETTINGS_VERSION),
+            );
+        }
+    }
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: SERVER_SETTINGS_VERSION,
+            general: GeneralSettings::default(),
+            feeds: FeedsSettings::default(),
+            voice: VoiceSettings::default(),
+            welcome: WelcomeSettings::default(),
+        }
+    }
+}
+
+impl Serialize for ServerSettings {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ServerSettings::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ServerSettings {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        ServerSettings::migrate(&mut value);
+        ServerSettings::deserialize(value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Default prefix for text commands.
+pub const DEFAULT_PREFIX: &str = "!";
+
+/// Default locale for dates and numbers.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Longest accepted text command prefix, in characters.
+pub const MAX_PREFIX_LEN: usize = 5;
+
+/// Server-wide preferences shared by every feature.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct GeneralSettings {
+    /// IANA timezone name, e.g. `Europe/Berlin`. Decides where days start for daily stats.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// BCP 47 locale tag, e.g. `en-US`.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Prefix for text commands.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Whether the web dashboard link of this server can be opened.
+    #[serde(default)]
+    pub dashboard_enabled: Option<bool>,
+    /// Names of the top-level commands turned off in this server, e.g. `vc`.
+    #[serde(default)]
+    pub disabled_commands: Option<Vec<String>>,
+}
+
+impl GeneralSettings {
+    /// The server's timezone (default: UTC). Unknown names fall back to UTC.
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(Self::parse_timezone)
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// Parses an IANA timezone name, ignoring case.
+    pub fn parse_timezone(name: &str) -> Option<Tz> {
+        Tz::from_str_insensitive(name.trim()).ok()
+    }
+
+    /// The server's locale (default: [`DEFAULT_LOCALE`]).
+    pub fn locale(&self) -> &str {
+        self.locale.as_deref().unwrap_or(DEFAULT_LOCALE)
+    }
+
+    /// The text command prefix (default: [`DEFAULT_PREFIX`]).
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or(DEFAULT_PREFIX)
+    }
+
+    /// Whether a prefix is non-empty, has no whitespace and fits [`MAX_PREFIX_LEN`].
+    pub fn is_valid_prefix(prefix: &str) -> bool {
+        !prefix.is_empty()
+            && prefix.chars().count() <= MAX_PREFIX_LEN
+            && !prefix.chars().any(char::is_whitespace)
+    }
+
+    /// Whether the web dashboard link can be opened (default: true).
+    pub fn is_dashboard_enabled(&self) -> bool {
+        self.dashboard_enabled.unwrap_or(true)
+    }
+
+    /// Whether the top-level command `name` can be used (default: true).
+    pub fn is_command_enabled(&self, name: &str) -> bool {
+        !self
+            .disabled_commands
+            .as_ref()
+            .is_some_and(|disabled| disabled.iter().any(|command| command == name))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct WelcomeSettings {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub primary_color: Option<String>,
+    #[serde(default)]
+    pub template_id: Option<String>,
+    #[serde(default)]
+    pub messages: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct FeedsSettings {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub subscribe_role_id: Option<String>,
+    #[serde(default)]
+    pub unsubscribe_role_id: Option<String>,
+    /// Whether members can only unsubscribe the server from feeds they subscribed it to.
+    /// Members with "Manage Server" can unsubscribe it from any.
+    #[serde(default)]
+    pub unsubscribe_own_only: Option<bool>,
+    /// Whether subscriptions by members without the subscribe role wait for an admin's
+    /// approval instead of failing.
+    #[serde(default)]
+    pub require_approval: Option<bool>,
+    #[serde(default)]
+    pub notification_style: Option<NotificationStyle>,
+    #[serde(default)]
+    pub hide_cover: Option<bool>,
+    #[serde(default)]
+    pub suppress_embeds: Option<bool>,
+    /// Set when a notification could not be delivered, cleared by the next successful one.
+    #[serde(default)]
+    pub delivery_failure: Option<DeliveryFailure>,
+    /// Days feed items are kept for this server. `None` or 0 uses the bot's default.
+    #[serde(default)]
+    pub item_retention_days: Option<u32>,
+    /// Whether a pinned release calendar is kept in the feed channel.
+    #[serde(default)]
+    pub release_calendar: Option<CalendarMode>,
+    /// Minutes from a click on "Schedule watch party" to the event's start. `None` hides
+    /// the button.
+    #[serde(default)]
+    pub watch_party_delay_mins: Option<u32>,
+    /// Whether a server event is kept for the next episode of each followed AniList anime.
+    #[serde(default)]
+    pub airing_events: Option<bool>,
+}
+
+impl FeedsSettings {
+    /// Days feed items are kept for this server, 0 for forever. The server can only
+    /// shorten `default_days`.
+    pub fn item_retention_days(&self, default_days: u32) -> u32 {
+        retention_days(self.item_retention_days, default_days)
+    }
+
+    /// The server's release calendar mode (default: [`CalendarMode::Off`]).
+    pub fn calendar_mode(&self) -> CalendarMode {
+        self.release_calendar.unwrap_or_default()
+    }
+
+    /// Whether airing anime get server events (default: off).
+    pub fn has_airing_events(&self) -> bool {
+        self.airing_events.unwrap_or(false)
+    }
+
+    /// Whether members can only remove subscriptions they added (default: off).
+    pub fn is_unsubscribe_own_only(&self) -> bool {
+        self.unsubscribe_own_only.unwrap_or(false)
+    }
+
+    /// Whether members without the subscribe role request subscriptions instead
+    /// (default: off).
+    pub fn is_approval_required(&self) -> bool {
+        self.require_approval.unwrap_or(false)
+    }
+}
+
+/// Resolves a server's retention override against the bot's default, in days. 0 keeps
+/// data forever. Overrides longer than the default are capped at it.
+pub fn retention_days(server_days: Option<u32>, default_days: u32) -> u32 {
+    match server_days.filter(|days| *days > 0) {
+        Some(days) if default_days == 0 => days,
+        Some(days) => days.min(default_days),
+        None => default_days,
+    }
+}
+
+/// A feed notification that could not be delivered to the server's channel.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DeliveryFailure {
+    pub reason: DeliveryFailureReason,
+    /// Channel the notification was meant for, if one was configured.
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Why a feed notification could not be delivered.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryFailureReason {
+    /// No feed channel is configured.
+    NoChannel,
+    /// The configured channel was deleted or is not visible to the bot.
+    UnknownChannel,
+    /// The bot lacks permissions to post in the configured channel.
+    MissingPermissions,
+}
+
+impl DeliveryFailureReason {
+    /// Classifies a failed Discord request by its HTTP status.
+    ///
+    /// Returns `None` for errors that are likely transient and need no action.
+    pub fn from_status(status: u16) -> Option<Self> {
+        match status {
+            403 => Some(Self::MissingPermissions),
+            404 => Some(Self::UnknownChannel),
+            _ => None,
+        }
+    }
+
+    /// Explains what an admin needs to fix.
+    pub fn advice(&self) -> &'static str {
+        match self {
+            Self::NoChannel => {
+                "No feed channel is set. Pick one in `/feed settings` so updates have somewhere to go."
+            }
+            Self::UnknownChannel => {
+                "The feed channel no longer exists or is hidden from the bot. Pick another one in `/feed settings`."
+            }
+            Self::MissingPermissions => {
+                "The bot can't post in the feed channel. Grant it View Channel, Send Messages and Embed Links there, or pick another channel in `/feed settings`. `/diagnose` lists what is missing."
+            }
+        }
+    }
+}
+
+/// How feed notifications are rendered in a server channel.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationStyle {
+    /// Full card with description, previous item, and cover art.
+    #[default]
+    Rich,
+    /// Single line with the feed name, new item, and source link.
+    Compact,
+}
+
+impl NotificationStyle {
+    /// All available styles, in display order.
+    pub const ALL: [NotificationStyle; 2] = [NotificationStyle::Rich, NotificationStyle::Compact];
+
+    /// Returns the user-facing name of this style.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rich => "Rich",
+            Self::Compact => "Compact",
+        }
+    }
+
+    /// Returns the style matching a user-facing name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.name() == name)
+    }
+}
+
+/// Whether a server keeps a pinned calendar of upcoming releases in its feed channel.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarMode {
+    /// No calendar is kept.
+    #[default]
+    Off,
+    /// The calendar is kept next to the notification of each new item.
+    Alongside,
+    /// The calendar replaces the notifications of new items.
+    Instead,
+}
+
+impl CalendarMode {
+    /// All available modes, in display order.
+    pub const ALL: [CalendarMode; 3] = [
+        CalendarMode::Off,
+        CalendarMode::Alongside,
+        CalendarMode::Instead,
+    ];
+
+    /// Returns the user-facing name of this mode.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Alongside => "Calendar and notifications",
+            Self::Instead => "Calendar only",
+        }
+    }
+
+    /// Returns the mode matching a user-facing name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// Whether a calendar is kept.
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::Off
+    }
+
+    /// Whether each new item is still notified.
+    pub fn notifies_items(&self) -> bool {
+        *self != Self::Instead
+    }
+}
+
+/// Which title of a feed is shown to a subscriber.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleLanguage {
+    /// The title the platform lists first, stored as the feed's name.
+    #[default]
+    Default,
+    /// The English title, e.g. "Attack on Titan".
+    English,
+    /// The original title in Latin script, e.g. "Shingeki no Kyojin".
+    Romanized,
+    /// The original title in its own script, e.g. "進撃の巨人".
+    Native,
+}
+
+impl TitleLanguage {
+    /// All available languages, in display order.
+    pub const ALL: [TitleLanguage; 4] = [
+        TitleLanguage::Default,
+        TitleLanguage::English,
+        TitleLanguage::Romanized,
+        TitleLanguage::Native,
+    ];
+
+    /// Returns the user-facing name of this language.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::English => "English",
+            Self::Romanized => "Romanized",
+            Self::Native => "Native",
+        }
+    }
+
+    /// Returns the language matching a user-facing name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lang| lang.name() == name)
+    }
+}
+
+/// A custom emoji or sticker seen in a message or reaction, counted into [`EmojiStatEntity`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmojiUse {
+    pub emoji_id: u64,
+    pub kind: EmojiKind,
+    pub name: String,
+    pub animated: bool,
+    /// Whether it was added as a reaction rather than sent in a message.
+    pub reaction: bool,
+}
+
+/// The invite a new member joined through, found by comparing invite use counts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsedInvite {
+    pub code: String,
+    pub inviter_id: Option<u64>,
+}
+
+/// An upcoming release of a feed on a server's release calendar.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpcomingRelease {
+    pub feed_name: String,
+    pub source_url: String,
+    /// Name of the item expected, e.g. "Episode 12", if known.
+    pub item_name: Option<String>,
+    pub expected_at: DateTime<Utc>,
+    /// Whether `expected_at` was guessed from how often the feed released so far, rather
+    /// than announced by the platform.
+    pub estimated: bool,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceSettings {
+    pub enabled: Option<bool>,
+    /// Sessions shorter than this many seconds are discarded instead of recorded.
+    #[serde(default)]
+    pub min_session_secs: Option<u32>,
+    #[serde(default)]
+    pub track_bots: Option<bool>,
+    #[serde(default)]
+    pub track_stage_channels: Option<bool>,
+    /// Per-channel time multipliers in percent, keyed by channel ID. Channels without an
+    /// entry count at 100%.
+    #[serde(default)]
+    pub channel_weights: Option<BTreeMap<String, u32>>,
+    /// Collective voice hours the server aims for each month. `None` or 0 sets no goal.
+    #[serde(default)]
+    pub monthly_goal_hours: Option<u32>,
+    /// Channel the goal announcement is posted in.
+    #[serde(default)]
+    pub goal_channel_id: Option<String>,
+    /// Minutes a member may sit alone in a channel before the leaderboard discounts the
+    /// rest of that time. `None` or 0 turns the discount off.
+    #[serde(default)]
+    pub idle_alone_mins: Option<u32>,
+    /// Percent of discounted idle-alone time that still counts (default: 0, excluded).
+    #[serde(default)]
+    pub idle_alone_percent: Option<u32>,
+    /// Whether this server's voice time counts on the bot owner's global leaderboard.
+    #[serde(default)]
+    pub global_leaderboard: Option<bool>,
+    /// Hides this server's name, and the names of its members, on the global leaderboard.
+    #[serde(default)]
+    pub global_leaderboard_anonymous: Option<bool>,
+    /// Days ended voice sessions are kept. `None` or 0 uses the bot's default.
+    #[serde(default)]
+    pub session_retention_days: Option<u32>,
+    /// Hub channel. Joining it creates a temporary channel owned by the member.
+    #[serde(default)]
+    pub temp_hub_channel_id: Option<String>,
+    /// Member limit of new temporary channels. `None` or 0 sets no limit.
+    #[serde(default)]
+    pub temp_user_limit: Option<u32>,
+    /// Minutes a member may sit self-deafened and alone before being moved to the AFK
+    /// channel. `None` or 0 turns the AFK mover off.
+    #[serde(default)]
+    pub afk_move_mins: Option<u32>,
+}
+
+impl VoiceSettings {
+    /// Whether voice tracking is enabled (default: true).
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    /// Minimum recorded session length in seconds (default: 0).
+    pub fn min_session_secs(&self) -> u32 {
+        self.min_session_secs.unwrap_or(0)
+    }
+
+    /// Whether bot accounts are tracked (default: false).
+    pub fn tracks_bots(&self) -> bool {
+        self.track_bots.unwrap_or(false)
+    }
+
+    /// Whether time spent in stage channels is tracked (default: true).
+    pub fn tracks_stage_channels(&self) -> bool {
+        self.track_stage_channels.unwrap_or(true)
+    }
+
+    /// Time multiplier for a channel in percent (default: 100).
+    pub fn channel_weight(&self, channel_id: u64) -> u32 {
+        self.channel_weights
+            .as_ref()
+            .and_then(|weights| weights.get(&channel_id.to_string()).copied())
+            .unwrap_or(100)
+    }
+
+    /// Sets a channel's time multiplier in percent. 100% removes the entry.
+    pub fn set_channel_weight(&mut self, channel_id: u64, weight_percent: u32) {
+        let weights = self.channel_weights.get_or_insert_default();
+        if weight_percent == 100 {
+            weights.remove(&channel_id.to_string());
+        } else {
+            weights.insert(channel_id.to_string(), weight_percent);
+        }
+        if weights.is_empty() {
+            self.channel_weights = None;
+        }
+    }
+
+    /// The monthly goal in hours, if one is set.
+    pub fn monthly_goal_hours(&self) -> Option<u32> {
+        self.monthly_goal_hours.filter(|hours| *hours > 0)
+    }
+
+    /// The leaderboard discount for time spent alone in a channel, if one is set.
+    pub fn idle_discount(&self) -> Option<IdleAloneDiscount> {
+        let mins = self.idle_alone_mins.filter(|mins| *mins > 0)?;
+        Some(IdleAloneDiscount {
+            after_secs: mins * 60,
+            weight_percent: self.idle_alone_percent.unwrap_or(0).min(100),
+        })
+    }
+
+    /// Whether the server opted in to the global leaderboard (default: false).
+    pub fn joins_global_leaderboard(&self) -> bool {
+        self.global_leaderboard.unwrap_or(false)
+    }
+
+    /// Whether the server is anonymous on the global leaderboard (default: false).
+    pub fn is_global_leaderboard_anonymous(&self) -> bool {
+        self.global_leaderboard_anonymous.unwrap_or(false)
+    }
 
-#[derive(Serialize, Deserialize, Default, Clone, Debug)]
-pub struct FeedsSettings {
-    #[serde(default)]
-    pub enabled: Option<bool>,
-    #[serde(default)]
-    pub channel_id: Option<String>,
-    #[serde(default)]
-    pub subscribe_role_id: Option<String>,
-    #[serde(default)]
-    pub unsubscribe_role_id: Option<String>,
+    /// Days ended voice sessions are kept, 0 for forever. The server can only shorten
+    /// `default_days`.
+    pub fn session_retention_days(&self, default_days: u32) -> u32 {
+        retention_days(self.session_retention_days, default_days)
+    }
+
+    /// The hub channel that creates temporary channels, if one is set.
+    pub fn temp_hub_channel_id(&self) -> Option<u64> {
+        self.temp_hub_channel_id.as_deref()?.parse().ok()
+    }
+
+    /// Member limit of new temporary channels, if one is set.
+    pub fn temp_user_limit(&self) -> Option<u32> {
+        self.temp_user_limit.filter(|limit| *limit > 0)
+    }
+
+    /// Seconds a member may sit self-deafened and alone before the AFK mover moves them,
+    /// if it is on.
+    pub fn afk_move_secs(&self) -> Option<u32> {
+        self.afk_move_mins
+            .filter(|mins| *mins > 0)
+            .map(|mins| mins * 60)
+    }
+
+    /// Whether a voice state with these properties should be tracked.
+    pub fn tracks(&self, is_bot: bool, is_stage: bool) -> bool {
+        self.is_enabled()
+            && (!is_bot || self.tracks_bots())
+            && (!is_stage || self.tracks_stage_channels())
+    }
 }
 
-#[derive(Serialize, Deserialize, Default, Clone, Debug)]
-pub struct VoiceSettings {
-    pub enabled: Option<bool>,
+/// Discount applied on the leaderboard to time a member spends alone in a channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IdleAloneDiscount {
+    /// Seconds alone that count in full before the discount starts.
+    pub after_secs: u32,
+    /// Percent of the time alone past `after_secs` that still counts.
+    pub weight_percent: u32,
 }
 
 /// Diesel-compatible struct for voice_sessions queries.
@@ -289,6 +1560,7 @@ pub struct DbVoiceSession {
     pub join_time: DateTime<Utc>,
     pub leave_time: DateTime<Utc>,
     pub is_active: bool,
+    pub parent_session_id: Option<i32>,
 }
 
 /// Diesel-compatible struct for inserting/updating voice sessions.
@@ -301,6 +1573,135 @@ pub struct NewDbVoiceSession {
     pub join_time: DateTime<Utc>,
     pub leave_time: DateTime<Utc>,
     pub is_active: bool,
+    pub parent_session_id: Option<i32>,
+}
+
+/// A server's progress toward its monthly voice goal.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceGoalProgress {
+    /// Month the progress is for, as `YYYY-MM` in the server's timezone.
+    pub month: String,
+    pub goal_seconds: i64,
+    /// Voice time spent in the server this month.
+    pub total_seconds: i64,
+}
+
+impl VoiceGoalProgress {
+    /// Share of the goal reached, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.goal_seconds <= 0 {
+            return 0.0;
+        }
+        (self.total_seconds as f64 / self.goal_seconds as f64).clamp(0.0, 1.0)
+    }
+
+    /// Whether the goal was reached.
+    pub fn is_reached(&self) -> bool {
+        self.total_seconds >= self.goal_seconds
+    }
+}
+
+/// Leaderboard time multiplier for a voice channel, mirrored from [`VoiceSettings`].
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = channel_weights)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct ChannelWeightEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub channel_id: DbU64,
+    /// Multiplier in percent, e.g. `150` counts time at 1.5×.
+    pub weight_percent: i32,
+}
+
+/// A user's all-time leaderboard standing at the time a weekly snapshot was taken.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = leaderboard_snapshots)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardSnapshotEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub user_id: DbU64,
+    pub rank: i32,
+    pub total_duration: i64,
+    pub taken_at: DateTime<Utc>,
+}
+
+/// A voice session flagged by the suspicious activity check, awaiting admin review.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = voice_session_flags)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceSessionFlagEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub session_id: i32,
+    pub user_id: DbU64,
+    pub reason: VoiceFlagReason,
+    /// Other accounts involved, for [`VoiceFlagReason::SyncedSessions`].
+    pub related_count: i32,
+    /// Set when an admin reviewed the session and kept it.
+    pub dismissed: bool,
+    pub flagged_at: DateTime<Utc>,
+}
+
+/// Audit record of voice data moved from one user ID to another, e.g. after an account change.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = voice_account_merges)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceAccountMergeEntity {
+    pub id: i32,
+    pub from_user_id: DbU64,
+    pub to_user_id: DbU64,
+    /// Bot owner who ran the merge.
+    pub merged_by: DbU64,
+    pub sessions_moved: i32,
+    pub adjustments_moved: i32,
+    pub flags_moved: i32,
+    pub snapshots_moved: i32,
+    pub merged_at: DateTime<Utc>,
+}
+
+/// Voice time an admin added (positive) or removed (negative) for a member, counted
+/// alongside their sessions without touching them.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = voice_adjustments)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceAdjustmentEntity {
+    pub id: i32,
+    pub guild_id: DbU64,
+    pub user_id: DbU64,
+    pub seconds: i64,
+    pub reason: Option<String>,
+    pub adjusted_by: DbU64,
+    /// When the adjusted time counts, for time-ranged stats.
+    pub applies_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A raw voice state change, kept unchanged so sessions can be derived from it again.
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset)]
+#[diesel(table_name = voice_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceEventEntity {
+    pub id: i64,
+    pub guild_id: DbU64,
+    pub user_id: DbU64,
+    pub kind: VoiceEventKind,
+    /// Channel the member is in after the event, `None` after leaving.
+    pub channel_id: Option<DbU64>,
+    /// Channel the member was in before a leave or move.
+    pub previous_channel_id: Option<DbU64>,
+    pub muted: bool,
+    pub deafened: bool,
+    pub is_bot: bool,
+    /// Whether `channel_id` is a stage channel.
+    pub is_stage: bool,
+    pub occurred_at: DateTime<Utc>,
 }
 
 /// Domain entity for voice channel sessions.
@@ -313,9 +1714,19 @@ pub struct VoiceSessionsEntity {
     pub join_time: DateTime<Utc>,
     pub leave_time: DateTime<Utc>,
     pub is_active: bool,
+    /// First split of the session this one continues after a channel move. `None` for
+    /// the first split, so a logical session is its first split and all splits pointing
+    /// to it.
+    pub parent_session_id: Option<i32>,
 }
 
 impl VoiceSessionsEntity {
+    /// ID of the first split of this session, identifying the logical session across
+    /// channel moves.
+    pub fn logical_id(&self) -> i32 {
+        self.parent_session_id.unwrap_or(self.id)
+    }
+
     pub fn to_insertable(&self) -> NewDbVoiceSession {
         NewDbVoiceSession {
             user_id: self.user_id.into(),
@@ -324,6 +1735,7 @@ impl VoiceSessionsEntity {
             join_time: self.join_time.trunc_subsecs(6),
             leave_time: self.leave_time.trunc_subsecs(6),
             is_active: self.is_active,
+            parent_session_id: self.parent_session_id,
         }
     }
 }
@@ -338,6 +1750,7 @@ impl From<DbVoiceSession> for VoiceSessionsEntity {
             join_time: db.join_time,
             leave_time: db.leave_time,
             is_active: db.is_active,
+            parent_session_id: db.parent_session_id,
         }
     }
 }
@@ -366,6 +1779,165 @@ impl From<VoiceLeaderboardRow> for VoiceLeaderboardEntry {
     }
 }
 
+/// A member's invites on a guild's invite leaderboard.
+#[derive(Serialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct InviteLeaderboardEntry {
+    pub inviter_id: u64,
+    /// Distinct members who joined through the inviter's invites.
+    pub invites: i64,
+}
+
+#[derive(QueryableByName)]
+#[diesel(table_name = invite_uses)]
+pub struct InviteLeaderboardRow {
+    #[diesel(sql_type = BigInt)]
+    pub inviter_id: DbU64,
+    #[diesel(sql_type = BigInt)]
+    pub invites: i64,
+}
+
+impl From<InviteLeaderboardRow> for InviteLeaderboardEntry {
+    fn from(row: InviteLeaderboardRow) -> Self {
+        Self {
+            inviter_id: row.inviter_id.into(),
+            invites: row.invites,
+        }
+    }
+}
+
+/// A member's voice time across every server on the global leaderboard.
+#[derive(Serialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct GlobalVoiceLeaderboardEntry {
+    pub user_id: u64,
+    pub total_duration: i64,
+    /// Server the member spent the most time in.
+    pub top_guild_id: u64,
+    pub guild_count: i32,
+    /// Whether the member only counts in servers that show names.
+    pub named: bool,
+}
+
+#[derive(QueryableByName)]
+#[diesel(table_name = voice_sessions)]
+pub struct GlobalVoiceLeaderboardRow {
+    #[diesel(sql_type = BigInt)]
+    pub user_id: DbU64,
+    #[diesel(sql_type = BigInt)]
+    pub total_duration: i64,
+    #[diesel(sql_type = BigInt)]
+    pub top_guild_id: DbU64,
+    #[diesel(sql_type = Integer)]
+    pub guild_count: i32,
+    #[diesel(sql_type = Bool)]
+    pub named: bool,
+}
+
+impl From<GlobalVoiceLeaderboardRow> for GlobalVoiceLeaderboardEntry {
+    fn from(row: GlobalVoiceLeaderboardRow) -> Self {
+        Self {
+            user_id: row.user_id.into(),
+            total_duration: row.total_duration,
+            top_guild_id: row.top_guild_id.into(),
+            guild_count: row.guild_count,
+            named: row.named,
+        }
+    }
+}
+
+/// Voice time spent in one channel.
+#[derive(Serialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceChannelUsage {
+    pub channel_id: u64,
+    pub total_duration: i64,
+    /// Distinct users who spent time in the channel.
+    pub user_count: i64,
+}
+
+#[derive(QueryableByName)]
+#[diesel(table_name = voice_sessions)]
+pub struct VoiceChannelUsageRow {
+    #[diesel(sql_type = BigInt)]
+    pub channel_id: DbU64,
+    #[diesel(sql_type = BigInt)]
+    pub total_duration: i64,
+    #[diesel(sql_type = BigInt)]
+    pub user_count: i64,
+}
+
+impl From<VoiceChannelUsageRow> for VoiceChannelUsage {
+    fn from(row: VoiceChannelUsageRow) -> Self {
+        Self {
+            channel_id: row.channel_id.into(),
+            total_duration: row.total_duration,
+            user_count: row.user_count,
+        }
+    }
+}
+
+/// An open flag joined with the session it points at.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceSessionFlag {
+    pub id: i32,
+    pub session_id: i32,
+    pub user_id: u64,
+    pub channel_id: u64,
+    pub reason: VoiceFlagReason,
+    pub related_count: i32,
+    pub join_time: DateTime<Utc>,
+    /// Leave time of the last split, or now for a session still in progress.
+    pub end_time: DateTime<Utc>,
+    pub is_active: bool,
+    pub flagged_at: DateTime<Utc>,
+}
+
+impl VoiceSessionFlag {
+    /// Length of the flagged session in seconds.
+    pub fn duration_secs(&self) -> i64 {
+        (self.end_time - self.join_time).num_seconds()
+    }
+}
+
+#[derive(QueryableByName)]
+pub struct VoiceSessionFlagRow {
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Integer)]
+    pub session_id: i32,
+    #[diesel(sql_type = BigInt)]
+    pub user_id: DbU64,
+    #[diesel(sql_type = BigInt)]
+    pub channel_id: DbU64,
+    #[diesel(sql_type = Text)]
+    pub reason: VoiceFlagReason,
+    #[diesel(sql_type = Integer)]
+    pub related_count: i32,
+    #[diesel(sql_type = Timestamptz)]
+    pub join_time: DateTime<Utc>,
+    #[diesel(sql_type = Timestamptz)]
+    pub end_time: DateTime<Utc>,
+    #[diesel(sql_type = Bool)]
+    pub is_active: bool,
+    #[diesel(sql_type = Timestamptz)]
+    pub flagged_at: DateTime<Utc>,
+}
+
+impl From<VoiceSessionFlagRow> for VoiceSessionFlag {
+    fn from(row: VoiceSessionFlagRow) -> Self {
+        Self {
+            id: row.id,
+            session_id: row.session_id,
+            user_id: row.user_id.into(),
+            channel_id: row.channel_id.into(),
+            reason: row.reason,
+            related_count: row.related_count,
+            join_time: row.join_time,
+            end_time: row.end_time,
+            is_active: row.is_active,
+            flagged_at: row.flagged_at,
+        }
+    }
+}
+
 #[derive(QueryableByName)]
 pub struct FeedWithLatestItemRow {
     #[diesel(sql_type = Integer)]
@@ -386,6 +1958,26 @@ pub struct FeedWithLatestItemRow {
     pub cover_url: String,
     #[diesel(sql_type = Text)]
     pub tags: String,
+    #[diesel(sql_type = Text)]
+    pub poll_tier: PollTier,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub accent_color: Option<i32>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub emoji: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub title_english: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub title_romanized: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub title_native: Option<String>,
+    #[diesel(sql_type = Bool)]
+    pub paused: bool,
+    #[diesel(sql_type = Bool)]
+    pub notify_edits: bool,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub added_by: Option<String>,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub collection_id: Option<i32>,
 
     #[diesel(sql_type = Nullable<Integer>)]
     pub item_id: Option<i32>,
@@ -409,6 +2001,9 @@ pub struct VoiceLeaderboardOpt {
     pub since: Option<DateTime<Utc>>,
     #[builder(default)]
     pub until: Option<DateTime<Utc>>,
+    /// Discounts time spent alone in a channel; `None` counts it in full.
+    #[builder(default)]
+    pub idle_discount: Option<IdleAloneDiscount>,
 }
 
 /// Daily voice activity aggregation for a specific user.
@@ -443,6 +2038,17 @@ pub struct BotMetaEntity {
 pub enum BotMetaKey {
     VoiceHeartbeat,
     BotVersion,
+    /// Version whose commands an extra bot last registered, keyed by bot name.
+    ExtraBotVersion(String),
+    LeaderboardSnapshot,
+    /// Month a guild's voice goal was last announced for, keyed by guild ID.
+    VoiceGoalAnnounced(u64),
+    /// ID of the last voice event applied to voice sessions.
+    VoiceEventsProjected,
+    /// Channel and message of a guild's pinned release calendar, keyed by guild ID.
+    ReleaseCalendarMessage(u64),
+    /// When the daily voice totals were last recomputed from the sessions.
+    VoiceTotalsReconciled,
 }
 
 impl From<&BotMetaKey> for String {
@@ -450,6 +2056,12 @@ impl From<&BotMetaKey> for String {
         match value {
             BotMetaKey::VoiceHeartbeat => "voice_heartbeat".to_string(),
             BotMetaKey::BotVersion => "bot_version".to_string(),
+            BotMetaKey::ExtraBotVersion(name) => format!("bot_version:{name}"),
+            BotMetaKey::LeaderboardSnapshot => "leaderboard_snapshot".to_string(),
+            BotMetaKey::VoiceGoalAnnounced(guild_id) => format!("voice_goal:{guild_id}"),
+            BotMetaKey::VoiceEventsProjected => "voice_events_projected".to_string(),
+            BotMetaKey::ReleaseCalendarMessage(guild_id) => format!("release_calendar:{guild_id}"),
+            BotMetaKey::VoiceTotalsReconciled => "voice_totals_reconciled".to_string(),
         }
     }
 }
@@ -459,3 +2071,253 @@ impl From<BotMetaKey> for String {
         String::from(&value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_preferences_parse_words() {
+        let words = PlatformPreferences::parse_words(" Trailer, PV,, trailer ,recap ");
+
+        assert_eq!(words, vec!["Trailer", "PV", "recap"]);
+    }
+
+    #[test]
+    fn feed_title_falls_back_to_name() {
+        let feed = FeedEntity {
+            name: "Shingeki no Kyojin".to_string(),
+            title_english: Some("Attack on Titan".to_string()),
+            title_native: Some(String::new()),
+            ..Default::default()
+        };
+
+        assert_eq!(feed.title(TitleLanguage::Default), "Shingeki no Kyojin");
+        assert_eq!(feed.title(TitleLanguage::English), "Attack on Titan");
+        assert_eq!(feed.title(TitleLanguage::Romanized), "Shingeki no Kyojin");
+        assert_eq!(feed.title(TitleLanguage::Native), "Shingeki no Kyojin");
+    }
+
+    #[test]
+    fn server_settings_migrates_unversioned_document() {
+        let stored = r#"{"feeds":{"channel_id":"123"},"voice":{"enabled":false}}"#;
+
+        let settings: ServerSettings = serde_json::from_str(stored).unwrap();
+
+        assert_eq!(settings.schema_version, SERVER_SETTINGS_VERSION);
+        assert_eq!(settings.feeds.channel_id.as_deref(), Some("123"));
+        assert_eq!(settings.voice.enabled, Some(false));
+    }
+
+    #[test]
+    fn server_settings_ignores_removed_fields() {
+        let stored = r#"{"schema_version":1,"feeds":{"legacy_flag":true},"legacy":{}}"#;
+
+        let settings: ServerSettings = serde_json::from_str(stored).unwrap();
+
+        assert_eq!(settings.feeds, FeedsSettings::default());
+    }
+
+    #[test]
+    fn server_settings_round_trips_current_version() {
+        let mut settings = ServerSettings::default();
+        settings.welcome.messages = Some(vec!["hi".to_string()]);
+
+        let json = serde_json::to_value(&settings).unwrap();
+        assert_eq!(json["schema_version"], SERVER_SETTINGS_VERSION);
+
+        let read: ServerSettings = serde_json::from_value(json).unwrap();
+        assert_eq!(read, settings);
+    }
+
+    #[test]
+    fn server_settings_keeps_newer_version() {
+        let mut value = serde_json::json!({ "schema_version": SERVER_SETTINGS_VERSION + 1 });
+
+        ServerSettings::migrate(&mut value);
+
+        assert_eq!(value["schema_version"], SERVER_SETTINGS_VERSION + 1);
+    }
+
+    #[test]
+    fn general_settings_defaults() {
+        let general = GeneralSettings::default();
+
+        assert_eq!(general.timezone(), Tz::UTC);
+        assert_eq!(general.locale(), DEFAULT_LOCALE);
+        assert_eq!(general.prefix(), DEFAULT_PREFIX);
+        assert!(general.is_dashboard_enabled());
+    }
+
+    #[test]
+    fn general_settings_parses_timezones() {
+        let general = GeneralSettings {
+            timezone: Some("asia/jakarta".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(general.timezone(), Tz::Asia__Jakarta);
+
+        let general = GeneralSettings {
+            timezone: Some("Mars/Olympus".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(general.timezone(), Tz::UTC);
+    }
+
+    #[test]
+    fn general_settings_validates_prefixes() {
+        assert!(GeneralSettings::is_valid_prefix("?"));
+        assert!(GeneralSettings::is_valid_prefix("pwr!"));
+        assert!(!GeneralSettings::is_valid_prefix(""));
+        assert!(!GeneralSettings::is_valid_prefix("a b"));
+        assert!(!GeneralSettings::is_valid_prefix("toolong"));
+    }
+
+    #[test]
+    fn general_settings_enables_commands_by_default() {
+        let general = GeneralSettings {
+            disabled_commands: Some(vec!["vc".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(GeneralSettings::default().is_command_enabled("vc"));
+        assert!(!general.is_command_enabled("vc"));
+        assert!(general.is_command_enabled("feed"));
+    }
+
+    #[test]
+    fn voice_settings_defaults_track_humans_in_all_channels() {
+        let voice = VoiceSettings::default();
+
+        assert!(voice.tracks(false, false));
+        assert!(voice.tracks(false, true));
+        assert!(!voice.tracks(true, false));
+        assert_eq!(voice.min_session_secs(), 0);
+    }
+
+    #[test]
+    fn voice_settings_respects_bot_and_stage_options() {
+        let voice = VoiceSettings {
+            track_bots: Some(true),
+            track_stage_channels: Some(false),
+            ..Default::default()
+        };
+
+        assert!(voice.tracks(true, false));
+        assert!(!voice.tracks(false, true));
+    }
+
+    #[test]
+    fn voice_settings_disabled_tracks_nothing() {
+        let voice = VoiceSettings {
+            enabled: Some(false),
+            ..Default::default()
+        };
+
+        assert!(!voice.tracks(false, false));
+    }
+
+    #[test]
+    fn voice_settings_channel_weight_defaults_and_resets() {
+        let mut voice = VoiceSettings::default();
+        assert_eq!(voice.channel_weight(1), 100);
+
+        voice.set_channel_weight(1, 150);
+        assert_eq!(voice.channel_weight(1), 150);
+
+        voice.set_channel_weight(1, 100);
+        assert_eq!(voice.channel_weight(1), 100);
+        assert_eq!(voice.channel_weights, None);
+    }
+
+    #[test]
+    fn voice_settings_global_leaderboard_opt_in() {
+        let mut voice = VoiceSettings::default();
+        assert!(!voice.joins_global_leaderboard());
+        assert!(!voice.is_global_leaderboard_anonymous());
+
+        voice.global_leaderboard = Some(true);
+        assert!(voice.joins_global_leaderboard());
+    }
+
+    #[test]
+    fn voice_settings_idle_discount_off_by_default() {
+        let mut voice = VoiceSettings::default();
+        assert_eq!(voice.idle_discount(), None);
+
+        voice.idle_alone_mins = Some(0);
+        assert_eq!(voice.idle_discount(), None);
+
+        voice.idle_alone_mins = Some(30);
+        voice.idle_alone_percent = Some(150);
+        assert_eq!(
+            voice.idle_discount(),
+            Some(IdleAloneDiscount {
+                after_secs: 1800,
+                weight_percent: 100,
+            })
+        );
+    }
+
+    #[test]
+    fn voice_settings_temp_channels_off_by_default() {
+        let mut voice = VoiceSettings::default();
+        assert_eq!(voice.temp_hub_channel_id(), None);
+        assert_eq!(voice.temp_user_limit(), None);
+
+        voice.temp_hub_channel_id = Some("123".to_string());
+        voice.temp_user_limit = Some(0);
+        assert_eq!(voice.temp_hub_channel_id(), Some(123));
+        assert_eq!(voice.temp_user_limit(), None);
+    }
+
+    #[test]
+    fn voice_settings_afk_mover_off_by_default() {
+        let mut voice = VoiceSettings::default();
+        assert_eq!(voice.afk_move_secs(), None);
+
+        voice.afk_move_mins = Some(0);
+        assert_eq!(voice.afk_move_secs(), None);
+        voice.afk_move_mins = Some(15);
+        assert_eq!(voice.afk_move_secs(), Some(900));
+    }
+
+    #[test]
+    fn retention_days_only_shortens_the_default() {
+        assert_eq!(retention_days(None, 90), 90);
+        assert_eq!(retention_days(Some(0), 90), 90);
+        assert_eq!(retention_days(Some(30), 90), 30);
+        assert_eq!(retention_days(Some(365), 90), 90);
+        assert_eq!(retention_days(Some(365), 0), 365);
+        assert_eq!(retention_days(None, 0), 0);
+    }
+
+    #[test]
+    fn voice_goal_progress_clamps_fraction() {
+        let mut progress = VoiceGoalProgress {
+            month: "2026-01".to_string(),
+            goal_seconds: 3600,
+            total_seconds: 1800,
+        };
+        assert_eq!(progress.fraction(), 0.5);
+        assert!(!progress.is_reached());
+
+        progress.total_seconds = 7200;
+        assert_eq!(progress.fraction(), 1.0);
+        assert!(progress.is_reached());
+    }
+
+    #[test]
+    fn delivery_failure_reason_from_status_ignores_transient_errors() {
+        assert_eq!(
+            DeliveryFailureReason::from_status(403),
+            Some(DeliveryFailureReason::MissingPermissions)
+        );
+        assert_eq!(
+            DeliveryFailureReason::from_status(404),
+            Some(DeliveryFailureReason::UnknownChannel)
+        );
+        assert_eq!(DeliveryFailureReason::from_status(500), None);
+        assert_eq!(DeliveryFailureReason::from_status(429), None);
+    }
+}
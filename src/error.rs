@@ -27,6 +27,16 @@ pub enum AppError {
 }
 
 impl AppError {
+    /// What kind of failure this is.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::InternalError | Self::InternalWithRef { .. } => ErrorCategory::Internal,
+            Self::MissingConfig { .. } | Self::ConfigurationError { .. } => {
+                ErrorCategory::Configuration
+            }
+        }
+    }
+
     /// Log details internally, return generic error to user
     pub fn internal_with_ref(msg: impl Debug) -> Self {
         let ref_id = Uuid::new_v4();
@@ -41,6 +51,57 @@ impl AppError {
     }
 }
 
+/// What kind of failure an error is. Decides how it is explained to users and whether
+/// they are asked to report it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// The input was wrong or the request is not possible. Retrying differently helps.
+    User,
+    /// The user or the bot lacks a permission.
+    Permission,
+    /// The server or the bot is not set up for the request.
+    Configuration,
+    /// Discord or a feed platform failed or rejected the request.
+    Upstream,
+    /// A bug or an outage on the bot's end.
+    Internal,
+}
+
+impl ErrorCategory {
+    /// Heading of the error response.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::User => "⚠️ Invalid Request",
+            Self::Permission => "🔒 Missing Permission",
+            Self::Configuration => "⚙️ Not Set Up",
+            Self::Upstream => "🌐 Service Unavailable",
+            Self::Internal => "❌ Internal Error",
+        }
+    }
+
+    /// What the user can do about the error.
+    pub fn next_steps(self) -> &'static str {
+        match self {
+            Self::User => "Check the command's options and try again.",
+            Self::Permission => {
+                "Ask a server admin to grant the missing permission, or run `/diagnose` to see what the bot lacks."
+            }
+            Self::Configuration => {
+                "A server admin can set this up in `/settings`. If it concerns the bot itself, contact the bot owner."
+            }
+            Self::Upstream => "This is usually temporary. Try again in a few minutes.",
+            Self::Internal => {
+                "Try again later. If it keeps happening, report it so the bot owner can look into it."
+            }
+        }
+    }
+
+    /// Whether users are offered to report the error to the bot owner.
+    pub fn is_reportable(self) -> bool {
+        self == Self::Internal
+    }
+}
+
 /// Union of all possible error types in the application.
 pub enum AppErrorKind {
     AppError(AppError),
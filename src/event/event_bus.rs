@@ -4,13 +4,17 @@ use std::any::Any;
 use std::any::TypeId;
 use std::collections::HashMap;
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::RwLock;
 
 use anyhow::Result;
+use futures::FutureExt;
+use tokio::sync::watch;
 
 use crate::subscriber::Subscriber;
+use crate::task::supervisor::TaskMonitor;
 
 type AsyncSubscriber<E> =
     Box<dyn Fn(E) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
@@ -19,6 +23,9 @@ type Subscribers = Arc<RwLock<HashMap<TypeId, Vec<Box<dyn Any + Send + Sync>>>>>
 /// Event bus for publishing events to subscribers.
 pub struct EventBus {
     subscribers: Subscribers,
+    monitor: Arc<TaskMonitor>,
+    /// Number of published events whose subscribers are still running.
+    in_flight: Arc<watch::Sender<usize>>,
 }
 
 impl EventBus {
@@ -26,9 +33,17 @@ impl EventBus {
     pub fn new() -> Self {
         Self {
             subscribers: Arc::new(RwLock::new(HashMap::new())),
+            monitor: Arc::new(TaskMonitor::new()),
+            in_flight: Arc::new(watch::Sender::new(0)),
         }
     }
 
+    /// Counts subscriber panics in this monitor instead of a private one.
+    pub fn with_monitor(mut self, monitor: Arc<TaskMonitor>) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
     /// Registers a callback function for events of type E.
     pub fn register_callback<E, F, Fut>(&self, callback: F) -> &Self
     where
@@ -60,6 +75,8 @@ impl EventBus {
     }
 
     /// Publishes an event to all registered subscribers.
+    ///
+    /// A panicking subscriber is logged and counted, and the others still run.
     pub fn publish<E>(&self, event: E) -> &Self
     where
         E: 'static + Send + Sync + Clone,
@@ -67,18 +84,46 @@ impl EventBus {
         let type_id = TypeId::of::<E>();
         let subs = self.subscribers.read().unwrap();
         if let Some(subs_list) = subs.get(&type_id) {
+            let name = format!(
+                "subscriber:{}",
+                std::any::type_name::<E>()
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default()
+            );
             let mut futures = Vec::new();
             for subs_box in subs_list {
                 if let Some(sub) = subs_box.downcast_ref::<AsyncSubscriber<E>>() {
-                    futures.push(sub(event.clone()));
+                    let fut = AssertUnwindSafe(sub(event.clone())).catch_unwind();
+                    let monitor = self.monitor.clone();
+                    let name = name.clone();
+                    futures.push(async move {
+                        if let Err(panic) = fut.await {
+                            monitor.record_panic(&name, &*panic);
+                        }
+                    });
                 }
             }
+            let in_flight = self.in_flight.clone();
+            in_flight.send_modify(|count| *count += 1);
             tokio::spawn(async move {
                 futures::future::join_all(futures).await;
+                in_flight.send_modify(|count| *count -= 1);
             });
         }
         self
     }
+
+    /// Waits until the subscribers of every event published so far have finished,
+    /// e.g. for notifications to be sent before shutting down.
+    pub async fn wait_idle(&self) {
+        // The sender is never dropped while `self` is borrowed
+        let _ = self
+            .in_flight
+            .subscribe()
+            .wait_for(|count| *count == 0)
+            .await;
+    }
 }
 
 impl Default for EventBus {
@@ -123,4 +168,49 @@ mod tests {
 
         assert_eq!(counter.load(Ordering::SeqCst), 10);
     }
+
+    #[tokio::test]
+    async fn event_bus_counts_panicking_subscribers() {
+        let monitor = Arc::new(TaskMonitor::new());
+        let bus = EventBus::new().with_monitor(monitor.clone());
+        let counter = Arc::new(AtomicI32::new(0));
+        let counter_clone = counter.clone();
+
+        bus.register_callback(|_: TestEvent| async { panic!("malformed event") });
+        bus.register_callback(move |event: TestEvent| {
+            let c = counter_clone.clone();
+            async move {
+                c.fetch_add(event.val, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+
+        bus.publish(TestEvent { val: 10 });
+
+        sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(counter.load(Ordering::SeqCst), 10);
+        assert_eq!(monitor.panics("subscriber:TestEvent"), 1);
+    }
+
+    #[tokio::test]
+    async fn wait_idle_waits_for_running_subscribers() {
+        let bus = EventBus::new();
+        let counter = Arc::new(AtomicI32::new(0));
+        let counter_clone = counter.clone();
+
+        bus.register_callback(move |event: TestEvent| {
+            let c = counter_clone.clone();
+            async move {
+                sleep(Duration::from_millis(50)).await;
+                c.fetch_add(event.val, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+
+        bus.publish(TestEvent { val: 10 });
+        bus.wait_idle().await;
+
+        assert_eq!(counter.load(Ordering::SeqCst), 10);
+    }
 }
@@ -3,11 +3,15 @@
 use std::sync::Arc;
 
 use poise::serenity_prelude::*;
+use semver::Version;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::entity::FeedEntity;
 use crate::entity::FeedItemEntity;
+use crate::entity::FeedsSettings;
+use crate::entity::NotificationStyle;
+use crate::entity::TitleLanguage;
 use crate::event::Event;
 use crate::feed::PlatformInfo;
 
@@ -16,6 +20,14 @@ use crate::feed::PlatformInfo;
 pub struct FeedUpdateEvent {
     pub feed: Arc<FeedEntity>,
     pub data: Arc<FeedUpdateData>,
+    /// IDs of the only subscribers to deliver to, when the update is delivered again
+    /// from the outbox. `None` delivers to every subscriber.
+    #[serde(default)]
+    pub recipients: Option<Arc<Vec<i32>>>,
+    /// Whether the update was made up by `/owner simulate-update`. It is only sent to
+    /// its recipients: not queued in the outbox, logged for stats or streamed.
+    #[serde(default)]
+    pub simulated: bool,
 }
 
 impl FeedUpdateEvent {
@@ -25,6 +37,110 @@ impl FeedUpdateEvent {
         Self {
             feed: data.feed.clone(),
             data,
+            recipients: None,
+            simulated: false,
+        }
+    }
+
+    /// Creates an event delivering an update again, only to the given subscribers.
+    pub fn redelivery(data: FeedUpdateData, recipients: Vec<i32>) -> Self {
+        Self {
+            recipients: Some(Arc::new(recipients)),
+            ..Self::new(data)
+        }
+    }
+
+    /// Creates an event sending a made-up update only to the given subscriber.
+    pub fn simulated(data: FeedUpdateData, recipient: i32) -> Self {
+        Self {
+            recipients: Some(Arc::new(vec![recipient])),
+            simulated: true,
+            ..Self::new(data)
+        }
+    }
+}
+
+/// Rendering options for a feed update message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MessageOptions {
+    pub style: NotificationStyle,
+    pub hide_cover: bool,
+    /// Suppresses link previews of compact messages.
+    pub suppress_embeds: bool,
+    /// Whether episode notifications get a "Schedule watch party" button.
+    pub watch_party: bool,
+    /// Role mentioned above the notification, e.g. a feed collection's role.
+    pub mention_role: Option<RoleId>,
+    /// Which of the feed's titles is shown, e.g. a DM subscriber's preference.
+    pub title_language: TitleLanguage,
+}
+
+impl From<&FeedsSettings> for MessageOptions {
+    fn from(settings: &FeedsSettings) -> Self {
+        Self {
+            style: settings.notification_style.unwrap_or_default(),
+            hide_cover: settings.hide_cover.unwrap_or(false),
+            suppress_embeds: settings.suppress_embeds.unwrap_or(false),
+            watch_party: settings.watch_party_delay_mins.is_some(),
+            mention_role: None,
+            title_language: TitleLanguage::Default,
+        }
+    }
+}
+
+/// Prefix of the custom ID of "Schedule watch party" buttons.
+pub const WATCH_PARTY_PREFIX: &str = "watch_party:";
+
+/// Longest custom ID Discord accepts on a component.
+const MAX_CUSTOM_ID_LEN: usize = 100;
+
+/// The episode a "Schedule watch party" button schedules, encoded in its custom ID.
+///
+/// The button outlives any command view, so its ID carries what the handler needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchPartyId {
+    pub feed_id: i32,
+    /// The episode's title. Cut short when it does not fit the custom ID.
+    pub episode: String,
+}
+
+impl WatchPartyId {
+    /// Encodes the ID as a component custom ID.
+    pub fn custom_id(&self) -> String {
+        let mut id = format!("{WATCH_PARTY_PREFIX}{}:", self.feed_id);
+        let room = MAX_CUSTOM_ID_LEN.saturating_sub(id.chars().count());
+        id.extend(self.episode.chars().take(room));
+        id
+    }
+
+    /// Decodes a custom ID. `None` if it is not a watch party button's.
+    pub fn parse(custom_id: &str) -> Option<Self> {
+        let (feed_id, episode) = custom_id
+            .strip_prefix(WATCH_PARTY_PREFIX)?
+            .split_once(':')?;
+        Some(Self {
+            feed_id: feed_id.parse().ok()?,
+            episode: episode.to_string(),
+        })
+    }
+}
+
+/// Whether a feed update announces a new item or an edit to a known one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedUpdateKind {
+    #[default]
+    New,
+    /// The item was already announced and its content changed at the source.
+    Edited,
+}
+
+impl FeedUpdateKind {
+    /// Label used in notification messages.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::New => "New",
+            Self::Edited => "✏️ Updated",
         }
     }
 }
@@ -35,16 +151,166 @@ pub struct FeedUpdateData {
     pub feed_info: Arc<PlatformInfo>,
     pub old_feed_item: Option<Arc<FeedItemEntity>>,
     pub new_feed_item: Arc<FeedItemEntity>,
+    #[serde(default)]
+    pub kind: FeedUpdateKind,
 }
 
 impl FeedUpdateData {
     /// Creates a Discord message for this feed update.
     pub fn create_message(&self) -> CreateMessage<'static> {
+        self.create_message_with(MessageOptions::default())
+    }
+
+    /// Creates a Discord message for this feed update using the given rendering options.
+    pub fn create_message_with(&self, options: MessageOptions) -> CreateMessage<'static> {
+        let watch_party = options
+            .watch_party
+            .then(|| self.watch_party_row())
+            .flatten();
+        let mention = options.mention_role.map(|role| format!("<@&{role}>"));
+        let title = self.feed.title(options.title_language);
+        let mut message = match options.style {
+            NotificationStyle::Rich => {
+                self.create_rich_message(title, options.hide_cover, watch_party, mention)
+            }
+            NotificationStyle::Compact => self.create_compact_message(title, watch_party, mention),
+        };
+        if let Some(role) = options.mention_role {
+            message = message.allowed_mentions(CreateAllowedMentions::new().roles(vec![role]));
+        }
+
+        // Rich cards are components, which get no link previews to suppress
+        if options.suppress_embeds && options.style == NotificationStyle::Compact {
+            message.flags(MessageFlags::SUPPRESS_EMBEDS)
+        } else {
+            message
+        }
+    }
+
+    /// Returns the "Schedule watch party" button row. `None` unless this announces a new
+    /// episode.
+    fn watch_party_row(&self) -> Option<CreateComponent<'static>> {
+        let is_episode = self
+            .feed_info
+            .feed_item_name
+            .eq_ignore_ascii_case("episode");
+        if !is_episode || self.kind != FeedUpdateKind::New {
+            return None;
+        }
+        let id = WatchPartyId {
+            feed_id: self.feed.id,
+            episode: self.new_feed_item.description.clone(),
+        };
+        let button = CreateButton::new(id.custom_id())
+            .label("🍿 Schedule watch party")
+            .style(ButtonStyle::Secondary);
+        Some(CreateComponent::ActionRow(CreateActionRow::Buttons(
+            vec![button].into(),
+        )))
+    }
+
+    /// Creates a single-line message for this feed update.
+    fn create_compact_message(
+        &self,
+        title: &str,
+        watch_party: Option<CreateComponent<'static>>,
+        mention: Option<String>,
+    ) -> CreateMessage<'static> {
+        let item = &self.new_feed_item;
+        let duration = item
+            .duration_secs
+            .map(|secs| format!(" • ⏱ {}", format_duration(secs)))
+            .unwrap_or_default();
+        let listen = item
+            .media_url
+            .as_ref()
+            .map(|url| format!(" • [Listen 🎧]({url})"))
+            .unwrap_or_default();
+        let mention = mention
+            .map(|mention| format!("{mention} "))
+            .unwrap_or_default();
+        let content = format!(
+            "{mention}{}**{}** • {} {}: {}{duration} • <t:{}:R>{listen} • [Open ↗]({})",
+            self.emoji_prefix(),
+            title,
+            self.kind.label(),
+            self.feed_info.feed_item_name,
+            self.item_title(),
+            item.published.timestamp(),
+            self.open_url()
+        );
+        let message = CreateMessage::new().content(content);
+        match watch_party {
+            Some(row) => message.components(vec![row]),
+            None => message,
+        }
+    }
+
+    /// Returns the accent color of the notification: the feed's own, else its platform's.
+    pub fn accent_color(&self) -> Option<u32> {
+        self.feed
+            .accent_color
+            .map(|color| color as u32)
+            .or(self.feed_info.accent_color)
+    }
+
+    /// Returns the emoji shown before the feed's name: the feed's own, else its
+    /// platform's, followed by a space. Empty without one.
+    pub fn emoji_prefix(&self) -> String {
+        let emoji = self.feed.emoji.as_deref().unwrap_or(&self.feed_info.emoji);
+        if emoji.is_empty() {
+            String::new()
+        } else {
+            format!("{emoji} ")
+        }
+    }
+
+    /// Returns the new item's title, showing the bump from the old one when both are versions.
+    fn item_title(&self) -> String {
+        let new = &self.new_feed_item.description;
+        match (&self.kind, &self.old_feed_item) {
+            (FeedUpdateKind::New, Some(old)) => {
+                format_version_bump(&old.description, new).unwrap_or_else(|| new.clone())
+            }
+            _ => new.clone(),
+        }
+    }
+
+    /// Returns where "Open" links point: the item itself if known, otherwise the feed source.
+    fn open_url(&self) -> &str {
+        self.new_feed_item
+            .item_url
+            .as_deref()
+            .unwrap_or(&self.feed.source_url)
+    }
+
+    /// Formats the new item's duration and media link as extra lines for the card message.
+    fn media_lines(&self) -> String {
+        let item = &self.new_feed_item;
+        let mut lines = String::new();
+        if let Some(secs) = item.duration_secs {
+            lines.push_str(&format!("\nDuration: {}", format_duration(secs)));
+        }
+        if let Some(url) = &item.media_url {
+            lines.push_str(&format!("\n**[Listen 🎧]({url})**"));
+        }
+        lines
+    }
+
+    /// Creates the full card message for this feed update.
+    fn create_rich_message(
+        &self,
+        title: &str,
+        hide_cover: bool,
+        watch_party: Option<CreateComponent<'static>>,
+        mention: Option<String>,
+    ) -> CreateMessage<'static> {
         let FeedUpdateData {
             feed,
             feed_info,
             old_feed_item,
             new_feed_item,
+            kind,
         } = self;
         let feed_desc = if feed.description.is_empty() {
             "> No description.".to_string()
@@ -78,40 +344,49 @@ impl FeedUpdateData {
             desc
         };
 
-        let old_section = old_feed_item.clone().map_or(
-            format!("**No previous {} **", feed_info.feed_item_name),
-            |old| {
-                format!(
-                    "**Old {}**: {}\nPublished on <t:{}>",
-                    feed_info.feed_item_name,
-                    old.description,
-                    old.published.timestamp()
-                )
-            },
-        );
+        let old_section = match kind {
+            FeedUpdateKind::Edited => format!(
+                "**This {} was edited at the source**",
+                feed_info.feed_item_name.to_lowercase()
+            ),
+            FeedUpdateKind::New => old_feed_item.clone().map_or(
+                format!("**No previous {} **", feed_info.feed_item_name),
+                |old| {
+                    format!(
+                        "**Old {}**: {}\nPublished on <t:{}>",
+                        feed_info.feed_item_name,
+                        old.description,
+                        old.published.timestamp()
+                    )
+                },
+            ),
+        };
 
         let text_main = format!(
-            "### {}
+            "### {}{}
 
 {}
 
 {}
 
-**New {}**: {}
-Published on <t:{}>
+**{} {}**: {}
+Published on <t:{}>{}
 
 **[Open in browser ↗]({})**",
-            feed.name,
+            self.emoji_prefix(),
+            title,
             feed_desc,
             old_section,
+            kind.label(),
             feed_info.feed_item_name,
-            new_feed_item.description,
+            self.item_title(),
             new_feed_item.published.timestamp(),
-            feed.source_url
+            self.media_lines(),
+            self.open_url()
         );
         let text_footer = format!("-# {}", feed_info.copyright_notice);
 
-        let container = CreateComponent::Container(CreateContainer::new(vec![
+        let mut components = vec![
             CreateContainerComponent::Section(CreateSection::new(
                 vec![CreateSectionComponent::TextDisplay(CreateTextDisplay::new(
                     text_main,
@@ -121,20 +396,205 @@ Published on <t:{}>
                 )),
             )),
             CreateContainerComponent::Separator(CreateSeparator::new(false)),
-            CreateContainerComponent::MediaGallery(CreateMediaGallery::new(vec![
-                CreateMediaGalleryItem::new(CreateUnfurledMediaItem::new(feed.cover_url.clone())),
-            ])),
-            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text_footer)),
-        ]));
+        ];
+        // An item's own preview image is content, so it is shown even when covers are hidden
+        let gallery_image = new_feed_item.image_url.clone().or_else(|| {
+            (!hide_cover && !feed.cover_url.is_empty()).then(|| feed.cover_url.clone())
+        });
+        if let Some(image) = gallery_image {
+            components.push(CreateContainerComponent::MediaGallery(
+                CreateMediaGallery::new(vec![CreateMediaGalleryItem::new(
+                    CreateUnfurledMediaItem::new(image),
+                )]),
+            ));
+        }
+        components.push(CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(text_footer),
+        ));
+
+        let mut container = CreateContainer::new(components);
+        if let Some(color) = self.accent_color() {
+            container = container.accent_colour(Colour::new(color));
+        }
+        let container = CreateComponent::Container(container);
+        // Components V2 messages cannot have content, so the mention is its own text
+        let mut top_level: Vec<_> = mention
+            .map(|mention| CreateComponent::TextDisplay(CreateTextDisplay::new(mention)))
+            .into_iter()
+            .collect();
+        top_level.push(container);
+        top_level.extend(watch_party);
 
         CreateMessage::new()
             .flags(MessageFlags::IS_COMPONENTS_V2)
-            .components(vec![container])
+            .components(top_level)
+    }
+}
+
+/// Formats a duration in seconds as `H:MM:SS`, or `M:SS` when under an hour.
+pub fn format_duration(secs: i32) -> String {
+    let secs = secs.max(0);
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
     }
 }
 
+/// Describes the bump between two semver versions, bolding the part that changed.
+///
+/// E.g. `1.2.3` to `1.3.0` gives ``1.2.3 → 1.**3.0** (minor)``. Follows Cargo's rules for
+/// `0.x` versions, where a minor or patch bump may be breaking. Returns `None` unless both
+/// are valid versions and `new` is the higher one.
+pub fn format_version_bump(old: &str, new: &str) -> Option<String> {
+    let parse = |version: &str| Version::parse(version.trim().trim_start_matches('v')).ok();
+    let (old, new) = (parse(old)?, parse(new)?);
+    if new <= old {
+        return None;
+    }
+
+    // Index of the first changed component: major, minor, patch, then pre-release/build
+    let (changed_at, bump) = if new.major != old.major {
+        (0, "major")
+    } else if new.minor != old.minor {
+        (1, if new.major == 0 { "breaking" } else { "minor" })
+    } else if new.patch != old.patch {
+        let breaking = new.major == 0 && new.minor == 0;
+        (2, if breaking { "breaking" } else { "patch" })
+    } else if new.pre.is_empty() {
+        // A pre-release became stable
+        (0, "stable")
+    } else {
+        (3, "pre-release")
+    };
+
+    let core = [new.major, new.minor, new.patch].map(|n| n.to_string());
+    let full = new.to_string();
+    let unchanged_len = match changed_at {
+        0 => 0,
+        // Keep the `.` or `-` separator outside the bold part
+        n => core[..n].join(".").len() + 1,
+    };
+    let (unchanged, changed) = full.split_at(unchanged_len.min(full.len()));
+    if changed.is_empty() {
+        return None;
+    }
+    Some(format!("{old} → {unchanged}**{changed}** ({bump})"))
+}
+
 impl Event for FeedUpdateEvent {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watch_party_id_round_trips() {
+        let id = WatchPartyId {
+            feed_id: 42,
+            episode: "12: The End?".to_string(),
+        };
+
+        assert_eq!(id.custom_id(), "watch_party:42:12: The End?");
+        assert_eq!(WatchPartyId::parse(&id.custom_id()), Some(id));
+        assert_eq!(WatchPartyId::parse("export_csv"), None);
+    }
+
+    #[test]
+    fn watch_party_id_fits_custom_id_limit() {
+        let id = WatchPartyId {
+            feed_id: 42,
+            episode: "é".repeat(200),
+        };
+
+        assert_eq!(id.custom_id().chars().count(), 100);
+    }
+
+    #[test]
+    fn feed_branding_overrides_platform_defaults() {
+        let data = |feed: FeedEntity| FeedUpdateData {
+            feed: Arc::new(feed),
+            feed_info: Arc::new(PlatformInfo {
+                accent_color: Some(0x02A9FF),
+                emoji: "📺".to_string(),
+                ..Default::default()
+            }),
+            old_feed_item: None,
+            new_feed_item: Arc::new(FeedItemEntity::default()),
+            kind: FeedUpdateKind::New,
+        };
+
+        let platform = data(FeedEntity::default());
+        assert_eq!(platform.accent_color(), Some(0x02A9FF));
+        assert_eq!(platform.emoji_prefix(), "📺 ");
+
+        let branded = data(FeedEntity {
+            accent_color: Some(0xFF6740),
+            emoji: Some("🔥".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(branded.accent_color(), Some(0xFF6740));
+        assert_eq!(branded.emoji_prefix(), "🔥 ");
+
+        // An empty emoji override hides the platform's emoji
+        let plain = data(FeedEntity {
+            emoji: Some(String::new()),
+            ..Default::default()
+        });
+        assert_eq!(plain.emoji_prefix(), "");
+    }
+
+    #[test]
+    fn simulated_event_only_goes_to_its_recipient() {
+        let data = FeedUpdateData {
+            feed: Arc::new(FeedEntity::default()),
+            feed_info: Arc::new(PlatformInfo::default()),
+            old_feed_item: None,
+            new_feed_item: Arc::new(FeedItemEntity::default()),
+            kind: FeedUpdateKind::New,
+        };
+
+        let event = FeedUpdateEvent::simulated(data.clone(), 7);
+        assert!(event.simulated);
+        assert_eq!(event.recipients.as_deref(), Some(&vec![7]));
+        assert!(!FeedUpdateEvent::new(data).simulated);
+    }
+
+    #[test]
+    fn format_duration_pads_minutes_and_seconds() {
+        assert_eq!(format_duration(59), "0:59");
+        assert_eq!(format_duration(2710), "45:10");
+        assert_eq!(format_duration(3723), "1:02:03");
+    }
+
+    #[test]
+    fn format_version_bump_highlights_changed_part() {
+        assert_eq!(
+            format_version_bump("1.2.3", "1.3.0").as_deref(),
+            Some("1.2.3 → 1.**3.0** (minor)")
+        );
+        assert_eq!(
+            format_version_bump("1.2.3", "2.0.0").as_deref(),
+            Some("1.2.3 → **2.0.0** (major)")
+        );
+        assert_eq!(
+            format_version_bump("0.4.1", "0.5.0").as_deref(),
+            Some("0.4.1 → 0.**5.0** (breaking)")
+        );
+        assert_eq!(
+            format_version_bump("1.0.0-beta.1", "1.0.0-beta.2").as_deref(),
+            Some("1.0.0-beta.1 → 1.0.0-**beta.2** (pre-release)")
+        );
+        assert_eq!(
+            format_version_bump("1.0.0-rc.1", "1.0.0").as_deref(),
+            Some("1.0.0-rc.1 → **1.0.0** (stable)")
+        );
+        assert_eq!(format_version_bump("1.3.0", "1.2.9"), None);
+        assert_eq!(format_version_bump("Ch. 1", "Ch. 2"), None);
+    }
+}
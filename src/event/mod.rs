@@ -5,8 +5,14 @@ pub mod feed_update;
 
 pub use feed_update::FeedUpdateData;
 pub use feed_update::FeedUpdateEvent;
+pub use feed_update::FeedUpdateKind;
+pub use feed_update::MessageOptions;
+pub use feed_update::WatchPartyId;
 use poise::serenity_prelude::VoiceState;
 
+use crate::entity::UpcomingRelease;
+use crate::entity::VoiceGoalProgress;
+
 /// Marker trait for events that can be dispatched through the event bus.
 ///
 /// Automatically implemented for all types that are thread-safe and have
@@ -30,6 +36,12 @@ pub trait Event: std::any::Any + Send + Sync + 'static {
 pub struct VoiceStateEvent {
     pub old: Option<VoiceState>,
     pub new: VoiceState,
+    /// Whether the user is a bot account.
+    #[serde(default)]
+    pub is_bot: bool,
+    /// Whether the new channel is a stage channel.
+    #[serde(default)]
+    pub is_stage: bool,
 }
 
 impl Event for VoiceStateEvent {
@@ -37,3 +49,47 @@ impl Event for VoiceStateEvent {
         self
     }
 }
+
+/// Event fired once a month when a server reaches its voice goal.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct VoiceGoalReachedEvent {
+    pub guild_id: u64,
+    /// Channel to announce the goal in.
+    pub channel_id: u64,
+    pub progress: VoiceGoalProgress,
+}
+
+impl Event for VoiceGoalReachedEvent {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Event fired daily for each server keeping a release calendar, carrying its releases.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ReleaseCalendarEvent {
+    pub guild_id: u64,
+    /// Channel the calendar is pinned in.
+    pub channel_id: u64,
+    pub releases: Vec<UpcomingRelease>,
+}
+
+impl Event for ReleaseCalendarEvent {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Event fired hourly for each server keeping airing events, carrying the next episode of
+/// each AniList anime it follows.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AiringEventsEvent {
+    pub guild_id: u64,
+    pub airings: Vec<UpcomingRelease>,
+}
+
+impl Event for AiringEventsEvent {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
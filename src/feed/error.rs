@@ -1,5 +1,7 @@
 //! Feed platform error types.
 
+use crate::error::ErrorCategory;
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum UrlParseError {
@@ -22,6 +24,12 @@ pub enum FeedError {
     #[error("Failed to parse API response: {0}")]
     JsonParseFailed(#[from] serde_json::Error),
 
+    #[error("Failed to parse feed XML: {0}")]
+    XmlParseFailed(#[from] rss::Error),
+
+    #[error("Failed to parse Atom feed: {0}")]
+    AtomParseFailed(#[from] atom_syndication::Error),
+
     #[error("Feed source not found (ID: {source_id}).")]
     SourceNotFound { source_id: String },
 
@@ -52,6 +60,15 @@ pub enum FeedError {
     #[error("The URL `{url}` is not supported.")]
     UnsupportedUrl { url: String },
 
+    #[error("Invalid JSONPath `{path}`: {message}")]
+    InvalidJsonPath { path: String, message: String },
+
+    #[error("A platform named `{name}` is already registered.")]
+    PlatformAlreadyRegistered { name: String },
+
+    #[error("{platform} feeds can't be filtered.")]
+    FilterUnsupported { platform: String },
+
     #[error("Unexpected error: {message}")]
     UnexpectedResult { message: String },
 
@@ -59,6 +76,34 @@ pub enum FeedError {
     UrlParseFailed(#[from] UrlParseError),
 }
 
+impl FeedError {
+    /// What kind of failure this is.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::RequestFailed(_)
+            | Self::JsonParseFailed(_)
+            | Self::XmlParseFailed(_)
+            | Self::AtomParseFailed(_)
+            | Self::MissingField { .. }
+            | Self::ApiError { .. }
+            | Self::InvalidTimestamp { .. }
+            | Self::InvalidTime { .. } => ErrorCategory::Upstream,
+            Self::SourceNotFound { .. }
+            | Self::ItemNotFound { .. }
+            | Self::SourceFinished { .. }
+            | Self::EmptySource { .. }
+            | Self::InvalidSourceId { .. }
+            | Self::UnsupportedUrl { .. }
+            | Self::InvalidJsonPath { .. }
+            | Self::FilterUnsupported { .. }
+            | Self::UrlParseFailed(_) => ErrorCategory::User,
+            Self::PlatformAlreadyRegistered { .. } | Self::UnexpectedResult { .. } => {
+                ErrorCategory::Internal
+            }
+        }
+    }
+}
+
 impl From<wreq::Error> for FeedError {
     fn from(e: wreq::Error) -> Self {
         FeedError::RequestFailed(Box::new(e))
@@ -1,7 +1,7 @@
 //! Feed platform integrations and content monitoring.
 //!
 //! This module provides abstractions for integrating with external content platforms
-//! (MangaDex, AniList, Comick) and fetching updates from them.
+//! (MangaDex, AniList, Comick, WEBTOON, Bluesky, crates.io, Nyaa, YouTube, Twitch, podcasts, any RSS or Atom feed) and fetching updates from them.
 //!
 //! # Terms
 //!
@@ -10,20 +10,38 @@
 //! - **Feed Item**: Individual updates within a source (e.g., chapters, episodes)
 
 pub mod error;
+pub mod mal;
+pub mod opml;
 pub mod platform;
+pub mod plugin;
+pub mod request_stats;
+
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::DateTime;
 use chrono::Utc;
+use governor::Quota;
 pub use platform::AniListPlatform;
+pub use platform::BlueskyPlatform;
 pub use platform::ComickPlatform;
+pub use platform::CratesIoPlatform;
+pub use platform::CustomJsonPlatform;
 pub use platform::MangaDexPlatform;
+pub use platform::NyaaPlatform;
+pub use platform::PlatformChoice;
 pub use platform::Platforms;
+pub use platform::PodcastPlatform;
+pub use platform::RssPlatform;
+pub use platform::TwitchPlatform;
+pub use platform::WebtoonPlatform;
+pub use platform::YoutubePlatform;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::feed::error::FeedError;
 use crate::feed::error::UrlParseError;
+use crate::feed::request_stats::RequestStats;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PlatformInfo {
@@ -43,16 +61,37 @@ pub struct PlatformInfo {
     pub logo_url: String,
     /// Platform tags. Mainly used for grouping and filtering
     pub tags: String,
+    /// Whether [`FeedItem::id`] identifies a single item. Otherwise items are told apart by title.
+    #[serde(default)]
+    pub unique_item_ids: bool,
+    /// Accent color of the platform's notifications, e.g. `0x02A9FF`. A feed can override it.
+    #[serde(default)]
+    pub accent_color: Option<u32>,
+    /// Emoji shown before feed names in the platform's notifications. A feed can override it.
+    #[serde(default)]
+    pub emoji: String,
 }
 
 #[derive(Clone, Debug)]
 pub struct BasePlatform {
     pub info: PlatformInfo,
+    /// Requests sent to the platform, see [`request_stats`].
+    pub requests: Arc<RequestStats>,
 }
 
 impl BasePlatform {
     pub fn new(info: PlatformInfo) -> Self {
-        BasePlatform { info }
+        BasePlatform {
+            info,
+            requests: Arc::default(),
+        }
+    }
+
+    /// Sets the quota the platform's rate limiter enforces, which `/owner apistatus`
+    /// reports requests against.
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.requests = Arc::new(RequestStats::with_quota(quota));
+        self
     }
     pub fn get_nth_path_from_url<'b>(
         &self,
@@ -105,6 +144,36 @@ pub struct FeedItem {
     pub title: String,
     /// Timestamp of the update.
     pub published: DateTime<Utc>,
+    /// Editable body of the update, e.g., a chapter name. `None` if the platform has none.
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Direct link to the item's media, e.g., a podcast episode's audio file.
+    #[serde(default)]
+    pub media_url: Option<String>,
+    /// Length of the item's media in seconds.
+    #[serde(default)]
+    pub duration_secs: Option<i32>,
+    /// Link to the update itself, e.g., a post's permalink.
+    #[serde(default)]
+    pub item_url: Option<String>,
+    /// Preview image of the update, e.g., a post's first attached image.
+    #[serde(default)]
+    pub image_url: Option<String>,
+}
+
+impl FeedItem {
+    /// Returns a stable hash of the title and content, used to detect edits.
+    ///
+    /// Uses 64-bit FNV-1a so the value stays the same across builds and restarts.
+    pub fn content_hash(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let content = self.content.as_deref().unwrap_or_default();
+        for byte in self.title.bytes().chain(*b"\n").chain(content.bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{hash:016x}")
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -122,6 +191,20 @@ pub struct FeedSource {
     pub source_url: String,
     /// Cover/Avatar url.
     pub image_url: Option<String>,
+    /// Other titles of the source, on platforms that list them.
+    #[serde(default)]
+    pub titles: TitleVariants,
+}
+
+/// Titles of a feed source besides its name, e.g., of a manga or anime.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TitleVariants {
+    /// English title, e.g., "Attack on Titan".
+    pub english: Option<String>,
+    /// Original title in Latin script, e.g., "Shingeki no Kyojin".
+    pub romanized: Option<String>,
+    /// Original title in its own script, e.g., "進撃の巨人".
+    pub native: Option<String>,
 }
 
 #[async_trait]
@@ -141,6 +224,29 @@ pub trait Platform: Send + Sync {
     fn get_id(&self) -> &'_ str {
         &self.get_info().name
     }
+    /// Whether this platform handles source urls on any domain, e.g., RSS feeds.
+    ///
+    /// Such platforms are only used for urls no other platform's domain matches.
+    fn accepts_any_domain(&self) -> bool {
+        false
+    }
+    /// Whether this platform handles `source_url` regardless of its domain, e.g., urls
+    /// configured at runtime. Such platforms take precedence over domain matches.
+    fn claims_source_url(&self, _source_url: &str) -> bool {
+        false
+    }
+    /// Get the url of a source narrowed to items matching `filter`, e.g., `1080p SubsPlease`.
+    ///
+    /// A filtered source is a separate feed, so each subscription can pick its own filter.
+    fn get_filtered_source_url(
+        &self,
+        _source_id: &str,
+        _filter: &str,
+    ) -> Result<String, FeedError> {
+        Err(FeedError::FilterUnsupported {
+            platform: self.get_id().to_string(),
+        })
+    }
     fn extract_error_message(&self, error: &serde_json::Value) -> String {
         let mut parts = Vec::new();
 
@@ -212,4 +318,28 @@ mod tests {
             Err(UrlParseError::InvalidFormat { .. })
         ));
     }
+
+    #[test]
+    fn content_hash_changes_with_content() {
+        let item = FeedItem {
+            title: "Chapter 100".to_string(),
+            content: Some("The Beginning".to_string()),
+            ..Default::default()
+        };
+        let edited = FeedItem {
+            content: Some("The Beginning (Fixed)".to_string()),
+            ..item.clone()
+        };
+
+        assert_eq!(item.content_hash(), item.clone().content_hash());
+        assert_ne!(item.content_hash(), edited.content_hash());
+        assert_eq!(
+            FeedItem::default().content_hash(),
+            FeedItem {
+                content: Some(String::new()),
+                ..Default::default()
+            }
+            .content_hash()
+        );
+    }
 }
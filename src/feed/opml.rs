@@ -0,0 +1,113 @@
+//! Reading and writing subscription lists in OPML, the format feed readers exchange
+//! them in.
+//!
+//! Only what a subscription list needs is supported: each `<outline>` with an `xmlUrl`
+//! is one feed. Nesting, e.g. folders of feeds, is flattened.
+
+/// One feed in an OPML document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpmlFeed {
+    pub title: String,
+    /// The feed's source URL.
+    pub url: String,
+}
+
+/// Writes feeds as an OPML 2.0 document.
+pub fn write(title: &str, feeds: &[OpmlFeed]) -> String {
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n  <head>\n    <title>{}</title>\n  </head>\n  <body>\n",
+        escape(title)
+    );
+    for feed in feeds {
+        out.push_str(&format!(
+            "    <outline type=\"rss\" text=\"{0}\" title=\"{0}\" xmlUrl=\"{1}\"/>\n",
+            escape(&feed.title),
+            escape(&feed.url)
+        ));
+    }
+    out.push_str("  </body>\n</opml>\n");
+    out
+}
+
+/// Returns the `xmlUrl` of every outline, in document order.
+pub fn parse_urls(opml: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = opml;
+    while let Some(start) = rest.find("<outline") {
+        rest = &rest[start + "<outline".len()..];
+        let end = rest.find('>').unwrap_or(rest.len());
+        if let Some(url) = attribute(&rest[..end], "xmlUrl") {
+            urls.push(url);
+        }
+        rest = &rest[end..];
+    }
+    urls
+}
+
+/// Reads an attribute of a tag, matching its name case-insensitively.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let needle = format!("{}=", name.to_ascii_lowercase());
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(&needle) {
+        let at = from + found;
+        from = at + needle.len();
+        // Skip matches that only end a longer attribute name
+        if at > 0 && !lower.as_bytes()[at - 1].is_ascii_whitespace() {
+            continue;
+        }
+        let value = &tag[from..];
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &value[1..];
+        let len = value.find(quote)?;
+        return Some(unescape(&value[..len]));
+    }
+    None
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opml_round_trips_feeds() {
+        let feeds = vec![OpmlFeed {
+            title: "Tom & Jerry <Podcast>".to_string(),
+            url: "https://example.com/feed.xml?a=1&b=2".to_string(),
+        }];
+
+        let opml = write("pwr-bot subscriptions", &feeds);
+
+        assert_eq!(parse_urls(&opml), vec![feeds[0].url.clone()]);
+    }
+
+    #[test]
+    fn parse_urls_reads_nested_outlines_from_other_readers() {
+        let opml = r#"<opml version="1.0"><body>
+            <outline text="Anime">
+                <outline htmlUrl='https://example.com' xmlurl='https://example.com/rss'/>
+            </outline>
+            <outline text="No feed"/>
+        </body></opml>"#;
+
+        assert_eq!(
+            parse_urls(opml),
+            vec!["https://example.com/rss".to_string()]
+        );
+    }
+}
@@ -1,11 +1,13 @@
 //! AniList anime platform integration.
 
+use std::collections::HashMap;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::num::NonZeroU32;
 
 use async_trait::async_trait;
 use chrono::DateTime;
+use chrono::Utc;
 use governor::Quota;
 use governor::RateLimiter;
 use governor::clock::QuantaClock;
@@ -21,8 +23,43 @@ use crate::feed::FeedItem;
 use crate::feed::FeedSource;
 use crate::feed::Platform;
 use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
 use crate::feed::error::FeedError;
 
+/// IDs looked up per request. AniList pages hold at most 50 media.
+const IDS_PER_PAGE: usize = 50;
+
+/// An anime on a user's AniList list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AniListListEntry {
+    pub media_id: i32,
+    pub title: String,
+    /// Whether episodes can still air, i.e. the anime is releasing, on hiatus or not
+    /// released yet.
+    pub airing: bool,
+}
+
+/// A manga on a user's AniList reading list. AniList has no manga feed, so these are
+/// matched to feeds of other platforms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AniListMangaEntry {
+    pub media_id: i32,
+    /// MyAnimeList ID, if AniList knows it.
+    pub mal_id: Option<i32>,
+    pub title: String,
+    /// English and native titles and synonyms.
+    pub alt_titles: Vec<String>,
+    /// Whether chapters can still come out.
+    pub releasing: bool,
+}
+
+/// The next episode of an anime scheduled to air.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AniListNextAiring {
+    pub episode: i32,
+    pub airing_at: DateTime<Utc>,
+}
+
 /// AniList GraphQL API platform for anime tracking.
 pub struct AniListPlatform {
     pub base: BasePlatform,
@@ -42,29 +79,359 @@ impl AniListPlatform {
             copyright_notice: "© AniList LLC 2025".to_string(),
             logo_url: "https://anilist.co/img/icons/android-chrome-192x192.png".to_string(),
             tags: "series".to_string(),
+            unique_item_ids: false,
+            accent_color: Some(0x02A9FF),
+            emoji: "📺".to_string(),
         };
         // TODO: See https://docs.anilist.co/guide/rate-limiting.
         // "The API is currently in a degraded state and is limited to 30 requests per minute."
         // We will use the ratelimit headers `X-RateLimit-Limit` and `X-RateLimit-Remaining` when
         // the API is fully restored.
-        let limiter = RateLimiter::direct(Quota::per_minute(NonZeroU32::new(30).unwrap()));
+        let quota = Quota::per_minute(NonZeroU32::new(30).unwrap());
+        let limiter = RateLimiter::direct(quota);
         let client = wreq::Client::builder()
             .emulation(wreq_util::Emulation::Chrome137)
             .build()
             .unwrap();
 
         Self {
-            base: BasePlatform::new(info),
+            base: BasePlatform::new(info).with_quota(quota),
             client,
             limiter,
         }
     }
 
+    /// Looks up an AniList user by name. Returns their ID and name as AniList spells it,
+    /// or `None` if no user has that name.
+    pub async fn fetch_user(&self, username: &str) -> Result<Option<(i32, String)>, FeedError> {
+        let query = r#"
+            query ($name: String) {
+              User(name: $name) {
+                id
+                name
+              }
+            }
+        "#;
+        let response_json = self
+            .post_query(query, serde_json::json!({ "name": username }))
+            .await?;
+
+        if let Some(user) = response_json
+            .get("data")
+            .and_then(|d| d.get("User"))
+            .and_then(|v| v.as_object())
+        {
+            let id = user
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .and_then(|id| i32::try_from(id).ok())
+                .ok_or_else(|| FeedError::MissingField {
+                    field: "data.User.id".to_string(),
+                })?;
+            let name = user
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or(username)
+                .to_string();
+            return Ok(Some((id, name)));
+        }
+        let not_found = response_json
+            .get("errors")
+            .and_then(|e| e.as_array())
+            .is_some_and(|errors| {
+                errors
+                    .iter()
+                    .any(|e| e.get("status").and_then(|v| v.as_i64()) == Some(404))
+            });
+        if not_found {
+            return Ok(None);
+        }
+        self.check_api_errors(&response_json)?;
+        Err(FeedError::MissingField {
+            field: "data.User".to_string(),
+        })
+    }
+
+    /// Fetches the anime a user is watching or rewatching.
+    pub async fn fetch_watching(&self, user_id: i32) -> Result<Vec<AniListListEntry>, FeedError> {
+        let query = r#"
+            query ($userId: Int) {
+              MediaListCollection(userId: $userId, type: ANIME, status_in: [CURRENT, REPEATING]) {
+                lists {
+                  entries {
+                    media {
+                      id
+                      status
+                      title { romaji }
+                    }
+                  }
+                }
+              }
+            }
+        "#;
+        let response_json = self
+            .post_query(query, serde_json::json!({ "userId": user_id }))
+            .await?;
+        self.check_api_errors(&response_json)?;
+
+        let lists = response_json
+            .get("data")
+            .and_then(|d| d.get("MediaListCollection"))
+            .and_then(|c| c.get("lists"))
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FeedError::MissingField {
+                field: "data.MediaListCollection.lists".to_string(),
+            })?;
+        Ok(Self::parse_list_entries(lists))
+    }
+
+    /// Fetches the manga a user is reading or rereading.
+    pub async fn fetch_reading(&self, user_id: i32) -> Result<Vec<AniListMangaEntry>, FeedError> {
+        let query = r#"
+            query ($userId: Int) {
+              MediaListCollection(userId: $userId, type: MANGA, status_in: [CURRENT, REPEATING]) {
+                lists {
+                  entries {
+                    media {
+                      id
+                      idMal
+                      status
+                      title { romaji english native }
+                      synonyms
+                    }
+                  }
+                }
+              }
+            }
+        "#;
+        let response_json = self
+            .post_query(query, serde_json::json!({ "userId": user_id }))
+            .await?;
+        self.check_api_errors(&response_json)?;
+
+        let lists = response_json
+            .get("data")
+            .and_then(|d| d.get("MediaListCollection"))
+            .and_then(|c| c.get("lists"))
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FeedError::MissingField {
+                field: "data.MediaListCollection.lists".to_string(),
+            })?;
+        Ok(Self::parse_manga_entries(lists))
+    }
+
+    /// Looks up the anime with the given MyAnimeList IDs. Returns the entries found, keyed
+    /// by MyAnimeList ID. Anime AniList doesn't know are left out.
+    ///
+    /// # Performance
+    /// * API calls: 1 per 50 IDs
+    pub async fn fetch_by_mal_ids(
+        &self,
+        mal_ids: &[i32],
+    ) -> Result<HashMap<i32, AniListListEntry>, FeedError> {
+        let query = r#"
+            query ($ids: [Int]) {
+              Page(perPage: 50) {
+                media(idMal_in: $ids, type: ANIME) {
+                  id
+                  idMal
+                  status
+                  title { romaji }
+                }
+              }
+            }
+        "#;
+        let mut found = HashMap::new();
+        for ids in mal_ids.chunks(IDS_PER_PAGE) {
+            let response_json = self
+                .post_query(query, serde_json::json!({ "ids": ids }))
+                .await?;
+            self.check_api_errors(&response_json)?;
+
+            let media = response_json
+                .get("data")
+                .and_then(|d| d.get("Page"))
+                .and_then(|p| p.get("media"))
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| FeedError::MissingField {
+                    field: "data.Page.media".to_string(),
+                })?;
+            for media in media {
+                let mal_id = media
+                    .get("idMal")
+                    .and_then(|v| v.as_i64())
+                    .and_then(|id| i32::try_from(id).ok());
+                if let (Some(mal_id), Some(entry)) = (mal_id, Self::parse_list_media(media)) {
+                    found.insert(mal_id, entry);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Looks up the next episode to air of the anime with the given AniList IDs, keyed
+    /// by AniList ID. Anime with no scheduled episode are left out.
+    ///
+    /// # Performance
+    /// * API calls: 1 per 50 IDs
+    pub async fn fetch_next_airing(
+        &self,
+        media_ids: &[i32],
+    ) -> Result<HashMap<i32, AniListNextAiring>, FeedError> {
+        let query = r#"
+            query ($ids: [Int]) {
+              Page(perPage: 50) {
+                media(id_in: $ids, type: ANIME) {
+                  id
+                  nextAiringEpisode {
+                    airingAt
+                    episode
+                  }
+                }
+              }
+            }
+        "#;
+        let mut found = HashMap::new();
+        for ids in media_ids.chunks(IDS_PER_PAGE) {
+            let response_json = self
+                .post_query(query, serde_json::json!({ "ids": ids }))
+                .await?;
+            self.check_api_errors(&response_json)?;
+
+            let media = response_json
+                .get("data")
+                .and_then(|d| d.get("Page"))
+                .and_then(|p| p.get("media"))
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| FeedError::MissingField {
+                    field: "data.Page.media".to_string(),
+                })?;
+            for media in media {
+                let media_id = media
+                    .get("id")
+                    .and_then(|v| v.as_i64())
+                    .and_then(|id| i32::try_from(id).ok());
+                let next = media.get("nextAiringEpisode");
+                let episode = next
+                    .and_then(|n| n.get("episode"))
+                    .and_then(|v| v.as_i64())
+                    .and_then(|ep| i32::try_from(ep).ok());
+                let airing_at = next
+                    .and_then(|n| n.get("airingAt"))
+                    .and_then(|v| v.as_i64())
+                    .and_then(|ts| DateTime::from_timestamp(ts, 0));
+                if let (Some(media_id), Some(episode), Some(airing_at)) =
+                    (media_id, episode, airing_at)
+                {
+                    found.insert(media_id, AniListNextAiring { episode, airing_at });
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Reads the entries of `MediaListCollection.lists`, skipping malformed ones and
+    /// entries listed twice, e.g. in a custom list.
+    fn parse_list_entries(lists: &[Value]) -> Vec<AniListListEntry> {
+        let mut entries: Vec<AniListListEntry> = Vec::new();
+        let media = lists
+            .iter()
+            .filter_map(|list| list.get("entries").and_then(|v| v.as_array()))
+            .flatten()
+            .filter_map(|entry| entry.get("media"));
+        for media in media {
+            let Some(entry) = Self::parse_list_media(media) else {
+                continue;
+            };
+            if entries.iter().any(|e| e.media_id == entry.media_id) {
+                continue;
+            }
+            entries.push(entry);
+        }
+        entries
+    }
+
+    /// Reads the manga of `MediaListCollection.lists` like [`Self::parse_list_entries`].
+    fn parse_manga_entries(lists: &[Value]) -> Vec<AniListMangaEntry> {
+        let mut entries: Vec<AniListMangaEntry> = Vec::new();
+        let media = lists
+            .iter()
+            .filter_map(|list| list.get("entries").and_then(|v| v.as_array()))
+            .flatten()
+            .filter_map(|entry| entry.get("media"));
+        for media in media {
+            let Some(entry) = Self::parse_list_media(media) else {
+                continue;
+            };
+            if entries.iter().any(|e| e.media_id == entry.media_id) {
+                continue;
+            }
+            let title = media.get("title");
+            let alt_titles = ["english", "native"]
+                .iter()
+                .filter_map(|key| title.and_then(|t| t.get(key)))
+                .chain(
+                    media
+                        .get("synonyms")
+                        .and_then(|v| v.as_array())
+                        .into_iter()
+                        .flatten(),
+                )
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect();
+            entries.push(AniListMangaEntry {
+                media_id: entry.media_id,
+                mal_id: media
+                    .get("idMal")
+                    .and_then(|v| v.as_i64())
+                    .and_then(|id| i32::try_from(id).ok()),
+                title: entry.title,
+                alt_titles,
+                releasing: entry.airing,
+            });
+        }
+        entries
+    }
+
+    /// Reads a media object with `id`, `status` and `title.romaji`.
+    fn parse_list_media(media: &Value) -> Option<AniListListEntry> {
+        let media_id = media
+            .get("id")
+            .and_then(|v| v.as_i64())
+            .and_then(|id| i32::try_from(id).ok())?;
+        let status = media.get("status").and_then(|v| v.as_str());
+        Some(AniListListEntry {
+            media_id,
+            title: media
+                .get("title")
+                .and_then(|t| t.get("romaji"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            airing: matches!(status, Some("RELEASING" | "NOT_YET_RELEASED" | "HIATUS")),
+        })
+    }
+
     async fn request(&self, source_id: &str, query: &str) -> Result<serde_json::Value, FeedError> {
         let source_id_num = Self::validate_id(source_id)?;
+        let response_json = self
+            .post_query(query, serde_json::json!({ "id": source_id_num }))
+            .await?;
+
+        self.check_api_errors(&response_json)?;
+
+        Ok(response_json)
+    }
+
+    async fn post_query(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value, FeedError> {
         let json = serde_json::json!({
             "query": query,
-            "variables": { "id": source_id_num }
+            "variables": variables
         });
 
         let request = self
@@ -74,11 +441,7 @@ impl AniListPlatform {
             .body(json.to_string());
         let response = self.send(request).await?;
         let body = response.text().await?;
-        let response_json: serde_json::Value = serde_json::from_str(&body)?;
-
-        self.check_api_errors(&response_json)?;
-
-        Ok(response_json)
+        Ok(serde_json::from_str(&body)?)
     }
 
     fn check_api_errors(&self, resp: &Value) -> Result<(), FeedError> {
@@ -161,6 +524,22 @@ impl AniListPlatform {
             })
     }
 
+    /// Reads `title.english`, `title.romaji` and `title.native`, whichever are set.
+    fn get_title_variants(&self, media: &Map<String, Value>) -> TitleVariants {
+        let title = |key: &str| {
+            media
+                .get("title")
+                .and_then(|t| t.get(key))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+        TitleVariants {
+            english: title("english"),
+            romanized: title("romaji"),
+            native: title("native"),
+        }
+    }
+
     fn get_description(&self, media: &Map<String, Value>) -> Result<String, FeedError> {
         media
             .get("description")
@@ -185,12 +564,15 @@ impl AniListPlatform {
     async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
         if self.limiter.check().is_err() {
             info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
         }
         self.limiter.until_ready().await;
 
         let req = request.build()?;
         debug!("Making request to: {}", req.url());
-        self.client.execute(req).await
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
     }
 
     /// Validate source_id format (should be numeric for AniList)
@@ -236,6 +618,7 @@ impl Platform for AniListPlatform {
             id,
             title,
             published,
+            ..Default::default()
         })
     }
 
@@ -249,7 +632,7 @@ impl Platform for AniListPlatform {
         let query = r#"
             query ($id: Int) {
               Media(id: $id, type: ANIME) {
-                title { romaji }
+                title { romaji english native }
                 description(asHtml: false)
                 coverImage {
                     extraLarge
@@ -261,6 +644,7 @@ impl Platform for AniListPlatform {
 
         let media = self.get_media(&response_json, &source_id)?;
         let name = self.get_title_romaji(media)?;
+        let titles = self.get_title_variants(media);
         let description = self.get_description(media)?;
         let image_url = Some(self.get_cover_image(media)?);
 
@@ -271,6 +655,7 @@ impl Platform for AniListPlatform {
             description,
             source_url: self.get_source_url_from_id(id),
             image_url,
+            titles,
         })
     }
 
@@ -300,3 +685,89 @@ impl Hash for AniListPlatform {
         self.base.info.api_url.hash(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_list_entries_dedupes_and_marks_airing() {
+        let lists = serde_json::json!([
+            {
+                "entries": [
+                    { "media": { "id": 1, "status": "RELEASING", "title": { "romaji": "One" } } },
+                    { "media": { "id": 2, "status": "FINISHED", "title": { "romaji": "Two" } } },
+                    { "media": { "status": "RELEASING" } }
+                ]
+            },
+            {
+                "entries": [
+                    { "media": { "id": 1, "status": "RELEASING", "title": { "romaji": "One" } } }
+                ]
+            }
+        ]);
+
+        let entries = AniListPlatform::parse_list_entries(lists.as_array().unwrap());
+
+        assert_eq!(
+            entries,
+            vec![
+                AniListListEntry {
+                    media_id: 1,
+                    title: "One".to_string(),
+                    airing: true,
+                },
+                AniListListEntry {
+                    media_id: 2,
+                    title: "Two".to_string(),
+                    airing: false,
+                },
+            ]
+        );
+    }
+    #[test]
+    fn parse_manga_entries_reads_alternative_titles() {
+        let lists = serde_json::json!([{
+            "entries": [{
+                "media": {
+                    "id": 105398,
+                    "idMal": 116778,
+                    "status": "RELEASING",
+                    "title": { "romaji": "Chainsaw Man", "english": null, "native": "チェンソーマン" },
+                    "synonyms": ["CSM"]
+                }
+            }]
+        }]);
+
+        let entries = AniListPlatform::parse_manga_entries(lists.as_array().unwrap());
+
+        assert_eq!(
+            entries,
+            vec![AniListMangaEntry {
+                media_id: 105398,
+                mal_id: Some(116778),
+                title: "Chainsaw Man".to_string(),
+                alt_titles: vec!["チェンソーマン".to_string(), "CSM".to_string()],
+                releasing: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn get_title_variants_skips_null_titles() {
+        let media = serde_json::json!({
+            "title": { "romaji": "Sousou no Frieren", "english": null, "native": "葬送のフリーレン" }
+        });
+
+        let titles = AniListPlatform::new().get_title_variants(media.as_object().unwrap());
+
+        assert_eq!(
+            titles,
+            TitleVariants {
+                english: None,
+                romanized: Some("Sousou no Frieren".to_string()),
+                native: Some("葬送のフリーレン".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Bluesky account platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroU32;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use governor::Quota;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use serde_json::Value;
+use wreq::Client;
+use wreq_util::Emulation;
+
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+
+/// Longest post text used as an item title, in characters.
+const MAX_TITLE_LEN: usize = 200;
+
+/// Number of posts fetched per check. Reposts are skipped, so this leaves room for a few.
+const FEED_LIMIT: &str = "10";
+
+/// Bluesky platform following the posts of an account through the public AppView API.
+pub struct BlueskyPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+}
+
+impl BlueskyPlatform {
+    /// Creates a new Bluesky platform with rate limiting.
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .emulation(Emulation::Chrome137)
+            .build()
+            .unwrap();
+
+        let info = PlatformInfo {
+            name: "Bluesky".to_string(),
+            feed_item_name: "Post".to_string(),
+            api_hostname: "public.api.bsky.app".to_string(),
+            api_domain: "bsky.app".to_string(),
+            api_url: "https://public.api.bsky.app".to_string(),
+            copyright_notice: "Posts © their respective authors on Bluesky".to_string(),
+            logo_url: "https://bsky.app/static/apple-touch-icon.png".to_string(),
+            // Accounts are polled along with series feeds
+            tags: "series,social".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0x1185FE),
+            emoji: "🦋".to_string(),
+        };
+
+        // See https://docs.bsky.app/docs/advanced-guides/rate-limits: 3000 requests per 5 minutes
+        // per IP. Stay well below that.
+        let quota = Quota::per_minute(NonZeroU32::new(120).unwrap());
+        let limiter = RateLimiter::direct(quota);
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+        }
+    }
+
+    fn check_resp_errors(&self, resp: &Value) -> Result<(), FeedError> {
+        if let Some(error) = resp.get("error").and_then(|v| v.as_str()) {
+            let message = resp
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or(error);
+            return Err(FeedError::ApiError {
+                message: message.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn get_str<'a>(&self, value: &'a Value, field: &str) -> Result<&'a str, FeedError> {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FeedError::MissingField {
+                field: field.to_string(),
+            })
+    }
+
+    /// Returns the newest post written by the account, skipping reposts.
+    fn get_latest_post<'a>(&self, resp: &'a Value, actor: &str) -> Result<&'a Value, FeedError> {
+        resp.get("feed")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FeedError::MissingField {
+                field: "feed".to_string(),
+            })?
+            .iter()
+            .filter(|entry| entry.get("reason").is_none())
+            .find_map(|entry| entry.get("post"))
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: actor.to_string(),
+            })
+    }
+
+    fn get_post_published(&self, post: &Value) -> Result<DateTime<Utc>, FeedError> {
+        let record = post.get("record").ok_or_else(|| FeedError::MissingField {
+            field: "post.record".to_string(),
+        })?;
+        let date_str = self.get_str(record, "createdAt")?;
+
+        DateTime::parse_from_rfc3339(date_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| FeedError::InvalidTime {
+                time: date_str.to_string(),
+            })
+    }
+
+    fn get_post_text(post: &Value) -> String {
+        post.get("record")
+            .and_then(|r| r.get("text"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    /// Returns the web link of a post from its `at://` uri.
+    fn get_post_url(&self, post: &Value) -> Result<String, FeedError> {
+        let uri = self.get_str(post, "uri")?;
+        let rkey = post_rkey(uri).ok_or_else(|| FeedError::UnexpectedResult {
+            message: format!("Invalid post uri: {uri}"),
+        })?;
+        let handle = post
+            .get("author")
+            .map(|author| self.get_str(author, "handle"))
+            .transpose()?
+            .ok_or_else(|| FeedError::MissingField {
+                field: "post.author".to_string(),
+            })?;
+        Ok(format!(
+            "https://{}/profile/{handle}/post/{rkey}",
+            self.base.info.api_domain
+        ))
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    async fn send_get_json(&self, request: wreq::RequestBuilder) -> Result<Value, FeedError> {
+        let response = self.send(request).await?;
+
+        let body = response.text().await?;
+        let resp: Value = serde_json::from_str(&body)?;
+        self.check_resp_errors(&resp)?;
+        Ok(resp)
+    }
+}
+
+/// Returns the record key, the last segment of an `at://` post uri.
+fn post_rkey(uri: &str) -> Option<&str> {
+    uri.strip_prefix("at://")?
+        .rsplit('/')
+        .next()
+        .filter(|rkey| !rkey.is_empty())
+}
+
+/// Returns a preview image for a post embed: the first image, a video thumbnail or a link card.
+fn preview_image(embed: &Value) -> Option<String> {
+    let image = match embed.get("$type").and_then(|v| v.as_str())? {
+        "app.bsky.embed.images#view" => embed.get("images")?.get(0)?.get("fullsize"),
+        "app.bsky.embed.video#view" => embed.get("thumbnail"),
+        "app.bsky.embed.external#view" => embed.get("external")?.get("thumb"),
+        "app.bsky.embed.recordWithMedia#view" => return preview_image(embed.get("media")?),
+        _ => None,
+    };
+    image.and_then(|v| v.as_str()).map(str::to_string)
+}
+
+/// Shortens post text to an item title, falling back to the record key for posts without text.
+fn post_title(text: &str, rkey: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.is_empty() {
+        return format!("Post {rkey}");
+    }
+    if line.chars().count() > MAX_TITLE_LEN {
+        let mut title: String = line.chars().take(MAX_TITLE_LEN - 1).collect();
+        title.push('…');
+        title
+    } else {
+        line.to_string()
+    }
+}
+
+#[async_trait]
+impl Platform for BlueskyPlatform {
+    async fn fetch_source(&self, handle: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {handle}",
+            self.base.info.name
+        );
+
+        let request = self
+            .client
+            .get(format!(
+                "{}/xrpc/app.bsky.actor.getProfile",
+                self.base.info.api_url
+            ))
+            .query(&[("actor", handle)]);
+        let profile = self.send_get_json(request).await?;
+
+        let did = self.get_str(&profile, "did")?;
+        let name = profile
+            .get("displayName")
+            .and_then(|v| v.as_str())
+            .filter(|name| !name.trim().is_empty())
+            .map_or_else(|| format!("@{handle}"), |name| name.trim().to_string());
+        let description = profile
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let image_url = profile
+            .get("avatar")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        Ok(FeedSource {
+            id: handle.to_string(),
+            // The DID stays the same when the account changes its handle
+            items_id: did.to_string(),
+            name,
+            description,
+            source_url: self.get_source_url_from_id(handle),
+            image_url,
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, did: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {did}",
+            self.base.info.name
+        );
+
+        let request = self
+            .client
+            .get(format!(
+                "{}/xrpc/app.bsky.feed.getAuthorFeed",
+                self.base.info.api_url
+            ))
+            .query(&[
+                ("actor", did),
+                ("limit", FEED_LIMIT),
+                ("filter", "posts_no_replies"),
+            ]);
+        let resp = self.send_get_json(request).await?;
+
+        let post = self.get_latest_post(&resp, did)?;
+        let uri = self.get_str(post, "uri")?;
+        let rkey = post_rkey(uri).unwrap_or(uri);
+        let text = Self::get_post_text(post);
+
+        Ok(FeedItem {
+            id: uri.to_string(),
+            title: post_title(&text, rkey),
+            published: self.get_post_published(post)?,
+            content: Some(text).filter(|text| !text.is_empty()),
+            item_url: Some(self.get_post_url(post)?),
+            image_url: post.get("embed").and_then(preview_image),
+            ..Default::default()
+        })
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        // https://bsky.app/profile/<handle>
+        Ok(self.base.get_nth_path_from_url(source_url, 1)?)
+    }
+
+    fn get_source_url_from_id(&self, handle: &str) -> String {
+        format!("https://{}/profile/{handle}", self.base.info.api_domain)
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+}
+
+impl PartialEq for BlueskyPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.api_url == other.base.info.api_url
+    }
+}
+
+impl Eq for BlueskyPlatform {}
+
+impl Hash for BlueskyPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.api_url.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn latest_post_skips_reposts() {
+        let platform = BlueskyPlatform::new();
+        let resp = json!({
+            "feed": [
+                {
+                    "post": { "uri": "at://did:plc:other/app.bsky.feed.post/aaa" },
+                    "reason": { "$type": "app.bsky.feed.defs#reasonRepost" }
+                },
+                {
+                    "post": {
+                        "uri": "at://did:plc:me/app.bsky.feed.post/3kxyz",
+                        "author": { "handle": "me.bsky.social" }
+                    }
+                }
+            ]
+        });
+
+        let post = platform.get_latest_post(&resp, "did:plc:me").unwrap();
+
+        assert_eq!(
+            platform.get_post_url(post).unwrap(),
+            "https://bsky.app/profile/me.bsky.social/post/3kxyz"
+        );
+    }
+
+    #[test]
+    fn preview_image_reads_nested_media() {
+        let embed = json!({
+            "$type": "app.bsky.embed.recordWithMedia#view",
+            "media": {
+                "$type": "app.bsky.embed.images#view",
+                "images": [{ "fullsize": "https://cdn.bsky.app/img/1.jpg" }]
+            }
+        });
+        assert_eq!(
+            preview_image(&embed).as_deref(),
+            Some("https://cdn.bsky.app/img/1.jpg")
+        );
+        assert_eq!(preview_image(&json!({ "$type": "unknown" })), None);
+    }
+
+    #[test]
+    fn post_title_uses_first_line_or_rkey() {
+        assert_eq!(post_title("Hello\nworld", "3kxyz"), "Hello");
+        assert_eq!(post_title("", "3kxyz"), "Post 3kxyz");
+        assert_eq!(
+            post_title(&"a".repeat(MAX_TITLE_LEN + 10), "3kxyz")
+                .chars()
+                .count(),
+            MAX_TITLE_LEN
+        );
+    }
+
+    #[test]
+    fn source_url_roundtrip() {
+        let platform = BlueskyPlatform::new();
+        let url = platform.get_source_url_from_id("me.bsky.social");
+
+        assert_eq!(url, "https://bsky.app/profile/me.bsky.social");
+        assert_eq!(
+            platform.get_id_from_source_url(&url).unwrap(),
+            "me.bsky.social"
+        );
+    }
+}
@@ -24,6 +24,7 @@ use crate::feed::FeedItem;
 use crate::feed::FeedSource;
 use crate::feed::Platform;
 use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
 use crate::feed::error::FeedError;
 
 /// Comick API platform for manga tracking.
@@ -57,14 +58,19 @@ impl ComickPlatform {
                 "https://comick.dev/_next/image?url=%2Fstatic%2Ficons%2Funicorn-64.png&w=144&q=75"
                     .to_string(),
             tags: "series".to_string(),
+            // Items carry the comic's ID, not the chapter's
+            unique_item_ids: false,
+            accent_color: Some(0xFFC107),
+            emoji: "📚".to_string(),
         };
 
         // NOTE: Not documented, but we will use the ratelimit described in "x-ratelimit-limit" and
         // "x-ratelimit-reset" headers
-        let limiter = RateLimiter::direct(Quota::per_minute(NonZeroU32::new(200).unwrap()));
+        let quota = Quota::per_minute(NonZeroU32::new(200).unwrap());
+        let limiter = RateLimiter::direct(quota);
 
         Self {
-            base: BasePlatform::new(info),
+            base: BasePlatform::new(info).with_quota(quota),
             client,
             limiter,
         }
@@ -211,12 +217,15 @@ impl ComickPlatform {
     async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
         if self.limiter.check().is_err() {
             info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
         }
         self.limiter.until_ready().await;
 
         let req = request.build()?;
         debug!("Making request to: {}", req.url());
-        self.client.execute(req).await
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
     }
 
     async fn send_get_json(&self, request: wreq::RequestBuilder) -> Result<Json, FeedError> {
@@ -257,6 +266,7 @@ impl Platform for ComickPlatform {
             name,
             source_url,
             image_url,
+            titles: TitleVariants::default(),
             description,
         })
     }
@@ -282,6 +292,7 @@ impl Platform for ComickPlatform {
             id: hid.to_string(),
             title,
             published,
+            ..Default::default()
         })
     }
 
@@ -0,0 +1,308 @@
+//! crates.io release platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroU32;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use governor::Quota;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use serde_json::Value;
+use wreq::Client;
+use wreq::header::HeaderMap;
+use wreq::header::HeaderValue;
+use wreq::header::USER_AGENT;
+
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+
+/// crates.io platform notifying on new, non-yanked versions of a crate.
+pub struct CratesIoPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+}
+
+impl CratesIoPlatform {
+    /// Creates a new crates.io platform with rate limiting.
+    pub fn new() -> Self {
+        // See https://crates.io/data-access: "We require that all API users provide a
+        // user-agent header that allows us to uniquely identify your application"
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_static("pwr-bot/0.1 (https://github.com/FAZuH/pwr-bot)"),
+        );
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Failed to create client");
+
+        let info = PlatformInfo {
+            name: "crates.io".to_string(),
+            feed_item_name: "Version".to_string(),
+            api_hostname: "crates.io".to_string(),
+            api_domain: "crates.io".to_string(),
+            api_url: "https://crates.io/api/v1".to_string(),
+            copyright_notice: "Crate metadata from crates.io".to_string(),
+            logo_url: "https://crates.io/assets/cargo.png".to_string(),
+            // Crates are polled along with series feeds
+            tags: "series,release".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0xFFC832),
+            emoji: "📦".to_string(),
+        };
+
+        // See https://crates.io/data-access: "A maximum of 1 request per second"
+        let quota = Quota::per_second(NonZeroU32::new(1).unwrap());
+        let limiter = RateLimiter::direct(quota);
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+        }
+    }
+
+    fn check_resp_errors(&self, resp: &Value) -> Result<(), FeedError> {
+        if let Some(errors) = resp.get("errors")
+            && let Some(error_array) = errors.as_array()
+            && let Some(first_error) = error_array.first()
+        {
+            let message = first_error
+                .get("detail")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown API error")
+                .to_string();
+
+            return Err(FeedError::ApiError { message });
+        }
+        Ok(())
+    }
+
+    fn get_str<'a>(&self, value: &'a Value, field: &str) -> Result<&'a str, FeedError> {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FeedError::MissingField {
+                field: field.to_string(),
+            })
+    }
+
+    fn get_crate<'a>(&self, resp: &'a Value) -> Result<&'a Value, FeedError> {
+        resp.get("crate").ok_or_else(|| FeedError::MissingField {
+            field: "crate".to_string(),
+        })
+    }
+
+    /// Returns the most recently published version that was not yanked.
+    fn get_latest_version<'a>(&self, resp: &'a Value, name: &str) -> Result<&'a Value, FeedError> {
+        resp.get("versions")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FeedError::MissingField {
+                field: "versions".to_string(),
+            })?
+            .iter()
+            .filter(|version| {
+                !version
+                    .get("yanked")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+            })
+            .max_by_key(|version| self.get_version_published(version).ok())
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: name.to_string(),
+            })
+    }
+
+    fn get_version_published(&self, version: &Value) -> Result<DateTime<Utc>, FeedError> {
+        let date_str = self.get_str(version, "created_at")?;
+
+        DateTime::parse_from_rfc3339(date_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| FeedError::InvalidTime {
+                time: date_str.to_string(),
+            })
+    }
+
+    /// Returns where a version's changes are described.
+    ///
+    /// GitHub repositories link to their releases page. Other crates link to the version's
+    /// page on crates.io, which shows its readme.
+    fn get_changelog_url(&self, krate: &Value, name: &str, num: &str) -> String {
+        let repository = krate
+            .get("repository")
+            .and_then(|v| v.as_str())
+            .map(|url| url.trim().trim_end_matches('/').trim_end_matches(".git"))
+            .filter(|url| url.starts_with("https://github.com/"));
+        match repository {
+            Some(repository) => format!("{repository}/releases"),
+            None => format!("{}/{num}", self.get_source_url_from_id(name)),
+        }
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    async fn fetch_crate(&self, name: &str) -> Result<Value, FeedError> {
+        let request = self
+            .client
+            .get(format!("{}/crates/{name}", self.base.info.api_url));
+        let response = self.send(request).await?;
+
+        let body = response.text().await?;
+        let resp: Value = serde_json::from_str(&body)?;
+        self.check_resp_errors(&resp)?;
+        Ok(resp)
+    }
+}
+
+#[async_trait]
+impl Platform for CratesIoPlatform {
+    async fn fetch_source(&self, name: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {name}",
+            self.base.info.name
+        );
+
+        let resp = self.fetch_crate(name).await?;
+        let krate = self.get_crate(&resp)?;
+        // crates.io resolves `-` and `_` to the same crate, so use its canonical name
+        let name = self.get_str(krate, "name")?;
+        let description = krate
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        Ok(FeedSource {
+            id: name.to_string(),
+            items_id: name.to_string(),
+            name: name.to_string(),
+            description,
+            source_url: self.get_source_url_from_id(name),
+            image_url: None,
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, name: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {name}",
+            self.base.info.name
+        );
+
+        let resp = self.fetch_crate(name).await?;
+        let krate = self.get_crate(&resp)?;
+        let version = self.get_latest_version(&resp, name)?;
+        let num = self.get_str(version, "num")?;
+
+        Ok(FeedItem {
+            id: num.to_string(),
+            title: num.to_string(),
+            published: self.get_version_published(version)?,
+            item_url: Some(self.get_changelog_url(krate, name, num)),
+            ..Default::default()
+        })
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        // https://crates.io/crates/<name>
+        Ok(self.base.get_nth_path_from_url(source_url, 1)?)
+    }
+
+    fn get_source_url_from_id(&self, name: &str) -> String {
+        format!("https://{}/crates/{name}", self.base.info.api_domain)
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+}
+
+impl PartialEq for CratesIoPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.api_url == other.base.info.api_url
+    }
+}
+
+impl Eq for CratesIoPlatform {}
+
+impl Hash for CratesIoPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.api_url.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn latest_version_skips_yanked() {
+        let platform = CratesIoPlatform::new();
+        let resp = json!({
+            "versions": [
+                { "num": "1.3.0", "created_at": "2026-10-12T10:00:00.123456Z", "yanked": true },
+                { "num": "1.2.3", "created_at": "2026-10-05T10:00:00.000000Z", "yanked": false },
+                { "num": "1.2.2", "created_at": "2026-09-01T10:00:00+00:00", "yanked": false }
+            ]
+        });
+
+        let version = platform.get_latest_version(&resp, "serde").unwrap();
+
+        assert_eq!(version.get("num").and_then(|v| v.as_str()), Some("1.2.3"));
+    }
+
+    #[test]
+    fn changelog_url_prefers_github_releases() {
+        let platform = CratesIoPlatform::new();
+
+        let krate = json!({ "repository": "https://github.com/serde-rs/serde.git" });
+        assert_eq!(
+            platform.get_changelog_url(&krate, "serde", "1.2.3"),
+            "https://github.com/serde-rs/serde/releases"
+        );
+
+        let krate = json!({ "repository": "https://gitlab.com/foo/bar" });
+        assert_eq!(
+            platform.get_changelog_url(&krate, "bar", "0.1.0"),
+            "https://crates.io/crates/bar/0.1.0"
+        );
+    }
+
+    #[test]
+    fn source_url_roundtrip() {
+        let platform = CratesIoPlatform::new();
+        let url = platform.get_source_url_from_id("serde_json");
+
+        assert_eq!(url, "https://crates.io/crates/serde_json");
+        assert_eq!(platform.get_id_from_source_url(&url).unwrap(), "serde_json");
+    }
+}
@@ -0,0 +1,419 @@
+//! Owner-configured JSON API platform.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use wreq::Client;
+use wreq::header::HeaderMap;
+use wreq::header::HeaderValue;
+use wreq::header::USER_AGENT;
+
+use super::arbitrary_host_limiter;
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+
+/// How items are read from one custom JSON endpoint.
+///
+/// Item paths are evaluated against each item selected by `items`.
+#[derive(Clone, Debug)]
+pub struct JsonFeedMapping {
+    pub name: String,
+    pub items: JsonPath,
+    pub id: JsonPath,
+    pub title: JsonPath,
+    pub url: Option<JsonPath>,
+    pub published: Option<JsonPath>,
+}
+
+impl JsonFeedMapping {
+    /// Parses the JSONPath expressions of a mapping.
+    pub fn parse(
+        name: &str,
+        items: &str,
+        id: &str,
+        title: &str,
+        url: Option<&str>,
+        published: Option<&str>,
+    ) -> Result<Self, FeedError> {
+        Ok(Self {
+            name: name.to_string(),
+            items: parse_path(items)?,
+            id: parse_path(id)?,
+            title: parse_path(title)?,
+            url: url.map(parse_path).transpose()?,
+            published: published.map(parse_path).transpose()?,
+        })
+    }
+
+    /// Reads the newest item of a response.
+    ///
+    /// Items are compared by publish time when the mapping has one. Otherwise the first
+    /// item in the response is the newest.
+    pub fn latest_item(&self, resp: &Value, source_id: &str) -> Result<FeedItem, FeedError> {
+        let items = self
+            .items
+            .query(resp)
+            .all()
+            .into_iter()
+            .map(|item| self.read_item(item))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let latest = if self.published.is_some() {
+            items.into_iter().max_by_key(|item| item.published)
+        } else {
+            items.into_iter().next()
+        };
+        latest.ok_or_else(|| FeedError::ItemNotFound {
+            source_id: source_id.to_string(),
+        })
+    }
+
+    fn read_item(&self, item: &Value) -> Result<FeedItem, FeedError> {
+        let published = match &self.published {
+            Some(path) => parse_time(query_one(path, item)?)?,
+            // Without a publish time, the item counts as published when first seen
+            None => Utc::now(),
+        };
+        Ok(FeedItem {
+            id: query_string(&self.id, item)?,
+            title: query_string(&self.title, item)?,
+            published,
+            item_url: self
+                .url
+                .as_ref()
+                .map(|path| query_string(path, item))
+                .transpose()?,
+            ..Default::default()
+        })
+    }
+}
+
+fn parse_path(path: &str) -> Result<JsonPath, FeedError> {
+    JsonPath::parse(path.trim()).map_err(|e| FeedError::InvalidJsonPath {
+        path: path.to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn query_one<'a>(path: &JsonPath, item: &'a Value) -> Result<&'a Value, FeedError> {
+    path.query(item)
+        .first()
+        .ok_or_else(|| FeedError::MissingField {
+            field: path.to_string(),
+        })
+}
+
+/// Reads a string or number as text.
+fn query_string(path: &JsonPath, item: &Value) -> Result<String, FeedError> {
+    match query_one(path, item)? {
+        Value::String(s) if !s.trim().is_empty() => Ok(s.trim().to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        _ => Err(FeedError::MissingField {
+            field: path.to_string(),
+        }),
+    }
+}
+
+/// Reads an RFC 3339 or RFC 2822 time, or a Unix timestamp in seconds or milliseconds.
+fn parse_time(value: &Value) -> Result<DateTime<Utc>, FeedError> {
+    if let Some(timestamp) = value.as_i64() {
+        // Second timestamps won't reach 10^12 until the year 33658
+        let parsed = if timestamp.abs() >= 1_000_000_000_000 {
+            DateTime::from_timestamp_millis(timestamp)
+        } else {
+            DateTime::from_timestamp(timestamp, 0)
+        };
+        return parsed.ok_or(FeedError::InvalidTimestamp { timestamp });
+    }
+
+    let time = value.as_str().unwrap_or_default().trim();
+    DateTime::parse_from_rfc3339(time)
+        .or_else(|_| DateTime::parse_from_rfc2822(time))
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| FeedError::InvalidTime {
+            time: value.to_string(),
+        })
+}
+
+/// Platform reading items from JSON APIs the bot owner configured at runtime.
+///
+/// Each configured endpoint is a feed whose source url is the endpoint itself. This lets
+/// operators follow niche APIs without writing a new [`Platform`].
+pub struct CustomJsonPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+    /// Mappings by endpoint url.
+    feeds: RwLock<HashMap<String, JsonFeedMapping>>,
+}
+
+impl CustomJsonPlatform {
+    /// Creates a new custom JSON platform with no feeds configured.
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("pwr-bot/0.1"));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Failed to create client");
+
+        let info = PlatformInfo {
+            name: "Custom JSON".to_string(),
+            feed_item_name: "Item".to_string(),
+            // Endpoints are configured at runtime, so there is no API domain to match on
+            api_hostname: String::new(),
+            api_domain: String::new(),
+            api_url: String::new(),
+            copyright_notice: "Items © their respective sources".to_string(),
+            logo_url:
+                "https://upload.wikimedia.org/wikipedia/commons/thumb/c/c9/JSON_vector_logo.svg/128px-JSON_vector_logo.svg.png"
+                    .to_string(),
+            tags: "series,custom".to_string(),
+            unique_item_ids: true,
+            // Sources vary, so feeds keep the default color unless overridden
+            accent_color: None,
+            emoji: "🧩".to_string(),
+        };
+
+        let (quota, limiter) = arbitrary_host_limiter();
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+            feeds: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Adds or replaces the mapping of an endpoint.
+    pub fn set_feed(&self, url: &str, mapping: JsonFeedMapping) {
+        if let Ok(mut feeds) = self.feeds.write() {
+            feeds.insert(url.trim().to_string(), mapping);
+        }
+    }
+
+    /// Removes the mapping of an endpoint. Returns whether one existed.
+    pub fn remove_feed(&self, url: &str) -> bool {
+        self.feeds
+            .write()
+            .map(|mut feeds| feeds.remove(url.trim()).is_some())
+            .unwrap_or(false)
+    }
+
+    fn get_mapping(&self, url: &str) -> Result<JsonFeedMapping, FeedError> {
+        self.feeds
+            .read()
+            .ok()
+            .and_then(|feeds| feeds.get(url).cloned())
+            .ok_or_else(|| FeedError::SourceNotFound {
+                source_id: url.to_string(),
+            })
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    async fn fetch_json(&self, url: &str) -> Result<Value, FeedError> {
+        let response = self.send(self.client.get(url)).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!("{url} returned HTTP {}", status.as_u16()),
+            });
+        }
+
+        let body = response.text().await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+#[async_trait]
+impl Platform for CustomJsonPlatform {
+    async fn fetch_source(&self, url: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {url}",
+            self.base.info.name
+        );
+
+        let mapping = self.get_mapping(url)?;
+        // Makes sure the mapping still matches the response before subscribing
+        let resp = self.fetch_json(url).await?;
+        mapping.latest_item(&resp, url)?;
+
+        Ok(FeedSource {
+            id: url.to_string(),
+            items_id: url.to_string(),
+            name: mapping.name,
+            description: String::new(),
+            source_url: self.get_source_url_from_id(url),
+            image_url: None,
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, url: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {url}",
+            self.base.info.name
+        );
+
+        let mapping = self.get_mapping(url)?;
+        let resp = self.fetch_json(url).await?;
+        mapping.latest_item(&resp, url)
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        let source_url = source_url.trim();
+        if !self.claims_source_url(source_url) {
+            return Err(FeedError::UnsupportedUrl {
+                url: source_url.to_string(),
+            });
+        }
+        Ok(source_url)
+    }
+
+    fn get_source_url_from_id(&self, url: &str) -> String {
+        url.to_string()
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+
+    fn claims_source_url(&self, source_url: &str) -> bool {
+        self.feeds
+            .read()
+            .is_ok_and(|feeds| feeds.contains_key(source_url.trim()))
+    }
+}
+
+impl PartialEq for CustomJsonPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.name == other.base.info.name
+    }
+}
+
+impl Eq for CustomJsonPlatform {}
+
+impl Hash for CustomJsonPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.name.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn mapping(published: Option<&str>) -> JsonFeedMapping {
+        JsonFeedMapping::parse(
+            "Releases",
+            "$.data.releases[*]",
+            "$.id",
+            "$.name",
+            Some("$.links.html"),
+            published,
+        )
+        .unwrap()
+    }
+
+    fn response() -> Value {
+        json!({
+            "data": {
+                "releases": [
+                    {
+                        "id": 41,
+                        "name": "v4.1",
+                        "links": { "html": "https://example.com/r/41" },
+                        "date": "2026-10-01T00:00:00Z"
+                    },
+                    {
+                        "id": 42,
+                        "name": "v4.2",
+                        "links": { "html": "https://example.com/r/42" },
+                        "date": 1792540800
+                    }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn latest_item_uses_publish_time() {
+        let item = mapping(Some("$.date"))
+            .latest_item(&response(), "https://example.com/api")
+            .unwrap();
+
+        assert_eq!(item.id, "42");
+        assert_eq!(item.title, "v4.2");
+        assert_eq!(item.item_url.as_deref(), Some("https://example.com/r/42"));
+    }
+
+    #[test]
+    fn latest_item_defaults_to_first() {
+        let item = mapping(None)
+            .latest_item(&response(), "https://example.com/api")
+            .unwrap();
+
+        assert_eq!(item.id, "41");
+    }
+
+    #[test]
+    fn invalid_path_is_rejected() {
+        assert!(matches!(
+            JsonFeedMapping::parse("Bad", "$.data[", "$.id", "$.name", None, None),
+            Err(FeedError::InvalidJsonPath { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_time_accepts_strings_and_timestamps() {
+        let expected = DateTime::from_timestamp(1792540800, 0).unwrap();
+        assert_eq!(parse_time(&json!(1792540800)).unwrap(), expected);
+        assert_eq!(parse_time(&json!(1792540800000i64)).unwrap(), expected);
+        assert_eq!(parse_time(&json!(expected.to_rfc3339())).unwrap(), expected);
+        assert!(parse_time(&json!("soon")).is_err());
+    }
+
+    #[test]
+    fn only_configured_urls_are_claimed() {
+        let platform = CustomJsonPlatform::new();
+        platform.set_feed("https://example.com/api", mapping(None));
+
+        assert!(platform.claims_source_url("https://example.com/api"));
+        assert!(!platform.claims_source_url("https://example.com/other"));
+        assert!(platform.remove_feed("https://example.com/api"));
+        assert!(!platform.claims_source_url("https://example.com/api"));
+    }
+}
@@ -27,8 +27,23 @@ use crate::feed::FeedItem;
 use crate::feed::FeedSource;
 use crate::feed::Platform;
 use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
 use crate::feed::error::FeedError;
 
+/// Manga found by a MangaDex title search.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MangaDexSearchResult {
+    pub id: String,
+    /// Preferred title, see [`MangaDexPlatform::search_manga`].
+    pub title: String,
+    /// Every title and alternative title, in any language.
+    pub titles: Vec<String>,
+    /// MyAnimeList ID from the manga's links, if MangaDex has one.
+    pub mal_id: Option<i32>,
+    /// Whether chapters can still be released, i.e. the manga is ongoing or on hiatus.
+    pub ongoing: bool,
+}
+
 /// MangaDex API platform for manga tracking.
 type Json<'a> = &'a Map<String, Value>;
 
@@ -63,20 +78,83 @@ impl MangaDexPlatform {
             logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/png/manga-dex.png"
                 .to_string(),
             tags: "series".to_string(),
+            // The same chapter is uploaded separately per language and scanlation group
+            unique_item_ids: false,
+            accent_color: Some(0xFF6740),
+            emoji: "📖".to_string(),
         };
         // NOTE: See https://api.mangadex.org/docs/2-limitations/
         // Because GET /manga/{id} is not specified on #endpoint-specific-rate-limits,
         // therefore GET /manga/{id} has a default ratelimit of 5 requests per second
 
-        let limiter = RateLimiter::direct(Quota::per_second(NonZeroU32::new(5).unwrap()));
+        let quota = Quota::per_second(NonZeroU32::new(5).unwrap());
+        let limiter = RateLimiter::direct(quota);
 
         Self {
-            base: BasePlatform::new(info),
+            base: BasePlatform::new(info).with_quota(quota),
             client,
             limiter,
         }
     }
 
+    /// Searches manga by title, best matches first.
+    ///
+    /// # Performance
+    /// * API calls: 1
+    pub async fn search_manga(&self, title: &str) -> Result<Vec<MangaDexSearchResult>, FeedError> {
+        let request = self
+            .client
+            .get(format!("{}/manga", self.base.info.api_url))
+            .query(&[
+                ("title", title),
+                ("limit", "10"),
+                ("order[relevance]", "desc"),
+            ]);
+
+        let resp = self.send_get_json(request).await?;
+        let data = self.get_data_from_resp(&resp)?;
+        let results = data.as_array().ok_or_else(|| FeedError::UnexpectedResult {
+            message: "data field is not an array".to_string(),
+        })?;
+        Ok(results
+            .iter()
+            .filter_map(|manga| self.parse_search_result(manga))
+            .collect())
+    }
+
+    fn parse_search_result(&self, manga: &Value) -> Option<MangaDexSearchResult> {
+        let id = manga.get("id").and_then(|v| v.as_str())?.to_string();
+        let attr = self.get_attr_from_data(manga).ok()?;
+
+        let mut titles: Vec<String> = Vec::new();
+        let title_maps = attr.get("title").into_iter().chain(
+            attr.get("altTitles")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten(),
+        );
+        for map in title_maps.filter_map(|v| v.as_object()) {
+            titles.extend(map.values().filter_map(|v| v.as_str()).map(str::to_string));
+        }
+        let mal_id = attr
+            .get("links")
+            .and_then(|l| l.get("mal"))
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.parse().ok());
+        let status = attr.get("status").and_then(|v| v.as_str());
+
+        Some(MangaDexSearchResult {
+            id,
+            title: self
+                .get_title_from_attr(attr)
+                .ok()
+                .or_else(|| titles.first().cloned())?,
+            titles,
+            mal_id,
+            ongoing: matches!(status, Some("ongoing" | "hiatus")),
+        })
+    }
+
     fn check_resp_errors(&self, resp: &Value) -> Result<(), FeedError> {
         if let Some(errors) = resp.get("errors")
             && let Some(error_array) = errors.as_array()
@@ -111,31 +189,42 @@ impl MangaDexPlatform {
     /// Get title from `/manga/{id}` endpoint response.
     ///
     /// Priority: title.en > altTitles.en > title.ja-ro > altTitles.ja-ro > title.ja > altTitles.ja
-    /// I apologize in advance to the future me for this mess
     fn get_title_from_attr(&self, attr: Json) -> Result<String, FeedError> {
-        let langs = ["en", "ja-ro", "ja"];
+        ["en", "ja-ro", "ja"]
+            .into_iter()
+            .find_map(|lang| self.get_title_in_lang(attr, lang))
+            .ok_or_else(|| FeedError::MissingField {
+                field: "title or altTitles in en/ja-ro/ja".to_string(),
+            })
+    }
 
-        for lang in langs {
-            if let Some(title) = attr
-                .get("title")
-                .and_then(|t| t.get(lang))
-                .and_then(|v| v.as_str())
-            {
-                return Ok(title.to_string());
-            }
+    /// Get the title in `lang` from title, else from altTitles.
+    fn get_title_in_lang(&self, attr: Json, lang: &str) -> Option<String> {
+        let title = attr.get("title").and_then(|t| t.get(lang));
+        let alt_title = || {
+            attr.get("altTitles")
+                .and_then(|v| v.as_array())?
+                .iter()
+                .find_map(|alt_title| alt_title.get(lang))
+        };
+        title
+            .or_else(alt_title)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    }
 
-            if let Some(alt_titles) = attr.get("altTitles").and_then(|v| v.as_array()) {
-                for alt_title in alt_titles {
-                    if let Some(title) = alt_title.get(lang).and_then(|v| v.as_str()) {
-                        return Ok(title.to_string());
-                    }
-                }
-            }
+    /// Get the English, romanized and native titles, based on the manga's original
+    /// language, e.g., `ja` and `ja-ro`.
+    fn get_title_variants_from_attr(&self, attr: Json) -> TitleVariants {
+        let original = attr
+            .get("originalLanguage")
+            .and_then(|v| v.as_str())
+            .unwrap_or("ja");
+        TitleVariants {
+            english: self.get_title_in_lang(attr, "en"),
+            romanized: self.get_title_in_lang(attr, &format!("{original}-ro")),
+            native: self.get_title_in_lang(attr, original),
         }
-
-        Err(FeedError::MissingField {
-            field: "title or altTitles in en/ja-ro/ja".to_string(),
-        })
     }
 
     fn get_description_from_attr(&self, attr: Json) -> String {
@@ -229,6 +318,14 @@ impl MangaDexPlatform {
             .map(|s| s.to_string())
     }
 
+    fn get_chapter_name(&self, attributes: &Map<String, Value>) -> Option<String> {
+        attributes
+            .get("title")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    }
+
     fn get_chapter_publish_at(
         &self,
         attributes: &Map<String, Value>,
@@ -259,12 +356,15 @@ impl MangaDexPlatform {
     async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
         if self.limiter.check().is_err() {
             info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
         }
         self.limiter.until_ready().await;
 
         let req = request.build()?;
         debug!("Making request to: {}", req.url());
-        self.client.execute(req).await
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
     }
 
     async fn send_get_json(
@@ -299,6 +399,7 @@ impl Platform for MangaDexPlatform {
         let data = self.get_data_from_resp(&resp)?;
         let attr = self.get_attr_from_data(data)?;
         let name = self.get_title_from_attr(attr)?;
+        let titles = self.get_title_variants_from_attr(attr);
         let description = self.get_description_from_attr(attr);
 
         let cover_filename = self.get_cover_filename(data)?;
@@ -312,6 +413,7 @@ impl Platform for MangaDexPlatform {
             name,
             source_url,
             image_url,
+            titles,
             id: source_id,
             description,
         })
@@ -344,12 +446,15 @@ impl Platform for MangaDexPlatform {
 
         let id = self.get_chapter_id(chapter)?;
         let title = self.get_chapter_title(attributes)?;
+        let content = self.get_chapter_name(attributes);
         let published = self.get_chapter_publish_at(attributes)?;
 
         Ok(FeedItem {
             id,
             title,
             published,
+            content,
+            ..Default::default()
         })
     }
 
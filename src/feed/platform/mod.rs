@@ -1,9 +1,43 @@
 pub mod anilist;
+pub mod bluesky;
 pub mod comick;
+pub mod crates_io;
+pub mod custom_json;
 pub mod mangadex;
+pub mod nyaa;
 pub mod platforms;
+pub mod podcast;
+pub mod rss_feed;
+pub mod twitch;
+pub mod webtoon;
+pub mod youtube;
 
 pub use anilist::AniListPlatform;
+pub use bluesky::BlueskyPlatform;
 pub use comick::ComickPlatform;
+pub use crates_io::CratesIoPlatform;
+pub use custom_json::CustomJsonPlatform;
 pub use mangadex::MangaDexPlatform;
+pub use nyaa::NyaaPlatform;
+pub use platforms::PlatformChoice;
 pub use platforms::Platforms;
+pub use podcast::PodcastPlatform;
+pub use rss_feed::RssPlatform;
+pub use twitch::TwitchPlatform;
+pub use webtoon::WebtoonPlatform;
+pub use youtube::YoutubePlatform;
+
+/// Returns the quota and rate limiter of a platform whose feeds live on any host, e.g.
+/// podcast or RSS feeds. Requests go to many different hosts, so the limit only bounds
+/// the bot's overall load.
+pub(crate) fn arbitrary_host_limiter() -> (
+    governor::Quota,
+    governor::RateLimiter<
+        governor::state::direct::NotKeyed,
+        governor::state::InMemoryState,
+        governor::clock::QuantaClock,
+    >,
+) {
+    let quota = governor::Quota::per_minute(std::num::NonZeroU32::new(60).unwrap());
+    (quota, governor::RateLimiter::direct(quota))
+}
@@ -0,0 +1,353 @@
+//! Nyaa torrent search platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroU32;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use governor::Quota;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use rss::Channel;
+use rss::Item;
+use url::form_urlencoded;
+use wreq::Client;
+use wreq_util::Emulation;
+
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+use crate::feed::error::UrlParseError;
+
+/// Nyaa platform following the newest torrent of a search.
+///
+/// A source is the query string of a search url, e.g. `f=0&c=1_2&q=frieren+1080p`, so
+/// every search, and every filter applied to it, is its own feed.
+pub struct NyaaPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+}
+
+impl NyaaPlatform {
+    /// Creates a new Nyaa platform with rate limiting.
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .emulation(Emulation::Chrome137)
+            .build()
+            .unwrap();
+
+        let info = PlatformInfo {
+            name: "Nyaa".to_string(),
+            feed_item_name: "Release".to_string(),
+            api_hostname: "nyaa.si".to_string(),
+            api_domain: "nyaa.si".to_string(),
+            api_url: "https://nyaa.si".to_string(),
+            copyright_notice: "Torrents listed on Nyaa".to_string(),
+            logo_url: "https://nyaa.si/static/img/avatar/default.png".to_string(),
+            // Searches are polled along with series feeds
+            tags: "series,torrent".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0x0B73D6),
+            emoji: "🧲".to_string(),
+        };
+
+        // Nyaa doesn't document a rate limit. Stay gentle.
+        let quota = Quota::per_minute(NonZeroU32::new(30).unwrap());
+        let limiter = RateLimiter::direct(quota);
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+        }
+    }
+
+    /// Returns the newest torrent of a search.
+    fn get_latest_torrent<'a>(
+        &self,
+        channel: &'a Channel,
+        query: &str,
+    ) -> Result<&'a Item, FeedError> {
+        channel
+            .items()
+            .iter()
+            .max_by_key(|item| Self::get_pub_date(item).ok())
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: query.to_string(),
+            })
+    }
+
+    fn get_torrent_id(item: &Item) -> Result<String, FeedError> {
+        item.guid()
+            .map(|guid| guid.value().to_string())
+            .ok_or_else(|| FeedError::MissingField {
+                field: "item.guid".to_string(),
+            })
+    }
+
+    fn get_torrent_title(item: &Item) -> Result<String, FeedError> {
+        item.title()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| FeedError::MissingField {
+                field: "item.title".to_string(),
+            })
+    }
+
+    fn get_pub_date(item: &Item) -> Result<DateTime<Utc>, FeedError> {
+        let date_str = item.pub_date().ok_or_else(|| FeedError::MissingField {
+            field: "item.pubDate".to_string(),
+        })?;
+
+        DateTime::parse_from_rfc2822(date_str.trim())
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| FeedError::InvalidTime {
+                time: date_str.to_string(),
+            })
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    async fn fetch_channel(&self, query: &str) -> Result<Channel, FeedError> {
+        let url = format!("{}/?page=rss&{query}", self.base.info.api_url);
+        let response = self.send(self.client.get(url)).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!("Nyaa returned HTTP {}", status.as_u16()),
+            });
+        }
+
+        let body = response.bytes().await?;
+        Ok(Channel::read_from(&body[..])?)
+    }
+}
+
+/// Returns the value of a search parameter, if set and not empty.
+fn search_param(query: &str, key: &str) -> Option<String> {
+    form_urlencoded::parse(query.as_bytes())
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Names a search after its terms and uploader.
+fn search_name(query: &str) -> String {
+    match (search_param(query, "q"), search_param(query, "u")) {
+        (Some(terms), Some(user)) => format!("{terms} (by {user})"),
+        (Some(terms), None) => terms,
+        (None, Some(user)) => format!("Uploads by {user}"),
+        (None, None) => "All torrents".to_string(),
+    }
+}
+
+#[async_trait]
+impl Platform for NyaaPlatform {
+    async fn fetch_source(&self, query: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {query}",
+            self.base.info.name
+        );
+
+        // Makes sure the search is valid before subscribing to it
+        let channel = self.fetch_channel(query).await?;
+
+        Ok(FeedSource {
+            id: query.to_string(),
+            items_id: query.to_string(),
+            name: search_name(query),
+            description: channel.description().trim().to_string(),
+            source_url: self.get_source_url_from_id(query),
+            image_url: None,
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, query: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {query}",
+            self.base.info.name
+        );
+
+        let channel = self.fetch_channel(query).await?;
+        let torrent = self.get_latest_torrent(&channel, query)?;
+        let id = Self::get_torrent_id(torrent)?;
+
+        Ok(FeedItem {
+            title: Self::get_torrent_title(torrent)?,
+            published: Self::get_pub_date(torrent)?,
+            // The guid is the torrent's page on Nyaa
+            item_url: Some(id.clone()),
+            id,
+            ..Default::default()
+        })
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        // https://nyaa.si/?f=0&c=1_2&q=<terms>, or its RSS url with `page=rss&` in front
+        let source_url = source_url.trim();
+        if !source_url.contains(&self.base.info.api_domain) {
+            return Err(UrlParseError::InvalidFormat {
+                url: source_url.to_string(),
+            }
+            .into());
+        }
+        let query = source_url
+            .split_once('?')
+            .map(|(_, query)| query.split('#').next().unwrap_or_default())
+            .map(|query| query.strip_prefix("page=rss&").unwrap_or(query))
+            .filter(|query| !query.is_empty())
+            .ok_or_else(|| UrlParseError::MissingId {
+                url: source_url.to_string(),
+            })?;
+        Ok(query)
+    }
+
+    fn get_source_url_from_id(&self, query: &str) -> String {
+        format!("https://{}/?{query}", self.base.info.api_domain)
+    }
+
+    fn get_filtered_source_url(&self, query: &str, filter: &str) -> Result<String, FeedError> {
+        // Nyaa matches torrents containing every search term, so filters become extra terms
+        let mut pairs: Vec<(String, String)> = form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .filter(|(key, _)| key != "page")
+            .collect();
+        match pairs.iter_mut().find(|(key, _)| key == "q") {
+            Some((_, terms)) => *terms = format!("{} {}", terms.trim(), filter.trim()),
+            None => pairs.push(("q".to_string(), filter.trim().to_string())),
+        }
+
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish();
+        Ok(self.get_source_url_from_id(&query))
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+}
+
+impl PartialEq for NyaaPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.api_url == other.base.info.api_url
+    }
+}
+
+impl Eq for NyaaPlatform {}
+
+impl Hash for NyaaPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.api_url.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:nyaa="https://nyaa.si/xmlns/nyaa">
+  <channel>
+    <title>Nyaa - "frieren" - Torrent File RSS</title>
+    <link>https://nyaa.si/</link>
+    <description>RSS Feed for "frieren"</description>
+    <item>
+      <title>[SubsPlease] Sousou no Frieren - 28 (720p) [AAAA].mkv</title>
+      <link>https://nyaa.si/download/101.torrent</link>
+      <guid isPermaLink="true">https://nyaa.si/view/101</guid>
+      <pubDate>Fri, 16 Oct 2026 16:01:00 -0000</pubDate>
+    </item>
+    <item>
+      <title>[SubsPlease] Sousou no Frieren - 28 (1080p) [BBBB].mkv</title>
+      <link>https://nyaa.si/download/102.torrent</link>
+      <guid isPermaLink="true">https://nyaa.si/view/102</guid>
+      <pubDate>Fri, 16 Oct 2026 16:02:00 -0000</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn latest_torrent_is_newest() {
+        let platform = NyaaPlatform::new();
+        let channel = Channel::read_from(FEED.as_bytes()).unwrap();
+
+        let torrent = platform.get_latest_torrent(&channel, "q=frieren").unwrap();
+
+        assert_eq!(
+            NyaaPlatform::get_torrent_id(torrent).unwrap(),
+            "https://nyaa.si/view/102"
+        );
+    }
+
+    #[test]
+    fn source_id_is_search_query() {
+        let platform = NyaaPlatform::new();
+        assert_eq!(
+            platform
+                .get_id_from_source_url("https://nyaa.si/?f=0&c=1_2&q=frieren")
+                .unwrap(),
+            "f=0&c=1_2&q=frieren"
+        );
+        assert_eq!(
+            platform
+                .get_id_from_source_url("https://nyaa.si/?page=rss&q=frieren")
+                .unwrap(),
+            "q=frieren"
+        );
+        assert!(platform.get_id_from_source_url("https://nyaa.si/").is_err());
+    }
+
+    #[test]
+    fn filter_adds_search_terms() {
+        let platform = NyaaPlatform::new();
+        assert_eq!(
+            platform
+                .get_filtered_source_url("page=rss&f=0&q=frieren", "1080p [SubsPlease]")
+                .unwrap(),
+            "https://nyaa.si/?f=0&q=frieren+1080p+%5BSubsPlease%5D"
+        );
+        assert_eq!(
+            platform
+                .get_filtered_source_url("u=subsplease", "1080p")
+                .unwrap(),
+            "https://nyaa.si/?u=subsplease&q=1080p"
+        );
+    }
+
+    #[test]
+    fn search_name_uses_terms_and_uploader() {
+        assert_eq!(search_name("f=0&q=frieren+1080p"), "frieren 1080p");
+        assert_eq!(
+            search_name("q=frieren&u=subsplease"),
+            "frieren (by subsplease)"
+        );
+        assert_eq!(search_name("u=subsplease"), "Uploads by subsplease");
+        assert_eq!(search_name("c=1_2"), "All torrents");
+    }
+}
@@ -2,37 +2,109 @@
 
 use std::sync::Arc;
 
+use crate::config::Config;
 use crate::feed::AniListPlatform;
+use crate::feed::BlueskyPlatform;
 use crate::feed::ComickPlatform;
+use crate::feed::CratesIoPlatform;
+use crate::feed::CustomJsonPlatform;
 use crate::feed::MangaDexPlatform;
+use crate::feed::NyaaPlatform;
 use crate::feed::Platform;
+use crate::feed::PodcastPlatform;
+use crate::feed::RssPlatform;
+use crate::feed::TwitchPlatform;
+use crate::feed::WebtoonPlatform;
+use crate::feed::YoutubePlatform;
 use crate::feed::error::FeedError;
 
+/// A platform as offered by autocomplete, e.g. "MangaDex (mangadex.org)".
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlatformChoice {
+    /// Platform name followed by its domain.
+    pub label: String,
+    pub domain: String,
+}
+
 /// Registry of all feed platforms.
 pub struct Platforms {
     platforms: Vec<Arc<dyn Platform>>,
+    /// Autocomplete choices of `platforms`, kept in the same order.
+    choices: Vec<PlatformChoice>,
     pub anilist: Arc<AniListPlatform>,
     pub mangadex: Arc<MangaDexPlatform>,
     pub comick: Arc<ComickPlatform>,
+    pub webtoon: Arc<WebtoonPlatform>,
+    pub bluesky: Arc<BlueskyPlatform>,
+    pub crates_io: Arc<CratesIoPlatform>,
+    pub nyaa: Arc<NyaaPlatform>,
+    pub custom_json: Arc<CustomJsonPlatform>,
+    pub podcast: Arc<PodcastPlatform>,
+    pub rss: Arc<RssPlatform>,
+    pub youtube: Arc<YoutubePlatform>,
+    /// Only registered when Twitch client credentials are configured.
+    pub twitch: Option<Arc<TwitchPlatform>>,
 }
 
 impl Platforms {
     /// Creates a new platform registry with all supported platforms.
     pub fn new() -> Self {
+        Self::with_configured(YoutubePlatform::new(), None)
+    }
+
+    /// Creates a new platform registry using the platform credentials in `config`.
+    pub fn from_config(config: &Config) -> Self {
+        Self::with_configured(
+            YoutubePlatform::new().with_api_key(config.youtube_api_key.clone()),
+            config.twitch.as_ref().map(TwitchPlatform::new),
+        )
+    }
+
+    fn with_configured(youtube: YoutubePlatform, twitch: Option<TwitchPlatform>) -> Self {
         let anilist = Arc::new(AniListPlatform::new());
         let mangadex = Arc::new(MangaDexPlatform::new());
         let comick = Arc::new(ComickPlatform::new());
+        let webtoon = Arc::new(WebtoonPlatform::new());
+        let bluesky = Arc::new(BlueskyPlatform::new());
+        let crates_io = Arc::new(CratesIoPlatform::new());
+        let nyaa = Arc::new(NyaaPlatform::new());
+        let custom_json = Arc::new(CustomJsonPlatform::new());
+        let podcast = Arc::new(PodcastPlatform::new());
+        let rss = Arc::new(RssPlatform::new());
+        let youtube = Arc::new(youtube);
+        let twitch = twitch.map(Arc::new);
 
         let mut _self = Self {
             platforms: Vec::new(),
+            choices: Vec::new(),
             anilist,
             mangadex,
             comick,
+            webtoon,
+            bluesky,
+            crates_io,
+            nyaa,
+            custom_json,
+            podcast,
+            rss,
+            youtube,
+            twitch,
         };
 
         _self.add_platform(_self.anilist.clone());
         _self.add_platform(_self.mangadex.clone());
         _self.add_platform(_self.comick.clone());
+        _self.add_platform(_self.webtoon.clone());
+        _self.add_platform(_self.bluesky.clone());
+        _self.add_platform(_self.youtube.clone());
+        if let Some(twitch) = _self.twitch.clone() {
+            _self.add_platform(twitch);
+        }
+        _self.add_platform(_self.crates_io.clone());
+        _self.add_platform(_self.nyaa.clone());
+        _self.add_platform(_self.custom_json.clone());
+        _self.add_platform(_self.podcast.clone());
+        _self.add_platform(_self.rss.clone());
         _self
     }
 
@@ -48,14 +120,90 @@ impl Platforms {
         Ok(ret)
     }
 
+    /// Gets the url of a source narrowed to items matching `filter`.
+    pub fn get_filtered_source_url(
+        &self,
+        source_url: &str,
+        filter: &str,
+    ) -> Result<String, FeedError> {
+        let feed = self.get_platform_by_source_url(source_url).ok_or_else(|| {
+            FeedError::UnsupportedUrl {
+                url: source_url.to_string(),
+            }
+        })?;
+
+        let source_id = feed.get_id_from_source_url(source_url)?;
+        feed.get_filtered_source_url(source_id, filter)
+    }
+
     /// Gets a platform that handles the given source url.
+    ///
+    /// Platforms claiming the exact url come first, then ones matching the url's domain,
+    /// then ones accepting any domain.
     pub fn get_platform_by_source_url(&self, source_url: &str) -> Option<&Arc<dyn Platform>> {
-        self.platforms.iter().find(|feed| {
-            feed.get_base()
-                .info
-                .api_url
-                .contains(&Self::extract_domain(source_url))
-        })
+        let domain = Self::extract_domain(source_url);
+        self.platforms
+            .iter()
+            .find(|feed| feed.claims_source_url(source_url))
+            .or_else(|| {
+                self.platforms.iter().find(|feed| {
+                    !feed.accepts_any_domain()
+                        && !feed.get_base().info.api_url.is_empty()
+                        && feed.get_base().info.api_url.contains(&domain)
+                })
+            })
+            .or_else(|| self.platforms.iter().find(|feed| feed.accepts_any_domain()))
+    }
+
+    /// Gets the platform whose domain is `domain`, e.g. `anilist.co`.
+    pub fn get_platform_by_domain(&self, domain: &str) -> Option<&Arc<dyn Platform>> {
+        let domain = domain.trim();
+        if domain.is_empty() {
+            return None;
+        }
+        self.platforms
+            .iter()
+            .find(|feed| feed.get_info().api_domain.eq_ignore_ascii_case(domain))
+    }
+
+    /// Gets the source url of a bare source id, e.g. `154587` on `anilist.co`.
+    ///
+    /// Without `domain`, only MangaDex ids are recognized, as they are UUIDs.
+    pub fn get_source_url_from_id(&self, source_id: &str, domain: Option<&str>) -> Option<String> {
+        let source_id = source_id.trim();
+        if source_id.is_empty() {
+            return None;
+        }
+        match domain {
+            Some(domain) => self
+                .get_platform_by_domain(domain)
+                .map(|feed| feed.get_source_url_from_id(source_id)),
+            None => uuid::Uuid::parse_str(source_id)
+                .is_ok()
+                .then(|| self.mangadex.get_source_url_from_id(source_id)),
+        }
+    }
+
+    /// Finds the urls in `text` handled by a specific platform, in order and without
+    /// duplicates.
+    ///
+    /// Urls only platforms accepting any domain would take, like podcasts, are skipped,
+    /// as most links in a message are not feeds.
+    pub fn find_source_urls(&self, text: &str) -> Vec<String> {
+        let mut urls: Vec<String> = Vec::new();
+        for word in text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '(' | ')')) {
+            let url = word.trim_end_matches(['.', ',', '!', '?', ';', ':', '*', '_', '|']);
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                continue;
+            }
+            let known = self
+                .get_platform_by_source_url(url)
+                .is_some_and(|feed| feed.claims_source_url(url) || !feed.accepts_any_domain());
+            if known && !urls.iter().any(|found| found == url) {
+                urls.push(url.to_string());
+            }
+        }
+        urls
     }
 
     /// Returns all registered platforms.
@@ -63,8 +211,34 @@ impl Platforms {
         self.platforms.clone()
     }
 
+    /// Returns the autocomplete choice of each registered platform, built once as
+    /// platforms are added.
+    pub fn get_choices(&self) -> &[PlatformChoice] {
+        &self.choices
+    }
+
+    /// Registers a platform defined outside this crate. See [`crate::feed::plugin`].
+    ///
+    /// Feeds store the name of their platform, so it must be unique and stay the same
+    /// across restarts.
+    pub fn register_platform(&mut self, platform: Box<dyn Platform>) -> Result<(), FeedError> {
+        let name = platform.get_id();
+        if self.platforms.iter().any(|feed| feed.get_id() == name) {
+            return Err(FeedError::PlatformAlreadyRegistered {
+                name: name.to_string(),
+            });
+        }
+        self.add_platform(Arc::from(platform));
+        Ok(())
+    }
+
     /// Adds a platform to the registry.
     pub fn add_platform(&mut self, feed: Arc<dyn Platform>) {
+        let info = feed.get_info();
+        self.choices.push(PlatformChoice {
+            label: format!("{} ({})", info.name, info.api_domain),
+            domain: info.api_domain.clone(),
+        });
         self.platforms.push(feed);
     }
 
@@ -92,7 +266,51 @@ impl Default for Platforms {
 
 #[cfg(test)]
 mod tests {
+    use async_trait::async_trait;
+
     use super::*;
+    use crate::feed::BasePlatform;
+    use crate::feed::FeedItem;
+    use crate::feed::FeedSource;
+    use crate::feed::PlatformInfo;
+
+    struct ExternalPlatform {
+        base: BasePlatform,
+    }
+
+    impl ExternalPlatform {
+        fn new(name: &str) -> Self {
+            Self {
+                base: BasePlatform::new(PlatformInfo {
+                    name: name.to_string(),
+                    api_domain: "example.org".to_string(),
+                    api_url: "https://api.example.org".to_string(),
+                    ..Default::default()
+                }),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Platform for ExternalPlatform {
+        async fn fetch_latest(&self, _items_id: &str) -> Result<FeedItem, FeedError> {
+            Ok(FeedItem::default())
+        }
+        async fn fetch_source(&self, source_id: &str) -> Result<FeedSource, FeedError> {
+            Err(FeedError::SourceNotFound {
+                source_id: source_id.to_string(),
+            })
+        }
+        fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+            Ok(self.base.get_nth_path_from_url(source_url, 0)?)
+        }
+        fn get_source_url_from_id(&self, source_id: &str) -> String {
+            format!("https://example.org/{source_id}")
+        }
+        fn get_base(&self) -> &BasePlatform {
+            &self.base
+        }
+    }
 
     #[test]
     fn extract_domain() {
@@ -111,4 +329,155 @@ mod tests {
             "sub.domain.co.uk"
         );
     }
+
+    #[test]
+    fn bare_ids_resolve_to_source_urls() {
+        let platforms = Platforms::new();
+
+        assert_eq!(
+            platforms.get_source_url_from_id("154587", Some("AniList.co")),
+            Some("https://anilist.co/anime/154587".to_string())
+        );
+        assert_eq!(
+            platforms.get_source_url_from_id("a1c7c817-4e59-43b7-9365-09675a149a6f", None),
+            Some("https://mangadex.org/title/a1c7c817-4e59-43b7-9365-09675a149a6f".to_string())
+        );
+        assert_eq!(platforms.get_source_url_from_id("154587", None), None);
+        assert_eq!(platforms.get_source_url_from_id("154587", Some("")), None);
+        assert_eq!(
+            platforms.get_source_url_from_id("154587", Some("unknown.org")),
+            None
+        );
+    }
+
+    #[test]
+    fn find_source_urls_skips_unknown_links() {
+        let platforms = Platforms::new();
+
+        let urls = platforms.find_source_urls(
+            "Read <https://mangadex.org/title/abc>, then https://anilist.co/anime/1. \
+             Also https://example.com/page and https://mangadex.org/title/abc!",
+        );
+
+        assert_eq!(
+            urls,
+            vec![
+                "https://mangadex.org/title/abc",
+                "https://anilist.co/anime/1"
+            ]
+        );
+    }
+
+    #[test]
+    fn rss_handles_unmatched_domains() {
+        let platforms = Platforms::new();
+
+        let platform = platforms
+            .get_platform_by_source_url("https://feeds.example.com/show.xml")
+            .unwrap();
+        assert_eq!(platform.get_id(), "RSS");
+
+        let platform = platforms
+            .get_platform_by_source_url("https://feeds.megaphone.fm/show")
+            .unwrap();
+        assert_eq!(platform.get_id(), "Podcast");
+
+        let platform = platforms
+            .get_platform_by_source_url("https://mangadex.org/title/abc")
+            .unwrap();
+        assert_eq!(platform.get_id(), "MangaDex");
+    }
+
+    #[test]
+    fn youtube_handles_channel_urls() {
+        let platforms = Platforms::new();
+
+        for url in [
+            "https://www.youtube.com/@GoogleDevelopers",
+            "https://youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw",
+            "https://m.youtube.com/@GoogleDevelopers",
+        ] {
+            let platform = platforms.get_platform_by_source_url(url).unwrap();
+            assert_eq!(platform.get_id(), "YouTube");
+        }
+    }
+
+    #[test]
+    fn webtoon_handles_series_urls() {
+        let platforms = Platforms::new();
+
+        for url in [
+            "https://www.webtoons.com/en/fantasy/tower-of-god/list?title_no=95",
+            "https://m.webtoons.com/en/fantasy/tower-of-god/list?title_no=95",
+        ] {
+            let platform = platforms.get_platform_by_source_url(url).unwrap();
+            assert_eq!(platform.get_id(), "WEBTOON");
+            assert_eq!(platforms.get_id_from_source_url(url).unwrap(), "95");
+        }
+    }
+
+    #[test]
+    fn twitch_needs_client_credentials() {
+        let url = "https://www.twitch.tv/some_streamer";
+        let platforms = Platforms::new();
+        let platform = platforms.get_platform_by_source_url(url);
+        assert_ne!(platform.map(|platform| platform.get_id()), Some("Twitch"));
+
+        let config = Config {
+            twitch: Some(crate::config::TwitchConfig {
+                client_id: "id".to_string(),
+                client_secret: "secret".to_string(),
+                poll_interval: std::time::Duration::from_secs(120),
+            }),
+            ..Default::default()
+        };
+        let platforms = Platforms::from_config(&config);
+        let platform = platforms.get_platform_by_source_url(url).unwrap();
+        assert_eq!(platform.get_id(), "Twitch");
+        assert_eq!(
+            platforms.get_id_from_source_url(url).unwrap(),
+            "some_streamer"
+        );
+    }
+
+    #[test]
+    fn filters_only_apply_to_searches() {
+        let platforms = Platforms::new();
+
+        assert_eq!(
+            platforms
+                .get_filtered_source_url("https://nyaa.si/?q=frieren", "1080p")
+                .unwrap(),
+            "https://nyaa.si/?q=frieren+1080p"
+        );
+        assert!(matches!(
+            platforms.get_filtered_source_url("https://mangadex.org/title/abc", "1080p"),
+            Err(FeedError::FilterUnsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn registered_platforms_handle_their_urls() {
+        let mut platforms = Platforms::new();
+        platforms
+            .register_platform(Box::new(ExternalPlatform::new("Example")))
+            .unwrap();
+
+        let platform = platforms
+            .get_platform_by_source_url("https://example.org/abc")
+            .unwrap();
+        assert_eq!(platform.get_id(), "Example");
+        assert_eq!(
+            platforms.get_choices().last().unwrap().label,
+            "Example (example.org)"
+        );
+        assert_eq!(
+            platforms.get_choices().len(),
+            platforms.get_all_platforms().len()
+        );
+        assert!(matches!(
+            platforms.register_platform(Box::new(ExternalPlatform::new("MangaDex"))),
+            Err(FeedError::PlatformAlreadyRegistered { .. })
+        ));
+    }
 }
@@ -0,0 +1,371 @@
+//! Podcast RSS platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use rss::Channel;
+use rss::Item;
+use wreq::Client;
+use wreq_util::Emulation;
+
+use super::arbitrary_host_limiter;
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+use crate::feed::error::UrlParseError;
+
+/// Podcast hosts whose feed urls this platform claims. Podcast feeds hosted elsewhere
+/// are read by `RssPlatform`, which keeps their audio links.
+const PODCAST_HOSTS: [&str; 16] = [
+    "anchor.fm",
+    "megaphone.fm",
+    "simplecast.com",
+    "buzzsprout.com",
+    "art19.com",
+    "libsyn.com",
+    "acast.com",
+    "transistor.fm",
+    "captivate.fm",
+    "podbean.com",
+    "omnycontent.com",
+    "redcircle.com",
+    "fireside.fm",
+    "spreaker.com",
+    "audioboom.com",
+    "feeds.soundcloud.com",
+];
+
+/// Podcast platform reading episodes from the RSS feeds of podcast hosts.
+///
+/// Feeds are identified by their full RSS url, and claimed by the host in it.
+pub struct PodcastPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+}
+
+impl PodcastPlatform {
+    /// Creates a new podcast platform with rate limiting.
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .emulation(Emulation::Chrome137)
+            .build()
+            .unwrap();
+
+        let info = PlatformInfo {
+            name: "Podcast".to_string(),
+            feed_item_name: "Episode".to_string(),
+            // Feeds are claimed by their host, so there is no API domain to match on
+            api_hostname: String::new(),
+            api_domain: String::new(),
+            api_url: String::new(),
+            copyright_notice: "Episodes © their respective publishers".to_string(),
+            logo_url:
+                "https://upload.wikimedia.org/wikipedia/commons/thumb/4/43/Feed-icon.svg/128px-Feed-icon.svg.png"
+                    .to_string(),
+            // Podcasts are polled along with series feeds
+            tags: "series,podcast".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0x8940FA),
+            emoji: "🎙️".to_string(),
+        };
+
+        let (quota, limiter) = arbitrary_host_limiter();
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+        }
+    }
+
+    /// Returns the newest episode with an audio enclosure.
+    fn get_latest_episode<'a>(
+        &self,
+        channel: &'a Channel,
+        url: &str,
+    ) -> Result<&'a Item, FeedError> {
+        channel
+            .items()
+            .iter()
+            .filter(|item| item.enclosure().is_some())
+            .max_by_key(|item| Self::get_pub_date(item).ok())
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: url.to_string(),
+            })
+    }
+
+    fn get_episode_id(item: &Item) -> Result<String, FeedError> {
+        item.guid()
+            .map(|guid| guid.value().to_string())
+            .or_else(|| item.enclosure().map(|e| e.url().to_string()))
+            .ok_or_else(|| FeedError::MissingField {
+                field: "item.guid".to_string(),
+            })
+    }
+
+    fn get_episode_title(item: &Item) -> Result<String, FeedError> {
+        item.title()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| FeedError::MissingField {
+                field: "item.title".to_string(),
+            })
+    }
+
+    fn get_pub_date(item: &Item) -> Result<DateTime<Utc>, FeedError> {
+        let date_str = item.pub_date().ok_or_else(|| FeedError::MissingField {
+            field: "item.pubDate".to_string(),
+        })?;
+
+        DateTime::parse_from_rfc2822(date_str.trim())
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| FeedError::InvalidTime {
+                time: date_str.to_string(),
+            })
+    }
+
+    fn get_duration(item: &Item) -> Option<i32> {
+        item.itunes_ext()
+            .and_then(|ext| ext.duration())
+            .and_then(parse_duration)
+    }
+
+    fn get_cover_url(channel: &Channel) -> Option<String> {
+        channel
+            .itunes_ext()
+            .and_then(|ext| ext.image())
+            .or_else(|| channel.image().map(|image| image.url()))
+            .map(str::to_string)
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    async fn fetch_channel(&self, url: &str) -> Result<Channel, FeedError> {
+        let response = self.send(self.client.get(url)).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!("{url} returned HTTP {}", status.as_u16()),
+            });
+        }
+
+        let body = response.bytes().await?;
+        Ok(Channel::read_from(&body[..])?)
+    }
+}
+
+/// Parses an `itunes:duration` value into seconds.
+///
+/// Accepts plain seconds (`"2710"`), `"MM:SS"` and `"HH:MM:SS"`.
+pub fn parse_duration(value: &str) -> Option<i32> {
+    let parts = value
+        .trim()
+        .split(':')
+        .map(|part| part.trim().parse::<i32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if parts.is_empty() || parts.len() > 3 || parts.iter().any(|part| *part < 0) {
+        return None;
+    }
+    parts
+        .into_iter()
+        .try_fold(0i32, |total, part| total.checked_mul(60)?.checked_add(part))
+}
+
+#[async_trait]
+impl Platform for PodcastPlatform {
+    async fn fetch_source(&self, url: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {url}",
+            self.base.info.name
+        );
+
+        let channel = self.fetch_channel(url).await?;
+
+        Ok(FeedSource {
+            id: url.to_string(),
+            items_id: url.to_string(),
+            name: channel.title().trim().to_string(),
+            description: channel.description().trim().to_string(),
+            source_url: self.get_source_url_from_id(url),
+            image_url: Self::get_cover_url(&channel),
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, url: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {url}",
+            self.base.info.name
+        );
+
+        let channel = self.fetch_channel(url).await?;
+        let episode = self.get_latest_episode(&channel, url)?;
+
+        Ok(FeedItem {
+            id: Self::get_episode_id(episode)?,
+            title: Self::get_episode_title(episode)?,
+            published: Self::get_pub_date(episode)?,
+            content: episode.description().map(|d| d.trim().to_string()),
+            media_url: episode.enclosure().map(|e| e.url().to_string()),
+            duration_secs: Self::get_duration(episode),
+            item_url: episode.link().map(str::to_string),
+            ..Default::default()
+        })
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        let source_url = source_url.trim();
+        if !(source_url.starts_with("https://") || source_url.starts_with("http://")) {
+            return Err(UrlParseError::InvalidFormat {
+                url: source_url.to_string(),
+            }
+            .into());
+        }
+        Ok(source_url)
+    }
+
+    fn get_source_url_from_id(&self, url: &str) -> String {
+        url.to_string()
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+
+    fn claims_source_url(&self, source_url: &str) -> bool {
+        url::Url::parse(source_url.trim())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .is_some_and(|host| {
+                PODCAST_HOSTS.iter().any(|podcast_host| {
+                    host == *podcast_host || host.ends_with(&format!(".{podcast_host}"))
+                })
+            })
+    }
+}
+
+impl PartialEq for PodcastPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.name == other.base.info.name
+    }
+}
+
+impl Eq for PodcastPlatform {}
+
+impl Hash for PodcastPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.name.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Test Show</title>
+    <link>https://example.com</link>
+    <description>A test podcast</description>
+    <itunes:image href="https://example.com/cover.png"/>
+    <item>
+      <title>Episode 1</title>
+      <guid>ep-1</guid>
+      <pubDate>Mon, 05 Oct 2026 10:00:00 +0000</pubDate>
+      <enclosure url="https://example.com/ep1.mp3" length="1" type="audio/mpeg"/>
+      <itunes:duration>45:10</itunes:duration>
+    </item>
+    <item>
+      <title>Episode 2</title>
+      <guid>ep-2</guid>
+      <pubDate>Mon, 12 Oct 2026 10:00:00 +0000</pubDate>
+      <enclosure url="https://example.com/ep2.mp3" length="1" type="audio/mpeg"/>
+      <itunes:duration>1:02:03</itunes:duration>
+    </item>
+    <item>
+      <title>Announcement</title>
+      <pubDate>Tue, 13 Oct 2026 10:00:00 +0000</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn parse_duration_accepts_itunes_formats() {
+        assert_eq!(parse_duration("2710"), Some(2710));
+        assert_eq!(parse_duration("45:10"), Some(2710));
+        assert_eq!(parse_duration("1:02:03"), Some(3723));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("1:2:3:4"), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn latest_episode_skips_items_without_audio() {
+        let platform = PodcastPlatform::new();
+        let channel = Channel::read_from(FEED.as_bytes()).unwrap();
+
+        let episode = platform
+            .get_latest_episode(&channel, "https://example.com/feed.xml")
+            .unwrap();
+
+        assert_eq!(PodcastPlatform::get_episode_id(episode).unwrap(), "ep-2");
+        assert_eq!(
+            episode.enclosure().map(|e| e.url()),
+            Some("https://example.com/ep2.mp3")
+        );
+        assert_eq!(PodcastPlatform::get_duration(episode), Some(3723));
+        assert_eq!(
+            PodcastPlatform::get_cover_url(&channel).as_deref(),
+            Some("https://example.com/cover.png")
+        );
+    }
+
+    #[test]
+    fn claims_podcast_hosts() {
+        let platform = PodcastPlatform::new();
+        assert!(platform.claims_source_url("https://feeds.megaphone.fm/show"));
+        assert!(platform.claims_source_url("https://anchor.fm/s/abc/podcast/rss"));
+        assert!(!platform.claims_source_url("https://notmegaphone.fm/show"));
+        assert!(!platform.claims_source_url("https://example.com/feed.xml"));
+    }
+
+    #[test]
+    fn source_url_must_be_http() {
+        let platform = PodcastPlatform::new();
+        assert_eq!(
+            platform
+                .get_id_from_source_url("https://example.com/feed.xml")
+                .unwrap(),
+            "https://example.com/feed.xml"
+        );
+        assert!(platform.get_id_from_source_url("example.com/feed").is_err());
+    }
+}
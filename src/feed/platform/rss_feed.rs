@@ -0,0 +1,391 @@
+//! Generic RSS and Atom platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use wreq::Client;
+use wreq_util::Emulation;
+
+use super::arbitrary_host_limiter;
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+use crate::feed::error::UrlParseError;
+use crate::feed::platform::podcast::parse_duration;
+
+/// A web feed read from either RSS or Atom.
+#[derive(Debug, Default)]
+struct WebFeed {
+    title: String,
+    description: String,
+    image_url: Option<String>,
+    /// Entries in document order, usually newest first.
+    entries: Vec<FeedItem>,
+}
+
+impl WebFeed {
+    /// Parses an RSS channel, or an Atom feed when the document is not RSS.
+    fn parse(body: &[u8]) -> Result<Self, FeedError> {
+        match rss::Channel::read_from(body) {
+            Ok(channel) => Ok(Self::from_rss(&channel)),
+            Err(rss_error) => atom_syndication::Feed::read_from(body)
+                .map(|feed| Self::from_atom(&feed))
+                // Most web feeds are RSS, so its error says more about a broken document
+                .map_err(|_| rss_error.into()),
+        }
+    }
+
+    fn from_rss(channel: &rss::Channel) -> Self {
+        let entries = channel
+            .items()
+            .iter()
+            .filter_map(|item| {
+                let link = item.link().map(str::to_string);
+                let id = item
+                    .guid()
+                    .map(|guid| guid.value().to_string())
+                    .or_else(|| link.clone())?;
+                Some(FeedItem {
+                    id,
+                    title: non_empty(item.title()).unwrap_or_else(|| "Untitled".to_string()),
+                    published: item.pub_date().and_then(parse_date).unwrap_or_default(),
+                    content: non_empty(item.description()),
+                    media_url: item.enclosure().map(|e| e.url().to_string()),
+                    duration_secs: item
+                        .itunes_ext()
+                        .and_then(|ext| ext.duration())
+                        .and_then(parse_duration),
+                    item_url: link,
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Self {
+            title: channel.title().trim().to_string(),
+            description: channel.description().trim().to_string(),
+            image_url: channel.image().map(|image| image.url().to_string()),
+            entries,
+        }
+    }
+
+    fn from_atom(feed: &atom_syndication::Feed) -> Self {
+        let entries = feed
+            .entries()
+            .iter()
+            .map(|entry| {
+                let link = |rel: &str| {
+                    entry
+                        .links()
+                        .iter()
+                        .find(|link| link.rel() == rel)
+                        .map(|link| link.href().to_string())
+                };
+                FeedItem {
+                    id: entry.id().to_string(),
+                    title: non_empty(Some(&entry.title().value))
+                        .unwrap_or_else(|| "Untitled".to_string()),
+                    published: entry
+                        .published()
+                        .unwrap_or(entry.updated())
+                        .with_timezone(&Utc),
+                    content: non_empty(entry.summary().map(|summary| summary.value.as_str())),
+                    media_url: link("enclosure"),
+                    item_url: link("alternate"),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Self {
+            title: feed.title().value.trim().to_string(),
+            description: feed
+                .subtitle()
+                .map(|subtitle| subtitle.value.trim().to_string())
+                .unwrap_or_default(),
+            image_url: feed.logo().or(feed.icon()).map(str::to_string),
+            entries,
+        }
+    }
+
+    /// Returns the newest entry. Entries without a date count as older than any dated
+    /// one, and the first of them in the document is taken if none are dated.
+    fn into_latest(self) -> Option<FeedItem> {
+        let mut latest: Option<FeedItem> = None;
+        for entry in self.entries {
+            if latest
+                .as_ref()
+                .is_none_or(|latest| entry.published > latest.published)
+            {
+                latest = Some(entry);
+            }
+        }
+        latest
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Parses an RSS `pubDate`, which some feeds write as RFC 3339 instead of RFC 2822.
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc2822(value)
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Platform reading posts from any RSS or Atom feed, e.g. a blog or a release feed.
+///
+/// Feeds are identified by their full url, so this platform accepts urls from any
+/// domain that no other platform handles.
+pub struct RssPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+}
+
+impl RssPlatform {
+    /// Creates a new RSS platform with rate limiting.
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .emulation(Emulation::Chrome137)
+            .build()
+            .unwrap();
+
+        let info = PlatformInfo {
+            name: "RSS".to_string(),
+            feed_item_name: "Post".to_string(),
+            // Feeds are hosted anywhere, so there is no API domain to match on
+            api_hostname: String::new(),
+            api_domain: String::new(),
+            api_url: String::new(),
+            copyright_notice: "Posts © their respective publishers".to_string(),
+            logo_url:
+                "https://upload.wikimedia.org/wikipedia/commons/thumb/4/43/Feed-icon.svg/128px-Feed-icon.svg.png"
+                    .to_string(),
+            tags: "series,rss".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0xF26522),
+            emoji: "📰".to_string(),
+        };
+
+        let (quota, limiter) = arbitrary_host_limiter();
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+        }
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    async fn fetch_feed(&self, url: &str) -> Result<WebFeed, FeedError> {
+        let response = self.send(self.client.get(url)).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!("{url} returned HTTP {}", status.as_u16()),
+            });
+        }
+
+        let body = response.bytes().await?;
+        WebFeed::parse(&body[..])
+    }
+}
+
+#[async_trait]
+impl Platform for RssPlatform {
+    async fn fetch_source(&self, url: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {url}",
+            self.base.info.name
+        );
+
+        let feed = self.fetch_feed(url).await?;
+
+        Ok(FeedSource {
+            id: url.to_string(),
+            items_id: url.to_string(),
+            name: feed.title,
+            description: feed.description,
+            source_url: self.get_source_url_from_id(url),
+            image_url: feed.image_url,
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, url: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {url}",
+            self.base.info.name
+        );
+
+        let feed = self.fetch_feed(url).await?;
+        let mut latest = feed.into_latest().ok_or_else(|| FeedError::ItemNotFound {
+            source_id: url.to_string(),
+        })?;
+        if latest.published == DateTime::<Utc>::default() {
+            latest.published = Utc::now();
+        }
+        Ok(latest)
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        let source_url = source_url.trim();
+        if !(source_url.starts_with("https://") || source_url.starts_with("http://")) {
+            return Err(UrlParseError::InvalidFormat {
+                url: source_url.to_string(),
+            }
+            .into());
+        }
+        Ok(source_url)
+    }
+
+    fn get_source_url_from_id(&self, url: &str) -> String {
+        url.to_string()
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+
+    fn accepts_any_domain(&self) -> bool {
+        true
+    }
+}
+
+impl PartialEq for RssPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.name == other.base.info.name
+    }
+}
+
+impl Eq for RssPlatform {}
+
+impl Hash for RssPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.name.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Test Blog</title>
+    <link>https://example.com</link>
+    <description>A test blog</description>
+    <item>
+      <title>Older post</title>
+      <link>https://example.com/older</link>
+      <pubDate>Mon, 05 Oct 2026 10:00:00 +0000</pubDate>
+    </item>
+    <item>
+      <title>Newer post</title>
+      <link>https://example.com/newer</link>
+      <guid>post-2</guid>
+      <pubDate>2026-10-12T10:00:00Z</pubDate>
+      <description>Hello</description>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Test Releases</title>
+  <subtitle>Releases of a test project</subtitle>
+  <id>urn:uuid:feed</id>
+  <updated>2026-10-12T10:00:00Z</updated>
+  <logo>https://example.com/logo.png</logo>
+  <entry>
+    <title>v1.1.0</title>
+    <id>tag:example.com,2026:v1.1.0</id>
+    <updated>2026-10-12T10:00:00Z</updated>
+    <link rel="alternate" href="https://example.com/releases/v1.1.0"/>
+    <summary>Bug fixes</summary>
+  </entry>
+  <entry>
+    <title>v1.0.0</title>
+    <id>tag:example.com,2026:v1.0.0</id>
+    <updated>2026-10-01T10:00:00Z</updated>
+    <link rel="alternate" href="https://example.com/releases/v1.0.0"/>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn reads_rss_posts() {
+        let feed = WebFeed::parse(RSS.as_bytes()).unwrap();
+        assert_eq!(feed.title, "Test Blog");
+        assert_eq!(feed.entries.len(), 2);
+        // Items without a guid are identified by their link
+        assert_eq!(feed.entries[0].id, "https://example.com/older");
+
+        let latest = feed.into_latest().unwrap();
+        assert_eq!(latest.id, "post-2");
+        assert_eq!(latest.title, "Newer post");
+        assert_eq!(latest.content.as_deref(), Some("Hello"));
+        assert_eq!(
+            latest.item_url.as_deref(),
+            Some("https://example.com/newer")
+        );
+    }
+
+    #[test]
+    fn reads_atom_entries() {
+        let feed = WebFeed::parse(ATOM.as_bytes()).unwrap();
+        assert_eq!(feed.title, "Test Releases");
+        assert_eq!(feed.description, "Releases of a test project");
+        assert_eq!(
+            feed.image_url.as_deref(),
+            Some("https://example.com/logo.png")
+        );
+
+        let latest = feed.into_latest().unwrap();
+        assert_eq!(latest.title, "v1.1.0");
+        assert_eq!(latest.content.as_deref(), Some("Bug fixes"));
+        assert_eq!(
+            latest.item_url.as_deref(),
+            Some("https://example.com/releases/v1.1.0")
+        );
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(WebFeed::parse(b"<html><body>Not a feed</body></html>").is_err());
+    }
+}
@@ -0,0 +1,553 @@
+//! Twitch live stream platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroU32;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use governor::Quota;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use url::form_urlencoded;
+use wreq::Client;
+use wreq_util::Emulation;
+
+use crate::config::TwitchConfig;
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+use crate::feed::error::UrlParseError;
+
+/// Base url of the Helix API.
+const HELIX_API_URL: &str = "https://api.twitch.tv/helix";
+
+/// Url of the OAuth token endpoint.
+const TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
+
+/// An app access token is replaced this long before it expires, so requests in flight
+/// don't use an expired one.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::minutes(5);
+
+/// Size stream thumbnails are requested in.
+const THUMBNAIL_SIZE: (&str, &str) = ("1280", "720");
+
+/// App access token of the client credentials flow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct AppToken {
+    access_token: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl AppToken {
+    /// Whether the token can still be used at `now`.
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        now + TOKEN_EXPIRY_MARGIN < self.expires_at
+    }
+}
+
+/// Twitch platform notifying when a channel goes live.
+///
+/// Sources are identified by the channel's login in its url, and items by stream, so a
+/// channel publishes one item per broadcast. While a channel is offline its latest
+/// broadcast is read from its past broadcasts, which keep the stream's ID.
+///
+/// Helix requests are signed with an app access token from the client credentials in
+/// [`TwitchConfig`]. The token is fetched on first use and replaced before it expires or
+/// when Twitch rejects it.
+pub struct TwitchPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+    client_id: String,
+    client_secret: String,
+    token: Mutex<Option<AppToken>>,
+}
+
+impl TwitchPlatform {
+    /// Creates a new Twitch platform with rate limiting.
+    pub fn new(config: &TwitchConfig) -> Self {
+        let client = Client::builder()
+            .emulation(Emulation::Chrome137)
+            .build()
+            .unwrap();
+
+        let info = PlatformInfo {
+            name: "Twitch".to_string(),
+            feed_item_name: "Stream".to_string(),
+            api_hostname: "api.twitch.tv".to_string(),
+            api_domain: "twitch.tv".to_string(),
+            api_url: "https://www.twitch.tv".to_string(),
+            copyright_notice: "Streams © their respective creators on Twitch".to_string(),
+            logo_url: "https://www.twitch.tv/favicon.ico".to_string(),
+            // Polled by the live feed publisher, not with series feeds
+            tags: "live,stream".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0x9146FF),
+            emoji: "🔴".to_string(),
+        };
+
+        // Helix allows an app token 800 points per minute, one per request
+        let quota = Quota::per_minute(NonZeroU32::new(600).unwrap());
+        let limiter = RateLimiter::direct(quota);
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+            client_id: config.client_id.clone(),
+            client_secret: config.client_secret.clone(),
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Returns the app access token, fetching a new one when it is missing or about to
+    /// expire.
+    async fn access_token(&self) -> Result<String, FeedError> {
+        let mut token = self.token.lock().await;
+        if let Some(current) = token.as_ref().filter(|t| t.is_fresh(Utc::now())) {
+            return Ok(current.access_token.clone());
+        }
+
+        info!("Requesting a new {} app access token.", self.base.info.name);
+        let fresh = self.request_token().await?;
+        let access_token = fresh.access_token.clone();
+        *token = Some(fresh);
+        Ok(access_token)
+    }
+
+    /// Drops the app access token after Twitch rejected it, unless another request
+    /// already replaced it.
+    async fn invalidate_token(&self, rejected: &str) {
+        let mut token = self.token.lock().await;
+        if token.as_ref().is_some_and(|t| t.access_token == rejected) {
+            *token = None;
+        }
+    }
+
+    async fn request_token(&self) -> Result<AppToken, FeedError> {
+        let form = form_urlencoded::Serializer::new(String::new())
+            .append_pair("client_id", &self.client_id)
+            .append_pair("client_secret", &self.client_secret)
+            .append_pair("grant_type", "client_credentials")
+            .finish();
+        let request = self
+            .client
+            .post(TOKEN_URL)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(form);
+        let response = self.send(request).await?;
+        let status = response.status();
+        let resp: Value = serde_json::from_str(&response.text().await?).unwrap_or_default();
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!(
+                    "Twitch token request returned HTTP {}: {}",
+                    status.as_u16(),
+                    self.extract_error_message(&resp)
+                ),
+            });
+        }
+        Self::token_from_response(&resp, Utc::now())
+    }
+
+    fn token_from_response(resp: &Value, now: DateTime<Utc>) -> Result<AppToken, FeedError> {
+        let access_token = Self::get_str(resp, "access_token")?;
+        let expires_in = resp
+            .get("expires_in")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| FeedError::MissingField {
+                field: "expires_in".to_string(),
+            })?;
+        Ok(AppToken {
+            access_token: access_token.to_string(),
+            expires_at: now + Duration::seconds(expires_in),
+        })
+    }
+
+    /// Sends a Helix GET request and returns its `data` array. A rejected token is
+    /// replaced and the request sent once more.
+    async fn get_helix(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<Value>, FeedError> {
+        let mut retried = false;
+        loop {
+            let access_token = self.access_token().await?;
+            let request = self
+                .client
+                .get(format!("{HELIX_API_URL}/{path}"))
+                .query(query)
+                .header("Client-Id", self.client_id.as_str())
+                .header("Authorization", format!("Bearer {access_token}"));
+            let response = self.send(request).await?;
+            let status = response.status();
+            if status.as_u16() == 401 && !retried {
+                info!(
+                    "{} rejected the app access token. Requesting a new one.",
+                    self.base.info.name
+                );
+                self.invalidate_token(&access_token).await;
+                retried = true;
+                continue;
+            }
+
+            let resp: Value = serde_json::from_str(&response.text().await?)?;
+            if !status.is_success() {
+                return Err(FeedError::ApiError {
+                    message: format!(
+                        "Twitch {path} returned HTTP {}: {}",
+                        status.as_u16(),
+                        self.extract_error_message(&resp)
+                    ),
+                });
+            }
+            return Ok(resp
+                .get("data")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default());
+        }
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    fn get_str<'a>(value: &'a Value, field: &str) -> Result<&'a str, FeedError> {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FeedError::MissingField {
+                field: field.to_string(),
+            })
+    }
+
+    fn get_time(value: &Value, field: &str) -> Result<DateTime<Utc>, FeedError> {
+        let time = Self::get_str(value, field)?;
+        DateTime::parse_from_rfc3339(time)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| FeedError::InvalidTime {
+                time: time.to_string(),
+            })
+    }
+
+    /// Builds the item of a live stream from the Helix `streams` endpoint.
+    fn item_from_stream(stream: &Value) -> Result<FeedItem, FeedError> {
+        let login = Self::get_str(stream, "user_login")?;
+        Ok(FeedItem {
+            id: Self::get_str(stream, "id")?.to_string(),
+            title: stream_title(Self::get_str(stream, "title")?),
+            published: Self::get_time(stream, "started_at")?,
+            item_url: Some(channel_url(login)),
+            image_url: stream
+                .get("thumbnail_url")
+                .and_then(|v| v.as_str())
+                .map(|url| {
+                    url.replace("{width}", THUMBNAIL_SIZE.0)
+                        .replace("{height}", THUMBNAIL_SIZE.1)
+                }),
+            ..Default::default()
+        })
+    }
+
+    /// Builds the item of an ended stream from its past broadcast in the Helix `videos`
+    /// endpoint. The item keeps the stream's ID, so it matches the item published while
+    /// the stream was live.
+    fn item_from_archive(video: &Value) -> Result<FeedItem, FeedError> {
+        let stream_id = Self::get_str(video, "stream_id")?;
+        Ok(FeedItem {
+            id: stream_id.to_string(),
+            title: stream_title(Self::get_str(video, "title")?),
+            published: Self::get_time(video, "created_at")?,
+            duration_secs: video
+                .get("duration")
+                .and_then(|v| v.as_str())
+                .and_then(parse_video_duration),
+            item_url: video
+                .get("url")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            image_url: video
+                .get("thumbnail_url")
+                .and_then(|v| v.as_str())
+                .filter(|url| !url.is_empty())
+                .map(|url| {
+                    url.replace("%{width}", THUMBNAIL_SIZE.0)
+                        .replace("%{height}", THUMBNAIL_SIZE.1)
+                }),
+            ..Default::default()
+        })
+    }
+}
+
+/// Whether `login` is a valid Twitch login: 1 to 25 letters, digits or underscores.
+fn is_login(login: &str) -> bool {
+    (1..=25).contains(&login.len())
+        && login
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Returns the url of a channel.
+fn channel_url(login: &str) -> String {
+    format!("https://www.twitch.tv/{login}")
+}
+
+/// Returns the title of a stream. Untitled streams are named after their broadcast.
+fn stream_title(title: &str) -> String {
+    let title = title.trim();
+    if title.is_empty() {
+        "Untitled broadcast".to_string()
+    } else {
+        title.to_string()
+    }
+}
+
+/// Parses a video duration like `3h21m5s` into seconds.
+fn parse_video_duration(value: &str) -> Option<i32> {
+    let mut total = 0i32;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        total = total.checked_add(number.parse::<i32>().ok()?.checked_mul(unit)?)?;
+        number.clear();
+    }
+    number.is_empty().then_some(total)
+}
+
+#[async_trait]
+impl Platform for TwitchPlatform {
+    async fn fetch_source(&self, login: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {login}",
+            self.base.info.name
+        );
+
+        let users = self.get_helix("users", &[("login", login)]).await?;
+        let user = users.first().ok_or_else(|| FeedError::SourceNotFound {
+            source_id: login.to_string(),
+        })?;
+
+        Ok(FeedSource {
+            id: login.to_string(),
+            // The user ID stays the same when the channel is renamed
+            items_id: Self::get_str(user, "id")?.to_string(),
+            name: Self::get_str(user, "display_name")?.to_string(),
+            description: user
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            source_url: self.get_source_url_from_id(login),
+            image_url: user
+                .get("profile_image_url")
+                .and_then(|v| v.as_str())
+                .filter(|url| !url.is_empty())
+                .map(str::to_string),
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, user_id: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {user_id}",
+            self.base.info.name
+        );
+
+        let streams = self.get_helix("streams", &[("user_id", user_id)]).await?;
+        if let Some(stream) = streams.first() {
+            return Self::item_from_stream(stream);
+        }
+
+        // Offline: the latest broadcast is the newest past broadcast
+        let videos = self
+            .get_helix(
+                "videos",
+                &[("user_id", user_id), ("type", "archive"), ("first", "1")],
+            )
+            .await?;
+        videos
+            .first()
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: user_id.to_string(),
+            })
+            .and_then(Self::item_from_archive)
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        // https://www.twitch.tv/<login>
+        let login = self
+            .base
+            .get_nth_path_from_url(source_url, 0)?
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default();
+        if is_login(login) {
+            Ok(login)
+        } else {
+            Err(UrlParseError::MissingId {
+                url: source_url.to_string(),
+            }
+            .into())
+        }
+    }
+
+    fn get_source_url_from_id(&self, login: &str) -> String {
+        channel_url(login)
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+
+    fn claims_source_url(&self, source_url: &str) -> bool {
+        // Domain matching misses mobile links
+        url::Url::parse(source_url.trim())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .is_some_and(|host| host == "m.twitch.tv")
+    }
+}
+
+impl PartialEq for TwitchPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.api_url == other.base.info.api_url
+    }
+}
+
+impl Eq for TwitchPlatform {}
+
+impl Hash for TwitchPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.api_url.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    fn platform() -> TwitchPlatform {
+        TwitchPlatform::new(&TwitchConfig {
+            client_id: "id".to_string(),
+            client_secret: "secret".to_string(),
+            poll_interval: std::time::Duration::from_secs(120),
+        })
+    }
+
+    #[test]
+    fn source_ids_from_channel_urls() {
+        let platform = platform();
+        assert_eq!(
+            platform
+                .get_id_from_source_url("https://www.twitch.tv/some_streamer?sr=a")
+                .unwrap(),
+            "some_streamer"
+        );
+        assert!(
+            platform
+                .get_id_from_source_url("https://www.twitch.tv/")
+                .is_err()
+        );
+        assert!(platform.claims_source_url("https://m.twitch.tv/some_streamer"));
+        assert_eq!(
+            platform.get_source_url_from_id("some_streamer"),
+            "https://www.twitch.tv/some_streamer"
+        );
+    }
+
+    #[test]
+    fn token_is_replaced_before_it_expires() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let token = TwitchPlatform::token_from_response(
+            &json!({ "access_token": "abc", "expires_in": 3600, "token_type": "bearer" }),
+            now,
+        )
+        .unwrap();
+
+        assert_eq!(token.access_token, "abc");
+        assert!(token.is_fresh(now + Duration::minutes(50)));
+        assert!(!token.is_fresh(now + Duration::minutes(56)));
+    }
+
+    #[test]
+    fn live_and_archived_stream_share_an_id() {
+        let stream = json!({
+            "id": "40952121085",
+            "user_login": "some_streamer",
+            "title": "  Launch day  ",
+            "started_at": "2026-10-16T10:00:00Z",
+            "thumbnail_url": "https://static-cdn.jtvnw.net/previews-ttv/live_user_some_streamer-{width}x{height}.jpg"
+        });
+        let video = json!({
+            "id": "335921245",
+            "stream_id": "40952121085",
+            "title": "Launch day",
+            "created_at": "2026-10-16T10:00:03Z",
+            "url": "https://www.twitch.tv/videos/335921245",
+            "thumbnail_url": "",
+            "duration": "3h8m33s"
+        });
+
+        let live = TwitchPlatform::item_from_stream(&stream).unwrap();
+        let archived = TwitchPlatform::item_from_archive(&video).unwrap();
+
+        assert_eq!(live.id, archived.id);
+        assert_eq!(live.title, "Launch day");
+        // Edits are told by title and content, so the stream must not look edited
+        // once it ends
+        assert_eq!(live.content_hash(), archived.content_hash());
+        assert_eq!(
+            live.item_url.as_deref(),
+            Some("https://www.twitch.tv/some_streamer")
+        );
+        assert_eq!(
+            live.image_url.as_deref(),
+            Some("https://static-cdn.jtvnw.net/previews-ttv/live_user_some_streamer-1280x720.jpg")
+        );
+        assert_eq!(archived.duration_secs, Some(11313));
+        assert_eq!(archived.image_url, None);
+    }
+
+    #[test]
+    fn parse_video_duration_accepts_helix_format() {
+        assert_eq!(parse_video_duration("3h8m33s"), Some(11313));
+        assert_eq!(parse_video_duration("45s"), Some(45));
+        assert_eq!(parse_video_duration("12m"), Some(720));
+        assert_eq!(parse_video_duration("12"), None);
+        assert_eq!(parse_video_duration("1d"), None);
+    }
+}
@@ -0,0 +1,357 @@
+//! WEBTOON (webtoons.com) platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroU32;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono::Utc;
+use governor::Quota;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use wreq::Client;
+use wreq::redirect::Policy;
+use wreq_util::Emulation;
+
+use super::youtube::meta_content;
+use super::youtube::unescape_html;
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+use crate::feed::error::UrlParseError;
+
+/// An episode in the episode list of a series page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Episode {
+    number: u32,
+    title: String,
+    url: Option<String>,
+    published: Option<NaiveDate>,
+    thumbnail_url: Option<String>,
+}
+
+/// WEBTOON platform following the episodes of a series.
+///
+/// Sources are identified by the `title_no` in their url, which every link to a series
+/// or one of its episodes carries. Episodes are read from the first page of the
+/// series' episode list, which lists the newest ones.
+pub struct WebtoonPlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+}
+
+impl WebtoonPlatform {
+    /// Creates a new WEBTOON platform with rate limiting.
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .emulation(Emulation::Chrome137)
+            // Short series links redirect to the series page
+            .redirect(Policy::limited(5))
+            .build()
+            .unwrap();
+
+        let info = PlatformInfo {
+            name: "WEBTOON".to_string(),
+            feed_item_name: "Episode".to_string(),
+            api_hostname: "www.webtoons.com".to_string(),
+            api_domain: "webtoons.com".to_string(),
+            api_url: "https://www.webtoons.com".to_string(),
+            copyright_notice: "Episodes © their respective creators on WEBTOON".to_string(),
+            logo_url: "https://www.webtoons.com/favicon.ico".to_string(),
+            tags: "series".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0x00DC64),
+            emoji: "📖".to_string(),
+        };
+
+        // Pages are scraped, so stay gentle
+        let quota = Quota::per_minute(NonZeroU32::new(30).unwrap());
+        let limiter = RateLimiter::direct(quota);
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+        }
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    /// Fetches the html of a series page.
+    async fn fetch_page(&self, url: &str, source_id: &str) -> Result<String, FeedError> {
+        let response = self.send(self.client.get(url)).await?;
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(FeedError::SourceNotFound {
+                source_id: source_id.to_string(),
+            });
+        }
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!("{url} returned HTTP {}", status.as_u16()),
+            });
+        }
+        Ok(response.text().await?)
+    }
+
+    /// Returns the newest episode of a series page.
+    fn get_latest_episode(html: &str, url: &str) -> Result<Episode, FeedError> {
+        parse_episodes(html)
+            .into_iter()
+            .max_by_key(|episode| episode.number)
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: url.to_string(),
+            })
+    }
+}
+
+/// Parses the episodes listed on a series page.
+fn parse_episodes(html: &str) -> Vec<Episode> {
+    html.split("<li class=\"_episodeItem\"")
+        .skip(1)
+        .filter_map(|item| {
+            let item = &item[..item.find("</li>").unwrap_or(item.len())];
+            let number = between(item, "data-episode-no=\"", "\"")?.parse().ok()?;
+            let title = between(item, "<span class=\"subj\"><span>", "</span>")
+                .map(|title| unescape_html(title.trim()))
+                .filter(|title| !title.is_empty())?;
+            Some(Episode {
+                number,
+                title,
+                url: between(item, "href=\"", "\"").map(unescape_html),
+                published: between(item, "<span class=\"date\">", "</span>")
+                    .and_then(|date| NaiveDate::parse_from_str(date.trim(), "%b %d, %Y").ok()),
+                thumbnail_url: between(item, "<img src=\"", "\"").map(unescape_html),
+            })
+        })
+        .collect()
+}
+
+/// Returns the text between the first `start` and the `end` after it.
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = text.find(start)? + start.len();
+    let to = text[from..].find(end)? + from;
+    Some(&text[from..to])
+}
+
+#[async_trait]
+impl Platform for WebtoonPlatform {
+    async fn fetch_source(&self, title_no: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {title_no}",
+            self.base.info.name
+        );
+
+        let url = self.get_source_url_from_id(title_no);
+        let html = self.fetch_page(&url, title_no).await?;
+        let name = meta_content(&html, "og:title").ok_or_else(|| FeedError::SourceNotFound {
+            source_id: title_no.to_string(),
+        })?;
+
+        Ok(FeedSource {
+            id: title_no.to_string(),
+            // The series page the short link redirects to, so checks skip the redirect
+            items_id: meta_content(&html, "og:url")
+                .filter(|page| page.starts_with(&self.base.info.api_url))
+                .unwrap_or_else(|| url.clone()),
+            name,
+            description: meta_content(&html, "og:description").unwrap_or_default(),
+            source_url: url,
+            image_url: meta_content(&html, "og:image"),
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, page_url: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {page_url}",
+            self.base.info.name
+        );
+
+        if !page_url.starts_with(&self.base.info.api_url) {
+            return Err(FeedError::InvalidSourceId {
+                source_id: page_url.to_string(),
+            });
+        }
+        let html = self.fetch_page(page_url, page_url).await?;
+        let episode = Self::get_latest_episode(&html, page_url)?;
+
+        Ok(FeedItem {
+            id: episode.number.to_string(),
+            title: episode.title,
+            // The list only shows the day an episode came out
+            published: episode
+                .published
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map_or_else(Utc::now, |date| date.and_utc()),
+            item_url: episode.url,
+            image_url: episode.thumbnail_url,
+            ..Default::default()
+        })
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        // https://www.webtoons.com/<lang>/<genre>/<series>/list?title_no=<id>, the same
+        // with /<episode>/viewer for an episode, or /episodeList?titleNo=<id>
+        self.base.get_nth_path_from_url(source_url, 0)?;
+        let query = source_url
+            .split_once('?')
+            .map(|(_, query)| query.split('#').next().unwrap_or_default())
+            .unwrap_or_default();
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "title_no" || *key == "titleNo")
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(|| {
+                UrlParseError::MissingId {
+                    url: source_url.to_string(),
+                }
+                .into()
+            })
+    }
+
+    fn get_source_url_from_id(&self, title_no: &str) -> String {
+        format!("{}/episodeList?titleNo={title_no}", self.base.info.api_url)
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+
+    fn claims_source_url(&self, source_url: &str) -> bool {
+        // Domain matching misses mobile links
+        url::Url::parse(source_url.trim())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .is_some_and(|host| host == "m.webtoons.com")
+    }
+}
+
+impl PartialEq for WebtoonPlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.api_url == other.base.info.api_url
+    }
+}
+
+impl Eq for WebtoonPlatform {}
+
+impl Hash for WebtoonPlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.api_url.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head>
+<meta property="og:title" content="Tower of God">
+<meta property="og:url" content="https://www.webtoons.com/en/fantasy/tower-of-god/list?title_no=95">
+<meta property="og:image" content="https://swebtoon-phinf.pstatic.net/tower.jpg">
+</head><body><ul id="_listUl">
+<li class="_episodeItem" id="episode_651" data-episode-no="651">
+  <a href="https://www.webtoons.com/en/fantasy/tower-of-god/season-3-ep-233/viewer?title_no=95&amp;episode_no=651">
+    <span class="thmb"><img src="https://webtoon-phinf.pstatic.net/651.jpg" width="77" height="73"></span>
+    <span class="subj"><span>[Season 3] Ep. 233</span></span>
+    <span class="date">
+      Oct 12, 2026
+    </span>
+  </a>
+</li>
+<li class="_episodeItem" id="episode_650" data-episode-no="650">
+  <a href="https://www.webtoons.com/en/fantasy/tower-of-god/season-3-ep-232/viewer?title_no=95&amp;episode_no=650">
+    <span class="subj"><span>[Season 3] Ep. 232</span></span>
+    <span class="date">Oct 5, 2026</span>
+  </a>
+</li>
+</ul></body></html>"#;
+
+    #[test]
+    fn source_ids_from_series_and_episode_urls() {
+        let platform = WebtoonPlatform::new();
+        for url in [
+            "https://www.webtoons.com/en/fantasy/tower-of-god/list?title_no=95",
+            "https://www.webtoons.com/en/fantasy/tower-of-god/list?title_no=95&page=2",
+            "https://www.webtoons.com/en/fantasy/tower-of-god/season-3-ep-233/viewer?episode_no=651&title_no=95",
+            "https://m.webtoons.com/en/fantasy/tower-of-god/list?title_no=95",
+            "https://www.webtoons.com/episodeList?titleNo=95",
+        ] {
+            assert_eq!(platform.get_id_from_source_url(url).unwrap(), "95", "{url}");
+        }
+        assert!(
+            platform
+                .get_id_from_source_url("https://www.webtoons.com/en/fantasy/tower-of-god/list")
+                .is_err()
+        );
+        assert!(
+            platform.claims_source_url(
+                "https://m.webtoons.com/en/fantasy/tower-of-god/list?title_no=95"
+            )
+        );
+        assert_eq!(
+            platform.get_source_url_from_id("95"),
+            "https://www.webtoons.com/episodeList?titleNo=95"
+        );
+    }
+
+    #[test]
+    fn latest_episode_from_episode_list() {
+        let episode = WebtoonPlatform::get_latest_episode(
+            PAGE,
+            "https://www.webtoons.com/en/fantasy/tower-of-god/list?title_no=95",
+        )
+        .unwrap();
+
+        assert_eq!(episode.number, 651);
+        assert_eq!(episode.title, "[Season 3] Ep. 233");
+        assert_eq!(
+            episode.url.as_deref(),
+            Some(
+                "https://www.webtoons.com/en/fantasy/tower-of-god/season-3-ep-233/viewer?title_no=95&episode_no=651"
+            )
+        );
+        assert_eq!(episode.published, NaiveDate::from_ymd_opt(2026, 10, 12));
+        assert_eq!(
+            episode.thumbnail_url.as_deref(),
+            Some("https://webtoon-phinf.pstatic.net/651.jpg")
+        );
+        assert_eq!(parse_episodes(PAGE).len(), 2);
+    }
+
+    #[test]
+    fn cover_and_page_from_meta_tags() {
+        assert_eq!(
+            meta_content(PAGE, "og:image").as_deref(),
+            Some("https://swebtoon-phinf.pstatic.net/tower.jpg")
+        );
+        assert_eq!(
+            meta_content(PAGE, "og:url").as_deref(),
+            Some("https://www.webtoons.com/en/fantasy/tower-of-god/list?title_no=95")
+        );
+    }
+}
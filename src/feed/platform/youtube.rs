@@ -0,0 +1,642 @@
+//! YouTube channel platform integration.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroU32;
+
+use async_trait::async_trait;
+use atom_syndication::Entry;
+use atom_syndication::Feed;
+use chrono::DateTime;
+use chrono::Utc;
+use governor::Quota;
+use governor::RateLimiter;
+use governor::clock::QuantaClock;
+use governor::state::InMemoryState;
+use governor::state::direct::NotKeyed;
+use log::debug;
+use log::info;
+use serde_json::Value;
+use wreq::Client;
+use wreq_util::Emulation;
+
+use crate::feed::BasePlatform;
+use crate::feed::FeedItem;
+use crate::feed::FeedSource;
+use crate::feed::Platform;
+use crate::feed::PlatformInfo;
+use crate::feed::TitleVariants;
+use crate::feed::error::FeedError;
+use crate::feed::error::UrlParseError;
+
+/// Base url of the YouTube Data API.
+const DATA_API_URL: &str = "https://www.googleapis.com/youtube/v3";
+
+/// Number of uploads fetched per check with the Data API.
+const UPLOADS_LIMIT: &str = "5";
+
+/// YouTube platform following the uploads of a channel.
+///
+/// Sources are identified by the handle (`@name`) or channel ID in their url. Uploads
+/// are read from the channel's public upload feed, or from the Data API when an API key
+/// is configured.
+pub struct YoutubePlatform {
+    pub base: BasePlatform,
+    client: Client,
+    limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock>,
+    /// YouTube Data API key. `None` reads the public upload feeds.
+    api_key: Option<String>,
+}
+
+impl YoutubePlatform {
+    /// Creates a new YouTube platform with rate limiting.
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .emulation(Emulation::Chrome137)
+            .build()
+            .unwrap();
+
+        let info = PlatformInfo {
+            name: "YouTube".to_string(),
+            feed_item_name: "Video".to_string(),
+            api_hostname: "www.youtube.com".to_string(),
+            api_domain: "youtube.com".to_string(),
+            api_url: "https://www.youtube.com".to_string(),
+            copyright_notice: "Videos © their respective creators on YouTube".to_string(),
+            logo_url: "https://www.gstatic.com/youtube/img/branding/favicon/favicon_144x144.png"
+                .to_string(),
+            // Channels are polled along with series feeds
+            tags: "series,video".to_string(),
+            unique_item_ids: true,
+            accent_color: Some(0xFF0000),
+            emoji: "▶️".to_string(),
+        };
+
+        // Upload feeds are unmetered but throttled per IP, and each Data API call costs
+        // 1 of the 10,000 daily quota units. Stay well below both.
+        let quota = Quota::per_minute(NonZeroU32::new(60).unwrap());
+        let limiter = RateLimiter::direct(quota);
+
+        Self {
+            base: BasePlatform::new(info).with_quota(quota),
+            client,
+            limiter,
+            api_key: None,
+        }
+    }
+
+    /// Reads channels through the YouTube Data API with `api_key`, if set.
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key.filter(|key| !key.trim().is_empty());
+        self
+    }
+
+    /// Fetches a channel from the Data API by handle or channel ID.
+    async fn fetch_channel(&self, api_key: &str, source_id: &str) -> Result<Value, FeedError> {
+        let by = if is_channel_id(source_id) {
+            "id"
+        } else {
+            "forHandle"
+        };
+        let request = self.client.get(format!("{DATA_API_URL}/channels")).query(&[
+            ("part", "snippet"),
+            (by, source_id),
+            ("key", api_key),
+        ]);
+        let resp = self.send_get_json(request).await?;
+        Self::get_first_item(&resp, source_id).cloned()
+    }
+
+    /// Returns the first of the `items` of a Data API response.
+    fn get_first_item<'a>(resp: &'a Value, source_id: &str) -> Result<&'a Value, FeedError> {
+        resp.get("items")
+            .and_then(|v| v.as_array())
+            .and_then(|items| items.first())
+            .ok_or_else(|| FeedError::SourceNotFound {
+                source_id: source_id.to_string(),
+            })
+    }
+
+    fn get_str<'a>(value: &'a Value, field: &str) -> Result<&'a str, FeedError> {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FeedError::MissingField {
+                field: field.to_string(),
+            })
+    }
+
+    /// Returns the largest thumbnail in a Data API `snippet`.
+    fn get_thumbnail_url(snippet: &Value) -> Option<String> {
+        let thumbnails = snippet.get("thumbnails")?;
+        ["maxres", "high", "medium", "default"]
+            .iter()
+            .find_map(|size| thumbnails.get(size)?.get("url")?.as_str())
+            .map(str::to_string)
+    }
+
+    /// Returns the newest upload in a Data API `playlistItems` response. Private and
+    /// deleted videos have no publish date and are skipped.
+    fn get_latest_upload<'a>(resp: &'a Value, channel_id: &str) -> Result<&'a Value, FeedError> {
+        resp.get("items")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FeedError::MissingField {
+                field: "items".to_string(),
+            })?
+            .iter()
+            .filter_map(|item| {
+                let published = item
+                    .get("contentDetails")?
+                    .get("videoPublishedAt")?
+                    .as_str()?;
+                Some((DateTime::parse_from_rfc3339(published).ok()?, item))
+            })
+            .max_by_key(|(published, _)| *published)
+            .map(|(_, item)| item)
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: channel_id.to_string(),
+            })
+    }
+
+    fn upload_from_api(item: &Value) -> Result<FeedItem, FeedError> {
+        let snippet = item.get("snippet").ok_or_else(|| FeedError::MissingField {
+            field: "snippet".to_string(),
+        })?;
+        let details = item
+            .get("contentDetails")
+            .ok_or_else(|| FeedError::MissingField {
+                field: "contentDetails".to_string(),
+            })?;
+        let video_id = Self::get_str(details, "videoId")?;
+        let published = Self::get_str(details, "videoPublishedAt")?;
+
+        Ok(FeedItem {
+            id: video_id.to_string(),
+            title: Self::get_str(snippet, "title")?.trim().to_string(),
+            published: DateTime::parse_from_rfc3339(published)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|_| FeedError::InvalidTime {
+                    time: published.to_string(),
+                })?,
+            content: snippet
+                .get("description")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|description| !description.is_empty())
+                .map(str::to_string),
+            item_url: Some(video_url(video_id)),
+            image_url: Self::get_thumbnail_url(snippet),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the newest entry of an upload feed.
+    fn get_latest_entry<'a>(feed: &'a Feed, channel_id: &str) -> Result<&'a Entry, FeedError> {
+        feed.entries()
+            .iter()
+            .max_by_key(|entry| *entry.published().unwrap_or(entry.updated()))
+            .ok_or_else(|| FeedError::ItemNotFound {
+                source_id: channel_id.to_string(),
+            })
+    }
+
+    fn upload_from_entry(entry: &Entry) -> Result<FeedItem, FeedError> {
+        let video_id = entry
+            .extensions()
+            .get("yt")
+            .and_then(|yt| yt.get("videoId"))
+            .and_then(|values| values.first())
+            .and_then(|value| value.value())
+            .or_else(|| entry.id().strip_prefix("yt:video:"))
+            .ok_or_else(|| FeedError::MissingField {
+                field: "entry.yt:videoId".to_string(),
+            })?;
+        let media = entry
+            .extensions()
+            .get("media")
+            .and_then(|media| media.get("group"))
+            .and_then(|groups| groups.first());
+        let media_child = |name: &str| {
+            media
+                .and_then(|group| group.children().get(name))
+                .and_then(|values| values.first())
+        };
+
+        Ok(FeedItem {
+            id: video_id.to_string(),
+            title: entry.title().value.trim().to_string(),
+            published: entry
+                .published()
+                .unwrap_or(entry.updated())
+                .with_timezone(&Utc),
+            content: media_child("description")
+                .and_then(|description| description.value())
+                .map(str::trim)
+                .filter(|description| !description.is_empty())
+                .map(str::to_string),
+            item_url: Some(video_url(video_id)),
+            image_url: media_child("thumbnail")
+                .and_then(|thumbnail| thumbnail.attrs().get("url"))
+                .cloned(),
+            ..Default::default()
+        })
+    }
+
+    async fn send(&self, request: wreq::RequestBuilder) -> Result<wreq::Response, wreq::Error> {
+        if self.limiter.check().is_err() {
+            info!("Source {} is ratelimited. Waiting...", self.base.info.name);
+            self.base.requests.record_throttled();
+        }
+        self.limiter.until_ready().await;
+
+        let req = request.build()?;
+        debug!("Making request to: {}", req.url());
+        let result = self.client.execute(req).await;
+        self.base.requests.record(&result);
+        result
+    }
+
+    async fn send_get_json(&self, request: wreq::RequestBuilder) -> Result<Value, FeedError> {
+        let response = self.send(request).await?;
+
+        let body = response.text().await?;
+        let resp: Value = serde_json::from_str(&body)?;
+        if let Some(error) = resp.get("error") {
+            return Err(FeedError::ApiError {
+                message: error
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .map_or_else(|| self.extract_error_message(error), str::to_string),
+            });
+        }
+        Ok(resp)
+    }
+
+    /// Fetches the html of a channel page.
+    async fn fetch_page(&self, source_id: &str) -> Result<String, FeedError> {
+        let url = self.get_source_url_from_id(source_id);
+        let response = self.send(self.client.get(url.as_str())).await?;
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(FeedError::SourceNotFound {
+                source_id: source_id.to_string(),
+            });
+        }
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!("{url} returned HTTP {}", status.as_u16()),
+            });
+        }
+        Ok(response.text().await?)
+    }
+
+    /// Fetches the public upload feed of a channel.
+    async fn fetch_upload_feed(&self, channel_id: &str) -> Result<Feed, FeedError> {
+        let request = self
+            .client
+            .get(format!("{}/feeds/videos.xml", self.base.info.api_url))
+            .query(&[("channel_id", channel_id)]);
+        let response = self.send(request).await?;
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(FeedError::SourceNotFound {
+                source_id: channel_id.to_string(),
+            });
+        }
+        if !status.is_success() {
+            return Err(FeedError::ApiError {
+                message: format!(
+                    "Upload feed of {channel_id} returned HTTP {}",
+                    status.as_u16()
+                ),
+            });
+        }
+
+        let body = response.bytes().await?;
+        Ok(Feed::read_from(&body[..])?)
+    }
+}
+
+/// Whether `id` looks like a channel ID, e.g. `UC_x5XG1OV2P6uZZ5FSM9Ttw`.
+fn is_channel_id(id: &str) -> bool {
+    id.len() == 24
+        && id.starts_with("UC")
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Returns the url of a video.
+fn video_url(video_id: &str) -> String {
+    format!("https://www.youtube.com/watch?v={video_id}")
+}
+
+/// Finds the channel ID in the html of a channel page, from its canonical link or
+/// embedded metadata.
+fn channel_id_from_page(html: &str) -> Option<&str> {
+    [
+        "<link rel=\"canonical\" href=\"https://www.youtube.com/channel/",
+        "<meta itemprop=\"identifier\" content=\"",
+        "\"externalId\":\"",
+    ]
+    .iter()
+    .find_map(|marker| {
+        let start = html.find(marker)? + marker.len();
+        let id = html.get(start..start + 24)?;
+        is_channel_id(id).then_some(id)
+    })
+}
+
+/// Returns the `content` of an Open Graph `<meta property="og:…">` tag.
+pub(super) fn meta_content(html: &str, property: &str) -> Option<String> {
+    let marker = format!("<meta property=\"{property}\" content=\"");
+    let start = html.find(&marker)? + marker.len();
+    let end = html[start..].find('"')? + start;
+    let content = unescape_html(&html[start..end]);
+    Some(content.trim().to_string()).filter(|content| !content.is_empty())
+}
+
+/// Replaces the html entities pages commonly escape text with.
+pub(super) fn unescape_html(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Strips the query and fragment a path segment may end with.
+fn path_segment(segment: &str) -> &str {
+    segment.split(['?', '#']).next().unwrap_or_default()
+}
+
+#[async_trait]
+impl Platform for YoutubePlatform {
+    async fn fetch_source(&self, source_id: &str) -> Result<FeedSource, FeedError> {
+        debug!(
+            "Fetching info from {} for source_id: {source_id}",
+            self.base.info.name
+        );
+
+        let (channel_id, name, description, image_url) = match &self.api_key {
+            Some(api_key) => {
+                let channel = self.fetch_channel(api_key, source_id).await?;
+                let snippet = channel
+                    .get("snippet")
+                    .ok_or_else(|| FeedError::MissingField {
+                        field: "snippet".to_string(),
+                    })?;
+                (
+                    Self::get_str(&channel, "id")?.to_string(),
+                    Self::get_str(snippet, "title")?.trim().to_string(),
+                    snippet
+                        .get("description")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                    Self::get_thumbnail_url(snippet),
+                )
+            }
+            None => {
+                // The page gives the avatar and description, the feed the channel's name
+                let page = self.fetch_page(source_id).await?;
+                let channel_id = if is_channel_id(source_id) {
+                    source_id.to_string()
+                } else {
+                    channel_id_from_page(&page)
+                        .map(str::to_string)
+                        .ok_or_else(|| FeedError::SourceNotFound {
+                            source_id: source_id.to_string(),
+                        })?
+                };
+                let feed = self.fetch_upload_feed(&channel_id).await?;
+                (
+                    channel_id,
+                    feed.title().value.trim().to_string(),
+                    meta_content(&page, "og:description").unwrap_or_default(),
+                    meta_content(&page, "og:image"),
+                )
+            }
+        };
+
+        Ok(FeedSource {
+            id: source_id.to_string(),
+            // The channel ID stays the same when the channel changes its handle
+            items_id: channel_id,
+            name,
+            description,
+            source_url: self.get_source_url_from_id(source_id),
+            image_url,
+            titles: TitleVariants::default(),
+        })
+    }
+
+    async fn fetch_latest(&self, channel_id: &str) -> Result<FeedItem, FeedError> {
+        debug!(
+            "Fetching latest from {} for source_id: {channel_id}",
+            self.base.info.name
+        );
+
+        if !is_channel_id(channel_id) {
+            return Err(FeedError::InvalidSourceId {
+                source_id: channel_id.to_string(),
+            });
+        }
+        match &self.api_key {
+            Some(api_key) => {
+                // Every channel's uploads playlist is its ID with `UU` in place of `UC`
+                let playlist_id = format!("UU{}", &channel_id[2..]);
+                let request = self
+                    .client
+                    .get(format!("{DATA_API_URL}/playlistItems"))
+                    .query(&[
+                        ("part", "snippet,contentDetails"),
+                        ("playlistId", playlist_id.as_str()),
+                        ("maxResults", UPLOADS_LIMIT),
+                        ("key", api_key),
+                    ]);
+                let resp = self.send_get_json(request).await?;
+                Self::upload_from_api(Self::get_latest_upload(&resp, channel_id)?)
+            }
+            None => {
+                let feed = self.fetch_upload_feed(channel_id).await?;
+                Self::upload_from_entry(Self::get_latest_entry(&feed, channel_id)?)
+            }
+        }
+    }
+
+    fn get_id_from_source_url<'a>(&self, source_url: &'a str) -> Result<&'a str, FeedError> {
+        // https://www.youtube.com/@<handle> or https://www.youtube.com/channel/<channel id>
+        let first = path_segment(self.base.get_nth_path_from_url(source_url, 0)?);
+        if first.len() > 1 && first.starts_with('@') {
+            return Ok(first);
+        }
+        if first == "channel" {
+            let id = path_segment(self.base.get_nth_path_from_url(source_url, 1)?);
+            if is_channel_id(id) {
+                return Ok(id);
+            }
+        }
+        Err(UrlParseError::MissingId {
+            url: source_url.to_string(),
+        }
+        .into())
+    }
+
+    fn get_source_url_from_id(&self, source_id: &str) -> String {
+        if source_id.starts_with('@') {
+            format!("{}/{source_id}", self.base.info.api_url)
+        } else {
+            format!("{}/channel/{source_id}", self.base.info.api_url)
+        }
+    }
+
+    fn get_base(&self) -> &BasePlatform {
+        &self.base
+    }
+
+    fn claims_source_url(&self, source_url: &str) -> bool {
+        // Domain matching misses mobile links
+        url::Url::parse(source_url.trim())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .is_some_and(|host| host == "m.youtube.com")
+    }
+}
+
+impl PartialEq for YoutubePlatform {
+    fn eq(&self, other: &Self) -> bool {
+        self.base.info.api_url == other.base.info.api_url
+    }
+}
+
+impl Eq for YoutubePlatform {}
+
+impl Hash for YoutubePlatform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.info.api_url.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns:media="http://search.yahoo.com/mrss/" xmlns="http://www.w3.org/2005/Atom">
+  <id>yt:channel:_x5XG1OV2P6uZZ5FSM9Ttw</id>
+  <title>Test Channel</title>
+  <updated>2026-10-12T10:00:00+00:00</updated>
+  <entry>
+    <id>yt:video:older</id>
+    <yt:videoId>older</yt:videoId>
+    <title>Older video</title>
+    <published>2026-10-05T10:00:00+00:00</published>
+    <updated>2026-10-05T10:00:00+00:00</updated>
+  </entry>
+  <entry>
+    <id>yt:video:newer</id>
+    <yt:videoId>newer</yt:videoId>
+    <title>Newer video</title>
+    <published>2026-10-12T10:00:00+00:00</published>
+    <updated>2026-10-12T10:00:00+00:00</updated>
+    <media:group>
+      <media:title>Newer video</media:title>
+      <media:thumbnail url="https://i1.ytimg.com/vi/newer/hqdefault.jpg" width="480" height="360"/>
+      <media:description>What's new</media:description>
+    </media:group>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn source_ids_from_handle_and_channel_urls() {
+        let platform = YoutubePlatform::new();
+        assert_eq!(
+            platform
+                .get_id_from_source_url("https://www.youtube.com/@GoogleDevelopers?si=abc")
+                .unwrap(),
+            "@GoogleDevelopers"
+        );
+        assert_eq!(
+            platform
+                .get_id_from_source_url(
+                    "https://www.youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw/videos"
+                )
+                .unwrap(),
+            "UC_x5XG1OV2P6uZZ5FSM9Ttw"
+        );
+        assert!(
+            platform
+                .get_id_from_source_url("https://www.youtube.com/watch?v=abc")
+                .is_err()
+        );
+        assert_eq!(
+            platform.get_source_url_from_id("@GoogleDevelopers"),
+            "https://www.youtube.com/@GoogleDevelopers"
+        );
+    }
+
+    #[test]
+    fn latest_upload_from_feed() {
+        let feed = Feed::read_from(FEED.as_bytes()).unwrap();
+        let entry = YoutubePlatform::get_latest_entry(&feed, "UC_x5XG1OV2P6uZZ5FSM9Ttw").unwrap();
+        let item = YoutubePlatform::upload_from_entry(entry).unwrap();
+
+        assert_eq!(item.id, "newer");
+        assert_eq!(item.title, "Newer video");
+        assert_eq!(item.content.as_deref(), Some("What's new"));
+        assert_eq!(
+            item.item_url.as_deref(),
+            Some("https://www.youtube.com/watch?v=newer")
+        );
+        assert_eq!(
+            item.image_url.as_deref(),
+            Some("https://i1.ytimg.com/vi/newer/hqdefault.jpg")
+        );
+    }
+
+    #[test]
+    fn latest_upload_from_api_skips_private_videos() {
+        let resp = json!({
+            "items": [
+                {
+                    "snippet": { "title": "Private video" },
+                    "contentDetails": { "videoId": "private" }
+                },
+                {
+                    "snippet": {
+                        "title": "Launch",
+                        "thumbnails": { "high": { "url": "https://i.ytimg.com/vi/launch/hq.jpg" } }
+                    },
+                    "contentDetails": {
+                        "videoId": "launch",
+                        "videoPublishedAt": "2026-10-12T10:00:00Z"
+                    }
+                }
+            ]
+        });
+
+        let upload = YoutubePlatform::get_latest_upload(&resp, "UC_x5XG1OV2P6uZZ5FSM9Ttw").unwrap();
+        let item = YoutubePlatform::upload_from_api(upload).unwrap();
+
+        assert_eq!(item.id, "launch");
+        assert_eq!(
+            item.image_url.as_deref(),
+            Some("https://i.ytimg.com/vi/launch/hq.jpg")
+        );
+    }
+
+    #[test]
+    fn channel_id_from_canonical_link() {
+        let page = r#"<html><head><link rel="canonical" href="https://www.youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw"><meta property="og:description" content="News &amp; updates"></head></html>"#;
+        assert_eq!(channel_id_from_page(page), Some("UC_x5XG1OV2P6uZZ5FSM9Ttw"));
+        assert_eq!(
+            meta_content(page, "og:description").as_deref(),
+            Some("News & updates")
+        );
+        assert_eq!(channel_id_from_page("<html></html>"), None);
+    }
+}
@@ -0,0 +1,26 @@
+//! Stable API for platforms defined outside this crate.
+//!
+//! Binaries embedding pwr-bot can follow proprietary sources without forking: implement
+//! [`Platform`] and register it before the services are built.
+//!
+//! ```rust,ignore
+//! use pwr_bot::feed::plugin::*;
+//!
+//! let mut platforms = Platforms::new();
+//! platforms.register_platform(Box::new(MyPlatform::new()))?;
+//! let services = Services::new(repos, Arc::new(platforms), &config).await?;
+//! ```
+//!
+//! Only the items re-exported here are kept stable across minor releases. The built-in
+//! platforms and the rest of [`crate::feed`] may change at any time.
+
+pub use async_trait::async_trait;
+
+pub use crate::feed::BasePlatform;
+pub use crate::feed::FeedItem;
+pub use crate::feed::FeedSource;
+pub use crate::feed::Platform;
+pub use crate::feed::PlatformInfo;
+pub use crate::feed::Platforms;
+pub use crate::feed::error::FeedError;
+pub use crate::feed::error::UrlParseError;
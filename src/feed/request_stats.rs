@@ -0,0 +1,251 @@
+//! Per-platform HTTP request counters for `/owner apistatus`.
+//!
+//! Every platform records its requests in the [`RequestStats`] of its [`BasePlatform`],
+//! bucketed by hour so the last 24 hours can be compared to the platform's budget, i.e.
+//! what its rate limiter allows in that time.
+//!
+//! [`BasePlatform`]: crate::feed::BasePlatform
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use governor::Quota;
+use wreq::header::HeaderMap;
+
+/// Hours of history kept.
+pub const WINDOW_HOURS: i64 = 24;
+
+/// Headers platforms report their own rate limit in, most common first.
+const REMAINING_HEADERS: [&str; 2] = ["x-ratelimit-remaining", "ratelimit-remaining"];
+const LIMIT_HEADERS: [&str; 2] = ["x-ratelimit-limit", "ratelimit-limit"];
+
+/// Request counts over some period.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RequestCounts {
+    pub requests: u32,
+    /// Requests that failed to send or got a non-success HTTP status.
+    pub errors: u32,
+    /// Times a request had to wait for the platform's rate limiter.
+    pub throttled: u32,
+}
+
+impl RequestCounts {
+    /// Share of requests that failed, from 0 to 1.
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            f64::from(self.errors) / f64::from(self.requests)
+        }
+    }
+}
+
+/// Rate limit a platform reported in its last response with rate limit headers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub remaining: u32,
+    pub limit: Option<u32>,
+    pub reported_at: DateTime<Utc>,
+}
+
+/// Requests of the last [`WINDOW_HOURS`] against the platform's budget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RequestSummary {
+    pub counts: RequestCounts,
+    /// Requests the rate limiter allows in the window. `None` if the platform has none.
+    pub budget: Option<u64>,
+    pub rate_limit: Option<RateLimitStatus>,
+}
+
+impl RequestSummary {
+    /// Share of the budget used, from 0 to 1.
+    pub fn budget_used(&self) -> Option<f64> {
+        self.budget
+            .filter(|budget| *budget > 0)
+            .map(|budget| f64::from(self.counts.requests) / budget as f64)
+    }
+}
+
+#[derive(Debug)]
+struct HourBucket {
+    /// Hours since the Unix epoch.
+    hour: i64,
+    counts: RequestCounts,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    buckets: VecDeque<HourBucket>,
+    rate_limit: Option<RateLimitStatus>,
+}
+
+/// Request counters of one platform.
+#[derive(Debug, Default)]
+pub struct RequestStats {
+    budget: Option<u64>,
+    state: Mutex<State>,
+}
+
+impl RequestStats {
+    /// Creates counters for a platform limited to `quota`.
+    pub fn with_quota(quota: Quota) -> Self {
+        let window = Duration::from_secs(WINDOW_HOURS as u64 * 3600);
+        let replenish = quota.replenish_interval().as_secs_f64();
+        Self {
+            budget: (replenish > 0.0).then(|| (window.as_secs_f64() / replenish) as u64),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Records a response, or the error sending the request.
+    pub fn record(&self, result: &Result<wreq::Response, wreq::Error>) {
+        let now = Utc::now();
+        match result {
+            Ok(response) => {
+                self.record_at(now, response.status().is_success());
+                if let Some(rate_limit) = Self::parse_rate_limit(response.headers(), now) {
+                    self.lock().rate_limit = Some(rate_limit);
+                }
+            }
+            Err(_) => self.record_at(now, false),
+        }
+    }
+
+    /// Records that a request waited for the rate limiter.
+    pub fn record_throttled(&self) {
+        self.update(Utc::now(), |counts| counts.throttled += 1);
+    }
+
+    fn record_at(&self, now: DateTime<Utc>, success: bool) {
+        self.update(now, |counts| {
+            counts.requests += 1;
+            if !success {
+                counts.errors += 1;
+            }
+        });
+    }
+
+    /// Sums the requests of the last [`WINDOW_HOURS`].
+    pub fn summary(&self) -> RequestSummary {
+        self.summary_at(Utc::now())
+    }
+
+    fn summary_at(&self, now: DateTime<Utc>) -> RequestSummary {
+        let first_hour = Self::hour(now) - WINDOW_HOURS + 1;
+        let state = self.lock();
+        let mut counts = RequestCounts::default();
+        for bucket in state.buckets.iter().filter(|b| b.hour >= first_hour) {
+            counts.requests += bucket.counts.requests;
+            counts.errors += bucket.counts.errors;
+            counts.throttled += bucket.counts.throttled;
+        }
+        RequestSummary {
+            counts,
+            budget: self.budget,
+            rate_limit: state.rate_limit,
+        }
+    }
+
+    fn update(&self, now: DateTime<Utc>, apply: impl FnOnce(&mut RequestCounts)) {
+        let hour = Self::hour(now);
+        let mut state = self.lock();
+        if state
+            .buckets
+            .back()
+            .is_none_or(|bucket| bucket.hour != hour)
+        {
+            state.buckets.push_back(HourBucket {
+                hour,
+                counts: RequestCounts::default(),
+            });
+        }
+        while state
+            .buckets
+            .front()
+            .is_some_and(|bucket| bucket.hour <= hour - WINDOW_HOURS)
+        {
+            state.buckets.pop_front();
+        }
+        if let Some(bucket) = state.buckets.back_mut() {
+            apply(&mut bucket.counts);
+        }
+    }
+
+    fn parse_rate_limit(headers: &HeaderMap, now: DateTime<Utc>) -> Option<RateLimitStatus> {
+        let read = |names: [&str; 2]| {
+            names.iter().find_map(|name| {
+                headers
+                    .get(*name)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse::<u32>().ok())
+            })
+        };
+        Some(RateLimitStatus {
+            remaining: read(REMAINING_HEADERS)?,
+            limit: read(LIMIT_HEADERS),
+            reported_at: now,
+        })
+    }
+
+    fn hour(time: DateTime<Utc>) -> i64 {
+        time.timestamp().div_euclid(3600)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use chrono::TimeDelta;
+    use wreq::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn summary_covers_only_the_last_day() {
+        let stats = RequestStats::with_quota(Quota::per_minute(NonZeroU32::new(30).unwrap()));
+        let now = Utc::now();
+
+        stats.record_at(now - TimeDelta::hours(30), true);
+        stats.record_at(now - TimeDelta::hours(2), false);
+        stats.record_at(now, true);
+        stats.update(now, |counts| counts.throttled += 1);
+
+        let summary = stats.summary_at(now);
+        assert_eq!(
+            summary.counts,
+            RequestCounts {
+                requests: 2,
+                errors: 1,
+                throttled: 1,
+            }
+        );
+        assert_eq!(summary.budget, Some(30 * 60 * 24));
+        assert_eq!(summary.counts.error_rate(), 0.5);
+    }
+
+    #[test]
+    fn parse_rate_limit_reads_either_header_style() {
+        let now = Utc::now();
+        let mut headers = HeaderMap::new();
+        assert_eq!(RequestStats::parse_rate_limit(&headers, now), None);
+
+        headers.insert("ratelimit-remaining", HeaderValue::from_static("2999"));
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("3000"));
+        assert_eq!(
+            RequestStats::parse_rate_limit(&headers, now),
+            Some(RateLimitStatus {
+                remaining: 2999,
+                limit: Some(3000),
+                reported_at: now,
+            })
+        );
+    }
+}
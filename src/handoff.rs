@@ -0,0 +1,165 @@
+//! Handoff snapshot passed from a run that shut down cleanly to the next one.
+//!
+//! On shutdown, pwr-bot writes the voice sessions still open, when each feed is checked
+//! next and the notifications not sent yet to `handoff.json` in the data directory. The
+//! next start reads the file and removes it once it took everything over: voice sessions
+//! of members still in the same channel carry on instead of being closed at the last
+//! heartbeat, so a deploy loses no voice time, and feeds keep their poll schedule
+//! instead of all being checked at once.
+//!
+//! Feed items are stored before their notifications are published, so a restart never
+//! notifies one again. Notifications are queued in the delivery outbox before they are
+//! sent and removed once sent. Shutdown waits a moment for the ones in flight, and the
+//! snapshot lists those still in the outbox, which the next start sends.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::entity::VoiceSessionsEntity;
+
+/// A snapshot older than this is ignored, as the bot was down too long for its voice
+/// sessions to carry on.
+pub const HANDOFF_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// How long after startup members found in voice can carry on a handed-off session.
+/// Sessions still handed off afterwards end when the previous run shut down.
+pub const HANDOFF_RESUME_WINDOW: Duration = Duration::from_secs(2 * 60);
+
+/// Name of the snapshot file in the data directory.
+const HANDOFF_FILE: &str = "handoff.json";
+
+/// State a run hands to the next one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handoff {
+    /// When the snapshot was written, after the last voice flush.
+    pub written_at: DateTime<Utc>,
+    /// Voice sessions open at shutdown.
+    pub voice_sessions: Vec<HandoffSession>,
+    /// When each feed is checked next, by feed ID.
+    pub poll_schedule: HashMap<i32, DateTime<Utc>>,
+    /// Notifications still in the delivery outbox at shutdown, by outbox ID.
+    #[serde(default)]
+    pub undelivered: Vec<i32>,
+}
+
+/// A voice session left open at shutdown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HandoffSession {
+    pub guild_id: u64,
+    pub user_id: u64,
+    pub channel_id: u64,
+    pub join_time: DateTime<Utc>,
+}
+
+impl From<&VoiceSessionsEntity> for HandoffSession {
+    fn from(session: &VoiceSessionsEntity) -> Self {
+        Self {
+            guild_id: session.guild_id,
+            user_id: session.user_id,
+            channel_id: session.channel_id,
+            join_time: session.join_time,
+        }
+    }
+}
+
+/// Returns the path of the snapshot in `data_path`.
+pub fn path(data_path: &Path) -> PathBuf {
+    data_path.join(HANDOFF_FILE)
+}
+
+/// Writes the snapshot and syncs it to disk. It is written next to its final path and
+/// renamed, so a crash mid-write leaves no partial snapshot.
+pub fn write(path: &Path, handoff: &Handoff) -> std::io::Result<()> {
+    let partial = path.with_extension("json.partial");
+    let mut file = File::create(&partial)?;
+    file.write_all(&serde_json::to_vec(handoff)?)?;
+    file.sync_all()?;
+    std::fs::rename(&partial, path)
+}
+
+/// Reads the snapshot. Returns `None` when there is none or it was written more than
+/// [`HANDOFF_MAX_AGE`] before `now`.
+///
+/// The snapshot stays until [`remove`]d, so a start that fails before taking it over
+/// leaves it to the next one.
+pub fn read(path: &Path, now: DateTime<Utc>) -> std::io::Result<Option<Handoff>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let handoff: Handoff = serde_json::from_slice(&bytes)?;
+    let age = (now - handoff.written_at).to_std().unwrap_or_default();
+    Ok((age <= HANDOFF_MAX_AGE).then_some(handoff))
+}
+
+/// Removes the snapshot once it was taken over, so a later crash doesn't reuse it.
+pub fn remove(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn snapshot_is_read_until_removed_while_fresh() {
+        let path =
+            std::env::temp_dir().join(format!("pwr-bot-handoff-{}.json", std::process::id()));
+        let written_at = Utc::now();
+        let handoff = Handoff {
+            written_at,
+            voice_sessions: vec![HandoffSession {
+                guild_id: 1,
+                user_id: 2,
+                channel_id: 3,
+                join_time: written_at - TimeDelta::hours(1),
+            }],
+            poll_schedule: HashMap::from([(7, written_at + TimeDelta::minutes(20))]),
+            undelivered: vec![4, 5],
+        };
+
+        write(&path, &handoff).unwrap();
+        assert_eq!(read(&path, written_at).unwrap(), Some(handoff.clone()));
+        // A start that failed before taking it over leaves it to the next one
+        assert_eq!(read(&path, written_at).unwrap(), Some(handoff.clone()));
+        remove(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(read(&path, written_at).unwrap(), None);
+        remove(&path).unwrap();
+
+        write(&path, &handoff).unwrap();
+        assert_eq!(
+            read(&path, written_at + TimeDelta::minutes(11)).unwrap(),
+            None
+        );
+        remove(&path).unwrap();
+    }
+
+    #[test]
+    fn snapshot_without_undelivered_notifications_is_read() {
+        let handoff: Handoff = serde_json::from_value(serde_json::json!({
+            "written_at": "2026-10-16T12:00:00Z",
+            "voice_sessions": [],
+            "poll_schedule": {},
+        }))
+        .unwrap();
+        assert!(handoff.undelivered.is_empty());
+    }
+}
@@ -1,20 +1,26 @@
 //! pwr-bot - A Discord bot with feed subscriptions and voice channel tracking.
 //!
 //! This crate provides a Discord bot implementation with features including:
-//! - Feed subscriptions (MangaDex, AniList, Comick)
+//! - Feed subscriptions (MangaDex, AniList, Comick, WEBTOON, Bluesky, crates.io, Nyaa, YouTube, Twitch, podcasts, RSS/Atom feeds)
 //! - Voice channel activity tracking and leaderboards
 //! - Server configuration management
+//! - An optional read-only web dashboard
 
 pub mod bot;
+pub mod check;
+pub mod cli;
 pub mod config;
 pub mod entity;
 pub mod error;
 pub mod event;
 pub mod feed;
+pub mod handoff;
 pub mod logging;
 pub mod macros;
+pub mod readiness;
 pub mod repo;
 pub mod service;
 pub mod subscriber;
 pub mod task;
 pub mod update;
+pub mod web;
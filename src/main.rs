@@ -1,75 +1,399 @@
 //! Application entry point for pwr-bot.
 //!
-//! Initializes all components and starts the Discord bot.
+//! Initializes all components and starts the Discord bot. With `ENABLE_DISCORD_BOT=false`
+//! the Discord client is skipped and pwr-bot runs headless as a feed notification engine.
+//! `pwr-bot --check` validates the deployment and exits without starting anything, and
+//! the other subcommands of [`Cli`] run maintenance operations without Discord.
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use std::time::Instant;
 
 use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use clap::Parser;
 use dotenv::dotenv;
 use log::debug;
+use log::error;
 use log::info;
+use log::warn;
+use poise::serenity_prelude::UserId;
 use pwr_bot::bot::Bot;
+use pwr_bot::bot::manager::BotManager;
+use pwr_bot::check;
+use pwr_bot::check::CheckReport;
+use pwr_bot::check::CheckStatus;
+use pwr_bot::cli;
+use pwr_bot::cli::Cli;
+use pwr_bot::cli::CliCommand;
 use pwr_bot::config::Config;
+use pwr_bot::event::AiringEventsEvent;
 use pwr_bot::event::FeedUpdateEvent;
+use pwr_bot::event::ReleaseCalendarEvent;
+use pwr_bot::event::VoiceGoalReachedEvent;
 use pwr_bot::event::VoiceStateEvent;
 use pwr_bot::event::event_bus::EventBus;
 use pwr_bot::feed::Platforms;
+use pwr_bot::handoff;
+use pwr_bot::handoff::Handoff;
+use pwr_bot::handoff::HandoffSession;
 use pwr_bot::logging::setup_logging;
+use pwr_bot::readiness;
 use pwr_bot::repo::PgRepos;
 use pwr_bot::repo::traits::Repos;
 use pwr_bot::service::Services;
+use pwr_bot::subscriber::airing_events::AiringEventsSubscriber;
 use pwr_bot::subscriber::discord_dm::DiscordDmSubscriber;
 use pwr_bot::subscriber::discord_guild::DiscordGuildSubscriber;
+use pwr_bot::subscriber::feed_stream::FeedStreamSubscriber;
+use pwr_bot::subscriber::release_calendar::ReleaseCalendarSubscriber;
+use pwr_bot::subscriber::voice_goal::VoiceGoalSubscriber;
 use pwr_bot::subscriber::voice_state::VoiceStateSubscriber;
+use pwr_bot::task::afk_mover::AfkMoverTask;
+use pwr_bot::task::airing_events::AiringEventsTask;
+use pwr_bot::task::anilist_sync::AniListSyncTask;
+use pwr_bot::task::data_pruning::DataPruningTask;
+use pwr_bot::task::leaderboard_snapshot::LeaderboardSnapshotTask;
+use pwr_bot::task::poll_schedule::PollSchedule;
+use pwr_bot::task::release_calendar::ReleaseCalendarTask;
 use pwr_bot::task::series_feed_publisher::SeriesFeedPublisher;
+use pwr_bot::task::startup_reconciler::Reconciliation;
+use pwr_bot::task::startup_reconciler::StartupReconciler;
+use pwr_bot::task::supervisor::TaskMonitor;
+use pwr_bot::task::voice_flags::VoiceFlagTask;
+use pwr_bot::task::voice_goal::VoiceGoalTask;
 use pwr_bot::task::voice_heartbeat::VoiceHeartbeatManager;
+use pwr_bot::task::voice_projection::VoiceProjectionTask;
+use pwr_bot::task::voice_totals::VoiceTotalsTask;
+use pwr_bot::web::WebDashboard;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
+    let cli = Cli::parse();
+    if cli.check {
+        return run_check().await;
+    }
+
+    match cli.command.unwrap_or(CliCommand::Run) {
+        CliCommand::Run => run().await,
+        command => run_maintenance(command).await,
+    }
+}
 
+/// Starts every enabled component and runs until Ctrl+C or SIGTERM.
+async fn run() -> Result<()> {
     let init_start = Instant::now();
     let config = load_config().await?;
-    let event_bus = Arc::new(EventBus::new());
+    // A marker left by a run that crashed would report this one ready too early
+    if let Some(ready_file) = &config.ready_file {
+        readiness::clear(ready_file)?;
+    }
+    let tasks = Arc::new(TaskMonitor::new());
+    let event_bus = Arc::new(EventBus::new().with_monitor(tasks.clone()));
 
     let repos = setup_database(&config, init_start).await?;
-    let platforms = Arc::new(Platforms::new());
-    let services = setup_services(repos.clone(), platforms.clone()).await?;
+    let platforms = Arc::new(Platforms::from_config(&config));
+    let services = setup_services(&config, repos.clone(), platforms.clone()).await?;
+    let handoff = read_handoff(&config);
+    // Event sourcing recovers sessions from the event log instead
+    let voice_handoff = handoff
+        .as_ref()
+        .filter(|_| !services.voice_tracking.records_voice_events());
+
+    let (bots, voice_heartbeat) = if config.features.discord_bot {
+        let (voice_heartbeat, mut reconciliation) = setup_voice_tracking(
+            &config,
+            &services,
+            event_bus.clone(),
+            &tasks,
+            voice_handoff,
+            init_start,
+        )
+        .await?;
+        let mut voice_subscriber = VoiceStateSubscriber::new(services.clone());
+        if let Some(voice_handoff) = voice_handoff {
+            voice_subscriber = voice_subscriber.with_handoff(voice_handoff);
+        }
+        let voice_subscriber = Arc::new(voice_subscriber);
+        if voice_handoff.is_some_and(|h| !h.voice_sessions.is_empty()) {
+            close_handed_off_later(voice_subscriber.clone());
+        }
+        let bots = setup_bots(
+            &config,
+            event_bus.clone(),
+            platforms,
+            services.clone(),
+            voice_subscriber.clone(),
+            init_start,
+        )
+        .await?;
 
-    let voice_heartbeat = setup_voice_tracking(&services, init_start).await?;
+        setup_discord_subscribers(
+            event_bus.clone(),
+            bots.main().clone(),
+            services.clone(),
+            voice_subscriber,
+        )
+        .await?;
+        if let Err(e) = reconciliation
+            .redeliver(services.feed_stats.as_ref(), &event_bus)
+            .await
+        {
+            error!("Failed to send notifications left in the outbox: {e}");
+        }
+        report_reconciliation(&reconciliation, bots.main(), &config).await;
+        setup_afk_mover(bots.main().clone(), &services, &tasks).await;
+        setup_release_calendar(&services, event_bus.clone(), &tasks).await;
+        setup_airing_events(&services, event_bus.clone(), &tasks).await;
+        (Some(bots), Some(voice_heartbeat))
+    } else {
+        info!("Discord bot is disabled. Running headless.");
+        // Findings are only logged without a bot to DM the owner
+        StartupReconciler::new(services.feed_subscription.clone())
+            .run()
+            .await?;
+        (None, None)
+    };
 
-    let voice_subscriber = Arc::new(VoiceStateSubscriber::new(services.clone()));
-    let bot = setup_bot(
+    let publishers = setup_publishers(
         &config,
+        &services,
         event_bus.clone(),
-        platforms,
-        services.clone(),
-        voice_subscriber.clone(),
+        &tasks,
+        handoff.map(|h| h.poll_schedule).unwrap_or_default(),
         init_start,
     )
     .await?;
+    // Taken over; a start failing before this reads it again
+    remove_handoff(&config);
+    setup_data_pruning(&services, &tasks).await;
+    setup_anilist_sync(&services, &tasks).await;
+    setup_web_dashboard(&config, &services, bots.as_ref(), &event_bus, tasks).await?;
 
-    setup_subscribers(
-        event_bus.clone(),
-        bot.clone(),
-        services.clone(),
-        voice_subscriber,
-    )
-    .await?;
-    setup_publishers(&config, &services, event_bus.clone(), init_start)?;
+    // With the Discord bot, the main bot writes the marker when the gateway is ready
+    if bots.is_none()
+        && let Some(ready_file) = &config.ready_file
+    {
+        readiness::mark_ready(ready_file)?;
+    }
 
     info!(
-        "pwr-bot is up in {:.2}s. Press Ctrl+C to stop.",
+        "pwr-bot is up in {:.2}s. Press Ctrl+C or send SIGTERM to stop.",
         init_start.elapsed().as_secs_f64()
     );
-    tokio::signal::ctrl_c().await?;
-    info!("Ctrl+C received, shutting down.");
-    voice_heartbeat.update().await;
+    let signal = shutdown_signal().await?;
+    info!("{signal} received, shutting down.");
+    if let Some(ready_file) = &config.ready_file
+        && let Err(e) = readiness::clear(ready_file)
+    {
+        warn!("Failed to remove ready file {}: {e}", ready_file.display());
+    }
+    // No new notifications are published once the publishers stopped
+    let poll_schedule = stop_publishers(&publishers).await;
+    if let Some(bots) = &bots {
+        bots.shutdown(Duration::from_secs(10)).await;
+    }
+    // Notifications still in flight afterwards stay in the outbox for the next start
+    if tokio::time::timeout(Duration::from_secs(10), event_bus.wait_idle())
+        .await
+        .is_err()
+    {
+        warn!("Timed out waiting for notifications in flight, the next start sends them");
+    }
+    let mut voice_flushed = false;
+    if let Some(voice_heartbeat) = voice_heartbeat {
+        match voice_heartbeat.flush().await {
+            Ok(flushed) => {
+                info!("Flushed {flushed} open voice sessions");
+                voice_flushed = true;
+            }
+            Err(e) => error!("Failed to flush voice sessions, the next start recovers them: {e}"),
+        }
+    }
+    write_handoff(&config, &services, voice_flushed, poll_schedule).await;
 
     Ok(())
 }
 
+/// Reads the snapshot written by the previous run's shutdown, if it is fresh enough to
+/// use. A snapshot that can't be read is ignored, as if the previous run crashed.
+fn read_handoff(config: &Config) -> Option<Handoff> {
+    match handoff::read(&handoff::path(&config.data_path), Utc::now()) {
+        Ok(Some(handoff)) => {
+            info!(
+                "Taking over {} voice sessions, {} feed schedules and {} undelivered notifications from the previous run",
+                handoff.voice_sessions.len(),
+                handoff.poll_schedule.len(),
+                handoff.undelivered.len()
+            );
+            Some(handoff)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Ignoring unreadable handoff from the previous run: {e}");
+            None
+        }
+    }
+}
+
+/// Removes the snapshot once the startup took it over.
+fn remove_handoff(config: &Config) {
+    let path = handoff::path(&config.data_path);
+    if let Err(e) = handoff::remove(&path) {
+        warn!("Failed to remove handoff {}: {e}", path.display());
+    }
+}
+
+/// Stops the feed publishers. Returns when each feed is checked next.
+async fn stop_publishers(publishers: &[Arc<SeriesFeedPublisher>]) -> HashMap<i32, DateTime<Utc>> {
+    let mut poll_schedule = HashMap::new();
+    for publisher in publishers {
+        match tokio::time::timeout(Duration::from_secs(10), publisher.shutdown()).await {
+            Ok(schedule) => poll_schedule.extend(schedule),
+            Err(_) => warn!("Timed out stopping a feed publisher, its schedule is not handed off"),
+        }
+    }
+    poll_schedule
+}
+
+/// Writes the snapshot for the next run to take over: the voice sessions still open,
+/// if they were flushed, when each feed is checked next, and the notifications still
+/// in the outbox.
+async fn write_handoff(
+    config: &Config,
+    services: &Services,
+    voice_flushed: bool,
+    poll_schedule: HashMap<i32, DateTime<Utc>>,
+) {
+    let mut handoff = Handoff {
+        poll_schedule,
+        ..Default::default()
+    };
+    match services.feed_stats.undelivered_ids().await {
+        Ok(undelivered) => handoff.undelivered = undelivered,
+        Err(e) => error!("Failed to load undelivered notifications for the handoff: {e}"),
+    }
+    // Event sourcing recovers sessions from the event log instead
+    if voice_flushed && !services.voice_tracking.records_voice_events() {
+        match services.voice_tracking.find_active_sessions().await {
+            Ok(sessions) => {
+                handoff.voice_sessions = sessions.iter().map(HandoffSession::from).collect()
+            }
+            Err(e) => error!("Failed to load open voice sessions for the handoff: {e}"),
+        }
+    }
+    handoff.written_at = Utc::now();
+
+    let path = handoff::path(&config.data_path);
+    match handoff::write(&path, &handoff) {
+        Ok(()) => info!(
+            "Handed off {} voice sessions, {} feed schedules and {} undelivered notifications to the next run",
+            handoff.voice_sessions.len(),
+            handoff.poll_schedule.len(),
+            handoff.undelivered.len()
+        ),
+        Err(e) => error!("Failed to write handoff to {}: {e}", path.display()),
+    }
+}
+
+/// Closes the voice sessions handed off by the previous run that no member carried on,
+/// once the members in voice had time to be found.
+fn close_handed_off_later(voice_subscriber: Arc<VoiceStateSubscriber>) {
+    tokio::spawn(async move {
+        tokio::time::sleep(handoff::HANDOFF_RESUME_WINDOW).await;
+        match voice_subscriber.close_handed_off().await {
+            Ok(closed) if closed > 0 => {
+                info!("Closed {closed} handed-off voice sessions no member carried on")
+            }
+            Ok(_) => {}
+            Err(e) => error!("Failed to close handed-off voice sessions: {e}"),
+        }
+    });
+}
+
+/// Waits for Ctrl+C or, on Unix, SIGTERM, which `docker stop` and other container
+/// runtimes send. Returns the signal's name.
+async fn shutdown_signal() -> Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::SignalKind;
+
+        let mut terminate = tokio::signal::unix::signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result?;
+                Ok("Ctrl+C")
+            }
+            _ = terminate.recv() => Ok("SIGTERM"),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+        Ok("Ctrl+C")
+    }
+}
+
+/// Runs a maintenance subcommand on the database, without Discord.
+async fn run_maintenance(command: CliCommand) -> Result<()> {
+    let init_start = Instant::now();
+    let config = load_config().await?;
+    // Applies pending migrations, which is all `migrate` does
+    let repos = setup_database(&config, init_start).await?;
+    if matches!(command, CliCommand::Migrate) {
+        return Ok(());
+    }
+    let services =
+        setup_services(&config, repos, Arc::new(Platforms::from_config(&config))).await?;
+
+    match command {
+        CliCommand::Run | CliCommand::Migrate => {}
+        CliCommand::Backup { output } => {
+            let output = output.unwrap_or_else(|| config.data_path.clone());
+            cli::backup(&services, &output).await?;
+        }
+        CliCommand::Export { target, output } => {
+            cli::export(&services, &target, &output).await?;
+        }
+        CliCommand::ImportOpml { target, file } => {
+            cli::import_opml(&services, &target, &file).await?;
+        }
+        CliCommand::Prune => cli::prune(&services).await,
+    }
+    Ok(())
+}
+
+/// Validates the configuration, database and Discord credentials, prints the report and
+/// exits non-zero if a check failed.
+async fn run_check() -> Result<()> {
+    let mut config = Config::new();
+    let report = match config.load() {
+        Ok(()) => {
+            let mut report = check::run(&config).await;
+            report.push(
+                "permissions",
+                CheckStatus::Skip,
+                "Needs a gateway connection, run /owner config-check",
+            );
+            report
+        }
+        Err(e) => {
+            let mut report = CheckReport::default();
+            report.push("config", CheckStatus::Fail, e.to_string());
+            report
+        }
+    };
+
+    print!("{}", report.render_text());
+    if !report.passed() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 async fn load_config() -> Result<Arc<Config>> {
     debug!("Loading configuration...");
     let mut config = Config::new();
@@ -98,47 +422,149 @@ async fn setup_database(
 }
 
 async fn setup_services(
+    config: &Config,
     repos: Arc<dyn Repos + Send + Sync>,
     platforms: Arc<Platforms>,
 ) -> Result<Arc<Services>> {
     debug!("Setting up Services...");
-    Ok(Arc::new(Services::new(repos, platforms).await?))
+    Ok(Arc::new(Services::new(repos, platforms, config).await?))
 }
 
 async fn setup_voice_tracking(
+    config: &Config,
     services: &Services,
+    event_bus: Arc<EventBus>,
+    tasks: &Arc<TaskMonitor>,
+    handoff: Option<&Handoff>,
     init_start: Instant,
-) -> Result<Arc<VoiceHeartbeatManager>> {
-    let voice_heartbeat = Arc::new(VoiceHeartbeatManager::new(
+) -> Result<(Arc<VoiceHeartbeatManager>, Reconciliation)> {
+    let voice_heartbeat = Arc::new(
+        VoiceHeartbeatManager::new(services.internal.clone(), services.voice_tracking.clone())
+            .with_interval(config.voice_heartbeat_interval),
+    );
+
+    let voice_projection = services
+        .voice_tracking
+        .records_voice_events()
+        .then(|| Arc::new(VoiceProjectionTask::new(services.voice_tracking.clone())));
+    let reconciliation = StartupReconciler::new(services.feed_subscription.clone())
+        .with_voice(voice_heartbeat.clone(), voice_projection.clone())
+        .with_handoff(
+            handoff
+                .map(|h| h.voice_sessions.clone())
+                .unwrap_or_default(),
+        )
+        .run()
+        .await?;
+
+    voice_heartbeat.clone().start(tasks).await;
+    if let Some(voice_projection) = voice_projection {
+        voice_projection.start(tasks).await;
+    }
+
+    Arc::new(LeaderboardSnapshotTask::new(
         services.internal.clone(),
         services.voice_tracking.clone(),
-    ));
+    ))
+    .start(tasks)
+    .await;
 
-    info!("Performing voice tracking crash recovery...");
-    let recovered = voice_heartbeat.recover_from_crash().await?;
-    if recovered > 0 {
-        info!("Recovered {recovered} orphaned voice sessions");
-    }
+    Arc::new(VoiceTotalsTask::new(
+        services.internal.clone(),
+        services.voice_tracking.clone(),
+    ))
+    .start(tasks)
+    .await;
 
-    voice_heartbeat.clone().start().await;
+    Arc::new(VoiceFlagTask::new(services.voice_tracking.clone()))
+        .start(tasks)
+        .await;
+
+    Arc::new(VoiceGoalTask::new(
+        services.internal.clone(),
+        services.voice_tracking.clone(),
+        event_bus,
+    ))
+    .start(tasks)
+    .await;
     debug!(
         "Voice tracking setup complete ({:.2}s).",
         init_start.elapsed().as_secs_f64()
     );
 
-    Ok(voice_heartbeat.clone())
+    Ok((voice_heartbeat.clone(), reconciliation))
+}
+
+async fn report_reconciliation(reconciliation: &Reconciliation, bot: &Bot, config: &Config) {
+    let owner_id = match config.admin_id.parse::<UserId>() {
+        Ok(owner_id) => owner_id,
+        Err(e) => {
+            warn!("Cannot report startup reconciliation, invalid ADMIN_ID: {e}");
+            return;
+        }
+    };
+    if let Err(e) = reconciliation.report_to_owner(bot, owner_id).await {
+        warn!("Failed to report startup reconciliation to the bot owner: {e}");
+    }
+}
+
+async fn setup_afk_mover(bot: Arc<Bot>, services: &Services, tasks: &Arc<TaskMonitor>) {
+    Arc::new(AfkMoverTask::new(bot, services.voice_tracking.clone()))
+        .start(tasks)
+        .await;
+}
+
+async fn setup_data_pruning(services: &Services, tasks: &Arc<TaskMonitor>) {
+    Arc::new(DataPruningTask::new(
+        services.feed_subscription.clone(),
+        services.voice_tracking.clone(),
+    ))
+    .start(tasks)
+    .await;
+}
+
+async fn setup_anilist_sync(services: &Services, tasks: &Arc<TaskMonitor>) {
+    Arc::new(AniListSyncTask::new(services.anilist_sync.clone()))
+        .start(tasks)
+        .await;
+}
+
+async fn setup_release_calendar(
+    services: &Services,
+    event_bus: Arc<EventBus>,
+    tasks: &Arc<TaskMonitor>,
+) {
+    Arc::new(ReleaseCalendarTask::new(
+        services.release_calendar.clone(),
+        event_bus,
+    ))
+    .start(tasks)
+    .await;
 }
 
-async fn setup_bot(
+async fn setup_airing_events(
+    services: &Services,
+    event_bus: Arc<EventBus>,
+    tasks: &Arc<TaskMonitor>,
+) {
+    Arc::new(AiringEventsTask::new(
+        services.release_calendar.clone(),
+        event_bus,
+    ))
+    .start(tasks)
+    .await;
+}
+
+async fn setup_bots(
     config: &Arc<Config>,
     event_bus: Arc<EventBus>,
     platforms: Arc<Platforms>,
     services: Arc<Services>,
     voice_subscriber: Arc<VoiceStateSubscriber>,
     init_start: Instant,
-) -> Result<Arc<Bot>> {
-    info!("Starting bot...");
-    let mut bot = Bot::new(
+) -> Result<BotManager> {
+    info!("Starting bots...");
+    let bots = BotManager::start(
         config.clone(),
         event_bus,
         platforms,
@@ -147,56 +573,118 @@ async fn setup_bot(
     )
     .await?;
 
-    bot.start();
-    let bot = Arc::new(bot);
     info!(
         "Bot setup complete ({:.2}s).",
         init_start.elapsed().as_secs_f64()
     );
 
-    Ok(bot)
+    Ok(bots)
 }
 
-async fn setup_subscribers(
+async fn setup_web_dashboard(
+    config: &Config,
+    services: &Arc<Services>,
+    bots: Option<&BotManager>,
+    event_bus: &EventBus,
+    tasks: Arc<TaskMonitor>,
+) -> Result<()> {
+    let features = &config.features;
+    if !features.web_dashboard && !features.web_api && config.mal.is_none() {
+        return Ok(());
+    }
+    debug!("Setting up Web dashboard...");
+
+    let mut dashboard = WebDashboard::new(services.clone())
+        .with_pages(features.web_dashboard)
+        .with_api(features.web_api)
+        .with_mal_callback(config.mal.is_some())
+        .with_tasks(tasks);
+    if let Some(bots) = bots {
+        dashboard = dashboard
+            .with_cache(bots.main().cache.clone())
+            .with_clients(bots.clients().clone())
+            .with_renderer(bots.renderer().clone());
+    }
+
+    if features.web_api {
+        let feed_stream = Arc::new(FeedStreamSubscriber::new(services.clone()));
+        event_bus.register_subcriber::<FeedUpdateEvent, _>(feed_stream.clone());
+        dashboard = dashboard.with_feed_stream(feed_stream);
+    }
+
+    Arc::new(dashboard).start(&config.web.bind_addr).await
+}
+
+async fn setup_discord_subscribers(
     event_bus: Arc<EventBus>,
     bot: Arc<Bot>,
     services: Arc<Services>,
     voice_subscriber: Arc<VoiceStateSubscriber>,
 ) -> Result<()> {
-    debug!("Setting up Subscribers...");
+    debug!("Setting up Discord subscribers...");
 
     let discord_dm_subscriber = Arc::new(DiscordDmSubscriber::new(bot.clone(), services.clone()));
-    let discord_channel_subscriber = Arc::new(DiscordGuildSubscriber::new(bot, services));
+    let release_calendar_subscriber = Arc::new(ReleaseCalendarSubscriber::new(
+        bot.clone(),
+        services.internal.clone(),
+    ));
+    let discord_channel_subscriber = Arc::new(DiscordGuildSubscriber::new(bot.clone(), services));
+    let airing_events_subscriber = Arc::new(AiringEventsSubscriber::new(bot.clone()));
+    let voice_goal_subscriber = Arc::new(VoiceGoalSubscriber::new(bot));
 
     event_bus
         .register_subcriber::<FeedUpdateEvent, _>(discord_dm_subscriber)
         .register_subcriber::<FeedUpdateEvent, _>(discord_channel_subscriber)
-        .register_subcriber::<VoiceStateEvent, _>(voice_subscriber);
+        .register_subcriber::<VoiceStateEvent, _>(voice_subscriber)
+        .register_subcriber::<VoiceGoalReachedEvent, _>(voice_goal_subscriber)
+        .register_subcriber::<ReleaseCalendarEvent, _>(release_calendar_subscriber)
+        .register_subcriber::<AiringEventsEvent, _>(airing_events_subscriber);
 
     Ok(())
 }
 
-fn setup_publishers(
+async fn setup_publishers(
     config: &Config,
     services: &Services,
     event_bus: Arc<EventBus>,
+    tasks: &Arc<TaskMonitor>,
+    poll_schedule: HashMap<i32, DateTime<Utc>>,
     init_start: Instant,
-) -> Result<()> {
+) -> Result<Vec<Arc<SeriesFeedPublisher>>> {
     if !config.features.feed_publisher {
-        return Ok(());
+        return Ok(Vec::new());
     }
     debug!("Setting up Publishers...");
 
-    SeriesFeedPublisher::new(
+    let mut publishers = vec![SeriesFeedPublisher::with_schedule(
         services.feed_subscription.clone(),
-        event_bus,
-        config.poll_interval,
-    )
-    .start()?;
+        event_bus.clone(),
+        PollSchedule {
+            base: config.poll_interval,
+            min: config.poll_interval_min,
+            max: config.poll_interval_max,
+            dormant_multiplier: config.poll_dormant_multiplier,
+        },
+    )];
+
+    // Twitch channels are only registered with client credentials
+    if let Some(twitch) = &config.twitch {
+        publishers.push(SeriesFeedPublisher::live(
+            services.feed_subscription.clone(),
+            event_bus,
+            twitch.poll_interval,
+        ));
+    }
+
+    for publisher in &publishers {
+        // Each publisher only tracks the feeds of its own tag
+        publisher.restore_schedule(poll_schedule.clone()).await;
+        publisher.clone().start(tasks)?;
+    }
 
     info!(
         "Publishers setup complete ({:.2}s).",
         init_start.elapsed().as_secs_f64()
     );
-    Ok(())
+    Ok(publishers)
 }
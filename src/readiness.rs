@@ -0,0 +1,45 @@
+//! Readiness marker file for container health checks.
+//!
+//! With `READY_FILE` set, the file exists only while pwr-bot is up: it is written once
+//! the main bot receives Discord's `Ready`, or right after startup when headless, and
+//! removed at startup and on shutdown. A health check can then test for the file.
+
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Utc;
+
+/// Writes the marker with the current time and syncs it to disk.
+pub fn mark_ready(path: &Path) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "{}", Utc::now().to_rfc3339())?;
+    file.sync_all()
+}
+
+/// Removes the marker, if there is one.
+pub fn clear(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_is_written_and_cleared() {
+        let path = std::env::temp_dir().join(format!("pwr-bot-ready-{}", std::process::id()));
+
+        mark_ready(&path).unwrap();
+        assert!(path.exists());
+
+        clear(&path).unwrap();
+        assert!(!path.exists());
+        // Clearing twice is fine, e.g. at startup after a clean shutdown
+        clear(&path).unwrap();
+    }
+}
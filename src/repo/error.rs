@@ -1,6 +1,7 @@
 //! Database-specific error types.
 
 use crate::error::AppError;
+use crate::error::ErrorCategory;
 
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -25,6 +26,16 @@ pub enum DatabaseError {
     PoolError(String),
 }
 
+impl DatabaseError {
+    /// What kind of failure this is. Database failures are on the bot's end.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::AppError(e) => e.category(),
+            _ => ErrorCategory::Internal,
+        }
+    }
+}
+
 impl From<tokio::task::JoinError> for DatabaseError {
     fn from(value: tokio::task::JoinError) -> Self {
         DatabaseError::JoinError(value.to_string())
@@ -34,7 +34,27 @@ pub struct PgRepos {
     pub feed_item: PgFeedItemRepo,
     pub subscriber: PgSubscriberRepo,
     pub feed_subscription: PgFeedSubscriptionRepo,
+    pub pending_subscriptions: PgPendingSubscriptionsRepo,
+    pub feed_collections: PgFeedCollectionsRepo,
+    pub notification_log: PgNotificationLogRepo,
+    pub delivery_outbox: PgDeliveryOutboxRepo,
     pub server_settings: PgServerSettingsRepo,
+    pub settings_audit: PgSettingsAuditRepo,
+    pub channel_weights: PgChannelWeightsRepo,
+    pub dashboard_tokens: PgDashboardTokensRepo,
+    pub api_tokens: PgApiTokensRepo,
+    pub tags: PgTagsRepo,
+    pub emoji_stats: PgEmojiStatsRepo,
+    pub invite_uses: PgInviteUsesRepo,
+    pub temp_voice_channels: PgTempVoiceChannelsRepo,
+    pub custom_json_feeds: PgCustomJsonFeedsRepo,
+    pub anilist_links: PgAniListLinksRepo,
+    pub mal_links: PgMalLinksRepo,
+    pub leaderboard_snapshots: PgLeaderboardSnapshotsRepo,
+    pub voice_session_flags: PgVoiceSessionFlagsRepo,
+    pub voice_adjustments: PgVoiceAdjustmentsRepo,
+    pub voice_account_merges: PgVoiceAccountMergesRepo,
+    pub voice_events: PgVoiceEventsRepo,
     pub voice_sessions: PgVoiceSessionsRepo,
     pub bot_meta: PgBotMetaRepo,
 
@@ -55,7 +75,27 @@ impl PgRepos {
             feed_item: PgFeedItemRepo::new(pool.clone()),
             subscriber: PgSubscriberRepo::new(pool.clone()),
             feed_subscription: PgFeedSubscriptionRepo::new(pool.clone()),
+            pending_subscriptions: PgPendingSubscriptionsRepo::new(pool.clone()),
+            feed_collections: PgFeedCollectionsRepo::new(pool.clone()),
+            notification_log: PgNotificationLogRepo::new(pool.clone()),
+            delivery_outbox: PgDeliveryOutboxRepo::new(pool.clone()),
             server_settings: PgServerSettingsRepo::new(pool.clone()),
+            settings_audit: PgSettingsAuditRepo::new(pool.clone()),
+            channel_weights: PgChannelWeightsRepo::new(pool.clone()),
+            dashboard_tokens: PgDashboardTokensRepo::new(pool.clone()),
+            api_tokens: PgApiTokensRepo::new(pool.clone()),
+            tags: PgTagsRepo::new(pool.clone()),
+            emoji_stats: PgEmojiStatsRepo::new(pool.clone()),
+            invite_uses: PgInviteUsesRepo::new(pool.clone()),
+            temp_voice_channels: PgTempVoiceChannelsRepo::new(pool.clone()),
+            custom_json_feeds: PgCustomJsonFeedsRepo::new(pool.clone()),
+            anilist_links: PgAniListLinksRepo::new(pool.clone()),
+            mal_links: PgMalLinksRepo::new(pool.clone()),
+            leaderboard_snapshots: PgLeaderboardSnapshotsRepo::new(pool.clone()),
+            voice_session_flags: PgVoiceSessionFlagsRepo::new(pool.clone()),
+            voice_adjustments: PgVoiceAdjustmentsRepo::new(pool.clone()),
+            voice_account_merges: PgVoiceAccountMergesRepo::new(pool.clone()),
+            voice_events: PgVoiceEventsRepo::new(pool.clone()),
             voice_sessions: PgVoiceSessionsRepo::new(pool.clone()),
             bot_meta: PgBotMetaRepo::new(pool.clone()),
             pool,
@@ -79,12 +119,45 @@ impl PgRepos {
         Ok(())
     }
 
+    /// Connects to the database and returns the number of migrations not applied yet.
+    pub async fn count_pending_migrations(&self) -> anyhow::Result<usize> {
+        let db_url = self.db_url.clone();
+        task::spawn_blocking(move || {
+            let mut conn = diesel::PgConnection::establish(&db_url)?;
+            let pending = conn
+                .pending_migrations(MIGRATIONS)
+                .map_err(|e| anyhow::anyhow!("Failed to read migrations: {e}"))?;
+            Ok(pending.len())
+        })
+        .await?
+    }
+
     pub async fn drop_all_tables(&self) -> anyhow::Result<()> {
         self.feed.drop_table().await?;
         self.feed_item.drop_table().await?;
         self.subscriber.drop_table().await?;
         self.feed_subscription.drop_table().await?;
+        self.pending_subscriptions.drop_table().await?;
+        self.feed_collections.drop_table().await?;
+        self.notification_log.drop_table().await?;
+        self.delivery_outbox.drop_table().await?;
         self.server_settings.drop_table().await?;
+        self.settings_audit.drop_table().await?;
+        self.channel_weights.drop_table().await?;
+        self.dashboard_tokens.drop_table().await?;
+        self.api_tokens.drop_table().await?;
+        self.tags.drop_table().await?;
+        self.emoji_stats.drop_table().await?;
+        self.invite_uses.drop_table().await?;
+        self.temp_voice_channels.drop_table().await?;
+        self.custom_json_feeds.drop_table().await?;
+        self.anilist_links.drop_table().await?;
+        self.mal_links.drop_table().await?;
+        self.leaderboard_snapshots.drop_table().await?;
+        self.voice_session_flags.drop_table().await?;
+        self.voice_adjustments.drop_table().await?;
+        self.voice_account_merges.drop_table().await?;
+        self.voice_events.drop_table().await?;
         self.voice_sessions.drop_table().await?;
         self.bot_meta.drop_table().await?;
         Ok(())
@@ -95,7 +168,27 @@ impl PgRepos {
         self.feed_item.delete_all().await?;
         self.subscriber.delete_all().await?;
         self.feed_subscription.delete_all().await?;
+        self.pending_subscriptions.delete_all().await?;
+        self.feed_collections.delete_all().await?;
+        self.notification_log.delete_all().await?;
+        self.delivery_outbox.delete_all().await?;
         self.server_settings.delete_all().await?;
+        self.settings_audit.delete_all().await?;
+        self.channel_weights.delete_all().await?;
+        self.dashboard_tokens.delete_all().await?;
+        self.api_tokens.delete_all().await?;
+        self.tags.delete_all().await?;
+        self.emoji_stats.delete_all().await?;
+        self.invite_uses.delete_all().await?;
+        self.temp_voice_channels.delete_all().await?;
+        self.custom_json_feeds.delete_all().await?;
+        self.anilist_links.delete_all().await?;
+        self.mal_links.delete_all().await?;
+        self.leaderboard_snapshots.delete_all().await?;
+        self.voice_session_flags.delete_all().await?;
+        self.voice_adjustments.delete_all().await?;
+        self.voice_account_merges.delete_all().await?;
+        self.voice_events.delete_all().await?;
         self.voice_sessions.delete_all().await?;
         self.bot_meta.delete_all().await?;
         Ok(())
@@ -119,10 +212,90 @@ impl Repos for PgRepos {
         Box::new(self.feed_subscription.clone())
     }
 
+    fn pending_subscriptions(&self) -> Box<dyn PendingSubscriptionsRepository + Send + Sync> {
+        Box::new(self.pending_subscriptions.clone())
+    }
+
+    fn feed_collections(&self) -> Box<dyn FeedCollectionsRepository + Send + Sync> {
+        Box::new(self.feed_collections.clone())
+    }
+
+    fn notification_log(&self) -> Box<dyn NotificationLogRepository + Send + Sync> {
+        Box::new(self.notification_log.clone())
+    }
+
+    fn delivery_outbox(&self) -> Box<dyn DeliveryOutboxRepository + Send + Sync> {
+        Box::new(self.delivery_outbox.clone())
+    }
+
     fn server_settings(&self) -> Box<dyn ServerSettingsRepository + Send + Sync> {
         Box::new(self.server_settings.clone())
     }
 
+    fn settings_audit(&self) -> Box<dyn SettingsAuditRepository + Send + Sync> {
+        Box::new(self.settings_audit.clone())
+    }
+
+    fn channel_weights(&self) -> Box<dyn ChannelWeightsRepository + Send + Sync> {
+        Box::new(self.channel_weights.clone())
+    }
+
+    fn dashboard_tokens(&self) -> Box<dyn DashboardTokensRepository + Send + Sync> {
+        Box::new(self.dashboard_tokens.clone())
+    }
+
+    fn api_tokens(&self) -> Box<dyn ApiTokensRepository + Send + Sync> {
+        Box::new(self.api_tokens.clone())
+    }
+
+    fn tags(&self) -> Box<dyn TagsRepository + Send + Sync> {
+        Box::new(self.tags.clone())
+    }
+
+    fn emoji_stats(&self) -> Box<dyn EmojiStatsRepository + Send + Sync> {
+        Box::new(self.emoji_stats.clone())
+    }
+
+    fn invite_uses(&self) -> Box<dyn InviteUsesRepository + Send + Sync> {
+        Box::new(self.invite_uses.clone())
+    }
+
+    fn temp_voice_channels(&self) -> Box<dyn TempVoiceChannelsRepository + Send + Sync> {
+        Box::new(self.temp_voice_channels.clone())
+    }
+
+    fn custom_json_feeds(&self) -> Box<dyn CustomJsonFeedsRepository + Send + Sync> {
+        Box::new(self.custom_json_feeds.clone())
+    }
+
+    fn anilist_links(&self) -> Box<dyn AniListLinksRepository + Send + Sync> {
+        Box::new(self.anilist_links.clone())
+    }
+
+    fn mal_links(&self) -> Box<dyn MalLinksRepository + Send + Sync> {
+        Box::new(self.mal_links.clone())
+    }
+
+    fn leaderboard_snapshots(&self) -> Box<dyn LeaderboardSnapshotsRepository + Send + Sync> {
+        Box::new(self.leaderboard_snapshots.clone())
+    }
+
+    fn voice_session_flags(&self) -> Box<dyn VoiceSessionFlagsRepository + Send + Sync> {
+        Box::new(self.voice_session_flags.clone())
+    }
+
+    fn voice_adjustments(&self) -> Box<dyn VoiceAdjustmentsRepository + Send + Sync> {
+        Box::new(self.voice_adjustments.clone())
+    }
+
+    fn voice_account_merges(&self) -> Box<dyn VoiceAccountMergesRepository + Send + Sync> {
+        Box::new(self.voice_account_merges.clone())
+    }
+
+    fn voice_events(&self) -> Box<dyn VoiceEventsRepository + Send + Sync> {
+        Box::new(self.voice_events.clone())
+    }
+
     fn voice_sessions(&self) -> Box<dyn VoiceSessionsRepository + Send + Sync> {
         Box::new(self.voice_sessions.clone())
     }
@@ -1,7 +1,9 @@
 //! PostgreSQL database operations and implementations.
 
 use diesel::prelude::*;
+use diesel_async::AsyncConnection;
 use diesel_async::RunQueryDsl;
+use diesel_async::scoped_futures::ScopedFutureExt;
 
 use crate::entity::*;
 use crate::error::AppError;
@@ -74,6 +76,12 @@ impl CrudTable<FeedEntity, i32> for PgFeedRepo {
                 feeds::source_url.eq(&model.source_url),
                 feeds::cover_url.eq(&model.cover_url),
                 feeds::tags.eq(&model.tags),
+                feeds::poll_tier.eq(&model.poll_tier),
+                feeds::accent_color.eq(model.accent_color),
+                feeds::emoji.eq(&model.emoji),
+                feeds::title_english.eq(&model.title_english),
+                feeds::title_romanized.eq(&model.title_romanized),
+                feeds::title_native.eq(&model.title_native),
             ))
             .returning(feeds::id)
             .get_result(&mut conn)
@@ -103,6 +111,12 @@ impl CrudTable<FeedEntity, i32> for PgFeedRepo {
                 feeds::source_url.eq(&model.source_url),
                 feeds::cover_url.eq(&model.cover_url),
                 feeds::tags.eq(&model.tags),
+                feeds::poll_tier.eq(&model.poll_tier),
+                feeds::accent_color.eq(model.accent_color),
+                feeds::emoji.eq(&model.emoji),
+                feeds::title_english.eq(&model.title_english),
+                feeds::title_romanized.eq(&model.title_romanized),
+                feeds::title_native.eq(&model.title_native),
             ))
             .execute(&mut conn)
             .await?;
@@ -165,12 +179,17 @@ impl FeedRepository for PgFeedRepo {
 
         Ok(feeds::table
             .filter(
-                feeds::name.ilike(pattern).and(
-                    feeds::id.eq_any(
-                        feed_subscriptions::table
-                            .filter(feed_subscriptions::subscriber_id.eq(subscriber_id))
-                            .select(feed_subscriptions::feed_id),
-                    ),
+                feeds::name
+                    .ilike(&pattern)
+                    .or(feeds::title_english.ilike(&pattern))
+                    .or(feeds::title_romanized.ilike(&pattern))
+                    .or(feeds::title_native.ilike(&pattern)),
+            )
+            .filter(
+                feeds::id.eq_any(
+                    feed_subscriptions::table
+                        .filter(feed_subscriptions::subscriber_id.eq(subscriber_id))
+                        .select(feed_subscriptions::feed_id),
                 ),
             )
             .order(feeds::name.asc())
@@ -179,6 +198,33 @@ impl FeedRepository for PgFeedRepo {
             .load(&mut conn)
             .await?)
     }
+
+    async fn select_all_by_subscriber_id(
+        &self,
+        subscriber_id: i32,
+    ) -> Result<Vec<FeedEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(feeds::table
+            .filter(
+                feeds::id.eq_any(
+                    feed_subscriptions::table
+                        .filter(feed_subscriptions::subscriber_id.eq(subscriber_id))
+                        .select(feed_subscriptions::feed_id),
+                ),
+            )
+            .select(FeedEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn update_poll_tier(&self, feed_id: i32, tier: PollTier) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(feeds::table.find(feed_id))
+            .set(feeds::poll_tier.eq(tier))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -215,6 +261,12 @@ impl CrudTable<FeedItemEntity, i32> for PgFeedItemRepo {
                 feed_items::feed_id.eq(model.feed_id),
                 feed_items::description.eq(&model.description),
                 feed_items::published.eq(model.published),
+                feed_items::content_hash.eq(&model.content_hash),
+                feed_items::media_url.eq(&model.media_url),
+                feed_items::duration_secs.eq(model.duration_secs),
+                feed_items::source_item_id.eq(&model.source_item_id),
+                feed_items::item_url.eq(&model.item_url),
+                feed_items::image_url.eq(&model.image_url),
             ))
             .returning(feed_items::id)
             .get_result(&mut conn)
@@ -239,6 +291,12 @@ impl CrudTable<FeedItemEntity, i32> for PgFeedItemRepo {
                 feed_items::feed_id.eq(model.feed_id),
                 feed_items::description.eq(&model.description),
                 feed_items::published.eq(model.published),
+                feed_items::content_hash.eq(&model.content_hash),
+                feed_items::media_url.eq(&model.media_url),
+                feed_items::duration_secs.eq(model.duration_secs),
+                feed_items::source_item_id.eq(&model.source_item_id),
+                feed_items::item_url.eq(&model.item_url),
+                feed_items::image_url.eq(&model.image_url),
             ))
             .execute(&mut conn)
             .await?;
@@ -294,6 +352,33 @@ impl FeedItemRepository for PgFeedItemRepo {
             .await?;
         Ok(())
     }
+
+    async fn delete_published_before(
+        &self,
+        feed_id: i32,
+        before: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let latest: Option<i32> = feed_items::table
+            .filter(feed_items::feed_id.eq(feed_id))
+            .order((feed_items::published.desc(), feed_items::id.desc()))
+            .select(feed_items::id)
+            .first(&mut conn)
+            .await
+            .optional()?;
+        let Some(latest) = latest else {
+            return Ok(0);
+        };
+        let affected = diesel::delete(
+            feed_items::table
+                .filter(feed_items::feed_id.eq(feed_id))
+                .filter(feed_items::published.lt(before))
+                .filter(feed_items::id.ne(latest)),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(affected as u32)
+    }
 }
 
 // ============================================================================
@@ -329,6 +414,9 @@ impl CrudTable<SubscriberEntity, i32> for PgSubscriberRepo {
             .values((
                 subscribers::type_.eq(model.r#type),
                 subscribers::target_id.eq(&model.target_id),
+                subscribers::max_subscriptions.eq(model.max_subscriptions),
+                subscribers::preferences.eq(&model.preferences),
+                subscribers::first_seen.eq(model.first_seen),
             ))
             .returning(subscribers::id)
             .get_result(&mut conn)
@@ -352,6 +440,9 @@ impl CrudTable<SubscriberEntity, i32> for PgSubscriberRepo {
             .set((
                 subscribers::type_.eq(model.r#type),
                 subscribers::target_id.eq(&model.target_id),
+                subscribers::max_subscriptions.eq(model.max_subscriptions),
+                subscribers::preferences.eq(&model.preferences),
+                subscribers::first_seen.eq(model.first_seen),
             ))
             .execute(&mut conn)
             .await?;
@@ -385,6 +476,29 @@ impl SubscriberRepository for PgSubscriberRepo {
                 subscribers::id.eq_any(
                     feed_subscriptions::table
                         .filter(feed_subscriptions::feed_id.eq(feed_id))
+                        .filter(feed_subscriptions::paused.eq(false))
+                        .select(feed_subscriptions::subscriber_id),
+                ),
+            )
+            .select(SubscriberEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn select_edit_subscribers_by_type_and_feed(
+        &self,
+        r#type: SubscriberType,
+        feed_id: i32,
+    ) -> Result<Vec<SubscriberEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(subscribers::table
+            .filter(subscribers::type_.eq(r#type))
+            .filter(
+                subscribers::id.eq_any(
+                    feed_subscriptions::table
+                        .filter(feed_subscriptions::feed_id.eq(feed_id))
+                        .filter(feed_subscriptions::paused.eq(false))
+                        .filter(feed_subscriptions::notify_edits.eq(true))
                         .select(feed_subscriptions::subscriber_id),
                 ),
             )
@@ -407,6 +521,19 @@ impl SubscriberRepository for PgSubscriberRepo {
             .await
             .optional()?)
     }
+
+    async fn clear_first_seen(&self, id: i32) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let affected = diesel::update(
+            subscribers::table
+                .find(id)
+                .filter(subscribers::first_seen.eq(true)),
+        )
+        .set(subscribers::first_seen.eq(false))
+        .execute(&mut conn)
+        .await?;
+        Ok(affected > 0)
+    }
 }
 
 // ============================================================================
@@ -442,6 +569,10 @@ impl CrudTable<FeedSubscriptionEntity, i32> for PgFeedSubscriptionRepo {
             .values((
                 feed_subscriptions::feed_id.eq(model.feed_id),
                 feed_subscriptions::subscriber_id.eq(model.subscriber_id),
+                feed_subscriptions::paused.eq(model.paused),
+                feed_subscriptions::notify_edits.eq(model.notify_edits),
+                feed_subscriptions::added_by.eq(&model.added_by),
+                feed_subscriptions::collection_id.eq(model.collection_id),
             ))
             .returning(feed_subscriptions::id)
             .get_result(&mut conn)
@@ -465,6 +596,10 @@ impl CrudTable<FeedSubscriptionEntity, i32> for PgFeedSubscriptionRepo {
             .set((
                 feed_subscriptions::feed_id.eq(model.feed_id),
                 feed_subscriptions::subscriber_id.eq(model.subscriber_id),
+                feed_subscriptions::paused.eq(model.paused),
+                feed_subscriptions::notify_edits.eq(model.notify_edits),
+                feed_subscriptions::added_by.eq(&model.added_by),
+                feed_subscriptions::collection_id.eq(model.collection_id),
             ))
             .execute(&mut conn)
             .await?;
@@ -510,6 +645,21 @@ impl FeedSubscriptionRepository for PgFeedSubscriptionRepo {
             .await?)
     }
 
+    async fn select_by_feed_and_subscriber(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+    ) -> Result<Option<FeedSubscriptionEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(feed_subscriptions::table
+            .filter(feed_subscriptions::feed_id.eq(feed_id))
+            .filter(feed_subscriptions::subscriber_id.eq(subscriber_id))
+            .select(FeedSubscriptionEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
     async fn count_by_subscriber_id(&self, subscriber_id: i32) -> Result<u32, DatabaseError> {
         let mut conn = self.pool.get().await?;
         let count: i64 = feed_subscriptions::table
@@ -552,7 +702,9 @@ impl FeedSubscriptionRepository for PgFeedSubscriptionRepo {
         let rows = diesel::sql_query(
             r#"
             SELECT
-                f.id, f.name, f.description, f.platform_id, f.source_id, f.items_id, f.source_url, f.cover_url, f.tags,
+                f.id, f.name, f.description, f.platform_id, f.source_id, f.items_id, f.source_url, f.cover_url, f.tags, f.poll_tier, f.accent_color, f.emoji,
+                f.title_english, f.title_romanized, f.title_native,
+                fs.paused, fs.notify_edits, fs.added_by, fs.collection_id,
                 fi.id as item_id, fi.description as item_description, fi.published as item_published
             FROM feed_subscriptions fs
             JOIN feeds f ON fs.feed_id = f.id
@@ -576,6 +728,7 @@ impl FeedSubscriptionRepository for PgFeedSubscriptionRepo {
         let mut conn = self.pool.get().await?;
         let count: i64 = feed_subscriptions::table
             .filter(feed_subscriptions::feed_id.eq(feed_id))
+            .filter(feed_subscriptions::paused.eq(false))
             .count()
             .get_result(&mut conn)
             .await?;
@@ -598,6 +751,60 @@ impl FeedSubscriptionRepository for PgFeedSubscriptionRepo {
         Ok(affected > 0)
     }
 
+    async fn update_paused(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+        paused: bool,
+    ) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let affected = diesel::update(
+            feed_subscriptions::table
+                .filter(feed_subscriptions::feed_id.eq(feed_id))
+                .filter(feed_subscriptions::subscriber_id.eq(subscriber_id)),
+        )
+        .set(feed_subscriptions::paused.eq(paused))
+        .execute(&mut conn)
+        .await?;
+        Ok(affected > 0)
+    }
+
+    async fn update_notify_edits(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+        notify_edits: bool,
+    ) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let affected = diesel::update(
+            feed_subscriptions::table
+                .filter(feed_subscriptions::feed_id.eq(feed_id))
+                .filter(feed_subscriptions::subscriber_id.eq(subscriber_id)),
+        )
+        .set(feed_subscriptions::notify_edits.eq(notify_edits))
+        .execute(&mut conn)
+        .await?;
+        Ok(affected > 0)
+    }
+
+    async fn update_collection(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+        collection_id: Option<i32>,
+    ) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let affected = diesel::update(
+            feed_subscriptions::table
+                .filter(feed_subscriptions::feed_id.eq(feed_id))
+                .filter(feed_subscriptions::subscriber_id.eq(subscriber_id)),
+        )
+        .set(feed_subscriptions::collection_id.eq(collection_id))
+        .execute(&mut conn)
+        .await?;
+        Ok(affected > 0)
+    }
+
     async fn delete_all_by_feed_id(&self, feed_id: i32) -> Result<(), DatabaseError> {
         let mut conn = self.pool.get().await?;
         diesel::delete(feed_subscriptions::table.filter(feed_subscriptions::feed_id.eq(feed_id)))
@@ -618,135 +825,196 @@ impl FeedSubscriptionRepository for PgFeedSubscriptionRepo {
 }
 
 // ============================================================================
-// PgServerSettingsRepo
+// PgPendingSubscriptionsRepo
 // ============================================================================
 
 #[derive(Clone)]
-pub struct PgServerSettingsRepo {
+pub struct PgPendingSubscriptionsRepo {
     pool: DbPool,
 }
 
-impl PgServerSettingsRepo {
+impl PgPendingSubscriptionsRepo {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
 }
 
-impl_table_base!(PgServerSettingsRepo, server_settings::table);
+impl_table_base!(PgPendingSubscriptionsRepo, pending_subscriptions::table);
 
 #[async_trait::async_trait]
-impl CrudTable<ServerSettingsEntity, u64> for PgServerSettingsRepo {
-    async fn select_all(&self) -> Result<Vec<ServerSettingsEntity>, DatabaseError> {
+impl CrudTable<PendingSubscriptionEntity, i32> for PgPendingSubscriptionsRepo {
+    async fn select_all(&self) -> Result<Vec<PendingSubscriptionEntity>, DatabaseError> {
         let mut conn = self.pool.get().await?;
-        Ok(server_settings::table
-            .select(ServerSettingsEntity::as_select())
+        Ok(pending_subscriptions::table
+            .select(PendingSubscriptionEntity::as_select())
             .load(&mut conn)
             .await?)
     }
 
-    async fn insert(&self, model: &ServerSettingsEntity) -> Result<u64, DatabaseError> {
+    async fn insert(&self, model: &PendingSubscriptionEntity) -> Result<i32, DatabaseError> {
         let mut conn = self.pool.get().await?;
-        let guild_id: DbU64 = diesel::insert_into(server_settings::table)
-            .values(model)
-            .returning(server_settings::guild_id)
+        let id = diesel::insert_into(pending_subscriptions::table)
+            .values((
+                pending_subscriptions::guild_id.eq(model.guild_id),
+                pending_subscriptions::source_url.eq(&model.source_url),
+                pending_subscriptions::requested_by.eq(model.requested_by),
+                pending_subscriptions::requested_at.eq(model.requested_at),
+            ))
+            .returning(pending_subscriptions::id)
             .get_result(&mut conn)
             .await?;
-        Ok(guild_id.into())
+        Ok(id)
     }
 
-    async fn select(&self, id: &u64) -> Result<Option<ServerSettingsEntity>, DatabaseError> {
+    async fn select(&self, id: &i32) -> Result<Option<PendingSubscriptionEntity>, DatabaseError> {
         let mut conn = self.pool.get().await?;
-        Ok(server_settings::table
-            .find(DbU64::from(*id))
-            .select(ServerSettingsEntity::as_select())
+        Ok(pending_subscriptions::table
+            .find(id)
+            .select(PendingSubscriptionEntity::as_select())
             .first(&mut conn)
             .await
             .optional()?)
     }
 
-    async fn update(&self, model: &ServerSettingsEntity) -> Result<(), DatabaseError> {
+    async fn update(&self, model: &PendingSubscriptionEntity) -> Result<(), DatabaseError> {
         let mut conn = self.pool.get().await?;
-        diesel::update(server_settings::table.find(model.guild_id))
-            .set(model)
+        diesel::update(pending_subscriptions::table.find(model.id))
+            .set((
+                pending_subscriptions::guild_id.eq(model.guild_id),
+                pending_subscriptions::source_url.eq(&model.source_url),
+                pending_subscriptions::requested_by.eq(model.requested_by),
+                pending_subscriptions::requested_at.eq(model.requested_at),
+            ))
             .execute(&mut conn)
             .await?;
         Ok(())
     }
 
-    async fn delete(&self, id: &u64) -> Result<(), DatabaseError> {
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
         let mut conn = self.pool.get().await?;
-        diesel::delete(server_settings::table.find(DbU64::from(*id)))
+        diesel::delete(pending_subscriptions::table.find(id))
             .execute(&mut conn)
             .await?;
         Ok(())
     }
 
-    async fn replace(&self, model: &ServerSettingsEntity) -> Result<u64, DatabaseError> {
-        let gid: u64 = model.guild_id.into();
-        if self.select(&gid).await?.is_some() {
-            self.update(model).await?;
-            return Ok(gid);
-        }
-        self.insert(model).await
+    async fn replace(&self, model: &PendingSubscriptionEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(pending_subscriptions::table)
+            .values((
+                pending_subscriptions::guild_id.eq(model.guild_id),
+                pending_subscriptions::source_url.eq(&model.source_url),
+                pending_subscriptions::requested_by.eq(model.requested_by),
+                pending_subscriptions::requested_at.eq(model.requested_at),
+            ))
+            .on_conflict((
+                pending_subscriptions::guild_id,
+                pending_subscriptions::source_url,
+            ))
+            .do_update()
+            .set((
+                pending_subscriptions::requested_by.eq(model.requested_by),
+                pending_subscriptions::requested_at.eq(model.requested_at),
+            ))
+            .returning(pending_subscriptions::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
     }
 }
 
 #[async_trait::async_trait]
-impl ServerSettingsRepository for PgServerSettingsRepo {}
+impl PendingSubscriptionsRepository for PgPendingSubscriptionsRepo {
+    async fn select_all_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<PendingSubscriptionEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(pending_subscriptions::table
+            .filter(pending_subscriptions::guild_id.eq(DbU64::from(guild_id)))
+            .order(pending_subscriptions::requested_at.asc())
+            .select(PendingSubscriptionEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn select_by_guild_and_url(
+        &self,
+        guild_id: u64,
+        source_url: &str,
+    ) -> Result<Option<PendingSubscriptionEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(pending_subscriptions::table
+            .filter(pending_subscriptions::guild_id.eq(DbU64::from(guild_id)))
+            .filter(pending_subscriptions::source_url.eq(source_url))
+            .select(PendingSubscriptionEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+}
 
 // ============================================================================
-// PgVoiceSessionsRepo
+// PgFeedCollectionsRepo
 // ============================================================================
 
 #[derive(Clone)]
-pub struct PgVoiceSessionsRepo {
+pub struct PgFeedCollectionsRepo {
     pool: DbPool,
 }
 
-impl PgVoiceSessionsRepo {
+impl PgFeedCollectionsRepo {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
 }
 
-impl_table_base!(PgVoiceSessionsRepo, voice_sessions::table);
+impl_table_base!(PgFeedCollectionsRepo, feed_collections::table);
 
 #[async_trait::async_trait]
-impl CrudTable<VoiceSessionsEntity, i32> for PgVoiceSessionsRepo {
-    async fn select_all(&self) -> Result<Vec<VoiceSessionsEntity>, DatabaseError> {
+impl CrudTable<FeedCollectionEntity, i32> for PgFeedCollectionsRepo {
+    async fn select_all(&self) -> Result<Vec<FeedCollectionEntity>, DatabaseError> {
         let mut conn = self.pool.get().await?;
-        let rows: Vec<DbVoiceSession> = voice_sessions::table
-            .select(DbVoiceSession::as_select())
+        Ok(feed_collections::table
+            .select(FeedCollectionEntity::as_select())
             .load(&mut conn)
-            .await?;
-        Ok(rows.into_iter().map(Into::into).collect())
+            .await?)
     }
 
-    async fn insert(&self, model: &VoiceSessionsEntity) -> Result<i32, DatabaseError> {
+    async fn insert(&self, model: &FeedCollectionEntity) -> Result<i32, DatabaseError> {
         let mut conn = self.pool.get().await?;
-        let id = diesel::insert_into(voice_sessions::table)
-            .values(&model.to_insertable())
-            .returning(voice_sessions::id)
+        let id = diesel::insert_into(feed_collections::table)
+            .values((
+                feed_collections::guild_id.eq(model.guild_id),
+                feed_collections::name.eq(&model.name),
+                feed_collections::channel_id.eq(model.channel_id),
+                feed_collections::mention_role_id.eq(model.mention_role_id),
+            ))
+            .returning(feed_collections::id)
             .get_result(&mut conn)
             .await?;
         Ok(id)
     }
 
-    async fn select(&self, id: &i32) -> Result<Option<VoiceSessionsEntity>, DatabaseError> {
+    async fn select(&self, id: &i32) -> Result<Option<FeedCollectionEntity>, DatabaseError> {
         let mut conn = self.pool.get().await?;
-        let result: Option<DbVoiceSession> = voice_sessions::table
+        Ok(feed_collections::table
             .find(id)
-            .select(DbVoiceSession::as_select())
+            .select(FeedCollectionEntity::as_select())
             .first(&mut conn)
             .await
-            .optional()?;
-        Ok(result.map(Into::into))
+            .optional()?)
     }
 
-    async fn update(&self, model: &VoiceSessionsEntity) -> Result<(), DatabaseError> {
+    async fn update(&self, model: &FeedCollectionEntity) -> Result<(), DatabaseError> {
         let mut conn = self.pool.get().await?;
-        diesel::update(voice_sessions::table.find(model.id))
-            .set(&model.to_insertable())
+        diesel::update(feed_collections::table.find(model.id))
+            .set((
+                feed_collections::guild_id.eq(model.guild_id),
+                feed_collections::name.eq(&model.name),
+                feed_collections::channel_id.eq(model.channel_id),
+                feed_collections::mention_role_id.eq(model.mention_role_id),
+            ))
             .execute(&mut conn)
             .await?;
         Ok(())
@@ -754,56 +1022,3130 @@ impl CrudTable<VoiceSessionsEntity, i32> for PgVoiceSessionsRepo {
 
     async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
         let mut conn = self.pool.get().await?;
-        diesel::delete(voice_sessions::table.find(id))
+        diesel::delete(feed_collections::table.find(id))
             .execute(&mut conn)
             .await?;
         Ok(())
     }
 
-    async fn replace(&self, model: &VoiceSessionsEntity) -> Result<i32, DatabaseError> {
-        if model.id != 0 && self.select(&model.id).await?.is_some() {
-            self.update(model).await?;
-            return Ok(model.id);
-        }
-        self.insert(model).await
+    async fn replace(&self, model: &FeedCollectionEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(feed_collections::table)
+            .values((
+                feed_collections::guild_id.eq(model.guild_id),
+                feed_collections::name.eq(&model.name),
+                feed_collections::channel_id.eq(model.channel_id),
+                feed_collections::mention_role_id.eq(model.mention_role_id),
+            ))
+            .on_conflict((feed_collections::guild_id, feed_collections::name))
+            .do_update()
+            .set((
+                feed_collections::channel_id.eq(model.channel_id),
+                feed_collections::mention_role_id.eq(model.mention_role_id),
+            ))
+            .returning(feed_collections::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
     }
 }
 
 #[async_trait::async_trait]
-impl VoiceSessionsRepository for PgVoiceSessionsRepo {
-    async fn get_leaderboard_opt(
+impl FeedCollectionsRepository for PgFeedCollectionsRepo {
+    async fn select_all_by_guild_id(
         &self,
-        opts: &VoiceLeaderboardOpt,
-    ) -> Result<Vec<VoiceLeaderboardEntry>, DatabaseError> {
+        guild_id: u64,
+    ) -> Result<Vec<FeedCollectionEntity>, DatabaseError> {
         let mut conn = self.pool.get().await?;
-        let limit = opts.limit.unwrap_or(10) as i64;
-        let offset = opts.offset.unwrap_or(0) as i64;
-        let since_val = opts.since.unwrap_or(chrono::DateTime::UNIX_EPOCH);
-        let until_val = opts
-            .until
-            .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::days(365));
+        Ok(feed_collections::table
+            .filter(feed_collections::guild_id.eq(DbU64::from(guild_id)))
+            .order(feed_collections::name.asc())
+            .select(FeedCollectionEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
 
-        let rows: Vec<VoiceLeaderboardRow> = diesel::sql_query(
-            r#"
-            SELECT
-                user_id,
-                SUM(
-                    EXTRACT(EPOCH FROM LEAST($1, CASE WHEN is_active THEN CURRENT_TIMESTAMP ELSE leave_time END))::bigint -
-                    EXTRACT(EPOCH FROM GREATEST($2, join_time))::bigint
-                )::bigint as total_duration
-            FROM voice_sessions
-            WHERE guild_id = $3
-            AND join_time <= $4
-            AND (is_active OR leave_time >= $5)
-            GROUP BY user_id ORDER BY total_duration DESC LIMIT $6 OFFSET $7
-            "#,
-        )
-        .bind::<diesel::sql_types::Timestamptz, _>(until_val)
-        .bind::<diesel::sql_types::Timestamptz, _>(since_val)
-        .bind::<diesel::sql_types::BigInt, _>(opts.guild_id as i64)
-        .bind::<diesel::sql_types::Timestamptz, _>(until_val)
-        .bind::<diesel::sql_types::Timestamptz, _>(since_val)
-        .bind::<diesel::sql_types::BigInt, _>(limit)
+    async fn select_by_guild_and_name(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<Option<FeedCollectionEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(feed_collections::table
+            .filter(feed_collections::guild_id.eq(DbU64::from(guild_id)))
+            .filter(feed_collections::name.eq(name))
+            .select(FeedCollectionEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+}
+
+// ============================================================================
+// PgNotificationLogRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgNotificationLogRepo {
+    pool: DbPool,
+}
+
+impl PgNotificationLogRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgNotificationLogRepo, notification_log::table);
+
+#[async_trait::async_trait]
+impl CrudTable<NotificationLogEntity, i32> for PgNotificationLogRepo {
+    async fn select_all(&self) -> Result<Vec<NotificationLogEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(notification_log::table
+            .select(NotificationLogEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &NotificationLogEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(notification_log::table)
+            .values((
+                notification_log::subscriber_id.eq(model.subscriber_id),
+                notification_log::feed_id.eq(model.feed_id),
+                notification_log::sent_at.eq(model.sent_at),
+                notification_log::delivered.eq(model.delivered),
+                notification_log::delay_secs.eq(model.delay_secs),
+            ))
+            .returning(notification_log::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<NotificationLogEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(notification_log::table
+            .find(id)
+            .select(NotificationLogEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &NotificationLogEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(notification_log::table.find(model.id))
+            .set((
+                notification_log::subscriber_id.eq(model.subscriber_id),
+                notification_log::feed_id.eq(model.feed_id),
+                notification_log::sent_at.eq(model.sent_at),
+                notification_log::delivered.eq(model.delivered),
+                notification_log::delay_secs.eq(model.delay_secs),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(notification_log::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &NotificationLogEntity) -> Result<i32, DatabaseError> {
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationLogRepository for PgNotificationLogRepo {
+    async fn count_by_subscriber_since(
+        &self,
+        subscriber_id: i32,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<i64, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(notification_log::table
+            .filter(notification_log::subscriber_id.eq(subscriber_id))
+            .filter(notification_log::sent_at.ge(since))
+            .filter(notification_log::delivered.eq(true))
+            .count()
+            .get_result(&mut conn)
+            .await?)
+    }
+
+    async fn select_top_feeds_by_subscriber_since(
+        &self,
+        subscriber_id: i32,
+        since: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<FeedNotificationCountRow>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::sql_query(
+            r#"
+            SELECT f.id as feed_id, f.name, f.source_url, COUNT(*) as notifications
+            FROM notification_log nl
+            JOIN feeds f ON nl.feed_id = f.id
+            WHERE nl.subscriber_id = $1 AND nl.sent_at >= $2 AND nl.delivered
+            GROUP BY f.id, f.name, f.source_url
+            ORDER BY notifications DESC, f.name
+            LIMIT $3
+            "#,
+        )
+        .bind::<diesel::sql_types::Integer, _>(subscriber_id)
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::BigInt, _>(limit as i64)
+        .load::<FeedNotificationCountRow>(&mut conn)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn select_daily_counts_since(
+        &self,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<DailyDeliveryRow>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::sql_query(
+            r#"
+            SELECT
+                DATE(sent_at AT TIME ZONE 'UTC') as day,
+                COUNT(*) FILTER (WHERE delivered) as delivered,
+                COUNT(*) FILTER (WHERE NOT delivered) as failed
+            FROM notification_log
+            WHERE sent_at >= $1
+            GROUP BY day
+            ORDER BY day
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .load::<DailyDeliveryRow>(&mut conn)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn select_counts_by_subscriber_type_since(
+        &self,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<SubscriberTypeDeliveryRow>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::sql_query(
+            r#"
+            SELECT
+                s.type as subscriber_type,
+                COUNT(*) FILTER (WHERE nl.delivered) as delivered,
+                COUNT(*) FILTER (WHERE NOT nl.delivered) as failed
+            FROM notification_log nl
+            JOIN subscribers s ON nl.subscriber_id = s.id
+            WHERE nl.sent_at >= $1
+            GROUP BY s.type
+            ORDER BY s.type
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .load::<SubscriberTypeDeliveryRow>(&mut conn)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn select_slowest_platforms_since(
+        &self,
+        since: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<PlatformDelayRow>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::sql_query(
+            r#"
+            SELECT
+                f.platform_id,
+                percentile_cont(0.5) WITHIN GROUP (ORDER BY nl.delay_secs)::float8 as median_delay_secs,
+                COUNT(*) as deliveries
+            FROM notification_log nl
+            JOIN feeds f ON nl.feed_id = f.id
+            WHERE nl.sent_at >= $1 AND nl.delivered AND nl.delay_secs IS NOT NULL
+            GROUP BY f.platform_id
+            ORDER BY median_delay_secs DESC, f.platform_id
+            LIMIT $2
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::BigInt, _>(limit as i64)
+        .load::<PlatformDelayRow>(&mut conn)
+        .await?;
+        Ok(rows)
+    }
+}
+
+// ============================================================================
+// PgDeliveryOutboxRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgDeliveryOutboxRepo {
+    pool: DbPool,
+}
+
+impl PgDeliveryOutboxRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgDeliveryOutboxRepo, delivery_outbox::table);
+
+#[async_trait::async_trait]
+impl CrudTable<DeliveryOutboxEntity, i32> for PgDeliveryOutboxRepo {
+    async fn select_all(&self) -> Result<Vec<DeliveryOutboxEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(delivery_outbox::table
+            .order(delivery_outbox::id.asc())
+            .select(DeliveryOutboxEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &DeliveryOutboxEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(delivery_outbox::table)
+            .values((
+                delivery_outbox::subscriber_id.eq(model.subscriber_id),
+                delivery_outbox::data.eq(&model.data),
+                delivery_outbox::queued_at.eq(model.queued_at),
+            ))
+            .returning(delivery_outbox::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<DeliveryOutboxEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(delivery_outbox::table
+            .find(id)
+            .select(DeliveryOutboxEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &DeliveryOutboxEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(delivery_outbox::table.find(model.id))
+            .set((
+                delivery_outbox::subscriber_id.eq(model.subscriber_id),
+                delivery_outbox::data.eq(&model.data),
+                delivery_outbox::queued_at.eq(model.queued_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(delivery_outbox::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &DeliveryOutboxEntity) -> Result<i32, DatabaseError> {
+        if self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl DeliveryOutboxRepository for PgDeliveryOutboxRepo {
+    async fn insert_many(
+        &self,
+        models: &[DeliveryOutboxEntity],
+    ) -> Result<Vec<DeliveryOutboxEntity>, DatabaseError> {
+        // Postgres takes at most 65535 bind parameters per statement
+        const CHUNK_SIZE: usize = 5000;
+
+        let mut conn = self.pool.get().await?;
+        conn.transaction::<_, DatabaseError, _>(|conn| {
+            async move {
+                let mut inserted = Vec::with_capacity(models.len());
+                for chunk in models.chunks(CHUNK_SIZE) {
+                    let rows: Vec<_> = chunk
+                        .iter()
+                        .map(|model| {
+                            (
+                                delivery_outbox::subscriber_id.eq(model.subscriber_id),
+                                delivery_outbox::data.eq(&model.data),
+                                delivery_outbox::queued_at.eq(model.queued_at),
+                            )
+                        })
+                        .collect();
+                    inserted.extend(
+                        diesel::insert_into(delivery_outbox::table)
+                            .values(&rows)
+                            .returning(DeliveryOutboxEntity::as_returning())
+                            .get_results(conn)
+                            .await?,
+                    );
+                }
+                Ok(inserted)
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
+    async fn take_all(&self) -> Result<Vec<DeliveryOutboxEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let mut taken = diesel::delete(delivery_outbox::table)
+            .returning(DeliveryOutboxEntity::as_returning())
+            .get_results(&mut conn)
+            .await?;
+        taken.sort_by_key(|entry: &DeliveryOutboxEntity| entry.id);
+        Ok(taken)
+    }
+}
+
+// ============================================================================
+// PgServerSettingsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgServerSettingsRepo {
+    pool: DbPool,
+}
+
+impl PgServerSettingsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgServerSettingsRepo, server_settings::table);
+
+#[async_trait::async_trait]
+impl CrudTable<ServerSettingsEntity, u64> for PgServerSettingsRepo {
+    async fn select_all(&self) -> Result<Vec<ServerSettingsEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(server_settings::table
+            .select(ServerSettingsEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &ServerSettingsEntity) -> Result<u64, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let guild_id: DbU64 = diesel::insert_into(server_settings::table)
+            .values(model)
+            .returning(server_settings::guild_id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(guild_id.into())
+    }
+
+    async fn select(&self, id: &u64) -> Result<Option<ServerSettingsEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(server_settings::table
+            .find(DbU64::from(*id))
+            .select(ServerSettingsEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &ServerSettingsEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(server_settings::table.find(model.guild_id))
+            .set(model)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &u64) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(server_settings::table.find(DbU64::from(*id)))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &ServerSettingsEntity) -> Result<u64, DatabaseError> {
+        let gid: u64 = model.guild_id.into();
+        if self.select(&gid).await?.is_some() {
+            self.update(model).await?;
+            return Ok(gid);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ServerSettingsRepository for PgServerSettingsRepo {}
+
+// ============================================================================
+// PgSettingsAuditRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgSettingsAuditRepo {
+    pool: DbPool,
+}
+
+impl PgSettingsAuditRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgSettingsAuditRepo, settings_audit::table);
+
+#[async_trait::async_trait]
+impl CrudTable<SettingsAuditEntity, i32> for PgSettingsAuditRepo {
+    async fn select_all(&self) -> Result<Vec<SettingsAuditEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(settings_audit::table
+            .select(SettingsAuditEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &SettingsAuditEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(settings_audit::table)
+            .values((
+                settings_audit::guild_id.eq(model.guild_id),
+                settings_audit::changed_by.eq(model.changed_by),
+                settings_audit::key.eq(&model.key),
+                settings_audit::old_value.eq(&model.old_value),
+                settings_audit::new_value.eq(&model.new_value),
+                settings_audit::changed_at.eq(model.changed_at),
+            ))
+            .returning(settings_audit::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<SettingsAuditEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(settings_audit::table
+            .find(id)
+            .select(SettingsAuditEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &SettingsAuditEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(settings_audit::table.find(model.id))
+            .set((
+                settings_audit::guild_id.eq(model.guild_id),
+                settings_audit::changed_by.eq(model.changed_by),
+                settings_audit::key.eq(&model.key),
+                settings_audit::old_value.eq(&model.old_value),
+                settings_audit::new_value.eq(&model.new_value),
+                settings_audit::changed_at.eq(model.changed_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(settings_audit::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &SettingsAuditEntity) -> Result<i32, DatabaseError> {
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl SettingsAuditRepository for PgSettingsAuditRepo {
+    async fn count_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let count: i64 = settings_audit::table
+            .filter(settings_audit::guild_id.eq(DbU64::from(guild_id)))
+            .count()
+            .get_result(&mut conn)
+            .await?;
+        Ok(count as u32)
+    }
+
+    async fn select_paginated_by_guild_id(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<SettingsAuditEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let limit = per_page as i64;
+        let offset = (per_page * page) as i64;
+        Ok(settings_audit::table
+            .filter(settings_audit::guild_id.eq(DbU64::from(guild_id)))
+            .order((settings_audit::changed_at.desc(), settings_audit::id.desc()))
+            .limit(limit)
+            .offset(offset)
+            .select(SettingsAuditEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+}
+
+// ============================================================================
+// PgDashboardTokensRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgDashboardTokensRepo {
+    pool: DbPool,
+}
+
+impl PgDashboardTokensRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgDashboardTokensRepo, dashboard_tokens::table);
+
+#[async_trait::async_trait]
+impl CrudTable<DashboardTokenEntity, i32> for PgDashboardTokensRepo {
+    async fn select_all(&self) -> Result<Vec<DashboardTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(dashboard_tokens::table
+            .select(DashboardTokenEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &DashboardTokenEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(dashboard_tokens::table)
+            .values((
+                dashboard_tokens::guild_id.eq(model.guild_id),
+                dashboard_tokens::token.eq(&model.token),
+                dashboard_tokens::created_by.eq(model.created_by),
+                dashboard_tokens::created_at.eq(model.created_at),
+            ))
+            .returning(dashboard_tokens::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<DashboardTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(dashboard_tokens::table
+            .find(id)
+            .select(DashboardTokenEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &DashboardTokenEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(dashboard_tokens::table.find(model.id))
+            .set((
+                dashboard_tokens::guild_id.eq(model.guild_id),
+                dashboard_tokens::token.eq(&model.token),
+                dashboard_tokens::created_by.eq(model.created_by),
+                dashboard_tokens::created_at.eq(model.created_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(dashboard_tokens::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &DashboardTokenEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        // One token per guild; replacing rotates it
+        let id = diesel::insert_into(dashboard_tokens::table)
+            .values((
+                dashboard_tokens::guild_id.eq(model.guild_id),
+                dashboard_tokens::token.eq(&model.token),
+                dashboard_tokens::created_by.eq(model.created_by),
+                dashboard_tokens::created_at.eq(model.created_at),
+            ))
+            .on_conflict(dashboard_tokens::guild_id)
+            .do_update()
+            .set((
+                dashboard_tokens::token.eq(&model.token),
+                dashboard_tokens::created_by.eq(model.created_by),
+                dashboard_tokens::created_at.eq(model.created_at),
+            ))
+            .returning(dashboard_tokens::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+}
+
+#[async_trait::async_trait]
+impl DashboardTokensRepository for PgDashboardTokensRepo {
+    async fn select_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<DashboardTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(dashboard_tokens::table
+            .filter(dashboard_tokens::guild_id.eq(DbU64::from(guild_id)))
+            .select(DashboardTokenEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn select_by_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<DashboardTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(dashboard_tokens::table
+            .filter(dashboard_tokens::token.eq(token))
+            .select(DashboardTokenEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn delete_by_guild_id(&self, guild_id: u64) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::delete(
+            dashboard_tokens::table.filter(dashboard_tokens::guild_id.eq(DbU64::from(guild_id))),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(rows > 0)
+    }
+}
+
+// ============================================================================
+// PgApiTokensRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgApiTokensRepo {
+    pool: DbPool,
+}
+
+impl PgApiTokensRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgApiTokensRepo, api_tokens::table);
+
+#[async_trait::async_trait]
+impl CrudTable<ApiTokenEntity, i32> for PgApiTokensRepo {
+    async fn select_all(&self) -> Result<Vec<ApiTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(api_tokens::table
+            .select(ApiTokenEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &ApiTokenEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(api_tokens::table)
+            .values((
+                api_tokens::guild_id.eq(model.guild_id),
+                api_tokens::token_hash.eq(&model.token_hash),
+                api_tokens::created_by.eq(model.created_by),
+                api_tokens::created_at.eq(model.created_at),
+            ))
+            .returning(api_tokens::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<ApiTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(api_tokens::table
+            .find(id)
+            .select(ApiTokenEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &ApiTokenEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(api_tokens::table.find(model.id))
+            .set((
+                api_tokens::guild_id.eq(model.guild_id),
+                api_tokens::token_hash.eq(&model.token_hash),
+                api_tokens::created_by.eq(model.created_by),
+                api_tokens::created_at.eq(model.created_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(api_tokens::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &ApiTokenEntity) -> Result<i32, DatabaseError> {
+        // The scope index is on an expression, so rotate by delete + insert
+        self.delete_by_guild_id(model.guild_id.map(|id| *id))
+            .await?;
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiTokensRepository for PgApiTokensRepo {
+    async fn select_by_guild_id(
+        &self,
+        guild_id: Option<u64>,
+    ) -> Result<Option<ApiTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let query = api_tokens::table.select(ApiTokenEntity::as_select());
+        let res = match guild_id {
+            Some(guild_id) => {
+                query
+                    .filter(api_tokens::guild_id.eq(DbU64::from(guild_id)))
+                    .first(&mut conn)
+                    .await
+            }
+            None => {
+                query
+                    .filter(api_tokens::guild_id.is_null())
+                    .first(&mut conn)
+                    .await
+            }
+        };
+        Ok(res.optional()?)
+    }
+
+    async fn select_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<ApiTokenEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(api_tokens::table
+            .filter(api_tokens::token_hash.eq(token_hash))
+            .select(ApiTokenEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn delete_by_guild_id(&self, guild_id: Option<u64>) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = match guild_id {
+            Some(guild_id) => {
+                diesel::delete(
+                    api_tokens::table.filter(api_tokens::guild_id.eq(DbU64::from(guild_id))),
+                )
+                .execute(&mut conn)
+                .await?
+            }
+            None => {
+                diesel::delete(api_tokens::table.filter(api_tokens::guild_id.is_null()))
+                    .execute(&mut conn)
+                    .await?
+            }
+        };
+        Ok(rows > 0)
+    }
+}
+
+// ============================================================================
+// PgCustomJsonFeedsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgCustomJsonFeedsRepo {
+    pool: DbPool,
+}
+
+impl PgCustomJsonFeedsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgCustomJsonFeedsRepo, custom_json_feeds::table);
+
+#[async_trait::async_trait]
+impl CrudTable<CustomJsonFeedEntity, i32> for PgCustomJsonFeedsRepo {
+    async fn select_all(&self) -> Result<Vec<CustomJsonFeedEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(custom_json_feeds::table
+            .select(CustomJsonFeedEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &CustomJsonFeedEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(custom_json_feeds::table)
+            .values((
+                custom_json_feeds::name.eq(&model.name),
+                custom_json_feeds::url.eq(&model.url),
+                custom_json_feeds::items_path.eq(&model.items_path),
+                custom_json_feeds::id_path.eq(&model.id_path),
+                custom_json_feeds::title_path.eq(&model.title_path),
+                custom_json_feeds::url_path.eq(&model.url_path),
+                custom_json_feeds::published_path.eq(&model.published_path),
+                custom_json_feeds::created_by.eq(model.created_by),
+                custom_json_feeds::created_at.eq(model.created_at),
+            ))
+            .returning(custom_json_feeds::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<CustomJsonFeedEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(custom_json_feeds::table
+            .find(id)
+            .select(CustomJsonFeedEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &CustomJsonFeedEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(custom_json_feeds::table.find(model.id))
+            .set((
+                custom_json_feeds::name.eq(&model.name),
+                custom_json_feeds::url.eq(&model.url),
+                custom_json_feeds::items_path.eq(&model.items_path),
+                custom_json_feeds::id_path.eq(&model.id_path),
+                custom_json_feeds::title_path.eq(&model.title_path),
+                custom_json_feeds::url_path.eq(&model.url_path),
+                custom_json_feeds::published_path.eq(&model.published_path),
+                custom_json_feeds::created_by.eq(model.created_by),
+                custom_json_feeds::created_at.eq(model.created_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(custom_json_feeds::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &CustomJsonFeedEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(custom_json_feeds::table)
+            .values((
+                custom_json_feeds::name.eq(&model.name),
+                custom_json_feeds::url.eq(&model.url),
+                custom_json_feeds::items_path.eq(&model.items_path),
+                custom_json_feeds::id_path.eq(&model.id_path),
+                custom_json_feeds::title_path.eq(&model.title_path),
+                custom_json_feeds::url_path.eq(&model.url_path),
+                custom_json_feeds::published_path.eq(&model.published_path),
+                custom_json_feeds::created_by.eq(model.created_by),
+                custom_json_feeds::created_at.eq(model.created_at),
+            ))
+            .on_conflict(custom_json_feeds::url)
+            .do_update()
+            .set((
+                custom_json_feeds::name.eq(&model.name),
+                custom_json_feeds::url.eq(&model.url),
+                custom_json_feeds::items_path.eq(&model.items_path),
+                custom_json_feeds::id_path.eq(&model.id_path),
+                custom_json_feeds::title_path.eq(&model.title_path),
+                custom_json_feeds::url_path.eq(&model.url_path),
+                custom_json_feeds::published_path.eq(&model.published_path),
+                custom_json_feeds::created_by.eq(model.created_by),
+                custom_json_feeds::created_at.eq(model.created_at),
+            ))
+            .returning(custom_json_feeds::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+}
+
+#[async_trait::async_trait]
+impl CustomJsonFeedsRepository for PgCustomJsonFeedsRepo {
+    async fn select_by_url(
+        &self,
+        url: &str,
+    ) -> Result<Option<CustomJsonFeedEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(custom_json_feeds::table
+            .filter(custom_json_feeds::url.eq(url))
+            .select(CustomJsonFeedEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn delete_by_url(&self, url: &str) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::delete(custom_json_feeds::table.filter(custom_json_feeds::url.eq(url)))
+            .execute(&mut conn)
+            .await?;
+        Ok(rows > 0)
+    }
+}
+
+// ============================================================================
+// PgAniListLinksRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgAniListLinksRepo {
+    pool: DbPool,
+}
+
+impl PgAniListLinksRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgAniListLinksRepo, anilist_links::table);
+
+#[async_trait::async_trait]
+impl CrudTable<AniListLinkEntity, u64> for PgAniListLinksRepo {
+    async fn select_all(&self) -> Result<Vec<AniListLinkEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(anilist_links::table
+            .select(AniListLinkEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &AniListLinkEntity) -> Result<u64, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let user_id: DbU64 = diesel::insert_into(anilist_links::table)
+            .values(model)
+            .returning(anilist_links::user_id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(user_id.into())
+    }
+
+    async fn select(&self, id: &u64) -> Result<Option<AniListLinkEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(anilist_links::table
+            .find(DbU64::from(*id))
+            .select(AniListLinkEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &AniListLinkEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(anilist_links::table.find(model.user_id))
+            .set(model)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &u64) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(anilist_links::table.find(DbU64::from(*id)))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &AniListLinkEntity) -> Result<u64, DatabaseError> {
+        let user_id: u64 = model.user_id.into();
+        if self.select(&user_id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(user_id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl AniListLinksRepository for PgAniListLinksRepo {
+    async fn select_due(
+        &self,
+        before: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<AniListLinkEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(anilist_links::table
+            .filter(anilist_links::auto_sync.eq(true))
+            .filter(
+                anilist_links::last_synced_at
+                    .is_null()
+                    .or(anilist_links::last_synced_at.lt(before)),
+            )
+            .order((
+                anilist_links::last_synced_at.asc().nulls_first(),
+                anilist_links::user_id.asc(),
+            ))
+            .limit(i64::from(limit))
+            .select(AniListLinkEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+}
+
+// ============================================================================
+// PgMalLinksRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgMalLinksRepo {
+    pool: DbPool,
+}
+
+impl PgMalLinksRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgMalLinksRepo, mal_links::table);
+
+#[async_trait::async_trait]
+impl CrudTable<MalLinkEntity, u64> for PgMalLinksRepo {
+    async fn select_all(&self) -> Result<Vec<MalLinkEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(mal_links::table
+            .select(MalLinkEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &MalLinkEntity) -> Result<u64, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let user_id: DbU64 = diesel::insert_into(mal_links::table)
+            .values(model)
+            .returning(mal_links::user_id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(user_id.into())
+    }
+
+    async fn select(&self, id: &u64) -> Result<Option<MalLinkEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(mal_links::table
+            .find(DbU64::from(*id))
+            .select(MalLinkEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &MalLinkEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(mal_links::table.find(model.user_id))
+            .set(model)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &u64) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(mal_links::table.find(DbU64::from(*id)))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &MalLinkEntity) -> Result<u64, DatabaseError> {
+        let user_id: u64 = model.user_id.into();
+        if self.select(&user_id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(user_id);
+        }
+        self.insert(model).await
+    }
+}
+
+impl MalLinksRepository for PgMalLinksRepo {}
+
+// ============================================================================
+// PgTagsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgTagsRepo {
+    pool: DbPool,
+}
+
+impl PgTagsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgTagsRepo, tags::table);
+
+#[async_trait::async_trait]
+impl CrudTable<TagEntity, i32> for PgTagsRepo {
+    async fn select_all(&self) -> Result<Vec<TagEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(tags::table
+            .select(TagEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &TagEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(tags::table)
+            .values((
+                tags::guild_id.eq(model.guild_id),
+                tags::name.eq(&model.name),
+                tags::content.eq(&model.content),
+                tags::embed.eq(model.embed),
+                tags::uses.eq(model.uses),
+                tags::created_by.eq(model.created_by),
+                tags::created_at.eq(model.created_at),
+                tags::updated_at.eq(model.updated_at),
+            ))
+            .returning(tags::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<TagEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(tags::table
+            .find(id)
+            .select(TagEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &TagEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(tags::table.find(model.id))
+            .set((
+                tags::guild_id.eq(model.guild_id),
+                tags::name.eq(&model.name),
+                tags::content.eq(&model.content),
+                tags::embed.eq(model.embed),
+                tags::uses.eq(model.uses),
+                tags::created_by.eq(model.created_by),
+                tags::created_at.eq(model.created_at),
+                tags::updated_at.eq(model.updated_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(tags::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &TagEntity) -> Result<i32, DatabaseError> {
+        if model.id != 0 && self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl TagsRepository for PgTagsRepo {
+    async fn select_by_name(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<Option<TagEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(tags::table
+            .filter(tags::guild_id.eq(DbU64::from(guild_id)))
+            .filter(tags::name.eq(name))
+            .select(TagEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn count_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let count: i64 = tags::table
+            .filter(tags::guild_id.eq(DbU64::from(guild_id)))
+            .count()
+            .get_result(&mut conn)
+            .await?;
+        Ok(count as u32)
+    }
+
+    async fn select_paginated_by_guild_id(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<TagEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let limit = per_page as i64;
+        let offset = (per_page * page) as i64;
+        Ok(tags::table
+            .filter(tags::guild_id.eq(DbU64::from(guild_id)))
+            .order((tags::uses.desc(), tags::name.asc()))
+            .limit(limit)
+            .offset(offset)
+            .select(TagEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn select_by_name_search(
+        &self,
+        guild_id: u64,
+        name_search: &str,
+        limit: u32,
+    ) -> Result<Vec<TagEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let pattern = format!("%{}%", name_search.to_lowercase());
+        Ok(tags::table
+            .filter(tags::guild_id.eq(DbU64::from(guild_id)))
+            .filter(tags::name.like(pattern))
+            .order(tags::name.asc())
+            .limit(limit as i64)
+            .select(TagEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn increment_uses(&self, id: i32) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(diesel::update(tags::table.find(id))
+            .set(tags::uses.eq(tags::uses + 1))
+            .returning(tags::uses)
+            .get_result(&mut conn)
+            .await?)
+    }
+
+    async fn delete_by_name(&self, guild_id: u64, name: &str) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::delete(
+            tags::table
+                .filter(tags::guild_id.eq(DbU64::from(guild_id)))
+                .filter(tags::name.eq(name)),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(rows > 0)
+    }
+}
+
+// ============================================================================
+// PgEmojiStatsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgEmojiStatsRepo {
+    pool: DbPool,
+}
+
+impl PgEmojiStatsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgEmojiStatsRepo, emoji_stats::table);
+
+#[async_trait::async_trait]
+impl CrudTable<EmojiStatEntity, i32> for PgEmojiStatsRepo {
+    async fn select_all(&self) -> Result<Vec<EmojiStatEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(emoji_stats::table
+            .select(EmojiStatEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &EmojiStatEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(emoji_stats::table)
+            .values((
+                emoji_stats::guild_id.eq(model.guild_id),
+                emoji_stats::emoji_id.eq(model.emoji_id),
+                emoji_stats::kind.eq(model.kind),
+                emoji_stats::name.eq(&model.name),
+                emoji_stats::animated.eq(model.animated),
+                emoji_stats::message_uses.eq(model.message_uses),
+                emoji_stats::reaction_uses.eq(model.reaction_uses),
+                emoji_stats::last_used_at.eq(model.last_used_at),
+            ))
+            .returning(emoji_stats::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<EmojiStatEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(emoji_stats::table
+            .find(id)
+            .select(EmojiStatEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &EmojiStatEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(emoji_stats::table.find(model.id))
+            .set((
+                emoji_stats::guild_id.eq(model.guild_id),
+                emoji_stats::emoji_id.eq(model.emoji_id),
+                emoji_stats::kind.eq(model.kind),
+                emoji_stats::name.eq(&model.name),
+                emoji_stats::animated.eq(model.animated),
+                emoji_stats::message_uses.eq(model.message_uses),
+                emoji_stats::reaction_uses.eq(model.reaction_uses),
+                emoji_stats::last_used_at.eq(model.last_used_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(emoji_stats::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &EmojiStatEntity) -> Result<i32, DatabaseError> {
+        if model.id != 0 && self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl EmojiStatsRepository for PgEmojiStatsRepo {
+    async fn increment_uses(&self, model: &EmojiStatEntity) -> Result<(), DatabaseError> {
+        use diesel::upsert::excluded;
+
+        let mut conn = self.pool.get().await?;
+        diesel::insert_into(emoji_stats::table)
+            .values((
+                emoji_stats::guild_id.eq(model.guild_id),
+                emoji_stats::emoji_id.eq(model.emoji_id),
+                emoji_stats::kind.eq(model.kind),
+                emoji_stats::name.eq(&model.name),
+                emoji_stats::animated.eq(model.animated),
+                emoji_stats::message_uses.eq(model.message_uses),
+                emoji_stats::reaction_uses.eq(model.reaction_uses),
+                emoji_stats::last_used_at.eq(model.last_used_at),
+            ))
+            .on_conflict((emoji_stats::guild_id, emoji_stats::emoji_id))
+            .do_update()
+            .set((
+                emoji_stats::name.eq(excluded(emoji_stats::name)),
+                emoji_stats::animated.eq(excluded(emoji_stats::animated)),
+                emoji_stats::message_uses
+                    .eq(emoji_stats::message_uses + excluded(emoji_stats::message_uses)),
+                emoji_stats::reaction_uses
+                    .eq(emoji_stats::reaction_uses + excluded(emoji_stats::reaction_uses)),
+                emoji_stats::last_used_at.eq(excluded(emoji_stats::last_used_at)),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn count_by_guild_id(
+        &self,
+        guild_id: u64,
+        kind: EmojiKind,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let count: i64 = emoji_stats::table
+            .filter(emoji_stats::guild_id.eq(DbU64::from(guild_id)))
+            .filter(emoji_stats::kind.eq(kind))
+            .count()
+            .get_result(&mut conn)
+            .await?;
+        Ok(count as u32)
+    }
+
+    async fn select_paginated_by_guild_id(
+        &self,
+        guild_id: u64,
+        kind: EmojiKind,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<EmojiStatEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let limit = per_page as i64;
+        let offset = (per_page * page) as i64;
+        Ok(emoji_stats::table
+            .filter(emoji_stats::guild_id.eq(DbU64::from(guild_id)))
+            .filter(emoji_stats::kind.eq(kind))
+            .order((
+                (emoji_stats::message_uses + emoji_stats::reaction_uses).desc(),
+                emoji_stats::name.asc(),
+            ))
+            .limit(limit)
+            .offset(offset)
+            .select(EmojiStatEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+}
+
+// ============================================================================
+// PgInviteUsesRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgInviteUsesRepo {
+    pool: DbPool,
+}
+
+impl PgInviteUsesRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgInviteUsesRepo, invite_uses::table);
+
+#[async_trait::async_trait]
+impl CrudTable<InviteUseEntity, i32> for PgInviteUsesRepo {
+    async fn select_all(&self) -> Result<Vec<InviteUseEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(invite_uses::table
+            .select(InviteUseEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &InviteUseEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(invite_uses::table)
+            .values((
+                invite_uses::guild_id.eq(model.guild_id),
+                invite_uses::member_id.eq(model.member_id),
+                invite_uses::inviter_id.eq(model.inviter_id),
+                invite_uses::invite_code.eq(&model.invite_code),
+                invite_uses::joined_at.eq(model.joined_at),
+            ))
+            .returning(invite_uses::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<InviteUseEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(invite_uses::table
+            .find(id)
+            .select(InviteUseEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &InviteUseEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(invite_uses::table.find(model.id))
+            .set((
+                invite_uses::guild_id.eq(model.guild_id),
+                invite_uses::member_id.eq(model.member_id),
+                invite_uses::inviter_id.eq(model.inviter_id),
+                invite_uses::invite_code.eq(&model.invite_code),
+                invite_uses::joined_at.eq(model.joined_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(invite_uses::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &InviteUseEntity) -> Result<i32, DatabaseError> {
+        if model.id != 0 && self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl InviteUsesRepository for PgInviteUsesRepo {
+    async fn count_inviters_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let count: i64 = invite_uses::table
+            .filter(invite_uses::guild_id.eq(DbU64::from(guild_id)))
+            .select(diesel::dsl::count_distinct(invite_uses::inviter_id))
+            .get_result(&mut conn)
+            .await?;
+        Ok(count as u32)
+    }
+
+    async fn select_leaderboard_by_guild_id(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<InviteLeaderboardEntry>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::sql_query(
+            r#"
+            SELECT inviter_id, COUNT(DISTINCT member_id) AS invites
+            FROM invite_uses
+            WHERE guild_id = $1 AND inviter_id IS NOT NULL
+            GROUP BY inviter_id
+            ORDER BY invites DESC, inviter_id ASC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
+        .bind::<diesel::sql_types::BigInt, _>(per_page as i64)
+        .bind::<diesel::sql_types::BigInt, _>((per_page * page) as i64)
+        .load::<InviteLeaderboardRow>(&mut conn)
+        .await?;
+        Ok(rows.into_iter().map(InviteLeaderboardEntry::from).collect())
+    }
+
+    async fn count_by_inviter_id(
+        &self,
+        guild_id: u64,
+        inviter_id: u64,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let count: i64 = invite_uses::table
+            .filter(invite_uses::guild_id.eq(DbU64::from(guild_id)))
+            .filter(invite_uses::inviter_id.eq(DbU64::from(inviter_id)))
+            .select(diesel::dsl::count_distinct(invite_uses::member_id))
+            .get_result(&mut conn)
+            .await?;
+        Ok(count as u32)
+    }
+}
+
+// ============================================================================
+// PgTempVoiceChannelsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgTempVoiceChannelsRepo {
+    pool: DbPool,
+}
+
+impl PgTempVoiceChannelsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgTempVoiceChannelsRepo, temp_voice_channels::table);
+
+#[async_trait::async_trait]
+impl CrudTable<TempVoiceChannelEntity, u64> for PgTempVoiceChannelsRepo {
+    async fn select_all(&self) -> Result<Vec<TempVoiceChannelEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(temp_voice_channels::table
+            .select(TempVoiceChannelEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &TempVoiceChannelEntity) -> Result<u64, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let channel_id: DbU64 = diesel::insert_into(temp_voice_channels::table)
+            .values(model)
+            .returning(temp_voice_channels::channel_id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(channel_id.into())
+    }
+
+    async fn select(&self, id: &u64) -> Result<Option<TempVoiceChannelEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(temp_voice_channels::table
+            .find(DbU64::from(*id))
+            .select(TempVoiceChannelEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &TempVoiceChannelEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(temp_voice_channels::table.find(model.channel_id))
+            .set(model)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &u64) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(temp_voice_channels::table.find(DbU64::from(*id)))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &TempVoiceChannelEntity) -> Result<u64, DatabaseError> {
+        let channel_id: u64 = model.channel_id.into();
+        if self.select(&channel_id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(channel_id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl TempVoiceChannelsRepository for PgTempVoiceChannelsRepo {
+    async fn select_all_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<TempVoiceChannelEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(temp_voice_channels::table
+            .filter(temp_voice_channels::guild_id.eq(DbU64::from(guild_id)))
+            .select(TempVoiceChannelEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+}
+
+// ============================================================================
+// PgChannelWeightsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgChannelWeightsRepo {
+    pool: DbPool,
+}
+
+impl PgChannelWeightsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgChannelWeightsRepo, channel_weights::table);
+
+#[async_trait::async_trait]
+impl CrudTable<ChannelWeightEntity, i32> for PgChannelWeightsRepo {
+    async fn select_all(&self) -> Result<Vec<ChannelWeightEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(channel_weights::table
+            .select(ChannelWeightEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &ChannelWeightEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(channel_weights::table)
+            .values((
+                channel_weights::guild_id.eq(model.guild_id),
+                channel_weights::channel_id.eq(model.channel_id),
+                channel_weights::weight_percent.eq(model.weight_percent),
+            ))
+            .returning(channel_weights::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<ChannelWeightEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(channel_weights::table
+            .find(id)
+            .select(ChannelWeightEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &ChannelWeightEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(channel_weights::table.find(model.id))
+            .set((
+                channel_weights::guild_id.eq(model.guild_id),
+                channel_weights::channel_id.eq(model.channel_id),
+                channel_weights::weight_percent.eq(model.weight_percent),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(channel_weights::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &ChannelWeightEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(channel_weights::table)
+            .values((
+                channel_weights::guild_id.eq(model.guild_id),
+                channel_weights::channel_id.eq(model.channel_id),
+                channel_weights::weight_percent.eq(model.weight_percent),
+            ))
+            .on_conflict((channel_weights::guild_id, channel_weights::channel_id))
+            .do_update()
+            .set(channel_weights::weight_percent.eq(model.weight_percent))
+            .returning(channel_weights::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+}
+
+#[async_trait::async_trait]
+impl ChannelWeightsRepository for PgChannelWeightsRepo {
+    async fn select_all_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ChannelWeightEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(channel_weights::table
+            .filter(channel_weights::guild_id.eq(DbU64::from(guild_id)))
+            .order(channel_weights::channel_id.asc())
+            .select(ChannelWeightEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn replace_all_by_guild_id(
+        &self,
+        guild_id: u64,
+        weights: &[ChannelWeightEntity],
+    ) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(
+            channel_weights::table.filter(channel_weights::guild_id.eq(DbU64::from(guild_id))),
+        )
+        .execute(&mut conn)
+        .await?;
+
+        if weights.is_empty() {
+            return Ok(());
+        }
+
+        let rows: Vec<_> = weights
+            .iter()
+            .map(|weight| {
+                (
+                    channel_weights::guild_id.eq(DbU64::from(guild_id)),
+                    channel_weights::channel_id.eq(weight.channel_id),
+                    channel_weights::weight_percent.eq(weight.weight_percent),
+                )
+            })
+            .collect();
+        diesel::insert_into(channel_weights::table)
+            .values(&rows)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// PgLeaderboardSnapshotsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgLeaderboardSnapshotsRepo {
+    pool: DbPool,
+}
+
+impl PgLeaderboardSnapshotsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgLeaderboardSnapshotsRepo, leaderboard_snapshots::table);
+
+#[async_trait::async_trait]
+impl CrudTable<LeaderboardSnapshotEntity, i32> for PgLeaderboardSnapshotsRepo {
+    async fn select_all(&self) -> Result<Vec<LeaderboardSnapshotEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(leaderboard_snapshots::table
+            .select(LeaderboardSnapshotEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &LeaderboardSnapshotEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(leaderboard_snapshots::table)
+            .values((
+                leaderboard_snapshots::guild_id.eq(model.guild_id),
+                leaderboard_snapshots::user_id.eq(model.user_id),
+                leaderboard_snapshots::rank.eq(model.rank),
+                leaderboard_snapshots::total_duration.eq(model.total_duration),
+                leaderboard_snapshots::taken_at.eq(model.taken_at),
+            ))
+            .returning(leaderboard_snapshots::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<LeaderboardSnapshotEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(leaderboard_snapshots::table
+            .find(id)
+            .select(LeaderboardSnapshotEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &LeaderboardSnapshotEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(leaderboard_snapshots::table.find(model.id))
+            .set((
+                leaderboard_snapshots::guild_id.eq(model.guild_id),
+                leaderboard_snapshots::user_id.eq(model.user_id),
+                leaderboard_snapshots::rank.eq(model.rank),
+                leaderboard_snapshots::total_duration.eq(model.total_duration),
+                leaderboard_snapshots::taken_at.eq(model.taken_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(leaderboard_snapshots::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &LeaderboardSnapshotEntity) -> Result<i32, DatabaseError> {
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl LeaderboardSnapshotsRepository for PgLeaderboardSnapshotsRepo {
+    async fn insert_snapshot(
+        &self,
+        taken_at: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+
+        // Same weighted all-time totals as the leaderboard, adjustments included, ranked per guild
+        let rows = diesel::sql_query(
+            r#"
+            INSERT INTO leaderboard_snapshots (guild_id, user_id, rank, total_duration, taken_at)
+            SELECT
+                guild_id,
+                user_id,
+                ROW_NUMBER() OVER (PARTITION BY guild_id ORDER BY total_duration DESC, user_id)::int,
+                total_duration,
+                $1
+            FROM (
+                SELECT
+                    guild_id,
+                    user_id,
+                    GREATEST(SUM(duration), 0)::bigint as total_duration
+                FROM (
+                    SELECT
+                        vs.guild_id,
+                        vs.user_id,
+                        (
+                            EXTRACT(EPOCH FROM LEAST($2, CASE WHEN vs.is_active THEN CURRENT_TIMESTAMP ELSE vs.leave_time END))::bigint -
+                            EXTRACT(EPOCH FROM vs.join_time)::bigint
+                        ) * COALESCE(cw.weight_percent, 100) / 100 as duration
+                    FROM voice_sessions vs
+                    LEFT JOIN channel_weights cw
+                        ON cw.guild_id = vs.guild_id AND cw.channel_id = vs.channel_id
+                    WHERE vs.join_time <= $3
+                    UNION ALL
+                    SELECT va.guild_id, va.user_id, va.seconds as duration
+                    FROM voice_adjustments va
+                    WHERE va.applies_at <= $3
+                ) durations
+                GROUP BY guild_id, user_id
+            ) totals
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(taken_at)
+        .bind::<diesel::sql_types::Timestamptz, _>(taken_at)
+        .bind::<diesel::sql_types::Timestamptz, _>(taken_at)
+        .execute(&mut conn)
+        .await?;
+
+        Ok(rows as u32)
+    }
+
+    async fn select_latest_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<LeaderboardSnapshotEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let latest: Option<chrono::DateTime<chrono::Utc>> = leaderboard_snapshots::table
+            .filter(leaderboard_snapshots::guild_id.eq(DbU64::from(guild_id)))
+            .select(diesel::dsl::max(leaderboard_snapshots::taken_at))
+            .first(&mut conn)
+            .await?;
+
+        let Some(latest) = latest else {
+            return Ok(Vec::new());
+        };
+
+        Ok(leaderboard_snapshots::table
+            .filter(leaderboard_snapshots::guild_id.eq(DbU64::from(guild_id)))
+            .filter(leaderboard_snapshots::taken_at.eq(latest))
+            .order(leaderboard_snapshots::rank.asc())
+            .select(LeaderboardSnapshotEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn select_by_guild_id_since(
+        &self,
+        guild_id: u64,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<LeaderboardSnapshotEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(leaderboard_snapshots::table
+            .filter(leaderboard_snapshots::guild_id.eq(DbU64::from(guild_id)))
+            .filter(leaderboard_snapshots::taken_at.ge(since))
+            .order((
+                leaderboard_snapshots::taken_at.asc(),
+                leaderboard_snapshots::rank.asc(),
+            ))
+            .select(LeaderboardSnapshotEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+}
+
+// ============================================================================
+// PgVoiceSessionFlagsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgVoiceSessionFlagsRepo {
+    pool: DbPool,
+}
+
+impl PgVoiceSessionFlagsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgVoiceSessionFlagsRepo, voice_session_flags::table);
+
+#[async_trait::async_trait]
+impl CrudTable<VoiceSessionFlagEntity, i32> for PgVoiceSessionFlagsRepo {
+    async fn select_all(&self) -> Result<Vec<VoiceSessionFlagEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_session_flags::table
+            .select(VoiceSessionFlagEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &VoiceSessionFlagEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(voice_session_flags::table)
+            .values((
+                voice_session_flags::guild_id.eq(model.guild_id),
+                voice_session_flags::session_id.eq(model.session_id),
+                voice_session_flags::user_id.eq(model.user_id),
+                voice_session_flags::reason.eq(model.reason),
+                voice_session_flags::related_count.eq(model.related_count),
+                voice_session_flags::dismissed.eq(model.dismissed),
+                voice_session_flags::flagged_at.eq(model.flagged_at),
+            ))
+            .returning(voice_session_flags::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<VoiceSessionFlagEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_session_flags::table
+            .find(id)
+            .select(VoiceSessionFlagEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &VoiceSessionFlagEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(voice_session_flags::table.find(model.id))
+            .set((
+                voice_session_flags::guild_id.eq(model.guild_id),
+                voice_session_flags::session_id.eq(model.session_id),
+                voice_session_flags::user_id.eq(model.user_id),
+                voice_session_flags::reason.eq(model.reason),
+                voice_session_flags::related_count.eq(model.related_count),
+                voice_session_flags::dismissed.eq(model.dismissed),
+                voice_session_flags::flagged_at.eq(model.flagged_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(voice_session_flags::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &VoiceSessionFlagEntity) -> Result<i32, DatabaseError> {
+        if self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl VoiceSessionFlagsRepository for PgVoiceSessionFlagsRepo {
+    async fn flag_long_sessions(
+        &self,
+        min_secs: i64,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::sql_query(
+            r#"
+            INSERT INTO voice_session_flags (guild_id, session_id, user_id, reason, related_count)
+            SELECT guild_id, COALESCE(parent_session_id, id), user_id, 'long_session', 0
+            FROM voice_sessions
+            WHERE COALESCE(parent_session_id, id) IN (
+                SELECT COALESCE(parent_session_id, id)
+                FROM voice_sessions
+                WHERE is_active OR leave_time >= $1
+            )
+            GROUP BY guild_id, COALESCE(parent_session_id, id), user_id
+            HAVING EXTRACT(EPOCH FROM MAX(CASE WHEN is_active THEN CURRENT_TIMESTAMP ELSE leave_time END) - MIN(join_time)) >= $2
+            ON CONFLICT (session_id, reason) DO NOTHING
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::BigInt, _>(min_secs)
+        .execute(&mut conn)
+        .await?;
+        Ok(rows as u32)
+    }
+
+    async fn flag_synced_sessions(
+        &self,
+        tolerance_secs: i32,
+        min_accounts: i32,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::sql_query(
+            r#"
+            INSERT INTO voice_session_flags (guild_id, session_id, user_id, reason, related_count)
+            SELECT s.guild_id, s.id, s.user_id, 'synced_sessions', COUNT(DISTINCT o.user_id)::int
+            FROM voice_sessions s
+            JOIN voice_sessions o
+                ON o.guild_id = s.guild_id
+                AND o.user_id != s.user_id
+                AND NOT o.is_active
+                AND o.join_time BETWEEN s.join_time - $1 * INTERVAL '1 second' AND s.join_time + $1 * INTERVAL '1 second'
+                AND o.leave_time BETWEEN s.leave_time - $1 * INTERVAL '1 second' AND s.leave_time + $1 * INTERVAL '1 second'
+            WHERE NOT s.is_active AND s.leave_time >= $2
+            GROUP BY s.guild_id, s.id, s.user_id
+            HAVING COUNT(DISTINCT o.user_id) + 1 >= $3
+            ON CONFLICT (session_id, reason) DO NOTHING
+            "#,
+        )
+        .bind::<diesel::sql_types::Integer, _>(tolerance_secs)
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::Integer, _>(min_accounts)
+        .execute(&mut conn)
+        .await?;
+        Ok(rows as u32)
+    }
+
+    async fn count_open_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let count: i64 = voice_session_flags::table
+            .filter(voice_session_flags::guild_id.eq(DbU64::from(guild_id)))
+            .filter(voice_session_flags::dismissed.eq(false))
+            .count()
+            .get_result(&mut conn)
+            .await?;
+        Ok(count as u32)
+    }
+
+    async fn select_open_by_guild_id(
+        &self,
+        guild_id: u64,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<VoiceSessionFlag>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows: Vec<VoiceSessionFlagRow> = diesel::sql_query(
+            r#"
+            SELECT
+                f.id,
+                f.session_id,
+                f.user_id,
+                vs.channel_id,
+                f.reason,
+                f.related_count,
+                vs.join_time,
+                splits.end_time,
+                splits.is_active,
+                f.flagged_at
+            FROM voice_session_flags f
+            JOIN voice_sessions vs ON vs.id = f.session_id
+            CROSS JOIN LATERAL (
+                SELECT
+                    MAX(CASE WHEN s.is_active THEN CURRENT_TIMESTAMP ELSE s.leave_time END) as end_time,
+                    bool_or(s.is_active) as is_active
+                FROM voice_sessions s
+                WHERE s.id = vs.id OR s.parent_session_id = vs.id
+            ) splits
+            WHERE f.guild_id = $1 AND NOT f.dismissed
+            ORDER BY f.flagged_at DESC, f.id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
+        .bind::<diesel::sql_types::BigInt, _>(limit as i64)
+        .bind::<diesel::sql_types::BigInt, _>(offset as i64)
+        .load(&mut conn)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn dismiss(&self, guild_id: u64, id: i32) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows = diesel::update(
+            voice_session_flags::table
+                .filter(voice_session_flags::id.eq(id))
+                .filter(voice_session_flags::guild_id.eq(DbU64::from(guild_id))),
+        )
+        .set(voice_session_flags::dismissed.eq(true))
+        .execute(&mut conn)
+        .await?;
+        Ok(rows > 0)
+    }
+
+    async fn delete_flagged_session(&self, guild_id: u64, id: i32) -> Result<bool, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        // Flags of the session are removed by the cascading foreign key
+        let rows = diesel::sql_query(
+            r#"
+            DELETE FROM voice_sessions
+            WHERE id = (
+                SELECT session_id FROM voice_session_flags WHERE id = $1 AND guild_id = $2
+            )
+            "#,
+        )
+        .bind::<diesel::sql_types::Integer, _>(id)
+        .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
+        .execute(&mut conn)
+        .await?;
+        Ok(rows > 0)
+    }
+}
+
+// ============================================================================
+// PgVoiceAccountMergesRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgVoiceAccountMergesRepo {
+    pool: DbPool,
+}
+
+impl PgVoiceAccountMergesRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgVoiceAccountMergesRepo, voice_account_merges::table);
+
+#[async_trait::async_trait]
+impl CrudTable<VoiceAccountMergeEntity, i32> for PgVoiceAccountMergesRepo {
+    async fn select_all(&self) -> Result<Vec<VoiceAccountMergeEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_account_merges::table
+            .select(VoiceAccountMergeEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &VoiceAccountMergeEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(voice_account_merges::table)
+            .values((
+                voice_account_merges::from_user_id.eq(model.from_user_id),
+                voice_account_merges::to_user_id.eq(model.to_user_id),
+                voice_account_merges::merged_by.eq(model.merged_by),
+                voice_account_merges::sessions_moved.eq(model.sessions_moved),
+                voice_account_merges::adjustments_moved.eq(model.adjustments_moved),
+                voice_account_merges::flags_moved.eq(model.flags_moved),
+                voice_account_merges::snapshots_moved.eq(model.snapshots_moved),
+                voice_account_merges::merged_at.eq(model.merged_at),
+            ))
+            .returning(voice_account_merges::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<VoiceAccountMergeEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_account_merges::table
+            .find(id)
+            .select(VoiceAccountMergeEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &VoiceAccountMergeEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(voice_account_merges::table.find(model.id))
+            .set((
+                voice_account_merges::from_user_id.eq(model.from_user_id),
+                voice_account_merges::to_user_id.eq(model.to_user_id),
+                voice_account_merges::merged_by.eq(model.merged_by),
+                voice_account_merges::sessions_moved.eq(model.sessions_moved),
+                voice_account_merges::adjustments_moved.eq(model.adjustments_moved),
+                voice_account_merges::flags_moved.eq(model.flags_moved),
+                voice_account_merges::snapshots_moved.eq(model.snapshots_moved),
+                voice_account_merges::merged_at.eq(model.merged_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(voice_account_merges::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &VoiceAccountMergeEntity) -> Result<i32, DatabaseError> {
+        if self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl VoiceAccountMergesRepository for PgVoiceAccountMergesRepo {
+    async fn merge_users(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        merged_by: u64,
+    ) -> Result<VoiceAccountMergeEntity, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let from = DbU64::from(from_user_id);
+        let to = DbU64::from(to_user_id);
+
+        conn.transaction::<_, DatabaseError, _>(|conn| {
+            async move {
+                // A session both accounts have at the same moment is already counted
+                // for the target, and would break the session uniqueness
+                diesel::sql_query(
+                    r#"
+                    DELETE FROM voice_sessions f
+                    USING voice_sessions t
+                    WHERE f.user_id = $1 AND t.user_id = $2
+                    AND t.channel_id = f.channel_id AND t.join_time = f.join_time
+                    "#,
+                )
+                .bind::<diesel::sql_types::BigInt, _>(from_user_id as i64)
+                .bind::<diesel::sql_types::BigInt, _>(to_user_id as i64)
+                .execute(conn)
+                .await?;
+                let sessions_moved =
+                    diesel::update(voice_sessions::table.filter(voice_sessions::user_id.eq(from)))
+                        .set(voice_sessions::user_id.eq(to))
+                        .execute(conn)
+                        .await?;
+
+                let adjustments_moved = diesel::update(
+                    voice_adjustments::table.filter(voice_adjustments::user_id.eq(from)),
+                )
+                .set(voice_adjustments::user_id.eq(to))
+                .execute(conn)
+                .await?;
+
+                let flags_moved = diesel::update(
+                    voice_session_flags::table.filter(voice_session_flags::user_id.eq(from)),
+                )
+                .set(voice_session_flags::user_id.eq(to))
+                .execute(conn)
+                .await?;
+
+                // Snapshots taken while both accounts were ranked are folded into the
+                // target's row; ranks stay as they were taken
+                let snapshots_merged = diesel::sql_query(
+                    r#"
+                    UPDATE leaderboard_snapshots t
+                    SET total_duration = t.total_duration + f.total_duration
+                    FROM leaderboard_snapshots f
+                    WHERE f.user_id = $1 AND t.user_id = $2
+                    AND t.guild_id = f.guild_id AND t.taken_at = f.taken_at
+                    "#,
+                )
+                .bind::<diesel::sql_types::BigInt, _>(from_user_id as i64)
+                .bind::<diesel::sql_types::BigInt, _>(to_user_id as i64)
+                .execute(conn)
+                .await?;
+                diesel::sql_query(
+                    r#"
+                    DELETE FROM leaderboard_snapshots f
+                    USING leaderboard_snapshots t
+                    WHERE f.user_id = $1 AND t.user_id = $2
+                    AND t.guild_id = f.guild_id AND t.taken_at = f.taken_at
+                    "#,
+                )
+                .bind::<diesel::sql_types::BigInt, _>(from_user_id as i64)
+                .bind::<diesel::sql_types::BigInt, _>(to_user_id as i64)
+                .execute(conn)
+                .await?;
+                let snapshots_moved = diesel::update(
+                    leaderboard_snapshots::table.filter(leaderboard_snapshots::user_id.eq(from)),
+                )
+                .set(leaderboard_snapshots::user_id.eq(to))
+                .execute(conn)
+                .await?;
+
+                Ok(diesel::insert_into(voice_account_merges::table)
+                    .values((
+                        voice_account_merges::from_user_id.eq(from),
+                        voice_account_merges::to_user_id.eq(to),
+                        voice_account_merges::merged_by.eq(DbU64::from(merged_by)),
+                        voice_account_merges::sessions_moved.eq(sessions_moved as i32),
+                        voice_account_merges::adjustments_moved.eq(adjustments_moved as i32),
+                        voice_account_merges::flags_moved.eq(flags_moved as i32),
+                        voice_account_merges::snapshots_moved
+                            .eq((snapshots_merged + snapshots_moved) as i32),
+                    ))
+                    .returning(VoiceAccountMergeEntity::as_returning())
+                    .get_result(conn)
+                    .await?)
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+}
+
+// ============================================================================
+// PgVoiceAdjustmentsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgVoiceAdjustmentsRepo {
+    pool: DbPool,
+}
+
+impl PgVoiceAdjustmentsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgVoiceAdjustmentsRepo, voice_adjustments::table);
+
+#[async_trait::async_trait]
+impl CrudTable<VoiceAdjustmentEntity, i32> for PgVoiceAdjustmentsRepo {
+    async fn select_all(&self) -> Result<Vec<VoiceAdjustmentEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_adjustments::table
+            .select(VoiceAdjustmentEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &VoiceAdjustmentEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(voice_adjustments::table)
+            .values((
+                voice_adjustments::guild_id.eq(model.guild_id),
+                voice_adjustments::user_id.eq(model.user_id),
+                voice_adjustments::seconds.eq(model.seconds),
+                voice_adjustments::reason.eq(&model.reason),
+                voice_adjustments::adjusted_by.eq(model.adjusted_by),
+                voice_adjustments::applies_at.eq(model.applies_at),
+                voice_adjustments::created_at.eq(model.created_at),
+            ))
+            .returning(voice_adjustments::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<VoiceAdjustmentEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_adjustments::table
+            .find(id)
+            .select(VoiceAdjustmentEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &VoiceAdjustmentEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(voice_adjustments::table.find(model.id))
+            .set((
+                voice_adjustments::guild_id.eq(model.guild_id),
+                voice_adjustments::user_id.eq(model.user_id),
+                voice_adjustments::seconds.eq(model.seconds),
+                voice_adjustments::reason.eq(&model.reason),
+                voice_adjustments::adjusted_by.eq(model.adjusted_by),
+                voice_adjustments::applies_at.eq(model.applies_at),
+                voice_adjustments::created_at.eq(model.created_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(voice_adjustments::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &VoiceAdjustmentEntity) -> Result<i32, DatabaseError> {
+        if self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl VoiceAdjustmentsRepository for PgVoiceAdjustmentsRepo {
+    async fn sum_by_user(&self, guild_id: u64, user_id: u64) -> Result<i64, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let seconds: Vec<i64> = voice_adjustments::table
+            .filter(voice_adjustments::guild_id.eq(DbU64::from(guild_id)))
+            .filter(voice_adjustments::user_id.eq(DbU64::from(user_id)))
+            .select(voice_adjustments::seconds)
+            .load(&mut conn)
+            .await?;
+        Ok(seconds.into_iter().sum())
+    }
+
+    async fn insert_many(&self, models: &[VoiceAdjustmentEntity]) -> Result<u32, DatabaseError> {
+        // Postgres takes at most 65535 bind parameters per statement
+        const CHUNK_SIZE: usize = 5000;
+
+        let mut conn = self.pool.get().await?;
+        conn.transaction::<_, DatabaseError, _>(|conn| {
+            async move {
+                let mut inserted = 0;
+                for chunk in models.chunks(CHUNK_SIZE) {
+                    let rows: Vec<_> = chunk
+                        .iter()
+                        .map(|model| {
+                            (
+                                voice_adjustments::guild_id.eq(model.guild_id),
+                                voice_adjustments::user_id.eq(model.user_id),
+                                voice_adjustments::seconds.eq(model.seconds),
+                                voice_adjustments::reason.eq(&model.reason),
+                                voice_adjustments::adjusted_by.eq(model.adjusted_by),
+                                voice_adjustments::applies_at.eq(model.applies_at),
+                                voice_adjustments::created_at.eq(model.created_at),
+                            )
+                        })
+                        .collect();
+                    inserted += diesel::insert_into(voice_adjustments::table)
+                        .values(&rows)
+                        .execute(conn)
+                        .await?;
+                }
+                Ok(inserted as u32)
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+}
+
+// ============================================================================
+// PgVoiceEventsRepo
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PgVoiceEventsRepo {
+    pool: DbPool,
+}
+
+impl PgVoiceEventsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgVoiceEventsRepo, voice_events::table);
+
+#[async_trait::async_trait]
+impl CrudTable<VoiceEventEntity, i64> for PgVoiceEventsRepo {
+    async fn select_all(&self) -> Result<Vec<VoiceEventEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_events::table
+            .order(voice_events::id.asc())
+            .select(VoiceEventEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn insert(&self, model: &VoiceEventEntity) -> Result<i64, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(voice_events::table)
+            .values((
+                voice_events::guild_id.eq(model.guild_id),
+                voice_events::user_id.eq(model.user_id),
+                voice_events::kind.eq(model.kind),
+                voice_events::channel_id.eq(model.channel_id),
+                voice_events::previous_channel_id.eq(model.previous_channel_id),
+                voice_events::muted.eq(model.muted),
+                voice_events::deafened.eq(model.deafened),
+                voice_events::is_bot.eq(model.is_bot),
+                voice_events::is_stage.eq(model.is_stage),
+                voice_events::occurred_at.eq(model.occurred_at),
+            ))
+            .returning(voice_events::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i64) -> Result<Option<VoiceEventEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_events::table
+            .find(id)
+            .select(VoiceEventEntity::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?)
+    }
+
+    async fn update(&self, model: &VoiceEventEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(voice_events::table.find(model.id))
+            .set((
+                voice_events::guild_id.eq(model.guild_id),
+                voice_events::user_id.eq(model.user_id),
+                voice_events::kind.eq(model.kind),
+                voice_events::channel_id.eq(model.channel_id),
+                voice_events::previous_channel_id.eq(model.previous_channel_id),
+                voice_events::muted.eq(model.muted),
+                voice_events::deafened.eq(model.deafened),
+                voice_events::is_bot.eq(model.is_bot),
+                voice_events::is_stage.eq(model.is_stage),
+                voice_events::occurred_at.eq(model.occurred_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i64) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(voice_events::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &VoiceEventEntity) -> Result<i64, DatabaseError> {
+        if self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+#[async_trait::async_trait]
+impl VoiceEventsRepository for PgVoiceEventsRepo {
+    async fn select_after(
+        &self,
+        after_id: i64,
+        limit: u32,
+    ) -> Result<Vec<VoiceEventEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_events::table
+            .filter(voice_events::id.gt(after_id))
+            .order(voice_events::id.asc())
+            .limit(limit as i64)
+            .select(VoiceEventEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn select_by_guild_id_between(
+        &self,
+        guild_id: u64,
+        after_id: i64,
+        until_id: i64,
+        limit: u32,
+    ) -> Result<Vec<VoiceEventEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        Ok(voice_events::table
+            .filter(voice_events::guild_id.eq(DbU64::from(guild_id)))
+            .filter(voice_events::id.gt(after_id))
+            .filter(voice_events::id.le(until_id))
+            .order(voice_events::id.asc())
+            .limit(limit as i64)
+            .select(VoiceEventEntity::as_select())
+            .load(&mut conn)
+            .await?)
+    }
+
+    async fn delete_before(
+        &self,
+        guild_id: Option<u64>,
+        before: &chrono::DateTime<chrono::Utc>,
+        until_id: i64,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let mut query = diesel::delete(voice_events::table)
+            .filter(voice_events::occurred_at.lt(before))
+            .filter(voice_events::id.le(until_id))
+            .into_boxed();
+        if let Some(guild_id) = guild_id {
+            query = query.filter(voice_events::guild_id.eq(DbU64::from(guild_id)));
+        }
+        let affected = query.execute(&mut conn).await?;
+        Ok(affected as u32)
+    }
+}
+
+// ============================================================================
+// PgVoiceSessionsRepo
+// ============================================================================
+
+/// Leaderboard ranges at least this many days long are read from `voice_daily_totals`.
+const DAILY_TOTALS_MIN_RANGE_DAYS: i64 = 7;
+
+#[derive(Clone)]
+pub struct PgVoiceSessionsRepo {
+    pool: DbPool,
+}
+
+impl PgVoiceSessionsRepo {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl_table_base!(PgVoiceSessionsRepo, voice_sessions::table);
+
+#[async_trait::async_trait]
+impl CrudTable<VoiceSessionsEntity, i32> for PgVoiceSessionsRepo {
+    async fn select_all(&self) -> Result<Vec<VoiceSessionsEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows: Vec<DbVoiceSession> = voice_sessions::table
+            .select(DbVoiceSession::as_select())
+            .load(&mut conn)
+            .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert(&self, model: &VoiceSessionsEntity) -> Result<i32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let id = diesel::insert_into(voice_sessions::table)
+            .values(&model.to_insertable())
+            .returning(voice_sessions::id)
+            .get_result(&mut conn)
+            .await?;
+        Ok(id)
+    }
+
+    async fn select(&self, id: &i32) -> Result<Option<VoiceSessionsEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let result: Option<DbVoiceSession> = voice_sessions::table
+            .find(id)
+            .select(DbVoiceSession::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(result.map(Into::into))
+    }
+
+    async fn update(&self, model: &VoiceSessionsEntity) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::update(voice_sessions::table.find(model.id))
+            .set(&model.to_insertable())
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &i32) -> Result<(), DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(voice_sessions::table.find(id))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace(&self, model: &VoiceSessionsEntity) -> Result<i32, DatabaseError> {
+        if model.id != 0 && self.select(&model.id).await?.is_some() {
+            self.update(model).await?;
+            return Ok(model.id);
+        }
+        self.insert(model).await
+    }
+}
+
+impl PgVoiceSessionsRepo {
+    /// Weighted leaderboard adding up `voice_daily_totals` for the whole UTC days in the
+    /// range. Only the parts of ended sessions outside those days, open sessions and
+    /// adjustments are read row by row.
+    ///
+    /// Weights apply to each member's total per channel, so it can differ by a second
+    /// per session from weighting each session.
+    async fn get_leaderboard_from_daily_totals(
+        &self,
+        guild_id: u64,
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<VoiceLeaderboardEntry>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        // Whole days are [first_day, end_day)
+        let first_day = if since.time() == chrono::NaiveTime::MIN {
+            since.date_naive()
+        } else {
+            since.date_naive() + chrono::Days::new(1)
+        };
+        let end_day = until.date_naive().max(first_day);
+        let days_start = first_day.and_time(chrono::NaiveTime::MIN).and_utc();
+        let days_end = end_day.and_time(chrono::NaiveTime::MIN).and_utc();
+
+        let rows: Vec<VoiceLeaderboardRow> = diesel::sql_query(
+            r#"
+            SELECT
+                user_id,
+                GREATEST(SUM(duration), 0)::bigint as total_duration
+            FROM (
+                SELECT
+                    d.user_id,
+                    SUM(d.seconds)::bigint * COALESCE(cw.weight_percent, 100) / 100 as duration
+                FROM (
+                    SELECT vdt.user_id, vdt.channel_id, vdt.seconds
+                    FROM voice_daily_totals vdt
+                    WHERE vdt.guild_id = $1
+                    AND vdt.day >= $2
+                    AND vdt.day < $3
+                    UNION ALL
+                    SELECT
+                        vs.user_id,
+                        vs.channel_id,
+                        CASE WHEN vs.is_active THEN
+                            EXTRACT(EPOCH FROM LEAST($5, vs.leave_time))::bigint -
+                            EXTRACT(EPOCH FROM GREATEST($4, vs.join_time))::bigint
+                        ELSE
+                            GREATEST(
+                                EXTRACT(EPOCH FROM LEAST($6, vs.leave_time))::bigint -
+                                EXTRACT(EPOCH FROM GREATEST($4, vs.join_time))::bigint,
+                                0
+                            ) +
+                            GREATEST(
+                                EXTRACT(EPOCH FROM LEAST($5, vs.leave_time))::bigint -
+                                EXTRACT(EPOCH FROM GREATEST($7, vs.join_time))::bigint,
+                                0
+                            )
+                        END as seconds
+                    FROM voice_sessions vs
+                    WHERE vs.guild_id = $1
+                    AND vs.join_time <= $5
+                    AND vs.leave_time >= $4
+                    AND (vs.is_active OR vs.join_time < $6 OR vs.leave_time > $7)
+                ) d
+                LEFT JOIN channel_weights cw
+                    ON cw.guild_id = $1 AND cw.channel_id = d.channel_id
+                GROUP BY d.user_id, d.channel_id, cw.weight_percent
+                UNION ALL
+                SELECT va.user_id, va.seconds as duration
+                FROM voice_adjustments va
+                WHERE va.guild_id = $1
+                AND va.applies_at <= $5
+                AND va.applies_at >= $4
+            ) durations
+            GROUP BY user_id ORDER BY total_duration DESC LIMIT $8 OFFSET $9
+            "#,
+        )
+        .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
+        .bind::<diesel::sql_types::Date, _>(first_day)
+        .bind::<diesel::sql_types::Date, _>(end_day)
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Timestamptz, _>(days_start)
+        .bind::<diesel::sql_types::Timestamptz, _>(days_end)
+        .bind::<diesel::sql_types::BigInt, _>(limit)
+        .bind::<diesel::sql_types::BigInt, _>(offset)
+        .load(&mut conn)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Weighted leaderboard where time a member spends alone in a channel past
+    /// `discount.after_secs` only counts `discount.weight_percent`.
+    ///
+    /// A member is alone whenever no other session overlaps theirs in the same channel.
+    /// Idle stretches are measured over the whole session, then clipped to the range.
+    /// Open sessions count up to their last flushed leave_time.
+    async fn get_leaderboard_idle_discounted(
+        &self,
+        guild_id: u64,
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+        discount: IdleAloneDiscount,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<VoiceLeaderboardEntry>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows: Vec<VoiceLeaderboardRow> = diesel::sql_query(
+            r#"
+            WITH sessions AS (
+                SELECT
+                    vs.id,
+                    vs.guild_id,
+                    vs.user_id,
+                    vs.channel_id,
+                    vs.join_time,
+                    vs.leave_time as end_time,
+                    COALESCE(cw.weight_percent, 100) as weight_percent
+                FROM voice_sessions vs
+                LEFT JOIN channel_weights cw
+                    ON cw.guild_id = vs.guild_id AND cw.channel_id = vs.channel_id
+                WHERE vs.guild_id = $1
+                AND vs.join_time <= $2
+                AND vs.leave_time >= $3
+            ),
+            idle AS (
+                SELECT
+                    s.id,
+                    SUM(COALESCE(
+                        EXTRACT(EPOCH FROM
+                            upper(tstzrange(lower(alone) + $4 * INTERVAL '1 second', upper(alone)) * tstzrange($3, $2)) -
+                            lower(tstzrange(lower(alone) + $4 * INTERVAL '1 second', upper(alone)) * tstzrange($3, $2))
+                        )::bigint,
+                        0
+                    ))::bigint as idle_seconds
+                FROM sessions s
+                CROSS JOIN LATERAL unnest(
+                    tstzmultirange(tstzrange(s.join_time, s.end_time)) - COALESCE((
+                        SELECT range_agg(tstzrange(o.join_time, o.leave_time))
+                        FROM voice_sessions o
+                        WHERE o.guild_id = s.guild_id
+                        AND o.channel_id = s.channel_id
+                        AND o.user_id != s.user_id
+                        AND o.join_time < s.end_time
+                        AND o.leave_time > s.join_time
+                    ), '{}'::tstzmultirange)
+                ) alone
+                WHERE upper(alone) - lower(alone) > $4 * INTERVAL '1 second'
+                GROUP BY s.id
+            )
+            SELECT
+                user_id,
+                GREATEST(SUM(duration), 0)::bigint as total_duration
+            FROM (
+                SELECT
+                    s.user_id,
+                    (
+                        EXTRACT(EPOCH FROM LEAST($2, s.end_time))::bigint -
+                        EXTRACT(EPOCH FROM GREATEST($3, s.join_time))::bigint -
+                        COALESCE(i.idle_seconds, 0) * (100 - $5) / 100
+                    ) * s.weight_percent / 100 as duration
+                FROM sessions s
+                LEFT JOIN idle i ON i.id = s.id
+                UNION ALL
+                SELECT va.user_id, va.seconds as duration
+                FROM voice_adjustments va
+                WHERE va.guild_id = $1
+                AND va.applies_at <= $2
+                AND va.applies_at >= $3
+            ) durations
+            GROUP BY user_id ORDER BY total_duration DESC LIMIT $6 OFFSET $7
+            "#,
+        )
+        .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
+        .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::Integer, _>(discount.after_secs as i32)
+        .bind::<diesel::sql_types::Integer, _>(discount.weight_percent as i32)
+        .bind::<diesel::sql_types::BigInt, _>(limit)
+        .bind::<diesel::sql_types::BigInt, _>(offset)
+        .load(&mut conn)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl VoiceSessionsRepository for PgVoiceSessionsRepo {
+    async fn get_leaderboard_opt(
+        &self,
+        opts: &VoiceLeaderboardOpt,
+    ) -> Result<Vec<VoiceLeaderboardEntry>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let limit = opts.limit.unwrap_or(10) as i64;
+        let offset = opts.offset.unwrap_or(0) as i64;
+        let since_val = opts.since.unwrap_or(chrono::DateTime::UNIX_EPOCH);
+        let until_val = opts
+            .until
+            .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::days(365));
+
+        if let Some(discount) = opts.idle_discount {
+            return self
+                .get_leaderboard_idle_discounted(
+                    opts.guild_id,
+                    since_val,
+                    until_val,
+                    discount,
+                    limit,
+                    offset,
+                )
+                .await;
+        }
+        if until_val - since_val >= chrono::Duration::days(DAILY_TOTALS_MIN_RANGE_DAYS) {
+            return self
+                .get_leaderboard_from_daily_totals(
+                    opts.guild_id,
+                    since_val,
+                    until_val,
+                    limit,
+                    offset,
+                )
+                .await;
+        }
+
+        // Durations are scaled by the channel's weight; unweighted channels count at 100%.
+        // Admin adjustments in the range are added as-is. Open sessions count up to their
+        // last flushed leave_time.
+        let rows: Vec<VoiceLeaderboardRow> = diesel::sql_query(
+            r#"
+            SELECT
+                user_id,
+                GREATEST(SUM(duration), 0)::bigint as total_duration
+            FROM (
+                SELECT
+                    vs.user_id,
+                    (
+                        EXTRACT(EPOCH FROM LEAST($1, vs.leave_time))::bigint -
+                        EXTRACT(EPOCH FROM GREATEST($2, vs.join_time))::bigint
+                    ) * COALESCE(cw.weight_percent, 100) / 100 as duration
+                FROM voice_sessions vs
+                LEFT JOIN channel_weights cw
+                    ON cw.guild_id = vs.guild_id AND cw.channel_id = vs.channel_id
+                WHERE vs.guild_id = $3
+                AND vs.join_time <= $4
+                AND vs.leave_time >= $5
+                UNION ALL
+                SELECT va.user_id, va.seconds as duration
+                FROM voice_adjustments va
+                WHERE va.guild_id = $3
+                AND va.applies_at <= $4
+                AND va.applies_at >= $5
+            ) durations
+            GROUP BY user_id ORDER BY total_duration DESC LIMIT $6 OFFSET $7
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(until_val)
+        .bind::<diesel::sql_types::Timestamptz, _>(since_val)
+        .bind::<diesel::sql_types::BigInt, _>(opts.guild_id as i64)
+        .bind::<diesel::sql_types::Timestamptz, _>(until_val)
+        .bind::<diesel::sql_types::Timestamptz, _>(since_val)
+        .bind::<diesel::sql_types::BigInt, _>(limit)
         .bind::<diesel::sql_types::BigInt, _>(offset)
         .load(&mut conn)
         .await?;
@@ -839,6 +4181,106 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         self.get_leaderboard_opt(&opts).await
     }
 
+    async fn get_global_leaderboard(
+        &self,
+        guild_ids: &[u64],
+        named_guild_ids: &[u64],
+        since: &chrono::DateTime<chrono::Utc>,
+        until: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<GlobalVoiceLeaderboardEntry>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let guild_ids: Vec<i64> = guild_ids.iter().map(|id| *id as i64).collect();
+        let named_guild_ids: Vec<i64> = named_guild_ids.iter().map(|id| *id as i64).collect();
+
+        // Weighted per-guild totals like the guild leaderboard, then summed per user
+        let rows: Vec<GlobalVoiceLeaderboardRow> = diesel::sql_query(
+            r#"
+            WITH per_guild AS (
+                SELECT
+                    user_id,
+                    guild_id,
+                    SUM(duration) as total
+                FROM (
+                    SELECT
+                        vs.user_id,
+                        vs.guild_id,
+                        (
+                            EXTRACT(EPOCH FROM LEAST($1, CASE WHEN vs.is_active THEN CURRENT_TIMESTAMP ELSE vs.leave_time END))::bigint -
+                            EXTRACT(EPOCH FROM GREATEST($2, vs.join_time))::bigint
+                        ) * COALESCE(cw.weight_percent, 100) / 100 as duration
+                    FROM voice_sessions vs
+                    LEFT JOIN channel_weights cw
+                        ON cw.guild_id = vs.guild_id AND cw.channel_id = vs.channel_id
+                    WHERE vs.guild_id = ANY($3)
+                    AND vs.join_time <= $1
+                    AND (vs.is_active OR vs.leave_time >= $2)
+                    UNION ALL
+                    SELECT va.user_id, va.guild_id, va.seconds as duration
+                    FROM voice_adjustments va
+                    WHERE va.guild_id = ANY($3)
+                    AND va.applies_at <= $1
+                    AND va.applies_at >= $2
+                ) durations
+                GROUP BY user_id, guild_id
+                HAVING SUM(duration) > 0
+            )
+            SELECT
+                user_id,
+                SUM(total)::bigint as total_duration,
+                (array_agg(guild_id ORDER BY total DESC))[1] as top_guild_id,
+                COUNT(*)::int as guild_count,
+                bool_and(guild_id = ANY($4)) as named
+            FROM per_guild
+            GROUP BY user_id
+            ORDER BY total_duration DESC, user_id
+            LIMIT $5
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::Array<diesel::sql_types::BigInt>, _>(guild_ids)
+        .bind::<diesel::sql_types::Array<diesel::sql_types::BigInt>, _>(named_guild_ids)
+        .bind::<diesel::sql_types::BigInt, _>(limit as i64)
+        .load(&mut conn)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_channel_usage(
+        &self,
+        guild_id: u64,
+        since: &chrono::DateTime<chrono::Utc>,
+        until: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<VoiceChannelUsage>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows: Vec<VoiceChannelUsageRow> = diesel::sql_query(
+            r#"
+            SELECT
+                channel_id,
+                SUM(
+                    EXTRACT(EPOCH FROM LEAST($1, CASE WHEN is_active THEN CURRENT_TIMESTAMP ELSE leave_time END))::bigint -
+                    EXTRACT(EPOCH FROM GREATEST($2, join_time))::bigint
+                )::bigint as total_duration,
+                COUNT(DISTINCT user_id)::bigint as user_count
+            FROM voice_sessions
+            WHERE guild_id = $3
+            AND join_time <= $1
+            AND (is_active OR leave_time >= $2)
+            GROUP BY channel_id
+            ORDER BY total_duration DESC, channel_id
+            "#,
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Timestamptz, _>(since)
+        .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
+        .load(&mut conn)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     async fn get_partner_leaderboard(
         &self,
         opts: &VoiceLeaderboardOpt,
@@ -912,6 +4354,20 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         Ok(())
     }
 
+    async fn update_active_leave_times(
+        &self,
+        leave_time: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<VoiceSessionsEntity>, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let rows: Vec<DbVoiceSession> =
+            diesel::update(voice_sessions::table.filter(voice_sessions::is_active.eq(true)))
+                .set(voice_sessions::leave_time.eq(leave_time))
+                .returning(DbVoiceSession::as_returning())
+                .get_results(&mut conn)
+                .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     async fn close_session(
         &self,
         user_id: u64,
@@ -962,6 +4418,98 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    async fn delete_by_guild_id_since(
+        &self,
+        guild_id: u64,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        let affected = diesel::delete(
+            voice_sessions::table
+                .filter(voice_sessions::guild_id.eq(DbU64::from(guild_id)))
+                .filter(voice_sessions::join_time.ge(since)),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(affected as u32)
+    }
+
+    async fn delete_ended_before(
+        &self,
+        guild_id: Option<u64>,
+        before: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        // Deleting the first split cascades to the later ones
+        let affected = diesel::sql_query(
+            r#"
+            DELETE FROM voice_sessions vs
+            WHERE vs.parent_session_id IS NULL
+                AND ($1 IS NULL OR vs.guild_id = $1)
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM voice_sessions s
+                    WHERE (s.id = vs.id OR s.parent_session_id = vs.id)
+                        AND (s.is_active OR s.leave_time >= $2)
+                )
+            "#,
+        )
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>, _>(
+            guild_id.map(|id| id as i64),
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(before)
+        .execute(&mut conn)
+        .await?;
+        Ok(affected as u32)
+    }
+
+    async fn rebuild_daily_totals(&self) -> Result<u32, DatabaseError> {
+        let mut conn = self.pool.get().await?;
+        conn.transaction::<_, DatabaseError, _>(|conn| {
+            async move {
+                // Sessions ending meanwhile wait, so their totals are neither lost nor
+                // counted twice
+                diesel::sql_query("LOCK TABLE voice_daily_totals IN EXCLUSIVE MODE")
+                    .execute(conn)
+                    .await?;
+                diesel::sql_query("DELETE FROM voice_daily_totals")
+                    .execute(conn)
+                    .await?;
+                let written = diesel::sql_query(
+                    r#"
+                    INSERT INTO voice_daily_totals (guild_id, day, user_id, channel_id, seconds)
+                    SELECT
+                        vs.guild_id,
+                        day::date,
+                        vs.user_id,
+                        vs.channel_id,
+                        SUM(
+                            EXTRACT(EPOCH FROM LEAST(vs.leave_time AT TIME ZONE 'UTC', day + INTERVAL '1 day'))::bigint -
+                            EXTRACT(EPOCH FROM GREATEST(vs.join_time AT TIME ZONE 'UTC', day))::bigint
+                        )::bigint
+                    FROM voice_sessions vs
+                    CROSS JOIN LATERAL generate_series(
+                        date_trunc('day', vs.join_time AT TIME ZONE 'UTC'),
+                        vs.leave_time AT TIME ZONE 'UTC',
+                        INTERVAL '1 day'
+                    ) AS day
+                    WHERE NOT vs.is_active AND vs.leave_time > vs.join_time
+                    GROUP BY vs.guild_id, day, vs.user_id, vs.channel_id
+                    HAVING SUM(
+                        EXTRACT(EPOCH FROM LEAST(vs.leave_time AT TIME ZONE 'UTC', day + INTERVAL '1 day'))::bigint -
+                        EXTRACT(EPOCH FROM GREATEST(vs.join_time AT TIME ZONE 'UTC', day))::bigint
+                    ) != 0
+                    "#,
+                )
+                .execute(conn)
+                .await?;
+                Ok(written as u32)
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
     async fn get_sessions_in_range(
         &self,
         guild_id: u64,
@@ -994,22 +4542,30 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<VoiceDailyActivity>, DatabaseError> {
         let mut conn = self.pool.get().await?;
         let rows = diesel::sql_query(
             r#"
             SELECT
-                DATE(join_time) as day,
-                SUM(
+                day,
+                GREATEST(SUM(seconds), 0)::bigint as total_seconds
+            FROM (
+                SELECT
+                    DATE(join_time AT TIME ZONE $5) as day,
                     CASE
                         WHEN is_active
                         THEN EXTRACT(EPOCH FROM NOW())::bigint - EXTRACT(EPOCH FROM join_time)::bigint
                         ELSE EXTRACT(EPOCH FROM leave_time)::bigint - EXTRACT(EPOCH FROM join_time)::bigint
-                    END
-                )::bigint as total_seconds
-            FROM voice_sessions
-            WHERE user_id = $1 AND guild_id = $2 AND join_time >= $3 AND join_time <= $4
-            GROUP BY DATE(join_time)
+                    END as seconds
+                FROM voice_sessions
+                WHERE user_id = $1 AND guild_id = $2 AND join_time >= $3 AND join_time <= $4
+                UNION ALL
+                SELECT DATE(applies_at AT TIME ZONE $5) as day, seconds
+                FROM voice_adjustments
+                WHERE user_id = $1 AND guild_id = $2 AND applies_at >= $3 AND applies_at <= $4
+            ) durations
+            GROUP BY day
             ORDER BY day
             "#,
         )
@@ -1017,6 +4573,7 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
         .bind::<diesel::sql_types::Timestamptz, _>(since)
         .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Text, _>(timezone)
         .load::<VoiceDailyActivity>(&mut conn)
         .await?;
         Ok(rows)
@@ -1027,6 +4584,7 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<GuildDailyStats>, DatabaseError> {
         let mut conn = self.pool.get().await?;
         let rows = diesel::sql_query(
@@ -1037,17 +4595,25 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
             FROM (
                 SELECT
                     user_id,
-                    DATE(join_time) as day,
-                    SUM(
+                    day,
+                    GREATEST(SUM(seconds), 0)::bigint as user_daily_total
+                FROM (
+                    SELECT
+                        user_id,
+                        DATE(join_time AT TIME ZONE $4) as day,
                         CASE
                             WHEN is_active
                             THEN EXTRACT(EPOCH FROM NOW())::bigint - EXTRACT(EPOCH FROM join_time)::bigint
                             ELSE EXTRACT(EPOCH FROM leave_time)::bigint - EXTRACT(EPOCH FROM join_time)::bigint
-                        END
-                    )::bigint as user_daily_total
-                FROM voice_sessions
-                WHERE guild_id = $1 AND join_time >= $2 AND join_time <= $3
-                GROUP BY user_id, DATE(join_time)
+                        END as seconds
+                    FROM voice_sessions
+                    WHERE guild_id = $1 AND join_time >= $2 AND join_time <= $3
+                    UNION ALL
+                    SELECT user_id, DATE(applies_at AT TIME ZONE $4) as day, seconds
+                    FROM voice_adjustments
+                    WHERE guild_id = $1 AND applies_at >= $2 AND applies_at <= $3
+                ) durations
+                GROUP BY user_id, day
             ) user_totals
             GROUP BY day
             ORDER BY day
@@ -1056,6 +4622,7 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
         .bind::<diesel::sql_types::Timestamptz, _>(since)
         .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Text, _>(timezone)
         .load::<GuildDailyStats>(&mut conn)
         .await?;
         Ok(rows)
@@ -1066,6 +4633,7 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<GuildDailyStats>, DatabaseError> {
         let mut conn = self.pool.get().await?;
         let rows = diesel::sql_query(
@@ -1076,17 +4644,25 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
             FROM (
                 SELECT
                     user_id,
-                    DATE(join_time) as day,
-                    SUM(
+                    day,
+                    GREATEST(SUM(seconds), 0)::bigint as user_daily_total
+                FROM (
+                    SELECT
+                        user_id,
+                        DATE(join_time AT TIME ZONE $4) as day,
                         CASE
                             WHEN is_active
                             THEN EXTRACT(EPOCH FROM NOW())::bigint - EXTRACT(EPOCH FROM join_time)::bigint
                             ELSE EXTRACT(EPOCH FROM leave_time)::bigint - EXTRACT(EPOCH FROM join_time)::bigint
-                        END
-                    )::bigint as user_daily_total
-                FROM voice_sessions
-                WHERE guild_id = $1 AND join_time >= $2 AND join_time <= $3
-                GROUP BY user_id, DATE(join_time)
+                        END as seconds
+                    FROM voice_sessions
+                    WHERE guild_id = $1 AND join_time >= $2 AND join_time <= $3
+                    UNION ALL
+                    SELECT user_id, DATE(applies_at AT TIME ZONE $4) as day, seconds
+                    FROM voice_adjustments
+                    WHERE guild_id = $1 AND applies_at >= $2 AND applies_at <= $3
+                ) durations
+                GROUP BY user_id, day
             ) user_totals
             GROUP BY day
             ORDER BY day
@@ -1095,6 +4671,7 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
         .bind::<diesel::sql_types::Timestamptz, _>(since)
         .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Text, _>(timezone)
         .load::<GuildDailyStats>(&mut conn)
         .await?;
         Ok(rows)
@@ -1105,22 +4682,24 @@ impl VoiceSessionsRepository for PgVoiceSessionsRepo {
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<GuildDailyStats>, DatabaseError> {
         let mut conn = self.pool.get().await?;
         let rows = diesel::sql_query(
             r#"
             SELECT
-                DATE(join_time) as day,
+                DATE(join_time AT TIME ZONE $4) as day,
                 COUNT(DISTINCT user_id) as value
             FROM voice_sessions
             WHERE guild_id = $1 AND join_time >= $2 AND join_time <= $3
-            GROUP BY DATE(join_time)
+            GROUP BY day
             ORDER BY day
             "#,
         )
         .bind::<diesel::sql_types::BigInt, _>(guild_id as i64)
         .bind::<diesel::sql_types::Timestamptz, _>(since)
         .bind::<diesel::sql_types::Timestamptz, _>(until)
+        .bind::<diesel::sql_types::Text, _>(timezone)
         .load::<GuildDailyStats>(&mut conn)
         .await?;
         Ok(rows)
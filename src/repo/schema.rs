@@ -1,189 +1,1329 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    /// Representation of the `anilist_links` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    anilist_links (user_id) {
+        /// The `user_id` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int8,
+        /// The `anilist_user_id` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        anilist_user_id -> Int4,
+        /// The `username` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        username -> Text,
+        /// The `auto_sync` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        auto_sync -> Bool,
+        /// The `synced_media_ids` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        synced_media_ids -> Jsonb,
+        /// The `ignored_media_ids` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ignored_media_ids -> Jsonb,
+        /// The `synced_manga_urls` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        synced_manga_urls -> Jsonb,
+        /// The `last_synced_at` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_synced_at -> Nullable<Timestamptz>,
+        /// The `created_at` column of the `anilist_links` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `api_tokens` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    api_tokens (id) {
+        /// The `id` column of the `api_tokens` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `api_tokens` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Nullable<Int8>,
+        /// The `token_hash` column of the `api_tokens` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        token_hash -> Text,
+        /// The `created_by` column of the `api_tokens` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Nullable<Int8>,
+        /// The `created_at` column of the `api_tokens` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     /// Representation of the `bot_meta` table.
     ///
     /// (Automatically generated by Diesel.)
-    bot_meta (key) {
-        /// The `key` column of the `bot_meta` table.
+    bot_meta (key) {
+        /// The `key` column of the `bot_meta` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        key -> Text,
+        /// The `value` column of the `bot_meta` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        value -> Text,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `channel_weights` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    channel_weights (id) {
+        /// The `id` column of the `channel_weights` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `channel_weights` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `channel_id` column of the `channel_weights` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        channel_id -> Int8,
+        /// The `weight_percent` column of the `channel_weights` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        weight_percent -> Int4,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `custom_json_feeds` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    custom_json_feeds (id) {
+        /// The `id` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `name` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `url` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        url -> Text,
+        /// The `items_path` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        items_path -> Text,
+        /// The `id_path` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id_path -> Text,
+        /// The `title_path` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title_path -> Text,
+        /// The `url_path` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        url_path -> Nullable<Text>,
+        /// The `published_path` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        published_path -> Nullable<Text>,
+        /// The `created_by` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Nullable<Int8>,
+        /// The `created_at` column of the `custom_json_feeds` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `dashboard_tokens` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    dashboard_tokens (id) {
+        /// The `id` column of the `dashboard_tokens` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `dashboard_tokens` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `token` column of the `dashboard_tokens` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        token -> Text,
+        /// The `created_by` column of the `dashboard_tokens` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Nullable<Int8>,
+        /// The `created_at` column of the `dashboard_tokens` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `delivery_outbox` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    delivery_outbox (id) {
+        /// The `id` column of the `delivery_outbox` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `subscriber_id` column of the `delivery_outbox` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        subscriber_id -> Int4,
+        /// The `data` column of the `delivery_outbox` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        data -> Jsonb,
+        /// The `queued_at` column of the `delivery_outbox` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        queued_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `emoji_stats` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    emoji_stats (id) {
+        /// The `id` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `emoji_id` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        emoji_id -> Int8,
+        /// The `kind` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Text,
+        /// The `name` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `animated` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        animated -> Bool,
+        /// The `message_uses` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        message_uses -> Int4,
+        /// The `reaction_uses` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reaction_uses -> Int4,
+        /// The `last_used_at` column of the `emoji_stats` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_used_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `feed_collections` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    feed_collections (id) {
+        /// The `id` column of the `feed_collections` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `feed_collections` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `name` column of the `feed_collections` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `channel_id` column of the `feed_collections` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        channel_id -> Int8,
+        /// The `mention_role_id` column of the `feed_collections` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        mention_role_id -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `feed_items` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    feed_items (id) {
+        /// The `id` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `feed_id` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        feed_id -> Int4,
+        /// The `description` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `published` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        published -> Timestamptz,
+        /// The `content_hash` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        content_hash -> Nullable<Text>,
+        /// The `media_url` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        media_url -> Nullable<Text>,
+        /// The `duration_secs` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        duration_secs -> Nullable<Int4>,
+        /// The `source_item_id` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_item_id -> Nullable<Text>,
+        /// The `item_url` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        item_url -> Nullable<Text>,
+        /// The `image_url` column of the `feed_items` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        image_url -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `feed_subscriptions` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    feed_subscriptions (id) {
+        /// The `id` column of the `feed_subscriptions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `feed_id` column of the `feed_subscriptions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        feed_id -> Int4,
+        /// The `subscriber_id` column of the `feed_subscriptions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        subscriber_id -> Int4,
+        /// The `paused` column of the `feed_subscriptions` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        paused -> Bool,
+        /// The `notify_edits` column of the `feed_subscriptions` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        notify_edits -> Bool,
+        /// The `added_by` column of the `feed_subscriptions` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        added_by -> Nullable<Text>,
+        /// The `collection_id` column of the `feed_subscriptions` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        collection_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `feeds` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    feeds (id) {
+        /// The `id` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `name` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+        /// The `description` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `platform_id` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        platform_id -> Text,
+        /// The `source_id` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_id -> Text,
+        /// The `items_id` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        items_id -> Text,
+        /// The `source_url` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_url -> Text,
+        /// The `cover_url` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        cover_url -> Text,
+        /// The `tags` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        tags -> Text,
+        /// The `poll_tier` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        poll_tier -> Text,
+        /// The `accent_color` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        accent_color -> Nullable<Int4>,
+        /// The `emoji` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        emoji -> Nullable<Text>,
+        /// The `title_english` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title_english -> Nullable<Text>,
+        /// The `title_romanized` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title_romanized -> Nullable<Text>,
+        /// The `title_native` column of the `feeds` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title_native -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `invite_uses` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    invite_uses (id) {
+        /// The `id` column of the `invite_uses` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `invite_uses` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `member_id` column of the `invite_uses` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        member_id -> Int8,
+        /// The `inviter_id` column of the `invite_uses` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        inviter_id -> Nullable<Int8>,
+        /// The `invite_code` column of the `invite_uses` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        invite_code -> Nullable<Text>,
+        /// The `joined_at` column of the `invite_uses` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        joined_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `leaderboard_snapshots` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    leaderboard_snapshots (id) {
+        /// The `id` column of the `leaderboard_snapshots` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `leaderboard_snapshots` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `user_id` column of the `leaderboard_snapshots` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int8,
+        /// The `rank` column of the `leaderboard_snapshots` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rank -> Int4,
+        /// The `total_duration` column of the `leaderboard_snapshots` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        total_duration -> Int8,
+        /// The `taken_at` column of the `leaderboard_snapshots` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        taken_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `mal_links` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    mal_links (user_id) {
+        /// The `user_id` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int8,
+        /// The `mal_user_id` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        mal_user_id -> Int4,
+        /// The `username` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        username -> Text,
+        /// The `access_token` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        access_token -> Text,
+        /// The `refresh_token` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        refresh_token -> Text,
+        /// The `token_expires_at` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        token_expires_at -> Timestamptz,
+        /// The `last_imported_at` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_imported_at -> Nullable<Timestamptz>,
+        /// The `created_at` column of the `mal_links` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `notification_log` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    notification_log (id) {
+        /// The `id` column of the `notification_log` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `subscriber_id` column of the `notification_log` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        subscriber_id -> Int4,
+        /// The `feed_id` column of the `notification_log` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        feed_id -> Int4,
+        /// The `sent_at` column of the `notification_log` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        sent_at -> Timestamptz,
+        /// The `delivered` column of the `notification_log` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        delivered -> Bool,
+        /// The `delay_secs` column of the `notification_log` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        delay_secs -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `pending_subscriptions` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    pending_subscriptions (id) {
+        /// The `id` column of the `pending_subscriptions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `pending_subscriptions` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `source_url` column of the `pending_subscriptions` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_url -> Text,
+        /// The `requested_by` column of the `pending_subscriptions` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        requested_by -> Int8,
+        /// The `requested_at` column of the `pending_subscriptions` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        requested_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `server_settings` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    server_settings (guild_id) {
+        /// The `guild_id` column of the `server_settings` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `settings` column of the `server_settings` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        settings -> Jsonb,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `settings_audit` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    settings_audit (id) {
+        /// The `id` column of the `settings_audit` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `guild_id` column of the `settings_audit` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `changed_by` column of the `settings_audit` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        changed_by -> Nullable<Int8>,
+        /// The `key` column of the `settings_audit` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        key -> Text,
+        /// The `old_value` column of the `settings_audit` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        old_value -> Nullable<Text>,
+        /// The `new_value` column of the `settings_audit` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        new_value -> Nullable<Text>,
+        /// The `changed_at` column of the `settings_audit` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        changed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `subscribers` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    subscribers (id) {
+        /// The `id` column of the `subscribers` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `type` column of the `subscribers` table.
         ///
         /// Its SQL type is `Text`.
         ///
         /// (Automatically generated by Diesel.)
-        key -> Text,
-        /// The `value` column of the `bot_meta` table.
+        #[sql_name = "type"]
+        type_ -> Text,
+        /// The `target_id` column of the `subscribers` table.
         ///
         /// Its SQL type is `Text`.
         ///
         /// (Automatically generated by Diesel.)
-        value -> Text,
+        target_id -> Text,
+        /// The `max_subscriptions` column of the `subscribers` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        max_subscriptions -> Nullable<Int4>,
+        /// The `preferences` column of the `subscribers` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        preferences -> Jsonb,
+        /// The `first_seen` column of the `subscribers` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        first_seen -> Bool,
     }
 }
 
 diesel::table! {
-    /// Representation of the `feed_items` table.
+    /// Representation of the `tags` table.
     ///
     /// (Automatically generated by Diesel.)
-    feed_items (id) {
-        /// The `id` column of the `feed_items` table.
+    tags (id) {
+        /// The `id` column of the `tags` table.
         ///
         /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
         id -> Int4,
-        /// The `feed_id` column of the `feed_items` table.
+        /// The `guild_id` column of the `tags` table.
         ///
-        /// Its SQL type is `Int4`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        feed_id -> Int4,
-        /// The `description` column of the `feed_items` table.
+        guild_id -> Int8,
+        /// The `name` column of the `tags` table.
         ///
         /// Its SQL type is `Text`.
         ///
         /// (Automatically generated by Diesel.)
-        description -> Text,
-        /// The `published` column of the `feed_items` table.
+        name -> Text,
+        /// The `content` column of the `tags` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        content -> Text,
+        /// The `embed` column of the `tags` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        embed -> Bool,
+        /// The `uses` column of the `tags` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        uses -> Int4,
+        /// The `created_by` column of the `tags` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Nullable<Int8>,
+        /// The `created_at` column of the `tags` table.
         ///
         /// Its SQL type is `Timestamptz`.
         ///
         /// (Automatically generated by Diesel.)
-        published -> Timestamptz,
+        created_at -> Timestamptz,
+        /// The `updated_at` column of the `tags` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamptz,
     }
 }
 
 diesel::table! {
-    /// Representation of the `feed_subscriptions` table.
+    /// Representation of the `temp_voice_channels` table.
     ///
     /// (Automatically generated by Diesel.)
-    feed_subscriptions (id) {
-        /// The `id` column of the `feed_subscriptions` table.
+    temp_voice_channels (channel_id) {
+        /// The `channel_id` column of the `temp_voice_channels` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        channel_id -> Int8,
+        /// The `guild_id` column of the `temp_voice_channels` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        guild_id -> Int8,
+        /// The `owner_id` column of the `temp_voice_channels` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        owner_id -> Int8,
+        /// The `created_at` column of the `temp_voice_channels` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `voice_account_merges` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    voice_account_merges (id) {
+        /// The `id` column of the `voice_account_merges` table.
         ///
         /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
         id -> Int4,
-        /// The `feed_id` column of the `feed_subscriptions` table.
+        /// The `from_user_id` column of the `voice_account_merges` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        from_user_id -> Int8,
+        /// The `to_user_id` column of the `voice_account_merges` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        to_user_id -> Int8,
+        /// The `merged_by` column of the `voice_account_merges` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        merged_by -> Int8,
+        /// The `sessions_moved` column of the `voice_account_merges` table.
         ///
         /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
-        feed_id -> Int4,
-        /// The `subscriber_id` column of the `feed_subscriptions` table.
+        sessions_moved -> Int4,
+        /// The `adjustments_moved` column of the `voice_account_merges` table.
         ///
         /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
-        subscriber_id -> Int4,
+        adjustments_moved -> Int4,
+        /// The `flags_moved` column of the `voice_account_merges` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        flags_moved -> Int4,
+        /// The `snapshots_moved` column of the `voice_account_merges` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        snapshots_moved -> Int4,
+        /// The `merged_at` column of the `voice_account_merges` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        merged_at -> Timestamptz,
     }
 }
 
 diesel::table! {
-    /// Representation of the `feeds` table.
+    /// Representation of the `voice_adjustments` table.
     ///
     /// (Automatically generated by Diesel.)
-    feeds (id) {
-        /// The `id` column of the `feeds` table.
+    voice_adjustments (id) {
+        /// The `id` column of the `voice_adjustments` table.
         ///
         /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
         id -> Int4,
-        /// The `name` column of the `feeds` table.
+        /// The `guild_id` column of the `voice_adjustments` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        name -> Text,
-        /// The `description` column of the `feeds` table.
+        guild_id -> Int8,
+        /// The `user_id` column of the `voice_adjustments` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        description -> Text,
-        /// The `platform_id` column of the `feeds` table.
+        user_id -> Int8,
+        /// The `seconds` column of the `voice_adjustments` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        platform_id -> Text,
-        /// The `source_id` column of the `feeds` table.
+        seconds -> Int8,
+        /// The `reason` column of the `voice_adjustments` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Nullable<Text>`.
         ///
         /// (Automatically generated by Diesel.)
-        source_id -> Text,
-        /// The `items_id` column of the `feeds` table.
+        reason -> Nullable<Text>,
+        /// The `adjusted_by` column of the `voice_adjustments` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        items_id -> Text,
-        /// The `source_url` column of the `feeds` table.
+        adjusted_by -> Int8,
+        /// The `applies_at` column of the `voice_adjustments` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Timestamptz`.
         ///
         /// (Automatically generated by Diesel.)
-        source_url -> Text,
-        /// The `cover_url` column of the `feeds` table.
+        applies_at -> Timestamptz,
+        /// The `created_at` column of the `voice_adjustments` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Timestamptz`.
         ///
         /// (Automatically generated by Diesel.)
-        cover_url -> Text,
-        /// The `tags` column of the `feeds` table.
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `voice_daily_totals` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    voice_daily_totals (guild_id, day, user_id, channel_id) {
+        /// The `guild_id` column of the `voice_daily_totals` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        tags -> Text,
+        guild_id -> Int8,
+        /// The `day` column of the `voice_daily_totals` table.
+        ///
+        /// Its SQL type is `Date`.
+        ///
+        /// (Automatically generated by Diesel.)
+        day -> Date,
+        /// The `user_id` column of the `voice_daily_totals` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int8,
+        /// The `channel_id` column of the `voice_daily_totals` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        channel_id -> Int8,
+        /// The `seconds` column of the `voice_daily_totals` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        seconds -> Int8,
     }
 }
 
 diesel::table! {
-    /// Representation of the `server_settings` table.
+    /// Representation of the `voice_events` table.
     ///
     /// (Automatically generated by Diesel.)
-    server_settings (guild_id) {
-        /// The `guild_id` column of the `server_settings` table.
+    voice_events (id) {
+        /// The `id` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `guild_id` column of the `voice_events` table.
         ///
         /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
         guild_id -> Int8,
-        /// The `settings` column of the `server_settings` table.
+        /// The `user_id` column of the `voice_events` table.
         ///
-        /// Its SQL type is `Jsonb`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        settings -> Jsonb,
+        user_id -> Int8,
+        /// The `kind` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Text,
+        /// The `channel_id` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        channel_id -> Nullable<Int8>,
+        /// The `previous_channel_id` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Nullable<Int8>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        previous_channel_id -> Nullable<Int8>,
+        /// The `muted` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        muted -> Bool,
+        /// The `deafened` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        deafened -> Bool,
+        /// The `is_bot` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        is_bot -> Bool,
+        /// The `is_stage` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        is_stage -> Bool,
+        /// The `occurred_at` column of the `voice_events` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        occurred_at -> Timestamptz,
     }
 }
 
 diesel::table! {
-    /// Representation of the `subscribers` table.
+    /// Representation of the `voice_session_flags` table.
     ///
     /// (Automatically generated by Diesel.)
-    subscribers (id) {
-        /// The `id` column of the `subscribers` table.
+    voice_session_flags (id) {
+        /// The `id` column of the `voice_session_flags` table.
         ///
         /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
         id -> Int4,
-        /// The `type` column of the `subscribers` table.
+        /// The `guild_id` column of the `voice_session_flags` table.
         ///
-        /// Its SQL type is `Text`.
+        /// Its SQL type is `Int8`.
         ///
         /// (Automatically generated by Diesel.)
-        #[sql_name = "type"]
-        type_ -> Text,
-        /// The `target_id` column of the `subscribers` table.
+        guild_id -> Int8,
+        /// The `session_id` column of the `voice_session_flags` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        session_id -> Int4,
+        /// The `user_id` column of the `voice_session_flags` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int8,
+        /// The `reason` column of the `voice_session_flags` table.
         ///
         /// Its SQL type is `Text`.
         ///
         /// (Automatically generated by Diesel.)
-        target_id -> Text,
+        reason -> Text,
+        /// The `related_count` column of the `voice_session_flags` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        related_count -> Int4,
+        /// The `dismissed` column of the `voice_session_flags` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        dismissed -> Bool,
+        /// The `flagged_at` column of the `voice_session_flags` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        flagged_at -> Timestamptz,
     }
 }
 
@@ -234,19 +1374,51 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         is_active -> Bool,
+        /// The `parent_session_id` column of the `voice_sessions` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        parent_session_id -> Nullable<Int4>,
     }
 }
 
+diesel::joinable!(delivery_outbox -> subscribers (subscriber_id));
 diesel::joinable!(feed_items -> feeds (feed_id));
+diesel::joinable!(feed_subscriptions -> feed_collections (collection_id));
 diesel::joinable!(feed_subscriptions -> feeds (feed_id));
 diesel::joinable!(feed_subscriptions -> subscribers (subscriber_id));
+diesel::joinable!(notification_log -> feeds (feed_id));
+diesel::joinable!(notification_log -> subscribers (subscriber_id));
+diesel::joinable!(voice_session_flags -> voice_sessions (session_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    anilist_links,
+    api_tokens,
     bot_meta,
+    channel_weights,
+    custom_json_feeds,
+    dashboard_tokens,
+    delivery_outbox,
+    emoji_stats,
+    feed_collections,
     feed_items,
     feed_subscriptions,
     feeds,
+    invite_uses,
+    leaderboard_snapshots,
+    mal_links,
+    notification_log,
+    pending_subscriptions,
     server_settings,
+    settings_audit,
     subscribers,
+    tags,
+    temp_voice_channels,
+    voice_account_merges,
+    voice_adjustments,
+    voice_daily_totals,
+    voice_events,
+    voice_session_flags,
     voice_sessions,
 );
@@ -49,13 +49,20 @@ pub trait FeedRepository: CrudTable<FeedEntity, i32> + Send + Sync {
         platform_id: &str,
         source_id: &str,
     ) -> Result<Option<FeedEntity>, DatabaseError>;
-    /// Searches for feeds by name within a subscriber's subscriptions.
+    /// Searches for feeds by name or title variant within a subscriber's subscriptions.
     async fn select_by_name_and_subscriber_id(
         &self,
         subscriber_id: &i32,
         name_search: &str,
         limit: Option<u32>,
     ) -> Result<Vec<FeedEntity>, DatabaseError>;
+    /// Returns every feed a subscriber is subscribed to.
+    async fn select_all_by_subscriber_id(
+        &self,
+        subscriber_id: i32,
+    ) -> Result<Vec<FeedEntity>, DatabaseError>;
+    /// Sets how often a feed is polled.
+    async fn update_poll_tier(&self, feed_id: i32, tier: PollTier) -> Result<(), DatabaseError>;
 }
 
 /// Operations for the `feed_item` table.
@@ -73,23 +80,42 @@ pub trait FeedItemRepository: CrudTable<FeedItemEntity, i32> + Send + Sync {
     ) -> Result<Vec<FeedItemEntity>, DatabaseError>;
     /// Deletes all items associated with a feed.
     async fn delete_all_by_feed_id(&self, feed_id: i32) -> Result<(), DatabaseError>;
+    /// Deletes a feed's items published before `before`, except its latest item. Returns
+    /// the number deleted.
+    async fn delete_published_before(
+        &self,
+        feed_id: i32,
+        before: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError>;
 }
 
 /// Operations for the `subscriber` table (Guilds or DMs).
 #[async_trait]
 pub trait SubscriberRepository: CrudTable<SubscriberEntity, i32> + Send + Sync {
-    /// Returns all subscribers of a specific type that are subscribed to a feed.
+    /// Returns all subscribers of a specific type with an active (not paused) subscription
+    /// to a feed.
     async fn select_all_by_type_and_feed(
         &self,
         r#type: SubscriberType,
         feed_id: i32,
     ) -> Result<Vec<SubscriberEntity>, DatabaseError>;
+    /// Returns all subscribers of a specific type with an active subscription to a feed
+    /// that also wants edit notifications.
+    async fn select_edit_subscribers_by_type_and_feed(
+        &self,
+        r#type: SubscriberType,
+        feed_id: i32,
+    ) -> Result<Vec<SubscriberEntity>, DatabaseError>;
     /// Finds a subscriber by its type and Discord target ID (Guild ID or User ID).
     async fn select_by_type_and_target(
         &self,
         r#type: &SubscriberType,
         target_id: &str,
     ) -> Result<Option<SubscriberEntity>, DatabaseError>;
+    /// Clears a subscriber's `first_seen` flag.
+    ///
+    /// Returns `false` if it was already cleared, so only one caller sees it set.
+    async fn clear_first_seen(&self, id: i32) -> Result<bool, DatabaseError>;
 }
 
 /// Operations for the `feed_subscription` table.
@@ -105,6 +131,12 @@ pub trait FeedSubscriptionRepository: CrudTable<FeedSubscriptionEntity, i32> + S
         &self,
         subscriber_id: i32,
     ) -> Result<Vec<FeedSubscriptionEntity>, DatabaseError>;
+    /// Returns a subscriber's subscription to a feed, if any.
+    async fn select_by_feed_and_subscriber(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+    ) -> Result<Option<FeedSubscriptionEntity>, DatabaseError>;
     /// Counts total subscriptions for a subscriber.
     async fn count_by_subscriber_id(&self, subscriber_id: i32) -> Result<u32, DatabaseError>;
     /// Returns a paginated list of subscriptions.
@@ -121,7 +153,7 @@ pub trait FeedSubscriptionRepository: CrudTable<FeedSubscriptionEntity, i32> + S
         page: u32,
         per_page: u32,
     ) -> Result<Vec<FeedWithLatestItemRow>, DatabaseError>;
-    /// Checks if any subscriber is currently following a feed.
+    /// Checks if any subscriber with an active (not paused) subscription is following a feed.
     async fn exists_by_feed_id(&self, feed_id: i32) -> Result<bool, DatabaseError>;
     /// Deletes a specific subscription link.
     async fn delete_subscription(
@@ -129,6 +161,33 @@ pub trait FeedSubscriptionRepository: CrudTable<FeedSubscriptionEntity, i32> + S
         feed_id: i32,
         subscriber_id: i32,
     ) -> Result<bool, DatabaseError>;
+    /// Sets the paused state of a specific subscription link.
+    ///
+    /// Returns `false` if the subscription does not exist.
+    async fn update_paused(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+        paused: bool,
+    ) -> Result<bool, DatabaseError>;
+    /// Sets whether a specific subscription link is notified of edited items.
+    ///
+    /// Returns `false` if the subscription does not exist.
+    async fn update_notify_edits(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+        notify_edits: bool,
+    ) -> Result<bool, DatabaseError>;
+    /// Sets the server collection of a specific subscription link.
+    ///
+    /// Returns `false` if the subscription does not exist.
+    async fn update_collection(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+        collection_id: Option<i32>,
+    ) -> Result<bool, DatabaseError>;
     /// Deletes all subscriptions for a specific feed.
     async fn delete_all_by_feed_id(&self, feed_id: i32) -> Result<(), DatabaseError>;
     /// Deletes all subscriptions for a specific subscriber.
@@ -139,10 +198,381 @@ pub trait FeedSubscriptionRepository: CrudTable<FeedSubscriptionEntity, i32> + S
 #[async_trait]
 pub trait ServerSettingsRepository: CrudTable<ServerSettingsEntity, u64> + Send + Sync {}
 
+/// Operations for the `settings_audit` table.
+#[async_trait]
+pub trait SettingsAuditRepository: CrudTable<SettingsAuditEntity, i32> + Send + Sync {
+    /// Counts recorded changes for a guild.
+    async fn count_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError>;
+    /// Returns a paginated list of changes for a guild, newest first.
+    async fn select_paginated_by_guild_id(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<SettingsAuditEntity>, DatabaseError>;
+}
+
+/// Operations for the `dashboard_tokens` table.
+#[async_trait]
+pub trait DashboardTokensRepository: CrudTable<DashboardTokenEntity, i32> + Send + Sync {
+    /// Returns the dashboard token of a guild, if one was generated.
+    async fn select_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<DashboardTokenEntity>, DatabaseError>;
+    /// Returns the dashboard token matching the given token string.
+    async fn select_by_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<DashboardTokenEntity>, DatabaseError>;
+    /// Deletes the dashboard token of a guild. Returns whether one existed.
+    async fn delete_by_guild_id(&self, guild_id: u64) -> Result<bool, DatabaseError>;
+}
+
+/// Operations for the `api_tokens` table.
+///
+/// A `None` guild ID addresses the bot owner's token.
+#[async_trait]
+pub trait ApiTokensRepository: CrudTable<ApiTokenEntity, i32> + Send + Sync {
+    /// Returns the API token of a guild, or the owner's token.
+    async fn select_by_guild_id(
+        &self,
+        guild_id: Option<u64>,
+    ) -> Result<Option<ApiTokenEntity>, DatabaseError>;
+    /// Returns the API token whose hash matches.
+    async fn select_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<ApiTokenEntity>, DatabaseError>;
+    /// Deletes the API token of a guild, or the owner's token. Returns whether one existed.
+    async fn delete_by_guild_id(&self, guild_id: Option<u64>) -> Result<bool, DatabaseError>;
+}
+
+/// Operations for the `custom_json_feeds` table.
+#[async_trait]
+pub trait CustomJsonFeedsRepository: CrudTable<CustomJsonFeedEntity, i32> + Send + Sync {
+    /// Returns the custom feed polling the given url.
+    async fn select_by_url(&self, url: &str)
+    -> Result<Option<CustomJsonFeedEntity>, DatabaseError>;
+    /// Deletes the custom feed polling the given url. Returns whether one existed.
+    async fn delete_by_url(&self, url: &str) -> Result<bool, DatabaseError>;
+}
+
+/// Operations for the `anilist_links` table.
+#[async_trait]
+pub trait AniListLinksRepository: CrudTable<AniListLinkEntity, u64> + Send + Sync {
+    /// Returns up to `limit` links with auto sync on that were last synced before
+    /// `before`, never synced and least recently synced first.
+    async fn select_due(
+        &self,
+        before: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<AniListLinkEntity>, DatabaseError>;
+}
+
+/// Operations for the `mal_links` table.
+pub trait MalLinksRepository: CrudTable<MalLinkEntity, u64> + Send + Sync {}
+
+/// Operations for the `tags` table.
+#[async_trait]
+pub trait TagsRepository: CrudTable<TagEntity, i32> + Send + Sync {
+    /// Returns the tag with the given name in a guild.
+    async fn select_by_name(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<Option<TagEntity>, DatabaseError>;
+    /// Returns the number of tags defined in a guild.
+    async fn count_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError>;
+    /// Returns a page of a guild's tags, most used first.
+    async fn select_paginated_by_guild_id(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<TagEntity>, DatabaseError>;
+    /// Returns up to `limit` tags of a guild whose name contains `name_search`.
+    async fn select_by_name_search(
+        &self,
+        guild_id: u64,
+        name_search: &str,
+        limit: u32,
+    ) -> Result<Vec<TagEntity>, DatabaseError>;
+    /// Increments a tag's usage counter and returns the new count.
+    async fn increment_uses(&self, id: i32) -> Result<i32, DatabaseError>;
+    /// Deletes the tag with the given name in a guild. Returns whether one existed.
+    async fn delete_by_name(&self, guild_id: u64, name: &str) -> Result<bool, DatabaseError>;
+}
+
+/// Operations for the `emoji_stats` table.
+#[async_trait]
+pub trait EmojiStatsRepository: CrudTable<EmojiStatEntity, i32> + Send + Sync {
+    /// Adds the model's use counts to the stored ones and takes its name and last use,
+    /// inserting the row if the guild has none for the emoji yet.
+    async fn increment_uses(&self, model: &EmojiStatEntity) -> Result<(), DatabaseError>;
+    /// Returns the number of emojis or stickers of a kind used in a guild.
+    async fn count_by_guild_id(&self, guild_id: u64, kind: EmojiKind)
+    -> Result<u32, DatabaseError>;
+    /// Returns a page of a guild's emojis or stickers of a kind, most used first.
+    async fn select_paginated_by_guild_id(
+        &self,
+        guild_id: u64,
+        kind: EmojiKind,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<EmojiStatEntity>, DatabaseError>;
+}
+
+/// Operations for the `invite_uses` table.
+#[async_trait]
+pub trait InviteUsesRepository: CrudTable<InviteUseEntity, i32> + Send + Sync {
+    /// Returns the number of members in a guild who invited anyone.
+    async fn count_inviters_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError>;
+    /// Returns a page of a guild's inviters, most distinct members invited first.
+    async fn select_leaderboard_by_guild_id(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<InviteLeaderboardEntry>, DatabaseError>;
+    /// Returns the number of distinct members who joined a guild through a member's invites.
+    async fn count_by_inviter_id(
+        &self,
+        guild_id: u64,
+        inviter_id: u64,
+    ) -> Result<u32, DatabaseError>;
+}
+
+/// Operations for the `pending_subscriptions` table.
+#[async_trait]
+pub trait PendingSubscriptionsRepository:
+    CrudTable<PendingSubscriptionEntity, i32> + Send + Sync
+{
+    /// Returns a guild's pending requests, oldest first.
+    async fn select_all_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<PendingSubscriptionEntity>, DatabaseError>;
+    /// Returns a guild's pending request for a feed, if any.
+    async fn select_by_guild_and_url(
+        &self,
+        guild_id: u64,
+        source_url: &str,
+    ) -> Result<Option<PendingSubscriptionEntity>, DatabaseError>;
+}
+
+/// Operations for the `feed_collections` table.
+#[async_trait]
+pub trait FeedCollectionsRepository: CrudTable<FeedCollectionEntity, i32> + Send + Sync {
+    /// Returns a guild's collections, ordered by name.
+    async fn select_all_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<FeedCollectionEntity>, DatabaseError>;
+    /// Returns a guild's collection by name, if any.
+    async fn select_by_guild_and_name(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<Option<FeedCollectionEntity>, DatabaseError>;
+}
+
+/// Operations for the `notification_log` table.
+#[async_trait]
+pub trait NotificationLogRepository: CrudTable<NotificationLogEntity, i32> + Send + Sync {
+    /// Counts the notifications delivered to a subscriber since `since`.
+    async fn count_by_subscriber_since(
+        &self,
+        subscriber_id: i32,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<i64, DatabaseError>;
+    /// Returns the feeds a subscriber received the most notifications from since
+    /// `since`, most first.
+    async fn select_top_feeds_by_subscriber_since(
+        &self,
+        subscriber_id: i32,
+        since: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<FeedNotificationCountRow>, DatabaseError>;
+    /// Counts the notifications delivered and failed per day since `since`, in UTC.
+    /// Days without any are left out.
+    async fn select_daily_counts_since(
+        &self,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<DailyDeliveryRow>, DatabaseError>;
+    /// Counts the notifications delivered and failed per subscriber type since `since`.
+    async fn select_counts_by_subscriber_type_since(
+        &self,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<SubscriberTypeDeliveryRow>, DatabaseError>;
+    /// Returns the platforms whose delivered notifications arrived the longest after
+    /// publication since `since`, by median delay, slowest first.
+    async fn select_slowest_platforms_since(
+        &self,
+        since: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<PlatformDelayRow>, DatabaseError>;
+}
+
+/// Operations for the `delivery_outbox` table.
+#[async_trait]
+pub trait DeliveryOutboxRepository: CrudTable<DeliveryOutboxEntity, i32> + Send + Sync {
+    /// Inserts many entries in one transaction. Returns the inserted entries.
+    async fn insert_many(
+        &self,
+        models: &[DeliveryOutboxEntity],
+    ) -> Result<Vec<DeliveryOutboxEntity>, DatabaseError>;
+    /// Deletes every entry and returns them, oldest first.
+    async fn take_all(&self) -> Result<Vec<DeliveryOutboxEntity>, DatabaseError>;
+}
+
+/// Operations for the `temp_voice_channels` table.
+#[async_trait]
+pub trait TempVoiceChannelsRepository:
+    CrudTable<TempVoiceChannelEntity, u64> + Send + Sync
+{
+    /// Returns every temporary channel of a guild.
+    async fn select_all_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<TempVoiceChannelEntity>, DatabaseError>;
+}
+
+/// Operations for the `channel_weights` table.
+#[async_trait]
+pub trait ChannelWeightsRepository: CrudTable<ChannelWeightEntity, i32> + Send + Sync {
+    /// Returns all channel weights for a guild.
+    async fn select_all_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ChannelWeightEntity>, DatabaseError>;
+    /// Replaces all channel weights for a guild.
+    async fn replace_all_by_guild_id(
+        &self,
+        guild_id: u64,
+        weights: &[ChannelWeightEntity],
+    ) -> Result<(), DatabaseError>;
+}
+
+/// Operations for the `leaderboard_snapshots` table.
+#[async_trait]
+pub trait LeaderboardSnapshotsRepository:
+    CrudTable<LeaderboardSnapshotEntity, i32> + Send + Sync
+{
+    /// Snapshots the all-time voice leaderboard of every guild. Returns the number of rows written.
+    async fn insert_snapshot(
+        &self,
+        taken_at: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError>;
+    /// Returns the most recent snapshot for a guild, ordered by rank.
+    async fn select_latest_by_guild_id(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<LeaderboardSnapshotEntity>, DatabaseError>;
+    /// Returns every snapshot for a guild taken at or after `since`, ordered by time then rank.
+    async fn select_by_guild_id_since(
+        &self,
+        guild_id: u64,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<LeaderboardSnapshotEntity>, DatabaseError>;
+}
+
+/// Operations for the `voice_session_flags` table.
+#[async_trait]
+pub trait VoiceSessionFlagsRepository:
+    CrudTable<VoiceSessionFlagEntity, i32> + Send + Sync
+{
+    /// Flags sessions lasting at least `min_secs` that were active at or after `since`,
+    /// counting all splits of a session across channel moves. The flag points at the
+    /// first split. Returns the number of new flags.
+    async fn flag_long_sessions(
+        &self,
+        min_secs: i64,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError>;
+    /// Flags closed sessions that ended at or after `since` whose join and leave times match
+    /// those of at least `min_accounts - 1` other accounts in the guild, within
+    /// `tolerance_secs`. Returns the number of new flags.
+    async fn flag_synced_sessions(
+        &self,
+        tolerance_secs: i32,
+        min_accounts: i32,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError>;
+    /// Counts a guild's flags that were not dismissed.
+    async fn count_open_by_guild_id(&self, guild_id: u64) -> Result<u32, DatabaseError>;
+    /// Returns a page of a guild's open flags with their sessions, newest first.
+    async fn select_open_by_guild_id(
+        &self,
+        guild_id: u64,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<VoiceSessionFlag>, DatabaseError>;
+    /// Keeps a flagged session and hides the flag. Returns whether the flag existed.
+    async fn dismiss(&self, guild_id: u64, id: i32) -> Result<bool, DatabaseError>;
+    /// Deletes the session a flag points at, together with its flags. Returns whether
+    /// the flag existed.
+    async fn delete_flagged_session(&self, guild_id: u64, id: i32) -> Result<bool, DatabaseError>;
+}
+
+/// Operations for the `voice_adjustments` table.
+#[async_trait]
+pub trait VoiceAdjustmentsRepository: CrudTable<VoiceAdjustmentEntity, i32> + Send + Sync {
+    /// Sums a member's adjustments in a guild, in seconds.
+    async fn sum_by_user(&self, guild_id: u64, user_id: u64) -> Result<i64, DatabaseError>;
+    /// Inserts many adjustments in one transaction. Returns the number inserted.
+    async fn insert_many(&self, models: &[VoiceAdjustmentEntity]) -> Result<u32, DatabaseError>;
+}
+
+/// Operations for the `voice_account_merges` table.
+#[async_trait]
+pub trait VoiceAccountMergesRepository:
+    CrudTable<VoiceAccountMergeEntity, i32> + Send + Sync
+{
+    /// Moves every voice session, adjustment, flag and leaderboard snapshot of
+    /// `from_user_id` to `to_user_id` across all guilds in one transaction, and records
+    /// the merge. Returns the recorded merge.
+    async fn merge_users(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        merged_by: u64,
+    ) -> Result<VoiceAccountMergeEntity, DatabaseError>;
+}
+
+/// Operations for the append-only `voice_events` table.
+#[async_trait]
+pub trait VoiceEventsRepository: CrudTable<VoiceEventEntity, i64> + Send + Sync {
+    /// Returns up to `limit` events recorded after `after_id`, oldest first.
+    async fn select_after(
+        &self,
+        after_id: i64,
+        limit: u32,
+    ) -> Result<Vec<VoiceEventEntity>, DatabaseError>;
+    /// Returns up to `limit` events of a guild with IDs in `after_id + 1..=until_id`,
+    /// oldest first.
+    async fn select_by_guild_id_between(
+        &self,
+        guild_id: u64,
+        after_id: i64,
+        until_id: i64,
+        limit: u32,
+    ) -> Result<Vec<VoiceEventEntity>, DatabaseError>;
+    /// Deletes events that occurred before `before` with IDs up to `until_id`, in one
+    /// guild or, with `None`, in every guild. Returns the number deleted.
+    async fn delete_before(
+        &self,
+        guild_id: Option<u64>,
+        before: &chrono::DateTime<chrono::Utc>,
+        until_id: i64,
+    ) -> Result<u32, DatabaseError>;
+}
+
 /// Operations for tracking voice channel activity.
 #[async_trait]
 pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send + Sync {
-    /// Generic leaderboard query with filters.
+    /// Generic leaderboard query with filters. Open sessions count up to their stored
+    /// leave_time.
     async fn get_leaderboard_opt(
         &self,
         opts: &VoiceLeaderboardOpt,
@@ -166,6 +596,25 @@ pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send +
         opts: &VoiceLeaderboardOpt,
         target_user_id: u64,
     ) -> Result<Vec<VoiceLeaderboardEntry>, DatabaseError>;
+    /// Returns the top users by weighted voice time summed over `guild_ids`, clipped to
+    /// `since` and `until`. Users count as named only when all their time is in
+    /// `named_guild_ids`.
+    async fn get_global_leaderboard(
+        &self,
+        guild_ids: &[u64],
+        named_guild_ids: &[u64],
+        since: &chrono::DateTime<chrono::Utc>,
+        until: &chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<GlobalVoiceLeaderboardEntry>, DatabaseError>;
+    /// Returns the voice time per channel in a guild between `since` and `until`, most
+    /// used first. Sessions are clipped to the range and channel weights are not applied.
+    async fn get_channel_usage(
+        &self,
+        guild_id: u64,
+        since: &chrono::DateTime<chrono::Utc>,
+        until: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<VoiceChannelUsage>, DatabaseError>;
     /// Updates the end time for an active voice session.
     async fn update_leave_time(
         &self,
@@ -174,6 +623,12 @@ pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send +
         join_time: &chrono::DateTime<chrono::Utc>,
         leave_time: &chrono::DateTime<chrono::Utc>,
     ) -> Result<(), DatabaseError>;
+    /// Sets the end time of every active session to `leave_time` in one statement.
+    /// Returns the updated sessions.
+    async fn update_active_leave_times(
+        &self,
+        leave_time: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<VoiceSessionsEntity>, DatabaseError>;
     /// Marks a session as closed.
     async fn close_session(
         &self,
@@ -184,6 +639,23 @@ pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send +
     ) -> Result<(), DatabaseError>;
     /// Returns all sessions currently marked as active.
     async fn find_active_sessions(&self) -> Result<Vec<VoiceSessionsEntity>, DatabaseError>;
+    /// Deletes a guild's sessions that started at or after `since`. Returns the number deleted.
+    async fn delete_by_guild_id_since(
+        &self,
+        guild_id: u64,
+        since: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError>;
+    /// Deletes sessions, with all their splits, that ended before `before`, in one guild
+    /// or, with `None`, in every guild. Sessions with a split still open or ending later
+    /// are kept. Returns the number of sessions deleted.
+    async fn delete_ended_before(
+        &self,
+        guild_id: Option<u64>,
+        before: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, DatabaseError>;
+    /// Recomputes the per-day totals of ended sessions from the sessions themselves,
+    /// dropping drift and days left at zero. Returns the number of totals written.
+    async fn rebuild_daily_totals(&self) -> Result<u32, DatabaseError>;
     /// Returns all active sessions for a specific user in a guild.
     async fn find_active_sessions_by_user(
         &self,
@@ -199,12 +671,15 @@ pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send +
         until: &chrono::DateTime<chrono::Utc>,
     ) -> Result<Vec<VoiceSessionsEntity>, DatabaseError>;
     /// Aggregates daily activity for a specific user.
+    ///
+    /// Days are bucketed in the given IANA timezone, like every `*_daily_*` query below.
     async fn get_user_daily_activity(
         &self,
         user_id: u64,
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<VoiceDailyActivity>, DatabaseError>;
     /// Aggregates daily total voice time for a guild.
     async fn get_guild_daily_total_time(
@@ -212,6 +687,7 @@ pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send +
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<GuildDailyStats>, DatabaseError>;
     /// Aggregates daily average voice time per user for a guild.
     async fn get_guild_daily_average_time(
@@ -219,6 +695,7 @@ pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send +
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<GuildDailyStats>, DatabaseError>;
     /// Aggregates daily unique user count in VCs for a guild.
     async fn get_guild_daily_user_count(
@@ -226,6 +703,7 @@ pub trait VoiceSessionsRepository: CrudTable<VoiceSessionsEntity, i32> + Send +
         guild_id: u64,
         since: &chrono::DateTime<chrono::Utc>,
         until: &chrono::DateTime<chrono::Utc>,
+        timezone: &str,
     ) -> Result<Vec<GuildDailyStats>, DatabaseError>;
 }
 
@@ -245,7 +723,27 @@ pub trait Repos: Send + Sync {
     fn feed_item(&self) -> Box<dyn FeedItemRepository + Send + Sync>;
     fn subscriber(&self) -> Box<dyn SubscriberRepository + Send + Sync>;
     fn feed_subscription(&self) -> Box<dyn FeedSubscriptionRepository + Send + Sync>;
+    fn pending_subscriptions(&self) -> Box<dyn PendingSubscriptionsRepository + Send + Sync>;
+    fn feed_collections(&self) -> Box<dyn FeedCollectionsRepository + Send + Sync>;
+    fn notification_log(&self) -> Box<dyn NotificationLogRepository + Send + Sync>;
+    fn delivery_outbox(&self) -> Box<dyn DeliveryOutboxRepository + Send + Sync>;
     fn server_settings(&self) -> Box<dyn ServerSettingsRepository + Send + Sync>;
+    fn settings_audit(&self) -> Box<dyn SettingsAuditRepository + Send + Sync>;
+    fn channel_weights(&self) -> Box<dyn ChannelWeightsRepository + Send + Sync>;
+    fn dashboard_tokens(&self) -> Box<dyn DashboardTokensRepository + Send + Sync>;
+    fn api_tokens(&self) -> Box<dyn ApiTokensRepository + Send + Sync>;
+    fn tags(&self) -> Box<dyn TagsRepository + Send + Sync>;
+    fn emoji_stats(&self) -> Box<dyn EmojiStatsRepository + Send + Sync>;
+    fn invite_uses(&self) -> Box<dyn InviteUsesRepository + Send + Sync>;
+    fn temp_voice_channels(&self) -> Box<dyn TempVoiceChannelsRepository + Send + Sync>;
+    fn custom_json_feeds(&self) -> Box<dyn CustomJsonFeedsRepository + Send + Sync>;
+    fn anilist_links(&self) -> Box<dyn AniListLinksRepository + Send + Sync>;
+    fn mal_links(&self) -> Box<dyn MalLinksRepository + Send + Sync>;
+    fn leaderboard_snapshots(&self) -> Box<dyn LeaderboardSnapshotsRepository + Send + Sync>;
+    fn voice_session_flags(&self) -> Box<dyn VoiceSessionFlagsRepository + Send + Sync>;
+    fn voice_adjustments(&self) -> Box<dyn VoiceAdjustmentsRepository + Send + Sync>;
+    fn voice_account_merges(&self) -> Box<dyn VoiceAccountMergesRepository + Send + Sync>;
+    fn voice_events(&self) -> Box<dyn VoiceEventsRepository + Send + Sync>;
     fn voice_sessions(&self) -> Box<dyn VoiceSessionsRepository + Send + Sync>;
     fn bot_meta(&self) -> Box<dyn BotMetaRepository + Send + Sync>;
 }
@@ -0,0 +1,504 @@
+//! Syncing AniList lists into feed subscriptions.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::Utc;
+use log::warn;
+
+use crate::entity::AniListLinkEntity;
+use crate::entity::Json;
+use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberType;
+use crate::feed::AniListPlatform;
+use crate::feed::Platform;
+use crate::feed::mal::MalListEntry;
+use crate::feed::platform::anilist::AniListListEntry;
+use crate::feed::platform::anilist::AniListMangaEntry;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::feed_subscription::SubscribeResult;
+use crate::service::feed_subscription::SubscriberTarget;
+use crate::service::title_match::TitleMatchService;
+use crate::service::traits::AniListSyncProvider;
+use crate::service::traits::FeedSubscriptionProvider;
+
+/// Hours between periodic syncs of a link.
+const RESYNC_HOURS: i64 = 12;
+
+#[async_trait::async_trait]
+impl AniListSyncProvider for AniListSyncService {
+    async fn link(
+        &self,
+        user_id: u64,
+        username: &str,
+        auto_sync: bool,
+    ) -> Result<AniListLinkEntity, ServiceError> {
+        self.link(user_id, username, auto_sync).await
+    }
+
+    async fn unlink(&self, user_id: u64) -> Result<bool, ServiceError> {
+        self.unlink(user_id).await
+    }
+
+    async fn get_link(&self, user_id: u64) -> Result<Option<AniListLinkEntity>, ServiceError> {
+        self.get_link(user_id).await
+    }
+
+    async fn sync(&self, user_id: u64) -> Result<AniListSyncResult, ServiceError> {
+        self.sync(user_id).await
+    }
+
+    async fn sync_due(&self, now: &DateTime<Utc>, limit: u32) -> Result<u32, ServiceError> {
+        self.sync_due(now, limit).await
+    }
+}
+
+/// Service linking AniList accounts and subscribing users to the anime they watch and
+/// the manga they read. AniList has no manga feeds, so manga are matched to MangaDex.
+pub struct AniListSyncService {
+    links: Arc<dyn AniListLinksRepository + Send + Sync>,
+    feeds: Arc<dyn FeedSubscriptionProvider>,
+    anilist: Arc<AniListPlatform>,
+    matcher: TitleMatchService,
+}
+
+impl AniListSyncService {
+    pub fn new(
+        links: Arc<dyn AniListLinksRepository + Send + Sync>,
+        feeds: Arc<dyn FeedSubscriptionProvider>,
+        anilist: Arc<AniListPlatform>,
+        matcher: TitleMatchService,
+    ) -> Self {
+        Self {
+            links,
+            feeds,
+            anilist,
+            matcher,
+        }
+    }
+
+    /// Links a user's AniList account, replacing an earlier link. Relinking the same
+    /// account keeps what earlier syncs subscribed to.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    /// * API calls: 1
+    pub async fn link(
+        &self,
+        user_id: u64,
+        username: &str,
+        auto_sync: bool,
+    ) -> Result<AniListLinkEntity, ServiceError> {
+        // API 1
+        let (anilist_user_id, username) = self
+            .anilist
+            .fetch_user(username.trim())
+            .await?
+            .ok_or_else(|| ServiceError::AniListUserNotFound {
+                username: username.trim().to_string(),
+            })?;
+
+        // DB 1
+        let link = match self.links.select(&user_id).await? {
+            Some(link) if link.anilist_user_id == anilist_user_id => AniListLinkEntity {
+                username,
+                auto_sync,
+                ..link
+            },
+            _ => AniListLinkEntity {
+                user_id: user_id.into(),
+                anilist_user_id,
+                username,
+                auto_sync,
+                created_at: Utc::now(),
+                ..Default::default()
+            },
+        };
+        // DB 2
+        self.links.replace(&link).await?;
+        Ok(link)
+    }
+
+    /// Removes a user's AniList link. Subscriptions made by earlier syncs are kept.
+    /// Returns whether a link existed.
+    ///
+    /// # Performance
+    /// * DB calls: 1 + 1?
+    pub async fn unlink(&self, user_id: u64) -> Result<bool, ServiceError> {
+        // DB 1
+        if self.links.select(&user_id).await?.is_none() {
+            return Ok(false);
+        }
+        // DB 1?
+        self.links.delete(&user_id).await?;
+        Ok(true)
+    }
+
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_link(&self, user_id: u64) -> Result<Option<AniListLinkEntity>, ServiceError> {
+        Ok(self.links.select(&user_id).await?)
+    }
+
+    /// Syncs a linked user's watching and reading lists into their DM subscriptions.
+    ///
+    /// Fails with [`ServiceError::AniListNotLinked`] if the user has no link.
+    ///
+    /// # Performance
+    /// * DB calls: 5 + 1? + 2 per change
+    /// * API calls: 2 + 1 per releasing manga without a known feed + 2? per new feed
+    pub async fn sync(&self, user_id: u64) -> Result<AniListSyncResult, ServiceError> {
+        // DB 1
+        let link = self
+            .links
+            .select(&user_id)
+            .await?
+            .ok_or(ServiceError::AniListNotLinked)?;
+        self.sync_link(link).await
+    }
+
+    /// Syncs up to `limit` links with auto sync on that were not synced in the last
+    /// [`RESYNC_HOURS`]. A failed sync is logged and retried on the next run. Returns the
+    /// number of links synced.
+    ///
+    /// # Performance
+    /// * DB calls: 1 + sync calls per link
+    pub async fn sync_due(&self, now: &DateTime<Utc>, limit: u32) -> Result<u32, ServiceError> {
+        let before = *now - chrono::Duration::hours(RESYNC_HOURS);
+        let mut synced = 0;
+        for link in self.links.select_due(&before, limit).await? {
+            let user_id = u64::from(link.user_id);
+            match self.sync_link(link).await {
+                Ok(_) => synced += 1,
+                Err(e) => warn!("Failed to sync AniList list of user {user_id}: {e}"),
+            }
+        }
+        Ok(synced)
+    }
+
+    async fn sync_link(
+        &self,
+        mut link: AniListLinkEntity,
+    ) -> Result<AniListSyncResult, ServiceError> {
+        // API 1
+        let mut entries = self.anilist.fetch_watching(link.anilist_user_id).await?;
+        // API 1
+        let reading = self.anilist.fetch_reading(link.anilist_user_id).await?;
+        let mut manga_urls = link.synced_manga_urls.0.clone();
+        // API 1 per releasing manga without a known feed
+        let unmatched = self.match_manga(&reading, &mut manga_urls).await;
+        entries.extend(
+            reading
+                .into_iter()
+                .filter(|manga| !unmatched.contains(&manga.media_id))
+                .map(|manga| AniListListEntry {
+                    media_id: manga.media_id,
+                    title: manga.title,
+                    airing: manga.releasing,
+                }),
+        );
+        // DB 2
+        let subscriber = self
+            .feeds
+            .get_or_create_subscriber(&SubscriberTarget {
+                subscriber_type: SubscriberType::Dm,
+                target_id: link.user_id.to_string(),
+            })
+            .await?;
+        // DB 2
+        let subscribed = self.subscribed_media_ids(&subscriber, &manga_urls).await?;
+
+        let plan = plan_sync(&link, &entries, &subscribed);
+        let mut result = AniListSyncResult {
+            already_subscribed: plan.already_subscribed,
+            finished: plan.finished,
+            ignored: plan.ignored.len() as u32,
+            unmatched: unmatched.len() as u32,
+            ..Default::default()
+        };
+        let mut synced = plan.keep_synced;
+        // AniList media IDs are unique across anime and manga
+        let url_of = |media_id: i32| {
+            manga_urls
+                .get(&media_id)
+                .cloned()
+                .unwrap_or_else(|| self.media_url(media_id))
+        };
+
+        for media_id in &plan.unsubscribe {
+            let url = url_of(*media_id);
+            // DB 1 per change
+            match self.feeds.unsubscribe(&url, &subscriber).await {
+                Ok(_) => result.unsubscribed += 1,
+                Err(e) => {
+                    warn!(
+                        "Failed to unsubscribe user {} from {url}: {e}",
+                        *link.user_id
+                    );
+                    synced.push(*media_id);
+                }
+            }
+        }
+
+        let mut subscribe = plan.subscribe.iter();
+        for entry in subscribe.by_ref() {
+            let url = url_of(entry.media_id);
+            // DB 2 + API 2? per new feed
+            match self.feeds.subscribe(&url, &subscriber).await {
+                Ok(SubscribeResult::Success { .. }) => {
+                    result.subscribed.push(entry.title.clone());
+                    synced.push(entry.media_id);
+                }
+                Ok(SubscribeResult::AlreadySubscribed { .. }) => result.already_subscribed += 1,
+                Err(ServiceError::SubscriptionLimitReached { .. }) => {
+                    result.over_limit += 1;
+                    break;
+                }
+                Err(e) => {
+                    warn!("Failed to subscribe user {} to {url}: {e}", *link.user_id);
+                    result.failed += 1;
+                }
+            }
+        }
+        result.over_limit += subscribe.count() as u32;
+
+        synced.sort_unstable();
+        manga_urls
+            .retain(|media_id, _| synced.contains(media_id) || plan.ignored.contains(media_id));
+        link.synced_manga_urls = Json(manga_urls);
+        link.synced_media_ids = Json(synced);
+        link.ignored_media_ids = Json(plan.ignored);
+        link.last_synced_at = Some(Utc::now());
+        // DB 1
+        self.links.update(&link).await?;
+        Ok(result)
+    }
+
+    /// Finds the MangaDex feed of each releasing manga without a known one, adding it to
+    /// `manga_urls`. Returns the media IDs of the manga not found.
+    ///
+    /// # Performance
+    /// * API calls: 1 per releasing manga without a known feed
+    async fn match_manga(
+        &self,
+        reading: &[AniListMangaEntry],
+        manga_urls: &mut BTreeMap<i32, String>,
+    ) -> HashSet<i32> {
+        let candidates: Vec<&AniListMangaEntry> = reading
+            .iter()
+            .filter(|manga| manga.releasing && !manga_urls.contains_key(&manga.media_id))
+            .collect();
+        if candidates.is_empty() {
+            return HashSet::new();
+        }
+        let entries: Vec<MalListEntry> = candidates
+            .iter()
+            .map(|manga| MalListEntry {
+                // Without a MyAnimeList ID only titles are compared
+                id: manga.mal_id.unwrap_or_default(),
+                title: manga.title.clone(),
+                alt_titles: manga.alt_titles.clone(),
+                ongoing: manga.releasing,
+            })
+            .collect();
+
+        let mut unmatched = HashSet::new();
+        // API 1 per entry
+        let matches = self.matcher.match_manga(&entries).await;
+        for (manga, matched) in candidates.iter().zip(matches) {
+            match matched.feed {
+                Some(feed) => {
+                    manga_urls.insert(manga.media_id, feed.source_url);
+                }
+                None => {
+                    unmatched.insert(manga.media_id);
+                }
+            }
+        }
+        unmatched
+    }
+
+    /// Returns the AniList media IDs of the subscriber's AniList feeds and of the manga
+    /// whose feed in `manga_urls` they are subscribed to.
+    async fn subscribed_media_ids(
+        &self,
+        subscriber: &SubscriberEntity,
+        manga_urls: &BTreeMap<i32, String>,
+    ) -> Result<HashSet<i32>, ServiceError> {
+        // DB 1
+        let count = self.feeds.get_subscription_count(subscriber).await?;
+        if count == 0 {
+            return Ok(HashSet::new());
+        }
+        // DB 1
+        let subscriptions = self
+            .feeds
+            .list_paginated_subscriptions(subscriber, 1, count)
+            .await?;
+        let urls: HashSet<&str> = subscriptions
+            .iter()
+            .map(|subscription| subscription.feed.source_url.as_str())
+            .collect();
+        Ok(subscriptions
+            .iter()
+            .filter(|subscription| subscription.feed.platform_id == self.anilist.get_id())
+            .filter_map(|subscription| subscription.feed.source_id.parse().ok())
+            .chain(
+                manga_urls
+                    .iter()
+                    .filter(|(_, url)| urls.contains(url.as_str()))
+                    .map(|(media_id, _)| *media_id),
+            )
+            .collect())
+    }
+
+    fn media_url(&self, media_id: i32) -> String {
+        self.anilist.get_source_url_from_id(&media_id.to_string())
+    }
+}
+
+/// Outcome of syncing a user's AniList list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AniListSyncResult {
+    /// Titles of the anime and manga newly subscribed to.
+    pub subscribed: Vec<String>,
+    /// Subscriptions removed because their anime or manga left the list.
+    pub unsubscribed: u32,
+    /// Entries the user was already subscribed to outside the sync.
+    pub already_subscribed: u32,
+    /// Entries skipped because the anime finished airing or the manga finished.
+    pub finished: u32,
+    /// Entries skipped because the user unsubscribed from them after an earlier sync.
+    pub ignored: u32,
+    /// Entries not subscribed to because the subscription limit was reached.
+    pub over_limit: u32,
+    /// Entries whose feed could not be created.
+    pub failed: u32,
+    /// Releasing manga not found on MangaDex.
+    pub unmatched: u32,
+}
+
+/// Changes a sync makes to a user's subscriptions.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SyncPlan<'a> {
+    subscribe: Vec<&'a AniListListEntry>,
+    unsubscribe: Vec<i32>,
+    /// Synced media IDs that stay subscribed.
+    keep_synced: Vec<i32>,
+    /// Media IDs not to subscribe to again while they stay on the list.
+    ignored: Vec<i32>,
+    already_subscribed: u32,
+    finished: u32,
+}
+
+/// Works out how to bring `subscribed`, the user's subscribed AniList media IDs, in line
+/// with their list.
+///
+/// Only entries an earlier sync subscribed to are unsubscribed when they leave the list;
+/// subscriptions the user made themselves are left alone. A synced entry the user
+/// unsubscribed from is ignored until it leaves the list or they subscribe again.
+fn plan_sync<'a>(
+    link: &AniListLinkEntity,
+    entries: &'a [AniListListEntry],
+    subscribed: &HashSet<i32>,
+) -> SyncPlan<'a> {
+    let listed: HashSet<i32> = entries.iter().map(|entry| entry.media_id).collect();
+    let mut plan = SyncPlan::default();
+
+    for media_id in &link.synced_media_ids.0 {
+        match (listed.contains(media_id), subscribed.contains(media_id)) {
+            (true, true) => plan.keep_synced.push(*media_id),
+            (true, false) => plan.ignored.push(*media_id),
+            (false, true) => plan.unsubscribe.push(*media_id),
+            (false, false) => {}
+        }
+    }
+    for media_id in &link.ignored_media_ids.0 {
+        if listed.contains(media_id)
+            && !subscribed.contains(media_id)
+            && !plan.ignored.contains(media_id)
+        {
+            plan.ignored.push(*media_id);
+        }
+    }
+
+    for entry in entries {
+        let media_id = entry.media_id;
+        if plan.keep_synced.contains(&media_id) || plan.ignored.contains(&media_id) {
+            continue;
+        }
+        if subscribed.contains(&media_id) {
+            plan.already_subscribed += 1;
+        } else if !entry.airing {
+            plan.finished += 1;
+        } else {
+            plan.subscribe.push(entry);
+        }
+    }
+    plan.ignored.sort_unstable();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(media_id: i32, airing: bool) -> AniListListEntry {
+        AniListListEntry {
+            media_id,
+            title: format!("Anime {media_id}"),
+            airing,
+        }
+    }
+
+    fn link(synced: Vec<i32>, ignored: Vec<i32>) -> AniListLinkEntity {
+        AniListLinkEntity {
+            synced_media_ids: Json(synced),
+            ignored_media_ids: Json(ignored),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plan_sync_subscribes_airing_entries_and_leaves_manual_ones() {
+        let entries = vec![entry(1, true), entry(2, false), entry(3, true)];
+        let subscribed = HashSet::from([3, 9]);
+
+        let plan = plan_sync(&link(vec![], vec![]), &entries, &subscribed);
+
+        assert_eq!(plan.subscribe, vec![&entries[0]]);
+        assert_eq!(plan.finished, 1);
+        assert_eq!(plan.already_subscribed, 1);
+        // Subscribed by hand, not on the list
+        assert!(plan.unsubscribe.is_empty());
+    }
+
+    #[test]
+    fn plan_sync_only_unsubscribes_what_it_subscribed() {
+        let entries = vec![entry(1, true)];
+        let subscribed = HashSet::from([1, 2, 3]);
+
+        let plan = plan_sync(&link(vec![1, 2], vec![]), &entries, &subscribed);
+
+        assert_eq!(plan.keep_synced, vec![1]);
+        assert_eq!(plan.unsubscribe, vec![2]);
+        assert!(plan.subscribe.is_empty());
+    }
+
+    #[test]
+    fn plan_sync_ignores_entries_unsubscribed_after_a_sync() {
+        let entries = vec![entry(1, true), entry(2, true), entry(3, true)];
+        // 1 was unsubscribed by hand, 2 was ignored before and resubscribed by hand,
+        // 4 was ignored and left the list
+        let subscribed = HashSet::from([2]);
+
+        let plan = plan_sync(&link(vec![1], vec![2, 4]), &entries, &subscribed);
+
+        assert_eq!(plan.ignored, vec![1]);
+        assert_eq!(plan.already_subscribed, 1);
+        assert_eq!(plan.subscribe, vec![&entries[2]]);
+    }
+}
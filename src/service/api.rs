@@ -0,0 +1,173 @@
+//! Bearer token management for the REST API.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::entity::ApiTokenEntity;
+use crate::entity::DbU64;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::new_token;
+use crate::service::traits::ApiTokenProvider;
+
+/// What an API token grants access to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiScope {
+    /// The bot owner's token, valid for every guild.
+    Owner,
+    /// A token valid for a single guild.
+    Guild(u64),
+}
+
+impl ApiScope {
+    /// Returns whether this scope may access the given guild.
+    pub fn allows(&self, guild_id: u64) -> bool {
+        match self {
+            ApiScope::Owner => true,
+            ApiScope::Guild(id) => *id == guild_id,
+        }
+    }
+
+    /// Returns the guild ID stored for this scope, `None` for the owner.
+    pub fn guild_id(&self) -> Option<u64> {
+        match self {
+            ApiScope::Owner => None,
+            ApiScope::Guild(id) => Some(*id),
+        }
+    }
+
+    fn from_guild_id(guild_id: Option<u64>) -> Self {
+        guild_id.map_or(ApiScope::Owner, ApiScope::Guild)
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiTokenProvider for ApiTokenService {
+    async fn get_api_token(&self, scope: ApiScope) -> Result<Option<ApiTokenEntity>, ServiceError> {
+        self.get_api_token(scope).await
+    }
+
+    async fn generate_api_token(
+        &self,
+        scope: ApiScope,
+        created_by: u64,
+    ) -> Result<(ApiTokenEntity, String), ServiceError> {
+        self.generate_api_token(scope, created_by).await
+    }
+
+    async fn revoke_api_token(&self, scope: ApiScope) -> Result<bool, ServiceError> {
+        self.revoke_api_token(scope).await
+    }
+
+    async fn authorize(&self, token: &str) -> Result<Option<ApiScope>, ServiceError> {
+        self.authorize(token).await
+    }
+}
+
+/// Service issuing REST API bearer tokens.
+///
+/// Each guild has at most one token, and there is a single owner token.
+/// Generating a new token invalidates the previous one of the same scope.
+/// Only a hash of each token is stored, so a token is shown once, when generated.
+pub struct ApiTokenService {
+    tokens: Arc<dyn ApiTokensRepository + Send + Sync>,
+}
+
+impl ApiTokenService {
+    /// Creates a new API token service.
+    pub fn new(tokens: Arc<dyn ApiTokensRepository + Send + Sync>) -> Self {
+        Self { tokens }
+    }
+
+    /// Returns the current token of a scope, if any.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_api_token(
+        &self,
+        scope: ApiScope,
+    ) -> Result<Option<ApiTokenEntity>, ServiceError> {
+        Ok(self.tokens.select_by_guild_id(scope.guild_id()).await?)
+    }
+
+    /// Generates a new token for a scope, replacing any existing one. Returns the
+    /// stored token and the token itself.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn generate_api_token(
+        &self,
+        scope: ApiScope,
+        created_by: u64,
+    ) -> Result<(ApiTokenEntity, String), ServiceError> {
+        let token = new_token();
+        let mut entity = ApiTokenEntity {
+            guild_id: scope.guild_id().map(DbU64::from),
+            token_hash: hash_token(&token),
+            created_by: Some(DbU64::from(created_by)),
+            created_at: Utc::now(),
+            ..Default::default()
+        };
+
+        // DB 2
+        entity.id = self.tokens.replace(&entity).await?;
+        Ok((entity, token))
+    }
+
+    /// Revokes the token of a scope. Returns whether one existed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn revoke_api_token(&self, scope: ApiScope) -> Result<bool, ServiceError> {
+        Ok(self.tokens.delete_by_guild_id(scope.guild_id()).await?)
+    }
+
+    /// Resolves a bearer token to the scope it grants.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn authorize(&self, token: &str) -> Result<Option<ApiScope>, ServiceError> {
+        Ok(self
+            .tokens
+            .select_by_token_hash(&hash_token(token))
+            .await?
+            .map(|t| ApiScope::from_guild_id(t.guild_id.map(|id| *id))))
+    }
+}
+
+/// Hex-encoded SHA-256 of a token, as stored.
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_scope_allows_every_guild() {
+        assert!(ApiScope::Owner.allows(1));
+        assert!(ApiScope::Owner.allows(2));
+        assert_eq!(ApiScope::Owner.guild_id(), None);
+    }
+
+    #[test]
+    fn guild_scope_allows_only_its_guild() {
+        let scope = ApiScope::Guild(1);
+        assert!(scope.allows(1));
+        assert!(!scope.allows(2));
+        assert_eq!(ApiScope::from_guild_id(scope.guild_id()), scope);
+    }
+
+    #[test]
+    fn hash_token_is_hex_sha256() {
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(hash_token(&new_token()), hash_token(&new_token()));
+    }
+}
@@ -0,0 +1,124 @@
+//! Owner-defined JSON API feeds.
+
+use std::sync::Arc;
+
+use log::warn;
+
+use crate::entity::CustomJsonFeedEntity;
+use crate::feed::CustomJsonPlatform;
+use crate::feed::error::FeedError;
+use crate::feed::error::UrlParseError;
+use crate::feed::platform::custom_json::JsonFeedMapping;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::CustomFeedProvider;
+
+#[async_trait::async_trait]
+impl CustomFeedProvider for CustomFeedService {
+    async fn set_custom_feed(
+        &self,
+        feed: CustomJsonFeedEntity,
+    ) -> Result<CustomJsonFeedEntity, ServiceError> {
+        self.set_custom_feed(feed).await
+    }
+
+    async fn remove_custom_feed(&self, url: &str) -> Result<bool, ServiceError> {
+        self.remove_custom_feed(url).await
+    }
+
+    async fn list_custom_feeds(&self) -> Result<Vec<CustomJsonFeedEntity>, ServiceError> {
+        self.list_custom_feeds().await
+    }
+}
+
+/// Service persisting custom JSON feeds and keeping [`CustomJsonPlatform`] in sync.
+pub struct CustomFeedService {
+    custom_json_feeds: Arc<dyn CustomJsonFeedsRepository + Send + Sync>,
+    platform: Arc<CustomJsonPlatform>,
+}
+
+impl CustomFeedService {
+    /// Creates the service and registers every stored feed with the platform.
+    ///
+    /// Feeds whose mappings no longer parse are skipped with a warning.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn new(
+        custom_json_feeds: Arc<dyn CustomJsonFeedsRepository + Send + Sync>,
+        platform: Arc<CustomJsonPlatform>,
+    ) -> Result<Self, ServiceError> {
+        // DB 1
+        for feed in custom_json_feeds.select_all().await? {
+            match mapping_of(&feed) {
+                Ok(mapping) => platform.set_feed(&feed.url, mapping),
+                Err(e) => warn!("Skipping custom feed `{}`: {e}", feed.url),
+            }
+        }
+        Ok(Self {
+            custom_json_feeds,
+            platform,
+        })
+    }
+
+    /// Adds a custom feed, or replaces the one polling the same url.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn set_custom_feed(
+        &self,
+        mut feed: CustomJsonFeedEntity,
+    ) -> Result<CustomJsonFeedEntity, ServiceError> {
+        feed.url = feed.url.trim().to_string();
+        if !(feed.url.starts_with("https://") || feed.url.starts_with("http://")) {
+            return Err(FeedError::from(UrlParseError::InvalidFormat { url: feed.url }).into());
+        }
+        let mapping = mapping_of(&feed)?;
+
+        // DB 1
+        let id = self.custom_json_feeds.replace(&feed).await?;
+        // DB 2
+        let feed = self.custom_json_feeds.select(&id).await?.ok_or_else(|| {
+            ServiceError::UnexpectedResult {
+                message: format!("Custom feed {id} vanished after saving"),
+            }
+        })?;
+        self.platform.set_feed(&feed.url, mapping);
+        Ok(feed)
+    }
+
+    /// Removes a custom feed. Returns whether it existed.
+    ///
+    /// Existing subscriptions stay, but stop receiving updates.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn remove_custom_feed(&self, url: &str) -> Result<bool, ServiceError> {
+        let url = url.trim();
+        // DB 1
+        let removed = self.custom_json_feeds.delete_by_url(url).await?;
+        self.platform.remove_feed(url);
+        Ok(removed)
+    }
+
+    /// Returns every custom feed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn list_custom_feeds(&self) -> Result<Vec<CustomJsonFeedEntity>, ServiceError> {
+        // DB 1
+        Ok(self.custom_json_feeds.select_all().await?)
+    }
+}
+
+/// Parses the JSONPath expressions of a stored feed.
+fn mapping_of(feed: &CustomJsonFeedEntity) -> Result<JsonFeedMapping, FeedError> {
+    JsonFeedMapping::parse(
+        &feed.name,
+        &feed.items_path,
+        &feed.id_path,
+        &feed.title_path,
+        feed.url_path.as_deref(),
+        feed.published_path.as_deref(),
+    )
+}
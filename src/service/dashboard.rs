@@ -0,0 +1,102 @@
+//! Access token management for the read-only web dashboard.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::entity::DashboardTokenEntity;
+use crate::entity::DbU64;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::new_token;
+use crate::service::traits::DashboardProvider;
+
+#[async_trait::async_trait]
+impl DashboardProvider for DashboardService {
+    async fn get_token(&self, guild_id: u64) -> Result<Option<DashboardTokenEntity>, ServiceError> {
+        self.get_token(guild_id).await
+    }
+
+    async fn generate_token(
+        &self,
+        guild_id: u64,
+        created_by: u64,
+    ) -> Result<DashboardTokenEntity, ServiceError> {
+        self.generate_token(guild_id, created_by).await
+    }
+
+    async fn revoke_token(&self, guild_id: u64) -> Result<bool, ServiceError> {
+        self.revoke_token(guild_id).await
+    }
+
+    async fn resolve_token(&self, token: &str) -> Result<Option<u64>, ServiceError> {
+        self.resolve_token(token).await
+    }
+}
+
+/// Service issuing guild-scoped dashboard access tokens.
+///
+/// Each guild has at most one token. Generating a new one invalidates the old.
+pub struct DashboardService {
+    tokens: Arc<dyn DashboardTokensRepository + Send + Sync>,
+}
+
+impl DashboardService {
+    /// Creates a new dashboard service.
+    pub fn new(tokens: Arc<dyn DashboardTokensRepository + Send + Sync>) -> Self {
+        Self { tokens }
+    }
+
+    /// Returns the current dashboard token of a guild, if any.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_token(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<DashboardTokenEntity>, ServiceError> {
+        Ok(self.tokens.select_by_guild_id(guild_id).await?)
+    }
+
+    /// Generates a new dashboard token for a guild, replacing any existing one.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn generate_token(
+        &self,
+        guild_id: u64,
+        created_by: u64,
+    ) -> Result<DashboardTokenEntity, ServiceError> {
+        let mut token = DashboardTokenEntity {
+            guild_id: DbU64::from(guild_id),
+            token: new_token(),
+            created_by: Some(DbU64::from(created_by)),
+            created_at: Utc::now(),
+            ..Default::default()
+        };
+
+        // DB 1
+        token.id = self.tokens.replace(&token).await?;
+        Ok(token)
+    }
+
+    /// Revokes a guild's dashboard token. Returns whether one existed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn revoke_token(&self, guild_id: u64) -> Result<bool, ServiceError> {
+        Ok(self.tokens.delete_by_guild_id(guild_id).await?)
+    }
+
+    /// Resolves a token to the guild it grants access to.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn resolve_token(&self, token: &str) -> Result<Option<u64>, ServiceError> {
+        Ok(self
+            .tokens
+            .select_by_token(token)
+            .await?
+            .map(|t| *t.guild_id))
+    }
+}
@@ -0,0 +1,165 @@
+//! Per-guild usage counts of custom emojis and stickers.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::entity::DbU64;
+use crate::entity::EmojiKind;
+use crate::entity::EmojiStatEntity;
+use crate::entity::EmojiUse;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::EmojiStatsProvider;
+
+#[async_trait::async_trait]
+impl EmojiStatsProvider for EmojiStatsService {
+    async fn record_uses(&self, guild_id: u64, uses: &[EmojiUse]) -> Result<(), ServiceError> {
+        self.record_uses(guild_id, uses).await
+    }
+
+    async fn count_emoji_stats(&self, guild_id: u64, kind: EmojiKind) -> Result<u32, ServiceError> {
+        self.count_emoji_stats(guild_id, kind).await
+    }
+
+    async fn list_emoji_stats(
+        &self,
+        guild_id: u64,
+        kind: EmojiKind,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<EmojiStatEntity>, ServiceError> {
+        self.list_emoji_stats(guild_id, kind, page, per_page).await
+    }
+}
+
+/// Service counting how often a guild's custom emojis and stickers are used.
+pub struct EmojiStatsService {
+    emoji_stats: Arc<dyn EmojiStatsRepository + Send + Sync>,
+}
+
+impl EmojiStatsService {
+    /// Creates a new emoji stats service.
+    pub fn new(emoji_stats: Arc<dyn EmojiStatsRepository + Send + Sync>) -> Self {
+        Self { emoji_stats }
+    }
+
+    /// Counts one use of each emoji or sticker.
+    ///
+    /// # Performance
+    /// * DB calls: 1 per use
+    pub async fn record_uses(&self, guild_id: u64, uses: &[EmojiUse]) -> Result<(), ServiceError> {
+        let now = Utc::now();
+        for emoji in uses {
+            let stat = EmojiStatEntity {
+                guild_id: DbU64::from(guild_id),
+                emoji_id: DbU64::from(emoji.emoji_id),
+                kind: emoji.kind,
+                name: emoji.name.clone(),
+                animated: emoji.animated,
+                message_uses: i32::from(!emoji.reaction),
+                reaction_uses: i32::from(emoji.reaction),
+                last_used_at: now,
+                ..Default::default()
+            };
+            // DB 1 per use
+            self.emoji_stats.increment_uses(&stat).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of emojis or stickers of a kind used in a guild.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn count_emoji_stats(
+        &self,
+        guild_id: u64,
+        kind: EmojiKind,
+    ) -> Result<u32, ServiceError> {
+        Ok(self.emoji_stats.count_by_guild_id(guild_id, kind).await?)
+    }
+
+    /// Returns a page of a guild's emojis or stickers of a kind, most used first.
+    /// Pages start at 1.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn list_emoji_stats(
+        &self,
+        guild_id: u64,
+        kind: EmojiKind,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<EmojiStatEntity>, ServiceError> {
+        Ok(self
+            .emoji_stats
+            .select_paginated_by_guild_id(guild_id, kind, page.saturating_sub(1), per_page)
+            .await?)
+    }
+}
+
+/// Returns the custom emojis in a message's content, each once, in order of appearance.
+///
+/// Custom emojis are written as `<:name:id>`, or `<a:name:id>` when animated.
+pub fn parse_custom_emojis(content: &str) -> Vec<EmojiUse> {
+    let mut uses: Vec<EmojiUse> = Vec::new();
+    for (start, _) in content.match_indices('<') {
+        let Some(end) = content[start..].find('>') else {
+            break;
+        };
+        let Some(emoji) = parse_custom_emoji(&content[start + 1..start + end]) else {
+            continue;
+        };
+        if !uses.iter().any(|e| e.emoji_id == emoji.emoji_id) {
+            uses.push(emoji);
+        }
+    }
+    uses
+}
+
+/// Parses the inside of a custom emoji mention, e.g. `a:name:id`.
+fn parse_custom_emoji(mention: &str) -> Option<EmojiUse> {
+    let mut parts = mention.split(':');
+    let animated = match parts.next()? {
+        "" => false,
+        "a" => true,
+        _ => return None,
+    };
+    let name = parts.next()?;
+    let emoji_id = parts.next()?.parse::<u64>().ok()?;
+    if name.is_empty() || parts.next().is_some() {
+        return None;
+    }
+    Some(EmojiUse {
+        emoji_id,
+        kind: EmojiKind::Emoji,
+        name: name.to_string(),
+        animated,
+        reaction: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_custom_emojis_counts_each_emoji_once() {
+        let uses = parse_custom_emojis("hi <:wave:123> <a:party:456> again <:wave:123>");
+
+        let parsed: Vec<_> = uses
+            .iter()
+            .map(|e| (e.emoji_id, e.name.as_str(), e.animated))
+            .collect();
+        assert_eq!(parsed, vec![(123, "wave", false), (456, "party", true)]);
+        assert!(uses.iter().all(|e| !e.reaction));
+    }
+
+    #[test]
+    fn parse_custom_emojis_skips_other_mentions() {
+        let uses = parse_custom_emojis("<@123> <#456> <t:1700000000:R> <:broken:abc> a < b");
+
+        assert!(uses.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! Service-level error types.
 
+use crate::error::ErrorCategory;
 use crate::feed::error::FeedError;
 use crate::repo::error::DatabaseError;
 
@@ -10,9 +11,86 @@ pub enum ServiceError {
     #[error("Unexpected result: {message}")]
     UnexpectedResult { message: String },
 
+    #[error(
+        "Subscription limit of {limit} feeds reached. Unsubscribe from a feed first, or ask the bot owner for a higher quota."
+    )]
+    SubscriptionLimitReached { limit: u32 },
+
+    #[error("No tag named `{name}` exists in this server.")]
+    TagNotFound { name: String },
+
+    #[error("A tag named `{name}` already exists in this server.")]
+    TagAlreadyExists { name: String },
+
+    #[error("Tag limit of {limit} reached. Remove an unused tag first.")]
+    TagLimitReached { limit: u32 },
+
+    #[error("Invalid tag: {0}")]
+    InvalidTag(String),
+
+    #[error("No feed collection named `{name}` exists in this server.")]
+    CollectionNotFound { name: String },
+
+    #[error("Collection limit of {limit} reached. Remove an unused collection first.")]
+    CollectionLimitReached { limit: usize },
+
+    #[error("Invalid collection: {0}")]
+    InvalidCollection(String),
+
+    #[error("Cannot merge voice data: {0}")]
+    InvalidAccountMerge(String),
+
+    #[error("Cannot import voice time: {0}")]
+    InvalidVoiceImport(String),
+
+    #[error("No AniList user named `{username}` exists.")]
+    AniListUserNotFound { username: String },
+
+    #[error("No AniList account is linked. Link one with `/feed link-anilist` first.")]
+    AniListNotLinked,
+
+    #[error("MyAnimeList linking is not set up on this bot.")]
+    MalNotConfigured,
+
+    #[error("No MyAnimeList account is linked. Link one with `/feed link-mal` first.")]
+    MalNotLinked,
+
+    #[error("This link request expired or was already used. Run `/feed link-mal` again.")]
+    MalLinkRequestExpired,
+
+    #[error("MyAnimeList no longer accepts the link. Run `/feed link-mal` again.")]
+    MalLinkRevoked,
+
     #[error(transparent)]
     FeedError(#[from] FeedError),
 
     #[error(transparent)]
     DatabaseError(#[from] DatabaseError),
 }
+
+impl ServiceError {
+    /// What kind of failure this is.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::UnexpectedResult { .. } => ErrorCategory::Internal,
+            Self::MalNotConfigured => ErrorCategory::Configuration,
+            Self::FeedError(e) => e.category(),
+            Self::DatabaseError(e) => e.category(),
+            Self::SubscriptionLimitReached { .. }
+            | Self::TagNotFound { .. }
+            | Self::TagAlreadyExists { .. }
+            | Self::TagLimitReached { .. }
+            | Self::InvalidTag(_)
+            | Self::CollectionNotFound { .. }
+            | Self::CollectionLimitReached { .. }
+            | Self::InvalidCollection(_)
+            | Self::InvalidAccountMerge(_)
+            | Self::InvalidVoiceImport(_)
+            | Self::AniListUserNotFound { .. }
+            | Self::AniListNotLinked
+            | Self::MalNotLinked
+            | Self::MalLinkRequestExpired
+            | Self::MalLinkRevoked => ErrorCategory::User,
+        }
+    }
+}
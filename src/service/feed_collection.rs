@@ -0,0 +1,214 @@
+//! Per-guild feed collections: named groups of server subscriptions with their own
+//! channel and mention role.
+
+use std::sync::Arc;
+
+use crate::entity::DbU64;
+use crate::entity::FeedCollectionEntity;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::FeedCollectionProvider;
+
+/// Longest accepted collection name, in characters.
+pub const MAX_COLLECTION_NAME_LEN: usize = 32;
+
+/// Maximum number of collections a guild can define. Matches the number of choices
+/// Discord shows in an autocomplete.
+pub const MAX_COLLECTIONS_PER_GUILD: usize = 25;
+
+#[async_trait::async_trait]
+impl FeedCollectionProvider for FeedCollectionService {
+    async fn set_collection(
+        &self,
+        guild_id: u64,
+        name: &str,
+        channel_id: u64,
+        mention_role_id: Option<u64>,
+    ) -> Result<FeedCollectionEntity, ServiceError> {
+        self.set_collection(guild_id, name, channel_id, mention_role_id)
+            .await
+    }
+
+    async fn delete_collection(&self, guild_id: u64, name: &str) -> Result<bool, ServiceError> {
+        self.delete_collection(guild_id, name).await
+    }
+
+    async fn list_collections(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<FeedCollectionEntity>, ServiceError> {
+        self.list_collections(guild_id).await
+    }
+
+    async fn get_collection(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<FeedCollectionEntity, ServiceError> {
+        self.get_collection(guild_id, name).await
+    }
+
+    async fn collection_for(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+    ) -> Result<Option<FeedCollectionEntity>, ServiceError> {
+        self.collection_for(feed_id, subscriber_id).await
+    }
+}
+
+/// Service managing per-guild feed collections.
+pub struct FeedCollectionService {
+    collections: Arc<dyn FeedCollectionsRepository + Send + Sync>,
+    feed_subscription: Arc<dyn FeedSubscriptionRepository + Send + Sync>,
+}
+
+impl FeedCollectionService {
+    /// Creates a new feed collection service.
+    pub fn new(
+        collections: Arc<dyn FeedCollectionsRepository + Send + Sync>,
+        feed_subscription: Arc<dyn FeedSubscriptionRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            collections,
+            feed_subscription,
+        }
+    }
+
+    /// Creates a collection, or updates the channel and role of an existing one.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn set_collection(
+        &self,
+        guild_id: u64,
+        name: &str,
+        channel_id: u64,
+        mention_role_id: Option<u64>,
+    ) -> Result<FeedCollectionEntity, ServiceError> {
+        let name = validate_collection_name(name)?;
+
+        // DB 1
+        let existing = self.collections.select_all_by_guild_id(guild_id).await?;
+        if existing.len() >= MAX_COLLECTIONS_PER_GUILD
+            && !existing.iter().any(|collection| collection.name == name)
+        {
+            return Err(ServiceError::CollectionLimitReached {
+                limit: MAX_COLLECTIONS_PER_GUILD,
+            });
+        }
+
+        let mut collection = FeedCollectionEntity {
+            guild_id: DbU64::from(guild_id),
+            name,
+            channel_id: DbU64::from(channel_id),
+            mention_role_id: mention_role_id.map(DbU64::from),
+            ..Default::default()
+        };
+        // DB 2
+        collection.id = self.collections.replace(&collection).await?;
+        Ok(collection)
+    }
+
+    /// Deletes a collection. Returns whether it existed.
+    ///
+    /// Its subscriptions are kept and posted in the server's feed channel again.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn delete_collection(&self, guild_id: u64, name: &str) -> Result<bool, ServiceError> {
+        let name = validate_collection_name(name)?;
+        // DB 1
+        let Some(collection) = self
+            .collections
+            .select_by_guild_and_name(guild_id, &name)
+            .await?
+        else {
+            return Ok(false);
+        };
+        // DB 2
+        self.collections.delete(&collection.id).await?;
+        Ok(true)
+    }
+
+    /// Returns a guild's collections, ordered by name.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn list_collections(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<FeedCollectionEntity>, ServiceError> {
+        Ok(self.collections.select_all_by_guild_id(guild_id).await?)
+    }
+
+    /// Looks up a collection by name, failing with [`ServiceError::CollectionNotFound`]
+    /// if missing.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_collection(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<FeedCollectionEntity, ServiceError> {
+        let name = validate_collection_name(name)?;
+        self.collections
+            .select_by_guild_and_name(guild_id, &name)
+            .await?
+            .ok_or(ServiceError::CollectionNotFound { name })
+    }
+
+    /// Returns the collection a subscription is posted in, if any.
+    ///
+    /// # Performance
+    /// * DB calls: 1 + 1?
+    pub async fn collection_for(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+    ) -> Result<Option<FeedCollectionEntity>, ServiceError> {
+        // DB 1
+        let Some(collection_id) = self
+            .feed_subscription
+            .select_by_feed_and_subscriber(feed_id, subscriber_id)
+            .await?
+            .and_then(|subscription| subscription.collection_id)
+        else {
+            return Ok(None);
+        };
+        // DB 1?
+        Ok(self.collections.select(&collection_id).await?)
+    }
+}
+
+/// Trims a collection name and checks it is not empty or too long.
+fn validate_collection_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::InvalidCollection(
+            "the name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_COLLECTION_NAME_LEN {
+        return Err(ServiceError::InvalidCollection(format!(
+            "the name cannot be longer than {MAX_COLLECTION_NAME_LEN} characters"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_collection_name_trims_and_rejects_invalid_names() {
+        assert_eq!(
+            validate_collection_name(" Dev tools ").unwrap(),
+            "Dev tools"
+        );
+        assert!(validate_collection_name("   ").is_err());
+        assert!(validate_collection_name(&"a".repeat(MAX_COLLECTION_NAME_LEN + 1)).is_err());
+    }
+}
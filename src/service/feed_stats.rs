@@ -0,0 +1,372 @@
+//! Delivery log of feed notifications, with per-subscriber stats and the bot-wide
+//! delivery analytics built on it, and the outbox of notifications not sent yet.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::TimeZone;
+use chrono::Utc;
+
+use crate::entity::DailyDeliveryRow;
+use crate::entity::DeliveryOutboxEntity;
+use crate::entity::FeedNotificationCountRow;
+use crate::entity::Json;
+use crate::entity::NotificationLogEntity;
+use crate::entity::PlatformDelayRow;
+use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberTypeDeliveryRow;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::FeedStatsProvider;
+
+/// Number of feeds listed as a subscriber's most active.
+pub const TOP_FEEDS: u32 = 3;
+
+/// Days the most active feeds are counted over.
+const TOP_FEEDS_DAYS: i64 = 30;
+
+/// Weeks the weekly average is taken over.
+const AVERAGE_WEEKS: i64 = 4;
+
+/// Number of platforms listed as the slowest.
+pub const SLOWEST_PLATFORMS: u32 = 5;
+
+#[async_trait::async_trait]
+impl FeedStatsProvider for FeedStatsService {
+    async fn record_delivery(
+        &self,
+        subscriber_id: i32,
+        feed_id: i32,
+        delivered: bool,
+        delay_secs: Option<i32>,
+    ) -> Result<(), ServiceError> {
+        self.record_delivery(subscriber_id, feed_id, delivered, delay_secs)
+            .await
+    }
+
+    async fn subscriber_stats(
+        &self,
+        subscriber: &SubscriberEntity,
+    ) -> Result<SubscriberStats, ServiceError> {
+        self.subscriber_stats(subscriber).await
+    }
+
+    async fn delivery_analytics(&self, days: u32) -> Result<DeliveryAnalytics, ServiceError> {
+        self.delivery_analytics(days).await
+    }
+
+    async fn queue_deliveries(
+        &self,
+        subscriber_ids: &[i32],
+        data: &serde_json::Value,
+    ) -> Result<HashMap<i32, i32>, ServiceError> {
+        self.queue_deliveries(subscriber_ids, data).await
+    }
+
+    async fn finish_delivery(&self, outbox_id: i32) -> Result<(), ServiceError> {
+        self.finish_delivery(outbox_id).await
+    }
+
+    async fn take_undelivered(&self) -> Result<Vec<DeliveryOutboxEntity>, ServiceError> {
+        self.take_undelivered().await
+    }
+
+    async fn undelivered_ids(&self) -> Result<Vec<i32>, ServiceError> {
+        self.undelivered_ids().await
+    }
+}
+
+/// Notification stats of one subscriber.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriberStats {
+    /// Number of feeds subscribed to, paused ones included.
+    pub subscriptions: u32,
+    /// Notifications received since the start of the month, in UTC.
+    pub this_month: i64,
+    /// Feeds with the most notifications in the last 30 days, most first.
+    pub top_feeds: Vec<FeedNotificationCountRow>,
+    /// Average notifications per week over the last 4 weeks.
+    pub weekly_average: f64,
+}
+
+/// Bot-wide delivery analytics over the last few days.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryAnalytics {
+    /// First day counted, in UTC.
+    pub since: NaiveDate,
+    /// Notifications per day from `since` to today, days without any included.
+    pub daily: Vec<DailyDeliveryRow>,
+    /// Notifications per subscriber type.
+    pub by_subscriber_type: Vec<SubscriberTypeDeliveryRow>,
+    /// Platforms by median delay from publication to delivery, slowest first.
+    pub slowest_platforms: Vec<PlatformDelayRow>,
+}
+
+impl DeliveryAnalytics {
+    /// Total notifications delivered.
+    pub fn delivered(&self) -> i64 {
+        self.daily.iter().map(|day| day.delivered).sum()
+    }
+
+    /// Total notifications that failed.
+    pub fn failed(&self) -> i64 {
+        self.daily.iter().map(|day| day.failed).sum()
+    }
+}
+
+/// Service logging sent notifications and summarizing them per subscriber and for the
+/// bot owner. Notifications are kept in an outbox while they are being sent, so the
+/// ones a run did not get to are sent by the next.
+pub struct FeedStatsService {
+    notification_log: Arc<dyn NotificationLogRepository + Send + Sync>,
+    delivery_outbox: Arc<dyn DeliveryOutboxRepository + Send + Sync>,
+    feed_subscription: Arc<dyn FeedSubscriptionRepository + Send + Sync>,
+}
+
+impl FeedStatsService {
+    /// Creates a new feed stats service.
+    pub fn new(
+        notification_log: Arc<dyn NotificationLogRepository + Send + Sync>,
+        delivery_outbox: Arc<dyn DeliveryOutboxRepository + Send + Sync>,
+        feed_subscription: Arc<dyn FeedSubscriptionRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            notification_log,
+            delivery_outbox,
+            feed_subscription,
+        }
+    }
+
+    /// Puts a feed update about to be sent to each subscriber in the outbox. Returns
+    /// the outbox ID of each subscriber's notification, by subscriber ID.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn queue_deliveries(
+        &self,
+        subscriber_ids: &[i32],
+        data: &serde_json::Value,
+    ) -> Result<HashMap<i32, i32>, ServiceError> {
+        if subscriber_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let queued_at = Utc::now();
+        let entries: Vec<DeliveryOutboxEntity> = subscriber_ids
+            .iter()
+            .map(|subscriber_id| DeliveryOutboxEntity {
+                subscriber_id: *subscriber_id,
+                data: Json(data.clone()),
+                queued_at,
+                ..Default::default()
+            })
+            .collect();
+        // DB 1
+        let inserted = self.delivery_outbox.insert_many(&entries).await?;
+        Ok(inserted
+            .into_iter()
+            .map(|entry| (entry.subscriber_id, entry.id))
+            .collect())
+    }
+
+    /// Removes a notification from the outbox once it was sent or failed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn finish_delivery(&self, outbox_id: i32) -> Result<(), ServiceError> {
+        // DB 1
+        self.delivery_outbox.delete(&outbox_id).await?;
+        Ok(())
+    }
+
+    /// Takes every notification left in the outbox, oldest first. At startup these are
+    /// the ones the previous run did not get to.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn take_undelivered(&self) -> Result<Vec<DeliveryOutboxEntity>, ServiceError> {
+        // DB 1
+        Ok(self.delivery_outbox.take_all().await?)
+    }
+
+    /// Returns the outbox IDs of the notifications not sent yet, oldest first, leaving
+    /// them in the outbox.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn undelivered_ids(&self) -> Result<Vec<i32>, ServiceError> {
+        // DB 1
+        let mut ids: Vec<i32> = self
+            .delivery_outbox
+            .select_all()
+            .await?
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    /// Logs a notification of a feed sent to a subscriber, or one that failed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn record_delivery(
+        &self,
+        subscriber_id: i32,
+        feed_id: i32,
+        delivered: bool,
+        delay_secs: Option<i32>,
+    ) -> Result<(), ServiceError> {
+        let entry = NotificationLogEntity {
+            subscriber_id,
+            feed_id,
+            sent_at: Utc::now(),
+            delivered,
+            delay_secs,
+            ..Default::default()
+        };
+        // DB 1
+        self.notification_log.insert(&entry).await?;
+        Ok(())
+    }
+
+    /// Summarizes the subscriptions and notifications of a subscriber.
+    ///
+    /// # Performance
+    /// * DB calls: 4
+    pub async fn subscriber_stats(
+        &self,
+        subscriber: &SubscriberEntity,
+    ) -> Result<SubscriberStats, ServiceError> {
+        let now = Utc::now();
+
+        // DB 1
+        let subscriptions = self
+            .feed_subscription
+            .count_by_subscriber_id(subscriber.id)
+            .await?;
+        // DB 2
+        let this_month = self
+            .notification_log
+            .count_by_subscriber_since(subscriber.id, &start_of_month(now))
+            .await?;
+        // DB 3
+        let top_feeds = self
+            .notification_log
+            .select_top_feeds_by_subscriber_since(
+                subscriber.id,
+                &(now - Duration::days(TOP_FEEDS_DAYS)),
+                TOP_FEEDS,
+            )
+            .await?;
+        // DB 4
+        let last_weeks = self
+            .notification_log
+            .count_by_subscriber_since(subscriber.id, &(now - Duration::weeks(AVERAGE_WEEKS)))
+            .await?;
+
+        Ok(SubscriberStats {
+            subscriptions,
+            this_month,
+            top_feeds,
+            weekly_average: last_weeks as f64 / AVERAGE_WEEKS as f64,
+        })
+    }
+
+    /// Summarizes every notification of the last `days` days, today included.
+    ///
+    /// # Performance
+    /// * DB calls: 3
+    pub async fn delivery_analytics(&self, days: u32) -> Result<DeliveryAnalytics, ServiceError> {
+        let today = Utc::now().date_naive();
+        let since_day = today - Duration::days(i64::from(days.max(1)) - 1);
+        let since = since_day.and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+        // DB 1
+        let daily = self
+            .notification_log
+            .select_daily_counts_since(&since)
+            .await?;
+        // DB 2
+        let by_subscriber_type = self
+            .notification_log
+            .select_counts_by_subscriber_type_since(&since)
+            .await?;
+        // DB 3
+        let slowest_platforms = self
+            .notification_log
+            .select_slowest_platforms_since(&since, SLOWEST_PLATFORMS)
+            .await?;
+
+        Ok(DeliveryAnalytics {
+            since: since_day,
+            daily: fill_days(daily, since_day, today),
+            by_subscriber_type,
+            slowest_platforms,
+        })
+    }
+}
+
+/// Adds a zero row for each day from `since` to `until` without notifications.
+fn fill_days(
+    rows: Vec<DailyDeliveryRow>,
+    since: NaiveDate,
+    until: NaiveDate,
+) -> Vec<DailyDeliveryRow> {
+    let mut rows = rows.into_iter().peekable();
+    since
+        .iter_days()
+        .take_while(|day| *day <= until)
+        .map(|day| {
+            rows.next_if(|row| row.day == day)
+                .unwrap_or(DailyDeliveryRow {
+                    day,
+                    delivered: 0,
+                    failed: 0,
+                })
+        })
+        .collect()
+}
+
+/// Returns midnight UTC of the first day of `now`'s month.
+fn start_of_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_of_month_is_first_day_at_midnight() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 13, 45, 10).unwrap();
+        assert_eq!(
+            start_of_month(now),
+            Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn fill_days_adds_missing_days() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let rows = vec![DailyDeliveryRow {
+            day: day(2),
+            delivered: 5,
+            failed: 1,
+        }];
+
+        let filled = fill_days(rows, day(1), day(3));
+
+        let counts: Vec<(NaiveDate, i64, i64)> = filled
+            .iter()
+            .map(|row| (row.day, row.delivered, row.failed))
+            .collect();
+        assert_eq!(counts, vec![(day(1), 0, 0), (day(2), 5, 1), (day(3), 0, 0)]);
+    }
+}
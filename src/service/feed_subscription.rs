@@ -1,17 +1,25 @@
 //! Feed subscription management service.
 
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 // TODO: Improve error handling here in general
 // Especially with db results
+use chrono::DateTime;
+use chrono::Utc;
 use diesel::result::DatabaseErrorKind;
 
+use crate::config::SubscriptionLimits;
 use crate::entity::FeedEntity;
 use crate::entity::FeedItemEntity;
 use crate::entity::FeedSubscriptionEntity;
 use crate::entity::FeedWithLatestItemRow;
+use crate::entity::Json;
+use crate::entity::PollTier;
 use crate::entity::ServerSettings;
 use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberPreferences;
 use crate::entity::SubscriberType;
 use crate::error::AppError;
 use crate::feed::PlatformInfo;
@@ -21,8 +29,14 @@ use crate::repo::error::DatabaseError;
 use crate::repo::traits::*;
 use crate::service::error::ServiceError;
 use crate::service::settings::SettingsService;
+use crate::service::subscription_index::IndexedFeeds;
+use crate::service::subscription_index::SubscriptionIndex;
 use crate::service::traits::FeedSubscriptionProvider;
 
+/// Most feeds returned per subscriber by an autocomplete search, Discord's limit of
+/// choices.
+const AUTOCOMPLETE_LIMIT: usize = 25;
+
 #[async_trait::async_trait]
 impl FeedSubscriptionProvider for FeedSubscriptionService {
     async fn subscribe(
@@ -33,6 +47,15 @@ impl FeedSubscriptionProvider for FeedSubscriptionService {
         self.subscribe(url, subscriber).await
     }
 
+    async fn subscribe_added_by(
+        &self,
+        url: &str,
+        subscriber: &SubscriberEntity,
+        added_by: Option<u64>,
+    ) -> Result<SubscribeResult, ServiceError> {
+        self.subscribe_added_by(url, subscriber, added_by).await
+    }
+
     async fn get_feeds_by_tag(&self, tag: &str) -> Result<Vec<FeedEntity>, ServiceError> {
         self.get_feeds_by_tag(tag).await
     }
@@ -48,10 +71,10 @@ impl FeedSubscriptionProvider for FeedSubscriptionService {
     async fn search_and_combine_feeds(
         &self,
         partial: &str,
-        user_subscriber: Option<SubscriberEntity>,
-        guild_subscriber: Option<SubscriberEntity>,
+        user_target: Option<SubscriberTarget>,
+        guild_target: Option<SubscriberTarget>,
     ) -> Vec<FeedEntity> {
-        self.search_and_combine_feeds(partial, user_subscriber, guild_subscriber)
+        self.search_and_combine_feeds(partial, user_target, guild_target)
             .await
     }
 
@@ -103,6 +126,21 @@ impl FeedSubscriptionProvider for FeedSubscriptionService {
         self.get_or_create_subscriber(target).await
     }
 
+    async fn get_subscriber(
+        &self,
+        target: &SubscriberTarget,
+    ) -> Result<Option<SubscriberEntity>, ServiceError> {
+        self.get_subscriber(target).await
+    }
+
+    async fn take_first_seen(&self, subscriber: &SubscriberEntity) -> Result<bool, ServiceError> {
+        self.take_first_seen(subscriber).await
+    }
+
+    async fn get_feed(&self, feed_id: i32) -> Result<Option<FeedEntity>, ServiceError> {
+        self.get_feed(feed_id).await
+    }
+
     async fn get_feed_by_source_url(
         &self,
         source_url: &str,
@@ -123,6 +161,15 @@ impl FeedSubscriptionProvider for FeedSubscriptionService {
             .await
     }
 
+    async fn get_edit_subscribers_by_type_and_feed(
+        &self,
+        subscriber_type: SubscriberType,
+        feed_id: i32,
+    ) -> Result<Vec<SubscriberEntity>, ServiceError> {
+        self.get_edit_subscribers_by_type_and_feed(subscriber_type, feed_id)
+            .await
+    }
+
     async fn update_server_settings(
         &self,
         guild_id: u64,
@@ -130,6 +177,121 @@ impl FeedSubscriptionProvider for FeedSubscriptionService {
     ) -> Result<(), ServiceError> {
         self.update_server_settings(guild_id, settings).await
     }
+
+    async fn simulate_feed_update(
+        &self,
+        source_url: &str,
+    ) -> Result<FeedUpdateResult, ServiceError> {
+        self.simulate_feed_update(source_url).await
+    }
+
+    async fn set_feed_branding(
+        &self,
+        source_url: &str,
+        accent_color: Option<i32>,
+        emoji: Option<String>,
+    ) -> Result<Option<FeedEntity>, ServiceError> {
+        self.set_feed_branding(source_url, accent_color, emoji)
+            .await
+    }
+
+    fn get_subscription_limit(&self, subscriber: &SubscriberEntity) -> u32 {
+        self.get_subscription_limit(subscriber)
+    }
+
+    async fn get_subscription(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+    ) -> Result<Option<FeedSubscriptionEntity>, ServiceError> {
+        self.get_subscription(source_url, subscriber).await
+    }
+
+    async fn set_subscription_paused(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        paused: bool,
+    ) -> Result<bool, ServiceError> {
+        self.set_subscription_paused(source_url, subscriber, paused)
+            .await
+    }
+
+    async fn set_subscription_notify_edits(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        notify_edits: bool,
+    ) -> Result<bool, ServiceError> {
+        self.set_subscription_notify_edits(source_url, subscriber, notify_edits)
+            .await
+    }
+
+    async fn set_subscription_collection(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        collection_id: Option<i32>,
+    ) -> Result<bool, ServiceError> {
+        self.set_subscription_collection(source_url, subscriber, collection_id)
+            .await
+    }
+
+    async fn move_subscription(
+        &self,
+        source_url: &str,
+        from: &SubscriberEntity,
+        to: &SubscriberEntity,
+    ) -> Result<SubscribeResult, ServiceError> {
+        self.move_subscription(source_url, from, to).await
+    }
+
+    async fn set_subscription_quota(
+        &self,
+        target: &SubscriberTarget,
+        limit: Option<u32>,
+    ) -> Result<SubscriberEntity, ServiceError> {
+        self.set_subscription_quota(target, limit).await
+    }
+
+    async fn set_subscriber_preferences(
+        &self,
+        target: &SubscriberTarget,
+        preferences: SubscriberPreferences,
+    ) -> Result<SubscriberEntity, ServiceError> {
+        self.set_subscriber_preferences(target, preferences).await
+    }
+
+    async fn prune_feed_items(&self, now: &DateTime<Utc>) -> Result<u32, ServiceError> {
+        self.prune_feed_items(now).await
+    }
+
+    async fn get_unregistered_platforms(&self) -> Result<Vec<(String, u32)>, ServiceError> {
+        self.get_unregistered_platforms().await
+    }
+
+    async fn get_release_history(
+        &self,
+        feed_id: i32,
+        limit: usize,
+    ) -> Result<Vec<DateTime<Utc>>, ServiceError> {
+        self.get_release_history(feed_id, limit).await
+    }
+
+    async fn set_poll_tier(&self, feed_id: i32, tier: PollTier) -> Result<(), ServiceError> {
+        self.set_poll_tier(feed_id, tier).await
+    }
+
+    async fn get_replay_items(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Option<ReplayItems>, ServiceError> {
+        self.get_replay_items(source_url, subscriber, since, limit)
+            .await
+    }
 }
 
 /// Service for managing feed subscriptions and updates.
@@ -140,6 +302,11 @@ pub struct FeedSubscriptionService {
     pub feed_subscription: Arc<dyn FeedSubscriptionRepository + Send + Sync>,
     pub platforms: Arc<Platforms>,
     settings: Arc<SettingsService>,
+    limits: SubscriptionLimits,
+    /// Days feed items are kept when no subscriber needs them longer, 0 for forever.
+    item_retention_days: u32,
+    /// Feeds of each subscriber that searched recently, for autocomplete.
+    index: SubscriptionIndex,
 }
 
 impl FeedSubscriptionService {
@@ -160,22 +327,83 @@ impl FeedSubscriptionService {
             feed_subscription,
             platforms,
             settings,
+            limits: SubscriptionLimits::default(),
+            item_retention_days: 0,
+            index: SubscriptionIndex::new(),
         }
     }
+
+    /// Sets the default subscription caps used when a subscriber has no override.
+    pub fn with_limits(mut self, limits: SubscriptionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Shares a settings service, e.g. one that audits changes.
+    pub fn with_settings(mut self, settings: Arc<SettingsService>) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Sets the days feed items are kept, 0 for forever. Guilds can only shorten it.
+    pub fn with_item_retention_days(mut self, days: u32) -> Self {
+        self.item_retention_days = days;
+        self
+    }
+
     /// Core subscription operations
     ///
+    /// Fails with [`ServiceError::SubscriptionLimitReached`] if the subscriber is at
+    /// its quota and not yet subscribed to the feed.
+    ///
     /// # Performance
-    /// * DB calls: 1
+    /// * DB calls: 2 + 2?
     pub async fn subscribe(
         &self,
         url: &str,
         subscriber: &SubscriberEntity,
     ) -> Result<SubscribeResult, ServiceError> {
+        self.subscribe_added_by(url, subscriber, None).await
+    }
+
+    /// Like [`Self::subscribe`], recording the member who subscribed a server.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + 2?
+    pub async fn subscribe_added_by(
+        &self,
+        url: &str,
+        subscriber: &SubscriberEntity,
+        added_by: Option<u64>,
+    ) -> Result<SubscribeResult, ServiceError> {
+        let limit = self.get_subscription_limit(subscriber);
+        // DB 1
+        if self.get_subscription_count(subscriber).await? >= limit {
+            // A feed already subscribed to doesn't count against the limit again
+            // DB 2?
+            if let Some(feed) = self.get_feed_by_source_url(url).await?
+                && self
+                    .feed_subscription
+                    .select_by_feed_and_subscriber(feed.id, subscriber.id)
+                    .await?
+                    .is_some()
+            {
+                return Ok(SubscribeResult::AlreadySubscribed { feed });
+            }
+            return Err(ServiceError::SubscriptionLimitReached { limit });
+        }
+
         let feed = self.get_or_create_feed(url).await?;
 
         // DB 1
-        match self.create_subscription(feed.id, subscriber.id).await {
-            Ok(_) => Ok(SubscribeResult::Success { feed }),
+        match self
+            .create_subscription(feed.id, subscriber.id, added_by)
+            .await
+        {
+            Ok(_) => {
+                self.index.invalidate(&SubscriberTarget::from(subscriber));
+                Ok(SubscribeResult::Success { feed })
+            }
             Err(err) => {
                 if let ServiceError::DatabaseError(DatabaseError::BackendError(
                     diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _),
@@ -222,25 +450,29 @@ impl FeedSubscriptionService {
         (user_subscriber, guild_subscriber)
     }
 
-    /// Gets both DM and guild subscribers for the current user.
     /// Searches and combines feeds from both user and guild subscriptions.
+    ///
+    /// Served from memory once each subscriber's feeds are indexed.
+    ///
+    /// # Performance
+    /// * DB calls: 2? per target
     pub async fn search_and_combine_feeds(
         &self,
         partial: &str,
-        user_subscriber: Option<SubscriberEntity>,
-        guild_subscriber: Option<SubscriberEntity>,
+        user_target: Option<SubscriberTarget>,
+        guild_target: Option<SubscriberTarget>,
     ) -> Vec<FeedEntity> {
-        let mut user_feeds = match user_subscriber {
-            Some(sub) => self
-                .search_subcriptions(&sub, partial)
+        let mut user_feeds = match user_target {
+            Some(target) => self
+                .search_indexed_subscriptions(&target, partial)
                 .await
                 .unwrap_or_default(),
             None => vec![],
         };
 
-        let mut guild_feeds = match guild_subscriber {
-            Some(sub) => self
-                .search_subcriptions(&sub, partial)
+        let mut guild_feeds = match guild_target {
+            Some(target) => self
+                .search_indexed_subscriptions(&target, partial)
                 .await
                 .unwrap_or_default(),
             None => vec![],
@@ -257,6 +489,39 @@ impl FeedSubscriptionService {
         user_feeds
     }
 
+    /// Searches a target's feeds by the start of any word of their titles, building the
+    /// target's index on first use. Targets without a subscriber have no feeds.
+    ///
+    /// # Performance
+    /// * DB calls: 2?
+    pub async fn search_indexed_subscriptions(
+        &self,
+        target: &SubscriberTarget,
+        partial: &str,
+    ) -> Result<Vec<FeedEntity>, ServiceError> {
+        if let Some(index) = self.index.get(target) {
+            return Ok(index.search(partial, AUTOCOMPLETE_LIMIT));
+        }
+
+        let generation = self.index.generation();
+        // DB 1?
+        let index = match self
+            .subscriber
+            .select_by_type_and_target(&target.subscriber_type, &target.target_id)
+            .await?
+        {
+            Some(subscriber) => {
+                // DB 1?
+                let feeds = self.feed.select_all_by_subscriber_id(subscriber.id).await?;
+                IndexedFeeds::new(feeds, subscriber.preferences.0.title_language)
+            }
+            None => IndexedFeeds::default(),
+        };
+        let index = Arc::new(index);
+        self.index.insert(target.clone(), index.clone(), generation);
+        Ok(index.search(partial, AUTOCOMPLETE_LIMIT))
+    }
+
     /// Adds a prefix to feed name indicating subscription type.
     fn format_subscription_with_prefix(feed: &mut FeedEntity, is_dm: bool) {
         let prefix = if is_dm { "(DM) " } else { "(Server) " };
@@ -301,12 +566,39 @@ impl FeedSubscriptionService {
             }
         };
 
-        // Check if version changed
-        if old_latest
-            .as_ref()
-            .is_some_and(|e| new_latest.title == e.description)
-        {
-            return Ok(FeedUpdateResult::NoUpdate);
+        let content_hash = new_latest.content_hash();
+
+        // Same version: check whether its content was edited
+        let unique_item_ids = platform.get_info().unique_item_ids;
+        if let Some(old) = old_latest.as_ref().filter(|old| match &old.source_item_id {
+            Some(id) if unique_item_ids => *id == new_latest.id,
+            _ => new_latest.title == old.description,
+        }) {
+            let edited = match &old.content_hash {
+                Some(hash) if *hash == content_hash => return Ok(FeedUpdateResult::NoUpdate),
+                Some(_) => true,
+                // Items stored before edits were tracked only get their hash recorded
+                None => false,
+            };
+            let item = FeedItemEntity {
+                content_hash: Some(content_hash),
+                media_url: new_latest.media_url.clone(),
+                duration_secs: new_latest.duration_secs,
+                source_item_id: Some(new_latest.id.clone()),
+                item_url: new_latest.item_url.clone(),
+                image_url: new_latest.image_url.clone(),
+                ..old.clone()
+            };
+            self.feed_item.update(&item).await?;
+
+            if !edited {
+                return Ok(FeedUpdateResult::NoUpdate);
+            }
+            return Ok(FeedUpdateResult::Edited {
+                feed: feed.clone(),
+                item,
+                feed_info: platform.get_base().info.clone(),
+            });
         }
 
         // Insert new version into database
@@ -315,6 +607,12 @@ impl FeedSubscriptionService {
             feed_id: feed.id,
             description: new_latest.title.clone(),
             published: new_latest.published,
+            content_hash: Some(content_hash),
+            media_url: new_latest.media_url.clone(),
+            duration_secs: new_latest.duration_secs,
+            source_item_id: Some(new_latest.id.clone()),
+            item_url: new_latest.item_url.clone(),
+            image_url: new_latest.image_url.clone(),
         };
         self.feed_item.replace(&new_feed_item).await?;
 
@@ -351,6 +649,7 @@ impl FeedSubscriptionService {
         {
             Ok(not_already_deleted) => {
                 if not_already_deleted {
+                    self.index.invalidate(&SubscriberTarget::from(subscriber));
                     Ok(UnsubscribeResult::Success { feed })
                 } else {
                     Ok(UnsubscribeResult::AlreadyUnsubscribed { feed })
@@ -362,6 +661,125 @@ impl FeedSubscriptionService {
         }
     }
 
+    /// Returns a subscriber's subscription to a feed, if any.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn get_subscription(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+    ) -> Result<Option<FeedSubscriptionEntity>, ServiceError> {
+        // DB 1
+        let Some(feed) = self.get_feed_by_source_url(source_url).await? else {
+            return Ok(None);
+        };
+
+        // DB 1
+        Ok(self
+            .feed_subscription
+            .select_by_feed_and_subscriber(feed.id, subscriber.id)
+            .await?)
+    }
+
+    /// Pauses or resumes notifications for a subscription without removing it.
+    ///
+    /// Returns `false` if the subscriber is not subscribed to the feed.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn set_subscription_paused(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        paused: bool,
+    ) -> Result<bool, ServiceError> {
+        // DB 1
+        let Some(feed) = self.get_feed_by_source_url(source_url).await? else {
+            return Ok(false);
+        };
+
+        // DB 1
+        Ok(self
+            .feed_subscription
+            .update_paused(feed.id, subscriber.id, paused)
+            .await?)
+    }
+
+    /// Sets whether a subscription is also notified when announced items are edited.
+    ///
+    /// Returns `false` if the subscriber is not subscribed to the feed.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn set_subscription_notify_edits(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        notify_edits: bool,
+    ) -> Result<bool, ServiceError> {
+        // DB 1
+        let Some(feed) = self.get_feed_by_source_url(source_url).await? else {
+            return Ok(false);
+        };
+
+        // DB 1
+        Ok(self
+            .feed_subscription
+            .update_notify_edits(feed.id, subscriber.id, notify_edits)
+            .await?)
+    }
+
+    /// Puts a server subscription in a collection, or back in the server's feed
+    /// channel with `None`.
+    ///
+    /// Returns `false` if the subscriber is not subscribed to the feed.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn set_subscription_collection(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        collection_id: Option<i32>,
+    ) -> Result<bool, ServiceError> {
+        // DB 1
+        let Some(feed) = self.get_feed_by_source_url(source_url).await? else {
+            return Ok(false);
+        };
+
+        // DB 1
+        Ok(self
+            .feed_subscription
+            .update_collection(feed.id, subscriber.id, collection_id)
+            .await?)
+    }
+
+    /// Moves a subscription from one subscriber to another, e.g., from a DM to a server.
+    ///
+    /// The target's subscription quota applies. A subscription moved from a DM into a
+    /// server is recorded as added by the DM's user.
+    ///
+    /// # Performance
+    /// * DB calls: 4 + 1?
+    pub async fn move_subscription(
+        &self,
+        source_url: &str,
+        from: &SubscriberEntity,
+        to: &SubscriberEntity,
+    ) -> Result<SubscribeResult, ServiceError> {
+        let added_by = match (from.r#type, to.r#type) {
+            (SubscriberType::Dm, SubscriberType::Guild) => from.target_id.parse().ok(),
+            _ => None,
+        };
+        // DB 2
+        let result = self.subscribe_added_by(source_url, to, added_by).await?;
+
+        // DB 1 + 1?
+        self.unsubscribe(source_url, from).await?;
+        Ok(result)
+    }
+
     /// # Performance
     /// * DB calls: 1
     ///
@@ -394,6 +812,12 @@ impl FeedSubscriptionService {
                     source_url: row.source_url,
                     cover_url: row.cover_url,
                     tags: row.tags,
+                    poll_tier: row.poll_tier,
+                    accent_color: row.accent_color,
+                    emoji: row.emoji,
+                    title_english: row.title_english,
+                    title_romanized: row.title_romanized,
+                    title_native: row.title_native,
                 };
 
                 let feed_latest = if let (Some(id), Some(desc), Some(pub_date)) =
@@ -404,12 +828,20 @@ impl FeedSubscriptionService {
                         feed_id: feed.id,
                         description: desc,
                         published: pub_date,
+                        ..Default::default()
                     })
                 } else {
                     None
                 };
 
-                Subscription { feed, feed_latest }
+                Subscription {
+                    feed,
+                    feed_latest,
+                    paused: row.paused,
+                    notify_edits: row.notify_edits,
+                    added_by: row.added_by,
+                    collection_id: row.collection_id,
+                }
             })
             .collect();
 
@@ -429,6 +861,171 @@ impl FeedSubscriptionService {
             .await?)
     }
 
+    /// Returns the maximum number of subscriptions allowed for a subscriber.
+    pub fn get_subscription_limit(&self, subscriber: &SubscriberEntity) -> u32 {
+        match subscriber.max_subscriptions {
+            Some(limit) => limit.max(0) as u32,
+            None => match subscriber.r#type {
+                SubscriberType::Dm => self.limits.per_user,
+                SubscriberType::Guild => self.limits.per_guild,
+            },
+        }
+    }
+
+    /// Overrides the subscription quota for a target. `None` restores the default.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + 1?
+    pub async fn set_subscription_quota(
+        &self,
+        target: &SubscriberTarget,
+        limit: Option<u32>,
+    ) -> Result<SubscriberEntity, ServiceError> {
+        // DB 1 + 1?
+        let mut subscriber = self.get_or_create_subscriber(target).await?;
+        subscriber.max_subscriptions = limit.map(|v| v.min(i32::MAX as u32) as i32);
+
+        // DB 1
+        self.subscriber.update(&subscriber).await?;
+        Ok(subscriber)
+    }
+
+    /// Replaces the notification preferences of a target.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + 1?
+    pub async fn set_subscriber_preferences(
+        &self,
+        target: &SubscriberTarget,
+        preferences: SubscriberPreferences,
+    ) -> Result<SubscriberEntity, ServiceError> {
+        // DB 1 + 1?
+        let mut subscriber = self.get_or_create_subscriber(target).await?;
+        subscriber.preferences = Json(preferences);
+
+        // DB 1
+        self.subscriber.update(&subscriber).await?;
+        // Autocomplete names feeds by the preferred title
+        self.index.invalidate(target);
+        Ok(subscriber)
+    }
+
+    /// Deletes feed items older than the retention window of every subscriber of their
+    /// feed. Each feed keeps its latest item, which new items are compared against.
+    ///
+    /// Subscribers share a feed's items, so a feed keeps them for the longest window among
+    /// its subscribers. DM subscribers and feeds without subscribers use the default.
+    ///
+    /// # Performance
+    /// * DB calls: 3 + 1 per guild subscriber + 1 per feed with a window
+    pub async fn prune_feed_items(&self, now: &DateTime<Utc>) -> Result<u32, ServiceError> {
+        let default_days = self.item_retention_days;
+
+        let mut windows: HashMap<i32, u32> = HashMap::new();
+        // DB 1
+        for subscriber in self.subscriber.select_all().await? {
+            let guild_id = match subscriber.r#type {
+                SubscriberType::Guild => subscriber.target_id.parse::<u64>().ok(),
+                SubscriberType::Dm => None,
+            };
+            let days = match guild_id {
+                // DB 1 per guild subscriber
+                Some(guild_id) => self
+                    .get_server_settings(guild_id)
+                    .await?
+                    .feeds
+                    .item_retention_days(default_days),
+                None => default_days,
+            };
+            windows.insert(subscriber.id, days);
+        }
+
+        let mut feed_windows: HashMap<i32, u32> = HashMap::new();
+        // DB 1
+        for subscription in self.feed_subscription.select_all().await? {
+            let days = windows
+                .get(&subscription.subscriber_id)
+                .copied()
+                .unwrap_or(default_days);
+            feed_windows
+                .entry(subscription.feed_id)
+                .and_modify(|longest| *longest = longest_retention_days(*longest, days))
+                .or_insert(days);
+        }
+
+        let mut pruned = 0;
+        // DB 1
+        for feed in self.feed.select_all().await? {
+            let days = feed_windows.get(&feed.id).copied().unwrap_or(default_days);
+            if days == 0 {
+                continue;
+            }
+            let before = *now - chrono::Duration::days(days.into());
+            // DB 1 per feed with a window
+            pruned += self
+                .feed_item
+                .delete_published_before(feed.id, &before)
+                .await?;
+        }
+        Ok(pruned)
+    }
+
+    /// Counts stored feeds by platform ID, keeping the IDs no registered platform has.
+    /// Their feeds can no longer be checked, e.g. after a platform was removed or renamed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_unregistered_platforms(&self) -> Result<Vec<(String, u32)>, ServiceError> {
+        let registered: Vec<String> = self
+            .platforms
+            .get_all_platforms()
+            .iter()
+            .map(|platform| platform.get_id().to_string())
+            .collect();
+
+        let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+        // DB 1
+        for feed in self.feed.select_all().await? {
+            if !registered.contains(&feed.platform_id) {
+                *counts.entry(feed.platform_id).or_default() += 1;
+            }
+        }
+        Ok(counts.into_iter().collect())
+    }
+
+    /// Publish times of a feed's stored items, oldest first, used to estimate how often
+    /// it releases. Only the latest `limit` are kept.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_release_history(
+        &self,
+        feed_id: i32,
+        limit: usize,
+    ) -> Result<Vec<DateTime<Utc>>, ServiceError> {
+        // DB 1
+        let mut published: Vec<DateTime<Utc>> = self
+            .feed_item
+            .select_all_by_feed_id(feed_id)
+            .await?
+            .into_iter()
+            .map(|item| item.published)
+            .collect();
+        published.sort();
+        let skip = published.len().saturating_sub(limit);
+        Ok(published.split_off(skip))
+    }
+
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn set_poll_tier(&self, feed_id: i32, tier: PollTier) -> Result<(), ServiceError> {
+        // DB 1
+        Ok(self.feed.update_poll_tier(feed_id, tier).await?)
+    }
+
+    /// Searches a subscriber's feeds by any of their titles. Each feed's name is set to
+    /// the title the subscriber prefers.
+    ///
     /// # Performance
     /// * DB calls: 1
     pub async fn search_subcriptions(
@@ -437,10 +1034,15 @@ impl FeedSubscriptionService {
         partial: &str,
     ) -> Result<Vec<FeedEntity>, ServiceError> {
         // DB 1
-        Ok(self
+        let mut feeds = self
             .feed
             .select_by_name_and_subscriber_id(&subscriber.id, partial, Some(25))
-            .await?)
+            .await?;
+        let language = subscriber.preferences.0.title_language;
+        for feed in &mut feeds {
+            feed.name = feed.title(language).to_string();
+        }
+        Ok(feeds)
     }
 
     /// # Performance
@@ -477,6 +1079,12 @@ impl FeedSubscriptionService {
                     source_url: feed_source.source_url,
                     cover_url: feed_source.image_url.unwrap_or("".to_string()),
                     tags: platform.get_info().tags.clone(),
+                    poll_tier: PollTier::Active,
+                    accent_color: None,
+                    emoji: None,
+                    title_english: feed_source.titles.english,
+                    title_romanized: feed_source.titles.romanized,
+                    title_native: feed_source.titles.native,
                 };
                 // DB 1?
                 feed.id = self.feed.insert(&feed).await?;
@@ -487,8 +1095,14 @@ impl FeedSubscriptionService {
                     let version = FeedItemEntity {
                         id: 0,
                         feed_id: feed.id,
+                        content_hash: Some(feed_latest.content_hash()),
                         description: feed_latest.title,
                         published: feed_latest.published,
+                        media_url: feed_latest.media_url,
+                        duration_secs: feed_latest.duration_secs,
+                        source_item_id: Some(feed_latest.id),
+                        item_url: feed_latest.item_url,
+                        image_url: feed_latest.image_url,
                     };
                     // DB 1??
                     self.feed_item.insert(&version).await?;
@@ -518,6 +1132,7 @@ impl FeedSubscriptionService {
                 let mut subscriber = SubscriberEntity {
                     r#type: target.subscriber_type,
                     target_id: target.target_id.clone(),
+                    first_seen: true,
                     ..Default::default()
                 };
                 // DB 1?
@@ -528,6 +1143,41 @@ impl FeedSubscriptionService {
         Ok(subscriber)
     }
 
+    /// Finds an existing subscriber without creating it.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_subscriber(
+        &self,
+        target: &SubscriberTarget,
+    ) -> Result<Option<SubscriberEntity>, ServiceError> {
+        // DB 1
+        Ok(self
+            .subscriber
+            .select_by_type_and_target(&target.subscriber_type, &target.target_id)
+            .await?)
+    }
+
+    /// Marks a subscriber as onboarded. Returns whether it was still first seen, so
+    /// the onboarding message is only sent once.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn take_first_seen(
+        &self,
+        subscriber: &SubscriberEntity,
+    ) -> Result<bool, ServiceError> {
+        Ok(self.subscriber.clear_first_seen(subscriber.id).await?)
+    }
+
+    /// Get [`FeedEntity`] by ID.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_feed(&self, feed_id: i32) -> Result<Option<FeedEntity>, ServiceError> {
+        Ok(self.feed.select(&feed_id).await?)
+    }
+
     /// Get [`FeedEntity`] by source url.
     ///
     /// Returns `Some(FeedEntity)` if found. `None` otherwise.
@@ -574,6 +1224,21 @@ impl FeedSubscriptionService {
             .await?)
     }
 
+    /// Get all subscribers of a specific type that want edit notifications for a given feed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_edit_subscribers_by_type_and_feed(
+        &self,
+        subscriber_type: SubscriberType,
+        feed_id: i32,
+    ) -> Result<Vec<SubscriberEntity>, ServiceError> {
+        Ok(self
+            .subscriber
+            .select_edit_subscribers_by_type_and_feed(subscriber_type, feed_id)
+            .await?)
+    }
+
     /// # Performance
     /// * DB calls: 1
     pub async fn update_server_settings(
@@ -586,16 +1251,157 @@ impl FeedSubscriptionService {
             .await
     }
 
+    /// Overrides the accent color and emoji of a tracked feed's notifications. `None`
+    /// falls back to the platform's.
+    ///
+    /// Returns `None` if the feed is not tracked.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn set_feed_branding(
+        &self,
+        source_url: &str,
+        accent_color: Option<i32>,
+        emoji: Option<String>,
+    ) -> Result<Option<FeedEntity>, ServiceError> {
+        // DB 1
+        let Some(mut feed) = self.get_feed_by_source_url(source_url).await? else {
+            return Ok(None);
+        };
+        feed.accent_color = accent_color;
+        feed.emoji = emoji;
+
+        // DB 1
+        self.feed.update(&feed).await?;
+        Ok(Some(feed))
+    }
+
+    /// Builds a fake [`FeedUpdateResult::Updated`] for an already tracked feed.
+    ///
+    /// The new item is derived from the latest known item and is never written to the
+    /// database, so the real update check is unaffected. Its ID is 0, so it is only
+    /// published with [`FeedUpdateEvent::simulated`](crate::event::FeedUpdateEvent::simulated),
+    /// which nothing stores or streams.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn simulate_feed_update(
+        &self,
+        source_url: &str,
+    ) -> Result<FeedUpdateResult, ServiceError> {
+        let platform = self
+            .platforms
+            .get_platform_by_source_url(source_url)
+            .ok_or_else(|| FeedError::UnsupportedUrl {
+                url: source_url.to_string(),
+            })?;
+        let source_id = platform.get_id_from_source_url(source_url)?;
+
+        // DB 1
+        let feed = self
+            .feed
+            .select_by_source_id(platform.get_id(), source_id)
+            .await?
+            .ok_or_else(|| FeedError::SourceNotFound {
+                source_id: source_id.to_string(),
+            })?;
+
+        // DB 1
+        let old_item = self.feed_item.select_latest_by_feed_id(feed.id).await?;
+
+        let description = match &old_item {
+            Some(item) => format!("{} (simulated)", item.description),
+            None => "Simulated update".to_string(),
+        };
+        let new_item = FeedItemEntity {
+            id: 0,
+            feed_id: feed.id,
+            description,
+            published: Utc::now(),
+            ..old_item.clone().unwrap_or_default()
+        };
+
+        Ok(FeedUpdateResult::Updated {
+            feed,
+            old_item,
+            new_item,
+            feed_info: platform.get_base().info.clone(),
+        })
+    }
+
+    /// Returns a subscribed feed's stored items published after `since`, oldest first,
+    /// to send to the subscriber again. At most `limit` items are returned, the oldest
+    /// ones.
+    ///
+    /// Returns `None` if the feed is not tracked or the subscriber is not subscribed to it.
+    ///
+    /// # Performance
+    /// * DB calls: 3
+    pub async fn get_replay_items(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Option<ReplayItems>, ServiceError> {
+        let platform = self
+            .platforms
+            .get_platform_by_source_url(source_url)
+            .ok_or_else(|| FeedError::UnsupportedUrl {
+                url: source_url.to_string(),
+            })?;
+        let source_id = platform.get_id_from_source_url(source_url)?;
+
+        // DB 1
+        let Some(feed) = self
+            .feed
+            .select_by_source_id(platform.get_id(), source_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // DB 1
+        let subscribed = self
+            .feed_subscription
+            .select_all_by_feed_id(feed.id)
+            .await?
+            .iter()
+            .any(|sub| sub.subscriber_id == subscriber.id);
+        if !subscribed {
+            return Ok(None);
+        }
+
+        // DB 1
+        let mut items: Vec<FeedItemEntity> = self
+            .feed_item
+            .select_all_by_feed_id(feed.id)
+            .await?
+            .into_iter()
+            .filter(|item| item.published > since)
+            .collect();
+        items.sort_by_key(|item| item.published);
+        items.truncate(limit);
+
+        Ok(Some(ReplayItems {
+            feed,
+            feed_info: platform.get_base().info.clone(),
+            items,
+        }))
+    }
+
     /// # Performance
     /// * DB calls: 1
     async fn create_subscription(
         &self,
         feed_id: i32,
         subscriber_id: i32,
+        added_by: Option<u64>,
     ) -> Result<(), ServiceError> {
         let subscription = FeedSubscriptionEntity {
             feed_id,
             subscriber_id,
+            added_by: added_by.map(|id| id.to_string()),
             ..Default::default()
         };
         self.feed_subscription.insert(&subscription).await?;
@@ -620,16 +1426,40 @@ pub enum UnsubscribeResult {
     NoneSubscribed { url: String },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SubscriberTarget {
     pub subscriber_type: SubscriberType, // Guild or Dm
     pub target_id: String,               // "guild_id:channel_id" or "user_id"
 }
 
+impl From<&SubscriberEntity> for SubscriberTarget {
+    fn from(subscriber: &SubscriberEntity) -> Self {
+        Self {
+            subscriber_type: subscriber.r#type,
+            target_id: subscriber.target_id.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Subscription {
     pub feed: FeedEntity,
     pub feed_latest: Option<FeedItemEntity>,
+    pub paused: bool,
+    pub notify_edits: bool,
+    /// Discord user ID of the member who subscribed the server, if known.
+    pub added_by: Option<String>,
+    /// Server collection the subscription is posted in, if any.
+    pub collection_id: Option<i32>,
+}
+
+/// Stored items of a feed to send to one subscriber again.
+#[derive(Clone, Debug)]
+pub struct ReplayItems {
+    pub feed: FeedEntity,
+    pub feed_info: PlatformInfo,
+    /// Oldest first.
+    pub items: Vec<FeedItemEntity>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -641,5 +1471,16 @@ pub enum FeedUpdateResult {
         new_item: FeedItemEntity,
         feed_info: PlatformInfo,
     },
+    /// The latest known item was edited at the source.
+    Edited {
+        feed: FeedEntity,
+        item: FeedItemEntity,
+        feed_info: PlatformInfo,
+    },
     SourceFinished,
 }
+
+/// The longer of two retention windows in days, where 0 is forever.
+fn longest_retention_days(a: u32, b: u32) -> u32 {
+    if a == 0 || b == 0 { 0 } else { a.max(b) }
+}
@@ -0,0 +1,122 @@
+//! Which invite each member joined a guild through.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::entity::DbU64;
+use crate::entity::InviteLeaderboardEntry;
+use crate::entity::InviteUseEntity;
+use crate::entity::UsedInvite;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::InviteTrackingProvider;
+
+#[async_trait::async_trait]
+impl InviteTrackingProvider for InviteTrackingService {
+    async fn record_join(
+        &self,
+        guild_id: u64,
+        member_id: u64,
+        invite: Option<&UsedInvite>,
+    ) -> Result<(), ServiceError> {
+        self.record_join(guild_id, member_id, invite).await
+    }
+
+    async fn count_inviters(&self, guild_id: u64) -> Result<u32, ServiceError> {
+        self.count_inviters(guild_id).await
+    }
+
+    async fn list_leaderboard(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<InviteLeaderboardEntry>, ServiceError> {
+        self.list_leaderboard(guild_id, page, per_page).await
+    }
+
+    async fn count_invites_by(&self, guild_id: u64, inviter_id: u64) -> Result<u32, ServiceError> {
+        self.count_invites_by(guild_id, inviter_id).await
+    }
+}
+
+/// Service recording the invites members join through and ranking inviters.
+pub struct InviteTrackingService {
+    invite_uses: Arc<dyn InviteUsesRepository + Send + Sync>,
+}
+
+impl InviteTrackingService {
+    /// Creates a new invite tracking service.
+    pub fn new(invite_uses: Arc<dyn InviteUsesRepository + Send + Sync>) -> Self {
+        Self { invite_uses }
+    }
+
+    /// Records a member joining, through `invite` if it is known.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn record_join(
+        &self,
+        guild_id: u64,
+        member_id: u64,
+        invite: Option<&UsedInvite>,
+    ) -> Result<(), ServiceError> {
+        let model = InviteUseEntity {
+            guild_id: DbU64::from(guild_id),
+            member_id: DbU64::from(member_id),
+            inviter_id: invite.and_then(|i| i.inviter_id).map(DbU64::from),
+            invite_code: invite.map(|i| i.code.clone()),
+            joined_at: Utc::now(),
+            ..Default::default()
+        };
+        // DB 1
+        self.invite_uses.insert(&model).await?;
+        Ok(())
+    }
+
+    /// Returns the number of members in a guild who invited anyone.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn count_inviters(&self, guild_id: u64) -> Result<u32, ServiceError> {
+        Ok(self
+            .invite_uses
+            .count_inviters_by_guild_id(guild_id)
+            .await?)
+    }
+
+    /// Returns a page of a guild's inviters, most members invited first. Pages start at 1.
+    ///
+    /// A member who left and joined again through the same inviter counts once.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn list_leaderboard(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<InviteLeaderboardEntry>, ServiceError> {
+        Ok(self
+            .invite_uses
+            .select_leaderboard_by_guild_id(guild_id, page.saturating_sub(1), per_page)
+            .await?)
+    }
+
+    /// Returns the number of distinct members who joined a guild through a member's
+    /// invites.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn count_invites_by(
+        &self,
+        guild_id: u64,
+        inviter_id: u64,
+    ) -> Result<u32, ServiceError> {
+        Ok(self
+            .invite_uses
+            .count_by_inviter_id(guild_id, inviter_id)
+            .await?)
+    }
+}
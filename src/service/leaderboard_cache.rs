@@ -0,0 +1,120 @@
+//! Short-lived cache of aggregated voice leaderboards.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use dashmap::DashMap;
+
+use crate::entity::IdleAloneDiscount;
+use crate::entity::VoiceLeaderboardEntry;
+use crate::entity::VoiceLeaderboardOpt;
+
+/// How long a leaderboard is served from the cache. Range bounds are also rounded to
+/// this, so rolling ranges like "past 7 days" share an entry while it is fresh.
+const LEADERBOARD_TTL: Duration = Duration::from_secs(30);
+
+/// Guild, range and discount a leaderboard was aggregated for. Range bounds are
+/// rounded down to [`LEADERBOARD_TTL`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct LeaderboardKey {
+    guild_id: u64,
+    since: Option<i64>,
+    until: Option<i64>,
+    idle_discount: Option<IdleAloneDiscount>,
+}
+
+impl LeaderboardKey {
+    fn of(options: &VoiceLeaderboardOpt) -> Self {
+        let ttl = LEADERBOARD_TTL.as_secs() as i64;
+        Self {
+            guild_id: options.guild_id,
+            since: options.since.map(|since| since.timestamp().div_euclid(ttl)),
+            until: options.until.map(|until| until.timestamp().div_euclid(ttl)),
+            idle_discount: options.idle_discount,
+        }
+    }
+}
+
+/// Full leaderboards by guild and time range, so repeated `/vc leaderboard` calls don't
+/// each aggregate every session in the range.
+///
+/// Entries hold flushed time only and expire after [`LEADERBOARD_TTL`]. A guild's
+/// entries are dropped when its sessions change, e.g. when one closes.
+#[derive(Debug, Default)]
+pub struct LeaderboardCache {
+    entries: DashMap<LeaderboardKey, (Instant, Vec<VoiceLeaderboardEntry>)>,
+}
+
+impl LeaderboardCache {
+    /// Returns the cached leaderboard for the guild, range and discount of `options`.
+    /// Paging is ignored.
+    pub fn get(&self, options: &VoiceLeaderboardOpt) -> Option<Vec<VoiceLeaderboardEntry>> {
+        let entry = self.entries.get(&LeaderboardKey::of(options))?;
+        let (cached_at, entries) = entry.value();
+        (cached_at.elapsed() < LEADERBOARD_TTL).then(|| entries.clone())
+    }
+
+    /// Caches the full leaderboard for `options`, dropping expired entries.
+    pub fn insert(&self, options: &VoiceLeaderboardOpt, entries: Vec<VoiceLeaderboardEntry>) {
+        self.entries
+            .retain(|_, (cached_at, _)| cached_at.elapsed() < LEADERBOARD_TTL);
+        self.entries
+            .insert(LeaderboardKey::of(options), (Instant::now(), entries));
+    }
+
+    /// Drops every cached leaderboard of a guild.
+    pub fn invalidate(&self, guild_id: u64) {
+        self.entries.retain(|key, _| key.guild_id != guild_id);
+    }
+
+    /// Drops every cached leaderboard.
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    use super::*;
+    use crate::entity::VoiceLeaderboardOptBuilder;
+
+    fn options(guild_id: u64, since_secs: i64) -> VoiceLeaderboardOpt {
+        VoiceLeaderboardOptBuilder::default()
+            .guild_id(guild_id)
+            .since(Some(Utc.timestamp_opt(since_secs, 0).unwrap()))
+            .build()
+            .unwrap()
+    }
+
+    fn entry(user_id: u64) -> VoiceLeaderboardEntry {
+        VoiceLeaderboardEntry {
+            user_id,
+            total_duration: 60,
+        }
+    }
+
+    #[test]
+    fn get_rounds_range_to_ttl() {
+        let cache = LeaderboardCache::default();
+        cache.insert(&options(1, 600), vec![entry(10)]);
+
+        assert_eq!(cache.get(&options(1, 629)).map(|e| e.len()), Some(1));
+        assert!(cache.get(&options(1, 630)).is_none());
+        assert!(cache.get(&options(2, 600)).is_none());
+    }
+
+    #[test]
+    fn invalidate_drops_only_that_guild() {
+        let cache = LeaderboardCache::default();
+        cache.insert(&options(1, 600), vec![entry(10)]);
+        cache.insert(&options(2, 600), vec![entry(20)]);
+
+        cache.invalidate(1);
+
+        assert!(cache.get(&options(1, 600)).is_none());
+        assert!(cache.get(&options(2, 600)).is_some());
+    }
+}
@@ -0,0 +1,422 @@
+//! Linking MyAnimeList accounts and importing their lists into feed subscriptions.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use dashmap::DashMap;
+use log::warn;
+
+use crate::entity::MalLinkEntity;
+use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberType;
+use crate::feed::error::FeedError;
+use crate::feed::mal::MalClient;
+use crate::feed::mal::MalListKind;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::feed_subscription::SubscribeResult;
+use crate::service::feed_subscription::SubscriberTarget;
+use crate::service::new_token;
+use crate::service::title_match::MatchedFeed;
+use crate::service::title_match::TitleMatch;
+use crate::service::title_match::TitleMatchService;
+use crate::service::traits::FeedSubscriptionProvider;
+use crate::service::traits::MalImportProvider;
+
+/// Minutes a user has to authorize the bot on MyAnimeList.
+const LINK_REQUEST_MINUTES: i64 = 10;
+
+/// Minutes before expiry an access token is refreshed.
+const TOKEN_REFRESH_MARGIN_MINUTES: i64 = 5;
+
+#[async_trait::async_trait]
+impl MalImportProvider for MalImportService {
+    fn is_enabled(&self) -> bool {
+        self.is_enabled()
+    }
+
+    fn start_link(&self, user_id: u64) -> Result<String, ServiceError> {
+        self.start_link(user_id)
+    }
+
+    async fn complete_link(&self, state: &str, code: &str) -> Result<MalLinkEntity, ServiceError> {
+        self.complete_link(state, code).await
+    }
+
+    async fn unlink(&self, user_id: u64) -> Result<bool, ServiceError> {
+        self.unlink(user_id).await
+    }
+
+    async fn get_link(&self, user_id: u64) -> Result<Option<MalLinkEntity>, ServiceError> {
+        self.get_link(user_id).await
+    }
+
+    async fn import(
+        &self,
+        user_id: u64,
+        kinds: &[MalListKind],
+        preview: bool,
+    ) -> Result<MalImportResult, ServiceError> {
+        self.import(user_id, kinds, preview).await
+    }
+}
+
+/// An authorization a user started with [`MalImportService::start_link`].
+struct LinkRequest {
+    user_id: u64,
+    code_verifier: String,
+    created_at: DateTime<Utc>,
+}
+
+/// Service linking MyAnimeList accounts and subscribing users to what they watch and
+/// read there.
+///
+/// Linking uses the OAuth authorization code flow. Started requests are kept in memory,
+/// so a restart cancels them.
+pub struct MalImportService {
+    links: Arc<dyn MalLinksRepository + Send + Sync>,
+    feeds: Arc<dyn FeedSubscriptionProvider>,
+    matcher: TitleMatchService,
+    client: Option<Arc<MalClient>>,
+    requests: DashMap<String, LinkRequest>,
+}
+
+impl MalImportService {
+    pub fn new(
+        links: Arc<dyn MalLinksRepository + Send + Sync>,
+        feeds: Arc<dyn FeedSubscriptionProvider>,
+        matcher: TitleMatchService,
+    ) -> Self {
+        Self {
+            links,
+            feeds,
+            matcher,
+            client: None,
+            requests: DashMap::new(),
+        }
+    }
+
+    /// Enables linking through this MyAnimeList API client.
+    pub fn with_client(mut self, client: Arc<MalClient>) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Whether a MyAnimeList API client is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.client.is_some()
+    }
+
+    /// Starts linking a user's account. Returns the MyAnimeList page where they authorize
+    /// the bot, valid for 10 minutes. Replaces the user's earlier request.
+    pub fn start_link(&self, user_id: u64) -> Result<String, ServiceError> {
+        let client = self.client()?;
+        let cutoff = Utc::now() - Duration::minutes(LINK_REQUEST_MINUTES);
+        self.requests
+            .retain(|_, request| request.user_id != user_id && request.created_at > cutoff);
+
+        let state = new_token();
+        let code_verifier = new_token();
+        let url = client.authorize_url(&state, &code_verifier);
+        self.requests.insert(
+            state,
+            LinkRequest {
+                user_id,
+                code_verifier,
+                created_at: Utc::now(),
+            },
+        );
+        Ok(url)
+    }
+
+    /// Finishes the link request `state` with the code MyAnimeList redirected the user
+    /// with, replacing the user's earlier link.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    /// * API calls: 2
+    pub async fn complete_link(
+        &self,
+        state: &str,
+        code: &str,
+    ) -> Result<MalLinkEntity, ServiceError> {
+        let client = self.client()?;
+        let cutoff = Utc::now() - Duration::minutes(LINK_REQUEST_MINUTES);
+        let (_, request) = self
+            .requests
+            .remove(state)
+            .filter(|(_, request)| request.created_at > cutoff)
+            .ok_or(ServiceError::MalLinkRequestExpired)?;
+
+        // API 1
+        let tokens = client.exchange_code(code, &request.code_verifier).await?;
+        // API 1
+        let (mal_user_id, username) = client.fetch_user(&tokens.access_token).await?;
+
+        let link = MalLinkEntity {
+            user_id: request.user_id.into(),
+            mal_user_id,
+            username,
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            token_expires_at: tokens.expires_at,
+            last_imported_at: None,
+            created_at: Utc::now(),
+        };
+        // DB 2
+        self.links.replace(&link).await?;
+        Ok(link)
+    }
+
+    /// Removes a user's MyAnimeList link. Imported subscriptions are kept. Returns whether
+    /// a link existed.
+    ///
+    /// # Performance
+    /// * DB calls: 1 + 1?
+    pub async fn unlink(&self, user_id: u64) -> Result<bool, ServiceError> {
+        // DB 1
+        if self.links.select(&user_id).await?.is_none() {
+            return Ok(false);
+        }
+        // DB 1?
+        self.links.delete(&user_id).await?;
+        Ok(true)
+    }
+
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_link(&self, user_id: u64) -> Result<Option<MalLinkEntity>, ServiceError> {
+        Ok(self.links.select(&user_id).await?)
+    }
+
+    /// Subscribes a linked user's DMs to the feeds of the anime they watch and the manga
+    /// they read on MyAnimeList. Finished titles are skipped. With `preview`, only reports
+    /// what would be subscribed to.
+    ///
+    /// Fails with [`ServiceError::MalNotLinked`] if the user has no link.
+    ///
+    /// # Performance
+    /// * DB calls: 5 + 1? + 2 per new subscription
+    /// * API calls: 1? + 1 per list + 1 per 50 anime + 1 per manga + 2? per new feed
+    pub async fn import(
+        &self,
+        user_id: u64,
+        kinds: &[MalListKind],
+        preview: bool,
+    ) -> Result<MalImportResult, ServiceError> {
+        let client = self.client()?;
+        // DB 1
+        let mut link = self
+            .links
+            .select(&user_id)
+            .await?
+            .ok_or(ServiceError::MalNotLinked)?;
+        // DB 1? + API 1?
+        self.refresh_tokens(client, &mut link).await?;
+
+        let mut matches = Vec::new();
+        for kind in kinds {
+            // API 1 per list
+            let entries = client.fetch_list(&link.access_token, *kind).await?;
+            match kind {
+                // API 1 per 50 anime
+                MalListKind::Anime => matches.extend(self.matcher.match_anime(&entries).await?),
+                // API 1 per manga
+                MalListKind::Manga => matches.extend(self.matcher.match_manga(&entries).await),
+            }
+        }
+
+        // DB 2
+        let subscriber = self
+            .feeds
+            .get_or_create_subscriber(&SubscriberTarget {
+                subscriber_type: SubscriberType::Dm,
+                target_id: user_id.to_string(),
+            })
+            .await?;
+        // DB 2
+        let subscribed = self.subscribed_urls(&subscriber).await?;
+
+        let plan = plan_import(&matches, &subscribed);
+        let mut result = MalImportResult {
+            already_subscribed: plan.already_subscribed,
+            finished: plan.finished,
+            unmatched: plan.unmatched,
+            ..Default::default()
+        };
+        if preview {
+            result.subscribed = plan
+                .subscribe
+                .iter()
+                .map(|feed| feed.title.clone())
+                .collect();
+            return Ok(result);
+        }
+
+        let mut subscribe = plan.subscribe.iter();
+        for feed in subscribe.by_ref() {
+            // DB 2 + API 2? per new feed
+            match self.feeds.subscribe(&feed.source_url, &subscriber).await {
+                Ok(SubscribeResult::Success { .. }) => result.subscribed.push(feed.title.clone()),
+                Ok(SubscribeResult::AlreadySubscribed { .. }) => result.already_subscribed += 1,
+                Err(ServiceError::SubscriptionLimitReached { .. }) => {
+                    result.over_limit += 1;
+                    break;
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to subscribe user {user_id} to {}: {e}",
+                        feed.source_url
+                    );
+                    result.failed += 1;
+                }
+            }
+        }
+        result.over_limit += subscribe.count() as u32;
+
+        link.last_imported_at = Some(Utc::now());
+        // DB 1
+        self.links.update(&link).await?;
+        Ok(result)
+    }
+
+    /// Refreshes the link's tokens if the access token is about to expire.
+    async fn refresh_tokens(
+        &self,
+        client: &MalClient,
+        link: &mut MalLinkEntity,
+    ) -> Result<(), ServiceError> {
+        if link.token_expires_at > Utc::now() + Duration::minutes(TOKEN_REFRESH_MARGIN_MINUTES) {
+            return Ok(());
+        }
+        // API 1
+        let tokens = match client.refresh(&link.refresh_token).await {
+            Ok(tokens) => tokens,
+            // The refresh token expired or the user revoked access
+            Err(FeedError::ApiError { .. }) => return Err(ServiceError::MalLinkRevoked),
+            Err(e) => return Err(e.into()),
+        };
+        link.access_token = tokens.access_token;
+        link.refresh_token = tokens.refresh_token;
+        link.token_expires_at = tokens.expires_at;
+        // DB 1
+        self.links.update(link).await?;
+        Ok(())
+    }
+
+    /// Returns the source URLs of the subscriber's feeds.
+    async fn subscribed_urls(
+        &self,
+        subscriber: &SubscriberEntity,
+    ) -> Result<HashSet<String>, ServiceError> {
+        // DB 1
+        let count = self.feeds.get_subscription_count(subscriber).await?;
+        if count == 0 {
+            return Ok(HashSet::new());
+        }
+        // DB 1
+        let subscriptions = self
+            .feeds
+            .list_paginated_subscriptions(subscriber, 1, count)
+            .await?;
+        Ok(subscriptions
+            .into_iter()
+            .map(|subscription| subscription.feed.source_url)
+            .collect())
+    }
+
+    fn client(&self) -> Result<&MalClient, ServiceError> {
+        self.client.as_deref().ok_or(ServiceError::MalNotConfigured)
+    }
+}
+
+/// Outcome of importing a user's MyAnimeList lists.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MalImportResult {
+    /// Titles of the feeds subscribed to, or that would be in a preview.
+    pub subscribed: Vec<String>,
+    /// Entries the user was already subscribed to.
+    pub already_subscribed: u32,
+    /// Entries skipped because they finished airing or publishing.
+    pub finished: u32,
+    /// MyAnimeList titles of the entries no feed was found for.
+    pub unmatched: Vec<String>,
+    /// Entries not subscribed to because the subscription limit was reached.
+    pub over_limit: u32,
+    /// Entries whose feed could not be created.
+    pub failed: u32,
+}
+
+/// Subscriptions an import makes.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ImportPlan<'a> {
+    subscribe: Vec<&'a MatchedFeed>,
+    already_subscribed: u32,
+    finished: u32,
+    unmatched: Vec<String>,
+}
+
+/// Works out which matched feeds to subscribe to, given the source URLs of the user's
+/// current subscriptions. Feeds matched by two entries are subscribed to once.
+fn plan_import<'a>(matches: &'a [TitleMatch], subscribed: &HashSet<String>) -> ImportPlan<'a> {
+    let mut plan = ImportPlan::default();
+    let mut planned: HashSet<&str> = HashSet::new();
+
+    for title_match in matches {
+        let Some(feed) = &title_match.feed else {
+            plan.unmatched.push(title_match.entry.title.clone());
+            continue;
+        };
+        if subscribed.contains(&feed.source_url) {
+            plan.already_subscribed += 1;
+        } else if !feed.ongoing {
+            plan.finished += 1;
+        } else if planned.insert(feed.source_url.as_str()) {
+            plan.subscribe.push(feed);
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::mal::MalListEntry;
+
+    fn title_match(title: &str, url: Option<&str>, ongoing: bool) -> TitleMatch {
+        TitleMatch {
+            entry: MalListEntry {
+                title: title.to_string(),
+                ..Default::default()
+            },
+            feed: url.map(|url| MatchedFeed {
+                source_url: url.to_string(),
+                title: title.to_string(),
+                ongoing,
+            }),
+        }
+    }
+
+    #[test]
+    fn plan_import_skips_subscribed_finished_and_unmatched_entries() {
+        let matches = vec![
+            title_match("Airing", Some("https://anilist.co/anime/1"), true),
+            title_match("Subscribed", Some("https://anilist.co/anime/2"), true),
+            title_match("Finished", Some("https://anilist.co/anime/3"), false),
+            title_match("Unknown", None, true),
+            title_match("Airing again", Some("https://anilist.co/anime/1"), true),
+        ];
+        let subscribed = HashSet::from(["https://anilist.co/anime/2".to_string()]);
+
+        let plan = plan_import(&matches, &subscribed);
+
+        assert_eq!(plan.subscribe.len(), 1);
+        assert_eq!(plan.subscribe[0].title, "Airing");
+        assert_eq!(plan.already_subscribed, 1);
+        assert_eq!(plan.finished, 1);
+        assert_eq!(plan.unmatched, vec!["Unknown".to_string()]);
+    }
+}
@@ -1,28 +1,77 @@
-//! Business logic services for feed subscriptions and voice tracking.
+//! Business logic services for feed subscriptions, voice tracking and server activity.
 
 use std::sync::Arc;
 
+use uuid::Uuid;
+
+use crate::config::Config;
 use crate::feed::Platforms;
+use crate::feed::mal::MalClient;
 use crate::repo::traits::Repos;
+use crate::service::anilist_sync::AniListSyncService;
+use crate::service::api::ApiTokenService;
+use crate::service::custom_feed::CustomFeedService;
+use crate::service::dashboard::DashboardService;
+use crate::service::emoji_stats::EmojiStatsService;
+use crate::service::feed_collection::FeedCollectionService;
+use crate::service::feed_stats::FeedStatsService;
 use crate::service::feed_subscription::FeedSubscriptionService;
 use crate::service::internal::InternalService;
+use crate::service::invite_tracking::InviteTrackingService;
+use crate::service::mal_import::MalImportService;
+use crate::service::release_calendar::ReleaseCalendarService;
 use crate::service::settings::SettingsService;
+use crate::service::subscription_request::SubscriptionRequestService;
+use crate::service::tag::TagService;
+use crate::service::temp_voice::TempVoiceService;
+use crate::service::title_match::TitleMatchService;
 use crate::service::traits::*;
 use crate::service::voice_tracking::VoiceTrackingService;
 
+pub mod anilist_sync;
+pub mod api;
+pub mod custom_feed;
+pub mod dashboard;
+pub mod emoji_stats;
 pub mod error;
+pub mod feed_collection;
+pub mod feed_stats;
 pub mod feed_subscription;
 pub mod internal;
+pub mod invite_tracking;
+pub mod leaderboard_cache;
+pub mod mal_import;
+pub mod open_sessions;
+pub mod release_calendar;
 pub mod settings;
+pub mod subscription_index;
+pub mod subscription_request;
+pub mod tag;
+pub mod temp_voice;
+pub mod title_match;
 pub mod traits;
+pub mod voice_import;
 pub mod voice_tracking;
 
 /// Container for all application services.
 pub struct Services {
     pub settings: Arc<dyn SettingsProvider>,
     pub feed_subscription: Arc<dyn FeedSubscriptionProvider>,
+    pub subscription_requests: Arc<dyn SubscriptionRequestProvider>,
+    pub feed_collections: Arc<dyn FeedCollectionProvider>,
+    pub feed_stats: Arc<dyn FeedStatsProvider>,
     pub voice_tracking: Arc<dyn VoiceTracker>,
     pub internal: Arc<dyn InternalOps>,
+    pub dashboard: Arc<dyn DashboardProvider>,
+    pub api_tokens: Arc<dyn ApiTokenProvider>,
+    pub tags: Arc<dyn TagProvider>,
+    pub emoji_stats: Arc<dyn EmojiStatsProvider>,
+    pub invite_tracking: Arc<dyn InviteTrackingProvider>,
+    pub temp_voice: Arc<dyn TempVoiceProvider>,
+    pub custom_feeds: Arc<dyn CustomFeedProvider>,
+    pub anilist_sync: Arc<dyn AniListSyncProvider>,
+    pub mal_import: Arc<dyn MalImportProvider>,
+    pub release_calendar: Arc<dyn ReleaseCalendarProvider>,
 }
 
 impl Services {
@@ -33,15 +82,29 @@ impl Services {
     pub async fn new(
         repos: Arc<dyn Repos + Send + Sync>,
         platforms: Arc<Platforms>,
+        config: &Config,
     ) -> anyhow::Result<Self> {
-        let settings = Arc::new(SettingsService::new(Arc::from(repos.server_settings())));
-        let voice_tracking = Arc::new(
-            VoiceTrackingService::new(
-                Arc::from(repos.voice_sessions()),
-                Arc::from(repos.server_settings()),
-            )
-            .await?,
+        let settings = Arc::new(
+            SettingsService::new(Arc::from(repos.server_settings()))
+                .with_audit(Arc::from(repos.settings_audit())),
         );
+        let mut voice_tracking = VoiceTrackingService::new(
+            Arc::from(repos.voice_sessions()),
+            Arc::from(repos.server_settings()),
+        )
+        .await?
+        .with_settings(settings.clone())
+        .with_channel_weights(Arc::from(repos.channel_weights()))
+        .with_snapshots(Arc::from(repos.leaderboard_snapshots()))
+        .with_flags(Arc::from(repos.voice_session_flags()))
+        .with_adjustments(Arc::from(repos.voice_adjustments()))
+        .with_merges(Arc::from(repos.voice_account_merges()))
+        .with_session_retention_days(config.retention.voice_session_days);
+        if config.features.voice_event_sourcing {
+            voice_tracking = voice_tracking
+                .with_event_sourcing(Arc::from(repos.voice_events()), Arc::from(repos.bot_meta()));
+        }
+        let voice_tracking = Arc::new(voice_tracking);
         let internal = Arc::new(InternalService::new(
             Arc::from(repos.feed()),
             Arc::from(repos.feed_item()),
@@ -49,20 +112,96 @@ impl Services {
             Arc::from(repos.feed_subscription()),
             Arc::from(repos.bot_meta()),
         ));
-        let feed_subscription = Arc::new(FeedSubscriptionService::new(
+        let feed_subscription = Arc::new(
+            FeedSubscriptionService::new(
+                Arc::from(repos.feed()),
+                Arc::from(repos.feed_item()),
+                Arc::from(repos.subscriber()),
+                Arc::from(repos.feed_subscription()),
+                Arc::from(repos.server_settings()),
+                platforms.clone(),
+            )
+            .with_limits(config.limits)
+            .with_settings(settings.clone())
+            .with_item_retention_days(config.retention.feed_item_days),
+        );
+        let subscription_requests = Arc::new(SubscriptionRequestService::new(
+            Arc::from(repos.pending_subscriptions()),
+            feed_subscription.clone(),
+        ));
+        let feed_collections = Arc::new(FeedCollectionService::new(
+            Arc::from(repos.feed_collections()),
+            Arc::from(repos.feed_subscription()),
+        ));
+        let feed_stats = Arc::new(FeedStatsService::new(
+            Arc::from(repos.notification_log()),
+            Arc::from(repos.delivery_outbox()),
+            Arc::from(repos.feed_subscription()),
+        ));
+
+        let dashboard = Arc::new(DashboardService::new(Arc::from(repos.dashboard_tokens())));
+        let api_tokens = Arc::new(ApiTokenService::new(Arc::from(repos.api_tokens())));
+        let tags = Arc::new(TagService::new(Arc::from(repos.tags())));
+        let emoji_stats = Arc::new(EmojiStatsService::new(Arc::from(repos.emoji_stats())));
+        let invite_tracking = Arc::new(InviteTrackingService::new(Arc::from(repos.invite_uses())));
+        let temp_voice = Arc::new(TempVoiceService::new(Arc::from(
+            repos.temp_voice_channels(),
+        )));
+        let custom_feeds = Arc::new(
+            CustomFeedService::new(
+                Arc::from(repos.custom_json_feeds()),
+                platforms.custom_json.clone(),
+            )
+            .await?,
+        );
+        let anilist_sync = Arc::new(AniListSyncService::new(
+            Arc::from(repos.anilist_links()),
+            feed_subscription.clone(),
+            platforms.anilist.clone(),
+            TitleMatchService::new(platforms.anilist.clone(), platforms.mangadex.clone()),
+        ));
+        let mut mal_import = MalImportService::new(
+            Arc::from(repos.mal_links()),
+            feed_subscription.clone(),
+            TitleMatchService::new(platforms.anilist.clone(), platforms.mangadex.clone()),
+        );
+        if let Some(mal) = &config.mal {
+            let redirect_url = format!("{}/mal/callback", config.web.public_url);
+            mal_import = mal_import.with_client(Arc::new(MalClient::new(mal, redirect_url)));
+        }
+        let mal_import = Arc::new(mal_import);
+        let release_calendar = Arc::new(ReleaseCalendarService::new(
             Arc::from(repos.feed()),
             Arc::from(repos.feed_item()),
             Arc::from(repos.subscriber()),
             Arc::from(repos.feed_subscription()),
-            Arc::from(repos.server_settings()),
-            platforms.clone(),
+            settings.clone(),
+            platforms.anilist.clone(),
         ));
 
         Ok(Self {
             settings,
             feed_subscription,
+            subscription_requests,
+            feed_collections,
+            feed_stats,
             voice_tracking,
             internal,
+            dashboard,
+            api_tokens,
+            tags,
+            emoji_stats,
+            invite_tracking,
+            temp_voice,
+            custom_feeds,
+            anilist_sync,
+            mal_import,
+            release_calendar,
         })
     }
 }
+
+/// Builds an unguessable, URL-safe access token from two random UUIDs.
+pub(crate) fn new_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
@@ -0,0 +1,157 @@
+//! Registry of voice sessions that are still open.
+
+use std::collections::HashMap;
+
+use chrono::DateTime;
+use chrono::SubsecRound;
+use chrono::Utc;
+use dashmap::DashMap;
+
+use crate::entity::VoiceSessionsEntity;
+
+/// A member's open voice session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenSession {
+    pub channel_id: u64,
+    /// Start of the current split.
+    pub join_time: DateTime<Utc>,
+    /// Start of the whole session, before any channel moves.
+    pub started_at: DateTime<Utc>,
+    /// The split's stored leave_time. Time since then is not in the database yet.
+    pub flushed_at: DateTime<Utc>,
+}
+
+impl OpenSession {
+    fn of(session: &VoiceSessionsEntity, started_at: DateTime<Utc>) -> Self {
+        Self {
+            channel_id: session.channel_id,
+            // Postgres keeps microseconds, so sessions read back compare equal
+            join_time: session.join_time.trunc_subsecs(6),
+            started_at,
+            flushed_at: session.leave_time,
+        }
+    }
+
+    /// Seconds since the session started, across channel moves.
+    pub fn duration_secs(&self, now: &DateTime<Utc>) -> i64 {
+        (*now - self.started_at).num_seconds().max(0)
+    }
+
+    fn is(&self, channel_id: u64, join_time: &DateTime<Utc>) -> bool {
+        self.channel_id == channel_id && self.join_time == join_time.trunc_subsecs(6)
+    }
+}
+
+/// Latest open voice session by guild and user ID. Kept up to date by
+/// `VoiceTrackingService`; views read it to show who is in voice right now.
+#[derive(Debug, Default)]
+pub struct OpenSessions {
+    sessions: DashMap<(u64, u64), OpenSession>,
+}
+
+impl OpenSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a member's open session.
+    pub fn get(&self, guild_id: u64, user_id: u64) -> Option<OpenSession> {
+        self.sessions
+            .get(&(guild_id, user_id))
+            .map(|session| *session)
+    }
+
+    /// Whether a member is in a tracked voice channel.
+    pub fn is_live(&self, guild_id: u64, user_id: u64) -> bool {
+        self.sessions.contains_key(&(guild_id, user_id))
+    }
+
+    /// Returns the open sessions of a guild by user ID.
+    pub fn by_guild(&self, guild_id: u64) -> Vec<(u64, OpenSession)> {
+        self.sessions
+            .iter()
+            .filter(|entry| entry.key().0 == guild_id)
+            .map(|entry| (entry.key().1, *entry.value()))
+            .collect()
+    }
+
+    /// Remembers `session` as its member's open session. `started_at` is the start of the
+    /// whole session when `session` is a later split.
+    pub(crate) fn open(&self, session: &VoiceSessionsEntity, started_at: DateTime<Utc>) {
+        self.sessions.insert(
+            (session.guild_id, session.user_id),
+            OpenSession::of(session, started_at),
+        );
+    }
+
+    /// Forgets the open session that started at `join_time` in `channel_id`. Returns its
+    /// guild ID, if it was open.
+    pub(crate) fn close(
+        &self,
+        user_id: u64,
+        channel_id: u64,
+        join_time: &DateTime<Utc>,
+    ) -> Option<u64> {
+        let mut closed_guild_id = None;
+        self.sessions.retain(|(guild_id, open_user_id), session| {
+            let closes = *open_user_id == user_id && session.is(channel_id, join_time);
+            if closes {
+                closed_guild_id = Some(*guild_id);
+            }
+            !closes
+        });
+        closed_guild_id
+    }
+
+    /// Records that the open session that started at `join_time` was flushed.
+    pub(crate) fn set_flushed(
+        &self,
+        user_id: u64,
+        channel_id: u64,
+        join_time: &DateTime<Utc>,
+        flushed_at: &DateTime<Utc>,
+    ) {
+        for mut entry in self.sessions.iter_mut() {
+            if entry.key().1 == user_id && entry.is(channel_id, join_time) {
+                entry.flushed_at = *flushed_at;
+            }
+        }
+    }
+
+    /// Replaces the registry with `sessions`, the open sessions just flushed at
+    /// `flushed_at`. Sessions opened since are kept.
+    pub(crate) fn resync(&self, sessions: &[VoiceSessionsEntity], flushed_at: &DateTime<Utc>) {
+        let previous: HashMap<(u64, u64), OpenSession> = self
+            .sessions
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+        // Sessions that started after the flush are not in its result
+        self.sessions
+            .retain(|_, session| session.join_time > *flushed_at);
+
+        for session in sessions {
+            let key = (session.guild_id, session.user_id);
+            if self
+                .sessions
+                .get(&key)
+                .is_some_and(|open| open.join_time > session.join_time)
+            {
+                continue;
+            }
+            let started_at = previous
+                .get(&key)
+                .filter(|open| open.is(session.channel_id, &session.join_time))
+                .map_or(session.join_time, |open| open.started_at);
+            self.sessions
+                .insert(key, OpenSession::of(session, started_at));
+        }
+    }
+
+    /// Forgets a guild's open sessions that started at or after `since`.
+    pub(crate) fn forget_guild_since(&self, guild_id: u64, since: &DateTime<Utc>) {
+        self.sessions.retain(|(open_guild_id, _), session| {
+            *open_guild_id != guild_id || session.join_time < *since
+        });
+    }
+}
@@ -0,0 +1,366 @@
+//! Upcoming releases shown on the pinned release calendars of servers, and the airings
+//! kept as server events.
+
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+
+use crate::entity::FeedEntity;
+use crate::entity::FeedsSettings;
+use crate::entity::SubscriberType;
+use crate::entity::UpcomingRelease;
+use crate::feed::AniListPlatform;
+use crate::feed::Platform;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::ReleaseCalendarProvider;
+use crate::service::traits::SettingsProvider;
+
+/// Days ahead a calendar looks for releases.
+const CALENDAR_DAYS: i64 = 7;
+
+/// Releases listed on a calendar, soonest first.
+const MAX_RELEASES: usize = 25;
+
+/// Most recent releases an estimate is based on.
+const CADENCE_RELEASES: usize = 10;
+
+/// Gaps between releases needed before the next one is estimated.
+const MIN_CADENCE_GAPS: usize = 2;
+
+#[async_trait::async_trait]
+impl ReleaseCalendarProvider for ReleaseCalendarService {
+    async fn guilds_with_calendar(&self) -> Result<Vec<(u64, u64)>, ServiceError> {
+        self.guilds_with_calendar().await
+    }
+
+    async fn upcoming_releases(
+        &self,
+        guild_id: u64,
+        now: &DateTime<Utc>,
+    ) -> Result<Vec<UpcomingRelease>, ServiceError> {
+        self.upcoming_releases(guild_id, now).await
+    }
+
+    async fn guilds_with_airing_events(&self) -> Result<Vec<u64>, ServiceError> {
+        self.guilds_with_airing_events().await
+    }
+
+    async fn upcoming_airings(&self, guild_id: u64) -> Result<Vec<UpcomingRelease>, ServiceError> {
+        self.upcoming_airings(guild_id).await
+    }
+}
+
+/// Service collecting the upcoming releases of the feeds a server follows.
+///
+/// AniList announces when the next episode airs. Other platforms don't, so their next
+/// release is estimated from the gaps between the feed's recent items.
+pub struct ReleaseCalendarService {
+    feed: Arc<dyn FeedRepository + Send + Sync>,
+    feed_item: Arc<dyn FeedItemRepository + Send + Sync>,
+    subscriber: Arc<dyn SubscriberRepository + Send + Sync>,
+    feed_subscription: Arc<dyn FeedSubscriptionRepository + Send + Sync>,
+    settings: Arc<dyn SettingsProvider>,
+    anilist: Arc<AniListPlatform>,
+}
+
+impl ReleaseCalendarService {
+    pub fn new(
+        feed: Arc<dyn FeedRepository + Send + Sync>,
+        feed_item: Arc<dyn FeedItemRepository + Send + Sync>,
+        subscriber: Arc<dyn SubscriberRepository + Send + Sync>,
+        feed_subscription: Arc<dyn FeedSubscriptionRepository + Send + Sync>,
+        settings: Arc<dyn SettingsProvider>,
+        anilist: Arc<AniListPlatform>,
+    ) -> Self {
+        Self {
+            feed,
+            feed_item,
+            subscriber,
+            feed_subscription,
+            settings,
+            anilist,
+        }
+    }
+
+    /// Returns the guilds keeping a release calendar with the channel it is kept in, as
+    /// `(guild_id, channel_id)`. Guilds with feeds paused or no feed channel are left out.
+    ///
+    /// # Performance
+    /// * DB calls: 1 + 1 per guild subscriber
+    pub async fn guilds_with_calendar(&self) -> Result<Vec<(u64, u64)>, ServiceError> {
+        let mut guilds = Vec::new();
+        for (guild_id, feeds) in self.guild_feed_settings().await? {
+            if !feeds.calendar_mode().is_enabled() {
+                continue;
+            }
+            if let Some(channel_id) = feeds.channel_id.and_then(|id| id.parse::<u64>().ok()) {
+                guilds.push((guild_id, channel_id));
+            }
+        }
+        Ok(guilds)
+    }
+
+    /// Returns the guilds keeping server events for airing anime. Guilds with feeds
+    /// paused are left out.
+    ///
+    /// # Performance
+    /// * DB calls: 1 + 1 per guild subscriber
+    pub async fn guilds_with_airing_events(&self) -> Result<Vec<u64>, ServiceError> {
+        Ok(self
+            .guild_feed_settings()
+            .await?
+            .into_iter()
+            .filter(|(_, feeds)| feeds.has_airing_events())
+            .map(|(guild_id, _)| guild_id)
+            .collect())
+    }
+
+    /// Returns the feed settings of every guild subscriber with feeds enabled.
+    ///
+    /// # Performance
+    /// * DB calls: 1 + 1 per guild subscriber
+    async fn guild_feed_settings(&self) -> Result<Vec<(u64, FeedsSettings)>, ServiceError> {
+        let mut guilds = Vec::new();
+        // DB 1
+        for subscriber in self.subscriber.select_all().await? {
+            if subscriber.r#type != SubscriberType::Guild {
+                continue;
+            }
+            let Ok(guild_id) = subscriber.target_id.parse::<u64>() else {
+                continue;
+            };
+            // DB 1 per guild subscriber
+            let feeds = self.settings.get_server_settings(guild_id).await?.feeds;
+            if feeds.enabled == Some(false) {
+                continue;
+            }
+            guilds.push((guild_id, feeds));
+        }
+        Ok(guilds)
+    }
+
+    /// Returns the releases expected in the next week from the feeds a guild follows,
+    /// soonest first. Paused subscriptions are left out.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + 1 per subscription + 1 per feed not on AniList
+    /// * API calls: 1 per 50 AniList feeds
+    pub async fn upcoming_releases(
+        &self,
+        guild_id: u64,
+        now: &DateTime<Utc>,
+    ) -> Result<Vec<UpcomingRelease>, ServiceError> {
+        let until = *now + Duration::days(CALENDAR_DAYS);
+
+        let mut anime = Vec::new();
+        let mut releases = Vec::new();
+        // DB 2 + 1 per subscription
+        for feed in self.active_feeds(guild_id).await? {
+            if feed.platform_id == self.anilist.get_id() {
+                anime.push(feed);
+                continue;
+            }
+
+            // DB 1 per feed not on AniList
+            let published: Vec<DateTime<Utc>> = self
+                .feed_item
+                .select_all_by_feed_id(feed.id)
+                .await?
+                .into_iter()
+                .map(|item| item.published)
+                .collect();
+            if let Some(expected_at) = estimate_next_release(&published, now) {
+                releases.push(UpcomingRelease {
+                    feed_name: feed.name,
+                    source_url: feed.source_url,
+                    item_name: None,
+                    expected_at,
+                    estimated: true,
+                });
+            }
+        }
+        // API 1 per 50 AniList feeds
+        releases.extend(self.next_airings(anime).await?);
+
+        releases.retain(|release| release.expected_at <= until);
+        releases.sort_by_key(|release| release.expected_at);
+        releases.truncate(MAX_RELEASES);
+        Ok(releases)
+    }
+
+    /// Returns the next announced episode of each AniList anime a guild follows, soonest
+    /// first. Paused subscriptions are left out.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + 1 per subscription
+    /// * API calls: 1 per 50 AniList feeds
+    pub async fn upcoming_airings(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<UpcomingRelease>, ServiceError> {
+        // DB 2 + 1 per subscription
+        let anime = self
+            .active_feeds(guild_id)
+            .await?
+            .into_iter()
+            .filter(|feed| feed.platform_id == self.anilist.get_id())
+            .collect();
+        // API 1 per 50 AniList feeds
+        let mut airings = self.next_airings(anime).await?;
+        airings.sort_by_key(|release| release.expected_at);
+        Ok(airings)
+    }
+
+    /// Returns the feeds of a guild's subscriptions that are not paused.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + 1 per subscription
+    async fn active_feeds(&self, guild_id: u64) -> Result<Vec<FeedEntity>, ServiceError> {
+        // DB 1
+        let Some(subscriber) = self
+            .subscriber
+            .select_by_type_and_target(&SubscriberType::Guild, &guild_id.to_string())
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut feeds = Vec::new();
+        // DB 1
+        for subscription in self
+            .feed_subscription
+            .select_all_by_subscriber_id(subscriber.id)
+            .await?
+        {
+            if subscription.paused {
+                continue;
+            }
+            // DB 1 per subscription
+            if let Some(feed) = self.feed.select(&subscription.feed_id).await? {
+                feeds.push(feed);
+            }
+        }
+        Ok(feeds)
+    }
+
+    /// Returns when the next episode of each AniList feed airs, for those with one
+    /// announced.
+    ///
+    /// # Performance
+    /// * API calls: 1 per 50 feeds
+    async fn next_airings(
+        &self,
+        anime: Vec<FeedEntity>,
+    ) -> Result<Vec<UpcomingRelease>, ServiceError> {
+        let anime: Vec<(i32, FeedEntity)> = anime
+            .into_iter()
+            .filter_map(|feed| Some((feed.source_id.parse::<i32>().ok()?, feed)))
+            .collect();
+        if anime.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i32> = anime.iter().map(|(media_id, _)| *media_id).collect();
+        // API 1 per 50 feeds
+        let mut airing = self.anilist.fetch_next_airing(&ids).await?;
+        let item_name = &self.anilist.get_info().feed_item_name;
+        Ok(anime
+            .into_iter()
+            .filter_map(|(media_id, feed)| {
+                let next = airing.remove(&media_id)?;
+                Some(UpcomingRelease {
+                    feed_name: feed.name,
+                    source_url: feed.source_url,
+                    item_name: Some(format!("{item_name} {}", next.episode)),
+                    expected_at: next.airing_at,
+                    estimated: false,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Estimates when a feed releases next from when its items were published: the latest
+/// release plus the median gap between recent releases.
+///
+/// Items published within an hour of each other, such as several chapters uploaded at
+/// once, count as one release. Returns `None` without enough history, or when the feed is
+/// more than a gap overdue and has likely stopped or changed its schedule.
+pub fn estimate_next_release(
+    published: &[DateTime<Utc>],
+    now: &DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let mut published = published.to_vec();
+    published.sort();
+    // Items within an hour of the previous one belong to the same release
+    let mut releases: Vec<DateTime<Utc>> = Vec::new();
+    let mut previous: Option<DateTime<Utc>> = None;
+    for at in published {
+        if previous.is_none_or(|previous| at - previous >= Duration::hours(1)) {
+            releases.push(at);
+        }
+        previous = Some(at);
+    }
+    let recent = &releases[releases.len().saturating_sub(CADENCE_RELEASES + 1)..];
+
+    let mut gaps: Vec<Duration> = recent.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    if gaps.len() < MIN_CADENCE_GAPS {
+        return None;
+    }
+    gaps.sort();
+    let median = gaps[gaps.len() / 2];
+
+    let next = *recent.last()? + median;
+    if next + median < *now {
+        return None;
+    }
+    Some(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn days_ago(now: &DateTime<Utc>, days: &[i64]) -> Vec<DateTime<Utc>> {
+        days.iter().map(|d| *now - Duration::days(*d)).collect()
+    }
+
+    #[test]
+    fn estimate_next_release_uses_median_gap() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        // Weekly, with one late release
+        let published = days_ago(&now, &[2, 9, 16, 26, 33]);
+
+        let next = estimate_next_release(&published, &now);
+
+        assert_eq!(next, Some(now + Duration::days(5)));
+    }
+
+    #[test]
+    fn estimate_next_release_merges_batch_uploads() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let mut published = days_ago(&now, &[1, 8, 15]);
+        published.push(now - Duration::days(1) - Duration::minutes(5));
+        published.push(now - Duration::days(8) - Duration::minutes(10));
+
+        let next = estimate_next_release(&published, &now);
+
+        assert_eq!(next, Some(now + Duration::days(6)));
+    }
+
+    #[test]
+    fn estimate_next_release_skips_stale_or_short_history() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+
+        assert_eq!(estimate_next_release(&days_ago(&now, &[3, 10]), &now), None);
+        assert_eq!(
+            estimate_next_release(&days_ago(&now, &[30, 37, 44]), &now),
+            None
+        );
+    }
+}
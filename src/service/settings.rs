@@ -1,10 +1,17 @@
 //! Server settings service for centralized settings management.
 
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::RwLock;
+
+use chrono::Utc;
+use serde_json::Value;
 
 use crate::entity::Json;
 use crate::entity::ServerSettings;
 use crate::entity::ServerSettingsEntity;
+use crate::entity::SettingsAuditEntity;
 use crate::repo::traits::*;
 use crate::service::error::ServiceError;
 use crate::service::traits::SettingsProvider;
@@ -22,18 +29,80 @@ impl SettingsProvider for SettingsService {
     ) -> Result<(), ServiceError> {
         self.update_server_settings(guild_id, settings).await
     }
+
+    async fn update_server_settings_by(
+        &self,
+        guild_id: u64,
+        settings: ServerSettings,
+        changed_by: u64,
+    ) -> Result<(), ServiceError> {
+        self.update_server_settings_by(guild_id, settings, changed_by)
+            .await
+    }
+
+    async fn get_disabled_commands(&self, guild_id: u64) -> Result<Vec<String>, ServiceError> {
+        self.get_disabled_commands(guild_id).await
+    }
+
+    async fn count_settings_history(&self, guild_id: u64) -> Result<u32, ServiceError> {
+        self.count_settings_history(guild_id).await
+    }
+
+    async fn get_settings_history(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<SettingsAuditEntity>, ServiceError> {
+        self.get_settings_history(guild_id, page, per_page).await
+    }
+}
+
+/// Disabled commands of each guild cached in memory, so commands don't each cost a
+/// settings lookup.
+///
+/// Entries are filled on first use and refreshed whenever the settings are saved.
+#[derive(Default)]
+pub struct DisabledCommandsCache {
+    disabled: RwLock<HashMap<u64, Vec<String>>>,
+}
+
+impl DisabledCommandsCache {
+    /// Returns the cached disabled commands of a guild, if they have been looked up.
+    pub fn get(&self, guild_id: u64) -> Option<Vec<String>> {
+        self.disabled.read().ok()?.get(&guild_id).cloned()
+    }
+
+    /// Stores the current disabled commands of a guild.
+    pub fn set(&self, guild_id: u64, commands: Vec<String>) {
+        if let Ok(mut disabled) = self.disabled.write() {
+            disabled.insert(guild_id, commands);
+        }
+    }
 }
 
 /// Service for managing server settings.
 /// Provides a single source of truth for all server configuration.
 pub struct SettingsService {
     server_settings: Arc<dyn ServerSettingsRepository + Send + Sync>,
+    audit: Option<Arc<dyn SettingsAuditRepository + Send + Sync>>,
+    disabled_commands: DisabledCommandsCache,
 }
 
 impl SettingsService {
     /// Creates a new settings service.
     pub fn new(server_settings: Arc<dyn ServerSettingsRepository + Send + Sync>) -> Self {
-        Self { server_settings }
+        Self {
+            server_settings,
+            audit: None,
+            disabled_commands: DisabledCommandsCache::default(),
+        }
+    }
+
+    /// Records every settings change into the given audit table.
+    pub fn with_audit(mut self, audit: Arc<dyn SettingsAuditRepository + Send + Sync>) -> Self {
+        self.audit = Some(audit);
+        self
     }
 
     /// Retrieves server settings for a guild.
@@ -51,18 +120,243 @@ impl SettingsService {
 
     /// Updates server settings for a guild.
     ///
+    /// Changes are audited without an author.
+    ///
     /// # Performance
-    /// * DB calls: 1
+    /// * DB calls: 1, or 2 + one per changed setting when auditing
     pub async fn update_server_settings(
         &self,
         guild_id: u64,
         settings: ServerSettings,
     ) -> Result<(), ServiceError> {
+        self.save(guild_id, settings, None).await
+    }
+
+    /// Updates server settings for a guild on behalf of a user.
+    ///
+    /// # Performance
+    /// * DB calls: 1, or 2 + one per changed setting when auditing
+    pub async fn update_server_settings_by(
+        &self,
+        guild_id: u64,
+        settings: ServerSettings,
+        changed_by: u64,
+    ) -> Result<(), ServiceError> {
+        self.save(guild_id, settings, Some(changed_by)).await
+    }
+
+    /// Returns the commands a guild turned off, from memory after the first lookup.
+    ///
+    /// # Performance
+    /// * DB calls: 0, or 1 on the guild's first lookup
+    pub async fn get_disabled_commands(&self, guild_id: u64) -> Result<Vec<String>, ServiceError> {
+        if let Some(disabled) = self.disabled_commands.get(guild_id) {
+            return Ok(disabled);
+        }
+        // DB 1
+        let settings = self.get_server_settings(guild_id).await?;
+        let disabled = settings.general.disabled_commands.unwrap_or_default();
+        self.disabled_commands.set(guild_id, disabled.clone());
+        Ok(disabled)
+    }
+
+    /// Counts recorded settings changes for a guild.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn count_settings_history(&self, guild_id: u64) -> Result<u32, ServiceError> {
+        match &self.audit {
+            Some(audit) => Ok(audit.count_by_guild_id(guild_id).await?),
+            None => Ok(0),
+        }
+    }
+
+    /// Returns a page of recorded settings changes for a guild, newest first.
+    ///
+    /// `page` starts at 1.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_settings_history(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<SettingsAuditEntity>, ServiceError> {
+        match &self.audit {
+            Some(audit) => Ok(audit
+                .select_paginated_by_guild_id(guild_id, page.saturating_sub(1), per_page)
+                .await?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn save(
+        &self,
+        guild_id: u64,
+        settings: ServerSettings,
+        changed_by: Option<u64>,
+    ) -> Result<(), ServiceError> {
+        let Some(audit) = &self.audit else {
+            // DB 1
+            return self.replace(guild_id, settings).await;
+        };
+
+        // DB 1
+        let old = self.get_server_settings(guild_id).await?;
+        let changes = diff_settings(&old, &settings);
+
+        // DB 2
+        self.replace(guild_id, settings).await?;
+
+        let changed_at = Utc::now();
+        for (key, old_value, new_value) in changes {
+            let entry = SettingsAuditEntity {
+                guild_id: guild_id.into(),
+                changed_by: changed_by.map(Into::into),
+                key,
+                old_value,
+                new_value,
+                changed_at,
+                ..Default::default()
+            };
+            // DB 3..
+            audit.insert(&entry).await?;
+        }
+        Ok(())
+    }
+
+    /// Stores the settings and refreshes the cached disabled commands.
+    async fn replace(&self, guild_id: u64, settings: ServerSettings) -> Result<(), ServiceError> {
+        let disabled = settings.general.disabled_commands.clone();
         let model = ServerSettingsEntity {
             guild_id: guild_id.into(),
             settings: Json(settings),
         };
         self.server_settings.replace(&model).await?;
+        self.disabled_commands
+            .set(guild_id, disabled.unwrap_or_default());
         Ok(())
     }
 }
+
+/// Returns `(key, old, new)` for every leaf setting that differs, keyed by dotted path.
+fn diff_settings(
+    old: &ServerSettings,
+    new: &ServerSettings,
+) -> Vec<(String, Option<String>, Option<String>)> {
+    let mut old_leaves = BTreeMap::new();
+    let mut new_leaves = BTreeMap::new();
+    flatten(
+        "",
+        &serde_json::to_value(old).unwrap_or_default(),
+        &mut old_leaves,
+    );
+    flatten(
+        "",
+        &serde_json::to_value(new).unwrap_or_default(),
+        &mut new_leaves,
+    );
+
+    let mut keys: Vec<&String> = old_leaves.keys().chain(new_leaves.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let old_value = old_leaves.get(key).cloned().flatten();
+            let new_value = new_leaves.get(key).cloned().flatten();
+            (old_value != new_value).then(|| (key.clone(), old_value, new_value))
+        })
+        .collect()
+}
+
+/// Flattens nested JSON objects into dotted keys. Arrays and scalars are leaves.
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Option<String>>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&key, value, out);
+            }
+        }
+        Value::Null => {
+            out.insert(prefix.to_string(), None);
+        }
+        Value::String(s) => {
+            out.insert(prefix.to_string(), Some(s.clone()));
+        }
+        other => {
+            out.insert(prefix.to_string(), Some(other.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_commands_cache_overwrites_entries() {
+        let cache = DisabledCommandsCache::default();
+        assert_eq!(cache.get(1), None);
+
+        cache.set(1, vec!["vc".to_string()]);
+        cache.set(1, vec![]);
+
+        assert_eq!(cache.get(1), Some(vec![]));
+        assert_eq!(cache.get(2), None);
+    }
+
+    #[test]
+    fn diff_settings_reports_changed_leaves() {
+        let old = ServerSettings::default();
+        let mut new = ServerSettings::default();
+        new.feeds.channel_id = Some("123".to_string());
+        new.voice.enabled = Some(false);
+
+        let changes = diff_settings(&old, &new);
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "feeds.channel_id".to_string(),
+                    None,
+                    Some("123".to_string())
+                ),
+                ("voice.enabled".to_string(), None, Some("false".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_settings_ignores_unchanged() {
+        let mut settings = ServerSettings::default();
+        settings.welcome.messages = Some(vec!["hi".to_string()]);
+
+        assert!(diff_settings(&settings, &settings.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_settings_renders_lists_as_json() {
+        let old = ServerSettings::default();
+        let mut new = ServerSettings::default();
+        new.welcome.messages = Some(vec!["hi".to_string()]);
+
+        let changes = diff_settings(&old, &new);
+
+        assert_eq!(
+            changes,
+            vec![(
+                "welcome.messages".to_string(),
+                None,
+                Some(r#"["hi"]"#.to_string())
+            )]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! In-memory search index of each subscriber's feeds, used by subscription autocomplete.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use dashmap::DashMap;
+
+use crate::entity::FeedEntity;
+use crate::entity::TitleLanguage;
+use crate::service::feed_subscription::SubscriberTarget;
+
+/// A subscriber's feeds with the start of every word of their titles, sorted for
+/// prefix lookups.
+#[derive(Debug, Default)]
+pub struct IndexedFeeds {
+    /// Feeds named by the subscriber's preferred title, ordered by that name.
+    feeds: Vec<FeedEntity>,
+    /// Lowercase title suffixes starting at a word, with the index of their feed.
+    keys: Vec<(String, usize)>,
+}
+
+impl IndexedFeeds {
+    /// Indexes every title of `feeds`, naming each by its title in `language`.
+    pub fn new(feeds: Vec<FeedEntity>, language: TitleLanguage) -> Self {
+        let mut feeds: Vec<FeedEntity> = feeds
+            .into_iter()
+            .map(|mut feed| {
+                feed.name = feed.title(language).to_string();
+                feed
+            })
+            .collect();
+        feeds.sort_by_cached_key(|feed| feed.name.to_lowercase());
+
+        let mut keys = Vec::new();
+        for (i, feed) in feeds.iter().enumerate() {
+            let titles = [
+                Some(&feed.name),
+                feed.title_english.as_ref(),
+                feed.title_romanized.as_ref(),
+                feed.title_native.as_ref(),
+            ];
+            for title in titles.into_iter().flatten() {
+                let title = title.to_lowercase();
+                for start in word_starts(&title) {
+                    keys.push((title[start..].to_string(), i));
+                }
+            }
+        }
+        keys.sort();
+        keys.dedup();
+
+        Self { feeds, keys }
+    }
+
+    /// Returns up to `limit` feeds with a word of any title starting with `partial`,
+    /// ignoring case, ordered by name.
+    pub fn search(&self, partial: &str, limit: usize) -> Vec<FeedEntity> {
+        let partial = partial.trim().to_lowercase();
+        let start = self
+            .keys
+            .partition_point(|(key, _)| key.as_str() < partial.as_str());
+
+        let mut matches: Vec<usize> = self.keys[start..]
+            .iter()
+            .take_while(|(key, _)| key.starts_with(&partial))
+            .map(|(_, i)| *i)
+            .collect();
+        matches.sort_unstable();
+        matches.dedup();
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|i| self.feeds[i].clone())
+            .collect()
+    }
+}
+
+/// Byte offsets of the words in `title`: its start and each character after a space or
+/// punctuation.
+fn word_starts(title: &str) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut at_boundary = true;
+    for (i, c) in title.char_indices() {
+        let is_separator = c.is_whitespace() || c.is_ascii_punctuation();
+        if at_boundary && !is_separator {
+            starts.push(i);
+        }
+        at_boundary = is_separator;
+    }
+    starts
+}
+
+/// Indexed feeds of each subscriber, built on first search and dropped when the
+/// subscriber's subscriptions or preferences change.
+#[derive(Debug, Default)]
+pub struct SubscriptionIndex {
+    entries: DashMap<SubscriberTarget, Arc<IndexedFeeds>>,
+    /// Bumped on every invalidation, so an index built from a stale read is not kept.
+    generation: AtomicU64,
+}
+
+impl SubscriptionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of a subscriber, if built.
+    pub fn get(&self, target: &SubscriberTarget) -> Option<Arc<IndexedFeeds>> {
+        self.entries.get(target).map(|entry| entry.clone())
+    }
+
+    /// Returns the generation to pass to [`Self::insert`] once an index is built.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Keeps the index of a subscriber unless it was invalidated since `generation`.
+    pub fn insert(&self, target: SubscriberTarget, feeds: Arc<IndexedFeeds>, generation: u64) {
+        if self.generation() == generation {
+            self.entries.insert(target, feeds);
+        }
+    }
+
+    /// Drops the index of a subscriber, e.g. after it subscribed to a feed.
+    pub fn invalidate(&self, target: &SubscriberTarget) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.entries.remove(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::SubscriberType;
+
+    fn feed(name: &str, english: Option<&str>) -> FeedEntity {
+        FeedEntity {
+            name: name.to_string(),
+            title_english: english.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn names(feeds: Vec<FeedEntity>) -> Vec<String> {
+        feeds.into_iter().map(|feed| feed.name).collect()
+    }
+
+    #[test]
+    fn search_matches_word_starts_of_any_title() {
+        let index = IndexedFeeds::new(
+            vec![
+                feed("One Piece", None),
+                feed("Shingeki no Kyojin", Some("Attack on Titan")),
+                feed("One-Punch Man", None),
+            ],
+            TitleLanguage::Default,
+        );
+
+        assert_eq!(
+            names(index.search("one", 25)),
+            vec!["One Piece", "One-Punch Man"]
+        );
+        assert_eq!(names(index.search(" PUNCH", 25)), vec!["One-Punch Man"]);
+        assert_eq!(names(index.search("tit", 25)), vec!["Shingeki no Kyojin"]);
+        assert!(index.search("iece", 25).is_empty());
+        assert_eq!(index.search("o", 1).len(), 1);
+    }
+
+    #[test]
+    fn search_names_feeds_by_preferred_title() {
+        let index = IndexedFeeds::new(
+            vec![feed("Shingeki no Kyojin", Some("Attack on Titan"))],
+            TitleLanguage::English,
+        );
+
+        assert_eq!(names(index.search("shingeki", 25)), vec!["Attack on Titan"]);
+    }
+
+    #[test]
+    fn insert_is_dropped_after_invalidation() {
+        let index = SubscriptionIndex::new();
+        let target = SubscriberTarget {
+            subscriber_type: SubscriberType::Dm,
+            target_id: "1".to_string(),
+        };
+
+        let generation = index.generation();
+        index.invalidate(&target);
+        index.insert(target.clone(), Arc::default(), generation);
+        assert!(index.get(&target).is_none());
+
+        index.insert(target.clone(), Arc::default(), index.generation());
+        assert!(index.get(&target).is_some());
+    }
+}
@@ -0,0 +1,200 @@
+//! Server subscriptions requested by members and approved by admins.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::entity::DbU64;
+use crate::entity::PendingSubscriptionEntity;
+use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberType;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::feed_subscription::SubscribeResult;
+use crate::service::feed_subscription::SubscriberTarget;
+use crate::service::traits::FeedSubscriptionProvider;
+use crate::service::traits::SubscriptionRequestProvider;
+
+#[async_trait::async_trait]
+impl SubscriptionRequestProvider for SubscriptionRequestService {
+    async fn request(
+        &self,
+        guild_id: u64,
+        source_url: &str,
+        requested_by: u64,
+    ) -> Result<RequestResult, ServiceError> {
+        self.request(guild_id, source_url, requested_by).await
+    }
+
+    async fn list_requests(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<PendingSubscriptionEntity>, ServiceError> {
+        self.list_requests(guild_id).await
+    }
+
+    async fn approve(
+        &self,
+        guild_id: u64,
+        request_id: i32,
+    ) -> Result<Option<(PendingSubscriptionEntity, SubscribeResult)>, ServiceError> {
+        self.approve(guild_id, request_id).await
+    }
+
+    async fn deny(
+        &self,
+        guild_id: u64,
+        request_id: i32,
+    ) -> Result<Option<PendingSubscriptionEntity>, ServiceError> {
+        self.deny(guild_id, request_id).await
+    }
+}
+
+/// Outcome of a member requesting a server subscription.
+pub enum RequestResult {
+    /// The request now waits for an admin.
+    Requested { request: PendingSubscriptionEntity },
+    /// Someone already requested the feed for this server.
+    AlreadyRequested { request: PendingSubscriptionEntity },
+    /// The server is already subscribed to the feed.
+    AlreadySubscribed,
+}
+
+/// Service keeping server subscription requests until an admin approves or denies them.
+pub struct SubscriptionRequestService {
+    requests: Arc<dyn PendingSubscriptionsRepository + Send + Sync>,
+    feeds: Arc<dyn FeedSubscriptionProvider>,
+}
+
+impl SubscriptionRequestService {
+    /// Creates a new subscription request service.
+    pub fn new(
+        requests: Arc<dyn PendingSubscriptionsRepository + Send + Sync>,
+        feeds: Arc<dyn FeedSubscriptionProvider>,
+    ) -> Self {
+        Self { requests, feeds }
+    }
+
+    /// Records a member's request to subscribe the server to `source_url`. An earlier
+    /// request for the same feed is kept as is.
+    ///
+    /// # Performance
+    /// * DB calls: 3-5
+    pub async fn request(
+        &self,
+        guild_id: u64,
+        source_url: &str,
+        requested_by: u64,
+    ) -> Result<RequestResult, ServiceError> {
+        // DB 1
+        if let Some(request) = self
+            .requests
+            .select_by_guild_and_url(guild_id, source_url)
+            .await?
+        {
+            return Ok(RequestResult::AlreadyRequested { request });
+        }
+
+        // DB 2-4
+        let subscriber = self.guild_subscriber(guild_id).await?;
+        if self
+            .feeds
+            .get_subscription(source_url, &subscriber)
+            .await?
+            .is_some()
+        {
+            return Ok(RequestResult::AlreadySubscribed);
+        }
+
+        let mut request = PendingSubscriptionEntity {
+            guild_id: DbU64::from(guild_id),
+            source_url: source_url.to_string(),
+            requested_by: DbU64::from(requested_by),
+            requested_at: Utc::now(),
+            ..Default::default()
+        };
+        // DB 5
+        request.id = self.requests.insert(&request).await?;
+        Ok(RequestResult::Requested { request })
+    }
+
+    /// Returns a guild's pending requests, oldest first.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn list_requests(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<PendingSubscriptionEntity>, ServiceError> {
+        // DB 1
+        Ok(self.requests.select_all_by_guild_id(guild_id).await?)
+    }
+
+    /// Subscribes the server to a requested feed on behalf of the requester, then drops
+    /// the request. Returns `None` if the request no longer exists. If subscribing
+    /// fails, e.g. at the subscription limit, the request is kept.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + subscribing
+    pub async fn approve(
+        &self,
+        guild_id: u64,
+        request_id: i32,
+    ) -> Result<Option<(PendingSubscriptionEntity, SubscribeResult)>, ServiceError> {
+        // DB 1
+        let Some(request) = self.select_in_guild(guild_id, request_id).await? else {
+            return Ok(None);
+        };
+        let subscriber = self.guild_subscriber(guild_id).await?;
+        let result = self
+            .feeds
+            .subscribe_added_by(
+                &request.source_url,
+                &subscriber,
+                Some(request.requested_by.into()),
+            )
+            .await?;
+        // DB 2
+        self.requests.delete(&request.id).await?;
+        Ok(Some((request, result)))
+    }
+
+    /// Drops a request without subscribing. Returns `None` if it no longer exists.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn deny(
+        &self,
+        guild_id: u64,
+        request_id: i32,
+    ) -> Result<Option<PendingSubscriptionEntity>, ServiceError> {
+        // DB 1
+        let Some(request) = self.select_in_guild(guild_id, request_id).await? else {
+            return Ok(None);
+        };
+        // DB 2
+        self.requests.delete(&request.id).await?;
+        Ok(Some(request))
+    }
+
+    /// Returns a request if it belongs to the guild.
+    async fn select_in_guild(
+        &self,
+        guild_id: u64,
+        request_id: i32,
+    ) -> Result<Option<PendingSubscriptionEntity>, ServiceError> {
+        Ok(self
+            .requests
+            .select(&request_id)
+            .await?
+            .filter(|request| u64::from(request.guild_id) == guild_id))
+    }
+
+    async fn guild_subscriber(&self, guild_id: u64) -> Result<SubscriberEntity, ServiceError> {
+        let target = SubscriberTarget {
+            subscriber_type: SubscriberType::Guild,
+            target_id: guild_id.to_string(),
+        };
+        self.feeds.get_or_create_subscriber(&target).await
+    }
+}
@@ -0,0 +1,312 @@
+//! Per-guild tags: named text responses defined by server admins.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::entity::DbU64;
+use crate::entity::TagEntity;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::TagProvider;
+
+/// Longest accepted tag name, in characters.
+pub const MAX_TAG_NAME_LEN: usize = 32;
+
+/// Longest accepted tag content, in characters. Matches Discord's message limit.
+pub const MAX_TAG_CONTENT_LEN: usize = 2000;
+
+/// Maximum number of tags a guild can define.
+pub const MAX_TAGS_PER_GUILD: u32 = 200;
+
+/// Maximum number of tags returned by a name search.
+const SEARCH_LIMIT: u32 = 25;
+
+#[async_trait::async_trait]
+impl TagProvider for TagService {
+    async fn get_tag(&self, guild_id: u64, name: &str) -> Result<Option<TagEntity>, ServiceError> {
+        self.get_tag(guild_id, name).await
+    }
+
+    async fn use_tag(&self, guild_id: u64, name: &str) -> Result<TagEntity, ServiceError> {
+        self.use_tag(guild_id, name).await
+    }
+
+    async fn create_tag(
+        &self,
+        guild_id: u64,
+        name: &str,
+        content: &str,
+        embed: bool,
+        created_by: u64,
+    ) -> Result<TagEntity, ServiceError> {
+        self.create_tag(guild_id, name, content, embed, created_by)
+            .await
+    }
+
+    async fn edit_tag(
+        &self,
+        guild_id: u64,
+        name: &str,
+        content: Option<&str>,
+        embed: Option<bool>,
+    ) -> Result<TagEntity, ServiceError> {
+        self.edit_tag(guild_id, name, content, embed).await
+    }
+
+    async fn delete_tag(&self, guild_id: u64, name: &str) -> Result<bool, ServiceError> {
+        self.delete_tag(guild_id, name).await
+    }
+
+    async fn count_tags(&self, guild_id: u64) -> Result<u32, ServiceError> {
+        self.count_tags(guild_id).await
+    }
+
+    async fn list_tags(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<TagEntity>, ServiceError> {
+        self.list_tags(guild_id, page, per_page).await
+    }
+
+    async fn search_tags(
+        &self,
+        guild_id: u64,
+        partial: &str,
+    ) -> Result<Vec<TagEntity>, ServiceError> {
+        self.search_tags(guild_id, partial).await
+    }
+}
+
+/// Service managing per-guild tags and their usage counters.
+pub struct TagService {
+    tags: Arc<dyn TagsRepository + Send + Sync>,
+}
+
+impl TagService {
+    /// Creates a new tag service.
+    pub fn new(tags: Arc<dyn TagsRepository + Send + Sync>) -> Self {
+        Self { tags }
+    }
+
+    /// Returns a tag by name, if it exists.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_tag(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<Option<TagEntity>, ServiceError> {
+        let name = normalize_tag_name(name)?;
+        Ok(self.tags.select_by_name(guild_id, &name).await?)
+    }
+
+    /// Returns a tag by name and increments its usage counter.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn use_tag(&self, guild_id: u64, name: &str) -> Result<TagEntity, ServiceError> {
+        // DB 1
+        let mut tag = self.require_tag(guild_id, name).await?;
+        // DB 2
+        tag.uses = self.tags.increment_uses(tag.id).await?;
+        Ok(tag)
+    }
+
+    /// Creates a new tag.
+    ///
+    /// # Performance
+    /// * DB calls: 3
+    pub async fn create_tag(
+        &self,
+        guild_id: u64,
+        name: &str,
+        content: &str,
+        embed: bool,
+        created_by: u64,
+    ) -> Result<TagEntity, ServiceError> {
+        let name = normalize_tag_name(name)?;
+        let content = validate_tag_content(content)?;
+
+        // DB 1
+        if self.tags.select_by_name(guild_id, &name).await?.is_some() {
+            return Err(ServiceError::TagAlreadyExists { name });
+        }
+        // DB 2
+        if self.tags.count_by_guild_id(guild_id).await? >= MAX_TAGS_PER_GUILD {
+            return Err(ServiceError::TagLimitReached {
+                limit: MAX_TAGS_PER_GUILD,
+            });
+        }
+
+        let now = Utc::now();
+        let mut tag = TagEntity {
+            guild_id: DbU64::from(guild_id),
+            name,
+            content,
+            embed,
+            created_by: Some(DbU64::from(created_by)),
+            created_at: now,
+            updated_at: now,
+            ..Default::default()
+        };
+        // DB 3
+        tag.id = self.tags.insert(&tag).await?;
+        Ok(tag)
+    }
+
+    /// Updates a tag's content and/or embed flag.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn edit_tag(
+        &self,
+        guild_id: u64,
+        name: &str,
+        content: Option<&str>,
+        embed: Option<bool>,
+    ) -> Result<TagEntity, ServiceError> {
+        let content = content.map(validate_tag_content).transpose()?;
+
+        // DB 1
+        let mut tag = self.require_tag(guild_id, name).await?;
+        if let Some(content) = content {
+            tag.content = content;
+        }
+        if let Some(embed) = embed {
+            tag.embed = embed;
+        }
+        tag.updated_at = Utc::now();
+
+        // DB 2
+        self.tags.update(&tag).await?;
+        Ok(tag)
+    }
+
+    /// Deletes a tag. Returns whether it existed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn delete_tag(&self, guild_id: u64, name: &str) -> Result<bool, ServiceError> {
+        let name = normalize_tag_name(name)?;
+        Ok(self.tags.delete_by_name(guild_id, &name).await?)
+    }
+
+    /// Returns the number of tags in a guild.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn count_tags(&self, guild_id: u64) -> Result<u32, ServiceError> {
+        Ok(self.tags.count_by_guild_id(guild_id).await?)
+    }
+
+    /// Returns a page of a guild's tags, most used first. Pages start at 1.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn list_tags(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<TagEntity>, ServiceError> {
+        Ok(self
+            .tags
+            .select_paginated_by_guild_id(guild_id, page.saturating_sub(1), per_page)
+            .await?)
+    }
+
+    /// Returns tags whose name contains the given text, for autocomplete.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn search_tags(
+        &self,
+        guild_id: u64,
+        partial: &str,
+    ) -> Result<Vec<TagEntity>, ServiceError> {
+        Ok(self
+            .tags
+            .select_by_name_search(guild_id, partial.trim(), SEARCH_LIMIT)
+            .await?)
+    }
+
+    /// Looks up a tag by name, failing with [`ServiceError::TagNotFound`] if missing.
+    async fn require_tag(&self, guild_id: u64, name: &str) -> Result<TagEntity, ServiceError> {
+        let name = normalize_tag_name(name)?;
+        self.tags
+            .select_by_name(guild_id, &name)
+            .await?
+            .ok_or(ServiceError::TagNotFound { name })
+    }
+}
+
+/// Normalizes a tag name to lowercase and checks it is a valid single word.
+///
+/// Names may contain letters, digits, `-` and `_`, up to [`MAX_TAG_NAME_LEN`] characters.
+pub fn normalize_tag_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(ServiceError::InvalidTag(
+            "the name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_TAG_NAME_LEN {
+        return Err(ServiceError::InvalidTag(format!(
+            "the name cannot be longer than {MAX_TAG_NAME_LEN} characters"
+        )));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ServiceError::InvalidTag(
+            "the name can only contain letters, numbers, `-` and `_`".to_string(),
+        ));
+    }
+    Ok(name)
+}
+
+/// Trims tag content and checks it fits in a single message.
+fn validate_tag_content(content: &str) -> Result<String, ServiceError> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(ServiceError::InvalidTag(
+            "the content cannot be empty".to_string(),
+        ));
+    }
+    if content.chars().count() > MAX_TAG_CONTENT_LEN {
+        return Err(ServiceError::InvalidTag(format!(
+            "the content cannot be longer than {MAX_TAG_CONTENT_LEN} characters"
+        )));
+    }
+    Ok(content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_tag_name_lowercases_and_trims() {
+        assert_eq!(normalize_tag_name("  Rules ").unwrap(), "rules");
+        assert_eq!(normalize_tag_name("faq_2-b").unwrap(), "faq_2-b");
+    }
+
+    #[test]
+    fn normalize_tag_name_rejects_invalid_names() {
+        assert!(normalize_tag_name("").is_err());
+        assert!(normalize_tag_name("two words").is_err());
+        assert!(normalize_tag_name("a".repeat(MAX_TAG_NAME_LEN + 1).as_str()).is_err());
+    }
+
+    #[test]
+    fn validate_tag_content_rejects_empty_and_oversized() {
+        assert_eq!(validate_tag_content(" hi \n").unwrap(), "hi");
+        assert!(validate_tag_content("   ").is_err());
+        assert!(validate_tag_content(&"a".repeat(MAX_TAG_CONTENT_LEN + 1)).is_err());
+    }
+}
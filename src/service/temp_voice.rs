@@ -0,0 +1,124 @@
+//! Temporary voice channels created from a guild's hub channel.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::entity::DbU64;
+use crate::entity::TempVoiceChannelEntity;
+use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::traits::TempVoiceProvider;
+
+#[async_trait::async_trait]
+impl TempVoiceProvider for TempVoiceService {
+    async fn add_channel(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        owner_id: u64,
+    ) -> Result<(), ServiceError> {
+        self.add_channel(guild_id, channel_id, owner_id).await
+    }
+
+    async fn get_channel(
+        &self,
+        channel_id: u64,
+    ) -> Result<Option<TempVoiceChannelEntity>, ServiceError> {
+        self.get_channel(channel_id).await
+    }
+
+    async fn list_channels(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<TempVoiceChannelEntity>, ServiceError> {
+        self.list_channels(guild_id).await
+    }
+
+    async fn set_owner(&self, channel_id: u64, owner_id: u64) -> Result<bool, ServiceError> {
+        self.set_owner(channel_id, owner_id).await
+    }
+
+    async fn remove_channel(&self, channel_id: u64) -> Result<(), ServiceError> {
+        self.remove_channel(channel_id).await
+    }
+}
+
+/// Service keeping track of the temporary voice channels the bot created.
+pub struct TempVoiceService {
+    channels: Arc<dyn TempVoiceChannelsRepository + Send + Sync>,
+}
+
+impl TempVoiceService {
+    /// Creates a new temporary voice channel service.
+    pub fn new(channels: Arc<dyn TempVoiceChannelsRepository + Send + Sync>) -> Self {
+        Self { channels }
+    }
+
+    /// Records a channel just created for `owner_id`.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn add_channel(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        owner_id: u64,
+    ) -> Result<(), ServiceError> {
+        let model = TempVoiceChannelEntity {
+            channel_id: DbU64::from(channel_id),
+            guild_id: DbU64::from(guild_id),
+            owner_id: DbU64::from(owner_id),
+            created_at: Utc::now(),
+        };
+        // DB 1
+        self.channels.insert(&model).await?;
+        Ok(())
+    }
+
+    /// Returns a temporary channel, or `None` if the channel is not one.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_channel(
+        &self,
+        channel_id: u64,
+    ) -> Result<Option<TempVoiceChannelEntity>, ServiceError> {
+        Ok(self.channels.select(&channel_id).await?)
+    }
+
+    /// Returns every temporary channel of a guild.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn list_channels(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<TempVoiceChannelEntity>, ServiceError> {
+        Ok(self.channels.select_all_by_guild_id(guild_id).await?)
+    }
+
+    /// Hands a temporary channel to another member. Returns `false` if the channel is
+    /// not one.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn set_owner(&self, channel_id: u64, owner_id: u64) -> Result<bool, ServiceError> {
+        // DB 1
+        let Some(mut channel) = self.channels.select(&channel_id).await? else {
+            return Ok(false);
+        };
+        channel.owner_id = DbU64::from(owner_id);
+        // DB 2
+        self.channels.update(&channel).await?;
+        Ok(true)
+    }
+
+    /// Forgets a temporary channel once it is deleted.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn remove_channel(&self, channel_id: u64) -> Result<(), ServiceError> {
+        Ok(self.channels.delete(&channel_id).await?)
+    }
+}
@@ -0,0 +1,202 @@
+//! Matching MyAnimeList entries to AniList and MangaDex feeds.
+
+use std::sync::Arc;
+
+use log::warn;
+
+use crate::feed::AniListPlatform;
+use crate::feed::MangaDexPlatform;
+use crate::feed::Platform;
+use crate::feed::mal::MalListEntry;
+use crate::feed::platform::mangadex::MangaDexSearchResult;
+use crate::service::error::ServiceError;
+
+/// Feed a MyAnimeList entry was matched to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchedFeed {
+    pub source_url: String,
+    pub title: String,
+    /// Whether new episodes or chapters can still come out.
+    pub ongoing: bool,
+}
+
+/// A MyAnimeList entry and the feed it was matched to, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TitleMatch {
+    pub entry: MalListEntry,
+    pub feed: Option<MatchedFeed>,
+}
+
+/// Service finding the feed of a MyAnimeList anime or manga.
+///
+/// Anime are looked up on AniList by their MyAnimeList ID, which AniList keeps for
+/// nearly every entry. MangaDex has no such lookup, so manga are searched by title and
+/// the result linking back to the MyAnimeList entry wins, else one with the same title.
+/// The AniList sync matches reading list manga the same way.
+pub struct TitleMatchService {
+    anilist: Arc<AniListPlatform>,
+    mangadex: Arc<MangaDexPlatform>,
+}
+
+impl TitleMatchService {
+    pub fn new(anilist: Arc<AniListPlatform>, mangadex: Arc<MangaDexPlatform>) -> Self {
+        Self { anilist, mangadex }
+    }
+
+    /// Matches anime entries to AniList feeds.
+    ///
+    /// # Performance
+    /// * API calls: 1 per 50 entries
+    pub async fn match_anime(
+        &self,
+        entries: &[MalListEntry],
+    ) -> Result<Vec<TitleMatch>, ServiceError> {
+        let ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+        // API 1 per 50 entries
+        let found = self.anilist.fetch_by_mal_ids(&ids).await?;
+
+        Ok(entries
+            .iter()
+            .map(|entry| TitleMatch {
+                entry: entry.clone(),
+                feed: found.get(&entry.id).map(|media| MatchedFeed {
+                    source_url: self
+                        .anilist
+                        .get_source_url_from_id(&media.media_id.to_string()),
+                    title: media.title.clone(),
+                    ongoing: media.airing,
+                }),
+            })
+            .collect())
+    }
+
+    /// Matches manga entries to MangaDex feeds. An entry whose search fails is logged
+    /// and left unmatched.
+    ///
+    /// # Performance
+    /// * API calls: 1 per entry
+    pub async fn match_manga(&self, entries: &[MalListEntry]) -> Vec<TitleMatch> {
+        let mut matches = Vec::with_capacity(entries.len());
+        for entry in entries {
+            // API 1 per entry
+            let feed = match self.mangadex.search_manga(&entry.title).await {
+                Ok(results) => best_manga_match(entry, &results).map(|manga| MatchedFeed {
+                    source_url: self.mangadex.get_source_url_from_id(&manga.id),
+                    title: manga.title.clone(),
+                    ongoing: manga.ongoing,
+                }),
+                Err(e) => {
+                    warn!("Failed to search MangaDex for `{}`: {e}", entry.title);
+                    None
+                }
+            };
+            matches.push(TitleMatch {
+                entry: entry.clone(),
+                feed,
+            });
+        }
+        matches
+    }
+}
+
+/// Picks the search result for a MyAnimeList manga: the first one linking to the entry,
+/// else the first one sharing a title with it. Titles are compared by their letters and
+/// digits only, ignoring case.
+fn best_manga_match<'a>(
+    entry: &MalListEntry,
+    results: &'a [MangaDexSearchResult],
+) -> Option<&'a MangaDexSearchResult> {
+    if let Some(linked) = results.iter().find(|manga| manga.mal_id == Some(entry.id)) {
+        return Some(linked);
+    }
+
+    let entry_titles: Vec<String> = std::iter::once(&entry.title)
+        .chain(&entry.alt_titles)
+        .map(|title| normalize_title(title))
+        .filter(|title| !title.is_empty())
+        .collect();
+    results.iter().find(|manga| {
+        // A result linked to another entry is a different manga with the same name
+        manga.mal_id.is_none()
+            && manga
+                .titles
+                .iter()
+                .any(|title| entry_titles.contains(&normalize_title(title)))
+    })
+}
+
+fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manga(id: &str, titles: &[&str], mal_id: Option<i32>) -> MangaDexSearchResult {
+        MangaDexSearchResult {
+            id: id.to_string(),
+            title: titles[0].to_string(),
+            titles: titles.iter().map(|t| t.to_string()).collect(),
+            mal_id,
+            ongoing: true,
+        }
+    }
+
+    fn entry(id: i32, title: &str, alt_titles: &[&str]) -> MalListEntry {
+        MalListEntry {
+            id,
+            title: title.to_string(),
+            alt_titles: alt_titles.iter().map(|t| t.to_string()).collect(),
+            ongoing: true,
+        }
+    }
+
+    #[test]
+    fn best_manga_match_prefers_the_linked_result() {
+        let results = vec![
+            manga("a", &["Chainsaw Man"], None),
+            manga("b", &["Chainsaw Man (Official Colored)"], Some(116778)),
+        ];
+
+        let matched = best_manga_match(&entry(116778, "Chainsaw Man", &[]), &results);
+
+        assert_eq!(matched.map(|m| m.id.as_str()), Some("b"));
+    }
+
+    #[test]
+    fn best_manga_match_falls_back_to_same_title() {
+        let results = vec![
+            manga("a", &["Sousou no Frieren: Side Story"], None),
+            manga("b", &["Sousou no Frieren"], Some(1)),
+            manga(
+                "c",
+                &["葬送のフリーレン", "Frieren: Beyond Journey's End"],
+                None,
+            ),
+        ];
+
+        let matched = best_manga_match(
+            &entry(
+                126287,
+                "Sousou no Frieren",
+                &["Frieren - Beyond Journey's End"],
+            ),
+            &results,
+        );
+
+        // "b" links to another MyAnimeList entry
+        assert_eq!(matched.map(|m| m.id.as_str()), Some("c"));
+    }
+
+    #[test]
+    fn best_manga_match_rejects_other_titles() {
+        let results = vec![manga("a", &["One Piece Party"], None)];
+
+        assert!(best_manga_match(&entry(13, "One Piece", &[]), &results).is_none());
+    }
+}
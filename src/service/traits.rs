@@ -4,6 +4,8 @@
 //! high-level business rules. They are the only layer that should handle
 //! cross-entity logic and complex validations.
 
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::vec::Vec;
 
 use async_trait::async_trait;
@@ -12,16 +14,26 @@ use chrono::Utc;
 
 use crate::bot::command::voice::GuildStatType;
 use crate::entity::*;
+use crate::feed::mal::MalListKind;
 use crate::repo::error::DatabaseError;
+use crate::service::anilist_sync::AniListSyncResult;
+use crate::service::api::ApiScope;
 use crate::service::error::ServiceError;
+use crate::service::feed_stats::DeliveryAnalytics;
+use crate::service::feed_stats::SubscriberStats;
 use crate::service::feed_subscription::FeedUpdateResult;
+use crate::service::feed_subscription::ReplayItems;
 use crate::service::feed_subscription::SubscribeResult;
 use crate::service::feed_subscription::SubscriberTarget;
 use crate::service::feed_subscription::Subscription;
 use crate::service::feed_subscription::UnsubscribeResult;
 use crate::service::internal::DatabaseDump;
+use crate::service::mal_import::MalImportResult;
+use crate::service::open_sessions::OpenSessions;
+use crate::service::subscription_request::RequestResult;
+use crate::service::voice_import::ImportedVoiceTime;
 
-/// Logic for managing feed subscriptions (AniList, MangaDex, Comick).
+/// Logic for managing feed subscriptions (AniList, MangaDex, Comick, Bluesky, crates.io, Nyaa, podcasts, RSS/Atom feeds).
 #[async_trait]
 pub trait FeedSubscriptionProvider: Send + Sync {
     /// Subscribes a user or guild to a feed by its URL.
@@ -31,6 +43,14 @@ pub trait FeedSubscriptionProvider: Send + Sync {
         subscriber: &SubscriberEntity,
     ) -> Result<SubscribeResult, ServiceError>;
 
+    /// Subscribes like [`Self::subscribe`], recording the member who subscribed a server.
+    async fn subscribe_added_by(
+        &self,
+        url: &str,
+        subscriber: &SubscriberEntity,
+        added_by: Option<u64>,
+    ) -> Result<SubscribeResult, ServiceError>;
+
     /// Returns all feeds tagged with a specific label.
     async fn get_feeds_by_tag(&self, tag: &str) -> Result<Vec<FeedEntity>, ServiceError>;
 
@@ -41,12 +61,13 @@ pub trait FeedSubscriptionProvider: Send + Sync {
         guild_id: Option<String>,
     ) -> (Option<SubscriberEntity>, Option<SubscriberEntity>);
 
-    /// Searches for feeds by name and filters by a subscriber's current follows.
+    /// Searches the feeds a user and a server follow by any of their titles, from an
+    /// in-memory index once built.
     async fn search_and_combine_feeds(
         &self,
         partial: &str,
-        user_subscriber: Option<SubscriberEntity>,
-        guild_subscriber: Option<SubscriberEntity>,
+        user_target: Option<SubscriberTarget>,
+        guild_target: Option<SubscriberTarget>,
     ) -> Vec<FeedEntity>;
 
     /// Polls a platform for the latest item of a feed and updates the database.
@@ -73,7 +94,8 @@ pub trait FeedSubscriptionProvider: Send + Sync {
         subscriber: &SubscriberEntity,
     ) -> Result<u32, ServiceError>;
 
-    /// Searches for feeds within a subscriber's active subscriptions.
+    /// Searches for feeds within a subscriber's active subscriptions, named by the
+    /// subscriber's preferred title.
     async fn search_subcriptions(
         &self,
         subscriber: &SubscriberEntity,
@@ -89,6 +111,18 @@ pub trait FeedSubscriptionProvider: Send + Sync {
         target: &SubscriberTarget,
     ) -> Result<SubscriberEntity, ServiceError>;
 
+    /// Finds an existing subscriber (Guild/DM) without creating it.
+    async fn get_subscriber(
+        &self,
+        target: &SubscriberTarget,
+    ) -> Result<Option<SubscriberEntity>, ServiceError>;
+
+    /// Marks a subscriber as onboarded. Returns whether it was still first seen.
+    async fn take_first_seen(&self, subscriber: &SubscriberEntity) -> Result<bool, ServiceError>;
+
+    /// Finds a feed by its ID.
+    async fn get_feed(&self, feed_id: i32) -> Result<Option<FeedEntity>, ServiceError>;
+
     /// Finds a feed by its source URL.
     async fn get_feed_by_source_url(
         &self,
@@ -105,12 +139,226 @@ pub trait FeedSubscriptionProvider: Send + Sync {
         feed_id: i32,
     ) -> Result<Vec<SubscriberEntity>, ServiceError>;
 
+    /// Returns all subscribers of a specific type that want edit notifications for a feed.
+    async fn get_edit_subscribers_by_type_and_feed(
+        &self,
+        subscriber_type: SubscriberType,
+        feed_id: i32,
+    ) -> Result<Vec<SubscriberEntity>, ServiceError>;
+
     /// Updates the feed settings for a guild.
     async fn update_server_settings(
         &self,
         guild_id: u64,
         settings: ServerSettings,
     ) -> Result<(), ServiceError>;
+
+    /// Builds a synthetic update for a tracked feed without persisting anything.
+    async fn simulate_feed_update(
+        &self,
+        source_url: &str,
+    ) -> Result<FeedUpdateResult, ServiceError>;
+
+    /// Overrides the accent color and emoji of a tracked feed's notifications.
+    async fn set_feed_branding(
+        &self,
+        source_url: &str,
+        accent_color: Option<i32>,
+        emoji: Option<String>,
+    ) -> Result<Option<FeedEntity>, ServiceError>;
+
+    /// Returns the maximum number of subscriptions allowed for a subscriber.
+    fn get_subscription_limit(&self, subscriber: &SubscriberEntity) -> u32;
+
+    /// Returns a subscriber's subscription to a feed, if any.
+    async fn get_subscription(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+    ) -> Result<Option<FeedSubscriptionEntity>, ServiceError>;
+
+    /// Pauses or resumes notifications for a subscription without removing it.
+    async fn set_subscription_paused(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        paused: bool,
+    ) -> Result<bool, ServiceError>;
+
+    /// Sets whether a subscription is notified when announced items are edited.
+    async fn set_subscription_notify_edits(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        notify_edits: bool,
+    ) -> Result<bool, ServiceError>;
+
+    /// Puts a server subscription in a collection, or takes it out with `None`.
+    async fn set_subscription_collection(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        collection_id: Option<i32>,
+    ) -> Result<bool, ServiceError>;
+
+    /// Moves a subscription from one subscriber to another.
+    async fn move_subscription(
+        &self,
+        source_url: &str,
+        from: &SubscriberEntity,
+        to: &SubscriberEntity,
+    ) -> Result<SubscribeResult, ServiceError>;
+
+    /// Overrides the subscription quota for a user or guild. `None` restores the default.
+    async fn set_subscription_quota(
+        &self,
+        target: &SubscriberTarget,
+        limit: Option<u32>,
+    ) -> Result<SubscriberEntity, ServiceError>;
+
+    /// Replaces the notification preferences of a user or guild.
+    async fn set_subscriber_preferences(
+        &self,
+        target: &SubscriberTarget,
+        preferences: SubscriberPreferences,
+    ) -> Result<SubscriberEntity, ServiceError>;
+
+    /// Deletes feed items past the retention window of their feed's subscribers. Returns
+    /// the number deleted.
+    async fn prune_feed_items(&self, now: &DateTime<Utc>) -> Result<u32, ServiceError>;
+
+    /// Returns the platform IDs of stored feeds that no registered platform has, with
+    /// their feed counts.
+    async fn get_unregistered_platforms(&self) -> Result<Vec<(String, u32)>, ServiceError>;
+
+    /// Returns when a feed's stored items were published, oldest first, keeping at most
+    /// the latest `limit`.
+    async fn get_release_history(
+        &self,
+        feed_id: i32,
+        limit: usize,
+    ) -> Result<Vec<DateTime<Utc>>, ServiceError>;
+
+    /// Sets how often the feed publisher polls a feed.
+    async fn set_poll_tier(&self, feed_id: i32, tier: PollTier) -> Result<(), ServiceError>;
+
+    /// Returns a subscribed feed's stored items published after `since`, oldest first and
+    /// at most `limit`. `None` if the subscriber is not subscribed to the feed.
+    async fn get_replay_items(
+        &self,
+        source_url: &str,
+        subscriber: &SubscriberEntity,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Option<ReplayItems>, ServiceError>;
+}
+
+/// Server subscriptions requested by members, waiting for an admin's decision.
+#[async_trait]
+pub trait SubscriptionRequestProvider: Send + Sync {
+    /// Records a member's request to subscribe the server to a feed.
+    async fn request(
+        &self,
+        guild_id: u64,
+        source_url: &str,
+        requested_by: u64,
+    ) -> Result<RequestResult, ServiceError>;
+
+    /// Returns a guild's pending requests, oldest first.
+    async fn list_requests(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<PendingSubscriptionEntity>, ServiceError>;
+
+    /// Subscribes the server to a requested feed and drops the request. Returns `None`
+    /// if the request no longer exists.
+    async fn approve(
+        &self,
+        guild_id: u64,
+        request_id: i32,
+    ) -> Result<Option<(PendingSubscriptionEntity, SubscribeResult)>, ServiceError>;
+
+    /// Drops a request without subscribing. Returns `None` if it no longer exists.
+    async fn deny(
+        &self,
+        guild_id: u64,
+        request_id: i32,
+    ) -> Result<Option<PendingSubscriptionEntity>, ServiceError>;
+}
+
+/// Named groups of a server's subscriptions, each posted in its own channel.
+#[async_trait]
+pub trait FeedCollectionProvider: Send + Sync {
+    /// Creates a collection, or moves an existing one to another channel and role.
+    async fn set_collection(
+        &self,
+        guild_id: u64,
+        name: &str,
+        channel_id: u64,
+        mention_role_id: Option<u64>,
+    ) -> Result<FeedCollectionEntity, ServiceError>;
+
+    /// Deletes a collection. Its subscriptions go back to the server's feed channel.
+    async fn delete_collection(&self, guild_id: u64, name: &str) -> Result<bool, ServiceError>;
+
+    /// Returns a guild's collections, ordered by name.
+    async fn list_collections(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<FeedCollectionEntity>, ServiceError>;
+
+    /// Returns a guild's collection by name, failing if it does not exist.
+    async fn get_collection(
+        &self,
+        guild_id: u64,
+        name: &str,
+    ) -> Result<FeedCollectionEntity, ServiceError>;
+
+    /// Returns the collection a subscription is posted in, if any.
+    async fn collection_for(
+        &self,
+        feed_id: i32,
+        subscriber_id: i32,
+    ) -> Result<Option<FeedCollectionEntity>, ServiceError>;
+}
+
+/// Log of delivered feed notifications and the stats built on it.
+#[async_trait]
+pub trait FeedStatsProvider: Send + Sync {
+    /// Logs a notification of a feed sent to a subscriber, or one that failed.
+    async fn record_delivery(
+        &self,
+        subscriber_id: i32,
+        feed_id: i32,
+        delivered: bool,
+        delay_secs: Option<i32>,
+    ) -> Result<(), ServiceError>;
+
+    /// Summarizes the subscriptions and notifications of a subscriber.
+    async fn subscriber_stats(
+        &self,
+        subscriber: &SubscriberEntity,
+    ) -> Result<SubscriberStats, ServiceError>;
+
+    /// Summarizes every notification of the last `days` days for the bot owner.
+    async fn delivery_analytics(&self, days: u32) -> Result<DeliveryAnalytics, ServiceError>;
+
+    /// Puts a feed update about to be sent to each subscriber in the outbox. Returns
+    /// the outbox ID of each subscriber's notification, by subscriber ID.
+    async fn queue_deliveries(
+        &self,
+        subscriber_ids: &[i32],
+        data: &serde_json::Value,
+    ) -> Result<HashMap<i32, i32>, ServiceError>;
+
+    /// Removes a notification from the outbox once it was sent or failed.
+    async fn finish_delivery(&self, outbox_id: i32) -> Result<(), ServiceError>;
+
+    /// Takes every notification left in the outbox, oldest first.
+    async fn take_undelivered(&self) -> Result<Vec<DeliveryOutboxEntity>, ServiceError>;
+
+    /// Returns the outbox IDs of the notifications not sent yet, oldest first.
+    async fn undelivered_ids(&self) -> Result<Vec<i32>, ServiceError>;
 }
 
 /// Logic for tracking and querying voice channel activity.
@@ -119,9 +367,18 @@ pub trait VoiceTracker: Send + Sync {
     /// Checks if voice tracking is enabled for a specific guild.
     async fn is_enabled(&self, guild_id: u64) -> bool;
 
+    /// Returns the cached voice settings for a guild.
+    async fn voice_settings(&self, guild_id: u64) -> VoiceSettings;
+
+    /// Checks if a voice state should be tracked under the guild's voice settings.
+    async fn should_track(&self, guild_id: u64, is_bot: bool, is_stage: bool) -> bool;
+
     /// Logs a voice session start.
     async fn insert(&self, model: &VoiceSessionsEntity) -> anyhow::Result<()>;
 
+    /// Returns the registry of members currently in a tracked voice channel.
+    fn open_sessions(&self) -> Arc<OpenSessions>;
+
     /// Updates or replaces an existing voice session.
     async fn replace(&self, model: &VoiceSessionsEntity) -> anyhow::Result<()>;
 
@@ -135,6 +392,14 @@ pub trait VoiceTracker: Send + Sync {
         settings: ServerSettings,
     ) -> anyhow::Result<()>;
 
+    /// Updates the voice settings for a guild, recording the user who changed them.
+    async fn update_server_settings_by(
+        &self,
+        guild_id: u64,
+        settings: ServerSettings,
+        changed_by: u64,
+    ) -> anyhow::Result<()>;
+
     /// Returns a leaderboard using custom filter options.
     async fn get_leaderboard_withopt(
         &self,
@@ -148,6 +413,37 @@ pub trait VoiceTracker: Send + Sync {
         target_user_id: u64,
     ) -> anyhow::Result<Vec<VoiceLeaderboardEntry>>;
 
+    /// Returns the voice time per channel in a guild, most used first.
+    async fn get_channel_usage(
+        &self,
+        guild_id: u64,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+    ) -> anyhow::Result<Vec<VoiceChannelUsage>>;
+
+    /// Returns the top members by voice time across the guilds that opted in to the
+    /// global leaderboard.
+    async fn get_global_leaderboard(
+        &self,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<GlobalVoiceLeaderboardEntry>>;
+
+    /// Returns the guilds with a monthly voice goal set.
+    async fn guilds_with_goals(&self) -> Vec<u64>;
+
+    /// Returns the guilds with the AFK mover on.
+    async fn guilds_with_afk_mover(&self) -> Vec<u64>;
+
+    /// Returns a guild's progress toward its monthly voice goal, or `None` if it has no
+    /// goal.
+    async fn get_goal_progress(
+        &self,
+        guild_id: u64,
+        now: &DateTime<Utc>,
+    ) -> anyhow::Result<Option<VoiceGoalProgress>>;
+
     /// Returns the top users by voice time in a guild.
     async fn get_leaderboard(
         &self,
@@ -163,6 +459,98 @@ pub trait VoiceTracker: Send + Sync {
         limit: u32,
     ) -> anyhow::Result<Vec<VoiceLeaderboardEntry>>;
 
+    /// Snapshots the all-time leaderboard of every guild. Returns the number of rows written.
+    async fn take_leaderboard_snapshot(&self, taken_at: &DateTime<Utc>) -> anyhow::Result<u32>;
+
+    /// Returns a guild's most recent leaderboard snapshot, ordered by rank.
+    async fn get_latest_leaderboard_snapshot(
+        &self,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<LeaderboardSnapshotEntity>>;
+
+    /// Returns a guild's snapshots taken since `since`, ordered by time then rank.
+    async fn get_leaderboard_snapshots_since(
+        &self,
+        guild_id: u64,
+        since: &DateTime<Utc>,
+    ) -> anyhow::Result<Vec<LeaderboardSnapshotEntity>>;
+
+    /// Flags improbable sessions that were active since `since` for admin review.
+    /// Returns the number of new flags.
+    async fn flag_suspicious_sessions(&self, since: &DateTime<Utc>) -> anyhow::Result<u32>;
+
+    /// Deletes sessions past each guild's retention window. Returns the number deleted.
+    async fn prune_sessions(&self, now: &DateTime<Utc>) -> anyhow::Result<u32>;
+
+    /// Recomputes the daily voice totals from the sessions. Returns the number of totals
+    /// written.
+    async fn reconcile_daily_totals(&self) -> anyhow::Result<u32>;
+
+    /// Counts a guild's flagged sessions awaiting review.
+    async fn count_open_flags(&self, guild_id: u64) -> anyhow::Result<u32>;
+
+    /// Returns a page of a guild's flagged sessions awaiting review, newest first.
+    async fn get_open_flags(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> anyhow::Result<Vec<VoiceSessionFlag>>;
+
+    /// Keeps a flagged session. Returns whether the flag existed.
+    async fn dismiss_flag(&self, guild_id: u64, flag_id: i32) -> anyhow::Result<bool>;
+
+    /// Deletes a flagged session so it no longer counts anywhere. Returns whether the flag
+    /// existed.
+    async fn invalidate_flagged_session(&self, guild_id: u64, flag_id: i32)
+    -> anyhow::Result<bool>;
+
+    /// Adds voice time for a member, or removes it when `seconds` is negative, without
+    /// touching their sessions. Returns the member's net adjustment afterwards.
+    async fn adjust_voice_time(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        seconds: i64,
+        reason: Option<String>,
+        adjusted_by: u64,
+    ) -> anyhow::Result<i64>;
+
+    /// Adds voice time imported from another bot as one adjustment per member, counted at
+    /// `applies_at`. Returns the number of adjustments added.
+    async fn import_voice_time(
+        &self,
+        guild_id: u64,
+        members: &[ImportedVoiceTime],
+        reason: String,
+        imported_by: u64,
+        applies_at: DateTime<Utc>,
+    ) -> anyhow::Result<u32>;
+
+    /// Moves all voice data of `from_user_id` to `to_user_id` in every guild and records
+    /// the merge. Refused while `from_user_id` is in voice.
+    async fn merge_users(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        merged_by: u64,
+    ) -> anyhow::Result<VoiceAccountMergeEntity>;
+
+    /// Whether voice state changes are recorded as events for the projection instead of
+    /// being written as sessions directly.
+    fn records_voice_events(&self) -> bool;
+
+    /// Appends a voice event to the event log. Returns its ID.
+    async fn record_voice_event(&self, event: &VoiceEventEntity) -> anyhow::Result<i64>;
+
+    /// Applies up to `limit` unprojected voice events to voice sessions. Returns the
+    /// number of events applied.
+    async fn project_voice_events(&self, limit: u32) -> anyhow::Result<u32>;
+
+    /// Recomputes a guild's voice sessions from its recorded events under the current
+    /// settings. Returns the number of events replayed.
+    async fn rebuild_voice_sessions(&self, guild_id: u64) -> anyhow::Result<u32>;
+
     /// Updates the end time for a voice session.
     async fn update_session_leave_time(
         &self,
@@ -172,6 +560,10 @@ pub trait VoiceTracker: Send + Sync {
         leave_time: &DateTime<Utc>,
     ) -> anyhow::Result<()>;
 
+    /// Writes the current time of every open session to the database. Returns the number
+    /// of sessions flushed.
+    async fn flush_open_sessions(&self, flushed_at: &DateTime<Utc>) -> anyhow::Result<u32>;
+
     /// Closes a voice session.
     async fn close_session(
         &self,
@@ -181,9 +573,28 @@ pub trait VoiceTracker: Send + Sync {
         leave_time: &DateTime<Utc>,
     ) -> anyhow::Result<()>;
 
+    /// Closes a voice session, discarding it if shorter than the guild's minimum length.
+    async fn end_session(
+        &self,
+        session: &VoiceSessionsEntity,
+        leave_time: &DateTime<Utc>,
+    ) -> anyhow::Result<()>;
+
     /// Returns all active voice sessions.
     async fn find_active_sessions(&self) -> anyhow::Result<Vec<VoiceSessionsEntity>>;
 
+    /// Continues a member's open session in another channel as a new split of the same
+    /// logical session, or ends it when the channel is not tracked.
+    async fn move_session(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        is_bot: bool,
+        is_stage: bool,
+        moved_at: &DateTime<Utc>,
+    ) -> anyhow::Result<()>;
+
     /// Returns all active voice sessions for a specific user in a guild.
     async fn find_active_sessions_by_user(
         &self,
@@ -231,6 +642,292 @@ pub trait SettingsProvider: Send + Sync {
         guild_id: u64,
         settings: ServerSettings,
     ) -> Result<(), ServiceError>;
+
+    /// Updates settings for a guild, recording the user who changed them.
+    async fn update_server_settings_by(
+        &self,
+        guild_id: u64,
+        settings: ServerSettings,
+        changed_by: u64,
+    ) -> Result<(), ServiceError>;
+
+    /// Returns the commands a guild turned off. Cached, and refreshed when settings are saved.
+    async fn get_disabled_commands(&self, guild_id: u64) -> Result<Vec<String>, ServiceError>;
+
+    /// Counts recorded settings changes for a guild.
+    async fn count_settings_history(&self, guild_id: u64) -> Result<u32, ServiceError>;
+
+    /// Returns a page of recorded settings changes for a guild, newest first.
+    async fn get_settings_history(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<SettingsAuditEntity>, ServiceError>;
+}
+
+/// Guild-scoped access tokens for the read-only web dashboard.
+#[async_trait]
+pub trait DashboardProvider: Send + Sync {
+    /// Returns the current dashboard token of a guild, if any.
+    async fn get_token(&self, guild_id: u64) -> Result<Option<DashboardTokenEntity>, ServiceError>;
+
+    /// Generates a new dashboard token for a guild, invalidating the previous one.
+    async fn generate_token(
+        &self,
+        guild_id: u64,
+        created_by: u64,
+    ) -> Result<DashboardTokenEntity, ServiceError>;
+
+    /// Revokes a guild's dashboard token. Returns whether one existed.
+    async fn revoke_token(&self, guild_id: u64) -> Result<bool, ServiceError>;
+
+    /// Resolves a token to the guild it grants access to.
+    async fn resolve_token(&self, token: &str) -> Result<Option<u64>, ServiceError>;
+}
+
+/// Bearer tokens for the REST API, scoped to a guild or to the bot owner.
+#[async_trait]
+pub trait ApiTokenProvider: Send + Sync {
+    /// Returns the current token of a scope, if any.
+    async fn get_api_token(&self, scope: ApiScope) -> Result<Option<ApiTokenEntity>, ServiceError>;
+
+    /// Generates a new token for a scope, invalidating the previous one. Returns the
+    /// stored token and the token itself, which is only stored hashed.
+    async fn generate_api_token(
+        &self,
+        scope: ApiScope,
+        created_by: u64,
+    ) -> Result<(ApiTokenEntity, String), ServiceError>;
+
+    /// Revokes the token of a scope. Returns whether one existed.
+    async fn revoke_api_token(&self, scope: ApiScope) -> Result<bool, ServiceError>;
+
+    /// Resolves a bearer token to the scope it grants.
+    async fn authorize(&self, token: &str) -> Result<Option<ApiScope>, ServiceError>;
+}
+
+/// JSON API feeds defined by the bot owner.
+#[async_trait]
+pub trait CustomFeedProvider: Send + Sync {
+    /// Adds a custom feed, or replaces the one polling the same url.
+    async fn set_custom_feed(
+        &self,
+        feed: CustomJsonFeedEntity,
+    ) -> Result<CustomJsonFeedEntity, ServiceError>;
+
+    /// Removes a custom feed. Returns whether it existed.
+    async fn remove_custom_feed(&self, url: &str) -> Result<bool, ServiceError>;
+
+    /// Returns every custom feed.
+    async fn list_custom_feeds(&self) -> Result<Vec<CustomJsonFeedEntity>, ServiceError>;
+}
+
+/// AniList accounts linked by users, synced into their DM subscriptions.
+#[async_trait]
+pub trait AniListSyncProvider: Send + Sync {
+    /// Links a user's AniList account by username, replacing an earlier link.
+    async fn link(
+        &self,
+        user_id: u64,
+        username: &str,
+        auto_sync: bool,
+    ) -> Result<AniListLinkEntity, ServiceError>;
+
+    /// Removes a user's AniList link, keeping their subscriptions. Returns whether a
+    /// link existed.
+    async fn unlink(&self, user_id: u64) -> Result<bool, ServiceError>;
+
+    /// Returns a user's AniList link, if any.
+    async fn get_link(&self, user_id: u64) -> Result<Option<AniListLinkEntity>, ServiceError>;
+
+    /// Subscribes a linked user to the airing anime on their watching list, and
+    /// unsubscribes them from synced anime that left it.
+    async fn sync(&self, user_id: u64) -> Result<AniListSyncResult, ServiceError>;
+
+    /// Syncs up to `limit` links with auto sync on that are due. Returns the number synced.
+    async fn sync_due(&self, now: &DateTime<Utc>, limit: u32) -> Result<u32, ServiceError>;
+}
+
+/// MyAnimeList accounts linked by users through OAuth, imported into their DM
+/// subscriptions.
+#[async_trait]
+pub trait MalImportProvider: Send + Sync {
+    /// Whether MyAnimeList linking is configured.
+    fn is_enabled(&self) -> bool;
+
+    /// Starts linking a user's account. Returns the page where they authorize the bot.
+    fn start_link(&self, user_id: u64) -> Result<String, ServiceError>;
+
+    /// Finishes a link request with the code MyAnimeList redirected the user with.
+    async fn complete_link(&self, state: &str, code: &str) -> Result<MalLinkEntity, ServiceError>;
+
+    /// Removes a user's MyAnimeList link, keeping their subscriptions. Returns whether a
+    /// link existed.
+    async fn unlink(&self, user_id: u64) -> Result<bool, ServiceError>;
+
+    /// Returns a user's MyAnimeList link, if any.
+    async fn get_link(&self, user_id: u64) -> Result<Option<MalLinkEntity>, ServiceError>;
+
+    /// Subscribes a linked user to the ongoing titles on the given lists, or with
+    /// `preview` only reports what would be subscribed to.
+    async fn import(
+        &self,
+        user_id: u64,
+        kinds: &[MalListKind],
+        preview: bool,
+    ) -> Result<MalImportResult, ServiceError>;
+}
+
+/// Upcoming releases for the pinned release calendars and airing events of guilds.
+#[async_trait]
+pub trait ReleaseCalendarProvider: Send + Sync {
+    /// Returns the guilds keeping a release calendar, as `(guild_id, channel_id)`.
+    async fn guilds_with_calendar(&self) -> Result<Vec<(u64, u64)>, ServiceError>;
+
+    /// Returns the releases expected in the next week from the feeds a guild follows,
+    /// soonest first.
+    async fn upcoming_releases(
+        &self,
+        guild_id: u64,
+        now: &DateTime<Utc>,
+    ) -> Result<Vec<UpcomingRelease>, ServiceError>;
+
+    /// Returns the guilds keeping server events for airing anime.
+    async fn guilds_with_airing_events(&self) -> Result<Vec<u64>, ServiceError>;
+
+    /// Returns the next announced episode of each AniList anime a guild follows, soonest
+    /// first.
+    async fn upcoming_airings(&self, guild_id: u64) -> Result<Vec<UpcomingRelease>, ServiceError>;
+}
+
+/// Usage counts of each guild's custom emojis and stickers.
+#[async_trait]
+pub trait EmojiStatsProvider: Send + Sync {
+    /// Counts one use of each emoji or sticker in a guild.
+    async fn record_uses(&self, guild_id: u64, uses: &[EmojiUse]) -> Result<(), ServiceError>;
+
+    /// Returns the number of emojis or stickers of a kind used in a guild.
+    async fn count_emoji_stats(&self, guild_id: u64, kind: EmojiKind) -> Result<u32, ServiceError>;
+
+    /// Returns a page of a guild's emojis or stickers of a kind, most used first.
+    async fn list_emoji_stats(
+        &self,
+        guild_id: u64,
+        kind: EmojiKind,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<EmojiStatEntity>, ServiceError>;
+}
+
+/// The invites members join each guild through.
+#[async_trait]
+pub trait InviteTrackingProvider: Send + Sync {
+    /// Records a member joining a guild, through `invite` if it is known.
+    async fn record_join(
+        &self,
+        guild_id: u64,
+        member_id: u64,
+        invite: Option<&UsedInvite>,
+    ) -> Result<(), ServiceError>;
+
+    /// Returns the number of members in a guild who invited anyone.
+    async fn count_inviters(&self, guild_id: u64) -> Result<u32, ServiceError>;
+
+    /// Returns a page of a guild's inviters, most members invited first.
+    async fn list_leaderboard(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<InviteLeaderboardEntry>, ServiceError>;
+
+    /// Returns the number of distinct members who joined a guild through a member's invites.
+    async fn count_invites_by(&self, guild_id: u64, inviter_id: u64) -> Result<u32, ServiceError>;
+}
+
+/// Temporary voice channels created when members join a guild's hub channel.
+#[async_trait]
+pub trait TempVoiceProvider: Send + Sync {
+    /// Records a channel just created for `owner_id`.
+    async fn add_channel(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        owner_id: u64,
+    ) -> Result<(), ServiceError>;
+
+    /// Returns a temporary channel, or `None` if the channel is not one.
+    async fn get_channel(
+        &self,
+        channel_id: u64,
+    ) -> Result<Option<TempVoiceChannelEntity>, ServiceError>;
+
+    /// Returns every temporary channel of a guild.
+    async fn list_channels(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<TempVoiceChannelEntity>, ServiceError>;
+
+    /// Hands a temporary channel to another member. Returns `false` if the channel is
+    /// not one.
+    async fn set_owner(&self, channel_id: u64, owner_id: u64) -> Result<bool, ServiceError>;
+
+    /// Forgets a temporary channel once it is deleted.
+    async fn remove_channel(&self, channel_id: u64) -> Result<(), ServiceError>;
+}
+
+/// Admin-defined text responses (tags), scoped per guild.
+///
+/// Tag names are normalized with [`normalize_tag_name`](crate::service::tag::normalize_tag_name)
+/// before every lookup.
+#[async_trait]
+pub trait TagProvider: Send + Sync {
+    /// Returns a tag by name, if it exists.
+    async fn get_tag(&self, guild_id: u64, name: &str) -> Result<Option<TagEntity>, ServiceError>;
+
+    /// Returns a tag by name and increments its usage counter.
+    async fn use_tag(&self, guild_id: u64, name: &str) -> Result<TagEntity, ServiceError>;
+
+    /// Creates a new tag.
+    async fn create_tag(
+        &self,
+        guild_id: u64,
+        name: &str,
+        content: &str,
+        embed: bool,
+        created_by: u64,
+    ) -> Result<TagEntity, ServiceError>;
+
+    /// Updates a tag's content and/or embed flag.
+    async fn edit_tag(
+        &self,
+        guild_id: u64,
+        name: &str,
+        content: Option<&str>,
+        embed: Option<bool>,
+    ) -> Result<TagEntity, ServiceError>;
+
+    /// Deletes a tag. Returns whether it existed.
+    async fn delete_tag(&self, guild_id: u64, name: &str) -> Result<bool, ServiceError>;
+
+    /// Returns the number of tags in a guild.
+    async fn count_tags(&self, guild_id: u64) -> Result<u32, ServiceError>;
+
+    /// Returns a page of a guild's tags, most used first. Pages start at 1.
+    async fn list_tags(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<TagEntity>, ServiceError>;
+
+    /// Returns tags whose name contains the given text, for autocomplete.
+    async fn search_tags(
+        &self,
+        guild_id: u64,
+        partial: &str,
+    ) -> Result<Vec<TagEntity>, ServiceError>;
 }
 
 /// Internal bot operations and metadata management.
@@ -0,0 +1,248 @@
+//! Parsing of voice time exported by other stat bots.
+
+use std::collections::BTreeMap;
+
+use crate::service::error::ServiceError;
+
+/// Lines listed by number when reporting skipped lines.
+const SKIPPED_LINES_SHOWN: usize = 10;
+
+/// Stat bot a voice time export comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoiceImportSource {
+    Statbot,
+    Mee6,
+}
+
+impl VoiceImportSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Statbot => "Statbot",
+            Self::Mee6 => "MEE6",
+        }
+    }
+
+    /// Seconds per unit of a voice column whose header names no unit.
+    fn default_unit_secs(&self) -> i64 {
+        match self {
+            Self::Statbot | Self::Mee6 => 60,
+        }
+    }
+}
+
+/// A member's voice time from an export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImportedVoiceTime {
+    pub user_id: u64,
+    pub seconds: i64,
+}
+
+/// Voice time parsed from an export.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VoiceImport {
+    /// Voice time by member, lowest user ID first. Members listed twice are summed.
+    pub members: Vec<ImportedVoiceTime>,
+    /// Numbers of the lines that could not be read, the first ten.
+    pub skipped_lines: Vec<usize>,
+    /// Number of lines that could not be read.
+    pub skipped: usize,
+}
+
+impl VoiceImport {
+    /// Total imported voice time, in seconds.
+    pub fn total_secs(&self) -> i64 {
+        self.members.iter().map(|member| member.seconds).sum()
+    }
+}
+
+/// Reads member voice time from a CSV export of `source`.
+///
+/// The header picks the columns: the user ID from `user_id`, `member_id`, `discord_id`
+/// or `id`, and the voice time from the first column naming `voice`. A unit in the voice
+/// header (`seconds`, `minutes` or `hours`) overrides the source's default of minutes.
+/// Lines with an invalid ID or time are skipped, and members without voice time left out.
+pub fn parse_voice_export(
+    source: VoiceImportSource,
+    csv: &str,
+) -> Result<VoiceImport, ServiceError> {
+    let mut lines = csv
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Err(ServiceError::InvalidVoiceImport(
+            "The file is empty".to_string(),
+        ));
+    };
+    let header: Vec<String> = split_csv_line(header)
+        .iter()
+        .map(|column| normalize_header(column))
+        .collect();
+
+    let user_column = ["userid", "memberid", "discordid", "id", "user"]
+        .iter()
+        .find_map(|name| header.iter().position(|column| column == name))
+        .ok_or_else(|| {
+            ServiceError::InvalidVoiceImport(format!(
+                "The {} export has no user ID column, e.g. `user_id`",
+                source.name()
+            ))
+        })?;
+    let voice_column = header
+        .iter()
+        .position(|column| column.contains("voice") && !column.contains("xp"))
+        .ok_or_else(|| {
+            let hint = match source {
+                VoiceImportSource::Mee6 => {
+                    ". MEE6 leaderboard exports only hold XP and messages, which are not imported"
+                }
+                VoiceImportSource::Statbot => "",
+            };
+            ServiceError::InvalidVoiceImport(format!(
+                "The {} export has no voice time column, e.g. `voice_minutes`{hint}",
+                source.name()
+            ))
+        })?;
+    let unit_secs = unit_secs(&header[voice_column]).unwrap_or(source.default_unit_secs());
+
+    let mut by_user: BTreeMap<u64, i64> = BTreeMap::new();
+    let mut import = VoiceImport::default();
+    for (index, line) in lines {
+        let fields = split_csv_line(line);
+        let user_id = fields
+            .get(user_column)
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        let seconds = fields
+            .get(voice_column)
+            .and_then(|value| parse_amount(value))
+            .map(|amount| (amount * unit_secs as f64).round() as i64);
+        match (user_id, seconds) {
+            (Some(user_id), Some(seconds)) => {
+                if seconds > 0 {
+                    *by_user.entry(user_id).or_default() += seconds;
+                }
+            }
+            _ => {
+                import.skipped += 1;
+                if import.skipped_lines.len() < SKIPPED_LINES_SHOWN {
+                    import.skipped_lines.push(index + 1);
+                }
+            }
+        }
+    }
+
+    import.members = by_user
+        .into_iter()
+        .map(|(user_id, seconds)| ImportedVoiceTime { user_id, seconds })
+        .collect();
+    Ok(import)
+}
+
+/// Lowercases a header and drops everything but letters and digits.
+fn normalize_header(column: &str) -> String {
+    column
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Seconds per unit named in a normalized voice header.
+fn unit_secs(header: &str) -> Option<i64> {
+    if header.contains("sec") {
+        Some(1)
+    } else if header.contains("min") {
+        Some(60)
+    } else if header.contains("hour") || header.contains("hrs") {
+        Some(3600)
+    } else {
+        None
+    }
+}
+
+/// Parses a non-negative amount, allowing decimals and thousands separators.
+fn parse_amount(value: &str) -> Option<f64> {
+    let value: String = value
+        .chars()
+        .filter(|c| *c != ',' && !c.is_whitespace())
+        .collect();
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|amount| amount.is_finite() && *amount >= 0.0)
+}
+
+/// Splits a CSV line into fields. Fields may be quoted, with `""` for a quote.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_voice_export_sums_members_and_skips_invalid_lines() {
+        let csv = "\u{feff}User ID,Username,Voice Minutes\r\n\
+                   200,\"Doe, Jane\",\"1,200\"\r\n\
+                   100,john,90.5\r\n\
+                   not-an-id,bad,5\r\n\
+                   \r\n\
+                   100,john,30\r\n\
+                   300,idle,0\r\n";
+
+        let import = parse_voice_export(VoiceImportSource::Statbot, csv).unwrap();
+
+        assert_eq!(
+            import.members,
+            vec![
+                ImportedVoiceTime {
+                    user_id: 100,
+                    seconds: 7230
+                },
+                ImportedVoiceTime {
+                    user_id: 200,
+                    seconds: 72_000
+                },
+            ]
+        );
+        assert_eq!(import.skipped, 1);
+        assert_eq!(import.skipped_lines, vec![4]);
+        assert_eq!(import.total_secs(), 79_230);
+    }
+
+    #[test]
+    fn parse_voice_export_reads_the_unit_from_the_header() {
+        let csv = "id,voice_hours\n1,2\n";
+
+        let import = parse_voice_export(VoiceImportSource::Statbot, csv).unwrap();
+
+        assert_eq!(import.total_secs(), 7200);
+    }
+
+    #[test]
+    fn parse_voice_export_rejects_exports_without_voice_time() {
+        let csv = "id,username,level,xp,message_count\n1,john,5,1200,300\n";
+
+        let err = parse_voice_export(VoiceImportSource::Mee6, csv).unwrap_err();
+
+        assert!(err.to_string().contains("only hold XP"));
+    }
+}
@@ -1,23 +1,54 @@
 //! Voice channel activity tracking service.
 
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use chrono::DateTime;
+use chrono::Datelike;
+use chrono::TimeZone;
 use chrono::Utc;
+use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 
 use crate::bot::command::voice::GuildStatType;
+use crate::entity::BotMetaEntity;
+use crate::entity::BotMetaKey;
+use crate::entity::ChannelWeightEntity;
+use crate::entity::DbU64;
+use crate::entity::GlobalVoiceLeaderboardEntry;
 use crate::entity::GuildDailyStats;
+use crate::entity::LeaderboardSnapshotEntity;
 use crate::entity::ServerSettings;
 use crate::entity::ServerSettingsEntity;
+use crate::entity::VoiceAccountMergeEntity;
+use crate::entity::VoiceAdjustmentEntity;
+use crate::entity::VoiceChannelUsage;
 use crate::entity::VoiceDailyActivity;
+use crate::entity::VoiceEventEntity;
+use crate::entity::VoiceEventKind;
+use crate::entity::VoiceGoalProgress;
 use crate::entity::VoiceLeaderboardEntry;
 use crate::entity::VoiceLeaderboardOpt;
+use crate::entity::VoiceLeaderboardOptBuilder;
+use crate::entity::VoiceSessionFlag;
 use crate::entity::VoiceSessionsEntity;
+use crate::entity::VoiceSettings;
 use crate::repo::traits::*;
+use crate::service::error::ServiceError;
+use crate::service::leaderboard_cache::LeaderboardCache;
+use crate::service::open_sessions::OpenSessions;
 use crate::service::settings::SettingsService;
 use crate::service::traits::VoiceTracker;
+use crate::service::voice_import::ImportedVoiceTime;
+
+/// Sessions at least this long are flagged for review.
+const LONG_SESSION_SECS: i64 = 24 * 3600;
+/// Join and leave times within this many seconds of each other count as identical.
+const SYNCED_TOLERANCE_SECS: i32 = 2;
+/// Accounts that must share join and leave times before their sessions are flagged.
+const MIN_SYNCED_ACCOUNTS: i32 = 3;
+/// Voice events loaded per query when rebuilding a guild's sessions.
+const REBUILD_BATCH_SIZE: u32 = 1000;
 
 #[async_trait::async_trait]
 impl VoiceTracker for VoiceTrackingService {
@@ -25,6 +56,14 @@ impl VoiceTracker for VoiceTrackingService {
         self.is_enabled(guild_id).await
     }
 
+    async fn voice_settings(&self, guild_id: u64) -> VoiceSettings {
+        self.voice_settings(guild_id).await
+    }
+
+    async fn should_track(&self, guild_id: u64, is_bot: bool, is_stage: bool) -> bool {
+        self.should_track(guild_id, is_bot, is_stage).await
+    }
+
     async fn insert(&self, model: &VoiceSessionsEntity) -> anyhow::Result<()> {
         self.insert(model).await
     }
@@ -33,6 +72,10 @@ impl VoiceTracker for VoiceTrackingService {
         self.replace(model).await
     }
 
+    fn open_sessions(&self) -> Arc<OpenSessions> {
+        self.open_sessions()
+    }
+
     async fn get_server_settings(&self, guild_id: u64) -> anyhow::Result<ServerSettings> {
         self.get_server_settings(guild_id).await
     }
@@ -45,6 +88,16 @@ impl VoiceTracker for VoiceTrackingService {
         self.update_server_settings(guild_id, settings).await
     }
 
+    async fn update_server_settings_by(
+        &self,
+        guild_id: u64,
+        settings: ServerSettings,
+        changed_by: u64,
+    ) -> anyhow::Result<()> {
+        self.update_server_settings_by(guild_id, settings, changed_by)
+            .await
+    }
+
     async fn get_leaderboard_withopt(
         &self,
         options: &VoiceLeaderboardOpt,
@@ -60,6 +113,40 @@ impl VoiceTracker for VoiceTrackingService {
         self.get_partner_leaderboard(options, target_user_id).await
     }
 
+    async fn get_channel_usage(
+        &self,
+        guild_id: u64,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+    ) -> anyhow::Result<Vec<VoiceChannelUsage>> {
+        self.get_channel_usage(guild_id, since, until).await
+    }
+
+    async fn get_global_leaderboard(
+        &self,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<GlobalVoiceLeaderboardEntry>> {
+        self.get_global_leaderboard(since, until, limit).await
+    }
+
+    async fn guilds_with_goals(&self) -> Vec<u64> {
+        self.guilds_with_goals().await
+    }
+
+    async fn guilds_with_afk_mover(&self) -> Vec<u64> {
+        self.guilds_with_afk_mover().await
+    }
+
+    async fn get_goal_progress(
+        &self,
+        guild_id: u64,
+        now: &DateTime<Utc>,
+    ) -> anyhow::Result<Option<VoiceGoalProgress>> {
+        self.get_goal_progress(guild_id, now).await
+    }
+
     async fn get_leaderboard(
         &self,
         guild_id: u64,
@@ -78,6 +165,111 @@ impl VoiceTracker for VoiceTrackingService {
             .await
     }
 
+    async fn take_leaderboard_snapshot(&self, taken_at: &DateTime<Utc>) -> anyhow::Result<u32> {
+        self.take_leaderboard_snapshot(taken_at).await
+    }
+
+    async fn get_latest_leaderboard_snapshot(
+        &self,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<LeaderboardSnapshotEntity>> {
+        self.get_latest_leaderboard_snapshot(guild_id).await
+    }
+
+    async fn get_leaderboard_snapshots_since(
+        &self,
+        guild_id: u64,
+        since: &DateTime<Utc>,
+    ) -> anyhow::Result<Vec<LeaderboardSnapshotEntity>> {
+        self.get_leaderboard_snapshots_since(guild_id, since).await
+    }
+
+    async fn flag_suspicious_sessions(&self, since: &DateTime<Utc>) -> anyhow::Result<u32> {
+        self.flag_suspicious_sessions(since).await
+    }
+
+    async fn prune_sessions(&self, now: &DateTime<Utc>) -> anyhow::Result<u32> {
+        self.prune_sessions(now).await
+    }
+
+    async fn reconcile_daily_totals(&self) -> anyhow::Result<u32> {
+        self.reconcile_daily_totals().await
+    }
+
+    async fn count_open_flags(&self, guild_id: u64) -> anyhow::Result<u32> {
+        self.count_open_flags(guild_id).await
+    }
+
+    async fn get_open_flags(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> anyhow::Result<Vec<VoiceSessionFlag>> {
+        self.get_open_flags(guild_id, page, per_page).await
+    }
+
+    async fn dismiss_flag(&self, guild_id: u64, flag_id: i32) -> anyhow::Result<bool> {
+        self.dismiss_flag(guild_id, flag_id).await
+    }
+
+    async fn invalidate_flagged_session(
+        &self,
+        guild_id: u64,
+        flag_id: i32,
+    ) -> anyhow::Result<bool> {
+        self.invalidate_flagged_session(guild_id, flag_id).await
+    }
+
+    async fn adjust_voice_time(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        seconds: i64,
+        reason: Option<String>,
+        adjusted_by: u64,
+    ) -> anyhow::Result<i64> {
+        self.adjust_voice_time(guild_id, user_id, seconds, reason, adjusted_by)
+            .await
+    }
+
+    async fn import_voice_time(
+        &self,
+        guild_id: u64,
+        members: &[ImportedVoiceTime],
+        reason: String,
+        imported_by: u64,
+        applies_at: DateTime<Utc>,
+    ) -> anyhow::Result<u32> {
+        self.import_voice_time(guild_id, members, reason, imported_by, applies_at)
+            .await
+    }
+
+    async fn merge_users(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        merged_by: u64,
+    ) -> anyhow::Result<VoiceAccountMergeEntity> {
+        self.merge_users(from_user_id, to_user_id, merged_by).await
+    }
+
+    fn records_voice_events(&self) -> bool {
+        self.records_voice_events()
+    }
+
+    async fn record_voice_event(&self, event: &VoiceEventEntity) -> anyhow::Result<i64> {
+        self.record_voice_event(event).await
+    }
+
+    async fn project_voice_events(&self, limit: u32) -> anyhow::Result<u32> {
+        self.project_voice_events(limit).await
+    }
+
+    async fn rebuild_voice_sessions(&self, guild_id: u64) -> anyhow::Result<u32> {
+        self.rebuild_voice_sessions(guild_id).await
+    }
+
     async fn update_session_leave_time(
         &self,
         user_id: u64,
@@ -89,6 +281,10 @@ impl VoiceTracker for VoiceTrackingService {
             .await
     }
 
+    async fn flush_open_sessions(&self, flushed_at: &DateTime<Utc>) -> anyhow::Result<u32> {
+        self.flush_open_sessions(flushed_at).await
+    }
+
     async fn close_session(
         &self,
         user_id: u64,
@@ -100,6 +296,27 @@ impl VoiceTracker for VoiceTrackingService {
             .await
     }
 
+    async fn end_session(
+        &self,
+        session: &VoiceSessionsEntity,
+        leave_time: &DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        self.end_session(session, leave_time).await
+    }
+
+    async fn move_session(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        is_bot: bool,
+        is_stage: bool,
+        moved_at: &DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        self.move_session(user_id, guild_id, channel_id, is_bot, is_stage, moved_at)
+            .await
+    }
+
     async fn find_active_sessions(&self) -> anyhow::Result<Vec<VoiceSessionsEntity>> {
         self.find_active_sessions().await
     }
@@ -151,11 +368,26 @@ pub struct VoiceTrackingService {
     voice_sessions: Arc<dyn VoiceSessionsRepository + Send + Sync>,
     server_settings: Arc<dyn ServerSettingsRepository + Send + Sync>,
     settings: Arc<SettingsService>,
-    disabled_guilds: Arc<RwLock<HashSet<u64>>>,
+    channel_weights: Option<Arc<dyn ChannelWeightsRepository + Send + Sync>>,
+    snapshots: Option<Arc<dyn LeaderboardSnapshotsRepository + Send + Sync>>,
+    flags: Option<Arc<dyn VoiceSessionFlagsRepository + Send + Sync>>,
+    adjustments: Option<Arc<dyn VoiceAdjustmentsRepository + Send + Sync>>,
+    merges: Option<Arc<dyn VoiceAccountMergesRepository + Send + Sync>>,
+    events: Option<Arc<dyn VoiceEventsRepository + Send + Sync>>,
+    bot_meta: Option<Arc<dyn BotMetaRepository + Send + Sync>>,
+    /// Held while voice events are applied, so a rebuild never races the projection.
+    projection: Mutex<()>,
+    voice_settings: Arc<RwLock<HashMap<u64, VoiceSettings>>>,
+    /// Shared with views; also lets leaderboards count time not yet flushed.
+    open_sessions: Arc<OpenSessions>,
+    /// Recent leaderboards, dropped when a guild's sessions change.
+    leaderboards: LeaderboardCache,
+    /// Days ended sessions are kept in guilds without a shorter window, 0 for forever.
+    session_retention_days: u32,
 }
 
 impl VoiceTrackingService {
-    /// Creates a new voice tracking service and loads disabled guilds.
+    /// Creates a new voice tracking service and loads per-guild voice settings.
     pub async fn new(
         voice_sessions: Arc<dyn VoiceSessionsRepository + Send + Sync>,
         server_settings: Arc<dyn ServerSettingsRepository + Send + Sync>,
@@ -165,35 +397,146 @@ impl VoiceTrackingService {
             voice_sessions,
             server_settings,
             settings: Arc::clone(&settings),
-            disabled_guilds: Arc::new(RwLock::new(HashSet::new())),
+            channel_weights: None,
+            snapshots: None,
+            flags: None,
+            adjustments: None,
+            merges: None,
+            events: None,
+            bot_meta: None,
+            projection: Mutex::new(()),
+            voice_settings: Arc::new(RwLock::new(HashMap::new())),
+            open_sessions: Arc::new(OpenSessions::new()),
+            leaderboards: LeaderboardCache::default(),
+            session_retention_days: 0,
         };
         let all_settings: Vec<ServerSettingsEntity> = _self.server_settings.select_all().await?;
-        let mut disabled = _self.disabled_guilds.write().await;
+        let mut cache = _self.voice_settings.write().await;
 
         for model in all_settings {
-            if let Some(false) = model.settings.0.voice.enabled {
-                disabled.insert(*model.guild_id);
+            let voice = model.settings.0.voice;
+            if voice != VoiceSettings::default() {
+                cache.insert(*model.guild_id, voice);
             }
         }
-        drop(disabled);
+        drop(cache);
 
         Ok(_self)
     }
 
+    /// Shares a settings service, e.g. one that audits changes.
+    pub fn with_settings(mut self, settings: Arc<SettingsService>) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Mirrors per-channel weights from voice settings into a table the leaderboard joins on.
+    pub fn with_channel_weights(
+        mut self,
+        channel_weights: Arc<dyn ChannelWeightsRepository + Send + Sync>,
+    ) -> Self {
+        self.channel_weights = Some(channel_weights);
+        self
+    }
+
+    /// Enables weekly leaderboard snapshots for week-over-week comparisons.
+    pub fn with_snapshots(
+        mut self,
+        snapshots: Arc<dyn LeaderboardSnapshotsRepository + Send + Sync>,
+    ) -> Self {
+        self.snapshots = Some(snapshots);
+        self
+    }
+
+    /// Enables flagging suspicious sessions for admin review.
+    pub fn with_flags(mut self, flags: Arc<dyn VoiceSessionFlagsRepository + Send + Sync>) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Enables manual voice time adjustments by admins.
+    pub fn with_adjustments(
+        mut self,
+        adjustments: Arc<dyn VoiceAdjustmentsRepository + Send + Sync>,
+    ) -> Self {
+        self.adjustments = Some(adjustments);
+        self
+    }
+
+    /// Enables moving voice data between user IDs when members change accounts.
+    pub fn with_merges(
+        mut self,
+        merges: Arc<dyn VoiceAccountMergesRepository + Send + Sync>,
+    ) -> Self {
+        self.merges = Some(merges);
+        self
+    }
+
+    /// Records voice state changes in an append-only event log and derives sessions from
+    /// it. `bot_meta` keeps track of the last event applied.
+    pub fn with_event_sourcing(
+        mut self,
+        events: Arc<dyn VoiceEventsRepository + Send + Sync>,
+        bot_meta: Arc<dyn BotMetaRepository + Send + Sync>,
+    ) -> Self {
+        self.events = Some(events);
+        self.bot_meta = Some(bot_meta);
+        self
+    }
+
+    /// Sets the days ended sessions are kept, 0 for forever. Guilds can only shorten it.
+    pub fn with_session_retention_days(mut self, days: u32) -> Self {
+        self.session_retention_days = days;
+        self
+    }
+
     /// Check if voice tracking is enabled for a guild (default: true)
     pub async fn is_enabled(&self, guild_id: u64) -> bool {
-        !self.disabled_guilds.read().await.contains(&guild_id)
+        self.voice_settings(guild_id).await.is_enabled()
+    }
+
+    /// Returns the cached voice settings for a guild.
+    pub async fn voice_settings(&self, guild_id: u64) -> VoiceSettings {
+        self.voice_settings
+            .read()
+            .await
+            .get(&guild_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Check if a voice state should be tracked under the guild's voice settings.
+    pub async fn should_track(&self, guild_id: u64, is_bot: bool, is_stage: bool) -> bool {
+        self.voice_settings(guild_id).await.tracks(is_bot, is_stage)
     }
 
     pub async fn insert(&self, model: &VoiceSessionsEntity) -> anyhow::Result<()> {
         self.voice_sessions.insert(model).await?;
+        self.track_open_session(model);
         Ok(())
     }
     pub async fn replace(&self, model: &VoiceSessionsEntity) -> anyhow::Result<()> {
         self.voice_sessions.replace(model).await?;
+        self.track_open_session(model);
         Ok(())
     }
 
+    /// Returns the registry of open sessions.
+    pub fn open_sessions(&self) -> Arc<OpenSessions> {
+        Arc::clone(&self.open_sessions)
+    }
+
+    /// Remembers `session` as its member's open session, or forgets it once closed.
+    fn track_open_session(&self, session: &VoiceSessionsEntity) {
+        if session.is_active {
+            self.open_sessions.open(session, session.join_time);
+        } else {
+            self.open_sessions
+                .close(session.user_id, session.channel_id, &session.join_time);
+            self.leaderboards.invalidate(session.guild_id);
+        }
+    }
+
     pub async fn get_server_settings(&self, guild_id: u64) -> anyhow::Result<ServerSettings> {
         Ok(self.settings.get_server_settings(guild_id).await?)
     }
@@ -203,27 +546,138 @@ impl VoiceTrackingService {
         guild_id: u64,
         settings: ServerSettings,
     ) -> anyhow::Result<()> {
-        // Update cache
-        {
-            let mut disabled = self.disabled_guilds.write().await;
-            if let Some(false) = settings.voice.enabled {
-                disabled.insert(guild_id);
-            } else {
-                disabled.remove(&guild_id);
-            }
-        }
-
+        self.update_settings_cache(guild_id, &settings).await;
+        self.sync_channel_weights(guild_id, &settings).await?;
         self.settings
             .update_server_settings(guild_id, settings)
             .await?;
         Ok(())
     }
 
+    pub async fn update_server_settings_by(
+        &self,
+        guild_id: u64,
+        settings: ServerSettings,
+        changed_by: u64,
+    ) -> anyhow::Result<()> {
+        self.update_settings_cache(guild_id, &settings).await;
+        self.sync_channel_weights(guild_id, &settings).await?;
+        self.settings
+            .update_server_settings_by(guild_id, settings, changed_by)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_settings_cache(&self, guild_id: u64, settings: &ServerSettings) {
+        let mut cache = self.voice_settings.write().await;
+        if settings.voice == VoiceSettings::default() {
+            cache.remove(&guild_id);
+        } else {
+            cache.insert(guild_id, settings.voice.clone());
+        }
+        // Channel weights change how time adds up
+        self.leaderboards.invalidate(guild_id);
+    }
+
+    async fn sync_channel_weights(
+        &self,
+        guild_id: u64,
+        settings: &ServerSettings,
+    ) -> anyhow::Result<()> {
+        let Some(repo) = &self.channel_weights else {
+            return Ok(());
+        };
+        let weights: Vec<ChannelWeightEntity> = settings
+            .voice
+            .channel_weights
+            .iter()
+            .flatten()
+            .filter_map(|(channel_id, weight_percent)| {
+                Some(ChannelWeightEntity {
+                    guild_id: DbU64::from(guild_id),
+                    channel_id: DbU64::from(channel_id.parse::<u64>().ok()?),
+                    weight_percent: *weight_percent as i32,
+                    ..Default::default()
+                })
+            })
+            .collect();
+        repo.replace_all_by_guild_id(guild_id, &weights).await?;
+        Ok(())
+    }
+
+    /// Returns the leaderboard for `options`, discounting idle-alone time per the guild's
+    /// voice settings unless `options` sets its own discount.
+    ///
+    /// Open sessions count up to their last flush in the database; the time since is
+    /// added from memory at the channel's weight, without the idle-alone discount. The
+    /// flushed totals of each guild and range are cached briefly, until a session of the
+    /// guild closes or sessions are flushed.
+    ///
+    /// # Performance
+    /// * DB calls: 1, 0 when cached
     pub async fn get_leaderboard_withopt(
         &self,
         options: &VoiceLeaderboardOpt,
     ) -> anyhow::Result<Vec<VoiceLeaderboardEntry>> {
-        Ok(self.voice_sessions.get_leaderboard_opt(options).await?)
+        let mut options = options.clone();
+        let voice = self.voice_settings(options.guild_id).await;
+        if options.idle_discount.is_none() {
+            options.idle_discount = voice.idle_discount();
+        }
+
+        // Live time can reorder members, and pages share the cached ranking, so rank
+        // everyone before paging
+        let offset = options.offset.take().unwrap_or(0) as usize;
+        let limit = options.limit.replace(u32::MAX).unwrap_or(10) as usize;
+        let mut unflushed = self.unflushed_durations(&options, &voice);
+        let mut entries = match self.leaderboards.get(&options) {
+            Some(entries) => entries,
+            None => {
+                // DB 1
+                let entries = self.voice_sessions.get_leaderboard_opt(&options).await?;
+                self.leaderboards.insert(&options, entries.clone());
+                entries
+            }
+        };
+        if unflushed.is_empty() {
+            return Ok(entries.into_iter().skip(offset).take(limit).collect());
+        }
+
+        for entry in &mut entries {
+            if let Some(seconds) = unflushed.remove(&entry.user_id) {
+                entry.total_duration += seconds;
+            }
+        }
+        entries.extend(unflushed.into_iter().map(|(user_id, total_duration)| {
+            VoiceLeaderboardEntry {
+                user_id,
+                total_duration,
+            }
+        }));
+        entries.sort_by(|a, b| b.total_duration.cmp(&a.total_duration));
+        Ok(entries.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// Returns the weighted time each member of the guild spent in open sessions since
+    /// their last flush, clipped to the range of `options`.
+    fn unflushed_durations(
+        &self,
+        options: &VoiceLeaderboardOpt,
+        voice: &VoiceSettings,
+    ) -> HashMap<u64, i64> {
+        let now = Utc::now();
+        let since = options.since.unwrap_or(DateTime::UNIX_EPOCH);
+        let until = options.until.map_or(now, |until| until.min(now));
+
+        let mut durations = HashMap::new();
+        for (user_id, open) in self.open_sessions.by_guild(options.guild_id) {
+            let seconds = (until - open.flushed_at.max(open.join_time).max(since)).num_seconds();
+            if seconds > 0 {
+                *durations.entry(user_id).or_insert(0) +=
+                    seconds * i64::from(voice.channel_weight(open.channel_id)) / 100;
+            }
+        }
+        durations
     }
 
     pub async fn get_partner_leaderboard(
@@ -237,12 +691,132 @@ impl VoiceTrackingService {
             .await?)
     }
 
+    /// Returns the voice time per channel in a guild, most used first.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_channel_usage(
+        &self,
+        guild_id: u64,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+    ) -> anyhow::Result<Vec<VoiceChannelUsage>> {
+        // DB 1
+        Ok(self
+            .voice_sessions
+            .get_channel_usage(guild_id, since, until)
+            .await?)
+    }
+
+    /// Returns the top members by voice time across every guild that opted in to the
+    /// global leaderboard. Idle-alone discounts are not applied.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_global_leaderboard(
+        &self,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<GlobalVoiceLeaderboardEntry>> {
+        let cache = self.voice_settings.read().await;
+        let guild_ids: Vec<u64> = cache
+            .iter()
+            .filter(|(_, voice)| voice.joins_global_leaderboard())
+            .map(|(guild_id, _)| *guild_id)
+            .collect();
+        let named_guild_ids: Vec<u64> = cache
+            .iter()
+            .filter(|(_, voice)| {
+                voice.joins_global_leaderboard() && !voice.is_global_leaderboard_anonymous()
+            })
+            .map(|(guild_id, _)| *guild_id)
+            .collect();
+        drop(cache);
+
+        if guild_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        // DB 1
+        Ok(self
+            .voice_sessions
+            .get_global_leaderboard(&guild_ids, &named_guild_ids, since, until, limit)
+            .await?)
+    }
+
+    /// Returns the guilds with a monthly voice goal set.
+    pub async fn guilds_with_goals(&self) -> Vec<u64> {
+        self.voice_settings
+            .read()
+            .await
+            .iter()
+            .filter(|(_, voice)| voice.monthly_goal_hours().is_some())
+            .map(|(guild_id, _)| *guild_id)
+            .collect()
+    }
+
+    /// Returns the guilds with the AFK mover on. Guilds that paused voice tracking are
+    /// left out, as their members have no open sessions.
+    pub async fn guilds_with_afk_mover(&self) -> Vec<u64> {
+        self.voice_settings
+            .read()
+            .await
+            .iter()
+            .filter(|(_, voice)| voice.is_enabled() && voice.afk_move_secs().is_some())
+            .map(|(guild_id, _)| *guild_id)
+            .collect()
+    }
+
+    /// Returns a guild's voice time this month against its monthly goal. Months follow
+    /// the guild's timezone and channel weights are not applied.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn get_goal_progress(
+        &self,
+        guild_id: u64,
+        now: &DateTime<Utc>,
+    ) -> anyhow::Result<Option<VoiceGoalProgress>> {
+        // DB 1
+        let settings = self.get_server_settings(guild_id).await?;
+        let Some(goal_hours) = settings.voice.monthly_goal_hours() else {
+            return Ok(None);
+        };
+
+        let local = now.with_timezone(&settings.general.timezone());
+        let month_start = local
+            .timezone()
+            .with_ymd_and_hms(local.year(), local.month(), 1, 0, 0, 0)
+            .earliest()
+            .map(|start| start.with_timezone(&Utc))
+            .unwrap_or(*now);
+
+        // DB 2
+        let total_seconds = self
+            .voice_sessions
+            .get_channel_usage(guild_id, &month_start, now)
+            .await?
+            .iter()
+            .map(|usage| usage.total_duration)
+            .sum();
+
+        Ok(Some(VoiceGoalProgress {
+            month: local.format("%Y-%m").to_string(),
+            goal_seconds: i64::from(goal_hours) * 3600,
+            total_seconds,
+        }))
+    }
+
     pub async fn get_leaderboard(
         &self,
         guild_id: u64,
         limit: u32,
     ) -> anyhow::Result<Vec<VoiceLeaderboardEntry>> {
-        Ok(self.voice_sessions.get_leaderboard(guild_id, limit).await?)
+        let options = VoiceLeaderboardOptBuilder::default()
+            .guild_id(guild_id)
+            .limit(Some(limit))
+            .build()?;
+        self.get_leaderboard_withopt(&options).await
     }
 
     pub async fn get_leaderboard_with_offset(
@@ -251,12 +825,512 @@ impl VoiceTrackingService {
         offset: u32,
         limit: u32,
     ) -> anyhow::Result<Vec<VoiceLeaderboardEntry>> {
-        Ok(self
-            .voice_sessions
-            .get_leaderboard_with_offset(guild_id, offset, limit)
+        let options = VoiceLeaderboardOptBuilder::default()
+            .guild_id(guild_id)
+            .offset(Some(offset))
+            .limit(Some(limit))
+            .build()?;
+        self.get_leaderboard_withopt(&options).await
+    }
+
+    /// Snapshots the all-time leaderboard of every guild. Returns the number of rows written.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn take_leaderboard_snapshot(&self, taken_at: &DateTime<Utc>) -> anyhow::Result<u32> {
+        let Some(snapshots) = &self.snapshots else {
+            return Ok(0);
+        };
+        // DB 1
+        Ok(snapshots.insert_snapshot(taken_at).await?)
+    }
+
+    /// Returns a guild's most recent leaderboard snapshot, ordered by rank.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn get_latest_leaderboard_snapshot(
+        &self,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<LeaderboardSnapshotEntity>> {
+        let Some(snapshots) = &self.snapshots else {
+            return Ok(Vec::new());
+        };
+        // DB 1-2
+        Ok(snapshots.select_latest_by_guild_id(guild_id).await?)
+    }
+
+    /// Returns a guild's snapshots taken since `since`, ordered by time then rank.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_leaderboard_snapshots_since(
+        &self,
+        guild_id: u64,
+        since: &DateTime<Utc>,
+    ) -> anyhow::Result<Vec<LeaderboardSnapshotEntity>> {
+        let Some(snapshots) = &self.snapshots else {
+            return Ok(Vec::new());
+        };
+        // DB 1
+        Ok(snapshots.select_by_guild_id_since(guild_id, since).await?)
+    }
+
+    /// Flags sessions active since `since` that ran for a day or more, or whose join and
+    /// leave times match those of several other accounts. Returns the number of new flags.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn flag_suspicious_sessions(&self, since: &DateTime<Utc>) -> anyhow::Result<u32> {
+        let Some(flags) = &self.flags else {
+            return Ok(0);
+        };
+        // DB 1
+        let long = flags.flag_long_sessions(LONG_SESSION_SECS, since).await?;
+        // DB 2
+        let synced = flags
+            .flag_synced_sessions(SYNCED_TOLERANCE_SECS, MIN_SYNCED_ACCOUNTS, since)
+            .await?;
+        Ok(long + synced)
+    }
+
+    /// Deletes sessions that ended before each guild's retention window, and with event
+    /// sourcing the voice events recorded before it. Returns the number of sessions
+    /// deleted.
+    ///
+    /// # Performance
+    /// * DB calls: 1 for the default window + 1 per guild with a shorter one, twice with
+    ///   event sourcing plus 1
+    pub async fn prune_sessions(&self, now: &DateTime<Utc>) -> anyhow::Result<u32> {
+        let default_days = self.session_retention_days;
+        let mut windows: Vec<(Option<u64>, u32)> = Vec::new();
+        if default_days > 0 {
+            windows.push((None, default_days));
+        }
+        windows.extend(
+            self.voice_settings
+                .read()
+                .await
+                .iter()
+                .map(|(guild_id, voice)| {
+                    (Some(*guild_id), voice.session_retention_days(default_days))
+                })
+                .filter(|(_, days)| *days > 0 && *days != default_days),
+        );
+        if windows.is_empty() {
+            return Ok(0);
+        }
+
+        // A rebuild replays events into sessions, so it must not see half-pruned data
+        let _projection = self.projection.lock().await;
+        let projected = match &self.bot_meta {
+            // DB 1
+            Some(bot_meta) if self.events.is_some() => {
+                Some(Self::projected_event_id(bot_meta.as_ref()).await?)
+            }
+            _ => None,
+        };
+
+        let mut pruned = 0;
+        for (guild_id, days) in windows {
+            let before = *now - chrono::Duration::days(days.into());
+            // DB 1 per window
+            pruned += self
+                .voice_sessions
+                .delete_ended_before(guild_id, &before)
+                .await?;
+            if let (Some(events), Some(projected)) = (&self.events, projected) {
+                // DB 1 per window
+                events.delete_before(guild_id, &before, projected).await?;
+            }
+        }
+        self.leaderboards.clear();
+        Ok(pruned)
+    }
+
+    /// Recomputes the daily voice totals that long-range leaderboards read from the
+    /// sessions, correcting any drift. Returns the number of totals written.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn reconcile_daily_totals(&self) -> anyhow::Result<u32> {
+        // DB 1
+        let written = self.voice_sessions.rebuild_daily_totals().await?;
+        self.leaderboards.clear();
+        Ok(written)
+    }
+
+    /// Counts a guild's flagged sessions awaiting review.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn count_open_flags(&self, guild_id: u64) -> anyhow::Result<u32> {
+        let Some(flags) = &self.flags else {
+            return Ok(0);
+        };
+        // DB 1
+        Ok(flags.count_open_by_guild_id(guild_id).await?)
+    }
+
+    /// Returns a page of a guild's flagged sessions awaiting review, newest first. Pages
+    /// start at 1.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn get_open_flags(
+        &self,
+        guild_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> anyhow::Result<Vec<VoiceSessionFlag>> {
+        let Some(flags) = &self.flags else {
+            return Ok(Vec::new());
+        };
+        let offset = page.saturating_sub(1) * per_page;
+        // DB 1
+        Ok(flags
+            .select_open_by_guild_id(guild_id, offset, per_page)
             .await?)
     }
 
+    /// Keeps a flagged session. Returns whether the flag existed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn dismiss_flag(&self, guild_id: u64, flag_id: i32) -> anyhow::Result<bool> {
+        let Some(flags) = &self.flags else {
+            return Ok(false);
+        };
+        // DB 1
+        Ok(flags.dismiss(guild_id, flag_id).await?)
+    }
+
+    /// Deletes a flagged session so it no longer counts anywhere. Returns whether the flag
+    /// existed.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn invalidate_flagged_session(
+        &self,
+        guild_id: u64,
+        flag_id: i32,
+    ) -> anyhow::Result<bool> {
+        let Some(flags) = &self.flags else {
+            return Ok(false);
+        };
+        // DB 1
+        let deleted = flags.delete_flagged_session(guild_id, flag_id).await?;
+        self.leaderboards.invalidate(guild_id);
+        Ok(deleted)
+    }
+
+    /// Adds `seconds` of voice time for a member, or removes it when negative, without
+    /// touching their sessions. Returns the member's net adjustment afterwards.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn adjust_voice_time(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        seconds: i64,
+        reason: Option<String>,
+        adjusted_by: u64,
+    ) -> anyhow::Result<i64> {
+        let Some(adjustments) = &self.adjustments else {
+            return Err(ServiceError::UnexpectedResult {
+                message: "Voice adjustments are not enabled".to_string(),
+            }
+            .into());
+        };
+        let now = Utc::now();
+        let model = VoiceAdjustmentEntity {
+            guild_id: guild_id.into(),
+            user_id: user_id.into(),
+            seconds,
+            reason,
+            adjusted_by: adjusted_by.into(),
+            applies_at: now,
+            created_at: now,
+            ..Default::default()
+        };
+        // DB 1
+        adjustments.insert(&model).await?;
+        self.leaderboards.invalidate(guild_id);
+        // DB 2
+        Ok(adjustments.sum_by_user(guild_id, user_id).await?)
+    }
+
+    /// Adds voice time imported from another bot as one adjustment per member, counted at
+    /// `applies_at`, in one transaction. Returns the number of adjustments added.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn import_voice_time(
+        &self,
+        guild_id: u64,
+        members: &[ImportedVoiceTime],
+        reason: String,
+        imported_by: u64,
+        applies_at: DateTime<Utc>,
+    ) -> anyhow::Result<u32> {
+        let Some(adjustments) = &self.adjustments else {
+            return Err(ServiceError::UnexpectedResult {
+                message: "Voice adjustments are not enabled".to_string(),
+            }
+            .into());
+        };
+        let now = Utc::now();
+        let models: Vec<VoiceAdjustmentEntity> = members
+            .iter()
+            .filter(|member| member.seconds != 0)
+            .map(|member| VoiceAdjustmentEntity {
+                guild_id: guild_id.into(),
+                user_id: member.user_id.into(),
+                seconds: member.seconds,
+                reason: Some(reason.clone()),
+                adjusted_by: imported_by.into(),
+                applies_at,
+                created_at: now,
+                ..Default::default()
+            })
+            .collect();
+        if models.is_empty() {
+            return Ok(0);
+        }
+        // DB 1
+        let inserted = adjustments.insert_many(&models).await?;
+        self.leaderboards.invalidate(guild_id);
+        Ok(inserted)
+    }
+
+    /// Moves all voice data of `from_user_id` to `to_user_id` in every guild, in one
+    /// transaction, and records who did it. Refused while `from_user_id` is in voice so
+    /// its open session is not split between the two accounts.
+    ///
+    /// # Performance
+    /// * DB calls: 2
+    pub async fn merge_users(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        merged_by: u64,
+    ) -> anyhow::Result<VoiceAccountMergeEntity> {
+        let Some(merges) = &self.merges else {
+            return Err(ServiceError::UnexpectedResult {
+                message: "Voice account merges are not enabled".to_string(),
+            }
+            .into());
+        };
+        if from_user_id == to_user_id {
+            return Err(ServiceError::InvalidAccountMerge(
+                "both user IDs are the same".to_string(),
+            )
+            .into());
+        }
+        // DB 1
+        let in_voice = self
+            .voice_sessions
+            .find_active_sessions()
+            .await?
+            .iter()
+            .any(|session| session.user_id == from_user_id);
+        if in_voice {
+            return Err(ServiceError::InvalidAccountMerge(format!(
+                "<@{from_user_id}> is in a voice channel. Try again after they leave."
+            ))
+            .into());
+        }
+        // DB 2
+        let merge = merges
+            .merge_users(from_user_id, to_user_id, merged_by)
+            .await?;
+        self.leaderboards.clear();
+        Ok(merge)
+    }
+
+    /// Whether voice state changes are recorded as events instead of written as sessions.
+    pub fn records_voice_events(&self) -> bool {
+        self.events.is_some()
+    }
+
+    /// Appends a voice event to the event log. Returns its ID.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn record_voice_event(&self, event: &VoiceEventEntity) -> anyhow::Result<i64> {
+        let (events, _) = self.event_sourcing()?;
+        // DB 1
+        Ok(events.insert(event).await?)
+    }
+
+    /// Applies up to `limit` voice events recorded after the last projected one, then
+    /// remembers the last event applied. Returns the number of events applied.
+    ///
+    /// # Performance
+    /// * DB calls: 3, plus up to 3 per event
+    pub async fn project_voice_events(&self, limit: u32) -> anyhow::Result<u32> {
+        let (events, bot_meta) = self.event_sourcing()?;
+        let _projection = self.projection.lock().await;
+
+        // DB 1
+        let projected = Self::projected_event_id(bot_meta.as_ref()).await?;
+        // DB 2
+        let batch = events.select_after(projected, limit).await?;
+
+        let mut applied = projected;
+        let mut result = Ok(());
+        for event in &batch {
+            if let Err(e) = self.apply_voice_event(event).await {
+                result = Err(e);
+                break;
+            }
+            applied = event.id;
+        }
+        if applied != projected {
+            // DB 3
+            bot_meta
+                .replace(&BotMetaEntity {
+                    key: BotMetaKey::VoiceEventsProjected.into(),
+                    value: applied.to_string(),
+                })
+                .await?;
+        }
+        result?;
+        Ok(batch.len() as u32)
+    }
+
+    /// Recomputes a guild's voice sessions from its recorded events: sessions that
+    /// started at or after its first event are deleted, then every projected event is
+    /// applied again under the current settings. Returns the number of events replayed.
+    ///
+    /// # Performance
+    /// * DB calls: 2 + events / 1000, plus up to 3 per event
+    pub async fn rebuild_voice_sessions(&self, guild_id: u64) -> anyhow::Result<u32> {
+        let (events, bot_meta) = self.event_sourcing()?;
+        let _projection = self.projection.lock().await;
+
+        // DB 1
+        let projected = Self::projected_event_id(bot_meta.as_ref()).await?;
+        // DB 2
+        let mut batch = events
+            .select_by_guild_id_between(guild_id, 0, projected, REBUILD_BATCH_SIZE)
+            .await?;
+        let Some(first) = batch.first() else {
+            return Ok(0);
+        };
+        self.voice_sessions
+            .delete_by_guild_id_since(guild_id, &first.occurred_at)
+            .await?;
+        self.open_sessions
+            .forget_guild_since(guild_id, &first.occurred_at);
+
+        let mut replayed = 0;
+        loop {
+            for event in &batch {
+                self.apply_voice_event(event).await?;
+            }
+            replayed += batch.len() as u32;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            if batch.len() < REBUILD_BATCH_SIZE as usize {
+                break;
+            }
+            batch = events
+                .select_by_guild_id_between(guild_id, last.id, projected, REBUILD_BATCH_SIZE)
+                .await?;
+        }
+        self.leaderboards.invalidate(guild_id);
+        Ok(replayed)
+    }
+
+    /// Returns the event log and metadata repositories, or an error when event sourcing
+    /// is off.
+    fn event_sourcing(
+        &self,
+    ) -> anyhow::Result<(
+        &Arc<dyn VoiceEventsRepository + Send + Sync>,
+        &Arc<dyn BotMetaRepository + Send + Sync>,
+    )> {
+        match (&self.events, &self.bot_meta) {
+            (Some(events), Some(bot_meta)) => Ok((events, bot_meta)),
+            _ => Err(ServiceError::UnexpectedResult {
+                message: "Voice event sourcing is not enabled".to_string(),
+            }
+            .into()),
+        }
+    }
+
+    /// Reads the ID of the last voice event applied to sessions, 0 if none was.
+    async fn projected_event_id(
+        bot_meta: &(dyn BotMetaRepository + Send + Sync),
+    ) -> anyhow::Result<i64> {
+        let meta = bot_meta
+            .select(&BotMetaKey::VoiceEventsProjected.into())
+            .await?;
+        Ok(meta.and_then(|m| m.value.parse().ok()).unwrap_or(0))
+    }
+
+    /// Applies one voice event to voice sessions, the same way live voice state changes
+    /// are handled: joins close the member's open sessions and start a new one if the
+    /// channel is tracked, moves continue the session in the new channel, and leaves
+    /// close it.
+    ///
+    /// # Performance
+    /// * DB calls: up to 3, plus 1-2 per orphaned session
+    async fn apply_voice_event(&self, event: &VoiceEventEntity) -> anyhow::Result<()> {
+        let guild_id = *event.guild_id;
+        let user_id = *event.user_id;
+
+        match (event.kind, event.channel_id) {
+            (VoiceEventKind::Move, Some(channel_id)) => {
+                self.move_session(
+                    user_id,
+                    guild_id,
+                    *channel_id,
+                    event.is_bot,
+                    event.is_stage,
+                    &event.occurred_at,
+                )
+                .await
+            }
+            // Kept for rules on muted or deafened time; sessions do not change
+            (VoiceEventKind::Mute, _) => Ok(()),
+            (kind, channel_id) => {
+                // DB 1
+                let open = self
+                    .voice_sessions
+                    .find_active_sessions_by_user(user_id, guild_id)
+                    .await?;
+                for session in &open {
+                    // DB 2
+                    self.end_session(session, &event.occurred_at).await?;
+                }
+
+                if let Some(channel_id) = channel_id
+                    && kind == VoiceEventKind::Join
+                    && self
+                        .should_track(guild_id, event.is_bot, event.is_stage)
+                        .await
+                {
+                    // DB 3
+                    self.insert(&VoiceSessionsEntity {
+                        user_id,
+                        guild_id,
+                        channel_id: *channel_id,
+                        join_time: event.occurred_at,
+                        leave_time: event.occurred_at,
+                        is_active: true,
+                        ..Default::default()
+                    })
+                    .await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     pub async fn get_voice_user_count(
         &self,
         _guild_id: impl Into<u64>,
@@ -277,9 +1351,29 @@ impl VoiceTrackingService {
         self.voice_sessions
             .update_leave_time(user_id, channel_id, join_time, leave_time)
             .await?;
+        self.open_sessions
+            .set_flushed(user_id, channel_id, join_time, leave_time);
         Ok(())
     }
 
+    /// Writes `flushed_at` as the leave_time of every open session in one batch, and
+    /// resyncs the open sessions kept in memory with the database.
+    ///
+    /// # Performance
+    /// * DB calls: 1
+    pub async fn flush_open_sessions(&self, flushed_at: &DateTime<Utc>) -> anyhow::Result<u32> {
+        // DB 1
+        let sessions = self
+            .voice_sessions
+            .update_active_leave_times(flushed_at)
+            .await?;
+
+        self.open_sessions.resync(&sessions, flushed_at);
+        // Flushed time moved from memory into the database, which cached totals miss
+        self.leaderboards.clear();
+        Ok(sessions.len() as u32)
+    }
+
     /// Close a session by setting leave_time and is_active = 0 atomically
     pub async fn close_session(
         &self,
@@ -291,6 +1385,120 @@ impl VoiceTrackingService {
         self.voice_sessions
             .close_session(user_id, channel_id, join_time, leave_time)
             .await?;
+        match self.open_sessions.close(user_id, channel_id, join_time) {
+            Some(guild_id) => self.leaderboards.invalidate(guild_id),
+            None => self.leaderboards.clear(),
+        }
+        Ok(())
+    }
+
+    /// Close a session, discarding it instead if it is shorter than the guild's
+    /// minimum session length. The length counts from the first split, so a member
+    /// who moved channels is judged on the whole logical session.
+    ///
+    /// # Performance
+    /// * DB calls: 1 (2 for a later split when a minimum length is set)
+    pub async fn end_session(
+        &self,
+        session: &VoiceSessionsEntity,
+        leave_time: &DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let min_secs = self
+            .voice_settings(session.guild_id)
+            .await
+            .min_session_secs();
+        let started = match session.parent_session_id {
+            // DB 1
+            Some(parent_id) if min_secs > 0 => self
+                .voice_sessions
+                .select(&parent_id)
+                .await?
+                .map_or(session.join_time, |first| first.join_time),
+            _ => session.join_time,
+        };
+        let duration = (*leave_time - started).num_seconds();
+
+        if duration < i64::from(min_secs) {
+            // DB 2: splits of the session are removed by the cascading foreign key
+            self.voice_sessions.delete(&session.logical_id()).await?;
+            self.open_sessions
+                .close(session.user_id, session.channel_id, &session.join_time);
+            self.leaderboards.invalidate(session.guild_id);
+        } else {
+            // DB 2
+            self.close_session(
+                session.user_id,
+                session.channel_id,
+                &session.join_time,
+                leave_time,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Moves a member's open session to another channel. The current split is closed at
+    /// `moved_at` and a new split of the same logical session starts in `channel_id`, so
+    /// the session's total time stays in one piece while per-channel time stays exact.
+    /// Any other open sessions of the member are ended as orphans. The session ends
+    /// instead when the new channel is not tracked, and a new one starts when the member
+    /// had none open.
+    ///
+    /// # Performance
+    /// * DB calls: 3, plus 1-2 per orphaned session
+    pub async fn move_session(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        is_bot: bool,
+        is_stage: bool,
+        moved_at: &DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        // DB 1
+        let mut open = self
+            .voice_sessions
+            .find_active_sessions_by_user(user_id, guild_id)
+            .await?;
+        open.sort_by_key(|session| session.join_time);
+        let tracked = self.should_track(guild_id, is_bot, is_stage).await;
+        let current = if tracked { open.pop() } else { None };
+
+        for session in &open {
+            self.end_session(session, moved_at).await?;
+        }
+        if !tracked {
+            return Ok(());
+        }
+
+        let mut started_at = *moved_at;
+        if let Some(current) = &current {
+            started_at = self
+                .open_sessions
+                .get(guild_id, user_id)
+                .map_or(current.join_time, |open| open.started_at);
+            // DB 2
+            self.close_session(
+                current.user_id,
+                current.channel_id,
+                &current.join_time,
+                moved_at,
+            )
+            .await?;
+        }
+        let split = VoiceSessionsEntity {
+            user_id,
+            guild_id,
+            channel_id,
+            join_time: *moved_at,
+            leave_time: *moved_at,
+            is_active: true,
+            parent_session_id: current.as_ref().map(VoiceSessionsEntity::logical_id),
+            ..Default::default()
+        };
+        // DB 3
+        self.voice_sessions.insert(&split).await?;
+        self.open_sessions.open(&split, started_at);
         Ok(())
     }
 
@@ -325,6 +1533,11 @@ impl VoiceTrackingService {
     }
 
     /// Get daily voice activity for a specific user in a guild.
+    ///
+    /// Days follow the guild's configured timezone.
+    ///
+    /// # Performance
+    /// * DB calls: 2
     pub async fn get_user_daily_activity(
         &self,
         user_id: u64,
@@ -332,13 +1545,21 @@ impl VoiceTrackingService {
         since: &DateTime<Utc>,
         until: &DateTime<Utc>,
     ) -> anyhow::Result<Vec<VoiceDailyActivity>> {
+        // DB 1
+        let timezone = self.guild_timezone(guild_id).await?;
+        // DB 2
         Ok(self
             .voice_sessions
-            .get_user_daily_activity(user_id, guild_id, since, until)
+            .get_user_daily_activity(user_id, guild_id, since, until, &timezone)
             .await?)
     }
 
     /// Get guild-wide daily statistics.
+    ///
+    /// Days follow the guild's configured timezone.
+    ///
+    /// # Performance
+    /// * DB calls: 2
     pub async fn get_guild_daily_stats(
         &self,
         guild_id: u64,
@@ -346,19 +1567,28 @@ impl VoiceTrackingService {
         until: &DateTime<Utc>,
         stat_type: GuildStatType,
     ) -> anyhow::Result<Vec<GuildDailyStats>> {
+        // DB 1
+        let timezone = self.guild_timezone(guild_id).await?;
+        // DB 2
         match stat_type {
             GuildStatType::AverageTime => Ok(self
                 .voice_sessions
-                .get_guild_daily_average_time(guild_id, since, until)
+                .get_guild_daily_average_time(guild_id, since, until, &timezone)
                 .await?),
             GuildStatType::ActiveUserCount => Ok(self
                 .voice_sessions
-                .get_guild_daily_user_count(guild_id, since, until)
+                .get_guild_daily_user_count(guild_id, since, until, &timezone)
                 .await?),
             GuildStatType::TotalTime => Ok(self
                 .voice_sessions
-                .get_guild_daily_total_time(guild_id, since, until)
+                .get_guild_daily_total_time(guild_id, since, until, &timezone)
                 .await?),
         }
     }
+
+    /// Returns the IANA name of the guild's timezone, used to bucket daily stats.
+    async fn guild_timezone(&self, guild_id: u64) -> anyhow::Result<String> {
+        let settings = self.get_server_settings(guild_id).await?;
+        Ok(settings.general.timezone().name().to_string())
+    }
 }
@@ -0,0 +1,215 @@
+//! Subscriber that keeps a guild's server events in line with the anime it follows.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::Duration;
+use chrono::Utc;
+use log::debug;
+use log::error;
+use log::info;
+use poise::serenity_prelude::*;
+
+use crate::bot::Bot;
+use crate::bot::scheduled_event::ExternalEvent;
+use crate::bot::scheduled_event::UpcomingEvent;
+use crate::bot::scheduled_event::create_external_event;
+use crate::bot::scheduled_event::delete_event;
+use crate::bot::scheduled_event::edit_external_event;
+use crate::bot::scheduled_event::upcoming_events_by;
+use crate::entity::UpcomingRelease;
+use crate::event::AiringEventsEvent;
+use crate::event::Event;
+use crate::subscriber::Subscriber;
+
+/// How long an airing event lasts.
+const AIRING_LENGTH_MINS: i64 = 30;
+
+/// Ends the description of every airing event. Tells airing events apart from other events
+/// the bot created, such as watch parties, which also link to the anime.
+const AIRING_EVENT_FOOTER: &str = "Kept in sync with the anime this server follows.";
+
+/// Subscriber that creates, moves and deletes a guild's airing events so there is one for
+/// the next episode of each anime in the event.
+pub struct AiringEventsSubscriber {
+    bot: Arc<Bot>,
+}
+
+/// Changes that bring a guild's airing events in line with its airings.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Reconciliation<'a> {
+    create: Vec<&'a ExternalEvent>,
+    edit: Vec<(ScheduledEventId, &'a ExternalEvent)>,
+    delete: Vec<ScheduledEventId>,
+}
+
+impl AiringEventsSubscriber {
+    /// Creates a new airing events subscriber.
+    pub fn new(bot: Arc<Bot>) -> Self {
+        debug!("Initializing AiringEventsSubscriber.");
+        Self { bot }
+    }
+
+    /// Returns the event kept for an airing.
+    fn create_event(airing: &UpcomingRelease) -> ExternalEvent {
+        let episode = airing.item_name.as_deref().unwrap_or("New episode");
+        ExternalEvent {
+            name: format!("{} · {episode}", airing.feed_name),
+            description: format!(
+                "{episode} of {} airs.\n\n{AIRING_EVENT_FOOTER}",
+                airing.feed_name
+            ),
+            location: airing.source_url.clone(),
+            start: airing.expected_at,
+            end: airing.expected_at + Duration::minutes(AIRING_LENGTH_MINS),
+        }
+    }
+
+    /// Matches the wanted events to the existing airing events by the anime they link to.
+    ///
+    /// An existing event is moved to the anime's next episode rather than replaced, so
+    /// members who marked themselves interested stay so. Events of anime no longer
+    /// followed, and duplicates, are deleted.
+    fn reconcile<'a>(
+        wanted: &'a [ExternalEvent],
+        existing: &[UpcomingEvent],
+    ) -> Reconciliation<'a> {
+        let mut existing: Vec<&UpcomingEvent> = existing
+            .iter()
+            .filter(|event| event.description.ends_with(AIRING_EVENT_FOOTER))
+            .collect();
+
+        let mut plan = Reconciliation::default();
+        for event in wanted {
+            match existing.iter().position(|e| e.location == event.location) {
+                Some(index) => {
+                    let current = existing.swap_remove(index);
+                    if !current.matches(event) {
+                        plan.edit.push((current.id, event));
+                    }
+                }
+                None => plan.create.push(event),
+            }
+        }
+        plan.delete = existing.into_iter().map(|event| event.id).collect();
+        plan
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber<AiringEventsEvent> for AiringEventsSubscriber {
+    async fn callback(&self, event: AiringEventsEvent) -> Result<()> {
+        debug!("Received event `{}`", event.event_name());
+        let guild_id = GuildId::new(event.guild_id);
+        let http = &self.bot.http;
+
+        // Discord rejects events that start in the past
+        let now = Utc::now();
+        let wanted: Vec<ExternalEvent> = event
+            .airings
+            .iter()
+            .filter(|airing| airing.expected_at > now)
+            .map(Self::create_event)
+            .collect();
+        let bot_id = self.bot.cache.current_user().id;
+        let existing = upcoming_events_by(http, guild_id, bot_id).await?;
+
+        let plan = Self::reconcile(&wanted, &existing);
+        for airing in plan.create {
+            if let Err(e) = create_external_event(http, guild_id, airing).await {
+                error!("Failed to create airing event in guild {guild_id}: {e:?}");
+            }
+        }
+        for (event_id, airing) in plan.edit {
+            if let Err(e) = edit_external_event(http, guild_id, event_id, airing).await {
+                error!("Failed to update airing event {event_id} in guild {guild_id}: {e:?}");
+            }
+        }
+        for event_id in plan.delete {
+            if let Err(e) = delete_event(http, guild_id, event_id).await {
+                error!("Failed to delete airing event {event_id} in guild {guild_id}: {e:?}");
+            }
+        }
+
+        info!("Synced airing events of guild {guild_id}.");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn airing(name: &str, url: &str, at: DateTime<Utc>) -> ExternalEvent {
+        AiringEventsSubscriber::create_event(&UpcomingRelease {
+            feed_name: name.to_string(),
+            source_url: url.to_string(),
+            item_name: Some("Episode 5".to_string()),
+            expected_at: at,
+            estimated: false,
+        })
+    }
+
+    fn existing(id: u64, event: &ExternalEvent) -> UpcomingEvent {
+        UpcomingEvent {
+            id: ScheduledEventId::new(id),
+            name: event.name.clone(),
+            description: event.description.clone(),
+            location: event.location.clone(),
+            start: event.start,
+        }
+    }
+
+    #[test]
+    fn reconcile_keeps_matching_events() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let wanted = vec![airing("Frieren", "https://anilist.co/anime/1", at)];
+
+        let plan = AiringEventsSubscriber::reconcile(&wanted, &[existing(1, &wanted[0])]);
+
+        assert_eq!(plan, Reconciliation::default());
+    }
+
+    #[test]
+    fn reconcile_moves_events_to_the_next_episode() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let previous = airing("Frieren", "https://anilist.co/anime/1", at);
+        let wanted = vec![airing(
+            "Frieren",
+            "https://anilist.co/anime/1",
+            at + Duration::days(7),
+        )];
+
+        let plan = AiringEventsSubscriber::reconcile(&wanted, &[existing(1, &previous)]);
+
+        assert_eq!(plan.edit, vec![(ScheduledEventId::new(1), &wanted[0])]);
+        assert!(plan.create.is_empty());
+        assert!(plan.delete.is_empty());
+    }
+
+    #[test]
+    fn reconcile_creates_and_deletes_by_anime() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let dropped = airing("Dropped", "https://anilist.co/anime/2", at);
+        let wanted = vec![airing("Frieren", "https://anilist.co/anime/1", at)];
+
+        let plan = AiringEventsSubscriber::reconcile(&wanted, &[existing(2, &dropped)]);
+
+        assert_eq!(plan.create, vec![&wanted[0]]);
+        assert_eq!(plan.delete, vec![ScheduledEventId::new(2)]);
+    }
+
+    #[test]
+    fn reconcile_ignores_other_bot_events() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let mut watch_party = existing(3, &airing("Frieren", "https://anilist.co/anime/1", at));
+        watch_party.description = "Watch party for episode 5 of Frieren.".to_string();
+
+        let plan = AiringEventsSubscriber::reconcile(&[], &[watch_party]);
+
+        assert_eq!(plan, Reconciliation::default());
+    }
+}
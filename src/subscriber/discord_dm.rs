@@ -5,30 +5,45 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use log::debug;
-use log::error;
 use log::info;
 use poise::serenity_prelude::CreateMessage;
+use poise::serenity_prelude::GuildId;
 use poise::serenity_prelude::UserId;
 
 use crate::bot::Bot;
+use crate::bot::send_queue::SendTarget;
 use crate::entity::SubscriberEntity;
 use crate::entity::SubscriberType;
 use crate::event::Event;
 use crate::event::FeedUpdateEvent;
+use crate::event::MessageOptions;
 use crate::service::Services;
 use crate::subscriber::Subscriber;
+use crate::subscriber::fan_out::FAN_OUT_CONCURRENCY;
+use crate::subscriber::fan_out::QueuedDeliveries;
+use crate::subscriber::fan_out::fan_out;
+use crate::subscriber::fan_out::record_delivery;
+use crate::subscriber::fan_out::subscribers_for;
+use crate::subscriber::filter::Delivery;
+use crate::subscriber::filter::FilterChain;
+use crate::subscriber::filter::ServerDuplicateFilter;
 
 /// Subscriber that sends feed updates to users via DM.
 pub struct DiscordDmSubscriber {
     bot: Arc<Bot>,
     services: Arc<Services>,
+    filters: FilterChain,
 }
 
 impl DiscordDmSubscriber {
     /// Creates a new DM subscriber.
     pub fn new(bot: Arc<Bot>, services: Arc<Services>) -> Self {
         debug!("Initializing DiscordDmSubscriber.");
-        Self { bot, services }
+        Self {
+            bot,
+            services,
+            filters: FilterChain::dm(),
+        }
     }
 
     /// Handles a feed update event by sending DMs to subscribers.
@@ -36,43 +51,104 @@ impl DiscordDmSubscriber {
         debug!("Received event `{}`", event.event_name());
 
         // Get all subscriptions for this feed
-        let subs = self
-            .services
-            .feed_subscription
-            .get_subscribers_by_type_and_feed(SubscriberType::Dm, event.feed.id)
-            .await?;
-
-        for sub in subs {
-            if let Err(e) = self.handle_sub(&sub, event.data.create_message()).await {
-                error!(
-                    "Error handling subscriber id `{}` target `{}`: {:?}",
-                    sub.id, sub.target_id, e
-                );
-            }
-        }
+        let subs = subscribers_for(&self.services, SubscriberType::Dm, &event).await?;
+        let subs = self.filters.apply(subs, &event.data);
+        let subs = self.skip_server_duplicates(subs, &event).await?;
+        let queued = QueuedDeliveries::queue(&self.services, &subs, &event).await;
+
+        let event = &event;
+        let queued = &queued;
+        let report = fan_out(&subs, FAN_OUT_CONCURRENCY, |sub| async move {
+            let options = MessageOptions {
+                title_language: sub.preferences.0.title_language,
+                ..Default::default()
+            };
+            let message = event.data.create_message_with(options);
+            let result = self.handle_sub(sub, message).await;
+            record_delivery(&self.services, sub, event, result.is_ok()).await;
+            queued.finish(&self.services, sub).await;
+            result
+        })
+        .await;
+        report.log(&event.event_name(), "DM");
 
         Ok(())
     }
 
+    /// Drops subscribers who opted out of updates a server they are in already receives.
+    ///
+    /// The servers are only looked up when a subscriber opted out.
+    async fn skip_server_duplicates(
+        &self,
+        subs: Vec<SubscriberEntity>,
+        event: &FeedUpdateEvent,
+    ) -> Result<Vec<SubscriberEntity>> {
+        if !subs
+            .iter()
+            .any(|sub| sub.preferences.0.skip_server_duplicates)
+        {
+            return Ok(subs);
+        }
+
+        let servers = self.receiving_servers(event).await?;
+        let filter = ServerDuplicateFilter::new(self.bot.cache.clone(), servers);
+        Ok(FilterChain::new().with(filter).apply(subs, &event.data))
+    }
+
+    /// Returns the servers the guild subscriber delivers an update to.
+    async fn receiving_servers(&self, event: &FeedUpdateEvent) -> Result<Vec<GuildId>> {
+        let guild_filters = FilterChain::guild();
+        // A redelivery's recipients are DM subscribers, not the servers
+        let event = FeedUpdateEvent {
+            recipients: None,
+            ..event.clone()
+        };
+        let mut servers = Vec::new();
+        for sub in subscribers_for(&self.services, SubscriberType::Guild, &event).await? {
+            let Ok(guild_id) = GuildId::from_str(&sub.target_id) else {
+                continue;
+            };
+            let settings = self
+                .services
+                .settings
+                .get_server_settings(guild_id.get())
+                .await?;
+            let delivery = Delivery {
+                sub: &sub,
+                data: &event.data,
+                settings: Some(&settings),
+            };
+            if settings.feeds.channel_id.is_some() && guild_filters.allows(&delivery) {
+                servers.push(guild_id);
+            }
+        }
+        Ok(servers)
+    }
+
     /// Sends a message to a subscriber via DM.
     pub async fn handle_sub(
         &self,
         sub: &SubscriberEntity,
-        message: CreateMessage<'_>,
+        message: CreateMessage<'static>,
     ) -> anyhow::Result<()> {
         let user_id = UserId::from_str(&sub.target_id)?;
 
-        debug!("Fetching user id `{user_id}`.");
-        let user = self.bot.http.get_user(user_id).await?;
+        self.bot
+            .send_queue
+            .send(SendTarget::Dm(user_id.get()), move |http| async move {
+                debug!("Fetching user id `{user_id}`.");
+                let user = http.get_user(user_id).await?;
 
-        debug!("Fetched user id `{}` ({}). Sending DM.", user_id, user.name);
-        user.id.dm(&self.bot.http, message).await?;
+                debug!("Fetched user id `{}` ({}). Sending DM.", user_id, user.name);
+                user.id.dm(&http, message).await?;
 
-        info!(
-            "Successfully sent DM to fetched user id `{}` ({}).",
-            user_id, user.name
-        );
-        Ok(())
+                info!(
+                    "Successfully sent DM to fetched user id `{}` ({}).",
+                    user_id, user.name
+                );
+                Ok(())
+            })
+            .await
     }
 }
 
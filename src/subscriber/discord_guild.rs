@@ -4,60 +4,90 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::Result;
+use chrono::Utc;
 use log::debug;
 use log::error;
 use log::info;
+use log::warn;
 use poise::serenity_prelude::*;
 
 use crate::bot::Bot;
+use crate::bot::send_queue::SendTarget;
+use crate::entity::DeliveryFailure;
+use crate::entity::DeliveryFailureReason;
 use crate::entity::SubscriberEntity;
 use crate::entity::SubscriberType;
 use crate::event::Event;
 use crate::event::FeedUpdateEvent;
+use crate::event::MessageOptions;
 use crate::service::Services;
 use crate::subscriber::Subscriber;
+use crate::subscriber::fan_out::FAN_OUT_CONCURRENCY;
+use crate::subscriber::fan_out::QueuedDeliveries;
+use crate::subscriber::fan_out::fan_out;
+use crate::subscriber::fan_out::record_delivery;
+use crate::subscriber::fan_out::subscribers_for;
+use crate::subscriber::filter::Delivery;
+use crate::subscriber::filter::FilterChain;
+
+/// Maximum number of admins notified about a delivery failure.
+const MAX_NOTIFIED_ADMINS: usize = 5;
 
 /// Subscriber that sends feed updates to guild channels.
 pub struct DiscordGuildSubscriber {
     bot: Arc<Bot>,
     services: Arc<Services>,
+    filters: FilterChain,
 }
 
 impl DiscordGuildSubscriber {
     /// Creates a new guild subscriber.
     pub fn new(bot: Arc<Bot>, services: Arc<Services>) -> Self {
         debug!("Initializing DiscordGuildSubscriber.");
-        Self { bot, services }
+        Self {
+            bot,
+            services,
+            filters: FilterChain::guild(),
+        }
     }
 
     /// Handles a feed update event by sending messages to guild channels.
     pub async fn feed_event_callback(&self, event: FeedUpdateEvent) -> Result<()> {
         debug!("Received event `{}`", event.event_name());
 
-        let subs = self
-            .services
-            .feed_subscription
-            .get_subscribers_by_type_and_feed(SubscriberType::Guild, event.feed.id)
-            .await?;
+        let subs = subscribers_for(&self.services, SubscriberType::Guild, &event).await?;
+        let queued = QueuedDeliveries::queue(&self.services, &subs, &event).await;
 
-        for sub in subs {
-            if let Err(e) = self.handle_sub(&sub, event.data.create_message()).await {
-                error!(
-                    "Error handling subscriber id `{}` target `{}`: {:?}",
-                    sub.id, sub.target_id, e
-                );
-            }
-        }
+        let event = &event;
+        let queued = &queued;
+        let report = fan_out(&subs, FAN_OUT_CONCURRENCY, |sub| async move {
+            let result = self.handle_sub(sub, event).await;
+            queued.finish(&self.services, sub).await;
+            result
+        })
+        .await;
+        report.log(&event.event_name(), "guild");
 
         Ok(())
     }
 
     /// Sends a message to a guild channel for a subscriber.
+    ///
+    /// Skipped when the guild's [`FilterChain`] rejects it. The message is rendered
+    /// using the guild's notification style settings.
+    /// Subscriptions in a collection are sent to the collection's channel, mentioning
+    /// its role, instead of the guild's feed channel.
+    /// Failures an admin has to fix are recorded in the guild's settings and
+    /// reported to its admins. Failures in a collection's channel are only logged,
+    /// since the recorded failure tracks the feed channel.
+    /// Sent and failed notifications are logged for feed stats and delivery analytics.
+    /// Simulated updates are only sent, never recorded.
     pub async fn handle_sub(
         &self,
         sub: &SubscriberEntity,
-        message: CreateMessage<'_>,
+        event: &FeedUpdateEvent,
     ) -> anyhow::Result<()> {
+        let data = &event.data;
         let guild_id = GuildId::from_str(&sub.target_id)?;
 
         let settings = self
@@ -66,29 +96,208 @@ impl DiscordGuildSubscriber {
             .get_server_settings(guild_id.get())
             .await?;
 
-        let channel_id_str = settings
-            .feeds
-            .channel_id
-            .ok_or_else(|| anyhow::anyhow!("No channel configured for guild {}", &sub.target_id))?;
+        let delivery = Delivery {
+            sub,
+            data,
+            settings: Some(&settings),
+        };
+        if !self.filters.allows(&delivery) {
+            return Ok(());
+        }
+
+        let mut options = MessageOptions::from(&settings.feeds);
+        let collection = self
+            .services
+            .feed_collections
+            .collection_for(data.feed.id, sub.id)
+            .await?;
+        if let Some(collection) = collection {
+            options.mention_role = collection.mention_role_id.map(|id| RoleId::new(id.into()));
+            let channel_id = ChannelId::new(collection.channel_id.into());
+            let message = data.create_message_with(options);
+            let result = self.send(guild_id, channel_id, message).await;
+            record_delivery(&self.services, sub, event, result.is_ok()).await;
+            return result;
+        }
+
+        let message = data.create_message_with(options);
+
+        let Some(channel_id_str) = settings.feeds.channel_id.clone() else {
+            if !event.simulated {
+                self.report_failure(guild_id, DeliveryFailureReason::NoChannel)
+                    .await;
+            }
+            record_delivery(&self.services, sub, event, false).await;
+            anyhow::bail!("No channel configured for guild {}", &sub.target_id);
+        };
 
         let channel_id = ChannelId::from_str(&channel_id_str)?;
 
-        debug!("Fetching channel id `{channel_id}`.");
-        let channel = channel_id
-            .to_guild_channel(&self.bot.http, Some(guild_id))
+        match self.send(guild_id, channel_id, message).await {
+            Ok(()) => {
+                record_delivery(&self.services, sub, event, true).await;
+                if settings.feeds.delivery_failure.is_some() && !event.simulated {
+                    self.clear_failure(guild_id).await?;
+                }
+                Ok(())
+            }
+            Err(e) => {
+                record_delivery(&self.services, sub, event, false).await;
+                if let Some(reason) = Self::failure_reason(&e)
+                    && !event.simulated
+                {
+                    self.report_failure(guild_id, reason).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Sends a message through the bot's send queue.
+    async fn send(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message: CreateMessage<'static>,
+    ) -> Result<()> {
+        let target = SendTarget::Channel(channel_id.get());
+        self.bot
+            .send_queue
+            .send(target, move |http| async move {
+                debug!("Fetching channel id `{channel_id}`.");
+                let channel = channel_id.to_guild_channel(&http, Some(guild_id)).await?;
+
+                debug!(
+                    "Fetched channel id `{}` ({}). Sending message.",
+                    channel_id, channel.base.name
+                );
+                channel.send_message(&http, message).await?;
+
+                info!(
+                    "Successfully sent message to fetched channel id `{}` ({}).",
+                    channel_id, channel.base.name
+                );
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns why a send failed, if it is something an admin has to fix.
+    fn failure_reason(e: &anyhow::Error) -> Option<DeliveryFailureReason> {
+        match e.downcast_ref::<SerenityError>() {
+            Some(SerenityError::Http(HttpError::UnsuccessfulRequest(response))) => {
+                DeliveryFailureReason::from_status(response.status_code.as_u16())
+            }
+            _ => None,
+        }
+    }
+
+    /// Flags the guild's feed settings as broken and notifies its admins.
+    ///
+    /// Admins are notified once per failure; repeats of an already recorded
+    /// failure are ignored until a delivery succeeds or the channel changes.
+    /// The settings are read again so edits saved during the delivery are kept.
+    async fn report_failure(&self, guild_id: GuildId, reason: DeliveryFailureReason) {
+        let mut settings = match self
+            .services
+            .settings
+            .get_server_settings(guild_id.get())
+            .await
+        {
+            Ok(settings) => settings,
+            Err(e) => {
+                error!("Failed to record delivery failure for guild `{guild_id}`: {e:?}");
+                return;
+            }
+        };
+        let feeds = &mut settings.feeds;
+        let already_reported = feeds.delivery_failure.as_ref().is_some_and(|failure| {
+            failure.reason == reason && failure.channel_id == feeds.channel_id
+        });
+        if already_reported {
+            return;
+        }
+
+        warn!("Feed delivery to guild `{guild_id}` failed: {reason:?}. Notifying admins.");
+        feeds.delivery_failure = Some(DeliveryFailure {
+            reason,
+            channel_id: feeds.channel_id.clone(),
+            failed_at: Utc::now(),
+        });
+        if let Err(e) = self
+            .services
+            .settings
+            .update_server_settings(guild_id.get(), settings)
+            .await
+        {
+            error!("Failed to record delivery failure for guild `{guild_id}`: {e:?}");
+            return;
+        }
+
+        self.notify_admins(guild_id, reason).await;
+    }
+
+    /// Clears a recorded failure after a successful delivery.
+    async fn clear_failure(&self, guild_id: GuildId) -> Result<()> {
+        info!("Feed delivery to guild `{guild_id}` recovered.");
+        let mut settings = self
+            .services
+            .settings
+            .get_server_settings(guild_id.get())
+            .await?;
+        settings.feeds.delivery_failure = None;
+        self.services
+            .settings
+            .update_server_settings(guild_id.get(), settings)
             .await?;
+        Ok(())
+    }
 
-        debug!(
-            "Fetched channel id `{}` ({}). Sending message.",
-            channel_id, channel.base.name
-        );
-        channel.send_message(&self.bot.http, message).await?;
+    /// DMs the guild's admins what to fix.
+    async fn notify_admins(&self, guild_id: GuildId, reason: DeliveryFailureReason) {
+        let Some((guild_name, admin_ids)) = self
+            .bot
+            .cache
+            .guild(guild_id)
+            .map(|guild| (guild.name.to_string(), Self::admin_ids(&guild)))
+        else {
+            return;
+        };
 
-        info!(
-            "Successfully sent message to fetched channel id `{}` ({}).",
-            channel_id, channel.base.name
+        let content = format!(
+            "⚠️ Feed updates for **{guild_name}** could not be delivered.\n\n{}",
+            reason.advice()
         );
-        Ok(())
+        for user_id in admin_ids {
+            let message = CreateMessage::new().content(content.clone());
+            let sent = self
+                .bot
+                .send_queue
+                .send(SendTarget::Dm(user_id.get()), move |http| async move {
+                    user_id.dm(&http, message).await?;
+                    Ok(())
+                })
+                .await;
+            // Admins may have DMs closed; the failure is still shown in `/feed settings`
+            if let Err(e) = sent {
+                debug!("Could not DM admin `{user_id}` of guild `{guild_id}`: {e}");
+            }
+        }
+    }
+
+    /// Returns the guild owner and cached members with `Administrator` or `Manage Server`.
+    fn admin_ids(guild: &Guild) -> Vec<UserId> {
+        let mut ids = vec![guild.owner_id];
+        for member in guild.members.iter().filter(|member| !member.user.bot()) {
+            let permissions = guild.member_permissions(member);
+            let is_admin = permissions.contains(Permissions::ADMINISTRATOR)
+                || permissions.contains(Permissions::MANAGE_GUILD);
+            if is_admin && !ids.contains(&member.user.id) {
+                ids.push(member.user.id);
+            }
+        }
+        ids.truncate(MAX_NOTIFIED_ADMINS);
+        ids
     }
 }
 
@@ -0,0 +1,311 @@
+//! Bounded concurrent delivery of one event to many subscribers.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::future::Future;
+
+use anyhow::Result;
+use chrono::Utc;
+use futures::StreamExt;
+use futures::stream;
+use log::error;
+use log::info;
+use log::warn;
+
+use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberType;
+use crate::event::FeedUpdateData;
+use crate::event::FeedUpdateEvent;
+use crate::event::FeedUpdateKind;
+use crate::service::Services;
+
+/// Maximum number of deliveries in flight per event.
+///
+/// Sends are paced by the bot's send queue; this only bounds how many wait there at once.
+pub const FAN_OUT_CONCURRENCY: usize = 16;
+
+/// Maximum number of targets listed per distinct error in a report.
+const MAX_LISTED_TARGETS: usize = 5;
+
+/// A subscriber an event could not be delivered to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryError {
+    pub subscriber_id: i32,
+    pub target_id: String,
+    pub error: String,
+}
+
+/// Outcome of delivering one event to every subscriber.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FanOutReport {
+    pub delivered: usize,
+    pub failures: Vec<DeliveryError>,
+}
+
+impl FanOutReport {
+    /// Total number of subscribers the event was delivered to or attempted.
+    pub fn total(&self) -> usize {
+        self.delivered + self.failures.len()
+    }
+
+    /// Summarizes the failures, grouping subscribers that failed with the same error.
+    pub fn summary(&self) -> String {
+        let mut by_error: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for failure in &self.failures {
+            by_error
+                .entry(&failure.error)
+                .or_default()
+                .push(&failure.target_id);
+        }
+
+        let groups = by_error
+            .into_iter()
+            .map(|(error, targets)| {
+                let listed = targets
+                    .iter()
+                    .take(MAX_LISTED_TARGETS)
+                    .map(|target| format!("`{target}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let more = targets.len().saturating_sub(MAX_LISTED_TARGETS);
+                let more = if more > 0 {
+                    format!(" and {more} more")
+                } else {
+                    String::new()
+                };
+                format!("{}× {error} ({listed}{more})", targets.len())
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        format!(
+            "{} of {} deliveries failed: {groups}",
+            self.failures.len(),
+            self.total()
+        )
+    }
+
+    /// Logs one line for the whole fan-out.
+    pub fn log(&self, event_name: &str, kind: &str) {
+        if self.failures.is_empty() {
+            info!(
+                "Delivered `{event_name}` to {} {kind} subscribers.",
+                self.delivered
+            );
+        } else {
+            error!(
+                "Delivering `{event_name}` to {kind} subscribers: {}",
+                self.summary()
+            );
+        }
+    }
+}
+
+/// Returns the subscribers of a type that should receive an event.
+///
+/// Edits only go to subscriptions that opted into edit notifications. An update
+/// delivered again from the outbox only goes to its recipients.
+pub async fn subscribers_for(
+    services: &Services,
+    subscriber_type: SubscriberType,
+    event: &FeedUpdateEvent,
+) -> Result<Vec<SubscriberEntity>> {
+    let feed_subscription = &services.feed_subscription;
+    let subs = match event.data.kind {
+        FeedUpdateKind::New => {
+            feed_subscription
+                .get_subscribers_by_type_and_feed(subscriber_type, event.feed.id)
+                .await?
+        }
+        FeedUpdateKind::Edited => {
+            feed_subscription
+                .get_edit_subscribers_by_type_and_feed(subscriber_type, event.feed.id)
+                .await?
+        }
+    };
+    Ok(match &event.recipients {
+        Some(recipients) => subs
+            .into_iter()
+            .filter(|sub| recipients.contains(&sub.id))
+            .collect(),
+        None => subs,
+    })
+}
+
+/// Outbox IDs of the notifications of one event, by subscriber ID.
+#[derive(Debug, Default)]
+pub struct QueuedDeliveries(HashMap<i32, i32>);
+
+impl QueuedDeliveries {
+    /// Puts an event's notifications in the outbox before they are sent, so the next
+    /// start sends them if this run stops first. A failure to queue is only logged and
+    /// the notifications are still sent, without that guarantee. Simulated updates are
+    /// not queued.
+    pub async fn queue(
+        services: &Services,
+        subs: &[SubscriberEntity],
+        event: &FeedUpdateEvent,
+    ) -> Self {
+        if event.simulated {
+            return Self::default();
+        }
+        let data = &event.data;
+        let queued = match serde_json::to_value(data) {
+            Ok(value) => {
+                let subscriber_ids: Vec<i32> = subs.iter().map(|sub| sub.id).collect();
+                services
+                    .feed_stats
+                    .queue_deliveries(&subscriber_ids, &value)
+                    .await
+                    .map_err(anyhow::Error::from)
+            }
+            Err(e) => Err(e.into()),
+        };
+        match queued {
+            Ok(ids) => Self(ids),
+            Err(e) => {
+                warn!(
+                    "Failed to queue notifications of feed {} in the outbox: {e}",
+                    data.feed.id
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Removes a subscriber's notification from the outbox once it was sent or failed.
+    pub async fn finish(&self, services: &Services, sub: &SubscriberEntity) {
+        let Some(outbox_id) = self.0.get(&sub.id) else {
+            return;
+        };
+        if let Err(e) = services.feed_stats.finish_delivery(*outbox_id).await {
+            warn!(
+                "Failed to remove notification {outbox_id} of subscriber {} from the outbox: {e}",
+                sub.id
+            );
+        }
+    }
+}
+
+/// Logs a notification sent to a subscriber, or one that failed, for feed stats and
+/// delivery analytics. Failures to log are only logged, so they never fail a delivery.
+/// Simulated updates are not logged.
+pub async fn record_delivery(
+    services: &Services,
+    sub: &SubscriberEntity,
+    event: &FeedUpdateEvent,
+    delivered: bool,
+) {
+    if event.simulated {
+        return;
+    }
+    let data = &event.data;
+    let feed_id = data.feed.id;
+    if let Err(e) = services
+        .feed_stats
+        .record_delivery(sub.id, feed_id, delivered, delivery_delay(data))
+        .await
+    {
+        warn!(
+            "Failed to log delivery of feed {feed_id} to subscriber {}: {e}",
+            sub.id
+        );
+    }
+}
+
+/// Seconds from an item's publication until now. `None` for edits, whose items were
+/// published long before.
+fn delivery_delay(data: &FeedUpdateData) -> Option<i32> {
+    if data.kind != FeedUpdateKind::New {
+        return None;
+    }
+    let delay = (Utc::now() - data.new_feed_item.published).num_seconds();
+    Some(delay.clamp(0, i64::from(i32::MAX)) as i32)
+}
+
+/// Runs `deliver` for every subscriber, with at most `concurrency` deliveries in flight.
+pub async fn fan_out<'a, F, Fut>(
+    subs: &'a [SubscriberEntity],
+    concurrency: usize,
+    deliver: F,
+) -> FanOutReport
+where
+    F: Fn(&'a SubscriberEntity) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let results: Vec<_> = stream::iter(subs)
+        .map(|sub| {
+            let delivery = deliver(sub);
+            async move { (sub, delivery.await) }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut report = FanOutReport::default();
+    for (sub, result) in results {
+        match result {
+            Ok(()) => report.delivered += 1,
+            Err(e) => report.failures.push(DeliveryError {
+                subscriber_id: sub.id,
+                target_id: sub.target_id.clone(),
+                error: format!("{e:#}"),
+            }),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(id: i32) -> SubscriberEntity {
+        SubscriberEntity {
+            id,
+            target_id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn fan_out_collects_failures() {
+        let subs: Vec<_> = (1..=4).map(sub).collect();
+
+        let report = fan_out(&subs, 2, |sub| async move {
+            if sub.id % 2 == 0 {
+                anyhow::bail!("Missing Permissions");
+            }
+            Ok(())
+        })
+        .await;
+
+        assert_eq!(report.delivered, 2);
+        assert_eq!(report.total(), 4);
+        let mut failed: Vec<_> = report.failures.iter().map(|f| f.subscriber_id).collect();
+        failed.sort();
+        assert_eq!(failed, vec![2, 4]);
+    }
+
+    #[test]
+    fn summary_groups_failures_by_error() {
+        let failure = |target: &str, error: &str| DeliveryError {
+            subscriber_id: 0,
+            target_id: target.to_string(),
+            error: error.to_string(),
+        };
+        let report = FanOutReport {
+            delivered: 7,
+            failures: vec![
+                failure("1", "Missing Permissions"),
+                failure("2", "Unknown Channel"),
+                failure("3", "Missing Permissions"),
+            ],
+        };
+
+        assert_eq!(
+            report.summary(),
+            "3 of 10 deliveries failed: 2× Missing Permissions (`1`, `3`); 1× Unknown Channel (`2`)"
+        );
+    }
+}
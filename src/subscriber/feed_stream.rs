@@ -0,0 +1,89 @@
+//! Subscriber that fans feed updates out to live API streams.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use log::debug;
+use tokio::sync::broadcast;
+
+use crate::entity::SubscriberType;
+use crate::event::Event;
+use crate::event::FeedUpdateEvent;
+use crate::service::Services;
+use crate::subscriber::Subscriber;
+use crate::subscriber::fan_out::subscribers_for;
+
+/// Number of updates buffered for slow stream clients before they start skipping.
+const STREAM_CAPACITY: usize = 64;
+
+/// A feed update tagged with the guilds subscribed to its feed.
+#[derive(Clone, Debug)]
+pub struct GuildFeedUpdate {
+    pub guild_ids: Arc<Vec<u64>>,
+    pub event: FeedUpdateEvent,
+}
+
+impl GuildFeedUpdate {
+    /// Returns whether the given guild is subscribed to the updated feed.
+    pub fn is_for(&self, guild_id: u64) -> bool {
+        self.guild_ids.contains(&guild_id)
+    }
+}
+
+/// Subscriber that rebroadcasts feed updates to connected stream clients.
+pub struct FeedStreamSubscriber {
+    services: Arc<Services>,
+    tx: broadcast::Sender<GuildFeedUpdate>,
+}
+
+impl FeedStreamSubscriber {
+    /// Creates a new stream subscriber.
+    pub fn new(services: Arc<Services>) -> Self {
+        debug!("Initializing FeedStreamSubscriber.");
+        let (tx, _) = broadcast::channel(STREAM_CAPACITY);
+        Self { services, tx }
+    }
+
+    /// Opens a new receiver for feed updates published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<GuildFeedUpdate> {
+        self.tx.subscribe()
+    }
+
+    /// Looks up the guilds subscribed to the updated feed and broadcasts the update.
+    pub async fn feed_event_callback(&self, event: FeedUpdateEvent) -> Result<()> {
+        // Nobody is listening, so skip the subscriber lookup
+        if self.tx.receiver_count() == 0 {
+            return Ok(());
+        }
+        // Redeliveries were streamed when first published; simulated updates never are
+        if event.recipients.is_some() || event.simulated {
+            return Ok(());
+        }
+        debug!("Streaming event `{}`", event.event_name());
+
+        let guild_ids = subscribers_for(&self.services, SubscriberType::Guild, &event)
+            .await?
+            .iter()
+            .filter_map(|sub| u64::from_str(&sub.target_id).ok())
+            .collect::<Vec<_>>();
+
+        if guild_ids.is_empty() {
+            return Ok(());
+        }
+
+        // Receivers may disconnect in between; a failed send only means nobody is left
+        let _ = self.tx.send(GuildFeedUpdate {
+            guild_ids: Arc::new(guild_ids),
+            event,
+        });
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber<FeedUpdateEvent> for FeedStreamSubscriber {
+    async fn callback(&self, event: FeedUpdateEvent) -> Result<()> {
+        self.feed_event_callback(event).await
+    }
+}
@@ -0,0 +1,307 @@
+//! Filters deciding which subscribers are notified of a feed update.
+//!
+//! Each delivery rule is a [`NotificationFilter`]. Subscribers compose the rules that
+//! apply to them into a [`FilterChain`] and run it before delivering, so a new rule is
+//! a new filter rather than another condition in a subscriber.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use log::debug;
+use poise::serenity_prelude::Cache;
+use poise::serenity_prelude::GuildId;
+use poise::serenity_prelude::UserId;
+
+use crate::entity::ServerSettings;
+use crate::entity::SubscriberEntity;
+use crate::event::FeedUpdateData;
+
+/// A feed update about to be delivered to one subscriber.
+pub struct Delivery<'a> {
+    pub sub: &'a SubscriberEntity,
+    pub data: &'a FeedUpdateData,
+    /// Settings of the subscriber's server. `None` for DM subscribers.
+    pub settings: Option<&'a ServerSettings>,
+}
+
+/// A rule run for every subscriber before a feed update is delivered to it.
+pub trait NotificationFilter: Send + Sync {
+    /// Name used when logging skipped subscribers.
+    fn name(&self) -> &'static str;
+
+    /// Whether the update should be delivered.
+    fn allows(&self, delivery: &Delivery<'_>) -> bool;
+}
+
+/// Skips items of platforms the subscriber muted or whose title contains a word they
+/// excluded.
+pub struct PlatformPreferenceFilter;
+
+impl NotificationFilter for PlatformPreferenceFilter {
+    fn name(&self) -> &'static str {
+        "platform preferences"
+    }
+
+    fn allows(&self, delivery: &Delivery<'_>) -> bool {
+        delivery.sub.preferences.0.allows(
+            &delivery.data.feed.platform_id,
+            &delivery.data.new_feed_item.description,
+        )
+    }
+}
+
+/// Skips servers that paused feed notifications.
+pub struct FeedsEnabledFilter;
+
+impl NotificationFilter for FeedsEnabledFilter {
+    fn name(&self) -> &'static str {
+        "feeds paused"
+    }
+
+    fn allows(&self, delivery: &Delivery<'_>) -> bool {
+        delivery
+            .settings
+            .is_none_or(|settings| settings.feeds.enabled.unwrap_or(true))
+    }
+}
+
+/// Skips servers whose release calendar replaces item notifications.
+pub struct CalendarModeFilter;
+
+impl NotificationFilter for CalendarModeFilter {
+    fn name(&self) -> &'static str {
+        "calendar only"
+    }
+
+    fn allows(&self, delivery: &Delivery<'_>) -> bool {
+        delivery
+            .settings
+            .is_none_or(|settings| settings.feeds.calendar_mode().notifies_items())
+    }
+}
+
+/// Skips DM subscribers who opted out of updates a server they are in already
+/// receives. Membership is read from the bot's cache, so servers the cache has no
+/// members of never count.
+pub struct ServerDuplicateFilter {
+    cache: Arc<Cache>,
+    /// Servers the update is delivered to.
+    servers: Vec<GuildId>,
+}
+
+impl ServerDuplicateFilter {
+    pub fn new(cache: Arc<Cache>, servers: Vec<GuildId>) -> Self {
+        Self { cache, servers }
+    }
+}
+
+impl NotificationFilter for ServerDuplicateFilter {
+    fn name(&self) -> &'static str {
+        "server duplicate"
+    }
+
+    fn allows(&self, delivery: &Delivery<'_>) -> bool {
+        if !delivery.sub.preferences.0.skip_server_duplicates {
+            return true;
+        }
+        let Ok(user_id) = UserId::from_str(&delivery.sub.target_id) else {
+            return true;
+        };
+        !self.servers.iter().any(|guild_id| {
+            self.cache
+                .guild(*guild_id)
+                .is_some_and(|guild| guild.members.contains_key(&user_id))
+        })
+    }
+}
+
+/// Filters run in order. A subscriber is notified only if every filter allows it.
+pub struct FilterChain {
+    filters: Vec<Box<dyn NotificationFilter>>,
+}
+
+impl Default for FilterChain {
+    /// The filters every feed update goes through.
+    fn default() -> Self {
+        Self::new().with(PlatformPreferenceFilter)
+    }
+}
+
+impl FilterChain {
+    /// Creates a chain without filters.
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+        }
+    }
+
+    /// The filters run for DM subscribers.
+    pub fn dm() -> Self {
+        Self::default()
+    }
+
+    /// The filters run for server subscribers, which also follow the server's settings.
+    pub fn guild() -> Self {
+        Self::default()
+            .with(FeedsEnabledFilter)
+            .with(CalendarModeFilter)
+    }
+
+    /// Appends a filter to the chain.
+    pub fn with(mut self, filter: impl NotificationFilter + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Whether every filter allows the delivery. Logs the filter that rejected it.
+    pub fn allows(&self, delivery: &Delivery<'_>) -> bool {
+        match self.filters.iter().find(|f| !f.allows(delivery)) {
+            Some(filter) => {
+                debug!(
+                    "Skipping subscriber {} for feed {}: filtered by {}",
+                    delivery.sub.id,
+                    delivery.data.feed.id,
+                    filter.name()
+                );
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Keeps the subscribers every filter allows, for subscribers without server
+    /// settings.
+    pub fn apply(
+        &self,
+        subs: Vec<SubscriberEntity>,
+        data: &FeedUpdateData,
+    ) -> Vec<SubscriberEntity> {
+        subs.into_iter()
+            .filter(|sub| {
+                self.allows(&Delivery {
+                    sub,
+                    data,
+                    settings: None,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::entity::CalendarMode;
+    use crate::entity::FeedEntity;
+    use crate::entity::FeedItemEntity;
+    use crate::entity::Json;
+    use crate::entity::PlatformPreferences;
+    use crate::entity::SubscriberPreferences;
+    use crate::feed::PlatformInfo;
+
+    fn data(platform_id: &str, title: &str) -> FeedUpdateData {
+        FeedUpdateData {
+            feed: Arc::new(FeedEntity {
+                platform_id: platform_id.to_string(),
+                ..Default::default()
+            }),
+            feed_info: Arc::new(PlatformInfo::default()),
+            old_feed_item: None,
+            new_feed_item: Arc::new(FeedItemEntity {
+                description: title.to_string(),
+                ..Default::default()
+            }),
+            kind: Default::default(),
+        }
+    }
+
+    fn sub(id: i32, platform_id: &str, preferences: PlatformPreferences) -> SubscriberEntity {
+        let mut prefs = SubscriberPreferences::default();
+        prefs.set_platform(platform_id, preferences);
+        SubscriberEntity {
+            id,
+            preferences: Json(prefs),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_chain_applies_platform_preferences() {
+        let subs = vec![
+            sub(1, "AniList Anime", PlatformPreferences::default()),
+            sub(
+                2,
+                "AniList Anime",
+                PlatformPreferences {
+                    muted: true,
+                    ..Default::default()
+                },
+            ),
+            sub(
+                3,
+                "AniList Anime",
+                PlatformPreferences {
+                    excluded_words: vec!["Trailer".to_string()],
+                    ..Default::default()
+                },
+            ),
+            sub(
+                4,
+                "MangaDex",
+                PlatformPreferences {
+                    muted: true,
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        let kept = FilterChain::default().apply(subs, &data("AniList Anime", "PV trailer 2"));
+
+        let ids: Vec<i32> = kept.iter().map(|sub| sub.id).collect();
+        assert_eq!(ids, vec![1, 4]);
+    }
+
+    #[test]
+    fn guild_chain_follows_server_settings() {
+        let sub = sub(1, "AniList Anime", PlatformPreferences::default());
+        let data = data("AniList Anime", "12");
+        let mut settings = ServerSettings::default();
+        let allows = |settings: &ServerSettings| {
+            FilterChain::guild().allows(&Delivery {
+                sub: &sub,
+                data: &data,
+                settings: Some(settings),
+            })
+        };
+
+        assert!(allows(&settings));
+
+        settings.feeds.release_calendar = Some(CalendarMode::Instead);
+        assert!(!allows(&settings));
+
+        settings.feeds.release_calendar = None;
+        settings.feeds.enabled = Some(false);
+        assert!(!allows(&settings));
+    }
+
+    #[test]
+    fn empty_chain_keeps_everyone() {
+        let subs = vec![sub(
+            1,
+            "AniList Anime",
+            PlatformPreferences {
+                muted: true,
+                ..Default::default()
+            },
+        )];
+
+        assert_eq!(
+            FilterChain::new()
+                .apply(subs, &data("AniList Anime", "12"))
+                .len(),
+            1
+        );
+    }
+}
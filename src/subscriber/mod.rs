@@ -1,7 +1,13 @@
 //! Event subscribers that handle published events.
 
+pub mod airing_events;
 pub mod discord_dm;
 pub mod discord_guild;
+pub mod fan_out;
+pub mod feed_stream;
+pub mod filter;
+pub mod release_calendar;
+pub mod voice_goal;
 pub mod voice_state;
 
 use anyhow::Result;
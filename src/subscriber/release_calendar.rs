@@ -0,0 +1,219 @@
+//! Subscriber that keeps the pinned release calendars of guilds up to date.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use log::debug;
+use log::info;
+use log::warn;
+use poise::serenity_prelude::*;
+
+use crate::bot::Bot;
+use crate::bot::send_queue::SendTarget;
+use crate::entity::BotMetaKey;
+use crate::entity::UpcomingRelease;
+use crate::event::Event;
+use crate::event::ReleaseCalendarEvent;
+use crate::service::traits::InternalOps;
+use crate::subscriber::Subscriber;
+
+/// Subscriber that edits a guild's pinned release calendar, or posts and pins a new one
+/// when there is none in the event's channel.
+pub struct ReleaseCalendarSubscriber {
+    bot: Arc<Bot>,
+    internal: Arc<dyn InternalOps>,
+}
+
+impl ReleaseCalendarSubscriber {
+    /// Creates a new release calendar subscriber.
+    pub fn new(bot: Arc<Bot>, internal: Arc<dyn InternalOps>) -> Self {
+        debug!("Initializing ReleaseCalendarSubscriber.");
+        Self { bot, internal }
+    }
+
+    /// Formats the calendar of a guild.
+    fn create_components(event: &ReleaseCalendarEvent) -> Vec<CreateComponent<'static>> {
+        let mut text = format!(
+            "### 📅 Upcoming Releases\n-# Updated <t:{}:R>",
+            chrono::Utc::now().timestamp()
+        );
+        if event.releases.is_empty() {
+            text.push_str("\nNo releases are expected in the next 7 days.");
+        }
+        for release in &event.releases {
+            text.push_str(&Self::format_release(release));
+        }
+        if event.releases.iter().any(|release| release.estimated) {
+            text.push_str(
+                "\n-# ~ Estimated from how often the feed released so far. Updated daily.",
+            );
+        }
+
+        vec![CreateComponent::Container(CreateContainer::new(vec![
+            CreateContainerComponent::TextDisplay(CreateTextDisplay::new(text)),
+        ]))]
+    }
+
+    fn format_release(release: &UpcomingRelease) -> String {
+        let timestamp = release.expected_at.timestamp();
+        format!(
+            "\n- {}<t:{timestamp}:f> · [{}](<{}>){}",
+            if release.estimated { "~" } else { "" },
+            release.feed_name,
+            release.source_url,
+            release
+                .item_name
+                .as_ref()
+                .map(|name| format!(" · {name}"))
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Reads the stored `(channel_id, message_id)` of a guild's calendar.
+    fn parse_message_ref(value: &str) -> Option<(u64, u64)> {
+        let (channel_id, message_id) = value.split_once(':')?;
+        Some((channel_id.parse().ok()?, message_id.parse().ok()?))
+    }
+
+    /// Edits the calendar message in place.
+    async fn edit(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        event: &ReleaseCalendarEvent,
+    ) -> Result<()> {
+        let components = Self::create_components(event);
+        self.bot
+            .send_queue
+            .send(
+                SendTarget::Channel(channel_id.get()),
+                move |http| async move {
+                    GenericChannelId::from(channel_id)
+                        .edit_message(&http, message_id, EditMessage::new().components(components))
+                        .await?;
+                    Ok(())
+                },
+            )
+            .await
+    }
+
+    /// Posts and pins a new calendar message. Returns its ID.
+    async fn post(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        event: &ReleaseCalendarEvent,
+    ) -> Result<u64> {
+        let message = CreateMessage::new()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(Self::create_components(event));
+        let sent_id = Arc::new(AtomicU64::new(0));
+        let sent = sent_id.clone();
+        self.bot
+            .send_queue
+            .send(
+                SendTarget::Channel(channel_id.get()),
+                move |http| async move {
+                    let channel = channel_id.to_guild_channel(&http, Some(guild_id)).await?;
+                    let message = channel.send_message(&http, message).await?;
+                    sent.store(message.id.get(), Ordering::Relaxed);
+                    message.pin(&http, Some("Release calendar")).await?;
+                    Ok(())
+                },
+            )
+            .await?;
+        Ok(sent_id.load(Ordering::Relaxed))
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber<ReleaseCalendarEvent> for ReleaseCalendarSubscriber {
+    async fn callback(&self, event: ReleaseCalendarEvent) -> Result<()> {
+        debug!("Received event `{}`", event.event_name());
+
+        let guild_id = GuildId::new(event.guild_id);
+        let channel_id = ChannelId::new(event.channel_id);
+
+        let stored = self
+            .internal
+            .get_meta(BotMetaKey::ReleaseCalendarMessage(event.guild_id))
+            .await?
+            .and_then(|value| Self::parse_message_ref(&value));
+        // A calendar in another channel is left behind when the feed channel changes
+        if let Some((stored_channel, message_id)) = stored
+            && stored_channel == event.channel_id
+        {
+            match self
+                .edit(channel_id, MessageId::new(message_id), &event)
+                .await
+            {
+                Ok(()) => {
+                    debug!("Updated release calendar of guild {}", event.guild_id);
+                    return Ok(());
+                }
+                Err(e) => warn!(
+                    "Failed to edit release calendar of guild {}, posting a new one: {e}",
+                    event.guild_id
+                ),
+            }
+        }
+
+        let message_id = self.post(guild_id, channel_id, &event).await?;
+        self.internal
+            .set_meta(
+                BotMetaKey::ReleaseCalendarMessage(event.guild_id),
+                format!("{}:{message_id}", event.channel_id),
+            )
+            .await?;
+
+        info!(
+            "Pinned release calendar of guild {} in channel {}",
+            event.guild_id, event.channel_id
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn format_release_marks_estimates() {
+        let release = UpcomingRelease {
+            feed_name: "Frieren".to_string(),
+            source_url: "https://anilist.co/anime/154587".to_string(),
+            item_name: Some("Episode 3".to_string()),
+            expected_at: Utc.with_ymd_and_hms(2026, 10, 17, 15, 0, 0).unwrap(),
+            estimated: false,
+        };
+
+        assert_eq!(
+            ReleaseCalendarSubscriber::format_release(&release),
+            "\n- <t:1792249200:f> · [Frieren](<https://anilist.co/anime/154587>) · Episode 3"
+        );
+        let estimated = UpcomingRelease {
+            item_name: None,
+            estimated: true,
+            ..release
+        };
+        assert!(
+            ReleaseCalendarSubscriber::format_release(&estimated)
+                .starts_with("\n- ~<t:1792249200:f>")
+        );
+    }
+
+    #[test]
+    fn parse_message_ref_reads_channel_and_message() {
+        assert_eq!(
+            ReleaseCalendarSubscriber::parse_message_ref("123:456"),
+            Some((123, 456))
+        );
+        assert_eq!(ReleaseCalendarSubscriber::parse_message_ref("123"), None);
+    }
+}
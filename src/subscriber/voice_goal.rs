@@ -0,0 +1,72 @@
+//! Subscriber that announces reached voice goals in guild channels.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use log::debug;
+use log::info;
+use poise::serenity_prelude::*;
+
+use crate::bot::Bot;
+use crate::bot::send_queue::SendTarget;
+use crate::bot::utils::format_duration;
+use crate::event::Event;
+use crate::event::VoiceGoalReachedEvent;
+use crate::subscriber::Subscriber;
+
+/// Subscriber that posts an announcement when a server reaches its monthly voice goal.
+pub struct VoiceGoalSubscriber {
+    bot: Arc<Bot>,
+}
+
+impl VoiceGoalSubscriber {
+    /// Creates a new goal subscriber.
+    pub fn new(bot: Arc<Bot>) -> Self {
+        debug!("Initializing VoiceGoalSubscriber.");
+        Self { bot }
+    }
+
+    /// Formats the announcement for a reached goal.
+    fn create_message(event: &VoiceGoalReachedEvent) -> CreateMessage<'static> {
+        let text = format!(
+            "### 🎉 Voice goal reached!\nThis server spent **{}** in voice this month, reaching its goal of **{}**.",
+            format_duration(event.progress.total_seconds),
+            format_duration(event.progress.goal_seconds),
+        );
+        CreateMessage::new()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(vec![CreateComponent::Container(CreateContainer::new(
+                vec![CreateContainerComponent::TextDisplay(
+                    CreateTextDisplay::new(text),
+                )],
+            ))])
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber<VoiceGoalReachedEvent> for VoiceGoalSubscriber {
+    async fn callback(&self, event: VoiceGoalReachedEvent) -> Result<()> {
+        debug!("Received event `{}`", event.event_name());
+
+        let guild_id = GuildId::new(event.guild_id);
+        let channel_id = ChannelId::new(event.channel_id);
+        let message = Self::create_message(&event);
+        self.bot
+            .send_queue
+            .send(
+                SendTarget::Channel(channel_id.get()),
+                move |http| async move {
+                    let channel = channel_id.to_guild_channel(&http, Some(guild_id)).await?;
+                    channel.send_message(&http, message).await?;
+                    Ok(())
+                },
+            )
+            .await?;
+
+        info!(
+            "Announced voice goal of guild {} in channel {}",
+            event.guild_id, event.channel_id
+        );
+        Ok(())
+    }
+}
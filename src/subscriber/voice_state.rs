@@ -2,19 +2,35 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use anyhow::Result;
 use chrono::DateTime;
 use chrono::Utc;
+use dashmap::DashMap;
 use log::debug;
 use poise::serenity_prelude::ChannelId;
-use tokio::sync::Mutex;
+use poise::serenity_prelude::GuildId;
+use poise::serenity_prelude::VoiceState;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 
+use crate::entity::VoiceEventEntity;
+use crate::entity::VoiceEventKind;
 use crate::entity::VoiceSessionsEntity;
 use crate::event::VoiceStateEvent;
+use crate::handoff::Handoff;
+use crate::handoff::HandoffSession;
 use crate::service::Services;
 use crate::subscriber::Subscriber;
 
+/// How long a guild's actor waits for a message before it stops, if it tracks no
+/// session. The guild's next message starts a new one.
+const ACTOR_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+type GuildInboxes = DashMap<u64, mpsc::UnboundedSender<VoiceMessage>>;
+
 /// Tracks active voice sessions with their join times.
 #[derive(Clone, Debug)]
 #[allow(dead_code)]
@@ -26,9 +42,40 @@ struct ActiveSession {
 }
 
 /// Subscriber that tracks voice channel state changes.
+///
+/// Each guild's changes go to its own [`GuildVoiceActor`], started on the guild's first
+/// [`VoiceMessage`]. The actor owns the guild's tracked sessions and handles its messages
+/// one at a time, so a member's sessions are never found, closed and started by two
+/// updates at once. Other guilds are handled in parallel. An actor stops once its guild
+/// is quiet with no session to track, or when the bot leaves the guild.
 pub struct VoiceStateSubscriber {
     pub services: Arc<Services>,
-    active_sessions: Mutex<HashMap<String, ActiveSession>>,
+    /// Inboxes of the running guild actors. Messages are only sent while holding the
+    /// guild's entry, so an actor removing its entry knows no message is on the way.
+    guilds: Arc<GuildInboxes>,
+    /// How long an actor without sessions waits for a message before it stops.
+    idle_timeout: Duration,
+    /// Sessions handed off by the previous run, by guild and user, until the guild's
+    /// actor starts and takes them.
+    handed_off: Mutex<HashMap<u64, HashMap<u64, HandoffSession>>>,
+    /// When the previous run shut down, which ends handed-off sessions not carried on.
+    handed_off_at: DateTime<Utc>,
+}
+
+/// Message handled by a [`GuildVoiceActor`], one at a time. Each carries the sender its
+/// result is returned on.
+enum VoiceMessage {
+    /// A voice state change in the guild.
+    StateChange(Box<VoiceStateEvent>, oneshot::Sender<Result<()>>),
+    /// A member found in a voice channel of the guild at startup.
+    TrackExisting {
+        user_id: u64,
+        channel_id: u64,
+        session_id: String,
+        done: oneshot::Sender<Result<()>>,
+    },
+    /// Closes the guild's handed-off sessions not carried on. Returns how many.
+    CloseHandedOff(oneshot::Sender<Result<u32>>),
 }
 
 impl VoiceStateSubscriber {
@@ -36,8 +83,261 @@ impl VoiceStateSubscriber {
     pub fn new(services: Arc<Services>) -> Self {
         Self {
             services,
-            active_sessions: Mutex::new(HashMap::new()),
+            guilds: Arc::new(DashMap::new()),
+            idle_timeout: ACTOR_IDLE_TIMEOUT,
+            handed_off: Mutex::new(HashMap::new()),
+            handed_off_at: Utc::now(),
+        }
+    }
+
+    /// Carries on the voice sessions the previous run handed off at shutdown for members
+    /// found in the same channel by [`track_existing_user`](Self::track_existing_user).
+    pub fn with_handoff(mut self, handoff: &Handoff) -> Self {
+        let mut handed_off: HashMap<u64, HashMap<u64, HandoffSession>> = HashMap::new();
+        for session in &handoff.voice_sessions {
+            handed_off
+                .entry(session.guild_id)
+                .or_default()
+                .insert(session.user_id, *session);
+        }
+        self.handed_off = Mutex::new(handed_off);
+        self.handed_off_at = handoff.written_at;
+        self
+    }
+
+    /// Tracks an existing user in a voice channel (used on bot startup).
+    pub async fn track_existing_user(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        session_id: &str,
+    ) -> Result<()> {
+        let session_id = session_id.to_string();
+        self.request(guild_id, |done| VoiceMessage::TrackExisting {
+            user_id,
+            channel_id,
+            session_id,
+            done,
+        })
+        .await
+    }
+
+    /// Closes the handed-off sessions not carried on, ending them when the previous run
+    /// shut down. Run once the members in voice were tracked after startup. Returns the
+    /// number of sessions closed.
+    pub async fn close_handed_off(&self) -> Result<u32> {
+        let pending: Vec<u64> = self.lock_handed_off().keys().copied().collect();
+        // Guilds with handed-off sessions but no update since startup
+        for guild_id in pending {
+            self.guilds
+                .entry(guild_id)
+                .or_insert_with(|| self.spawn_actor(guild_id));
         }
+
+        let guilds: Vec<u64> = self.guilds.iter().map(|guild| *guild.key()).collect();
+        let mut closed = 0;
+        for guild_id in guilds {
+            closed += self.request(guild_id, VoiceMessage::CloseHandedOff).await?;
+        }
+        Ok(closed)
+    }
+
+    /// Sends a message to a guild's actor and waits for its result.
+    async fn request<T>(
+        &self,
+        guild_id: u64,
+        message: impl FnOnce(oneshot::Sender<Result<T>>) -> VoiceMessage,
+    ) -> Result<T> {
+        let (done, result) = oneshot::channel();
+        self.send(guild_id, message(done))?;
+        result
+            .await
+            .map_err(|_| anyhow::anyhow!("Voice actor of guild {guild_id} dropped the message"))?
+    }
+
+    /// Sends a message to a guild's actor, starting the actor if needed.
+    fn send(&self, guild_id: u64, message: VoiceMessage) -> Result<()> {
+        let mut inbox = self
+            .guilds
+            .entry(guild_id)
+            .or_insert_with(|| self.spawn_actor(guild_id));
+        let Err(mpsc::error::SendError(message)) = inbox.send(message) else {
+            return Ok(());
+        };
+        // The actor panicked on an earlier message; a new one picks up from the database
+        *inbox = self.spawn_actor(guild_id);
+        inbox
+            .send(message)
+            .map_err(|_| anyhow::anyhow!("Voice actor of guild {guild_id} stopped"))
+    }
+
+    /// Starts a guild's actor and returns its inbox.
+    fn spawn_actor(&self, guild_id: u64) -> mpsc::UnboundedSender<VoiceMessage> {
+        let (inbox, messages) = mpsc::unbounded_channel();
+        let mut actor = GuildVoiceActor::new(self.services.clone(), guild_id);
+        actor.handed_off = self.lock_handed_off().remove(&guild_id).unwrap_or_default();
+        actor.handed_off_at = self.handed_off_at;
+        tokio::spawn(actor.run(messages, self.guilds.clone(), self.idle_timeout));
+        inbox
+    }
+
+    /// Stops the actor of a guild the bot left, once it handled the messages already
+    /// sent. Sessions handed off for the guild are dropped.
+    pub fn remove_guild(&self, guild_id: u64) {
+        self.guilds.remove(&guild_id);
+        self.lock_handed_off().remove(&guild_id);
+    }
+
+    fn lock_handed_off(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<u64, HashMap<u64, HandoffSession>>> {
+        self.handed_off
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The guild of a voice state change.
+    fn guild_of(event: &VoiceStateEvent) -> Option<GuildId> {
+        event
+            .new
+            .guild_id
+            .or_else(|| event.old.as_ref().and_then(|v| v.guild_id))
+    }
+}
+
+/// Handles one guild's voice state changes, owning the sessions it tracks.
+struct GuildVoiceActor {
+    services: Arc<Services>,
+    guild_id: u64,
+    /// Sessions tracked in memory, by voice session ID.
+    active_sessions: HashMap<String, ActiveSession>,
+    /// Sessions handed off by the previous run and not yet carried on, by user.
+    handed_off: HashMap<u64, HandoffSession>,
+    /// When the previous run shut down, which ends handed-off sessions not carried on.
+    handed_off_at: DateTime<Utc>,
+}
+
+impl GuildVoiceActor {
+    fn new(services: Arc<Services>, guild_id: u64) -> Self {
+        Self {
+            services,
+            guild_id,
+            active_sessions: HashMap::new(),
+            handed_off: HashMap::new(),
+            handed_off_at: Utc::now(),
+        }
+    }
+
+    /// Handles messages until the subscriber drops the inbox, or until no message came
+    /// for `idle_timeout` while no session is tracked.
+    async fn run(
+        mut self,
+        mut messages: mpsc::UnboundedReceiver<VoiceMessage>,
+        guilds: Arc<GuildInboxes>,
+        idle_timeout: Duration,
+    ) {
+        loop {
+            match tokio::time::timeout(idle_timeout, messages.recv()).await {
+                Ok(Some(message)) => self.handle(message).await,
+                Ok(None) => break,
+                Err(_) if self.is_idle() => {
+                    // The entry holds the only sender, and senders hold the entry while
+                    // sending, so an inbox found empty stays empty once it is removed
+                    let removed = guilds.remove_if(&self.guild_id, |_, _| messages.is_empty());
+                    if removed.is_some() {
+                        debug!("Stopping idle voice actor of guild {}", self.guild_id);
+                        break;
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Whether the actor tracks no session, so it can stop without losing any.
+    fn is_idle(&self) -> bool {
+        self.active_sessions.is_empty() && self.handed_off.is_empty()
+    }
+
+    async fn handle(&mut self, message: VoiceMessage) {
+        // A sender that stopped waiting doesn't need the result
+        match message {
+            VoiceMessage::StateChange(event, done) => {
+                let _ = done.send(self.handle_state_change(&event).await);
+            }
+            VoiceMessage::TrackExisting {
+                user_id,
+                channel_id,
+                session_id,
+                done,
+            } => {
+                let _ = done.send(
+                    self.track_existing_user(user_id, channel_id, &session_id)
+                        .await,
+                );
+            }
+            VoiceMessage::CloseHandedOff(done) => {
+                let _ = done.send(self.close_handed_off().await);
+            }
+        }
+    }
+
+    /// Takes the session handed off for a member found in `channel_id` at startup.
+    /// Returns its join time if it carries on. A member who moved or left while the bot
+    /// was down ended the session when the previous run shut down.
+    async fn resume_handed_off(
+        &mut self,
+        user_id: u64,
+        channel_id: u64,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let Some(handed_off) = self.handed_off.remove(&user_id) else {
+            return Ok(None);
+        };
+        let active = self
+            .services
+            .voice_tracking
+            .find_active_sessions_by_user(user_id, self.guild_id)
+            .await?;
+        let Some(session) = active
+            .iter()
+            .find(|session| HandoffSession::from(*session) == handed_off)
+        else {
+            return Ok(None);
+        };
+
+        if handed_off.channel_id == channel_id && active.len() == 1 {
+            return Ok(Some(handed_off.join_time));
+        }
+        self.services
+            .voice_tracking
+            .end_session(session, &self.handed_off_at)
+            .await?;
+        Ok(None)
+    }
+
+    /// Closes the handed-off sessions not carried on, ending them when the previous run
+    /// shut down. Returns the number of sessions closed.
+    async fn close_handed_off(&mut self) -> Result<u32> {
+        let mut closed = 0;
+        for (user_id, handed_off) in std::mem::take(&mut self.handed_off) {
+            let active = self
+                .services
+                .voice_tracking
+                .find_active_sessions_by_user(user_id, self.guild_id)
+                .await?;
+            for session in active
+                .iter()
+                .filter(|session| HandoffSession::from(*session) == handed_off)
+            {
+                self.services
+                    .voice_tracking
+                    .end_session(session, &self.handed_off_at)
+                    .await?;
+                closed += 1;
+            }
+        }
+        Ok(closed)
     }
 
     /// Closes all orphaned active sessions for a user in a guild.
@@ -52,12 +352,7 @@ impl VoiceStateSubscriber {
         for session in orphaned {
             self.services
                 .voice_tracking
-                .close_session(
-                    session.user_id,
-                    session.channel_id,
-                    &session.join_time,
-                    &now,
-                )
+                .end_session(&session, &now)
                 .await?;
             debug!(
                 "Closed orphaned session for user {} in channel {} (guild {})",
@@ -68,33 +363,64 @@ impl VoiceStateSubscriber {
         Ok(())
     }
 
-    /// Tracks an existing user in a voice channel (used on bot startup).
-    pub async fn track_existing_user(
-        &self,
+    /// Tracks an existing user in a voice channel of the guild (used on bot startup).
+    async fn track_existing_user(
+        &mut self,
         user_id: u64,
-        guild_id: u64,
         channel_id: u64,
         session_id: &str,
     ) -> Result<()> {
+        let guild_id = self.guild_id;
         let now = Utc::now();
-        let sessions = self.active_sessions.lock().await;
 
         // Only track if not already tracked
-        if sessions.contains_key(session_id) {
+        if self.active_sessions.contains_key(session_id) {
             return Ok(());
         }
 
-        // Close any orphaned active sessions before creating a new one
-        drop(sessions);
-        self.close_orphaned_sessions(user_id, guild_id).await?;
+        if self.services.voice_tracking.records_voice_events() {
+            // Recorded as a join at scan time; the projection closes orphaned sessions
+            self.record_event(VoiceEventEntity {
+                guild_id: guild_id.into(),
+                user_id: user_id.into(),
+                kind: VoiceEventKind::Join,
+                channel_id: Some(channel_id.into()),
+                occurred_at: now,
+                ..Default::default()
+            })
+            .await?;
+            self.active_sessions.insert(
+                session_id.to_string(),
+                ActiveSession {
+                    user_id,
+                    guild_id,
+                    channel_id,
+                    join_time: now,
+                },
+            );
+            return Ok(());
+        }
 
-        // Re-check after await: another task may have inserted this session while we were
-        // closing orphaned sessions (e.g. concurrent VoiceStateUpdate on gateway reconnect).
-        let mut sessions = self.active_sessions.lock().await;
-        if sessions.contains_key(session_id) {
+        // Still in the channel the previous run handed off: the open session carries on
+        if let Some(join_time) = self.resume_handed_off(user_id, channel_id).await? {
+            self.active_sessions.insert(
+                session_id.to_string(),
+                ActiveSession {
+                    user_id,
+                    guild_id,
+                    channel_id,
+                    join_time,
+                },
+            );
+            debug!(
+                "Carried on handed-off session of user {user_id} in voice channel {channel_id} (guild {guild_id})"
+            );
             return Ok(());
         }
 
+        // Close any orphaned active sessions before creating a new one
+        self.close_orphaned_sessions(user_id, guild_id).await?;
+
         let session = ActiveSession {
             user_id,
             guild_id,
@@ -114,7 +440,7 @@ impl VoiceStateSubscriber {
         };
 
         self.services.voice_tracking.insert(&model).await?;
-        sessions.insert(session_id.to_string(), session);
+        self.active_sessions.insert(session_id.to_string(), session);
 
         debug!(
             "Started tracking existing user {user_id} in voice channel {channel_id} (guild {guild_id})"
@@ -123,7 +449,128 @@ impl VoiceStateSubscriber {
         Ok(())
     }
 
-    async fn handle_join(&self, event: &VoiceStateEvent, channel_id: ChannelId) -> Result<()> {
+    /// Appends an event to the voice event log.
+    async fn record_event(&self, model: VoiceEventEntity) -> Result<()> {
+        let id = self
+            .services
+            .voice_tracking
+            .record_voice_event(&model)
+            .await?;
+        debug!(
+            "Recorded voice event {id} ({}) for user {} in guild {}",
+            model.kind.as_str(),
+            *model.user_id,
+            *model.guild_id
+        );
+        Ok(())
+    }
+
+    /// From <https://discord.com/developers/docs/events/gateway-events#voice-state-update>:
+    /// > Called when someone joins/leaves/moves voice channels. Inner payload is a voice state object.
+    ///
+    /// - event.old is None if and only if user joined a voice channel
+    /// - event.new.channel_id is None if and only if user left a voice channel
+    /// - event.old is Some and event.new.channel_id is Some if and only if user moved between
+    /// voice channels
+    async fn handle_state_change(&mut self, event: &VoiceStateEvent) -> Result<()> {
+        if self.services.voice_tracking.records_voice_events() {
+            let guild_id =
+                VoiceStateSubscriber::guild_of(event).ok_or(anyhow::anyhow!("Missing guild_id"))?;
+            return self.handle_event_sourced(event, guild_id.get()).await;
+        }
+
+        let old_channel = event.old.as_ref().and_then(|v| v.channel_id);
+        let new_channel = event.new.channel_id;
+
+        match (old_channel, new_channel) {
+            // User joined
+            (None, Some(channel_id)) => self.handle_join(event, channel_id).await?,
+
+            // User left
+            (Some(old_channel_id), None) => self.handle_leave(event, old_channel_id).await?,
+
+            // User moved channels
+            (Some(old_channel_id), Some(new_channel_id)) if old_channel_id != new_channel_id => {
+                self.handle_move(event, old_channel_id, new_channel_id)
+                    .await?
+            }
+
+            _ => {} // Same channel or other state changes (mute/deafen)
+        }
+
+        Ok(())
+    }
+
+    /// Records a voice state change as an event instead of writing sessions, leaving
+    /// sessions to the projection. Mute and deafen changes are recorded too.
+    async fn handle_event_sourced(&mut self, event: &VoiceStateEvent, guild_id: u64) -> Result<()> {
+        let old_channel = event.old.as_ref().and_then(|v| v.channel_id);
+        let new_channel = event.new.channel_id;
+        let kind = match (old_channel, new_channel) {
+            (None, Some(_)) => VoiceEventKind::Join,
+            (Some(_), None) => VoiceEventKind::Leave,
+            (Some(old_channel_id), Some(new_channel_id)) if old_channel_id != new_channel_id => {
+                VoiceEventKind::Move
+            }
+            (Some(_), Some(_))
+                if event.old.as_ref().map(Self::mute_state)
+                    != Some(Self::mute_state(&event.new)) =>
+            {
+                VoiceEventKind::Mute
+            }
+            _ => return Ok(()), // Other state changes (video, streaming, suppress)
+        };
+
+        let session_id = event.new.session_id.to_string();
+        let sessions = &mut self.active_sessions;
+        match (kind, new_channel) {
+            // Skip joins already recorded (prevents duplicates on gateway reconnects)
+            (VoiceEventKind::Join, _) if sessions.contains_key(&session_id) => return Ok(()),
+            (VoiceEventKind::Join | VoiceEventKind::Move, Some(channel_id)) => {
+                sessions.insert(
+                    session_id,
+                    ActiveSession {
+                        user_id: event.new.user_id.get(),
+                        guild_id,
+                        channel_id: channel_id.get(),
+                        join_time: Utc::now(),
+                    },
+                );
+            }
+            (VoiceEventKind::Leave, _) => {
+                if let Some(old_state) = &event.old {
+                    sessions.remove(&old_state.session_id.to_string());
+                }
+            }
+            _ => {}
+        }
+
+        let (muted, deafened) = Self::mute_state(&event.new);
+        self.record_event(VoiceEventEntity {
+            guild_id: guild_id.into(),
+            user_id: event.new.user_id.get().into(),
+            kind,
+            channel_id: new_channel.map(|c| c.get().into()),
+            previous_channel_id: old_channel.map(|c| c.get().into()),
+            muted,
+            deafened,
+            is_bot: event.is_bot,
+            is_stage: event.is_stage,
+            occurred_at: Utc::now(),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Whether a voice state is muted and deafened, by the member or by a moderator.
+    fn mute_state(state: &VoiceState) -> (bool, bool) {
+        (
+            state.mute() || state.self_mute(),
+            state.deaf() || state.self_deaf(),
+        )
+    }
+
+    async fn handle_join(&mut self, event: &VoiceStateEvent, channel_id: ChannelId) -> Result<()> {
         debug!(
             "User {} detected joining voice channel id {}",
             event.new.user_id.get(),
@@ -138,8 +585,17 @@ impl VoiceStateSubscriber {
         let user_id = event.new.user_id.get();
         let session_id = event.new.session_id.to_string();
 
+        if !self
+            .services
+            .voice_tracking
+            .should_track(guild_id, event.is_bot, event.is_stage)
+            .await
+        {
+            return Ok(());
+        }
+
         // Skip if already tracking this session (prevents duplicates on gateway reconnects)
-        if self.active_sessions.lock().await.contains_key(&session_id) {
+        if self.active_sessions.contains_key(&session_id) {
             return Ok(());
         }
 
@@ -153,10 +609,7 @@ impl VoiceStateSubscriber {
             join_time,
         };
 
-        self.active_sessions
-            .lock()
-            .await
-            .insert(session_id, session);
+        self.active_sessions.insert(session_id, session);
 
         let model = VoiceSessionsEntity {
             user_id,
@@ -172,7 +625,11 @@ impl VoiceStateSubscriber {
         Ok(())
     }
 
-    async fn handle_leave(&self, event: &VoiceStateEvent, old_channel_id: ChannelId) -> Result<()> {
+    async fn handle_leave(
+        &mut self,
+        event: &VoiceStateEvent,
+        old_channel_id: ChannelId,
+    ) -> Result<()> {
         debug!(
             "User {} detected leaving voice channel id {}",
             event.new.user_id.get(),
@@ -185,7 +642,7 @@ impl VoiceStateSubscriber {
         let guild_id = old_state.guild_id.map(|g| g.get()).unwrap_or(0);
 
         // Remove from in-memory tracking
-        self.active_sessions.lock().await.remove(&session_id);
+        self.active_sessions.remove(&session_id);
 
         // Close ALL active sessions for this user in the DB
         // (not just the one tracked in memory, to handle orphaned sessions)
@@ -198,19 +655,14 @@ impl VoiceStateSubscriber {
         for session in active_sessions {
             self.services
                 .voice_tracking
-                .close_session(
-                    session.user_id,
-                    session.channel_id,
-                    &session.join_time,
-                    &leave_time,
-                )
+                .end_session(&session, &leave_time)
                 .await?;
         }
         Ok(())
     }
 
     async fn handle_move(
-        &self,
+        &mut self,
         event: &VoiceStateEvent,
         old_channel_id: ChannelId,
         new_channel_id: ChannelId,
@@ -233,70 +685,44 @@ impl VoiceStateSubscriber {
             .get();
 
         // Remove old session from in-memory tracking
-        self.active_sessions.lock().await.remove(&old_session_id);
+        self.active_sessions.remove(&old_session_id);
 
-        // Close ALL active sessions for this user in the DB
-        // (handles orphaned sessions from previous crashes)
-        let active_sessions = self
-            .services
+        // Continue the session in the new channel, or end it if that channel isn't tracked.
+        // Also closes orphaned sessions from previous crashes.
+        self.services
             .voice_tracking
-            .find_active_sessions_by_user(user_id, guild_id)
+            .move_session(
+                user_id,
+                guild_id,
+                new_channel_id.get(),
+                event.is_bot,
+                event.is_stage,
+                &now,
+            )
             .await?;
 
-        for session in active_sessions {
-            self.services
-                .voice_tracking
-                .close_session(
-                    session.user_id,
-                    session.channel_id,
-                    &session.join_time,
-                    &now,
-                )
-                .await?;
-        }
-
-        // Start new session
-        let session = ActiveSession {
-            user_id,
-            guild_id,
-            channel_id: new_channel_id.get(),
-            join_time: now,
-        };
-
-        self.active_sessions
-            .lock()
+        if self
+            .services
+            .voice_tracking
+            .should_track(guild_id, event.is_bot, event.is_stage)
             .await
-            .insert(new_session_id, session);
-
-        let model = VoiceSessionsEntity {
-            user_id,
-            guild_id,
-            channel_id: new_channel_id.get(),
-            join_time: now,
-            leave_time: now,
-            is_active: true,
-            ..Default::default()
-        };
-
-        self.services.voice_tracking.insert(&model).await?;
+        {
+            let session = ActiveSession {
+                user_id,
+                guild_id,
+                channel_id: new_channel_id.get(),
+                join_time: now,
+            };
+            self.active_sessions.insert(new_session_id, session);
+        }
         Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl Subscriber<VoiceStateEvent> for VoiceStateSubscriber {
-    /// From <https://discord.com/developers/docs/events/gateway-events#voice-state-update>:
-    /// > Called when someone joins/leaves/moves voice channels. Inner payload is a voice state object.
-    ///
-    /// - event.old is None if and only if user joined a voice channel
-    /// - event.new.channel_id is None if and only if user left a voice channel
-    /// - event.old is Some and event.new.channel_id is Some if and only if user moved between
-    /// voice channels
     async fn callback(&self, event: VoiceStateEvent) -> Result<()> {
-        let guild_id = event
-            .new
-            .guild_id
-            .or_else(|| event.old.as_ref().and_then(|v| v.guild_id));
+        let guild_id = Self::guild_of(&event);
 
         if let Some(guild_id) = guild_id
             && !self
@@ -308,34 +734,19 @@ impl Subscriber<VoiceStateEvent> for VoiceStateSubscriber {
             return Ok(());
         }
 
-        let old_channel = event.old.as_ref().and_then(|v| v.channel_id);
-        let new_channel = event.new.channel_id;
-
-        match (old_channel, new_channel) {
-            // User joined
-            (None, Some(channel_id)) => self.handle_join(&event, channel_id).await?,
-
-            // User left
-            (Some(old_channel_id), None) => self.handle_leave(&event, old_channel_id).await?,
-
-            // User moved channels
-            (Some(old_channel_id), Some(new_channel_id)) if old_channel_id != new_channel_id => {
-                self.handle_move(&event, old_channel_id, new_channel_id)
-                    .await?
-            }
-
-            _ => {} // Same channel or other state changes (mute/deafen)
-        }
-
-        Ok(())
+        // Changes without a guild go to the actor of guild 0, which rejects or skips them
+        let guild_id = guild_id.map(|guild_id| guild_id.get()).unwrap_or(0);
+        self.request(guild_id, |done| {
+            VoiceMessage::StateChange(Box::new(event), done)
+        })
+        .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use poise::serenity_prelude::VoiceState;
-
     use super::*;
+    use crate::config::Config;
     use crate::feed::Platforms;
     use crate::repo::PgRepos;
 
@@ -354,10 +765,17 @@ mod tests {
             .await
             .unwrap();
 
-        let services = Arc::new(Services::new(Arc::new(db), Arc::new(Platforms::new())).await?);
+        let services = Arc::new(
+            Services::new(Arc::new(db), Arc::new(Platforms::new()), &Config::default()).await?,
+        );
         Ok(VoiceStateSubscriber::new(services))
     }
 
+    async fn create_mock_actor(guild_id: u64) -> anyhow::Result<GuildVoiceActor> {
+        let sub = create_mock_subscriber().await?;
+        Ok(GuildVoiceActor::new(sub.services, guild_id))
+    }
+
     fn create_voice_state(
         user_id: u64,
         guild_id: Option<u64>,
@@ -382,23 +800,25 @@ mod tests {
     #[tokio::test]
     #[serial_test::serial]
     async fn handle_join_logic() {
-        let sub = create_mock_subscriber().await.unwrap();
+        let mut actor = create_mock_actor(456).await.unwrap();
         let event = VoiceStateEvent {
             old: None,
             new: create_voice_state(123, Some(456), Some(789), "session1"),
+            is_bot: false,
+            is_stage: false,
         };
 
-        let result = sub.handle_join(&event, ChannelId::new(789)).await;
+        let result = actor.handle_join(&event, ChannelId::new(789)).await;
         assert!(result.is_ok());
 
-        let sessions = sub.active_sessions.lock().await;
+        let sessions = &actor.active_sessions;
         assert!(sessions.contains_key("session1"));
     }
 
     #[tokio::test]
     #[serial_test::serial]
     async fn handle_leave_logic() {
-        let sub = create_mock_subscriber().await.unwrap();
+        let mut actor = create_mock_actor(456).await.unwrap();
         let join_time = Utc::now();
         let session = ActiveSession {
             user_id: 123,
@@ -406,28 +826,29 @@ mod tests {
             channel_id: 789,
             join_time,
         };
-        sub.active_sessions
-            .lock()
-            .await
+        actor
+            .active_sessions
             .insert("session1".to_string(), session);
 
         let old_state = create_voice_state(123, Some(456), Some(789), "session1");
         let event = VoiceStateEvent {
             old: Some(old_state),
             new: create_voice_state(123, Some(456), None, "session1"),
+            is_bot: false,
+            is_stage: false,
         };
 
-        let result = sub.handle_leave(&event, ChannelId::new(789)).await;
+        let result = actor.handle_leave(&event, ChannelId::new(789)).await;
         assert!(result.is_ok());
 
-        let sessions = sub.active_sessions.lock().await;
+        let sessions = &actor.active_sessions;
         assert!(!sessions.contains_key("session1"));
     }
 
     #[tokio::test]
     #[serial_test::serial]
     async fn handle_move_logic() {
-        let sub = create_mock_subscriber().await.unwrap();
+        let mut actor = create_mock_actor(456).await.unwrap();
         let join_time = Utc::now();
         let session = ActiveSession {
             user_id: 123,
@@ -435,9 +856,8 @@ mod tests {
             channel_id: 781,
             join_time,
         };
-        sub.active_sessions
-            .lock()
-            .await
+        actor
+            .active_sessions
             .insert("session1".to_string(), session);
 
         let old_state = create_voice_state(123, Some(456), Some(781), "session1");
@@ -445,14 +865,16 @@ mod tests {
         let event = VoiceStateEvent {
             old: Some(old_state),
             new: new_state,
+            is_bot: false,
+            is_stage: false,
         };
 
-        let result = sub
+        let result = actor
             .handle_move(&event, ChannelId::new(781), ChannelId::new(782))
             .await;
         assert!(result.is_ok());
 
-        let sessions = sub.active_sessions.lock().await;
+        let sessions = &actor.active_sessions;
         assert!(sessions.contains_key("session1"));
         assert_ne!(sessions.get("session1").unwrap().join_time, join_time);
         assert_eq!(sessions.get("session1").unwrap().channel_id, 782);
@@ -461,14 +883,14 @@ mod tests {
     #[tokio::test]
     #[serial_test::serial]
     async fn track_existing_user() {
-        let sub = create_mock_subscriber().await.unwrap();
+        let mut actor = create_mock_actor(456).await.unwrap();
 
         // Track an existing user (simulating startup scan)
-        let result = sub.track_existing_user(123, 456, 789, "session1").await;
+        let result = actor.track_existing_user(123, 789, "session1").await;
         assert!(result.is_ok());
 
         // Verify session is tracked in memory
-        let sessions = sub.active_sessions.lock().await;
+        let sessions = &actor.active_sessions;
         assert!(sessions.contains_key("session1"));
         assert_eq!(sessions.get("session1").unwrap().user_id, 123);
         assert_eq!(sessions.get("session1").unwrap().guild_id, 456);
@@ -478,25 +900,27 @@ mod tests {
     #[tokio::test]
     #[serial_test::serial]
     async fn track_existing_user_already_tracked() {
-        let sub = create_mock_subscriber().await.unwrap();
+        let mut actor = create_mock_actor(456).await.unwrap();
 
         // Track user first time
-        sub.track_existing_user(123, 456, 789, "session1")
+        actor
+            .track_existing_user(123, 789, "session1")
             .await
             .unwrap();
 
         let first_join_time = {
-            let sessions = sub.active_sessions.lock().await;
+            let sessions = &actor.active_sessions;
             sessions.get("session1").unwrap().join_time
         };
 
         // Try to track same user again (should not create duplicate)
-        sub.track_existing_user(123, 456, 789, "session1")
+        actor
+            .track_existing_user(123, 789, "session1")
             .await
             .unwrap();
 
         // Verify still only one session and join_time hasn't changed
-        let sessions = sub.active_sessions.lock().await;
+        let sessions = &actor.active_sessions;
         assert!(sessions.contains_key("session1"));
         assert_eq!(sessions.len(), 1);
         assert_eq!(sessions.get("session1").unwrap().join_time, first_join_time);
@@ -520,6 +944,7 @@ mod tests {
             join_time: Utc::now() - chrono::Duration::hours(2),
             leave_time: Utc::now() - chrono::Duration::hours(2),
             is_active: true,
+            parent_session_id: None,
         };
         sub.services.voice_tracking.insert(&orphaned).await.unwrap();
 
@@ -554,10 +979,35 @@ mod tests {
 
     #[tokio::test]
     #[serial_test::serial]
-    async fn handle_join_closes_orphaned_sessions() {
+    async fn concurrent_track_existing_user_tracks_once() {
         let sub = create_mock_subscriber().await.unwrap();
+        let user_id = 998u64;
+        let guild_id = 888u64;
+        let channel_id = 777u64;
+
+        // e.g. the startup scan and a gateway reconnect reporting the same member
+        let (first, second) = tokio::join!(
+            sub.track_existing_user(user_id, guild_id, channel_id, "concurrent_session"),
+            sub.track_existing_user(user_id, guild_id, channel_id, "concurrent_session"),
+        );
+        first.unwrap();
+        second.unwrap();
+
+        let active = sub
+            .services
+            .voice_tracking
+            .find_active_sessions_by_user(user_id, guild_id)
+            .await
+            .unwrap();
+        assert_eq!(active.len(), 1);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn handle_join_closes_orphaned_sessions() {
         let user_id = 444u64;
         let guild_id = 555u64;
+        let mut actor = create_mock_actor(guild_id).await.unwrap();
         let channel_id = 789u64;
 
         // Simulate an orphaned active session (e.g., from a previous crash)
@@ -569,11 +1019,17 @@ mod tests {
             join_time: Utc::now() - chrono::Duration::hours(2),
             leave_time: Utc::now() - chrono::Duration::hours(2),
             is_active: true,
+            parent_session_id: None,
         };
-        sub.services.voice_tracking.insert(&orphaned).await.unwrap();
+        actor
+            .services
+            .voice_tracking
+            .insert(&orphaned)
+            .await
+            .unwrap();
 
         // Verify the orphaned session exists in the DB
-        let active_before = sub
+        let active_before = actor
             .services
             .voice_tracking
             .find_active_sessions_by_user(user_id, guild_id)
@@ -585,13 +1041,15 @@ mod tests {
         let event = VoiceStateEvent {
             old: None,
             new: create_voice_state(user_id, Some(guild_id), Some(channel_id), "session1"),
+            is_bot: false,
+            is_stage: false,
         };
 
-        let result = sub.handle_join(&event, ChannelId::new(channel_id)).await;
+        let result = actor.handle_join(&event, ChannelId::new(channel_id)).await;
         assert!(result.is_ok());
 
         // Verify the orphaned session was closed
-        let active_after = sub
+        let active_after = actor
             .services
             .voice_tracking
             .find_active_sessions_by_user(user_id, guild_id)
@@ -606,9 +1064,9 @@ mod tests {
     #[tokio::test]
     #[serial_test::serial]
     async fn handle_leave_closes_all_active_sessions() {
-        let sub = create_mock_subscriber().await.unwrap();
         let user_id = 555u64;
         let guild_id = 666u64;
+        let mut actor = create_mock_actor(guild_id).await.unwrap();
 
         // Create multiple active sessions (simulating duplicates from crashes)
         for channel_id in [789u64, 790, 791] {
@@ -620,12 +1078,18 @@ mod tests {
                 join_time: Utc::now() - chrono::Duration::hours(1),
                 leave_time: Utc::now() - chrono::Duration::hours(1),
                 is_active: true,
+                parent_session_id: None,
             };
-            sub.services.voice_tracking.insert(&session).await.unwrap();
+            actor
+                .services
+                .voice_tracking
+                .insert(&session)
+                .await
+                .unwrap();
         }
 
         // Verify all 3 are active
-        let active_before = sub
+        let active_before = actor
             .services
             .voice_tracking
             .find_active_sessions_by_user(user_id, guild_id)
@@ -638,13 +1102,15 @@ mod tests {
         let event = VoiceStateEvent {
             old: Some(old_state),
             new: create_voice_state(user_id, Some(guild_id), None, "session1"),
+            is_bot: false,
+            is_stage: false,
         };
 
-        let result = sub.handle_leave(&event, ChannelId::new(789)).await;
+        let result = actor.handle_leave(&event, ChannelId::new(789)).await;
         assert!(result.is_ok());
 
         // Verify ALL active sessions were closed
-        let active_after = sub
+        let active_after = actor
             .services
             .voice_tracking
             .find_active_sessions_by_user(user_id, guild_id)
@@ -664,10 +1130,12 @@ mod tests {
         let event = VoiceStateEvent {
             old: None,
             new: create_voice_state(user_id, Some(guild_id), Some(channel_id), "session_dup"),
+            is_bot: false,
+            is_stage: false,
         };
 
         // First join should succeed
-        let result = sub.handle_join(&event, ChannelId::new(channel_id)).await;
+        let result = sub.callback(event.clone()).await;
         assert!(result.is_ok());
 
         let active_after_first = sub
@@ -679,7 +1147,7 @@ mod tests {
         assert_eq!(active_after_first.len(), 1);
 
         // Second join with same session_id should be a no-op
-        let result = sub.handle_join(&event, ChannelId::new(channel_id)).await;
+        let result = sub.callback(event).await;
         assert!(result.is_ok());
 
         let active_after_second = sub
@@ -694,4 +1162,207 @@ mod tests {
             active_after_first[0].join_time
         );
     }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn state_changes_of_a_guild_are_handled_in_order() {
+        let sub = create_mock_subscriber().await.unwrap();
+        let user_id = 667u64;
+        let guild_id = 777u64;
+        let channel_id = 888u64;
+
+        let join = VoiceStateEvent {
+            old: None,
+            new: create_voice_state(user_id, Some(guild_id), Some(channel_id), "session_order"),
+            is_bot: false,
+            is_stage: false,
+        };
+        let leave = VoiceStateEvent {
+            old: Some(join.new.clone()),
+            new: create_voice_state(user_id, Some(guild_id), None, "session_order"),
+            is_bot: false,
+            is_stage: false,
+        };
+
+        // Both are queued before the actor handles either
+        let (joined, left) = tokio::join!(sub.callback(join), sub.callback(leave));
+        joined.unwrap();
+        left.unwrap();
+
+        let active = sub
+            .services
+            .voice_tracking
+            .find_active_sessions_by_user(user_id, guild_id)
+            .await
+            .unwrap();
+        assert!(active.is_empty());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn idle_actor_stops_and_restarts_on_next_change() {
+        let mut sub = create_mock_subscriber().await.unwrap();
+        sub.idle_timeout = Duration::from_millis(100);
+        let user_id = 669u64;
+        let guild_id = 777u64;
+        let channel_id = 888u64;
+
+        let join = VoiceStateEvent {
+            old: None,
+            new: create_voice_state(user_id, Some(guild_id), Some(channel_id), "session_idle"),
+            is_bot: false,
+            is_stage: false,
+        };
+        let leave = VoiceStateEvent {
+            old: Some(join.new.clone()),
+            new: create_voice_state(user_id, Some(guild_id), None, "session_idle"),
+            is_bot: false,
+            is_stage: false,
+        };
+
+        sub.callback(join.clone()).await.unwrap();
+        // A tracked session keeps the actor running
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(sub.guilds.contains_key(&guild_id));
+
+        sub.callback(leave).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(!sub.guilds.contains_key(&guild_id));
+
+        sub.callback(join).await.unwrap();
+        assert!(sub.guilds.contains_key(&guild_id));
+        let active = sub
+            .services
+            .voice_tracking
+            .find_active_sessions_by_user(user_id, guild_id)
+            .await
+            .unwrap();
+        assert_eq!(active.len(), 1);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn close_handed_off_ends_sessions_not_carried_on() {
+        use chrono::SubsecRound;
+
+        let sub = create_mock_subscriber().await.unwrap();
+        let user_id = 668u64;
+        let guild_id = 777u64;
+        // Whole seconds, so the handed-off join time matches the stored one
+        let shut_down_at = (Utc::now() - chrono::Duration::minutes(5)).trunc_subsecs(0);
+
+        let session = VoiceSessionsEntity {
+            id: 0,
+            user_id,
+            guild_id,
+            channel_id: 888,
+            join_time: shut_down_at - chrono::Duration::hours(1),
+            leave_time: shut_down_at,
+            is_active: true,
+            parent_session_id: None,
+        };
+        sub.services.voice_tracking.insert(&session).await.unwrap();
+        let sub = sub.with_handoff(&Handoff {
+            written_at: shut_down_at,
+            voice_sessions: vec![HandoffSession::from(&session)],
+            ..Default::default()
+        });
+
+        assert_eq!(sub.close_handed_off().await.unwrap(), 1);
+        assert_eq!(sub.close_handed_off().await.unwrap(), 0);
+
+        let active = sub
+            .services
+            .voice_tracking
+            .find_active_sessions_by_user(user_id, guild_id)
+            .await
+            .unwrap();
+        assert!(active.is_empty());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn handle_join_skips_bots_by_default() {
+        let user_id = 777u64;
+        let guild_id = 888u64;
+        let mut actor = create_mock_actor(guild_id).await.unwrap();
+        let channel_id = 999u64;
+
+        let event = VoiceStateEvent {
+            old: None,
+            new: create_voice_state(user_id, Some(guild_id), Some(channel_id), "session_bot"),
+            is_bot: true,
+            is_stage: false,
+        };
+
+        let result = actor.handle_join(&event, ChannelId::new(channel_id)).await;
+        assert!(result.is_ok());
+
+        let active = actor
+            .services
+            .voice_tracking
+            .find_active_sessions_by_user(user_id, guild_id)
+            .await
+            .unwrap();
+        assert!(active.is_empty());
+        assert!(!actor.active_sessions.contains_key("session_bot"));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn handle_leave_discards_short_sessions() {
+        let user_id = 888u64;
+        let guild_id = 999u64;
+        let mut actor = create_mock_actor(guild_id).await.unwrap();
+        let channel_id = 111u64;
+
+        let mut settings = actor
+            .services
+            .voice_tracking
+            .get_server_settings(guild_id)
+            .await
+            .unwrap();
+        settings.voice.min_session_secs = Some(30);
+        actor
+            .services
+            .voice_tracking
+            .update_server_settings(guild_id, settings)
+            .await
+            .unwrap();
+
+        let join = VoiceStateEvent {
+            old: None,
+            new: create_voice_state(user_id, Some(guild_id), Some(channel_id), "session_hop"),
+            is_bot: false,
+            is_stage: false,
+        };
+        actor
+            .handle_join(&join, ChannelId::new(channel_id))
+            .await
+            .unwrap();
+
+        let leave = VoiceStateEvent {
+            old: Some(join.new.clone()),
+            new: create_voice_state(user_id, Some(guild_id), None, "session_hop"),
+            is_bot: false,
+            is_stage: false,
+        };
+        actor
+            .handle_leave(&leave, ChannelId::new(channel_id))
+            .await
+            .unwrap();
+
+        let sessions = actor
+            .services
+            .voice_tracking
+            .get_sessions_in_range(
+                guild_id,
+                Some(user_id),
+                &(Utc::now() - chrono::Duration::hours(1)),
+                &(Utc::now() + chrono::Duration::hours(1)),
+            )
+            .await
+            .unwrap();
+        assert!(sessions.is_empty());
+    }
 }
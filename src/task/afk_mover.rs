@@ -0,0 +1,189 @@
+/// Periodic check that moves members idling self-deafened and alone to the AFK channel.
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use dashmap::DashMap;
+use log::debug;
+use log::error;
+use log::info;
+use poise::serenity_prelude::ChannelId;
+use poise::serenity_prelude::GuildId;
+use poise::serenity_prelude::Permissions;
+use poise::serenity_prelude::UserId;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::bot::Bot;
+use crate::service::open_sessions::OpenSession;
+use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between checks
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Moves members who stayed self-deafened and alone in a voice channel for the guild's
+/// configured time to its AFK channel.
+///
+/// Candidates come from the open-session registry: members whose open session is the
+/// only one in its channel. The cache then tells whether they are self-deafened. Discord
+/// does not say since when, so the time counts from the first check that saw it.
+pub struct AfkMoverTask {
+    bot: Arc<Bot>,
+    service: Arc<dyn VoiceTracker>,
+    /// When each member, by guild and user ID, was first seen deafened and alone.
+    idle_since: DashMap<(u64, u64), DateTime<Utc>>,
+}
+
+impl AfkMoverTask {
+    /// Creates a new AFK mover task with the given bot and service.
+    pub fn new(bot: Arc<Bot>, service: Arc<dyn VoiceTracker>) -> Self {
+        Self {
+            bot,
+            service,
+            idle_since: DashMap::new(),
+        }
+    }
+
+    /// Starts the AFK mover task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("afk_mover", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                let guild_ids = task.service.guilds_with_afk_mover().await;
+                // Forget members of guilds that turned the mover off
+                task.idle_since
+                    .retain(|(guild_id, _), _| guild_ids.contains(guild_id));
+                for guild_id in guild_ids {
+                    if let Err(e) = task.check_guild(guild_id, now).await {
+                        error!("Failed to move idle members of guild {guild_id}: {e}");
+                    }
+                }
+            }
+        });
+
+        info!("AFK mover task started (every {CHECK_INTERVAL_SECS} seconds)");
+    }
+
+    /// Moves the guild's members that idled long enough. Returns the number moved.
+    pub async fn check_guild(&self, guild_id: u64, now: DateTime<Utc>) -> Result<u32> {
+        let settings = self.service.voice_settings(guild_id).await;
+        let Some(after_secs) = settings.afk_move_secs() else {
+            return Ok(0);
+        };
+        let alone = alone_members(&self.service.open_sessions().by_guild(guild_id));
+
+        let (afk_channel_id, idle) = {
+            let Some(guild) = self.bot.cache.guild(GuildId::new(guild_id)) else {
+                return Ok(0);
+            };
+            let Some(afk_channel_id) = guild.afk_metadata.as_ref().map(|afk| afk.afk_channel_id)
+            else {
+                debug!("Guild {guild_id} has no AFK channel, not moving idle members");
+                return Ok(0);
+            };
+            let bot_id = self.bot.cache.current_user().id;
+            let Some(bot) = guild.members.get(&bot_id) else {
+                return Ok(0);
+            };
+            let can_move = |channel_id: ChannelId| {
+                guild.channels.get(&channel_id).is_some_and(|channel| {
+                    guild
+                        .user_permissions_in(channel, bot)
+                        .contains(Permissions::MOVE_MEMBERS)
+                })
+            };
+            let can_join_afk = guild.channels.get(&afk_channel_id).is_some_and(|channel| {
+                guild
+                    .user_permissions_in(channel, bot)
+                    .contains(Permissions::CONNECT)
+            });
+            if !can_join_afk {
+                debug!("Cannot move members of guild {guild_id} into its AFK channel");
+                return Ok(0);
+            }
+
+            let idle: Vec<(u64, u64)> = alone
+                .into_iter()
+                .filter(|(user_id, channel_id)| {
+                    let channel_id = ChannelId::new(*channel_id);
+                    channel_id != afk_channel_id
+                        && can_move(channel_id)
+                        && guild
+                            .voice_states
+                            .get(&UserId::new(*user_id))
+                            .is_some_and(|state| {
+                                state.self_deaf() && state.channel_id == Some(channel_id)
+                            })
+                })
+                .collect();
+            (afk_channel_id, idle)
+        };
+
+        // Members no longer deafened and alone start over next time
+        let idle_users: HashSet<u64> = idle.iter().map(|(user_id, _)| *user_id).collect();
+        self.idle_since.retain(|(idle_guild_id, user_id), _| {
+            *idle_guild_id != guild_id || idle_users.contains(user_id)
+        });
+
+        let mut moved = 0u32;
+        for (user_id, _) in idle {
+            let since = *self.idle_since.entry((guild_id, user_id)).or_insert(now);
+            if (now - since).num_seconds() < i64::from(after_secs) {
+                continue;
+            }
+            GuildId::new(guild_id)
+                .move_member(&self.bot.http, UserId::new(user_id), afk_channel_id)
+                .await?;
+            self.idle_since.remove(&(guild_id, user_id));
+            moved += 1;
+        }
+
+        if moved > 0 {
+            info!("Moved {moved} idle members of guild {guild_id} to the AFK channel");
+        }
+        Ok(moved)
+    }
+}
+
+/// Returns the members whose open session is the only one in its channel, as user and
+/// channel IDs.
+fn alone_members(sessions: &[(u64, OpenSession)]) -> Vec<(u64, u64)> {
+    let mut per_channel: HashMap<u64, u32> = HashMap::new();
+    for (_, session) in sessions {
+        *per_channel.entry(session.channel_id).or_default() += 1;
+    }
+    sessions
+        .iter()
+        .filter(|(_, session)| per_channel[&session.channel_id] == 1)
+        .map(|(user_id, session)| (*user_id, session.channel_id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(channel_id: u64) -> OpenSession {
+        let now = Utc::now();
+        OpenSession {
+            channel_id,
+            join_time: now,
+            started_at: now,
+            flushed_at: now,
+        }
+    }
+
+    #[test]
+    fn alone_members_skips_shared_channels() {
+        let sessions = vec![(1, session(10)), (2, session(10)), (3, session(20))];
+
+        assert_eq!(alone_members(&sessions), vec![(3, 20)]);
+    }
+}
@@ -0,0 +1,64 @@
+/// Hourly sync of the server events kept for airing anime.
+use std::sync::Arc;
+
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::event::AiringEventsEvent;
+use crate::event::event_bus::EventBus;
+use crate::service::traits::ReleaseCalendarProvider;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between runs
+const SYNC_INTERVAL_SECS: u64 = 3600;
+
+/// Collects the next airings of each server keeping airing events and publishes an
+/// [`AiringEventsEvent`] for it, which brings the server's events in line with them.
+pub struct AiringEventsTask {
+    service: Arc<dyn ReleaseCalendarProvider>,
+    event_bus: Arc<EventBus>,
+}
+
+impl AiringEventsTask {
+    /// Creates a new airing events task with the given service.
+    pub fn new(service: Arc<dyn ReleaseCalendarProvider>, event_bus: Arc<EventBus>) -> Self {
+        Self { service, event_bus }
+    }
+
+    /// Starts the airing events task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("airing_events", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(SYNC_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                task.run().await;
+            }
+        });
+
+        info!("Airing events task started (every {SYNC_INTERVAL_SECS} seconds)");
+    }
+
+    /// Syncs the airing events of every server keeping them.
+    pub async fn run(&self) {
+        let guilds = match self.service.guilds_with_airing_events().await {
+            Ok(guilds) => guilds,
+            Err(e) => {
+                error!("Failed to list servers with airing events: {e}");
+                return;
+            }
+        };
+
+        for guild_id in guilds {
+            match self.service.upcoming_airings(guild_id).await {
+                Ok(airings) => {
+                    self.event_bus
+                        .publish(AiringEventsEvent { guild_id, airings });
+                }
+                Err(e) => error!("Failed to collect airings of guild {guild_id}: {e}"),
+            }
+        }
+    }
+}
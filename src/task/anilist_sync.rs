@@ -0,0 +1,54 @@
+/// Periodic re-sync of linked AniList watching lists.
+use std::sync::Arc;
+
+use chrono::Utc;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::service::traits::AniListSyncProvider;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between runs
+const SYNC_INTERVAL_SECS: u64 = 3600;
+
+/// Links synced per run, two AniList requests each. AniList allows 30 requests a
+/// minute, shared with feed polling.
+const LINKS_PER_RUN: u32 = 20;
+
+/// Syncs the lists of users with auto sync on, a few links per run, so subscriptions
+/// follow their AniList list without running `/feed sync-anilist`.
+pub struct AniListSyncTask {
+    service: Arc<dyn AniListSyncProvider>,
+}
+
+impl AniListSyncTask {
+    /// Creates a new AniList sync task with the given service.
+    pub fn new(service: Arc<dyn AniListSyncProvider>) -> Self {
+        Self { service }
+    }
+
+    /// Starts the sync task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("anilist_sync", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(SYNC_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                task.run().await;
+            }
+        });
+
+        info!("AniList sync task started (every {SYNC_INTERVAL_SECS} seconds)");
+    }
+
+    /// Syncs the links that are due.
+    pub async fn run(&self) {
+        match self.service.sync_due(&Utc::now(), LINKS_PER_RUN).await {
+            Ok(0) => {}
+            Ok(synced) => info!("Synced {synced} AniList lists"),
+            Err(e) => error!("Failed to sync AniList lists: {e}"),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+/// Periodic deletion of feed items and voice sessions past their retention window.
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::service::traits::FeedSubscriptionProvider;
+use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between prunes
+const PRUNE_INTERVAL_SECS: u64 = 24 * 3600;
+
+/// Deletes data older than the bot's default retention or a server's shorter window, so
+/// servers can keep less history than the default.
+pub struct DataPruningTask {
+    feeds: Arc<dyn FeedSubscriptionProvider>,
+    voice: Arc<dyn VoiceTracker>,
+}
+
+impl DataPruningTask {
+    /// Creates a new pruning task with the given services.
+    pub fn new(feeds: Arc<dyn FeedSubscriptionProvider>, voice: Arc<dyn VoiceTracker>) -> Self {
+        Self { feeds, voice }
+    }
+
+    /// Starts the pruning task. The first prune runs right away.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("data_pruning", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(PRUNE_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                task.run(Utc::now()).await;
+            }
+        });
+
+        info!("Data pruning task started (every {PRUNE_INTERVAL_SECS} seconds)");
+    }
+
+    /// Prunes feed items and voice sessions. A failure in one does not stop the other.
+    pub async fn run(&self, now: DateTime<Utc>) {
+        if let Err(e) = self.prune_feed_items(&now).await {
+            error!("Failed to prune feed items: {e}");
+        }
+        if let Err(e) = self.prune_voice_sessions(&now).await {
+            error!("Failed to prune voice sessions: {e}");
+        }
+    }
+
+    async fn prune_feed_items(&self, now: &DateTime<Utc>) -> Result<u32> {
+        let pruned = self.feeds.prune_feed_items(now).await?;
+        if pruned > 0 {
+            info!("Pruned {pruned} feed items past their retention window");
+        }
+        Ok(pruned)
+    }
+
+    async fn prune_voice_sessions(&self, now: &DateTime<Utc>) -> Result<u32> {
+        let pruned = self.voice.prune_sessions(now).await?;
+        if pruned > 0 {
+            info!("Pruned {pruned} voice sessions past their retention window");
+        }
+        Ok(pruned)
+    }
+}
@@ -0,0 +1,87 @@
+/// Weekly leaderboard snapshot task for week-over-week comparisons.
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use log::debug;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::entity::BotMetaKey;
+use crate::service::traits::InternalOps;
+use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between checks for a due snapshot
+const CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Minimum time between snapshots
+const SNAPSHOT_INTERVAL_DAYS: i64 = 7;
+
+/// Snapshots leaderboard standings once a week.
+pub struct LeaderboardSnapshotTask {
+    internal: Arc<dyn InternalOps>,
+    service: Arc<dyn VoiceTracker>,
+}
+
+impl LeaderboardSnapshotTask {
+    /// Creates a new snapshot task with the given services.
+    pub fn new(internal: Arc<dyn InternalOps>, service: Arc<dyn VoiceTracker>) -> Self {
+        Self { internal, service }
+    }
+
+    /// Reads the last snapshot timestamp from database.
+    pub async fn read_last_snapshot(&self) -> Result<Option<DateTime<Utc>>> {
+        let value = self
+            .internal
+            .get_meta(BotMetaKey::LeaderboardSnapshot)
+            .await?;
+
+        match value {
+            Some(ts_str) => {
+                let timestamp = DateTime::parse_from_rfc3339(&ts_str)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|e| anyhow::anyhow!("Invalid snapshot timestamp: {e}"))?;
+                Ok(Some(timestamp))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Starts the snapshot task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("leaderboard_snapshot", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                if let Err(e) = task.run_if_due(Utc::now()).await {
+                    error!("Failed to take leaderboard snapshot: {e}");
+                }
+            }
+        });
+
+        info!("Leaderboard snapshot task started (every {SNAPSHOT_INTERVAL_DAYS} days)");
+    }
+
+    /// Takes a snapshot if none was taken in the last week. Returns whether one was taken.
+    pub async fn run_if_due(&self, now: DateTime<Utc>) -> Result<bool> {
+        if let Some(last) = self.read_last_snapshot().await?
+            && now - last < chrono::Duration::days(SNAPSHOT_INTERVAL_DAYS)
+        {
+            debug!("Leaderboard snapshot not due (last taken at {last})");
+            return Ok(false);
+        }
+
+        let rows = self.service.take_leaderboard_snapshot(&now).await?;
+        self.internal
+            .set_meta(BotMetaKey::LeaderboardSnapshot, now.to_rfc3339())
+            .await?;
+
+        info!("Leaderboard snapshot taken: {rows} standings recorded");
+        Ok(true)
+    }
+}
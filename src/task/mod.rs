@@ -1,7 +1,21 @@
-//! Background tasks for feed polling and voice tracking.
+//! Background tasks for feed polling, AniList list sync, release calendars, airing events,
+//! voice tracking and data pruning.
 
+pub mod afk_mover;
+pub mod airing_events;
+pub mod anilist_sync;
+pub mod data_pruning;
+pub mod leaderboard_snapshot;
+pub mod poll_schedule;
+pub mod release_calendar;
 pub mod series_feed_publisher;
+pub mod startup_reconciler;
+pub mod supervisor;
+pub mod voice_flags;
+pub mod voice_goal;
 pub mod voice_heartbeat;
+pub mod voice_projection;
+pub mod voice_totals;
 
 // use std::borrow::Cow;
 // use std::sync::Arc;
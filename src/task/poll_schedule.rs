@@ -0,0 +1,215 @@
+//! Per-feed poll intervals adapted to how often each feed releases.
+//!
+//! Feeds are either [`PollTier::Active`], with a release in the last [`ACTIVE_HOURS`],
+//! or [`PollTier::Dormant`] and polled at a multiple of the normal interval. On top of
+//! that, feeds with a known release cadence are polled rarely between releases and
+//! often around their expected release.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+
+use crate::entity::PollTier;
+
+/// A feed stays active while its latest release is at most this many hours old.
+pub const ACTIVE_HOURS: i64 = 48;
+/// Release gaps averaged to estimate how often a feed releases.
+pub const CADENCE_SAMPLES: usize = 8;
+/// Away from its expected release, a feed is checked this many times per release interval.
+const CHECKS_PER_RELEASE: i32 = 8;
+/// A feed is checked at the minimum interval from this share of its release interval
+/// before its expected release until the same time after.
+const RELEASE_WINDOW_DIVISOR: i32 = 10;
+
+/// Publish times of a feed's latest releases.
+#[derive(Clone, Debug, Default)]
+pub struct ReleaseCadence {
+    published: VecDeque<DateTime<Utc>>,
+}
+
+impl ReleaseCadence {
+    /// Creates a cadence from publish times, oldest first.
+    pub fn new(published: impl IntoIterator<Item = DateTime<Utc>>) -> Self {
+        let mut cadence = Self::default();
+        for published in published {
+            cadence.record(published);
+        }
+        cadence
+    }
+
+    /// Adds a release. Releases not newer than the latest one are ignored.
+    pub fn record(&mut self, published: DateTime<Utc>) {
+        if self.published.back().is_some_and(|last| published <= *last) {
+            return;
+        }
+        self.published.push_back(published);
+        if self.published.len() > CADENCE_SAMPLES + 1 {
+            self.published.pop_front();
+        }
+    }
+
+    /// Average time between releases. `None` until the feed released twice.
+    pub fn average_interval(&self) -> Option<TimeDelta> {
+        let (first, last) = (self.published.front()?, self.published.back()?);
+        let gaps = i32::try_from(self.published.len() - 1).ok()?;
+        (gaps > 0).then(|| (*last - *first) / gaps)
+    }
+
+    /// When the next release is expected, one average interval after the latest.
+    pub fn expected_release(&self) -> Option<DateTime<Utc>> {
+        Some(self.latest()? + self.average_interval()?)
+    }
+
+    /// When the latest release was published.
+    pub fn latest(&self) -> Option<DateTime<Utc>> {
+        self.published.back().copied()
+    }
+
+    /// The tier of a feed with these releases: active if it released in the last
+    /// [`ACTIVE_HOURS`], dormant otherwise.
+    pub fn tier(&self, now: DateTime<Utc>) -> PollTier {
+        match self.latest() {
+            Some(latest) if now - latest <= TimeDelta::hours(ACTIVE_HOURS) => PollTier::Active,
+            _ => PollTier::Dormant,
+        }
+    }
+}
+
+/// Bounds of the time between checks of one feed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PollSchedule {
+    /// Interval for active feeds without enough releases to estimate their cadence.
+    pub base: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    /// Dormant feeds are polled at most once per `base` times this, outside their
+    /// expected release.
+    pub dormant_multiplier: u32,
+}
+
+impl PollSchedule {
+    /// A schedule checking every feed every `interval`.
+    pub fn fixed(interval: Duration) -> Self {
+        Self {
+            base: interval,
+            min: interval,
+            max: interval,
+            dormant_multiplier: 1,
+        }
+    }
+
+    /// Returns how long to wait before checking a feed again.
+    ///
+    /// Near its expected release a feed is checked at [`Self::min`]. Otherwise it is
+    /// checked a few times per release interval, so monthly feeds are checked rarely,
+    /// but never later than the start of the expected release's window. Dormant feeds
+    /// wait at least [`Self::dormant_multiplier`] times [`Self::base`] outside that window.
+    pub fn interval(&self, cadence: &ReleaseCadence, now: DateTime<Utc>) -> Duration {
+        let tier = cadence.tier(now);
+        let tier_interval = match tier {
+            PollTier::Active => self.base,
+            PollTier::Dormant => self.base * self.dormant_multiplier,
+        };
+        let (Some(average), Some(expected)) =
+            (cadence.average_interval(), cadence.expected_release())
+        else {
+            return self.clamp(tier_interval);
+        };
+        if average <= TimeDelta::zero() {
+            return self.clamp(tier_interval);
+        }
+
+        let floor = match tier {
+            PollTier::Active => TimeDelta::zero(),
+            PollTier::Dormant => TimeDelta::from_std(tier_interval).unwrap_or(TimeDelta::MAX),
+        };
+        let relaxed = (average / CHECKS_PER_RELEASE).max(floor);
+        let window = average / RELEASE_WINDOW_DIVISOR;
+        let interval = if now < expected - window {
+            relaxed.min(expected - window - now)
+        } else if now <= expected + window {
+            TimeDelta::zero()
+        } else {
+            // Late releases are caught at the relaxed pace
+            relaxed
+        };
+        self.clamp(interval.to_std().unwrap_or_default())
+    }
+
+    fn clamp(&self, interval: Duration) -> Duration {
+        interval.max(self.min).min(self.max.max(self.min))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> PollSchedule {
+        PollSchedule {
+            base: Duration::from_secs(180),
+            min: Duration::from_secs(60),
+            max: Duration::from_secs(6 * 3600),
+            dormant_multiplier: 4,
+        }
+    }
+
+    #[test]
+    fn cadence_averages_recent_gaps() {
+        let start = Utc::now();
+        let cadence = ReleaseCadence::new((0..20).map(|week| start + TimeDelta::weeks(week)));
+
+        assert_eq!(cadence.average_interval(), Some(TimeDelta::weeks(1)));
+        assert_eq!(
+            cadence.expected_release(),
+            Some(start + TimeDelta::weeks(20))
+        );
+        assert_eq!(ReleaseCadence::new([start]).average_interval(), None);
+    }
+
+    #[test]
+    fn interval_stretches_for_slow_feeds_and_tightens_near_release() {
+        let schedule = schedule();
+        let last = Utc::now();
+        let monthly =
+            ReleaseCadence::new([last - TimeDelta::days(60), last - TimeDelta::days(30), last]);
+
+        // Unknown cadence uses the base interval
+        assert_eq!(
+            schedule.interval(&ReleaseCadence::default(), last),
+            schedule.base
+        );
+        // Right after a release, a monthly feed is checked at the maximum interval
+        assert_eq!(
+            schedule.interval(&monthly, last + TimeDelta::days(1)),
+            schedule.max
+        );
+        // Near the expected release it is checked at the minimum interval
+        assert_eq!(
+            schedule.interval(&monthly, last + TimeDelta::days(30)),
+            schedule.min
+        );
+        // Just before the window, the wait ends when the window starts
+        assert_eq!(
+            schedule.interval(&monthly, last + TimeDelta::days(27) - TimeDelta::hours(1)),
+            Duration::from_secs(3600)
+        );
+    }
+
+    #[test]
+    fn dormant_feeds_wait_a_multiple_of_the_base_interval() {
+        let schedule = schedule();
+        let now = Utc::now();
+        let recent = ReleaseCadence::new([now - TimeDelta::hours(47)]);
+        let dormant = ReleaseCadence::new([now - TimeDelta::hours(49)]);
+
+        assert_eq!(recent.tier(now), PollTier::Active);
+        assert_eq!(schedule.interval(&recent, now), schedule.base);
+        assert_eq!(dormant.tier(now), PollTier::Dormant);
+        assert_eq!(schedule.interval(&dormant, now), schedule.base * 4);
+        assert_eq!(ReleaseCadence::default().tier(now), PollTier::Dormant);
+    }
+}
@@ -0,0 +1,69 @@
+/// Daily refresh of the pinned release calendars of servers.
+use std::sync::Arc;
+
+use chrono::Utc;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::event::ReleaseCalendarEvent;
+use crate::event::event_bus::EventBus;
+use crate::service::traits::ReleaseCalendarProvider;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between runs
+const REFRESH_INTERVAL_SECS: u64 = 86400;
+
+/// Collects the upcoming releases of each server keeping a release calendar and publishes
+/// a [`ReleaseCalendarEvent`] for it, which updates the server's pinned message.
+pub struct ReleaseCalendarTask {
+    service: Arc<dyn ReleaseCalendarProvider>,
+    event_bus: Arc<EventBus>,
+}
+
+impl ReleaseCalendarTask {
+    /// Creates a new release calendar task with the given service.
+    pub fn new(service: Arc<dyn ReleaseCalendarProvider>, event_bus: Arc<EventBus>) -> Self {
+        Self { service, event_bus }
+    }
+
+    /// Starts the release calendar task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("release_calendar", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                task.run().await;
+            }
+        });
+
+        info!("Release calendar task started (every {REFRESH_INTERVAL_SECS} seconds)");
+    }
+
+    /// Refreshes the calendar of every server keeping one.
+    pub async fn run(&self) {
+        let guilds = match self.service.guilds_with_calendar().await {
+            Ok(guilds) => guilds,
+            Err(e) => {
+                error!("Failed to list release calendars: {e}");
+                return;
+            }
+        };
+
+        let now = Utc::now();
+        for (guild_id, channel_id) in guilds {
+            match self.service.upcoming_releases(guild_id, &now).await {
+                Ok(releases) => {
+                    self.event_bus.publish(ReleaseCalendarEvent {
+                        guild_id,
+                        channel_id,
+                        releases,
+                    });
+                }
+                Err(e) => error!("Failed to collect releases of guild {guild_id}: {e}"),
+            }
+        }
+    }
+}
@@ -1,102 +1,441 @@
 //! Background task for polling feed updates.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::ControlFlow;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
 use log::debug;
 use log::error;
 use log::info;
+use log::warn;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
 use tokio::time::Sleep;
 use tokio::time::sleep;
 
 use crate::entity::FeedEntity;
+use crate::entity::PollTier;
 use crate::event::FeedUpdateData;
 use crate::event::FeedUpdateEvent;
+use crate::event::FeedUpdateKind;
 use crate::event::event_bus::EventBus;
 use crate::service::feed_subscription::FeedUpdateResult;
 use crate::service::traits::FeedSubscriptionProvider;
+use crate::task::poll_schedule::CADENCE_SAMPLES;
+use crate::task::poll_schedule::PollSchedule;
+use crate::task::poll_schedule::ReleaseCadence;
+use crate::task::supervisor::TaskMonitor;
 
 /// Task that periodically checks feeds for updates.
+///
+/// Each feed has its own next check, set by the [`PollSchedule`] from the feed's
+/// [`ReleaseCadence`]. Every cycle, one [`PollSchedule::min`] apart, checks the feeds
+/// that are due, spread evenly over the cycle. The feed's [`PollTier`] is stored after
+/// each check.
+///
+/// The poll state is owned by the check loop, which is driven by
+/// [`PublisherMessage`]s: the cycle timer sends [`PublisherMessage::CheckDue`], and
+/// callers can [`send`](Self::send) their own, e.g. to check a feed right away.
 pub struct SeriesFeedPublisher {
+    messages: mpsc::UnboundedSender<PublisherMessage>,
+    /// The loop's state and inbox. Only the running loop locks them, so they survive
+    /// a restart after a panic.
+    actor: Mutex<(PublisherActor, mpsc::UnboundedReceiver<PublisherMessage>)>,
+    running: AtomicBool,
+    /// Number of the latest run of the check loop, so a stop sent to an earlier run
+    /// doesn't stop a later one.
+    run: AtomicU64,
+    /// Name of the check loop in the [`TaskMonitor`].
+    task_name: &'static str,
+}
+
+/// Message handled by the [`SeriesFeedPublisher`] check loop, one at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublisherMessage {
+    /// Checks the feeds that are due, as every cycle does.
+    CheckDue,
+    /// Checks one feed now, whether or not it is due.
+    CheckFeed(i32),
+    /// Stops the given run of the loop, see [`SeriesFeedPublisher::stop`]. Sent during a
+    /// cycle, it stops before the next feed. Stops of an earlier run are ignored.
+    Stop(u64),
+}
+
+/// State of the check loop.
+struct PublisherActor {
     service: Arc<dyn FeedSubscriptionProvider>,
     event_bus: Arc<EventBus>,
-    poll_interval: Duration,
-    running: AtomicBool,
+    schedule: PollSchedule,
+    /// Tag of the feeds this loop checks.
+    tag: &'static str,
+    /// Run of the check loop that owns the state.
+    run: u64,
+    feeds: HashMap<i32, FeedPollState>,
+    /// Next checks handed off by the previous run, used once each feed is tracked.
+    restored: HashMap<i32, DateTime<Utc>>,
+}
+
+/// When a feed is checked next and what its releases look like.
+struct FeedPollState {
+    cadence: ReleaseCadence,
+    next_check: DateTime<Utc>,
 }
 
 impl SeriesFeedPublisher {
-    /// Creates a new feed publisher with the given configuration.
+    /// Creates a new feed publisher checking every feed every `poll_interval`.
     pub fn new(
         service: Arc<dyn FeedSubscriptionProvider>,
         event_bus: Arc<EventBus>,
         poll_interval: Duration,
     ) -> Arc<Self> {
-        info!("Initializing FeedPublisher with poll interval {poll_interval:?}");
-        Arc::new(Self {
+        Self::with_schedule(service, event_bus, PollSchedule::fixed(poll_interval))
+    }
+
+    /// Creates a new feed publisher adapting each feed's poll interval to its releases.
+    pub fn with_schedule(
+        service: Arc<dyn FeedSubscriptionProvider>,
+        event_bus: Arc<EventBus>,
+        schedule: PollSchedule,
+    ) -> Arc<Self> {
+        info!(
+            "Initializing FeedPublisher with poll interval {:?} (min {:?}, max {:?}, dormant x{})",
+            schedule.base, schedule.min, schedule.max, schedule.dormant_multiplier
+        );
+        Self::for_tag(
+            service,
+            event_bus,
+            schedule,
+            "series",
+            "series_feed_publisher",
+        )
+    }
+
+    /// Creates a new feed publisher checking live feeds, e.g. Twitch channels, every
+    /// `poll_interval`. Live feeds go live at any time, so they don't follow a release
+    /// cadence.
+    pub fn live(
+        service: Arc<dyn FeedSubscriptionProvider>,
+        event_bus: Arc<EventBus>,
+        poll_interval: Duration,
+    ) -> Arc<Self> {
+        info!("Initializing live FeedPublisher with poll interval {poll_interval:?}");
+        Self::for_tag(
+            service,
+            event_bus,
+            PollSchedule::fixed(poll_interval),
+            "live",
+            "live_feed_publisher",
+        )
+    }
+
+    fn for_tag(
+        service: Arc<dyn FeedSubscriptionProvider>,
+        event_bus: Arc<EventBus>,
+        schedule: PollSchedule,
+        tag: &'static str,
+        task_name: &'static str,
+    ) -> Arc<Self> {
+        let (messages, inbox) = mpsc::unbounded_channel();
+        let actor = PublisherActor {
             service,
             event_bus,
-            poll_interval,
+            schedule,
+            tag,
+            run: 0,
+            feeds: HashMap::new(),
+            restored: HashMap::new(),
+        };
+        Arc::new(Self {
+            messages,
+            actor: Mutex::new((actor, inbox)),
             running: AtomicBool::new(false),
+            run: AtomicU64::new(0),
+            task_name,
         })
     }
 
     /// Starts the feed polling loop.
-    pub fn start(self: Arc<Self>) -> anyhow::Result<()> {
-        if !self.running.load(Ordering::SeqCst) {
-            self.running.store(true, Ordering::SeqCst);
+    pub fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) -> anyhow::Result<()> {
+        if !self.running.swap(true, Ordering::SeqCst) {
             info!("Starting FeedPublisher check loop.");
-            self.spawn_check_loop();
+            let run = self.run.fetch_add(1, Ordering::SeqCst) + 1;
+            self.spawn_check_loop(tasks, run);
         }
         Ok(())
     }
 
-    /// Stops the feed polling loop.
+    /// Stops the feed polling loop. It can be started again right away; the new loop
+    /// begins once the stopped one finished its current feed.
     pub fn stop(self: Arc<Self>) -> anyhow::Result<()> {
+        self.stop_run()
+    }
+
+    /// Sends a stop to the current run of the check loop, if it is running.
+    fn stop_run(&self) -> anyhow::Result<()> {
+        if !self.running.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
         info!("Stopping FeedPublisher check loop.");
-        self.running.store(false, Ordering::SeqCst);
-        Ok(())
+        self.send(PublisherMessage::Stop(self.run.load(Ordering::SeqCst)))
     }
 
-    fn spawn_check_loop(self: Arc<Self>) {
-        let mut interval = tokio::time::interval(self.poll_interval);
-        tokio::spawn(async move {
+    /// Restores when each feed is checked next, as handed off by the previous run, so
+    /// a restart doesn't check every feed at once. Call it before [`start`](Self::start).
+    pub async fn restore_schedule(&self, next_checks: HashMap<i32, DateTime<Utc>>) {
+        self.actor.lock().await.0.restored = next_checks;
+    }
+
+    /// Stops the feed polling loop and returns when each feed is checked next, for the
+    /// next run to [`restore_schedule`](Self::restore_schedule). Waits for the feed
+    /// being checked, if any.
+    pub async fn shutdown(&self) -> HashMap<i32, DateTime<Utc>> {
+        if let Err(e) = self.stop_run() {
+            warn!("{e}");
+        }
+        let guard = self.actor.lock().await;
+        guard
+            .0
+            .feeds
+            .iter()
+            .map(|(&id, state)| (id, state.next_check))
+            .collect()
+    }
+
+    /// Queues a message for the check loop. Messages sent before [`start`](Self::start)
+    /// are handled once the loop runs.
+    pub fn send(&self, message: PublisherMessage) -> anyhow::Result<()> {
+        self.messages
+            .send(message)
+            .map_err(|e| anyhow::anyhow!("Failed to send {:?} to FeedPublisher", e.0))
+    }
+
+    fn spawn_check_loop(self: Arc<Self>, tasks: &Arc<TaskMonitor>, run: u64) {
+        tasks.spawn(self.task_name, self, move |task| async move {
+            let mut guard = task.actor.lock().await;
+            let (actor, inbox) = &mut *guard;
+            actor.run = run;
+            let mut interval = tokio::time::interval(actor.schedule.min);
             loop {
-                interval.tick().await;
-                if !self.running.load(Ordering::SeqCst) {
+                let message = tokio::select! {
+                    _ = interval.tick() => PublisherMessage::CheckDue,
+                    message = inbox.recv() => message.unwrap_or(PublisherMessage::Stop(run)),
+                };
+                if actor.handle(message, inbox).await.is_break() {
                     info!("Stopping check loop.");
                     break;
                 }
-                if let Err(e) = self.check_updates().await {
+            }
+        });
+    }
+}
+
+impl PublisherActor {
+    /// Handles one message, breaking when the loop should stop.
+    async fn handle(
+        &mut self,
+        message: PublisherMessage,
+        inbox: &mut mpsc::UnboundedReceiver<PublisherMessage>,
+    ) -> ControlFlow<()> {
+        match message {
+            PublisherMessage::CheckDue => match self.check_updates(inbox).await {
+                Ok(flow) => flow,
+                Err(e) => {
                     error!("Error checking updates: {e}");
+                    ControlFlow::Continue(())
                 }
+            },
+            PublisherMessage::CheckFeed(feed_id) => {
+                self.check_feed_now(feed_id).await;
+                ControlFlow::Continue(())
             }
-        });
+            PublisherMessage::Stop(run) => self.stop_flow(run),
+        }
     }
 
-    async fn check_updates(&self) -> anyhow::Result<()> {
+    /// Breaks on a stop sent to this run, and ignores the stop of an earlier one.
+    fn stop_flow(&self, run: u64) -> ControlFlow<()> {
+        if run == self.run {
+            ControlFlow::Break(())
+        } else {
+            debug!("Ignoring stop of earlier check loop run {run}.");
+            ControlFlow::Continue(())
+        }
+    }
+
+    async fn check_updates(
+        &mut self,
+        inbox: &mut mpsc::UnboundedReceiver<PublisherMessage>,
+    ) -> anyhow::Result<ControlFlow<()>> {
         debug!("Checking for feed updates.");
 
-        // Get all feeds containing tag "series"
-        let feeds = self.service.get_feeds_by_tag("series").await?;
+        let feeds = self.service.get_feeds_by_tag(self.tag).await?;
+        let feeds_total = feeds.len();
+        let now = Utc::now();
+        // Forget feeds that were removed since the last cycle
+        let ids: HashSet<i32> = feeds.iter().map(|feed| feed.id).collect();
+        self.feeds.retain(|id, _| ids.contains(id));
+        let feeds = self.due_feeds(feeds, now).await;
         let feeds_len = feeds.len();
-        info!("Found {} feeds to check.", feeds.len());
+        info!("Found {feeds_len} of {feeds_total} feeds due for a check.");
 
         for feed in feeds {
             let id = feed.id;
             let name = feed.name.clone();
+            let poll_tier = feed.poll_tier;
             if let Err(e) = self.check_feed(feed).await {
                 error!("Error checking feed id `{id}` ({name}): {e:?}");
             };
-            Self::check_feed_wait(feeds_len, &self.poll_interval).await;
+            self.schedule_next_check(id, now);
+            self.update_poll_tier(id, poll_tier).await;
+
+            // Messages are handled while waiting for the next feed, so a stop or a
+            // requested check doesn't wait for the cycle to end
+            let wait = Self::check_feed_wait(feeds_len, &self.schedule.min);
+            tokio::pin!(wait);
+            loop {
+                tokio::select! {
+                    _ = &mut wait => break,
+                    message = inbox.recv() => match message {
+                        Some(PublisherMessage::Stop(run)) => {
+                            if self.stop_flow(run).is_break() {
+                                return Ok(ControlFlow::Break(()));
+                            }
+                        }
+                        None => return Ok(ControlFlow::Break(())),
+                        // This cycle is already checking the due feeds
+                        Some(PublisherMessage::CheckDue) => {}
+                        Some(PublisherMessage::CheckFeed(feed_id)) => {
+                            self.check_feed_now(feed_id).await
+                        }
+                    },
+                }
+            }
         }
 
         debug!("Finished checking for feed updates.");
-        Ok(())
+        Ok(ControlFlow::Continue(()))
     }
 
-    async fn check_feed(&self, feed: FeedEntity) -> anyhow::Result<()> {
+    /// Checks a feed outside of its schedule. Its next check is counted from now.
+    async fn check_feed_now(&mut self, feed_id: i32) {
+        let feed = match self.service.get_feed(feed_id).await {
+            Ok(Some(feed)) => feed,
+            Ok(None) => {
+                debug!("Feed id `{feed_id}` no longer exists.");
+                return;
+            }
+            Err(e) => {
+                error!("Failed to load feed id `{feed_id}`: {e}");
+                return;
+            }
+        };
+        let now = Utc::now();
+        let poll_tier = feed.poll_tier;
+        if !self.feeds.contains_key(&feed_id) {
+            self.track_feed(&feed, now).await;
+        }
+        let desc = self.get_feed_desc(&feed);
+        if let Err(e) = self.check_feed(feed).await {
+            error!("Error checking {desc}: {e:?}");
+        }
+        self.schedule_next_check(feed_id, now);
+        self.update_poll_tier(feed_id, poll_tier).await;
+    }
+
+    /// Returns the feeds whose next check is due. Feeds seen for the first time are due
+    /// unless the previous run handed off a later check, and their cadence is loaded from
+    /// their stored items.
+    ///
+    /// A check due within half a cycle counts as due now, since the next cycle would be
+    /// later than this one.
+    async fn due_feeds(&mut self, feeds: Vec<FeedEntity>, now: DateTime<Utc>) -> Vec<FeedEntity> {
+        let horizon = now + TimeDelta::from_std(self.schedule.min / 2).unwrap_or_default();
+
+        let mut due = Vec::new();
+        for feed in feeds {
+            match self.feeds.get(&feed.id).map(|state| state.next_check) {
+                Some(next_check) if next_check > horizon => continue,
+                Some(_) => due.push(feed),
+                None => {
+                    self.track_feed(&feed, now).await;
+                    if self.feeds[&feed.id].next_check <= horizon {
+                        due.push(feed);
+                    }
+                }
+            }
+        }
+        due
+    }
+
+    /// Starts tracking a feed, due at `now` or its restored next check, with its cadence
+    /// loaded from its stored items.
+    async fn track_feed(&mut self, feed: &FeedEntity, now: DateTime<Utc>) {
+        let cadence = match self
+            .service
+            .get_release_history(feed.id, CADENCE_SAMPLES + 1)
+            .await
+        {
+            Ok(published) => ReleaseCadence::new(published),
+            Err(e) => {
+                warn!(
+                    "Failed to load release history of {}: {e}",
+                    self.get_feed_desc(feed)
+                );
+                ReleaseCadence::default()
+            }
+        };
+        self.feeds.insert(
+            feed.id,
+            FeedPollState {
+                cadence,
+                next_check: self.restored.remove(&feed.id).unwrap_or(now),
+            },
+        );
+    }
+
+    /// Sets when a feed checked in the cycle started at `cycle_start` is checked next.
+    ///
+    /// Counting from the cycle start keeps feeds on a fixed schedule due every cycle,
+    /// however late in the cycle they were checked.
+    fn schedule_next_check(&mut self, feed_id: i32, cycle_start: DateTime<Utc>) {
+        if let Some(state) = self.feeds.get_mut(&feed_id) {
+            let interval = self.schedule.interval(&state.cadence, Utc::now());
+            debug!("Checking feed id `{feed_id}` again in {interval:?}");
+            state.next_check = cycle_start + interval;
+        }
+    }
+
+    /// Stores a feed's tier when its releases moved it to another one.
+    async fn update_poll_tier(&self, feed_id: i32, stored: PollTier) {
+        let tier = self
+            .feeds
+            .get(&feed_id)
+            .map(|state| state.cadence.tier(Utc::now()));
+        let Some(tier) = tier.filter(|tier| *tier != stored) else {
+            return;
+        };
+        match self.service.set_poll_tier(feed_id, tier).await {
+            Ok(()) => info!("Feed id `{feed_id}` is now {}.", tier.as_str()),
+            Err(e) => error!("Failed to store poll tier of feed id `{feed_id}`: {e}"),
+        }
+    }
+
+    /// Adds a new release to a feed's cadence.
+    fn record_release(&mut self, feed_id: i32, published: DateTime<Utc>) {
+        if let Some(state) = self.feeds.get_mut(&feed_id) {
+            state.cadence.record(published);
+        }
+    }
+
+    async fn check_feed(&mut self, feed: FeedEntity) -> anyhow::Result<()> {
         match self.service.check_feed_update(&feed).await? {
             FeedUpdateResult::NoUpdate => {
                 debug!(
@@ -126,6 +465,7 @@ impl SeriesFeedPublisher {
                         .map_or("None".to_string(), |e| e.description.clone()),
                     new_item.description
                 );
+                self.record_release(feed.id, new_item.published);
 
                 let feed = Arc::new(feed);
                 let feed_info = Arc::new(feed_info);
@@ -137,6 +477,7 @@ impl SeriesFeedPublisher {
                     feed_info: feed_info.clone(),
                     old_feed_item: old_feed_item.clone(),
                     new_feed_item: new_feed_item.clone(),
+                    kind: FeedUpdateKind::New,
                 };
 
                 // Publish update event
@@ -145,6 +486,33 @@ impl SeriesFeedPublisher {
                 self.event_bus.publish(event);
                 Ok(())
             }
+            FeedUpdateResult::Edited {
+                feed: _,
+                item,
+                feed_info,
+            } => {
+                info!(
+                    "Edited {} found for {}: {}",
+                    feed_info.feed_item_name.to_lowercase(),
+                    self.get_feed_desc(&feed),
+                    item.description
+                );
+
+                let data = FeedUpdateData {
+                    feed: Arc::new(feed),
+                    feed_info: Arc::new(feed_info),
+                    old_feed_item: None,
+                    new_feed_item: Arc::new(item),
+                    kind: FeedUpdateKind::Edited,
+                };
+
+                info!(
+                    "Publishing edit event for {}.",
+                    self.get_feed_desc(&data.feed)
+                );
+                self.event_bus.publish(FeedUpdateEvent::new(data));
+                Ok(())
+            }
         }
     }
 
@@ -169,12 +537,12 @@ mod tests {
     #[test]
     fn feed_interval_calculation() {
         assert_eq!(
-            SeriesFeedPublisher::calculate_feed_interval(10, &Duration::from_secs(60)),
+            PublisherActor::calculate_feed_interval(10, &Duration::from_secs(60)),
             Duration::from_secs(6)
         );
 
         assert_eq!(
-            SeriesFeedPublisher::calculate_feed_interval(0, &Duration::from_secs(60)),
+            PublisherActor::calculate_feed_interval(0, &Duration::from_secs(60)),
             Duration::from_secs(60) // Division by 1 when length is 0
         );
     }
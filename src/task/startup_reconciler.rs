@@ -0,0 +1,250 @@
+/// One-off reconciliation of state a previous run may have left behind.
+use std::sync::Arc;
+
+use anyhow::Result;
+use log::info;
+use log::warn;
+use poise::serenity_prelude::*;
+
+use crate::bot::Bot;
+use crate::bot::send_queue::SendTarget;
+use crate::event::FeedUpdateData;
+use crate::event::FeedUpdateEvent;
+use crate::event::event_bus::EventBus;
+use crate::handoff::HandoffSession;
+use crate::service::traits::FeedStatsProvider;
+use crate::service::traits::FeedSubscriptionProvider;
+use crate::task::voice_heartbeat::VoiceHeartbeatManager;
+use crate::task::voice_projection::VoiceProjectionTask;
+
+/// Runs once at startup, before the bot connects, to fix what an unclean shutdown left
+/// behind and find what no longer matches the code:
+///
+/// 1. Applies voice events recorded but not yet projected into sessions.
+/// 2. Closes voice sessions left open, ending them at the last heartbeat, except those
+///    handed off by a clean shutdown.
+/// 3. Finds stored feeds whose platform is no longer registered.
+///
+/// Feed notifications the previous run left in the outbox are sent again by
+/// [`Reconciliation::redeliver`] once the Discord subscribers are registered, since
+/// they do the sending.
+///
+/// Every step is safe to run again: a second run finds nothing left to do.
+pub struct StartupReconciler {
+    feeds: Arc<dyn FeedSubscriptionProvider>,
+    voice_heartbeat: Option<Arc<VoiceHeartbeatManager>>,
+    voice_projection: Option<Arc<VoiceProjectionTask>>,
+    handed_off: Vec<HandoffSession>,
+}
+
+/// What a reconciliation pass found and fixed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reconciliation {
+    /// Voice events applied that the previous run had not projected yet.
+    pub projected_events: u32,
+    /// Voice sessions closed at the last heartbeat.
+    pub closed_sessions: u32,
+    /// Platform IDs of stored feeds without a registered platform, with feed counts.
+    pub unregistered_platforms: Vec<(String, u32)>,
+    /// Feed notifications from the outbox the previous run had not sent.
+    pub redelivered: u32,
+}
+
+impl StartupReconciler {
+    /// Creates a reconciler checking the feeds of the given service.
+    pub fn new(feeds: Arc<dyn FeedSubscriptionProvider>) -> Self {
+        Self {
+            feeds,
+            voice_heartbeat: None,
+            voice_projection: None,
+            handed_off: Vec::new(),
+        }
+    }
+
+    /// Recovers voice sessions through the heartbeat, applying pending voice events first
+    /// when event sourcing is enabled.
+    pub fn with_voice(
+        mut self,
+        voice_heartbeat: Arc<VoiceHeartbeatManager>,
+        voice_projection: Option<Arc<VoiceProjectionTask>>,
+    ) -> Self {
+        self.voice_heartbeat = Some(voice_heartbeat);
+        self.voice_projection = voice_projection;
+        self
+    }
+
+    /// Keeps the voice sessions the previous run handed off at shutdown open, for the
+    /// voice state subscriber to carry on.
+    pub fn with_handoff(mut self, sessions: Vec<HandoffSession>) -> Self {
+        self.handed_off = sessions;
+        self
+    }
+
+    /// Runs the reconciliation pass.
+    pub async fn run(&self) -> Result<Reconciliation> {
+        info!("Reconciling state from the previous run...");
+        let mut reconciliation = Reconciliation::default();
+
+        // Apply events recorded before the restart, so crash recovery sees their sessions
+        if let Some(voice_projection) = &self.voice_projection {
+            reconciliation.projected_events = voice_projection.run().await?;
+            if reconciliation.projected_events > 0 {
+                info!(
+                    "Projected {} pending voice events",
+                    reconciliation.projected_events
+                );
+            }
+        }
+
+        if let Some(voice_heartbeat) = &self.voice_heartbeat {
+            reconciliation.closed_sessions =
+                voice_heartbeat.recover_keeping(&self.handed_off).await?;
+            if reconciliation.closed_sessions > 0 {
+                info!(
+                    "Recovered {} orphaned voice sessions",
+                    reconciliation.closed_sessions
+                );
+            }
+            if !self.handed_off.is_empty() {
+                info!(
+                    "Kept {} voice sessions handed off by the previous run open",
+                    self.handed_off.len()
+                );
+            }
+        }
+
+        reconciliation.unregistered_platforms = self.feeds.get_unregistered_platforms().await?;
+        for (platform_id, count) in &reconciliation.unregistered_platforms {
+            warn!(
+                "{count} feeds belong to unregistered platform `{platform_id}` and are not checked"
+            );
+        }
+
+        Ok(reconciliation)
+    }
+}
+
+impl Reconciliation {
+    /// Sends the feed notifications the previous run left in the outbox again, one
+    /// event per update with only their subscribers as recipients. The subscribers put
+    /// them back in the outbox until they are sent, so a run stopping again loses none.
+    pub async fn redeliver(
+        &mut self,
+        feed_stats: &dyn FeedStatsProvider,
+        event_bus: &EventBus,
+    ) -> Result<()> {
+        let mut updates: Vec<(serde_json::Value, Vec<i32>)> = Vec::new();
+        for entry in feed_stats.take_undelivered().await? {
+            match updates.iter_mut().find(|(data, _)| *data == entry.data.0) {
+                Some((_, recipients)) => recipients.push(entry.subscriber_id),
+                None => updates.push((entry.data.0, vec![entry.subscriber_id])),
+            }
+        }
+
+        for (data, recipients) in updates {
+            let data = match serde_json::from_value::<FeedUpdateData>(data) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!(
+                        "Dropping {} notifications from the outbox that can't be read: {e}",
+                        recipients.len()
+                    );
+                    continue;
+                }
+            };
+            self.redelivered += recipients.len() as u32;
+            event_bus.publish(FeedUpdateEvent::redelivery(data, recipients));
+        }
+        if self.redelivered > 0 {
+            info!(
+                "Sending {} notifications the previous run had not sent",
+                self.redelivered
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether the pass found anything worth telling the owner.
+    pub fn has_discrepancies(&self) -> bool {
+        self.projected_events > 0
+            || self.closed_sessions > 0
+            || !self.unregistered_platforms.is_empty()
+            || self.redelivered > 0
+    }
+
+    /// DMs the findings to the bot owner. Does nothing if there are none.
+    pub async fn report_to_owner(&self, bot: &Bot, owner_id: UserId) -> Result<()> {
+        if !self.has_discrepancies() {
+            return Ok(());
+        }
+
+        let message = CreateMessage::new()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(vec![CreateComponent::Container(CreateContainer::new(
+                vec![CreateContainerComponent::TextDisplay(
+                    CreateTextDisplay::new(self.render()),
+                )],
+            ))]);
+        bot.send_queue
+            .send(SendTarget::Dm(owner_id.get()), move |http| async move {
+                owner_id.dm(&http, message).await?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Formats the findings for the owner's DM.
+    fn render(&self) -> String {
+        let mut lines = vec!["## 🔧 Startup Reconciliation".to_string()];
+        if self.projected_events > 0 {
+            lines.push(format!(
+                "- Applied **{}** voice events the previous run had not projected.",
+                self.projected_events
+            ));
+        }
+        if self.closed_sessions > 0 {
+            lines.push(format!(
+                "- Closed **{}** voice sessions left open, ending them at the last heartbeat.",
+                self.closed_sessions
+            ));
+        }
+        if self.redelivered > 0 {
+            lines.push(format!(
+                "- Sent **{}** feed notifications the previous run had not sent.",
+                self.redelivered
+            ));
+        }
+        for (platform_id, count) in &self.unregistered_platforms {
+            lines.push(format!(
+                "- **{count}** feeds belong to `{platform_id}`, which is not a registered platform. They are not checked for updates."
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconciliation_renders_only_discrepancies() {
+        let clean = Reconciliation::default();
+        assert!(!clean.has_discrepancies());
+
+        let reconciliation = Reconciliation {
+            closed_sessions: 3,
+            unregistered_platforms: vec![("Old Platform".to_string(), 2)],
+            redelivered: 4,
+            ..Default::default()
+        };
+        let rendered = reconciliation.render();
+
+        assert!(reconciliation.has_discrepancies());
+        assert!(!rendered.contains("voice events"));
+        assert!(rendered.contains("Sent **4** feed notifications"));
+        assert!(rendered.contains("Closed **3** voice sessions"));
+        assert!(rendered.contains("**2** feeds belong to `Old Platform`"));
+    }
+}
@@ -0,0 +1,146 @@
+//! Keeps background tasks running through panics.
+//!
+//! A task whose loop panics is logged and started again, waiting longer after each
+//! panic in a row, so one malformed API response can't stop feed polling for good.
+//! Panics of tasks and event subscribers are counted in a [`TaskMonitor`] shown by the
+//! web server's `/metrics` endpoint.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use futures::FutureExt;
+use log::error;
+use log::warn;
+use tokio::time::sleep;
+
+/// Delay before the first restart.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest delay between restarts.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// A task running this long is considered stable, so the next panic starts the backoff
+/// over.
+const STABLE_AFTER: Duration = Duration::from_secs(600);
+
+/// Panic counts of background tasks and event subscribers, by name.
+#[derive(Default)]
+pub struct TaskMonitor {
+    panics: Mutex<BTreeMap<String, u64>>,
+}
+
+impl TaskMonitor {
+    /// Creates a monitor with no panics recorded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs a task's loop in the background, starting it again whenever it panics.
+    ///
+    /// `run` builds the loop from the task, once at the start and again after each
+    /// panic. A loop that returns is not restarted.
+    pub fn spawn<T, F, Fut>(self: &Arc<Self>, name: &'static str, task: Arc<T>, run: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(Arc<T>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut backoff = INITIAL_BACKOFF;
+            loop {
+                let started = Instant::now();
+                let Err(panic) = AssertUnwindSafe(run(task.clone())).catch_unwind().await else {
+                    return;
+                };
+                monitor.record_panic(name, &*panic);
+
+                if started.elapsed() >= STABLE_AFTER {
+                    backoff = INITIAL_BACKOFF;
+                }
+                warn!("Restarting task `{name}` in {}s", backoff.as_secs());
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+    }
+
+    /// Logs a panic and counts it for `name`.
+    pub fn record_panic(&self, name: &str, panic: &(dyn Any + Send)) {
+        error!("Task `{name}` panicked: {}", panic_message(panic));
+        if let Ok(mut panics) = self.panics.lock() {
+            *panics.entry(name.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Returns how many times `name` panicked since startup.
+    pub fn panics(&self, name: &str) -> u64 {
+        self.panics
+            .lock()
+            .map(|panics| panics.get(name).copied().unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    /// Renders panic counts in the Prometheus text format.
+    pub fn render_metrics(&self) -> String {
+        let mut out = String::new();
+        out.push_str(
+            "# HELP pwr_bot_task_panics_total Times a background task or subscriber panicked.\n",
+        );
+        out.push_str("# TYPE pwr_bot_task_panics_total counter\n");
+        if let Ok(panics) = self.panics.lock() {
+            for (name, count) in panics.iter() {
+                let _ = writeln!(out, "pwr_bot_task_panics_total{{task=\"{name}\"}} {count}");
+            }
+        }
+        out
+    }
+}
+
+/// Returns the message a panic was raised with.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn spawn_restarts_panicking_tasks() {
+        let monitor = Arc::new(TaskMonitor::new());
+        let runs = Arc::new(AtomicU32::new(0));
+
+        monitor.spawn("flaky", runs.clone(), |runs| async move {
+            if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("malformed response");
+            }
+        });
+        // The restart waits out the initial backoff
+        sleep(INITIAL_BACKOFF + Duration::from_millis(500)).await;
+
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(monitor.panics("flaky"), 1);
+        assert!(
+            monitor
+                .render_metrics()
+                .contains("pwr_bot_task_panics_total{task=\"flaky\"} 1\n")
+        );
+    }
+}
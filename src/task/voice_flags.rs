@@ -0,0 +1,59 @@
+/// Periodic check that flags suspicious voice sessions for admin review.
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between checks
+const CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Sessions that ended this long ago are not checked again. Twice the interval, so a
+/// delayed tick does not skip any.
+const LOOKBACK_SECS: i64 = 2 * CHECK_INTERVAL_SECS as i64;
+
+/// Flags improbable voice sessions, such as day-long sessions or accounts joining and
+/// leaving in lockstep, so admins can review them in `/vc admin review`.
+pub struct VoiceFlagTask {
+    service: Arc<dyn VoiceTracker>,
+}
+
+impl VoiceFlagTask {
+    /// Creates a new flag task with the given service.
+    pub fn new(service: Arc<dyn VoiceTracker>) -> Self {
+        Self { service }
+    }
+
+    /// Starts the flag task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("voice_flags", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                if let Err(e) = task.run(Utc::now()).await {
+                    error!("Failed to check for suspicious voice sessions: {e}");
+                }
+            }
+        });
+
+        info!("Voice flag task started (every {CHECK_INTERVAL_SECS} seconds)");
+    }
+
+    /// Flags sessions active within the lookback window. Returns the number of new flags.
+    pub async fn run(&self, now: DateTime<Utc>) -> Result<u32> {
+        let since = now - chrono::Duration::seconds(LOOKBACK_SECS);
+        let flagged = self.service.flag_suspicious_sessions(&since).await?;
+        if flagged > 0 {
+            info!("Flagged {flagged} suspicious voice sessions for review");
+        }
+        Ok(flagged)
+    }
+}
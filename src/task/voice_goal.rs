@@ -0,0 +1,112 @@
+/// Monthly voice goal task that announces when a server reaches its goal.
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use log::debug;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::entity::BotMetaKey;
+use crate::event::VoiceGoalReachedEvent;
+use crate::event::event_bus::EventBus;
+use crate::service::traits::InternalOps;
+use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between progress checks
+const CHECK_INTERVAL_SECS: u64 = 900;
+
+/// Checks each server's monthly voice goal and publishes a [`VoiceGoalReachedEvent`] the
+/// first time it is reached in a month.
+pub struct VoiceGoalTask {
+    internal: Arc<dyn InternalOps>,
+    service: Arc<dyn VoiceTracker>,
+    event_bus: Arc<EventBus>,
+}
+
+impl VoiceGoalTask {
+    /// Creates a new goal task with the given services.
+    pub fn new(
+        internal: Arc<dyn InternalOps>,
+        service: Arc<dyn VoiceTracker>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
+        Self {
+            internal,
+            service,
+            event_bus,
+        }
+    }
+
+    /// Starts the goal task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("voice_goal", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                for guild_id in task.service.guilds_with_goals().await {
+                    if let Err(e) = task.check_guild(guild_id, now).await {
+                        error!("Failed to check voice goal of guild {guild_id}: {e}");
+                    }
+                }
+            }
+        });
+
+        info!("Voice goal task started (every {CHECK_INTERVAL_SECS} seconds)");
+    }
+
+    /// Announces the guild's goal if it was reached and not yet announced this month.
+    /// Returns whether it was announced.
+    pub async fn check_guild(&self, guild_id: u64, now: DateTime<Utc>) -> Result<bool> {
+        let Some(progress) = self.service.get_goal_progress(guild_id, &now).await? else {
+            return Ok(false);
+        };
+        if !progress.is_reached() {
+            return Ok(false);
+        }
+
+        let announced = self
+            .internal
+            .get_meta(BotMetaKey::VoiceGoalAnnounced(guild_id))
+            .await?;
+        if announced.as_deref() == Some(progress.month.as_str()) {
+            debug!(
+                "Voice goal of guild {guild_id} already announced for {}",
+                progress.month
+            );
+            return Ok(false);
+        }
+        self.internal
+            .set_meta(
+                BotMetaKey::VoiceGoalAnnounced(guild_id),
+                progress.month.clone(),
+            )
+            .await?;
+
+        let settings = self.service.voice_settings(guild_id).await;
+        let Some(channel_id) = settings
+            .goal_channel_id
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            debug!("Voice goal of guild {guild_id} reached, but no channel is set");
+            return Ok(false);
+        };
+
+        info!(
+            "Voice goal of guild {guild_id} reached for {}",
+            progress.month
+        );
+        self.event_bus.publish(VoiceGoalReachedEvent {
+            guild_id,
+            channel_id,
+            progress,
+        });
+        Ok(true)
+    }
+}
@@ -11,22 +11,35 @@ use tokio::time::Duration;
 use tokio::time::interval;
 
 use crate::entity::BotMetaKey;
+use crate::handoff::HandoffSession;
 use crate::service::traits::InternalOps;
 use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
 
-/// Interval between heartbeats
-const HEARTBEAT_INTERVAL_SECS: u64 = 10;
+/// Default interval between heartbeats
+pub const HEARTBEAT_INTERVAL_SECS: u64 = 10;
 
 /// Manages heartbeat for voice tracking to prevent data loss on crashes.
 pub struct VoiceHeartbeatManager {
     internal: Arc<dyn InternalOps>,
     service: Arc<dyn VoiceTracker>,
+    interval: Duration,
 }
 
 impl VoiceHeartbeatManager {
     /// Creates a new heartbeat manager with the given service.
     pub fn new(internal: Arc<dyn InternalOps>, service: Arc<dyn VoiceTracker>) -> Self {
-        Self { internal, service }
+        Self {
+            internal,
+            service,
+            interval: Duration::from_secs(HEARTBEAT_INTERVAL_SECS),
+        }
+    }
+
+    /// Sets how often the heartbeat is written and open sessions are flushed.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
     }
 
     /// Reads the last heartbeat timestamp from database.
@@ -45,21 +58,32 @@ impl VoiceHeartbeatManager {
     }
 
     /// Starts the heartbeat task.
-    pub async fn start(self: Arc<Self>) {
-        tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        let period = self.interval;
+        tasks.spawn("voice_heartbeat", self, |task| async move {
+            let mut interval = interval(period);
 
             loop {
                 interval.tick().await;
-                self.update().await;
+                task.update().await;
             }
         });
 
-        info!("Voice session heartbeat started (interval: {HEARTBEAT_INTERVAL_SECS}s)");
+        info!(
+            "Voice session heartbeat started (interval: {}s)",
+            period.as_secs()
+        );
     }
 
     /// Handles recovery from a crash by closing orphaned sessions.
     pub async fn recover_from_crash(&self) -> Result<u32> {
+        self.recover_keeping(&[]).await
+    }
+
+    /// Closes orphaned sessions like [`Self::recover_from_crash`], except the sessions
+    /// the previous run handed off at shutdown. Those stay open for members who are
+    /// still in their channel to carry on.
+    pub async fn recover_keeping(&self, handed_off: &[HandoffSession]) -> Result<u32> {
         let last_heartbeat = match self.read_last_heartbeat().await? {
             Some(ts) => ts,
             None => {
@@ -75,6 +99,10 @@ impl VoiceHeartbeatManager {
         let mut closed = 0u32;
 
         for session in active_sessions {
+            if handed_off.contains(&HandoffSession::from(&session)) {
+                continue;
+            }
+
             // Use the last known heartbeat as the leave_time
             // This represents the last time the bot was known to be running
             // Also set is_active = 0 to properly close the session
@@ -100,10 +128,16 @@ impl VoiceHeartbeatManager {
         Ok(closed)
     }
 
+    /// Flushes open sessions in one batch, then writes the heartbeat timestamp.
     pub async fn update(&self) {
         let internal = self.internal.clone();
 
         let now = Utc::now();
+        match self.service.flush_open_sessions(&now).await {
+            Ok(flushed) => debug!("Flushed {flushed} open voice sessions"),
+            Err(e) => error!("Failed to flush open voice sessions: {e}"),
+        }
+
         // Write heartbeat timestamp to database
         if let Err(e) = internal
             .set_meta(BotMetaKey::VoiceHeartbeat, now.to_rfc3339())
@@ -114,4 +148,18 @@ impl VoiceHeartbeatManager {
             debug!("Heartbeat written to database: {now}");
         }
     }
+
+    /// Flushes open sessions and writes a final heartbeat at shutdown.
+    ///
+    /// Unlike [`Self::update`], stops at the first error, so the caller can report that
+    /// tracking data may be stale. Postgres has committed both writes once this returns.
+    /// Returns the number of sessions flushed.
+    pub async fn flush(&self) -> Result<u32> {
+        let now = Utc::now();
+        let flushed = self.service.flush_open_sessions(&now).await?;
+        self.internal
+            .set_meta(BotMetaKey::VoiceHeartbeat, now.to_rfc3339())
+            .await?;
+        Ok(flushed)
+    }
 }
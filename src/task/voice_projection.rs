@@ -0,0 +1,62 @@
+/// Periodic projection of recorded voice events into voice sessions.
+use std::sync::Arc;
+
+use anyhow::Result;
+use log::debug;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between projection runs
+const PROJECTION_INTERVAL_SECS: u64 = 5;
+
+/// Events applied per batch
+const BATCH_SIZE: u32 = 500;
+
+/// Turns the voice event log into voice sessions when event sourcing is enabled.
+pub struct VoiceProjectionTask {
+    service: Arc<dyn VoiceTracker>,
+}
+
+impl VoiceProjectionTask {
+    /// Creates a new projection task with the given service.
+    pub fn new(service: Arc<dyn VoiceTracker>) -> Self {
+        Self { service }
+    }
+
+    /// Starts the projection task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("voice_projection", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(PROJECTION_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                if let Err(e) = task.run().await {
+                    error!("Failed to project voice events: {e}");
+                }
+            }
+        });
+
+        info!("Voice projection task started (every {PROJECTION_INTERVAL_SECS} seconds)");
+    }
+
+    /// Applies all pending voice events in batches. Returns the number of events applied.
+    pub async fn run(&self) -> Result<u32> {
+        let mut applied = 0;
+        loop {
+            let batch = self.service.project_voice_events(BATCH_SIZE).await?;
+            applied += batch;
+            if batch < BATCH_SIZE {
+                break;
+            }
+        }
+        if applied > 0 {
+            debug!("Projected {applied} voice events into sessions");
+        }
+        Ok(applied)
+    }
+}
@@ -0,0 +1,89 @@
+/// Nightly reconciliation of the daily voice totals read by long-range leaderboards.
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use log::debug;
+use log::error;
+use log::info;
+use tokio::time::Duration;
+use tokio::time::interval;
+
+use crate::entity::BotMetaKey;
+use crate::service::traits::InternalOps;
+use crate::service::traits::VoiceTracker;
+use crate::task::supervisor::TaskMonitor;
+
+/// Interval between checks for a due reconciliation
+const CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Minimum time between reconciliations
+const RECONCILE_INTERVAL_HOURS: i64 = 24;
+
+/// Recomputes the daily voice totals from the sessions once a day. The totals are kept
+/// up to date as sessions end, so this only corrects drift.
+pub struct VoiceTotalsTask {
+    internal: Arc<dyn InternalOps>,
+    service: Arc<dyn VoiceTracker>,
+}
+
+impl VoiceTotalsTask {
+    /// Creates a new reconciliation task with the given services.
+    pub fn new(internal: Arc<dyn InternalOps>, service: Arc<dyn VoiceTracker>) -> Self {
+        Self { internal, service }
+    }
+
+    /// Reads the last reconciliation timestamp from database.
+    pub async fn read_last_reconciled(&self) -> Result<Option<DateTime<Utc>>> {
+        let value = self
+            .internal
+            .get_meta(BotMetaKey::VoiceTotalsReconciled)
+            .await?;
+
+        match value {
+            Some(ts_str) => {
+                let timestamp = DateTime::parse_from_rfc3339(&ts_str)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|e| anyhow::anyhow!("Invalid reconciliation timestamp: {e}"))?;
+                Ok(Some(timestamp))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Starts the reconciliation task.
+    pub async fn start(self: Arc<Self>, tasks: &Arc<TaskMonitor>) {
+        tasks.spawn("voice_totals", self, |task| async move {
+            let mut interval = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                if let Err(e) = task.run_if_due(Utc::now()).await {
+                    error!("Failed to reconcile daily voice totals: {e}");
+                }
+            }
+        });
+
+        info!("Voice totals task started (every {RECONCILE_INTERVAL_HOURS} hours)");
+    }
+
+    /// Reconciles the totals if they were not reconciled in the last day. Returns whether
+    /// they were.
+    pub async fn run_if_due(&self, now: DateTime<Utc>) -> Result<bool> {
+        if let Some(last) = self.read_last_reconciled().await?
+            && now - last < chrono::Duration::hours(RECONCILE_INTERVAL_HOURS)
+        {
+            debug!("Voice totals reconciliation not due (last run at {last})");
+            return Ok(false);
+        }
+
+        let rows = self.service.reconcile_daily_totals().await?;
+        self.internal
+            .set_meta(BotMetaKey::VoiceTotalsReconciled, now.to_rfc3339())
+            .await?;
+
+        info!("Daily voice totals reconciled: {rows} totals written");
+        Ok(true)
+    }
+}
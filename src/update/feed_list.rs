@@ -1,6 +1,6 @@
-//! Pure update logic for the feed subscription list.
+//! Pure update logic for the feed subscription manager.
 //!
-//! Manages view/edit state, mark-for-unsubscribe bookkeeping, and pagination.
+//! Manages view/edit state, row selection, bulk actions, and pagination.
 
 use std::collections::HashSet;
 
@@ -21,10 +21,23 @@ pub enum FeedListMsg {
     Edit,
     /// Switch to view mode.
     View,
-    /// Toggle a subscription's mark-for-removal status.
-    ToggleUnsub { source_url: String },
-    /// Save removals and return to view mode.
-    Save,
+    /// Toggle a subscription's selection.
+    ToggleSelect { source_url: String },
+    /// Unsubscribe from the selected subscriptions.
+    Unsubscribe,
+    /// Pause notifications for the selected subscriptions.
+    Pause,
+    /// Resume notifications for the selected subscriptions.
+    Resume,
+    /// Notify the selected subscriptions when announced items are edited.
+    NotifyEdits,
+    /// Stop notifying the selected subscriptions of edited items.
+    MuteEdits,
+    /// Move the selected subscriptions between DM and server.
+    Move,
+    /// Put the selected server subscriptions in a collection, or back in the server's
+    /// feed channel with `None`.
+    SetCollection(Option<i32>),
     /// Navigate pagination.
     Pagination(PaginationAction),
 }
@@ -35,7 +48,24 @@ pub enum FeedListCmd {
     /// Nothing else to do.
     None,
     /// Perform actual unsubscriptions and refetch the list.
-    SaveUnsubscribes(HashSet<String>),
+    Unsubscribe(HashSet<String>),
+    /// Set the paused state of subscriptions and refetch the list.
+    SetPaused {
+        source_urls: HashSet<String>,
+        paused: bool,
+    },
+    /// Set whether subscriptions are notified of edits and refetch the list.
+    SetNotifyEdits {
+        source_urls: HashSet<String>,
+        notify_edits: bool,
+    },
+    /// Move subscriptions to the other send target and refetch the list.
+    Move(HashSet<String>),
+    /// Set the collection of subscriptions and refetch the list.
+    SetCollection {
+        source_urls: HashSet<String>,
+        collection_id: Option<i32>,
+    },
     /// Refetch subscriptions for the current page.
     RefetchSubscriptions,
 }
@@ -44,7 +74,7 @@ pub enum FeedListCmd {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FeedListModel {
     pub state: FeedListViewState,
-    pub marked_unsub: HashSet<String>,
+    pub selected: HashSet<String>,
     pub current_page: u32,
     pub per_page: u32,
     pub pagination_disabled: bool,
@@ -54,12 +84,21 @@ impl FeedListModel {
     pub fn new(per_page: u32) -> Self {
         Self {
             state: FeedListViewState::View,
-            marked_unsub: HashSet::new(),
+            selected: HashSet::new(),
             current_page: 1,
             per_page: per_page.max(1),
             pagination_disabled: false,
         }
     }
+
+    /// Takes the current selection, returning `None` if nothing is selected.
+    fn take_selection(&mut self) -> Option<HashSet<String>> {
+        if self.selected.is_empty() {
+            None
+        } else {
+            Some(self.selected.drain().collect())
+        }
+    }
 }
 
 /// The update implementation for feed list.
@@ -87,24 +126,60 @@ impl Update for FeedListUpdate {
             }
             View => {
                 model.state = FeedListViewState::View;
+                model.selected.clear();
                 FeedListCmd::None
             }
-            ToggleUnsub { source_url } => {
-                if model.marked_unsub.contains(&source_url) {
-                    model.marked_unsub.remove(&source_url);
+            ToggleSelect { source_url } => {
+                if model.selected.contains(&source_url) {
+                    model.selected.remove(&source_url);
                 } else {
-                    model.marked_unsub.insert(source_url);
+                    model.selected.insert(source_url);
                 }
                 FeedListCmd::None
             }
-            Save => {
-                let to_remove: HashSet<String> = model.marked_unsub.drain().collect();
-                model.state = FeedListViewState::View;
-                if to_remove.is_empty() {
-                    FeedListCmd::RefetchSubscriptions
-                } else {
-                    FeedListCmd::SaveUnsubscribes(to_remove)
-                }
+            Unsubscribe => model
+                .take_selection()
+                .map_or(FeedListCmd::None, FeedListCmd::Unsubscribe),
+            Pause => model
+                .take_selection()
+                .map_or(FeedListCmd::None, |source_urls| FeedListCmd::SetPaused {
+                    source_urls,
+                    paused: true,
+                }),
+            Resume => model
+                .take_selection()
+                .map_or(FeedListCmd::None, |source_urls| FeedListCmd::SetPaused {
+                    source_urls,
+                    paused: false,
+                }),
+            NotifyEdits => model
+                .take_selection()
+                .map_or(FeedListCmd::None, |source_urls| {
+                    FeedListCmd::SetNotifyEdits {
+                        source_urls,
+                        notify_edits: true,
+                    }
+                }),
+            MuteEdits => model
+                .take_selection()
+                .map_or(FeedListCmd::None, |source_urls| {
+                    FeedListCmd::SetNotifyEdits {
+                        source_urls,
+                        notify_edits: false,
+                    }
+                }),
+            Move => model
+                .take_selection()
+                .map_or(FeedListCmd::None, FeedListCmd::Move),
+            SetCollection(collection_id) => {
+                model
+                    .take_selection()
+                    .map_or(FeedListCmd::None, |source_urls| {
+                        FeedListCmd::SetCollection {
+                            source_urls,
+                            collection_id,
+                        }
+                    })
             }
             Pagination(action) => {
                 match action {
@@ -126,6 +201,13 @@ impl Update for FeedListUpdate {
 mod tests {
     use super::*;
 
+    fn model_with_selection(urls: &[&str]) -> FeedListModel {
+        let mut model = FeedListModel::new(10);
+        model.state = FeedListViewState::Edit;
+        model.selected = urls.iter().map(|u| u.to_string()).collect();
+        model
+    }
+
     #[test]
     fn edit_sets_state() {
         let mut model = FeedListModel::new(10);
@@ -149,66 +231,156 @@ mod tests {
     }
 
     #[test]
-    fn toggle_unsub_adds() {
+    fn view_clears_selection() {
+        let mut model = model_with_selection(&["https://a.com"]);
+
+        FeedListUpdate::update(FeedListMsg::View, &mut model);
+
+        assert!(model.selected.is_empty());
+    }
+
+    #[test]
+    fn toggle_select_adds() {
         let mut model = FeedListModel::new(10);
 
         let cmd = FeedListUpdate::update(
-            FeedListMsg::ToggleUnsub {
+            FeedListMsg::ToggleSelect {
                 source_url: "https://example.com".to_string(),
             },
             &mut model,
         );
 
         assert_eq!(cmd, FeedListCmd::None);
-        assert!(model.marked_unsub.contains("https://example.com"));
+        assert!(model.selected.contains("https://example.com"));
     }
 
     #[test]
-    fn toggle_unsub_removes() {
-        let mut model = FeedListModel::new(10);
-        model.marked_unsub.insert("https://example.com".to_string());
+    fn toggle_select_removes() {
+        let mut model = model_with_selection(&["https://example.com"]);
 
         let cmd = FeedListUpdate::update(
-            FeedListMsg::ToggleUnsub {
+            FeedListMsg::ToggleSelect {
                 source_url: "https://example.com".to_string(),
             },
             &mut model,
         );
 
         assert_eq!(cmd, FeedListCmd::None);
-        assert!(!model.marked_unsub.contains("https://example.com"));
+        assert!(!model.selected.contains("https://example.com"));
     }
 
     #[test]
-    fn save_with_marked_returns_save_cmd() {
-        let mut model = FeedListModel::new(10);
-        model.state = FeedListViewState::Edit;
-        model.marked_unsub.insert("https://a.com".to_string());
-        model.marked_unsub.insert("https://b.com".to_string());
+    fn unsubscribe_with_selected_returns_unsubscribe_cmd() {
+        let mut model = model_with_selection(&["https://a.com", "https://b.com"]);
 
-        let cmd = FeedListUpdate::update(FeedListMsg::Save, &mut model);
+        let cmd = FeedListUpdate::update(FeedListMsg::Unsubscribe, &mut model);
 
-        assert_eq!(model.state, FeedListViewState::View);
-        assert!(model.marked_unsub.is_empty());
+        assert!(model.selected.is_empty());
+        assert_eq!(model.state, FeedListViewState::Edit);
         match cmd {
-            FeedListCmd::SaveUnsubscribes(urls) => {
+            FeedListCmd::Unsubscribe(urls) => {
                 assert_eq!(urls.len(), 2);
                 assert!(urls.contains("https://a.com"));
                 assert!(urls.contains("https://b.com"));
             }
-            other => panic!("expected SaveUnsubscribes, got {other:?}"),
+            other => panic!("expected Unsubscribe, got {other:?}"),
         }
     }
 
     #[test]
-    fn save_empty_returns_refetch() {
-        let mut model = FeedListModel::new(10);
-        model.state = FeedListViewState::Edit;
+    fn bulk_action_without_selection_is_noop() {
+        for msg in [
+            FeedListMsg::Unsubscribe,
+            FeedListMsg::Pause,
+            FeedListMsg::Resume,
+            FeedListMsg::NotifyEdits,
+            FeedListMsg::MuteEdits,
+            FeedListMsg::Move,
+        ] {
+            let mut model = FeedListModel::new(10);
+            model.state = FeedListViewState::Edit;
+
+            let cmd = FeedListUpdate::update(msg, &mut model);
+
+            assert_eq!(cmd, FeedListCmd::None);
+        }
+    }
 
-        let cmd = FeedListUpdate::update(FeedListMsg::Save, &mut model);
+    #[test]
+    fn pause_and_resume_return_set_paused() {
+        let mut model = model_with_selection(&["https://a.com"]);
+        let cmd = FeedListUpdate::update(FeedListMsg::Pause, &mut model);
+        assert_eq!(
+            cmd,
+            FeedListCmd::SetPaused {
+                source_urls: HashSet::from(["https://a.com".to_string()]),
+                paused: true,
+            }
+        );
+        assert!(model.selected.is_empty());
+
+        let mut model = model_with_selection(&["https://a.com"]);
+        let cmd = FeedListUpdate::update(FeedListMsg::Resume, &mut model);
+        assert_eq!(
+            cmd,
+            FeedListCmd::SetPaused {
+                source_urls: HashSet::from(["https://a.com".to_string()]),
+                paused: false,
+            }
+        );
+    }
 
-        assert_eq!(model.state, FeedListViewState::View);
-        assert_eq!(cmd, FeedListCmd::RefetchSubscriptions);
+    #[test]
+    fn notify_and_mute_edits_return_set_notify_edits() {
+        let mut model = model_with_selection(&["https://a.com"]);
+        let cmd = FeedListUpdate::update(FeedListMsg::NotifyEdits, &mut model);
+        assert_eq!(
+            cmd,
+            FeedListCmd::SetNotifyEdits {
+                source_urls: HashSet::from(["https://a.com".to_string()]),
+                notify_edits: true,
+            }
+        );
+        assert!(model.selected.is_empty());
+
+        let mut model = model_with_selection(&["https://a.com"]);
+        let cmd = FeedListUpdate::update(FeedListMsg::MuteEdits, &mut model);
+        assert_eq!(
+            cmd,
+            FeedListCmd::SetNotifyEdits {
+                source_urls: HashSet::from(["https://a.com".to_string()]),
+                notify_edits: false,
+            }
+        );
+    }
+
+    #[test]
+    fn move_returns_move_cmd() {
+        let mut model = model_with_selection(&["https://a.com"]);
+
+        let cmd = FeedListUpdate::update(FeedListMsg::Move, &mut model);
+
+        assert_eq!(
+            cmd,
+            FeedListCmd::Move(HashSet::from(["https://a.com".to_string()]))
+        );
+        assert!(model.selected.is_empty());
+    }
+
+    #[test]
+    fn set_collection_returns_set_collection_cmd() {
+        let mut model = model_with_selection(&["https://a.com"]);
+
+        let cmd = FeedListUpdate::update(FeedListMsg::SetCollection(Some(3)), &mut model);
+
+        assert_eq!(
+            cmd,
+            FeedListCmd::SetCollection {
+                source_urls: HashSet::from(["https://a.com".to_string()]),
+                collection_id: Some(3),
+            }
+        );
+        assert!(model.selected.is_empty());
     }
 
     #[test]
@@ -251,7 +423,7 @@ mod tests {
     fn model_new_defaults() {
         let model = FeedListModel::new(10);
         assert_eq!(model.state, FeedListViewState::View);
-        assert!(model.marked_unsub.is_empty());
+        assert!(model.selected.is_empty());
         assert_eq!(model.current_page, 1);
         assert_eq!(model.per_page, 10);
         assert!(!model.pagination_disabled);
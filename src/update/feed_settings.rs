@@ -1,7 +1,12 @@
 //! Pure update logic for feed settings.
 //!
-//! Manages notification channel and role-permission toggles.
+//! Manages notification channel, role-permission, unsubscribe ownership, subscription
+//! approval, notification style toggles, item retention, the release calendar, watch party scheduling, and
+//! airing events.
 
+use crate::entity::CalendarMode;
+use crate::entity::FeedsSettings;
+use crate::entity::NotificationStyle;
 use crate::update::Update;
 
 /// Messages that can mutate the feed-settings model.
@@ -11,6 +16,15 @@ pub enum FeedSettingsMsg {
     SetChannel(Option<String>),
     SetSubRole(Option<String>),
     SetUnsubRole(Option<String>),
+    ToggleUnsubscribeOwnOnly,
+    ToggleRequireApproval,
+    SetStyle(NotificationStyle),
+    ToggleHideCover,
+    ToggleSuppressEmbeds,
+    SetItemRetention(Option<u32>),
+    SetCalendarMode(CalendarMode),
+    SetWatchPartyDelay(Option<u32>),
+    ToggleAiringEvents,
 }
 
 /// Commands returned by the update.
@@ -26,12 +40,69 @@ pub struct FeedSettingsModel {
     pub channel_id: Option<String>,
     pub subscribe_role_id: Option<String>,
     pub unsubscribe_role_id: Option<String>,
+    pub unsubscribe_own_only: Option<bool>,
+    pub require_approval: Option<bool>,
+    pub notification_style: Option<NotificationStyle>,
+    pub hide_cover: Option<bool>,
+    pub suppress_embeds: Option<bool>,
+    pub item_retention_days: Option<u32>,
+    pub release_calendar: Option<CalendarMode>,
+    pub watch_party_delay_mins: Option<u32>,
+    pub airing_events: Option<bool>,
 }
 
 impl FeedSettingsModel {
     pub fn is_enabled(&self) -> bool {
         self.enabled.unwrap_or(true)
     }
+
+    pub fn is_unsubscribe_own_only(&self) -> bool {
+        self.unsubscribe_own_only.unwrap_or(false)
+    }
+
+    pub fn is_approval_required(&self) -> bool {
+        self.require_approval.unwrap_or(false)
+    }
+
+    pub fn style(&self) -> NotificationStyle {
+        self.notification_style.unwrap_or_default()
+    }
+
+    pub fn is_cover_hidden(&self) -> bool {
+        self.hide_cover.unwrap_or(false)
+    }
+
+    pub fn is_embeds_suppressed(&self) -> bool {
+        self.suppress_embeds.unwrap_or(false)
+    }
+
+    pub fn calendar_mode(&self) -> CalendarMode {
+        self.release_calendar.unwrap_or_default()
+    }
+
+    pub fn has_airing_events(&self) -> bool {
+        self.airing_events.unwrap_or(false)
+    }
+}
+
+impl From<&FeedsSettings> for FeedSettingsModel {
+    fn from(settings: &FeedsSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            channel_id: settings.channel_id.clone(),
+            subscribe_role_id: settings.subscribe_role_id.clone(),
+            unsubscribe_role_id: settings.unsubscribe_role_id.clone(),
+            unsubscribe_own_only: settings.unsubscribe_own_only,
+            require_approval: settings.require_approval,
+            notification_style: settings.notification_style,
+            hide_cover: settings.hide_cover,
+            suppress_embeds: settings.suppress_embeds,
+            item_retention_days: settings.item_retention_days,
+            release_calendar: settings.release_calendar,
+            watch_party_delay_mins: settings.watch_party_delay_mins,
+            airing_events: settings.airing_events,
+        }
+    }
 }
 
 /// The update implementation for feed settings.
@@ -66,6 +137,33 @@ impl Update for FeedSettingsUpdate {
             SetUnsubRole(id) => {
                 model.unsubscribe_role_id = id;
             }
+            ToggleUnsubscribeOwnOnly => {
+                model.unsubscribe_own_only = Some(!model.is_unsubscribe_own_only());
+            }
+            ToggleRequireApproval => {
+                model.require_approval = Some(!model.is_approval_required());
+            }
+            SetStyle(style) => {
+                model.notification_style = Some(style);
+            }
+            ToggleHideCover => {
+                model.hide_cover = Some(!model.is_cover_hidden());
+            }
+            ToggleSuppressEmbeds => {
+                model.suppress_embeds = Some(!model.is_embeds_suppressed());
+            }
+            SetItemRetention(days) => {
+                model.item_retention_days = days.filter(|days| *days > 0);
+            }
+            SetCalendarMode(mode) => {
+                model.release_calendar = Some(mode);
+            }
+            SetWatchPartyDelay(mins) => {
+                model.watch_party_delay_mins = mins;
+            }
+            ToggleAiringEvents => {
+                model.airing_events = Some(!model.has_airing_events());
+            }
         }
         FeedSettingsCmd::None
     }
@@ -159,6 +257,143 @@ mod tests {
         assert_eq!(model.unsubscribe_role_id, Some("role2".to_string()));
     }
 
+    // ── Notification style ──────────────────────────────────────────────────
+
+    #[test]
+    fn set_style() {
+        let mut model = FeedSettingsModel::default();
+        assert_eq!(model.style(), NotificationStyle::Rich);
+
+        let cmd = FeedSettingsUpdate::update(
+            FeedSettingsMsg::SetStyle(NotificationStyle::Compact),
+            &mut model,
+        );
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.style(), NotificationStyle::Compact);
+    }
+
+    #[test]
+    fn toggle_hide_cover() {
+        let mut model = FeedSettingsModel::default();
+        assert!(!model.is_cover_hidden());
+
+        FeedSettingsUpdate::update(FeedSettingsMsg::ToggleHideCover, &mut model);
+        assert!(model.is_cover_hidden());
+
+        FeedSettingsUpdate::update(FeedSettingsMsg::ToggleHideCover, &mut model);
+        assert!(!model.is_cover_hidden());
+    }
+
+    #[test]
+    fn toggle_suppress_embeds() {
+        let mut model = FeedSettingsModel::default();
+        assert!(!model.is_embeds_suppressed());
+
+        let cmd = FeedSettingsUpdate::update(FeedSettingsMsg::ToggleSuppressEmbeds, &mut model);
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.suppress_embeds, Some(true));
+    }
+
+    // ── Item retention ──────────────────────────────────────────────────────
+
+    #[test]
+    fn set_item_retention_clears_zero() {
+        let mut model = FeedSettingsModel::default();
+
+        let cmd =
+            FeedSettingsUpdate::update(FeedSettingsMsg::SetItemRetention(Some(30)), &mut model);
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.item_retention_days, Some(30));
+
+        FeedSettingsUpdate::update(FeedSettingsMsg::SetItemRetention(Some(0)), &mut model);
+        assert_eq!(model.item_retention_days, None);
+    }
+
+    // ── Release calendar ────────────────────────────────────────────────────
+
+    #[test]
+    fn set_calendar_mode() {
+        let mut model = FeedSettingsModel::default();
+        assert_eq!(model.calendar_mode(), CalendarMode::Off);
+
+        let cmd = FeedSettingsUpdate::update(
+            FeedSettingsMsg::SetCalendarMode(CalendarMode::Instead),
+            &mut model,
+        );
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.calendar_mode(), CalendarMode::Instead);
+        assert!(!model.calendar_mode().notifies_items());
+    }
+
+    // ── Watch party ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn set_watch_party_delay() {
+        let mut model = FeedSettingsModel::default();
+
+        let cmd =
+            FeedSettingsUpdate::update(FeedSettingsMsg::SetWatchPartyDelay(Some(30)), &mut model);
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.watch_party_delay_mins, Some(30));
+
+        FeedSettingsUpdate::update(FeedSettingsMsg::SetWatchPartyDelay(None), &mut model);
+        assert_eq!(model.watch_party_delay_mins, None);
+        assert_eq!(model.airing_events, None);
+    }
+
+    // ── Airing events ───────────────────────────────────────────────────────
+
+    #[test]
+    fn toggle_airing_events() {
+        let mut model = FeedSettingsModel::default();
+        assert!(!model.has_airing_events());
+
+        let cmd = FeedSettingsUpdate::update(FeedSettingsMsg::ToggleAiringEvents, &mut model);
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.airing_events, Some(true));
+
+        FeedSettingsUpdate::update(FeedSettingsMsg::ToggleAiringEvents, &mut model);
+        assert_eq!(model.airing_events, Some(false));
+    }
+
+    // ── Unsubscribe ownership ───────────────────────────────────────────────
+
+    #[test]
+    fn toggle_unsubscribe_own_only() {
+        let mut model = FeedSettingsModel::default();
+        assert!(!model.is_unsubscribe_own_only());
+
+        let cmd = FeedSettingsUpdate::update(FeedSettingsMsg::ToggleUnsubscribeOwnOnly, &mut model);
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.unsubscribe_own_only, Some(true));
+
+        FeedSettingsUpdate::update(FeedSettingsMsg::ToggleUnsubscribeOwnOnly, &mut model);
+        assert_eq!(model.unsubscribe_own_only, Some(false));
+    }
+
+    // ── Subscription approval ───────────────────────────────────────────────
+
+    #[test]
+    fn toggle_require_approval() {
+        let mut model = FeedSettingsModel::default();
+        assert!(!model.is_approval_required());
+
+        let cmd = FeedSettingsUpdate::update(FeedSettingsMsg::ToggleRequireApproval, &mut model);
+
+        assert_eq!(cmd, FeedSettingsCmd::None);
+        assert_eq!(model.require_approval, Some(true));
+
+        FeedSettingsUpdate::update(FeedSettingsMsg::ToggleRequireApproval, &mut model);
+        assert_eq!(model.require_approval, Some(false));
+    }
+
     // ── Model helpers ───────────────────────────────────────────────────────
 
     #[test]
@@ -174,5 +409,11 @@ mod tests {
         assert_eq!(model.channel_id, None);
         assert_eq!(model.subscribe_role_id, None);
         assert_eq!(model.unsubscribe_role_id, None);
+        assert_eq!(model.notification_style, None);
+        assert_eq!(model.hide_cover, None);
+        assert_eq!(model.suppress_embeds, None);
+        assert_eq!(model.item_retention_days, None);
+        assert_eq!(model.release_calendar, None);
+        assert_eq!(model.watch_party_delay_mins, None);
     }
 }
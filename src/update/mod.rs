@@ -18,6 +18,7 @@ pub trait Update {
 pub mod feed_list;
 pub mod feed_settings;
 pub mod settings_main;
+pub mod staged;
 pub mod voice_leaderboard;
 pub mod voice_stats;
 pub mod welcome_settings;
@@ -35,6 +36,7 @@ pub use settings_main::SettingsMainCmd;
 pub use settings_main::SettingsMainModel;
 pub use settings_main::SettingsMainMsg;
 pub use settings_main::SettingsMainUpdate;
+pub use staged::Staged;
 pub use voice_stats::VoiceStatsCmd;
 pub use voice_stats::VoiceStatsModel;
 pub use voice_stats::VoiceStatsMsg;
@@ -2,6 +2,7 @@
 //!
 //! Manages feature-enablement toggles.
 
+use crate::entity::ServerSettings;
 use crate::update::Update;
 
 /// Messages that can mutate the settings-main model.
@@ -36,6 +37,15 @@ impl SettingsMainModel {
             is_modified: false,
         }
     }
+
+    /// Builds an unmodified model from the persisted server settings.
+    pub fn from_settings(settings: &ServerSettings) -> Self {
+        Self::new(
+            settings.feeds.enabled.unwrap_or(false),
+            settings.voice.enabled.unwrap_or(false),
+            settings.welcome.enabled.unwrap_or(false),
+        )
+    }
 }
 
 /// The update implementation for the main settings page.
@@ -138,6 +148,20 @@ mod tests {
         assert!(model.is_modified);
     }
 
+    #[test]
+    fn from_settings_reads_enabled_flags() {
+        let mut settings = ServerSettings::default();
+        settings.feeds.enabled = Some(true);
+        settings.welcome.enabled = Some(true);
+
+        let model = SettingsMainModel::from_settings(&settings);
+
+        assert!(model.feeds_enabled);
+        assert!(!model.voice_enabled);
+        assert!(model.welcome_enabled);
+        assert!(!model.is_modified);
+    }
+
     #[test]
     fn new_preserves_initial_state() {
         let model = SettingsMainModel::new(true, false, true);
@@ -0,0 +1,235 @@
+//! Staged-changes model shared by the settings pages.
+//!
+//! Keeps the last saved value next to the working copy so views can show a
+//! dirty indicator and offer explicit Save/Revert instead of persisting on
+//! every interaction.
+
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// A value with uncommitted edits.
+///
+/// Derefs to the working copy. The saved copy only changes on [`Staged::save`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Staged<T> {
+    saved: T,
+    current: T,
+}
+
+impl<T: Clone + PartialEq> Staged<T> {
+    /// Starts staging from a value that is already persisted.
+    pub fn new(value: T) -> Self {
+        Self {
+            saved: value.clone(),
+            current: value,
+        }
+    }
+
+    /// The last saved value.
+    pub fn saved(&self) -> &T {
+        &self.saved
+    }
+
+    /// The working copy, including unsaved edits.
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// Whether the working copy differs from the saved value.
+    pub fn is_dirty(&self) -> bool {
+        self.current != self.saved
+    }
+
+    /// Marks the working copy as saved.
+    ///
+    /// Call after the value has been persisted.
+    pub fn save(&mut self) {
+        self.saved = self.current.clone();
+    }
+
+    /// Discards unsaved edits.
+    pub fn revert(&mut self) {
+        self.current = self.saved.clone();
+    }
+}
+
+impl<T: Clone + PartialEq + Serialize + DeserializeOwned> Staged<T> {
+    /// Moves the unsaved edits on top of `latest`, the value as persisted now.
+    ///
+    /// Fields edited since the last save keep the edited value, every other
+    /// field takes the one from `latest`, so changes saved elsewhere in the
+    /// meantime survive the next save. `latest` becomes the saved value.
+    pub fn rebase(&mut self, latest: T) -> serde_json::Result<()> {
+        let saved = serde_json::to_value(&self.saved)?;
+        let current = serde_json::to_value(&self.current)?;
+        let mut merged = serde_json::to_value(&latest)?;
+        apply_edits(&saved, &current, &mut merged);
+
+        self.current = serde_json::from_value(merged)?;
+        self.saved = latest;
+        Ok(())
+    }
+}
+
+/// Applies the difference between `base` and `edited` to `target`, object key by object key.
+fn apply_edits(base: &Value, edited: &Value, target: &mut Value) {
+    if base == edited {
+        return;
+    }
+    match (base, edited, target) {
+        (Value::Object(base), Value::Object(edited), Value::Object(target)) => {
+            for (key, value) in edited {
+                match (base.get(key), target.get_mut(key)) {
+                    (Some(old), _) if old == value => {}
+                    (Some(old), Some(target)) => apply_edits(old, value, target),
+                    _ => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            for key in base.keys().filter(|key| !edited.contains_key(*key)) {
+                target.remove(key);
+            }
+        }
+        (_, edited, target) => *target = edited.clone(),
+    }
+}
+
+impl<T> Deref for Staged<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.current
+    }
+}
+
+impl<T> DerefMut for Staged<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_is_clean() {
+        let staged = Staged::new(1);
+
+        assert!(!staged.is_dirty());
+        assert_eq!(*staged.saved(), 1);
+        assert_eq!(*staged.current(), 1);
+    }
+
+    #[test]
+    fn edit_marks_dirty() {
+        let mut staged = Staged::new(1);
+
+        *staged = 2;
+
+        assert!(staged.is_dirty());
+        assert_eq!(*staged.saved(), 1);
+        assert_eq!(*staged, 2);
+    }
+
+    #[test]
+    fn editing_back_to_saved_is_clean() {
+        let mut staged = Staged::new(1);
+
+        *staged = 2;
+        *staged = 1;
+
+        assert!(!staged.is_dirty());
+    }
+
+    #[test]
+    fn save_commits_working_copy() {
+        let mut staged = Staged::new(1);
+
+        *staged = 2;
+        staged.save();
+
+        assert!(!staged.is_dirty());
+        assert_eq!(*staged.saved(), 2);
+    }
+
+    #[test]
+    fn revert_discards_edits() {
+        let mut staged = Staged::new(vec![1]);
+
+        staged.push(2);
+        staged.revert();
+
+        assert!(!staged.is_dirty());
+        assert_eq!(*staged, vec![1]);
+    }
+
+    #[derive(Serialize, serde::Deserialize, Clone, Debug, PartialEq, Default)]
+    struct Page {
+        name: Option<String>,
+        limits: Limits,
+    }
+
+    #[derive(Serialize, serde::Deserialize, Clone, Debug, PartialEq, Default)]
+    struct Limits {
+        low: u32,
+        high: u32,
+    }
+
+    #[test]
+    fn rebase_keeps_changes_saved_elsewhere() {
+        let mut staged = Staged::new(Page::default());
+        staged.limits.low = 1;
+
+        let latest = Page {
+            name: Some("other admin".to_string()),
+            limits: Limits { low: 0, high: 9 },
+        };
+        staged.rebase(latest.clone()).unwrap();
+
+        assert_eq!(*staged.saved(), latest);
+        assert_eq!(
+            *staged,
+            Page {
+                name: Some("other admin".to_string()),
+                limits: Limits { low: 1, high: 9 },
+            }
+        );
+    }
+
+    #[test]
+    fn rebase_prefers_edits_over_latest() {
+        let mut staged = Staged::new(Page::default());
+        staged.name = Some("mine".to_string());
+
+        staged
+            .rebase(Page {
+                name: Some("theirs".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(staged.name.as_deref(), Some("mine"));
+        assert!(staged.is_dirty());
+    }
+
+    #[test]
+    fn rebase_without_edits_is_clean() {
+        let mut staged = Staged::new(Page::default());
+
+        staged
+            .rebase(Page {
+                name: Some("theirs".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(!staged.is_dirty());
+        assert_eq!(staged.name.as_deref(), Some("theirs"));
+    }
+}
@@ -1,11 +1,17 @@
 //! Pure update logic for the voice leaderboard.
 //!
 //! All business logic — pagination, mode toggling, time-range changes,
-//! and entry bookkeeping — lives here so it can be unit-tested without
-//! touching Discord or the database.
+//! week-over-week movement, and entry bookkeeping — lives here so it can be
+//! unit-tested without touching Discord or the database.
+
+use std::collections::HashMap;
+
+use chrono::DateTime;
+use chrono::Utc;
 
 use crate::bot::command::voice::VoiceLeaderboardTimeRange;
 use crate::bot::view::pagination::PaginationAction;
+use crate::entity::LeaderboardSnapshotEntity;
 use crate::entity::VoiceLeaderboardEntry;
 use crate::update::Update;
 
@@ -14,6 +20,8 @@ use crate::update::Update;
 pub enum VoiceLeaderboardMsg {
     /// Replace the full entry set (e.g. after a database fetch).
     SetEntries(Vec<VoiceLeaderboardEntry>),
+    /// Replace last week's standings used for movement stats.
+    SetSnapshot(Vec<LeaderboardSnapshotEntity>),
     /// Change the active time range.
     ChangeTimeRange(VoiceLeaderboardTimeRange),
     /// Toggle between server-wide and partner mode.
@@ -33,6 +41,25 @@ pub enum VoiceLeaderboardCmd {
     RefetchData,
 }
 
+/// A user's rank movement since the last snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankChange {
+    Up(u32),
+    Down(u32),
+    Same,
+    /// Not ranked in the last snapshot.
+    New,
+}
+
+/// The user who gained the most voice time since the last snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BiggestGainer {
+    pub user_id: u64,
+    /// Seconds gained since the snapshot.
+    pub gained: i64,
+    pub change: RankChange,
+}
+
 /// The leaderboard model — everything needed to render a page.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct VoiceLeaderboardModel {
@@ -45,6 +72,9 @@ pub struct VoiceLeaderboardModel {
     pub author_id: u64,
     pub current_page: u32,
     pub per_page: u32,
+    /// Last week's standings, keyed by user ID.
+    pub snapshot: HashMap<u64, LeaderboardSnapshotEntity>,
+    pub snapshot_taken_at: Option<DateTime<Utc>>,
 }
 
 impl VoiceLeaderboardModel {
@@ -112,6 +142,72 @@ impl VoiceLeaderboardModel {
         &self.entries[start..end]
     }
 
+    /// Rank movement for each entry on the current page, aligned with
+    /// [`Self::current_page_entries`].
+    pub fn current_page_rank_changes(&self) -> Vec<Option<RankChange>> {
+        let offset = self.current_page_rank_offset();
+        self.current_page_entries()
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| self.rank_change(entry.user_id, offset + idx as u32 + 1))
+            .collect()
+    }
+
+    /// Whether movement since the last snapshot applies to the current view.
+    ///
+    /// Snapshots hold all-time standings, so they are only comparable with the
+    /// all-time server leaderboard.
+    pub fn shows_movement(&self) -> bool {
+        !self.is_partner_mode
+            && self.time_range == VoiceLeaderboardTimeRange::AllTime
+            && self.snapshot_taken_at.is_some()
+    }
+
+    /// Rank movement of a user now at `rank`, if movement applies.
+    pub fn rank_change(&self, user_id: u64, rank: u32) -> Option<RankChange> {
+        if !self.shows_movement() {
+            return None;
+        }
+        let change = match self.snapshot.get(&user_id) {
+            None => RankChange::New,
+            Some(previous) => {
+                let previous = previous.rank.max(0) as u32;
+                match previous.cmp(&rank) {
+                    std::cmp::Ordering::Greater => RankChange::Up(previous - rank),
+                    std::cmp::Ordering::Less => RankChange::Down(rank - previous),
+                    std::cmp::Ordering::Equal => RankChange::Same,
+                }
+            }
+        };
+        Some(change)
+    }
+
+    /// The user with the most voice time gained since the last snapshot, if movement applies.
+    pub fn biggest_gainer(&self) -> Option<BiggestGainer> {
+        if !self.shows_movement() {
+            return None;
+        }
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                let previous = self
+                    .snapshot
+                    .get(&entry.user_id)
+                    .map(|s| s.total_duration)
+                    .unwrap_or(0);
+                BiggestGainer {
+                    user_id: entry.user_id,
+                    gained: entry.total_duration - previous,
+                    change: self
+                        .rank_change(entry.user_id, idx as u32 + 1)
+                        .unwrap_or(RankChange::Same),
+                }
+            })
+            .filter(|gainer| gainer.gained > 0)
+            .max_by_key(|gainer| gainer.gained)
+    }
+
     /// Whether the target user is the author.
     pub fn target_is_author(&self) -> bool {
         self.target_user_id == Some(self.author_id)
@@ -142,6 +238,11 @@ impl Update for VoiceLeaderboardUpdate {
                 VoiceLeaderboardModel::apply_entries(model, entries);
                 None
             }
+            SetSnapshot(snapshot) => {
+                model.snapshot_taken_at = snapshot.first().map(|s| s.taken_at);
+                model.snapshot = snapshot.into_iter().map(|s| (*s.user_id, s)).collect();
+                None
+            }
             ChangeTimeRange(range) => {
                 if model.time_range != range {
                     model.time_range = range;
@@ -355,6 +456,80 @@ mod tests {
         assert_eq!(cmd, VoiceLeaderboardCmd::None);
     }
 
+    // ── SetSnapshot ─────────────────────────────────────────────────────────
+
+    fn snapshot(user_id: u64, rank: i32, duration: i64) -> LeaderboardSnapshotEntity {
+        LeaderboardSnapshotEntity {
+            user_id: user_id.into(),
+            rank,
+            total_duration: duration,
+            taken_at: DateTime::UNIX_EPOCH,
+            ..Default::default()
+        }
+    }
+
+    fn all_time_model_with_snapshot() -> VoiceLeaderboardModel {
+        let mut model = model_with(vec![entry(1, 9000), entry(2, 5000), entry(3, 4000)], 10);
+        model.time_range = VoiceLeaderboardTimeRange::AllTime;
+        VoiceLeaderboardUpdate::update(
+            VoiceLeaderboardMsg::SetSnapshot(vec![snapshot(2, 1, 4000), snapshot(1, 2, 3000)]),
+            &mut model,
+        );
+        model
+    }
+
+    #[test]
+    fn set_snapshot_indexes_by_user() {
+        let model = all_time_model_with_snapshot();
+
+        assert_eq!(model.snapshot.len(), 2);
+        assert_eq!(model.snapshot_taken_at, Some(DateTime::UNIX_EPOCH));
+        assert!(model.shows_movement());
+    }
+
+    #[test]
+    fn rank_change_compares_with_snapshot() {
+        let model = all_time_model_with_snapshot();
+
+        assert_eq!(model.rank_change(1, 1), Some(RankChange::Up(1)));
+        assert_eq!(model.rank_change(2, 2), Some(RankChange::Down(1)));
+        assert_eq!(model.rank_change(3, 3), Some(RankChange::New));
+    }
+
+    #[test]
+    fn rank_change_hidden_outside_all_time() {
+        let mut model = all_time_model_with_snapshot();
+        model.time_range = VoiceLeaderboardTimeRange::ThisMonth;
+
+        assert!(!model.shows_movement());
+        assert_eq!(model.rank_change(1, 1), None);
+        assert_eq!(model.biggest_gainer(), None);
+    }
+
+    #[test]
+    fn biggest_gainer_includes_new_users() {
+        let model = all_time_model_with_snapshot();
+
+        assert_eq!(
+            model.biggest_gainer(),
+            Some(BiggestGainer {
+                user_id: 1,
+                gained: 6000,
+                change: RankChange::Up(1),
+            })
+        );
+    }
+
+    #[test]
+    fn empty_snapshot_hides_movement() {
+        let mut model = all_time_model_with_snapshot();
+
+        VoiceLeaderboardUpdate::update(VoiceLeaderboardMsg::SetSnapshot(vec![]), &mut model);
+
+        assert!(!model.shows_movement());
+        assert!(model.snapshot.is_empty());
+    }
+
     // ── Pagination ──────────────────────────────────────────────────────────
 
     #[test]
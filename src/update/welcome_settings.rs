@@ -24,7 +24,7 @@ pub enum WelcomeSettingsMsg {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WelcomeSettingsCmd {
     None,
-    PersistSettings,
+    RegeneratePreview,
 }
 
 /// The welcome-settings model.
@@ -78,15 +78,15 @@ impl Update for WelcomeSettingsUpdate {
             ToggleEnabled => {
                 let current = model.settings.enabled.unwrap_or(false);
                 model.settings.enabled = Some(!current);
-                PersistSettings
+                RegeneratePreview
             }
             SetChannel(channel_id) => {
                 model.settings.channel_id = channel_id;
-                PersistSettings
+                RegeneratePreview
             }
             SetTemplate(template_id) => {
                 model.settings.template_id = template_id;
-                PersistSettings
+                RegeneratePreview
             }
             MarkRemoval(indices) => {
                 model.marked_removal = indices;
@@ -100,14 +100,14 @@ impl Update for WelcomeSettingsUpdate {
                         msgs.push(trimmed);
                     }
                 }
-                PersistSettings
+                RegeneratePreview
             }
             SetColor(color) => {
                 let trimmed = color.trim().to_string();
                 if trimmed.starts_with('#') {
                     model.settings.primary_color = Some(trimmed);
                 }
-                PersistSettings
+                RegeneratePreview
             }
             SaveRemoval => {
                 let msgs = model.settings.messages.clone().unwrap_or_default();
@@ -119,7 +119,7 @@ impl Update for WelcomeSettingsUpdate {
                         .collect(),
                 );
                 model.marked_removal.clear();
-                PersistSettings
+                RegeneratePreview
             }
             CancelRemoval => {
                 model.marked_removal.clear();
@@ -146,7 +146,7 @@ mod tests {
 
         let cmd = WelcomeSettingsUpdate::update(WelcomeSettingsMsg::ToggleEnabled, &mut model);
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert!(model.is_enabled());
     }
 
@@ -157,7 +157,7 @@ mod tests {
 
         let cmd = WelcomeSettingsUpdate::update(WelcomeSettingsMsg::ToggleEnabled, &mut model);
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert!(!model.is_enabled());
     }
 
@@ -172,7 +172,7 @@ mod tests {
             &mut model,
         );
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.settings.channel_id, Some("123".to_string()));
     }
 
@@ -183,7 +183,7 @@ mod tests {
 
         let cmd = WelcomeSettingsUpdate::update(WelcomeSettingsMsg::SetChannel(None), &mut model);
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.settings.channel_id, None);
     }
 
@@ -198,7 +198,7 @@ mod tests {
             &mut model,
         );
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.settings.template_id, Some("5".to_string()));
     }
 
@@ -231,7 +231,7 @@ mod tests {
             &mut model,
         );
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.message_count(), 1);
         assert_eq!(model.settings.messages.as_ref().unwrap()[0], "Hello!");
     }
@@ -245,7 +245,7 @@ mod tests {
             &mut model,
         );
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.message_count(), 0);
     }
 
@@ -259,7 +259,7 @@ mod tests {
             &mut model,
         );
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.message_count(), 25);
     }
 
@@ -274,7 +274,7 @@ mod tests {
             &mut model,
         );
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.settings.primary_color, Some("#FF5733".to_string()));
     }
 
@@ -287,7 +287,7 @@ mod tests {
             &mut model,
         );
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert_eq!(model.settings.primary_color, None);
     }
 
@@ -307,7 +307,7 @@ mod tests {
 
         let cmd = WelcomeSettingsUpdate::update(WelcomeSettingsMsg::SaveRemoval, &mut model);
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert!(model.marked_removal.is_empty());
         assert_eq!(
             model.settings.messages,
@@ -322,7 +322,7 @@ mod tests {
 
         let cmd = WelcomeSettingsUpdate::update(WelcomeSettingsMsg::SaveRemoval, &mut model);
 
-        assert_eq!(cmd, WelcomeSettingsCmd::PersistSettings);
+        assert_eq!(cmd, WelcomeSettingsCmd::RegeneratePreview);
         assert!(model.marked_removal.is_empty());
         assert_eq!(model.settings.messages, Some(vec![]));
     }
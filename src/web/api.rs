@@ -0,0 +1,603 @@
+//! Authenticated JSON REST API.
+//!
+//! Every request needs an `Authorization: Bearer <token>` header with a token
+//! from `/settings api` (one guild) or `/owner api_token` (every guild).
+//! Handlers go through the same services as the bot's commands.
+//!
+//! `GET /guilds/{guild_id}/events` is a server-sent events stream emitting a
+//! `feed_update` event whenever a feed the guild subscribes to publishes.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::Router;
+use axum::extract::FromRequestParts;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::request::Parts;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::response::sse::Event;
+use axum::response::sse::KeepAlive;
+use axum::response::sse::Sse;
+use axum::routing::get;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+use futures::Stream;
+use futures::stream;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::bot::command::voice::GuildStatType;
+use crate::entity::FeedEntity;
+use crate::entity::FeedItemEntity;
+use crate::entity::GuildDailyStats;
+use crate::entity::SubscriberEntity;
+use crate::entity::SubscriberType;
+use crate::entity::VoiceLeaderboardOptBuilder;
+use crate::event::FeedUpdateEvent;
+use crate::event::FeedUpdateKind;
+use crate::service::api::ApiScope;
+use crate::service::feed_subscription::SubscribeResult;
+use crate::service::feed_subscription::SubscriberTarget;
+use crate::service::feed_subscription::Subscription;
+use crate::service::feed_subscription::UnsubscribeResult;
+use crate::subscriber::feed_stream::GuildFeedUpdate;
+use crate::web::WebDashboard;
+use crate::web::error::ApiError;
+
+/// Largest page size accepted by paginated endpoints.
+const MAX_PER_PAGE: u32 = 100;
+
+/// Longest period accepted by the stats export, in days.
+const MAX_STATS_DAYS: i64 = 365;
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// Routes served under `/api/v1`.
+pub fn router() -> Router<Arc<WebDashboard>> {
+    Router::new()
+        .route(
+            "/guilds/{guild_id}/subscriptions",
+            get(list_subscriptions).post(subscribe).delete(unsubscribe),
+        )
+        .route("/guilds/{guild_id}/leaderboard", get(leaderboard))
+        .route("/guilds/{guild_id}/stats", get(stats))
+        .route("/guilds/{guild_id}/events", get(events))
+}
+
+/// Scope of the bearer token sent with a request.
+pub struct Auth(pub ApiScope);
+
+impl Auth {
+    /// Checks that the token may access `guild_id`.
+    fn guild(&self, guild_id: u64) -> ApiResult<u64> {
+        if self.0.allows(guild_id) {
+            Ok(guild_id)
+        } else {
+            Err(ApiError::Forbidden { guild_id })
+        }
+    }
+}
+
+impl FromRequestParts<Arc<WebDashboard>> for Auth {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<WebDashboard>,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or(ApiError::Unauthorized)?;
+
+        let scope = state
+            .services()
+            .api_tokens
+            .authorize(token)
+            .await?
+            .ok_or(ApiError::Unauthorized)?;
+        Ok(Self(scope))
+    }
+}
+
+// ── Subscriptions ───────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct PageQuery {
+    page: Option<u32>,
+    per_page: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct FeedJson {
+    id: i32,
+    name: String,
+    platform: String,
+    url: String,
+}
+
+impl From<FeedEntity> for FeedJson {
+    fn from(feed: FeedEntity) -> Self {
+        Self {
+            id: feed.id,
+            name: feed.name,
+            platform: feed.platform_id,
+            url: feed.source_url,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LatestItemJson {
+    description: String,
+    published: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct SubscriptionJson {
+    feed: FeedJson,
+    latest: Option<LatestItemJson>,
+    paused: bool,
+}
+
+impl From<Subscription> for SubscriptionJson {
+    fn from(sub: Subscription) -> Self {
+        Self {
+            feed: sub.feed.into(),
+            latest: sub.feed_latest.map(|item| LatestItemJson {
+                description: item.description,
+                published: item.published,
+            }),
+            paused: sub.paused,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SubscriptionPage {
+    total: u32,
+    page: u32,
+    per_page: u32,
+    items: Vec<SubscriptionJson>,
+}
+
+#[derive(Deserialize)]
+struct SubscribeBody {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct UnsubscribeQuery {
+    url: String,
+}
+
+#[derive(Serialize)]
+struct SubscriptionChange {
+    /// `subscribed`, `already_subscribed`, `unsubscribed` or `not_subscribed`.
+    status: &'static str,
+    feed: Option<FeedJson>,
+}
+
+async fn guild_subscriber(dashboard: &WebDashboard, guild_id: u64) -> ApiResult<SubscriberEntity> {
+    Ok(dashboard
+        .services()
+        .feed_subscription
+        .get_or_create_subscriber(&SubscriberTarget {
+            subscriber_type: SubscriberType::Guild,
+            target_id: guild_id.to_string(),
+        })
+        .await?)
+}
+
+async fn list_subscriptions(
+    State(dashboard): State<Arc<WebDashboard>>,
+    auth: Auth,
+    Path(guild_id): Path<u64>,
+    Query(query): Query<PageQuery>,
+) -> ApiResult<Json<SubscriptionPage>> {
+    let guild_id = auth.guild(guild_id)?;
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(25).clamp(1, MAX_PER_PAGE);
+
+    let service = &dashboard.services().feed_subscription;
+    let subscriber = guild_subscriber(&dashboard, guild_id).await?;
+    let total = service.get_subscription_count(&subscriber).await?;
+    let items = service
+        .list_paginated_subscriptions(&subscriber, page, per_page)
+        .await?;
+
+    Ok(Json(SubscriptionPage {
+        total,
+        page,
+        per_page,
+        items: items.into_iter().map(SubscriptionJson::from).collect(),
+    }))
+}
+
+async fn subscribe(
+    State(dashboard): State<Arc<WebDashboard>>,
+    auth: Auth,
+    Path(guild_id): Path<u64>,
+    Json(body): Json<SubscribeBody>,
+) -> ApiResult<Response> {
+    let guild_id = auth.guild(guild_id)?;
+    let subscriber = guild_subscriber(&dashboard, guild_id).await?;
+
+    let result = dashboard
+        .services()
+        .feed_subscription
+        .subscribe(body.url.trim(), &subscriber)
+        .await?;
+
+    let (status, change) = match result {
+        SubscribeResult::Success { feed } => (
+            StatusCode::CREATED,
+            SubscriptionChange {
+                status: "subscribed",
+                feed: Some(feed.into()),
+            },
+        ),
+        SubscribeResult::AlreadySubscribed { feed } => (
+            StatusCode::OK,
+            SubscriptionChange {
+                status: "already_subscribed",
+                feed: Some(feed.into()),
+            },
+        ),
+    };
+    Ok((status, Json(change)).into_response())
+}
+
+async fn unsubscribe(
+    State(dashboard): State<Arc<WebDashboard>>,
+    auth: Auth,
+    Path(guild_id): Path<u64>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> ApiResult<Json<SubscriptionChange>> {
+    let guild_id = auth.guild(guild_id)?;
+    let subscriber = guild_subscriber(&dashboard, guild_id).await?;
+
+    let result = dashboard
+        .services()
+        .feed_subscription
+        .unsubscribe(query.url.trim(), &subscriber)
+        .await?;
+
+    Ok(Json(match result {
+        UnsubscribeResult::Success { feed } => SubscriptionChange {
+            status: "unsubscribed",
+            feed: Some(feed.into()),
+        },
+        UnsubscribeResult::AlreadyUnsubscribed { feed } => SubscriptionChange {
+            status: "not_subscribed",
+            feed: Some(feed.into()),
+        },
+        UnsubscribeResult::NoneSubscribed { .. } => SubscriptionChange {
+            status: "not_subscribed",
+            feed: None,
+        },
+    }))
+}
+
+// ── Leaderboard ─────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct LeaderboardQuery {
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct LeaderboardEntryJson {
+    rank: u32,
+    /// Discord snowflakes are sent as strings to survive JSON number precision.
+    user_id: String,
+    total_duration: i64,
+}
+
+async fn leaderboard(
+    State(dashboard): State<Arc<WebDashboard>>,
+    auth: Auth,
+    Path(guild_id): Path<u64>,
+    Query(query): Query<LeaderboardQuery>,
+) -> ApiResult<Json<Vec<LeaderboardEntryJson>>> {
+    let guild_id = auth.guild(guild_id)?;
+    let offset = query.offset.unwrap_or(0);
+
+    let options = VoiceLeaderboardOptBuilder::default()
+        .guild_id(guild_id)
+        .offset(Some(offset))
+        .limit(Some(query.limit.unwrap_or(10).clamp(1, MAX_PER_PAGE)))
+        .since(query.since)
+        .until(query.until)
+        .build()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let entries = dashboard
+        .services()
+        .voice_tracking
+        .get_leaderboard_withopt(&options)
+        .await?;
+
+    Ok(Json(
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| LeaderboardEntryJson {
+                rank: offset + i as u32 + 1,
+                user_id: entry.user_id.to_string(),
+                total_duration: entry.total_duration,
+            })
+            .collect(),
+    ))
+}
+
+// ── Stats export ────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum StatKind {
+    #[default]
+    TotalTime,
+    AverageTime,
+    ActiveUsers,
+}
+
+impl From<StatKind> for GuildStatType {
+    fn from(kind: StatKind) -> Self {
+        match kind {
+            StatKind::TotalTime => GuildStatType::TotalTime,
+            StatKind::AverageTime => GuildStatType::AverageTime,
+            StatKind::ActiveUsers => GuildStatType::ActiveUserCount,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    #[serde(rename = "type", default)]
+    kind: StatKind,
+    days: Option<i64>,
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+#[derive(Serialize)]
+struct StatsDayJson {
+    day: NaiveDate,
+    value: i64,
+}
+
+#[derive(Serialize)]
+struct StatsJson {
+    #[serde(rename = "type")]
+    kind: StatKind,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    days: Vec<StatsDayJson>,
+}
+
+async fn stats(
+    State(dashboard): State<Arc<WebDashboard>>,
+    auth: Auth,
+    Path(guild_id): Path<u64>,
+    Query(query): Query<StatsQuery>,
+) -> ApiResult<Response> {
+    let guild_id = auth.guild(guild_id)?;
+    let days = query.days.unwrap_or(30);
+    if !(1..=MAX_STATS_DAYS).contains(&days) {
+        return Err(ApiError::BadRequest(format!(
+            "`days` must be between 1 and {MAX_STATS_DAYS}"
+        )));
+    }
+
+    let until = Utc::now();
+    let since = until - Duration::days(days);
+    let stats = dashboard
+        .services()
+        .voice_tracking
+        .get_guild_daily_stats(guild_id, &since, &until, query.kind.into())
+        .await?;
+
+    Ok(match query.format {
+        ExportFormat::Csv => ([(CONTENT_TYPE, "text/csv")], stats_csv(&stats)).into_response(),
+        ExportFormat::Json => Json(StatsJson {
+            kind: query.kind,
+            since,
+            until,
+            days: stats
+                .into_iter()
+                .map(|s| StatsDayJson {
+                    day: s.day,
+                    value: s.value,
+                })
+                .collect(),
+        })
+        .into_response(),
+    })
+}
+
+/// Formats daily stats as a `day,value` CSV document.
+fn stats_csv(stats: &[GuildDailyStats]) -> String {
+    let mut csv = String::from("day,value\n");
+    for stat in stats {
+        csv.push_str(&format!("{},{}\n", stat.day, stat.value));
+    }
+    csv
+}
+
+// ── Feed update stream ──────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct FeedUpdateJson {
+    feed: FeedJson,
+    /// What the platform calls an item, e.g. `Chapter` or `Episode`.
+    item_name: String,
+    item: LatestItemJson,
+    previous: Option<LatestItemJson>,
+    kind: FeedUpdateKind,
+}
+
+impl From<&FeedUpdateEvent> for FeedUpdateJson {
+    fn from(event: &FeedUpdateEvent) -> Self {
+        let data = &event.data;
+        let item = |item: &FeedItemEntity| LatestItemJson {
+            description: item.description.clone(),
+            published: item.published,
+        };
+        Self {
+            feed: FeedEntity::clone(&event.feed).into(),
+            item_name: data.feed_info.feed_item_name.clone(),
+            item: item(&data.new_feed_item),
+            previous: data.old_feed_item.as_deref().map(item),
+            kind: data.kind,
+        }
+    }
+}
+
+/// Converts a feed update into an SSE `feed_update` event.
+fn sse_event(update: &GuildFeedUpdate) -> Option<Event> {
+    Event::default()
+        .event("feed_update")
+        .id(update.event.data.new_feed_item.id.to_string())
+        .json_data(FeedUpdateJson::from(&update.event))
+        .ok()
+}
+
+async fn events(
+    State(dashboard): State<Arc<WebDashboard>>,
+    auth: Auth,
+    Path(guild_id): Path<u64>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let guild_id = auth.guild(guild_id)?;
+    let rx = dashboard
+        .feed_stream()
+        .ok_or_else(|| ApiError::Unavailable("Feed update streaming is disabled".to_string()))?
+        .subscribe();
+
+    let stream = stream::unfold(rx, move |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(update) if update.is_for(guild_id) => {
+                    if let Some(event) = sse_event(&update) {
+                        return Some((Ok(event), rx));
+                    }
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_rejects_other_guilds() {
+        let auth = Auth(ApiScope::Guild(1));
+        assert_eq!(auth.guild(1).unwrap(), 1);
+        assert!(matches!(
+            auth.guild(2),
+            Err(ApiError::Forbidden { guild_id: 2 })
+        ));
+        assert!(Auth(ApiScope::Owner).guild(2).is_ok());
+    }
+
+    #[test]
+    fn stats_csv_lists_one_row_per_day() {
+        let stats = vec![
+            GuildDailyStats {
+                day: NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(),
+                value: 60,
+            },
+            GuildDailyStats {
+                day: NaiveDate::from_ymd_opt(2026, 10, 2).unwrap(),
+                value: 0,
+            },
+        ];
+
+        assert_eq!(
+            stats_csv(&stats),
+            "day,value\n2026-10-01,60\n2026-10-02,0\n"
+        );
+    }
+
+    #[test]
+    fn guild_feed_update_matches_subscribed_guilds() {
+        use crate::event::FeedUpdateData;
+        use crate::feed::PlatformInfo;
+
+        let event = FeedUpdateEvent::new(FeedUpdateData {
+            feed: Arc::new(FeedEntity {
+                name: "Series".to_string(),
+                ..Default::default()
+            }),
+            feed_info: Arc::new(PlatformInfo {
+                feed_item_name: "Chapter".to_string(),
+                ..Default::default()
+            }),
+            old_feed_item: None,
+            new_feed_item: Arc::new(FeedItemEntity {
+                id: 7,
+                description: "Ch. 2".to_string(),
+                ..Default::default()
+            }),
+            kind: FeedUpdateKind::New,
+        });
+        let update = GuildFeedUpdate {
+            guild_ids: Arc::new(vec![1, 2]),
+            event,
+        };
+
+        assert!(update.is_for(2));
+        assert!(!update.is_for(3));
+
+        let json = serde_json::to_value(FeedUpdateJson::from(&update.event)).unwrap();
+        assert_eq!(json["feed"]["name"], "Series");
+        assert_eq!(json["item_name"], "Chapter");
+        assert_eq!(json["item"]["description"], "Ch. 2");
+        assert!(json["previous"].is_null());
+        assert_eq!(json["kind"], "new");
+        assert!(sse_event(&update).is_some());
+    }
+
+    #[test]
+    fn stat_kind_parses_snake_case() {
+        let query: StatsQuery =
+            serde_json::from_str(r#"{"type": "active_users", "format": "csv"}"#).unwrap();
+        assert_eq!(query.kind, StatKind::ActiveUsers);
+        assert_eq!(query.format, ExportFormat::Csv);
+        assert_eq!(
+            GuildStatType::from(query.kind),
+            GuildStatType::ActiveUserCount
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! REST API error types.
+
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use log::error;
+use serde_json::json;
+
+use crate::service::error::ServiceError;
+
+/// Errors returned by REST API endpoints, rendered as `{"error": "..."}`.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ApiError {
+    #[error("Missing or invalid API token")]
+    Unauthorized,
+
+    #[error("This token cannot access guild {guild_id}")]
+    Forbidden { guild_id: u64 },
+
+    #[error("Only the owner token can access this endpoint")]
+    OwnerOnly,
+
+    #[error("Invalid request: {0}")]
+    BadRequest(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    Unavailable(String),
+
+    #[error("Internal server error")]
+    Internal(#[source] anyhow::Error),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden { .. } | ApiError::OwnerOnly => StatusCode::FORBIDDEN,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<ServiceError> for ApiError {
+    fn from(e: ServiceError) -> Self {
+        match e {
+            ServiceError::SubscriptionLimitReached { .. } => ApiError::Conflict(e.to_string()),
+            ServiceError::FeedError(e) => ApiError::BadRequest(e.to_string()),
+            e => ApiError::Internal(e.into()),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError::Internal(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if let ApiError::Internal(e) = &self {
+            error!("REST API request failed: {e:?}");
+        }
+        (self.status(), Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
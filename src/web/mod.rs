@@ -0,0 +1,406 @@
+//! Optional web dashboard and REST API.
+//!
+//! Serves read-only per-guild pages with the feed list, voice leaderboard and
+//! activity charts, plus an authenticated JSON API and feed update stream under
+//! `/api/v1`. Both read from the same services the bot uses. A page is
+//! reachable only through the guild-scoped token generated by
+//! `/settings dashboard`. Client health, the image render queue and task
+//! panics are exported for Prometheus under `/metrics`, which needs the owner's API
+//! token, and `/mal/callback` finishes MyAnimeList account links started with
+//! `/feed link-mal`.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::Router;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::routing::get;
+use chrono::Duration;
+use chrono::Utc;
+use log::error;
+use log::info;
+use poise::serenity_prelude::Cache;
+use poise::serenity_prelude::GuildId;
+use poise::serenity_prelude::UserId;
+use serde::Deserialize;
+use tokio::net::TcpListener;
+
+use crate::bot::command::voice::GuildStatType;
+use crate::bot::render::ImageRenderService;
+use crate::bot::supervisor::ClientMonitor;
+use crate::bot::utils::format_duration;
+use crate::entity::GeneralSettings;
+use crate::entity::SubscriberType;
+use crate::service::Services;
+use crate::service::api::ApiScope;
+use crate::service::error::ServiceError;
+use crate::service::feed_subscription::SubscriberTarget;
+use crate::subscriber::feed_stream::FeedStreamSubscriber;
+use crate::task::supervisor::TaskMonitor;
+use crate::web::api::Auth;
+use crate::web::error::ApiError;
+use crate::web::page::Chart;
+use crate::web::page::FeedRow;
+use crate::web::page::GuildPage;
+use crate::web::page::LeaderboardRow;
+use crate::web::page::PageRenderer;
+
+pub mod api;
+pub mod error;
+pub mod page;
+
+/// Number of leaderboard entries shown on a dashboard page.
+const LEADERBOARD_LIMIT: u32 = 25;
+
+/// Maximum number of feeds shown on a dashboard page.
+const FEEDS_LIMIT: u32 = 100;
+
+/// Number of days covered by the activity charts.
+const CHART_DAYS: i64 = 30;
+
+/// HTTP server rendering guild dashboards and serving the REST API.
+pub struct WebDashboard {
+    services: Arc<Services>,
+    cache: Option<Arc<Cache>>,
+    pages: PageRenderer,
+    feed_stream: Option<Arc<FeedStreamSubscriber>>,
+    clients: Option<Arc<ClientMonitor>>,
+    renderer: Option<Arc<ImageRenderService>>,
+    tasks: Option<Arc<TaskMonitor>>,
+    serve_pages: bool,
+    serve_api: bool,
+    serve_mal_callback: bool,
+}
+
+/// Query MyAnimeList redirects an authorizing user with.
+#[derive(Deserialize)]
+struct MalCallbackQuery {
+    state: Option<String>,
+    code: Option<String>,
+    error: Option<String>,
+}
+
+impl WebDashboard {
+    /// Creates a new dashboard backed by the given services.
+    pub fn new(services: Arc<Services>) -> Self {
+        Self {
+            services,
+            cache: None,
+            pages: PageRenderer::new(),
+            feed_stream: None,
+            clients: None,
+            renderer: None,
+            tasks: None,
+            serve_pages: true,
+            serve_api: false,
+            serve_mal_callback: false,
+        }
+    }
+
+    /// Sets whether the dashboard pages are served. Enabled by default.
+    pub fn with_pages(mut self, enabled: bool) -> Self {
+        self.serve_pages = enabled;
+        self
+    }
+
+    /// Sets whether the REST API is served under `/api/v1`. Disabled by default.
+    pub fn with_api(mut self, enabled: bool) -> Self {
+        self.serve_api = enabled;
+        self
+    }
+
+    /// Sets whether `/mal/callback` finishes MyAnimeList account links. Disabled by default.
+    pub fn with_mal_callback(mut self, enabled: bool) -> Self {
+        self.serve_mal_callback = enabled;
+        self
+    }
+
+    /// Streams feed updates from this subscriber to API clients.
+    pub fn with_feed_stream(mut self, feed_stream: Arc<FeedStreamSubscriber>) -> Self {
+        self.feed_stream = Some(feed_stream);
+        self
+    }
+
+    /// Serves the health of these bot clients under `/metrics`.
+    pub fn with_clients(mut self, clients: Arc<ClientMonitor>) -> Self {
+        self.clients = Some(clients);
+        self
+    }
+
+    /// Serves the depth of this image render queue under `/metrics`.
+    pub fn with_renderer(mut self, renderer: Arc<ImageRenderService>) -> Self {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    /// Serves the panic counts of background tasks under `/metrics`.
+    pub fn with_tasks(mut self, tasks: Arc<TaskMonitor>) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+
+    /// Returns the feed update stream, if one is attached.
+    pub fn feed_stream(&self) -> Option<&Arc<FeedStreamSubscriber>> {
+        self.feed_stream.as_ref()
+    }
+
+    /// Returns the services backing the dashboard.
+    pub fn services(&self) -> &Arc<Services> {
+        &self.services
+    }
+
+    /// Resolves guild and member names from the bot's cache instead of showing IDs.
+    pub fn with_cache(mut self, cache: Arc<Cache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Binds to `addr` and serves the dashboard in the background.
+    pub async fn start(self: Arc<Self>, addr: &str) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        let router = self.router();
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router).await {
+                error!("Web dashboard stopped: {e}");
+            }
+        });
+
+        info!("Web dashboard listening on {addr}");
+        Ok(())
+    }
+
+    fn router(self: Arc<Self>) -> Router {
+        let mut router = Router::new();
+        if self.serve_pages {
+            router = router.route("/g/{token}", get(Self::guild_page));
+        }
+        if self.serve_api {
+            router = router.nest("/api/v1", api::router());
+        }
+        if self.serve_mal_callback {
+            router = router.route("/mal/callback", get(Self::mal_callback));
+        }
+        if self.clients.is_some() || self.renderer.is_some() || self.tasks.is_some() {
+            router = router.route("/metrics", get(Self::metrics));
+        }
+        router.with_state(self)
+    }
+
+    /// Serves the metrics to requests carrying the owner's API token, since they cover
+    /// every guild.
+    async fn metrics(State(dashboard): State<Arc<Self>>, Auth(scope): Auth) -> Response {
+        if scope != ApiScope::Owner {
+            return ApiError::OwnerOnly.into_response();
+        }
+        let mut body = String::new();
+        if let Some(clients) = &dashboard.clients {
+            body.push_str(&clients.render_metrics());
+        }
+        if let Some(renderer) = &dashboard.renderer {
+            body.push_str(&renderer.render_metrics());
+        }
+        if let Some(tasks) = &dashboard.tasks {
+            body.push_str(&tasks.render_metrics());
+        }
+        ([("content-type", "text/plain; version=0.0.4")], body).into_response()
+    }
+
+    async fn mal_callback(
+        State(dashboard): State<Arc<Self>>,
+        Query(query): Query<MalCallbackQuery>,
+    ) -> Response {
+        let (Some(state), Some(code)) = (query.state, query.code) else {
+            let message = match query.error.as_deref() {
+                Some("access_denied") => "Linking was cancelled. You can close this page.",
+                _ => "MyAnimeList did not send an authorization code. Run /feed link-mal again.",
+            };
+            return (StatusCode::BAD_REQUEST, message).into_response();
+        };
+
+        match dashboard.services.mal_import.complete_link(&state, &code).await {
+            Ok(link) => format!(
+                "Linked MyAnimeList account {}. You can close this page and run /feed import-mal in Discord.",
+                link.username
+            )
+            .into_response(),
+            Err(ServiceError::MalLinkRequestExpired) => (
+                StatusCode::BAD_REQUEST,
+                ServiceError::MalLinkRequestExpired.to_string(),
+            )
+                .into_response(),
+            Err(e) => {
+                error!("Failed to complete MyAnimeList link: {e}");
+                (
+                    StatusCode::BAD_GATEWAY,
+                    "Could not link the MyAnimeList account. Run /feed link-mal again.",
+                )
+                    .into_response()
+            }
+        }
+    }
+
+    async fn guild_page(State(dashboard): State<Arc<Self>>, Path(token): Path<String>) -> Response {
+        let guild_id = match dashboard.services.dashboard.resolve_token(&token).await {
+            Ok(Some(guild_id)) => guild_id,
+            Ok(None) => return (StatusCode::NOT_FOUND, "Dashboard not found").into_response(),
+            Err(e) => {
+                error!("Failed to resolve dashboard token: {e}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        let general = match dashboard
+            .services
+            .settings
+            .get_server_settings(guild_id)
+            .await
+        {
+            Ok(settings) => settings.general,
+            Err(e) => {
+                error!("Failed to get settings for guild {guild_id}: {e}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+        // Admins can block the dashboard without revoking the link
+        if !general.is_dashboard_enabled() {
+            return (StatusCode::NOT_FOUND, "Dashboard not found").into_response();
+        }
+
+        let html = match dashboard.build_page(guild_id, general).await {
+            Ok(page) => dashboard
+                .pages
+                .render_guild(&page)
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e),
+        };
+
+        match html {
+            Ok(html) => Html(html).into_response(),
+            Err(e) => {
+                error!("Failed to render dashboard for guild {guild_id}: {e}");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+
+    /// Collects everything shown on a guild's dashboard page.
+    async fn build_page(&self, guild_id: u64, general: GeneralSettings) -> Result<GuildPage> {
+        let (leaderboard, charts) = if self.services.voice_tracking.is_enabled(guild_id).await {
+            (
+                Some(self.leaderboard(guild_id).await?),
+                self.charts(guild_id).await?,
+            )
+        } else {
+            (None, Vec::new())
+        };
+
+        Ok(GuildPage {
+            guild_name: self.guild_name(guild_id),
+            feeds: self.feeds(guild_id).await?,
+            leaderboard,
+            charts,
+            generated_at: Utc::now(),
+            general,
+        })
+    }
+
+    async fn feeds(&self, guild_id: u64) -> Result<Vec<FeedRow>> {
+        let service = &self.services.feed_subscription;
+        // Viewing the page doesn't register the guild as a subscriber
+        let Some(subscriber) = service
+            .get_subscriber(&SubscriberTarget {
+                subscriber_type: SubscriberType::Guild,
+                target_id: guild_id.to_string(),
+            })
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let subscriptions = service
+            .list_paginated_subscriptions(&subscriber, 1, FEEDS_LIMIT)
+            .await?;
+
+        Ok(subscriptions
+            .into_iter()
+            .map(|sub| FeedRow {
+                name: sub.feed.name,
+                url: sub.feed.source_url,
+                latest: sub
+                    .feed_latest
+                    .as_ref()
+                    .map(|item| item.description.clone()),
+                latest_at: sub
+                    .feed_latest
+                    .as_ref()
+                    .map(|item| item.published.format("%Y-%m-%d").to_string()),
+                paused: sub.paused,
+            })
+            .collect())
+    }
+
+    async fn leaderboard(&self, guild_id: u64) -> Result<Vec<LeaderboardRow>> {
+        let entries = self
+            .services
+            .voice_tracking
+            .get_leaderboard(guild_id, LEADERBOARD_LIMIT)
+            .await?;
+
+        Ok(entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| LeaderboardRow {
+                rank: i as u32 + 1,
+                name: self.member_name(guild_id, entry.user_id),
+                duration: format_duration(entry.total_duration),
+            })
+            .collect())
+    }
+
+    async fn charts(&self, guild_id: u64) -> Result<Vec<Chart>> {
+        let until = Utc::now();
+        let since = until - Duration::days(CHART_DAYS);
+        let voice = &self.services.voice_tracking;
+
+        let total = voice
+            .get_guild_daily_stats(guild_id, &since, &until, GuildStatType::TotalTime)
+            .await?;
+        let users = voice
+            .get_guild_daily_stats(guild_id, &since, &until, GuildStatType::ActiveUserCount)
+            .await?;
+
+        Ok(vec![
+            Chart::from_daily("Daily Voice Time", &total, Chart::duration),
+            Chart::from_daily("Daily Active Users", &users, Chart::count),
+        ])
+    }
+
+    fn guild_name(&self, guild_id: u64) -> String {
+        self.cache
+            .as_ref()
+            .and_then(|cache| {
+                cache
+                    .guild(GuildId::new(guild_id))
+                    .map(|g| g.name.to_string())
+            })
+            .unwrap_or_else(|| format!("Server {guild_id}"))
+    }
+
+    fn member_name(&self, guild_id: u64, user_id: u64) -> String {
+        self.cache
+            .as_ref()
+            .and_then(|cache| {
+                let guild = cache.guild(GuildId::new(guild_id))?;
+                let member = guild.members.get(&UserId::new(user_id))?;
+                Some(member.display_name().to_string())
+            })
+            .unwrap_or_else(|| format!("User {user_id}"))
+    }
+}
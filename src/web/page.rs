@@ -0,0 +1,217 @@
+//! Dashboard page data and HTML rendering.
+
+use chrono::DateTime;
+use chrono::Utc;
+use minijinja::Environment;
+use minijinja::context;
+use serde::Serialize;
+
+use crate::bot::utils::format_duration;
+use crate::entity::GeneralSettings;
+use crate::entity::GuildDailyStats;
+
+/// Chart drawing area width in SVG units.
+const CHART_WIDTH: f32 = 600.0;
+/// Chart drawing area height in SVG units.
+const CHART_HEIGHT: f32 = 160.0;
+/// Horizontal gap between bars in SVG units.
+const BAR_GAP: f32 = 2.0;
+
+/// A subscribed feed as shown on the dashboard.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FeedRow {
+    pub name: String,
+    pub url: String,
+    pub latest: Option<String>,
+    pub latest_at: Option<String>,
+    pub paused: bool,
+}
+
+/// A voice leaderboard row as shown on the dashboard.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardRow {
+    pub rank: u32,
+    pub name: String,
+    pub duration: String,
+}
+
+/// A single bar of an activity chart, in SVG coordinates.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ChartBar {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Tooltip text, e.g. `2026-10-15: 3h 20m`.
+    pub label: String,
+}
+
+/// A daily activity bar chart rendered as inline SVG.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub title: String,
+    pub width: f32,
+    pub height: f32,
+    pub bars: Vec<ChartBar>,
+    /// Label of the tallest bar, shown as the chart's scale.
+    pub max_label: String,
+}
+
+impl Chart {
+    /// Builds a chart with one bar per day, scaled to the largest value.
+    pub fn from_daily(title: &str, stats: &[GuildDailyStats], format: fn(i64) -> String) -> Self {
+        let max = stats.iter().map(|s| s.value).max().unwrap_or(0).max(1);
+        let slot = CHART_WIDTH / stats.len().max(1) as f32;
+
+        let bars = stats
+            .iter()
+            .enumerate()
+            .map(|(i, stat)| {
+                let height = CHART_HEIGHT * stat.value.max(0) as f32 / max as f32;
+                ChartBar {
+                    x: i as f32 * slot,
+                    y: CHART_HEIGHT - height,
+                    width: (slot - BAR_GAP).max(1.0),
+                    height,
+                    label: format!("{}: {}", stat.day, format(stat.value)),
+                }
+            })
+            .collect();
+
+        Self {
+            title: title.to_string(),
+            width: CHART_WIDTH,
+            height: CHART_HEIGHT,
+            bars,
+            max_label: format(max),
+        }
+    }
+
+    /// Formats a chart value as a duration in seconds.
+    pub fn duration(value: i64) -> String {
+        format_duration(value)
+    }
+
+    /// Formats a chart value as a plain count.
+    pub fn count(value: i64) -> String {
+        value.to_string()
+    }
+}
+
+/// Everything shown on a guild's dashboard page.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GuildPage {
+    pub guild_name: String,
+    pub feeds: Vec<FeedRow>,
+    /// `None` when voice tracking is disabled for the guild.
+    pub leaderboard: Option<Vec<LeaderboardRow>>,
+    pub charts: Vec<Chart>,
+    pub generated_at: DateTime<Utc>,
+    /// The guild's general settings, deciding the page language and timezone.
+    pub general: GeneralSettings,
+}
+
+/// Renders dashboard pages from the bundled HTML template.
+pub struct PageRenderer {
+    jinja_env: Environment<'static>,
+}
+
+impl PageRenderer {
+    /// Creates a renderer with the dashboard templates loaded.
+    pub fn new() -> Self {
+        let mut jinja_env = Environment::new();
+        jinja_env
+            .add_template("guild.html", include_str!("../../assets/dashboard.html"))
+            .expect("Failed to add dashboard template");
+        Self { jinja_env }
+    }
+
+    /// Renders a guild's dashboard page.
+    pub fn render_guild(&self, page: &GuildPage) -> Result<String, minijinja::Error> {
+        let template = self.jinja_env.get_template("guild.html")?;
+        let generated_at = page
+            .generated_at
+            .with_timezone(&page.general.timezone())
+            .format("%Y-%m-%d %H:%M %Z");
+        template.render(context! {
+            lang => page.general.locale(),
+            guild_name => page.guild_name,
+            feeds => page.feeds,
+            leaderboard => page.leaderboard,
+            charts => page.charts,
+            generated_at => generated_at.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn stat(day: u32, value: i64) -> GuildDailyStats {
+        GuildDailyStats {
+            day: NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+            value,
+        }
+    }
+
+    #[test]
+    fn chart_scales_bars_to_largest_value() {
+        let chart = Chart::from_daily("Users", &[stat(1, 2), stat(2, 4)], Chart::count);
+
+        assert_eq!(chart.bars.len(), 2);
+        assert_eq!(chart.bars[1].height, CHART_HEIGHT);
+        assert_eq!(chart.bars[0].height, CHART_HEIGHT / 2.0);
+        assert_eq!(chart.bars[0].y, CHART_HEIGHT / 2.0);
+        assert_eq!(chart.bars[1].x, CHART_WIDTH / 2.0);
+        assert_eq!(chart.bars[0].label, "2026-10-01: 2");
+        assert_eq!(chart.max_label, "4");
+    }
+
+    #[test]
+    fn chart_handles_no_activity() {
+        let chart = Chart::from_daily("Time", &[stat(1, 0)], Chart::duration);
+
+        assert_eq!(chart.bars[0].height, 0.0);
+        assert_eq!(chart.bars[0].y, CHART_HEIGHT);
+
+        let empty = Chart::from_daily("Time", &[], Chart::duration);
+        assert!(empty.bars.is_empty());
+    }
+
+    #[test]
+    fn render_guild_escapes_names() {
+        let page = GuildPage {
+            guild_name: "<script>".to_string(),
+            leaderboard: Some(vec![LeaderboardRow {
+                rank: 1,
+                name: "Alice".to_string(),
+                duration: "1h".to_string(),
+            }]),
+            ..Default::default()
+        };
+
+        let html = PageRenderer::new().render_guild(&page).unwrap();
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("Alice"));
+    }
+
+    #[test]
+    fn render_guild_uses_guild_locale_and_timezone() {
+        let page = GuildPage {
+            generated_at: DateTime::from_timestamp(0, 0).unwrap(),
+            general: GeneralSettings {
+                timezone: Some("Asia/Jakarta".to_string()),
+                locale: Some("id-ID".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let html = PageRenderer::new().render_guild(&page).unwrap();
+        assert!(html.contains(r#"<html lang="id-ID">"#));
+        assert!(html.contains("1970-01-01 07:00 WIB"));
+    }
+}
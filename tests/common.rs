@@ -68,6 +68,9 @@ impl MockFeed {
             copyright_notice: "Mock".to_string(),
             logo_url: "".to_string(),
             tags: "series".to_string(),
+            unique_item_ids: false,
+            accent_color: None,
+            emoji: String::new(),
         };
         Self {
             base: BasePlatform::new(info),
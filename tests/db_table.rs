@@ -9,6 +9,7 @@ use pwr_bot::entity::FeedItemEntity;
 use pwr_bot::entity::FeedSubscriptionEntity;
 use pwr_bot::entity::FeedsSettings;
 use pwr_bot::entity::Json;
+use pwr_bot::entity::PollTier;
 use pwr_bot::entity::ServerSettingsEntity;
 use pwr_bot::entity::SubscriberEntity;
 use pwr_bot::entity::SubscriberType;
@@ -181,6 +182,31 @@ mod feed_table_tests {
         assert_eq!(anime[0].name, "Feed 2");
     });
 
+    db_test!(update_poll_tier, |db| {
+        let id = create_feed!(db, "Feed");
+        let fetched = db.feed.select(&id).await.unwrap().unwrap();
+        assert_eq!(fetched.poll_tier, PollTier::Active);
+
+        db.feed
+            .update_poll_tier(id, PollTier::Dormant)
+            .await
+            .expect("Failed to update poll tier");
+
+        let fetched = db.feed.select(&id).await.unwrap().unwrap();
+        assert_eq!(fetched.poll_tier, PollTier::Dormant);
+    });
+
+    db_test!(select_all_by_subscriber_id, |db| {
+        let sub_id = create_sub!(db, "user1");
+        let f1 = create_feed!(db, "One Piece");
+        let _ = create_feed!(db, "Naruto");
+        create_subscription!(db, f1, sub_id);
+
+        let feeds = db.feed.select_all_by_subscriber_id(sub_id).await.unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].id, f1);
+    });
+
     db_test!(select_by_name_and_subscriber_id, |db| {
         let sub_id = create_sub!(db, "user1");
         let f1 = create_feed!(db, "One Piece");
@@ -213,6 +239,19 @@ mod feed_table_tests {
             .await
             .unwrap();
         assert_eq!(res.len(), 1);
+
+        // Search by a title variant
+        let f3 = create_feed!(db, "Shingeki no Kyojin", {
+            title_english: Some("Attack on Titan".to_string())
+        });
+        create_subscription!(db, f3, sub_id);
+        let res = db
+            .feed
+            .select_by_name_and_subscriber_id(&sub_id, "titan", None)
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].name, "Shingeki no Kyojin");
     });
 }
 
@@ -295,6 +334,48 @@ mod subscriber_table_tests {
         assert_eq!(subs.len(), 1);
         assert_eq!(subs[0].target_id, "user1");
     });
+
+    db_test!(select_edit_subscribers_by_type_and_feed, |db| {
+        let feed_id = create_feed!(db, "Feed");
+        let wants_edits = create_sub!(db, "user1");
+        let new_only = create_sub!(db, "user2");
+        create_subscription!(db, feed_id, wants_edits);
+        create_subscription!(db, feed_id, new_only);
+
+        assert!(
+            db.feed_subscription
+                .update_notify_edits(feed_id, wants_edits, true)
+                .await
+                .unwrap()
+        );
+
+        let subs = db
+            .subscriber
+            .select_edit_subscribers_by_type_and_feed(SubscriberType::Dm, feed_id)
+            .await
+            .unwrap();
+
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].target_id, "user1");
+    });
+
+    db_test!(clear_first_seen_once, |db| {
+        let id = db
+            .subscriber
+            .insert(&SubscriberEntity {
+                r#type: SubscriberType::Dm,
+                target_id: "user1".to_string(),
+                first_seen: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert!(db.subscriber.clear_first_seen(id).await.unwrap());
+        assert!(!db.subscriber.clear_first_seen(id).await.unwrap());
+        let fetched = db.subscriber.select(&id).await.unwrap().unwrap();
+        assert!(!fetched.first_seen);
+    });
 }
 
 mod feed_subscription_table_tests {
@@ -347,6 +428,36 @@ mod feed_subscription_table_tests {
         assert_eq!(subs.len(), 1);
     });
 
+    db_test!(select_by_feed_and_subscriber, |db| {
+        let f_id = create_feed!(db, "Feed");
+        let s_id = create_sub!(db, "u1");
+        let other_id = create_sub!(db, "u2");
+        db.feed_subscription
+            .insert(&FeedSubscriptionEntity {
+                feed_id: f_id,
+                subscriber_id: s_id,
+                added_by: Some("42".to_string()),
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to subscribe");
+
+        let sub = db
+            .feed_subscription
+            .select_by_feed_and_subscriber(f_id, s_id)
+            .await
+            .unwrap()
+            .expect("Subscription not found");
+        assert_eq!(sub.added_by, Some("42".to_string()));
+        assert!(
+            db.feed_subscription
+                .select_by_feed_and_subscriber(f_id, other_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    });
+
     db_test!(select_paginated, |db| {
         let f_id = create_feed!(db, "Feed");
         let s_id = create_sub!(db, "u1");
@@ -435,8 +546,10 @@ mod server_settings_table_tests {
                     channel_id: Some(chan.to_string()),
                     subscribe_role_id: None,
                     unsubscribe_role_id: None,
+                    ..Default::default()
                 },
                 welcome: WelcomeSettings::default(),
+                ..Default::default()
             }),
         }
     }
@@ -477,6 +590,68 @@ mod server_settings_table_tests {
     });
 }
 
+mod settings_audit_table_tests {
+    use pwr_bot::entity::SettingsAuditEntity;
+
+    use super::*;
+
+    fn create_change(guild_id: u64, key: &str, minutes_ago: i64) -> SettingsAuditEntity {
+        SettingsAuditEntity {
+            guild_id: DbU64::from(guild_id),
+            changed_by: Some(DbU64::from(42)),
+            key: key.to_string(),
+            old_value: None,
+            new_value: Some("true".to_string()),
+            changed_at: Utc::now().trunc_subsecs(6) - Duration::minutes(minutes_ago),
+            ..Default::default()
+        }
+    }
+
+    db_test!(insert_and_select, |db| {
+        let id = db
+            .settings_audit
+            .insert(&create_change(123, "feeds.enabled", 0))
+            .await
+            .unwrap();
+
+        let fetched = db.settings_audit.select(&id).await.unwrap().unwrap();
+        assert_eq!(fetched.key, "feeds.enabled");
+        assert_eq!(fetched.changed_by, Some(DbU64::from(42)));
+        assert_eq!(fetched.old_value, None);
+    });
+
+    db_test!(select_paginated_by_guild_id_newest_first, |db| {
+        for (key, minutes_ago) in [("a", 3), ("b", 2), ("c", 1)] {
+            db.settings_audit
+                .insert(&create_change(123, key, minutes_ago))
+                .await
+                .unwrap();
+        }
+        db.settings_audit
+            .insert(&create_change(456, "other", 0))
+            .await
+            .unwrap();
+
+        assert_eq!(db.settings_audit.count_by_guild_id(123).await.unwrap(), 3);
+
+        let first = db
+            .settings_audit
+            .select_paginated_by_guild_id(123, 0, 2)
+            .await
+            .unwrap();
+        let keys: Vec<_> = first.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["c", "b"]);
+
+        let second = db
+            .settings_audit
+            .select_paginated_by_guild_id(123, 1, 2)
+            .await
+            .unwrap();
+        let keys: Vec<_> = second.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["a"]);
+    });
+}
+
 mod voice_sessions_table_tests {
     use super::*;
 
@@ -517,6 +692,7 @@ mod voice_sessions_table_tests {
             join_time,
             leave_time: join_time, // Active session
             is_active: true,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session)
@@ -553,6 +729,7 @@ mod voice_sessions_table_tests {
             join_time: now - Duration::hours(1),
             leave_time: now - Duration::hours(1), // Active
             is_active: true,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&active_session)
@@ -567,6 +744,7 @@ mod voice_sessions_table_tests {
             join_time: now - Duration::hours(2),
             leave_time: now - Duration::hours(1), // Completed
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&completed_session)
@@ -581,6 +759,7 @@ mod voice_sessions_table_tests {
             join_time: now - Duration::minutes(30),
             leave_time: now - Duration::minutes(30), // Active
             is_active: true,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&another_active)
@@ -616,6 +795,42 @@ mod voice_sessions_table_tests {
         assert!(active.is_empty());
     });
 
+    db_test!(update_active_leave_times, |db| {
+        let join_time = Utc::now().trunc_subsecs(6) - Duration::hours(1);
+        for (user_id, is_active) in [(100, true), (101, false)] {
+            db.voice_sessions
+                .insert(&VoiceSessionsEntity {
+                    user_id,
+                    guild_id: 200,
+                    channel_id: 300,
+                    join_time,
+                    leave_time: join_time,
+                    is_active,
+                    ..Default::default()
+                })
+                .await
+                .expect("Failed to insert session");
+        }
+
+        let flushed_at = join_time + Duration::minutes(30);
+        let updated = db
+            .voice_sessions
+            .update_active_leave_times(&flushed_at)
+            .await
+            .expect("Failed to update leave times");
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].user_id, 100);
+        assert_eq!(updated[0].leave_time, flushed_at);
+
+        let sessions: Vec<VoiceSessionsEntity> = db
+            .voice_sessions
+            .select_all()
+            .await
+            .expect("Failed to select sessions");
+        let closed = sessions.iter().find(|s| s.user_id == 101).unwrap();
+        assert_eq!(closed.leave_time, join_time);
+    });
+
     db_test!(update_leave_time_no_match, |db| {
         let join_time = Utc::now();
 
@@ -652,6 +867,7 @@ mod voice_sessions_table_tests {
             join_time: now,
             leave_time: now + Duration::hours(2), // 2 hours today
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session1)
@@ -666,6 +882,7 @@ mod voice_sessions_table_tests {
             join_time: now - Duration::days(1),
             leave_time: now - Duration::days(1) + Duration::hours(1), // 1 hour yesterday
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session2)
@@ -681,6 +898,7 @@ mod voice_sessions_table_tests {
             join_time: now,
             leave_time: now + Duration::minutes(30),
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session3)
@@ -692,7 +910,7 @@ mod voice_sessions_table_tests {
         let until = now + Duration::days(1);
         let activity = db
             .voice_sessions
-            .get_user_daily_activity(100, 200, &since, &until)
+            .get_user_daily_activity(100, 200, &since, &until, "UTC")
             .await
             .expect("Failed to get user daily activity");
 
@@ -728,7 +946,7 @@ mod voice_sessions_table_tests {
         // Get activity for user with no sessions
         let activity = db
             .voice_sessions
-            .get_user_daily_activity(999, 200, &since, &until)
+            .get_user_daily_activity(999, 200, &since, &until, "UTC")
             .await
             .expect("Failed to get user daily activity");
 
@@ -750,6 +968,7 @@ mod voice_sessions_table_tests {
             join_time: now,
             leave_time: now + Duration::hours(2),
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session1)
@@ -765,6 +984,7 @@ mod voice_sessions_table_tests {
             join_time: now,
             leave_time: now + Duration::hours(1),
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session2)
@@ -780,6 +1000,7 @@ mod voice_sessions_table_tests {
             join_time: now,
             leave_time: now + Duration::minutes(30),
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session3)
@@ -791,7 +1012,7 @@ mod voice_sessions_table_tests {
         let until = now + Duration::days(1);
         let stats = db
             .voice_sessions
-            .get_guild_daily_average_time(200, &since, &until)
+            .get_guild_daily_average_time(200, &since, &until, "UTC")
             .await
             .expect("Failed to get guild daily average time");
 
@@ -818,6 +1039,7 @@ mod voice_sessions_table_tests {
             join_time: now,
             leave_time: now + Duration::hours(1),
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session1)
@@ -833,6 +1055,7 @@ mod voice_sessions_table_tests {
             join_time: now,
             leave_time: now + Duration::hours(2),
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session2)
@@ -848,6 +1071,7 @@ mod voice_sessions_table_tests {
             join_time: now + Duration::minutes(30),
             leave_time: now + Duration::minutes(90),
             is_active: false,
+            parent_session_id: None,
         };
         db.voice_sessions
             .insert(&session3)
@@ -859,7 +1083,7 @@ mod voice_sessions_table_tests {
         let until = now + Duration::days(1);
         let stats = db
             .voice_sessions
-            .get_guild_daily_user_count(200, &since, &until)
+            .get_guild_daily_user_count(200, &since, &until, "UTC")
             .await
             .expect("Failed to get guild daily user count");
 
@@ -868,6 +1092,46 @@ mod voice_sessions_table_tests {
         assert_eq!(stats[0].value, 2, "Should have 2 unique users");
     });
 
+    db_test!(get_guild_daily_user_count_buckets_in_timezone, |db| {
+        let late = Utc::now()
+            .date_naive()
+            .and_hms_opt(20, 0, 0)
+            .unwrap()
+            .and_utc();
+
+        let session = VoiceSessionsEntity {
+            id: 0,
+            user_id: 100,
+            guild_id: 200,
+            channel_id: 300,
+            join_time: late,
+            leave_time: late + Duration::hours(1),
+            is_active: false,
+            parent_session_id: None,
+        };
+        db.voice_sessions
+            .insert(&session)
+            .await
+            .expect("Failed to insert session");
+
+        let since = late - Duration::days(1);
+        let until = late + Duration::days(1);
+        let utc = db
+            .voice_sessions
+            .get_guild_daily_user_count(200, &since, &until, "UTC")
+            .await
+            .expect("Failed to get UTC stats");
+        // 20:00 UTC is already 03:00 the next day in Jakarta (UTC+7)
+        let jakarta = db
+            .voice_sessions
+            .get_guild_daily_user_count(200, &since, &until, "Asia/Jakarta")
+            .await
+            .expect("Failed to get Jakarta stats");
+
+        assert_eq!(utc[0].day, late.date_naive());
+        assert_eq!(jakarta[0].day, late.date_naive().succ_opt().unwrap());
+    });
+
     db_test!(get_guild_daily_stats_empty, |db| {
         let now = Utc::now();
         let since = now - Duration::days(7);
@@ -876,7 +1140,7 @@ mod voice_sessions_table_tests {
         // Get average time for guild with no sessions
         let avg_stats = db
             .voice_sessions
-            .get_guild_daily_average_time(999, &since, &until)
+            .get_guild_daily_average_time(999, &since, &until, "UTC")
             .await
             .expect("Failed to get guild daily average time");
         assert!(
@@ -887,7 +1151,7 @@ mod voice_sessions_table_tests {
         // Get user count for guild with no sessions
         let count_stats = db
             .voice_sessions
-            .get_guild_daily_user_count(999, &since, &until)
+            .get_guild_daily_user_count(999, &since, &until, "UTC")
             .await
             .expect("Failed to get guild daily user count");
         assert!(
@@ -896,3 +1160,1335 @@ mod voice_sessions_table_tests {
         );
     });
 }
+
+mod channel_weights_table_tests {
+    use pwr_bot::entity::ChannelWeightEntity;
+
+    use super::*;
+
+    fn create_weight(guild_id: u64, channel_id: u64, weight_percent: i32) -> ChannelWeightEntity {
+        ChannelWeightEntity {
+            guild_id: DbU64::from(guild_id),
+            channel_id: DbU64::from(channel_id),
+            weight_percent,
+            ..Default::default()
+        }
+    }
+
+    db_test!(replace_upserts_by_channel, |db| {
+        let id = db
+            .channel_weights
+            .replace(&create_weight(123, 1, 150))
+            .await
+            .unwrap();
+        let same_id = db
+            .channel_weights
+            .replace(&create_weight(123, 1, 200))
+            .await
+            .unwrap();
+        assert_eq!(id, same_id);
+
+        let fetched = db.channel_weights.select(&id).await.unwrap().unwrap();
+        assert_eq!(fetched.weight_percent, 200);
+    });
+
+    db_test!(replace_all_by_guild_id_replaces_only_that_guild, |db| {
+        db.channel_weights
+            .insert(&create_weight(123, 1, 150))
+            .await
+            .unwrap();
+        db.channel_weights
+            .insert(&create_weight(456, 1, 50))
+            .await
+            .unwrap();
+
+        db.channel_weights
+            .replace_all_by_guild_id(123, &[create_weight(123, 2, 200), create_weight(123, 3, 0)])
+            .await
+            .unwrap();
+
+        let weights = db
+            .channel_weights
+            .select_all_by_guild_id(123)
+            .await
+            .unwrap();
+        let channels: Vec<_> = weights
+            .iter()
+            .map(|w| (*w.channel_id, w.weight_percent))
+            .collect();
+        assert_eq!(channels, vec![(2, 200), (3, 0)]);
+
+        let other = db
+            .channel_weights
+            .select_all_by_guild_id(456)
+            .await
+            .unwrap();
+        assert_eq!(other.len(), 1);
+    });
+}
+
+mod leaderboard_snapshots_table_tests {
+    use pwr_bot::entity::LeaderboardSnapshotEntity;
+
+    use super::*;
+
+    fn create_snapshot(
+        guild_id: u64,
+        user_id: u64,
+        rank: i32,
+        taken_at: chrono::DateTime<Utc>,
+    ) -> LeaderboardSnapshotEntity {
+        LeaderboardSnapshotEntity {
+            guild_id: DbU64::from(guild_id),
+            user_id: DbU64::from(user_id),
+            rank,
+            total_duration: 3600,
+            taken_at,
+            ..Default::default()
+        }
+    }
+
+    db_test!(insert_and_select, |db| {
+        let taken_at = Utc::now().trunc_subsecs(6);
+        let id = db
+            .leaderboard_snapshots
+            .insert(&create_snapshot(123, 1, 1, taken_at))
+            .await
+            .unwrap();
+
+        let fetched = db.leaderboard_snapshots.select(&id).await.unwrap().unwrap();
+        assert_eq!(*fetched.user_id, 1);
+        assert_eq!(fetched.taken_at, taken_at);
+    });
+
+    db_test!(select_latest_by_guild_id_returns_newest_snapshot, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        let last_week = now - Duration::days(7);
+        for snapshot in [
+            create_snapshot(123, 1, 1, last_week),
+            create_snapshot(123, 2, 2, now),
+            create_snapshot(123, 1, 1, now),
+            create_snapshot(456, 3, 1, now),
+        ] {
+            db.leaderboard_snapshots.insert(&snapshot).await.unwrap();
+        }
+
+        let latest = db
+            .leaderboard_snapshots
+            .select_latest_by_guild_id(123)
+            .await
+            .unwrap();
+        let ranks: Vec<_> = latest.iter().map(|s| (*s.user_id, s.rank)).collect();
+        assert_eq!(ranks, vec![(1, 1), (2, 2)]);
+        assert!(latest.iter().all(|s| s.taken_at == now));
+
+        let empty = db
+            .leaderboard_snapshots
+            .select_latest_by_guild_id(789)
+            .await
+            .unwrap();
+        assert!(empty.is_empty());
+    });
+}
+
+mod dashboard_tokens_table_tests {
+    use pwr_bot::entity::DashboardTokenEntity;
+
+    use super::*;
+
+    fn create_token(guild_id: u64, token: &str) -> DashboardTokenEntity {
+        DashboardTokenEntity {
+            guild_id: DbU64::from(guild_id),
+            token: token.to_string(),
+            created_by: Some(DbU64::from(1)),
+            created_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(replace_rotates_guild_token, |db| {
+        let id = db
+            .dashboard_tokens
+            .replace(&create_token(123, "old"))
+            .await
+            .unwrap();
+        let same_id = db
+            .dashboard_tokens
+            .replace(&create_token(123, "new"))
+            .await
+            .unwrap();
+        assert_eq!(id, same_id);
+
+        assert!(
+            db.dashboard_tokens
+                .select_by_token("old")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let fetched = db
+            .dashboard_tokens
+            .select_by_guild_id(123)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.token, "new");
+    });
+
+    db_test!(delete_by_guild_id_reports_existence, |db| {
+        db.dashboard_tokens
+            .insert(&create_token(123, "abc"))
+            .await
+            .unwrap();
+
+        assert!(db.dashboard_tokens.delete_by_guild_id(123).await.unwrap());
+        assert!(!db.dashboard_tokens.delete_by_guild_id(123).await.unwrap());
+        assert!(
+            db.dashboard_tokens
+                .select_by_token("abc")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    });
+}
+
+mod api_tokens_table_tests {
+    use pwr_bot::entity::ApiTokenEntity;
+
+    use super::*;
+
+    fn create_token(guild_id: Option<u64>, token_hash: &str) -> ApiTokenEntity {
+        ApiTokenEntity {
+            guild_id: guild_id.map(DbU64::from),
+            token_hash: token_hash.to_string(),
+            created_by: Some(DbU64::from(1)),
+            created_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(replace_keeps_one_token_per_scope, |db| {
+        db.api_tokens
+            .replace(&create_token(Some(123), "guild-old"))
+            .await
+            .unwrap();
+        db.api_tokens
+            .replace(&create_token(Some(123), "guild-new"))
+            .await
+            .unwrap();
+        db.api_tokens
+            .replace(&create_token(None, "owner-old"))
+            .await
+            .unwrap();
+        db.api_tokens
+            .replace(&create_token(None, "owner-new"))
+            .await
+            .unwrap();
+
+        assert_eq!(db.api_tokens.select_all().await.unwrap().len(), 2);
+        let guild = db
+            .api_tokens
+            .select_by_guild_id(Some(123))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(guild.token_hash, "guild-new");
+        let owner = db
+            .api_tokens
+            .select_by_guild_id(None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(owner.token_hash, "owner-new");
+        assert!(owner.guild_id.is_none());
+    });
+
+    db_test!(delete_by_guild_id_only_touches_that_scope, |db| {
+        db.api_tokens
+            .insert(&create_token(Some(123), "guild"))
+            .await
+            .unwrap();
+        db.api_tokens
+            .insert(&create_token(None, "owner"))
+            .await
+            .unwrap();
+
+        assert!(db.api_tokens.delete_by_guild_id(None).await.unwrap());
+        assert!(
+            db.api_tokens
+                .select_by_token_hash("owner")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            db.api_tokens
+                .select_by_token_hash("guild")
+                .await
+                .unwrap()
+                .is_some()
+        );
+    });
+}
+
+mod anilist_links_table_tests {
+    use pwr_bot::entity::AniListLinkEntity;
+
+    use super::*;
+
+    fn create_link(user_id: u64, auto_sync: bool) -> AniListLinkEntity {
+        AniListLinkEntity {
+            user_id: user_id.into(),
+            anilist_user_id: user_id as i32,
+            username: format!("user{user_id}"),
+            auto_sync,
+            created_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(select_due_orders_never_synced_first, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        let stale = AniListLinkEntity {
+            last_synced_at: Some(now - Duration::hours(24)),
+            ..create_link(1, true)
+        };
+        let fresh = AniListLinkEntity {
+            last_synced_at: Some(now - Duration::hours(1)),
+            ..create_link(2, true)
+        };
+        for link in [stale, fresh, create_link(3, true), create_link(4, false)] {
+            db.anilist_links.insert(&link).await.unwrap();
+        }
+
+        let due = db
+            .anilist_links
+            .select_due(&(now - Duration::hours(12)), 10)
+            .await
+            .unwrap();
+        let ids: Vec<u64> = due.iter().map(|link| *link.user_id).collect();
+        assert_eq!(ids, vec![3, 1]);
+
+        let limited = db
+            .anilist_links
+            .select_due(&(now - Duration::hours(12)), 1)
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+        assert_eq!(*limited[0].user_id, 3);
+    });
+}
+
+mod mal_links_table_tests {
+    use pwr_bot::entity::MalLinkEntity;
+
+    use super::*;
+
+    db_test!(replace_updates_tokens, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        let link = MalLinkEntity {
+            user_id: 1u64.into(),
+            mal_user_id: 100,
+            username: "fazuh".to_string(),
+            access_token: "old-access".to_string(),
+            refresh_token: "old-refresh".to_string(),
+            token_expires_at: now,
+            created_at: now,
+            ..Default::default()
+        };
+        db.mal_links.replace(&link).await.unwrap();
+
+        let refreshed = MalLinkEntity {
+            access_token: "new-access".to_string(),
+            token_expires_at: now + Duration::days(31),
+            ..link
+        };
+        assert_eq!(db.mal_links.replace(&refreshed).await.unwrap(), 1);
+
+        let stored = db.mal_links.select(&1).await.unwrap().unwrap();
+        assert_eq!(stored.access_token, "new-access");
+        assert_eq!(stored.token_expires_at, now + Duration::days(31));
+        assert_eq!(db.mal_links.select_all().await.unwrap().len(), 1);
+    });
+}
+
+mod custom_json_feeds_table_tests {
+    use pwr_bot::entity::CustomJsonFeedEntity;
+
+    use super::*;
+
+    fn create_custom_feed(url: &str, name: &str) -> CustomJsonFeedEntity {
+        CustomJsonFeedEntity {
+            name: name.to_string(),
+            url: url.to_string(),
+            items_path: "$.items[*]".to_string(),
+            id_path: "$.id".to_string(),
+            title_path: "$.title".to_string(),
+            created_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(replace_updates_feed_with_same_url, |db| {
+        let url = "https://example.com/api";
+        let id = db
+            .custom_json_feeds
+            .replace(&create_custom_feed(url, "Old"))
+            .await
+            .unwrap();
+        let replaced = db
+            .custom_json_feeds
+            .replace(&create_custom_feed(url, "New"))
+            .await
+            .unwrap();
+
+        assert_eq!(id, replaced);
+        let feed = db
+            .custom_json_feeds
+            .select_by_url(url)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feed.name, "New");
+        assert_eq!(db.custom_json_feeds.select_all().await.unwrap().len(), 1);
+    });
+
+    db_test!(delete_by_url_reports_existence, |db| {
+        let url = "https://example.com/api";
+        db.custom_json_feeds
+            .insert(&create_custom_feed(url, "Feed"))
+            .await
+            .unwrap();
+
+        assert!(db.custom_json_feeds.delete_by_url(url).await.unwrap());
+        assert!(!db.custom_json_feeds.delete_by_url(url).await.unwrap());
+        assert!(
+            db.custom_json_feeds
+                .select_by_url(url)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    });
+}
+
+mod tags_table_tests {
+    use pwr_bot::entity::TagEntity;
+
+    use super::*;
+
+    fn create_tag(guild_id: u64, name: &str) -> TagEntity {
+        TagEntity {
+            guild_id: DbU64::from(guild_id),
+            name: name.to_string(),
+            content: format!("Content of {name}"),
+            created_by: Some(DbU64::from(1)),
+            created_at: Utc::now().trunc_subsecs(6),
+            updated_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(select_by_name_is_scoped_to_guild, |db| {
+        db.tags.insert(&create_tag(1, "rules")).await.unwrap();
+        db.tags.insert(&create_tag(2, "rules")).await.unwrap();
+
+        let tag = db.tags.select_by_name(1, "rules").await.unwrap().unwrap();
+        assert_eq!(*tag.guild_id, 1);
+        assert!(db.tags.select_by_name(3, "rules").await.unwrap().is_none());
+        assert_eq!(db.tags.count_by_guild_id(1).await.unwrap(), 1);
+    });
+
+    db_test!(increment_uses_returns_new_count, |db| {
+        let id = db.tags.insert(&create_tag(1, "faq")).await.unwrap();
+
+        assert_eq!(db.tags.increment_uses(id).await.unwrap(), 1);
+        assert_eq!(db.tags.increment_uses(id).await.unwrap(), 2);
+        assert_eq!(db.tags.select(&id).await.unwrap().unwrap().uses, 2);
+    });
+
+    db_test!(paginated_lists_most_used_first, |db| {
+        db.tags.insert(&create_tag(1, "a")).await.unwrap();
+        let popular = db.tags.insert(&create_tag(1, "b")).await.unwrap();
+        db.tags.increment_uses(popular).await.unwrap();
+
+        let page = db
+            .tags
+            .select_paginated_by_guild_id(1, 0, 10)
+            .await
+            .unwrap();
+        let names: Vec<_> = page.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a"]);
+    });
+
+    db_test!(name_search_and_delete_by_name, |db| {
+        db.tags
+            .insert(&create_tag(1, "server-rules"))
+            .await
+            .unwrap();
+        db.tags.insert(&create_tag(1, "faq")).await.unwrap();
+
+        let found = db.tags.select_by_name_search(1, "RULE", 25).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "server-rules");
+
+        assert!(db.tags.delete_by_name(1, "faq").await.unwrap());
+        assert!(!db.tags.delete_by_name(1, "faq").await.unwrap());
+        assert_eq!(db.tags.count_by_guild_id(1).await.unwrap(), 1);
+    });
+}
+
+mod emoji_stats_table_tests {
+    use pwr_bot::entity::EmojiKind;
+    use pwr_bot::entity::EmojiStatEntity;
+
+    use super::*;
+
+    fn emoji_use(emoji_id: u64, name: &str, kind: EmojiKind, reaction: bool) -> EmojiStatEntity {
+        EmojiStatEntity {
+            guild_id: DbU64::from(1),
+            emoji_id: DbU64::from(emoji_id),
+            kind,
+            name: name.to_string(),
+            message_uses: i32::from(!reaction),
+            reaction_uses: i32::from(reaction),
+            last_used_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(increment_uses_accumulates_and_renames, |db| {
+        let stats = &db.emoji_stats;
+        stats
+            .increment_uses(&emoji_use(10, "wave", EmojiKind::Emoji, false))
+            .await
+            .unwrap();
+        stats
+            .increment_uses(&emoji_use(10, "wave", EmojiKind::Emoji, true))
+            .await
+            .unwrap();
+        stats
+            .increment_uses(&emoji_use(10, "hello", EmojiKind::Emoji, false))
+            .await
+            .unwrap();
+
+        let page = stats
+            .select_paginated_by_guild_id(1, EmojiKind::Emoji, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].name, "hello");
+        assert_eq!(page[0].message_uses, 2);
+        assert_eq!(page[0].reaction_uses, 1);
+    });
+
+    db_test!(paginated_lists_most_used_first_by_kind, |db| {
+        let stats = &db.emoji_stats;
+        stats
+            .increment_uses(&emoji_use(10, "rare", EmojiKind::Emoji, false))
+            .await
+            .unwrap();
+        for reaction in [false, true] {
+            stats
+                .increment_uses(&emoji_use(11, "popular", EmojiKind::Emoji, reaction))
+                .await
+                .unwrap();
+        }
+        stats
+            .increment_uses(&emoji_use(20, "cat", EmojiKind::Sticker, false))
+            .await
+            .unwrap();
+
+        let page = stats
+            .select_paginated_by_guild_id(1, EmojiKind::Emoji, 0, 10)
+            .await
+            .unwrap();
+        let names: Vec<_> = page.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["popular", "rare"]);
+        assert_eq!(
+            stats
+                .count_by_guild_id(1, EmojiKind::Sticker)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            stats.count_by_guild_id(2, EmojiKind::Emoji).await.unwrap(),
+            0
+        );
+    });
+}
+
+mod invite_uses_table_tests {
+    use pwr_bot::entity::InviteUseEntity;
+
+    use super::*;
+
+    fn join(member_id: u64, inviter_id: Option<u64>) -> InviteUseEntity {
+        InviteUseEntity {
+            guild_id: DbU64::from(1),
+            member_id: DbU64::from(member_id),
+            inviter_id: inviter_id.map(DbU64::from),
+            invite_code: inviter_id.map(|id| format!("code{id}")),
+            joined_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(leaderboard_counts_distinct_members, |db| {
+        let uses = &db.invite_uses;
+        // Member 10 left and rejoined through the same inviter
+        for (member_id, inviter_id) in [(10, 1), (10, 1), (11, 2), (12, 2)] {
+            uses.insert(&join(member_id, Some(inviter_id)))
+                .await
+                .unwrap();
+        }
+        uses.insert(&join(13, None)).await.unwrap();
+
+        let page = uses.select_leaderboard_by_guild_id(1, 0, 10).await.unwrap();
+        let ranked: Vec<_> = page.iter().map(|e| (e.inviter_id, e.invites)).collect();
+        assert_eq!(ranked, vec![(2, 2), (1, 1)]);
+        assert_eq!(uses.count_inviters_by_guild_id(1).await.unwrap(), 2);
+        assert_eq!(uses.count_by_inviter_id(1, 1).await.unwrap(), 1);
+        assert_eq!(uses.count_by_inviter_id(2, 2).await.unwrap(), 0);
+    });
+}
+
+mod pending_subscriptions_table_tests {
+    use pwr_bot::entity::PendingSubscriptionEntity;
+
+    use super::*;
+
+    fn request(guild_id: u64, source_url: &str, requested_by: u64) -> PendingSubscriptionEntity {
+        PendingSubscriptionEntity {
+            guild_id: DbU64::from(guild_id),
+            source_url: source_url.to_string(),
+            requested_by: DbU64::from(requested_by),
+            requested_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(selects_requests_by_guild_and_url, |db| {
+        let requests = &db.pending_subscriptions;
+        let first = requests
+            .insert(&request(1, "https://example.com/a", 10))
+            .await
+            .unwrap();
+        requests
+            .insert(&request(1, "https://example.com/b", 11))
+            .await
+            .unwrap();
+        requests
+            .insert(&request(2, "https://example.com/a", 12))
+            .await
+            .unwrap();
+
+        let urls: Vec<String> = requests
+            .select_all_by_guild_id(1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.source_url)
+            .collect();
+        assert_eq!(urls, vec!["https://example.com/a", "https://example.com/b"]);
+
+        let found = requests
+            .select_by_guild_and_url(2, "https://example.com/a")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(u64::from(found.requested_by), 12);
+        assert!(
+            requests
+                .select_by_guild_and_url(2, "https://example.com/b")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // The same feed cannot be requested twice for a guild
+        assert!(
+            requests
+                .insert(&request(1, "https://example.com/a", 13))
+                .await
+                .is_err()
+        );
+
+        requests.delete(&first).await.unwrap();
+        assert_eq!(requests.select_all_by_guild_id(1).await.unwrap().len(), 1);
+    });
+}
+
+mod feed_collections_table_tests {
+    use pwr_bot::entity::FeedCollectionEntity;
+
+    use super::*;
+
+    fn collection(guild_id: u64, name: &str, channel_id: u64) -> FeedCollectionEntity {
+        FeedCollectionEntity {
+            guild_id: DbU64::from(guild_id),
+            name: name.to_string(),
+            channel_id: DbU64::from(channel_id),
+            ..Default::default()
+        }
+    }
+
+    db_test!(selects_collections_by_guild_and_name, |db| {
+        let collections = &db.feed_collections;
+        collections
+            .insert(&collection(1, "Manga", 10))
+            .await
+            .unwrap();
+        collections
+            .insert(&collection(1, "Dev tools", 11))
+            .await
+            .unwrap();
+        collections
+            .insert(&collection(2, "Manga", 20))
+            .await
+            .unwrap();
+
+        let names: Vec<String> = collections
+            .select_all_by_guild_id(1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names, vec!["Dev tools", "Manga"]);
+
+        let found = collections
+            .select_by_guild_and_name(2, "Manga")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(u64::from(found.channel_id), 20);
+        assert!(
+            collections
+                .select_by_guild_and_name(2, "Dev tools")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    });
+
+    db_test!(replace_updates_channel_and_role, |db| {
+        let collections = &db.feed_collections;
+        let id = collections
+            .insert(&collection(1, "Manga", 10))
+            .await
+            .unwrap();
+
+        let mut updated = collection(1, "Manga", 12);
+        updated.mention_role_id = Some(DbU64::from(99));
+        assert_eq!(collections.replace(&updated).await.unwrap(), id);
+
+        let found = collections.select(&id).await.unwrap().unwrap();
+        assert_eq!(u64::from(found.channel_id), 12);
+        assert_eq!(found.mention_role_id.map(u64::from), Some(99));
+    });
+
+    db_test!(deleting_collection_clears_subscriptions, |db| {
+        let f_id = create_feed!(db, "Feed");
+        let s_id = create_sub!(db, "u1");
+        create_subscription!(db, f_id, s_id);
+        let c_id = db
+            .feed_collections
+            .insert(&collection(1, "Manga", 10))
+            .await
+            .unwrap();
+
+        assert!(
+            db.feed_subscription
+                .update_collection(f_id, s_id, Some(c_id))
+                .await
+                .unwrap()
+        );
+        let sub = db
+            .feed_subscription
+            .select_by_feed_and_subscriber(f_id, s_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sub.collection_id, Some(c_id));
+
+        db.feed_collections.delete(&c_id).await.unwrap();
+        let sub = db
+            .feed_subscription
+            .select_by_feed_and_subscriber(f_id, s_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sub.collection_id, None);
+    });
+}
+
+mod notification_log_table_tests {
+    use pwr_bot::entity::NotificationLogEntity;
+
+    use super::*;
+
+    fn entry(subscriber_id: i32, feed_id: i32, days_ago: i64) -> NotificationLogEntity {
+        NotificationLogEntity {
+            subscriber_id,
+            feed_id,
+            sent_at: Utc::now() - Duration::days(days_ago),
+            delivered: true,
+            ..Default::default()
+        }
+    }
+
+    db_test!(counts_and_ranks_notifications_since, |db| {
+        let f1 = create_feed!(db, "Feed One");
+        let f2 = create_feed!(db, "Feed Two");
+        let s1 = create_sub!(db, "u1");
+        let s2 = create_sub!(db, "u2");
+
+        let log = &db.notification_log;
+        for days_ago in [0, 1, 2] {
+            log.insert(&entry(s1, f1, days_ago)).await.unwrap();
+        }
+        log.insert(&entry(s1, f2, 0)).await.unwrap();
+        // Too old, and another subscriber's
+        log.insert(&entry(s1, f2, 40)).await.unwrap();
+        log.insert(&entry(s2, f2, 0)).await.unwrap();
+        // Failed notifications are not received
+        log.insert(&NotificationLogEntity {
+            delivered: false,
+            ..entry(s1, f2, 0)
+        })
+        .await
+        .unwrap();
+
+        let since = Utc::now() - Duration::days(30);
+        assert_eq!(log.count_by_subscriber_since(s1, &since).await.unwrap(), 4);
+        assert_eq!(log.count_by_subscriber_since(s2, &since).await.unwrap(), 1);
+
+        let top = log
+            .select_top_feeds_by_subscriber_since(s1, &since, 3)
+            .await
+            .unwrap();
+        let counts: Vec<(i32, i64)> = top.iter().map(|r| (r.feed_id, r.notifications)).collect();
+        assert_eq!(counts, vec![(f1, 3), (f2, 1)]);
+        assert_eq!(top[0].name, "Feed One");
+
+        let top = log
+            .select_top_feeds_by_subscriber_since(s1, &since, 1)
+            .await
+            .unwrap();
+        assert_eq!(top.len(), 1);
+    });
+
+    db_test!(aggregates_delivery_analytics, |db| {
+        let f1 = create_feed!(db, "Feed One");
+        let f2 = create_feed!(db, "Feed Two");
+        let dm = create_sub!(db, "u1");
+        let guild = db
+            .subscriber
+            .insert(&SubscriberEntity {
+                r#type: SubscriberType::Guild,
+                target_id: "g1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let log = &db.notification_log;
+        for (sub, feed, delay) in [(dm, f1, 60), (dm, f1, 120), (guild, f2, 600)] {
+            log.insert(&NotificationLogEntity {
+                delay_secs: Some(delay),
+                ..entry(sub, feed, 0)
+            })
+            .await
+            .unwrap();
+        }
+        log.insert(&NotificationLogEntity {
+            delivered: false,
+            ..entry(dm, f1, 1)
+        })
+        .await
+        .unwrap();
+        // Edits have no delay
+        log.insert(&entry(guild, f1, 1)).await.unwrap();
+
+        let since = Utc::now() - Duration::days(7);
+        let daily = log.select_daily_counts_since(&since).await.unwrap();
+        let totals: Vec<(i64, i64)> = daily.iter().map(|d| (d.delivered, d.failed)).collect();
+        assert_eq!(totals, vec![(1, 1), (3, 0)]);
+
+        let by_type = log
+            .select_counts_by_subscriber_type_since(&since)
+            .await
+            .unwrap();
+        let by_type: Vec<(SubscriberType, i64, i64)> = by_type
+            .iter()
+            .map(|r| (r.subscriber_type, r.delivered, r.failed))
+            .collect();
+        assert_eq!(
+            by_type,
+            vec![(SubscriberType::Dm, 2, 1), (SubscriberType::Guild, 2, 0)]
+        );
+
+        let slowest = log.select_slowest_platforms_since(&since, 5).await.unwrap();
+        let slowest: Vec<(String, f64)> = slowest
+            .into_iter()
+            .map(|r| (r.platform_id, r.median_delay_secs))
+            .collect();
+        assert_eq!(
+            slowest,
+            vec![
+                ("feedtwo".to_string(), 600.0),
+                ("feedone".to_string(), 90.0)
+            ]
+        );
+    });
+
+    db_test!(deleting_subscriber_clears_log, |db| {
+        let f_id = create_feed!(db, "Feed");
+        let s_id = create_sub!(db, "u1");
+        db.notification_log
+            .insert(&entry(s_id, f_id, 0))
+            .await
+            .unwrap();
+
+        db.subscriber.delete(&s_id).await.unwrap();
+        assert!(db.notification_log.select_all().await.unwrap().is_empty());
+    });
+}
+
+mod delivery_outbox_table_tests {
+    use pwr_bot::entity::DeliveryOutboxEntity;
+
+    use super::*;
+
+    fn entry(subscriber_id: i32, item: &str) -> DeliveryOutboxEntity {
+        DeliveryOutboxEntity {
+            subscriber_id,
+            data: Json(serde_json::json!({ "item": item })),
+            queued_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(insert_many_returns_ids_and_take_all_empties_outbox, |db| {
+        let s1 = create_sub!(db, "u1");
+        let s2 = create_sub!(db, "u2");
+
+        let inserted = db
+            .delivery_outbox
+            .insert_many(&[entry(s1, "a"), entry(s2, "a")])
+            .await
+            .unwrap();
+        let subscriber_ids: Vec<i32> = inserted.iter().map(|e| e.subscriber_id).collect();
+        assert_eq!(subscriber_ids, vec![s1, s2]);
+        db.delivery_outbox.delete(&inserted[0].id).await.unwrap();
+
+        let taken = db.delivery_outbox.take_all().await.unwrap();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].id, inserted[1].id);
+        assert_eq!(taken[0].data.0, serde_json::json!({ "item": "a" }));
+        assert!(db.delivery_outbox.select_all().await.unwrap().is_empty());
+    });
+}
+
+mod temp_voice_channels_table_tests {
+    use pwr_bot::entity::TempVoiceChannelEntity;
+
+    use super::*;
+
+    fn channel(channel_id: u64, guild_id: u64) -> TempVoiceChannelEntity {
+        TempVoiceChannelEntity {
+            channel_id: DbU64::from(channel_id),
+            guild_id: DbU64::from(guild_id),
+            owner_id: DbU64::from(100),
+            created_at: Utc::now().trunc_subsecs(6),
+        }
+    }
+
+    db_test!(selects_channels_by_guild, |db| {
+        let channels = &db.temp_voice_channels;
+        for (channel_id, guild_id) in [(10, 1), (11, 1), (20, 2)] {
+            channels
+                .insert(&channel(channel_id, guild_id))
+                .await
+                .unwrap();
+        }
+
+        let mut ids: Vec<u64> = channels
+            .select_all_by_guild_id(1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.channel_id.into())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec![10, 11]);
+
+        channels.delete(&10).await.unwrap();
+        assert!(channels.select(&10).await.unwrap().is_none());
+        assert!(channels.select(&11).await.unwrap().is_some());
+    });
+}
+
+mod voice_session_flags_table_tests {
+    use pwr_bot::entity::VoiceFlagReason;
+
+    use super::*;
+
+    async fn insert_session(
+        db: &pwr_bot::repo::PgRepos,
+        user_id: u64,
+        join_time: chrono::DateTime<Utc>,
+        leave_time: chrono::DateTime<Utc>,
+    ) -> i32 {
+        db.voice_sessions
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id: 1,
+                channel_id: 10,
+                join_time,
+                leave_time,
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    db_test!(flags_long_and_synced_sessions_once, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        let since = now - Duration::hours(2);
+        let long = insert_session(&db, 1, now - Duration::hours(26), now).await;
+        for (user_id, offset) in [(2, 0), (3, 1), (4, 2)] {
+            insert_session(
+                &db,
+                user_id,
+                now - Duration::hours(1) + Duration::seconds(offset),
+                now - Duration::minutes(10) - Duration::seconds(offset),
+            )
+            .await;
+        }
+        // Left at a different time, so not part of the group
+        insert_session(
+            &db,
+            5,
+            now - Duration::hours(1),
+            now - Duration::minutes(30),
+        )
+        .await;
+
+        let flags = &db.voice_session_flags;
+        assert_eq!(flags.flag_long_sessions(86400, &since).await.unwrap(), 1);
+        assert_eq!(flags.flag_synced_sessions(2, 3, &since).await.unwrap(), 3);
+        assert_eq!(flags.flag_long_sessions(86400, &since).await.unwrap(), 0);
+        assert_eq!(flags.count_open_by_guild_id(1).await.unwrap(), 4);
+
+        let open = flags.select_open_by_guild_id(1, 0, 10).await.unwrap();
+        let long_flag = open
+            .iter()
+            .find(|flag| flag.reason == VoiceFlagReason::LongSession)
+            .unwrap();
+        assert_eq!(long_flag.session_id, long);
+        assert_eq!(long_flag.duration_secs(), 26 * 3600);
+        assert!(
+            open.iter()
+                .filter(|flag| flag.reason == VoiceFlagReason::SyncedSessions)
+                .all(|flag| flag.related_count == 2)
+        );
+    });
+
+    db_test!(flags_long_session_across_channel_moves, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        let first =
+            insert_session(&db, 1, now - Duration::hours(26), now - Duration::hours(13)).await;
+        db.voice_sessions
+            .insert(&VoiceSessionsEntity {
+                user_id: 1,
+                guild_id: 1,
+                channel_id: 11,
+                join_time: now - Duration::hours(13),
+                leave_time: now,
+                is_active: false,
+                parent_session_id: Some(first),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        // Neither split is a day long, the whole session is
+        let flags = &db.voice_session_flags;
+        let since = now - Duration::hours(2);
+        assert_eq!(flags.flag_long_sessions(86400, &since).await.unwrap(), 1);
+
+        let open = flags.select_open_by_guild_id(1, 0, 10).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].session_id, first);
+        assert_eq!(open[0].duration_secs(), 26 * 3600);
+
+        // Invalidating the session removes every split
+        assert!(flags.delete_flagged_session(1, open[0].id).await.unwrap());
+        assert!(db.voice_sessions.select_all().await.unwrap().is_empty());
+    });
+
+    db_test!(dismissed_flags_stay_hidden, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        insert_session(&db, 1, now - Duration::hours(25), now).await;
+
+        let flags = &db.voice_session_flags;
+        flags
+            .flag_long_sessions(86400, &(now - Duration::hours(2)))
+            .await
+            .unwrap();
+        let flag_id = flags.select_open_by_guild_id(1, 0, 10).await.unwrap()[0].id;
+
+        assert!(!flags.dismiss(2, flag_id).await.unwrap());
+        assert!(flags.dismiss(1, flag_id).await.unwrap());
+        flags
+            .flag_long_sessions(86400, &(now - Duration::hours(2)))
+            .await
+            .unwrap();
+        assert_eq!(flags.count_open_by_guild_id(1).await.unwrap(), 0);
+    });
+
+    db_test!(delete_flagged_session_removes_session_and_flags, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        let session_id = insert_session(&db, 1, now - Duration::hours(25), now).await;
+
+        let flags = &db.voice_session_flags;
+        flags
+            .flag_long_sessions(86400, &(now - Duration::hours(2)))
+            .await
+            .unwrap();
+        let flag_id = flags.select_open_by_guild_id(1, 0, 10).await.unwrap()[0].id;
+
+        assert!(!flags.delete_flagged_session(2, flag_id).await.unwrap());
+        assert!(flags.delete_flagged_session(1, flag_id).await.unwrap());
+        assert!(
+            db.voice_sessions
+                .select(&session_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(flags.select(&flag_id).await.unwrap().is_none());
+    });
+}
+
+mod voice_account_merges_table_tests {
+    use pwr_bot::entity::LeaderboardSnapshotEntity;
+    use pwr_bot::entity::VoiceAdjustmentEntity;
+
+    use super::*;
+
+    async fn insert_session(
+        db: &pwr_bot::repo::PgRepos,
+        user_id: u64,
+        join_time: chrono::DateTime<Utc>,
+    ) -> i32 {
+        db.voice_sessions
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id: 1,
+                channel_id: 10,
+                join_time,
+                leave_time: join_time + Duration::hours(1),
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn insert_snapshot(
+        db: &pwr_bot::repo::PgRepos,
+        user_id: u64,
+        taken_at: chrono::DateTime<Utc>,
+    ) {
+        db.leaderboard_snapshots
+            .insert(&LeaderboardSnapshotEntity {
+                guild_id: DbU64::from(1),
+                user_id: DbU64::from(user_id),
+                rank: 1,
+                total_duration: 3600,
+                taken_at,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    db_test!(merge_users_moves_voice_data, |db| {
+        let now = Utc::now().trunc_subsecs(6);
+        insert_session(&db, 1, now - Duration::hours(5)).await;
+        insert_session(&db, 1, now - Duration::hours(3)).await;
+        // Same session recorded for both accounts
+        insert_session(&db, 1, now - Duration::hours(2)).await;
+        insert_session(&db, 2, now - Duration::hours(2)).await;
+        db.voice_adjustments
+            .insert(&VoiceAdjustmentEntity {
+                guild_id: DbU64::from(1),
+                user_id: DbU64::from(1),
+                seconds: 600,
+                adjusted_by: DbU64::from(9),
+                applies_at: now,
+                created_at: now,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        insert_snapshot(&db, 1, now - Duration::days(7)).await;
+        insert_snapshot(&db, 1, now).await;
+        insert_snapshot(&db, 2, now).await;
+
+        let merge = db.voice_account_merges.merge_users(1, 2, 9).await.unwrap();
+        assert_eq!(merge.sessions_moved, 2);
+        assert_eq!(merge.adjustments_moved, 1);
+        assert_eq!(merge.flags_moved, 0);
+        assert_eq!(merge.snapshots_moved, 2);
+        assert_eq!(*merge.merged_by, 9);
+
+        let sessions = db.voice_sessions.select_all().await.unwrap();
+        assert_eq!(sessions.len(), 3);
+        assert!(sessions.iter().all(|session| session.user_id == 2));
+        assert_eq!(db.voice_adjustments.sum_by_user(1, 2).await.unwrap(), 600);
+
+        let latest = db
+            .leaderboard_snapshots
+            .select_latest_by_guild_id(1)
+            .await
+            .unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(*latest[0].user_id, 2);
+        assert_eq!(latest[0].total_duration, 7200);
+
+        assert_eq!(
+            db.voice_account_merges.select_all().await.unwrap(),
+            vec![merge]
+        );
+    });
+}
+
+mod voice_events_table_tests {
+    use pwr_bot::entity::VoiceEventEntity;
+    use pwr_bot::entity::VoiceEventKind;
+
+    use super::*;
+
+    fn event(guild_id: u64, kind: VoiceEventKind) -> VoiceEventEntity {
+        VoiceEventEntity {
+            guild_id: DbU64::from(guild_id),
+            user_id: DbU64::from(1),
+            kind,
+            channel_id: Some(DbU64::from(10)),
+            occurred_at: Utc::now().trunc_subsecs(6),
+            ..Default::default()
+        }
+    }
+
+    db_test!(insert_and_select, |db| {
+        let model = VoiceEventEntity {
+            previous_channel_id: Some(DbU64::from(9)),
+            muted: true,
+            ..event(1, VoiceEventKind::Move)
+        };
+        let id = db.voice_events.insert(&model).await.unwrap();
+
+        let selected = db.voice_events.select(&id).await.unwrap().unwrap();
+        assert_eq!(selected, VoiceEventEntity { id, ..model });
+    });
+
+    db_test!(select_after_and_by_guild, |db| {
+        let first = db
+            .voice_events
+            .insert(&event(1, VoiceEventKind::Join))
+            .await
+            .unwrap();
+        let second = db
+            .voice_events
+            .insert(&event(2, VoiceEventKind::Join))
+            .await
+            .unwrap();
+        let third = db
+            .voice_events
+            .insert(&event(1, VoiceEventKind::Leave))
+            .await
+            .unwrap();
+
+        let after: Vec<i64> = db
+            .voice_events
+            .select_after(first, 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(after, vec![second, third]);
+        assert_eq!(db.voice_events.select_after(0, 1).await.unwrap().len(), 1);
+
+        let guild: Vec<i64> = db
+            .voice_events
+            .select_by_guild_id_between(1, 0, third, 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(guild, vec![first, third]);
+        let until_second = db
+            .voice_events
+            .select_by_guild_id_between(1, 0, second, 10)
+            .await
+            .unwrap();
+        assert_eq!(until_second.len(), 1);
+    });
+
+    db_test!(delete_before, |db| {
+        let old = |guild_id| VoiceEventEntity {
+            occurred_at: Utc::now().trunc_subsecs(6) - Duration::days(40),
+            ..event(guild_id, VoiceEventKind::Join)
+        };
+        let first = db.voice_events.insert(&old(1)).await.unwrap();
+        let second = db.voice_events.insert(&old(2)).await.unwrap();
+        let unprojected = db.voice_events.insert(&old(1)).await.unwrap();
+        let recent = db
+            .voice_events
+            .insert(&event(1, VoiceEventKind::Leave))
+            .await
+            .unwrap();
+        let before = Utc::now() - Duration::days(30);
+
+        // Only the guild's old events that were already projected
+        let deleted = db
+            .voice_events
+            .delete_before(Some(1), &before, second)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert!(db.voice_events.select(&first).await.unwrap().is_none());
+        assert!(
+            db.voice_events
+                .select(&unprojected)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        let deleted = db
+            .voice_events
+            .delete_before(None, &before, recent)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 2);
+        let left: Vec<i64> = db
+            .voice_events
+            .select_after(0, 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(left, vec![recent]);
+    });
+}
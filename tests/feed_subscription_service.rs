@@ -2,7 +2,9 @@
 
 use std::sync::Arc;
 
+use chrono::Duration;
 use chrono::Utc;
+use pwr_bot::config::SubscriptionLimits;
 use pwr_bot::entity::FeedEntity;
 use pwr_bot::entity::FeedItemEntity;
 use pwr_bot::entity::ServerSettings;
@@ -10,8 +12,12 @@ use pwr_bot::entity::SubscriberType;
 use pwr_bot::feed::FeedItem;
 use pwr_bot::feed::FeedSource;
 use pwr_bot::feed::Platforms;
+use pwr_bot::feed::TitleVariants;
 use pwr_bot::repo::traits::*;
+use pwr_bot::service::error::ServiceError;
 use pwr_bot::service::feed_subscription::FeedSubscriptionService;
+use pwr_bot::service::feed_subscription::FeedUpdateResult;
+use pwr_bot::service::feed_subscription::SubscribeResult;
 use pwr_bot::service::feed_subscription::SubscriberTarget;
 
 mod common;
@@ -51,6 +57,11 @@ async fn get_or_create_subscriber() {
     assert_eq!(sub1.id, sub2.id);
     assert_eq!(sub1.target_id, sub2.target_id);
 
+    // 3. New subscribers are onboarded once
+    assert!(sub2.first_seen);
+    assert!(service.take_first_seen(&sub2).await.unwrap());
+    assert!(!service.take_first_seen(&sub2).await.unwrap());
+
     common::teardown_db(&db).await;
 }
 
@@ -85,12 +96,14 @@ async fn get_or_create_feed() {
         source_url: url.clone(),
         description: "A test manga".to_string(),
         image_url: None,
+        titles: TitleVariants::default(),
     });
 
     mock_feed.set_latest(Some(FeedItem {
         id: "ch-1".to_string(),
         title: "Chapter 1".to_string(),
         published: Utc::now(),
+        ..Default::default()
     }));
 
     // 1. Create new feed
@@ -120,6 +133,7 @@ async fn get_or_create_feed() {
         description: "A test manga 2".to_string(),
         source_url: url.clone(),
         image_url: None,
+        titles: TitleVariants::default(),
     });
     mock_feed.set_latest(None);
 
@@ -139,6 +153,68 @@ async fn get_or_create_feed() {
     common::teardown_db(&db).await;
 }
 
+#[serial_test::serial]
+#[tokio::test]
+async fn search_and_combine_feeds_follows_subscriptions() {
+    let db = common::setup_db().await;
+
+    let mut feeds = Platforms::new();
+    let mock_domain = "test.com";
+    let mock_feed = Arc::new(common::MockFeed::new(mock_domain));
+    feeds.add_platform(mock_feed.clone());
+    let service = FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        Arc::new(feeds),
+    );
+
+    let target = SubscriberTarget {
+        subscriber_type: SubscriberType::Dm,
+        target_id: "user_search".to_string(),
+    };
+    let url = format!("https://{mock_domain}/title/titan");
+    mock_feed.set_info(FeedSource {
+        id: "titan".to_string(),
+        items_id: "titan".to_string(),
+        name: "Shingeki no Kyojin".to_string(),
+        description: String::new(),
+        source_url: url.clone(),
+        image_url: None,
+        titles: TitleVariants {
+            english: Some("Attack on Titan".to_string()),
+            ..Default::default()
+        },
+    });
+    mock_feed.set_latest(None);
+
+    // 1. Targets without a subscriber have no feeds
+    let found = service
+        .search_and_combine_feeds("titan", Some(target.clone()), None)
+        .await;
+    assert!(found.is_empty());
+
+    // 2. Subscribing drops the index built above
+    let subscriber = service.get_or_create_subscriber(&target).await.unwrap();
+    service.subscribe(&url, &subscriber).await.unwrap();
+    let found = service
+        .search_and_combine_feeds("titan", Some(target.clone()), None)
+        .await;
+    let names: Vec<&str> = found.iter().map(|feed| feed.name.as_str()).collect();
+    assert_eq!(names, vec!["(DM) Shingeki no Kyojin"]);
+
+    // 3. So does unsubscribing
+    service.unsubscribe(&url, &subscriber).await.unwrap();
+    let found = service
+        .search_and_combine_feeds("shingeki", Some(target), None)
+        .await;
+    assert!(found.is_empty());
+
+    common::teardown_db(&db).await;
+}
+
 #[serial_test::serial]
 #[tokio::test]
 async fn server_settings_service() {
@@ -170,6 +246,7 @@ async fn server_settings_service() {
         channel_id: Some("chan_456".to_string()),
         subscribe_role_id: Some("role_123".to_string()),
         unsubscribe_role_id: Some("role_456".to_string()),
+        ..Default::default()
     };
     let new_settings = ServerSettings {
         feeds: feed_settings,
@@ -285,3 +362,441 @@ async fn list_paginated_subscriptions_optimization() {
 
     common::teardown_db(&db).await;
 }
+
+#[serial_test::serial]
+#[tokio::test]
+async fn simulate_feed_update_does_not_persist() {
+    let db = common::setup_db().await;
+
+    let mut feeds = Platforms::new();
+    let mock_domain = "test.com";
+    let mock_feed = Arc::new(common::MockFeed::new(mock_domain));
+    feeds.add_platform(mock_feed.clone());
+    let feeds = Arc::new(feeds);
+
+    let service = FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        feeds.clone(),
+    );
+
+    let source_id = "manga-sim";
+    let url = format!("https://{mock_domain}/title/{source_id}");
+
+    // 1. Untracked feeds cannot be simulated
+    assert!(service.simulate_feed_update(&url).await.is_err());
+
+    mock_feed.set_info(FeedSource {
+        id: source_id.to_string(),
+        items_id: "abc".to_string(),
+        name: "Simulated Manga".to_string(),
+        source_url: url.clone(),
+        description: "A test manga".to_string(),
+        image_url: None,
+        titles: TitleVariants::default(),
+    });
+    mock_feed.set_latest(Some(FeedItem {
+        id: "ch-1".to_string(),
+        title: "Chapter 1".to_string(),
+        published: Utc::now(),
+        ..Default::default()
+    }));
+    let feed = service
+        .get_or_create_feed(&url)
+        .await
+        .expect("Failed to create feed");
+
+    // 2. Simulation derives the new item from the latest one
+    let result = service
+        .simulate_feed_update(&url)
+        .await
+        .expect("Failed to simulate update");
+    match result {
+        FeedUpdateResult::Updated {
+            feed: simulated,
+            old_item,
+            new_item,
+            ..
+        } => {
+            assert_eq!(simulated.id, feed.id);
+            assert_eq!(old_item.unwrap().description, "Chapter 1");
+            assert_eq!(new_item.description, "Chapter 1 (simulated)");
+        }
+        _ => panic!("Expected FeedUpdateResult::Updated"),
+    }
+
+    // 3. Nothing is written to the database
+    let latest = db
+        .feed_item
+        .select_latest_by_feed_id(feed.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.description, "Chapter 1");
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn check_feed_update_detects_edits() {
+    let db = common::setup_db().await;
+
+    let mut feeds = Platforms::new();
+    let mock_domain = "test.com";
+    let mock_feed = Arc::new(common::MockFeed::new(mock_domain));
+    feeds.add_platform(mock_feed.clone());
+    let feeds = Arc::new(feeds);
+
+    let service = FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        feeds.clone(),
+    );
+
+    let source_id = "manga-edit";
+    let url = format!("https://{mock_domain}/title/{source_id}");
+    mock_feed.set_info(FeedSource {
+        id: source_id.to_string(),
+        items_id: "abc".to_string(),
+        name: "Edited Manga".to_string(),
+        source_url: url.clone(),
+        description: "A test manga".to_string(),
+        image_url: None,
+        titles: TitleVariants::default(),
+    });
+    let chapter = FeedItem {
+        id: "ch-1".to_string(),
+        title: "Chapter 1".to_string(),
+        published: Utc::now(),
+        content: Some("The Beginning".to_string()),
+        ..Default::default()
+    };
+    mock_feed.set_latest(Some(chapter.clone()));
+
+    let target = SubscriberTarget {
+        subscriber_type: SubscriberType::Dm,
+        target_id: "user_edits".to_string(),
+    };
+    let subscriber = service.get_or_create_subscriber(&target).await.unwrap();
+    service.subscribe(&url, &subscriber).await.unwrap();
+    let feed = service.get_feed_by_source_url(&url).await.unwrap().unwrap();
+
+    // 1. Unchanged content is not an update
+    assert!(matches!(
+        service.check_feed_update(&feed).await.unwrap(),
+        FeedUpdateResult::NoUpdate
+    ));
+
+    // 2. Changed content of the same version is an edit
+    mock_feed.set_latest(Some(FeedItem {
+        content: Some("The Beginning (Fixed)".to_string()),
+        ..chapter
+    }));
+    match service.check_feed_update(&feed).await.unwrap() {
+        FeedUpdateResult::Edited { item, .. } => assert_eq!(item.description, "Chapter 1"),
+        _ => panic!("Expected FeedUpdateResult::Edited"),
+    }
+
+    // 3. The edit is recorded, so it is reported once
+    assert!(matches!(
+        service.check_feed_update(&feed).await.unwrap(),
+        FeedUpdateResult::NoUpdate
+    ));
+
+    // 4. Only subscriptions that opted in receive edits
+    let edit_subs = service
+        .get_edit_subscribers_by_type_and_feed(SubscriberType::Dm, feed.id)
+        .await
+        .unwrap();
+    assert!(edit_subs.is_empty());
+    assert!(
+        service
+            .set_subscription_notify_edits(&url, &subscriber, true)
+            .await
+            .unwrap()
+    );
+    let edit_subs = service
+        .get_edit_subscribers_by_type_and_feed(SubscriberType::Dm, feed.id)
+        .await
+        .unwrap();
+    assert_eq!(edit_subs.len(), 1);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn subscribe_enforces_subscription_limits() {
+    let db = common::setup_db().await;
+
+    let mut feeds = Platforms::new();
+    let mock_domain = "test.com";
+    let mock_feed = Arc::new(common::MockFeed::new(mock_domain));
+    feeds.add_platform(mock_feed.clone());
+    let feeds = Arc::new(feeds);
+
+    let service = FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        feeds.clone(),
+    )
+    .with_limits(SubscriptionLimits {
+        per_user: 1,
+        per_guild: 5,
+    });
+
+    let urls: Vec<String> = (1..=2)
+        .map(|i| format!("https://{mock_domain}/title/manga-{i}"))
+        .collect();
+    let target = SubscriberTarget {
+        subscriber_type: SubscriberType::Dm,
+        target_id: "user_quota".to_string(),
+    };
+    let subscriber = service.get_or_create_subscriber(&target).await.unwrap();
+    assert_eq!(service.get_subscription_limit(&subscriber), 1);
+
+    // 1. First subscription is within the default limit
+    mock_feed.set_info(FeedSource {
+        id: "manga-1".to_string(),
+        items_id: "abc".to_string(),
+        name: "Manga 1".to_string(),
+        source_url: urls[0].clone(),
+        description: String::new(),
+        image_url: None,
+        titles: TitleVariants::default(),
+    });
+    service
+        .subscribe(&urls[0], &subscriber)
+        .await
+        .expect("Failed to subscribe");
+
+    // 2. Second subscription exceeds the default limit
+    mock_feed.set_info(FeedSource {
+        id: "manga-2".to_string(),
+        items_id: "def".to_string(),
+        name: "Manga 2".to_string(),
+        source_url: urls[1].clone(),
+        description: String::new(),
+        image_url: None,
+        titles: TitleVariants::default(),
+    });
+    let result = service.subscribe(&urls[1], &subscriber).await;
+    assert!(matches!(
+        result,
+        Err(ServiceError::SubscriptionLimitReached { limit: 1 })
+    ));
+
+    // A feed already subscribed to is reported as such, not as over the limit
+    let result = service.subscribe(&urls[0], &subscriber).await;
+    assert!(matches!(
+        result,
+        Ok(SubscribeResult::AlreadySubscribed { .. })
+    ));
+
+    // 3. Owner-granted quota lifts the limit
+    let subscriber = service
+        .set_subscription_quota(&target, Some(2))
+        .await
+        .expect("Failed to set quota");
+    assert_eq!(subscriber.max_subscriptions, Some(2));
+    service
+        .subscribe(&urls[1], &subscriber)
+        .await
+        .expect("Failed to subscribe with custom quota");
+
+    // 4. Resetting restores the default
+    let subscriber = service.set_subscription_quota(&target, None).await.unwrap();
+    assert_eq!(service.get_subscription_limit(&subscriber), 1);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn paused_subscriptions_are_not_notified() {
+    let db = common::setup_db().await;
+    let feeds = Arc::new(Platforms::new());
+    let service = FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        feeds.clone(),
+    );
+
+    let target = SubscriberTarget {
+        subscriber_type: SubscriberType::Dm,
+        target_id: "user_paused".to_string(),
+    };
+    let subscriber = service.get_or_create_subscriber(&target).await.unwrap();
+
+    let feed = FeedEntity {
+        name: "Paused Feed".to_string(),
+        platform_id: "mock".to_string(),
+        source_id: "src_paused".to_string(),
+        items_id: "items_paused".to_string(),
+        source_url: "http://mock/paused".to_string(),
+        ..Default::default()
+    };
+    let feed_id = db.feed.insert(&feed).await.unwrap();
+    db.feed_subscription
+        .insert(&pwr_bot::entity::FeedSubscriptionEntity {
+            feed_id,
+            subscriber_id: subscriber.id,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // 1. Active subscriptions are notified
+    let subs = service
+        .get_subscribers_by_type_and_feed(SubscriberType::Dm, feed_id)
+        .await
+        .unwrap();
+    assert_eq!(subs.len(), 1);
+
+    // 2. Paused subscriptions are kept but not notified
+    assert!(
+        db.feed_subscription
+            .update_paused(feed_id, subscriber.id, true)
+            .await
+            .unwrap()
+    );
+    let subs = service
+        .get_subscribers_by_type_and_feed(SubscriberType::Dm, feed_id)
+        .await
+        .unwrap();
+    assert!(subs.is_empty());
+    assert!(
+        !db.feed_subscription
+            .exists_by_feed_id(feed_id)
+            .await
+            .unwrap()
+    );
+
+    let listed = service
+        .list_paginated_subscriptions(&subscriber, 1u32, 10u32)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(listed[0].paused);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn prune_feed_items_keeps_longest_subscriber_window() {
+    let db = common::setup_db().await;
+    let feeds = Arc::new(Platforms::new());
+    let service = FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        feeds.clone(),
+    )
+    .with_item_retention_days(90);
+
+    let guild_id: u64 = 9100;
+    let mut settings = ServerSettings::default();
+    settings.feeds.item_retention_days = Some(7);
+    service
+        .update_server_settings(guild_id, settings)
+        .await
+        .unwrap();
+    let guild = service
+        .get_or_create_subscriber(&SubscriberTarget {
+            subscriber_type: SubscriberType::Guild,
+            target_id: guild_id.to_string(),
+        })
+        .await
+        .unwrap();
+    let user = service
+        .get_or_create_subscriber(&SubscriberTarget {
+            subscriber_type: SubscriberType::Dm,
+            target_id: "user_retention".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let now = Utc::now();
+    // Items of each feed by age in days, and the feed's subscribers
+    let cases = [
+        (vec![30, 20, 2], vec![guild.id]),
+        (vec![100, 30], vec![guild.id, user.id]),
+        (vec![200], vec![]),
+    ];
+    let mut feed_ids = Vec::new();
+    for (i, (ages, subscriber_ids)) in cases.iter().enumerate() {
+        let feed_id = db
+            .feed
+            .insert(&FeedEntity {
+                name: format!("Retention Feed {i}"),
+                platform_id: "mock".to_string(),
+                source_id: format!("src_retention_{i}"),
+                items_id: format!("items_retention_{i}"),
+                source_url: format!("http://mock/retention/{i}"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        for age in ages {
+            db.feed_item
+                .insert(&FeedItemEntity {
+                    feed_id,
+                    description: format!("{age} days old"),
+                    published: now - Duration::days(*age),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        for subscriber_id in subscriber_ids {
+            db.feed_subscription
+                .insert(&pwr_bot::entity::FeedSubscriptionEntity {
+                    feed_id,
+                    subscriber_id: *subscriber_id,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        feed_ids.push(feed_id);
+    }
+
+    let pruned = service.prune_feed_items(&now).await.unwrap();
+    assert_eq!(pruned, 3);
+
+    let mut remaining = Vec::new();
+    for feed_id in feed_ids {
+        let items = db.feed_item.select_all_by_feed_id(feed_id).await.unwrap();
+        let mut descriptions: Vec<String> = items.into_iter().map(|i| i.description).collect();
+        descriptions.sort();
+        remaining.push(descriptions);
+    }
+    // The guild's 7 days apply only where no one keeps items longer, and the
+    // latest item of a feed is never pruned
+    assert_eq!(
+        remaining,
+        vec![
+            vec!["2 days old".to_string()],
+            vec!["30 days old".to_string()],
+            vec!["200 days old".to_string()],
+        ]
+    );
+
+    common::teardown_db(&db).await;
+}
@@ -0,0 +1,128 @@
+//! Integration tests for the weekly leaderboard snapshot task.
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use chrono::SubsecRound;
+use chrono::Utc;
+use pwr_bot::entity::VoiceSessionsEntity;
+use pwr_bot::repo::PgRepos;
+use pwr_bot::service::internal::InternalService;
+use pwr_bot::service::voice_tracking::VoiceTrackingService;
+use pwr_bot::task::leaderboard_snapshot::LeaderboardSnapshotTask;
+
+mod common;
+
+async fn setup(db: &PgRepos) -> (Arc<VoiceTrackingService>, LeaderboardSnapshotTask) {
+    let service = Arc::new(
+        VoiceTrackingService::new(
+            Arc::new(db.voice_sessions.clone()),
+            Arc::new(db.server_settings.clone()),
+        )
+        .await
+        .expect("Failed to create service")
+        .with_snapshots(Arc::new(db.leaderboard_snapshots.clone())),
+    );
+    let internal = Arc::new(InternalService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.bot_meta.clone()),
+    ));
+    let task = LeaderboardSnapshotTask::new(internal, service.clone());
+    (service, task)
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn snapshot_records_ranked_standings() {
+    let db = common::setup_db().await;
+    let (service, task) = setup(&db).await;
+
+    let guild_id = 7000;
+    let now = Utc::now().trunc_subsecs(6);
+    for (user_id, hours) in [(7001, 1), (7002, 3)] {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id: 7100,
+                join_time: now - Duration::hours(hours + 1),
+                leave_time: now - Duration::hours(1),
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    assert!(task.run_if_due(now).await.expect("Failed to run task"));
+
+    let snapshot = service
+        .get_latest_leaderboard_snapshot(guild_id)
+        .await
+        .expect("Failed to get snapshot");
+    let standings: Vec<_> = snapshot
+        .iter()
+        .map(|s| (*s.user_id, s.rank, s.total_duration))
+        .collect();
+    assert_eq!(standings, vec![(7002, 1, 10800), (7001, 2, 3600)]);
+    assert_eq!(snapshot[0].taken_at, now);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn snapshot_runs_once_per_week() {
+    let db = common::setup_db().await;
+    let (_, task) = setup(&db).await;
+
+    let now = Utc::now().trunc_subsecs(6);
+    assert!(task.run_if_due(now).await.unwrap());
+    assert!(!task.run_if_due(now + Duration::days(6)).await.unwrap());
+    assert!(task.run_if_due(now + Duration::days(7)).await.unwrap());
+
+    let last = task.read_last_snapshot().await.unwrap();
+    assert_eq!(last, Some(now + Duration::days(7)));
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn snapshots_since_are_ordered_by_time() {
+    let db = common::setup_db().await;
+    let (service, task) = setup(&db).await;
+
+    let guild_id = 7200;
+    let now = Utc::now().trunc_subsecs(6);
+    service
+        .insert(&VoiceSessionsEntity {
+            user_id: 7201,
+            guild_id,
+            channel_id: 7300,
+            join_time: now - Duration::hours(2),
+            leave_time: now - Duration::hours(1),
+            is_active: false,
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to insert session");
+    for weeks in 0..3 {
+        task.run_if_due(now + Duration::weeks(weeks)).await.unwrap();
+    }
+
+    let snapshots = service
+        .get_leaderboard_snapshots_since(guild_id, &(now + Duration::weeks(1)))
+        .await
+        .expect("Failed to get snapshots");
+    let taken_at: Vec<_> = snapshots.iter().map(|s| s.taken_at).collect();
+    assert_eq!(
+        taken_at,
+        vec![now + Duration::weeks(1), now + Duration::weeks(2)]
+    );
+
+    common::teardown_db(&db).await;
+}
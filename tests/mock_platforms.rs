@@ -5,10 +5,14 @@ use std::path::PathBuf;
 use httpmock::Method::GET;
 use httpmock::Method::POST;
 use httpmock::MockServer;
+use pwr_bot::config::MalConfig;
 use pwr_bot::feed::AniListPlatform;
 use pwr_bot::feed::ComickPlatform;
 use pwr_bot::feed::MangaDexPlatform;
 use pwr_bot::feed::Platform;
+use pwr_bot::feed::error::FeedError;
+use pwr_bot::feed::mal::MalClient;
+use pwr_bot::feed::mal::MalListKind;
 
 /// Loads a test response file from the responses directory.
 fn get_response(filename: &str) -> String {
@@ -80,6 +84,114 @@ async fn anilist_fetch_latest() {
     assert_eq!(item.published.timestamp(), 1766327400);
 }
 
+#[tokio::test]
+async fn anilist_fetch_user() {
+    let server = MockServer::start();
+    let mut platform = AniListPlatform::new();
+    platform.base.info.api_url = server.url("");
+
+    let found = server.mock(|when, then| {
+        when.method(POST).body_contains("fazuh");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("anilist_fetch_user_exist.json"));
+    });
+    let missing = server.mock(|when, then| {
+        when.method(POST).body_contains("nobody");
+        then.status(404)
+            .header("content-type", "application/json")
+            .body(get_response("anilist_fetch_user_not_exist.json"));
+    });
+
+    let user = platform
+        .fetch_user("fazuh")
+        .await
+        .expect("Failed to fetch user");
+    let nobody = platform
+        .fetch_user("nobody")
+        .await
+        .expect("Failed to fetch user");
+
+    found.assert();
+    missing.assert();
+    assert_eq!(user, Some((5531478, "FAZuH".to_string())));
+    assert_eq!(nobody, None);
+}
+
+#[tokio::test]
+async fn anilist_fetch_watching() {
+    let server = MockServer::start();
+    let mut platform = AniListPlatform::new();
+    platform.base.info.api_url = server.url("");
+
+    let mock = server.mock(|when, then| {
+        when.method(POST).body_contains("5531478");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("anilist_fetch_watching.json"));
+    });
+
+    let entries = platform
+        .fetch_watching(5531478)
+        .await
+        .expect("Failed to fetch watching list");
+
+    mock.assert();
+    let ids: Vec<(i32, bool)> = entries.iter().map(|e| (e.media_id, e.airing)).collect();
+    assert_eq!(ids, vec![(173692, true), (21, true), (101177, false)]);
+    assert_eq!(entries[1].title, "ONE PIECE");
+}
+
+#[tokio::test]
+async fn anilist_fetch_by_mal_ids() {
+    let server = MockServer::start();
+    let mut platform = AniListPlatform::new();
+    platform.base.info.api_url = server.url("");
+
+    let mock = server.mock(|when, then| {
+        when.method(POST).body_contains("idMal_in");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("anilist_fetch_by_mal_ids.json"));
+    });
+
+    let found = platform
+        .fetch_by_mal_ids(&[21, 52991, 99999])
+        .await
+        .expect("Failed to fetch by MyAnimeList IDs");
+
+    mock.assert();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[&21].media_id, 21);
+    assert!(found[&21].airing);
+    assert_eq!(found[&52991].media_id, 154587);
+    assert!(!found[&52991].airing);
+}
+
+#[tokio::test]
+async fn anilist_fetch_next_airing() {
+    let server = MockServer::start();
+    let mut platform = AniListPlatform::new();
+    platform.base.info.api_url = server.url("");
+
+    let mock = server.mock(|when, then| {
+        when.method(POST).body_contains("nextAiringEpisode");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("anilist_fetch_next_airing.json"));
+    });
+
+    let found = platform
+        .fetch_next_airing(&[21, 154587])
+        .await
+        .expect("Failed to fetch next airing episodes");
+
+    mock.assert();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[&21].episode, 1150);
+    assert_eq!(found[&21].airing_at.timestamp(), 1792386000);
+}
+
 #[tokio::test]
 async fn mangadex_fetch_source() {
     let server = MockServer::start();
@@ -139,6 +251,41 @@ async fn mangadex_fetch_latest() {
     assert_eq!(item.published.to_rfc3339(), "2025-12-23T03:19:29+00:00");
 }
 
+#[tokio::test]
+async fn mangadex_search_manga() {
+    let server = MockServer::start();
+    let mut platform = MangaDexPlatform::new();
+    platform.base.info.api_url = server.url("");
+
+    let mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/manga")
+            .query_param("title", "Sousou no Frieren");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("mangadex_search_manga.json"));
+    });
+
+    let results = platform
+        .search_manga("Sousou no Frieren")
+        .await
+        .expect("Failed to search manga");
+
+    mock.assert();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].id, "b0b721ff-c388-4486-aa0f-c2b0bb321512");
+    assert_eq!(results[0].title, "Sousou no Frieren");
+    assert_eq!(results[0].mal_id, Some(126287));
+    assert!(results[0].ongoing);
+    assert!(
+        results[0]
+            .titles
+            .contains(&"Frieren: Beyond Journey's End".to_string())
+    );
+    assert_eq!(results[1].mal_id, None);
+    assert!(!results[1].ongoing);
+}
+
 #[tokio::test]
 async fn comick_fetch_source() {
     let server = MockServer::start();
@@ -194,3 +341,110 @@ async fn comick_fetch_latest() {
     // "2025-12-27T14:44:40.000Z"
     assert_eq!(item.published.timestamp(), 1766846680);
 }
+
+fn mal_client(server: &MockServer) -> MalClient {
+    let config = MalConfig {
+        client_id: "client-id".to_string(),
+        client_secret: String::new(),
+    };
+    let mut client = MalClient::new(&config, "https://bot.example.com/mal/callback".to_string());
+    client.auth_url = server.url("/oauth2");
+    client.api_url = server.url("");
+    client
+}
+
+#[tokio::test]
+async fn mal_exchange_code() {
+    let server = MockServer::start();
+    let client = mal_client(&server);
+
+    let token = server.mock(|when, then| {
+        when.method(POST)
+            .path("/oauth2/token")
+            .body_contains("grant_type=authorization_code")
+            .body_contains("code_verifier=verifier");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("mal_token.json"));
+    });
+    let user = server.mock(|when, then| {
+        when.method(GET)
+            .path("/users/@me")
+            .header("Authorization", "Bearer access-token");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("mal_fetch_user.json"));
+    });
+
+    let tokens = client
+        .exchange_code("code", "verifier")
+        .await
+        .expect("Failed to exchange code");
+    let (id, name) = client
+        .fetch_user(&tokens.access_token)
+        .await
+        .expect("Failed to fetch user");
+
+    token.assert();
+    user.assert();
+    assert_eq!(tokens.refresh_token, "refresh-token");
+    assert!(tokens.expires_at > chrono::Utc::now());
+    assert_eq!((id, name.as_str()), (1234567, "fazuh"));
+}
+
+#[tokio::test]
+async fn mal_refresh_rejected() {
+    let server = MockServer::start();
+    let client = mal_client(&server);
+
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/oauth2/token")
+            .body_contains("grant_type=refresh_token");
+        then.status(400)
+            .header("content-type", "application/json")
+            .body(get_response("mal_token_invalid.json"));
+    });
+
+    let result = client.refresh("expired").await;
+
+    mock.assert();
+    assert!(matches!(result, Err(FeedError::ApiError { .. })));
+}
+
+#[tokio::test]
+async fn mal_fetch_list_follows_pages() {
+    let server = MockServer::start();
+    let client = mal_client(&server);
+
+    let first = server.mock(|when, then| {
+        when.method(GET)
+            .path("/users/@me/animelist")
+            .query_param("status", "watching");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("mal_animelist_page1.json").replace("{next}", &server.url("/next")));
+    });
+    let second = server.mock(|when, then| {
+        when.method(GET)
+            .path("/next")
+            .header("Authorization", "Bearer access-token");
+        then.status(200)
+            .header("content-type", "application/json")
+            .body(get_response("mal_animelist_page2.json"));
+    });
+
+    let entries = client
+        .fetch_list("access-token", MalListKind::Anime)
+        .await
+        .expect("Failed to fetch list");
+
+    first.assert();
+    second.assert();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].id, 21);
+    assert!(entries[0].ongoing);
+    assert_eq!(entries[0].alt_titles, vec!["One Piece", "OP"]);
+    assert_eq!(entries[1].title, "Sousou no Frieren");
+    assert!(!entries[1].ongoing);
+}
@@ -10,11 +10,14 @@ use pwr_bot::event::event_bus::EventBus;
 use pwr_bot::feed::FeedItem;
 use pwr_bot::feed::FeedSource;
 use pwr_bot::feed::Platforms;
+use pwr_bot::feed::TitleVariants;
 use pwr_bot::repo::traits::*;
 use pwr_bot::service::feed_subscription::FeedSubscriptionService;
 use pwr_bot::service::feed_subscription::SubscribeResult;
 use pwr_bot::service::feed_subscription::SubscriberTarget;
+use pwr_bot::task::series_feed_publisher::PublisherMessage;
 use pwr_bot::task::series_feed_publisher::SeriesFeedPublisher;
+use pwr_bot::task::supervisor::TaskMonitor;
 use tokio::time::sleep;
 
 mod common;
@@ -53,12 +56,14 @@ async fn subscription_and_publishing() {
         source_url: url.clone(),
         description: "Desc".to_string(),
         image_url: None,
+        titles: TitleVariants::default(),
     });
 
     let initial_latest = FeedItem {
         id: "ch1".to_string(),
         title: "Chapter 1".to_string(),
         published: Utc::now(),
+        ..Default::default()
     };
     mock_feed.set_latest(Some(initial_latest.clone()));
 
@@ -112,7 +117,7 @@ async fn subscription_and_publishing() {
     );
     publisher
         .clone()
-        .start()
+        .start(&Arc::new(TaskMonitor::new()))
         .expect("Failed to start publisher");
 
     // Update Mock Data
@@ -120,6 +125,7 @@ async fn subscription_and_publishing() {
         id: "ch2".to_string(),
         title: "Chapter 2".to_string(),
         published: Utc::now(),
+        ..Default::default()
     };
     mock_feed.set_latest(Some(new_latest));
 
@@ -154,3 +160,194 @@ async fn subscription_and_publishing() {
     publisher.stop().unwrap();
     common::teardown_db(&db).await;
 }
+
+#[serial_test::serial]
+#[tokio::test]
+async fn publisher_checks_feed_on_request() {
+    let db = common::setup_db().await;
+    let event_bus = Arc::new(EventBus::new());
+
+    let mut feeds = Platforms::new();
+    let mock_domain = "mock.test";
+    let mock_feed = Arc::new(common::MockFeed::new(mock_domain));
+    feeds.add_platform(mock_feed.clone());
+
+    let service = Arc::new(FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        Arc::new(feeds),
+    ));
+
+    let url = format!("https://{mock_domain}/title/123");
+    mock_feed.set_info(FeedSource {
+        id: "123".to_string(),
+        items_id: "abc".to_string(),
+        name: "Test Name".to_string(),
+        source_url: url.clone(),
+        description: "Desc".to_string(),
+        image_url: None,
+        titles: TitleVariants::default(),
+    });
+    mock_feed.set_latest(Some(FeedItem {
+        id: "ch1".to_string(),
+        title: "Chapter 1".to_string(),
+        published: Utc::now(),
+        ..Default::default()
+    }));
+
+    let target = SubscriberTarget {
+        subscriber_type: SubscriberType::Dm,
+        target_id: "user1".to_string(),
+    };
+    let subscriber = service.get_or_create_subscriber(&target).await.unwrap();
+    let feed_id = match service.subscribe(&url, &subscriber).await.unwrap() {
+        SubscribeResult::Success { feed } => feed.id,
+        _ => panic!("Expected Success"),
+    };
+
+    let event_received = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let event_received_clone = event_received.clone();
+    event_bus.register_callback(move |_event: FeedUpdateEvent| {
+        event_received_clone.store(true, std::sync::atomic::Ordering::SeqCst);
+        async { Ok(()) }
+    });
+
+    // The first cycle runs on start, the next one only after an hour
+    let publisher = SeriesFeedPublisher::new(
+        service.clone(),
+        event_bus.clone(),
+        Duration::from_secs(3600),
+    );
+    publisher
+        .clone()
+        .start(&Arc::new(TaskMonitor::new()))
+        .expect("Failed to start publisher");
+    sleep(Duration::from_millis(500)).await;
+    assert!(!event_received.load(std::sync::atomic::Ordering::SeqCst));
+
+    mock_feed.set_latest(Some(FeedItem {
+        id: "ch2".to_string(),
+        title: "Chapter 2".to_string(),
+        published: Utc::now(),
+        ..Default::default()
+    }));
+    publisher
+        .send(PublisherMessage::CheckFeed(feed_id))
+        .expect("Failed to send message");
+
+    let mut attempts = 0;
+    while !event_received.load(std::sync::atomic::Ordering::SeqCst) && attempts < 50 {
+        sleep(Duration::from_millis(100)).await;
+        attempts += 1;
+    }
+    assert!(
+        event_received.load(std::sync::atomic::Ordering::SeqCst),
+        "Publisher did not check the requested feed"
+    );
+
+    let db_latest = db
+        .feed_item
+        .select_latest_by_feed_id(feed_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(db_latest.description, "Chapter 2");
+
+    publisher.stop().unwrap();
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn publisher_restarts_after_stop() {
+    let db = common::setup_db().await;
+    let event_bus = Arc::new(EventBus::new());
+
+    let mut feeds = Platforms::new();
+    let mock_domain = "mock.test";
+    let mock_feed = Arc::new(common::MockFeed::new(mock_domain));
+    feeds.add_platform(mock_feed.clone());
+
+    let service = Arc::new(FeedSubscriptionService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.server_settings.clone()),
+        Arc::new(feeds),
+    ));
+
+    let url = format!("https://{mock_domain}/title/123");
+    mock_feed.set_info(FeedSource {
+        id: "123".to_string(),
+        items_id: "abc".to_string(),
+        name: "Test Name".to_string(),
+        source_url: url.clone(),
+        description: "Desc".to_string(),
+        image_url: None,
+        titles: TitleVariants::default(),
+    });
+    mock_feed.set_latest(Some(FeedItem {
+        id: "ch1".to_string(),
+        title: "Chapter 1".to_string(),
+        published: Utc::now(),
+        ..Default::default()
+    }));
+
+    let target = SubscriberTarget {
+        subscriber_type: SubscriberType::Dm,
+        target_id: "user1".to_string(),
+    };
+    let subscriber = service.get_or_create_subscriber(&target).await.unwrap();
+    let feed_id = match service.subscribe(&url, &subscriber).await.unwrap() {
+        SubscribeResult::Success { feed } => feed.id,
+        _ => panic!("Expected Success"),
+    };
+
+    let event_received = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let event_received_clone = event_received.clone();
+    event_bus.register_callback(move |_event: FeedUpdateEvent| {
+        event_received_clone.store(true, std::sync::atomic::Ordering::SeqCst);
+        async { Ok(()) }
+    });
+
+    let tasks = Arc::new(TaskMonitor::new());
+    let publisher = SeriesFeedPublisher::new(
+        service.clone(),
+        event_bus.clone(),
+        Duration::from_secs(3600),
+    );
+    // Stopping a publisher that isn't running leaves nothing behind for the next run
+    publisher.clone().stop().unwrap();
+    publisher.clone().start(&tasks).unwrap();
+    sleep(Duration::from_millis(500)).await;
+    publisher.clone().stop().unwrap();
+    publisher.clone().start(&tasks).unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    mock_feed.set_latest(Some(FeedItem {
+        id: "ch2".to_string(),
+        title: "Chapter 2".to_string(),
+        published: Utc::now(),
+        ..Default::default()
+    }));
+    publisher
+        .send(PublisherMessage::CheckFeed(feed_id))
+        .expect("Failed to send message");
+
+    let mut attempts = 0;
+    while !event_received.load(std::sync::atomic::Ordering::SeqCst) && attempts < 50 {
+        sleep(Duration::from_millis(100)).await;
+        attempts += 1;
+    }
+    assert!(
+        event_received.load(std::sync::atomic::Ordering::SeqCst),
+        "Restarted publisher did not check the requested feed"
+    );
+
+    publisher.stop().unwrap();
+    common::teardown_db(&db).await;
+}
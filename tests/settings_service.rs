@@ -0,0 +1,70 @@
+//! Integration tests for the settings service.
+
+use std::sync::Arc;
+
+use pwr_bot::entity::ServerSettings;
+use pwr_bot::service::settings::SettingsService;
+
+mod common;
+
+#[serial_test::serial]
+#[tokio::test]
+async fn update_server_settings_records_audit_trail() {
+    let db = common::setup_db().await;
+    let service = SettingsService::new(Arc::new(db.server_settings.clone()))
+        .with_audit(Arc::new(db.settings_audit.clone()));
+
+    let mut settings = ServerSettings::default();
+    settings.feeds.channel_id = Some("111".to_string());
+    service
+        .update_server_settings_by(123, settings.clone(), 42)
+        .await
+        .unwrap();
+
+    settings.feeds.channel_id = Some("222".to_string());
+    service
+        .update_server_settings(123, settings.clone())
+        .await
+        .unwrap();
+
+    // Saving unchanged settings records nothing
+    service
+        .update_server_settings_by(123, settings, 42)
+        .await
+        .unwrap();
+
+    assert_eq!(service.count_settings_history(123).await.unwrap(), 2);
+
+    let history = service.get_settings_history(123, 1, 10).await.unwrap();
+    assert_eq!(history[0].key, "feeds.channel_id");
+    assert_eq!(history[0].old_value.as_deref(), Some("111"));
+    assert_eq!(history[0].new_value.as_deref(), Some("222"));
+    assert_eq!(history[0].changed_by, None);
+    assert_eq!(history[1].old_value, None);
+    assert_eq!(history[1].changed_by.map(|id| *id), Some(42));
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn disabled_commands_follow_settings_updates() {
+    let db = common::setup_db().await;
+    let service = SettingsService::new(Arc::new(db.server_settings.clone()));
+
+    assert!(service.get_disabled_commands(123).await.unwrap().is_empty());
+
+    let mut settings = ServerSettings::default();
+    settings.general.disabled_commands = Some(vec!["vc".to_string()]);
+    service
+        .update_server_settings(123, settings.clone())
+        .await
+        .unwrap();
+    assert_eq!(service.get_disabled_commands(123).await.unwrap(), ["vc"]);
+
+    settings.general.disabled_commands = None;
+    service.update_server_settings(123, settings).await.unwrap();
+    assert!(service.get_disabled_commands(123).await.unwrap().is_empty());
+
+    common::teardown_db(&db).await;
+}
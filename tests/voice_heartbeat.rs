@@ -7,6 +7,7 @@ use chrono::SubsecRound;
 use chrono::Utc;
 use pwr_bot::entity::BotMetaKey;
 use pwr_bot::entity::VoiceSessionsEntity;
+use pwr_bot::handoff::HandoffSession;
 use pwr_bot::repo::traits::*;
 use pwr_bot::service::internal::InternalService;
 use pwr_bot::service::voice_tracking::VoiceTrackingService;
@@ -115,6 +116,7 @@ async fn heartbeat_crash_recovery_with_active_sessions() {
             join_time: now - Duration::hours(2),
             leave_time: now - Duration::hours(2), // Active session
             is_active: true,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -124,6 +126,7 @@ async fn heartbeat_crash_recovery_with_active_sessions() {
             join_time: now - Duration::minutes(30),
             leave_time: now - Duration::minutes(30), // Active session
             is_active: true,
+            parent_session_id: None,
         },
     ];
 
@@ -175,6 +178,70 @@ async fn heartbeat_crash_recovery_with_active_sessions() {
     common::teardown_db(&db).await;
 }
 
+#[serial_test::serial]
+#[tokio::test]
+async fn heartbeat_recovery_keeps_handed_off_sessions() {
+    let db = common::setup_db().await;
+    let service = Arc::new(
+        VoiceTrackingService::new(
+            Arc::new(db.voice_sessions.clone()),
+            Arc::new(db.server_settings.clone()),
+        )
+        .await
+        .expect("Failed to create service"),
+    );
+    let internal = Arc::new(InternalService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.bot_meta.clone()),
+    ));
+
+    let now = Utc::now();
+    for user_id in [1001, 1002] {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id: 555555,
+                channel_id: 9001,
+                join_time: now - Duration::hours(1),
+                leave_time: now - Duration::hours(1),
+                is_active: true,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+    internal
+        .set_meta(
+            BotMetaKey::VoiceHeartbeat,
+            &(now - Duration::minutes(1)).to_rfc3339(),
+        )
+        .await
+        .expect("Failed to set heartbeat");
+
+    // The previous run handed off user 1001's session at shutdown
+    let sessions = db.voice_sessions.select_all().await.unwrap();
+    let handed_off: Vec<HandoffSession> = sessions
+        .iter()
+        .filter(|s| s.user_id == 1001)
+        .map(HandoffSession::from)
+        .collect();
+
+    let recovered = VoiceHeartbeatManager::new(internal.clone(), service.clone())
+        .recover_keeping(&handed_off)
+        .await
+        .expect("Failed to recover");
+    assert_eq!(recovered, 1);
+
+    let active = service.find_active_sessions().await.unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].user_id, 1001);
+
+    common::teardown_db(&db).await;
+}
+
 #[serial_test::serial]
 #[tokio::test]
 async fn heartbeat_crash_recovery_no_heartbeat() {
@@ -207,6 +274,7 @@ async fn heartbeat_crash_recovery_no_heartbeat() {
         join_time: now - Duration::hours(1),
         leave_time: now - Duration::hours(1),
         is_active: true,
+        parent_session_id: None,
     };
 
     service
@@ -263,6 +331,7 @@ async fn find_active_sessions() {
             join_time: now - Duration::hours(2),
             leave_time: now - Duration::hours(2), // Active
             is_active: true,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -272,6 +341,7 @@ async fn find_active_sessions() {
             join_time: now - Duration::hours(3),
             leave_time: now - Duration::hours(1), // Completed (2 hours)
             is_active: false,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -281,6 +351,7 @@ async fn find_active_sessions() {
             join_time: now - Duration::minutes(30),
             leave_time: now - Duration::minutes(30), // Active
             is_active: true,
+            parent_session_id: None,
         },
     ];
 
@@ -331,6 +402,7 @@ async fn update_session_leave_time() {
         join_time,
         leave_time: join_time, // Active
         is_active: true,
+        parent_session_id: None,
     };
 
     service
@@ -366,3 +438,66 @@ async fn update_session_leave_time() {
 
     common::teardown_db(&db).await;
 }
+
+#[serial_test::serial]
+#[tokio::test]
+async fn heartbeat_flushes_open_sessions() {
+    let db = common::setup_db().await;
+    let service = Arc::new(
+        VoiceTrackingService::new(
+            Arc::new(db.voice_sessions.clone()),
+            Arc::new(db.server_settings.clone()),
+        )
+        .await
+        .expect("Failed to create service"),
+    );
+    let internal = Arc::new(InternalService::new(
+        Arc::new(db.feed.clone()),
+        Arc::new(db.feed_item.clone()),
+        Arc::new(db.subscriber.clone()),
+        Arc::new(db.feed_subscription.clone()),
+        Arc::new(db.bot_meta.clone()),
+    ));
+
+    let now = Utc::now().trunc_subsecs(6);
+    for (user_id, is_active) in [(1001, true), (1002, true), (1003, false)] {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id: 555555,
+                channel_id: 9001,
+                join_time: now - Duration::hours(1),
+                leave_time: now - Duration::hours(1),
+                is_active,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    let heartbeat_manager = VoiceHeartbeatManager::new(internal.clone(), service.clone())
+        .with_interval(std::time::Duration::from_secs(1));
+    heartbeat_manager.update().await;
+
+    let heartbeat = heartbeat_manager
+        .read_last_heartbeat()
+        .await
+        .expect("Failed to read heartbeat")
+        .expect("Heartbeat should be written");
+    let sessions: Vec<VoiceSessionsEntity> = db
+        .voice_sessions
+        .select_all()
+        .await
+        .expect("Failed to select sessions");
+    assert_eq!(sessions.len(), 3);
+    for session in sessions {
+        if session.is_active {
+            // Open sessions are flushed with the heartbeat's timestamp
+            assert_eq!(session.leave_time, heartbeat.trunc_subsecs(6));
+        } else {
+            assert_eq!(session.leave_time, session.join_time);
+        }
+    }
+
+    common::teardown_db(&db).await;
+}
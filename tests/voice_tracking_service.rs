@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use chrono::DateTime;
 use chrono::Duration;
 use chrono::SubsecRound;
 use chrono::Utc;
@@ -9,9 +10,14 @@ use pwr_bot::entity::DbU64;
 use pwr_bot::entity::Json;
 use pwr_bot::entity::ServerSettings;
 use pwr_bot::entity::ServerSettingsEntity;
+use pwr_bot::entity::VoiceEventEntity;
+use pwr_bot::entity::VoiceEventKind;
+use pwr_bot::entity::VoiceLeaderboardEntry;
+use pwr_bot::entity::VoiceLeaderboardOptBuilder;
 use pwr_bot::entity::VoiceSessionsEntity;
 use pwr_bot::entity::VoiceSettings;
 use pwr_bot::repo::traits::*;
+use pwr_bot::service::voice_import::ImportedVoiceTime;
 use pwr_bot::service::voice_tracking::VoiceTrackingService;
 
 mod common;
@@ -68,6 +74,7 @@ async fn is_enabled_when_disabled() {
     // Disable voice tracking for the guild
     let voice_settings = VoiceSettings {
         enabled: Some(false),
+        ..Default::default()
     };
     let settings = ServerSettings {
         voice: voice_settings,
@@ -101,6 +108,7 @@ async fn is_enabled_when_re_enabled() {
     // Disable voice tracking
     let voice_settings = VoiceSettings {
         enabled: Some(false),
+        ..Default::default()
     };
     let settings = ServerSettings {
         voice: voice_settings,
@@ -115,6 +123,7 @@ async fn is_enabled_when_re_enabled() {
     // Re-enable voice tracking
     let voice_settings = VoiceSettings {
         enabled: Some(true),
+        ..Default::default()
     };
     let settings = ServerSettings {
         voice: voice_settings,
@@ -151,6 +160,7 @@ async fn insert_and_replace_voice_session() {
         join_time: now,
         leave_time: now + Duration::hours(1),
         is_active: false,
+        parent_session_id: None,
     };
 
     // Insert the session
@@ -178,6 +188,7 @@ async fn insert_and_replace_voice_session() {
         join_time: now,
         leave_time: now + Duration::hours(2), // Changed duration
         is_active: false,
+        parent_session_id: None,
     };
     service
         .replace(&updated_session)
@@ -235,6 +246,7 @@ async fn update_and_get_server_settings() {
     // Update settings
     let voice_settings = VoiceSettings {
         enabled: Some(true),
+        ..Default::default()
     };
     let settings = ServerSettings {
         voice: voice_settings,
@@ -279,6 +291,7 @@ async fn get_leaderboard() {
             join_time: now,
             leave_time: now + Duration::hours(1), // 3600 seconds
             is_active: false,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -288,6 +301,7 @@ async fn get_leaderboard() {
             join_time: now + Duration::hours(2),
             leave_time: now + Duration::hours(4), // 7200 seconds, total: 10800
             is_active: false,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -297,6 +311,7 @@ async fn get_leaderboard() {
             join_time: now,
             leave_time: now + Duration::minutes(30), // 1800 seconds
             is_active: false,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -306,6 +321,7 @@ async fn get_leaderboard() {
             join_time: now,
             leave_time: now + Duration::hours(2), // 7200 seconds
             is_active: false,
+            parent_session_id: None,
         },
     ];
 
@@ -364,6 +380,7 @@ async fn get_leaderboard_with_limit() {
             join_time: now,
             leave_time: now + Duration::hours(i as i64), // Each user has different duration
             is_active: false,
+            parent_session_id: None,
         };
         service
             .insert(&session)
@@ -411,6 +428,7 @@ async fn get_leaderboard_with_offset() {
             join_time: now,
             leave_time: now + Duration::hours(i as i64),
             is_active: false,
+            parent_session_id: None,
         };
         service
             .insert(&session)
@@ -475,6 +493,7 @@ async fn disabled_guilds_cache_on_init() {
         settings: Json(ServerSettings {
             voice: VoiceSettings {
                 enabled: Some(false),
+                ..Default::default()
             },
             ..Default::default()
         }),
@@ -528,6 +547,7 @@ async fn get_leaderboard_includes_active_sessions() {
             join_time: now - Duration::hours(2),
             leave_time: now, // Completed: 2 hours (7200 seconds)
             is_active: false,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -537,6 +557,7 @@ async fn get_leaderboard_includes_active_sessions() {
             join_time: now - Duration::hours(1),
             leave_time: now - Duration::hours(1), // Active: 1 hour so far
             is_active: true,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -546,6 +567,7 @@ async fn get_leaderboard_includes_active_sessions() {
             join_time: now - Duration::minutes(30),
             leave_time: now - Duration::minutes(30), // Active: 30 minutes so far
             is_active: true,
+            parent_session_id: None,
         },
     ];
 
@@ -614,6 +636,7 @@ async fn get_leaderboard_active_and_completed_mixed() {
             join_time: now - Duration::hours(3),
             leave_time: now - Duration::hours(2),
             is_active: false,
+            parent_session_id: None,
         },
         // User 3001: Active session (30 minutes so far)
         VoiceSessionsEntity {
@@ -624,6 +647,7 @@ async fn get_leaderboard_active_and_completed_mixed() {
             join_time: now - Duration::minutes(30),
             leave_time: now - Duration::minutes(30),
             is_active: true,
+            parent_session_id: None,
         },
         // User 3002: Only completed sessions (2 hours total)
         VoiceSessionsEntity {
@@ -634,6 +658,7 @@ async fn get_leaderboard_active_and_completed_mixed() {
             join_time: now - Duration::hours(4),
             leave_time: now - Duration::hours(3),
             is_active: false,
+            parent_session_id: None,
         },
         VoiceSessionsEntity {
             id: 0,
@@ -643,6 +668,7 @@ async fn get_leaderboard_active_and_completed_mixed() {
             join_time: now - Duration::hours(2),
             leave_time: now - Duration::hours(1),
             is_active: false,
+            parent_session_id: None,
         },
     ];
 
@@ -677,3 +703,1124 @@ async fn get_leaderboard_active_and_completed_mixed() {
 
     common::teardown_db(&db).await;
 }
+
+#[serial_test::serial]
+#[tokio::test]
+async fn end_session_respects_min_session_length() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 4000;
+    let settings = ServerSettings {
+        voice: VoiceSettings {
+            min_session_secs: Some(30),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    service
+        .update_server_settings(guild_id, settings)
+        .await
+        .expect("Failed to update settings");
+
+    let now = Utc::now().trunc_subsecs(6);
+    for (user_id, join_time) in [
+        (4001, now - Duration::seconds(10)),
+        (4002, now - Duration::minutes(5)),
+    ] {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id: 4100,
+                join_time,
+                leave_time: join_time,
+                is_active: true,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    for user_id in [4001, 4002] {
+        for session in service
+            .find_active_sessions_by_user(user_id, guild_id)
+            .await
+            .expect("Failed to find sessions")
+        {
+            service
+                .end_session(&session, &now)
+                .await
+                .expect("Failed to end session");
+        }
+    }
+
+    let sessions: Vec<VoiceSessionsEntity> = db
+        .voice_sessions
+        .select_all()
+        .await
+        .expect("Failed to select sessions");
+    assert_eq!(sessions.len(), 1, "Short session should be discarded");
+    assert_eq!(sessions[0].user_id, 4002);
+    assert!(!sessions[0].is_active);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn should_track_ignores_bots_by_default() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 5000;
+    assert!(service.should_track(guild_id, false, true).await);
+    assert!(!service.should_track(guild_id, true, false).await);
+
+    let settings = ServerSettings {
+        voice: VoiceSettings {
+            track_bots: Some(true),
+            track_stage_channels: Some(false),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    service
+        .update_server_settings(guild_id, settings)
+        .await
+        .expect("Failed to update settings");
+
+    assert!(service.should_track(guild_id, true, false).await);
+    assert!(!service.should_track(guild_id, false, true).await);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn leaderboard_applies_channel_weights() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service")
+    .with_channel_weights(Arc::new(db.channel_weights.clone()));
+
+    let guild_id: u64 = 6000;
+    let study_room: u64 = 6100;
+    let mut settings = ServerSettings::default();
+    settings.voice.set_channel_weight(study_room, 150);
+    service
+        .update_server_settings(guild_id, settings)
+        .await
+        .expect("Failed to update settings");
+
+    let now = Utc::now().trunc_subsecs(6);
+    for (user_id, channel_id) in [(6001, study_room), (6002, 6200)] {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id,
+                join_time: now - Duration::hours(2),
+                leave_time: now - Duration::hours(1),
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    let leaderboard = service
+        .get_leaderboard(guild_id, 10)
+        .await
+        .expect("Failed to get leaderboard");
+
+    assert_eq!(leaderboard[0].user_id, 6001);
+    assert_eq!(
+        leaderboard[0].total_duration, 5400,
+        "Study room counts 1.5×"
+    );
+    assert_eq!(leaderboard[1].user_id, 6002);
+    assert_eq!(leaderboard[1].total_duration, 3600);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn leaderboard_discounts_idle_alone_time() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 6500;
+    let mut settings = ServerSettings::default();
+    settings.voice.idle_alone_mins = Some(30);
+    service
+        .update_server_settings(guild_id, settings)
+        .await
+        .expect("Failed to update settings");
+
+    let now = Utc::now().trunc_subsecs(6);
+    let sessions = [
+        // Parked alone for 3 hours; only the first 30 minutes count
+        (
+            6501,
+            6510,
+            now - Duration::hours(4),
+            now - Duration::hours(1),
+        ),
+        // Alone for the first 20 minutes, under the threshold
+        (
+            6502,
+            6520,
+            now - Duration::hours(2),
+            now - Duration::hours(1),
+        ),
+        (
+            6503,
+            6520,
+            now - Duration::minutes(100),
+            now - Duration::hours(1),
+        ),
+    ];
+    for (user_id, channel_id, join_time, leave_time) in sessions {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id,
+                join_time,
+                leave_time,
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    let leaderboard = service
+        .get_leaderboard(guild_id, 10)
+        .await
+        .expect("Failed to get leaderboard");
+
+    let totals: Vec<(u64, i64)> = leaderboard
+        .iter()
+        .map(|entry| (entry.user_id, entry.total_duration))
+        .collect();
+    assert_eq!(totals, vec![(6502, 3600), (6503, 2400), (6501, 1800)]);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn channel_usage_clips_sessions_to_range() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 7000;
+    let now = Utc::now().trunc_subsecs(6);
+    let since = now - Duration::hours(3);
+    let sessions = [
+        (
+            7001,
+            7100,
+            now - Duration::hours(2),
+            now - Duration::hours(1),
+        ),
+        (
+            7002,
+            7100,
+            now - Duration::hours(2),
+            now - Duration::minutes(90),
+        ),
+        // Started before the range, so only the last hour counts
+        (
+            7001,
+            7200,
+            since - Duration::hours(1),
+            since + Duration::hours(1),
+        ),
+        // Ended before the range
+        (
+            7003,
+            7300,
+            since - Duration::hours(2),
+            since - Duration::hours(1),
+        ),
+    ];
+    for (user_id, channel_id, join_time, leave_time) in sessions {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id,
+                join_time,
+                leave_time,
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    let usage = service
+        .get_channel_usage(guild_id, &since, &now)
+        .await
+        .expect("Failed to get channel usage");
+
+    assert_eq!(usage.len(), 2);
+    assert_eq!(usage[0].channel_id, 7100);
+    assert_eq!(usage[0].total_duration, 5400);
+    assert_eq!(usage[0].user_count, 2);
+    assert_eq!(usage[1].channel_id, 7200);
+    assert_eq!(usage[1].total_duration, 3600);
+    assert_eq!(usage[1].user_count, 1);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn leaderboard_includes_voice_adjustments() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service")
+    .with_adjustments(Arc::new(db.voice_adjustments.clone()));
+
+    let guild_id: u64 = 7500;
+    let now = Utc::now().trunc_subsecs(6);
+    for user_id in [7501, 7502] {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id: 7510,
+                join_time: now - Duration::hours(2),
+                leave_time: now - Duration::hours(1),
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    // Lost two hours to an outage; the other member's time is partly removed
+    let net = service
+        .adjust_voice_time(guild_id, 7501, 7200, Some("Outage".to_string()), 1)
+        .await
+        .expect("Failed to adjust");
+    assert_eq!(net, 7200);
+    let net = service
+        .adjust_voice_time(guild_id, 7502, -1800, None, 1)
+        .await
+        .expect("Failed to adjust");
+    assert_eq!(net, -1800);
+    // Adjusted past zero, still listed at zero
+    service
+        .adjust_voice_time(guild_id, 7503, -600, None, 1)
+        .await
+        .expect("Failed to adjust");
+
+    let leaderboard = service
+        .get_leaderboard(guild_id, 10)
+        .await
+        .expect("Failed to get leaderboard");
+    let totals: Vec<(u64, i64)> = leaderboard
+        .iter()
+        .map(|entry| (entry.user_id, entry.total_duration))
+        .collect();
+    assert_eq!(totals, vec![(7501, 10800), (7502, 1800), (7503, 0)]);
+
+    // Sessions are left as recorded
+    let sessions = service
+        .get_sessions_in_range(guild_id, None, &(now - Duration::days(1)), &now)
+        .await
+        .expect("Failed to get sessions");
+    assert_eq!(sessions.len(), 2);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn import_voice_time_adds_dated_adjustments() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service")
+    .with_adjustments(Arc::new(db.voice_adjustments.clone()));
+
+    let guild_id: u64 = 7600;
+    let applies_at = Utc::now().trunc_subsecs(6) - Duration::days(400);
+    let members = [
+        ImportedVoiceTime {
+            user_id: 7601,
+            seconds: 36_000,
+        },
+        ImportedVoiceTime {
+            user_id: 7602,
+            seconds: 600,
+        },
+        ImportedVoiceTime {
+            user_id: 7603,
+            seconds: 0,
+        },
+    ];
+
+    let imported = service
+        .import_voice_time(
+            guild_id,
+            &members,
+            "Imported from Statbot".to_string(),
+            1,
+            applies_at,
+        )
+        .await
+        .expect("Failed to import");
+    assert_eq!(imported, 2);
+
+    let leaderboard = service
+        .get_leaderboard(guild_id, 10)
+        .await
+        .expect("Failed to get leaderboard");
+    let totals: Vec<(u64, i64)> = leaderboard
+        .iter()
+        .map(|entry| (entry.user_id, entry.total_duration))
+        .collect();
+    assert_eq!(totals, vec![(7601, 36_000), (7602, 600)]);
+
+    let adjustments = db
+        .voice_adjustments
+        .select_all()
+        .await
+        .expect("Failed to select adjustments");
+    assert!(adjustments.iter().all(|adjustment| {
+        adjustment.applies_at == applies_at
+            && adjustment.reason.as_deref() == Some("Imported from Statbot")
+    }));
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn global_leaderboard_only_counts_opted_in_guilds() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let (named_guild, anonymous_guild, private_guild): (u64, u64, u64) = (8100, 8200, 8300);
+    let mut settings = ServerSettings::default();
+    settings.voice.global_leaderboard = Some(true);
+    service
+        .update_server_settings(named_guild, settings.clone())
+        .await
+        .expect("Failed to update settings");
+    settings.voice.global_leaderboard_anonymous = Some(true);
+    service
+        .update_server_settings(anonymous_guild, settings)
+        .await
+        .expect("Failed to update settings");
+
+    let now = Utc::now().trunc_subsecs(6);
+    let sessions = [
+        (8001, named_guild, Duration::hours(2)),
+        (8001, anonymous_guild, Duration::hours(1)),
+        (8002, named_guild, Duration::hours(1)),
+        // Not opted in, so never counted
+        (8002, private_guild, Duration::hours(5)),
+        (8003, private_guild, Duration::hours(4)),
+    ];
+    for (user_id, guild_id, length) in sessions {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id: guild_id + 1,
+                join_time: now - length - Duration::minutes(1),
+                leave_time: now - Duration::minutes(1),
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    let leaderboard = service
+        .get_global_leaderboard(&(now - Duration::days(1)), &now, 10)
+        .await
+        .expect("Failed to get global leaderboard");
+    let rows: Vec<(u64, i64, u64, i32, bool)> = leaderboard
+        .iter()
+        .map(|entry| {
+            (
+                entry.user_id,
+                entry.total_duration,
+                entry.top_guild_id,
+                entry.guild_count,
+                entry.named,
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            (8001, 10800, named_guild, 2, false),
+            (8002, 3600, named_guild, 1, true),
+        ]
+    );
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn voice_events_project_into_sessions_and_rebuild() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service")
+    .with_event_sourcing(
+        Arc::new(db.voice_events.clone()),
+        Arc::new(db.bot_meta.clone()),
+    );
+    assert!(service.records_voice_events());
+
+    let guild_id: u64 = 7700;
+    let now = Utc::now().trunc_subsecs(6);
+    let event = |user_id: u64,
+                 kind: VoiceEventKind,
+                 channel_id: Option<u64>,
+                 is_bot: bool,
+                 hours_ago: i64| VoiceEventEntity {
+        guild_id: DbU64::from(guild_id),
+        user_id: DbU64::from(user_id),
+        kind,
+        channel_id: channel_id.map(DbU64::from),
+        is_bot,
+        occurred_at: now - Duration::hours(hours_ago),
+        ..Default::default()
+    };
+    for model in [
+        event(7701, VoiceEventKind::Join, Some(7710), false, 4),
+        event(7701, VoiceEventKind::Mute, Some(7710), false, 3),
+        event(7701, VoiceEventKind::Move, Some(7711), false, 2),
+        event(7701, VoiceEventKind::Leave, None, false, 1),
+        // Bots are not tracked by default
+        event(7702, VoiceEventKind::Join, Some(7710), true, 3),
+        event(7702, VoiceEventKind::Leave, None, true, 2),
+    ] {
+        service
+            .record_voice_event(&model)
+            .await
+            .expect("Failed to record event");
+    }
+
+    assert_eq!(service.project_voice_events(100).await.unwrap(), 6);
+    assert_eq!(service.project_voice_events(100).await.unwrap(), 0);
+
+    let since = now - Duration::days(1);
+    let sessions = service
+        .get_sessions_in_range(guild_id, None, &since, &now)
+        .await
+        .expect("Failed to get sessions");
+    let spans: Vec<(u64, u64, i64)> = sessions
+        .iter()
+        .map(|s| {
+            (
+                s.user_id,
+                s.channel_id,
+                (s.leave_time - s.join_time).num_hours(),
+            )
+        })
+        .collect();
+    assert_eq!(spans, vec![(7701, 7710, 2), (7701, 7711, 1)]);
+    assert!(sessions.iter().all(|s| !s.is_active));
+
+    // Tracking bots afterwards applies to the recorded history on rebuild
+    let settings = ServerSettings {
+        voice: VoiceSettings {
+            track_bots: Some(true),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    service
+        .update_server_settings(guild_id, settings)
+        .await
+        .expect("Failed to update settings");
+    assert_eq!(service.rebuild_voice_sessions(guild_id).await.unwrap(), 6);
+
+    let sessions = service
+        .get_sessions_in_range(guild_id, None, &since, &now)
+        .await
+        .expect("Failed to get sessions");
+    let mut users: Vec<u64> = sessions.iter().map(|s| s.user_id).collect();
+    users.sort();
+    assert_eq!(users, vec![7701, 7701, 7702]);
+
+    // The event log itself is untouched
+    assert_eq!(db.voice_events.select_all().await.unwrap().len(), 6);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn channel_move_continues_session_as_split() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 7800;
+    let user_id: u64 = 7801;
+    let now = Utc::now().trunc_subsecs(6);
+    service
+        .insert(&VoiceSessionsEntity {
+            user_id,
+            guild_id,
+            channel_id: 7810,
+            join_time: now - Duration::hours(3),
+            leave_time: now - Duration::hours(3),
+            is_active: true,
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to insert session");
+
+    service
+        .move_session(
+            user_id,
+            guild_id,
+            7811,
+            false,
+            false,
+            &(now - Duration::hours(1)),
+        )
+        .await
+        .expect("Failed to move");
+    service
+        .move_session(
+            user_id,
+            guild_id,
+            7812,
+            false,
+            false,
+            &(now - Duration::minutes(30)),
+        )
+        .await
+        .expect("Failed to move");
+    let open = service
+        .find_active_sessions_by_user(user_id, guild_id)
+        .await
+        .expect("Failed to find sessions");
+    assert_eq!(open.len(), 1);
+    service
+        .end_session(&open[0], &now)
+        .await
+        .expect("Failed to end session");
+
+    // Every split points at the first one
+    let sessions = service
+        .get_sessions_in_range(guild_id, None, &(now - Duration::days(1)), &now)
+        .await
+        .expect("Failed to get sessions");
+    assert_eq!(sessions.len(), 3);
+    let first = sessions[0].id;
+    assert_eq!(sessions[0].parent_session_id, None);
+    assert!(
+        sessions[1..]
+            .iter()
+            .all(|s| s.parent_session_id == Some(first))
+    );
+    assert!(
+        sessions
+            .iter()
+            .all(|s| s.logical_id() == first && !s.is_active)
+    );
+
+    // Total time is continuous, per-channel time is exact
+    let leaderboard = service.get_leaderboard(guild_id, 10).await.unwrap();
+    assert_eq!(leaderboard[0].total_duration, 3 * 3600);
+    let mut usage: Vec<(u64, i64)> = service
+        .get_channel_usage(guild_id, &(now - Duration::days(1)), &now)
+        .await
+        .unwrap()
+        .iter()
+        .map(|u| (u.channel_id, u.total_duration))
+        .collect();
+    usage.sort();
+    assert_eq!(usage, vec![(7810, 7200), (7811, 1800), (7812, 1800)]);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn leaderboard_counts_unflushed_time_of_open_sessions() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 7850;
+    let now = Utc::now().trunc_subsecs(6);
+    let sessions = [
+        (
+            7851,
+            now - Duration::minutes(150),
+            now - Duration::minutes(60),
+            false,
+        ),
+        (
+            7852,
+            now - Duration::hours(2),
+            now - Duration::hours(2),
+            true,
+        ),
+    ];
+    for (user_id, join_time, leave_time, is_active) in sessions {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id: 7860,
+                join_time,
+                leave_time,
+                is_active,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    // One hour is flushed to the database, the other is only known in memory
+    let flushed_at = now - Duration::hours(1);
+    assert_eq!(service.flush_open_sessions(&flushed_at).await.unwrap(), 1);
+    let sessions = service.find_active_sessions().await.unwrap();
+    assert_eq!(sessions[0].leave_time, flushed_at);
+
+    // Live time is ranked before paging
+    let first = service.get_leaderboard(guild_id, 1).await.unwrap();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].user_id, 7852);
+    assert!(first[0].total_duration >= 7200);
+    let second = service
+        .get_leaderboard_with_offset(guild_id, 1, 1)
+        .await
+        .unwrap();
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].user_id, 7851);
+    assert_eq!(second[0].total_duration, 5400);
+
+    // A restarted service picks up open sessions on its first flush
+    let restarted = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+    assert_eq!(restarted.flush_open_sessions(&now).await.unwrap(), 1);
+    let leaderboard = restarted.get_leaderboard(guild_id, 10).await.unwrap();
+    assert_eq!(leaderboard[0].user_id, 7852);
+    assert!(leaderboard[0].total_duration >= 7200);
+
+    // Closed sessions no longer grow
+    let open = restarted.find_active_sessions().await.unwrap();
+    restarted.end_session(&open[0], &now).await.unwrap();
+    let leaderboard = restarted.get_leaderboard(guild_id, 10).await.unwrap();
+    assert_eq!(leaderboard[0].total_duration, 7200);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn leaderboard_is_cached_until_a_session_closes() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 7880;
+    let now = Utc::now().trunc_subsecs(6);
+    let session = |user_id, join_time, leave_time, is_active| VoiceSessionsEntity {
+        user_id,
+        guild_id,
+        channel_id: 7890,
+        join_time,
+        leave_time,
+        is_active,
+        ..Default::default()
+    };
+    service
+        .insert(&session(
+            7881,
+            now - Duration::hours(2),
+            now - Duration::hours(1),
+            false,
+        ))
+        .await
+        .expect("Failed to insert session");
+    let leaderboard = service.get_leaderboard(guild_id, 10).await.unwrap();
+    assert_eq!(leaderboard.len(), 1);
+
+    // Written behind the service's back, so the cached leaderboard is served
+    db.voice_sessions
+        .insert(&session(
+            7882,
+            now - Duration::hours(3),
+            now - Duration::hours(2),
+            false,
+        ))
+        .await
+        .expect("Failed to insert session");
+    let leaderboard = service.get_leaderboard(guild_id, 10).await.unwrap();
+    assert_eq!(leaderboard.len(), 1);
+
+    let join_time = now - Duration::minutes(10);
+    service
+        .insert(&session(7881, join_time, join_time, true))
+        .await
+        .expect("Failed to insert session");
+    service
+        .close_session(7881, 7890, &join_time, &(now - Duration::minutes(5)))
+        .await
+        .unwrap();
+
+    let leaderboard = service.get_leaderboard(guild_id, 10).await.unwrap();
+    let totals: Vec<(u64, i64)> = leaderboard
+        .iter()
+        .map(|entry| (entry.user_id, entry.total_duration))
+        .collect();
+    assert_eq!(totals, vec![(7881, 3900), (7882, 3600)]);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn open_sessions_follow_session_lifecycle() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 7870;
+    let user_id: u64 = 7871;
+    let now = Utc::now().trunc_subsecs(6);
+    let joined = now - Duration::hours(1);
+    service
+        .insert(&VoiceSessionsEntity {
+            user_id,
+            guild_id,
+            channel_id: 7880,
+            join_time: joined,
+            leave_time: joined,
+            is_active: true,
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to insert session");
+    let open_sessions = service.open_sessions();
+    assert!(open_sessions.is_live(guild_id, user_id));
+    assert!(!open_sessions.is_live(guild_id + 1, user_id));
+
+    // A move keeps the start of the whole session
+    let moved_at = now - Duration::minutes(10);
+    service
+        .move_session(user_id, guild_id, 7881, false, false, &moved_at)
+        .await
+        .expect("Failed to move");
+    let open = open_sessions.get(guild_id, user_id).unwrap();
+    assert_eq!(open.channel_id, 7881);
+    assert_eq!(open.join_time, moved_at);
+    assert_eq!(open.started_at, joined);
+    assert_eq!(open.duration_secs(&now), 3600);
+    assert_eq!(open_sessions.by_guild(guild_id).len(), 1);
+
+    let current = service
+        .find_active_sessions_by_user(user_id, guild_id)
+        .await
+        .unwrap();
+    service.end_session(&current[0], &now).await.unwrap();
+    assert!(!open_sessions.is_live(guild_id, user_id));
+    assert!(open_sessions.by_guild(guild_id).is_empty());
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn short_session_with_moves_is_discarded_whole() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 7900;
+    let user_id: u64 = 7901;
+    let settings = ServerSettings {
+        voice: VoiceSettings {
+            min_session_secs: Some(600),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    service
+        .update_server_settings(guild_id, settings)
+        .await
+        .expect("Failed to update settings");
+
+    let now = Utc::now().trunc_subsecs(6);
+    service
+        .insert(&VoiceSessionsEntity {
+            user_id,
+            guild_id,
+            channel_id: 7910,
+            join_time: now - Duration::minutes(5),
+            leave_time: now - Duration::minutes(5),
+            is_active: true,
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to insert session");
+    service
+        .move_session(
+            user_id,
+            guild_id,
+            7911,
+            false,
+            false,
+            &(now - Duration::minutes(4)),
+        )
+        .await
+        .expect("Failed to move");
+    let open = service
+        .find_active_sessions_by_user(user_id, guild_id)
+        .await
+        .expect("Failed to find sessions");
+    service
+        .end_session(&open[0], &now)
+        .await
+        .expect("Failed to end session");
+
+    // Five minutes in total is below the minimum, so both splits are gone
+    let sessions = service
+        .get_sessions_in_range(guild_id, None, &(now - Duration::days(1)), &now)
+        .await
+        .expect("Failed to get sessions");
+    assert!(sessions.is_empty());
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn prune_sessions_honours_shorter_guild_windows() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service")
+    .with_session_retention_days(30);
+
+    let (default_guild, private_guild): (u64, u64) = (8900, 8950);
+    let mut settings = ServerSettings::default();
+    settings.voice.session_retention_days = Some(7);
+    service
+        .update_server_settings(private_guild, settings)
+        .await
+        .expect("Failed to update settings");
+
+    let now = Utc::now().trunc_subsecs(6);
+    let sessions = [
+        (8901, default_guild, Duration::days(40)),
+        (8902, default_guild, Duration::days(10)),
+        (8903, private_guild, Duration::days(10)),
+        (8904, private_guild, Duration::days(2)),
+    ];
+    for (user_id, guild_id, ended) in sessions {
+        service
+            .insert(&VoiceSessionsEntity {
+                user_id,
+                guild_id,
+                channel_id: guild_id + 1,
+                join_time: now - ended - Duration::hours(1),
+                leave_time: now - ended,
+                is_active: false,
+                ..Default::default()
+            })
+            .await
+            .expect("Failed to insert session");
+    }
+
+    // A session whose first split ended long ago but that is still open elsewhere
+    service
+        .insert(&VoiceSessionsEntity {
+            user_id: 8905,
+            guild_id: private_guild,
+            channel_id: private_guild + 1,
+            join_time: now - Duration::days(20),
+            leave_time: now - Duration::days(20),
+            is_active: true,
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to insert session");
+    service
+        .move_session(
+            8905,
+            private_guild,
+            private_guild + 2,
+            false,
+            false,
+            &(now - Duration::days(15)),
+        )
+        .await
+        .expect("Failed to move");
+
+    let pruned = service.prune_sessions(&now).await.expect("Failed to prune");
+    assert_eq!(pruned, 2);
+
+    let range = (now - Duration::days(60), now);
+    let mut kept: Vec<u64> = Vec::new();
+    for guild_id in [default_guild, private_guild] {
+        let sessions = service
+            .get_sessions_in_range(guild_id, None, &range.0, &range.1)
+            .await
+            .expect("Failed to get sessions");
+        kept.extend(sessions.iter().map(|s| s.user_id));
+    }
+    kept.sort();
+    assert_eq!(kept, vec![8902, 8904, 8905, 8905]);
+
+    common::teardown_db(&db).await;
+}
+
+#[serial_test::serial]
+#[tokio::test]
+async fn long_range_leaderboard_reads_daily_totals() {
+    let db = common::setup_db().await;
+    let service = VoiceTrackingService::new(
+        Arc::new(db.voice_sessions.clone()),
+        Arc::new(db.server_settings.clone()),
+    )
+    .await
+    .expect("Failed to create service");
+
+    let guild_id: u64 = 7900;
+    let at = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+    let session = |user_id, join_time, leave_time| VoiceSessionsEntity {
+        user_id,
+        guild_id,
+        channel_id: 7910,
+        join_time,
+        leave_time,
+        ..Default::default()
+    };
+    // Crosses midnight and the start of the range
+    service
+        .insert(&session(
+            7901,
+            at("2026-03-09T22:00:00Z"),
+            at("2026-03-10T02:00:00Z"),
+        ))
+        .await
+        .expect("Failed to insert session");
+    service
+        .insert(&session(
+            7902,
+            at("2026-03-12T10:00:00Z"),
+            at("2026-03-12T11:30:00Z"),
+        ))
+        .await
+        .expect("Failed to insert session");
+    // Only partly in whole days of the range
+    service
+        .insert(&session(
+            7902,
+            at("2026-03-16T23:00:00Z"),
+            at("2026-03-17T13:00:00Z"),
+        ))
+        .await
+        .expect("Failed to insert session");
+
+    let options = VoiceLeaderboardOptBuilder::default()
+        .guild_id(guild_id)
+        .since(Some(at("2026-03-09T23:00:00Z")))
+        .until(Some(at("2026-03-17T12:00:00Z")))
+        .build()
+        .unwrap();
+    let totals = |leaderboard: Vec<VoiceLeaderboardEntry>| -> Vec<(u64, i64)> {
+        leaderboard
+            .iter()
+            .map(|entry| (entry.user_id, entry.total_duration))
+            .collect()
+    };
+
+    let leaderboard = service.get_leaderboard_withopt(&options).await.unwrap();
+    assert_eq!(totals(leaderboard), vec![(7902, 52200), (7901, 10800)]);
+
+    assert!(service.reconcile_daily_totals().await.unwrap() >= 4);
+    let leaderboard = service.get_leaderboard_withopt(&options).await.unwrap();
+    assert_eq!(totals(leaderboard), vec![(7902, 52200), (7901, 10800)]);
+
+    common::teardown_db(&db).await;
+}